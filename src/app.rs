@@ -4,13 +4,17 @@
 //! Switch between tools via the tab bar - all tools stay alive in background.
 
 use crate::auth::AuthState;
-use crate::editor::{EditorState, EditorLayout, LevelBrowser};
-use crate::game::GameToolState;
+use crate::clipboard_history::ClipboardPopup;
+use crate::cloud_sync::SyncConflict;
+use crate::frame_pacer::FramePacer;
+use crate::editor::{EditorState, EditorLayout, LevelBrowser, PrefabBrowser, DungeonWizard};
+use crate::game::{Console, GameToolState};
 use crate::input::InputState;
 use crate::landing::LandingState;
 use crate::modeler::{ModelerState, ModelerLayout, ModelBrowser, ObjImportBrowser};
 use crate::project::ProjectData;
 use crate::storage::{Storage, PendingSave, PendingLoad};
+use crate::sfx::{SfxEditorState, SfxLibrary};
 use crate::tracker::TrackerState;
 use crate::world::Level;
 use macroquad::prelude::{Font, Texture2D};
@@ -66,16 +70,18 @@ pub enum Tool {
     Test = 2,
     Modeler = 3,
     Tracker = 4,
-    InputTest = 5,
+    Sfx = 5,
+    InputTest = 6,
 }
 
 impl Tool {
-    pub const ALL: [Tool; 6] = [
+    pub const ALL: [Tool; 7] = [
         Tool::Home,
         Tool::WorldEditor,
         Tool::Test,
         Tool::Modeler,
         Tool::Tracker,
+        Tool::Sfx,
         Tool::InputTest,
     ];
 
@@ -88,19 +94,21 @@ impl Tool {
             Tool::Test => "Test",
             Tool::Modeler => "Assets",
             Tool::Tracker => "Music",
+            Tool::Sfx => "SFX",
             Tool::InputTest => "Input",
         }
     }
 
     /// Get all tool labels (for tab bar)
     #[allow(dead_code)]
-    pub fn labels() -> [&'static str; 6] {
+    pub fn labels() -> [&'static str; 7] {
         [
             Tool::Home.label(),
             Tool::WorldEditor.label(),
             Tool::Test.label(),
             Tool::Modeler.label(),
             Tool::Tracker.label(),
+            Tool::Sfx.label(),
             Tool::InputTest.label(),
         ]
     }
@@ -115,6 +123,8 @@ pub struct WorldEditorState {
     pub editor_state: EditorState,
     pub editor_layout: EditorLayout,
     pub level_browser: LevelBrowser,
+    pub prefab_browser: PrefabBrowser,
+    pub dungeon_wizard: DungeonWizard,
 }
 
 /// State for the Modeler tool
@@ -156,6 +166,9 @@ pub struct AppState {
     /// Music Editor state
     pub tracker: TrackerState,
 
+    /// Sound effect editor state (its library is what the game plays)
+    pub sfx: SfxEditorState,
+
     /// Icon font (Lucide)
     pub icon_font: Option<Font>,
 
@@ -167,6 +180,18 @@ pub struct AppState {
 
     /// Pending async operations (save, load)
     pub pending_ops: PendingOps,
+
+    /// Engine-wide frame rate limiter
+    pub frame_pacer: FramePacer,
+
+    /// Clipboard history popup (Ctrl+Shift+V, shared by all editors)
+    pub clipboard_popup: ClipboardPopup,
+
+    /// Cloud save refused because the file changed elsewhere (conflict dialog)
+    pub sync_conflict: Option<SyncConflict>,
+
+    /// Test console (commands from the MCP server for end-to-end tests)
+    pub console: Console,
 }
 
 impl AppState {
@@ -178,16 +203,23 @@ impl AppState {
             EditorState::new(level)
         };
 
+        // Sound effects render with the tracker's soundfont
+        let storage = Storage::new();
+        let tracker = TrackerState::new();
+        let sfx = SfxEditorState::new(SfxLibrary::load(&storage, &tracker.audio));
+
         Self {
             active_tool: Tool::Home,
             prev_tool: Tool::Home,
-            storage: Storage::new(),
+            storage,
             project: ProjectData::new(),
             landing: LandingState::new(logo_texture),
             world_editor: WorldEditorState {
                 editor_state,
                 editor_layout: EditorLayout::new(),
                 level_browser: LevelBrowser::default(),
+                prefab_browser: PrefabBrowser::default(),
+                dungeon_wizard: DungeonWizard::default(),
             },
             game: GameToolState::new(),
             modeler: ModelerToolState {
@@ -196,11 +228,16 @@ impl AppState {
                 model_browser: ModelBrowser::default(),
                 obj_importer: ObjImportBrowser::default(),
             },
-            tracker: TrackerState::new(),
+            tracker,
+            sfx,
             icon_font,
             input: InputState::new(),
             auth: AuthState::new(),
             pending_ops: PendingOps::default(),
+            frame_pacer: FramePacer::new(),
+            clipboard_popup: ClipboardPopup::default(),
+            sync_conflict: None,
+            console: Console::new(),
         }
     }
 
@@ -8,11 +8,34 @@ use std::sync::atomic::{AtomicU64, Ordering};
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::Path;
 use serde::{Deserialize, Serialize};
+use crate::load_check::{describe_ron_error, unresolved, LoadIssue};
 use crate::modeler::{MeshPart, MeshProject, RigBone};
 use crate::rasterizer::Vec3;
-use super::component::AssetComponent;
+use super::component::{AssetComponent, StatusEffectDef};
 use super::library::AssetSource;
 
+/// Which level-of-detail variant of an asset's mesh to draw
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LodLevel {
+    /// The Mesh component
+    Full,
+    /// The Lod component's reduced mesh
+    Reduced,
+    /// The Lod component's billboard impostor
+    Impostor,
+}
+
+impl LodLevel {
+    /// Short label for UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            LodLevel::Full => "Full",
+            LodLevel::Reduced => "Reduced",
+            LodLevel::Impostor => "Impostor",
+        }
+    }
+}
+
 /// Counter for generating unique asset IDs
 static ASSET_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
 
@@ -68,6 +91,23 @@ impl std::fmt::Display for AssetError {
 
 impl std::error::Error for AssetError {}
 
+/// Decode asset file bytes (brotli-compressed or plain RON) to RON text
+fn asset_text(data: &[u8]) -> Result<String, AssetError> {
+    // Try to detect if compressed or plain text
+    // RON files start with '(' or whitespace before '('
+    let is_ron = data.first().map(|&b| b == b'(' || b == b' ' || b == b'\n' || b == b'\r').unwrap_or(false);
+
+    if is_ron {
+        Ok(String::from_utf8_lossy(data).to_string())
+    } else {
+        // Decompress brotli
+        let mut decompressed = Vec::new();
+        brotli::BrotliDecompress(&mut std::io::Cursor::new(data), &mut decompressed)
+            .map_err(|e| AssetError::Io(format!("decompression failed: {}", e)))?;
+        Ok(String::from_utf8_lossy(&decompressed).to_string())
+    }
+}
+
 impl From<std::io::Error> for AssetError {
     fn from(e: std::io::Error) -> Self {
         AssetError::Io(e.to_string())
@@ -220,6 +260,64 @@ impl Asset {
         })
     }
 
+    /// Get the LOD switch distances (reduced, impostor) if a Lod component is present
+    ///
+    /// Distances are only returned for levels that actually have mesh parts.
+    pub fn lod_distances(&self) -> Option<(Option<f32>, Option<f32>)> {
+        self.components.iter().find_map(|c| match c {
+            AssetComponent::Lod { reduced, reduced_distance, impostor, impostor_distance } => Some((
+                (!reduced.is_empty()).then_some(*reduced_distance),
+                (!impostor.is_empty()).then_some(*impostor_distance),
+            )),
+            _ => None,
+        })
+    }
+
+    /// Pick the LOD level for a camera distance (world units)
+    ///
+    /// `scale` multiplies the switch distances (a global quality setting).
+    pub fn lod_level(&self, distance: f32, scale: f32) -> LodLevel {
+        let (reduced, impostor) = match self.lod_distances() {
+            Some(d) => d,
+            None => return LodLevel::Full,
+        };
+        if let Some(d) = impostor {
+            if distance >= d * scale {
+                return LodLevel::Impostor;
+            }
+        }
+        if let Some(d) = reduced {
+            if distance >= d * scale {
+                return LodLevel::Reduced;
+            }
+        }
+        LodLevel::Full
+    }
+
+    /// Get the mesh parts for a LOD level (falls back to the Mesh component)
+    pub fn lod_mesh(&self, level: LodLevel) -> Option<&Vec<MeshPart>> {
+        let lod_parts = self.components.iter().find_map(|c| match c {
+            AssetComponent::Lod { reduced, impostor, .. } => match level {
+                LodLevel::Full => None,
+                LodLevel::Reduced => Some(reduced),
+                LodLevel::Impostor => Some(impostor),
+            },
+            _ => None,
+        });
+        match lod_parts {
+            Some(parts) if !parts.is_empty() => Some(parts),
+            _ => self.mesh(),
+        }
+    }
+
+    /// Get the Destructible component's broken mesh parts, if it has any
+    pub fn broken_mesh(&self) -> Option<&Vec<MeshPart>> {
+        self.components.iter().find_map(|c| match c {
+            AssetComponent::Destructible { broken, .. } if !broken.is_empty() => Some(broken),
+            _ => None,
+        })
+    }
+
     /// Add a component to this asset
     pub fn add_component(&mut self, component: AssetComponent) {
         self.components.push(component);
@@ -275,6 +373,18 @@ impl Asset {
             .any(|c| matches!(c, AssetComponent::Door { .. }))
     }
 
+    /// Check if this asset has a MovingPlatform component
+    pub fn has_moving_platform(&self) -> bool {
+        self.components
+            .iter()
+            .any(|c| matches!(c, AssetComponent::MovingPlatform { .. }))
+    }
+
+    /// The status effect this asset defines (its StatusEffect component), if any
+    pub fn status_effect(&self) -> Option<&StatusEffectDef> {
+        self.components.iter().find_map(|c| c.status_effect())
+    }
+
     /// Check if this asset has a SpawnPoint component with the given player flag
     pub fn has_spawn_point(&self, is_player: bool) -> bool {
         self.components.iter().any(|c| {
@@ -282,6 +392,44 @@ impl Asset {
         })
     }
 
+    /// Can instances follow a path? Enemies, moving platforms, and solid
+    /// meshes without door, pickup or spawn behaviour (ridden as platforms)
+    pub fn can_follow_path(&self) -> bool {
+        self.has_enemy()
+            || self.has_moving_platform()
+            || (self.has_mesh() && self.has_collision()
+                && !self.has_door() && !self.has_pickup() && !self.has_spawn_point(true))
+    }
+
+    /// Problems with the asset's components as (component index, message):
+    /// each component's own `validate` plus duplicates that would be ignored,
+    /// repeated trigger ids and LOD levels with no Mesh to switch from
+    pub fn component_issues(&self) -> Vec<(usize, String)> {
+        let mut issues = Vec::new();
+        for (i, component) in self.components.iter().enumerate() {
+            issues.extend(component.validate().into_iter().map(|msg| (i, msg)));
+
+            let earlier = &self.components[..i];
+            if !component.allows_multiple()
+                && earlier.iter().any(|c| c.type_name() == component.type_name())
+            {
+                issues.push((i, format!("Only the first {} component is used", component.type_name())));
+            }
+            if let AssetComponent::Trigger { trigger_id, .. } = component {
+                let repeated = earlier.iter().any(|c| {
+                    matches!(c, AssetComponent::Trigger { trigger_id: id, .. } if id == trigger_id)
+                });
+                if repeated && !trigger_id.is_empty() {
+                    issues.push((i, format!("Trigger ID '{}' is already used", trigger_id)));
+                }
+            }
+            if component.is_lod() && !self.has_mesh() {
+                issues.push((i, "Needs a Mesh component".to_string()));
+            }
+        }
+        issues
+    }
+
     /// Compute axis-aligned bounding box from mesh (if present)
     ///
     /// Returns (min, max) corners of the bounding box, or None if no mesh.
@@ -325,6 +473,18 @@ impl Asset {
             .unwrap_or(0)
     }
 
+    /// Get total triangle count across all mesh objects (n-gons fan into n-2)
+    pub fn total_triangles(&self) -> usize {
+        self.mesh()
+            .map(|objs| {
+                objs.iter()
+                    .flat_map(|o| o.mesh.faces.iter())
+                    .map(|f| f.vertices.len().saturating_sub(2))
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+
     /// Save asset to file (compressed RON format with brotli)
     #[cfg(not(target_arch = "wasm32"))]
     pub fn save(&self, path: &Path) -> Result<(), AssetError> {
@@ -360,26 +520,90 @@ impl Asset {
         Self::load_from_bytes(&data)
     }
 
+    /// Load asset from file like `load`, repairing broken mesh data instead
+    /// of failing on it (see `check_meshes`)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_repaired(path: &Path) -> Result<(Self, Vec<LoadIssue>), AssetError> {
+        let data = std::fs::read(path)?;
+        Self::from_ron(&asset_text(&data)?, true)
+    }
+
     /// Load asset from bytes (supports both compressed and uncompressed RON)
     pub fn load_from_bytes(data: &[u8]) -> Result<Self, AssetError> {
-        // Try to detect if compressed or plain text
-        // RON files start with '(' or whitespace before '('
-        let is_ron = data.first().map(|&b| b == b'(' || b == b' ' || b == b'\n' || b == b'\r').unwrap_or(false);
-
-        let ron_str = if is_ron {
-            String::from_utf8_lossy(data).to_string()
-        } else {
-            // Decompress brotli
-            let mut decompressed = Vec::new();
-            brotli::BrotliDecompress(&mut std::io::Cursor::new(data), &mut decompressed)
-                .map_err(|e| AssetError::Io(format!("decompression failed: {}", e)))?;
-            String::from_utf8_lossy(&decompressed).to_string()
-        };
+        Self::from_ron(&asset_text(data)?, false).map(|(asset, _)| asset)
+    }
 
-        let mut asset: Self = ron::from_str(&ron_str).map_err(|e| AssetError::Serialization(e.to_string()))?;
+    /// Parse RON text and check the meshes, repairing them with `fix`
+    fn from_ron(ron_str: &str, fix: bool) -> Result<(Self, Vec<LoadIssue>), AssetError> {
+        let mut asset: Self = ron::from_str(ron_str)
+            .map_err(|e| AssetError::Serialization(describe_ron_error(ron_str, &e)))?;
+        let issues = asset.check_meshes(fix);
+        if let Some(problems) = unresolved(&issues) {
+            return Err(AssetError::ValidationError(problems));
+        }
         // Resolve texture refs to populate atlas fields
         asset.resolve_texture_refs();
-        Ok(asset)
+        Ok((asset, issues))
+    }
+
+    /// Check mesh data that would break rendering: faces with fewer than 3
+    /// corners or corners past the vertex list, and embedded textures whose
+    /// pixels don't match their size or palette. With `fix`, broken faces are
+    /// dropped and texture pixels padded, cut or reset to 0 (transparent).
+    pub fn check_meshes(&mut self, fix: bool) -> Vec<LoadIssue> {
+        let mut issues = Vec::new();
+        for (c, component) in self.components.iter_mut().enumerate() {
+            let AssetComponent::Mesh { parts } = component else {
+                continue;
+            };
+            for (p, part) in parts.iter_mut().enumerate() {
+                let field = format!("components[{}] > parts[{}]", c, p);
+                let vertex_count = part.mesh.vertices.len();
+                let broken: Vec<usize> = (0..part.mesh.faces.len())
+                    .filter(|&f| {
+                        let corners = &part.mesh.faces[f].vertices;
+                        corners.len() < 3 || corners.iter().any(|&v| v >= vertex_count)
+                    })
+                    .collect();
+                if let Some(&first) = broken.first() {
+                    let mut message = format!("face uses a missing vertex or has fewer than 3 ({} vertices)", vertex_count);
+                    if broken.len() > 1 {
+                        message.push_str(&format!(", {} more like it", broken.len() - 1));
+                    }
+                    issues.push(LoadIssue::repairable(format!("{} > mesh > faces[{}]", field, first), message, fix));
+                    if fix {
+                        for f in broken.into_iter().rev() {
+                            part.mesh.faces.remove(f);
+                        }
+                    }
+                }
+
+                if let crate::modeler::TextureRef::Embedded(atlas) = &mut part.texture_ref {
+                    let field = format!("{} > texture_ref", field);
+                    let expected = atlas.width * atlas.height;
+                    if atlas.indices.len() != expected {
+                        issues.push(LoadIssue::repairable(format!("{} > indices", field), format!(
+                            "a {}x{} texture has {} pixels, found {}", atlas.width, atlas.height, expected, atlas.indices.len()
+                        ), fix));
+                        if fix {
+                            atlas.indices.resize(expected, 0);
+                        }
+                    }
+                    let max_index = atlas.depth.max_index();
+                    if let Some(i) = atlas.indices.iter().position(|&index| index > max_index) {
+                        issues.push(LoadIssue::repairable(format!("{} > indices[{}]", field, i), format!(
+                            "index {} is past the {:?} palette (max {})", atlas.indices[i], atlas.depth, max_index
+                        ), fix));
+                        if fix {
+                            for index in atlas.indices.iter_mut().filter(|index| **index > max_index) {
+                                *index = 0;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        issues
     }
 
     /// Resolve texture references and populate atlas fields
@@ -486,10 +710,81 @@ mod tests {
         assert_eq!(asset.components.len(), 0);
     }
 
+    #[test]
+    fn test_component_issues() {
+        let mut asset = Asset::new("crate");
+        assert!(asset.component_issues().is_empty());
+
+        let trigger = |id: &str| AssetComponent::Trigger {
+            trigger_id: id.to_string(),
+            on_enter: None,
+            on_exit: None,
+        };
+        asset.add_component(trigger("lever"));
+        asset.add_component(trigger("lever"));
+        asset.add_component(trigger(""));
+        asset.add_component(AssetComponent::Mesh { parts: Vec::new() });
+        asset.add_component(AssetComponent::Audio {
+            sound: "drip".to_string(),
+            volume: 1.0,
+            radius: 512.0,
+            looping: true,
+        });
+
+        let issues = asset.component_issues();
+        assert_eq!(issues.len(), 3, "{:?}", issues);
+        assert_eq!(issues[0].0, 2, "repeated trigger id");
+        assert_eq!(issues[1], (3, "Trigger ID is empty".to_string()));
+        assert_eq!(issues[2], (4, "Only the first Mesh component is used".to_string()));
+    }
+
+    #[test]
+    fn test_check_meshes() {
+        let mut asset = Asset::new("crate");
+        let parts = asset.mesh_mut().unwrap();
+        let faces = parts[0].mesh.faces.len();
+        parts[0].mesh.faces.push(crate::modeler::EditFace::tri(0, 1, 10_000));
+        parts[0].mesh.faces.push(crate::modeler::EditFace::ngon(&[0, 1]));
+        let ron = ron::to_string(&asset).unwrap();
+
+        let Err(AssetError::ValidationError(message)) = Asset::from_ron(&ron, false) else {
+            panic!("expected a validation error");
+        };
+        assert!(message.contains(&format!("components[0] > parts[0] > mesh > faces[{}]", faces)), "{}", message);
+        assert!(message.contains("1 more like it"), "{}", message);
+
+        let (fixed, issues) = Asset::from_ron(&ron, true).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(fixed.mesh().unwrap()[0].mesh.faces.len(), faces);
+    }
+
     #[test]
     fn test_unique_ids() {
         let asset1 = Asset::new("asset1");
         let asset2 = Asset::new("asset2");
         assert_ne!(asset1.id, asset2.id);
     }
+
+    #[test]
+    fn test_lod_selection() {
+        let mut asset = Asset::new("tree");
+        assert_eq!(asset.lod_level(100_000.0, 1.0), LodLevel::Full);
+
+        let full = asset.mesh().cloned().unwrap();
+        asset.add_component(AssetComponent::Lod {
+            reduced: full.clone(),
+            reduced_distance: 4096.0,
+            impostor: Vec::new(),
+            impostor_distance: 8192.0,
+        });
+
+        assert_eq!(asset.lod_level(1000.0, 1.0), LodLevel::Full);
+        assert_eq!(asset.lod_level(5000.0, 1.0), LodLevel::Reduced);
+        // Empty impostor level is skipped
+        assert_eq!(asset.lod_level(20_000.0, 1.0), LodLevel::Reduced);
+        // Scale pushes the switch point out
+        assert_eq!(asset.lod_level(5000.0, 2.0), LodLevel::Full);
+        // Empty level falls back to the full mesh
+        assert!(asset.lod_mesh(LodLevel::Impostor).is_some());
+    }
 }
@@ -8,7 +8,9 @@
 
 use serde::{Deserialize, Serialize};
 use crate::modeler::{MeshPart, RigBone};
+use crate::rasterizer::BlendMode;
 use crate::game::components::{EnemyType, ItemType};
+use super::dialogue::{dialogue_issues, DialogueNode};
 
 /// Components that can be attached to an asset
 ///
@@ -35,6 +37,9 @@ pub enum AssetComponent {
         /// If false, this is a solid collider (blocks movement)
         #[serde(default)]
         is_trigger: bool,
+        /// Shape origin relative to the asset origin (assets can combine several shapes)
+        #[serde(default)]
+        offset: [f32; 3],
     },
 
     /// Point light attached to asset
@@ -90,18 +95,109 @@ pub enum AssetComponent {
         /// Patrol radius in world units (for AI)
         #[serde(default)]
         patrol_radius: f32,
+        /// Poise: hits wear it down, at zero the enemy staggers (0 = never staggers)
+        #[serde(default = "default_enemy_poise")]
+        poise: f32,
+        /// Its melee attack (timing, reach, poise damage)
+        #[serde(default)]
+        attack: AttackDef,
+    },
+
+    /// Melee weapon the player wields (on the player start asset, or picked
+    /// up in the level and equipped from the inventory)
+    ///
+    /// Light and heavy attacks with their active frames, plus how well it
+    /// guards: blocked hits deal the unabsorbed rest as chip damage and drain
+    /// stamina.
+    Weapon {
+        /// Base damage, scaled per attack
+        damage: i32,
+        /// Light attack (Attack)
+        #[serde(default = "AttackDef::light")]
+        light: AttackDef,
+        /// Heavy attack (Strong Attack)
+        #[serde(default = "AttackDef::heavy")]
+        heavy: AttackDef,
+        /// Fraction of a blocked hit's damage the guard absorbs (0..1)
+        #[serde(default = "default_guard_absorption")]
+        guard_absorption: f32,
+        /// Stamina lost per point of damage blocked
+        #[serde(default = "default_guard_stamina")]
+        guard_stamina: f32,
+        /// Swing speed multiplier on both attacks (2 = twice as fast)
+        #[serde(default = "default_weapon_speed")]
+        speed: f32,
+        /// Mesh part held in the player's hand (None = the whole mesh)
+        #[serde(default)]
+        model_part: Option<String>,
+    },
+
+    /// Armor the player wears (on the player start asset, or picked up in
+    /// the level and equipped from the inventory)
+    ///
+    /// Defense takes a share off every hit; weight slows the dodge roll
+    /// down in tiers (light, medium, heavy).
+    Armor {
+        /// Fraction of hit damage absorbed (0..1)
+        defense: f32,
+        /// Equip weight (sets the roll tier)
+        #[serde(default)]
+        weight: f32,
     },
 
     /// Interactive door
     ///
-    /// Can be locked, requiring a key to open.
+    /// Blocks movement while closed. Opens with Interact, when the player
+    /// enters its trigger, or from a script; can be locked, requiring a key.
     Door {
-        /// Key required to open (None = unlocked)
+        /// Key required to open: the name of a key pickup (None = unlocked)
         #[serde(default)]
         required_key: Option<String>,
         /// Whether the door starts in the open state
         #[serde(default)]
         start_open: bool,
+        /// How the door moves when opening
+        #[serde(default)]
+        motion: DoorMotion,
+        /// Seconds to fully open or close
+        #[serde(default = "default_door_open_time")]
+        open_time: f32,
+        /// Trigger id that opens the door when the player enters it (None = Interact only)
+        #[serde(default)]
+        open_trigger: Option<String>,
+    },
+
+    /// Moving platform or elevator
+    ///
+    /// Travels its route (the path the instance is attached to, or up and
+    /// down between two points), stopping at each waypoint. Characters
+    /// standing on it ride along. With an activation trigger or a lever it
+    /// only moves one stop per activation.
+    MovingPlatform {
+        /// Where it travels
+        #[serde(default)]
+        route: PlatformRoute,
+        /// World units per second
+        #[serde(default = "default_platform_speed")]
+        speed: f32,
+        /// Seconds it waits at each stop
+        #[serde(default = "default_platform_wait")]
+        wait: f32,
+        /// Trigger id that sends it to its next stop (None = no trigger)
+        #[serde(default)]
+        activate_trigger: Option<String>,
+        /// Interact next to it (or riding it) sends it to its next stop
+        #[serde(default)]
+        lever: bool,
+    },
+
+    /// Conversation the player starts with Interact (NPCs, signs)
+    ///
+    /// A tree of lines and choices; see `asset::dialogue`. Nodes can require
+    /// and set world flags (the level scripts' variables).
+    Dialogue {
+        /// Opens at the first node whose condition holds
+        nodes: Vec<DialogueNode>,
     },
 
     /// Audio source
@@ -122,13 +218,87 @@ pub enum AssetComponent {
 
     /// Particle emitter
     ///
-    /// Smoke, fire, sparkles, etc.
+    /// Smoke, sparks, dust, blood, fog wisps. Drawn as camera-facing quads
+    /// in play mode (see `game::particles`).
     Particle {
-        /// Particle effect identifier
+        /// Preset the emitter settings were last loaded from (see `PARTICLE_PRESETS`)
         effect: String,
         /// Offset from asset origin
         #[serde(default)]
         offset: [f32; 3],
+        /// Spawn rate, lifetime, velocity cone, gravity and look
+        #[serde(default)]
+        emitter: ParticleEmitterDef,
+    },
+
+    /// Camera-facing sprite (pickups, flames, grass)
+    ///
+    /// Draws a library texture as a billboard that always faces the camera,
+    /// depth-tested so it sorts with the level around it (see
+    /// `rasterizer::billboard`).
+    Sprite {
+        /// Texture library id (None = nothing to draw)
+        #[serde(default)]
+        texture: Option<u64>,
+        /// World units per texel
+        #[serde(default = "default_sprite_scale")]
+        scale: f32,
+        /// How the sprite combines with the scene (Opaque writes depth)
+        #[serde(default)]
+        blend: BlendMode,
+        /// Center offset from asset origin
+        #[serde(default)]
+        offset: [f32; 3],
+    },
+
+    /// Status effect (poison, bleed, slow, buffs), named after the asset
+    ///
+    /// The asset's Hazard applies it to the player standing in it, its Pickup
+    /// gives it to whoever collects it and its Enemy inflicts it on hit.
+    /// Scripts apply it by the asset's name (see `game::status`).
+    StatusEffect {
+        /// Preset the settings were last loaded from (see `STATUS_PRESETS`)
+        preset: String,
+        /// Duration, stacking, damage over time, speed and buildup
+        #[serde(default)]
+        effect: StatusEffectDef,
+    },
+
+    /// Trap or harmful area (spikes, poison gas, fire)
+    ///
+    /// Hurts the player within `radius` every `interval` seconds and applies
+    /// the asset's StatusEffect, if it has one.
+    Hazard {
+        /// Horizontal reach in world units
+        radius: f32,
+        /// Seconds between hits while the player stays inside
+        #[serde(default = "default_hazard_interval")]
+        interval: f32,
+        /// Damage per hit
+        #[serde(default)]
+        damage: i32,
+        /// Buildup added per hit (effects without buildup just apply)
+        #[serde(default)]
+        buildup: f32,
+    },
+
+    /// Breakable prop (crates, pots, barrels)
+    ///
+    /// Player attacks in reach take `health` off; when it runs out the prop
+    /// bursts into its debris particles, leaves its drop behind and swaps to
+    /// the broken mesh (or disappears when there is none).
+    Destructible {
+        /// Damage it takes to break (each attack deals `ATTACK_DAMAGE`)
+        health: i32,
+        /// Particle preset burst when it breaks (see `PARTICLE_PRESETS`, empty = none)
+        #[serde(default)]
+        debris: String,
+        /// Pickup asset left where it broke (asset name)
+        #[serde(default)]
+        drop: Option<String>,
+        /// Mesh parts drawn once broken (empty = the prop disappears)
+        #[serde(default)]
+        broken: Vec<MeshPart>,
     },
 
     /// Character controller for movement
@@ -165,6 +335,508 @@ pub enum AssetComponent {
         /// The bone hierarchy
         bones: Vec<RigBone>,
     },
+
+    /// Level-of-detail variants of the Mesh component
+    ///
+    /// The Mesh component is the full-detail version. Past `reduced_distance`
+    /// the reduced mesh is drawn instead, and past `impostor_distance` the
+    /// impostor is drawn as a Y-axis billboard turned to face the camera.
+    /// Empty levels are skipped (the next more detailed level is used).
+    Lod {
+        /// Reduced-detail mesh parts
+        #[serde(default)]
+        reduced: Vec<MeshPart>,
+        /// Camera distance (world units) where the reduced mesh takes over
+        #[serde(default = "default_lod_reduced_distance")]
+        reduced_distance: f32,
+        /// Billboard impostor parts (typically a single textured quad facing +Z)
+        #[serde(default)]
+        impostor: Vec<MeshPart>,
+        /// Camera distance (world units) where the impostor takes over
+        #[serde(default = "default_lod_impostor_distance")]
+        impostor_distance: f32,
+    },
+}
+
+fn default_door_open_time() -> f32 {
+    1.0
+}
+
+fn default_platform_speed() -> f32 {
+    1024.0 // A sector per second
+}
+
+fn default_platform_wait() -> f32 {
+    1.0
+}
+
+fn default_hazard_interval() -> f32 {
+    1.0
+}
+
+fn default_sprite_scale() -> f32 {
+    16.0
+}
+
+/// Blend modes a Sprite can use, with their editor labels
+pub const SPRITE_BLENDS: [(BlendMode, &str); 5] = [
+    (BlendMode::Opaque, "Opaque"),
+    (BlendMode::Average, "Average"),
+    (BlendMode::Add, "Add"),
+    (BlendMode::Subtract, "Subtract"),
+    (BlendMode::AddQuarter, "Add 25%"),
+];
+
+/// How a door moves from closed to open
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DoorMotion {
+    /// Slides by an offset in the asset's local space (e.g. up into the ceiling)
+    Slide { offset: [f32; 3] },
+    /// Swings around the asset origin's vertical axis (degrees, model the hinge at the origin)
+    Rotate { angle: f32 },
+}
+
+impl Default for DoorMotion {
+    fn default() -> Self {
+        DoorMotion::Slide { offset: [0.0, 1024.0, 0.0] }
+    }
+}
+
+impl DoorMotion {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DoorMotion::Slide { .. } => "Slide",
+            DoorMotion::Rotate { .. } => "Rotate",
+        }
+    }
+
+    /// Local offset and extra yaw (radians) at `amount` open (0 = closed, 1 = open)
+    pub fn pose(&self, amount: f32) -> ([f32; 3], f32) {
+        let t = amount.clamp(0.0, 1.0);
+        match *self {
+            DoorMotion::Slide { offset } => ([offset[0] * t, offset[1] * t, offset[2] * t], 0.0),
+            DoorMotion::Rotate { angle } => ([0.0; 3], angle.to_radians() * t),
+        }
+    }
+}
+
+/// Where a moving platform travels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PlatformRoute {
+    /// Follow the path the instance is attached to in the World Editor
+    Path,
+    /// Go back and forth between where it's placed and an offset in the
+    /// asset's local space (e.g. straight up for an elevator)
+    Elevator { offset: [f32; 3] },
+}
+
+impl Default for PlatformRoute {
+    fn default() -> Self {
+        PlatformRoute::Elevator { offset: [0.0, 2048.0, 0.0] }
+    }
+}
+
+impl PlatformRoute {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlatformRoute::Path => "Path",
+            PlatformRoute::Elevator { .. } => "Elevator",
+        }
+    }
+
+    /// Where an elevator ends up, relative to the instance turned to `facing`
+    /// (yaw, 0 = +Z). None for path routes.
+    pub fn end_offset(&self, facing: f32) -> Option<[f32; 3]> {
+        let PlatformRoute::Elevator { offset } = *self else {
+            return None;
+        };
+        let (sin_f, cos_f) = facing.sin_cos();
+        Some([offset[0] * cos_f - offset[2] * sin_f, offset[1], offset[0] * sin_f + offset[2] * cos_f])
+    }
+}
+
+/// Built-in particle presets, in the order the editor cycles them
+pub const PARTICLE_PRESETS: [&str; 5] = ["smoke", "sparks", "dust", "blood", "fog"];
+
+/// Cap on live particles per emitter
+pub const MAX_PARTICLES_PER_EMITTER: usize = 256;
+
+/// How particles combine with the scene behind them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ParticleBlend {
+    /// Mix by opacity (smoke, dust, blood)
+    #[default]
+    Alpha,
+    /// Add light to the scene (sparks, embers)
+    Additive,
+}
+
+impl ParticleBlend {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ParticleBlend::Alpha => "Alpha",
+            ParticleBlend::Additive => "Additive",
+        }
+    }
+
+    /// The other mode
+    pub fn toggled(self) -> Self {
+        match self {
+            ParticleBlend::Alpha => ParticleBlend::Additive,
+            ParticleBlend::Additive => ParticleBlend::Alpha,
+        }
+    }
+}
+
+/// Particle emitter settings (the simulation lives in `game::particles`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParticleEmitterDef {
+    /// Particles spawned per second
+    pub rate: f32,
+    /// Seconds each particle lives
+    pub lifetime: f32,
+    /// Launch speed in world units per second
+    pub speed: f32,
+    /// Axis of the launch cone (world axes, normalized when used)
+    pub direction: [f32; 3],
+    /// Half-angle of the launch cone in degrees (180 = any direction)
+    pub spread: f32,
+    /// Downward acceleration in world units/s² (negative drifts up, like smoke)
+    pub gravity: f32,
+    /// Quad size in world units at birth
+    pub start_size: f32,
+    /// Quad size in world units at death
+    pub end_size: f32,
+    /// Color at birth (RGB)
+    pub start_color: [u8; 3],
+    /// Color at death (RGB)
+    pub end_color: [u8; 3],
+    /// Opacity at birth (0-255); particles fade out over their lifetime
+    pub opacity: u8,
+    pub blend: ParticleBlend,
+}
+
+impl Default for ParticleEmitterDef {
+    fn default() -> Self {
+        Self {
+            rate: 8.0,
+            lifetime: 3.0,
+            speed: 200.0,
+            direction: [0.0, 1.0, 0.0],
+            spread: 15.0,
+            gravity: -40.0,
+            start_size: 96.0,
+            end_size: 320.0,
+            start_color: [90, 90, 90],
+            end_color: [60, 60, 60],
+            opacity: 160,
+            blend: ParticleBlend::Alpha,
+        }
+    }
+}
+
+impl ParticleEmitterDef {
+    /// Settings for a built-in preset (smoke is the default)
+    pub fn preset(name: &str) -> Option<Self> {
+        let smoke = Self::default();
+        Some(match name {
+            "smoke" => smoke,
+            "sparks" => Self {
+                rate: 40.0,
+                lifetime: 0.5,
+                speed: 900.0,
+                spread: 60.0,
+                gravity: 2000.0,
+                start_size: 16.0,
+                end_size: 8.0,
+                start_color: [255, 220, 120],
+                end_color: [255, 80, 20],
+                opacity: 255,
+                blend: ParticleBlend::Additive,
+                ..smoke
+            },
+            "dust" => Self {
+                rate: 6.0,
+                lifetime: 4.0,
+                speed: 40.0,
+                spread: 180.0,
+                gravity: -5.0,
+                start_size: 24.0,
+                end_size: 24.0,
+                start_color: [180, 160, 130],
+                end_color: [140, 120, 100],
+                opacity: 90,
+                ..smoke
+            },
+            "blood" => Self {
+                rate: 40.0,
+                lifetime: 0.8,
+                speed: 600.0,
+                spread: 45.0,
+                gravity: 2400.0,
+                start_size: 24.0,
+                end_size: 16.0,
+                start_color: [150, 10, 10],
+                end_color: [90, 0, 0],
+                opacity: 230,
+                ..smoke
+            },
+            "fog" => Self {
+                rate: 2.0,
+                lifetime: 8.0,
+                speed: 30.0,
+                direction: [1.0, 0.0, 0.0],
+                spread: 30.0,
+                gravity: 0.0,
+                start_size: 512.0,
+                end_size: 900.0,
+                start_color: [170, 175, 185],
+                end_color: [150, 155, 165],
+                opacity: 50,
+                ..smoke
+            },
+            _ => return None,
+        })
+    }
+
+    /// Most particles alive at once: rate x lifetime, capped
+    pub fn max_alive(&self) -> usize {
+        ((self.rate.max(0.0) * self.lifetime.max(0.0)).ceil() as usize).min(MAX_PARTICLES_PER_EMITTER)
+    }
+}
+
+/// Built-in status effect presets, in the order the editor shows them
+pub const STATUS_PRESETS: [&str; 5] = ["poison", "bleed", "slow", "regen", "haste"];
+
+/// What applying a status effect does while it's already running
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StatusStacking {
+    /// Restart the timer
+    #[default]
+    Refresh,
+    /// Add the duration to the time left (up to `max_stacks` durations)
+    Extend,
+    /// Add a stack (up to `max_stacks`) and restart the timer; damage and speed apply per stack
+    Stack,
+    /// Leave the running effect alone
+    Ignore,
+}
+
+impl StatusStacking {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StatusStacking::Refresh => "Refresh",
+            StatusStacking::Extend => "Extend",
+            StatusStacking::Stack => "Stack",
+            StatusStacking::Ignore => "Ignore",
+        }
+    }
+
+    /// The next rule, for cycling in the editor
+    pub fn next(self) -> Self {
+        match self {
+            StatusStacking::Refresh => StatusStacking::Extend,
+            StatusStacking::Extend => StatusStacking::Stack,
+            StatusStacking::Stack => StatusStacking::Ignore,
+            StatusStacking::Ignore => StatusStacking::Refresh,
+        }
+    }
+}
+
+/// Status effect settings (the runtime lives in `game::status`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusEffectDef {
+    /// Seconds the effect lasts once it takes hold (0 = just the burst)
+    pub duration: f32,
+    pub stacking: StatusStacking,
+    /// Cap on `Stack` stacks and on `Extend` durations
+    pub max_stacks: u32,
+    /// Health lost per second per stack (negative heals)
+    pub damage_per_second: f32,
+    /// Movement speed factor per stack (0.5 = half speed)
+    pub speed_multiplier: f32,
+    /// Buildup needed before the effect takes hold (0 = takes hold at once)
+    pub buildup: f32,
+    /// Buildup lost per second
+    pub buildup_decay: f32,
+    /// Damage dealt the moment the effect takes hold
+    pub burst_damage: i32,
+    /// HUD icon text (up to 3 characters of the HUD font)
+    pub icon: String,
+    /// HUD icon color (RGB)
+    pub color: [u8; 3],
+}
+
+impl Default for StatusEffectDef {
+    fn default() -> Self {
+        Self {
+            duration: 10.0,
+            stacking: StatusStacking::Stack,
+            max_stacks: 3,
+            damage_per_second: 2.0,
+            speed_multiplier: 1.0,
+            buildup: 0.0,
+            buildup_decay: 0.0,
+            burst_damage: 0,
+            icon: "PSN".to_string(),
+            color: [120, 200, 60],
+        }
+    }
+}
+
+impl StatusEffectDef {
+    /// Settings for a built-in preset (poison is the default)
+    pub fn preset(name: &str) -> Option<Self> {
+        let poison = Self::default();
+        Some(match name {
+            "poison" => poison,
+            "bleed" => Self {
+                duration: 0.0,
+                stacking: StatusStacking::Refresh,
+                max_stacks: 1,
+                damage_per_second: 0.0,
+                buildup: 100.0,
+                buildup_decay: 10.0,
+                burst_damage: 30,
+                icon: "BLD".to_string(),
+                color: [200, 30, 30],
+                ..poison
+            },
+            "slow" => Self {
+                duration: 4.0,
+                stacking: StatusStacking::Refresh,
+                max_stacks: 1,
+                damage_per_second: 0.0,
+                speed_multiplier: 0.5,
+                icon: "SLW".to_string(),
+                color: [90, 140, 220],
+                ..poison
+            },
+            "regen" => Self {
+                duration: 10.0,
+                stacking: StatusStacking::Extend,
+                damage_per_second: -3.0,
+                icon: "REG".to_string(),
+                color: [80, 220, 120],
+                ..poison
+            },
+            "haste" => Self {
+                duration: 8.0,
+                stacking: StatusStacking::Refresh,
+                max_stacks: 1,
+                damage_per_second: 0.0,
+                speed_multiplier: 1.4,
+                icon: "SPD".to_string(),
+                color: [250, 210, 80],
+                ..poison
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Timing and reach of a melee attack, in frames of its animation
+///
+/// The hitbox is a sphere `reach` in front of the attacker, out from
+/// `active_start` to `active_end` (inclusive); the rest of the frames are
+/// windup and recovery, during which the attacker can't move or act.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AttackDef {
+    /// Animation clip the attack plays (name in the asset's model)
+    pub animation: String,
+    /// Animation frames per second
+    pub fps: f32,
+    /// Length of the attack in frames
+    pub frames: u32,
+    /// First frame the hitbox is out
+    pub active_start: u32,
+    /// Last frame the hitbox is out
+    pub active_end: u32,
+    /// Multiplier on the weapon's (or enemy's) base damage
+    pub damage_scale: f32,
+    /// Poise damage dealt on hit
+    pub poise_damage: f32,
+    /// Stamina spent to swing
+    pub stamina_cost: f32,
+    /// Distance from the attacker to the hitbox center (world units)
+    pub reach: f32,
+    /// Hitbox radius (world units)
+    pub radius: f32,
+}
+
+impl Default for AttackDef {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+impl AttackDef {
+    /// A quick swing
+    pub fn light() -> Self {
+        Self {
+            animation: "attack_light".to_string(),
+            fps: 30.0,
+            frames: 24,
+            active_start: 8,
+            active_end: 12,
+            damage_scale: 1.0,
+            poise_damage: 15.0,
+            stamina_cost: 15.0,
+            reach: 600.0,
+            radius: 400.0,
+        }
+    }
+
+    /// A slow, hard-hitting swing
+    pub fn heavy() -> Self {
+        Self {
+            animation: "attack_heavy".to_string(),
+            frames: 40,
+            active_start: 18,
+            active_end: 23,
+            damage_scale: 1.8,
+            poise_damage: 40.0,
+            stamina_cost: 30.0,
+            reach: 700.0,
+            radius: 450.0,
+            ..Self::light()
+        }
+    }
+
+    /// Seconds from start to the end of recovery
+    pub fn duration(&self) -> f32 {
+        self.frames as f32 / self.fps.max(1.0)
+    }
+
+    /// Animation frame `time` seconds into the attack
+    pub fn frame_at(&self, time: f32) -> u32 {
+        (time.max(0.0) * self.fps.max(1.0)) as u32
+    }
+
+    /// Is the hitbox out `time` seconds into the attack?
+    pub fn is_active(&self, time: f32) -> bool {
+        let frame = self.frame_at(time);
+        frame >= self.active_start && frame <= self.active_end && frame < self.frames
+    }
+}
+
+fn default_enemy_poise() -> f32 {
+    30.0
+}
+
+fn default_guard_absorption() -> f32 {
+    0.7
+}
+
+fn default_guard_stamina() -> f32 {
+    1.0
+}
+
+fn default_weapon_speed() -> f32 {
+    1.0
 }
 
 fn default_volume() -> f32 {
@@ -175,6 +847,14 @@ fn default_step_height() -> f32 {
     384.0 // Default from game::components::character
 }
 
+fn default_lod_reduced_distance() -> f32 {
+    8192.0 // 8 sectors
+}
+
+fn default_lod_impostor_distance() -> f32 {
+    16384.0 // 16 sectors
+}
+
 impl AssetComponent {
     /// Get a human-readable name for this component type
     pub fn type_name(&self) -> &'static str {
@@ -185,12 +865,21 @@ impl AssetComponent {
             AssetComponent::Trigger { .. } => "Trigger",
             AssetComponent::Pickup { .. } => "Pickup",
             AssetComponent::Enemy { .. } => "Enemy",
+            AssetComponent::Weapon { .. } => "Weapon",
+            AssetComponent::Armor { .. } => "Armor",
             AssetComponent::Door { .. } => "Door",
+            AssetComponent::MovingPlatform { .. } => "MovingPlatform",
+            AssetComponent::Dialogue { .. } => "Dialogue",
             AssetComponent::Audio { .. } => "Audio",
             AssetComponent::Particle { .. } => "Particle",
+            AssetComponent::Sprite { .. } => "Sprite",
+            AssetComponent::StatusEffect { .. } => "StatusEffect",
+            AssetComponent::Hazard { .. } => "Hazard",
+            AssetComponent::Destructible { .. } => "Destructible",
             AssetComponent::CharacterController { .. } => "CharacterController",
             AssetComponent::SpawnPoint { .. } => "SpawnPoint",
             AssetComponent::Skeleton { .. } => "Skeleton",
+            AssetComponent::Lod { .. } => "Lod",
         }
     }
 
@@ -203,12 +892,21 @@ impl AssetComponent {
             AssetComponent::Trigger { .. } => '\u{E8B8}', // flag icon
             AssetComponent::Pickup { .. } => '\u{E838}', // star icon
             AssetComponent::Enemy { .. } => '\u{E87C}', // skull icon
+            AssetComponent::Weapon { .. } => '\u{E9E0}', // shield icon
+            AssetComponent::Armor { .. } => '\u{E32A}', // security icon (shield)
             AssetComponent::Door { .. } => '\u{E88A}', // door icon
+            AssetComponent::MovingPlatform { .. } => '\u{E5D8}', // arrow upward icon
+            AssetComponent::Dialogue { .. } => '\u{E0B7}', // chat icon
             AssetComponent::Audio { .. } => '\u{E050}', // speaker icon
             AssetComponent::Particle { .. } => '\u{E3A5}', // sparkle icon
+            AssetComponent::Sprite { .. } => '\u{E3F4}', // image icon
+            AssetComponent::StatusEffect { .. } => '\u{E3F3}', // healing icon
+            AssetComponent::Hazard { .. } => '\u{E002}', // warning icon
+            AssetComponent::Destructible { .. } => '\u{E3C9}', // broken image icon
             AssetComponent::CharacterController { .. } => '\u{E7FD}', // person icon
             AssetComponent::SpawnPoint { .. } => '\u{E566}', // location icon
             AssetComponent::Skeleton { .. } => '\u{E91B}', // accessibility icon (stick figure)
+            AssetComponent::Lod { .. } => '\u{E3E8}', // layers icon
         }
     }
 
@@ -232,6 +930,14 @@ impl AssetComponent {
         matches!(self, AssetComponent::Enemy { .. })
     }
 
+    /// The status effect settings, if this is a StatusEffect component
+    pub fn status_effect(&self) -> Option<&StatusEffectDef> {
+        match self {
+            AssetComponent::StatusEffect { effect, .. } => Some(effect),
+            _ => None,
+        }
+    }
+
     /// Check if this is a SpawnPoint component
     pub fn is_spawn_point(&self) -> bool {
         matches!(self, AssetComponent::SpawnPoint { .. })
@@ -241,6 +947,149 @@ impl AssetComponent {
     pub fn is_skeleton(&self) -> bool {
         matches!(self, AssetComponent::Skeleton { .. })
     }
+
+    /// Check if this is a Lod component
+    pub fn is_lod(&self) -> bool {
+        matches!(self, AssetComponent::Lod { .. })
+    }
+
+    /// Can an asset hold more than one of this component? Runtime lookups
+    /// only read the first Mesh, Enemy, Door, etc., so extras would be ignored.
+    pub fn allows_multiple(&self) -> bool {
+        matches!(
+            self,
+            AssetComponent::Collision { .. }
+                | AssetComponent::Light { .. }
+                | AssetComponent::Trigger { .. }
+                | AssetComponent::Audio { .. }
+                | AssetComponent::Particle { .. }
+                | AssetComponent::Sprite { .. }
+        )
+    }
+
+    /// Problems with this component's own settings, as messages for the
+    /// inspector (empty = valid). See `Asset::component_issues` for checks
+    /// across components.
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        let mut check = |ok: bool, msg: &str| {
+            if !ok {
+                issues.push(msg.to_string());
+            }
+        };
+        match self {
+            AssetComponent::Collision { shape, .. } => {
+                let ok = match shape {
+                    CollisionShapeDef::Sphere { radius } => *radius > 0.0,
+                    CollisionShapeDef::Box { half_extents } => half_extents.iter().all(|&h| h > 0.0),
+                    CollisionShapeDef::Capsule { radius, height } => *radius > 0.0 && *height >= 0.0,
+                    CollisionShapeDef::Cylinder { radius, height } => *radius > 0.0 && *height > 0.0,
+                    CollisionShapeDef::FromMesh => true,
+                };
+                check(ok, "Shape has zero size");
+            }
+            AssetComponent::Light { intensity, radius, .. } => {
+                check(*radius > 0.0, "Radius must be above zero");
+                check(*intensity > 0.0, "Intensity is zero (light is off)");
+            }
+            AssetComponent::Trigger { trigger_id, on_enter, on_exit } => {
+                check(!trigger_id.trim().is_empty(), "Trigger ID is empty");
+                check(on_enter.as_deref() != Some(""), "Enter event is empty");
+                check(on_exit.as_deref() != Some(""), "Exit event is empty");
+            }
+            AssetComponent::Pickup { item_type, respawn_time } => {
+                let amount = match item_type {
+                    ItemType::HealthPickup { amount } | ItemType::Currency { amount } => *amount,
+                    _ => 1,
+                };
+                check(amount > 0, "Amount must be above zero");
+                check(respawn_time.map(|t| t > 0.0).unwrap_or(true), "Respawn time must be above zero");
+            }
+            AssetComponent::Enemy { health, damage, patrol_radius, .. } => {
+                check(*health > 0, "Health must be above zero");
+                check(*damage >= 0, "Damage can't be negative");
+                check(*patrol_radius >= 0.0, "Patrol radius can't be negative");
+            }
+            AssetComponent::Weapon { damage, speed, model_part, .. } => {
+                check(*damage >= 0, "Damage can't be negative");
+                check(*speed > 0.0, "Speed must be above zero");
+                check(model_part.as_deref() != Some(""), "Model part is empty");
+            }
+            AssetComponent::Armor { defense, weight } => {
+                check((0.0..=1.0).contains(defense), "Defense must be between 0 and 1");
+                check(*weight >= 0.0, "Weight can't be negative");
+            }
+            AssetComponent::Door { required_key, open_time, open_trigger, .. } => {
+                check(*open_time > 0.0, "Open time must be above zero");
+                check(required_key.as_deref() != Some(""), "Required key is empty");
+                check(open_trigger.as_deref() != Some(""), "Open trigger is empty");
+            }
+            AssetComponent::MovingPlatform { route, speed, wait, activate_trigger, .. } => {
+                check(*speed > 0.0, "Speed must be above zero");
+                check(*wait >= 0.0, "Wait can't be negative");
+                check(activate_trigger.as_deref() != Some(""), "Activation trigger is empty");
+                if let PlatformRoute::Elevator { offset } = route {
+                    check(offset.iter().any(|&v| v != 0.0), "Elevator offset is zero");
+                }
+            }
+            AssetComponent::Dialogue { nodes } => issues.extend(dialogue_issues(nodes)),
+            AssetComponent::Audio { sound, volume, radius, .. } => {
+                check(!sound.trim().is_empty(), "No sound set");
+                check((0.0..=1.0).contains(volume), "Volume must be 0-100%");
+                check(*radius > 0.0, "Radius must be above zero");
+            }
+            AssetComponent::Hazard { radius, interval, .. } => {
+                check(*radius > 0.0, "Radius must be above zero");
+                check(*interval > 0.0, "Interval must be above zero");
+            }
+            AssetComponent::Destructible { health, drop, .. } => {
+                check(*health > 0, "Health must be above zero");
+                check(drop.as_deref() != Some(""), "Drop asset name is empty");
+            }
+            AssetComponent::CharacterController { height, radius, step_height } => {
+                check(*height > 0.0 && *radius > 0.0, "Height and radius must be above zero");
+                check(*step_height < *height, "Step height must be below the character height");
+            }
+            AssetComponent::Lod { reduced, reduced_distance, impostor, impostor_distance } => {
+                check(
+                    reduced.is_empty() || impostor.is_empty() || impostor_distance > reduced_distance,
+                    "Impostor distance must be past the reduced distance",
+                );
+            }
+            AssetComponent::Sprite { texture, scale, .. } => {
+                check(texture.is_some(), "No texture (nothing is drawn)");
+                check(*scale > 0.0, "Scale must be above zero");
+            }
+            AssetComponent::Mesh { .. }
+            | AssetComponent::Particle { .. }
+            | AssetComponent::StatusEffect { .. }
+            | AssetComponent::SpawnPoint { .. }
+            | AssetComponent::Skeleton { .. } => {}
+        }
+        issues
+    }
+
+    /// Offset from the asset origin, for components placed with the move gizmo
+    pub fn offset(&self) -> Option<[f32; 3]> {
+        match self {
+            AssetComponent::Collision { offset, .. }
+            | AssetComponent::Light { offset, .. }
+            | AssetComponent::Particle { offset, .. }
+            | AssetComponent::Sprite { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Mutable offset, see `offset`
+    pub fn offset_mut(&mut self) -> Option<&mut [f32; 3]> {
+        match self {
+            AssetComponent::Collision { offset, .. }
+            | AssetComponent::Light { offset, .. }
+            | AssetComponent::Particle { offset, .. }
+            | AssetComponent::Sprite { offset, .. } => Some(offset),
+            _ => None,
+        }
+    }
 }
 
 /// Collision shape definition for assets
@@ -304,6 +1153,54 @@ impl CollisionShapeDef {
             CollisionShapeDef::FromMesh => "From Mesh".to_string(),
         }
     }
+
+    /// Distance along `axis` (0=X, 1=Y, 2=Z) from the shape origin to its resize handle.
+    ///
+    /// Capsules and cylinders stand on their origin, so their Y handle sits on
+    /// top (including the capsule's cap). FromMesh has no handles.
+    pub fn handle_extent(&self, axis: usize) -> Option<f32> {
+        match self {
+            CollisionShapeDef::Sphere { radius } => Some(*radius),
+            CollisionShapeDef::Box { half_extents } => half_extents.get(axis).copied(),
+            CollisionShapeDef::Cylinder { radius, height } => Some(if axis == 1 { *height } else { *radius }),
+            CollisionShapeDef::Capsule { radius, height } => Some(if axis == 1 { *height + *radius } else { *radius }),
+            CollisionShapeDef::FromMesh => None,
+        }
+    }
+
+    /// Resize so the handle on `axis` sits at `extent` (see `handle_extent`)
+    pub fn set_handle_extent(&mut self, axis: usize, extent: f32) {
+        let extent = extent.max(1.0);
+        match self {
+            CollisionShapeDef::Sphere { radius } => *radius = extent,
+            CollisionShapeDef::Box { half_extents } => {
+                if let Some(half) = half_extents.get_mut(axis) {
+                    *half = extent;
+                }
+            }
+            CollisionShapeDef::Cylinder { radius, height } => {
+                if axis == 1 { *height = extent } else { *radius = extent }
+            }
+            CollisionShapeDef::Capsule { radius, height } => {
+                if axis == 1 { *height = (extent - *radius).max(0.0) } else { *radius = extent }
+            }
+            CollisionShapeDef::FromMesh => {}
+        }
+    }
+
+    /// Resize handle position relative to the shape origin.
+    /// Side handles of capsules and cylinders are at half height.
+    pub fn handle_position(&self, axis: usize) -> Option<[f32; 3]> {
+        let extent = self.handle_extent(axis)?;
+        let mut pos = [0.0; 3];
+        if axis != 1 {
+            if let CollisionShapeDef::Capsule { height, .. } | CollisionShapeDef::Cylinder { height, .. } = self {
+                pos[1] = *height * 0.5;
+            }
+        }
+        pos[axis] = extent;
+        Some(pos)
+    }
 }
 
 impl Default for CollisionShapeDef {
@@ -312,3 +1209,163 @@ impl Default for CollisionShapeDef {
         CollisionShapeDef::FromMesh
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_extents_round_trip() {
+        let mut shapes = [
+            CollisionShapeDef::sphere(100.0),
+            CollisionShapeDef::box_shape(10.0, 20.0, 30.0),
+            CollisionShapeDef::capsule(50.0, 200.0),
+            CollisionShapeDef::cylinder(50.0, 200.0),
+        ];
+        for shape in &mut shapes {
+            for axis in 0..3 {
+                shape.set_handle_extent(axis, 400.0 + axis as f32);
+                assert_eq!(shape.handle_extent(axis), Some(400.0 + axis as f32));
+            }
+        }
+        assert!(CollisionShapeDef::FromMesh.handle_extent(0).is_none());
+    }
+
+    #[test]
+    fn test_capsule_handles() {
+        let mut capsule = CollisionShapeDef::capsule(50.0, 200.0);
+        assert_eq!(capsule.handle_position(1), Some([0.0, 250.0, 0.0]));
+        assert_eq!(capsule.handle_position(0), Some([50.0, 100.0, 0.0]));
+        // The top handle can't pull the body below zero height
+        capsule.set_handle_extent(1, 10.0);
+        assert!(matches!(capsule, CollisionShapeDef::Capsule { height, .. } if height == 0.0));
+    }
+
+    #[test]
+    fn test_collision_offset_defaults() {
+        let component: AssetComponent = ron::from_str("Collision(shape: Sphere(radius: 64.0))").unwrap();
+        assert_eq!(component.offset(), Some([0.0, 0.0, 0.0]));
+        let door = AssetComponent::Door {
+            required_key: None,
+            start_open: false,
+            motion: DoorMotion::default(),
+            open_time: 1.0,
+            open_trigger: None,
+        };
+        assert_eq!(door.offset(), None);
+    }
+
+    #[test]
+    fn test_moving_platform_defaults() {
+        let component: AssetComponent = ron::from_str("MovingPlatform()").unwrap();
+        match &component {
+            AssetComponent::MovingPlatform { route, speed, wait, activate_trigger, lever } => {
+                assert_eq!(*route, PlatformRoute::default());
+                assert_eq!((*speed, *wait), (1024.0, 1.0));
+                assert!(activate_trigger.is_none() && !lever);
+            }
+            _ => panic!("expected a moving platform component"),
+        }
+        assert!(component.validate().is_empty());
+        let stuck: AssetComponent = ron::from_str("MovingPlatform(route: Elevator(offset: (0.0, 0.0, 0.0)))").unwrap();
+        assert_eq!(stuck.validate(), vec!["Elevator offset is zero".to_string()]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let light = AssetComponent::Light { color: [255; 3], intensity: 1.0, radius: 512.0, offset: [0.0; 3] };
+        assert!(light.validate().is_empty());
+        let dark = AssetComponent::Light { color: [255; 3], intensity: 0.0, radius: 0.0, offset: [0.0; 3] };
+        assert_eq!(dark.validate().len(), 2);
+
+        let enemy: AssetComponent = ron::from_str("Enemy(enemy_type: Grunt, health: 0, damage: 10)").unwrap();
+        assert_eq!(enemy.validate(), vec!["Health must be above zero".to_string()]);
+        let door = AssetComponent::Door {
+            required_key: Some(String::new()),
+            start_open: false,
+            motion: DoorMotion::default(),
+            open_time: 1.0,
+            open_trigger: None,
+        };
+        assert_eq!(door.validate(), vec!["Required key is empty".to_string()]);
+        let flat = AssetComponent::Collision { shape: CollisionShapeDef::box_shape(64.0, 0.0, 64.0), is_trigger: false, offset: [0.0; 3] };
+        assert_eq!(flat.validate().len(), 1);
+
+        assert!(flat.allows_multiple());
+        assert!(!enemy.allows_multiple());
+    }
+
+    #[test]
+    fn test_particle_emitter_defaults_and_presets() {
+        // Assets saved before emitter settings load as smoke
+        let component: AssetComponent = ron::from_str("Particle(effect: \"smoke\")").unwrap();
+        match component {
+            AssetComponent::Particle { emitter, .. } => assert_eq!(emitter, ParticleEmitterDef::default()),
+            _ => panic!("expected a particle component"),
+        }
+        for name in PARTICLE_PRESETS {
+            assert!(ParticleEmitterDef::preset(name).is_some(), "{}", name);
+        }
+        assert!(ParticleEmitterDef::preset("confetti").is_none());
+        let sparks = ParticleEmitterDef::preset("sparks").unwrap();
+        assert_eq!(sparks.blend, ParticleBlend::Additive);
+        assert_eq!(sparks.max_alive(), 20);
+        let flood = ParticleEmitterDef { rate: 1000.0, lifetime: 10.0, ..Default::default() };
+        assert_eq!(flood.max_alive(), MAX_PARTICLES_PER_EMITTER);
+    }
+
+    #[test]
+    fn test_sprite_defaults() {
+        let component: AssetComponent = ron::from_str("Sprite()").unwrap();
+        match component {
+            AssetComponent::Sprite { texture, scale, blend, offset } => {
+                assert_eq!(texture, None);
+                assert_eq!(scale, 16.0);
+                assert_eq!(blend, BlendMode::Opaque);
+                assert_eq!(offset, [0.0; 3]);
+            }
+            _ => panic!("expected a sprite component"),
+        }
+        assert!(SPRITE_BLENDS.iter().all(|&(mode, _)| mode != BlendMode::Erase));
+    }
+
+    #[test]
+    fn test_status_effect_defaults_and_presets() {
+        // Missing settings load as poison, missing hazard fields as a harmless 1s tick
+        let component: AssetComponent = ron::from_str("StatusEffect(preset: \"poison\")").unwrap();
+        assert_eq!(component.status_effect(), Some(&StatusEffectDef::default()));
+        let hazard: AssetComponent = ron::from_str("Hazard(radius: 512.0)").unwrap();
+        assert!(matches!(hazard, AssetComponent::Hazard { interval, damage: 0, .. } if interval == 1.0));
+        for name in STATUS_PRESETS {
+            assert!(StatusEffectDef::preset(name).is_some(), "{}", name);
+        }
+        assert!(StatusEffectDef::preset("petrify").is_none());
+        let bleed = StatusEffectDef::preset("bleed").unwrap();
+        assert!(bleed.buildup > 0.0 && bleed.burst_damage > 0 && bleed.duration == 0.0);
+        assert!(StatusEffectDef::preset("regen").unwrap().damage_per_second < 0.0);
+
+        let mut rule = StatusStacking::default();
+        for _ in 0..4 {
+            rule = rule.next();
+        }
+        assert_eq!(rule, StatusStacking::Refresh);
+    }
+
+    #[test]
+    fn test_attack_frames_and_combat_defaults() {
+        // Enemies saved before combat settings get default poise and a light attack
+        let enemy: AssetComponent = ron::from_str("Enemy(enemy_type: Grunt, health: 100, damage: 10)").unwrap();
+        assert!(matches!(&enemy, AssetComponent::Enemy { poise, attack, .. } if *poise == 30.0 && *attack == AttackDef::light()));
+        let weapon: AssetComponent = ron::from_str("Weapon(damage: 20)").unwrap();
+        assert!(matches!(&weapon, AssetComponent::Weapon { heavy, guard_absorption, speed, model_part: None, .. }
+            if *heavy == AttackDef::heavy() && *guard_absorption == 0.7 && *speed == 1.0));
+        let armor: AssetComponent = ron::from_str("Armor(defense: 0.2)").unwrap();
+        assert!(matches!(armor, AssetComponent::Armor { weight, .. } if weight == 0.0));
+
+        let light = AttackDef::light();
+        assert_eq!(light.duration(), 0.8);
+        assert!(!light.is_active(0.2), "frame 6 is windup");
+        assert!(light.is_active(0.3), "frame 9 is active");
+        assert!(!light.is_active(0.5), "frame 15 is recovery");
+    }
+}
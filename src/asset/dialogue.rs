@@ -0,0 +1,247 @@
+//! Dialogue Trees
+//!
+//! Conversations for NPC assets (the Dialogue component). A tree is a list of
+//! nodes: each has a speaker, a line of text and either choices for the
+//! player or a `next` node (none ends the conversation). Conditions and
+//! effects work on world flags - the level scripts' variables - so scripts
+//! and conversations see the same state:
+//!
+//! ```text
+//! greet   [met == 0]  Guard: "Halt! Who goes there?"      sets met = 1
+//!   > "A friend."                       -> friend
+//!   > "Let me through." [gems >= 3]     -> bribe    sets gate = 1
+//! again               Guard: "You again?"                 (end)
+//! ```
+//!
+//! Talking opens at the first node whose condition holds, so conditional
+//! greetings go first. Choices whose condition fails aren't offered.
+//! Playback is `game::dialogue`.
+
+use serde::{Deserialize, Serialize};
+
+/// Most choices a node can offer (they all have to fit the dialogue box)
+pub const MAX_DIALOGUE_CHOICES: usize = 4;
+
+/// A line of a conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DialogueNode {
+    /// Name other nodes and choices jump to
+    pub id: String,
+    /// Who says it (shown above the text, empty = narration)
+    #[serde(default)]
+    pub speaker: String,
+    pub text: String,
+    /// Only opens the conversation while this holds (None = always)
+    #[serde(default)]
+    pub condition: Option<FlagCondition>,
+    /// Flags set when the node is shown
+    #[serde(default)]
+    pub effects: Vec<FlagEffect>,
+    /// Answers the player picks from (empty = continue to `next`)
+    #[serde(default)]
+    pub choices: Vec<DialogueChoice>,
+    /// Node after this one when there are no choices (None = end)
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+impl DialogueNode {
+    pub fn new(id: impl Into<String>, speaker: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            speaker: speaker.into(),
+            text: text.into(),
+            condition: None,
+            effects: Vec::new(),
+            choices: Vec::new(),
+            next: None,
+        }
+    }
+}
+
+/// An answer the player can pick
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DialogueChoice {
+    pub text: String,
+    /// Node it leads to (None = end the conversation)
+    #[serde(default)]
+    pub next: Option<String>,
+    /// Only offered while this holds (None = always)
+    #[serde(default)]
+    pub condition: Option<FlagCondition>,
+    /// Flags set when it's picked
+    #[serde(default)]
+    pub effects: Vec<FlagEffect>,
+}
+
+impl DialogueChoice {
+    pub fn new(text: impl Into<String>, next: Option<String>) -> Self {
+        Self { text: text.into(), next, condition: None, effects: Vec::new() }
+    }
+}
+
+/// Comparison of a flag against a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlagCompare {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl FlagCompare {
+    /// Operators as written in the editor, longest first so `<=` isn't read as `<`
+    const ALL: [(FlagCompare, &'static str); 6] = [
+        (FlagCompare::Eq, "=="),
+        (FlagCompare::Ne, "!="),
+        (FlagCompare::Le, "<="),
+        (FlagCompare::Ge, ">="),
+        (FlagCompare::Lt, "<"),
+        (FlagCompare::Gt, ">"),
+    ];
+
+    pub fn symbol(&self) -> &'static str {
+        Self::ALL.iter().find(|(op, _)| op == self).map(|(_, s)| *s).unwrap_or("==")
+    }
+}
+
+/// `flag <op> value`, e.g. `gems >= 3` (unset flags are 0)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlagCondition {
+    pub flag: String,
+    pub op: FlagCompare,
+    pub value: f32,
+}
+
+impl FlagCondition {
+    /// Does it hold, reading flags with `flag`?
+    pub fn holds(&self, flag: impl Fn(&str) -> f32) -> bool {
+        let current = flag(&self.flag);
+        match self.op {
+            FlagCompare::Eq => current == self.value,
+            FlagCompare::Ne => current != self.value,
+            FlagCompare::Lt => current < self.value,
+            FlagCompare::Le => current <= self.value,
+            FlagCompare::Gt => current > self.value,
+            FlagCompare::Ge => current >= self.value,
+        }
+    }
+
+    /// Read `flag <op> value`; a bare flag name means "not zero"
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let Some((op, at, symbol)) = FlagCompare::ALL.iter().find_map(|(op, s)| text.find(s).map(|at| (*op, at, *s))) else {
+            return is_flag_name(text).then(|| Self { flag: text.to_string(), op: FlagCompare::Ne, value: 0.0 });
+        };
+        let flag = text[..at].trim();
+        let value = text[at + symbol.len()..].trim().parse().ok()?;
+        is_flag_name(flag).then(|| Self { flag: flag.to_string(), op, value })
+    }
+}
+
+impl std::fmt::Display for FlagCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.flag, self.op.symbol(), self.value)
+    }
+}
+
+/// `flag = value`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlagEffect {
+    pub flag: String,
+    pub value: f32,
+}
+
+impl std::fmt::Display for FlagEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}", self.flag, self.value)
+    }
+}
+
+/// Read comma-separated `flag = value` effects (`met = 1, gold = 5`)
+pub fn parse_effects(text: &str) -> Option<Vec<FlagEffect>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let (flag, value) = part.split_once('=')?;
+            let flag = flag.trim();
+            is_flag_name(flag).then_some(())?;
+            Some(FlagEffect { flag: flag.to_string(), value: value.trim().parse().ok()? })
+        })
+        .collect()
+}
+
+/// Effects as `parse_effects` reads them
+pub fn effects_text(effects: &[FlagEffect]) -> String {
+    effects.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Flags are script variables, so they follow script naming
+fn is_flag_name(text: &str) -> bool {
+    text.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Problems with a tree, as messages for the inspector (empty = valid)
+pub fn dialogue_issues(nodes: &[DialogueNode]) -> Vec<String> {
+    let mut issues = Vec::new();
+    if nodes.is_empty() {
+        issues.push("No dialogue nodes".to_string());
+    }
+    let exists = |id: &str| nodes.iter().any(|n| n.id == id);
+    for (i, node) in nodes.iter().enumerate() {
+        if node.id.trim().is_empty() {
+            issues.push(format!("Node {} has no ID", i + 1));
+        } else if nodes[..i].iter().any(|n| n.id == node.id) {
+            issues.push(format!("Node ID '{}' is used twice", node.id));
+        }
+        if node.choices.len() > MAX_DIALOGUE_CHOICES {
+            issues.push(format!("'{}' has more than {} choices", node.id, MAX_DIALOGUE_CHOICES));
+        }
+        let targets = node.next.iter().chain(node.choices.iter().filter_map(|c| c.next.as_ref()));
+        for target in targets.filter(|t| !exists(t)) {
+            issues.push(format!("'{}' leads to missing node '{}'", node.id, target));
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_condition_parse() {
+        let condition = FlagCondition::parse(" gems >= 3 ").unwrap();
+        assert_eq!(condition, FlagCondition { flag: "gems".to_string(), op: FlagCompare::Ge, value: 3.0 });
+        assert_eq!(condition.to_string(), "gems >= 3");
+        assert!(condition.holds(|_| 3.0));
+        assert!(!condition.holds(|_| 2.0));
+        assert_eq!(FlagCondition::parse("met").map(|c| c.op), Some(FlagCompare::Ne));
+        assert_eq!(FlagCondition::parse("met < x"), None);
+        assert_eq!(FlagCondition::parse("2 == 2"), None);
+    }
+
+    #[test]
+    fn test_parse_effects() {
+        let effects = parse_effects("met = 1, gold = 5").unwrap();
+        assert_eq!(effects_text(&effects), "met = 1, gold = 5");
+        assert_eq!(parse_effects(""), Some(Vec::new()));
+        assert_eq!(parse_effects("met"), None);
+    }
+
+    #[test]
+    fn test_dialogue_issues() {
+        let mut start = DialogueNode::new("start", "Guard", "Halt!");
+        start.choices.push(DialogueChoice::new("Hello", Some("missing".to_string())));
+        let nodes = vec![start, DialogueNode::new("start", "Guard", "Again?")];
+        assert_eq!(dialogue_issues(&nodes), vec![
+            "'start' leads to missing node 'missing'".to_string(),
+            "Node ID 'start' is used twice".to_string(),
+        ]);
+        assert!(dialogue_issues(&nodes[1..]).is_empty());
+    }
+}
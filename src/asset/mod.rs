@@ -18,11 +18,20 @@
 //! ├── components: Vec<AssetComponent>
 //! │   ├── Mesh { parts: Vec<MeshPart> }  // EMBEDDED mesh data
 //! │   │   └── Each object has geometry + TextureRef::Id (points to shared textures)
-//! │   ├── Collision { shape: CollisionShapeDef }
+//! │   ├── Collision { shape: CollisionShapeDef, offset }
 //! │   ├── Light { color, intensity, radius, offset }
 //! │   ├── Trigger { trigger_id, on_enter, on_exit }
 //! │   ├── Pickup { item_type: ItemType }
-//! │   ├── Enemy { enemy_type, health, damage, patrol_radius }
+//! │   ├── Enemy { enemy_type, health, damage, patrol_radius, poise, attack }
+//! │   ├── Weapon { damage, light, heavy, guard_absorption, guard_stamina, speed, model_part }
+//! │   ├── Armor { defense, weight }
+//! │   ├── MovingPlatform { route, speed, wait, activate_trigger, lever }
+//! │   ├── Dialogue { nodes }  // branching conversation, see dialogue.rs
+//! │   ├── Lod { reduced, impostor, switch distances }
+//! │   ├── Particle { effect, offset, emitter }
+//! │   ├── Sprite { texture, scale, blend, offset }
+//! │   ├── StatusEffect { preset, effect }  // named after the asset
+//! │   ├── Hazard { radius, interval, damage, buildup }
 //! │   └── ... (extensible)
 //! └── metadata: category, tags, description
 //! ```
@@ -39,8 +48,15 @@
 
 mod asset;
 mod component;
+mod dialogue;
 mod library;
 
-pub use asset::{Asset, generate_asset_id};
-pub use component::{AssetComponent, CollisionShapeDef};
+pub use asset::{Asset, LodLevel, generate_asset_id};
+pub use component::{
+    AssetComponent, AttackDef, CollisionShapeDef, DoorMotion, ParticleBlend, ParticleEmitterDef,
+    PARTICLE_PRESETS, PlatformRoute, SPRITE_BLENDS, STATUS_PRESETS, StatusEffectDef, StatusStacking,
+};
+pub use dialogue::{
+    effects_text, parse_effects, DialogueChoice, DialogueNode, FlagCondition, FlagEffect, MAX_DIALOGUE_CHOICES,
+};
 pub use library::{AssetLibrary, AssetSource, ASSETS_DIR, SAMPLES_ASSETS_DIR, USER_ASSETS_DIR};
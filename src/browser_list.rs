@@ -0,0 +1,218 @@
+//! Browser list
+//!
+//! The collapsible, sectioned item list shared by the browser modals (levels,
+//! assets, songs, prefabs). Items are filtered by the browser's `TagFilter`
+//! and, for kinds that have them, drawn with their thumbnail.
+
+use macroquad::prelude::*;
+use crate::storage::Storage;
+use crate::tags::{TagFilter, TagKind};
+use crate::thumbnails::{self, THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT};
+use crate::ui::{Rect, UiContext};
+
+/// Row height without thumbnails
+pub const LIST_ITEM_HEIGHT: f32 = 26.0;
+/// Row height with thumbnails
+pub const LIST_THUMB_ITEM_HEIGHT: f32 = THUMBNAIL_HEIGHT as f32 + 6.0;
+/// Section header height
+pub const LIST_SECTION_HEIGHT: f32 = 28.0;
+
+/// One entry of a section
+pub struct ListItem<'a> {
+    pub name: &'a str,
+    /// Key for tags and thumbnails
+    pub key: String,
+}
+
+/// A collapsible group of items
+pub struct ListSection<'a> {
+    /// Header text (the item count is appended)
+    pub title: &'a str,
+    pub items: Vec<ListItem<'a>>,
+    pub collapsed: bool,
+    /// Items are still being listed (shows a spinner)
+    pub loading: bool,
+    /// Shown when the section has no items
+    pub empty_text: Option<&'a str>,
+    /// Items live in cloud storage (marked in the header and on each row)
+    pub cloud: bool,
+}
+
+/// What happened in the list this frame
+#[derive(Debug, Default)]
+pub struct ListResult {
+    /// Clicked item as (section, index within the section)
+    pub clicked: Option<(usize, usize)>,
+    /// The clicked item was double-clicked
+    pub double_clicked: bool,
+    /// Section whose header was clicked (caller flips its collapsed state)
+    pub toggled: Option<usize>,
+}
+
+/// Draw a sectioned list. `selected` is (section, index); thumbnails are shown
+/// when `thumbnail_storage` is given.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_browser_list(
+    ctx: &mut UiContext,
+    rect: Rect,
+    scroll_offset: &mut f32,
+    sections: &[ListSection],
+    filter: &TagFilter,
+    kind: TagKind,
+    selected: Option<(usize, usize)>,
+    thumbnail_storage: Option<&Storage>,
+) -> ListResult {
+    let mut result = ListResult::default();
+
+    let item_h = if thumbnail_storage.is_some() { LIST_THUMB_ITEM_HEIGHT } else { LIST_ITEM_HEIGHT };
+    let section_h = LIST_SECTION_HEIGHT;
+
+    let section_bg = Color::from_rgba(40, 40, 50, 255);
+    let item_bg = Color::from_rgba(30, 30, 38, 255);
+    let item_hover = Color::from_rgba(50, 50, 60, 255);
+    let item_selected = Color::from_rgba(60, 80, 120, 255);
+    let text_color = Color::from_rgba(200, 200, 200, 255);
+    let text_dim = Color::from_rgba(140, 140, 140, 255);
+    let cloud_color = Color::from_rgba(100, 180, 255, 255);
+
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(25, 25, 30, 255));
+
+    // Items hidden by the tag filter take no space
+    let visible: Vec<Vec<usize>> = sections.iter()
+        .map(|section| section.items.iter().enumerate()
+            .filter(|(_, item)| filter.matches(kind, &item.key, item.name))
+            .map(|(i, _)| i)
+            .collect())
+        .collect();
+
+    // Calculate total content height for scroll
+    let total_h: f32 = sections.iter().zip(&visible).map(|(section, shown)| {
+        let rows = if section.collapsed {
+            0
+        } else if section.loading || (section.items.is_empty() && section.empty_text.is_some()) {
+            1
+        } else {
+            shown.len()
+        };
+        section_h + rows as f32 * item_h
+    }).sum();
+
+    // Handle scroll within list bounds
+    if ctx.mouse.inside(&rect) && ctx.mouse.scroll != 0.0 {
+        *scroll_offset = (*scroll_offset - ctx.mouse.scroll * 30.0).clamp(0.0, (total_h - rect.h).max(0.0));
+    }
+    *scroll_offset = scroll_offset.clamp(0.0, (total_h - rect.h).max(0.0));
+
+    let mut y = rect.y - *scroll_offset;
+    for (s, (section, shown)) in sections.iter().zip(&visible).enumerate() {
+        // Section header
+        let header_rect = Rect::new(rect.x, y, rect.w, section_h);
+        if y + section_h > rect.y && y < rect.bottom() {
+            let draw_y = y.max(rect.y);
+            draw_rectangle(rect.x, draw_y, rect.w, section_h.min(rect.bottom() - draw_y), section_bg);
+
+            if y >= rect.y {
+                let arrow = if section.collapsed { ">" } else { "v" };
+                let count = if filter.is_active() {
+                    format!("{}/{}", shown.len(), section.items.len())
+                } else {
+                    section.items.len().to_string()
+                };
+                let cloud_indicator = if section.cloud { " [cloud]" } else { "" };
+                draw_text(&format!("{} {} ({}){}", arrow, section.title, count, cloud_indicator),
+                         rect.x + 8.0, y + 18.0, 14.0, text_color);
+            }
+
+            if ctx.mouse.inside(&header_rect) && ctx.mouse.left_pressed && header_rect.y >= rect.y {
+                result.toggled = Some(s);
+            }
+        }
+        y += section_h;
+
+        if section.collapsed {
+            continue;
+        }
+
+        if section.loading || (section.items.is_empty() && section.empty_text.is_some()) {
+            if y + item_h > rect.y && y + item_h <= rect.bottom() {
+                let text_y = y + item_h / 2.0 + 4.0;
+                if section.loading {
+                    let time = get_time() as f32;
+                    let spinner_chars = ['|', '/', '-', '\\'];
+                    let spinner_idx = (time * 8.0) as usize % spinner_chars.len();
+                    draw_text(&format!("  {} Loading...", spinner_chars[spinner_idx]), rect.x + 8.0, text_y, 12.0, text_dim);
+                } else if let Some(text) = section.empty_text {
+                    draw_text(&format!("  {}", text), rect.x + 8.0, text_y, 12.0, text_dim);
+                }
+            }
+            y += item_h;
+            continue;
+        }
+
+        for &i in shown {
+            let item = &section.items[i];
+            let item_rect = Rect::new(rect.x, y, rect.w, item_h);
+
+            if y + item_h > rect.y && y < rect.bottom() {
+                let is_selected = selected == Some((s, i));
+                let is_hovered = ctx.mouse.inside(&item_rect) && item_rect.y >= rect.y;
+
+                let bg = if is_selected { item_selected }
+                        else if is_hovered { item_hover }
+                        else { item_bg };
+
+                // Clip to list bounds
+                let draw_y = item_rect.y.max(rect.y);
+                let draw_h = item_h.min(rect.bottom() - draw_y);
+                if draw_h > 0.0 {
+                    draw_rectangle(item_rect.x + 2.0, draw_y, item_rect.w - 4.0, draw_h, bg);
+                }
+
+                // Rows are only drawn once fully inside the list
+                if y >= rect.y && y + item_h <= rect.bottom() {
+                    let mut text_x = rect.x + 20.0;
+                    if let Some(storage) = thumbnail_storage {
+                        let thumb_rect = Rect::new(rect.x + 6.0, y + 3.0, THUMBNAIL_WIDTH as f32, THUMBNAIL_HEIGHT as f32);
+                        draw_thumbnail(thumb_rect, thumbnails::get(storage, kind, &item.key));
+                        text_x = thumb_rect.right() + 8.0;
+                    }
+                    draw_text(item.name, text_x, y + item_h / 2.0 + 4.0, 13.0, text_color);
+
+                    if section.cloud {
+                        draw_text("*", rect.x + rect.w - 20.0, y + item_h / 2.0 + 4.0, 13.0, cloud_color);
+                    }
+                }
+
+                if is_hovered && ctx.mouse.left_pressed && item_rect.y >= rect.y {
+                    result.clicked = Some((s, i));
+                    result.double_clicked = ctx.mouse.double_clicked;
+                }
+            }
+            y += item_h;
+        }
+    }
+
+    result
+}
+
+/// Draw a thumbnail, or an empty frame where there is none yet
+fn draw_thumbnail(rect: Rect, texture: Option<Texture2D>) {
+    match texture {
+        Some(texture) => draw_texture_ex(
+            &texture,
+            rect.x,
+            rect.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(rect.w, rect.h)),
+                ..Default::default()
+            },
+        ),
+        None => {
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(20, 20, 25, 255));
+            draw_line(rect.x, rect.y, rect.right(), rect.bottom(), 1.0, Color::from_rgba(45, 45, 55, 255));
+            draw_line(rect.right(), rect.y, rect.x, rect.bottom(), 1.0, Color::from_rgba(45, 45, 55, 255));
+        }
+    }
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, Color::from_rgba(60, 60, 70, 255));
+}
@@ -0,0 +1,238 @@
+//! Asset bundle (.bpk)
+//!
+//! A single file holding many assets, used to ship a game without loose files.
+//! Layout (all integers little-endian):
+//!
+//! ```text
+//! "BPK1"                      magic
+//! u32                         entry count
+//! per entry:
+//!   u16 + bytes               path (UTF-8, '/' separated, e.g. "assets/samples/levels/a.ron")
+//!   u32                       uncompressed size
+//!   u32                       stored (brotli-compressed) size
+//! entry data                  stored bytes, in index order
+//! ```
+//!
+//! The writer in `xtask` must stay in sync with this layout.
+
+#![allow(dead_code)]
+
+use std::io::Cursor;
+
+/// File magic and format version
+pub const BUNDLE_MAGIC: &[u8; 4] = b"BPK1";
+/// Bundle file name the standalone player looks for
+pub const PLAYER_BUNDLE: &str = "game.bpk";
+/// Entry describing the exported game (title, start level)
+pub const PLAYER_INFO_ENTRY: &str = "game/player.ron";
+
+/// Errors reading or writing a bundle
+#[derive(Debug, Clone, PartialEq)]
+pub enum BundleError {
+    /// Not a bundle, or truncated/corrupt index
+    Invalid(String),
+    /// No entry with this path
+    NotFound(String),
+    /// Entry data failed to decompress
+    Corrupt(String),
+}
+
+impl std::fmt::Display for BundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BundleError::Invalid(msg) => write!(f, "invalid bundle: {}", msg),
+            BundleError::NotFound(path) => write!(f, "not in bundle: {}", path),
+            BundleError::Corrupt(path) => write!(f, "corrupt bundle entry: {}", path),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+#[derive(Debug, Clone)]
+struct BundleEntry {
+    path: String,
+    size: usize,
+    offset: usize,
+    stored: usize,
+}
+
+/// A loaded bundle. Entries are decompressed on read.
+#[derive(Debug, Clone, Default)]
+pub struct Bundle {
+    entries: Vec<BundleEntry>,
+    data: Vec<u8>,
+}
+
+/// Read helper over the bundle bytes
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], BundleError> {
+        let end = self.pos.checked_add(n).filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| BundleError::Invalid("truncated index".to_string()))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u16(&mut self) -> Result<u16, BundleError> {
+        let b = self.take(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, BundleError> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+impl Bundle {
+    /// Parse a bundle's index (entry data stays compressed until read)
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, BundleError> {
+        let mut reader = Reader { bytes: &data, pos: 0 };
+        if reader.take(4)? != BUNDLE_MAGIC {
+            return Err(BundleError::Invalid("bad magic".to_string()));
+        }
+        let count = reader.u32()? as usize;
+        let mut entries = Vec::with_capacity(count.min(4096));
+        for _ in 0..count {
+            let len = reader.u16()? as usize;
+            let path = std::str::from_utf8(reader.take(len)?)
+                .map_err(|_| BundleError::Invalid("entry path is not UTF-8".to_string()))?
+                .to_string();
+            let size = reader.u32()? as usize;
+            let stored = reader.u32()? as usize;
+            entries.push(BundleEntry { path, size, offset: 0, stored });
+        }
+
+        // Data follows the index in entry order
+        let mut offset = reader.pos;
+        for entry in &mut entries {
+            entry.offset = offset;
+            offset = offset.checked_add(entry.stored)
+                .filter(|&end| end <= data.len())
+                .ok_or_else(|| BundleError::Invalid(format!("data for {} is truncated", entry.path)))?;
+        }
+
+        Ok(Self { entries, data })
+    }
+
+    /// Does the bundle contain this path?
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries.iter().any(|e| e.path == path)
+    }
+
+    /// All entry paths, in bundle order
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|e| e.path.as_str())
+    }
+
+    /// Entry paths under a directory (recursive), e.g. `files_in("assets/samples/textures")`
+    pub fn files_in<'a>(&'a self, dir: &'a str) -> impl Iterator<Item = &'a str> {
+        let dir = dir.trim_end_matches('/');
+        self.paths().filter(move |p| {
+            p.strip_prefix(dir).map(|rest| rest.starts_with('/')).unwrap_or(false)
+        })
+    }
+
+    /// Decompress and return an entry's contents
+    pub fn read(&self, path: &str) -> Result<Vec<u8>, BundleError> {
+        let entry = self.entries.iter().find(|e| e.path == path)
+            .ok_or_else(|| BundleError::NotFound(path.to_string()))?;
+        let stored = &self.data[entry.offset..entry.offset + entry.stored];
+        let mut out = Vec::with_capacity(entry.size);
+        brotli::BrotliDecompress(&mut Cursor::new(stored), &mut out)
+            .map_err(|_| BundleError::Corrupt(path.to_string()))?;
+        if out.len() != entry.size {
+            return Err(BundleError::Corrupt(path.to_string()));
+        }
+        Ok(out)
+    }
+
+    /// Read an entry as UTF-8 text
+    pub fn read_string(&self, path: &str) -> Result<String, BundleError> {
+        String::from_utf8(self.read(path)?).map_err(|_| BundleError::Corrupt(path.to_string()))
+    }
+}
+
+/// Builds a bundle in memory
+#[derive(Debug, Default)]
+pub struct BundleWriter {
+    entries: Vec<(String, Vec<u8>)>,
+}
+
+impl BundleWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add (or replace) an entry
+    pub fn add(&mut self, path: &str, data: Vec<u8>) {
+        let path = path.replace('\\', "/");
+        self.entries.retain(|(p, _)| *p != path);
+        self.entries.push((path, data));
+    }
+
+    /// Compress all entries and produce the bundle bytes
+    pub fn finish(self) -> Result<Vec<u8>, BundleError> {
+        let mut index = Vec::new();
+        let mut blobs = Vec::new();
+        index.extend_from_slice(BUNDLE_MAGIC);
+        index.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for (path, data) in &self.entries {
+            let path_len = u16::try_from(path.len())
+                .map_err(|_| BundleError::Invalid(format!("path too long: {}", path)))?;
+            let mut compressed = Vec::new();
+            brotli::BrotliCompress(&mut Cursor::new(data), &mut compressed, &brotli::enc::BrotliEncoderParams {
+                quality: 9,
+                lgwin: 22,
+                ..Default::default()
+            }).map_err(|_| BundleError::Corrupt(path.clone()))?;
+            index.extend_from_slice(&path_len.to_le_bytes());
+            index.extend_from_slice(path.as_bytes());
+            index.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            index.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+            blobs.extend_from_slice(&compressed);
+        }
+        index.extend_from_slice(&blobs);
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut writer = BundleWriter::new();
+        writer.add("game/player.ron", b"(title: \"Demo\")".to_vec());
+        writer.add("assets\\samples\\levels\\a.ron", vec![7; 1000]);
+        writer.add("assets/samples/textures/stone/b.ron", Vec::new());
+        let bundle = Bundle::from_bytes(writer.finish().unwrap()).unwrap();
+
+        assert_eq!(bundle.read_string("game/player.ron").unwrap(), "(title: \"Demo\")");
+        assert_eq!(bundle.read("assets/samples/levels/a.ron").unwrap(), vec![7; 1000]);
+        assert!(bundle.read("assets/samples/textures/stone/b.ron").unwrap().is_empty());
+        assert_eq!(bundle.read("missing.ron"), Err(BundleError::NotFound("missing.ron".to_string())));
+
+        let textures: Vec<&str> = bundle.files_in("assets/samples/textures/").collect();
+        assert_eq!(textures, vec!["assets/samples/textures/stone/b.ron"]);
+        assert_eq!(bundle.files_in("assets/samples/level").count(), 0);
+    }
+
+    #[test]
+    fn test_rejects_bad_data() {
+        assert!(matches!(Bundle::from_bytes(b"nope".to_vec()), Err(BundleError::Invalid(_))));
+
+        let mut writer = BundleWriter::new();
+        writer.add("a", vec![1, 2, 3]);
+        let mut bytes = writer.finish().unwrap();
+        bytes.truncate(bytes.len() - 1);
+        assert!(matches!(Bundle::from_bytes(bytes), Err(BundleError::Invalid(_))));
+    }
+}
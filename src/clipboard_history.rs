@@ -0,0 +1,241 @@
+//! Shared clipboard history
+//!
+//! Every editor keeps its own clipboard for Ctrl+V; on top of that, each copy
+//! is also recorded here so the last few copies from any editor can be brought
+//! back without recopying. Ctrl+Shift+V opens a popup listing the history;
+//! picking an entry puts it back into the matching editor's clipboard, ready
+//! to paste.
+//!
+//! The history is shared across tabs (texture copies made in the World Editor
+//! can be restored in the Asset Editor), so it lives in a thread-local rather
+//! than in any one editor's state.
+
+use std::cell::RefCell;
+use macroquad::prelude::*;
+use crate::editor::GeometryClipboard;
+use crate::modeler::Clipboard as MeshClipboard;
+use crate::texture::ClipboardData;
+use crate::tracker::Note;
+use crate::ui::{Rect, UiContext};
+
+/// Number of copies kept
+pub const MAX_CLIPBOARD_HISTORY: usize = 10;
+
+/// Copied content, by editor
+#[derive(Debug, Clone)]
+pub enum ClipData {
+    /// Texture pixels (palette indices)
+    Texture(ClipboardData),
+    /// World editor sectors, faces and objects
+    Tiles(GeometryClipboard),
+    /// Tracker pattern block, [channel][row]
+    Pattern(Vec<Vec<Note>>),
+    /// Modeler mesh part
+    Mesh(MeshClipboard),
+}
+
+/// Which editor a copy can be restored into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipKind {
+    Texture,
+    Tiles,
+    Pattern,
+    Mesh,
+}
+
+impl ClipKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClipKind::Texture => "Texture",
+            ClipKind::Tiles => "Tiles",
+            ClipKind::Pattern => "Pattern",
+            ClipKind::Mesh => "Mesh",
+        }
+    }
+}
+
+impl ClipData {
+    pub fn kind(&self) -> ClipKind {
+        match self {
+            ClipData::Texture(_) => ClipKind::Texture,
+            ClipData::Tiles(_) => ClipKind::Tiles,
+            ClipData::Pattern(_) => ClipKind::Pattern,
+            ClipData::Mesh(_) => ClipKind::Mesh,
+        }
+    }
+
+    /// Short description of the content
+    pub fn describe(&self) -> String {
+        match self {
+            ClipData::Texture(c) => format!("{}x{} pixels", c.width, c.height),
+            ClipData::Tiles(c) => {
+                if c.objects.is_empty() {
+                    format!("{} faces", c.faces.len())
+                } else {
+                    format!("{} faces, {} objects", c.faces.len(), c.objects.len())
+                }
+            }
+            ClipData::Pattern(c) => {
+                let rows = c.first().map(|ch| ch.len()).unwrap_or(0);
+                format!("{} rows x {} channels", rows, c.len())
+            }
+            ClipData::Mesh(c) => {
+                let faces = c.mesh.as_ref().map(|m| m.faces.len()).unwrap_or(0);
+                format!("{} faces", faces)
+            }
+        }
+    }
+}
+
+/// Most-recent-first list of copies
+#[derive(Debug, Clone, Default)]
+pub struct ClipboardHistory {
+    entries: Vec<ClipData>,
+}
+
+impl ClipboardHistory {
+    /// Record a copy at the front, dropping the oldest past the limit
+    pub fn push(&mut self, data: ClipData) {
+        self.entries.insert(0, data);
+        self.entries.truncate(MAX_CLIPBOARD_HISTORY);
+    }
+
+    pub fn entries(&self) -> &[ClipData] {
+        &self.entries
+    }
+
+    pub fn get(&self, index: usize) -> Option<&ClipData> {
+        self.entries.get(index)
+    }
+
+    /// Move an entry to the front (it is the current clipboard again)
+    pub fn promote(&mut self, index: usize) {
+        if index < self.entries.len() {
+            let data = self.entries.remove(index);
+            self.entries.insert(0, data);
+        }
+    }
+}
+
+thread_local! {
+    static HISTORY: RefCell<ClipboardHistory> = RefCell::new(ClipboardHistory::default());
+}
+
+/// Record a copy made in any editor
+pub fn record(data: ClipData) {
+    HISTORY.with(|h| h.borrow_mut().push(data));
+}
+
+/// Run `f` with the shared history
+pub fn with_history<R>(f: impl FnOnce(&mut ClipboardHistory) -> R) -> R {
+    HISTORY.with(|h| f(&mut h.borrow_mut()))
+}
+
+/// Popup open state (owned by the app)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClipboardPopup {
+    pub open: bool,
+}
+
+/// What the user did in the popup
+pub enum ClipboardPopupAction {
+    None,
+    Close,
+    /// Restore this entry into the active editor's clipboard
+    Restore(ClipData),
+}
+
+/// Draw the history popup. Entries the active editor can't take (`accepts`)
+/// are dimmed. Number keys 1-9 and 0 pick entries directly.
+pub fn draw_clipboard_popup(ctx: &mut UiContext, accepts: &[ClipKind]) -> ClipboardPopupAction {
+    let row_h = 20.0;
+    let w = 300.0;
+    let count = with_history(|h| h.entries().len());
+    let h = 32.0 + row_h * count.max(1) as f32 + 8.0;
+    let rect = Rect::new(
+        ((screen_width() - w) * 0.5).floor(),
+        ((screen_height() - h) * 0.5).floor(),
+        w,
+        h,
+    );
+
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.4));
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(35, 35, 42, 255));
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, Color::from_rgba(80, 80, 90, 255));
+    draw_text("Clipboard History", rect.x + 10.0, rect.y + 18.0, 14.0, WHITE);
+    draw_text("1-0 / click", rect.right() - 70.0, rect.y + 18.0, 11.0, Color::from_rgba(120, 120, 130, 255));
+
+    if count == 0 {
+        draw_text("Nothing copied yet", rect.x + 10.0, rect.y + 32.0 + 14.0, 12.0, Color::from_rgba(150, 150, 150, 255));
+    }
+
+    let digit_keys = [
+        KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5,
+        KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9, KeyCode::Key0,
+    ];
+    let mut picked = None;
+    with_history(|history| {
+        for (i, data) in history.entries().iter().enumerate() {
+            let row = Rect::new(rect.x + 4.0, rect.y + 32.0 + i as f32 * row_h, rect.w - 8.0, row_h);
+            let usable = accepts.contains(&data.kind());
+            let hovered = usable && ctx.mouse.inside(&row);
+            if hovered {
+                draw_rectangle(row.x, row.y, row.w, row.h, Color::from_rgba(60, 60, 75, 255));
+            }
+            let text_color = if usable { WHITE } else { Color::from_rgba(90, 90, 95, 255) };
+            let key = if i == 9 { 0 } else { i + 1 };
+            draw_text(&key.to_string(), row.x + 6.0, row.y + 14.0, 12.0, Color::from_rgba(120, 120, 130, 255));
+            draw_text(data.kind().label(), row.x + 22.0, row.y + 14.0, 12.0, text_color);
+            draw_text(&data.describe(), row.x + 90.0, row.y + 14.0, 12.0, text_color);
+
+            if usable && ((hovered && ctx.mouse.left_pressed) || is_key_pressed(digit_keys[i])) {
+                picked = Some(i);
+            }
+        }
+        if let Some(i) = picked {
+            history.promote(i);
+        }
+        picked.and_then(|_| history.get(0).cloned())
+    })
+    .map(ClipboardPopupAction::Restore)
+    .unwrap_or_else(|| {
+        let clicked_outside = ctx.mouse.left_pressed && !ctx.mouse.inside(&rect);
+        if is_key_pressed(KeyCode::Escape) || clicked_outside {
+            ClipboardPopupAction::Close
+        } else {
+            ClipboardPopupAction::None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texture(w: usize) -> ClipData {
+        ClipData::Texture(ClipboardData { width: w, height: 1, indices: vec![0; w] })
+    }
+
+    #[test]
+    fn keeps_last_ten_newest_first() {
+        let mut history = ClipboardHistory::default();
+        for w in 1..=12 {
+            history.push(texture(w));
+        }
+        assert_eq!(history.entries().len(), MAX_CLIPBOARD_HISTORY);
+        assert_eq!(history.get(0).unwrap().describe(), "12x1 pixels");
+        assert_eq!(history.get(9).unwrap().describe(), "3x1 pixels");
+    }
+
+    #[test]
+    fn promote_moves_entry_to_front() {
+        let mut history = ClipboardHistory::default();
+        history.push(texture(1));
+        history.push(ClipData::Pattern(vec![vec![Note::EMPTY; 4]; 2]));
+        history.promote(1);
+        assert_eq!(history.get(0).unwrap().kind(), ClipKind::Texture);
+        assert_eq!(history.get(1).unwrap().describe(), "4 rows x 2 channels");
+        history.promote(5);
+        assert_eq!(history.entries().len(), 2);
+    }
+}
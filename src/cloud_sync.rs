@@ -0,0 +1,247 @@
+//! Cloud sync conflicts and revision history
+//!
+//! A cloud save is refused when the file changed since it was read - saved
+//! from another browser or machine (see `storage::versions`). The conflict
+//! dialog then offers to keep mine (overwrite the newer copy), keep theirs
+//! (reload the cloud copy, dropping my changes) or save mine as a copy.
+//!
+//! The revision history browser lists every stored revision of a cloud file
+//! so an older one can be opened again.
+
+use std::path::{Path, PathBuf};
+use macroquad::prelude::*;
+use crate::modeler::snapshots::{format_age, unix_now};
+use crate::storage::versions::RemoteVersion;
+use crate::storage::Storage;
+use crate::ui::{Rect, UiContext};
+
+/// Revision rows shown at once in the history browser
+const HISTORY_ROWS: usize = 12;
+
+/// Editor whose save was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncTarget {
+    Level,
+    Asset,
+}
+
+/// A cloud save refused because the file changed since it was read
+#[derive(Debug, Clone)]
+pub struct SyncConflict {
+    pub target: SyncTarget,
+    pub path: PathBuf,
+    /// The newer cloud copy
+    pub remote: RemoteVersion,
+}
+
+/// What the user picked in the conflict dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictChoice {
+    None,
+    /// Overwrite the cloud copy with mine
+    KeepMine,
+    /// Reload the cloud copy, dropping my changes
+    KeepTheirs,
+    /// Save mine under a new name, leaving the cloud copy alone
+    SaveAsCopy,
+    /// Decide later (stays unsaved)
+    Cancel,
+}
+
+/// "3m ago" for a revision time (0 = not reported)
+fn describe_time(updated_at: u64) -> String {
+    if updated_at == 0 {
+        "at an unknown time".to_string()
+    } else {
+        format_age(unix_now(), updated_at)
+    }
+}
+
+/// File name of a path, for dialog text
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+/// Draw a row of buttons right-aligned at `y`; returns the index clicked
+fn draw_buttons(ctx: &mut UiContext, rect: Rect, y: f32, labels: &[&str]) -> Option<usize> {
+    let button_w = 100.0;
+    let mut x = rect.right() - 10.0 - (button_w + 6.0) * labels.len() as f32 + 6.0;
+    let mut clicked = None;
+    for (i, label) in labels.iter().enumerate() {
+        let button = Rect::new(x, y, button_w, 22.0);
+        let hovered = ctx.mouse.inside(&button);
+        let bg = if hovered { Color::from_rgba(70, 70, 85, 255) } else { Color::from_rgba(50, 50, 60, 255) };
+        draw_rectangle(button.x, button.y, button.w, button.h, bg);
+        let text_w = measure_text(label, None, 12, 1.0).width;
+        draw_text(label, button.x + (button.w - text_w) * 0.5, button.y + 15.0, 12.0, WHITE);
+        if hovered && ctx.mouse.left_pressed {
+            clicked = Some(i);
+        }
+        x += button_w + 6.0;
+    }
+    clicked
+}
+
+/// Draw the dialog shown when a cloud save was refused
+pub fn draw_conflict_dialog(ctx: &mut UiContext, conflict: &SyncConflict) -> ConflictChoice {
+    let w = 360.0;
+    let h = 130.0;
+    let rect = Rect::new(
+        ((screen_width() - w) * 0.5).floor(),
+        ((screen_height() - h) * 0.5).floor(),
+        w,
+        h,
+    );
+    let dim = Color::from_rgba(150, 150, 150, 255);
+
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.4));
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(35, 35, 42, 255));
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, Color::from_rgba(80, 80, 90, 255));
+    draw_text("Cloud copy changed", rect.x + 10.0, rect.y + 20.0, 14.0, WHITE);
+    draw_text(&file_name(&conflict.path), rect.x + 10.0, rect.y + 42.0, 12.0, WHITE);
+    draw_text(
+        &format!("was saved elsewhere {} (revision {})", describe_time(conflict.remote.updated_at), conflict.remote.revision),
+        rect.x + 10.0,
+        rect.y + 58.0,
+        12.0,
+        dim,
+    );
+    draw_text("since you opened it. Which copy do you want?", rect.x + 10.0, rect.y + 74.0, 12.0, dim);
+
+    let choices = [ConflictChoice::KeepMine, ConflictChoice::KeepTheirs, ConflictChoice::SaveAsCopy];
+    if let Some(i) = draw_buttons(ctx, rect, rect.bottom() - 30.0, &["Keep Mine", "Keep Theirs", "Save as Copy"]) {
+        return choices[i];
+    }
+    if is_key_pressed(KeyCode::Escape) {
+        ConflictChoice::Cancel
+    } else {
+        ConflictChoice::None
+    }
+}
+
+/// Revision history browser for one cloud file
+#[derive(Debug, Clone)]
+pub struct RevisionHistory {
+    pub path: PathBuf,
+    /// Newest first
+    pub revisions: Vec<RemoteVersion>,
+    pub selected: usize,
+    scroll: usize,
+    /// Why the history couldn't be listed
+    pub error: Option<String>,
+}
+
+/// What the user did in the history browser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryAction {
+    None,
+    /// Open this revision
+    Open(u64),
+    Close,
+}
+
+impl RevisionHistory {
+    /// List the revisions of `path`
+    pub fn load(storage: &Storage, path: PathBuf) -> Self {
+        let (revisions, error) = match storage.revisions(&path.to_string_lossy()).take() {
+            Some(Ok(revisions)) => (revisions, None),
+            Some(Err(e)) => (Vec::new(), Some(e.to_string())),
+            None => (Vec::new(), Some("Still loading".to_string())),
+        };
+        Self { path, revisions, selected: 0, scroll: 0, error }
+    }
+
+    /// The current revision (what a save must be based on)
+    pub fn head(&self) -> Option<RemoteVersion> {
+        self.revisions.first().copied()
+    }
+
+    /// Selected revision, if any
+    pub fn selected_revision(&self) -> Option<RemoteVersion> {
+        self.revisions.get(self.selected).copied()
+    }
+}
+
+/// Draw the revision history browser
+pub fn draw_revision_history(ctx: &mut UiContext, history: &mut RevisionHistory) -> HistoryAction {
+    let row_h = 20.0;
+    let w = 340.0;
+    let h = 40.0 + row_h * HISTORY_ROWS as f32 + 40.0;
+    let rect = Rect::new(
+        ((screen_width() - w) * 0.5).floor(),
+        ((screen_height() - h) * 0.5).floor(),
+        w,
+        h,
+    );
+    let dim = Color::from_rgba(150, 150, 150, 255);
+
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.4));
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(35, 35, 42, 255));
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, Color::from_rgba(80, 80, 90, 255));
+    draw_text(&format!("History: {}", file_name(&history.path)), rect.x + 10.0, rect.y + 20.0, 14.0, WHITE);
+
+    let list = Rect::new(rect.x + 4.0, rect.y + 32.0, rect.w - 8.0, row_h * HISTORY_ROWS as f32);
+    if let Some(error) = &history.error {
+        draw_text(error, list.x + 6.0, list.y + 14.0, 12.0, dim);
+    } else if history.revisions.is_empty() {
+        draw_text("No revisions stored yet", list.x + 6.0, list.y + 14.0, 12.0, dim);
+    }
+
+    // Scroll with the wheel and keep the selection in view
+    let max_scroll = history.revisions.len().saturating_sub(HISTORY_ROWS);
+    if ctx.mouse.inside(&list) && ctx.mouse.scroll != 0.0 {
+        history.scroll = if ctx.mouse.scroll > 0.0 {
+            history.scroll.saturating_sub(1)
+        } else {
+            (history.scroll + 1).min(max_scroll)
+        };
+    }
+    if is_key_pressed(KeyCode::Down) && history.selected + 1 < history.revisions.len() {
+        history.selected += 1;
+    }
+    if is_key_pressed(KeyCode::Up) {
+        history.selected = history.selected.saturating_sub(1);
+    }
+    if history.selected < history.scroll {
+        history.scroll = history.selected;
+    } else if history.selected >= history.scroll + HISTORY_ROWS {
+        history.scroll = history.selected + 1 - HISTORY_ROWS;
+    }
+
+    let now = unix_now();
+    for (row_idx, (i, version)) in history.revisions.iter().enumerate().skip(history.scroll).take(HISTORY_ROWS).enumerate() {
+        let row = Rect::new(list.x, list.y + row_idx as f32 * row_h, list.w, row_h);
+        let hovered = ctx.mouse.inside(&row);
+        if i == history.selected {
+            draw_rectangle(row.x, row.y, row.w, row.h, Color::from_rgba(60, 80, 120, 255));
+        } else if hovered {
+            draw_rectangle(row.x, row.y, row.w, row.h, Color::from_rgba(50, 50, 60, 255));
+        }
+        let when = if version.updated_at == 0 { "-".to_string() } else { format_age(now, version.updated_at) };
+        draw_text(&format!("#{}", version.revision), row.x + 6.0, row.y + 14.0, 12.0, WHITE);
+        draw_text(&when, row.x + 80.0, row.y + 14.0, 12.0, WHITE);
+        draw_text(&format!("{:.1} KB", version.size as f32 / 1024.0), row.x + 170.0, row.y + 14.0, 12.0, dim);
+        if i == 0 {
+            draw_text("current", row.right() - 56.0, row.y + 14.0, 12.0, Color::from_rgba(100, 180, 255, 255));
+        }
+        if hovered && ctx.mouse.left_pressed {
+            history.selected = i;
+        }
+    }
+
+    let open = |history: &RevisionHistory| {
+        history.selected_revision().map_or(HistoryAction::None, |v| HistoryAction::Open(v.revision))
+    };
+    match draw_buttons(ctx, rect, rect.bottom() - 30.0, &["Close", "Open"]) {
+        Some(0) => return HistoryAction::Close,
+        Some(_) => return open(history),
+        None => {}
+    }
+    if is_key_pressed(KeyCode::Enter) {
+        open(history)
+    } else if is_key_pressed(KeyCode::Escape) {
+        HistoryAction::Close
+    } else {
+        HistoryAction::None
+    }
+}
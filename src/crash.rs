@@ -0,0 +1,611 @@
+//! Crash reports and autosave recovery
+//!
+//! A panic hook writes a plain-text report to `assets/userdata/crash/`: the
+//! panic message and location, a backtrace, the engine version, the files
+//! open in each tool and the last actions taken (status bar messages, shortcuts
+//! and tab switches). It also leaves a marker so the next launch knows the
+//! previous session crashed.
+//!
+//! Tools with unsaved changes are autosaved every minute to
+//! `assets/userdata/autosave/`, keeping the last few snapshots per tool. The
+//! next launch offers to restore autosaves that are newer than the document's
+//! last explicit save, whether the session crashed or was killed. After a crash
+//! it also offers to report it: reporting opens a prefilled GitHub issue in the
+//! browser for the user to review; nothing is sent without that click.
+//!
+//! The action log is shared by every tab and read from the panic hook (which
+//! may run on any thread), so it lives in a static rather than in app state.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Number of actions kept for the report
+pub const MAX_RECENT_ACTIONS: usize = 50;
+
+/// Where crash reports are written
+#[cfg(not(target_arch = "wasm32"))]
+pub const CRASH_DIR: &str = "assets/userdata/crash";
+
+/// Where autosaves are written
+#[cfg(not(target_arch = "wasm32"))]
+pub const AUTOSAVE_DIR: &str = "assets/userdata/autosave";
+
+/// Seconds between autosaves
+#[cfg(not(target_arch = "wasm32"))]
+pub const AUTOSAVE_INTERVAL: f64 = 60.0;
+
+/// Autosave snapshots kept per tool (the oldest is dropped first)
+#[cfg(not(target_arch = "wasm32"))]
+pub const AUTOSAVE_SNAPSHOTS: usize = 5;
+
+/// Marker left by the panic hook, holds the report's file name
+#[cfg(not(target_arch = "wasm32"))]
+const PENDING_MARKER: &str = "pending";
+
+/// New issue page for opt-in reports
+#[cfg(not(target_arch = "wasm32"))]
+const ISSUE_URL: &str = "https://github.com/EBonura/bonnie-32/issues/new";
+
+/// Longest report text put in an issue URL (browsers cap URL length)
+#[cfg(not(target_arch = "wasm32"))]
+const ISSUE_BODY_LIMIT: usize = 6000;
+
+/// Last actions, oldest first, with the app time they happened at
+#[derive(Debug)]
+pub struct ActionLog {
+    entries: VecDeque<(f64, String)>,
+}
+
+impl ActionLog {
+    pub const fn new() -> Self {
+        Self { entries: VecDeque::new() }
+    }
+
+    /// Add an action; repeats of the previous one are folded into it
+    pub fn push(&mut self, time: f64, action: &str) {
+        if let Some(last) = self.entries.back_mut() {
+            if last.1 == action {
+                last.0 = time;
+                return;
+            }
+        }
+        if self.entries.len() == MAX_RECENT_ACTIONS {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((time, action.to_string()));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn entries(&self) -> impl Iterator<Item = &(f64, String)> {
+        self.entries.iter()
+    }
+}
+
+impl Default for ActionLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What the report needs to know about the session
+struct Session {
+    actions: ActionLog,
+    /// (tool label, path) for tools with a file open
+    open_files: Vec<(&'static str, PathBuf)>,
+}
+
+static SESSION: Mutex<Session> = Mutex::new(Session {
+    actions: ActionLog::new(),
+    open_files: Vec::new(),
+});
+
+/// Record a user-visible action for the crash report
+pub fn record(action: &str) {
+    let time = macroquad::time::get_time();
+    if let Ok(mut session) = SESSION.lock() {
+        session.actions.push(time, action);
+    }
+}
+
+/// Remember which file a tool has open (None when untitled)
+pub fn set_open_file(tool: &'static str, path: Option<&Path>) {
+    let Ok(mut session) = SESSION.lock() else { return };
+    let current = session.open_files.iter().position(|(t, _)| *t == tool);
+    match (current, path) {
+        (Some(i), Some(path)) if session.open_files[i].1 != path => {
+            session.open_files[i].1 = path.to_path_buf();
+        }
+        (Some(i), None) => {
+            session.open_files.remove(i);
+        }
+        (None, Some(path)) => session.open_files.push((tool, path.to_path_buf())),
+        _ => {}
+    }
+}
+
+/// Format a crash report
+#[cfg(not(target_arch = "wasm32"))]
+fn build_report(
+    panic: &str,
+    backtrace: &str,
+    open_files: &[(&'static str, PathBuf)],
+    actions: &ActionLog,
+) -> String {
+    let mut report = String::new();
+    report.push_str(&format!("BONNIE-32 v{} crash report\n", crate::VERSION));
+    report.push_str(&format!("Platform: {} {}\n\n", std::env::consts::OS, std::env::consts::ARCH));
+    report.push_str(panic);
+    report.push_str("\n\nOpen files:\n");
+    if open_files.is_empty() {
+        report.push_str("  (none)\n");
+    }
+    for (tool, path) in open_files {
+        report.push_str(&format!("  {}: {}\n", tool, path.display()));
+    }
+    report.push_str("\nLast actions (oldest first):\n");
+    if actions.entries.is_empty() {
+        report.push_str("  (none)\n");
+    }
+    for (time, action) in actions.entries() {
+        report.push_str(&format!("  [{:>8.1}s] {}\n", time, action));
+    }
+    report.push_str("\nBacktrace:\n");
+    report.push_str(backtrace);
+    report
+}
+
+/// Install the panic hook (after crashlog's, which still runs)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn install() {
+    use std::sync::TryLockError;
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+        let panic = info.to_string();
+        // try_lock: the panic may have happened while the session was locked
+        let report = match SESSION.try_lock() {
+            Ok(session) => build_report(&panic, &backtrace, &session.open_files, &session.actions),
+            Err(TryLockError::Poisoned(poisoned)) => {
+                let session = poisoned.into_inner();
+                build_report(&panic, &backtrace, &session.open_files, &session.actions)
+            }
+            Err(TryLockError::WouldBlock) => build_report(&panic, &backtrace, &[], &ActionLog::new()),
+        };
+        match write_report(&report) {
+            Ok(path) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write crash report: {}", e),
+        }
+        previous(info);
+    }));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_report(report: &str) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(CRASH_DIR)?;
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let name = format!("crash-{}.txt", stamp);
+    let path = Path::new(CRASH_DIR).join(&name);
+    std::fs::write(&path, report)?;
+    std::fs::write(Path::new(CRASH_DIR).join(PENDING_MARKER), &name)?;
+    Ok(path)
+}
+
+// ============================================================================
+// Autosave
+// ============================================================================
+
+/// One autosaved document per tool
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutosaveSlot {
+    Level,
+    Asset,
+    Song,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AutosaveSlot {
+    pub const ALL: [AutosaveSlot; 3] = [AutosaveSlot::Level, AutosaveSlot::Asset, AutosaveSlot::Song];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AutosaveSlot::Level => "Level",
+            AutosaveSlot::Asset => "Asset",
+            AutosaveSlot::Song => "Song",
+        }
+    }
+
+    fn file_stem(&self) -> &'static str {
+        match self {
+            AutosaveSlot::Level => "level",
+            AutosaveSlot::Asset => "asset",
+            AutosaveSlot::Song => "song",
+        }
+    }
+
+    /// Snapshot `index`, 0 being the newest
+    fn data_path(&self, index: usize) -> PathBuf {
+        snapshot_path(Path::new(AUTOSAVE_DIR), self.file_stem(), index)
+    }
+
+    /// Sidecar holding the document's real path (empty when untitled)
+    fn origin_path(&self, index: usize) -> PathBuf {
+        self.data_path(index).with_extension("path")
+    }
+
+    /// The document's real path as recorded next to snapshot `index`
+    fn origin(&self, index: usize) -> Option<PathBuf> {
+        std::fs::read_to_string(self.origin_path(index))
+            .ok()
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+    }
+
+    fn clear(&self) {
+        for index in 0..AUTOSAVE_SNAPSHOTS {
+            let _ = std::fs::remove_file(self.data_path(index));
+            let _ = std::fs::remove_file(self.origin_path(index));
+        }
+    }
+
+    /// The newest snapshot holds work that was never saved: it is newer than
+    /// the document on disk, or the document is untitled or gone
+    fn has_unsaved(&self) -> bool {
+        let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let Some(autosaved) = modified(&self.data_path(0)) else {
+            return false;
+        };
+        match self.origin(0).and_then(|origin| modified(&origin)) {
+            Some(saved) => autosaved > saved,
+            None => true,
+        }
+    }
+}
+
+/// `level.ron` for the newest snapshot, then `level.1.ron`, `level.2.ron`...
+#[cfg(not(target_arch = "wasm32"))]
+fn snapshot_path(dir: &Path, stem: &str, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(format!("{}.ron", stem))
+    } else {
+        dir.join(format!("{}.{}.ron", stem, index))
+    }
+}
+
+/// Make room for a new newest snapshot: each one moves down a place (with its
+/// path sidecar) and the oldest of `count` falls off
+#[cfg(not(target_arch = "wasm32"))]
+fn rotate_snapshots(dir: &Path, stem: &str, count: usize) {
+    if let Some(oldest) = count.checked_sub(1) {
+        let path = snapshot_path(dir, stem, oldest);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("path"));
+    }
+    for index in (0..count.saturating_sub(1)).rev() {
+        let from = snapshot_path(dir, stem, index);
+        let to = snapshot_path(dir, stem, index + 1);
+        if from.exists() {
+            let _ = std::fs::rename(&from, &to);
+            let _ = std::fs::rename(from.with_extension("path"), to.with_extension("path"));
+        }
+    }
+}
+
+/// Writes autosaves for tools with unsaved changes
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Default)]
+pub struct Autosaver {
+    last_save: f64,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Autosaver {
+    /// Autosave dirty tools once per interval; clean tools drop their autosaves
+    pub fn tick(&mut self, app: &crate::app::AppState) {
+        let now = macroquad::time::get_time();
+        if now - self.last_save < AUTOSAVE_INTERVAL {
+            return;
+        }
+        self.last_save = now;
+        if std::fs::create_dir_all(AUTOSAVE_DIR).is_err() {
+            return;
+        }
+
+        let editor = &app.world_editor.editor_state;
+        let modeler = &app.modeler.modeler_state;
+        let tracker = &app.tracker;
+        for slot in AutosaveSlot::ALL {
+            let (dirty, origin) = match slot {
+                AutosaveSlot::Level => (editor.dirty, &editor.current_file),
+                AutosaveSlot::Asset => (modeler.dirty, &modeler.current_file),
+                AutosaveSlot::Song => (tracker.dirty, &tracker.current_file),
+            };
+            if !dirty {
+                slot.clear();
+                continue;
+            }
+            // Write next to the newest snapshot first, so an unchanged document
+            // does not push older snapshots out
+            let pending = slot.data_path(0).with_extension("tmp");
+            let saved = match slot {
+                AutosaveSlot::Level => crate::world::save_level(&editor.level, &pending).map_err(|e| e.to_string()),
+                AutosaveSlot::Asset => modeler.asset.save(&pending).map_err(|e| e.to_string()),
+                AutosaveSlot::Song => crate::tracker::save_song(&tracker.song, &pending),
+            };
+            let origin = origin.as_ref().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+            let stored = saved.and_then(|_| {
+                let unchanged = std::fs::read(slot.data_path(0)).ok() == std::fs::read(&pending).ok()
+                    && slot.origin(0).map(|p| p.to_string_lossy().to_string()).unwrap_or_default() == origin;
+                if unchanged {
+                    return std::fs::remove_file(&pending).map_err(|e| e.to_string());
+                }
+                rotate_snapshots(Path::new(AUTOSAVE_DIR), slot.file_stem(), AUTOSAVE_SNAPSHOTS);
+                std::fs::rename(&pending, slot.data_path(0)).map_err(|e| e.to_string())?;
+                std::fs::write(slot.origin_path(0), origin).map_err(|e| e.to_string())
+            });
+            if let Err(e) = stored {
+                eprintln!("Autosave of {} failed: {}", slot.label().to_lowercase(), e);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Recovery after a crash or an unclean exit
+// ============================================================================
+
+/// Left over from the previous session
+#[cfg(not(target_arch = "wasm32"))]
+pub struct CrashRecovery {
+    /// Crash report, when the session ended in a panic
+    pub report_path: Option<PathBuf>,
+    pub report: String,
+    /// Tools with autosaves newer than their last explicit save
+    pub autosaves: Vec<AutosaveSlot>,
+}
+
+/// Check for a crash or unsaved autosaves from the previous session
+/// (clears the crash marker)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn take_pending() -> Option<CrashRecovery> {
+    let autosaves: Vec<AutosaveSlot> = AutosaveSlot::ALL.into_iter().filter(|s| s.has_unsaved()).collect();
+    let marker = Path::new(CRASH_DIR).join(PENDING_MARKER);
+    let Ok(name) = std::fs::read_to_string(&marker) else {
+        return (!autosaves.is_empty()).then_some(CrashRecovery { report_path: None, report: String::new(), autosaves });
+    };
+    let _ = std::fs::remove_file(&marker);
+    let report_path = Path::new(CRASH_DIR).join(name.trim());
+    let report = std::fs::read_to_string(&report_path).unwrap_or_default();
+    Some(CrashRecovery { report_path: Some(report_path), report, autosaves })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl CrashRecovery {
+    /// Load the autosaves back into their tools, marked unsaved. Falls back
+    /// to older snapshots when the newest one can't be read.
+    pub fn restore(&self, app: &mut crate::app::AppState) {
+        for &slot in &self.autosaves {
+            let mut restored = Err("no snapshot".to_string());
+            for index in 0..AUTOSAVE_SNAPSHOTS {
+                if !slot.data_path(index).exists() {
+                    continue;
+                }
+                restored = restore_snapshot(app, slot, index);
+                match &restored {
+                    Ok(()) => break,
+                    Err(e) => eprintln!("{} autosave {} unreadable: {}", slot.label(), index, e),
+                }
+            }
+            if restored.is_err() {
+                eprintln!("{} autosave could not be restored", slot.label());
+            }
+        }
+    }
+
+    /// Prefilled new-issue URL (the user reviews it before submitting)
+    pub fn issue_url(&self) -> String {
+        let report_path = self.report_path.as_deref().unwrap_or(Path::new(CRASH_DIR));
+        let first_line = self.report.lines().find(|l| l.starts_with("panicked at")).unwrap_or("Crash report");
+        let mut body = self.report.clone();
+        if body.len() > ISSUE_BODY_LIMIT {
+            let mut cut = ISSUE_BODY_LIMIT;
+            while !body.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            body.truncate(cut);
+            body.push_str(&format!("\n... (truncated, full report in {})", report_path.display()));
+        }
+        format!(
+            "{}?title={}&body={}",
+            ISSUE_URL,
+            urlencoding::encode(&format!("Crash: {}", first_line)),
+            urlencoding::encode(&format!("```\n{}\n```", body)),
+        )
+    }
+}
+
+/// Load one autosave snapshot back into its tool, marked unsaved
+#[cfg(not(target_arch = "wasm32"))]
+fn restore_snapshot(app: &mut crate::app::AppState, slot: AutosaveSlot, index: usize) -> Result<(), String> {
+    let path = slot.data_path(index);
+    let origin = slot.origin(index);
+    let status = "Restored from autosave (unsaved)";
+    match slot {
+        AutosaveSlot::Level => crate::world::load_level(&path).map_err(|e| e.to_string()).map(|level| {
+            let es = &mut app.world_editor.editor_state;
+            es.load_level(level, origin.clone().unwrap_or_default());
+            es.current_file = origin;
+            es.dirty = true;
+            es.set_status(status, 5.0);
+        }),
+        AutosaveSlot::Asset => crate::asset::Asset::load(&path).map_err(|e| e.to_string()).map(|asset| {
+            let ms = &mut app.modeler.modeler_state;
+            ms.asset = asset;
+            ms.selected_object = if ms.objects().is_empty() { None } else { Some(0) };
+            ms.selected_component = ms.asset.components.iter().position(|c| c.is_mesh());
+            ms.resolve_all_texture_refs();
+            ms.current_file = origin;
+            ms.dirty = true;
+            ms.selection = crate::modeler::ModelerSelection::None;
+            ms.set_status(status, 5.0);
+        }),
+        AutosaveSlot::Song => crate::tracker::load_song(&path).map(|song| {
+            app.tracker.apply_song(song, origin);
+            app.tracker.dirty = true;
+            app.tracker.set_status(status, 5.0);
+        }),
+    }
+}
+
+/// What the user picked in the recovery dialog
+#[cfg(not(target_arch = "wasm32"))]
+pub enum RecoveryAction {
+    None,
+    Restore,
+    Report,
+    Dismiss,
+}
+
+/// Draw the recovery dialog shown after a crash
+#[cfg(not(target_arch = "wasm32"))]
+pub fn draw_recovery_dialog(ctx: &mut crate::ui::UiContext, recovery: &CrashRecovery) -> RecoveryAction {
+    use crate::ui::Rect;
+    use macroquad::prelude::*;
+
+    let w = 380.0;
+    let h = 150.0 + 16.0 * recovery.autosaves.len() as f32;
+    let rect = Rect::new(
+        ((screen_width() - w) * 0.5).floor(),
+        ((screen_height() - h) * 0.5).floor(),
+        w,
+        h,
+    );
+    let dim = Color::from_rgba(150, 150, 150, 255);
+
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.4));
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(35, 35, 42, 255));
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, Color::from_rgba(80, 80, 90, 255));
+    if let Some(report_path) = &recovery.report_path {
+        draw_text("BONNIE-32 closed unexpectedly", rect.x + 10.0, rect.y + 20.0, 14.0, WHITE);
+        draw_text("A crash report was saved to:", rect.x + 10.0, rect.y + 42.0, 12.0, dim);
+        draw_text(&report_path.display().to_string(), rect.x + 10.0, rect.y + 58.0, 12.0, WHITE);
+    } else {
+        draw_text("Unsaved work from the last session", rect.x + 10.0, rect.y + 20.0, 14.0, WHITE);
+        draw_text("Autosaves newer than the last save were found.", rect.x + 10.0, rect.y + 42.0, 12.0, dim);
+    }
+
+    let mut y = rect.y + 82.0;
+    if recovery.autosaves.is_empty() {
+        draw_text("No unsaved work was found.", rect.x + 10.0, y, 12.0, dim);
+        y += 16.0;
+    } else {
+        draw_text("Unsaved work can be restored:", rect.x + 10.0, y, 12.0, dim);
+        for slot in &recovery.autosaves {
+            y += 16.0;
+            draw_text(&format!("- {}", slot.label()), rect.x + 18.0, y, 12.0, WHITE);
+        }
+    }
+    if recovery.report_path.is_some() {
+        draw_text(
+            "Reporting opens a GitHub issue with the report for you to review.",
+            rect.x + 10.0,
+            y + 18.0,
+            11.0,
+            Color::from_rgba(120, 120, 130, 255),
+        );
+    }
+
+    let button_w = 110.0;
+    let button_y = rect.bottom() - 30.0;
+    let mut buttons = vec![("Dismiss", RecoveryAction::Dismiss)];
+    if recovery.report_path.is_some() {
+        buttons.insert(0, ("Report Issue", RecoveryAction::Report));
+    }
+    if !recovery.autosaves.is_empty() {
+        buttons.insert(0, ("Restore", RecoveryAction::Restore));
+    }
+    let mut x = rect.right() - 10.0 - (button_w + 6.0) * buttons.len() as f32 + 6.0;
+    for (label, action) in buttons {
+        let button = Rect::new(x, button_y, button_w, 22.0);
+        let hovered = ctx.mouse.inside(&button);
+        let bg = if hovered { Color::from_rgba(70, 70, 85, 255) } else { Color::from_rgba(50, 50, 60, 255) };
+        draw_rectangle(button.x, button.y, button.w, button.h, bg);
+        let text_w = measure_text(label, None, 12, 1.0).width;
+        draw_text(label, button.x + (button.w - text_w) * 0.5, button.y + 15.0, 12.0, WHITE);
+        if hovered && ctx.mouse.left_pressed {
+            return action;
+        }
+        x += button_w + 6.0;
+    }
+
+    if is_key_pressed(KeyCode::Escape) {
+        RecoveryAction::Dismiss
+    } else {
+        RecoveryAction::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn action_log_keeps_last_fifty_and_folds_repeats() {
+        let mut log = ActionLog::new();
+        log.push(1.0, "Saved");
+        log.push(2.0, "Saved");
+        assert_eq!(log.entries().count(), 1);
+        assert_eq!(log.entries().next().unwrap().0, 2.0);
+
+        for i in 0..60 {
+            log.push(3.0 + i as f64, &format!("Action {}", i));
+        }
+        assert_eq!(log.entries().count(), MAX_RECENT_ACTIONS);
+        assert_eq!(log.entries().next().unwrap().1, "Action 10");
+        assert_eq!(log.entries().last().unwrap().1, "Action 59");
+    }
+
+    #[test]
+    fn report_lists_files_and_actions() {
+        let mut log = ActionLog::new();
+        log.push(12.5, "Undo (Ctrl+Z)");
+        let files = [("World Editor", PathBuf::from("assets/userdata/levels/cave.ron"))];
+        let report = build_report("panicked at src/main.rs:1:1:\nboom", "0: main", &files, &log);
+
+        assert!(report.starts_with(&format!("BONNIE-32 v{} crash report", crate::VERSION)));
+        assert!(report.contains("boom"));
+        assert!(report.contains("World Editor: assets/userdata/levels/cave.ron"));
+        assert!(report.contains("12.5s] Undo (Ctrl+Z)"));
+        assert!(report.ends_with("Backtrace:\n0: main"));
+
+        let empty = build_report("boom", "", &[], &ActionLog::new());
+        assert_eq!(empty.matches("(none)").count(), 2);
+    }
+
+    #[test]
+    fn autosave_snapshots_rotate_and_drop_the_oldest() {
+        let dir = std::env::temp_dir().join(format!("b32-autosave-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(snapshot_path(&dir, "level", 0), dir.join("level.ron"));
+        assert_eq!(snapshot_path(&dir, "level", 2), dir.join("level.2.ron"));
+
+        // Save four times keeping three snapshots: "1" falls off
+        for n in 1..=4 {
+            rotate_snapshots(&dir, "level", 3);
+            std::fs::write(snapshot_path(&dir, "level", 0), n.to_string()).unwrap();
+            std::fs::write(snapshot_path(&dir, "level", 0).with_extension("path"), format!("origin{}", n)).unwrap();
+        }
+        let read = |index| std::fs::read_to_string(snapshot_path(&dir, "level", index)).unwrap();
+        assert_eq!((read(0), read(1), read(2)), ("4".to_string(), "3".to_string(), "2".to_string()));
+        assert!(!snapshot_path(&dir, "level", 3).exists());
+        let origin = std::fs::read_to_string(snapshot_path(&dir, "level", 1).with_extension("path")).unwrap();
+        assert_eq!(origin, "origin3");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
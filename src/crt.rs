@@ -0,0 +1,178 @@
+//! CRT / composite video filter
+//!
+//! Optional post-process applied on the GPU when the game view's framebuffer
+//! is drawn to the screen: scanlines, barrel distortion, chroma bleed and
+//! adjustable pixel sharpness. It is a macroquad material on the final blit,
+//! so the software rasterizer does no extra work.
+//!
+//! Presets and the sharpness come from `DisplaySettings`. If the shader fails
+//! to compile (old GL drivers) the view is drawn unfiltered.
+
+use macroquad::prelude::*;
+
+use crate::display::{CrtPreset, DisplaySettings};
+
+/// Shader inputs for one look
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrtParams {
+    /// Darkening between source rows (0 = none, 1 = black gaps)
+    pub scanlines: f32,
+    /// Barrel distortion strength (0 = flat)
+    pub curvature: f32,
+    /// How far color smears sideways, in source pixels
+    pub chroma_bleed: f32,
+    /// Pixel edges: 0 = soft (bilinear), 1 = hard
+    pub sharpness: f32,
+}
+
+impl CrtPreset {
+    /// Shader inputs for this preset at the given sharpness
+    pub fn params(self, sharpness: f32) -> CrtParams {
+        let sharpness = sharpness.clamp(0.0, 1.0);
+        match self {
+            CrtPreset::Sharp => CrtParams { scanlines: 0.25, curvature: 0.0, chroma_bleed: 0.0, sharpness },
+            CrtPreset::Crt => CrtParams { scanlines: 0.5, curvature: 0.08, chroma_bleed: 0.5, sharpness },
+            // Composite is always a bit soft, whatever the setting
+            CrtPreset::Composite => CrtParams { scanlines: 0.2, curvature: 0.04, chroma_bleed: 1.5, sharpness: sharpness * 0.5 },
+        }
+    }
+}
+
+const VERTEX: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+
+varying lowp vec4 color;
+varying mediump vec2 uv;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1.0);
+    color = color0 / 255.0;
+    uv = texcoord;
+}
+"#;
+
+const FRAGMENT: &str = r#"#version 100
+precision mediump float;
+
+varying lowp vec4 color;
+varying mediump vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 SourceSize;
+uniform float Scanlines;
+uniform float Curvature;
+uniform float ChromaBleed;
+uniform float Sharpness;
+
+// Source texture (nearest-filtered) resampled with adjustable edge hardness
+vec3 fetch(vec2 p) {
+    vec2 texel = p * SourceSize - 0.5;
+    vec2 base = floor(texel);
+    vec2 f = texel - base;
+    float k = mix(1.0, 16.0, Sharpness);
+    f = clamp((f - 0.5) * k + 0.5, 0.0, 1.0);
+    vec2 inv = 1.0 / SourceSize;
+    vec2 p0 = (base + 0.5) * inv;
+    vec3 a = texture2D(Texture, p0).rgb;
+    vec3 b = texture2D(Texture, p0 + vec2(inv.x, 0.0)).rgb;
+    vec3 c = texture2D(Texture, p0 + vec2(0.0, inv.y)).rgb;
+    vec3 d = texture2D(Texture, p0 + inv).rgb;
+    return mix(mix(a, b, f.x), mix(c, d, f.x), f.y);
+}
+
+void main() {
+    // Barrel distortion: push coordinates outwards with distance from the center
+    vec2 centered = uv - 0.5;
+    vec2 p = uv + centered * dot(centered, centered) * Curvature * 4.0;
+    if (p.x < 0.0 || p.x > 1.0 || p.y < 0.0 || p.y > 1.0) {
+        gl_FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+        return;
+    }
+
+    // Chroma bleed: keep the sharp luma, take the color from a sideways blur
+    vec3 center = fetch(p);
+    vec2 bleed = vec2(ChromaBleed / SourceSize.x, 0.0);
+    vec3 blurred = (fetch(p - bleed) + center + fetch(p + bleed)) / 3.0;
+    const vec3 LUMA = vec3(0.299, 0.587, 0.114);
+    vec3 rgb = blurred + (dot(center, LUMA) - dot(blurred, LUMA));
+
+    // Scanlines: bright at the middle of each source row, darker between rows
+    float row = fract(p.y * SourceSize.y);
+    float scan = 1.0 - Scanlines * (0.5 + 0.5 * cos(row * 6.2831853));
+    rgb *= scan * (1.0 + Scanlines * 0.25);
+
+    gl_FragColor = vec4(clamp(rgb, 0.0, 1.0), 1.0) * color;
+}
+"#;
+
+/// The filter material, compiled on first use
+#[derive(Default)]
+pub struct CrtFilter {
+    material: Option<Material>,
+    /// Compilation failed; draw unfiltered from now on
+    failed: bool,
+}
+
+impl CrtFilter {
+    fn material(&mut self) -> Option<&Material> {
+        if self.material.is_none() && !self.failed {
+            let params = MaterialParams {
+                uniforms: vec![
+                    UniformDesc::new("SourceSize", UniformType::Float2),
+                    UniformDesc::new("Scanlines", UniformType::Float1),
+                    UniformDesc::new("Curvature", UniformType::Float1),
+                    UniformDesc::new("ChromaBleed", UniformType::Float1),
+                    UniformDesc::new("Sharpness", UniformType::Float1),
+                ],
+                ..Default::default()
+            };
+            match load_material(ShaderSource::Glsl { vertex: VERTEX, fragment: FRAGMENT }, params) {
+                Ok(material) => self.material = Some(material),
+                Err(e) => {
+                    eprintln!("CRT filter unavailable: {:?}", e);
+                    self.failed = true;
+                }
+            }
+        }
+        self.material.as_ref()
+    }
+
+    /// Draw `texture` into the given screen rect, through the filter if the
+    /// display settings turn it on
+    pub fn draw(&mut self, texture: &Texture2D, x: f32, y: f32, w: f32, h: f32, display: &DisplaySettings) {
+        let params = DrawTextureParams { dest_size: Some(vec2(w, h)), ..Default::default() };
+        let material = if display.crt_filter { self.material() } else { None };
+        let Some(material) = material else {
+            draw_texture_ex(texture, x, y, WHITE, params);
+            return;
+        };
+
+        let crt = display.crt_preset.params(display.crt_sharpness);
+        material.set_uniform("SourceSize", (texture.width(), texture.height()));
+        material.set_uniform("Scanlines", crt.scanlines);
+        material.set_uniform("Curvature", crt.curvature);
+        material.set_uniform("ChromaBleed", crt.chroma_bleed);
+        material.set_uniform("Sharpness", crt.sharpness);
+        gl_use_material(material);
+        draw_texture_ex(texture, x, y, WHITE, params);
+        gl_use_default_material();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_params() {
+        let sharp = CrtPreset::Sharp.params(1.0);
+        assert_eq!((sharp.curvature, sharp.chroma_bleed, sharp.sharpness), (0.0, 0.0, 1.0));
+        assert!(CrtPreset::Composite.params(1.0).chroma_bleed > CrtPreset::Crt.params(1.0).chroma_bleed);
+        assert_eq!(CrtPreset::Crt.params(3.0).sharpness, 1.0);
+    }
+}
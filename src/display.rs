@@ -0,0 +1,456 @@
+//! Display settings
+//!
+//! Window mode, integer scaling, the CRT filter, window placement, the UI
+//! language and the editor theme, persisted per user in the config directory
+//! (`<config>/bonnie-32/display.ron`) on native builds.
+//! On WASM the browser owns the canvas, so settings stay at their defaults
+//! and nothing is saved.
+//!
+//! miniquad can't enumerate monitors, so the target monitor is remembered as
+//! the window position: move the window to a monitor once and it reopens there.
+
+#![allow(dead_code)]
+
+use macroquad::prelude::get_time;
+use serde::{Deserialize, Serialize};
+use crate::ui::ThemeSettings;
+
+/// Size requested for `Maximized` so the OS clamps the window to the screen
+/// bounds (pseudo-maximize; miniquad has no maximize call)
+const MAXIMIZED_SIZE: (u32, u32) = (3840, 2160);
+/// Smallest window size we restore (guards against corrupt settings)
+const MIN_WINDOW_SIZE: u32 = 320;
+/// Seconds the window must stay put before its new position is saved
+const SAVE_DELAY: f64 = 1.0;
+
+/// How the main window is presented
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WindowMode {
+    /// Regular window at the saved size
+    Windowed,
+    /// Window filling the current monitor, with decorations
+    #[default]
+    Maximized,
+    /// Borderless fullscreen on the current monitor
+    Borderless,
+}
+
+impl WindowMode {
+    pub const ALL: [WindowMode; 3] = [WindowMode::Windowed, WindowMode::Maximized, WindowMode::Borderless];
+
+    /// Cycle to next value
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&m| m == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// Cycle to previous value
+    pub fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|&m| m == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    /// Display name
+    pub fn label(&self) -> &'static str {
+        match self {
+            WindowMode::Windowed => "Windowed",
+            WindowMode::Maximized => "Maximized",
+            WindowMode::Borderless => "Borderless",
+        }
+    }
+}
+
+/// Look of the CRT filter (see `crate::crt`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CrtPreset {
+    /// Faint scanlines on a flat screen
+    Sharp,
+    /// Curved tube, strong scanlines
+    #[default]
+    Crt,
+    /// Soft picture with chroma bleeding into neighbouring pixels
+    Composite,
+}
+
+impl CrtPreset {
+    pub const ALL: [CrtPreset; 3] = [CrtPreset::Sharp, CrtPreset::Crt, CrtPreset::Composite];
+
+    /// Cycle to next value
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&p| p == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// Cycle to previous value
+    pub fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|&p| p == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    /// Display name
+    pub fn label(&self) -> &'static str {
+        match self {
+            CrtPreset::Sharp => "Sharp",
+            CrtPreset::Crt => "CRT",
+            CrtPreset::Composite => "Composite",
+        }
+    }
+}
+
+/// Persisted display settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisplaySettings {
+    #[serde(default)]
+    pub window_mode: WindowMode,
+    /// Window size used in `Windowed` mode (logical pixels)
+    #[serde(default = "default_window_size")]
+    pub window_size: (u32, u32),
+    /// Last window position (top-left, screen pixels). Selects the monitor the
+    /// window opens on; None = let the OS decide.
+    #[serde(default)]
+    pub window_position: Option<(u32, u32)>,
+    /// Scale the game view by whole multiples only (crisp pixels, may letterbox)
+    #[serde(default)]
+    pub integer_scaling: bool,
+    /// Draw the game view through the CRT filter
+    #[serde(default)]
+    pub crt_filter: bool,
+    #[serde(default)]
+    pub crt_preset: CrtPreset,
+    /// CRT filter pixel edges: 0 = soft (bilinear), 1 = hard
+    #[serde(default = "default_crt_sharpness")]
+    pub crt_sharpness: f32,
+    /// Language code of the string table to use (see `crate::locale`)
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Editor theme preset and accent color (see `crate::ui::Theme`)
+    #[serde(default)]
+    pub theme: ThemeSettings,
+
+    /// Settings changed and need to be applied to the window
+    #[serde(skip)]
+    dirty: bool,
+    /// Position seen last frame and when it last changed (for delayed saving)
+    #[serde(skip)]
+    last_seen_position: Option<((u32, u32), f64)>,
+}
+
+fn default_window_size() -> (u32, u32) {
+    (1280, 960)
+}
+
+fn default_crt_sharpness() -> f32 {
+    0.5
+}
+
+fn default_language() -> String {
+    crate::locale::DEFAULT_LANGUAGE.to_string()
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            window_mode: WindowMode::default(),
+            window_size: default_window_size(),
+            window_position: None,
+            integer_scaling: false,
+            crt_filter: false,
+            crt_preset: CrtPreset::default(),
+            crt_sharpness: default_crt_sharpness(),
+            language: default_language(),
+            theme: ThemeSettings::default(),
+            dirty: false,
+            last_seen_position: None,
+        }
+    }
+}
+
+impl DisplaySettings {
+    /// Load settings from the config directory (defaults if missing or unreadable)
+    pub fn load() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Ok(contents) = std::fs::read_to_string(settings_path()) {
+                match ron::from_str::<DisplaySettings>(&contents) {
+                    Ok(mut settings) => {
+                        settings.sanitize();
+                        return settings;
+                    }
+                    Err(e) => eprintln!("Failed to parse display settings: {}", e),
+                }
+            }
+        }
+        Self::default()
+    }
+
+    /// Write settings to the config directory
+    pub fn save(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = settings_path();
+            if let Some(parent) = path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+                Ok(contents) => {
+                    if let Err(e) = std::fs::write(&path, contents) {
+                        eprintln!("Failed to save display settings: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("Failed to serialize display settings: {}", e),
+            }
+        }
+    }
+
+    /// Clamp values that would produce an unusable window
+    fn sanitize(&mut self) {
+        self.window_size.0 = self.window_size.0.max(MIN_WINDOW_SIZE);
+        self.window_size.1 = self.window_size.1.max(MIN_WINDOW_SIZE);
+        self.crt_sharpness = self.crt_sharpness.clamp(0.0, 1.0);
+    }
+
+    /// Initial window size and fullscreen flag for the window `Conf`
+    pub fn startup_window(&self) -> (i32, i32, bool) {
+        let (w, h) = match self.window_mode {
+            WindowMode::Windowed => self.window_size,
+            WindowMode::Maximized | WindowMode::Borderless => MAXIMIZED_SIZE,
+        };
+        (w as i32, h as i32, self.window_mode == WindowMode::Borderless)
+    }
+
+    /// Change the window mode (applied on the next `update`, and saved)
+    pub fn set_window_mode(&mut self, mode: WindowMode) {
+        if self.window_mode != mode {
+            self.window_mode = mode;
+            self.dirty = true;
+        }
+    }
+
+    /// Toggle integer scaling (saved on the next `update`)
+    pub fn set_integer_scaling(&mut self, enabled: bool) {
+        if self.integer_scaling != enabled {
+            self.integer_scaling = enabled;
+            self.dirty = true;
+        }
+    }
+
+    /// Toggle the CRT filter (saved on the next `update`)
+    pub fn set_crt_filter(&mut self, enabled: bool) {
+        if self.crt_filter != enabled {
+            self.crt_filter = enabled;
+            self.dirty = true;
+        }
+    }
+
+    /// Change the CRT filter's look (saved on the next `update`)
+    pub fn set_crt_preset(&mut self, preset: CrtPreset) {
+        if self.crt_preset != preset {
+            self.crt_preset = preset;
+            self.dirty = true;
+        }
+    }
+
+    /// Set the CRT filter's sharpness, clamped to 0..1 (saved on the next `update`)
+    pub fn set_crt_sharpness(&mut self, sharpness: f32) {
+        let sharpness = sharpness.clamp(0.0, 1.0);
+        if self.crt_sharpness != sharpness {
+            self.crt_sharpness = sharpness;
+            self.dirty = true;
+        }
+    }
+
+    /// Change the UI language (saved on the next `update`)
+    pub fn set_language(&mut self, code: &str) {
+        if self.language != code {
+            self.language = code.to_string();
+            self.dirty = true;
+        }
+    }
+
+    /// Change the editor theme. Applied immediately so the UI restyles on
+    /// the next frame, saved on the next `update`.
+    pub fn set_theme(&mut self, theme: ThemeSettings) {
+        if self.theme != theme {
+            self.theme = theme;
+            self.dirty = true;
+        }
+        self.apply_theme();
+    }
+
+    /// Make the saved theme the one the UI draws with. Call once at startup.
+    pub fn apply_theme(&self) {
+        crate::ui::set_theme(self.theme.theme());
+    }
+
+    /// Move the window to its saved position. Call once after the window opens.
+    pub fn restore_position(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some((x, y)) = self.window_position {
+                macroquad::miniquad::window::set_window_position(x, y);
+            }
+        }
+    }
+
+    /// Apply pending changes to the window and remember where the window was
+    /// moved to. Call once per frame.
+    pub fn update(&mut self) {
+        if self.dirty {
+            self.dirty = false;
+            self.apply_window_mode();
+            self.save();
+        }
+        self.track_position(get_time());
+    }
+
+    fn apply_window_mode(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            macroquad::miniquad::window::set_fullscreen(self.window_mode == WindowMode::Borderless);
+            match self.window_mode {
+                WindowMode::Windowed => {
+                    let (w, h) = self.window_size;
+                    macroquad::miniquad::window::set_window_size(w, h);
+                }
+                WindowMode::Maximized => {
+                    macroquad::miniquad::window::set_window_size(MAXIMIZED_SIZE.0, MAXIMIZED_SIZE.1);
+                }
+                WindowMode::Borderless => {}
+            }
+        }
+    }
+
+    /// Save the window position (and windowed size) once the user stops moving it
+    fn track_position(&mut self, now: f64) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // Fullscreen position is always the monitor origin, nothing to learn there
+            if self.window_mode == WindowMode::Borderless {
+                self.last_seen_position = None;
+                return;
+            }
+            let position = macroquad::miniquad::window::get_window_position();
+            if self.observe_position(position, now) {
+                if self.window_mode == WindowMode::Windowed {
+                    let (w, h) = macroquad::miniquad::window::screen_size();
+                    let dpi = macroquad::miniquad::window::dpi_scale().max(1.0);
+                    self.window_size = ((w / dpi) as u32, (h / dpi) as u32);
+                    self.sanitize();
+                }
+                self.save();
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = now;
+        }
+    }
+
+    /// Record the window position seen at `now`. Returns true when a moved
+    /// window has stayed put for `SAVE_DELAY` and its position was stored.
+    fn observe_position(&mut self, position: (u32, u32), now: f64) -> bool {
+        match self.last_seen_position {
+            Some((seen, since)) if seen == position => {
+                if self.window_position != Some(position) && now - since >= SAVE_DELAY {
+                    self.window_position = Some(position);
+                    return true;
+                }
+                false
+            }
+            _ => {
+                self.last_seen_position = Some((position, now));
+                false
+            }
+        }
+    }
+}
+
+/// Location of the display settings file
+#[cfg(not(target_arch = "wasm32"))]
+fn settings_path() -> std::path::PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("bonnie-32")
+        .join("display.ron")
+}
+
+/// Fit a `fb_w` x `fb_h` image into `area_w` x `area_h`, keeping its aspect ratio.
+/// With `integer_scaling` the scale is rounded down to a whole multiple (at least 1x).
+/// Returns (width, height, x offset, y offset) of the centered image.
+pub fn fit_scaled(area_w: f32, area_h: f32, fb_w: usize, fb_h: usize, integer_scaling: bool) -> (f32, f32, f32, f32) {
+    let (fb_w, fb_h) = (fb_w.max(1) as f32, fb_h.max(1) as f32);
+    let mut scale = (area_w / fb_w).min(area_h / fb_h);
+    if integer_scaling {
+        scale = scale.floor().max(1.0);
+    }
+    let (w, h) = (fb_w * scale, fb_h * scale);
+    (w, h, ((area_w - w) * 0.5).floor(), ((area_h - h) * 0.5).floor())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_scaled() {
+        // 4:3 into 16:9, fractional scale fills the height
+        let (w, h, x, y) = fit_scaled(1920.0, 1080.0, 320, 240, false);
+        assert_eq!((w, h, x, y), (1440.0, 1080.0, 240.0, 0.0));
+
+        // Integer scaling rounds 4.5x down to 4x and centers
+        let (w, h, x, y) = fit_scaled(1920.0, 1080.0, 320, 240, true);
+        assert_eq!((w, h, x, y), (1280.0, 960.0, 320.0, 60.0));
+
+        // Never below 1x, even if the area is too small
+        let (w, h, _, _) = fit_scaled(200.0, 100.0, 320, 240, true);
+        assert_eq!((w, h), (320.0, 240.0));
+    }
+
+    #[test]
+    fn test_settings_roundtrip() {
+        let settings = DisplaySettings {
+            window_mode: WindowMode::Borderless,
+            window_position: Some((1920, 0)),
+            integer_scaling: true,
+            crt_filter: true,
+            crt_preset: CrtPreset::Composite,
+            theme: ThemeSettings { preset: crate::ui::ThemePreset::HighContrast, accent: Some([255, 64, 0]) },
+            ..DisplaySettings::default()
+        };
+        let text = ron::to_string(&settings).unwrap();
+        let loaded: DisplaySettings = ron::from_str(&text).unwrap();
+        assert_eq!(loaded, settings);
+
+        // Older/partial files fall back to defaults
+        let loaded: DisplaySettings = ron::from_str("(integer_scaling: true)").unwrap();
+        assert_eq!(loaded.window_mode, WindowMode::Maximized);
+        assert!(loaded.integer_scaling);
+        assert!(!loaded.crt_filter);
+        assert_eq!(loaded.crt_sharpness, 0.5);
+        assert_eq!(loaded.language, "en");
+        assert_eq!(loaded.theme, ThemeSettings::default());
+    }
+
+    #[test]
+    fn test_position_saved_after_delay() {
+        let mut settings = DisplaySettings::default();
+        assert!(!settings.observe_position((100, 50), 0.0));
+        // Still moving
+        assert!(!settings.observe_position((900, 50), 0.5));
+        assert!(!settings.observe_position((900, 50), 1.0));
+        // Settled for a second
+        assert!(settings.observe_position((900, 50), 1.6));
+        assert_eq!(settings.window_position, Some((900, 50)));
+        // Already saved: no repeated writes
+        assert!(!settings.observe_position((900, 50), 5.0));
+    }
+
+    #[test]
+    fn test_startup_window() {
+        let mut settings = DisplaySettings { window_size: (800, 600), ..DisplaySettings::default() };
+        settings.window_mode = WindowMode::Windowed;
+        assert_eq!(settings.startup_window(), (800, 600, false));
+        settings.window_mode = WindowMode::Borderless;
+        assert!(settings.startup_window().2);
+    }
+}
@@ -0,0 +1,195 @@
+//! Dungeon Wizard
+//!
+//! Modal dialog for the procedural dungeon generator: tweak room count, room
+//! sizes, corridor width, loops and seed while a top-down preview updates,
+//! then generate the blockout into the level.
+
+use macroquad::prelude::*;
+use crate::ui::{Rect, UiContext, theme};
+use crate::world::{generate_dungeon_layout, DungeonCell, DungeonLayout, DungeonSettings};
+use super::level_browser::{draw_close_button, draw_text_button};
+
+/// State for the dungeon wizard dialog
+#[derive(Default)]
+pub struct DungeonWizard {
+    /// Whether the wizard is open
+    pub open: bool,
+    /// Generator parameters (kept between openings)
+    pub settings: DungeonSettings,
+    /// Layout for the current settings
+    pub preview: Option<DungeonLayout>,
+}
+
+impl DungeonWizard {
+    pub fn open(&mut self) {
+        self.open = true;
+        self.preview = Some(generate_dungeon_layout(&self.settings));
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+        self.preview = None;
+    }
+}
+
+/// Result from drawing the dungeon wizard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DungeonWizardAction {
+    None,
+    /// Build the previewed layout into the level
+    Generate,
+    Cancel,
+}
+
+/// Draw the dungeon wizard modal dialog
+pub fn draw_dungeon_wizard(
+    ctx: &mut UiContext,
+    wizard: &mut DungeonWizard,
+    icon_font: Option<&Font>,
+) -> DungeonWizardAction {
+    if !wizard.open {
+        return DungeonWizardAction::None;
+    }
+
+    let mut action = DungeonWizardAction::None;
+
+    // Darken background
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::from_rgba(0, 0, 0, 180));
+
+    let dialog_w = (screen_width() * 0.7).min(680.0);
+    let dialog_h = (screen_height() * 0.7).min(440.0);
+    let dialog_x = (screen_width() - dialog_w) / 2.0;
+    let dialog_y = (screen_height() - dialog_h) / 2.0;
+
+    draw_rectangle(dialog_x, dialog_y, dialog_w, dialog_h, Color::from_rgba(35, 35, 40, 255));
+    draw_rectangle_lines(dialog_x, dialog_y, dialog_w, dialog_h, 2.0, Color::from_rgba(60, 60, 70, 255));
+
+    // Header
+    let header_h = 40.0;
+    draw_rectangle(dialog_x, dialog_y, dialog_w, header_h, Color::from_rgba(45, 45, 55, 255));
+    draw_text("Generate Dungeon", dialog_x + 16.0, dialog_y + 26.0, 20.0, WHITE);
+
+    let close_rect = Rect::new(dialog_x + dialog_w - 36.0, dialog_y + 4.0, 32.0, 32.0);
+    if draw_close_button(ctx, close_rect, icon_font) {
+        action = DungeonWizardAction::Cancel;
+    }
+
+    // Parameters (left)
+    let content_y = dialog_y + header_h + 12.0;
+    let content_h = dialog_h - header_h - 68.0;
+    let params_w = 220.0;
+    let row_h = 30.0;
+    let s = &mut wizard.settings;
+    let mut changed = false;
+
+    let rows: [(&str, String); 6] = [
+        ("Rooms", s.rooms.to_string()),
+        ("Min size", s.room_min.to_string()),
+        ("Max size", s.room_max.to_string()),
+        ("Corridor", s.corridor_width.to_string()),
+        ("Loops", s.loops.to_string()),
+        ("Seed", s.seed.to_string()),
+    ];
+    for (i, (label, value)) in rows.iter().enumerate() {
+        let y = content_y + i as f32 * row_h;
+        draw_text(label, dialog_x + 16.0, y + 18.0, 14.0, Color::from_rgba(170, 170, 180, 255));
+        draw_text(value, dialog_x + 100.0, y + 18.0, 14.0, WHITE);
+        let minus_rect = Rect::new(dialog_x + params_w - 56.0, y + 2.0, 24.0, 24.0);
+        let plus_rect = Rect::new(dialog_x + params_w - 28.0, y + 2.0, 24.0, 24.0);
+        let minus = draw_text_button(ctx, minus_rect, "-", Color::from_rgba(55, 55, 65, 255));
+        let plus = draw_text_button(ctx, plus_rect, "+", Color::from_rgba(55, 55, 65, 255));
+        let step = plus as i64 - minus as i64;
+        if step == 0 {
+            continue;
+        }
+        let bump = |v: usize, lo: usize, hi: usize| (v as i64 + step).clamp(lo as i64, hi as i64) as usize;
+        match i {
+            0 => s.rooms = bump(s.rooms, 2, 32),
+            1 => {
+                s.room_min = bump(s.room_min, 2, 12);
+                s.room_max = s.room_max.max(s.room_min);
+            }
+            2 => {
+                s.room_max = bump(s.room_max, 2, 12);
+                s.room_min = s.room_min.min(s.room_max);
+            }
+            3 => s.corridor_width = bump(s.corridor_width, 1, 3),
+            4 => s.loops = bump(s.loops, 0, 16),
+            _ => s.seed = s.seed.wrapping_add_signed(step),
+        }
+        changed = true;
+    }
+
+    let reroll_rect = Rect::new(dialog_x + 16.0, content_y + rows.len() as f32 * row_h + 6.0, params_w - 20.0, 26.0);
+    if draw_text_button(ctx, reroll_rect, "Random Seed", Color::from_rgba(60, 60, 70, 255)) {
+        s.seed = (get_time() * 1000.0) as u64 ^ s.seed.rotate_left(17);
+        changed = true;
+    }
+
+    if changed || wizard.preview.is_none() {
+        wizard.preview = Some(generate_dungeon_layout(&wizard.settings));
+    }
+
+    // Preview (right)
+    let preview_rect = Rect::new(dialog_x + params_w + 16.0, content_y, dialog_w - params_w - 32.0, content_h);
+    draw_rectangle(preview_rect.x, preview_rect.y, preview_rect.w, preview_rect.h, Color::from_rgba(20, 20, 25, 255));
+    if let Some(layout) = &wizard.preview {
+        draw_layout_preview(layout, preview_rect);
+        let stats = format!(
+            "{} rooms, {} corridors",
+            layout.rooms.len(),
+            layout.corridor_count()
+        );
+        draw_text(&stats, preview_rect.x + 8.0, preview_rect.bottom() - 8.0, 14.0, Color::from_rgba(180, 180, 180, 255));
+    }
+
+    // Footer with buttons
+    let footer_y = dialog_y + dialog_h - 44.0;
+    draw_rectangle(dialog_x, footer_y, dialog_w, 44.0, Color::from_rgba(40, 40, 48, 255));
+    draw_text(
+        "Adds the rooms east of the level",
+        dialog_x + 16.0,
+        footer_y + 27.0,
+        14.0,
+        Color::from_rgba(120, 120, 130, 255),
+    );
+
+    let cancel_rect = Rect::new(dialog_x + dialog_w - 190.0, footer_y + 8.0, 70.0, 28.0);
+    if draw_text_button(ctx, cancel_rect, "Cancel", Color::from_rgba(60, 60, 70, 255)) {
+        action = DungeonWizardAction::Cancel;
+    }
+
+    let generate_rect = Rect::new(dialog_x + dialog_w - 110.0, footer_y + 8.0, 100.0, 28.0);
+    if draw_text_button(ctx, generate_rect, "Generate", theme().accent) {
+        action = DungeonWizardAction::Generate;
+    }
+
+    if is_key_pressed(KeyCode::Escape) {
+        action = DungeonWizardAction::Cancel;
+    }
+
+    action
+}
+
+/// Top-down view of the layout: rooms, corridors and the grid outline
+fn draw_layout_preview(layout: &DungeonLayout, rect: Rect) {
+    let pad = 12.0;
+    let cell = ((rect.w - pad * 2.0) / layout.width as f32)
+        .min((rect.h - pad * 2.0 - 16.0) / layout.depth as f32)
+        .floor()
+        .max(1.0);
+    let ox = rect.x + (rect.w - cell * layout.width as f32) / 2.0;
+    let oy = rect.y + pad;
+
+    for z in 0..layout.depth {
+        for x in 0..layout.width {
+            let color = match layout.cell(x, z) {
+                DungeonCell::Empty => continue,
+                DungeonCell::Room(_) => Color::from_rgba(90, 140, 200, 255),
+                DungeonCell::Corridor(_) => Color::from_rgba(150, 130, 90, 255),
+            };
+            draw_rectangle(ox + x as f32 * cell, oy + z as f32 * cell, cell - 1.0, cell - 1.0, color);
+        }
+    }
+    draw_rectangle_lines(ox, oy, cell * layout.width as f32, cell * layout.depth as f32, 1.0, Color::from_rgba(50, 50, 60, 255));
+}
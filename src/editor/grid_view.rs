@@ -6,7 +6,9 @@ use macroquad::prelude::*;
 use crate::rasterizer::Vec3;
 use crate::ui::{Rect, UiContext};
 use crate::world::{Direction, SplitDirection, SECTOR_SIZE};
+use crate::game::analytics::HeatmapKind;
 use super::{EditorState, EditorTool, Selection, GridViewMode, CEILING_HEIGHT, CLICK_HEIGHT};
+use super::level_diff::{draw_diff_report, sector_change_color};
 
 /// Determine which edge of a sector the mouse is closest to (in Top view mode)
 /// Returns the direction of the closest edge based on position within the sector
@@ -521,6 +523,117 @@ pub fn draw_grid_view(ctx: &mut UiContext, rect: Rect, state: &mut EditorState)
         }
     }
 
+    // Texture brush: outline the sector that will be painted
+    if view_mode == GridViewMode::Top && state.tool == super::EditorTool::PaintTexture {
+        if let Some((gx, gz)) = hovered_sector {
+            let base_x = room.position.x + (gx as f32) * SECTOR_SIZE;
+            let base_z = room.position.z + (gz as f32) * SECTOR_SIZE;
+            let (sx0, sy0) = world_to_screen(base_x, base_z);
+            let (sx2, sy2) = world_to_screen(base_x + SECTOR_SIZE, base_z + SECTOR_SIZE);
+            let brush_color = Color::from_rgba(120, 200, 255, 255);
+            draw_rectangle_lines(sx0.min(sx2), sy0.min(sy2), (sx2 - sx0).abs(), (sy2 - sy0).abs(), 2.0, brush_color);
+        }
+    }
+
+    // Draw playtest heatmap (top view only)
+    if view_mode == GridViewMode::Top && state.playtest.overlay != HeatmapKind::Off {
+        let kind = state.playtest.overlay;
+        let heat = state.playtest.heat(kind);
+        let max_heat = heat.values().copied().fold(0.0_f32, f32::max);
+        if max_heat > 0.0 {
+            for (&(room_idx, gx, gz), &value) in &heat {
+                let Some(heat_room) = state.level.rooms.get(room_idx) else {
+                    continue;
+                };
+                if state.hidden_rooms.contains(&room_idx) {
+                    continue;
+                }
+                let x0 = heat_room.position.x + gx as f32 * SECTOR_SIZE;
+                let z1 = heat_room.position.z + (gz + 1) as f32 * SECTOR_SIZE;
+                let (sx, sy) = world_to_screen(x0, z1);
+                let size = SECTOR_SIZE * scale;
+                // Yellow (low) to red (high)
+                let t = (value / max_heat).clamp(0.0, 1.0);
+                let color = Color::new(1.0, 1.0 - t, 0.0, 0.15 + 0.45 * t);
+                draw_rectangle(sx, sy, size, size, color);
+            }
+        }
+
+        // Exact death spots
+        if kind == HeatmapKind::Deaths {
+            for death in state.playtest.sessions.iter().flat_map(|s| &s.deaths) {
+                let (sx, sy) = world_to_screen(death.position.0, death.position.2);
+                let color = Color::from_rgba(255, 60, 60, 255);
+                draw_line(sx - 4.0, sy - 4.0, sx + 4.0, sy + 4.0, 2.0, color);
+                draw_line(sx - 4.0, sy + 4.0, sx + 4.0, sy - 4.0, 2.0, color);
+            }
+        }
+
+        // Legend: overlay, session count and current room summary
+        let (room_time, room_deaths, room_damage) = state.playtest.room_summary(current_room_idx);
+        let mut legend = format!(
+            "Heatmap: {} | {} session(s) | R{}: {:.0}s, {} death(s), {} dmg",
+            kind.label(),
+            state.playtest.sessions.len(),
+            current_room_idx,
+            room_time,
+            room_deaths,
+            room_damage,
+        );
+        if kind == HeatmapKind::Damage {
+            if let Some((source, amount)) = state.playtest.damage_by_source().into_iter().next() {
+                legend.push_str(&format!(" | top: {} ({})", source, amount));
+            }
+        }
+        draw_text(&legend, rect.x + 6.0, rect.bottom() - 6.0, 12.0, Color::from_rgba(255, 200, 120, 255));
+    }
+
+    // Draw level diff highlights (top view only)
+    if view_mode == GridViewMode::Top {
+        if let Some(view) = &state.level_diff {
+            for change in &view.diff.sectors {
+                let Some(diff_room) = state.level.rooms.get(change.room) else {
+                    continue;
+                };
+                if state.hidden_rooms.contains(&change.room) {
+                    continue;
+                }
+                let x0 = diff_room.position.x + change.x as f32 * SECTOR_SIZE;
+                let z1 = diff_room.position.z + (change.z + 1) as f32 * SECTOR_SIZE;
+                let (sx, sy) = world_to_screen(x0, z1);
+                let size = SECTOR_SIZE * scale;
+                let color = sector_change_color(change.kind, change.texture_only);
+                draw_rectangle(sx, sy, size, size, Color::new(color.r, color.g, color.b, 0.25));
+                draw_rectangle_lines(sx, sy, size, size, 2.0, color);
+            }
+
+            // Object changes: marker on the sector center
+            for change in &view.diff.objects {
+                let Some(diff_room) = state.level.rooms.get(change.room) else {
+                    continue;
+                };
+                if state.hidden_rooms.contains(&change.room) {
+                    continue;
+                }
+                let cx = diff_room.position.x + (change.sector_x as f32 + 0.5) * SECTOR_SIZE;
+                let cz = diff_room.position.z + (change.sector_z as f32 + 0.5) * SECTOR_SIZE;
+                let (sx, sy) = world_to_screen(cx, cz);
+                let color = sector_change_color(change.kind, false);
+                draw_circle_lines(sx, sy, 6.0, 2.0, color);
+                let symbol = change.kind.symbol().to_string();
+                draw_text(&symbol, sx - 3.0, sy + 4.0, 12.0, color);
+            }
+
+            // Keep clear of the heatmap legend line
+            let report_rect = if state.playtest.overlay != HeatmapKind::Off {
+                Rect::new(rect.x, rect.y, rect.w, rect.h - 14.0)
+            } else {
+                rect
+            };
+            draw_diff_report(report_rect, view);
+        }
+    }
+
     // Draw portals (view-mode-aware)
     for portal in &room.portals {
         // Portal vertices are room-relative, convert to world space
@@ -601,6 +714,247 @@ pub fn draw_grid_view(ctx: &mut UiContext, rect: Rect, state: &mut EditorState)
         draw_line(sx3, sy3, sx0, sy0, 2.0, outline_color);
     }
 
+    // Draw trigger volumes for current room (hovered near their outline)
+    let mut hovered_trigger: Option<usize> = None;
+    for (trig_idx, trigger) in room.triggers.iter().enumerate() {
+        let (min, max) = trigger.world_bounds(room.position);
+        let (a0, b0) = world_pos_to_plane(min.x, min.y, min.z);
+        let (a1, b1) = world_pos_to_plane(max.x, max.y, max.z);
+        let (sx0, sy0) = world_to_screen(a0, b0);
+        let (sx1, sy1) = world_to_screen(a1, b1);
+        let (left, right) = (sx0.min(sx1), sx0.max(sx1));
+        let (top, bottom) = (sy0.min(sy1), sy0.max(sy1));
+
+        let is_selected = matches!(&state.selection, Selection::Trigger { room: r, index } if *r == current_room_idx && *index == trig_idx);
+        let (mx, my) = mouse_pos;
+        let near_x = mx >= left - 5.0 && mx <= right + 5.0;
+        let near_y = my >= top - 5.0 && my <= bottom + 5.0;
+        let on_outline = near_x && near_y
+            && ((mx - left).abs() <= 5.0 || (mx - right).abs() <= 5.0 || (my - top).abs() <= 5.0 || (my - bottom).abs() <= 5.0);
+        if inside && on_outline && hovered_trigger.is_none() {
+            hovered_trigger = Some(trig_idx);
+        }
+
+        draw_rectangle(left, top, right - left, bottom - top, Color::from_rgba(255, 150, 100, 35));
+        let outline = if is_selected {
+            WHITE
+        } else if hovered_trigger == Some(trig_idx) {
+            Color::from_rgba(255, 220, 180, 255)
+        } else {
+            Color::from_rgba(255, 150, 100, 200)
+        };
+        draw_rectangle_lines(left, top, right - left, bottom - top, if is_selected { 2.0 } else { 1.0 }, outline);
+        draw_text(&trigger.trigger_id, left + 3.0, top + 11.0, 11.0, outline);
+    }
+
+    // Draw ladders for current room (hovered near their outline); the thick
+    // edge is the side the ladder is fixed to
+    let mut hovered_ladder: Option<usize> = None;
+    for (ladder_idx, ladder) in room.ladders.iter().enumerate() {
+        let (min, max) = ladder.world_bounds(room.position);
+        let (a0, b0) = world_pos_to_plane(min.x, min.y, min.z);
+        let (a1, b1) = world_pos_to_plane(max.x, max.y, max.z);
+        let (sx0, sy0) = world_to_screen(a0, b0);
+        let (sx1, sy1) = world_to_screen(a1, b1);
+        let (left, right) = (sx0.min(sx1), sx0.max(sx1));
+        let (top, bottom) = (sy0.min(sy1), sy0.max(sy1));
+
+        let is_selected = matches!(&state.selection, Selection::Ladder { room: r, index } if *r == current_room_idx && *index == ladder_idx);
+        let (mx, my) = mouse_pos;
+        let near_x = mx >= left - 5.0 && mx <= right + 5.0;
+        let near_y = my >= top - 5.0 && my <= bottom + 5.0;
+        let on_outline = near_x && near_y
+            && ((mx - left).abs() <= 5.0 || (mx - right).abs() <= 5.0 || (my - top).abs() <= 5.0 || (my - bottom).abs() <= 5.0);
+        if inside && on_outline && hovered_ladder.is_none() && hovered_trigger.is_none() {
+            hovered_ladder = Some(ladder_idx);
+        }
+
+        draw_rectangle(left, top, right - left, bottom - top, Color::from_rgba(120, 200, 80, 35));
+        let outline = if is_selected {
+            WHITE
+        } else if hovered_ladder == Some(ladder_idx) {
+            Color::from_rgba(200, 255, 170, 255)
+        } else {
+            Color::from_rgba(120, 200, 80, 200)
+        };
+        draw_rectangle_lines(left, top, right - left, bottom - top, if is_selected { 2.0 } else { 1.0 }, outline);
+
+        let facing = ladder.facing_vector();
+        let (ex0, ex1) = if facing.x != 0.0 {
+            let x = if facing.x > 0.0 { max.x } else { min.x };
+            (Vec3::new(x, min.y, min.z), Vec3::new(x, max.y, max.z))
+        } else {
+            let z = if facing.z > 0.0 { max.z } else { min.z };
+            (Vec3::new(min.x, min.y, z), Vec3::new(max.x, max.y, z))
+        };
+        let (ea0, eb0) = world_pos_to_plane(ex0.x, ex0.y, ex0.z);
+        let (ea1, eb1) = world_pos_to_plane(ex1.x, ex1.y, ex1.z);
+        let (ex0, ey0) = world_to_screen(ea0, eb0);
+        let (ex1, ey1) = world_to_screen(ea1, eb1);
+        draw_line(ex0, ey0, ex1, ey1, 4.0, outline);
+        draw_text("Ladder", left + 3.0, top + 11.0, 11.0, outline);
+    }
+
+    // Draw camera volumes for current room (hovered near their outline), with
+    // the fixed camera or the rail it slides along
+    let mut hovered_camera: Option<usize> = None;
+    for (camera_idx, camera) in room.cameras.iter().enumerate() {
+        let (min, max) = camera.world_bounds(room.position);
+        let (a0, b0) = world_pos_to_plane(min.x, min.y, min.z);
+        let (a1, b1) = world_pos_to_plane(max.x, max.y, max.z);
+        let (sx0, sy0) = world_to_screen(a0, b0);
+        let (sx1, sy1) = world_to_screen(a1, b1);
+        let (left, right) = (sx0.min(sx1), sx0.max(sx1));
+        let (top, bottom) = (sy0.min(sy1), sy0.max(sy1));
+
+        let is_selected = matches!(&state.selection, Selection::Camera { room: r, index } if *r == current_room_idx && *index == camera_idx);
+        let (mx, my) = mouse_pos;
+        let near_x = mx >= left - 5.0 && mx <= right + 5.0;
+        let near_y = my >= top - 5.0 && my <= bottom + 5.0;
+        let on_outline = near_x && near_y
+            && ((mx - left).abs() <= 5.0 || (mx - right).abs() <= 5.0 || (my - top).abs() <= 5.0 || (my - bottom).abs() <= 5.0);
+        if inside && on_outline && hovered_camera.is_none() && hovered_ladder.is_none() && hovered_trigger.is_none() {
+            hovered_camera = Some(camera_idx);
+        }
+
+        draw_rectangle(left, top, right - left, bottom - top, Color::from_rgba(100, 170, 255, 30));
+        let outline = if is_selected {
+            WHITE
+        } else if hovered_camera == Some(camera_idx) {
+            Color::from_rgba(190, 220, 255, 255)
+        } else {
+            Color::from_rgba(100, 170, 255, 200)
+        };
+        draw_rectangle_lines(left, top, right - left, bottom - top, if is_selected { 2.0 } else { 1.0 }, outline);
+        draw_text(&camera.name, left + 3.0, top + 11.0, 11.0, outline);
+
+        let rail: Vec<(f32, f32)> = camera.rail_samples().iter()
+            .map(|p| {
+                let p = room.position + *p;
+                let (a, b) = world_pos_to_plane(p.x, p.y, p.z);
+                world_to_screen(a, b)
+            })
+            .collect();
+        for pair in rail.windows(2) {
+            draw_line(pair[0].0, pair[0].1, pair[1].0, pair[1].1, 1.0, outline);
+        }
+        for point in camera.shot_points() {
+            let p = room.position + *point;
+            let (a, b) = world_pos_to_plane(p.x, p.y, p.z);
+            let (px, py) = world_to_screen(a, b);
+            draw_rectangle(px - 3.0, py - 3.0, 6.0, 6.0, outline);
+        }
+    }
+
+    // Trigger tool: preview the box being dragged
+    if let Some((start_a, start_b)) = state.trigger_drag_start {
+        let (end_a, end_b) = screen_to_world(mouse_pos.0, mouse_pos.1);
+        let (rx, rz) = (room.position.x, room.position.z);
+        let preview = crate::world::TriggerVolume::from_corners("", (start_a - rx, start_b - rz), (end_a - rx, end_b - rz));
+        let (min, max) = preview.world_bounds(room.position);
+        let (sx0, sy0) = world_to_screen(min.x, min.z);
+        let (sx1, sy1) = world_to_screen(max.x, max.z);
+        let (left, top) = (sx0.min(sx1), sy0.min(sy1));
+        let (w, h) = ((sx1 - sx0).abs(), (sy1 - sy0).abs());
+        draw_rectangle(left, top, w, h, Color::from_rgba(255, 150, 100, 60));
+        draw_rectangle_lines(left, top, w, h, 1.0, Color::from_rgba(255, 200, 150, 255));
+        let size = preview.size();
+        draw_text(&format!("{:.0} x {:.0}", size.x, size.z), left + 3.0, top - 3.0, 11.0, Color::from_rgba(255, 200, 150, 255));
+    }
+
+    // Ladder tool: preview the box being dragged
+    if let Some((start_a, start_b)) = state.ladder_drag_start {
+        let (end_a, end_b) = screen_to_world(mouse_pos.0, mouse_pos.1);
+        let (rx, rz) = (room.position.x, room.position.z);
+        let preview = crate::world::Ladder::from_corners((start_a - rx, start_b - rz), (end_a - rx, end_b - rz), Default::default());
+        let (min, max) = preview.world_bounds(room.position);
+        let (sx0, sy0) = world_to_screen(min.x, min.z);
+        let (sx1, sy1) = world_to_screen(max.x, max.z);
+        let (left, top) = (sx0.min(sx1), sy0.min(sy1));
+        let (w, h) = ((sx1 - sx0).abs(), (sy1 - sy0).abs());
+        draw_rectangle(left, top, w, h, Color::from_rgba(120, 200, 80, 60));
+        draw_rectangle_lines(left, top, w, h, 1.0, Color::from_rgba(180, 240, 140, 255));
+    }
+
+    // Camera tool: preview the box being dragged
+    if let Some((start_a, start_b)) = state.camera_drag_start {
+        let (end_a, end_b) = screen_to_world(mouse_pos.0, mouse_pos.1);
+        let (rx, rz) = (room.position.x, room.position.z);
+        let preview = crate::world::CameraVolume::from_corners("", (start_a - rx, start_b - rz), (end_a - rx, end_b - rz));
+        let (min, max) = preview.world_bounds(room.position);
+        let (sx0, sy0) = world_to_screen(min.x, min.z);
+        let (sx1, sy1) = world_to_screen(max.x, max.z);
+        let (left, top) = (sx0.min(sx1), sy0.min(sy1));
+        let (w, h) = ((sx1 - sx0).abs(), (sy1 - sy0).abs());
+        draw_rectangle(left, top, w, h, Color::from_rgba(100, 170, 255, 60));
+        draw_rectangle_lines(left, top, w, h, 1.0, Color::from_rgba(170, 210, 255, 255));
+    }
+
+    // Waypoint drag offset in world space: quarter sectors across, clicks up and down
+    let snap_waypoint_offset = |da: f32, db: f32| -> Vec3 {
+        let (dx, dy, dz) = plane_to_world_offset(da, db);
+        Vec3::new(
+            crate::world::snap_waypoint_coord(dx),
+            (dy / CLICK_HEIGHT).round() * CLICK_HEIGHT,
+            crate::world::snap_waypoint_coord(dz),
+        )
+    };
+    let dragged_waypoint = state.path_drag.map(|(waypoint, (start_a, start_b))| {
+        let (wa, wb) = screen_to_world(mouse_pos.0, mouse_pos.1);
+        (waypoint, snap_waypoint_offset(wa - start_a, wb - start_b))
+    });
+
+    // Draw entity paths for current room (waypoints numbered in travel order)
+    let mut hovered_waypoint: Option<(usize, usize)> = None;
+    for (path_idx, path) in room.paths.iter().enumerate() {
+        let selected_point = match &state.selection {
+            Selection::Path { room: r, index, point } if *r == current_room_idx && *index == path_idx => Some(*point),
+            _ => None,
+        };
+        let color = if selected_point.is_some() {
+            Color::from_rgba(140, 220, 255, 255)
+        } else {
+            Color::from_rgba(80, 160, 220, 200)
+        };
+        let screen_points: Vec<(f32, f32)> = path.points.iter().enumerate()
+            .map(|(i, &p)| {
+                let mut world = room.position + p;
+                if let Some(((r, pi, wi), offset)) = dragged_waypoint {
+                    if r == current_room_idx && pi == path_idx && wi == i {
+                        world = world + offset;
+                    }
+                }
+                let (a, b) = world_pos_to_plane(world.x, world.y, world.z);
+                world_to_screen(a, b)
+            })
+            .collect();
+
+        for pair in screen_points.windows(2) {
+            draw_line(pair[0].0, pair[0].1, pair[1].0, pair[1].1, 1.5, color);
+        }
+        // Loops close back to the first waypoint
+        if path.mode == crate::world::PathMode::Loop && screen_points.len() > 2 {
+            let (first, last) = (screen_points[0], screen_points[screen_points.len() - 1]);
+            draw_line(last.0, last.1, first.0, first.1, 1.0, Color::new(color.r, color.g, color.b, 0.4));
+        }
+
+        for (i, &(sx, sy)) in screen_points.iter().enumerate() {
+            let dist_to_mouse = ((mouse_pos.0 - sx).powi(2) + (mouse_pos.1 - sy).powi(2)).sqrt();
+            if inside && dist_to_mouse < 8.0 && hovered_waypoint.is_none() {
+                hovered_waypoint = Some((path_idx, i));
+            }
+            let is_point_selected = selected_point == Some(Some(i));
+            let radius = if is_point_selected { 6.0 } else { 4.5 };
+            draw_circle(sx, sy, radius, Color::from_rgba(30, 60, 90, 255));
+            let outline = if is_point_selected || hovered_waypoint == Some((path_idx, i)) { WHITE } else { color };
+            draw_circle_lines(sx, sy, radius, 1.5, outline);
+            draw_text(&(i + 1).to_string(), sx + 6.0, sy - 4.0, 11.0, outline);
+        }
+        if let Some(&(sx, sy)) = screen_points.first() {
+            draw_text(&path.path_id, sx + 6.0, sy + 12.0, 11.0, color);
+        }
+    }
+
     // Draw level objects (spawns, lights, triggers, etc.) for current room and detect hover
     let mut hovered_object: Option<usize> = None;
     for (obj_idx, obj) in room.objects.iter().enumerate() {
@@ -1183,6 +1537,31 @@ pub fn draw_grid_view(ctx: &mut UiContext, rect: Rect, state: &mut EditorState)
                             state.set_selection(Selection::Object { room: current_room_idx, index: obj_idx });
                         }
                     }
+                    // Check if clicking on a path waypoint (select it and start dragging)
+                    else if let Some((path_idx, point_idx)) = hovered_waypoint {
+                        state.save_selection_undo();
+                        state.clear_multi_selection();
+                        state.set_selection(Selection::Path { room: current_room_idx, index: path_idx, point: Some(point_idx) });
+                        state.path_drag = Some(((current_room_idx, path_idx, point_idx), screen_to_world(mouse_pos.0, mouse_pos.1)));
+                    }
+                    // Check if clicking on a trigger volume's outline
+                    else if let Some(trig_idx) = hovered_trigger {
+                        state.save_selection_undo();
+                        state.clear_multi_selection();
+                        state.set_selection(Selection::Trigger { room: current_room_idx, index: trig_idx });
+                    }
+                    // Check if clicking on a ladder's outline
+                    else if let Some(ladder_idx) = hovered_ladder {
+                        state.save_selection_undo();
+                        state.clear_multi_selection();
+                        state.set_selection(Selection::Ladder { room: current_room_idx, index: ladder_idx });
+                    }
+                    // Check if clicking on a camera volume's outline
+                    else if let Some(camera_idx) = hovered_camera {
+                        state.save_selection_undo();
+                        state.clear_multi_selection();
+                        state.set_selection(Selection::Camera { room: current_room_idx, index: camera_idx });
+                    }
                     // Check if clicking on room origin
                     else if let Some(origin_room_idx) = hovered_room_origin {
                         // Start dragging room origin
@@ -1440,6 +1819,89 @@ pub fn draw_grid_view(ctx: &mut UiContext, rect: Rect, state: &mut EditorState)
                     }
                 }
 
+                EditorTool::PaintTexture => {
+                    if view_mode != GridViewMode::Top {
+                        state.set_status("Texture brush: switch to Top view", 2.0);
+                    } else {
+                        state.texture_brush.begin_stroke();
+                    }
+                }
+
+                EditorTool::DrawTrigger => {
+                    if view_mode != GridViewMode::Top {
+                        state.set_status("Trigger tool: switch to Top view", 2.0);
+                    } else {
+                        state.trigger_drag_start = Some(screen_to_world(mouse_pos.0, mouse_pos.1));
+                    }
+                }
+
+                EditorTool::DrawLadder => {
+                    if view_mode != GridViewMode::Top {
+                        state.set_status("Ladder tool: switch to Top view", 2.0);
+                    } else {
+                        state.ladder_drag_start = Some(screen_to_world(mouse_pos.0, mouse_pos.1));
+                    }
+                }
+
+                EditorTool::DrawCamera => {
+                    if view_mode != GridViewMode::Top {
+                        state.set_status("Camera tool: switch to Top view", 2.0);
+                    } else {
+                        state.camera_drag_start = Some(screen_to_world(mouse_pos.0, mouse_pos.1));
+                    }
+                }
+
+                EditorTool::DrawPath => {
+                    if let Some((path_idx, point_idx)) = hovered_waypoint {
+                        state.save_selection_undo();
+                        state.set_selection(Selection::Path { room: current_room_idx, index: path_idx, point: Some(point_idx) });
+                        state.path_drag = Some(((current_room_idx, path_idx, point_idx), screen_to_world(mouse_pos.0, mouse_pos.1)));
+                    } else if view_mode != GridViewMode::Top {
+                        state.set_status("Path tool: switch to Top view", 2.0);
+                    } else {
+                        // Waypoints sit on the floor of the sector they're placed in
+                        let (wx, wz) = screen_to_world(mouse_pos.0, mouse_pos.1);
+                        let (local_x, local_z) = (wx - room.position.x, wz - room.position.z);
+                        let floor_y = hovered_sector
+                            .and_then(|(gx, gz)| room.get_sector(gx, gz))
+                            .and_then(|sector| sector.floor.as_ref())
+                            .map_or(0.0, |floor| floor.avg_height() - room.position.y);
+                        let waypoint = Vec3::new(
+                            crate::world::snap_waypoint_coord(local_x),
+                            floor_y,
+                            crate::world::snap_waypoint_coord(local_z),
+                        );
+
+                        // Extend the selected path after its selected waypoint, else start a new one
+                        let extending = match state.selection {
+                            Selection::Path { room: r, index, point } if r == current_room_idx && index < room.paths.len() => {
+                                Some((index, point.map_or(room.paths[index].points.len(), |p| p + 1)))
+                            }
+                            _ => None,
+                        };
+                        state.save_undo();
+                        if let Some((index, insert_at)) = extending {
+                            if let Some(path) = state.level.rooms.get_mut(current_room_idx).and_then(|r| r.paths.get_mut(index)) {
+                                let insert_at = insert_at.min(path.points.len());
+                                path.points.insert(insert_at, waypoint);
+                                state.set_selection(Selection::Path { room: current_room_idx, index, point: Some(insert_at) });
+                            }
+                        } else {
+                            let path_id = crate::world::next_path_id(state.level.rooms.iter().flat_map(|r| r.paths.iter()));
+                            let mut path = crate::world::EntityPath::new(path_id.clone());
+                            path.points.push(waypoint);
+                            let added = state.level.rooms.get_mut(current_room_idx).map(|r| {
+                                r.paths.push(path);
+                                r.paths.len() - 1
+                            });
+                            if let Some(index) = added {
+                                state.set_selection(Selection::Path { room: current_room_idx, index, point: Some(0) });
+                                state.set_status(&format!("Added path '{}' (Esc to finish)", path_id), 2.0);
+                            }
+                        }
+                    }
+                }
+
                 EditorTool::PlaceObject => {
                     let (wx, wz) = screen_to_world(mouse_pos.0, mouse_pos.1);
                     let snapped_x = (wx / SECTOR_SIZE).floor() * SECTOR_SIZE;
@@ -1506,6 +1968,175 @@ pub fn draw_grid_view(ctx: &mut UiContext, rect: Rect, state: &mut EditorState)
         }
     }
 
+    // Texture brush: paint each sector under the cursor once per stroke (one undo step per drag)
+    if state.texture_brush.is_painting() {
+        if !ctx.mouse.left_down || view_mode != GridViewMode::Top {
+            state.texture_brush.end_stroke();
+        } else if let Some((gx, gz)) = hovered_sector {
+            if state.texture_brush.visit(current_room_idx, gx, gz) {
+                if let Some(mut sector) = room.get_sector(gx, gz).cloned() {
+                    let fallback = state.selected_texture.clone();
+                    if state.texture_brush.paint_sector(&mut sector, gx, gz, &fallback) {
+                        if state.texture_brush.take_undo() {
+                            state.save_undo();
+                        }
+                        if let Some(slot) = state.level.rooms.get_mut(current_room_idx).and_then(|r| r.get_sector_mut(gx, gz)) {
+                            *slot = sector;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Trigger tool: add the dragged box on release (cancelled if released outside)
+    if state.trigger_drag_start.is_some() && !ctx.mouse.left_down {
+        let (start_a, start_b) = state.trigger_drag_start.take().unwrap();
+        if inside {
+            let (end_a, end_b) = screen_to_world(mouse_pos.0, mouse_pos.1);
+            let (rx, rz) = (room.position.x, room.position.z);
+            let trigger_id = crate::world::next_trigger_id(state.level.rooms.iter().flat_map(|r| r.triggers.iter()));
+            let volume = crate::world::TriggerVolume::from_corners(trigger_id.clone(), (start_a - rx, start_b - rz), (end_a - rx, end_b - rz));
+            state.save_undo();
+            let added = state.level.rooms.get_mut(current_room_idx).map(|r| {
+                r.triggers.push(volume);
+                r.triggers.len() - 1
+            });
+            if let Some(index) = added {
+                state.set_selection(Selection::Trigger { room: current_room_idx, index });
+                state.set_status(&format!("Added trigger volume '{}'", trigger_id), 2.0);
+            }
+        }
+    }
+
+    // Ladder tool: add the dragged box on release, fixed to the side with the
+    // highest floor behind it (cancelled if released outside)
+    if state.ladder_drag_start.is_some() && !ctx.mouse.left_down {
+        let (start_a, start_b) = state.ladder_drag_start.take().unwrap();
+        if inside {
+            let (end_a, end_b) = screen_to_world(mouse_pos.0, mouse_pos.1);
+            let (rx, rz) = (room.position.x, room.position.z);
+            let mut ladder = crate::world::Ladder::from_corners((start_a - rx, start_b - rz), (end_a - rx, end_b - rz), Default::default());
+            ladder.facing = crate::world::guess_ladder_facing(&state.level, room.position, &ladder);
+            state.save_undo();
+            let added = state.level.rooms.get_mut(current_room_idx).map(|r| {
+                r.ladders.push(ladder);
+                r.ladders.len() - 1
+            });
+            if let Some(index) = added {
+                state.set_selection(Selection::Ladder { room: current_room_idx, index });
+                state.set_status("Added ladder", 2.0);
+            }
+        }
+    }
+
+    // Camera tool: add the dragged box on release (cancelled if released outside)
+    if state.camera_drag_start.is_some() && !ctx.mouse.left_down {
+        let (start_a, start_b) = state.camera_drag_start.take().unwrap();
+        if inside {
+            let (end_a, end_b) = screen_to_world(mouse_pos.0, mouse_pos.1);
+            let (rx, rz) = (room.position.x, room.position.z);
+            let name = crate::world::next_camera_name(state.level.rooms.iter().flat_map(|r| r.cameras.iter()));
+            let volume = crate::world::CameraVolume::from_corners(name.clone(), (start_a - rx, start_b - rz), (end_a - rx, end_b - rz));
+            state.save_undo();
+            let added = state.level.rooms.get_mut(current_room_idx).map(|r| {
+                r.cameras.push(volume);
+                r.cameras.len() - 1
+            });
+            if let Some(index) = added {
+                state.set_selection(Selection::Camera { room: current_room_idx, index });
+                state.set_status(&format!("Added camera volume '{}'", name), 2.0);
+            }
+        }
+    }
+
+    // Waypoint drag: move it on release (cancelled if released outside)
+    if let Some(((drag_room, path_idx, point_idx), offset)) = dragged_waypoint {
+        if !ctx.mouse.left_down {
+            state.path_drag = None;
+            if inside && offset.len() > 0.0 {
+                state.save_undo();
+                let waypoint = state.level.rooms.get_mut(drag_room)
+                    .and_then(|r| r.paths.get_mut(path_idx))
+                    .and_then(|p| p.points.get_mut(point_idx));
+                if let Some(waypoint) = waypoint {
+                    *waypoint = *waypoint + offset;
+                }
+            }
+        }
+    }
+
+    // Delete/Backspace on a selected path: its selected waypoint, else the whole path
+    if inside && (is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace)) {
+        if let Selection::Path { room: path_room, index, point } = state.selection.clone() {
+            let point_count = state.level.rooms.get(path_room).and_then(|r| r.paths.get(index)).map(|p| p.points.len());
+            if let Some(count) = point_count {
+                state.save_undo();
+                match point.filter(|&p| p < count && count > 1) {
+                    Some(p) => {
+                        state.level.rooms[path_room].paths[index].points.remove(p);
+                        let point = (p > 0).then(|| p - 1);
+                        state.set_selection(Selection::Path { room: path_room, index, point });
+                        state.set_status("Deleted waypoint", 2.0);
+                    }
+                    None => {
+                        state.level.rooms[path_room].paths.remove(index);
+                        state.set_selection(Selection::None);
+                        state.set_status("Deleted path", 2.0);
+                    }
+                }
+            }
+        }
+    }
+
+    // Path tool: Escape finishes the path (the next click starts a new one)
+    if inside && state.tool == EditorTool::DrawPath && is_key_pressed(KeyCode::Escape) {
+        if matches!(state.selection, Selection::Path { .. }) {
+            state.save_selection_undo();
+            state.set_selection(Selection::None);
+            state.set_status("Path finished", 1.0);
+        }
+    }
+
+    // Delete/Backspace on a selected trigger volume
+    if inside && (is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace)) {
+        if let Selection::Trigger { room: trig_room, index } = state.selection.clone() {
+            let removable = state.level.rooms.get(trig_room).is_some_and(|r| index < r.triggers.len());
+            if removable {
+                state.save_undo();
+                state.level.rooms[trig_room].triggers.remove(index);
+                state.set_selection(Selection::None);
+                state.set_status("Deleted trigger volume", 2.0);
+            }
+        }
+    }
+
+    // Delete/Backspace on a selected ladder
+    if inside && (is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace)) {
+        if let Selection::Ladder { room: ladder_room, index } = state.selection.clone() {
+            let removable = state.level.rooms.get(ladder_room).is_some_and(|r| index < r.ladders.len());
+            if removable {
+                state.save_undo();
+                state.level.rooms[ladder_room].ladders.remove(index);
+                state.set_selection(Selection::None);
+                state.set_status("Deleted ladder", 2.0);
+            }
+        }
+    }
+
+    // Delete/Backspace on a selected camera volume
+    if inside && (is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace)) {
+        if let Selection::Camera { room: camera_room, index } = state.selection.clone() {
+            let removable = state.level.rooms.get(camera_room).is_some_and(|r| index < r.cameras.len());
+            if removable {
+                state.save_undo();
+                state.level.rooms[camera_room].cameras.remove(index);
+                state.set_selection(Selection::None);
+                state.set_status("Deleted camera volume", 2.0);
+            }
+        }
+    }
+
     // Handle Delete/Backspace key for deletion in 2D view (objects and sectors)
     if inside && (is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace)) {
         // Collect all selections (primary + multi)
@@ -1600,7 +2231,7 @@ pub fn draw_grid_view(ctx: &mut UiContext, rect: Rect, state: &mut EditorState)
         }
     }
 
-    // Tool shortcuts: 1=Select, 2=Floor, 3=Wall, 4=Ceiling, 5=Object
+    // Tool shortcuts: 1=Select, 2=Floor, 3=Wall, 4=Ceiling, 5=Object, 6=Trigger, 7=Texture brush, 8=Path, 9=Ladder, 0=Camera
     if inside {
         if is_key_pressed(KeyCode::Key1) {
             state.tool = EditorTool::Select;
@@ -1612,6 +2243,16 @@ pub fn draw_grid_view(ctx: &mut UiContext, rect: Rect, state: &mut EditorState)
             state.tool = EditorTool::DrawCeiling;
         } else if is_key_pressed(KeyCode::Key5) {
             state.tool = EditorTool::PlaceObject;
+        } else if is_key_pressed(KeyCode::Key6) {
+            state.tool = EditorTool::DrawTrigger;
+        } else if is_key_pressed(KeyCode::Key7) {
+            state.tool = EditorTool::PaintTexture;
+        } else if is_key_pressed(KeyCode::Key8) {
+            state.tool = EditorTool::DrawPath;
+        } else if is_key_pressed(KeyCode::Key9) {
+            state.tool = EditorTool::DrawLadder;
+        } else if is_key_pressed(KeyCode::Key0) {
+            state.tool = EditorTool::DrawCamera;
         }
     }
 
@@ -2,11 +2,12 @@
 
 use macroquad::prelude::*;
 use crate::storage::Storage;
-use crate::ui::{Rect, UiContext, SplitPanel, draw_panel, panel_content_rect, draw_collapsible_panel, COLLAPSED_PANEL_HEIGHT, Toolbar, icon, draw_ps1_color_picker, ps1_color_picker_height, ActionRegistry};
-use crate::rasterizer::{Framebuffer, Texture as RasterTexture, Camera, Color as RasterColor, Vec3, RasterSettings, ShadingMode};
+use crate::ui::{Rect, UiContext, SplitPanel, DockLayout, DockEdge, PanelDock, draw_panel, panel_content_rect, COLLAPSED_PANEL_HEIGHT, Toolbar, icon, draw_ps1_color_picker, ps1_color_picker_height, ActionRegistry};
+use crate::rasterizer::{Framebuffer, Texture as RasterTexture, Camera, Color as RasterColor, Vec3, RasterSettings, ShadingMode, BlendMode, MAX_SNAP_SIZE};
 use crate::input::InputState;
-use super::{EditorState, EditorTool, Selection, SectorFace, GridViewMode, SECTOR_SIZE, FaceClipboard, GeometryClipboard, CopiedFace, CopiedFaceData};
-use crate::world::{UV_SCALE, Sector};
+use super::{EditorState, EditorTool, BrushSurface, Selection, SectorFace, GridViewMode, SECTOR_SIZE, FaceClipboard, GeometryClipboard, CopiedFace, CopiedFaceData, LevelDiffView};
+use crate::world::{UV_SCALE, Sector, BudgetPreset};
+use crate::game::analytics::HeatmapKind;
 use super::grid_view::draw_grid_view;
 use super::viewport_3d::draw_viewport_3d;
 use super::texture_palette::draw_texture_palette;
@@ -26,6 +27,10 @@ pub enum EditorAction {
     Export,         // Browser: download as file
     Import,         // Browser: upload file
     OpenLevelBrowser, // Open level browser
+    Share,          // Publish the level with a public link
+    SavePrefab,     // Save selection as a prefab (prompts for a name)
+    OpenPrefabBrowser, // Open prefab browser
+    OpenDungeonWizard, // Open procedural dungeon generator
     SwitchToModeler, // Switch to Asset Editor and create new asset
     Exit,           // Close/quit
 }
@@ -49,12 +54,28 @@ pub struct EditorLayout {
     pub left_split_3: SplitPanel,
     /// Right vertical split (texture palette | properties)
     pub right_panel_split: SplitPanel,
+    /// Bottom split (3D viewport | bottom dock)
+    pub bottom_split: SplitPanel,
+    /// Which edge each panel is docked to (or floating), collapsed state
+    pub docks: DockLayout,
     /// Action registry for keyboard shortcuts
     pub actions: ActionRegistry,
-    /// Collapsed state for left panels
-    pub left_collapsed: [bool; 4], // Skybox, 2D Grid, Room, Debug
 }
 
+/// Dockable editor panels: (key, title)
+const EDITOR_PANELS: [(&str, &str); 10] = [
+    ("skybox", "Skybox"),
+    ("grid", "2D Grid"),
+    ("rooms", "Rooms"),
+    ("debug", "Debug"),
+    ("scripts", "Scripts"),
+    ("cutscenes", "Cutscenes"),
+    ("hud", "HUD"),
+    ("packs", "Texture Packs"),
+    ("textures", "Textures"),
+    ("properties", "Properties"),
+];
+
 impl EditorLayout {
     pub fn new() -> Self {
         // Use high IDs (1000+) to avoid collision with toolbar button IDs
@@ -68,11 +89,44 @@ impl EditorLayout {
             left_split_2: SplitPanel::vertical(1004).with_ratio(0.45).with_min_size(50.0),
             left_split_3: SplitPanel::vertical(1005).with_ratio(0.65).with_min_size(50.0),
             right_panel_split: SplitPanel::vertical(1003).with_ratio(0.6).with_min_size(100.0),
+            bottom_split: SplitPanel::vertical(1006).with_ratio(0.7).with_min_size(100.0),
+            docks: DockLayout::new(1007, Self::trle_panels()),
             actions: create_editor_actions(),
-            left_collapsed: [false, false, false, true], // Debug collapsed by default
         }
     }
 
+    /// Default arrangement: tool panels on the left, textures and properties on the right
+    pub fn trle_panels() -> Vec<PanelDock> {
+        vec![
+            PanelDock::new("skybox", DockEdge::Left),
+            PanelDock::new("grid", DockEdge::Left),
+            PanelDock::new("rooms", DockEdge::Left),
+            PanelDock::new("debug", DockEdge::Left).collapsed(),
+            PanelDock::new("scripts", DockEdge::Left).collapsed(),
+            PanelDock::new("cutscenes", DockEdge::Left).collapsed(),
+            PanelDock::new("hud", DockEdge::Left).collapsed(),
+            PanelDock::new("packs", DockEdge::Right).collapsed(),
+            PanelDock::new("textures", DockEdge::Right),
+            PanelDock::new("properties", DockEdge::Right),
+        ]
+    }
+
+    /// Blender-like arrangement: grid and rooms left, properties right, browsers at the bottom
+    pub fn blender_panels() -> Vec<PanelDock> {
+        vec![
+            PanelDock::new("grid", DockEdge::Left),
+            PanelDock::new("rooms", DockEdge::Left),
+            PanelDock::new("properties", DockEdge::Right),
+            PanelDock::new("skybox", DockEdge::Right).collapsed(),
+            PanelDock::new("textures", DockEdge::Bottom),
+            PanelDock::new("packs", DockEdge::Bottom).collapsed(),
+            PanelDock::new("scripts", DockEdge::Bottom).collapsed(),
+            PanelDock::new("cutscenes", DockEdge::Bottom).collapsed(),
+            PanelDock::new("hud", DockEdge::Bottom).collapsed(),
+            PanelDock::new("debug", DockEdge::Bottom).collapsed(),
+        ]
+    }
+
     /// Apply layout config from a level (panel splits only)
     pub fn apply_config(&mut self, config: &crate::world::EditorLayoutConfig) {
         self.main_split.ratio = config.main_split;
@@ -80,6 +134,12 @@ impl EditorLayout {
         // left_split from old config maps to left_split_2 (2D Grid | Room+Debug)
         self.left_split_2.ratio = config.left_split;
         self.right_panel_split.ratio = config.right_panel_split;
+        self.bottom_split.ratio = config.bottom_split;
+        if config.panels.is_empty() {
+            self.docks.reset();
+        } else {
+            self.docks.restore(&config.panels);
+        }
     }
 
     /// Extract current layout as a config (for saving with level)
@@ -108,8 +168,179 @@ impl EditorLayout {
             orbit_distance,
             orbit_azimuth,
             orbit_elevation,
+            bottom_split: self.bottom_split.ratio,
+            panels: self.docks.panels.clone(),
+        }
+    }
+}
+
+/// Panels with a text editor that were drawn expanded this frame (the
+/// others drop text focus)
+#[derive(Default)]
+struct TextPanelsDrawn {
+    scripts: bool,
+    cutscenes: bool,
+}
+
+/// Draw one dockable panel (header + content) by its key
+#[allow(clippy::too_many_arguments)]
+fn draw_dock_panel(
+    ctx: &mut UiContext,
+    layout: &mut EditorLayout,
+    index: usize,
+    rect: Rect,
+    state: &mut EditorState,
+    icon_font: Option<&Font>,
+    storage: &Storage,
+    text_panels: &mut TextPanelsDrawn,
+) {
+    let key = layout.docks.panels[index].key.clone();
+    let title = EDITOR_PANELS.iter().find(|(k, _)| *k == key).map_or(key.as_str(), |(_, t)| *t);
+    if key == "textures" && ctx.mouse.clicked(&rect.slice_top(COLLAPSED_PANEL_HEIGHT)) {
+        state.active_panel = super::state::ActivePanel::TexturePalette;
+    }
+    let Some(content) = layout.docks.draw_header(ctx, index, rect, title, icon_font) else {
+        return;
+    };
+    match key.as_str() {
+        "skybox" => draw_skybox_panel(ctx, content, state),
+        "grid" => draw_grid_panel(ctx, content, state, icon_font, storage),
+        "rooms" => draw_room_properties(ctx, content, state, icon_font),
+        "debug" => draw_debug_panel(ctx, content, state),
+        "scripts" => {
+            draw_scripts_panel(ctx, content, state, icon_font);
+            text_panels.scripts = true;
+        }
+        "cutscenes" => {
+            draw_cutscenes_panel(ctx, content, state, icon_font);
+            text_panels.cutscenes = true;
+        }
+        "hud" => draw_hud_panel(ctx, content, state),
+        "packs" => draw_texture_packs_panel(ctx, content, state),
+        "textures" => draw_texture_palette(ctx, content, state, icon_font, storage),
+        "properties" => draw_properties(ctx, content, state, icon_font),
+        _ => {}
+    }
+}
+
+/// Layout preset buttons in the 3D viewport title bar
+fn draw_layout_presets(ctx: &mut UiContext, rect: Rect, layout: &mut EditorLayout, state: &mut EditorState) {
+    let mut toolbar = Toolbar::new(rect);
+    if toolbar.letter_button_active(ctx, 'T', "TRLE layout: tools left, textures and properties right", false) {
+        layout.docks.restore(&EditorLayout::trle_panels());
+        state.set_status("Layout: TRLE", 2.0);
+    }
+    if toolbar.letter_button_active(ctx, 'B', "Blender layout: properties right, textures and scripts at the bottom", false) {
+        layout.docks.restore(&EditorLayout::blender_panels());
+        state.set_status("Layout: Blender", 2.0);
+    }
+}
+
+/// 2D grid panel: view mode / playtest / diff toolbar above the grid view
+fn draw_grid_panel(ctx: &mut UiContext, content: Rect, state: &mut EditorState, icon_font: Option<&Font>, storage: &Storage) {
+    // Add view mode toolbar inside the 2D grid panel
+    let view_toolbar_height = 22.0;
+    let view_toolbar_rect = Rect::new(content.x, content.y, content.w, view_toolbar_height);
+    let grid_view_rect = Rect::new(content.x, content.y + view_toolbar_height, content.w, content.h - view_toolbar_height);
+
+    // Draw view mode toolbar
+    draw_rectangle(view_toolbar_rect.x, view_toolbar_rect.y, view_toolbar_rect.w, view_toolbar_rect.h, Color::from_rgba(45, 45, 50, 255));
+    let mut view_toolbar = Toolbar::new(view_toolbar_rect);
+
+    if view_toolbar.letter_button_active(ctx, 'T', "Top view (X-Z)", state.grid_view_mode == GridViewMode::Top) {
+        state.grid_view_mode = GridViewMode::Top;
+    }
+    if view_toolbar.letter_button_active(ctx, 'F', "Front view (X-Y)", state.grid_view_mode == GridViewMode::Front) {
+        state.grid_view_mode = GridViewMode::Front;
+    }
+    if view_toolbar.letter_button_active(ctx, 'S', "Side view (Y-Z)", state.grid_view_mode == GridViewMode::Side) {
+        state.grid_view_mode = GridViewMode::Side;
+    }
+
+    // Playtest analytics: opt-in recording and heatmap overlay
+    view_toolbar.separator();
+    let overlay = state.playtest.overlay;
+    if view_toolbar.letter_button_active(ctx, 'H', "Playtest heatmap (cycle: time, deaths, damage)", overlay != HeatmapKind::Off) {
+        state.playtest.overlay = overlay.next();
+        state.set_status(&format!("Playtest heatmap: {}", state.playtest.overlay.label()), 2.0);
+    }
+    let recording = state.playtest.recording;
+    let record_tip = if recording { "Stop recording playtests" } else { "Record playtests (local only)" };
+    if view_toolbar.icon_button_active(ctx, icon::CIRCLE_DOT, icon_font, record_tip, recording) {
+        state.playtest.recording = !recording;
+        let msg = if recording { "Playtest recording off" } else { "Playtest recording on: Game tab sessions are kept locally" };
+        state.set_status(msg, 3.0);
+    }
+    if state.playtest.sessions.is_empty() {
+        view_toolbar.icon_button_disabled(ctx, icon::TRASH, icon_font, "Clear playtest sessions");
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            view_toolbar.icon_button_disabled(ctx, icon::DOWNLOAD, icon_font, "Export playtest sessions");
+        }
+    } else {
+        if view_toolbar.icon_button(ctx, icon::TRASH, icon_font, "Clear playtest sessions") {
+            state.playtest.clear();
+            state.set_status("Playtest sessions cleared", 2.0);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if view_toolbar.icon_button(ctx, icon::DOWNLOAD, icon_font, "Export playtest sessions") {
+                let name = state.current_file.as_ref()
+                    .and_then(|p| p.file_stem())
+                    .map(|s| format!("{}_playtest", s.to_string_lossy()))
+                    .unwrap_or_else(|| "playtest".to_string());
+                match state.playtest.export(&name) {
+                    Ok(path) => state.set_status(&format!("Exported playtest sessions to {}", path.display()), 3.0),
+                    Err(e) => state.set_status(&format!("Export failed: {}", e), 3.0),
+                }
+            }
+        }
+    }
+
+    // Level diff: compare against the last save (or another level file)
+    view_toolbar.separator();
+    if view_toolbar.letter_button_active(ctx, 'D', "Diff against last saved file", state.level_diff.is_some()) {
+        if state.level_diff.take().is_some() {
+            state.set_status("Level diff off", 2.0);
+        } else if let Some(path) = state.current_file.clone() {
+            match LevelDiffView::from_storage(&path.to_string_lossy(), storage, &state.level, get_time()) {
+                Ok(view) => {
+                    state.set_status(&format!("Diff vs last save: {}", view.diff.summary()), 3.0);
+                    state.level_diff = Some(view);
+                }
+                Err(e) => state.set_status(&format!("Diff failed: {}", e), 3.0),
+            }
+        } else {
+            state.set_status("Save the level first to diff against it", 3.0);
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if view_toolbar.icon_button(ctx, icon::FOLDER_OPEN, icon_font, "Compare with level file...") {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Levels", &["ron"])
+                .pick_file()
+            {
+                match LevelDiffView::from_file(&path, &state.level, get_time()) {
+                    Ok(view) => {
+                        state.set_status(&format!("Diff vs {}: {}", view.base_name, view.diff.summary()), 3.0);
+                        state.level_diff = Some(view);
+                    }
+                    Err(e) => state.set_status(&format!("Diff failed: {}", e), 3.0),
+                }
+            }
         }
     }
+    if let Some(view) = state.level_diff.as_mut() {
+        view.refresh(&state.level, get_time());
+    }
+
+    // Center 2D view on current room button (right-aligned)
+    if view_toolbar.icon_button_right(ctx, icon::SQUARE_SQUARE, icon_font, "Center 2D view on current room") {
+        state.center_2d_on_current_room();
+    }
+
+    draw_grid_view(ctx, grid_view_rect, state);
 }
 
 /// Result from drawing a player property field
@@ -227,6 +458,89 @@ fn draw_player_prop_field(
     PlayerPropResult { new_y: y + line_height, new_value }
 }
 
+/// Draw a click-to-edit text field for trigger volume properties.
+/// Returns the new text when an edit is confirmed (Enter or click outside).
+fn draw_trigger_text_field(
+    ctx: &mut UiContext,
+    rect: Rect,
+    value: &str,
+    field_id: usize,
+    editing: &mut Option<(usize, crate::ui::TextInputState)>,
+) -> Option<String> {
+    let hovered = rect.contains(ctx.mouse.x, ctx.mouse.y);
+
+    if let Some((_, input)) = editing.as_mut().filter(|(id, _)| *id == field_id) {
+        crate::ui::draw_text_input(rect, input, 12.0);
+        let confirmed = is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter)
+            || (ctx.mouse.left_pressed && !hovered);
+        if confirmed {
+            let text = input.text.trim().to_string();
+            *editing = None;
+            return Some(text);
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            *editing = None;
+        }
+        return None;
+    }
+
+    let bg_color = if hovered { Color::from_rgba(55, 55, 65, 255) } else { Color::from_rgba(45, 45, 55, 255) };
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, bg_color);
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, Color::from_rgba(60, 60, 65, 255));
+    let (text, color) = if value.is_empty() {
+        ("(none)", Color::from_rgba(110, 110, 120, 255))
+    } else {
+        (value, Color::from_rgba(220, 220, 230, 255))
+    };
+    draw_text(text, rect.x + 4.0, (rect.y + 13.0).floor(), 12.0, color);
+
+    if hovered && ctx.mouse.left_pressed {
+        *editing = Some((field_id, crate::ui::TextInputState::new(value)));
+    }
+    None
+}
+
+/// Names a door can link to: key pickups (instance names, as play mode sees
+/// them) and trigger ids (Trigger components and trigger volumes)
+fn door_link_candidates(level: &crate::world::Level, library: &crate::asset::AssetLibrary) -> (Vec<String>, Vec<String>) {
+    use crate::asset::AssetComponent;
+    let mut keys = Vec::new();
+    let mut triggers = Vec::new();
+    for room in &level.rooms {
+        for obj in &room.objects {
+            let Some(asset) = library.get_by_id(obj.asset_id) else {
+                continue;
+            };
+            for component in &asset.components {
+                match component {
+                    AssetComponent::Pickup { item_type: crate::game::components::ItemType::Key(_), .. } => {
+                        keys.push(if obj.name.is_empty() { asset.name.clone() } else { obj.name.clone() });
+                    }
+                    AssetComponent::Trigger { trigger_id, .. } => triggers.push(trigger_id.clone()),
+                    _ => {}
+                }
+            }
+        }
+        triggers.extend(room.triggers.iter().map(|t| t.trigger_id.clone()));
+    }
+    for names in [&mut keys, &mut triggers] {
+        names.retain(|n| !n.is_empty());
+        names.sort();
+        names.dedup();
+    }
+    (keys, triggers)
+}
+
+/// Next link after `current` in (none, names...), wrapping back to none
+fn cycle_link_name(names: &[String], current: Option<&str>) -> Option<String> {
+    match current.and_then(|c| names.iter().position(|n| n == c)) {
+        Some(i) => names.get(i + 1).cloned(),
+        None if current.is_none() => names.first().cloned(),
+        // Name not placed in this level: back to none
+        None => None,
+    }
+}
+
 /// Draw the complete editor UI, returns action if triggered
 pub fn draw_editor(
     ctx: &mut UiContext,
@@ -259,102 +573,40 @@ pub fn draw_editor(
     let mut action = draw_unified_toolbar(ctx, toolbar_rect, state, icon_font, &layout.actions);
     let toolbar_ms = EditorFrameTimings::elapsed_ms(toolbar_start);
 
-    // Main split: left panels | rest
-    let (left_rect, rest_rect) = layout.main_split.update(ctx, panels_rect);
-
-    // Right split: center viewport | right panels
-    let (center_rect, right_rect) = layout.right_split.update(ctx, rest_rect);
-
-    // Right panel: collapsible sections for Textures and Properties
-    // (Old split panel layout replaced with collapsible sections)
-
-    // === LEFT PANEL ===
-    let left_start = EditorFrameTimings::start();
-
-    // Left sidebar: 4 collapsible panels (Skybox, 2D Grid, Room, Debug)
-    let panel_bg = Color::from_rgba(35, 35, 40, 255);
-    let header_h = COLLAPSED_PANEL_HEIGHT;
-
-    // Count collapsed panels and calculate available height for expanded ones
-    let num_collapsed = layout.left_collapsed.iter().filter(|&&c| c).count();
-    let collapsed_height = num_collapsed as f32 * header_h;
-    let available_height = (left_rect.h - collapsed_height).max(0.0);
+    // Floating panels are drawn last, on top: block clicks to whatever is under them
+    let real_mouse = ctx.mouse;
+    let over_floating = !ctx.is_modal_active()
+        && layout.docks.floating_contains(panels_rect, ctx.mouse.x, ctx.mouse.y);
+    if over_floating {
+        ctx.begin_modal();
+    }
 
-    // Calculate heights for expanded panels (equal distribution)
-    let num_expanded = 4 - num_collapsed;
-    let expanded_panel_height = if num_expanded > 0 {
-        available_height / num_expanded as f32
+    // Docked panels: left column | center (3D viewport over the bottom strip) | right column.
+    // Empty edges give their space to the viewport.
+    let docks_used = |edge| layout.docks.is_edge_used(edge);
+    let (left_used, right_used, bottom_used) = (docks_used(DockEdge::Left), docks_used(DockEdge::Right), docks_used(DockEdge::Bottom));
+    let (left_rect, rest_rect) = if left_used {
+        layout.main_split.update(ctx, panels_rect)
     } else {
-        0.0
+        (Rect::new(panels_rect.x, panels_rect.y, 0.0, panels_rect.h), panels_rect)
+    };
+    let (center_rect, right_rect) = if right_used {
+        layout.right_split.update(ctx, rest_rect)
+    } else {
+        (rest_rect, Rect::new(rest_rect.right(), rest_rect.y, 0.0, rest_rect.h))
+    };
+    let (center_rect, bottom_rect) = if bottom_used {
+        layout.bottom_split.update(ctx, center_rect)
+    } else {
+        (center_rect, Rect::new(center_rect.x, center_rect.bottom(), center_rect.w, 0.0))
     };
 
-    // Calculate panel rects and draw them
-    let mut y = left_rect.y;
-    let panel_names = ["Skybox", "2D Grid", "Rooms", "Debug"];
-
-    // Panel 0: Skybox
-    let skybox_h = if layout.left_collapsed[0] { header_h } else { expanded_panel_height };
-    let skybox_rect = Rect::new(left_rect.x, y, left_rect.w, skybox_h);
-    let (clicked, skybox_content) = draw_collapsible_panel(ctx, skybox_rect, panel_names[0], layout.left_collapsed[0], panel_bg);
-    if clicked { layout.left_collapsed[0] = !layout.left_collapsed[0]; }
-    if let Some(content) = skybox_content {
-        draw_skybox_panel(ctx, content, state);
-    }
-    y += skybox_h;
-
-    // Panel 1: 2D Grid
-    let grid_h = if layout.left_collapsed[1] { header_h } else { expanded_panel_height };
-    let grid_rect = Rect::new(left_rect.x, y, left_rect.w, grid_h);
-    let (clicked, grid_content) = draw_collapsible_panel(ctx, grid_rect, panel_names[1], layout.left_collapsed[1], panel_bg);
-    if clicked { layout.left_collapsed[1] = !layout.left_collapsed[1]; }
-    if let Some(content) = grid_content {
-        // Add view mode toolbar inside the 2D grid panel
-        let view_toolbar_height = 22.0;
-        let view_toolbar_rect = Rect::new(content.x, content.y, content.w, view_toolbar_height);
-        let grid_view_rect = Rect::new(content.x, content.y + view_toolbar_height, content.w, content.h - view_toolbar_height);
-
-        // Draw view mode toolbar
-        draw_rectangle(view_toolbar_rect.x, view_toolbar_rect.y, view_toolbar_rect.w, view_toolbar_rect.h, Color::from_rgba(45, 45, 50, 255));
-        let mut view_toolbar = Toolbar::new(view_toolbar_rect);
-
-        if view_toolbar.letter_button_active(ctx, 'T', "Top view (X-Z)", state.grid_view_mode == GridViewMode::Top) {
-            state.grid_view_mode = GridViewMode::Top;
-        }
-        if view_toolbar.letter_button_active(ctx, 'F', "Front view (X-Y)", state.grid_view_mode == GridViewMode::Front) {
-            state.grid_view_mode = GridViewMode::Front;
-        }
-        if view_toolbar.letter_button_active(ctx, 'S', "Side view (Y-Z)", state.grid_view_mode == GridViewMode::Side) {
-            state.grid_view_mode = GridViewMode::Side;
-        }
-
-        // Center 2D view on current room button (right-aligned)
-        if view_toolbar.icon_button_right(ctx, icon::SQUARE_SQUARE, icon_font, "Center 2D view on current room") {
-            state.center_2d_on_current_room();
-        }
-
-        draw_grid_view(ctx, grid_view_rect, state);
-    }
-    y += grid_h;
-
-    // Panel 2: Rooms
-    let room_h = if layout.left_collapsed[2] { header_h } else { expanded_panel_height };
-    let room_rect = Rect::new(left_rect.x, y, left_rect.w, room_h);
-    let (clicked, room_content) = draw_collapsible_panel(ctx, room_rect, panel_names[2], layout.left_collapsed[2], panel_bg);
-    if clicked { layout.left_collapsed[2] = !layout.left_collapsed[2]; }
-    if let Some(content) = room_content {
-        draw_room_properties(ctx, content, state, icon_font);
-    }
-    y += room_h;
-
-    // Panel 3: Debug
-    let debug_h = if layout.left_collapsed[3] { header_h } else { expanded_panel_height };
-    let debug_rect = Rect::new(left_rect.x, y, left_rect.w, debug_h);
-    let (clicked, debug_content) = draw_collapsible_panel(ctx, debug_rect, panel_names[3], layout.left_collapsed[3], panel_bg);
-    if clicked { layout.left_collapsed[3] = !layout.left_collapsed[3]; }
-    if let Some(content) = debug_content {
-        draw_debug_panel(ctx, content, state);
+    // === LEFT PANEL ===
+    let left_start = EditorFrameTimings::start();
+    let mut text_panels = TextPanelsDrawn::default();
+    for (index, rect) in layout.docks.stack(DockEdge::Left, left_rect) {
+        draw_dock_panel(ctx, layout, index, rect, state, icon_font, storage, &mut text_panels);
     }
-
     let left_panel_ms = EditorFrameTimings::elapsed_ms(left_start);
 
     // === 3D VIEWPORT ===
@@ -370,51 +622,30 @@ pub fn draw_editor(
         WHITE
     };
     draw_text("3D Viewport", center_rect.x + 5.0, center_rect.y + 14.0, 16.0, title_color);
+    draw_layout_presets(ctx, Rect::new(center_rect.right() - 44.0, center_rect.y, 44.0, title_height), layout, state);
     draw_viewport_3d(ctx, panel_content_rect(center_rect, true), state, textures, fb, input, icon_font);
     let viewport_3d_ms = EditorFrameTimings::elapsed_ms(viewport_start);
 
-    // === RIGHT PANEL (Collapsible Sections) ===
+    // === RIGHT, BOTTOM AND FLOATING PANELS ===
     let right_start = EditorFrameTimings::start();
-    let panel_bg = Color::from_rgba(35, 35, 40, 255);
-    let header_h = COLLAPSED_PANEL_HEIGHT;
-
-    // Count collapsed panels and calculate available height for expanded ones
-    let textures_collapsed = !state.textures_section_expanded;
-    let properties_collapsed = !state.properties_section_expanded;
-    let num_collapsed = [textures_collapsed, properties_collapsed].iter().filter(|&&c| c).count();
-    let collapsed_height = num_collapsed as f32 * header_h;
-    let available_height = (right_rect.h - collapsed_height).max(0.0);
-
-    // Calculate heights for expanded panels (equal distribution)
-    let num_expanded = 2 - num_collapsed;
-    let expanded_panel_height = if num_expanded > 0 {
-        available_height / num_expanded as f32
-    } else {
-        0.0
-    };
-
-    // Panel 0: Textures
-    let mut y = right_rect.y;
-    let textures_h = if textures_collapsed { header_h } else { expanded_panel_height };
-    let texture_rect = Rect::new(right_rect.x, y, right_rect.w, textures_h);
-    let (clicked, textures_content) = draw_collapsible_panel(ctx, texture_rect, "Textures", textures_collapsed, panel_bg);
-    if clicked {
-        state.textures_section_expanded = !state.textures_section_expanded;
-        // Also set focus when clicking on the header
-        state.active_panel = super::state::ActivePanel::TexturePalette;
+    let mut docked = layout.docks.stack(DockEdge::Right, right_rect);
+    docked.extend(layout.docks.stack(DockEdge::Bottom, bottom_rect));
+    for (index, rect) in docked {
+        draw_dock_panel(ctx, layout, index, rect, state, icon_font, storage, &mut text_panels);
     }
-    if let Some(content) = textures_content {
-        draw_texture_palette(ctx, content, state, icon_font, storage);
+    if over_floating {
+        ctx.end_modal(real_mouse);
     }
-    y += textures_h;
-
-    // Panel 1: Properties
-    let props_h = if properties_collapsed { header_h } else { expanded_panel_height };
-    let props_rect = Rect::new(right_rect.x, y, right_rect.w, props_h);
-    let (clicked, props_content) = draw_collapsible_panel(ctx, props_rect, "Properties", properties_collapsed, panel_bg);
-    if clicked { state.properties_section_expanded = !state.properties_section_expanded; }
-    if let Some(content) = props_content {
-        draw_properties(ctx, content, state, icon_font);
+    for index in layout.docks.on_edge(DockEdge::Floating) {
+        let rect = layout.docks.float_rect(index, panels_rect);
+        draw_dock_panel(ctx, layout, index, rect, state, icon_font, storage, &mut text_panels);
+    }
+    layout.docks.finish(ctx, panels_rect);
+    if !text_panels.scripts {
+        state.script_input.focused = false;
+    }
+    if !text_panels.cutscenes {
+        state.cutscene_input.focused = false;
     }
 
     let right_panel_ms = EditorFrameTimings::elapsed_ms(right_start);
@@ -537,7 +768,11 @@ pub fn draw_editor(
             // Close browser (DeleteAsset/RenameAsset don't apply to World Editor)
             state.asset_browser.close();
         }
-        AssetBrowserAction::None => {}
+        // History and usages are Asset Editor features
+        AssetBrowserAction::FindUsages
+        | AssetBrowserAction::SelectSnapshot(_)
+        | AssetBrowserAction::RestoreSnapshot
+        | AssetBrowserAction::None => {}
     }
 
     action
@@ -594,6 +829,15 @@ fn draw_unified_toolbar(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
         action = EditorAction::OpenLevelBrowser;
     }
 
+    // Share link (uploads to cloud, so only when signed in)
+    if crate::auth::is_authenticated() {
+        if toolbar.icon_button(ctx, icon::LINK, icon_font, "Share (copy a public link)") {
+            action = EditorAction::Share;
+        }
+    } else {
+        toolbar.icon_button_disabled(ctx, icon::LINK, icon_font, "Sign in to share levels");
+    }
+
     toolbar.separator();
 
     // Edit operations
@@ -606,6 +850,22 @@ fn draw_unified_toolbar(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
 
     toolbar.separator();
 
+    // Prefabs (native only - stored as files in assets/userdata/prefabs)
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if toolbar.icon_button(ctx, icon::SQUARE_SQUARE, icon_font, "Save Selection as Prefab") {
+            action = EditorAction::SavePrefab;
+        }
+        if toolbar.icon_button(ctx, icon::BOX, icon_font, "Browse Prefabs") {
+            action = EditorAction::OpenPrefabBrowser;
+        }
+        toolbar.separator();
+    }
+
+    if toolbar.icon_button(ctx, icon::GIT_BRANCH, icon_font, "Generate Dungeon Blockout") {
+        action = EditorAction::OpenDungeonWizard;
+    }
+
     // Tool buttons (Portal removed - portals are now auto-generated)
     // Wall tool handles all 6 directions (N, E, S, W, NW-SE, NE-SW) - use R to rotate
     let tools = [
@@ -614,6 +874,11 @@ fn draw_unified_toolbar(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
         (icon::BRICK_WALL, "Wall", EditorTool::DrawWall),
         (icon::LAYERS, "Ceiling", EditorTool::DrawCeiling),
         (icon::MAP_PIN, "Object", EditorTool::PlaceObject),
+        (icon::SCAN, "Trigger Volume", EditorTool::DrawTrigger),
+        (icon::ARROW_DOWN_UP, "Ladder", EditorTool::DrawLadder),
+        (icon::CCTV, "Camera Volume", EditorTool::DrawCamera),
+        (icon::BRUSH, "Texture Brush", EditorTool::PaintTexture),
+        (icon::FOOTPRINTS, "Path", EditorTool::DrawPath),
     ];
 
     for (icon_char, tooltip, tool) in tools {
@@ -624,6 +889,21 @@ fn draw_unified_toolbar(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
             if tool == EditorTool::DrawWall {
                 state.set_status(&format!("Wall direction: {} (R to rotate, F for gap)", state.wall_direction.name()), 2.0);
             }
+            if tool == EditorTool::DrawTrigger {
+                state.set_status("Drag a box in the 2D top view to add a trigger volume", 2.0);
+            }
+            if tool == EditorTool::DrawLadder {
+                state.set_status("Drag a box in the 2D top view to add a ladder", 2.0);
+            }
+            if tool == EditorTool::DrawCamera {
+                state.set_status("Drag a box in the 2D top view to add a camera volume", 2.0);
+            }
+            if tool == EditorTool::PaintTexture {
+                state.set_status("Drag across sectors in the 2D top view to paint textures", 2.0);
+            }
+            if tool == EditorTool::DrawPath {
+                state.set_status("Click in the 2D top view to place path waypoints", 2.0);
+            }
         }
     }
 
@@ -739,6 +1019,31 @@ fn draw_unified_toolbar(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
         state.set_status(&format!("Room boundaries: {}", mode), 2.0);
     }
 
+    // Portal debug toggle
+    let portal_tooltip = if state.portal_debug { "Portal Debug: ON" } else { "Portal Debug: OFF" };
+    if toolbar.icon_button_active(ctx, icon::DOOR_CLOSED, icon_font, portal_tooltip, state.portal_debug) {
+        state.portal_debug = !state.portal_debug;
+        if state.portal_debug {
+            let count = state.check_portals();
+            let msg = match count {
+                0 => "Portal debug: all rooms connect cleanly".to_string(),
+                1 => "Portal debug: 1 connection issue".to_string(),
+                n => format!("Portal debug: {} connection issues", n),
+            };
+            state.set_status(&msg, 3.0);
+        } else {
+            state.set_status("Portal debug: hidden", 2.0);
+        }
+    }
+
+    // Player capsule preview toggle
+    let capsule_tooltip = if state.show_player_capsule { "Player Capsule: ON" } else { "Player Capsule: OFF" };
+    if toolbar.icon_button_active(ctx, icon::PERSON_STANDING, icon_font, capsule_tooltip, state.show_player_capsule) {
+        state.show_player_capsule = !state.show_player_capsule;
+        let mode = if state.show_player_capsule { "shown at cursor" } else { "hidden" };
+        state.set_status(&format!("Player capsule: {}", mode), 2.0);
+    }
+
     // Wireframe toggle
     let wireframe_tooltip = if state.raster_settings.wireframe_overlay { "Wireframe: ON" } else { "Wireframe: OFF" };
     if toolbar.icon_button_active(ctx, icon::GRID, icon_font, wireframe_tooltip, state.raster_settings.wireframe_overlay) {
@@ -799,9 +1104,27 @@ fn draw_unified_toolbar(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
         let mode = if state.raster_settings.shading != ShadingMode::None { "ON" } else { "OFF" };
         state.set_status(&format!("Shading: {}", mode), 2.0);
     }
+    // Light baking (TR1-style vertex lighting): bake, or Shift+click to clear
+    if toolbar.text_button(ctx, "Bake", "Bake Lighting (Shift+click: clear baked lighting)") {
+        state.save_undo();
+        if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) {
+            for room in &mut state.level.rooms {
+                room.clear_baked_lighting();
+            }
+            state.set_status("Cleared baked lighting", 2.0);
+        } else {
+            let rooms = crate::scene::bake_rooms_lighting(&mut state.level.rooms, &state.asset_library, false);
+            state.set_status(&format!("Baked lighting for {} room(s) (rebaked on save)", rooms), 3.0);
+        }
+    }
+    if toolbar.letter_button_active(ctx, 'B', "Preview Baked Lighting (OFF = dynamic)", state.preview_baked_lighting) {
+        state.preview_baked_lighting = !state.preview_baked_lighting;
+        let mode = if state.preview_baked_lighting { "Baked" } else { "Dynamic" };
+        state.set_status(&format!("Lighting preview: {}", mode), 2.0);
+    }
     if toolbar.icon_button_active(ctx, icon::MONITOR, icon_font, "Low Resolution (PS1 320x240)", state.raster_settings.low_resolution) {
         state.raster_settings.low_resolution = !state.raster_settings.low_resolution;
-        let mode = if state.raster_settings.low_resolution { "320x240" } else { "High-res" };
+        let mode = if state.raster_settings.low_resolution { "320x240" } else { "640x480" };
         state.set_status(&format!("Resolution: {}", mode), 2.0);
     }
     if toolbar.icon_button_active(ctx, icon::BLEND, icon_font, "Dithering (PS1 color banding)", state.raster_settings.dithering) {
@@ -869,7 +1192,7 @@ fn draw_unified_toolbar(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
         has_selection,
         state.clipboard.is_some() || state.face_clipboard.is_some(),
         selection_flags,
-        false, // text_editing
+        state.script_input.focused || state.cutscene_input.focused || state.trigger_field_edit.is_some() || state.texture_tag_filter.is_typing(), // text_editing
         state.dirty,
     );
 
@@ -1309,23 +1632,28 @@ fn paste_face_properties(sector: &mut Sector, target_face: &SectorFace, fc: &Fac
 /// Copy all selected faces as geometry (with relative positions)
 /// Handles both SectorFace selections (individual faces) and Sector selections (all faces in sector)
 fn copy_geometry_selection(state: &mut EditorState) {
+    match clipboard_from_selection(state, false) {
+        Some(gc) => {
+            let count = gc.faces.len();
+            crate::clipboard_history::record(crate::clipboard_history::ClipData::Tiles(gc.clone()));
+            state.geometry_clipboard = Some(gc);
+            state.set_status(&format!("Copied {} faces to geometry clipboard", count), 2.0);
+        }
+        None => state.set_status("No geometry to copy", 2.0),
+    }
+}
+
+/// Build a geometry clipboard from the current selection (None if nothing is selected).
+/// With `include_objects`, asset instances in fully selected sectors and selected
+/// objects are captured too.
+pub fn clipboard_from_selection(state: &EditorState, include_objects: bool) -> Option<GeometryClipboard> {
     // Collect all sector positions that need their faces extracted
     let mut sector_positions: Vec<(usize, usize, usize)> = Vec::new();
     let mut all_faces: Vec<(usize, usize, usize, SectorFace)> = Vec::new();
+    let mut objects: Vec<(usize, usize)> = Vec::new();
 
-    // Handle primary selection
-    match &state.selection {
-        Selection::SectorFace { room, x, z, face } => {
-            all_faces.push((*room, *x, *z, face.clone()));
-        }
-        Selection::Sector { room, x, z } => {
-            sector_positions.push((*room, *x, *z));
-        }
-        _ => {}
-    }
-
-    // Handle multi-selection
-    for sel in &state.multi_selection {
+    // Handle primary selection and multi-selection
+    for sel in std::iter::once(&state.selection).chain(&state.multi_selection) {
         match sel {
             Selection::SectorFace { room, x, z, face } => {
                 all_faces.push((*room, *x, *z, face.clone()));
@@ -1333,10 +1661,29 @@ fn copy_geometry_selection(state: &mut EditorState) {
             Selection::Sector { room, x, z } => {
                 sector_positions.push((*room, *x, *z));
             }
+            Selection::Object { room, index } if include_objects => {
+                objects.push((*room, *index));
+            }
             _ => {}
         }
     }
 
+    // Objects standing in fully selected sectors
+    if include_objects {
+        for &(room_idx, x, z) in &sector_positions {
+            if let Some(room) = state.level.rooms.get(room_idx) {
+                for (i, obj) in room.objects.iter().enumerate() {
+                    if obj.sector_x == x && obj.sector_z == z && !objects.contains(&(room_idx, i)) {
+                        objects.push((room_idx, i));
+                    }
+                }
+            }
+        }
+    }
+    let objects: Vec<crate::world::AssetInstance> = objects.iter()
+        .filter_map(|&(room, i)| state.level.rooms.get(room).and_then(|r| r.objects.get(i)).cloned())
+        .collect();
+
     // Extract faces from sector positions
     for (room_idx, x, z) in sector_positions {
         if let Some(room) = state.level.rooms.get(room_idx) {
@@ -1372,14 +1719,15 @@ fn copy_geometry_selection(state: &mut EditorState) {
         }
     }
 
-    if all_faces.is_empty() {
-        state.set_status("No geometry to copy", 2.0);
-        return;
+    if all_faces.is_empty() && objects.is_empty() {
+        return None;
     }
 
     // Find anchor point (minimum x, z coordinates)
-    let anchor_x = all_faces.iter().map(|(_, x, _, _)| *x as i32).min().unwrap_or(0);
-    let anchor_z = all_faces.iter().map(|(_, _, z, _)| *z as i32).min().unwrap_or(0);
+    let positions = all_faces.iter().map(|(_, x, z, _)| (*x, *z))
+        .chain(objects.iter().map(|o| (o.sector_x, o.sector_z)));
+    let anchor_x = positions.clone().map(|(x, _)| x as i32).min().unwrap_or(0);
+    let anchor_z = positions.map(|(_, z)| z as i32).min().unwrap_or(0);
 
     let mut copied_faces: Vec<CopiedFace> = Vec::new();
 
@@ -1427,16 +1775,24 @@ fn copy_geometry_selection(state: &mut EditorState) {
         }
     }
 
-    if !copied_faces.is_empty() {
-        let count = copied_faces.len();
-        state.geometry_clipboard = Some(GeometryClipboard {
-            faces: copied_faces,
-            flip_h: false,
-            flip_v: false,
-            rotation: 0,
-        });
-        state.set_status(&format!("Copied {} faces to geometry clipboard", count), 2.0);
+    let objects: Vec<crate::world::AssetInstance> = objects.into_iter()
+        .map(|mut obj| {
+            obj.sector_x = (obj.sector_x as i32 - anchor_x) as usize;
+            obj.sector_z = (obj.sector_z as i32 - anchor_z) as usize;
+            obj
+        })
+        .collect();
+
+    if copied_faces.is_empty() && objects.is_empty() {
+        return None;
     }
+    Some(GeometryClipboard {
+        faces: copied_faces,
+        objects,
+        flip_h: false,
+        flip_v: false,
+        rotation: 0,
+    })
 }
 
 /// Paste geometry from clipboard at the selected/hovered sector
@@ -1585,9 +1941,11 @@ fn paste_geometry_at_impl(state: &mut EditorState, gc: &GeometryClipboard, room_
     let mut target_min_z = i32::MAX;
     let mut target_max_z = i32::MIN;
 
-    for face in &gc.faces {
+    let positions = gc.faces.iter().map(|f| (f.rel_x, f.rel_z))
+        .chain(gc.objects.iter().map(|o| (o.sector_x as i32, o.sector_z as i32)));
+    for (face_x, face_z) in positions {
         let (rel_x, rel_z, _, _) = transform_clipboard_position(
-            face.rel_x, face.rel_z, width, depth,
+            face_x, face_z, width, depth,
             gc.rotation, gc.flip_h, gc.flip_v,
         );
         let target_x = anchor_x + rel_x;
@@ -1864,21 +2222,66 @@ fn paste_geometry_at_impl(state: &mut EditorState, gc: &GeometryClipboard, room_
         }
     }
 
+    // Third pass: place copied objects
+    let mut object_count = 0;
+    if let Some(room) = state.level.rooms.get_mut(room_idx) {
+        for obj in &gc.objects {
+            let (rel_x, rel_z, _, _) = transform_clipboard_position(
+                obj.sector_x as i32, obj.sector_z as i32, width, depth,
+                gc.rotation, gc.flip_h, gc.flip_v,
+            );
+            let mut new_obj = obj.clone();
+            new_obj.sector_x = (anchor_x + rel_x + offset_x) as usize;
+            new_obj.sector_z = (anchor_z + rel_z + offset_z) as usize;
+            new_obj.facing = transform_facing(obj.facing, gc.rotation, gc.flip_h, gc.flip_v);
+            room.objects.push(new_obj);
+            object_count += 1;
+        }
+
+        // Objects placed before the grid grew in -X/-Z keep their world position
+        if offset_x > 0 || offset_z > 0 {
+            let pasted_from = room.objects.len() - object_count;
+            for obj in &mut room.objects[..pasted_from] {
+                obj.sector_x += offset_x as usize;
+                obj.sector_z += offset_z as usize;
+            }
+        }
+    }
+
     // Recalculate room bounds
     if let Some(room) = state.level.rooms.get_mut(room_idx) {
         room.recalculate_bounds();
     }
 
-    if paste_count > 0 {
+    if paste_count > 0 && object_count > 0 {
+        state.set_status(&format!("Pasted {} faces and {} objects", paste_count, object_count), 2.0);
+    } else if paste_count > 0 {
         state.set_status(&format!("Pasted {} faces", paste_count), 2.0);
+    } else if object_count > 0 {
+        state.set_status(&format!("Pasted {} objects", object_count), 2.0);
     } else {
         state.set_status("No faces pasted (out of bounds?)", 2.0);
     }
 }
 
+/// Transform an object's facing (yaw, 0 = +Z) by the clipboard rotation and flips
+fn transform_facing(facing: f32, rotation: u8, flip_h: bool, flip_v: bool) -> f32 {
+    use std::f32::consts::{FRAC_PI_2, PI};
+    // 90° CW on the grid turns +Z into -X
+    let mut yaw = facing - (rotation % 4) as f32 * FRAC_PI_2;
+    if flip_h {
+        yaw = -yaw;
+    }
+    if flip_v {
+        yaw = PI - yaw;
+    }
+    yaw.rem_euclid(2.0 * PI)
+}
+
 /// Draw the skybox configuration panel - PS1 Spyro-style with collapsible sections
 fn draw_skybox_panel(ctx: &mut UiContext, rect: Rect, state: &mut EditorState) {
-    use crate::world::{Skybox, HorizonDirection, CloudLayer, MountainRange};
+    use crate::world::{Skybox, HorizonDirection, CloudLayer, MountainRange, WeatherPreset};
+    use crate::tracker::MixGroup;
 
     let x = rect.x.floor();
     let mut y = rect.y.floor();
@@ -1937,6 +2340,258 @@ fn draw_skybox_panel(ctx: &mut UiContext, rect: Rect, state: &mut EditorState) {
 
     y += 22.0;
 
+    // === WEATHER ===
+    // Level-wide particles; rooms opt in with their Outdoor toggle
+    draw_text("Weather", x, y + 10.0, 10.0, label_gray);
+    let weather_w = (panel_w - 8.0 - 45.0 - 4.0 * 3.0) / 5.0;
+    for (i, preset) in WeatherPreset::ALL.into_iter().enumerate() {
+        let btn_rect = Rect::new(x + 45.0 + i as f32 * (weather_w + 3.0), y, weather_w, 14.0);
+        let btn_hovered = btn_rect.contains(ctx.mouse.x, ctx.mouse.y);
+        let active = state.level.weather.preset == preset;
+        let btn_bg = if active {
+            Color::from_rgba(60, 120, 80, 255)
+        } else if btn_hovered {
+            Color::from_rgba(70, 70, 90, 255)
+        } else {
+            Color::from_rgba(50, 50, 65, 255)
+        };
+        draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, btn_bg);
+        if btn_hovered {
+            draw_rectangle_lines(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, 1.0, WHITE);
+        }
+        let name = preset.label();
+        let text_w = name.len() as f32 * 5.0;
+        draw_text(name, btn_rect.x + (btn_rect.w - text_w) / 2.0, btn_rect.y + 10.0, 9.0, WHITE);
+        if btn_hovered && ctx.mouse.left_pressed {
+            state.level.weather.preset = preset;
+        }
+    }
+    y += 18.0;
+
+    if !state.level.weather.is_clear() {
+        draw_text("Amount", x + 4.0, y + 10.0, 10.0, label_gray);
+        let slider_rect = Rect::new(x + 50.0, y, panel_w - 58.0 - 62.0, 12.0);
+        if let Some(new_val) = draw_slider(ctx, slider_rect, state.level.weather.intensity, 0.1, 2.0,
+            Color::from_rgba(120, 150, 190, 255), &mut state.skybox_active_slider, 200) {
+            state.level.weather.intensity = new_val;
+        }
+
+        let outdoor_rect = Rect::new(slider_rect.right() + 6.0, y, 56.0, 12.0);
+        let outdoor_hovered = outdoor_rect.contains(ctx.mouse.x, ctx.mouse.y);
+        let outdoor_only = state.level.weather.outdoor_only;
+        let outdoor_bg = if outdoor_only { Color::from_rgba(60, 120, 80, 255) } else { Color::from_rgba(60, 60, 65, 255) };
+        draw_rectangle(outdoor_rect.x, outdoor_rect.y, outdoor_rect.w, outdoor_rect.h, outdoor_bg);
+        if outdoor_hovered {
+            draw_rectangle_lines(outdoor_rect.x, outdoor_rect.y, outdoor_rect.w, outdoor_rect.h, 1.0, WHITE);
+            ctx.set_tooltip("Only in rooms marked Outdoor", ctx.mouse.x, ctx.mouse.y);
+        }
+        draw_text(if outdoor_only { "Outdoor" } else { "All" }, outdoor_rect.x + 4.0, outdoor_rect.y + 9.0, 9.0, WHITE);
+        if outdoor_hovered && ctx.mouse.left_pressed {
+            state.level.weather.outdoor_only = !outdoor_only;
+        }
+        y += 18.0;
+    }
+
+    // === ATMOSPHERE ===
+    // Level fog (rooms with their own fog keep it) and time-of-day tint
+    draw_text("Fog", x, y + 10.0, 10.0, label_gray);
+    let fog_rect = Rect::new(x + 45.0, y, 50.0, 14.0);
+    let fog_hovered = fog_rect.contains(ctx.mouse.x, ctx.mouse.y);
+    let fog_enabled = state.level.atmosphere.fog_enabled;
+    let fog_bg = if fog_enabled { Color::from_rgba(60, 120, 80, 255) } else { Color::from_rgba(60, 60, 65, 255) };
+    draw_rectangle(fog_rect.x, fog_rect.y, fog_rect.w, fog_rect.h, fog_bg);
+    if fog_hovered {
+        draw_rectangle_lines(fog_rect.x, fog_rect.y, fog_rect.w, fog_rect.h, 1.0, WHITE);
+        ctx.set_tooltip("Level-wide distance fog (rooms with their own fog override it)", ctx.mouse.x, ctx.mouse.y);
+    }
+    draw_text(if fog_enabled { "ON" } else { "OFF" }, fog_rect.x + 16.0, fog_rect.y + 11.0, 11.0, WHITE);
+    if fog_hovered && ctx.mouse.left_pressed {
+        state.level.atmosphere.fog_enabled = !fog_enabled;
+    }
+    if fog_enabled {
+        let (r, g, b) = state.level.atmosphere.fog_color;
+        let swatch = Rect::new(fog_rect.right() + 6.0, y, 14.0, 14.0);
+        draw_rectangle(swatch.x, swatch.y, swatch.w, swatch.h, Color::new(r, g, b, 1.0));
+        draw_rectangle_lines(swatch.x, swatch.y, swatch.w, swatch.h, 1.0, Color::from_rgba(80, 80, 90, 255));
+    }
+    y += 18.0;
+
+    let channel_colors = [
+        Color::from_rgba(200, 80, 80, 255),
+        Color::from_rgba(80, 200, 80, 255),
+        Color::from_rgba(80, 80, 200, 255),
+    ];
+    if fog_enabled {
+        draw_text("Color", x + 4.0, y + 10.0, 10.0, label_gray);
+        let (r, g, b) = state.level.atmosphere.fog_color;
+        let mut channels = [r, g, b];
+        let channel_w = (panel_w - 58.0 - 6.0) / 3.0;
+        for (i, channel) in channels.iter_mut().enumerate() {
+            let slider_rect = Rect::new(x + 50.0 + i as f32 * (channel_w + 3.0), y, channel_w, 12.0);
+            if let Some(new_val) = draw_slider(ctx, slider_rect, *channel, 0.0, 1.0,
+                channel_colors[i], &mut state.skybox_active_slider, 270 + i) {
+                *channel = new_val;
+            }
+        }
+        state.level.atmosphere.fog_color = (channels[0], channels[1], channels[2]);
+        y += 16.0;
+
+        // Distances in world units (SECTOR_SIZE = 1024), shown in sectors
+        let fog_max = 50000.0;
+        let atmosphere = &mut state.level.atmosphere;
+        for (i, label) in ["Start", "End"].into_iter().enumerate() {
+            draw_text(label, x + 4.0, y + 10.0, 10.0, label_gray);
+            let slider_rect = Rect::new(x + 50.0, y, panel_w - 58.0, 12.0);
+            let value = if i == 0 { atmosphere.fog_start } else { atmosphere.fog_end };
+            if slider_rect.contains(ctx.mouse.x, ctx.mouse.y) {
+                ctx.set_tooltip(&format!("{:.1} sectors", value / SECTOR_SIZE), ctx.mouse.x, ctx.mouse.y);
+            }
+            if let Some(new_val) = draw_slider(ctx, slider_rect, value, 0.0, fog_max,
+                Color::from_rgba(150, 170, 200, 255), &mut state.skybox_active_slider, 273 + i) {
+                // Snap to quarter sectors; the end never passes the start
+                let snapped = (new_val / (SECTOR_SIZE / 4.0)).round() * (SECTOR_SIZE / 4.0);
+                if i == 0 {
+                    atmosphere.fog_start = snapped;
+                    atmosphere.fog_end = atmosphere.fog_end.max(snapped);
+                } else {
+                    atmosphere.fog_end = snapped.max(atmosphere.fog_start);
+                }
+            }
+            y += 16.0;
+        }
+    }
+
+    // Time of day: 12:00 is neutral, night is dark blue
+    draw_text("Time", x, y + 10.0, 10.0, label_gray);
+    let time_rect = Rect::new(x + 50.0, y, panel_w - 58.0 - 40.0, 12.0);
+    if let Some(new_val) = draw_slider(ctx, time_rect, state.level.atmosphere.time_of_day, 0.0, 24.0,
+        Color::from_rgba(200, 170, 110, 255), &mut state.skybox_active_slider, 275) {
+        // Quarter-hour steps
+        state.level.atmosphere.time_of_day = (new_val * 4.0).round() / 4.0;
+    }
+    draw_text(&state.level.atmosphere.clock_label(), time_rect.right() + 6.0, y + 10.0, 10.0, WHITE);
+    y += 16.0;
+
+    draw_text("Tint", x + 4.0, y + 10.0, 10.0, label_gray);
+    let (r, g, b) = state.level.atmosphere.tint;
+    let mut channels = [r, g, b];
+    let channel_w = (panel_w - 58.0 - 6.0) / 3.0;
+    for (i, channel) in channels.iter_mut().enumerate() {
+        let slider_rect = Rect::new(x + 50.0 + i as f32 * (channel_w + 3.0), y, channel_w, 12.0);
+        if let Some(new_val) = draw_slider(ctx, slider_rect, *channel, 0.0, 1.0,
+            channel_colors[i], &mut state.skybox_active_slider, 276 + i) {
+            *channel = new_val;
+        }
+    }
+    state.level.atmosphere.tint = (channels[0], channels[1], channels[2]);
+    y += 18.0;
+
+    // === VERTEX SNAP ===
+    // PS1 wobble for this project (with Fixed-Point Math on), previewed live
+    draw_text("Vertex Snap", x, y + 10.0, 10.0, label_gray);
+    y += 16.0;
+    let snap = &mut state.level.vertex_snap;
+    draw_text("Grid", x + 4.0, y + 10.0, 10.0, label_gray);
+    let grid_rect = Rect::new(x + 50.0, y, panel_w - 58.0 - 40.0, 12.0);
+    if grid_rect.contains(ctx.mouse.x, ctx.mouse.y) {
+        ctx.set_tooltip("Snap step in PS1 pixels (1 = authentic, higher = coarser)", ctx.mouse.x, ctx.mouse.y);
+    }
+    if let Some(new_val) = draw_slider(ctx, grid_rect, snap.size as f32, 1.0, MAX_SNAP_SIZE as f32,
+        Color::from_rgba(170, 150, 200, 255), &mut state.skybox_active_slider, 280) {
+        snap.size = new_val.round() as u8;
+    }
+    draw_text(&format!("{}px", snap.size), grid_rect.right() + 6.0, y + 10.0, 10.0, WHITE);
+    y += 16.0;
+    draw_text("Jitter", x + 4.0, y + 10.0, 10.0, label_gray);
+    let jitter_rect = Rect::new(x + 50.0, y, panel_w - 58.0 - 40.0, 12.0);
+    if jitter_rect.contains(ctx.mouse.x, ctx.mouse.y) {
+        ctx.set_tooltip("How far vertices jump to the grid (0% = smooth)", ctx.mouse.x, ctx.mouse.y);
+    }
+    if let Some(new_val) = draw_slider(ctx, jitter_rect, snap.jitter, 0.0, 1.0,
+        Color::from_rgba(170, 150, 200, 255), &mut state.skybox_active_slider, 281) {
+        snap.jitter = new_val;
+    }
+    draw_text(&format!("{:.0}%", snap.jitter * 100.0), jitter_rect.right() + 6.0, y + 10.0, 10.0, WHITE);
+    y += 18.0;
+
+    // === AUDIO MIX ===
+    // Project default group volumes; the game's options menu can override them
+    draw_text("Audio Mix", x, y + 10.0, 10.0, label_gray);
+    y += 16.0;
+    for (i, group) in MixGroup::ALL.into_iter().enumerate() {
+        draw_text(group.label(), x + 4.0, y + 10.0, 10.0, label_gray);
+        let slider_rect = Rect::new(x + 50.0, y, panel_w - 58.0, 12.0);
+        if let Some(new_val) = draw_slider(ctx, slider_rect, state.level.audio_mix.volume(group), 0.0, 1.0,
+            Color::from_rgba(120, 170, 130, 255), &mut state.skybox_active_slider, 210 + i) {
+            state.level.audio_mix.set_volume(group, new_val);
+        }
+        y += 16.0;
+    }
+    draw_text("Duck", x + 4.0, y + 10.0, 10.0, label_gray);
+    let duck_rect = Rect::new(x + 50.0, y, panel_w - 58.0, 12.0);
+    if duck_rect.contains(ctx.mouse.x, ctx.mouse.y) {
+        ctx.set_tooltip("How far dialogue lowers the music", ctx.mouse.x, ctx.mouse.y);
+    }
+    if let Some(new_val) = draw_slider(ctx, duck_rect, state.level.audio_mix.duck, 0.0, 1.0,
+        Color::from_rgba(170, 140, 110, 255), &mut state.skybox_active_slider, 214) {
+        state.level.audio_mix.duck = new_val;
+    }
+    y += 18.0;
+
+    // === STREAMING ===
+    // Other level files loaded around the player in the Test tab
+    draw_text("Streaming", x, y + 10.0, 10.0, label_gray);
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let add_rect = Rect::new(x + panel_w - 8.0 - 56.0, y, 56.0, 14.0);
+        if crate::ui::text_button(ctx, add_rect, "+ Chunk", "Stream another level file in as part of this world") {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Levels", &["ron"])
+                .pick_file()
+            {
+                match crate::world::load_level(&path) {
+                    Ok(chunk_level) => {
+                        let relative = std::env::current_dir().ok()
+                            .and_then(|cwd| path.strip_prefix(cwd).ok().map(|p| p.to_path_buf()))
+                            .unwrap_or(path);
+                        let chunk = crate::world::LevelChunk::new(relative.to_string_lossy().replace('\\', "/"), &chunk_level);
+                        state.set_status(&format!("Streaming chunk {} ({} rooms)", chunk.name(), chunk_level.rooms.len()), 3.0);
+                        state.level.streaming.add_chunk(chunk);
+                    }
+                    Err(e) => state.set_status(&format!("Chunk load failed: {}", e), 3.0),
+                }
+            }
+        }
+    }
+    y += 18.0;
+
+    let mut remove_chunk = None;
+    for (i, chunk) in state.level.streaming.chunks.iter().enumerate() {
+        draw_text(chunk.name(), x + 4.0, y + 10.0, 10.0, WHITE);
+        let remove_rect = Rect::new(x + panel_w - 8.0 - 14.0, y, 14.0, 12.0);
+        if crate::ui::text_button(ctx, remove_rect, "x", &format!("Stop streaming {}", chunk.path)) {
+            remove_chunk = Some(i);
+        }
+        y += 14.0;
+    }
+    if let Some(i) = remove_chunk {
+        state.level.streaming.chunks.remove(i);
+    }
+    if !state.level.streaming.is_off() {
+        // Distance in sectors between the player's room and a chunk before it loads
+        draw_text("Range", x + 4.0, y + 10.0, 10.0, label_gray);
+        let range_rect = Rect::new(x + 50.0, y, panel_w - 58.0, 12.0);
+        if range_rect.contains(ctx.mouse.x, ctx.mouse.y) {
+            ctx.set_tooltip(&format!("Load chunks within {:.0} sectors of the player's room",
+                state.level.streaming.distance / SECTOR_SIZE), ctx.mouse.x, ctx.mouse.y);
+        }
+        if let Some(new_val) = draw_slider(ctx, range_rect, state.level.streaming.distance / SECTOR_SIZE, 1.0, 32.0,
+            Color::from_rgba(150, 130, 190, 255), &mut state.skybox_active_slider, 260) {
+            state.level.streaming.distance = new_val.round() * SECTOR_SIZE;
+        }
+        y += 18.0;
+    }
+
     // === SKYBOX CONTROLS ===
     if let Some(skybox) = state.level.skybox.clone() {
         // Helper to draw a collapsible section header
@@ -2813,35 +3468,614 @@ fn draw_compact_rgb_sliders(
 }
 
 /// Draw debug panel with frame timing information
-fn draw_debug_panel(_ctx: &mut UiContext, rect: Rect, state: &mut EditorState) {
-    use macroquad::prelude::*;
+/// Level scripts: list of scripts plus a text editor for the selected one
+fn draw_scripts_panel(ctx: &mut UiContext, rect: Rect, state: &mut EditorState, icon_font: Option<&Font>) {
+    use crate::world::LevelScript;
 
-    let mut y = rect.y.floor();
     let x = rect.x.floor();
-    let bar_w = rect.w - 4.0;
+    let mut y = rect.y.floor();
+    let icon_btn_size = 14.0;
 
-    // Colors for timing breakdown
-    let label_color = Color::from_rgba(150, 150, 160, 255);
-    let value_color = Color::from_rgba(200, 200, 210, 255);
-    let toolbar_color = Color::from_rgba(100, 180, 255, 255);   // Blue
-    let left_color = Color::from_rgba(180, 100, 255, 255);      // Purple
-    let viewport_color = Color::from_rgba(255, 100, 100, 255);  // Red
-    let right_color = Color::from_rgba(255, 200, 100, 255);     // Orange
-    let status_color = Color::from_rgba(100, 255, 180, 255);    // Cyan
+    // Toolbar: add script
+    let toolbar_rect = Rect::new(rect.x, y, rect.w, 22.0);
+    let mut toolbar = Toolbar::new(toolbar_rect);
+    if toolbar.icon_button(ctx, icon::PLUS, icon_font, "Add script") {
+        state.save_undo();
+        let name = format!("script{}", state.level.scripts.len() + 1);
+        state.level.scripts.push(LevelScript::new(name));
+        state.selected_script = state.level.scripts.len() - 1;
+    }
+    toolbar.label(&format!("{} scripts", state.level.scripts.len()));
+    y += 24.0;
 
-    // 3D viewport sub-timing colors (shades of red)
-    let vp_input_color = Color::from_rgba(255, 180, 180, 255);   // Light red
-    let vp_clear_color = Color::from_rgba(255, 150, 150, 255);   // Light-mid red
-    let vp_grid_color = Color::from_rgba(255, 120, 120, 255);    // Mid red
-    let vp_lights_color = Color::from_rgba(240, 100, 100, 255);  // Mid-dark red
-    let vp_texconv_color = Color::from_rgba(220, 80, 80, 255);   // Dark red
-    let vp_meshgen_color = Color::from_rgba(200, 60, 60, 255);   // Darker red
-    let vp_raster_color = Color::from_rgba(180, 40, 40, 255);    // Darkest red
-    let vp_preview_color = Color::from_rgba(255, 100, 150, 255); // Red-pink
-    let vp_upload_color = Color::from_rgba(255, 130, 100, 255);  // Red-orange
+    // Script list
+    let mut script_to_delete: Option<usize> = None;
+    for i in 0..state.level.scripts.len() {
+        let is_selected = i == state.selected_script;
+        let enabled = state.level.scripts[i].enabled;
 
-    // FPS and frame time
-    let fps = get_fps();
+        let vis_btn_rect = Rect::new(x, y + 1.0, icon_btn_size, icon_btn_size);
+        let (vis_icon, vis_tooltip) = if enabled { (icon::EYE, "Disable script") } else { (icon::EYE_OFF, "Enable script") };
+        if crate::ui::icon_button(ctx, vis_btn_rect, vis_icon, icon_font, vis_tooltip) {
+            state.save_undo();
+            state.level.scripts[i].enabled = !enabled;
+        }
+
+        let del_btn_rect = Rect::new(x + rect.w - icon_btn_size - 4.0, y + 1.0, icon_btn_size, icon_btn_size);
+        if crate::ui::icon_button(ctx, del_btn_rect, icon::TRASH, icon_font, "Delete script") {
+            script_to_delete = Some(i);
+        }
+
+        let row_rect = Rect::new(x + icon_btn_size + 2.0, y, rect.w - icon_btn_size * 2.0 - 10.0, LINE_HEIGHT);
+        if ctx.mouse.clicked(&row_rect) {
+            state.selected_script = i;
+        }
+        if is_selected {
+            draw_rectangle(row_rect.x.floor(), row_rect.y.floor(), row_rect.w, row_rect.h, Color::from_rgba(60, 80, 60, 255));
+        }
+        let text_color = if !enabled {
+            Color::from_rgba(80, 80, 80, 255)
+        } else if is_selected {
+            Color::from_rgba(100, 200, 100, 255)
+        } else {
+            WHITE
+        };
+        draw_text(&state.level.scripts[i].name, (x + icon_btn_size + 4.0).floor(), (y + 11.0).floor(), FONT_SIZE_CONTENT, text_color);
+        y += LINE_HEIGHT;
+    }
+
+    if let Some(i) = script_to_delete {
+        state.save_undo();
+        state.level.scripts.remove(i);
+        state.script_input_for = None;
+    }
+
+    if state.level.scripts.is_empty() {
+        state.script_input.focused = false;
+        draw_text("No scripts. Add one to react to", x, (y + 11.0).floor(), FONT_SIZE_CONTENT, Color::from_rgba(120, 120, 120, 255));
+        draw_text("triggers, pickups and timers.", x, (y + 11.0 + LINE_HEIGHT).floor(), FONT_SIZE_CONTENT, Color::from_rgba(120, 120, 120, 255));
+        return;
+    }
+    state.selected_script = state.selected_script.min(state.level.scripts.len() - 1);
+    let sel = state.selected_script;
+
+    // Reload the editor when switching scripts or when the level changed underneath (undo/load)
+    if state.script_input_for != Some(sel) || state.script_input.text != state.level.scripts[sel].source {
+        let focused = state.script_input.focused && state.script_input_for == Some(sel);
+        state.script_input = crate::ui::TextInputState::new(state.level.scripts[sel].source.clone());
+        state.script_input.focused = focused;
+        state.script_input_for = Some(sel);
+    }
+
+    // Validate for the status line
+    let validation = crate::game::script::validate(&state.level.scripts[sel].source);
+    let error_line = validation.as_ref().err().map(|(line, _)| line.saturating_sub(1));
+
+    y += 4.0;
+    let status_h = LINE_HEIGHT;
+    let area_rect = Rect::new(x, y, rect.w - 4.0, (rect.bottom() - y - status_h - 4.0).max(LINE_HEIGHT));
+    let was_focused = state.script_input.focused;
+    if crate::ui::draw_text_area(area_rect, &mut state.script_input, FONT_SIZE_CONTENT, error_line) {
+        // One undo step per editing session
+        if !state.script_undo_saved {
+            state.save_undo();
+            state.script_undo_saved = true;
+        }
+        state.level.scripts[sel].source = state.script_input.text.clone();
+        state.dirty = true;
+    }
+    if state.script_input.focused {
+        state.active_panel = super::state::ActivePanel::Scripts;
+    } else if was_focused {
+        state.script_undo_saved = false;
+    }
+
+    let status_y = (area_rect.bottom() + 12.0).floor();
+    match validation {
+        Ok(handlers) => {
+            let text = if handlers == 1 { "1 handler".to_string() } else { format!("{} handlers", handlers) };
+            draw_text(&text, x, status_y, FONT_SIZE_CONTENT, Color::from_rgba(100, 200, 100, 255));
+        }
+        Err((line, message)) => {
+            draw_text(&format!("Line {}: {}", line, message), x, status_y, FONT_SIZE_CONTENT, Color::from_rgba(255, 100, 100, 255));
+        }
+    }
+}
+
+/// Cutscenes: list of cutscenes, the selected one's camera keys (captured
+/// from the 3D view) and a text editor for its timeline
+fn draw_cutscenes_panel(ctx: &mut UiContext, rect: Rect, state: &mut EditorState, icon_font: Option<&Font>) {
+    use crate::world::{next_cutscene_name, Cutscene};
+
+    /// Step of a camera key's time buttons (seconds)
+    const KEY_TIME_STEP: f32 = 0.5;
+    /// How far ahead of the 3D view a captured key looks
+    const KEY_TARGET_DISTANCE: f32 = 1024.0;
+
+    let x = rect.x.floor();
+    let mut y = rect.y.floor();
+    let icon_btn_size = 14.0;
+    let dim = Color::from_rgba(120, 120, 120, 255);
+
+    // Toolbar: add cutscene
+    let mut toolbar = Toolbar::new(Rect::new(rect.x, y, rect.w, 22.0));
+    if toolbar.icon_button(ctx, icon::PLUS, icon_font, "Add cutscene") {
+        state.save_undo();
+        let name = next_cutscene_name(state.level.cutscenes.iter());
+        state.level.cutscenes.push(Cutscene::new(name));
+        state.selected_cutscene = state.level.cutscenes.len() - 1;
+    }
+    toolbar.label(&format!("{} cutscenes", state.level.cutscenes.len()));
+    y += 24.0;
+
+    // Cutscene list
+    let mut cutscene_to_delete: Option<usize> = None;
+    for i in 0..state.level.cutscenes.len() {
+        let is_selected = i == state.selected_cutscene;
+
+        let del_btn_rect = Rect::new(x + rect.w - icon_btn_size - 4.0, y + 1.0, icon_btn_size, icon_btn_size);
+        if crate::ui::icon_button(ctx, del_btn_rect, icon::TRASH, icon_font, "Delete cutscene") {
+            cutscene_to_delete = Some(i);
+        }
+
+        let row_rect = Rect::new(x, y, rect.w - icon_btn_size - 8.0, LINE_HEIGHT);
+        if ctx.mouse.clicked(&row_rect) {
+            state.selected_cutscene = i;
+        }
+        if is_selected {
+            draw_rectangle(row_rect.x.floor(), row_rect.y.floor(), row_rect.w, row_rect.h, Color::from_rgba(60, 80, 60, 255));
+        }
+        let text_color = if is_selected { Color::from_rgba(100, 200, 100, 255) } else { WHITE };
+        draw_text(&state.level.cutscenes[i].name, (x + 2.0).floor(), (y + 11.0).floor(), FONT_SIZE_CONTENT, text_color);
+        y += LINE_HEIGHT;
+    }
+
+    if let Some(i) = cutscene_to_delete {
+        state.save_undo();
+        state.level.cutscenes.remove(i);
+        state.cutscene_input_for = None;
+    }
+
+    if state.level.cutscenes.is_empty() {
+        state.cutscene_input.focused = false;
+        draw_text("No cutscenes. Add one, then play it", x, (y + 11.0).floor(), FONT_SIZE_CONTENT, dim);
+        draw_text("from a trigger or a script.", x, (y + 11.0 + LINE_HEIGHT).floor(), FONT_SIZE_CONTENT, dim);
+        return;
+    }
+    state.selected_cutscene = state.selected_cutscene.min(state.level.cutscenes.len() - 1);
+    let sel = state.selected_cutscene;
+
+    // Options and camera capture
+    y += 4.0;
+    let mut toolbar = Toolbar::new(Rect::new(rect.x, y, rect.w, 22.0));
+    let (letterbox, skippable) = (state.level.cutscenes[sel].letterbox, state.level.cutscenes[sel].skippable);
+    if toolbar.letter_button_active(ctx, 'L', "Letterbox bars while playing", letterbox) {
+        state.save_undo();
+        state.level.cutscenes[sel].letterbox = !letterbox;
+    }
+    if toolbar.letter_button_active(ctx, 'S', "Skippable with Interact", skippable) {
+        state.save_undo();
+        state.level.cutscenes[sel].skippable = !skippable;
+    }
+    toolbar.separator();
+    if toolbar.icon_button(ctx, icon::PLUS, icon_font, "Add camera key from the 3D view") {
+        state.save_undo();
+        let camera = &state.camera_3d;
+        let (position, target) = (camera.position, camera.position + camera.basis_z * KEY_TARGET_DISTANCE);
+        state.level.cutscenes[sel].push_camera_key(position, target);
+    }
+    let cutscene = &state.level.cutscenes[sel];
+    toolbar.label(&format!("{} keys, {:.1}s", cutscene.camera.len(), cutscene.camera_duration()));
+    y += 24.0;
+
+    // Camera keys: time, nudge buttons, delete
+    let mut key_to_delete: Option<usize> = None;
+    let mut key_time_change: Option<(usize, f32)> = None;
+    for i in 0..state.level.cutscenes[sel].camera.len() {
+        let key = state.level.cutscenes[sel].camera[i];
+        let mut bx = x;
+        if crate::ui::icon_button(ctx, Rect::new(bx, y + 1.0, icon_btn_size, icon_btn_size), icon::MINUS, icon_font, "Earlier") {
+            key_time_change = Some((i, -KEY_TIME_STEP));
+        }
+        bx += icon_btn_size + 2.0;
+        if crate::ui::icon_button(ctx, Rect::new(bx, y + 1.0, icon_btn_size, icon_btn_size), icon::PLUS, icon_font, "Later") {
+            key_time_change = Some((i, KEY_TIME_STEP));
+        }
+        bx += icon_btn_size + 6.0;
+        let text = format!("{:.1}s  ({:.0}, {:.0}, {:.0})", key.time, key.position.x, key.position.y, key.position.z);
+        draw_text(&text, bx.floor(), (y + 11.0).floor(), FONT_SIZE_CONTENT, WHITE);
+
+        let del_btn_rect = Rect::new(x + rect.w - icon_btn_size - 4.0, y + 1.0, icon_btn_size, icon_btn_size);
+        if crate::ui::icon_button(ctx, del_btn_rect, icon::TRASH, icon_font, "Delete camera key") {
+            key_to_delete = Some(i);
+        }
+        y += LINE_HEIGHT;
+    }
+    if let Some((i, step)) = key_time_change {
+        state.save_undo();
+        let key = &mut state.level.cutscenes[sel].camera[i];
+        key.time = (key.time + step).max(0.0);
+        state.level.cutscenes[sel].sort_camera_keys();
+    }
+    if let Some(i) = key_to_delete {
+        state.save_undo();
+        state.level.cutscenes[sel].camera.remove(i);
+    }
+    if state.level.cutscenes[sel].camera.is_empty() {
+        draw_text("No camera keys: the camera follows the player.", x, (y + 11.0).floor(), FONT_SIZE_CONTENT, dim);
+        y += LINE_HEIGHT;
+    }
+
+    // Reload the editor when switching cutscenes or when the level changed underneath (undo/load)
+    if state.cutscene_input_for != Some(sel) || state.cutscene_input.text != state.level.cutscenes[sel].timeline {
+        let focused = state.cutscene_input.focused && state.cutscene_input_for == Some(sel);
+        state.cutscene_input = crate::ui::TextInputState::new(state.level.cutscenes[sel].timeline.clone());
+        state.cutscene_input.focused = focused;
+        state.cutscene_input_for = Some(sel);
+    }
+
+    // Validate for the status line
+    let validation = crate::game::cutscene::validate_timeline(&state.level.cutscenes[sel].timeline);
+    let error_line = validation.as_ref().err().map(|(line, _)| line.saturating_sub(1));
+
+    y += 4.0;
+    let status_h = LINE_HEIGHT;
+    let area_rect = Rect::new(x, y, rect.w - 4.0, (rect.bottom() - y - status_h - 4.0).max(LINE_HEIGHT));
+    let was_focused = state.cutscene_input.focused;
+    if crate::ui::draw_text_area(area_rect, &mut state.cutscene_input, FONT_SIZE_CONTENT, error_line) {
+        // One undo step per editing session
+        if !state.cutscene_undo_saved {
+            state.save_undo();
+            state.cutscene_undo_saved = true;
+        }
+        state.level.cutscenes[sel].timeline = state.cutscene_input.text.clone();
+        state.dirty = true;
+    }
+    if state.cutscene_input.focused {
+        state.active_panel = super::state::ActivePanel::Scripts;
+    } else if was_focused {
+        state.cutscene_undo_saved = false;
+    }
+
+    let status_y = (area_rect.bottom() + 12.0).floor();
+    match validation {
+        Ok(events) => {
+            let text = if events == 1 { "1 event".to_string() } else { format!("{} events", events) };
+            draw_text(&text, x, status_y, FONT_SIZE_CONTENT, Color::from_rgba(100, 200, 100, 255));
+        }
+        Err((line, message)) => {
+            draw_text(&format!("Line {}: {}", line, message), x, status_y, FONT_SIZE_CONTENT, Color::from_rgba(255, 100, 100, 255));
+        }
+    }
+}
+
+/// HUD layout editor: live preview with sample values, click to select an
+/// element, drag to move it, and anchor/offset controls for the selection
+fn draw_hud_panel(ctx: &mut UiContext, rect: Rect, state: &mut EditorState) {
+    use crate::game::hud::{self, HudAnchor, HudElement, HudFrame, HudLayout};
+
+    let x = rect.x.floor();
+    let mut y = rect.y.floor();
+    let btn_h = 16.0;
+    let label_color = Color::from_rgba(150, 150, 160, 255);
+
+    let enabled = state.level.hud.enabled;
+    if crate::ui::text_button(ctx, Rect::new(x, y, 60.0, btn_h), if enabled { "HUD: On" } else { "HUD: Off" }, "Show the HUD in the Game tab") {
+        state.save_undo();
+        state.level.hud.enabled = !enabled;
+    }
+    if crate::ui::text_button(ctx, Rect::new(x + 64.0, y, 50.0, btn_h), "Reset", "Restore the default HUD layout") {
+        state.save_undo();
+        state.level.hud = HudLayout::default();
+    }
+    y += btn_h + 4.0;
+
+    // Preview at the low resolution, drawn scaled to fit
+    let controls_h = btn_h * 3.0 + 12.0;
+    let area = Rect::new(x, y, rect.w - 4.0, (rect.bottom() - y - controls_h).max(60.0));
+    let mut fb = Framebuffer::new(crate::rasterizer::WIDTH, crate::rasterizer::HEIGHT);
+    fb.clear(RasterColor::new(40, 48, 60));
+    hud::draw(&mut fb, &state.level.hud, &HudFrame::sample());
+    let (w, h, ox, oy) = crate::display::fit_scaled(area.w, area.h, fb.width, fb.height, false);
+    let preview = Rect::new(area.x + ox, area.y + oy, w, h);
+    let fb_texture = Texture2D::from_rgba8(fb.width as u16, fb.height as u16, &fb.pixels);
+    fb_texture.set_filter(FilterMode::Nearest);
+    draw_rectangle(preview.x - 1.0, preview.y - 1.0, preview.w + 2.0, preview.h + 2.0, Color::from_rgba(60, 60, 65, 255));
+    draw_texture_ex(&fb_texture, preview.x, preview.y, WHITE, DrawTextureParams { dest_size: Some(vec2(preview.w, preview.h)), ..Default::default() });
+
+    let scale = preview.w / fb.width as f32;
+    for (i, element) in state.level.hud.elements.iter().enumerate() {
+        let (ex, ey, ew, eh) = element.rect(fb.width, fb.height);
+        let color = if state.hud_selected == Some(i) {
+            Color::from_rgba(255, 200, 80, 255)
+        } else if element.visible {
+            Color::from_rgba(120, 120, 130, 160)
+        } else {
+            Color::from_rgba(90, 90, 100, 90)
+        };
+        draw_rectangle_lines(preview.x + ex as f32 * scale, preview.y + ey as f32 * scale, ew as f32 * scale, eh as f32 * scale, 1.0, color);
+    }
+
+    // Select and drag in the preview
+    if ctx.mouse.left_pressed && ctx.mouse.inside(&preview) {
+        let px = ((ctx.mouse.x - preview.x) / scale) as i32;
+        let py = ((ctx.mouse.y - preview.y) / scale) as i32;
+        state.hud_selected = state.level.hud.element_at(px, py, fb.width, fb.height);
+        if let Some(i) = state.hud_selected {
+            state.save_undo();
+            state.hud_drag = Some((ctx.mouse.x, ctx.mouse.y, state.level.hud.elements[i].offset));
+        }
+    }
+    if let (Some((start_x, start_y, start)), Some(i)) = (state.hud_drag, state.hud_selected) {
+        if ctx.mouse.left_down && i < state.level.hud.elements.len() {
+            // Offsets are in 320x240 pixels, whatever the preview size
+            let base = scale * (fb.height / hud::HUD_BASE_HEIGHT).max(1) as f32;
+            let dx = ((ctx.mouse.x - start_x) / base).round() as i32;
+            let dy = ((ctx.mouse.y - start_y) / base).round() as i32;
+            state.level.hud.elements[i].offset = (start.0 + dx, start.1 + dy);
+        } else {
+            state.hud_drag = None;
+        }
+    }
+    y = area.bottom() + 4.0;
+
+    let Some(sel) = state.hud_selected.filter(|&i| i < state.level.hud.elements.len()) else {
+        draw_text("Click an element to edit it", x, (y + 11.0).floor(), FONT_SIZE_CONTENT, label_color);
+        return;
+    };
+
+    // Selected element: name, visibility and offset nudges
+    let element = state.level.hud.elements[sel];
+    draw_text(element.kind.label(), x, (y + 11.0).floor(), FONT_SIZE_CONTENT, WHITE);
+    if crate::ui::text_button(ctx, Rect::new(x + 70.0, y, 44.0, btn_h), if element.visible { "Shown" } else { "Hidden" }, "Show or hide this element") {
+        state.save_undo();
+        state.level.hud.elements[sel].visible = !element.visible;
+    }
+    y += btn_h + 4.0;
+
+    let mut nudge = (0, 0);
+    for (row, (label, axis_value)) in [("X", element.offset.0), ("Y", element.offset.1)].into_iter().enumerate() {
+        let ry = y + row as f32 * (btn_h + 2.0);
+        draw_text(&format!("{} {:>4}", label, axis_value), x, (ry + 11.0).floor(), FONT_SIZE_CONTENT, label_color);
+        let step = if row == 0 { (1, 0) } else { (0, 1) };
+        if crate::ui::text_button(ctx, Rect::new(x + 50.0, ry, 18.0, btn_h), "-", "Move by one pixel") {
+            nudge = (-step.0, -step.1);
+        }
+        if crate::ui::text_button(ctx, Rect::new(x + 70.0, ry, 18.0, btn_h), "+", "Move by one pixel") {
+            nudge = step;
+        }
+    }
+    if nudge != (0, 0) {
+        state.save_undo();
+        let offset = &mut state.level.hud.elements[sel].offset;
+        *offset = (offset.0 + nudge.0, offset.1 + nudge.1);
+    }
+
+    // 3x3 anchor picker
+    let cell = (btn_h * 2.0 + 2.0) / 3.0;
+    let grid_x = x + 100.0;
+    for (i, anchor) in HudAnchor::ALL.into_iter().enumerate() {
+        let cell_rect = Rect::new(grid_x + (i % 3) as f32 * (cell + 1.0), y + (i / 3) as f32 * (cell + 1.0), cell, cell);
+        let active = element.anchor == anchor;
+        let hovered = ctx.mouse.inside(&cell_rect);
+        let fill = if active {
+            Color::from_rgba(255, 200, 80, 255)
+        } else if hovered {
+            Color::from_rgba(90, 90, 100, 255)
+        } else {
+            Color::from_rgba(60, 60, 65, 255)
+        };
+        draw_rectangle(cell_rect.x, cell_rect.y, cell_rect.w, cell_rect.h, fill);
+        if hovered {
+            ctx.set_tooltip(anchor.label(), ctx.mouse.x, ctx.mouse.y);
+        }
+        if ctx.mouse.clicked(&cell_rect) && !active {
+            state.save_undo();
+            // Keep the element where it is on screen, relative to the new anchor
+            let (old_x, old_y, _, _) = element.rect(hud::HUD_BASE_WIDTH, hud::HUD_BASE_HEIGHT);
+            let moved = HudElement { anchor, offset: (0, 0), ..element };
+            let (new_x, new_y, _, _) = moved.rect(hud::HUD_BASE_WIDTH, hud::HUD_BASE_HEIGHT);
+            state.level.hud.elements[sel] = HudElement { offset: (old_x - new_x, old_y - new_y), ..moved };
+        }
+    }
+}
+
+/// Texture pack manager: the level's pack list in priority order, which packs
+/// the faces reference, and bulk remapping of faces when art is replaced
+fn draw_texture_packs_panel(ctx: &mut UiContext, rect: Rect, state: &mut EditorState) {
+    use crate::world::{pack_usage, remap_level_textures, TextureRef, USER_TEXTURE_PACK};
+
+    let x = rect.x.floor();
+    let mut y = rect.y.floor();
+    let w = rect.w - 4.0;
+    let btn_h = 16.0;
+    let label_color = Color::from_rgba(150, 150, 160, 255);
+    let warn_color = Color::from_rgba(255, 150, 80, 255);
+
+    let usage = pack_usage(&state.level);
+    let loaded: Vec<String> = state.texture_packs.iter().map(|p| p.name.clone()).collect();
+    let is_loaded = |name: &str| name == USER_TEXTURE_PACK || loaded.iter().any(|n| n == name);
+    let faces_in = |name: &str| usage.iter().find(|u| u.pack == name).map_or(0, |u| u.faces);
+
+    // Level pack list (empty: every loaded pack)
+    if state.level.texture_packs.is_empty() {
+        draw_text("Using all loaded packs", x, (y + 11.0).floor(), FONT_SIZE_CONTENT, label_color);
+        if crate::ui::text_button(ctx, Rect::new(x + w - 70.0, y, 70.0, btn_h), "Customize", "List the loaded packs so they can be reordered or removed") {
+            state.save_undo();
+            state.level.texture_packs = loaded.clone();
+        }
+    } else {
+        draw_text("Packs, first wins", x, (y + 11.0).floor(), FONT_SIZE_CONTENT, label_color);
+        if crate::ui::text_button(ctx, Rect::new(x + w - 70.0, y, 70.0, btn_h), "Use All", "Clear the list and use every loaded pack") {
+            state.save_undo();
+            state.level.texture_packs.clear();
+        }
+    }
+    y += btn_h + 4.0;
+
+    // (index, move by -1/+1, or 0 to remove)
+    let mut edit: Option<(usize, i32)> = None;
+    for (i, name) in state.level.texture_packs.iter().enumerate() {
+        let color = if is_loaded(name) { WHITE } else { warn_color };
+        draw_text(&format!("{} ({})", name, faces_in(name)), x, (y + 11.0).floor(), FONT_SIZE_CONTENT, color);
+        let bx = x + w - 58.0;
+        if crate::ui::text_button(ctx, Rect::new(bx, y, 18.0, btn_h), "^", "Higher priority") && i > 0 {
+            edit = Some((i, -1));
+        }
+        if crate::ui::text_button(ctx, Rect::new(bx + 20.0, y, 18.0, btn_h), "v", "Lower priority") && i + 1 < state.level.texture_packs.len() {
+            edit = Some((i, 1));
+        }
+        if crate::ui::text_button(ctx, Rect::new(bx + 40.0, y, 18.0, btn_h), "x", "Remove from the level (faces keep their textures)") {
+            edit = Some((i, 0));
+        }
+        y += btn_h + 2.0;
+    }
+    if let Some((i, step)) = edit {
+        state.save_undo();
+        match step {
+            0 => { state.level.texture_packs.remove(i); }
+            _ => state.level.texture_packs.swap(i, (i as i32 + step) as usize),
+        }
+    }
+
+    // Loaded packs that are not listed yet
+    if !state.level.texture_packs.is_empty() {
+        let mut bx = x;
+        let mut add: Option<String> = None;
+        for pack in state.texture_packs.iter().filter(|p| !state.level.texture_packs.contains(&p.name)) {
+            let label = format!("+ {}", pack.name);
+            let bw = measure_text(&label, None, FONT_SIZE_CONTENT as u16, 1.0).width + 10.0;
+            if bx > x && bx + bw > x + w {
+                bx = x;
+                y += btn_h + 2.0;
+            }
+            if crate::ui::text_button(ctx, Rect::new(bx, y, bw, btn_h), &label, "Add to the end of the list") {
+                add = Some(pack.name.clone());
+            }
+            bx += bw + 4.0;
+        }
+        if bx > x {
+            y += btn_h + 2.0;
+        }
+        if let Some(name) = add {
+            state.save_undo();
+            state.level.texture_packs.push(name);
+        }
+    }
+    y += 6.0;
+
+    // Which packs the faces reference; click one to remap from it
+    draw_text("Faces by pack", x, (y + 11.0).floor(), FONT_SIZE_CONTENT, label_color);
+    y += LINE_HEIGHT;
+    let listed = |name: &str| state.level.texture_packs.is_empty() || name == USER_TEXTURE_PACK || state.level.texture_packs.iter().any(|p| p == name);
+    for u in &usage {
+        let row = Rect::new(x, y, w, LINE_HEIGHT);
+        let problem = if !is_loaded(&u.pack) {
+            Some("Pack is not loaded: these faces render as a checkerboard")
+        } else if !listed(&u.pack) {
+            Some("Pack is not in the level's list: these faces render as a checkerboard in game")
+        } else {
+            None
+        };
+        if state.pack_remap_from.pack == u.pack {
+            draw_rectangle(row.x, row.y, row.w, row.h, Color::from_rgba(60, 60, 70, 255));
+        }
+        let rooms = if u.rooms.len() == 1 { "1 room".to_string() } else { format!("{} rooms", u.rooms.len()) };
+        let name = if u.pack == USER_TEXTURE_PACK { "CLUT textures" } else { u.pack.as_str() };
+        draw_text(&format!("{}: {} faces, {}", name, u.faces, rooms), x + 2.0, (y + 12.0).floor(), FONT_SIZE_CONTENT, if problem.is_some() { warn_color } else { WHITE });
+        if ctx.mouse.inside(&row) {
+            ctx.set_tooltip(problem.unwrap_or("Click to remap faces from this pack"), ctx.mouse.x, ctx.mouse.y);
+        }
+        if ctx.mouse.clicked(&row) {
+            state.pack_remap_from = TextureRef::new(u.pack.clone(), String::new());
+        }
+        y += LINE_HEIGHT;
+    }
+    if usage.is_empty() {
+        draw_text("No textured faces", x, (y + 11.0).floor(), FONT_SIZE_CONTENT, label_color);
+        y += LINE_HEIGHT;
+    }
+    y += 6.0;
+
+    // Bulk remap: an empty texture name stands for every texture in the pack
+    let describe = |tex: &TextureRef| -> String {
+        match (tex.pack.is_empty(), tex.name.is_empty()) {
+            (true, _) => "(none)".to_string(),
+            (false, true) => format!("{}/*", tex.pack),
+            (false, false) => format!("{}/{}", tex.pack, tex.name),
+        }
+    };
+    let selected = state.selected_texture.clone();
+    let pick_tooltip = "Use the texture selected in the Textures panel";
+    let pack_tooltip = "Use the whole pack of the texture selected in the Textures panel";
+    for (row, label) in ["From", "To"].into_iter().enumerate() {
+        let current = if row == 0 { &state.pack_remap_from } else { &state.pack_remap_to };
+        draw_text(&format!("{} {}", label, describe(current)), x, (y + 11.0).floor(), FONT_SIZE_CONTENT, label_color);
+        let bx = x + w - 76.0;
+        let mut picked = None;
+        if crate::ui::text_button(ctx, Rect::new(bx, y, 36.0, btn_h), "Tex", pick_tooltip) && selected.is_valid() {
+            picked = Some(selected.clone());
+        }
+        if crate::ui::text_button(ctx, Rect::new(bx + 40.0, y, 36.0, btn_h), "Pack", pack_tooltip) && selected.is_valid() {
+            picked = Some(TextureRef::new(selected.pack.clone(), String::new()));
+        }
+        if let Some(tex) = picked {
+            if row == 0 { state.pack_remap_from = tex } else { state.pack_remap_to = tex }
+        }
+        y += btn_h + 2.0;
+    }
+
+    let (from, to) = (state.pack_remap_from.clone(), state.pack_remap_to.clone());
+    if crate::ui::text_button(ctx, Rect::new(x, y, 90.0, btn_h), "Remap Faces", "Point every matching face at the target (undoable)") {
+        if from.pack.is_empty() || to.pack.is_empty() {
+            state.set_status("Pick a source and a target first", 2.0);
+        } else {
+            let from_name = (!from.name.is_empty()).then_some(from.name.as_str());
+            let to_name = (!to.name.is_empty()).then_some(to.name.as_str());
+            // Remap a copy first so a no-op does not leave an undo step
+            let mut level = state.level.clone();
+            let changed = remap_level_textures(&mut level, &from.pack, from_name, &to.pack, to_name);
+            if changed > 0 {
+                state.save_undo();
+                state.level = level;
+            }
+            state.set_status(&format!("Remapped {} faces", changed), 2.0);
+        }
+    }
+}
+
+fn draw_debug_panel(ctx: &mut UiContext, rect: Rect, state: &mut EditorState) {
+    use macroquad::prelude::*;
+
+    let mut y = rect.y.floor();
+    let x = rect.x.floor();
+    let bar_w = rect.w - 4.0;
+
+    // Colors for timing breakdown
+    let label_color = Color::from_rgba(150, 150, 160, 255);
+    let value_color = Color::from_rgba(200, 200, 210, 255);
+    let toolbar_color = Color::from_rgba(100, 180, 255, 255);   // Blue
+    let left_color = Color::from_rgba(180, 100, 255, 255);      // Purple
+    let viewport_color = Color::from_rgba(255, 100, 100, 255);  // Red
+    let right_color = Color::from_rgba(255, 200, 100, 255);     // Orange
+    let status_color = Color::from_rgba(100, 255, 180, 255);    // Cyan
+
+    // 3D viewport sub-timing colors (shades of red)
+    let vp_input_color = Color::from_rgba(255, 180, 180, 255);   // Light red
+    let vp_clear_color = Color::from_rgba(255, 150, 150, 255);   // Light-mid red
+    let vp_grid_color = Color::from_rgba(255, 120, 120, 255);    // Mid red
+    let vp_lights_color = Color::from_rgba(240, 100, 100, 255);  // Mid-dark red
+    let vp_texconv_color = Color::from_rgba(220, 80, 80, 255);   // Dark red
+    let vp_meshgen_color = Color::from_rgba(200, 60, 60, 255);   // Darker red
+    let vp_raster_color = Color::from_rgba(180, 40, 40, 255);    // Darkest red
+    let vp_preview_color = Color::from_rgba(255, 100, 150, 255); // Red-pink
+    let vp_upload_color = Color::from_rgba(255, 130, 100, 255);  // Red-orange
+
+    y = draw_budget_section(ctx, x, y, bar_w, state);
+    y = draw_portal_section(ctx, x, y, bar_w, state);
+
+    // FPS and frame time
+    let fps = get_fps();
     let frame_time_ms = get_frame_time() * 1000.0;
 
     let fps_color = if fps >= 55 {
@@ -3011,6 +4245,242 @@ fn draw_debug_panel(_ctx: &mut UiContext, rect: Rect, state: &mut EditorState) {
     let _ = y; // suppress unused warning
 }
 
+/// Content budget preset toggle and the report from the last save check.
+/// Returns the y below the section.
+fn draw_budget_section(ctx: &mut UiContext, x: f32, mut y: f32, w: f32, state: &mut EditorState) -> f32 {
+    use macroquad::prelude::*;
+
+    let label_color = Color::from_rgba(150, 150, 160, 255);
+    let value_color = Color::from_rgba(200, 200, 210, 255);
+    let ok_color = Color::from_rgba(100, 255, 100, 255);
+    let over_color = Color::from_rgba(255, 100, 100, 255);
+
+    // Preset row: click to toggle between Off and Strict PS1
+    let preset = state.level.budget;
+    let row = crate::ui::Rect::new(x, y, w, LINE_HEIGHT);
+    if ctx.mouse.inside(&row) {
+        draw_rectangle(row.x, row.y, row.w, row.h, Color::from_rgba(50, 50, 60, 255));
+    }
+    draw_text("Budget:", x, y + 10.0, FONT_SIZE_CONTENT, label_color);
+    let preset_w = preset.label().len() as f32 * 6.0;
+    draw_text(preset.label(), x + w - preset_w, y + 10.0, FONT_SIZE_CONTENT, value_color);
+    if ctx.mouse.clicked(&row) {
+        state.save_undo();
+        state.level.budget = match preset {
+            BudgetPreset::Off => BudgetPreset::StrictPs1,
+            BudgetPreset::StrictPs1 => BudgetPreset::Off,
+        };
+        // Voice count comes from the tracker on save; keep the last one
+        let voices = state.budget_report.as_ref().map(|r| r.usage.audio_voices).unwrap_or(0);
+        state.check_budget(voices);
+    }
+    y += LINE_HEIGHT;
+
+    let (Some(limits), Some(report)) = (state.level.budget.limits(), &state.budget_report) else {
+        if !state.level.budget.is_off() {
+            draw_text("Checked on save", x + 8.0, y + 10.0, FONT_SIZE_CONTENT, label_color);
+            y += LINE_HEIGHT;
+        }
+        return y + 8.0;
+    };
+
+    let u = &report.usage;
+    let rows = [
+        (
+            "VRAM",
+            format!("{}/{} KB", u.texture_bytes.div_ceil(1024), limits.texture_bytes / 1024),
+            u.texture_bytes <= limits.texture_bytes,
+        ),
+        (
+            "Tris/frame",
+            format!("{}/{}", u.peak_triangles, limits.triangles_per_frame),
+            u.peak_triangles <= limits.triangles_per_frame,
+        ),
+        (
+            "Voices",
+            format!("{}/{}", u.audio_voices, limits.audio_voices),
+            u.audio_voices <= limits.audio_voices,
+        ),
+    ];
+    for (name, value, ok) in rows.iter() {
+        draw_text(name, x + 8.0, y + 10.0, FONT_SIZE_CONTENT, label_color);
+        let value_w = value.len() as f32 * 6.0;
+        draw_text(value, x + w - value_w, y + 10.0, FONT_SIZE_CONTENT, if *ok { ok_color } else { over_color });
+        y += LINE_HEIGHT;
+    }
+
+    // Violations, wrapped to the panel width
+    for violation in &report.violations {
+        for line in wrap_words(&violation.to_string(), w - 8.0) {
+            draw_text(&line, x + 8.0, y + 10.0, FONT_SIZE_CONTENT, over_color);
+            y += LINE_HEIGHT;
+        }
+    }
+
+    y + 8.0
+}
+
+/// Split `text` into lines that fit `width` pixels of panel text
+fn wrap_words(text: &str, width: f32) -> Vec<String> {
+    let max_chars = (width / 6.0).max(8.0) as usize;
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split(' ') {
+        if !line.is_empty() && line.len() + 1 + word.len() > max_chars {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Room connection issues from the portal debug check (only while the
+/// portal debug view is on). Clicking an issue selects its room.
+/// Returns the y below the section.
+fn draw_portal_section(ctx: &mut UiContext, x: f32, mut y: f32, w: f32, state: &mut EditorState) -> f32 {
+    use macroquad::prelude::*;
+
+    if !state.portal_debug {
+        return y;
+    }
+    let label_color = Color::from_rgba(150, 150, 160, 255);
+    let ok_color = Color::from_rgba(100, 255, 100, 255);
+    let over_color = Color::from_rgba(255, 100, 100, 255);
+
+    draw_text("Portals:", x, y + 10.0, FONT_SIZE_CONTENT, label_color);
+    let summary = match state.portal_issues.len() {
+        0 => "OK".to_string(),
+        1 => "1 issue".to_string(),
+        n => format!("{} issues", n),
+    };
+    let summary_w = summary.len() as f32 * 6.0;
+    let summary_color = if state.portal_issues.is_empty() { ok_color } else { over_color };
+    draw_text(&summary, x + w - summary_w, y + 10.0, FONT_SIZE_CONTENT, summary_color);
+    y += LINE_HEIGHT;
+
+    let mut clicked_room = None;
+    for issue in &state.portal_issues {
+        let lines = wrap_words(&issue.to_string(), w - 8.0);
+        let row = crate::ui::Rect::new(x, y, w, LINE_HEIGHT * lines.len() as f32);
+        if ctx.mouse.inside(&row) {
+            draw_rectangle(row.x, row.y, row.w, row.h, Color::from_rgba(50, 50, 60, 255));
+        }
+        if ctx.mouse.clicked(&row) {
+            clicked_room = Some(issue.room());
+        }
+        for line in lines {
+            draw_text(&line, x + 8.0, y + 10.0, FONT_SIZE_CONTENT, over_color);
+            y += LINE_HEIGHT;
+        }
+    }
+    if let Some(room) = clicked_room.filter(|&r| r < state.level.rooms.len()) {
+        state.current_room = room;
+    }
+
+    y + 8.0
+}
+
+/// Terrain import row plus its settings (cell size, height scale, room size).
+/// Returns the y below the section.
+#[cfg(not(target_arch = "wasm32"))]
+fn draw_heightmap_import(ctx: &mut UiContext, x: f32, mut y: f32, w: f32, state: &mut EditorState, icon_font: Option<&Font>) -> f32 {
+    let icon_btn_size = 14.0;
+    let label_color = Color::from_rgba(150, 150, 150, 255);
+
+    let import_rect = Rect::new(x, y + 2.0, icon_btn_size, icon_btn_size);
+    if crate::ui::icon_button(ctx, import_rect, icon::GLOBE, icon_font, "Import heightmap as terrain rooms (uses the selected texture)") {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Heightmap", &["png", "bmp", "jpg", "jpeg"])
+            .pick_file()
+        {
+            let result = std::fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| state.import_heightmap(&bytes));
+            match result {
+                Ok(count) => state.set_status(&format!("Imported terrain: {} rooms", count), 3.0),
+                Err(e) => state.set_status(&format!("Heightmap import failed: {}", e), 4.0),
+            }
+        }
+    }
+    draw_text("Import Heightmap", (x + icon_btn_size + 4.0).floor(), (y + 12.0).floor(), FONT_SIZE_CONTENT, label_color);
+    y += LINE_HEIGHT;
+
+    // Settings: label, value, then -/+ buttons on the right
+    let settings = &mut state.heightmap_settings;
+    let rows: [(&str, String, &str); 3] = [
+        ("Cell", format!("{} px", settings.cell_pixels), "Heightmap pixels per sector"),
+        ("Height", format!("{:.0}", settings.height_scale), "World height of white pixels"),
+        ("Room", format!("{} sectors", settings.room_size), "Sectors per terrain room side"),
+    ];
+    for (i, (label, value, tooltip)) in rows.iter().enumerate() {
+        draw_text(label, x + 8.0, (y + 12.0).floor(), FONT_SIZE_CONTENT, label_color);
+        draw_text(value, x + 56.0, (y + 12.0).floor(), FONT_SIZE_CONTENT, WHITE);
+        let plus_rect = Rect::new(x + w - icon_btn_size - 4.0, y + 2.0, icon_btn_size, icon_btn_size);
+        let minus_rect = Rect::new(plus_rect.x - icon_btn_size - 2.0, y + 2.0, icon_btn_size, icon_btn_size);
+        let minus = crate::ui::icon_button(ctx, minus_rect, icon::MINUS, icon_font, tooltip);
+        let plus = crate::ui::icon_button(ctx, plus_rect, icon::PLUS, icon_font, tooltip);
+        let step = plus as i32 - minus as i32;
+        if step != 0 {
+            match i {
+                0 => settings.cell_pixels = (settings.cell_pixels as i32 + step).clamp(1, 64) as usize,
+                1 => settings.height_scale = (settings.height_scale + step as f32 * 1024.0).clamp(1024.0, 65536.0),
+                _ => settings.room_size = (settings.room_size as i32 + step * 4).clamp(4, 64) as usize,
+            }
+        }
+        y += LINE_HEIGHT;
+    }
+
+    y
+}
+
+/// Quake .map import row plus its grid setting. Returns the y below the section.
+#[cfg(not(target_arch = "wasm32"))]
+fn draw_quake_map_import(ctx: &mut UiContext, x: f32, mut y: f32, w: f32, state: &mut EditorState, icon_font: Option<&Font>) -> f32 {
+    let icon_btn_size = 14.0;
+    let label_color = Color::from_rgba(150, 150, 150, 255);
+
+    let import_rect = Rect::new(x, y + 2.0, icon_btn_size, icon_btn_size);
+    if crate::ui::icon_button(ctx, import_rect, icon::BOX, icon_font, "Import Quake/TrenchBroom .map brushes as rooms") {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Quake map", &["map"])
+            .pick_file()
+        {
+            let result = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| state.import_quake_map(&text));
+            match result {
+                Ok(count) => state.set_status(&format!("Imported map: {} rooms", count), 3.0),
+                Err(e) => state.set_status(&format!("Map import failed: {}", e), 4.0),
+            }
+        }
+    }
+    draw_text("Import .map", (x + icon_btn_size + 4.0).floor(), (y + 12.0).floor(), FONT_SIZE_CONTENT, label_color);
+    y += LINE_HEIGHT;
+
+    // Grid: Quake units per sector, doubled/halved with -/+
+    let settings = &mut state.quake_map_settings;
+    draw_text("Grid", x + 8.0, (y + 12.0).floor(), FONT_SIZE_CONTENT, label_color);
+    draw_text(&format!("{:.0} units", settings.units_per_sector), x + 56.0, (y + 12.0).floor(), FONT_SIZE_CONTENT, WHITE);
+    let plus_rect = Rect::new(x + w - icon_btn_size - 4.0, y + 2.0, icon_btn_size, icon_btn_size);
+    let minus_rect = Rect::new(plus_rect.x - icon_btn_size - 2.0, y + 2.0, icon_btn_size, icon_btn_size);
+    let tooltip = "Quake units per sector";
+    if crate::ui::icon_button(ctx, minus_rect, icon::MINUS, icon_font, tooltip) {
+        settings.units_per_sector = (settings.units_per_sector / 2.0).max(16.0);
+    }
+    if crate::ui::icon_button(ctx, plus_rect, icon::PLUS, icon_font, tooltip) {
+        settings.units_per_sector = (settings.units_per_sector * 2.0).min(256.0);
+    }
+    y += LINE_HEIGHT;
+
+    y
+}
+
 fn draw_room_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState, icon_font: Option<&Font>) {
     let mut y = rect.y.floor();
     let x = rect.x.floor();
@@ -3134,6 +4604,13 @@ fn draw_room_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
     draw_text("Add Room", (x + icon_btn_size + 4.0).floor(), (y + 12.0).floor(), FONT_SIZE_CONTENT, Color::from_rgba(150, 150, 150, 255));
     y += LINE_HEIGHT;
 
+    // Heightmap terrain import (native only - needs a file dialog)
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        y = draw_heightmap_import(ctx, x, y, rect.w, state, icon_font);
+        y = draw_quake_map_import(ctx, x, y, rect.w, state, icon_font);
+    }
+
     // Separator line
     y += 6.0;
     draw_line(x, y, x + rect.w - 4.0, y, 1.0, Color::from_rgba(60, 60, 70, 255));
@@ -3155,7 +4632,9 @@ fn draw_room_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
             room.iter_sectors().count(),
             room.portals.len(),
             light_count,
+            room.has_baked_lighting(),
             room.ambient,
+            room.ambient_color,
             room.fog.enabled,
             room.fog.color,
             room.fog.start,
@@ -3164,7 +4643,7 @@ fn draw_room_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
         )
     });
 
-    if let Some((position, width, depth, sector_count, portal_count, light_count, ambient, fog_enabled, fog_color, fog_start, fog_falloff, fog_cull_offset)) = room_data {
+    if let Some((position, width, depth, sector_count, portal_count, light_count, lighting_baked, ambient, ambient_color, fog_enabled, fog_color, fog_start, fog_falloff, fog_cull_offset)) = room_data {
         // Section header
         draw_text("Properties", x, (y + 10.0).floor(), FONT_SIZE_HEADER, Color::from_rgba(150, 150, 150, 255));
         y += LINE_HEIGHT;
@@ -3184,9 +4663,114 @@ fn draw_room_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
         draw_text(&format!("Portals: {}", portal_count), x, (y + 10.0).floor(), FONT_SIZE_CONTENT, WHITE);
         y += LINE_HEIGHT;
 
-        draw_text(&format!("Lights: {}", light_count), x, (y + 10.0).floor(), FONT_SIZE_CONTENT, WHITE);
+        let baked_label = if lighting_baked { " (baked)" } else { "" };
+        draw_text(&format!("Lights: {}{}", light_count, baked_label), x, (y + 10.0).floor(), FONT_SIZE_CONTENT, WHITE);
+        y += LINE_HEIGHT;
+
+        // Outdoor toggle: the level's weather falls in outdoor rooms
+        let outdoor = state.level.rooms.get(state.current_room).map(|r| r.outdoor).unwrap_or(false);
+        let outdoor_rect = Rect::new(x, y, 12.0, 12.0);
+        draw_rectangle(outdoor_rect.x, outdoor_rect.y, outdoor_rect.w, outdoor_rect.h, Color::new(0.2, 0.2, 0.25, 1.0));
+        if outdoor {
+            draw_rectangle(outdoor_rect.x + 2.0, outdoor_rect.y + 2.0, 8.0, 8.0, Color::new(0.4, 0.8, 1.0, 1.0));
+        }
+        draw_text("Outdoor (weather)", x + 18.0, y + 10.0, 11.0, Color::new(0.8, 0.8, 0.8, 1.0));
+        if ctx.mouse.inside(&outdoor_rect) && ctx.mouse.left_pressed {
+            if let Some(room) = state.level.rooms.get_mut(state.current_room) {
+                room.outdoor = !room.outdoor;
+            }
+        }
+        y += LINE_HEIGHT;
+
+        // Reverb preset for sounds heard in this room (see `game::spatial`)
+        let preset_count = crate::tracker::ReverbType::ALL.len() as u8;
+        let reverb = state.level.rooms.get(state.current_room).map_or(0, |r| r.reverb).min(preset_count - 1);
+        let reverb_name = crate::tracker::ReverbType::from_index(reverb).name();
+        draw_text(&format!("Reverb: {}", reverb_name), x, y + 10.0, 11.0, Color::new(0.8, 0.8, 0.8, 1.0));
+        let mut step = None;
+        if crate::ui::text_button(ctx, Rect::new(rect.right() - 40.0, y - 1.0, 16.0, 14.0), "<", "Previous reverb preset") {
+            step = Some((reverb + preset_count - 1) % preset_count);
+        }
+        if crate::ui::text_button(ctx, Rect::new(rect.right() - 22.0, y - 1.0, 16.0, 14.0), ">", "Next reverb preset") {
+            step = Some((reverb + 1) % preset_count);
+        }
+        if let Some(next) = step {
+            state.save_undo();
+            if let Some(room) = state.level.rooms.get_mut(state.current_room) {
+                room.reverb = next;
+            }
+        }
+        y += LINE_HEIGHT;
+
+        // Wet level of that reverb, faded in as the player enters the room
+        if reverb > 0 {
+            let wet = state.level.rooms.get(state.current_room).map_or(1.0, |r| r.reverb_wet);
+            let wet_percent = (wet * 100.0).round() as i32;
+            draw_text(&format!("Wet: {}%", wet_percent), x + 18.0, y + 10.0, 11.0, Color::new(0.8, 0.8, 0.8, 1.0));
+            let mut change = 0;
+            if crate::ui::text_button(ctx, Rect::new(rect.right() - 40.0, y - 1.0, 16.0, 14.0), "-", "Less reverb in this room") {
+                change = -10;
+            }
+            if crate::ui::text_button(ctx, Rect::new(rect.right() - 22.0, y - 1.0, 16.0, 14.0), "+", "More reverb in this room") {
+                change = 10;
+            }
+            if change != 0 {
+                state.save_undo();
+                if let Some(room) = state.level.rooms.get_mut(state.current_room) {
+                    room.reverb_wet = (wet_percent + change).clamp(0, 100) as f32 / 100.0;
+                }
+            }
+            y += LINE_HEIGHT;
+        }
+
+        // Water: a flooded height range the player swims in (see `world::water`)
+        let water = state.level.rooms.get(state.current_room).and_then(|r| r.water.clone());
+        let water_rect = Rect::new(x, y, 12.0, 12.0);
+        draw_rectangle(water_rect.x, water_rect.y, water_rect.w, water_rect.h, Color::new(0.2, 0.2, 0.25, 1.0));
+        if water.is_some() {
+            draw_rectangle(water_rect.x + 2.0, water_rect.y + 2.0, 8.0, 8.0, Color::new(0.4, 0.8, 1.0, 1.0));
+        }
+        draw_text("Water (swimming)", x + 18.0, y + 10.0, 11.0, Color::new(0.8, 0.8, 0.8, 1.0));
+        if ctx.mouse.inside(&water_rect) && ctx.mouse.left_pressed {
+            state.save_undo();
+            if let Some(room) = state.level.rooms.get_mut(state.current_room) {
+                room.water = match room.water {
+                    Some(_) => None,
+                    None => {
+                        let floor = room.bounds.min.y;
+                        Some(crate::world::RoomWater::new(floor + crate::world::WATER_DEFAULT_DEPTH, floor))
+                    }
+                };
+            }
+        }
         y += LINE_HEIGHT;
 
+        if let Some(water) = water {
+            // Surface and bottom heights, a quarter sector per step
+            let step_size = crate::world::TRIGGER_SNAP;
+            for (label, value, is_surface) in [("Surface", water.surface, true), ("Bottom", water.bottom, false)] {
+                draw_text(&format!("{}: {:.0}", label, value), x + 18.0, y + 10.0, 11.0, Color::new(0.8, 0.8, 0.8, 1.0));
+                let mut change = 0.0;
+                if crate::ui::text_button(ctx, Rect::new(rect.right() - 40.0, y - 1.0, 16.0, 14.0), "-", &format!("Lower the water {}", label.to_lowercase())) {
+                    change = -step_size;
+                }
+                if crate::ui::text_button(ctx, Rect::new(rect.right() - 22.0, y - 1.0, 16.0, 14.0), "+", &format!("Raise the water {}", label.to_lowercase())) {
+                    change = step_size;
+                }
+                if change != 0.0 {
+                    state.save_undo();
+                    if let Some(water) = state.level.rooms.get_mut(state.current_room).and_then(|r| r.water.as_mut()) {
+                        if is_surface {
+                            water.surface = (water.surface + change).max(water.bottom);
+                        } else {
+                            water.bottom = (water.bottom + change).min(water.surface);
+                        }
+                    }
+                }
+                y += LINE_HEIGHT;
+            }
+        }
+
         // Ambient light slider (0-31 display, maps to 0.0-1.0 internally)
         y += 8.0;
         let slider_height = 12.0;
@@ -3246,6 +4830,35 @@ fn draw_room_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState
             state.ambient_slider_active = false;
         }
 
+        // Ambient color RGB sliders (tint the ambient level)
+        let channel_colors = [
+            Color::new(1.0, 0.3, 0.3, 1.0),
+            Color::new(0.3, 1.0, 0.3, 1.0),
+            Color::new(0.3, 0.3, 1.0, 1.0),
+        ];
+        let channels = [ambient_color.0, ambient_color.1, ambient_color.2];
+        for (i, label) in ["R", "G", "B"].into_iter().enumerate() {
+            y += LINE_HEIGHT - 4.0;
+            draw_text(label, x + 4.0, y + slider_height - 2.0, 10.0, channel_colors[i]);
+            let track = Rect::new(slider_x, y, slider_width, slider_height);
+            draw_rectangle(track.x, track.y, track.w, track.h, track_bg);
+            let fill = channels[i] * track.w;
+            draw_rectangle(track.x, track.y, fill, track.h, channel_colors[i]);
+            draw_rectangle(track.x + fill - 1.0, track.y, 3.0, track.h, WHITE);
+            draw_text(&format!("{:.0}", channels[i] * 31.0), slider_x + slider_width + 4.0, y + slider_height - 2.0, 10.0, text_color);
+
+            if ctx.mouse.inside(&track) && ctx.mouse.left_down {
+                let value = ((ctx.mouse.x - track.x) / track.w).clamp(0.0, 1.0);
+                if let Some(room) = state.level.rooms.get_mut(state.current_room) {
+                    match i {
+                        0 => room.ambient_color.0 = value,
+                        1 => room.ambient_color.1 = value,
+                        _ => room.ambient_color.2 = value,
+                    }
+                }
+            }
+        }
+
         // === FOG SETTINGS (PS1-style depth cueing) ===
         y += LINE_HEIGHT + 4.0;
         draw_text("Fog (Depth Cueing)", x, (y + 10.0).floor(), FONT_SIZE_CONTENT, WHITE);
@@ -3492,6 +5105,79 @@ fn apply_normal_mode_to_face(
     }
 }
 
+/// Blend modes offered for level faces, with their toggle labels
+const FACE_BLEND_MODES: [(BlendMode, &str); 4] = [
+    (BlendMode::Opaque, "Opaque"),
+    (BlendMode::Average, "Alpha"),
+    (BlendMode::Add, "Add"),
+    (BlendMode::Subtract, "Sub"),
+];
+
+/// Draw the blend mode toggle for a face; returns the mode picked, if any
+fn draw_face_blend_toggle(ctx: &mut UiContext, rect: Rect, current: BlendMode) -> Option<BlendMode> {
+    let labels = FACE_BLEND_MODES.map(|(_, label)| label);
+    // Modes not on offer (set by hand in the file) highlight nothing
+    let selected = FACE_BLEND_MODES.iter().position(|(mode, _)| *mode == current).unwrap_or(usize::MAX);
+    crate::ui::draw_pill_toggle(ctx, rect, &labels, selected).map(|i| FACE_BLEND_MODES[i].0)
+}
+
+/// Apply blend_mode to a face within a sector
+fn apply_blend_mode_to_face(
+    level: &mut crate::world::Level,
+    room: usize,
+    x: usize,
+    z: usize,
+    face: &SectorFace,
+    mode: BlendMode,
+) {
+    if let Some(r) = level.rooms.get_mut(room) {
+        if let Some(s) = r.get_sector_mut(x, z) {
+            match face {
+                SectorFace::Floor => {
+                    if let Some(f) = &mut s.floor {
+                        f.blend_mode = mode;
+                    }
+                }
+                SectorFace::Ceiling => {
+                    if let Some(c) = &mut s.ceiling {
+                        c.blend_mode = mode;
+                    }
+                }
+                SectorFace::WallNorth(i) => {
+                    if let Some(w) = s.walls_north.get_mut(*i) {
+                        w.blend_mode = mode;
+                    }
+                }
+                SectorFace::WallEast(i) => {
+                    if let Some(w) = s.walls_east.get_mut(*i) {
+                        w.blend_mode = mode;
+                    }
+                }
+                SectorFace::WallSouth(i) => {
+                    if let Some(w) = s.walls_south.get_mut(*i) {
+                        w.blend_mode = mode;
+                    }
+                }
+                SectorFace::WallWest(i) => {
+                    if let Some(w) = s.walls_west.get_mut(*i) {
+                        w.blend_mode = mode;
+                    }
+                }
+                SectorFace::WallNwSe(i) => {
+                    if let Some(w) = s.walls_nwse.get_mut(*i) {
+                        w.blend_mode = mode;
+                    }
+                }
+                SectorFace::WallNeSw(i) => {
+                    if let Some(w) = s.walls_nesw.get_mut(*i) {
+                        w.blend_mode = mode;
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Apply black_transparent to a face within a sector
 fn apply_black_transparent_to_face(
     level: &mut crate::world::Level,
@@ -3549,6 +5235,62 @@ fn apply_black_transparent_to_face(
     }
 }
 
+/// Set the mirror flag of a face within a sector
+fn apply_mirror_to_face(
+    level: &mut crate::world::Level,
+    room: usize,
+    x: usize,
+    z: usize,
+    face: &SectorFace,
+    value: bool,
+) {
+    let Some(s) = level.rooms.get_mut(room).and_then(|r| r.get_sector_mut(x, z)) else {
+        return;
+    };
+    match face {
+        SectorFace::Floor => if let Some(f) = &mut s.floor { f.mirror = value; },
+        SectorFace::Ceiling => if let Some(c) = &mut s.ceiling { c.mirror = value; },
+        SectorFace::WallNorth(i) => if let Some(w) = s.walls_north.get_mut(*i) { w.mirror = value; },
+        SectorFace::WallEast(i) => if let Some(w) = s.walls_east.get_mut(*i) { w.mirror = value; },
+        SectorFace::WallSouth(i) => if let Some(w) = s.walls_south.get_mut(*i) { w.mirror = value; },
+        SectorFace::WallWest(i) => if let Some(w) = s.walls_west.get_mut(*i) { w.mirror = value; },
+        SectorFace::WallNwSe(i) => if let Some(w) = s.walls_nwse.get_mut(*i) { w.mirror = value; },
+        SectorFace::WallNeSw(i) => if let Some(w) = s.walls_nesw.get_mut(*i) { w.mirror = value; },
+    }
+}
+
+/// Mirror toggle next to the Black toggle. Applies to the primary face and to
+/// multi-selected faces accepted by `matches`. Warns when the level goes over
+/// the mirror budget.
+fn draw_mirror_toggle(
+    ctx: &mut UiContext,
+    state: &mut EditorState,
+    btn_rect: Rect,
+    icon_font: Option<&Font>,
+    (room_idx, gx, gz, primary_face, is_mirror): (usize, usize, usize, SectorFace, bool),
+    matches: impl Fn(&SectorFace) -> bool,
+) {
+    let tooltip = if is_mirror { "Mirror (click to make a normal face)" } else { "Make mirror (reflects the room while playing)" };
+    if crate::ui::icon_button_active(ctx, btn_rect, icon::FLIP_HORIZONTAL, icon_font, tooltip, is_mirror) {
+        state.save_undo();
+        let new_value = !is_mirror;
+        apply_mirror_to_face(&mut state.level, room_idx, gx, gz, &primary_face, new_value);
+        for sel in state.multi_selection.clone() {
+            if let Selection::SectorFace { room, x, z, face } = sel {
+                if matches(&face) {
+                    apply_mirror_to_face(&mut state.level, room, x, z, &face, new_value);
+                }
+            }
+        }
+        let mirrors = state.level.mirror_count();
+        if new_value && mirrors > crate::world::MIRROR_BUDGET {
+            state.set_status(&format!("{} mirrors in level: only the nearest one renders", mirrors), 3.0);
+        }
+    }
+    draw_text("Mirror", (btn_rect.right() + 4.0).floor(), (btn_rect.y + 12.0).floor(), 11.0,
+        Color::from_rgba(120, 120, 120, 255));
+}
+
 /// Apply vertex colors to a face within a sector
 fn apply_vertex_colors_to_face(
     level: &mut crate::world::Level,
@@ -3648,6 +5390,7 @@ fn horizontal_face_container_height(face: &crate::world::HorizontalFace, is_floo
     let uv_controls_height = 80.0; // X offset + Y offset + scale row + angle row (4 rows × 20px)
     let color_picker_height = ps1_color_picker_height() + 54.0; // PS1 color picker widget
     let normal_mode_height = 40.0; // Label + 3-way toggle
+    let blend_mode_height = 44.0; // Label + blend mode toggle
     let split_diagram_height = 50.0; // Mini split diagram with toggle
     let triangle_textures_height = 40.0; // Dual texture slots with link toggle
     let extrude_button_height = if is_floor { 56.0 } else { 0.0 }; // Extrude button only for floors
@@ -3657,7 +5400,7 @@ fn horizontal_face_container_height(face: &crate::world::HorizontalFace, is_floo
     let lines = 1; // walkable only (height moved to link row)
     // Add space for UV coordinates, controls, buttons, color, color picker, normal mode, split diagram, triangle textures, and extrude
     let uv_lines = 1; // Just coordinates
-    header_height + CONTAINER_PADDING * 2.0 + (lines as f32) * line_height + (uv_lines as f32) * line_height + uv_controls_height + button_row_height + color_row_height + color_picker_height + normal_mode_height + split_diagram_height + triangle_textures_height + height_link_row + height_controls_height + extrude_button_height
+    header_height + CONTAINER_PADDING * 2.0 + (lines as f32) * line_height + (uv_lines as f32) * line_height + uv_controls_height + button_row_height + color_row_height + color_picker_height + normal_mode_height + blend_mode_height + split_diagram_height + triangle_textures_height + height_link_row + height_controls_height + extrude_button_height
 }
 
 /// Calculate height needed for a wall face container
@@ -3669,10 +5412,11 @@ fn wall_face_container_height(_wall: &crate::world::VerticalFace) -> f32 {
     let uv_controls_height = 80.0; // X offset + Y offset + scale row + angle row (4 rows × 20px)
     let color_picker_height = ps1_color_picker_height() + 54.0; // PS1 color picker widget
     let normal_mode_height = 40.0; // Label + 3-way toggle
-    let lines = 3; // texture, y range, blend
-    // Add space for UV coordinates, controls, buttons, color, color picker, and normal mode
+    let blend_mode_height = 44.0; // Label + blend mode toggle
+    let lines = 2; // texture, y range
+    // Add space for UV coordinates, controls, buttons, color, color picker, normal mode, and blend mode
     let uv_lines = 1; // Just coordinates
-    header_height + CONTAINER_PADDING * 2.0 + (lines as f32) * line_height + (uv_lines as f32) * line_height + uv_controls_height + button_row_height + color_row_height + color_picker_height + normal_mode_height
+    header_height + CONTAINER_PADDING * 2.0 + (lines as f32) * line_height + (uv_lines as f32) * line_height + uv_controls_height + button_row_height + color_row_height + color_picker_height + normal_mode_height + blend_mode_height
 }
 
 /// Draw properties for a horizontal face inside a container
@@ -4328,6 +6072,31 @@ fn draw_horizontal_face_container(
     }
     content_y += 28.0;
 
+    // Blend mode (PS1 semi-transparency)
+    draw_text("Blend", content_x.floor(), (content_y + 12.0).floor(), 12.0, Color::from_rgba(150, 150, 150, 255));
+    content_y += 16.0;
+
+    let blend_rect = Rect::new(content_x, content_y, width - CONTAINER_PADDING * 2.0, 24.0);
+    if let Some(mode) = draw_face_blend_toggle(ctx, blend_rect, face.blend_mode) {
+        state.save_undo();
+        // Apply to primary selection
+        let primary_face = if is_floor { SectorFace::Floor } else { SectorFace::Ceiling };
+        apply_blend_mode_to_face(&mut state.level, room_idx, gx, gz, &primary_face, mode);
+        // Apply to multi-selection (only matching face types: floors or ceilings)
+        for sel in state.multi_selection.clone() {
+            if let Selection::SectorFace { room, x, z, face } = sel {
+                let is_matching = match (&face, is_floor) {
+                    (SectorFace::Floor, true) | (SectorFace::Ceiling, false) => true,
+                    _ => false,
+                };
+                if is_matching {
+                    apply_blend_mode_to_face(&mut state.level, room, x, z, &face, mode);
+                }
+            }
+        }
+    }
+    content_y += 28.0;
+
     // Black transparent toggle (PS1 CLUT-style transparency) - icon button
     draw_text("Black", content_x.floor(), (content_y + 12.0).floor(), 12.0, Color::from_rgba(150, 150, 150, 255));
 
@@ -4361,6 +6130,13 @@ fn draw_horizontal_face_container(
     let state_text = if face.black_transparent { "Transparent" } else { "Visible" };
     draw_text(state_text, (btn_x + btn_size + 6.0).floor(), (content_y + 12.0).floor(), 11.0, Color::from_rgba(120, 120, 120, 255));
 
+    // Mirror toggle on the same row
+    let primary_face = if is_floor { SectorFace::Floor } else { SectorFace::Ceiling };
+    let mirror_rect = Rect::new(btn_x + 100.0, content_y, btn_size, btn_size);
+    draw_mirror_toggle(ctx, state, mirror_rect, icon_font, (room_idx, gx, gz, primary_face, face.mirror), |f| {
+        matches!((f, is_floor), (SectorFace::Floor, true) | (SectorFace::Ceiling, false))
+    });
+
     // Extrude button (only for floors)
     if is_floor {
         content_y += 32.0;
@@ -4813,10 +6589,6 @@ fn draw_wall_face_container(
     draw_text(&format!("Y Range: {:.0} - {:.0}", wall.y_bottom(), wall.y_top()), content_x.floor(), (content_y + 12.0).floor(), 13.0, WHITE);
     content_y += line_height;
 
-    // Blend mode
-    draw_text(&format!("Blend: {:?}", wall.blend_mode), content_x.floor(), (content_y + 12.0).floor(), 13.0, Color::from_rgba(150, 150, 150, 255));
-    content_y += line_height;
-
     // UV coordinates display (scaled by UV_SCALE)
     let uv = wall.uv.unwrap_or([
         crate::rasterizer::Vec2::new(0.0, UV_SCALE),       // bottom-left
@@ -5140,24 +6912,49 @@ fn draw_wall_face_container(
     };
     if let Some(new_mode) = crate::ui::draw_three_way_toggle(ctx, toggle_rect, ["Front", "Both", "Back"], current_mode) {
         state.save_undo();
-        let mode = match new_mode {
-            0 => crate::world::FaceNormalMode::Front,
-            1 => crate::world::FaceNormalMode::Both,
-            _ => crate::world::FaceNormalMode::Back,
-        };
+        let mode = match new_mode {
+            0 => crate::world::FaceNormalMode::Front,
+            1 => crate::world::FaceNormalMode::Both,
+            _ => crate::world::FaceNormalMode::Back,
+        };
+        // Apply to primary selection
+        apply_normal_mode_to_face(&mut state.level, room_idx, gx, gz, &wall_face, mode);
+        // Apply to multi-selection (only wall faces)
+        for sel in state.multi_selection.clone() {
+            if let Selection::SectorFace { room, x, z, face } = sel {
+                // Check if it's a wall face (any type)
+                let is_wall = matches!(face,
+                    SectorFace::WallNorth(_) | SectorFace::WallEast(_) |
+                    SectorFace::WallSouth(_) | SectorFace::WallWest(_) |
+                    SectorFace::WallNwSe(_) | SectorFace::WallNeSw(_)
+                );
+                if is_wall {
+                    apply_normal_mode_to_face(&mut state.level, room, x, z, &face, mode);
+                }
+            }
+        }
+    }
+    content_y += 28.0;
+
+    // Blend mode (PS1 semi-transparency)
+    draw_text("Blend", content_x.floor(), (content_y + 12.0).floor(), 12.0, Color::from_rgba(150, 150, 150, 255));
+    content_y += 16.0;
+
+    let blend_rect = Rect::new(content_x, content_y, width - CONTAINER_PADDING * 2.0, 24.0);
+    if let Some(mode) = draw_face_blend_toggle(ctx, blend_rect, wall.blend_mode) {
+        state.save_undo();
         // Apply to primary selection
-        apply_normal_mode_to_face(&mut state.level, room_idx, gx, gz, &wall_face, mode);
+        apply_blend_mode_to_face(&mut state.level, room_idx, gx, gz, &wall_face, mode);
         // Apply to multi-selection (only wall faces)
         for sel in state.multi_selection.clone() {
             if let Selection::SectorFace { room, x, z, face } = sel {
-                // Check if it's a wall face (any type)
                 let is_wall = matches!(face,
                     SectorFace::WallNorth(_) | SectorFace::WallEast(_) |
                     SectorFace::WallSouth(_) | SectorFace::WallWest(_) |
                     SectorFace::WallNwSe(_) | SectorFace::WallNeSw(_)
                 );
                 if is_wall {
-                    apply_normal_mode_to_face(&mut state.level, room, x, z, &face, mode);
+                    apply_blend_mode_to_face(&mut state.level, room, x, z, &face, mode);
                 }
             }
         }
@@ -5198,6 +6995,10 @@ fn draw_wall_face_container(
     let state_text = if wall.black_transparent { "Transparent" } else { "Visible" };
     draw_text(state_text, (btn_x + btn_size + 6.0).floor(), (content_y + 12.0).floor(), 11.0, Color::from_rgba(120, 120, 120, 255));
 
+    // Mirror toggle on the same row
+    let mirror_rect = Rect::new(btn_x + 100.0, content_y, btn_size, btn_size);
+    draw_mirror_toggle(ctx, state, mirror_rect, icon_font, (room_idx, gx, gz, wall_face, wall.mirror), |f| f.is_wall());
+
     container_height
 }
 
@@ -5209,7 +7010,8 @@ fn draw_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState, ico
     let selection = state.selection.clone();
 
     // Calculate total content height first
-    let total_height = calculate_properties_content_height(&selection, state);
+    let brush_height = if state.tool == EditorTool::PaintTexture { texture_brush_panel_height(state) } else { 0.0 };
+    let total_height = calculate_properties_content_height(&selection, state) + brush_height;
 
     // Clamp scroll
     let max_scroll = (total_height - rect.h + 20.0).max(0.0);
@@ -5232,6 +7034,11 @@ fn draw_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState, ico
     // Start Y position with scroll offset
     let mut y = rect.y.floor() - state.properties_scroll;
 
+    // Brush settings sit above the selection while painting
+    if state.tool == EditorTool::PaintTexture {
+        y = draw_texture_brush_panel(ctx, x, y, container_width, state);
+    }
+
     match &selection {
         super::Selection::None => {
             draw_text("Nothing selected", x, (y + 10.0).floor(), FONT_SIZE_CONTENT, Color::from_rgba(150, 150, 150, 255));
@@ -5666,6 +7473,25 @@ fn draw_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState, ico
                         (Vec::new(), None, false)
                     }
                 };
+                // Enemy (health, damage, patrol radius) and Door (start_open, key, trigger) defaults
+                let (enemy_defaults, door_default) = match state.asset_library.get_by_id(obj.asset_id) {
+                    Some(asset) => (
+                        asset.components.iter().find_map(|c| match c {
+                            crate::asset::AssetComponent::Enemy { health, damage, patrol_radius, .. } => {
+                                Some((*health, *damage, *patrol_radius))
+                            }
+                            _ => None,
+                        }),
+                        asset.components.iter().find_map(|c| match c {
+                            crate::asset::AssetComponent::Door { start_open, required_key, open_trigger, .. } => {
+                                Some((*start_open, required_key.clone(), open_trigger.clone()))
+                            }
+                            _ => None,
+                        }),
+                    ),
+                    None => (None, None),
+                };
+                let can_follow_path = state.asset_library.get_by_id(obj.asset_id).is_some_and(|a| a.can_follow_path());
 
                 // Header with asset name
                 draw_text(&asset_name, x, (y + 10.0).floor(), FONT_SIZE_HEADER, WHITE);
@@ -5693,6 +7519,22 @@ fn draw_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState, ico
                     }
                     y += 8.0;
 
+                    // Per-instance overrides: count plus a button to drop them all
+                    let override_count = obj.overrides.count();
+                    if override_count > 0 {
+                        let label = if override_count == 1 { "1 override".to_string() } else { format!("{} overrides", override_count) };
+                        draw_text(&label, x, (y + 13.0).floor(), FONT_SIZE_CONTENT, Color::from_rgba(0, 180, 180, 255));
+                        let revert_rect = Rect::new(x + container_width - 110.0, y, 100.0, 18.0);
+                        if crate::ui::text_button(ctx, revert_rect, "Revert to Asset", "Remove this instance's overrides and use the asset's values") {
+                            state.save_undo();
+                            if let Some(obj_mut) = state.level.get_object_mut(obj_room_idx, obj_idx) {
+                                obj_mut.overrides = Default::default();
+                            }
+                            state.set_status("Instance reverted to asset", 2.0);
+                        }
+                        y += 24.0;
+                    }
+
                     // Light properties (editable with per-instance overrides)
                     // Uses slider UI matching asset editor style
                     if let Some((color, intensity, radius, _offset)) = light_defaults {
@@ -5875,6 +7717,152 @@ fn draw_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState, ico
                         y += line_height + 8.0;
                     }
 
+                    // Enemy stats (per-instance overrides, highlighted when set)
+                    if let Some((health, damage, patrol_radius)) = enemy_defaults {
+                        let line_height = 20.0;
+                        let label_color = Color::from_rgba(180, 180, 190, 255);
+                        let overridden_color = Color::from_rgba(0, 180, 180, 255);
+                        let enemy = obj.overrides.enemy.clone().unwrap_or_default();
+
+                        draw_text("Enemy", x, (y + 12.0).floor(), 11.0, Color::from_rgba(255, 120, 100, 255));
+                        y += 18.0;
+
+                        let fields = [
+                            ("Health", enemy.health.map(|v| v as f32), health as f32, 20),
+                            ("Damage", enemy.damage.map(|v| v as f32), damage as f32, 21),
+                            ("Patrol", enemy.patrol_radius, patrol_radius, 22),
+                        ];
+                        for (label, value, default, field_id) in fields {
+                            let color = if value.is_some() { overridden_color } else { label_color };
+                            let r = draw_player_prop_field(ctx, x, y, container_width, line_height, label,
+                                value.unwrap_or(default), field_id,
+                                &mut state.player_prop_editing, &mut state.player_prop_buffer, color);
+                            if let Some(v) = r.new_value {
+                                state.save_undo();
+                                if let Some(obj_mut) = state.level.get_object_mut(obj_room_idx, obj_idx) {
+                                    let o = obj_mut.overrides.enemy.get_or_insert_with(Default::default);
+                                    // Typing the asset's value clears the override
+                                    let changed = (v - default).abs() > f32::EPSILON;
+                                    match field_id {
+                                        20 => o.health = changed.then_some(v.round() as i32),
+                                        21 => o.damage = changed.then_some(v.round() as i32),
+                                        _ => o.patrol_radius = changed.then_some(v.max(0.0)),
+                                    }
+                                }
+                            }
+                            y = r.new_y;
+                        }
+                        y += 8.0;
+                    }
+
+                    // Door start state, key and trigger (per-instance overrides)
+                    if let Some((start_open, default_key, default_trigger)) = door_default {
+                        let current = obj.overrides.door.as_ref().and_then(|d| d.start_open);
+                        let open = current.unwrap_or(start_open);
+                        draw_text("Door", x, (y + 12.0).floor(), 11.0, Color::from_rgba(200, 160, 100, 255));
+                        y += 18.0;
+                        let color = if current.is_some() { Color::from_rgba(0, 180, 180, 255) } else { Color::from_rgba(180, 180, 190, 255) };
+                        draw_text("Starts", x, (y + 13.0).floor(), 12.0, color);
+                        let toggle_rect = Rect::new(x + 80.0, y, 60.0, 18.0);
+                        if crate::ui::text_button(ctx, toggle_rect, if open { "Open" } else { "Closed" }, "Toggle whether this door starts open") {
+                            state.save_undo();
+                            if let Some(obj_mut) = state.level.get_object_mut(obj_room_idx, obj_idx) {
+                                let door = obj_mut.overrides.door.get_or_insert_with(Default::default);
+                                door.start_open = (!open != start_open).then_some(!open);
+                            }
+                        }
+                        y += 22.0;
+
+                        // Link a key pickup and a trigger placed in this level
+                        let (key_names, trigger_ids) = door_link_candidates(&state.level, &state.asset_library);
+                        let door_override = obj.overrides.door.clone().unwrap_or_default();
+                        let links = [
+                            ("Key", door_override.required_key, default_key, key_names, "Unlocked",
+                                "Cycle the key pickup that unlocks this door (right-click: asset default)"),
+                            ("Trigger", door_override.open_trigger, default_trigger, trigger_ids, "Interact",
+                                "Cycle the trigger that opens this door (right-click: asset default)"),
+                        ];
+                        for (label, current, default, names, none_label, tooltip) in links {
+                            let color = if current.is_some() { Color::from_rgba(0, 180, 180, 255) } else { Color::from_rgba(180, 180, 190, 255) };
+                            // Empty override = explicitly none
+                            let value = match current {
+                                Some(name) => (!name.is_empty()).then_some(name),
+                                None => default.clone(),
+                            };
+                            draw_text(label, x, (y + 13.0).floor(), 12.0, color);
+                            let button_rect = Rect::new(x + 80.0, y, (container_width - 88.0).max(60.0), 18.0);
+                            let set = if crate::ui::text_button(ctx, button_rect, value.as_deref().unwrap_or(none_label), tooltip) {
+                                let next = cycle_link_name(&names, value.as_deref());
+                                Some((next != default).then(|| next.unwrap_or_default()))
+                            } else if ctx.mouse.inside(&button_rect) && ctx.mouse.right_pressed {
+                                Some(None)
+                            } else {
+                                None
+                            };
+                            if let Some(new_override) = set {
+                                state.save_undo();
+                                if let Some(obj_mut) = state.level.get_object_mut(obj_room_idx, obj_idx) {
+                                    let door = obj_mut.overrides.door.get_or_insert_with(Default::default);
+                                    if label == "Key" {
+                                        door.required_key = new_override;
+                                    } else {
+                                        door.open_trigger = new_override;
+                                    }
+                                }
+                            }
+                            y += 22.0;
+                        }
+                        y += 6.0;
+                    }
+
+                    // Path to follow in play mode (enemies and moving platforms)
+                    if can_follow_path {
+                        let label_color = Color::from_rgba(180, 180, 190, 255);
+                        draw_text("Path", x, (y + 12.0).floor(), 11.0, Color::from_rgba(140, 220, 255, 255));
+                        y += 18.0;
+
+                        let path_ids: Vec<String> = state.level.rooms.iter()
+                            .flat_map(|room| room.paths.iter().map(|p| p.path_id.clone()))
+                            .collect();
+                        let current = obj.path.as_ref().map(|f| f.path_id.clone());
+                        draw_text("Follow", x, (y + 13.0).floor(), 12.0, label_color);
+                        let button_rect = Rect::new(x + 80.0, y, (container_width - 88.0).max(60.0), 18.0);
+                        let set = if crate::ui::text_button(ctx, button_rect, current.as_deref().unwrap_or("None"),
+                            "Cycle the path this instance follows in play mode (right-click: none)")
+                        {
+                            Some(cycle_link_name(&path_ids, current.as_deref()))
+                        } else if ctx.mouse.inside(&button_rect) && ctx.mouse.right_pressed {
+                            Some(None)
+                        } else {
+                            None
+                        };
+                        if let Some(next) = set.filter(|next| *next != current) {
+                            state.save_undo();
+                            if let Some(obj_mut) = state.level.get_object_mut(obj_room_idx, obj_idx) {
+                                let speed = obj_mut.path.as_ref().map(|f| f.speed);
+                                obj_mut.path = next.map(|path_id| crate::world::PathFollow {
+                                    path_id,
+                                    speed: speed.unwrap_or(crate::world::DEFAULT_PATH_SPEED),
+                                });
+                            }
+                        }
+                        y += 22.0;
+
+                        if let Some(follow) = &obj.path {
+                            let r = draw_player_prop_field(ctx, x, y, container_width, 20.0, "Speed",
+                                follow.speed, 41,
+                                &mut state.player_prop_editing, &mut state.player_prop_buffer, label_color);
+                            if let Some(v) = r.new_value {
+                                state.save_undo();
+                                if let Some(f) = state.level.get_object_mut(obj_room_idx, obj_idx).and_then(|o| o.path.as_mut()) {
+                                    f.speed = v.max(0.0);
+                                }
+                            }
+                            y = r.new_y;
+                        }
+                        y += 6.0;
+                    }
+
                     // Player spawn shows player settings
                     if is_player_spawn {
                         let section_color = Color::from_rgba(120, 150, 180, 255);
@@ -6043,6 +8031,400 @@ fn draw_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState, ico
                 draw_text("Object not found", x, (y + 14.0).floor(), 14.0, Color::from_rgba(255, 100, 100, 255));
             }
         }
+        super::Selection::Trigger { room: room_idx, index } => {
+            let (trig_room_idx, trig_idx) = (*room_idx, *index);
+            let trigger_opt = state.level.rooms.get(trig_room_idx)
+                .and_then(|room| room.triggers.get(trig_idx))
+                .cloned();
+
+            if let Some(trigger) = trigger_opt {
+                let label_color = Color::from_rgba(150, 150, 150, 255);
+                let line_height = 20.0;
+
+                draw_text("Trigger Volume", x, (y + 10.0).floor(), FONT_SIZE_HEADER, Color::from_rgba(255, 150, 100, 255));
+                y += 20.0;
+                let size = trigger.size();
+                draw_text(&format!("  Room: {}  Size: {:.0} x {:.0} x {:.0}", trig_room_idx, size.x, size.y, size.z),
+                    x, (y + 10.0).floor(), FONT_SIZE_CONTENT, WHITE);
+                y += 20.0;
+
+                // Trigger id, named events and cutscene (empty = none)
+                let fields: [(&str, String); 4] = [
+                    ("Trigger ID", trigger.trigger_id.clone()),
+                    ("On Enter", trigger.on_enter.clone().unwrap_or_default()),
+                    ("On Exit", trigger.on_exit.clone().unwrap_or_default()),
+                    ("Cutscene", trigger.cutscene.clone().unwrap_or_default()),
+                ];
+                for (i, (label, value)) in fields.iter().enumerate() {
+                    draw_text(label, x, (y + 13.0).floor(), 12.0, label_color);
+                    let field_rect = Rect::new(x + 80.0, y, container_width - 90.0, line_height - 2.0);
+                    if let Some(text) = draw_trigger_text_field(ctx, field_rect, value, i, &mut state.trigger_field_edit) {
+                        if text != *value && !(i == 0 && text.is_empty()) {
+                            state.save_undo();
+                            if let Some(t) = state.level.rooms.get_mut(trig_room_idx).and_then(|r| r.triggers.get_mut(trig_idx)) {
+                                let event = if text.is_empty() { None } else { Some(text.clone()) };
+                                match i {
+                                    0 => t.trigger_id = text,
+                                    1 => t.on_enter = event,
+                                    2 => t.on_exit = event,
+                                    _ => t.cutscene = event,
+                                }
+                            }
+                        }
+                    }
+                    y += line_height;
+                }
+                y += 6.0;
+
+                // Vertical extent (room-relative)
+                let r = draw_player_prop_field(ctx, x, y, container_width, line_height, "Bottom",
+                    trigger.min.y, 30,
+                    &mut state.player_prop_editing, &mut state.player_prop_buffer, label_color);
+                if let Some(v) = r.new_value {
+                    state.save_undo();
+                    if let Some(t) = state.level.rooms.get_mut(trig_room_idx).and_then(|r| r.triggers.get_mut(trig_idx)) {
+                        t.min.y = v.min(t.max.y - crate::world::TRIGGER_SNAP);
+                    }
+                }
+                y = r.new_y;
+                let r = draw_player_prop_field(ctx, x, y, container_width, line_height, "Top",
+                    trigger.max.y, 31,
+                    &mut state.player_prop_editing, &mut state.player_prop_buffer, label_color);
+                if let Some(v) = r.new_value {
+                    state.save_undo();
+                    if let Some(t) = state.level.rooms.get_mut(trig_room_idx).and_then(|r| r.triggers.get_mut(trig_idx)) {
+                        t.max.y = v.max(t.min.y + crate::world::TRIGGER_SNAP);
+                    }
+                }
+                y = r.new_y + 8.0;
+
+                draw_text(&format!("Scripts: on enter {}", trigger.trigger_id), x, (y + 10.0).floor(), FONT_SIZE_CONTENT, label_color);
+                y += 22.0;
+                if let Some(name) = trigger.cutscene.as_ref().filter(|n| !state.level.cutscenes.iter().any(|c| c.name == **n)) {
+                    draw_text(&format!("No cutscene named '{}'", name), x, (y + 10.0).floor(), FONT_SIZE_CONTENT, Color::from_rgba(255, 150, 80, 255));
+                    y += 22.0;
+                }
+
+                // Delete button
+                let delete_rect = Rect::new(x, y, container_width - 8.0, 22.0);
+                if crate::ui::text_button(ctx, delete_rect, "Delete Trigger", "Remove this trigger volume") {
+                    state.save_undo();
+                    if let Some(room) = state.level.rooms.get_mut(trig_room_idx) {
+                        if trig_idx < room.triggers.len() {
+                            room.triggers.remove(trig_idx);
+                        }
+                    }
+                    state.trigger_field_edit = None;
+                    state.set_selection(super::Selection::None);
+                    state.set_status("Trigger deleted", 2.0);
+                }
+            } else {
+                draw_text("Trigger not found", x, (y + 14.0).floor(), 14.0, Color::from_rgba(255, 100, 100, 255));
+            }
+        }
+        super::Selection::Ladder { room: room_idx, index } => {
+            let (ladder_room_idx, ladder_idx) = (*room_idx, *index);
+            let ladder_opt = state.level.rooms.get(ladder_room_idx)
+                .and_then(|room| room.ladders.get(ladder_idx))
+                .cloned();
+
+            if let Some(ladder) = ladder_opt {
+                let label_color = Color::from_rgba(150, 150, 150, 255);
+                let line_height = 20.0;
+
+                draw_text("Ladder", x, (y + 10.0).floor(), FONT_SIZE_HEADER, Color::from_rgba(120, 200, 80, 255));
+                y += 20.0;
+                let size = ladder.size();
+                draw_text(&format!("  Room: {}  Size: {:.0} x {:.0} x {:.0}", ladder_room_idx, size.x, size.y, size.z),
+                    x, (y + 10.0).floor(), FONT_SIZE_CONTENT, WHITE);
+                y += 20.0;
+
+                // Side the ladder is fixed to (the climber faces it)
+                draw_text("Facing", x, (y + 13.0).floor(), 12.0, label_color);
+                let facing_rect = Rect::new(x + 80.0, y, container_width - 90.0, line_height - 2.0);
+                if crate::ui::text_button(ctx, facing_rect, ladder.facing.name(), "Turn the ladder to the next side") {
+                    state.save_undo();
+                    if let Some(l) = state.level.rooms.get_mut(ladder_room_idx).and_then(|r| r.ladders.get_mut(ladder_idx)) {
+                        l.rotate_facing();
+                    }
+                }
+                y += line_height + 6.0;
+
+                // Vertical extent (room-relative)
+                let r = draw_player_prop_field(ctx, x, y, container_width, line_height, "Bottom",
+                    ladder.min.y, 32,
+                    &mut state.player_prop_editing, &mut state.player_prop_buffer, label_color);
+                if let Some(v) = r.new_value {
+                    state.save_undo();
+                    if let Some(l) = state.level.rooms.get_mut(ladder_room_idx).and_then(|r| r.ladders.get_mut(ladder_idx)) {
+                        l.min.y = v.min(l.max.y - crate::world::TRIGGER_SNAP);
+                    }
+                }
+                y = r.new_y;
+                let r = draw_player_prop_field(ctx, x, y, container_width, line_height, "Top",
+                    ladder.max.y, 33,
+                    &mut state.player_prop_editing, &mut state.player_prop_buffer, label_color);
+                if let Some(v) = r.new_value {
+                    state.save_undo();
+                    if let Some(l) = state.level.rooms.get_mut(ladder_room_idx).and_then(|r| r.ladders.get_mut(ladder_idx)) {
+                        l.max.y = v.max(l.min.y + crate::world::TRIGGER_SNAP);
+                    }
+                }
+                y = r.new_y + 8.0;
+
+                // Delete button
+                let delete_rect = Rect::new(x, y, container_width - 8.0, 22.0);
+                if crate::ui::text_button(ctx, delete_rect, "Delete Ladder", "Remove this ladder") {
+                    state.save_undo();
+                    if let Some(room) = state.level.rooms.get_mut(ladder_room_idx) {
+                        if ladder_idx < room.ladders.len() {
+                            room.ladders.remove(ladder_idx);
+                        }
+                    }
+                    state.set_selection(super::Selection::None);
+                    state.set_status("Ladder deleted", 2.0);
+                }
+            } else {
+                draw_text("Ladder not found", x, (y + 14.0).floor(), 14.0, Color::from_rgba(255, 100, 100, 255));
+            }
+        }
+        super::Selection::Camera { room: room_idx, index } => {
+            let (camera_room_idx, camera_idx) = (*room_idx, *index);
+            let camera_opt = state.level.rooms.get(camera_room_idx)
+                .and_then(|room| room.cameras.get(camera_idx).map(|c| (c.clone(), room.position)));
+
+            if let Some((camera, room_position)) = camera_opt {
+                let label_color = Color::from_rgba(150, 150, 150, 255);
+                let line_height = 20.0;
+
+                draw_text("Camera Volume", x, (y + 10.0).floor(), FONT_SIZE_HEADER, Color::from_rgba(100, 170, 255, 255));
+                y += 20.0;
+                let size = camera.size();
+                draw_text(&format!("  Room: {}  Size: {:.0} x {:.0} x {:.0}", camera_room_idx, size.x, size.y, size.z),
+                    x, (y + 10.0).floor(), FONT_SIZE_CONTENT, WHITE);
+                y += 20.0;
+
+                draw_text("Name", x, (y + 13.0).floor(), 12.0, label_color);
+                let field_rect = Rect::new(x + 80.0, y, container_width - 90.0, line_height - 2.0);
+                if let Some(text) = draw_trigger_text_field(ctx, field_rect, &camera.name, 0, &mut state.trigger_field_edit) {
+                    if text != camera.name && !text.is_empty() {
+                        state.save_undo();
+                        if let Some(c) = state.level.rooms.get_mut(camera_room_idx).and_then(|r| r.cameras.get_mut(camera_idx)) {
+                            c.name = text;
+                        }
+                    }
+                }
+                y += line_height;
+
+                // Fixed camera or spline rail (switching keeps the first position)
+                draw_text("Shot", x, (y + 13.0).floor(), 12.0, label_color);
+                let shot_rect = Rect::new(x + 80.0, y, container_width - 90.0, line_height - 2.0);
+                let shot_label = if camera.is_rail() { "Rail" } else { "Fixed" };
+                if crate::ui::text_button(ctx, shot_rect, shot_label, "Switch between a fixed camera and a spline rail") {
+                    state.save_undo();
+                    if let Some(c) = state.level.rooms.get_mut(camera_room_idx).and_then(|r| r.cameras.get_mut(camera_idx)) {
+                        let first = c.shot_points().first().copied();
+                        c.shot = match (&c.shot, first) {
+                            (crate::world::CameraShot::Fixed { position }, _) => crate::world::CameraShot::Rail { points: vec![*position] },
+                            (_, Some(position)) => crate::world::CameraShot::Fixed { position },
+                            (_, None) => crate::world::CameraShot::Fixed { position: Vec3::new(c.min.x, c.max.y, c.min.z) },
+                        };
+                    }
+                }
+                y += line_height + 6.0;
+
+                // Camera positions come from the 3D view's camera
+                let view_position = state.camera_3d.position - room_position;
+                let button_rect = Rect::new(x, y, container_width - 8.0, 22.0);
+                if camera.is_rail() {
+                    let label = format!("Add Rail Point From View ({})", camera.shot_points().len());
+                    if crate::ui::text_button(ctx, button_rect, &label, "Append the 3D view's camera position to the rail") {
+                        state.save_undo();
+                        if let Some(c) = state.level.rooms.get_mut(camera_room_idx).and_then(|r| r.cameras.get_mut(camera_idx)) {
+                            if let crate::world::CameraShot::Rail { points } = &mut c.shot {
+                                points.push(view_position);
+                            }
+                        }
+                    }
+                    y += 24.0;
+                    let clear_rect = Rect::new(x, y, container_width - 8.0, 22.0);
+                    if crate::ui::text_button(ctx, clear_rect, "Clear Rail Points", "Remove every point of the rail") {
+                        state.save_undo();
+                        if let Some(c) = state.level.rooms.get_mut(camera_room_idx).and_then(|r| r.cameras.get_mut(camera_idx)) {
+                            c.shot = crate::world::CameraShot::Rail { points: Vec::new() };
+                        }
+                    }
+                } else if crate::ui::text_button(ctx, button_rect, "Set Camera From View", "Place the camera where the 3D view's camera is") {
+                    state.save_undo();
+                    if let Some(c) = state.level.rooms.get_mut(camera_room_idx).and_then(|r| r.cameras.get_mut(camera_idx)) {
+                        c.shot = crate::world::CameraShot::Fixed { position: view_position };
+                    }
+                }
+                y += 30.0;
+
+                let r = draw_player_prop_field(ctx, x, y, container_width, line_height, "Blend (s)",
+                    camera.blend_time, 34,
+                    &mut state.player_prop_editing, &mut state.player_prop_buffer, label_color);
+                if let Some(v) = r.new_value {
+                    state.save_undo();
+                    if let Some(c) = state.level.rooms.get_mut(camera_room_idx).and_then(|r| r.cameras.get_mut(camera_idx)) {
+                        c.blend_time = v.max(0.0);
+                    }
+                }
+                y = r.new_y;
+
+                // Vertical extent (room-relative)
+                let r = draw_player_prop_field(ctx, x, y, container_width, line_height, "Bottom",
+                    camera.min.y, 35,
+                    &mut state.player_prop_editing, &mut state.player_prop_buffer, label_color);
+                if let Some(v) = r.new_value {
+                    state.save_undo();
+                    if let Some(c) = state.level.rooms.get_mut(camera_room_idx).and_then(|r| r.cameras.get_mut(camera_idx)) {
+                        c.min.y = v.min(c.max.y - crate::world::TRIGGER_SNAP);
+                    }
+                }
+                y = r.new_y;
+                let r = draw_player_prop_field(ctx, x, y, container_width, line_height, "Top",
+                    camera.max.y, 36,
+                    &mut state.player_prop_editing, &mut state.player_prop_buffer, label_color);
+                if let Some(v) = r.new_value {
+                    state.save_undo();
+                    if let Some(c) = state.level.rooms.get_mut(camera_room_idx).and_then(|r| r.cameras.get_mut(camera_idx)) {
+                        c.max.y = v.max(c.min.y + crate::world::TRIGGER_SNAP);
+                    }
+                }
+                y = r.new_y + 8.0;
+
+                // Preview: look through the shot at the volume's center
+                let preview_rect = Rect::new(x, y, container_width - 8.0, 22.0);
+                if crate::ui::text_button(ctx, preview_rect, "Preview Shot", "Move the 3D view's camera to this shot") {
+                    if state.preview_camera_volume(camera_room_idx, camera_idx) {
+                        state.set_status(&format!("Previewing '{}'", camera.name), 2.0);
+                    } else {
+                        state.set_status("Rail has no points yet", 2.0);
+                    }
+                }
+                y += 26.0;
+
+                // Delete button
+                let delete_rect = Rect::new(x, y, container_width - 8.0, 22.0);
+                if crate::ui::text_button(ctx, delete_rect, "Delete Camera Volume", "Remove this camera volume") {
+                    state.save_undo();
+                    if let Some(room) = state.level.rooms.get_mut(camera_room_idx) {
+                        if camera_idx < room.cameras.len() {
+                            room.cameras.remove(camera_idx);
+                        }
+                    }
+                    state.trigger_field_edit = None;
+                    state.set_selection(super::Selection::None);
+                    state.set_status("Camera volume deleted", 2.0);
+                }
+            } else {
+                draw_text("Camera volume not found", x, (y + 14.0).floor(), 14.0, Color::from_rgba(255, 100, 100, 255));
+            }
+        }
+        super::Selection::Path { room: room_idx, index, point } => {
+            let (path_room_idx, path_idx, selected_point) = (*room_idx, *index, *point);
+            let path_opt = state.level.rooms.get(path_room_idx)
+                .and_then(|room| room.paths.get(path_idx))
+                .cloned();
+
+            if let Some(path) = path_opt {
+                let label_color = Color::from_rgba(150, 150, 150, 255);
+                let line_height = 20.0;
+
+                draw_text("Path", x, (y + 10.0).floor(), FONT_SIZE_HEADER, Color::from_rgba(140, 220, 255, 255));
+                y += 20.0;
+                draw_text(&format!("  Room: {}  Waypoints: {}", path_room_idx, path.points.len()),
+                    x, (y + 10.0).floor(), FONT_SIZE_CONTENT, WHITE);
+                y += 20.0;
+
+                // Path id (renaming keeps attached instances on the path)
+                draw_text("Path ID", x, (y + 13.0).floor(), 12.0, label_color);
+                let field_rect = Rect::new(x + 80.0, y, container_width - 90.0, line_height - 2.0);
+                if let Some(text) = draw_trigger_text_field(ctx, field_rect, &path.path_id, 0, &mut state.trigger_field_edit) {
+                    if !text.is_empty() && text != path.path_id {
+                        state.save_undo();
+                        for room in &mut state.level.rooms {
+                            for obj in &mut room.objects {
+                                if let Some(follow) = obj.path.as_mut().filter(|f| f.path_id == path.path_id) {
+                                    follow.path_id = text.clone();
+                                }
+                            }
+                        }
+                        if let Some(p) = state.level.rooms.get_mut(path_room_idx).and_then(|r| r.paths.get_mut(path_idx)) {
+                            p.path_id = text;
+                        }
+                    }
+                }
+                y += line_height;
+
+                draw_text("Mode", x, (y + 13.0).floor(), 12.0, label_color);
+                let mode_rect = Rect::new(x + 80.0, y, 80.0, 18.0);
+                if crate::ui::text_button(ctx, mode_rect, path.mode.label(), "Loop back to the first waypoint, or turn around at the ends") {
+                    state.save_undo();
+                    if let Some(p) = state.level.rooms.get_mut(path_room_idx).and_then(|r| r.paths.get_mut(path_idx)) {
+                        p.mode = p.mode.toggled();
+                    }
+                }
+                y += 22.0 + 6.0;
+
+                // Selected waypoint height (room-relative)
+                if let Some((i, waypoint)) = selected_point.and_then(|i| Some((i, *path.points.get(i)?))) {
+                    draw_text(&format!("Waypoint {}", i + 1), x, (y + 12.0).floor(), 11.0, Color::from_rgba(140, 220, 255, 255));
+                    y += 18.0;
+                    let r = draw_player_prop_field(ctx, x, y, container_width, line_height, "Height",
+                        waypoint.y, 40,
+                        &mut state.player_prop_editing, &mut state.player_prop_buffer, label_color);
+                    if let Some(v) = r.new_value {
+                        state.save_undo();
+                        if let Some(p) = state.level.rooms.get_mut(path_room_idx)
+                            .and_then(|r| r.paths.get_mut(path_idx))
+                            .and_then(|p| p.points.get_mut(i))
+                        {
+                            p.y = v;
+                        }
+                    }
+                    y = r.new_y + 6.0;
+                }
+
+                // Instances attached to this path
+                let followers: Vec<String> = state.level.rooms.iter()
+                    .flat_map(|room| room.objects.iter())
+                    .filter(|obj| obj.path.as_ref().is_some_and(|f| f.path_id == path.path_id))
+                    .map(|obj| {
+                        if obj.name.is_empty() {
+                            state.asset_library.get_name_by_id(obj.asset_id).unwrap_or("Unknown").to_string()
+                        } else {
+                            obj.name.clone()
+                        }
+                    })
+                    .collect();
+                let followers_text = if followers.is_empty() {
+                    "Followers: none (attach in an enemy's or platform's properties)".to_string()
+                } else {
+                    format!("Followers: {}", followers.join(", "))
+                };
+                draw_text(&followers_text, x, (y + 10.0).floor(), FONT_SIZE_CONTENT, label_color);
+                y += 22.0;
+
+                // Delete button
+                let delete_rect = Rect::new(x, y, container_width - 8.0, 22.0);
+                if crate::ui::text_button(ctx, delete_rect, "Delete Path", "Remove this path (attached instances stay put)") {
+                    state.save_undo();
+                    if let Some(room) = state.level.rooms.get_mut(path_room_idx) {
+                        if path_idx < room.paths.len() {
+                            room.paths.remove(path_idx);
+                        }
+                    }
+                    state.trigger_field_edit = None;
+                    state.set_selection(super::Selection::None);
+                    state.set_status("Path deleted", 2.0);
+                }
+            } else {
+                draw_text("Path not found", x, (y + 14.0).floor(), 14.0, Color::from_rgba(255, 100, 100, 255));
+            }
+        }
     }
 
     // Disable scissor
@@ -6070,6 +8452,68 @@ fn draw_properties(ctx: &mut UiContext, rect: Rect, state: &mut EditorState, ico
     }
 }
 
+/// Height of the texture brush section (one row per set entry)
+fn texture_brush_panel_height(state: &EditorState) -> f32 {
+    let rows: usize = BrushSurface::ALL.iter().map(|&s| state.texture_brush.set(s).len().max(1)).sum();
+    24.0 + 26.0 + BrushSurface::ALL.len() as f32 * 30.0 + rows as f32 * 18.0 + 16.0
+}
+
+/// Texture brush settings: per-surface toggles and texture sets, variation options
+fn draw_texture_brush_panel(ctx: &mut UiContext, x: f32, mut y: f32, container_width: f32, state: &mut EditorState) -> f32 {
+    let label_color = Color::from_rgba(150, 150, 150, 255);
+    let btn_h = 20.0;
+
+    draw_text("Texture Brush", x, (y + 10.0).floor(), FONT_SIZE_HEADER, Color::from_rgba(120, 200, 255, 255));
+    y += 24.0;
+
+    // Variation options
+    let half = ((container_width - 12.0) / 2.0).floor();
+    let random_label = if state.texture_brush.random { "Random: On" } else { "Random: Off" };
+    if crate::ui::text_button(ctx, Rect::new(x, y, half, btn_h), random_label, "Pick randomly from each set to break up repetition") {
+        state.texture_brush.random = !state.texture_brush.random;
+    }
+    if crate::ui::text_button(ctx, Rect::new(x + half + 4.0, y, half, btn_h), "Reroll", "New random layout for the next strokes") {
+        state.texture_brush.reseed();
+    }
+    y += 26.0;
+
+    for surface in BrushSurface::ALL {
+        let enabled = state.texture_brush.is_enabled(surface);
+        let toggle_label = format!("[{}] {}", if enabled { "x" } else { " " }, surface.label());
+        if crate::ui::text_button(ctx, Rect::new(x, y, 90.0, btn_h), &toggle_label, "Paint this surface") {
+            state.texture_brush.set_enabled(surface, !enabled);
+        }
+        let add_w = ((container_width - 110.0) / 2.0).floor();
+        if crate::ui::text_button(ctx, Rect::new(x + 94.0, y, add_w, btn_h), "+ Selected", "Add the palette's selected texture to this set") {
+            let texture = state.selected_texture.clone();
+            state.texture_brush.add_to_set(surface, texture);
+        }
+        if crate::ui::text_button(ctx, Rect::new(x + 98.0 + add_w, y, add_w, btn_h), "Clear", "Empty this set") {
+            state.texture_brush.clear_set(surface);
+        }
+        y += btn_h + 4.0;
+
+        let set = state.texture_brush.set(surface).to_vec();
+        if set.is_empty() {
+            draw_text("  (selected texture)", x, (y + 12.0).floor(), 12.0, label_color);
+            y += 18.0;
+        }
+        let mut remove = None;
+        for (i, texture) in set.iter().enumerate() {
+            draw_text(&format!("  {}/{}", texture.pack, texture.name), x, (y + 12.0).floor(), 12.0, WHITE);
+            if crate::ui::text_button(ctx, Rect::new(x + container_width - 30.0, y, 20.0, 16.0), "x", "Remove from set") {
+                remove = Some(i);
+            }
+            y += 18.0;
+        }
+        if let Some(i) = remove {
+            state.texture_brush.remove_from_set(surface, i);
+        }
+        y += 6.0;
+    }
+    y + 16.0
+}
+
 /// Calculate total content height for properties panel (for scroll bounds)
 fn calculate_properties_content_height(selection: &super::Selection, state: &EditorState) -> f32 {
     let header_height = 24.0;
@@ -6077,6 +8521,14 @@ fn calculate_properties_content_height(selection: &super::Selection, state: &Edi
     match selection {
         super::Selection::None | super::Selection::Room(_) | super::Selection::Portal { .. } => 30.0,
 
+        super::Selection::Trigger { .. } => 220.0, // Header + 3 text fields + bottom/top + delete
+
+        super::Selection::Ladder { .. } => 150.0, // Header + facing + bottom/top + delete
+
+        super::Selection::Camera { .. } => 280.0, // Header + name + shot + view buttons + blend/bottom/top + preview + delete
+
+        super::Selection::Path { .. } => 240.0, // Header + id + mode + waypoint height + followers + delete
+
         super::Selection::Edge { .. } => 120.0, // Edge header + 2 vertex coords
 
         super::Selection::Vertex { room, x: gx, z: gz, face, .. } => {
@@ -6226,6 +8678,10 @@ fn calculate_properties_content_height(selection: &super::Selection, state: &Edi
                 if let Some(asset) = state.asset_library.get_by_id(obj.asset_id) {
                     height += 18.0 + asset.components.len() as f32 * 18.0; // Components header + list
 
+                    if asset.can_follow_path() {
+                        height += 18.0 + 22.0 + 20.0 + 6.0; // Path header + follow + speed
+                    }
+
                     if asset.has_spawn_point(true) {
                         // Player settings: 3 sections with scroll-to-edit rows
                         // Collision: header 18 + 3 rows at 20 = 78
@@ -6285,6 +8741,26 @@ fn draw_status_bar(rect: Rect, state: &EditorState) {
             shortcuts.push("[Click] Place object");
             shortcuts.push("[Del] Delete");
         }
+        EditorTool::DrawTrigger => {
+            shortcuts.push("[Drag] Draw trigger (2D top view)");
+            shortcuts.push("[Del] Delete");
+        }
+        EditorTool::DrawLadder => {
+            shortcuts.push("[Drag] Draw ladder (2D top view)");
+            shortcuts.push("[Del] Delete");
+        }
+        EditorTool::DrawCamera => {
+            shortcuts.push("[Drag] Draw camera volume (2D top view)");
+            shortcuts.push("[Del] Delete");
+        }
+        EditorTool::PaintTexture => {
+            shortcuts.push("[Drag] Paint textures (2D top view)");
+        }
+        EditorTool::DrawPath => {
+            shortcuts.push("[Click] Add waypoint (2D top view)");
+            shortcuts.push("[Esc] Finish path");
+            shortcuts.push("[Del] Delete");
+        }
         _ => {}
     }
 
@@ -6412,8 +8888,16 @@ fn draw_player_camera_preview(
         &resolve_texture,
         &crate::scene::SceneRenderOptions {
             use_fog: false,
+            atmosphere: None,
             render_assets: true,
             skip_rooms: &[],
+            lod_scale: None,
+            hidden_objects: &[],
+            moved_objects: &[],
+            broken_objects: &[],
+            dropped_assets: &[],
+            baked_lighting: true,
+            water_time: Some(macroquad::prelude::get_time() as f32),
         },
     );
 
@@ -1,13 +1,21 @@
 //! Level Browser
 //!
 //! Modal dialog for browsing and previewing levels - both bundled samples
-//! and user-created levels from storage.
+//! and user-created levels from storage. "Open Link" imports a level someone
+//! shared (see `share`).
+//!
+//! The filter bar above the list narrows it by name, tag or smart collection.
+//! List rows show a thumbnail taken from each level's first preview frame.
 
 use macroquad::prelude::*;
+use crate::cloud_sync::{draw_revision_history, HistoryAction, RevisionHistory};
 use crate::storage::{Storage, PendingLoad, PendingList};
-use crate::ui::{Rect, UiContext, draw_icon_centered, ACCENT_COLOR, TextInputState, draw_text_input};
+use crate::ui::{Rect, UiContext, draw_icon_centered, theme, TextInputState, draw_text_input};
 use crate::world::Level;
 use crate::rasterizer::{Framebuffer, Texture as RasterTexture, Camera, Color as RasterColor, Vec3, RasterSettings, ShadingMode};
+use crate::tags::{TagFilter, TagKind, draw_tag_filter, draw_item_tags, TAG_FILTER_HEIGHT, TAG_ROW_HEIGHT};
+use crate::browser_list::{draw_browser_list, ListItem, ListSection};
+use crate::thumbnails;
 use super::sample_levels::{LevelInfo, LevelCategory, LevelStats, get_level_stats};
 use super::TexturePack;
 
@@ -51,6 +59,14 @@ pub struct LevelBrowser {
     pub pending_user_list: Option<PendingList>,
     /// Active rename dialog (TextInputState for the new name)
     pub rename_dialog: Option<TextInputState>,
+    /// Revision history of the selected cloud level, while browsing it
+    pub history: Option<RevisionHistory>,
+    /// Active "Open Link" dialog (TextInputState for the share link)
+    pub link_dialog: Option<TextInputState>,
+    /// Search and tag filter above the list
+    pub tag_filter: TagFilter,
+    /// The next preview frame becomes the selected level's thumbnail
+    thumbnail_pending: bool,
     /// Local framebuffer for preview rendering (avoids resizing main fb)
     preview_fb: Framebuffer,
 }
@@ -79,6 +95,10 @@ impl Default for LevelBrowser {
             pending_preview_load: None,
             pending_user_list: None,
             rename_dialog: None,
+            history: None,
+            link_dialog: None,
+            tag_filter: TagFilter::default(),
+            thumbnail_pending: false,
             preview_fb: Framebuffer::new(320, 240), // Initial size, will resize as needed
         }
     }
@@ -106,6 +126,7 @@ impl LevelBrowser {
     pub fn close(&mut self) {
         self.open = false;
         self.preview_level = None;
+        self.history = None;
     }
 
     /// Get the currently selected level info
@@ -202,6 +223,7 @@ impl LevelBrowser {
 
         self.preview_stats = Some(get_level_stats(&level));
         self.preview_level = Some(level);
+        self.thumbnail_pending = true;
 
         // Reset orbit angle - start looking at level from an angle
         self.orbit_yaw = 0.8;
@@ -232,6 +254,12 @@ pub enum BrowserAction {
     NewLevel,
     /// User wants to refresh the level list
     Refresh,
+    /// User wants to browse the selected cloud level's revision history
+    ShowHistory,
+    /// User wants to open an older revision of the selected cloud level
+    OpenRevision(u64),
+    /// User wants to import the shared level in the link dialog
+    OpenLink,
     /// User cancelled
     Cancel,
 }
@@ -281,26 +309,55 @@ pub fn draw_level_browser(
     let content_h = dialog_h - header_h - 60.0; // Leave room for footer
     let list_w = 220.0;
 
-    // List panel (left) - custom two-section list
-    let list_rect = Rect::new(dialog_x + 8.0, content_y, list_w, content_h);
-    draw_rectangle(list_rect.x, list_rect.y, list_rect.w, list_rect.h, Color::from_rgba(25, 25, 30, 255));
-
-    let item_h = 26.0;
-    let section_h = 28.0;
-    let has_cloud = storage.has_cloud();
-
-    // Draw two-section list
-    let list_action = draw_two_section_list(
+    // Search and tag filter above the list
+    let filter_rect = Rect::new(dialog_x + 8.0, content_y, list_w, TAG_FILTER_HEIGHT);
+    draw_tag_filter(ctx, filter_rect, &mut browser.tag_filter);
+
+    // List panel (left) - samples + user levels, with thumbnails
+    let list_top = filter_rect.bottom() + 4.0;
+    let list_rect = Rect::new(dialog_x + 8.0, list_top, list_w, content_y + content_h - list_top);
+    let sections = [
+        ListSection {
+            title: "SAMPLE LEVELS",
+            items: browser.samples.iter().map(|l| ListItem { name: &l.name, key: l.library_key() }).collect(),
+            collapsed: browser.samples_collapsed,
+            loading: false,
+            empty_text: None,
+            cloud: false,
+        },
+        ListSection {
+            title: "MY LEVELS",
+            items: browser.user_levels.iter().map(|l| ListItem { name: &l.name, key: l.library_key() }).collect(),
+            collapsed: browser.user_collapsed,
+            loading: browser.is_loading_user_levels(),
+            empty_text: Some("(no saved levels)"),
+            cloud: storage.has_cloud(),
+        },
+    ];
+    let selected = match (browser.selected_category, browser.selected_index) {
+        (Some(LevelCategory::Sample), Some(i)) => Some((0, i)),
+        (Some(LevelCategory::User), Some(i)) => Some((1, i)),
+        _ => None,
+    };
+    let list = draw_browser_list(
         ctx,
         list_rect,
-        browser,
-        item_h,
-        section_h,
-        has_cloud,
+        &mut browser.scroll_offset,
+        &sections,
+        &browser.tag_filter,
+        TagKind::Level,
+        selected,
+        Some(storage),
     );
 
     // Handle list actions
-    if let Some((category, idx)) = list_action {
+    match list.toggled {
+        Some(0) => browser.samples_collapsed = !browser.samples_collapsed,
+        Some(_) => browser.user_collapsed = !browser.user_collapsed,
+        None => {}
+    }
+    if let Some((section, idx)) = list.clicked {
+        let category = if section == 0 { LevelCategory::Sample } else { LevelCategory::User };
         if browser.selected_category != Some(category) || browser.selected_index != Some(idx) {
             browser.selected_category = Some(category);
             browser.selected_index = Some(idx);
@@ -311,7 +368,13 @@ pub fn draw_level_browser(
     // Preview panel (right)
     let preview_x = dialog_x + list_w + 16.0;
     let preview_w = dialog_w - list_w - 24.0;
-    let preview_rect = Rect::new(preview_x, content_y, preview_w, content_h);
+    let preview_rect = Rect::new(preview_x, content_y, preview_w, content_h - TAG_ROW_HEIGHT - 4.0);
+
+    // Tags of the selected level, under the preview
+    if let Some(key) = browser.selected_level().map(|info| info.library_key()) {
+        let tags_rect = Rect::new(preview_x, preview_rect.bottom() + 4.0, preview_w, TAG_ROW_HEIGHT);
+        draw_item_tags(ctx, tags_rect, &mut browser.tag_filter, TagKind::Level, &key);
+    }
 
     draw_rectangle(preview_rect.x, preview_rect.y, preview_rect.w, preview_rect.h, Color::from_rgba(20, 20, 25, 255));
 
@@ -321,7 +384,7 @@ pub fn draw_level_browser(
 
     if has_preview {
         // Render 3D preview with orbit camera (uses browser's local framebuffer)
-        draw_orbit_preview(ctx, browser, preview_rect, texture_packs, asset_library, user_textures);
+        draw_orbit_preview(ctx, browser, preview_rect, storage, texture_packs, asset_library, user_textures);
 
         // Draw stats at bottom of preview
         if let Some(stats) = &browser.preview_stats {
@@ -348,6 +411,13 @@ pub fn draw_level_browser(
         draw_text("Select a level to preview", preview_rect.x + 20.0, preview_rect.y + 40.0, 16.0, Color::from_rgba(100, 100, 100, 255));
     }
 
+    // History button (top-right of the preview, cloud user levels only)
+    let history_rect = Rect::new(preview_rect.right() - 78.0, preview_rect.y + 6.0, 72.0, 24.0);
+    let history_enabled = browser.is_user_selected() && browser.preview_level.is_some() && storage.has_cloud();
+    if draw_text_button_enabled(ctx, history_rect, "History", Color::from_rgba(60, 60, 70, 255), history_enabled) {
+        action = BrowserAction::ShowHistory;
+    }
+
     // Footer with buttons
     let footer_y = dialog_y + dialog_h - 44.0;
     draw_rectangle(dialog_x, footer_y, dialog_w, 44.0, Color::from_rgba(40, 40, 48, 255));
@@ -380,6 +450,12 @@ pub fn draw_level_browser(
         action = BrowserAction::Refresh;
     }
 
+    // Open Link button - import a level someone shared
+    let link_rect = Rect::new(dialog_x + 330.0, footer_y + 8.0, 90.0, 28.0);
+    if draw_text_button(ctx, link_rect, "Open Link", Color::from_rgba(60, 80, 100, 255)) {
+        browser.link_dialog = Some(TextInputState::new(""));
+    }
+
     // Cancel button
     let cancel_rect = Rect::new(dialog_x + dialog_w - 270.0, footer_y + 8.0, 70.0, 28.0);
     if draw_text_button(ctx, cancel_rect, "Cancel", Color::from_rgba(60, 60, 70, 255)) {
@@ -396,50 +472,36 @@ pub fn draw_level_browser(
     // Open button (enabled if something is selected and loaded)
     let open_rect = Rect::new(dialog_x + dialog_w - 90.0, footer_y + 8.0, 80.0, 28.0);
     let open_enabled = browser.preview_level.is_some();
-    if draw_text_button_enabled(ctx, open_rect, "Open", ACCENT_COLOR, open_enabled) {
+    if draw_text_button_enabled(ctx, open_rect, "Open", theme().accent, open_enabled) {
         action = BrowserAction::OpenLevel;
     }
 
-    // Rename dialog overlay
-    if browser.rename_dialog.is_some() {
-        let rdw = 280.0;
-        let rdh = 120.0;
-        let rdx = (screen_width() - rdw) / 2.0;
-        let rdy = (screen_height() - rdh) / 2.0;
-
-        draw_rectangle(rdx, rdy, rdw, rdh, Color::from_rgba(45, 45, 50, 255));
-        draw_rectangle_lines(rdx, rdy, rdw, rdh, 2.0, Color::from_rgba(80, 80, 90, 255));
-        draw_text("Rename Level", rdx + 12.0, rdy + 22.0, 16.0, WHITE);
-
-        let input_rect = Rect::new(rdx + 12.0, rdy + 40.0, rdw - 24.0, 28.0);
-        if let Some(ref mut input_state) = browser.rename_dialog {
-            draw_text_input(input_rect, input_state, 14.0);
+    // Revision history, Rename and Open Link overlays
+    if let Some(history) = &mut browser.history {
+        // The history takes over the browser while open
+        action = match draw_revision_history(ctx, history) {
+            HistoryAction::Open(revision) => BrowserAction::OpenRevision(revision),
+            HistoryAction::Close => {
+                browser.history = None;
+                BrowserAction::None
+            }
+            HistoryAction::None => BrowserAction::None,
+        };
+    } else if let Some(input_state) = browser.rename_dialog.as_mut() {
+        match draw_input_dialog(ctx, "Rename Level", "Rename", 280.0, input_state) {
+            Some(true) => action = BrowserAction::RenameLevel,
+            Some(false) => browser.rename_dialog = None,
+            None => {}
         }
-
-        let btn_w = 80.0;
-        let btn_h = 28.0;
-        let btn_y = rdy + rdh - btn_h - 12.0;
-
-        let cancel_rect = Rect::new(rdx + rdw - btn_w * 2.0 - 20.0, btn_y, btn_w, btn_h);
-        let cancel_hover = ctx.mouse.inside(&cancel_rect);
-        draw_rectangle(cancel_rect.x, cancel_rect.y, cancel_rect.w, cancel_rect.h,
-            if cancel_hover { Color::from_rgba(70, 70, 75, 255) } else { Color::from_rgba(55, 55, 60, 255) });
-        draw_text("Cancel", cancel_rect.x + 18.0, cancel_rect.y + 18.0, 14.0, Color::from_rgba(200, 200, 200, 255));
-
-        let confirm_rect = Rect::new(rdx + rdw - btn_w - 12.0, btn_y, btn_w, btn_h);
-        let confirm_hover = ctx.mouse.inside(&confirm_rect);
-        draw_rectangle(confirm_rect.x, confirm_rect.y, confirm_rect.w, confirm_rect.h,
-            if confirm_hover { Color::from_rgba(60, 100, 140, 255) } else { ACCENT_COLOR });
-        draw_text("Rename", confirm_rect.x + 14.0, confirm_rect.y + 18.0, 14.0, WHITE);
-
-        if ctx.mouse.clicked(&cancel_rect) || is_key_pressed(KeyCode::Escape) {
-            browser.rename_dialog = None;
-        } else if ctx.mouse.clicked(&confirm_rect) || is_key_pressed(KeyCode::Enter) {
-            action = BrowserAction::RenameLevel;
+    } else if let Some(input_state) = browser.link_dialog.as_mut() {
+        match draw_input_dialog(ctx, "Open Shared Level (paste the link)", "Open", 440.0, input_state) {
+            Some(true) => action = BrowserAction::OpenLink,
+            Some(false) => browser.link_dialog = None,
+            None => {}
         }
     } else {
-        // Handle Escape to close (only when rename dialog is not open)
-        if is_key_pressed(KeyCode::Escape) {
+        // Handle Escape to close (only when no dialog is open)
+        if is_key_pressed(KeyCode::Escape) && !browser.tag_filter.is_typing() {
             action = BrowserAction::Cancel;
         }
     }
@@ -447,162 +509,46 @@ pub fn draw_level_browser(
     action
 }
 
-/// Draw the two-section list (Samples + My Levels)
-fn draw_two_section_list(
-    ctx: &mut UiContext,
-    rect: Rect,
-    browser: &mut LevelBrowser,
-    item_h: f32,
-    section_h: f32,
-    has_cloud: bool,
-) -> Option<(LevelCategory, usize)> {
-    let mut clicked: Option<(LevelCategory, usize)> = None;
-    let mut y = rect.y - browser.scroll_offset;
-
-    let section_bg = Color::from_rgba(40, 40, 50, 255);
-    let item_bg = Color::from_rgba(30, 30, 38, 255);
-    let item_hover = Color::from_rgba(50, 50, 60, 255);
-    let item_selected = Color::from_rgba(60, 80, 120, 255);
-    let text_color = Color::from_rgba(200, 200, 200, 255);
-    let text_dim = Color::from_rgba(140, 140, 140, 255);
-    let cloud_color = Color::from_rgba(100, 180, 255, 255);
-
-    // Calculate total content height for scroll
-    let samples_content_h = if browser.samples_collapsed { 0.0 } else { browser.samples.len() as f32 * item_h };
-    let user_content_h = if browser.user_collapsed { 0.0 } else { browser.user_levels.len() as f32 * item_h };
-    let total_h = section_h * 2.0 + samples_content_h + user_content_h;
-
-    // Handle scroll within list bounds
-    if ctx.mouse.inside(&rect) && ctx.mouse.scroll != 0.0 {
-        browser.scroll_offset = (browser.scroll_offset - ctx.mouse.scroll * 30.0)
-            .clamp(0.0, (total_h - rect.h).max(0.0));
-    }
-
-    // SAMPLES section header
-    let samples_header_rect = Rect::new(rect.x, y, rect.w, section_h);
-    if y + section_h > rect.y && y < rect.bottom() {
-        draw_rectangle(samples_header_rect.x, samples_header_rect.y.max(rect.y),
-                      samples_header_rect.w, section_h.min(rect.bottom() - samples_header_rect.y.max(rect.y)), section_bg);
-
-        let arrow = if browser.samples_collapsed { ">" } else { "v" };
-        draw_text(&format!("{} SAMPLE LEVELS ({})", arrow, browser.samples.len()),
-                 rect.x + 8.0, y + 18.0, 14.0, text_color);
-
-        // Toggle collapse on click
-        if ctx.mouse.inside(&samples_header_rect) && ctx.mouse.left_pressed {
-            browser.samples_collapsed = !browser.samples_collapsed;
-        }
-    }
-    y += section_h;
-
-    // SAMPLES items
-    if !browser.samples_collapsed {
-        for (i, level) in browser.samples.iter().enumerate() {
-            let item_rect = Rect::new(rect.x, y, rect.w, item_h);
-
-            if y + item_h > rect.y && y < rect.bottom() {
-                let is_selected = browser.selected_category == Some(LevelCategory::Sample)
-                    && browser.selected_index == Some(i);
-                let is_hovered = ctx.mouse.inside(&item_rect) && item_rect.y >= rect.y;
-
-                let bg = if is_selected { item_selected }
-                        else if is_hovered { item_hover }
-                        else { item_bg };
-
-                // Clip to list bounds
-                let draw_y = item_rect.y.max(rect.y);
-                let draw_h = item_h.min(rect.bottom() - draw_y);
-                if draw_h > 0.0 {
-                    draw_rectangle(item_rect.x + 2.0, draw_y, item_rect.w - 4.0, draw_h, bg);
-
-                    if y >= rect.y {
-                        draw_text(&level.name, rect.x + 20.0, y + 17.0, 13.0, text_color);
-                    }
-                }
-
-                // Handle click
-                if is_hovered && ctx.mouse.left_pressed && item_rect.y >= rect.y {
-                    clicked = Some((LevelCategory::Sample, i));
-                }
-            }
-            y += item_h;
-        }
-    }
-
-    // MY LEVELS section header
-    let user_header_rect = Rect::new(rect.x, y, rect.w, section_h);
-    if y + section_h > rect.y && y < rect.bottom() {
-        draw_rectangle(user_header_rect.x, user_header_rect.y.max(rect.y),
-                      user_header_rect.w, section_h.min(rect.bottom() - user_header_rect.y.max(rect.y)), section_bg);
-
-        let arrow = if browser.user_collapsed { ">" } else { "v" };
-        let cloud_indicator = if has_cloud { " [cloud]" } else { "" };
-        draw_text(&format!("{} MY LEVELS ({}){}", arrow, browser.user_levels.len(), cloud_indicator),
-                 rect.x + 8.0, y + 18.0, 14.0, text_color);
-
-        // Toggle collapse on click
-        if ctx.mouse.inside(&user_header_rect) && ctx.mouse.left_pressed && user_header_rect.y >= rect.y {
-            browser.user_collapsed = !browser.user_collapsed;
-        }
-    }
-    y += section_h;
-
-    // MY LEVELS items
-    if !browser.user_collapsed {
-        if browser.is_loading_user_levels() {
-            // Show loading indicator
-            if y + item_h > rect.y && y < rect.bottom() {
-                let time = get_time() as f32;
-                let spinner_chars = ['|', '/', '-', '\\'];
-                let spinner_idx = (time * 8.0) as usize % spinner_chars.len();
-                let loading_text = format!("  {} Loading...", spinner_chars[spinner_idx]);
-                draw_text(&loading_text, rect.x + 8.0, y + 17.0, 12.0, text_dim);
-            }
-        } else if browser.user_levels.is_empty() {
-            // Show empty state message
-            if y + item_h > rect.y && y < rect.bottom() {
-                draw_text("  (no saved levels)", rect.x + 8.0, y + 17.0, 12.0, text_dim);
-            }
-        } else {
-            for (i, level) in browser.user_levels.iter().enumerate() {
-                let item_rect = Rect::new(rect.x, y, rect.w, item_h);
-
-                if y + item_h > rect.y && y < rect.bottom() {
-                    let is_selected = browser.selected_category == Some(LevelCategory::User)
-                        && browser.selected_index == Some(i);
-                    let is_hovered = ctx.mouse.inside(&item_rect) && item_rect.y >= rect.y;
-
-                    let bg = if is_selected { item_selected }
-                            else if is_hovered { item_hover }
-                            else { item_bg };
-
-                    // Clip to list bounds
-                    let draw_y = item_rect.y.max(rect.y);
-                    let draw_h = item_h.min(rect.bottom() - draw_y);
-                    if draw_h > 0.0 {
-                        draw_rectangle(item_rect.x + 2.0, draw_y, item_rect.w - 4.0, draw_h, bg);
-
-                        if y >= rect.y {
-                            draw_text(&level.name, rect.x + 20.0, y + 17.0, 13.0, text_color);
-
-                            // Cloud icon for user levels when cloud storage is active
-                            if has_cloud {
-                                draw_text("*", rect.x + rect.w - 20.0, y + 17.0, 13.0, cloud_color);
-                            }
-                        }
-                    }
-
-                    // Handle click
-                    if is_hovered && ctx.mouse.left_pressed && item_rect.y >= rect.y {
-                        clicked = Some((LevelCategory::User, i));
-                    }
-                }
-                y += item_h;
-            }
-        }
+/// Draw a small modal with one text field and Cancel/confirm buttons.
+/// Returns Some(true) when confirmed (button or Enter), Some(false) when
+/// cancelled (button or Escape).
+fn draw_input_dialog(ctx: &mut UiContext, title: &str, confirm_label: &str, width: f32, input_state: &mut TextInputState) -> Option<bool> {
+    let rdw = width;
+    let rdh = 120.0;
+    let rdx = (screen_width() - rdw) / 2.0;
+    let rdy = (screen_height() - rdh) / 2.0;
+
+    draw_rectangle(rdx, rdy, rdw, rdh, Color::from_rgba(45, 45, 50, 255));
+    draw_rectangle_lines(rdx, rdy, rdw, rdh, 2.0, Color::from_rgba(80, 80, 90, 255));
+    draw_text(title, rdx + 12.0, rdy + 22.0, 16.0, WHITE);
+
+    let input_rect = Rect::new(rdx + 12.0, rdy + 40.0, rdw - 24.0, 28.0);
+    draw_text_input(input_rect, input_state, 14.0);
+
+    let btn_w = 80.0;
+    let btn_h = 28.0;
+    let btn_y = rdy + rdh - btn_h - 12.0;
+
+    let cancel_rect = Rect::new(rdx + rdw - btn_w * 2.0 - 20.0, btn_y, btn_w, btn_h);
+    let cancel_hover = ctx.mouse.inside(&cancel_rect);
+    draw_rectangle(cancel_rect.x, cancel_rect.y, cancel_rect.w, cancel_rect.h,
+        if cancel_hover { Color::from_rgba(70, 70, 75, 255) } else { Color::from_rgba(55, 55, 60, 255) });
+    draw_text("Cancel", cancel_rect.x + 18.0, cancel_rect.y + 18.0, 14.0, Color::from_rgba(200, 200, 200, 255));
+
+    let confirm_rect = Rect::new(rdx + rdw - btn_w - 12.0, btn_y, btn_w, btn_h);
+    let confirm_hover = ctx.mouse.inside(&confirm_rect);
+    draw_rectangle(confirm_rect.x, confirm_rect.y, confirm_rect.w, confirm_rect.h,
+        if confirm_hover { Color::from_rgba(60, 100, 140, 255) } else { theme().accent });
+    let label_w = measure_text(confirm_label, None, 14, 1.0).width;
+    draw_text(confirm_label, confirm_rect.x + ((btn_w - label_w) / 2.0).floor(), confirm_rect.y + 18.0, 14.0, WHITE);
+
+    if ctx.mouse.clicked(&cancel_rect) || is_key_pressed(KeyCode::Escape) {
+        Some(false)
+    } else if ctx.mouse.clicked(&confirm_rect) || is_key_pressed(KeyCode::Enter) {
+        Some(true)
+    } else {
+        None
     }
-
-    clicked
 }
 
 /// Draw the orbit preview of a level (uses browser's local framebuffer)
@@ -610,12 +556,15 @@ fn draw_orbit_preview(
     ctx: &mut UiContext,
     browser: &mut LevelBrowser,
     rect: Rect,
+    storage: &Storage,
     texture_packs: &[TexturePack],
     asset_library: &crate::asset::AssetLibrary,
     user_textures: &crate::texture::TextureLibrary,
 ) {
     use crate::rasterizer::WIDTH;
 
+    let thumbnail_key = browser.selected_level().map(|info| info.library_key());
+
     // Get the level from browser (we know it exists from the caller check)
     let level = match &browser.preview_level {
         Some(l) => l,
@@ -780,11 +729,27 @@ fn draw_orbit_preview(
         &resolve_texture,
         &crate::scene::SceneRenderOptions {
             use_fog: false,
+            atmosphere: None,
             render_assets: true,
             skip_rooms: &[],
+            lod_scale: None,
+            hidden_objects: &[],
+            moved_objects: &[],
+            broken_objects: &[],
+            dropped_assets: &[],
+            baked_lighting: true,
+            water_time: Some(get_time() as f32),
         },
     );
 
+    // First frame of a newly loaded level: keep it as the list thumbnail
+    if browser.thumbnail_pending {
+        if let Some(key) = &thumbnail_key {
+            thumbnails::capture(storage, TagKind::Level, key, fb);
+        }
+        browser.thumbnail_pending = false;
+    }
+
     // Draw framebuffer to screen
     let fb_texture = Texture2D::from_rgba8(fb.width as u16, fb.height as u16, &fb.pixels);
     fb_texture.set_filter(FilterMode::Nearest);
@@ -802,7 +767,7 @@ fn draw_orbit_preview(
 }
 
 /// Draw a close button (X)
-fn draw_close_button(ctx: &mut UiContext, rect: Rect, icon_font: Option<&Font>) -> bool {
+pub(super) fn draw_close_button(ctx: &mut UiContext, rect: Rect, icon_font: Option<&Font>) -> bool {
     let hovered = ctx.mouse.inside(&rect);
     let clicked = hovered && ctx.mouse.left_pressed;
 
@@ -817,12 +782,12 @@ fn draw_close_button(ctx: &mut UiContext, rect: Rect, icon_font: Option<&Font>)
 }
 
 /// Draw a text button
-fn draw_text_button(ctx: &mut UiContext, rect: Rect, text: &str, bg_color: Color) -> bool {
+pub(super) fn draw_text_button(ctx: &mut UiContext, rect: Rect, text: &str, bg_color: Color) -> bool {
     draw_text_button_enabled(ctx, rect, text, bg_color, true)
 }
 
 /// Draw a text button with enabled state
-fn draw_text_button_enabled(ctx: &mut UiContext, rect: Rect, text: &str, bg_color: Color, enabled: bool) -> bool {
+pub(super) fn draw_text_button_enabled(ctx: &mut UiContext, rect: Rect, text: &str, bg_color: Color, enabled: bool) -> bool {
     let hovered = enabled && ctx.mouse.inside(&rect);
     let clicked = hovered && ctx.mouse.left_pressed;
 
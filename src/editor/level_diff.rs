@@ -0,0 +1,114 @@
+//! Level Diff View
+//!
+//! Editor side of `world::LevelDiff`: holds the base level being compared
+//! against (the last-saved file, or any level file picked on native) and keeps
+//! the diff up to date while the level is edited. The grid view highlights the
+//! changes and shows the report.
+
+use macroquad::prelude::*;
+use crate::storage::Storage;
+use crate::world::{parse_level_data, ChangeKind, Level, LevelDiff};
+
+/// Seconds between diff recomputations while editing
+const REFRESH_INTERVAL: f64 = 1.0;
+
+/// Report lines shown in the grid view before "... N more"
+pub const MAX_REPORT_LINES: usize = 8;
+
+/// Active comparison of the open level against a base level
+pub struct LevelDiffView {
+    /// Level being compared against
+    pub base: Level,
+    /// Where the base came from (shown in the legend)
+    pub base_name: String,
+    pub diff: LevelDiff,
+    /// Time (get_time) the diff was last computed
+    computed_at: f64,
+}
+
+impl LevelDiffView {
+    pub fn new(base: Level, base_name: String, level: &Level, now: f64) -> Self {
+        let diff = LevelDiff::compare(&base, level);
+        Self { base, base_name, diff, computed_at: now }
+    }
+
+    /// Recompute the diff if it's older than the refresh interval
+    pub fn refresh(&mut self, level: &Level, now: f64) {
+        if now - self.computed_at >= REFRESH_INTERVAL {
+            self.diff = LevelDiff::compare(&self.base, level);
+            self.computed_at = now;
+        }
+    }
+
+    /// Compare against a level file read through storage (e.g. the last save)
+    pub fn from_storage(path: &str, storage: &Storage, level: &Level, now: f64) -> Result<Self, String> {
+        let bytes = storage.read_sync(path).map_err(|e| e.to_string())?;
+        let base = parse_level_data(&bytes).map_err(|e| e.to_string())?;
+        Ok(Self::new(base, display_name(path), level, now))
+    }
+
+    /// Compare against any level file on disk
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(path: &std::path::Path, level: &Level, now: f64) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        let base = parse_level_data(&bytes).map_err(|e| e.to_string())?;
+        Ok(Self::new(base, display_name(&path.to_string_lossy()), level, now))
+    }
+}
+
+fn display_name(path: &str) -> String {
+    path.rsplit(['/', '\\']).next().unwrap_or(path).to_string()
+}
+
+/// Highlight color for a changed sector
+pub fn sector_change_color(kind: ChangeKind, texture_only: bool) -> Color {
+    match kind {
+        ChangeKind::Added => Color::from_rgba(80, 220, 100, 255),
+        ChangeKind::Removed => Color::from_rgba(230, 70, 70, 255),
+        ChangeKind::Modified if texture_only => Color::from_rgba(80, 200, 230, 255),
+        ChangeKind::Modified => Color::from_rgba(230, 200, 70, 255),
+    }
+}
+
+/// Draw the diff summary and report in the bottom-left corner of the grid view
+pub fn draw_diff_report(rect: crate::ui::Rect, view: &LevelDiffView) {
+    let lines = view.diff.report();
+    let shown = lines.len().min(MAX_REPORT_LINES);
+    let extra = lines.len() - shown;
+    let line_h = 13.0;
+    let rows = 1 + shown + if extra > 0 { 1 } else { 0 };
+    let box_h = rows as f32 * line_h + 8.0;
+    let box_w = (rect.w - 8.0).min(360.0);
+    let x = rect.x + 4.0;
+    let y = rect.bottom() - box_h - 4.0;
+
+    draw_rectangle(x, y, box_w, box_h, Color::from_rgba(20, 20, 25, 210));
+    let header = format!("Diff vs {}: {}", view.base_name, view.diff.summary());
+    let mut ty = y + 4.0 + line_h - 3.0;
+    draw_text(&truncate(&header, box_w - 8.0), x + 4.0, ty, 12.0, Color::from_rgba(230, 230, 240, 255));
+    for line in lines.iter().take(shown) {
+        ty += line_h;
+        let color = match line.chars().next() {
+            Some('+') => sector_change_color(ChangeKind::Added, false),
+            Some('-') => sector_change_color(ChangeKind::Removed, false),
+            _ => sector_change_color(ChangeKind::Modified, false),
+        };
+        draw_text(&truncate(line, box_w - 8.0), x + 4.0, ty, 12.0, color);
+    }
+    if extra > 0 {
+        ty += line_h;
+        draw_text(&format!("... {} more", extra), x + 4.0, ty, 12.0, Color::from_rgba(150, 150, 160, 255));
+    }
+}
+
+/// Cut text to fit `max_w` pixels at font size 12
+fn truncate(text: &str, max_w: f32) -> String {
+    if measure_text(text, None, 12, 1.0).width <= max_w {
+        return text.to_string();
+    }
+    let mut out = text.to_string();
+    while !out.is_empty() && measure_text(&format!("{}...", out), None, 12, 1.0).width > max_w {
+        out.pop();
+    }
+    format!("{}...", out)
+}
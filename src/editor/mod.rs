@@ -16,13 +16,25 @@ mod grid_view;
 mod viewport_3d;
 mod texture_palette;
 mod texture_pack;
+mod texture_brush;
 mod sample_levels;
 mod level_browser;
+mod prefab;
+mod prefab_browser;
+mod level_diff;
+mod dungeon_wizard;
+mod share;
 pub mod actions;
 
 pub use state::*;
 pub use layout::*;
 pub use texture_pack::TexturePack;
+pub use texture_brush::*;
 pub use sample_levels::*;
 pub use level_browser::*;
+pub use prefab::*;
+pub use prefab_browser::*;
+pub use level_diff::*;
+pub use dungeon_wizard::*;
+pub use share::*;
 // Actions used internally by layout.rs
@@ -0,0 +1,310 @@
+//! Prefabs
+//!
+//! Reusable pieces of level geometry (floors, ceilings, walls and asset
+//! instances) saved from a selection in the World Editor and stamped into any
+//! level through the prefab browser.
+//!
+//! Prefabs are stored as brotli-compressed RON files in `assets/userdata/prefabs/`
+//! (native only). Faces reference textures by pack and name, so stamping into a
+//! level that uses different texture packs remaps each texture to a pack that
+//! has a texture with the same name.
+
+use std::io::Cursor;
+use std::path::PathBuf;
+use serde::{Serialize, Deserialize};
+use crate::texture::TextureLibrary;
+use crate::world::{AssetInstance, TextureRef};
+use crate::world::limits::{MAX_ROOM_SIZE, MAX_STRING_LEN};
+use super::{CopiedFace, CopiedFaceData, GeometryClipboard, TexturePack};
+
+/// Directory prefabs are saved to
+pub const PREFABS_DIR: &str = "assets/userdata/prefabs";
+
+/// A saved selection of geometry and objects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prefab {
+    /// Display name
+    pub name: String,
+    /// Faces with sector positions relative to the prefab's min corner
+    #[serde(default)]
+    pub faces: Vec<CopiedFace>,
+    /// Asset instances (sector_x/sector_z relative to the prefab's min corner)
+    #[serde(default)]
+    pub objects: Vec<AssetInstance>,
+}
+
+/// Metadata about a prefab file (without loading it)
+#[derive(Debug, Clone)]
+pub struct PrefabInfo {
+    /// Display name (filename without extension)
+    pub name: String,
+    /// Full path to the prefab file
+    pub path: PathBuf,
+}
+
+/// Outcome of remapping a prefab's textures onto the available packs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextureRemap {
+    /// References moved to another pack (or to user textures)
+    pub remapped: usize,
+    /// References no loaded pack or user texture can satisfy
+    pub missing: usize,
+}
+
+impl Prefab {
+    /// Create a prefab from a geometry clipboard (rotation/flip state is dropped)
+    pub fn from_clipboard(name: &str, gc: &GeometryClipboard) -> Self {
+        Self {
+            name: name.to_string(),
+            faces: gc.faces.clone(),
+            objects: gc.objects.clone(),
+        }
+    }
+
+    /// Load the prefab into a fresh clipboard for stamping
+    pub fn to_clipboard(&self) -> GeometryClipboard {
+        GeometryClipboard {
+            faces: self.faces.clone(),
+            objects: self.objects.clone(),
+            ..GeometryClipboard::new()
+        }
+    }
+
+    /// Footprint in sectors (width, depth)
+    pub fn size(&self) -> (usize, usize) {
+        let (min_x, max_x, min_z, max_z) = self.to_clipboard().bounds();
+        ((max_x - min_x + 1) as usize, (max_z - min_z + 1) as usize)
+    }
+
+    /// Count faces by kind: (floors, ceilings, walls)
+    pub fn face_counts(&self) -> (usize, usize, usize) {
+        let floors = self.faces.iter().filter(|f| matches!(f.face, CopiedFaceData::Floor(_))).count();
+        let ceilings = self.faces.iter().filter(|f| matches!(f.face, CopiedFaceData::Ceiling(_))).count();
+        (floors, ceilings, self.faces.len() - floors - ceilings)
+    }
+
+    /// Visit every texture reference in the prefab
+    fn for_each_texture_mut(&mut self, mut f: impl FnMut(&mut TextureRef)) {
+        for face in &mut self.faces {
+            match &mut face.face {
+                CopiedFaceData::Floor(h) | CopiedFaceData::Ceiling(h) => {
+                    f(&mut h.texture);
+                    if let Some(tex) = &mut h.texture_2 {
+                        f(tex);
+                    }
+                }
+                CopiedFaceData::WallNorth(_, w)
+                | CopiedFaceData::WallEast(_, w)
+                | CopiedFaceData::WallSouth(_, w)
+                | CopiedFaceData::WallWest(_, w)
+                | CopiedFaceData::WallNwSe(_, w)
+                | CopiedFaceData::WallNeSw(_, w) => f(&mut w.texture),
+            }
+        }
+    }
+
+    /// Point texture references at packs that are actually loaded.
+    ///
+    /// References that resolve as-is are kept. Otherwise the first pack with a
+    /// texture of the same name wins, then user textures. Anything left over
+    /// renders with the fallback checkerboard and is counted as missing.
+    pub fn remap_textures(&mut self, packs: &[TexturePack], user_textures: &TextureLibrary) -> TextureRemap {
+        let mut result = TextureRemap::default();
+        self.for_each_texture_mut(|tex| {
+            if !tex.is_valid() {
+                return;
+            }
+            let resolves = if tex.is_user_texture() {
+                user_textures.contains(&tex.name)
+            } else {
+                packs.iter().any(|p| p.name == tex.pack && p.textures.iter().any(|t| t.name == tex.name))
+            };
+            if resolves {
+                return;
+            }
+            if let Some(pack) = packs.iter().find(|p| p.textures.iter().any(|t| t.name == tex.name)) {
+                tex.pack = pack.name.clone();
+                result.remapped += 1;
+            } else if user_textures.contains(&tex.name) {
+                *tex = TextureRef::user(tex.name.clone());
+                result.remapped += 1;
+            } else {
+                result.missing += 1;
+            }
+        });
+        result
+    }
+
+    /// Reject prefabs that would blow up a room when stamped
+    fn validate(&self) -> Result<(), String> {
+        if self.name.len() > MAX_STRING_LEN {
+            return Err("prefab name too long".to_string());
+        }
+        let in_range = |x: i32, z: i32| (0..MAX_ROOM_SIZE as i32).contains(&x) && (0..MAX_ROOM_SIZE as i32).contains(&z);
+        if let Some(face) = self.faces.iter().find(|f| !in_range(f.rel_x, f.rel_z)) {
+            return Err(format!("face at ({}, {}) is outside the {}x{} limit", face.rel_x, face.rel_z, MAX_ROOM_SIZE, MAX_ROOM_SIZE));
+        }
+        if self.objects.iter().any(|o| o.sector_x >= MAX_ROOM_SIZE || o.sector_z >= MAX_ROOM_SIZE) {
+            return Err(format!("object outside the {}x{} limit", MAX_ROOM_SIZE, MAX_ROOM_SIZE));
+        }
+        Ok(())
+    }
+}
+
+/// Turn a prefab name into a safe file stem (None if nothing usable is left)
+pub fn prefab_file_stem(name: &str) -> Option<String> {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' { c } else { '_' })
+        .collect();
+    let stem = stem.trim().to_string();
+    (!stem.is_empty()).then_some(stem)
+}
+
+/// Serialize a prefab to compressed bytes
+pub fn serialize_prefab(prefab: &Prefab) -> Result<Vec<u8>, String> {
+    let config = ron::ser::PrettyConfig::new()
+        .depth_limit(4)
+        .indentor("  ".to_string());
+    let ron_string = ron::ser::to_string_pretty(prefab, config).map_err(|e| e.to_string())?;
+
+    let mut compressed = Vec::new();
+    brotli::BrotliCompress(&mut Cursor::new(ron_string.as_bytes()), &mut compressed, &brotli::enc::BrotliEncoderParams {
+        quality: 6,
+        lgwin: 22,
+        ..Default::default()
+    }).map_err(|e| format!("brotli compression failed: {}", e))?;
+    Ok(compressed)
+}
+
+/// Parse a prefab from bytes (compressed or plain RON)
+pub fn parse_prefab_data(bytes: &[u8]) -> Result<Prefab, String> {
+    let is_plain_ron = bytes.first().map(|&b| b == b'(' || b.is_ascii_whitespace()).unwrap_or(false);
+    let contents = if is_plain_ron {
+        String::from_utf8(bytes.to_vec()).map_err(|e| format!("invalid UTF-8: {}", e))?
+    } else {
+        let mut decompressed = Vec::new();
+        brotli::BrotliDecompress(&mut Cursor::new(bytes), &mut decompressed)
+            .map_err(|e| format!("brotli decompression failed: {}", e))?;
+        String::from_utf8(decompressed).map_err(|e| format!("invalid UTF-8 after decompression: {}", e))?
+    };
+    let prefab: Prefab = ron::from_str(&contents).map_err(|e| e.to_string())?;
+    prefab.validate()?;
+    Ok(prefab)
+}
+
+/// Discover saved prefabs (native)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn discover_prefabs() -> Vec<PrefabInfo> {
+    let mut prefabs = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(PREFABS_DIR) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.is_file() && path.extension().map(|e| e == "ron").unwrap_or(false) {
+                let name = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "unnamed".to_string());
+                prefabs.push(PrefabInfo { name, path });
+            }
+        }
+    }
+    prefabs.sort_by(|a, b| a.name.cmp(&b.name));
+    prefabs
+}
+
+/// Discover saved prefabs (WASM: prefabs are not available in the browser)
+#[cfg(target_arch = "wasm32")]
+pub fn discover_prefabs() -> Vec<PrefabInfo> {
+    Vec::new()
+}
+
+/// Load a prefab file
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_prefab(path: &std::path::Path) -> Result<Prefab, String> {
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    parse_prefab_data(&bytes)
+}
+
+/// Save a prefab under `PREFABS_DIR`, returning the file path
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_prefab(prefab: &Prefab) -> Result<PathBuf, String> {
+    let stem = prefab_file_stem(&prefab.name).ok_or_else(|| "Name cannot be empty".to_string())?;
+    std::fs::create_dir_all(PREFABS_DIR).map_err(|e| e.to_string())?;
+    let path = PathBuf::from(PREFABS_DIR).join(format!("{}.ron", stem));
+    std::fs::write(&path, serialize_prefab(prefab)?).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rasterizer::Texture;
+    use crate::world::{HorizontalFace, VerticalFace};
+
+    fn pack(name: &str, textures: &[&str]) -> TexturePack {
+        TexturePack {
+            name: name.to_string(),
+            path: PathBuf::new(),
+            textures: textures.iter().map(|t| {
+                let mut tex = Texture::new(4, 4);
+                tex.name = t.to_string();
+                tex
+            }).collect(),
+        }
+    }
+
+    fn sample_prefab() -> Prefab {
+        Prefab {
+            name: "corridor".to_string(),
+            faces: vec![
+                CopiedFace { rel_x: 0, rel_z: 0, face: CopiedFaceData::Floor(HorizontalFace::flat(0.0, TextureRef::new("OLD", "stone"))) },
+                CopiedFace { rel_x: 1, rel_z: 0, face: CopiedFaceData::WallNorth(0, VerticalFace::new(0.0, 1024.0, TextureRef::new("OLD", "brick"))) },
+                CopiedFace { rel_x: 1, rel_z: 2, face: CopiedFaceData::Ceiling(HorizontalFace::flat(1024.0, TextureRef::new("KEEP", "moss"))) },
+            ],
+            objects: vec![AssetInstance::new(1, 1, 42)],
+        }
+    }
+
+    #[test]
+    fn test_prefab_roundtrip() {
+        let prefab = sample_prefab();
+        let bytes = serialize_prefab(&prefab).unwrap();
+        let loaded = parse_prefab_data(&bytes).unwrap();
+        assert_eq!(loaded.name, "corridor");
+        assert_eq!(loaded.faces.len(), 3);
+        assert_eq!(loaded.objects[0].asset_id, 42);
+        assert_eq!(loaded.size(), (2, 3));
+        assert_eq!(loaded.face_counts(), (1, 1, 1));
+    }
+
+    #[test]
+    fn test_remap_textures() {
+        let mut prefab = sample_prefab();
+        let packs = [pack("KEEP", &["moss"]), pack("NEW", &["stone", "moss"])];
+        let remap = prefab.remap_textures(&packs, &TextureLibrary::new());
+        // stone moves to NEW, brick is nowhere, moss already resolves in KEEP
+        assert_eq!(remap, TextureRemap { remapped: 1, missing: 1 });
+        let CopiedFaceData::Floor(floor) = &prefab.faces[0].face else { panic!() };
+        assert_eq!(floor.texture, TextureRef::new("NEW", "stone"));
+        let CopiedFaceData::Ceiling(ceiling) = &prefab.faces[2].face else { panic!() };
+        assert_eq!(ceiling.texture.pack, "KEEP");
+    }
+
+    #[test]
+    fn test_rejects_out_of_range() {
+        let mut prefab = sample_prefab();
+        prefab.faces[0].rel_x = -1;
+        assert!(prefab.validate().is_err());
+        let mut prefab = sample_prefab();
+        prefab.objects[0].sector_z = MAX_ROOM_SIZE;
+        assert!(prefab.validate().is_err());
+    }
+
+    #[test]
+    fn test_prefab_file_stem() {
+        assert_eq!(prefab_file_stem("  Big Room/2 "), Some("Big Room_2".to_string()));
+        assert_eq!(prefab_file_stem("   "), None);
+    }
+}
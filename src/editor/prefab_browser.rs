@@ -0,0 +1,303 @@
+//! Prefab Browser
+//!
+//! Modal dialog for picking a saved prefab to stamp into the level, with a
+//! top-down preview of its footprint. Also hosts the name prompt used when
+//! saving the current selection as a prefab. The list has the same search
+//! and tag filter as the other browsers.
+
+use macroquad::prelude::*;
+use crate::ui::{Rect, UiContext, theme, TextInputState, draw_text_input};
+use crate::tags::{TagFilter, TagKind, draw_tag_filter, draw_item_tags, TAG_FILTER_HEIGHT, TAG_ROW_HEIGHT};
+use crate::browser_list::{draw_browser_list, ListItem, ListSection};
+use super::level_browser::{draw_close_button, draw_text_button, draw_text_button_enabled};
+use super::prefab::{Prefab, PrefabInfo};
+use super::CopiedFaceData;
+
+/// State for the prefab browser dialog
+#[derive(Default)]
+pub struct PrefabBrowser {
+    /// Whether the browser is open
+    pub open: bool,
+    /// Saved prefabs
+    pub prefabs: Vec<PrefabInfo>,
+    /// Currently selected index
+    pub selected_index: Option<usize>,
+    /// Currently loaded preview prefab
+    pub preview: Option<Prefab>,
+    /// Scroll offset for the list
+    pub scroll_offset: f32,
+    /// Prefab waiting for a name before it is saved
+    pub pending_save: Option<Prefab>,
+    /// Name input for the save prompt
+    pub save_dialog: Option<TextInputState>,
+    /// Whether the prefab list is collapsed
+    pub collapsed: bool,
+    /// Search and tag filter above the list
+    pub tag_filter: TagFilter,
+}
+
+impl PrefabBrowser {
+    /// Open the browser with a list of prefabs
+    pub fn open_with_prefabs(&mut self, prefabs: Vec<PrefabInfo>) {
+        self.open = true;
+        self.prefabs = prefabs;
+        self.selected_index = None;
+        self.preview = None;
+        self.scroll_offset = 0.0;
+    }
+
+    /// Open the browser with the save prompt for `prefab`
+    pub fn open_save(&mut self, prefab: Prefab, prefabs: Vec<PrefabInfo>) {
+        self.open_with_prefabs(prefabs);
+        let mut input = TextInputState::new(&prefab.name);
+        input.select_all();
+        self.save_dialog = Some(input);
+        self.pending_save = Some(prefab);
+    }
+
+    /// Close the browser
+    pub fn close(&mut self) {
+        self.open = false;
+        self.preview = None;
+        self.pending_save = None;
+        self.save_dialog = None;
+    }
+
+    /// Get the currently selected prefab info
+    pub fn selected_prefab(&self) -> Option<&PrefabInfo> {
+        self.selected_index.and_then(|i| self.prefabs.get(i))
+    }
+
+    /// Name typed into the save prompt
+    pub fn save_name(&self) -> Option<String> {
+        self.save_dialog.as_ref().map(|input| input.text.trim().to_string())
+    }
+}
+
+/// Result from drawing the prefab browser
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrefabBrowserAction {
+    None,
+    /// User selected a prefab to preview (needs loading)
+    SelectPreview(usize),
+    /// User wants to stamp the previewed prefab into the level
+    Stamp,
+    /// User wants to delete the selected prefab
+    Delete,
+    /// User confirmed the save prompt
+    Save,
+    /// User cancelled
+    Cancel,
+}
+
+/// Draw the prefab browser modal dialog
+pub fn draw_prefab_browser(
+    ctx: &mut UiContext,
+    browser: &mut PrefabBrowser,
+    icon_font: Option<&Font>,
+) -> PrefabBrowserAction {
+    if !browser.open {
+        return PrefabBrowserAction::None;
+    }
+
+    let mut action = PrefabBrowserAction::None;
+
+    // Darken background
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::from_rgba(0, 0, 0, 180));
+
+    let dialog_w = (screen_width() * 0.7).min(760.0);
+    let dialog_h = (screen_height() * 0.7).min(520.0);
+    let dialog_x = (screen_width() - dialog_w) / 2.0;
+    let dialog_y = (screen_height() - dialog_h) / 2.0;
+
+    draw_rectangle(dialog_x, dialog_y, dialog_w, dialog_h, Color::from_rgba(35, 35, 40, 255));
+    draw_rectangle_lines(dialog_x, dialog_y, dialog_w, dialog_h, 2.0, Color::from_rgba(60, 60, 70, 255));
+
+    // Header
+    let header_h = 40.0;
+    draw_rectangle(dialog_x, dialog_y, dialog_w, header_h, Color::from_rgba(45, 45, 55, 255));
+    draw_text("Prefabs", dialog_x + 16.0, dialog_y + 26.0, 20.0, WHITE);
+
+    let close_rect = Rect::new(dialog_x + dialog_w - 36.0, dialog_y + 4.0, 32.0, 32.0);
+    if draw_close_button(ctx, close_rect, icon_font) {
+        action = PrefabBrowserAction::Cancel;
+    }
+
+    // Content area
+    let content_y = dialog_y + header_h + 8.0;
+    let content_h = dialog_h - header_h - 60.0;
+    let list_w = 200.0;
+
+    // Search and tag filter above the list
+    let filter_rect = Rect::new(dialog_x + 8.0, content_y, list_w, TAG_FILTER_HEIGHT);
+    draw_tag_filter(ctx, filter_rect, &mut browser.tag_filter);
+
+    // Prefab list (left)
+    let list_top = filter_rect.bottom() + 4.0;
+    let list_rect = Rect::new(dialog_x + 8.0, list_top, list_w, content_y + content_h - list_top);
+    let sections = [ListSection {
+        title: "PREFABS",
+        items: browser.prefabs.iter().map(|p| ListItem { name: &p.name, key: p.name.clone() }).collect(),
+        collapsed: browser.collapsed,
+        loading: false,
+        empty_text: None,
+        cloud: false,
+    }];
+    let selected = browser.selected_index.map(|i| (0, i));
+    let list = draw_browser_list(
+        ctx,
+        list_rect,
+        &mut browser.scroll_offset,
+        &sections,
+        &browser.tag_filter,
+        TagKind::Prefab,
+        selected,
+        None,
+    );
+    if list.toggled.is_some() {
+        browser.collapsed = !browser.collapsed;
+    }
+    if let Some((_, idx)) = list.clicked {
+        if browser.selected_index != Some(idx) {
+            browser.selected_index = Some(idx);
+            action = PrefabBrowserAction::SelectPreview(idx);
+        }
+    }
+
+    // Preview (right), with the selected prefab's tags under it
+    let preview_x = dialog_x + list_w + 16.0;
+    let preview_w = dialog_w - list_w - 24.0;
+    let preview_rect = Rect::new(preview_x, content_y, preview_w, content_h - TAG_ROW_HEIGHT - 4.0);
+    if let Some(key) = browser.selected_prefab().map(|info| info.name.clone()) {
+        let tags_rect = Rect::new(preview_x, preview_rect.bottom() + 4.0, preview_w, TAG_ROW_HEIGHT);
+        draw_item_tags(ctx, tags_rect, &mut browser.tag_filter, TagKind::Prefab, &key);
+    }
+    draw_rectangle(preview_rect.x, preview_rect.y, preview_rect.w, preview_rect.h, Color::from_rgba(20, 20, 25, 255));
+
+    if let Some(prefab) = &browser.preview {
+        let stats_h = 24.0;
+        let map_rect = Rect::new(preview_rect.x + 12.0, preview_rect.y + 12.0, preview_rect.w - 24.0, preview_rect.h - stats_h - 24.0);
+        draw_prefab_footprint(prefab, map_rect);
+
+        let stats_y = preview_rect.bottom() - stats_h;
+        draw_rectangle(preview_rect.x, stats_y, preview_rect.w, stats_h, Color::from_rgba(30, 30, 35, 200));
+        let (w, d) = prefab.size();
+        let (floors, ceilings, walls) = prefab.face_counts();
+        let stats_text = format!(
+            "{}x{} sectors  Floors: {}  Ceilings: {}  Walls: {}  Objects: {}",
+            w, d, floors, ceilings, walls, prefab.objects.len()
+        );
+        draw_text(&stats_text, preview_rect.x + 8.0, stats_y + 17.0, 14.0, Color::from_rgba(180, 180, 180, 255));
+    } else if browser.prefabs.is_empty() {
+        draw_text("No prefabs yet", preview_rect.x + 20.0, preview_rect.y + 40.0, 16.0, Color::from_rgba(100, 100, 100, 255));
+        draw_text("Select sectors or faces and use 'Save Selection as Prefab'",
+            preview_rect.x + 20.0, preview_rect.y + 62.0, 14.0, Color::from_rgba(100, 100, 100, 255));
+    } else {
+        draw_text("Select a prefab to preview", preview_rect.x + 20.0, preview_rect.y + 40.0, 16.0, Color::from_rgba(100, 100, 100, 255));
+    }
+
+    // Footer with buttons
+    let footer_y = dialog_y + dialog_h - 44.0;
+    draw_rectangle(dialog_x, footer_y, dialog_w, 44.0, Color::from_rgba(40, 40, 48, 255));
+
+    let delete_rect = Rect::new(dialog_x + 10.0, footer_y + 8.0, 70.0, 28.0);
+    let delete_enabled = browser.selected_prefab().is_some();
+    if draw_text_button_enabled(ctx, delete_rect, "Delete", Color::from_rgba(120, 50, 50, 255), delete_enabled) {
+        action = PrefabBrowserAction::Delete;
+    }
+
+    let cancel_rect = Rect::new(dialog_x + dialog_w - 170.0, footer_y + 8.0, 70.0, 28.0);
+    if draw_text_button(ctx, cancel_rect, "Cancel", Color::from_rgba(60, 60, 70, 255)) {
+        action = PrefabBrowserAction::Cancel;
+    }
+
+    let stamp_rect = Rect::new(dialog_x + dialog_w - 90.0, footer_y + 8.0, 80.0, 28.0);
+    if draw_text_button_enabled(ctx, stamp_rect, "Stamp", theme().accent, browser.preview.is_some()) {
+        action = PrefabBrowserAction::Stamp;
+    }
+
+    // Save prompt overlay
+    if browser.save_dialog.is_some() {
+        let sdw = 300.0;
+        let sdh = 120.0;
+        let sdx = (screen_width() - sdw) / 2.0;
+        let sdy = (screen_height() - sdh) / 2.0;
+
+        draw_rectangle(sdx, sdy, sdw, sdh, Color::from_rgba(45, 45, 50, 255));
+        draw_rectangle_lines(sdx, sdy, sdw, sdh, 2.0, Color::from_rgba(80, 80, 90, 255));
+        draw_text("Save Prefab As", sdx + 12.0, sdy + 22.0, 16.0, WHITE);
+
+        let input_rect = Rect::new(sdx + 12.0, sdy + 40.0, sdw - 24.0, 28.0);
+        if let Some(ref mut input_state) = browser.save_dialog {
+            draw_text_input(input_rect, input_state, 14.0);
+        }
+
+        let btn_w = 80.0;
+        let btn_h = 28.0;
+        let btn_y = sdy + sdh - btn_h - 12.0;
+        let cancel_rect = Rect::new(sdx + sdw - btn_w * 2.0 - 20.0, btn_y, btn_w, btn_h);
+        let confirm_rect = Rect::new(sdx + sdw - btn_w - 12.0, btn_y, btn_w, btn_h);
+        let cancel = draw_text_button(ctx, cancel_rect, "Cancel", Color::from_rgba(55, 55, 60, 255));
+        let confirm = draw_text_button(ctx, confirm_rect, "Save", theme().accent);
+
+        if cancel || is_key_pressed(KeyCode::Escape) {
+            // Cancelling the prompt also closes the browser it was opened with
+            action = PrefabBrowserAction::Cancel;
+        } else if confirm || is_key_pressed(KeyCode::Enter) {
+            action = PrefabBrowserAction::Save;
+        } else if action != PrefabBrowserAction::Cancel {
+            // The prompt is modal: ignore list and footer clicks underneath
+            action = PrefabBrowserAction::None;
+        }
+    } else if is_key_pressed(KeyCode::Escape) && !browser.tag_filter.is_typing() {
+        action = PrefabBrowserAction::Cancel;
+    }
+
+    action
+}
+
+/// Draw a top-down schematic of a prefab (floors, walls, objects)
+fn draw_prefab_footprint(prefab: &Prefab, rect: Rect) {
+    let gc = prefab.to_clipboard();
+    let (min_x, _, min_z, _) = gc.bounds();
+    let (w, d) = prefab.size();
+    let cell = (rect.w / w as f32).min(rect.h / d as f32).clamp(4.0, 48.0);
+    let ox = rect.x + (rect.w - cell * w as f32) / 2.0;
+    let oz = rect.y + (rect.h - cell * d as f32) / 2.0;
+
+    let grid_color = Color::from_rgba(45, 45, 55, 255);
+    let floor_color = Color::from_rgba(70, 90, 120, 255);
+    let ceiling_color = Color::from_rgba(140, 140, 160, 255);
+    let wall_color = Color::from_rgba(220, 180, 100, 255);
+    let object_color = Color::from_rgba(100, 255, 150, 255);
+
+    for i in 0..=w {
+        let x = ox + i as f32 * cell;
+        draw_line(x, oz, x, oz + d as f32 * cell, 1.0, grid_color);
+    }
+    for i in 0..=d {
+        let z = oz + i as f32 * cell;
+        draw_line(ox, z, ox + w as f32 * cell, z, 1.0, grid_color);
+    }
+
+    for face in &prefab.faces {
+        let x = ox + (face.rel_x - min_x) as f32 * cell;
+        let z = oz + (face.rel_z - min_z) as f32 * cell;
+        match &face.face {
+            CopiedFaceData::Floor(_) => draw_rectangle(x + 1.0, z + 1.0, cell - 2.0, cell - 2.0, floor_color),
+            CopiedFaceData::Ceiling(_) => draw_rectangle_lines(x + 3.0, z + 3.0, cell - 6.0, cell - 6.0, 1.0, ceiling_color),
+            CopiedFaceData::WallNorth(..) => draw_line(x, z, x + cell, z, 3.0, wall_color),
+            CopiedFaceData::WallSouth(..) => draw_line(x, z + cell, x + cell, z + cell, 3.0, wall_color),
+            CopiedFaceData::WallWest(..) => draw_line(x, z, x, z + cell, 3.0, wall_color),
+            CopiedFaceData::WallEast(..) => draw_line(x + cell, z, x + cell, z + cell, 3.0, wall_color),
+            CopiedFaceData::WallNwSe(..) => draw_line(x, z, x + cell, z + cell, 2.0, wall_color),
+            CopiedFaceData::WallNeSw(..) => draw_line(x + cell, z, x, z + cell, 2.0, wall_color),
+        }
+    }
+
+    for obj in &prefab.objects {
+        let x = ox + (obj.sector_x as i32 - min_x) as f32 * cell + cell / 2.0;
+        let z = oz + (obj.sector_z as i32 - min_z) as f32 * cell + cell / 2.0;
+        draw_circle(x, z, (cell * 0.2).max(2.0), object_color);
+    }
+}
@@ -33,6 +33,23 @@ pub struct LevelInfo {
     pub category: LevelCategory,
 }
 
+impl LevelCategory {
+    /// Prefix of library keys (tags and thumbnails) in this category
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            LevelCategory::Sample => "sample:",
+            LevelCategory::User => "user:",
+        }
+    }
+}
+
+impl LevelInfo {
+    /// Key for tags and thumbnails, unique across categories
+    pub fn library_key(&self) -> String {
+        format!("{}{}", self.category.prefix(), self.name)
+    }
+}
+
 /// Discover all sample levels in the levels directory (native)
 #[cfg(not(target_arch = "wasm32"))]
 pub fn discover_sample_levels() -> Vec<LevelInfo> {
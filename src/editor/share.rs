@@ -0,0 +1,194 @@
+//! Level Sharing
+//!
+//! "Share" packs the open level with the user textures it paints with into a
+//! bundle (the `.bpk` layout, see `crate::bundle`) and publishes it through
+//! cloud storage, which answers with a token. The link handed out is the
+//! public `/shared/<token>` URL, so whoever gets it needs no account.
+//!
+//! "Open Link" in the level browser takes that link (or the bare token),
+//! downloads the bundle and imports it: textures join the user library -
+//! renamed when a different texture already has the name - and the level
+//! opens unsaved.
+
+use std::collections::{HashMap, HashSet};
+use crate::bundle::{Bundle, BundleWriter};
+use crate::project::{level_dependencies, Dependency};
+use crate::texture::{generate_texture_id, TextureLibrary, TextureSource, UserTexture};
+use crate::world::{level_to_ron, parse_level_data, remap_level_textures, Level, USER_TEXTURE_PACK};
+
+/// Bundle directory holding the level (one file, named after it)
+const LEVEL_DIR: &str = "level";
+/// Bundle directory holding the level's user textures
+const TEXTURES_DIR: &str = "textures";
+/// Longest token accepted from a pasted link
+const MAX_TOKEN_LEN: usize = 64;
+
+/// A downloaded shared level, before it's imported
+#[derive(Debug)]
+pub struct SharedLevel {
+    pub name: String,
+    pub level: Level,
+    pub textures: Vec<UserTexture>,
+}
+
+/// The token in a share link, or a bare token; None if it isn't one
+pub fn share_token(link: &str) -> Option<String> {
+    let link = link.trim();
+    let token = match link.rfind("/shared/") {
+        Some(i) => &link[i + "/shared/".len()..],
+        None => link,
+    };
+    let token = token.split(['?', '#']).next().unwrap_or_default().trim_end_matches('/');
+    let valid = !token.is_empty()
+        && token.len() <= MAX_TOKEN_LEN
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| token.to_string())
+}
+
+/// Bundle a level with the user textures it uses. Sample textures ship with
+/// every copy of the engine, so they're left out.
+pub fn pack_shared_level(name: &str, level: &Level, textures: &TextureLibrary) -> Result<Vec<u8>, String> {
+    let mut writer = BundleWriter::new();
+    let ron = level_to_ron(level).map_err(|e| e.to_string())?;
+    writer.add(&format!("{}/{}.ron", LEVEL_DIR, name), ron.into_bytes());
+    for dependency in level_dependencies(level, &HashMap::new()) {
+        let Dependency::Texture(texture_name) = dependency else { continue };
+        if let Some(texture) = textures.get(&texture_name).filter(|t| t.source == TextureSource::User) {
+            let ron = texture.to_ron_string().map_err(|e| e.to_string())?;
+            writer.add(&format!("{}/{}.ron", TEXTURES_DIR, texture_name), ron.into_bytes());
+        }
+    }
+    writer.finish().map_err(|e| e.to_string())
+}
+
+/// Read a bundle made by `pack_shared_level`
+pub fn unpack_shared_level(bytes: Vec<u8>) -> Result<SharedLevel, String> {
+    let bundle = Bundle::from_bytes(bytes).map_err(|e| e.to_string())?;
+    let level_path = bundle.files_in(LEVEL_DIR).next()
+        .ok_or_else(|| "No level in the shared bundle".to_string())?;
+    let name = file_stem(level_path, LEVEL_DIR)?.to_string();
+    let level = parse_level_data(&bundle.read(level_path).map_err(|e| e.to_string())?)
+        .map_err(|e| e.to_string())?;
+    let mut textures = Vec::new();
+    for path in bundle.files_in(TEXTURES_DIR) {
+        let bytes = bundle.read(path).map_err(|e| e.to_string())?;
+        let mut texture = UserTexture::load_from_bytes(&bytes).map_err(|e| format!("{}: {}", path, e))?;
+        // The file name is what the level refers to
+        texture.name = file_stem(path, TEXTURES_DIR)?.to_string();
+        textures.push(texture);
+    }
+    Ok(SharedLevel { name, level, textures })
+}
+
+/// Move the shared textures into `library`, returning the names added (to
+/// save). A texture identical to the library's one of the same name is
+/// skipped; a different one gets a free name and the level follows it.
+pub fn import_shared_textures(shared: &mut SharedLevel, library: &mut TextureLibrary) -> Vec<String> {
+    // New names avoid every shared name, so no rename is undone by a later one
+    let mut taken: HashSet<String> = shared.textures.iter().map(|t| t.name.clone()).collect();
+    let mut added = Vec::new();
+    for mut texture in std::mem::take(&mut shared.textures) {
+        if let Some(existing) = library.get(&texture.name) {
+            if existing.content_hash() == texture.content_hash() {
+                continue;
+            }
+            let mut counter = 1;
+            let name = loop {
+                let name = format!("{}_{}", texture.name, counter);
+                if !library.contains(&name) && !taken.contains(&name) {
+                    break name;
+                }
+                counter += 1;
+            };
+            remap_level_textures(&mut shared.level, USER_TEXTURE_PACK, Some(texture.name.as_str()), USER_TEXTURE_PACK, Some(name.as_str()));
+            taken.insert(name.clone());
+            texture.name = name;
+        }
+        if library.get_by_id(texture.id).is_some() {
+            texture.id = generate_texture_id();
+        }
+        texture.source = TextureSource::User;
+        added.push(texture.name.clone());
+        library.add(texture);
+    }
+    added
+}
+
+/// Name of a bundle file in `dir`, without the directory and extension.
+/// Shared names end up in file paths on save, so anything that could leave
+/// the userdata directory (separators, `..`, a drive prefix) is refused.
+fn file_stem<'a>(path: &'a str, dir: &str) -> Result<&'a str, String> {
+    let name = path.strip_prefix(dir).and_then(|rest| rest.strip_prefix('/')).unwrap_or(path);
+    let stem = name.strip_suffix(".ron").unwrap_or(name);
+    let unsafe_name = stem.is_empty() || stem.contains(['/', '\\', ':']) || stem.contains("..");
+    if unsafe_name {
+        Err(format!("Unsafe file name in the shared bundle: {}", path))
+    } else {
+        Ok(stem)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rasterizer::ClutDepth;
+    use crate::world::{create_empty_level, TextureRef};
+
+    #[test]
+    fn test_share_token() {
+        assert_eq!(share_token("https://example.run.app/shared/Ab3-x_9"), Some("Ab3-x_9".to_string()));
+        assert_eq!(share_token("  Ab3x9/?dl=1 "), Some("Ab3x9".to_string()));
+        assert_eq!(share_token(""), None);
+        assert_eq!(share_token("https://example.run.app/shared/"), None);
+        assert_eq!(share_token("not a token"), None);
+    }
+
+    #[test]
+    fn test_unpack_rejects_unsafe_names() {
+        let level = level_to_ron(&create_empty_level()).unwrap();
+        let texture = UserTexture::new_64x64("bricks", ClutDepth::Bpp4).to_ron_string().unwrap();
+        let bundle = |level_path: &str, texture_path: &str| {
+            let mut writer = BundleWriter::new();
+            writer.add(level_path, level.clone().into_bytes());
+            writer.add(texture_path, texture.clone().into_bytes());
+            writer.finish().unwrap()
+        };
+        assert!(unpack_shared_level(bundle("level/castle.ron", "textures/bricks.ron")).is_ok());
+        for (level_path, texture_path) in [
+            ("level/../castle.ron", "textures/bricks.ron"),
+            ("level/sub/castle.ron", "textures/bricks.ron"),
+            ("level/.ron", "textures/bricks.ron"),
+            ("level/castle.ron", "textures/..\\..\\bricks.ron"),
+            ("level/castle.ron", "textures/C:bricks.ron"),
+        ] {
+            assert!(unpack_shared_level(bundle(level_path, texture_path)).is_err(), "{} {}", level_path, texture_path);
+        }
+    }
+
+    #[test]
+    fn test_share_round_trip_renames_clashing_textures() {
+        let mut level = create_empty_level();
+        level.rooms[0].get_sector_mut(0, 0).unwrap().floor.as_mut().unwrap().texture = TextureRef::new(USER_TEXTURE_PACK, "bricks");
+        let mut mine = TextureLibrary::new();
+        let mut bricks = UserTexture::new_64x64("bricks", ClutDepth::Bpp4);
+        bricks.fill(3);
+        mine.add(bricks);
+        let bytes = pack_shared_level("castle", &level, &mine).unwrap();
+
+        let mut shared = unpack_shared_level(bytes).unwrap();
+        assert_eq!(shared.name, "castle");
+        assert_eq!(shared.textures.len(), 1);
+
+        // Importing into the library it came from adds nothing
+        let mut same = unpack_shared_level(pack_shared_level("castle", &level, &mine).unwrap()).unwrap();
+        assert!(import_shared_textures(&mut same, &mut mine).is_empty());
+
+        // A friend's different "bricks" stays; the shared one is renamed
+        let mut theirs = TextureLibrary::new();
+        theirs.add(UserTexture::new_64x64("bricks", ClutDepth::Bpp4));
+        assert_eq!(import_shared_textures(&mut shared, &mut theirs), vec!["bricks_1".to_string()]);
+        let floor = shared.level.rooms[0].get_sector(0, 0).unwrap().floor.as_ref().unwrap();
+        assert_eq!(floor.texture.name, "bricks_1");
+        assert!(theirs.contains("bricks") && theirs.contains("bricks_1"));
+    }
+}
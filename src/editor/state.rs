@@ -1,11 +1,13 @@
 //! Editor state and data
 
 use std::path::PathBuf;
-use crate::world::{Level, AssetInstance, TextureRef, FaceNormalMode, UvProjection, SplitDirection, HorizontalFace, VerticalFace};
+use serde::{Serialize, Deserialize};
+use crate::world::{Level, AssetInstance, BudgetReport, PortalIssue, check_portals, HeightmapSettings, QuakeMapSettings, measure_level, check_budget, indexed_texture_bytes, direct_texture_bytes, TextureRef, FaceNormalMode, UvProjection, SplitDirection, HorizontalFace, VerticalFace};
 use crate::rasterizer::{Camera, Vec3, Vec2, Texture, Texture15, RasterSettings, Color, BlendMode, Color15};
-use crate::texture::{TextureLibrary, TextureEditorState};
+use crate::texture::{TextureLibrary, TextureEditorState, TextureAnimation, TextureLayer};
 use crate::asset::AssetLibrary;
 use crate::modeler::AssetBrowser;
+use crate::project::{DependencyGraph, UsagesPanel};
 use super::texture_pack::TexturePack;
 
 /// Frame timing breakdown for editor performance debugging
@@ -119,6 +121,7 @@ pub enum ActivePanel {
     #[default]
     Viewport3D,      // 3D viewport (WASD camera, selection shortcuts)
     TexturePalette,  // Texture panel / UV editor (G/T/R/Ctrl+A for UV)
+    Scripts,         // Level script / cutscene timeline editor (typing, no shortcuts)
 }
 
 /// Current editor tool
@@ -129,6 +132,16 @@ pub enum EditorTool {
     DrawWall,      // Handles all 6 directions (N, E, S, W, NW-SE, NE-SW)
     DrawCeiling,
     PlaceObject,
+    /// Drag a box in the 2D grid view to add a trigger volume
+    DrawTrigger,
+    /// Drag a box in the 2D grid view to add a ladder
+    DrawLadder,
+    /// Drag a box in the 2D grid view to add a camera volume
+    DrawCamera,
+    /// Drag across sectors in the 2D grid view to paint textures
+    PaintTexture,
+    /// Click in the 2D grid view to place path waypoints
+    DrawPath,
 }
 
 /// 2D Grid View projection mode
@@ -207,6 +220,15 @@ pub enum Selection {
     /// room: which room the object belongs to
     /// index: index within that room's objects array
     Object { room: usize, index: usize },
+    /// Trigger volume: index within that room's triggers array
+    Trigger { room: usize, index: usize },
+    /// Ladder: index within that room's ladders array
+    Ladder { room: usize, index: usize },
+    /// Camera volume: index within that room's cameras array
+    Camera { room: usize, index: usize },
+    /// Entity path: index within that room's paths array, plus the selected
+    /// waypoint (new waypoints are inserted after it)
+    Path { room: usize, index: usize, point: Option<usize> },
 }
 
 /// Snapshot of selection state for undo/redo
@@ -247,7 +269,7 @@ pub enum FaceClipboard {
 }
 
 /// A copied face with its position relative to anchor
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CopiedFace {
     /// Relative sector position (from anchor)
     pub rel_x: i32,
@@ -257,7 +279,7 @@ pub struct CopiedFace {
 }
 
 /// The actual face data (floor, ceiling, or wall)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CopiedFaceData {
     Floor(HorizontalFace),
     Ceiling(HorizontalFace),
@@ -274,6 +296,8 @@ pub enum CopiedFaceData {
 pub struct GeometryClipboard {
     /// All copied faces with their relative positions
     pub faces: Vec<CopiedFace>,
+    /// Copied asset instances (sector_x/sector_z relative to the anchor)
+    pub objects: Vec<AssetInstance>,
     /// Horizontal flip state (toggled with H key)
     pub flip_h: bool,
     /// Vertical flip state (toggled with V key)
@@ -286,29 +310,37 @@ impl GeometryClipboard {
     pub fn new() -> Self {
         Self {
             faces: Vec::new(),
+            objects: Vec::new(),
             flip_h: false,
             flip_v: false,
             rotation: 0,
         }
     }
 
-    /// Get bounding box of copied geometry (min_x, max_x, min_z, max_z)
+    /// Get bounding box of copied geometry and objects (min_x, max_x, min_z, max_z)
     pub fn bounds(&self) -> (i32, i32, i32, i32) {
-        if self.faces.is_empty() {
+        if self.is_empty() {
             return (0, 0, 0, 0);
         }
         let mut min_x = i32::MAX;
         let mut max_x = i32::MIN;
         let mut min_z = i32::MAX;
         let mut max_z = i32::MIN;
-        for face in &self.faces {
-            min_x = min_x.min(face.rel_x);
-            max_x = max_x.max(face.rel_x);
-            min_z = min_z.min(face.rel_z);
-            max_z = max_z.max(face.rel_z);
+        let positions = self.faces.iter().map(|f| (f.rel_x, f.rel_z))
+            .chain(self.objects.iter().map(|o| (o.sector_x as i32, o.sector_z as i32)));
+        for (x, z) in positions {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_z = min_z.min(z);
+            max_z = max_z.max(z);
         }
         (min_x, max_x, min_z, max_z)
     }
+
+    /// Nothing to paste
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty() && self.objects.is_empty()
+    }
 }
 
 /// Unified undo event - level change, selection change, or texture change
@@ -316,11 +348,13 @@ impl GeometryClipboard {
 pub enum UndoEvent {
     Level(Level),
     Selection(SelectionSnapshot),
-    /// Texture paint edit (name, pixel indices, palette)
+    /// Texture paint edit (name, pixel indices, palette, flipbook frames, layers)
     Texture {
         name: String,
         indices: Vec<u8>,
         palette: Vec<Color15>,
+        animation: Option<TextureAnimation>,
+        layers: Vec<TextureLayer>,
     },
 }
 
@@ -441,6 +475,8 @@ pub struct EditorState {
 
     /// 3D viewport settings
     pub show_room_bounds: bool, // Show room boundary wireframes
+    pub show_player_capsule: bool, // Player collision and jump reach at the cursor
+    pub portal_debug: bool, // Draw every portal and highlight connection problems
 
     /// Vertex editing mode
     pub link_coincident_vertices: bool, // When true, moving a vertex moves all vertices at same position
@@ -485,6 +521,19 @@ pub struct EditorState {
     pub grid_dragging_room_origin: bool,
     /// Object being dragged in 2D grid view (room_idx, object_idx)
     pub grid_dragging_object: Option<(usize, usize)>,
+    /// Trigger tool: world plane position where the box drag began
+    pub trigger_drag_start: Option<(f32, f32)>,
+    /// Ladder tool: world plane position where the box drag began
+    pub ladder_drag_start: Option<(f32, f32)>,
+    /// Camera tool: world plane position where the box drag began
+    pub camera_drag_start: Option<(f32, f32)>,
+    /// Trigger or path text field being edited in the properties panel (field, input)
+    pub trigger_field_edit: Option<(usize, crate::ui::TextInputState)>,
+    /// Waypoint being dragged in 2D grid view: ((room_idx, path_idx, point_idx),
+    /// plane position where the drag began)
+    pub path_drag: Option<((usize, usize, usize), (f32, f32))>,
+    /// Texture paint brush (sets, variation and current stroke)
+    pub texture_brush: super::TextureBrush,
 
     /// 3D viewport vertex dragging state (legacy - kept for compatibility)
     pub viewport_dragging_vertices: Vec<(usize, usize)>, // List of (room_idx, vertex_idx)
@@ -629,6 +678,15 @@ pub struct EditorState {
     /// Geometry clipboard for copy/paste entire face selections
     pub geometry_clipboard: Option<GeometryClipboard>,
 
+    /// Recorded playtest sessions and heatmap overlay settings
+    pub playtest: crate::game::analytics::PlaytestAnalytics,
+
+    /// Active level diff (highlighted in the grid view), if any
+    pub level_diff: Option<super::LevelDiffView>,
+
+    /// Show baked vertex lighting in the 3D viewport (false = dynamic lights)
+    pub preview_baked_lighting: bool,
+
     /// Frame timing breakdown for debug panel
     pub frame_timings: EditorFrameTimings,
 
@@ -645,10 +703,17 @@ pub struct EditorState {
     /// Last generation when textures_15_cache was built
     pub textures_15_cache_generation: u64,
 
+    /// Flipbook frame key of user textures (see TextureLibrary::animation_key)
+    /// A change bumps texture_generation so animated textures advance in the 3D view
+    pub texture_anim_key: u64,
+
     /// Cached GPU textures for palette display (prevents memory leak from repeated uploads)
     /// Key: (pack_index, texture_index), Value: Texture2D
     pub gpu_texture_cache: std::collections::HashMap<(usize, usize), macroquad::prelude::Texture2D>,
 
+    /// GPU copy of the 3D viewport framebuffer (re-uploaded only where it changed)
+    pub viewport_texture: crate::rasterizer::FramebufferTexture,
+
     /// User texture library (editable indexed textures)
     pub user_textures: TextureLibrary,
 
@@ -663,6 +728,8 @@ pub struct EditorState {
 
     /// Texture pending deletion (shows confirmation dialog when Some)
     pub texture_pending_delete: Option<String>,
+    /// Files still using the texture pending deletion (listed in the dialog)
+    pub texture_delete_usages: Option<UsagesPanel>,
 
     /// Texture palette mode: false = source PNGs, true = user/paint textures
     pub texture_palette_user_mode: bool,
@@ -676,6 +743,8 @@ pub struct EditorState {
     /// Paint tab section collapse state
     pub paint_samples_collapsed: bool,
     pub paint_user_collapsed: bool,
+    /// Tag filter for the user texture grid
+    pub texture_tag_filter: crate::tags::TagFilter,
 
     /// Pending async user texture list (cloud discovery)
     pub pending_user_texture_list: Option<crate::storage::PendingList>,
@@ -684,10 +753,6 @@ pub struct EditorState {
     /// Flag to trigger user texture refresh from main loop
     pub pending_texture_refresh: bool,
 
-    /// Collapsible right panel sections (both can be open simultaneously)
-    pub textures_section_expanded: bool,
-    pub properties_section_expanded: bool,
-
     /// Asset library for object placement
     pub asset_library: AssetLibrary,
 
@@ -697,6 +762,40 @@ pub struct EditorState {
 
     /// Asset browser modal for selecting assets to place
     pub asset_browser: AssetBrowser,
+
+    /// Level scripts panel: selected script, editor text, and which script it holds
+    pub selected_script: usize,
+    pub script_input: crate::ui::TextInputState,
+    pub script_input_for: Option<usize>,
+    /// Undo already saved for the current script editing session
+    pub script_undo_saved: bool,
+
+    /// Cutscenes panel: selected cutscene, timeline editor text, and which cutscene it holds
+    pub selected_cutscene: usize,
+    pub cutscene_input: crate::ui::TextInputState,
+    pub cutscene_input_for: Option<usize>,
+    /// Undo already saved for the current timeline editing session
+    pub cutscene_undo_saved: bool,
+
+    /// Result of the last content budget check (None until checked on save)
+    pub budget_report: Option<BudgetReport>,
+
+    /// Room connection problems from the last portal check (portal debug view)
+    pub portal_issues: Vec<PortalIssue>,
+
+    /// Settings for the next heightmap terrain import (Rooms panel)
+    pub heightmap_settings: HeightmapSettings,
+
+    /// Settings for the next Quake .map import (Rooms panel)
+    pub quake_map_settings: QuakeMapSettings,
+
+    /// HUD panel: selected element index and drag start (mouse x, y, element offset)
+    pub hud_selected: Option<usize>,
+    pub hud_drag: Option<(f32, f32, (i32, i32))>,
+
+    /// Texture Packs panel: faces to remap and where to (an empty name means the whole pack)
+    pub pack_remap_from: crate::world::TextureRef,
+    pub pack_remap_to: crate::world::TextureRef,
 }
 
 impl EditorState {
@@ -757,6 +856,8 @@ impl EditorState {
             grid_size: SECTOR_SIZE, // TRLE sector size
             show_grid: true,
             show_room_bounds: true, // Room boundaries visible by default
+            show_player_capsule: false,
+            portal_debug: false,
             link_coincident_vertices: true, // Default to linked mode
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
@@ -777,6 +878,12 @@ impl EditorState {
             grid_sector_drag_start: None,
             grid_dragging_room_origin: false,
             grid_dragging_object: None,
+            trigger_drag_start: None,
+            ladder_drag_start: None,
+            camera_drag_start: None,
+            texture_brush: super::TextureBrush::default(),
+            trigger_field_edit: None,
+            path_drag: None,
             viewport_dragging_vertices: Vec::new(),
             viewport_drag_started: false,
             viewport_drag_plane_y: 0.0,
@@ -842,12 +949,17 @@ impl EditorState {
             clipboard: None,
             face_clipboard: None,
             geometry_clipboard: None,
+            playtest: crate::game::analytics::PlaytestAnalytics::default(),
+            level_diff: None,
+            preview_baked_lighting: true,
             frame_timings: EditorFrameTimings::default(),
             memory_stats: MemoryStats::default(),
             textures_15_cache: Vec::new(),
             texture_generation: 0,
             textures_15_cache_generation: 0,
+            texture_anim_key: 0,
             gpu_texture_cache: std::collections::HashMap::new(),
+            viewport_texture: crate::rasterizer::FramebufferTexture::new(),
             user_textures: {
                 let mut lib = TextureLibrary::new();
                 if let Err(e) = lib.discover() {
@@ -859,19 +971,17 @@ impl EditorState {
             editing_texture: None,
             selected_user_texture: None,
             texture_pending_delete: None,
+            texture_delete_usages: None,
             texture_palette_user_mode: false,
             source_thumb_size: 64.0,  // Default thumbnail size
             paint_thumb_size: 64.0,   // Default thumbnail size
             paint_samples_collapsed: false,
             paint_user_collapsed: false,
+            texture_tag_filter: crate::tags::TagFilter::default(),
             pending_user_texture_list: None,
             pending_texture_loads: Vec::new(),
             pending_texture_refresh: false,
 
-            // Collapsible sections (both can be open simultaneously)
-            textures_section_expanded: true,
-            properties_section_expanded: true,
-
             // Asset library for object placement
             asset_library: {
                 let mut lib = AssetLibrary::new();
@@ -883,6 +993,30 @@ impl EditorState {
             // Auto-select first available asset (populated below after library is built)
             selected_asset: None,
             asset_browser: AssetBrowser::default(),
+            selected_script: 0,
+            script_input: {
+                let mut input = crate::ui::TextInputState::new("");
+                input.focused = false;
+                input
+            },
+            script_input_for: None,
+            script_undo_saved: false,
+            selected_cutscene: 0,
+            cutscene_input: {
+                let mut input = crate::ui::TextInputState::new("");
+                input.focused = false;
+                input
+            },
+            cutscene_input_for: None,
+            cutscene_undo_saved: false,
+            budget_report: None,
+            portal_issues: Vec::new(),
+            heightmap_settings: HeightmapSettings::default(),
+            quake_map_settings: QuakeMapSettings::default(),
+            hud_selected: None,
+            hud_drag: None,
+            pack_remap_from: crate::world::TextureRef::none(),
+            pack_remap_to: crate::world::TextureRef::none(),
         }
     }
 
@@ -904,16 +1038,35 @@ impl EditorState {
         self.multi_selection.clear();
         self.selected_vertex_indices.clear();
         self.portals_dirty = true; // Recalculate portals for loaded level
+        self.level_diff = None; // Base was for the previous level
         // Clamp current_room to valid range
         if self.current_room >= self.level.rooms.len() {
             self.current_room = 0;
         }
     }
 
+    /// Swap in a newer copy of the open level (changed on disk), keeping the
+    /// view. The old version stays on the undo stack.
+    pub fn reload_level(&mut self, level: Level) {
+        self.save_undo();
+        self.level = level;
+        self.dirty = false;
+        self.selection = Selection::None;
+        self.multi_selection.clear();
+        self.selected_vertex_indices.clear();
+        self.portals_dirty = true;
+        if self.current_room >= self.level.rooms.len() {
+            self.current_room = 0;
+        }
+    }
+
     /// Set the current selection and clear vertex color selection
     /// Does NOT auto-save undo - caller should call save_selection_undo() BEFORE
     /// modifying any selection state (including toggle/clear multi_selection)
     pub fn set_selection(&mut self, selection: Selection) {
+        if selection != self.selection {
+            self.trigger_field_edit = None;
+        }
         self.selection = selection;
         self.selected_vertex_indices.clear();
     }
@@ -922,6 +1075,108 @@ impl EditorState {
     pub fn set_status(&mut self, message: &str, duration_secs: f64) {
         let expiry = macroquad::time::get_time() + duration_secs;
         self.status_message = Some((message.to_string(), expiry));
+        crate::crash::record(message);
+    }
+
+    /// Check the level against its budget preset and store the report.
+    /// `audio_voices` is the peak voice count of the music that plays with it.
+    /// Returns the number of violations (0 when the preset is off).
+    pub fn check_budget(&mut self, audio_voices: usize) -> usize {
+        let Some(limits) = self.level.budget.limits() else {
+            self.budget_report = None;
+            return 0;
+        };
+        let usage = measure_level(
+            &self.level,
+            |tex| {
+                if tex.is_user_texture() {
+                    self.user_textures.get(&tex.name).map(|t| {
+                        indexed_texture_bytes(t.width, t.height, t.depth.bits_per_pixel(), t.depth.color_count())
+                    })
+                } else {
+                    self.texture_packs.iter()
+                        .find(|p| p.name == tex.pack)
+                        .and_then(|p| p.textures.iter().find(|t| t.name == tex.name))
+                        .map(|t| direct_texture_bytes(t.width, t.height))
+                }
+            },
+            |asset_id| self.asset_library.get_by_id(asset_id).map(|a| a.total_triangles()).unwrap_or(0),
+            audio_voices,
+        );
+        let violations = check_budget(&usage, &limits);
+        let count = violations.len();
+        self.budget_report = Some(BudgetReport { usage, violations });
+        count
+    }
+
+    /// Check how rooms connect, measuring reachability from the player
+    /// start's room, and store the issues for the portal debug view
+    pub fn check_portals(&mut self) -> usize {
+        let start_room = self.level.get_player_start(&self.asset_library).map(|(room, _)| room);
+        self.portal_issues = check_portals(&self.level, start_room);
+        self.portal_issues.len()
+    }
+
+    /// Saved project files plus the open level, which may have unsaved edits
+    pub fn dependency_graph(&self, storage: &crate::storage::Storage) -> DependencyGraph {
+        let mut graph = DependencyGraph::scan(storage, &self.user_textures);
+        let path = self.current_file.as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        graph.add_level(&path, &self.level);
+        graph
+    }
+
+    /// Add terrain rooms generated from a heightmap image, east of the last
+    /// room, textured with the selected texture. Returns the number of rooms.
+    pub fn import_heightmap(&mut self, bytes: &[u8]) -> Result<usize, String> {
+        let map = crate::world::Heightmap::from_image_bytes(bytes)?;
+        let rooms = crate::world::generate_terrain(
+            &map,
+            &self.heightmap_settings,
+            &self.selected_texture,
+            self.free_room_origin(),
+            self.level.rooms.len(),
+        );
+        Ok(self.append_rooms(rooms))
+    }
+
+    /// Add rooms built from Quake .map brushes, east of the last room. Map
+    /// textures are matched to texture-pack entries by name; unmatched ones
+    /// use the selected texture. Returns the number of rooms.
+    pub fn import_quake_map(&mut self, text: &str) -> Result<usize, String> {
+        let entities = crate::world::parse_quake_map(text)?;
+        let packs = &self.texture_packs;
+        let fallback = &self.selected_texture;
+        let rooms = crate::world::build_quake_map_rooms(
+            &entities,
+            &self.quake_map_settings,
+            |name| {
+                let textures = packs.iter().flat_map(|p| p.textures.iter().map(move |t| (p.name.as_str(), t.name.as_str())));
+                crate::world::match_map_texture(name, textures).unwrap_or_else(|| fallback.clone())
+            },
+            self.free_room_origin(),
+            self.level.rooms.len(),
+        )?;
+        Ok(self.append_rooms(rooms))
+    }
+
+    /// Where generated rooms go: one sector east of the last room
+    pub fn free_room_origin(&self) -> Vec3 {
+        self.level.rooms.last()
+            .map(|last| Vec3::new(last.position.x + (last.width as f32 + 1.0) * crate::world::SECTOR_SIZE, 0.0, 0.0))
+            .unwrap_or(Vec3::ZERO)
+    }
+
+    /// Add generated rooms (ids must continue from the current room count),
+    /// select the first one and rebuild portals. Returns the number added.
+    pub fn append_rooms(&mut self, rooms: Vec<crate::world::Room>) -> usize {
+        let count = rooms.len();
+        self.save_undo();
+        self.current_room = self.level.rooms.len();
+        self.level.rooms.extend(rooms);
+        self.mark_portals_dirty();
+        count
     }
 
     /// Get current status message if not expired
@@ -978,6 +1233,8 @@ impl EditorState {
                 name: name.to_string(),
                 indices: tex.indices.clone(),
                 palette: tex.palette.clone(),
+                animation: tex.animation.clone(),
+                layers: tex.layers.clone(),
             });
             self.redo_stack.clear();
             self.texture_editor.dirty = true;
@@ -1005,19 +1262,23 @@ impl EditorState {
                     self.set_selection(prev_sel.selection);
                     self.multi_selection = prev_sel.multi_selection;
                 }
-                UndoEvent::Texture { name, indices, palette } => {
+                UndoEvent::Texture { name, indices, palette, animation, layers } => {
                     // Save current state to redo stack
                     if let Some(tex) = self.user_textures.get(&name) {
                         self.redo_stack.push(UndoEvent::Texture {
                             name: name.clone(),
                             indices: tex.indices.clone(),
                             palette: tex.palette.clone(),
+                            animation: tex.animation.clone(),
+                            layers: tex.layers.clone(),
                         });
                     }
                     // Restore previous state
                     if let Some(tex) = self.user_textures.get_mut(&name) {
                         tex.indices = indices;
                         tex.palette = palette;
+                        tex.animation = animation;
+                        tex.layers = layers;
                     }
                 }
             }
@@ -1040,19 +1301,23 @@ impl EditorState {
                     self.set_selection(next_sel.selection);
                     self.multi_selection = next_sel.multi_selection;
                 }
-                UndoEvent::Texture { name, indices, palette } => {
+                UndoEvent::Texture { name, indices, palette, animation, layers } => {
                     // Save current state to undo stack
                     if let Some(tex) = self.user_textures.get(&name) {
                         self.undo_stack.push(UndoEvent::Texture {
                             name: name.clone(),
                             indices: tex.indices.clone(),
                             palette: tex.palette.clone(),
+                            animation: tex.animation.clone(),
+                            layers: tex.layers.clone(),
                         });
                     }
                     // Apply redo state
                     if let Some(tex) = self.user_textures.get_mut(&name) {
                         tex.indices = indices;
                         tex.palette = palette;
+                        tex.animation = animation;
+                        tex.layers = layers;
                     }
                 }
             }
@@ -1203,6 +1468,30 @@ impl EditorState {
                     })
                 })
             }
+            Selection::Trigger { room: room_idx, index } => {
+                self.level.rooms.get(*room_idx).and_then(|room| {
+                    room.triggers.get(*index).map(|t| t.world_center(room.position))
+                })
+            }
+            Selection::Ladder { room: room_idx, index } => {
+                self.level.rooms.get(*room_idx).and_then(|room| {
+                    room.ladders.get(*index).map(|l| l.world_center(room.position))
+                })
+            }
+            Selection::Camera { room: room_idx, index } => {
+                self.level.rooms.get(*room_idx).and_then(|room| {
+                    room.cameras.get(*index).map(|c| c.world_center(room.position))
+                })
+            }
+            Selection::Path { room: room_idx, index, point } => {
+                self.level.rooms.get(*room_idx).and_then(|room| {
+                    let path = room.paths.get(*index)?;
+                    match point.and_then(|i| path.points.get(i)) {
+                        Some(&waypoint) => Some(room.position + waypoint),
+                        None => Some(path.world_center(room.position)),
+                    }
+                })
+            }
         }
     }
 
@@ -1247,6 +1536,23 @@ impl EditorState {
         }
     }
 
+    /// Look through a camera volume's shot at its center (free camera).
+    /// Returns false if the volume is gone or its rail has no points.
+    pub fn preview_camera_volume(&mut self, room_idx: usize, index: usize) -> bool {
+        let Some(room) = self.level.rooms.get(room_idx) else { return false };
+        let Some(volume) = room.cameras.get(index) else { return false };
+        let center = volume.world_center(room.position);
+        let Some(position) = volume.camera_position(room.position, center) else { return false };
+
+        self.camera_mode = CameraMode::Free;
+        self.camera_3d.position = position;
+        let to_center = (center - position).normalize();
+        self.camera_3d.rotation_y = to_center.x.atan2(to_center.z);
+        self.camera_3d.rotation_x = (-to_center.y).asin();
+        self.camera_3d.update_basis();
+        true
+    }
+
     /// Mark portals as needing recalculation
     pub fn mark_portals_dirty(&mut self) {
         self.portals_dirty = true;
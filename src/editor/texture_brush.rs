@@ -0,0 +1,267 @@
+//! Texture paint brush
+//!
+//! Paints textures across many sectors in one drag of the 2D grid view.
+//! Floors, walls and ceilings each have their own texture set; with random
+//! variation on, every painted face picks from its set so large areas don't
+//! tile visibly. Picks are hashed from the sector position and seed, so
+//! dragging back over a tile never makes it flicker between textures.
+
+use std::collections::HashSet;
+use crate::world::{Sector, TextureRef};
+
+/// Surface kind a texture set applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrushSurface {
+    Floor,
+    Wall,
+    Ceiling,
+}
+
+impl BrushSurface {
+    pub const ALL: [BrushSurface; 3] = [BrushSurface::Floor, BrushSurface::Wall, BrushSurface::Ceiling];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BrushSurface::Floor => "Floor",
+            BrushSurface::Wall => "Walls",
+            BrushSurface::Ceiling => "Ceiling",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            BrushSurface::Floor => 0,
+            BrushSurface::Wall => 1,
+            BrushSurface::Ceiling => 2,
+        }
+    }
+}
+
+/// Sectors already painted during the current drag
+#[derive(Debug, Clone, Default)]
+struct BrushStroke {
+    painted: HashSet<(usize, usize, usize)>,
+    undo_saved: bool,
+}
+
+/// Texture brush settings and stroke state
+#[derive(Debug, Clone)]
+pub struct TextureBrush {
+    /// Texture set per surface (empty = use the palette's selected texture)
+    sets: [Vec<TextureRef>; 3],
+    /// Which surfaces the brush paints
+    enabled: [bool; 3],
+    /// Pick randomly from each set instead of always using its first texture
+    pub random: bool,
+    /// Variation seed (reroll to get a different random layout)
+    pub seed: u32,
+    stroke: Option<BrushStroke>,
+}
+
+impl Default for TextureBrush {
+    fn default() -> Self {
+        Self {
+            sets: Default::default(),
+            enabled: [true, false, false],
+            random: false,
+            seed: 0,
+            stroke: None,
+        }
+    }
+}
+
+/// Integer hash of a face position (stable across runs)
+fn variation_hash(seed: u32, x: usize, z: usize, face: usize) -> u32 {
+    let mut h = seed ^ 0x9E37_79B9;
+    for v in [x as u32, z as u32, face as u32] {
+        h = (h ^ v).wrapping_mul(0x85EB_CA6B);
+        h ^= h >> 13;
+        h = h.wrapping_mul(0xC2B2_AE35);
+        h ^= h >> 16;
+    }
+    h
+}
+
+impl TextureBrush {
+    pub fn set(&self, surface: BrushSurface) -> &[TextureRef] {
+        &self.sets[surface.index()]
+    }
+
+    /// Add a texture to a surface's set (ignored if already present)
+    pub fn add_to_set(&mut self, surface: BrushSurface, texture: TextureRef) {
+        let set = &mut self.sets[surface.index()];
+        if texture.is_valid() && !set.contains(&texture) {
+            set.push(texture);
+        }
+    }
+
+    pub fn remove_from_set(&mut self, surface: BrushSurface, index: usize) {
+        let set = &mut self.sets[surface.index()];
+        if index < set.len() {
+            set.remove(index);
+        }
+    }
+
+    pub fn clear_set(&mut self, surface: BrushSurface) {
+        self.sets[surface.index()].clear();
+    }
+
+    pub fn is_enabled(&self, surface: BrushSurface) -> bool {
+        self.enabled[surface.index()]
+    }
+
+    pub fn set_enabled(&mut self, surface: BrushSurface, enabled: bool) {
+        self.enabled[surface.index()] = enabled;
+    }
+
+    /// New random layout for the next strokes
+    pub fn reseed(&mut self) {
+        self.seed = variation_hash(self.seed, 1, 2, 3);
+    }
+
+    /// Texture for one face. `face` distinguishes faces within a sector
+    /// (walls get their own picks so a corridor's sides don't match).
+    pub fn pick(&self, surface: BrushSurface, fallback: &TextureRef, x: usize, z: usize, face: usize) -> TextureRef {
+        let set = self.set(surface);
+        match set.len() {
+            0 => fallback.clone(),
+            1 => set[0].clone(),
+            n if self.random => set[variation_hash(self.seed, x, z, face) as usize % n].clone(),
+            _ => set[0].clone(),
+        }
+    }
+
+    /// Paint the enabled surfaces of a sector. Returns true if anything changed.
+    pub fn paint_sector(&self, sector: &mut Sector, x: usize, z: usize, fallback: &TextureRef) -> bool {
+        let mut changed = false;
+        // Returns true if the slot changed
+        let apply = |slot: &mut TextureRef, texture: TextureRef| {
+            if *slot == texture {
+                return false;
+            }
+            *slot = texture;
+            true
+        };
+
+        if self.is_enabled(BrushSurface::Floor) {
+            if let Some(floor) = &mut sector.floor {
+                changed |= apply(&mut floor.texture, self.pick(BrushSurface::Floor, fallback, x, z, 0));
+                if floor.texture_2.take().is_some() {
+                    changed = true;
+                }
+            }
+        }
+        if self.is_enabled(BrushSurface::Ceiling) {
+            if let Some(ceiling) = &mut sector.ceiling {
+                changed |= apply(&mut ceiling.texture, self.pick(BrushSurface::Ceiling, fallback, x, z, 1));
+                if ceiling.texture_2.take().is_some() {
+                    changed = true;
+                }
+            }
+        }
+        if self.is_enabled(BrushSurface::Wall) {
+            let walls = sector.walls_north.iter_mut()
+                .chain(sector.walls_east.iter_mut())
+                .chain(sector.walls_south.iter_mut())
+                .chain(sector.walls_west.iter_mut())
+                .chain(sector.walls_nwse.iter_mut())
+                .chain(sector.walls_nesw.iter_mut());
+            for (i, wall) in walls.enumerate() {
+                changed |= apply(&mut wall.texture, self.pick(BrushSurface::Wall, fallback, x, z, 2 + i));
+            }
+        }
+        changed
+    }
+
+    /// Start a drag
+    pub fn begin_stroke(&mut self) {
+        self.stroke = Some(BrushStroke::default());
+    }
+
+    pub fn end_stroke(&mut self) {
+        self.stroke = None;
+    }
+
+    pub fn is_painting(&self) -> bool {
+        self.stroke.is_some()
+    }
+
+    /// Mark a sector as visited by this stroke. Returns false if it was
+    /// already painted (or no stroke is active).
+    pub fn visit(&mut self, room: usize, x: usize, z: usize) -> bool {
+        self.stroke.as_mut().is_some_and(|s| s.painted.insert((room, x, z)))
+    }
+
+    /// True the first time it's called in a stroke (one undo step per drag)
+    pub fn take_undo(&mut self) -> bool {
+        match self.stroke.as_mut() {
+            Some(s) if !s.undo_saved => {
+                s.undo_saved = true;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Direction;
+
+    fn tex(name: &str) -> TextureRef {
+        TextureRef::new("SAMPLE", name)
+    }
+
+    #[test]
+    fn test_pick_fallback_first_and_random() {
+        let mut brush = TextureBrush::default();
+        let fallback = tex("selected");
+        assert_eq!(brush.pick(BrushSurface::Floor, &fallback, 0, 0, 0), fallback);
+
+        brush.add_to_set(BrushSurface::Floor, tex("a"));
+        brush.add_to_set(BrushSurface::Floor, tex("b"));
+        brush.add_to_set(BrushSurface::Floor, tex("a"));
+        assert_eq!(brush.set(BrushSurface::Floor).len(), 2);
+        assert_eq!(brush.pick(BrushSurface::Floor, &fallback, 3, 4, 0), tex("a"));
+
+        // Random picks are stable per position and use the whole set
+        brush.random = true;
+        let picks: Vec<TextureRef> = (0..32).map(|x| brush.pick(BrushSurface::Floor, &fallback, x, 7, 0)).collect();
+        assert!(picks.contains(&tex("a")) && picks.contains(&tex("b")));
+        assert_eq!(picks[5], brush.pick(BrushSurface::Floor, &fallback, 5, 7, 0));
+    }
+
+    #[test]
+    fn test_paint_sector_only_enabled_surfaces() {
+        let mut sector = Sector::with_floor_and_ceiling(0.0, 3072.0, tex("old"));
+        sector.walls_mut(Direction::North).push(crate::world::VerticalFace::new(0.0, 3072.0, tex("old")));
+
+        let mut brush = TextureBrush::default();
+        brush.add_to_set(BrushSurface::Wall, tex("brick"));
+        assert!(brush.paint_sector(&mut sector, 0, 0, &tex("stone")));
+        assert_eq!(sector.floor.as_ref().unwrap().texture, tex("stone"));
+        assert_eq!(sector.ceiling.as_ref().unwrap().texture, tex("old"));
+        assert_eq!(sector.walls_north[0].texture, tex("old"));
+
+        brush.set_enabled(BrushSurface::Wall, true);
+        assert!(brush.paint_sector(&mut sector, 0, 0, &tex("stone")));
+        assert_eq!(sector.walls_north[0].texture, tex("brick"));
+        // Nothing left to change
+        assert!(!brush.paint_sector(&mut sector, 0, 0, &tex("stone")));
+    }
+
+    #[test]
+    fn test_stroke_visits_each_sector_once() {
+        let mut brush = TextureBrush::default();
+        assert!(!brush.visit(0, 1, 1));
+        brush.begin_stroke();
+        assert!(brush.take_undo());
+        assert!(!brush.take_undo());
+        assert!(brush.visit(0, 1, 1));
+        assert!(!brush.visit(0, 1, 1));
+        assert!(brush.visit(1, 1, 1));
+        brush.end_stroke();
+        assert!(!brush.is_painting());
+    }
+}
@@ -3,17 +3,24 @@
 //! Supports two modes:
 //! - Source PNGs: Read-only textures from assets/samples/textures/
 //! - User Textures: Editable indexed textures from assets/userdata/textures/
+//!
+//! User textures can be filtered by name, tag or smart collection, and the
+//! selected texture's tags are edited above the grid.
 
 use macroquad::prelude::*;
 use crate::storage::Storage;
+use crate::project::{draw_usages_panel, Dependency, UsagesPanel};
 use crate::ui::{Rect, UiContext, icon, draw_icon_centered};
 use crate::rasterizer::{Texture as RasterTexture, ClutDepth};
 use crate::texture::{
     UserTexture, TextureSize, draw_texture_canvas, draw_tool_panel, draw_palette_panel_constrained,
-    draw_mode_tabs, TextureEditorMode, UvOverlayData, UvVertex, UvFace,
+    draw_mode_tabs, draw_frame_strip, apply_frame_action, FRAME_STRIP_HEIGHT,
+    draw_layer_strip, apply_layer_action, LAYER_STRIP_HEIGHT, TextureEditorMode, UvOverlayData, UvVertex, UvFace,
     draw_import_dialog, ImportAction, load_png_to_import_state,
+    draw_palette_import_dialog, apply_palette, handle_palette_file_request, apply_wrap_shift,
 };
 use crate::rasterizer::Vec2 as RastVec2;
+use crate::tags::{TagKind, draw_tag_filter, draw_item_tags, TAG_FILTER_HEIGHT, TAG_ROW_HEIGHT};
 use super::EditorState;
 
 const THUMB_PADDING: f32 = 4.0;
@@ -64,7 +71,19 @@ pub fn draw_texture_palette(
 
     if state.texture_palette_user_mode {
         draw_user_texture_header(ctx, header_rect, state, icon_font);
-        let grid_rect = Rect::new(content_rect.x, content_rect.y + MODE_TOGGLE_HEIGHT + HEADER_HEIGHT, content_rect.w, content_rect.h - MODE_TOGGLE_HEIGHT - HEADER_HEIGHT);
+        let mut top = header_rect.bottom();
+
+        // Tag filter, then the selected texture's tags
+        let filter_rect = Rect::new(content_rect.x, top, content_rect.w, TAG_FILTER_HEIGHT);
+        draw_tag_filter(ctx, filter_rect, &mut state.texture_tag_filter);
+        top += TAG_FILTER_HEIGHT;
+        if let Some(name) = state.selected_user_texture.clone() {
+            let tags_rect = Rect::new(content_rect.x, top, content_rect.w, TAG_ROW_HEIGHT);
+            draw_item_tags(ctx, tags_rect, &mut state.texture_tag_filter, TagKind::Texture, &name);
+            top += TAG_ROW_HEIGHT;
+        }
+
+        let grid_rect = Rect::new(content_rect.x, top, content_rect.w, content_rect.bottom() - top);
         draw_user_texture_grid(ctx, grid_rect, state, storage);
     } else {
         draw_folder_selector(ctx, header_rect, state, icon_font);
@@ -103,6 +122,13 @@ pub fn draw_texture_palette(
     }
 
     // Draw delete confirmation dialog (modal overlay) if pending
+    if let Some(name) = &state.texture_pending_delete {
+        let dependency = Dependency::Texture(name.clone());
+        if state.texture_delete_usages.as_ref().map(|u| &u.dependency) != Some(&dependency) {
+            let graph = state.dependency_graph(storage);
+            state.texture_delete_usages = Some(UsagesPanel::find(&graph, dependency));
+        }
+    }
     if let Some(action) = draw_delete_texture_dialog(ctx, state, icon_font) {
         match action {
             DeleteTextureAction::Confirm => {
@@ -126,6 +152,7 @@ pub fn draw_texture_palette(
                 state.texture_pending_delete = None;
             }
         }
+        state.texture_delete_usages = None;
     }
 }
 
@@ -146,9 +173,11 @@ fn draw_delete_texture_dialog(
     // Dark overlay
     draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
 
-    // Dialog dimensions
+    // Dialog dimensions (taller when levels or assets still use the texture)
+    let usages = state.texture_delete_usages.as_ref().filter(|u| !u.users.is_empty());
+    let usages_h = usages.map(|u| (24.0 + u.users.len() as f32 * 16.0).min(120.0)).unwrap_or(0.0);
     let dialog_w = 300.0;
-    let dialog_h = 120.0;
+    let dialog_h = 120.0 + usages_h;
     let dialog_x = (screen_width() - dialog_w) / 2.0;
     let dialog_y = (screen_height() - dialog_h) / 2.0;
 
@@ -165,6 +194,9 @@ fn draw_delete_texture_dialog(
     let msg_dims = measure_text(&msg, None, 14, 1.0);
     draw_text(&msg, dialog_x + (dialog_w - msg_dims.width) / 2.0, dialog_y + 55.0, 14.0, WHITE);
     draw_text("This cannot be undone.", dialog_x + (dialog_w - measure_text("This cannot be undone.", None, 12, 1.0).width) / 2.0, dialog_y + 75.0, 12.0, Color::from_rgba(180, 150, 150, 255));
+    if let Some(usages) = usages {
+        draw_usages_panel(Rect::new(dialog_x + 10.0, dialog_y + 84.0, dialog_w - 20.0, usages_h - 4.0), usages);
+    }
 
     // Buttons
     let btn_w = 80.0;
@@ -868,9 +900,11 @@ fn draw_user_texture_grid(
     let cols = ((content_rect.w - THUMB_PADDING) / (thumb_size + THUMB_PADDING)).floor() as usize;
     let cols = cols.max(1);
 
-    // Collect texture names for both sections
-    let sample_names: Vec<String> = state.user_textures.sample_names().map(|s| s.to_string()).collect();
-    let user_names: Vec<String> = state.user_textures.user_names().map(|s| s.to_string()).collect();
+    // Collect texture names for both sections (hiding those the tag filter rejects)
+    let filter = &state.texture_tag_filter;
+    let visible = |name: &&str| filter.matches(TagKind::Texture, name, name);
+    let sample_names: Vec<String> = state.user_textures.sample_names().filter(visible).map(|s| s.to_string()).collect();
+    let user_names: Vec<String> = state.user_textures.user_names().filter(visible).map(|s| s.to_string()).collect();
 
     // Calculate content heights for each section
     let sample_rows = if state.paint_samples_collapsed { 0 } else { (sample_names.len() + cols - 1) / cols.max(1) };
@@ -1297,7 +1331,14 @@ fn draw_texture_editor_panel(
     let canvas_h = canvas_w.min(available_for_canvas).max(min_canvas_h.min(available_for_canvas));
     let palette_panel_h = content_rect.h - canvas_h;
 
-    let canvas_rect = Rect::new(content_rect.x, content_rect.y, canvas_w, canvas_h);
+    // Flipbook frame strip and layer strip sit under the canvas in Paint mode
+    let paint_mode = state.texture_editor.mode == TextureEditorMode::Paint;
+    let frame_strip_h = if paint_mode { FRAME_STRIP_HEIGHT } else { 0.0 };
+    let layer_strip_h = if paint_mode { LAYER_STRIP_HEIGHT } else { 0.0 };
+    let strips_h = frame_strip_h + layer_strip_h;
+    let canvas_rect = Rect::new(content_rect.x, content_rect.y, canvas_w, canvas_h - strips_h);
+    let frame_strip_rect = Rect::new(content_rect.x, content_rect.y + canvas_h - strips_h, canvas_w, frame_strip_h);
+    let layer_strip_rect = Rect::new(content_rect.x, content_rect.y + canvas_h - layer_strip_h, canvas_w, layer_strip_h);
     let tool_rect = Rect::new(content_rect.x + canvas_w, content_rect.y, tool_panel_w, canvas_h);
     let palette_rect = Rect::new(content_rect.x, content_rect.y + canvas_h, content_rect.w, palette_panel_h);
 
@@ -1307,6 +1348,16 @@ fn draw_texture_editor_panel(
 
     // Draw panels
     draw_texture_canvas(ctx, canvas_rect, tex, &mut state.texture_editor, uv_data.as_ref());
+    let frame_action = if frame_strip_h > 0.0 {
+        draw_frame_strip(ctx, frame_strip_rect, tex, &mut state.texture_editor, icon_font)
+    } else {
+        None
+    };
+    let layer_action = if layer_strip_h > 0.0 {
+        draw_layer_strip(ctx, layer_strip_rect, tex, &mut state.texture_editor, icon_font)
+    } else {
+        None
+    };
     draw_tool_panel(ctx, tool_rect, &mut state.texture_editor, icon_font);
     // Constrain top section (4/8-bit, Gen) to canvas width so it doesn't overlap tool panel
     draw_palette_panel_constrained(ctx, palette_rect, tex, &mut state.texture_editor, icon_font, Some(canvas_w));
@@ -1325,6 +1376,53 @@ fn draw_texture_editor_panel(
         state.save_texture_undo(&texture_name);
     }
 
+    // Add/remove flipbook frames (snapshot undo first so frames can be restored)
+    if let Some(action) = frame_action {
+        state.save_texture_undo(&texture_name);
+        if let Some(tex) = state.user_textures.get_mut(&texture_name) {
+            apply_frame_action(tex, &mut state.texture_editor, action);
+        }
+    }
+
+    // Add/remove/reorder/merge paint layers (snapshot undo first)
+    if let Some(action) = layer_action {
+        state.save_texture_undo(&texture_name);
+        if let Some(tex) = state.user_textures.get_mut(&texture_name) {
+            apply_layer_action(tex, &mut state.texture_editor, action);
+        }
+    }
+
+    // Wrap-shift from arrow keys / offset button (snapshot undo first)
+    if let Some(shift) = state.texture_editor.wrap_shift_pending.take() {
+        state.save_texture_undo(&texture_name);
+        if let Some(tex) = state.user_textures.get_mut(&texture_name) {
+            apply_wrap_shift(tex, &mut state.texture_editor, shift);
+        }
+    }
+
+    // Palette file import/export requested from the palette panel
+    if let Some(request) = state.texture_editor.palette_file_request.take() {
+        if let Some(tex) = state.user_textures.get(&texture_name) {
+            match handle_palette_file_request(request, tex, &mut state.texture_editor.palette_import) {
+                Ok(Some(msg)) => state.set_status(&msg, 2.0),
+                Ok(None) => {}
+                Err(e) => state.set_status(&e, 3.0),
+            }
+        }
+    }
+
+    // Palette import dialog (modal overlay)
+    if let Some(ImportAction::Confirm) = draw_palette_import_dialog(ctx, &mut state.texture_editor.palette_import, icon_font) {
+        state.save_texture_undo(&texture_name);
+        if let Some(tex) = state.user_textures.get_mut(&texture_name) {
+            let import = &state.texture_editor.palette_import;
+            apply_palette(tex, &import.preview, import.remap_pixels);
+            state.texture_editor.dirty = true;
+        }
+        state.set_status(&format!("Imported palette '{}'", state.texture_editor.palette_import.file_name), 2.0);
+        state.texture_editor.palette_import.reset();
+    }
+
     // Handle undo/redo button requests (uses global undo system)
     if state.texture_editor.undo_requested {
         state.texture_editor.undo_requested = false;
@@ -444,6 +444,10 @@ pub fn draw_viewport_3d(
             Selection::SectorFace { room, .. } => Some(*room),
             Selection::Vertex { room, .. } => Some(*room),
             Selection::Object { room, .. } => Some(*room),
+            Selection::Trigger { room, .. } => Some(*room),
+            Selection::Ladder { room, .. } => Some(*room),
+            Selection::Camera { room, .. } => Some(*room),
+            Selection::Path { room, .. } => Some(*room),
             Selection::Room(room) => Some(*room),
             Selection::Sector { room, .. } => Some(*room),
             Selection::Edge { room, .. } => Some(*room),
@@ -697,6 +701,32 @@ pub fn draw_viewport_3d(
         }
     }
 
+    // Player capsule preview: feet on the floor under the cursor
+    let capsule_feet = if inside_viewport && state.show_player_capsule {
+        screen_to_fb(mouse_pos.0, mouse_pos.1).and_then(|mouse_fb| {
+            let pick_floor = |y: f32| pick_plane(
+                Vec3::new(0.0, y, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+                Vec3::ZERO,
+                mouse_fb,
+                &state.camera_3d,
+                fb_width, fb_height,
+                None,
+            );
+            // Pick the room's base plane, then re-pick at the floor found there
+            // so raised floors line up with the cursor
+            let room_y = state.level.rooms.get(state.current_room).map(|r| r.position.y).unwrap_or(0.0);
+            let hint = Some(state.current_room);
+            let first = pick_floor(room_y)?;
+            let floor = state.level.get_floor_height(first, hint)?;
+            let second = pick_floor(floor).unwrap_or(first);
+            let floor = state.level.get_floor_height(second, hint).unwrap_or(floor);
+            Some(Vec3::new(second.x, floor, second.z))
+        })
+    } else {
+        None
+    };
+
     // In drawing modes, find preview sector position
     if inside_viewport && (state.tool == EditorTool::DrawFloor || state.tool == EditorTool::DrawCeiling) {
         if let Some((mouse_fb_x, mouse_fb_y)) = screen_to_fb(mouse_pos.0, mouse_pos.1) {
@@ -3469,6 +3499,8 @@ pub fn draw_viewport_3d(
     let vp_raster_ms = 0.0f32;
 
     let skip_rooms: Vec<usize> = state.hidden_rooms.iter().copied().collect();
+    // Preview the project's vertex snapping live as it's edited
+    state.raster_settings.vertex_snap = state.level.vertex_snap;
     crate::scene::render_scene(
         fb,
         &state.level.rooms,
@@ -3482,11 +3514,33 @@ pub fn draw_viewport_3d(
         &resolve_texture,
         &crate::scene::SceneRenderOptions {
             use_fog: true,
+            atmosphere: Some(&state.level.atmosphere),
             render_assets: true,
             skip_rooms: &skip_rooms,
+            lod_scale: None,
+            hidden_objects: &[],
+            moved_objects: &[],
+            broken_objects: &[],
+            dropped_assets: &[],
+            baked_lighting: state.preview_baked_lighting,
+            water_time: Some(macroquad::prelude::get_time() as f32),
         },
     );
 
+    // Trigger volumes (translucent boxes, drawn after rooms so they blend over them)
+    draw_trigger_volumes(fb, state, use_rgb555);
+    draw_ladders(fb, state);
+    draw_camera_volumes(fb, state);
+    draw_entity_paths(fb, state);
+    draw_platform_routes(fb, state);
+    if state.portal_debug {
+        draw_portal_debug(fb, state);
+    }
+
+    if let Some(feet) = capsule_feet {
+        draw_player_capsule(fb, &state.camera_3d, &state.level.player_settings, feet);
+    }
+
     let _render_total_ms = EditorFrameTimings::elapsed_ms(render_start);
 
     // === PREVIEW/SELECTION PHASE ===
@@ -4200,7 +4254,7 @@ pub fn draw_viewport_3d(
                     let mut drew_collision = false;
                     if let Some(asset) = asset {
                         for comp in &asset.components {
-                            if let crate::asset::AssetComponent::Collision { shape, is_trigger } = comp {
+                            if let crate::asset::AssetComponent::Collision { shape, is_trigger, offset } = comp {
                                 let wire_color = if is_selected {
                                     RasterColor::new(255, 255, 255)
                                 } else if *is_trigger {
@@ -4208,36 +4262,39 @@ pub fn draw_viewport_3d(
                                 } else {
                                     RasterColor::new(100, 150, 255)
                                 };
+                                // Shape offset follows the instance's facing
+                                let cos_f = obj.facing.cos();
+                                let sin_f = obj.facing.sin();
+                                let center = Vec3::new(
+                                    world_pos.x + offset[0] * cos_f - offset[2] * sin_f,
+                                    world_pos.y + offset[1],
+                                    world_pos.z + offset[0] * sin_f + offset[2] * cos_f,
+                                );
                                 match shape {
                                     crate::asset::CollisionShapeDef::Sphere { radius } => {
-                                        draw_wireframe_sphere(fb, &state.camera_3d, world_pos, *radius, 16, wire_color);
+                                        draw_wireframe_sphere(fb, &state.camera_3d, center, *radius, 16, wire_color);
                                     }
                                     crate::asset::CollisionShapeDef::Box { half_extents } => {
-                                        let cos_f = obj.facing.cos();
-                                        let sin_f = obj.facing.sin();
                                         let hx = half_extents[0];
                                         let hy = half_extents[1];
                                         let hz = half_extents[2];
-                                        let min = Vec3::new(-hx, -hy, -hz);
-                                        let max = Vec3::new(hx, hy, hz);
+                                        let min = Vec3::new(offset[0] - hx, offset[1] - hy, offset[2] - hz);
+                                        let max = Vec3::new(offset[0] + hx, offset[1] + hy, offset[2] + hz);
                                         draw_rotated_bounding_box(fb, &state.camera_3d, min, max, world_pos, cos_f, sin_f, wire_color);
                                     }
                                     crate::asset::CollisionShapeDef::Cylinder { radius, height } => {
-                                        draw_wireframe_cylinder(fb, &state.camera_3d, world_pos, *radius, *height, 12, wire_color);
+                                        draw_wireframe_cylinder(fb, &state.camera_3d, center, *radius, *height, 12, wire_color);
                                     }
                                     crate::asset::CollisionShapeDef::Capsule { radius, height } => {
-                                        draw_wireframe_capsule(fb, &state.camera_3d, world_pos, *radius, *height, 12, wire_color);
+                                        draw_wireframe_capsule(fb, &state.camera_3d, center, *radius, *height, 12, wire_color);
                                     }
                                     crate::asset::CollisionShapeDef::FromMesh => {
                                         if let Some((min, max)) = asset.bounds() {
-                                            let cos_f = obj.facing.cos();
-                                            let sin_f = obj.facing.sin();
                                             draw_rotated_bounding_box(fb, &state.camera_3d, min, max, world_pos, cos_f, sin_f, wire_color);
                                         }
                                     }
                                 }
                                 drew_collision = true;
-                                break;
                             }
                         }
                     }
@@ -4264,6 +4321,16 @@ pub fn draw_viewport_3d(
                                 RasterColor::new(255, 200, 50), // Yellow highlight
                             );
                         }
+
+                        // Preview LOD switch points as rings on the ground
+                        if let Some((reduced, impostor)) = asset.lod_distances() {
+                            if let Some(d) = reduced {
+                                draw_ground_ring(fb, &state.camera_3d, world_pos, d, 48, RasterColor::new(100, 200, 255));
+                            }
+                            if let Some(d) = impostor {
+                                draw_ground_ring(fb, &state.camera_3d, world_pos, d, 48, RasterColor::new(200, 120, 255));
+                            }
+                        }
                     }
                 }
             }
@@ -4669,6 +4736,23 @@ pub fn draw_viewport_3d(
                 let width = max_x - min_x;
                 let depth = max_z - min_z;
 
+                // Copied objects: vertical marker at the sector center
+                for obj in &gc.objects {
+                    let (ox, oz) = (obj.sector_x as i32, obj.sector_z as i32);
+                    let (rx, rz, rw, rd) = match gc.rotation % 4 {
+                        1 => (depth - oz, ox, depth, width),
+                        2 => (width - ox, depth - oz, width, depth),
+                        3 => (oz, width - ox, depth, width),
+                        _ => (ox, oz, width, depth),
+                    };
+                    let rel_x = if gc.flip_h { rw - rx } else { rx };
+                    let rel_z = if gc.flip_v { rd - rz } else { rz };
+                    let center_x = room.position.x + ((anchor_gx + rel_x) as f32 + 0.5) * SECTOR_SIZE;
+                    let center_z = room.position.z + ((anchor_gz + rel_z) as f32 + 0.5) * SECTOR_SIZE;
+                    let base_y = room_y + obj.height;
+                    draw_3d_line(fb, Vec3::new(center_x, base_y, center_z), Vec3::new(center_x, base_y + SECTOR_SIZE * 0.5, center_z), &state.camera_3d, preview_color);
+                }
+
                 for copied_face in &gc.faces {
                     // Apply rotation first, then flip transformations
                     let (rx, rz, rw, rd) = match gc.rotation % 4 {
@@ -5559,12 +5643,11 @@ pub fn draw_viewport_3d(
     // === UPLOAD PHASE ===
     let upload_start = EditorFrameTimings::start();
 
-    // Convert framebuffer to texture and draw to viewport
-    let texture = Texture2D::from_rgba8(fb.width as u16, fb.height as u16, &fb.pixels);
-    texture.set_filter(FilterMode::Nearest);
+    // Upload what changed in the framebuffer and draw it to the viewport
+    let texture = state.viewport_texture.upload(fb);
 
     draw_texture_ex(
-        &texture,
+        texture,
         draw_x,
         draw_y,
         WHITE,
@@ -5636,6 +5719,27 @@ pub fn draw_viewport_3d(
         Color::from_rgba(200, 200, 200, 255),
     );
 
+    // Player capsule measurements - bottom left
+    if capsule_feet.is_some() {
+        let settings = &state.level.player_settings;
+        draw_text(
+            &format!(
+                "Player: {:.0} tall, {:.0} wide | Step {:.0} | Jump {:.0} up, {:.0} far (sprint {:.0} up, {:.0} far)",
+                settings.height,
+                settings.radius * 2.0,
+                settings.step_height,
+                settings.jump_height(false),
+                settings.jump_distance(false),
+                settings.jump_height(true),
+                settings.jump_distance(true),
+            ),
+            rect.x + 5.0,
+            rect.bottom() - 6.0,
+            14.0,
+            Color::from_rgba(120, 220, 255, 255),
+        );
+    }
+
     // Center 3D camera on current room button - top right
     let btn_size = 24.0;
     let btn_rect = crate::ui::Rect::new(
@@ -5695,6 +5799,358 @@ fn draw_3d_line(
 }
 
 /// Draw a 3D line with depth testing (overlay mode - draws on co-planar surfaces)
+/// Render every visible room's trigger volumes as translucent boxes with outlines
+fn draw_trigger_volumes(fb: &mut Framebuffer, state: &EditorState, use_rgb555: bool) {
+    use crate::rasterizer::{Vertex, Face, Vec2, ShadingMode};
+
+    // Corner indices of a box: bit 0 = x, bit 1 = y, bit 2 = z
+    const QUADS: [[usize; 4]; 6] = [
+        [0, 1, 3, 2], [4, 6, 7, 5], // -z, +z
+        [0, 2, 6, 4], [1, 5, 7, 3], // -x, +x
+        [0, 4, 5, 1], [2, 3, 7, 6], // -y, +y
+    ];
+    const EDGES: [(usize, usize); 12] = [
+        (0, 1), (2, 3), (4, 5), (6, 7),
+        (0, 2), (1, 3), (4, 6), (5, 7),
+        (0, 4), (1, 5), (2, 6), (3, 7),
+    ];
+
+    let mut settings = state.raster_settings.clone();
+    settings.backface_cull = false;
+    settings.shading = ShadingMode::None;
+
+    for (room_idx, room) in state.level.rooms.iter().enumerate() {
+        if state.hidden_rooms.contains(&room_idx) {
+            continue;
+        }
+        for (index, trigger) in room.triggers.iter().enumerate() {
+            let selected = matches!(state.selection, Selection::Trigger { room: r, index: i } if r == room_idx && i == index);
+            let (fill, alpha) = if selected {
+                (RasterColor::new(255, 200, 80), 110)
+            } else {
+                (RasterColor::new(255, 140, 40), 60)
+            };
+            let (min, max) = trigger.world_bounds(room.position);
+            let corners: Vec<Vec3> = (0..8)
+                .map(|bit| Vec3::new(
+                    if bit & 1 != 0 { max.x } else { min.x },
+                    if bit & 2 != 0 { max.y } else { min.y },
+                    if bit & 4 != 0 { max.z } else { min.z },
+                ))
+                .collect();
+
+            let vertices: Vec<Vertex> = corners.iter()
+                .map(|&p| Vertex::with_color(p, Vec2::default(), Vec3::ZERO, fill))
+                .collect();
+            let mut faces = Vec::with_capacity(12);
+            for [a, b, c, d] in QUADS {
+                faces.push(Face::new(a, b, c).with_editor_alpha(alpha));
+                faces.push(Face::new(a, c, d).with_editor_alpha(alpha));
+            }
+            if use_rgb555 {
+                crate::rasterizer::render_mesh_15(fb, &vertices, &faces, &[], &state.camera_3d, &settings, None);
+            } else {
+                crate::rasterizer::render_mesh(fb, &vertices, &faces, &[], &state.camera_3d, &settings);
+            }
+
+            for (a, b) in EDGES {
+                draw_3d_line_depth(fb, corners[a], corners[b], &state.camera_3d, fill);
+            }
+        }
+    }
+}
+
+/// Render every visible room's ladders as box outlines with rungs on their fixed side
+fn draw_ladders(fb: &mut Framebuffer, state: &EditorState) {
+    const RUNG_SPACING: f32 = 256.0;
+    for (room_idx, room) in state.level.rooms.iter().enumerate() {
+        if state.hidden_rooms.contains(&room_idx) {
+            continue;
+        }
+        for (index, ladder) in room.ladders.iter().enumerate() {
+            let selected = matches!(state.selection, Selection::Ladder { room: r, index: i } if r == room_idx && i == index);
+            let color = if selected { RasterColor::new(200, 255, 160) } else { RasterColor::new(120, 200, 80) };
+            let (min, max) = ladder.world_bounds(room.position);
+            let corner = |bit: usize| Vec3::new(
+                if bit & 1 != 0 { max.x } else { min.x },
+                if bit & 2 != 0 { max.y } else { min.y },
+                if bit & 4 != 0 { max.z } else { min.z },
+            );
+            for (a, b) in [(0, 1), (2, 3), (4, 5), (6, 7), (0, 2), (1, 3), (4, 6), (5, 7), (0, 4), (1, 5), (2, 6), (3, 7)] {
+                draw_3d_line_depth(fb, corner(a), corner(b), &state.camera_3d, color);
+            }
+
+            // Rungs across the side the ladder is fixed to
+            let facing = ladder.facing_vector();
+            let (from, to) = match (facing.x as i32, facing.z as i32) {
+                (1, _) => (Vec3::new(max.x, 0.0, min.z), Vec3::new(max.x, 0.0, max.z)),
+                (-1, _) => (Vec3::new(min.x, 0.0, min.z), Vec3::new(min.x, 0.0, max.z)),
+                (_, 1) => (Vec3::new(min.x, 0.0, max.z), Vec3::new(max.x, 0.0, max.z)),
+                _ => (Vec3::new(min.x, 0.0, min.z), Vec3::new(max.x, 0.0, min.z)),
+            };
+            let mut y = min.y + RUNG_SPACING * 0.5;
+            while y < max.y {
+                let lift = Vec3::new(0.0, y, 0.0);
+                draw_3d_line_depth(fb, from + lift, to + lift, &state.camera_3d, color);
+                y += RUNG_SPACING;
+            }
+        }
+    }
+}
+
+/// Render every visible room's camera volumes as box outlines, with their
+/// rail and a marker at each camera position aimed at the volume's center
+fn draw_camera_volumes(fb: &mut Framebuffer, state: &EditorState) {
+    const MARKER: f32 = 64.0;
+    for (room_idx, room) in state.level.rooms.iter().enumerate() {
+        if state.hidden_rooms.contains(&room_idx) {
+            continue;
+        }
+        for (index, camera) in room.cameras.iter().enumerate() {
+            let selected = matches!(state.selection, Selection::Camera { room: r, index: i } if r == room_idx && i == index);
+            let color = if selected { RasterColor::new(190, 220, 255) } else { RasterColor::new(100, 170, 255) };
+            let (min, max) = camera.world_bounds(room.position);
+            let corner = |bit: usize| Vec3::new(
+                if bit & 1 != 0 { max.x } else { min.x },
+                if bit & 2 != 0 { max.y } else { min.y },
+                if bit & 4 != 0 { max.z } else { min.z },
+            );
+            for (a, b) in [(0, 1), (2, 3), (4, 5), (6, 7), (0, 2), (1, 3), (4, 6), (5, 7), (0, 4), (1, 5), (2, 6), (3, 7)] {
+                draw_3d_line_depth(fb, corner(a), corner(b), &state.camera_3d, color);
+            }
+
+            let rail: Vec<Vec3> = camera.rail_samples().into_iter().map(|p| room.position + p).collect();
+            for pair in rail.windows(2) {
+                draw_3d_line_depth(fb, pair[0], pair[1], &state.camera_3d, color);
+            }
+
+            // Camera markers: a small cross with a line toward what they frame
+            let center = camera.world_center(room.position);
+            for point in camera.shot_points() {
+                let p = room.position + *point;
+                for axis in [Vec3::new(MARKER, 0.0, 0.0), Vec3::new(0.0, MARKER, 0.0), Vec3::new(0.0, 0.0, MARKER)] {
+                    draw_3d_line_depth(fb, p - axis, p + axis, &state.camera_3d, color);
+                }
+                let aim = (center - p).normalize() * (MARKER * 4.0);
+                draw_3d_line_depth(fb, p, p + aim, &state.camera_3d, color);
+            }
+        }
+    }
+}
+
+/// Render every visible room's entity paths as lines between their waypoints
+fn draw_entity_paths(fb: &mut Framebuffer, state: &EditorState) {
+    // Lift lines off the floor so they don't z-fight with it
+    let lift = Vec3::new(0.0, 16.0, 0.0);
+    for (room_idx, room) in state.level.rooms.iter().enumerate() {
+        if state.hidden_rooms.contains(&room_idx) {
+            continue;
+        }
+        for (index, path) in room.paths.iter().enumerate() {
+            let selected = matches!(state.selection, Selection::Path { room: r, index: i, .. } if r == room_idx && i == index);
+            let color = if selected { RasterColor::new(140, 220, 255) } else { RasterColor::new(60, 140, 200) };
+            let points: Vec<Vec3> = path.world_points(room.position).into_iter().map(|p| p + lift).collect();
+            for pair in points.windows(2) {
+                draw_3d_line_depth(fb, pair[0], pair[1], &state.camera_3d, color);
+            }
+            if path.mode == crate::world::PathMode::Loop && points.len() > 2 {
+                draw_3d_line_depth(fb, points[points.len() - 1], points[0], &state.camera_3d, color);
+            }
+            // Short posts mark the waypoints
+            for &p in &points {
+                draw_3d_line_depth(fb, p - lift, p + lift * 8.0, &state.camera_3d, color);
+            }
+        }
+    }
+}
+
+/// Moving platform previews: elevators get a line up to where they stop, and
+/// every platform a marker travelling its route at its speed (path platforms
+/// follow their attached path, drawn by `draw_entity_paths`)
+fn draw_platform_routes(fb: &mut Framebuffer, state: &EditorState) {
+    let color = RasterColor::new(255, 180, 60);
+    let camera = &state.camera_3d;
+    let time = macroquad::prelude::get_time() as f32;
+    for (room_idx, room) in state.level.rooms.iter().enumerate() {
+        if state.hidden_rooms.contains(&room_idx) {
+            continue;
+        }
+        for obj in &room.objects {
+            let Some(asset) = state.asset_library.get_by_id(obj.asset_id) else {
+                continue;
+            };
+            let Some((route, speed)) = asset.components.iter().find_map(|c| match c {
+                crate::asset::AssetComponent::MovingPlatform { route, speed, .. } => Some((*route, *speed)),
+                _ => None,
+            }) else {
+                continue;
+            };
+            let position = obj.world_position(room);
+            let (points, mode) = match route.end_offset(obj.facing) {
+                Some([dx, dy, dz]) => {
+                    let end = position + Vec3::new(dx, dy, dz);
+                    draw_3d_line_depth(fb, position, end, camera, color);
+                    // Crosses mark both stops
+                    for p in [position, end] {
+                        draw_3d_line_depth(fb, p - Vec3::new(128.0, 0.0, 0.0), p + Vec3::new(128.0, 0.0, 0.0), camera, color);
+                        draw_3d_line_depth(fb, p - Vec3::new(0.0, 0.0, 128.0), p + Vec3::new(0.0, 0.0, 128.0), camera, color);
+                    }
+                    (vec![position, end], crate::world::PathMode::PingPong)
+                }
+                None => {
+                    let Some(path) = obj.path.as_ref().and_then(|follow| {
+                        state.level.rooms.iter()
+                            .find_map(|r| r.paths.iter().find(|p| p.path_id == follow.path_id).map(|p| (r, p)))
+                    }) else {
+                        continue;
+                    };
+                    let lift = Vec3::new(0.0, obj.height, 0.0);
+                    (path.1.world_points(path.0.position).into_iter().map(|p| p + lift).collect(), path.1.mode)
+                }
+            };
+            // Marker: a small diamond where the platform would be now (stops not included)
+            let Some((p, _)) = crate::world::point_along_path(&points, mode, time * speed) else {
+                continue;
+            };
+            let (up, side, front) = (Vec3::new(0.0, 64.0, 0.0), Vec3::new(64.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 64.0));
+            for a in [p + up, p - up] {
+                for b in [p + side, p - side, p + front, p - front] {
+                    draw_3d_line_depth(fb, a, b, camera, color);
+                }
+            }
+        }
+    }
+}
+
+/// Portal debug view: every portal polygon, with the problems from the last
+/// portal check in red (one-way portals, open edges, overlapping sectors and
+/// unreachable rooms)
+fn draw_portal_debug(fb: &mut Framebuffer, state: &EditorState) {
+    use crate::world::{Direction, PortalIssue};
+    let ok_color = RasterColor::new(100, 255, 160);
+    let bad_color = RasterColor::new(255, 60, 60);
+    let camera = &state.camera_3d;
+    let quad = |fb: &mut Framebuffer, v: [Vec3; 4], color: RasterColor| {
+        for i in 0..4 {
+            draw_3d_line_depth(fb, v[i], v[(i + 1) % 4], camera, color);
+        }
+        // Diagonals so the opening reads as a filled polygon
+        draw_3d_line_depth(fb, v[0], v[2], camera, color);
+        draw_3d_line_depth(fb, v[1], v[3], camera, color);
+    };
+
+    for (room_idx, room) in state.level.rooms.iter().enumerate() {
+        if state.hidden_rooms.contains(&room_idx) {
+            continue;
+        }
+        // Open-air portals reach to infinity; cut them at the room's bounds
+        let (low, high) = (room.position.y + room.bounds.min.y, room.position.y + room.bounds.max.y);
+        for (portal_idx, portal) in room.portals.iter().enumerate() {
+            let broken = state.portal_issues.iter().any(|issue| matches!(*issue,
+                PortalIssue::OneWay { room, portal, .. } | PortalIssue::BadTarget { room, portal, .. }
+                    if room == room_idx && portal == portal_idx));
+            let v = portal.vertices.map(|v| {
+                let w = room.position + v;
+                Vec3::new(w.x, w.y.max(low).min(high), w.z)
+            });
+            quad(fb, v, if broken { bad_color } else { ok_color });
+        }
+    }
+
+    for issue in &state.portal_issues {
+        match *issue {
+            PortalIssue::OpenEdge { room, x, z, dir, .. } => {
+                let Some(r) = state.level.rooms.get(room) else { continue };
+                let Some(sector) = r.get_sector(x, z) else { continue };
+                let (Some(floor), Some(ceiling)) = (&sector.floor, &sector.ceiling) else { continue };
+                let (x0, z0) = (r.position.x + x as f32 * SECTOR_SIZE, r.position.z + z as f32 * SECTOR_SIZE);
+                let (x1, z1) = (x0 + SECTOR_SIZE, z0 + SECTOR_SIZE);
+                let ((ax, az), (bx, bz)) = match dir {
+                    Direction::North => ((x0, z0), (x1, z0)),
+                    Direction::East => ((x1, z0), (x1, z1)),
+                    Direction::South => ((x1, z1), (x0, z1)),
+                    _ => ((x0, z1), (x0, z0)),
+                };
+                let (fy, cy) = (r.position.y + floor.avg_height(), r.position.y + ceiling.avg_height());
+                quad(fb, [Vec3::new(ax, fy, az), Vec3::new(bx, fy, bz), Vec3::new(bx, cy, bz), Vec3::new(ax, cy, az)], bad_color);
+            }
+            PortalIssue::Overlap { a, x, z, .. } => {
+                let Some(r) = state.level.rooms.get(a) else { continue };
+                let Some(sector) = r.get_sector(x, z) else { continue };
+                let y = r.position.y + sector.floor.as_ref().map_or(0.0, |f| f.avg_height()) + 16.0;
+                let (x0, z0) = (r.position.x + x as f32 * SECTOR_SIZE, r.position.z + z as f32 * SECTOR_SIZE);
+                let (x1, z1) = (x0 + SECTOR_SIZE, z0 + SECTOR_SIZE);
+                quad(fb, [Vec3::new(x0, y, z0), Vec3::new(x1, y, z0), Vec3::new(x1, y, z1), Vec3::new(x0, y, z1)], bad_color);
+            }
+            PortalIssue::Unreachable { room } => {
+                let Some(r) = state.level.rooms.get(room) else { continue };
+                if state.hidden_rooms.contains(&room) || r.bounds.min.y > r.bounds.max.y {
+                    continue;
+                }
+                let bounds = r.world_bounds();
+                let center = (bounds.min + bounds.max) * 0.5;
+                let half = (bounds.max - bounds.min) * 0.5;
+                draw_wireframe_box(fb, camera, center, [half.x, half.y, half.z], bad_color);
+            }
+            PortalIssue::OneWay { .. } | PortalIssue::BadTarget { .. } => {}
+        }
+    }
+}
+
+/// Player collision cylinder, step height and jump reach standing at `feet`
+fn draw_player_capsule(
+    fb: &mut Framebuffer,
+    camera: &crate::rasterizer::Camera,
+    settings: &crate::world::PlayerSettings,
+    feet: Vec3,
+) {
+    const SEGMENTS: usize = 16;
+    const ARCS: usize = 8;
+    const ARC_STEPS: usize = 12;
+
+    let ring_point = |radius: f32, y: f32, i: usize| {
+        let angle = i as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+        Vec3::new(feet.x + angle.cos() * radius, feet.y + y, feet.z + angle.sin() * radius)
+    };
+    let ring = |fb: &mut Framebuffer, radius: f32, y: f32, color: RasterColor| {
+        for i in 0..SEGMENTS {
+            draw_3d_line_depth(fb, ring_point(radius, y, i), ring_point(radius, y, i + 1), camera, color);
+        }
+    };
+
+    // Collision cylinder
+    let body = RasterColor::new(100, 200, 255);
+    ring(fb, settings.radius, 0.0, body);
+    ring(fb, settings.radius, settings.height, body);
+    for i in (0..SEGMENTS).step_by(SEGMENTS / 4) {
+        draw_3d_line_depth(fb, ring_point(settings.radius, 0.0, i), ring_point(settings.radius, settings.height, i), camera, body);
+    }
+
+    // Highest ledge the player steps onto without jumping
+    ring(fb, settings.radius, settings.step_height, RasterColor::new(100, 255, 100));
+
+    // Jump reach: peak height over the player, landing circles at floor level
+    // (walking and sprinting), and sprint-jump arcs out to the landing circle
+    let walk = RasterColor::new(255, 160, 60);
+    let sprint = RasterColor::new(255, 230, 80);
+    ring(fb, settings.radius, settings.jump_height(true), sprint);
+    ring(fb, settings.jump_distance(false), 0.0, walk);
+    ring(fb, settings.jump_distance(true), 0.0, sprint);
+
+    let (height, distance) = (settings.jump_height(true), settings.jump_distance(true));
+    for arc in 0..ARCS {
+        let angle = arc as f32 / ARCS as f32 * std::f32::consts::TAU;
+        let (dx, dz) = (angle.cos(), angle.sin());
+        let point = |step: usize| {
+            let t = step as f32 / ARC_STEPS as f32;
+            Vec3::new(feet.x + dx * distance * t, feet.y + 4.0 * height * t * (1.0 - t), feet.z + dz * distance * t)
+        };
+        for step in 0..ARC_STEPS {
+            draw_3d_line_depth(fb, point(step), point(step + 1), camera, sprint);
+        }
+    }
+}
+
 fn draw_3d_line_depth(
     fb: &mut Framebuffer,
     p0: Vec3,
@@ -5975,6 +6431,26 @@ fn draw_3d_point(
     }
 }
 
+/// Draw a flat ring on the XZ plane (for LOD switch distance preview)
+fn draw_ground_ring(
+    fb: &mut Framebuffer,
+    camera: &crate::rasterizer::Camera,
+    center: Vec3,
+    radius: f32,
+    segments: usize,
+    color: RasterColor,
+) {
+    use std::f32::consts::PI;
+
+    let point = |i: usize| {
+        let angle = (i as f32 / segments as f32) * 2.0 * PI;
+        Vec3::new(center.x + radius * angle.cos(), center.y, center.z + radius * angle.sin())
+    };
+    for i in 0..segments {
+        draw_3d_line(fb, point(i), point(i + 1), camera, color);
+    }
+}
+
 /// Draw a wireframe cylinder in the 3D view (for player collision visualization)
 fn draw_wireframe_cylinder(
     fb: &mut Framebuffer,
@@ -6308,6 +6784,10 @@ fn draw_filled_triangle_3d(
 
     if y2 == y0 { return; } // Degenerate triangle
 
+    // Writes pixels directly, so report the covered area
+    let (min_x, max_x) = (x0.min(x1).min(x2).max(0) as usize, x0.max(x1).max(x2).max(0) as usize);
+    fb.mark_dirty(min_x, y0.max(0) as usize, max_x + 1, y2.max(0) as usize + 1);
+
     // Scanline fill
     let total_height = (y2 - y0) as f32;
 
@@ -6375,7 +6855,8 @@ fn handle_camera_input(
     let delta = get_frame_time();
 
     // Gamepad input (use gamepad_left_stick to avoid macOS stuck key issue from Cmd+A)
-    let left_stick = input.gamepad_left_stick();
+    // While the stick drives the UI cursor, only RB + stick moves the camera
+    let left_stick = if ctx.gamepad.active { ctx.gamepad.fly } else { input.gamepad_left_stick() };
     let right_stick = input.right_stick();
     let has_gamepad_input = left_stick.length() > 0.0 || right_stick.length() > 0.0
         || input.action_down(Action::FlyUp) || input.action_down(Action::FlyDown);
@@ -0,0 +1,328 @@
+//! Frame pacing
+//!
+//! Engine-wide frame rate limiter applied once per main loop iteration, for
+//! every tool. Works in two modes:
+//!
+//! - **Vsync-locked**: once the display refresh interval has been measured and
+//!   the target frame time is a whole multiple of it (30fps on a 60Hz display),
+//!   the pacer wakes half a refresh before the n-th vsync and lets the buffer
+//!   swap block the rest. Every frame is then held for exactly n refreshes, the
+//!   same frame duplication a PS1 running at 30fps shows on a 60Hz TV.
+//! - **Timer**: otherwise frames are scheduled against absolute deadlines
+//!   (sleep for the bulk, spin for the last couple of milliseconds) so timing
+//!   error doesn't accumulate from frame to frame.
+
+#![allow(dead_code)]
+
+use macroquad::prelude::get_time;
+
+/// FPS limit setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FpsLimit {
+    /// 24 FPS (film cadence, used by some FMV-heavy games)
+    Fps24,
+    /// 30 FPS (authentic PS1 for many games)
+    Fps30,
+    /// 60 FPS (smooth gameplay)
+    #[default]
+    Fps60,
+    /// Unlocked (as fast as possible, or the display rate with vsync)
+    Unlocked,
+}
+
+impl FpsLimit {
+    pub const ALL: [FpsLimit; 4] = [FpsLimit::Fps24, FpsLimit::Fps30, FpsLimit::Fps60, FpsLimit::Unlocked];
+
+    /// Get the target frame time in seconds (None = unlocked)
+    pub fn frame_time(&self) -> Option<f64> {
+        match self {
+            FpsLimit::Fps24 => Some(1.0 / 24.0),
+            FpsLimit::Fps30 => Some(1.0 / 30.0),
+            FpsLimit::Fps60 => Some(1.0 / 60.0),
+            FpsLimit::Unlocked => None,
+        }
+    }
+
+    /// Cycle to next value
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&l| l == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// Cycle to previous value
+    pub fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|&l| l == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    /// Display name
+    pub fn label(&self) -> &'static str {
+        match self {
+            FpsLimit::Fps24 => "24",
+            FpsLimit::Fps30 => "30",
+            FpsLimit::Fps60 => "60",
+            FpsLimit::Unlocked => "Unlocked",
+        }
+    }
+}
+
+/// Number of present intervals collected before trying to detect vsync
+const REFRESH_WINDOW: usize = 30;
+/// Fastest display we try to lock to (360Hz)
+const MIN_REFRESH_INTERVAL: f64 = 1.0 / 360.0;
+/// Slowest display we try to lock to (~23Hz)
+const MAX_REFRESH_INTERVAL: f64 = 1.0 / 23.0;
+/// How far (in refreshes) an interval may be from a whole number of refreshes
+const REFRESH_TOLERANCE: f64 = 0.1;
+/// How close target/refresh must be to a whole number to lock to vsync
+const LOCK_TOLERANCE: f64 = 0.05;
+
+/// Vsync-aware frame rate limiter
+pub struct FramePacer {
+    /// Active limit
+    limit: FpsLimit,
+    /// Recent present-to-present intervals (seconds) used for vsync detection
+    samples: Vec<f64>,
+    /// Measured display refresh interval (None = vsync not detected)
+    refresh: Option<f64>,
+    /// Time the previous frame was presented
+    last_present: Option<f64>,
+    /// Next deadline in timer mode
+    next_deadline: f64,
+    /// Refreshes per frame this frame was paced for (0 = timer/unlocked)
+    aimed_vsyncs: u32,
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FramePacer {
+    pub fn new() -> Self {
+        Self {
+            limit: FpsLimit::default(),
+            samples: Vec::with_capacity(REFRESH_WINDOW),
+            refresh: None,
+            last_present: None,
+            next_deadline: 0.0,
+            aimed_vsyncs: 0,
+        }
+    }
+
+    pub fn limit(&self) -> FpsLimit {
+        self.limit
+    }
+
+    /// Change the limit (restarts timer scheduling)
+    pub fn set_limit(&mut self, limit: FpsLimit) {
+        if self.limit != limit {
+            self.limit = limit;
+            self.next_deadline = 0.0;
+        }
+    }
+
+    /// Measured display refresh interval in seconds, if vsync was detected
+    pub fn refresh_interval(&self) -> Option<f64> {
+        self.refresh
+    }
+
+    /// Refreshes each frame is held for when locked to vsync
+    pub fn vsyncs_per_frame(&self) -> Option<u32> {
+        (self.aimed_vsyncs > 0).then_some(self.aimed_vsyncs)
+    }
+
+    /// Block until it's time to present this frame. Call right before `next_frame()`.
+    pub fn wait(&mut self) {
+        if let Some(deadline) = self.plan(get_time()) {
+            wait_until(deadline);
+        }
+    }
+
+    /// Record that a frame was presented. Call right after `next_frame()` returns.
+    pub fn frame_presented(&mut self, now: f64) {
+        let Some(last) = self.last_present.replace(now) else {
+            return;
+        };
+        let interval = now - last;
+
+        if let (true, Some(refresh)) = (self.aimed_vsyncs > 0, self.refresh) {
+            // The swap should have blocked until the n-th vsync. Returning
+            // early means vsync is off (or the estimate is stale): re-measure.
+            if interval < (self.aimed_vsyncs as f64 - 0.25) * refresh {
+                self.refresh = None;
+                self.samples.clear();
+            }
+            return;
+        }
+
+        self.samples.push(interval);
+        if self.samples.len() >= REFRESH_WINDOW {
+            self.refresh = detect_refresh(&self.samples);
+            self.samples.clear();
+        }
+    }
+
+    /// Work out when this frame should be presented (None = right away)
+    fn plan(&mut self, now: f64) -> Option<f64> {
+        self.aimed_vsyncs = 0;
+        let target = self.limit.frame_time()?;
+
+        if let (Some(refresh), Some(last)) = (self.refresh, self.last_present) {
+            let ratio = target / refresh;
+            let n = ratio.round();
+            if n >= 1.0 && (ratio - n).abs() < LOCK_TOLERANCE {
+                self.aimed_vsyncs = n as u32;
+                // Wake half a refresh early; the swap blocks until the n-th vsync
+                let deadline = last + (n - 0.5) * refresh;
+                return (deadline > now).then_some(deadline);
+            }
+        }
+
+        // Timer mode: absolute deadlines, resynced if we fell more than a frame behind
+        if self.next_deadline < now - target {
+            self.next_deadline = now;
+        }
+        let deadline = self.next_deadline;
+        self.next_deadline += target;
+        (deadline > now).then_some(deadline)
+    }
+}
+
+/// Detect a display refresh interval from present-to-present intervals.
+///
+/// With vsync on every interval is a whole number of refreshes; the shortest
+/// cluster gives the refresh interval. Returns None when the intervals don't
+/// line up (vsync off, or too noisy to tell).
+fn detect_refresh(samples: &[f64]) -> Option<f64> {
+    let min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+    if !(MIN_REFRESH_INTERVAL..=MAX_REFRESH_INTERVAL).contains(&min) {
+        return None;
+    }
+
+    // Average the shortest cluster to smooth out timer jitter
+    let cluster: Vec<f64> = samples.iter().copied().filter(|&s| s < min * (1.0 + REFRESH_TOLERANCE)).collect();
+    let refresh = cluster.iter().sum::<f64>() / cluster.len() as f64;
+
+    let aligned = samples.iter().filter(|&&s| {
+        let ratio = s / refresh;
+        (ratio - ratio.round()).abs() < REFRESH_TOLERANCE
+    }).count();
+
+    // Allow a few stragglers (hitches, window drags)
+    (aligned * 5 >= samples.len() * 4).then_some(refresh)
+}
+
+/// Wait until `deadline` (macroquad time)
+fn wait_until(deadline: f64) {
+    // Native: use sleep for bulk, then spin-wait for precision
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let spin_margin = 0.002; // 2ms
+        while get_time() + spin_margin < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        while get_time() < deadline {
+            std::hint::spin_loop();
+        }
+    }
+    // WASM: just spin-wait (no thread::sleep available)
+    #[cfg(target_arch = "wasm32")]
+    {
+        while get_time() < deadline {
+            // Busy wait - browser will handle frame pacing
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HZ60: f64 = 1.0 / 60.0;
+
+    /// Feed a pacer `count` presents spaced `interval` apart, starting at `start`
+    fn present_every(pacer: &mut FramePacer, start: f64, interval: f64, count: usize) -> f64 {
+        let mut t = start;
+        for _ in 0..count {
+            pacer.frame_presented(t);
+            t += interval;
+        }
+        t - interval
+    }
+
+    #[test]
+    fn test_fps_limit_cycle() {
+        let mut limit = FpsLimit::Unlocked;
+        for _ in 0..FpsLimit::ALL.len() {
+            limit = limit.next();
+        }
+        assert_eq!(limit, FpsLimit::Unlocked);
+        assert_eq!(FpsLimit::Fps24.prev(), FpsLimit::Unlocked);
+        assert_eq!(FpsLimit::Fps30.next(), FpsLimit::Fps60);
+    }
+
+    #[test]
+    fn test_detect_refresh() {
+        // 60Hz with jitter and a few dropped frames
+        let samples: Vec<f64> = (0..30)
+            .map(|i| if i % 7 == 0 { 2.0 * HZ60 } else { HZ60 + (i % 3) as f64 * 0.0002 })
+            .collect();
+        let refresh = detect_refresh(&samples).unwrap();
+        assert!((refresh - HZ60).abs() < 0.0005);
+
+        // No vsync: intervals follow the workload
+        let samples: Vec<f64> = (0..30).map(|i| 0.004 + (i % 5) as f64 * 0.0013).collect();
+        assert_eq!(detect_refresh(&samples), None);
+    }
+
+    #[test]
+    fn test_vsync_lock_at_30fps() {
+        let mut pacer = FramePacer::new();
+        pacer.set_limit(FpsLimit::Unlocked);
+        let last = present_every(&mut pacer, 1.0, HZ60, REFRESH_WINDOW + 1);
+        assert!(pacer.refresh_interval().is_some());
+
+        pacer.set_limit(FpsLimit::Fps30);
+        let deadline = pacer.plan(last + 0.003).unwrap();
+        assert_eq!(pacer.vsyncs_per_frame(), Some(2));
+        // Wakes between the first and second vsync, never past the second
+        assert!(deadline > last + HZ60 && deadline < last + 2.0 * HZ60);
+
+        // 24fps doesn't divide 60Hz: falls back to timer pacing
+        pacer.set_limit(FpsLimit::Fps24);
+        pacer.plan(last + 0.003);
+        assert_eq!(pacer.vsyncs_per_frame(), None);
+    }
+
+    #[test]
+    fn test_vsync_lost() {
+        let mut pacer = FramePacer::new();
+        pacer.set_limit(FpsLimit::Unlocked);
+        let last = present_every(&mut pacer, 1.0, HZ60, REFRESH_WINDOW + 1);
+        pacer.set_limit(FpsLimit::Fps30);
+        let deadline = pacer.plan(last).unwrap();
+
+        // Swap returned immediately instead of blocking until the vsync
+        pacer.frame_presented(deadline + 0.0001);
+        assert_eq!(pacer.refresh_interval(), None);
+    }
+
+    #[test]
+    fn test_timer_deadlines_do_not_drift() {
+        let mut pacer = FramePacer::new();
+        pacer.set_limit(FpsLimit::Fps30);
+        let start = 10.0;
+        assert_eq!(pacer.plan(start), None);
+
+        // Varying work times still land on the fixed 30fps grid
+        let mut now = start;
+        for (i, work) in [0.005, 0.012, 0.020, 0.001].iter().enumerate() {
+            let deadline = pacer.plan(now + work).unwrap();
+            let expected = start + (i + 1) as f64 / 30.0;
+            assert!((deadline - expected).abs() < 1e-9);
+            now = deadline;
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Playtest Analytics
+//!
+//! Opt-in, local-only recording of Game tab sessions: where the player spends
+//! time, where they take damage (and from what) and where they die. Finished
+//! sessions are handed to the World Editor, which draws them as a heatmap over
+//! the 2D grid view to help find difficulty spikes.
+//!
+//! Nothing leaves the machine: data lives in memory until the designer
+//! explicitly exports it as a RON file.
+
+use std::collections::{BTreeMap, HashMap};
+use serde::{Deserialize, Serialize};
+use super::event::Events;
+use super::{Entity, World};
+use crate::rasterizer::Vec3;
+use crate::world::{Level, SECTOR_SIZE};
+
+/// Where exported sessions are written (native only)
+pub const ANALYTICS_DIR: &str = "assets/userdata/analytics";
+
+/// Sector address in a level: (room index, sector x, sector z)
+pub type SectorKey = (usize, usize, usize);
+
+/// A death or damage hit recorded during a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Incident {
+    /// Sector the player was in (None = outside every room)
+    pub sector: Option<SectorKey>,
+    /// World position
+    pub position: (f32, f32, f32),
+    /// Seconds since the session started
+    pub time: f32,
+    /// Damage amount (0 for deaths)
+    #[serde(default)]
+    pub amount: i32,
+    /// What caused it ("Grunt", "Projectile", "Environment", ...)
+    #[serde(default)]
+    pub source: String,
+}
+
+/// Everything recorded during one play session
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionStats {
+    /// Session length in seconds
+    pub duration: f32,
+    /// Seconds spent in each room
+    #[serde(default)]
+    pub room_time: BTreeMap<usize, f32>,
+    /// Seconds spent in each sector
+    #[serde(default)]
+    pub sector_time: BTreeMap<SectorKey, f32>,
+    #[serde(default)]
+    pub deaths: Vec<Incident>,
+    #[serde(default)]
+    pub damage: Vec<Incident>,
+}
+
+/// Records the current session while playing (only when enabled)
+#[derive(Debug, Clone, Default)]
+pub struct SessionRecorder {
+    enabled: bool,
+    current: Option<SessionStats>,
+    /// Ended sessions waiting to be picked up by the editor
+    finished: Vec<SessionStats>,
+    /// Room the player was last seen in (lookup hint)
+    last_room: Option<usize>,
+}
+
+impl SessionRecorder {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Opt in or out. Disabling drops the session in progress.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.current = None;
+        }
+    }
+
+    /// Record one frame. Call before the frame's events are cleared.
+    pub fn record(&mut self, level: &Level, world: &World, events: &Events, player: Option<(Entity, Vec3)>, delta: f32) {
+        if !self.enabled {
+            return;
+        }
+        let Some((player, pos)) = player else {
+            return;
+        };
+        let stats = self.current.get_or_insert_with(SessionStats::default);
+        stats.duration += delta;
+        let time = stats.duration;
+
+        let sector = locate_sector(level, pos, self.last_room);
+        if let Some(key) = sector {
+            self.last_room = Some(key.0);
+            *stats.room_time.entry(key.0).or_insert(0.0) += delta;
+            *stats.sector_time.entry(key).or_insert(0.0) += delta;
+        }
+
+        for hit in events.damage.iter().filter(|e| e.target == player) {
+            stats.damage.push(Incident {
+                sector: locate_sector(level, hit.position, self.last_room).or(sector),
+                position: (hit.position.x, hit.position.y, hit.position.z),
+                time,
+                amount: hit.amount,
+                source: source_name(world, hit.source),
+            });
+        }
+        for death in events.death.iter().filter(|e| e.entity == player) {
+            stats.deaths.push(Incident {
+                sector: locate_sector(level, death.position, self.last_room).or(sector),
+                position: (death.position.x, death.position.y, death.position.z),
+                time,
+                amount: 0,
+                source: source_name(world, death.killer),
+            });
+        }
+    }
+
+    /// End the session in progress (if anything was recorded)
+    pub fn end_session(&mut self) {
+        self.last_room = None;
+        if let Some(session) = self.current.take().filter(|s| s.duration > 0.0) {
+            self.finished.push(session);
+        }
+    }
+
+    /// Take the sessions ended since the last call
+    pub fn take_finished(&mut self) -> Vec<SessionStats> {
+        std::mem::take(&mut self.finished)
+    }
+}
+
+/// Which statistic the editor heatmap shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeatmapKind {
+    #[default]
+    Off,
+    /// Time spent per sector
+    Time,
+    /// Deaths per sector
+    Deaths,
+    /// Damage taken per sector
+    Damage,
+}
+
+impl HeatmapKind {
+    pub const ALL: [HeatmapKind; 4] = [HeatmapKind::Off, HeatmapKind::Time, HeatmapKind::Deaths, HeatmapKind::Damage];
+
+    /// Cycle to next value
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&k| k == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// Display name
+    pub fn label(&self) -> &'static str {
+        match self {
+            HeatmapKind::Off => "Off",
+            HeatmapKind::Time => "Time",
+            HeatmapKind::Deaths => "Deaths",
+            HeatmapKind::Damage => "Damage",
+        }
+    }
+}
+
+/// Finished sessions collected for the current level
+#[derive(Debug, Clone, Default)]
+pub struct PlaytestAnalytics {
+    /// Opt-in: record Game tab sessions
+    pub recording: bool,
+    /// Heatmap overlay shown in the 2D grid view
+    pub overlay: HeatmapKind,
+    pub sessions: Vec<SessionStats>,
+}
+
+impl PlaytestAnalytics {
+    pub fn add_session(&mut self, session: SessionStats) {
+        self.sessions.push(session);
+    }
+
+    pub fn clear(&mut self) {
+        self.sessions.clear();
+    }
+
+    /// Per-sector totals for a heatmap, summed over all sessions
+    pub fn heat(&self, kind: HeatmapKind) -> HashMap<SectorKey, f32> {
+        let mut heat: HashMap<SectorKey, f32> = HashMap::new();
+        for session in &self.sessions {
+            match kind {
+                HeatmapKind::Off => {}
+                HeatmapKind::Time => {
+                    for (key, t) in &session.sector_time {
+                        *heat.entry(*key).or_insert(0.0) += t;
+                    }
+                }
+                HeatmapKind::Deaths => {
+                    for key in session.deaths.iter().filter_map(|d| d.sector) {
+                        *heat.entry(key).or_insert(0.0) += 1.0;
+                    }
+                }
+                HeatmapKind::Damage => {
+                    for (key, amount) in session.damage.iter().filter_map(|d| Some((d.sector?, d.amount))) {
+                        *heat.entry(key).or_insert(0.0) += amount.max(0) as f32;
+                    }
+                }
+            }
+        }
+        heat
+    }
+
+    /// (seconds, deaths, damage taken) in a room over all sessions
+    pub fn room_summary(&self, room: usize) -> (f32, usize, i32) {
+        let in_room = |i: &&Incident| i.sector.map(|s| s.0) == Some(room);
+        self.sessions.iter().fold((0.0, 0, 0), |(t, d, dmg), s| (
+            t + s.room_time.get(&room).copied().unwrap_or(0.0),
+            d + s.deaths.iter().filter(in_room).count(),
+            dmg + s.damage.iter().filter(in_room).map(|h| h.amount).sum::<i32>(),
+        ))
+    }
+
+    /// Damage taken per source over all sessions, largest first
+    pub fn damage_by_source(&self) -> Vec<(String, i32)> {
+        let mut totals: BTreeMap<&str, i32> = BTreeMap::new();
+        for hit in self.sessions.iter().flat_map(|s| &s.damage) {
+            *totals.entry(hit.source.as_str()).or_insert(0) += hit.amount;
+        }
+        let mut totals: Vec<(String, i32)> = totals.into_iter().map(|(s, a)| (s.to_string(), a)).collect();
+        totals.sort_by(|a, b| b.1.cmp(&a.1));
+        totals
+    }
+
+    /// Serialize all sessions for export
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(&self.sessions, ron::ser::PrettyConfig::default())
+    }
+
+    /// Write all sessions to `ANALYTICS_DIR/<name>.ron`, returning the file path
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn export(&self, name: &str) -> Result<std::path::PathBuf, String> {
+        std::fs::create_dir_all(ANALYTICS_DIR).map_err(|e| e.to_string())?;
+        let path = std::path::PathBuf::from(ANALYTICS_DIR).join(format!("{}.ron", name));
+        let contents = self.to_ron().map_err(|e| e.to_string())?;
+        std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+        Ok(path)
+    }
+}
+
+/// Find the sector containing `pos`, checking `hint` first.
+/// Rooms are matched on the XZ footprint where a sector exists.
+fn locate_sector(level: &Level, pos: Vec3, hint: Option<usize>) -> Option<SectorKey> {
+    let in_room = |room_idx: usize| -> Option<SectorKey> {
+        let room = level.rooms.get(room_idx)?;
+        let lx = pos.x - room.position.x;
+        let lz = pos.z - room.position.z;
+        if lx < 0.0 || lz < 0.0 {
+            return None;
+        }
+        let (x, z) = ((lx / SECTOR_SIZE) as usize, (lz / SECTOR_SIZE) as usize);
+        room.get_sector(x, z).map(|_| (room_idx, x, z))
+    };
+    hint.and_then(in_room)
+        .or_else(|| level.find_room_at(pos).and_then(in_room))
+        .or_else(|| (0..level.rooms.len()).find_map(in_room))
+}
+
+/// Name what dealt the damage, from its components
+fn source_name(world: &World, source: Option<Entity>) -> String {
+    let Some(entity) = source else {
+        return "Environment".to_string();
+    };
+    if let Some(enemy) = world.enemies.get(entity) {
+        format!("{:?}", enemy.enemy_type)
+    } else if world.projectiles.contains(entity) {
+        "Projectile".to_string()
+    } else if world.players.contains(entity) {
+        "Player".to_string()
+    } else {
+        "Unknown".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::event::{DamageEvent, DeathEvent};
+    use crate::world::Room;
+
+    fn test_level() -> Level {
+        let mut level = Level::new();
+        let mut room = Room::new(0, Vec3::ZERO, 2, 2);
+        room.set_floor(0, 0, 0.0, crate::world::TextureRef::none());
+        room.set_floor(1, 0, 0.0, crate::world::TextureRef::none());
+        level.rooms.push(room);
+        level
+    }
+
+    #[test]
+    fn test_locate_sector() {
+        let level = test_level();
+        assert_eq!(locate_sector(&level, Vec3::new(1500.0, 0.0, 100.0), None), Some((0, 1, 0)));
+        // No sector at (0, 1)
+        assert_eq!(locate_sector(&level, Vec3::new(100.0, 0.0, 1500.0), None), None);
+        assert_eq!(locate_sector(&level, Vec3::new(-10.0, 0.0, 100.0), None), None);
+    }
+
+    #[test]
+    fn test_records_only_when_enabled() {
+        let level = test_level();
+        let mut world = World::new();
+        let mut events = Events::new();
+        let player = world.spawn();
+        let pos = Vec3::new(100.0, 0.0, 100.0);
+
+        let mut recorder = SessionRecorder::default();
+        recorder.record(&level, &world, &events, Some((player, pos)), 0.5);
+        recorder.end_session();
+        assert!(recorder.take_finished().is_empty());
+
+        recorder.set_enabled(true);
+        events.damage.send(DamageEvent { target: player, source: None, amount: 10, position: pos });
+        events.death.send(DeathEvent { entity: player, killer: None, position: pos });
+        recorder.record(&level, &world, &events, Some((player, pos)), 0.5);
+        recorder.record(&level, &world, &Events::new(), Some((player, Vec3::new(1500.0, 0.0, 100.0))), 0.25);
+
+        recorder.end_session();
+        let stats = recorder.take_finished().pop().unwrap();
+        assert_eq!(stats.duration, 0.75);
+        assert_eq!(stats.room_time.get(&0), Some(&0.75));
+        assert_eq!(stats.sector_time.get(&(0, 0, 0)), Some(&0.5));
+        assert_eq!(stats.damage[0].source, "Environment");
+        assert_eq!(stats.deaths[0].sector, Some((0, 0, 0)));
+    }
+
+    #[test]
+    fn test_heat_and_summary() {
+        let incident = |x, amount| Incident {
+            sector: Some((0, x, 0)),
+            position: (0.0, 0.0, 0.0),
+            time: 0.0,
+            amount,
+            source: "Grunt".to_string(),
+        };
+        let session = SessionStats {
+            duration: 3.0,
+            room_time: BTreeMap::from([(0, 3.0)]),
+            sector_time: BTreeMap::from([((0, 0, 0), 2.0), ((0, 1, 0), 1.0)]),
+            deaths: vec![incident(1, 0)],
+            damage: vec![incident(1, 5), incident(1, 7)],
+        };
+        let mut analytics = PlaytestAnalytics::default();
+        analytics.add_session(session.clone());
+        analytics.add_session(session);
+
+        assert_eq!(analytics.heat(HeatmapKind::Time).get(&(0, 0, 0)), Some(&4.0));
+        assert_eq!(analytics.heat(HeatmapKind::Deaths).get(&(0, 1, 0)), Some(&2.0));
+        assert_eq!(analytics.heat(HeatmapKind::Damage).get(&(0, 1, 0)), Some(&24.0));
+        assert_eq!(analytics.room_summary(0), (6.0, 2, 24));
+        assert_eq!(analytics.damage_by_source(), vec![("Grunt".to_string(), 24)]);
+
+        let text = analytics.to_ron().unwrap();
+        let sessions: Vec<SessionStats> = ron::from_str(&text).unwrap();
+        assert_eq!(sessions, analytics.sessions);
+    }
+}
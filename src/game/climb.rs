@@ -0,0 +1,306 @@
+//! Ladder Climbing
+//!
+//! The character controller's climbing state. A character grabs a ladder by
+//! walking into it from below, or by crouching on the ledge at its top; while
+//! it holds on, `character_controllers` moves it with `climb_step` instead of
+//! gravity and cylinder collision. Climbing past the top steps onto the ledge,
+//! climbing down to the floor lets go, and jumping lets go anywhere.
+//!
+//! Every transition is sent as a `ClimbEvent`: the hook the animation system
+//! will pick grab/dismount clips from (with `LadderGrip::progress` for rungs).
+
+use serde::{Serialize, Deserialize};
+use crate::rasterizer::Vec3;
+use crate::world::{Ladder, Level};
+use super::components::{CharacterController, character};
+use super::lock_on::wrap_angle;
+
+/// Climbing speed (units per second)
+pub const CLIMB_SPEED: f32 = 500.0;
+/// How far past its radius a character reaches to grab a ladder
+pub const GRAB_REACH: f32 = 96.0;
+/// Movement toward the ladder (cosine to its facing) needed to grab it
+pub const GRAB_ALIGNMENT: f32 = 0.7;
+/// Grabbing from the ledge starts this far below the top
+pub const TOP_GRAB_DROP: f32 = 256.0;
+/// How far past the ladder's wall a top dismount steps onto the ledge
+pub const DISMOUNT_STEP: f32 = 128.0;
+/// The camera swings at most this far (radians) either side of the climber's back
+pub const CAMERA_YAW_RANGE: f32 = 1.2;
+
+/// A ladder a character holds on to (world space)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LadderGrip {
+    pub min: Vec3,
+    pub max: Vec3,
+    /// Unit XZ vector toward the ladder's wall (the way the climber faces)
+    pub facing: Vec3,
+}
+
+impl LadderGrip {
+    pub fn new(ladder: &Ladder, room_position: Vec3) -> Self {
+        let (min, max) = ladder.world_bounds(room_position);
+        Self { min, max, facing: ladder.facing_vector() }
+    }
+
+    /// Yaw the climber faces (`CharacterController::facing` convention)
+    pub fn facing_yaw(&self) -> f32 {
+        self.facing.x.atan2(self.facing.z)
+    }
+
+    /// 0 with the feet at the bottom, 1 at the top (drives climb animations)
+    pub fn progress(&self, feet_y: f32) -> f32 {
+        ((feet_y - self.min.y) / (self.max.y - self.min.y).max(1.0)).clamp(0.0, 1.0)
+    }
+
+    /// Coordinate of the wall along the facing axis
+    fn wall(&self) -> f32 {
+        if self.facing.x > 0.5 {
+            self.max.x
+        } else if self.facing.x < -0.5 {
+            -self.min.x
+        } else if self.facing.z > 0.5 {
+            self.max.z
+        } else {
+            -self.min.z
+        }
+    }
+
+    /// Signed distance from `position` to the wall (positive = in front of it)
+    fn distance_to_wall(&self, position: Vec3) -> f32 {
+        self.wall() - position.dot(self.facing)
+    }
+
+    /// Is `position` within the ladder's width (across the facing axis)?
+    fn within_width(&self, position: Vec3, slack: f32) -> bool {
+        if self.facing.x.abs() > 0.5 {
+            position.z >= self.min.z - slack && position.z <= self.max.z + slack
+        } else {
+            position.x >= self.min.x - slack && position.x <= self.max.x + slack
+        }
+    }
+
+    /// Where a climber of `radius` hangs: against the rungs, as far along the
+    /// ladder's width as `position` is (kept inside it)
+    pub fn hold_position(&self, position: Vec3, radius: f32) -> Vec3 {
+        let across = |p: f32, lo: f32, hi: f32| if hi - lo > radius * 2.0 {
+            p.clamp(lo + radius, hi - radius)
+        } else {
+            (lo + hi) * 0.5
+        };
+        let back = self.distance_to_wall(position) - radius;
+        let mut hold = position + self.facing * back;
+        if self.facing.x.abs() > 0.5 {
+            hold.z = across(position.z, self.min.z, self.max.z);
+        } else {
+            hold.x = across(position.x, self.min.x, self.max.x);
+        }
+        hold.y = position.y.clamp(self.min.y, self.max.y);
+        hold
+    }
+}
+
+/// What a climber just did (for animations and sounds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClimbAction {
+    /// Grabbed a ladder from below (walking into it)
+    Grab,
+    /// Grabbed a ladder from the ledge at its top
+    GrabFromTop,
+    /// Climbed off the top onto the ledge
+    DismountTop,
+    /// Reached the floor at the bottom
+    DismountBottom,
+    /// Jumped off
+    LetGo,
+}
+
+/// The ladder a character at `position` (feet) can grab.
+///
+/// From below, the character must be in front of the ladder, below its top
+/// and moving toward it (`direction`, unit XZ). With `from_top` it must stand
+/// on the ledge just behind the ladder's top edge instead.
+pub fn find_ladder(level: &Level, position: Vec3, radius: f32, direction: Vec3, from_top: bool) -> Option<LadderGrip> {
+    let reach = radius + GRAB_REACH;
+    level.rooms.iter()
+        .flat_map(|room| room.ladders.iter().map(move |ladder| LadderGrip::new(ladder, room.position)))
+        .find(|grip| {
+            if !grip.within_width(position, 0.0) {
+                return false;
+            }
+            let distance = grip.distance_to_wall(position);
+            if from_top {
+                (position.y - grip.max.y).abs() <= character::STEP_HEIGHT * 0.5
+                    && distance <= 0.0 && distance >= -(reach + DISMOUNT_STEP)
+            } else {
+                let depth = grip.facing.dot(grip.max - grip.min).abs();
+                direction.dot(grip.facing) >= GRAB_ALIGNMENT
+                    && position.y >= grip.min.y - character::STEP_HEIGHT
+                    && position.y < grip.max.y - TOP_GRAB_DROP
+                    && distance >= 0.0 && distance <= depth + reach
+            }
+        })
+}
+
+/// Start climbing `grip`. Returns where the character hangs on.
+pub fn grab(controller: &mut CharacterController, grip: LadderGrip, position: Vec3, from_top: bool) -> Vec3 {
+    let mut hold = grip.hold_position(position, controller.radius);
+    if from_top {
+        hold.y = (grip.max.y - TOP_GRAB_DROP).max(grip.min.y);
+    }
+    controller.climbing = Some(grip);
+    controller.grounded = false;
+    controller.vertical_velocity = 0.0;
+    controller.facing = grip.facing_yaw();
+    hold
+}
+
+/// Let go of the ladder with a hop away from it. Returns the push-off velocity.
+pub fn let_go(controller: &mut CharacterController, hop_velocity: f32) -> Vec3 {
+    let push = controller.climbing.take().map_or(Vec3::ZERO, |grip| grip.facing * -(character::WALK_SPEED * 0.5));
+    controller.vertical_velocity = hop_velocity;
+    push
+}
+
+/// Move a climber along its ladder by `climb_velocity` (+ up, - down).
+/// Returns the new position and the dismount that happened, if any.
+pub fn climb_step(
+    level: &Level,
+    position: Vec3,
+    climb_velocity: f32,
+    controller: &mut CharacterController,
+    delta: f32,
+) -> (Vec3, Option<ClimbAction>) {
+    let Some(grip) = controller.climbing else {
+        return (position, None);
+    };
+    let mut pos = grip.hold_position(position, controller.radius);
+    pos.y = position.y + climb_velocity * delta;
+
+    // Top: step onto the ledge behind the wall, if there is room to stand
+    if climb_velocity > 0.0 && pos.y >= grip.max.y {
+        let mut exit = pos + grip.facing * (controller.radius * 2.0 + DISMOUNT_STEP);
+        let ledge = level.get_floor_info(Vec3::new(exit.x, grip.max.y, exit.z), Some(controller.current_room))
+            .filter(|info| (info.floor - grip.max.y).abs() <= controller.step_height
+                && info.ceiling - info.floor >= controller.height);
+        if let Some(info) = ledge {
+            exit.y = info.floor;
+            controller.climbing = None;
+            controller.grounded = true;
+            controller.current_room = info.room;
+            return (exit, Some(ClimbAction::DismountTop));
+        }
+        pos.y = grip.max.y;
+    }
+
+    // Bottom: the floor under the ladder (or its bottom edge)
+    let floor = level.get_floor_info(pos, Some(controller.current_room));
+    if let Some(info) = floor {
+        controller.current_room = info.room;
+    }
+    let bottom = floor.map_or(grip.min.y, |info| info.floor.max(grip.min.y));
+    if pos.y <= bottom {
+        pos.y = bottom;
+        if climb_velocity < 0.0 {
+            controller.climbing = None;
+            controller.grounded = true;
+            return (pos, Some(ClimbAction::DismountBottom));
+        }
+    }
+    (pos, None)
+}
+
+/// Keep the camera behind a climber: yaw within `CAMERA_YAW_RANGE` of the ladder's facing
+pub fn constrain_camera_yaw(yaw: f32, grip: &LadderGrip) -> f32 {
+    let facing = grip.facing_yaw();
+    facing + wrap_angle(yaw - facing).clamp(-CAMERA_YAW_RANGE, CAMERA_YAW_RANGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{Direction, Room, TextureRef, SECTOR_SIZE};
+
+    /// A pit sector next to a ledge two sectors up (east), with a ladder on
+    /// the ledge's wall
+    fn ladder_level() -> Level {
+        let mut level = Level::new();
+        let mut room = Room::new(0, Vec3::ZERO, 2, 1);
+        room.set_floor(0, 0, 0.0, TextureRef::none());
+        room.set_floor(1, 0, SECTOR_SIZE * 2.0, TextureRef::none());
+        room.set_ceiling(0, 0, SECTOR_SIZE * 4.0, TextureRef::none());
+        room.set_ceiling(1, 0, SECTOR_SIZE * 4.0, TextureRef::none());
+        room.ladders.push(Ladder::from_corners((SECTOR_SIZE * 0.75, SECTOR_SIZE * 0.25), (SECTOR_SIZE, SECTOR_SIZE * 0.75), Direction::East));
+        room.recalculate_bounds();
+        level.rooms.push(room);
+        level
+    }
+
+    #[test]
+    fn test_grab_from_below_needs_to_face_the_ladder() {
+        let level = ladder_level();
+        let radius = character::PLAYER_RADIUS;
+        let feet = Vec3::new(SECTOR_SIZE * 0.6, 0.0, SECTOR_SIZE * 0.5);
+        let east = Vec3::new(1.0, 0.0, 0.0);
+        assert!(find_ladder(&level, feet, radius, east * -1.0, false).is_none());
+        let grip = find_ladder(&level, feet, radius, east, false).unwrap();
+
+        let mut controller = CharacterController::player();
+        let hold = grab(&mut controller, grip, feet, false);
+        assert!(controller.climbing.is_some());
+        assert!((hold.x - (SECTOR_SIZE - radius)).abs() < 1e-3);
+        assert!((controller.facing - std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_climb_up_and_dismount_onto_ledge() {
+        let level = ladder_level();
+        let mut controller = CharacterController::player();
+        let grip = find_ladder(&level, Vec3::new(SECTOR_SIZE * 0.6, 0.0, SECTOR_SIZE * 0.5), controller.radius, Vec3::new(1.0, 0.0, 0.0), false).unwrap();
+        let mut pos = grab(&mut controller, grip, Vec3::new(SECTOR_SIZE * 0.6, 0.0, SECTOR_SIZE * 0.5), false);
+
+        let mut dismount = None;
+        for _ in 0..200 {
+            let (next, action) = climb_step(&level, pos, CLIMB_SPEED, &mut controller, 0.05);
+            pos = next;
+            if action.is_some() {
+                dismount = action;
+                break;
+            }
+        }
+        assert_eq!(dismount, Some(ClimbAction::DismountTop));
+        assert!(controller.climbing.is_none() && controller.grounded);
+        assert_eq!(pos.y, SECTOR_SIZE * 2.0);
+        assert!(pos.x > SECTOR_SIZE);
+
+        // Crouching at the top grabs it again, a little below the edge
+        let grip = find_ladder(&level, pos, controller.radius, Vec3::ZERO, true).unwrap();
+        let hold = grab(&mut controller, grip, pos, true);
+        assert_eq!(hold.y, SECTOR_SIZE * 2.0 - TOP_GRAB_DROP);
+    }
+
+    #[test]
+    fn test_climb_down_lets_go_at_the_floor() {
+        let level = ladder_level();
+        let mut controller = CharacterController::player();
+        let grip = LadderGrip::new(&level.rooms[0].ladders[0], Vec3::ZERO);
+        let mut pos = grab(&mut controller, grip, Vec3::new(SECTOR_SIZE * 0.6, SECTOR_SIZE, SECTOR_SIZE * 0.5), false);
+        assert_eq!(grip.progress(pos.y), 0.5);
+
+        let (next, action) = climb_step(&level, pos, -CLIMB_SPEED, &mut controller, 1.5);
+        pos = next;
+        assert_eq!(action, None);
+        let (next, action) = climb_step(&level, pos, -CLIMB_SPEED, &mut controller, 1.5);
+        assert_eq!(action, Some(ClimbAction::DismountBottom));
+        assert_eq!(next.y, 0.0);
+        assert!(controller.grounded);
+    }
+
+    #[test]
+    fn test_camera_yaw_stays_behind_climber() {
+        let grip = LadderGrip { min: Vec3::ZERO, max: Vec3::new(256.0, 2048.0, 256.0), facing: Vec3::new(0.0, 0.0, 1.0) };
+        assert_eq!(constrain_camera_yaw(0.5, &grip), 0.5);
+        assert_eq!(constrain_camera_yaw(3.0, &grip), CAMERA_YAW_RANGE);
+        assert_eq!(constrain_camera_yaw(-2.0, &grip), -CAMERA_YAW_RANGE);
+    }
+}
@@ -191,3 +191,30 @@ pub fn move_and_slide(
 
     result.position
 }
+
+/// Keep a character cylinder out of solid boxes (closed doors)
+///
+/// `boxes` are world-space (min, max). A blocked move slides along one axis
+/// when it can; a character already overlapping a box (a door closed on it)
+/// moves freely so it can step out.
+pub fn block_by_boxes(from: Vec3, to: Vec3, radius: f32, height: f32, boxes: &[(Vec3, Vec3)]) -> Vec3 {
+    let hits = |p: Vec3| {
+        boxes.iter().any(|(min, max)| {
+            p.x + radius > min.x && p.x - radius < max.x
+                && p.z + radius > min.z && p.z - radius < max.z
+                && p.y + height > min.y && p.y < max.y
+        })
+    };
+    if !hits(to) || hits(from) {
+        return to;
+    }
+    let x_only = Vec3::new(to.x, to.y, from.z);
+    if !hits(x_only) {
+        return x_only;
+    }
+    let z_only = Vec3::new(from.x, to.y, to.z);
+    if !hits(z_only) {
+        return z_only;
+    }
+    Vec3::new(from.x, to.y, from.z)
+}
@@ -0,0 +1,527 @@
+//! Melee Combat
+//!
+//! Souls-like fighting on top of the `Combat`, `Poise` and `Stamina`
+//! components. Attacks spend stamina and put a hitbox out in front of the
+//! attacker during their active frames (`AttackDef`), hitting each target
+//! once per swing. Hits take health and poise; breaking poise staggers,
+//! which interrupts whatever the target was doing. A raised guard facing
+//! the attacker absorbs most of a hit: the rest gets through as chip damage
+//! and the blocked part costs stamina, and a guard without the stamina to
+//! take the hit breaks into a stagger. Dodge rolls spend stamina and let
+//! hits pass through for their first part. Armor takes its `defense` off
+//! every hit and its weight sets the roll tier: light loads roll fast and
+//! cheap, heavy ones slow and expensive.
+//!
+//! Enemies with a `Combat` component swing at the player once in reach.
+
+use crate::asset::AssetComponent;
+use crate::rasterizer::Vec3;
+use super::components::{CollisionShape, Combat, Enemy, Health, Hurtbox, Player, Poise, Swing};
+use super::entity::Entity;
+use super::event::{DamageEvent, DeathEvent, Events};
+use super::schedule::SystemContext;
+use super::transform::Transform;
+use super::world::World;
+
+/// Seconds a dodge roll lasts
+pub const DODGE_TIME: f32 = 0.5;
+/// Seconds at the start of a roll that hits pass through
+pub const DODGE_IFRAMES: f32 = 0.3;
+/// Roll speed (world units per second)
+pub const DODGE_SPEED: f32 = 4000.0;
+/// Stamina spent per roll
+pub const DODGE_COST: f32 = 20.0;
+/// Longest press of Dodge that rolls instead of sprinting (seconds)
+pub const DODGE_TAP_TIME: f32 = 0.25;
+/// How long a broken poise or guard staggers for (seconds)
+pub const STAGGER_TIME: f32 = 1.2;
+/// Pause after an enemy's swing before its next one (seconds)
+pub const ENEMY_ATTACK_COOLDOWN: f32 = 1.0;
+/// Height difference a hitbox still connects across (world units)
+pub const VERTICAL_REACH: f32 = 1536.0;
+/// Base damage without a weapon
+pub const UNARMED_DAMAGE: i32 = 10;
+/// Player poise
+pub const PLAYER_POISE: f32 = 40.0;
+/// Heaviest armor that still rolls light
+pub const LIGHT_LOAD: f32 = 10.0;
+/// Heaviest armor that still rolls at medium speed
+pub const MEDIUM_LOAD: f32 = 25.0;
+
+/// How fast and costly dodge rolls are, by armor weight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RollTier {
+    Light,
+    #[default]
+    Medium,
+    Heavy,
+}
+
+impl RollTier {
+    pub fn from_weight(weight: f32) -> Self {
+        if weight <= LIGHT_LOAD {
+            RollTier::Light
+        } else if weight <= MEDIUM_LOAD {
+            RollTier::Medium
+        } else {
+            RollTier::Heavy
+        }
+    }
+
+    /// Multiplier on DODGE_SPEED
+    pub fn speed_scale(&self) -> f32 {
+        match self {
+            RollTier::Light => 1.2,
+            RollTier::Medium => 1.0,
+            RollTier::Heavy => 0.6,
+        }
+    }
+
+    /// Multiplier on DODGE_COST
+    pub fn cost_scale(&self) -> f32 {
+        match self {
+            RollTier::Light => 0.8,
+            RollTier::Medium => 1.0,
+            RollTier::Heavy => 1.5,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RollTier::Light => "Light",
+            RollTier::Medium => "Medium",
+            RollTier::Heavy => "Heavy",
+        }
+    }
+}
+
+/// Moveset of a Weapon asset component (its speed plays both attacks faster)
+pub fn weapon(component: &AssetComponent) -> Option<Combat> {
+    let AssetComponent::Weapon { damage, light, heavy, guard_absorption, guard_stamina, speed, .. } = component else {
+        return None;
+    };
+    let speed = speed.max(0.1);
+    let (mut light, mut heavy) = (light.clone(), heavy.clone());
+    light.fps *= speed;
+    heavy.fps *= speed;
+    let mut combat = Combat::new(*damage, light, heavy);
+    combat.guard_absorption = guard_absorption.clamp(0.0, 1.0);
+    combat.guard_stamina = guard_stamina.max(0.0);
+    Some(combat)
+}
+
+/// Horizontal unit direction, or None for vertical or zero vectors
+fn flat(direction: Vec3) -> Option<Vec3> {
+    let flat = Vec3::new(direction.x, 0.0, direction.z);
+    if flat.len() < 1e-3 {
+        None
+    } else {
+        Some(flat.normalize())
+    }
+}
+
+/// Direction an entity faces: its controller's facing, else its transform's yaw
+pub fn facing(world: &World, entity: Entity) -> Vec3 {
+    if let Some(controller) = world.controllers.get(entity) {
+        return Vec3::new(controller.facing.sin(), 0.0, controller.facing.cos());
+    }
+    // Patrols turn with atan2(-dx, dz)
+    let yaw = world.transforms.get(entity).map_or(0.0, |t| t.rotation.y.to_radians());
+    Vec3::new(-yaw.sin(), 0.0, yaw.cos())
+}
+
+/// Spend stamina if the entity has a pool (entities without one act freely)
+fn spend(world: &mut World, entity: Entity, amount: f32) -> bool {
+    match world.stamina.get_mut(entity) {
+        Some(stamina) => stamina.drain(amount),
+        None => true,
+    }
+}
+
+/// Swing the light or heavy attack towards `direction` (zero: where the
+/// entity faces); false if it's busy, has no `Combat` or is out of stamina
+pub fn start_attack(world: &mut World, entity: Entity, heavy: bool, direction: Vec3) -> bool {
+    let Some(combat) = world.combat.get(entity) else {
+        return false;
+    };
+    if combat.busy() {
+        return false;
+    }
+    let attack = if heavy { combat.heavy.clone() } else { combat.light.clone() };
+    if !spend(world, entity, attack.stamina_cost) {
+        return false;
+    }
+    let direction = flat(direction).unwrap_or_else(|| facing(world, entity));
+    if let Some(combat) = world.combat.get_mut(entity) {
+        let damage = (combat.damage as f32 * attack.damage_scale).round() as i32;
+        combat.guarding = false;
+        combat.swing = Some(Swing { attack, damage, elapsed: 0.0, direction, hit: Vec::new() });
+    }
+    true
+}
+
+/// Roll towards `direction` (zero: a backstep); false if busy or out of stamina
+pub fn start_dodge(world: &mut World, entity: Entity, direction: Vec3) -> bool {
+    let Some(roll) = world.combat.get(entity).filter(|c| !c.busy()).map(|c| c.roll) else {
+        return false;
+    };
+    if !spend(world, entity, DODGE_COST * roll.cost_scale()) {
+        return false;
+    }
+    let direction = flat(direction).unwrap_or_else(|| facing(world, entity) * -1.0);
+    if let Some(combat) = world.combat.get_mut(entity) {
+        combat.guarding = false;
+        combat.dodge = Some((direction, DODGE_TIME));
+        combat.iframes = DODGE_IFRAMES;
+    }
+    true
+}
+
+/// Raise or lower the guard (it stays down while busy)
+pub fn set_guard(world: &mut World, entity: Entity, up: bool) {
+    if let Some(combat) = world.combat.get_mut(entity) {
+        combat.guarding = up && !combat.busy();
+    }
+}
+
+/// Horizontal velocity a fighting entity is held to: the roll while
+/// dodging, standing still while swinging or staggered
+pub fn movement_override(world: &World, entity: Entity) -> Option<Vec3> {
+    let combat = world.combat.get(entity)?;
+    if let Some((direction, _)) = combat.dodge {
+        return Some(direction * (DODGE_SPEED * combat.roll.speed_scale()));
+    }
+    if combat.swing.is_some() || combat.staggered() {
+        Some(Vec3::ZERO)
+    } else {
+        None
+    }
+}
+
+/// Advance swings, rolls and staggers, let enemies attack and resolve hits
+pub fn combat(ctx: &mut SystemContext<'_>) {
+    update(ctx.world, ctx.events, ctx.delta);
+}
+
+/// One frame of combat (the body of the `combat` system)
+pub fn update(world: &mut World, events: &mut Events, delta: f32) {
+    for (_, poise) in world.query::<&mut Poise>() {
+        poise.regenerate(delta);
+    }
+    for (_, combat) in world.query::<&mut Combat>() {
+        combat.iframes = (combat.iframes - delta).max(0.0);
+        combat.stagger = (combat.stagger - delta).max(0.0);
+        combat.cooldown = (combat.cooldown - delta).max(0.0);
+        combat.dodge = combat.dodge.and_then(|(direction, left)| (left > delta).then_some((direction, left - delta)));
+    }
+
+    enemy_attacks(world);
+
+    for strike in advance_swings(world, delta) {
+        resolve(world, events, &strike);
+    }
+}
+
+/// Enemies that are free to swing and have the player in reach attack
+fn enemy_attacks(world: &mut World) {
+    let target = world.query_ref::<(&Transform, &Player, &Health)>()
+        .find(|(_, (_, _, health))| !health.is_dead())
+        .map(|(_, (transform, _, _))| transform.position);
+    let Some(target) = target else {
+        return;
+    };
+    let attackers: Vec<(Entity, Vec3)> = world.query_ref::<(&Transform, &Combat, &Enemy)>()
+        .filter(|(_, (_, combat, _))| !combat.busy() && combat.cooldown <= 0.0)
+        .filter_map(|(entity, (transform, combat, _))| {
+            let offset = target - transform.position;
+            let reach = combat.light.reach + combat.light.radius;
+            let in_reach = offset.x * offset.x + offset.z * offset.z <= reach * reach && offset.y.abs() <= VERTICAL_REACH;
+            in_reach.then_some((entity, offset))
+        })
+        .collect();
+    for (entity, offset) in attackers {
+        if !start_attack(world, entity, false, offset) {
+            continue;
+        }
+        if let Some(transform) = world.transforms.get_mut(entity) {
+            transform.rotation.y = (-offset.x).atan2(offset.z).to_degrees();
+        }
+        if let Some(combat) = world.combat.get_mut(entity) {
+            combat.cooldown = combat.light.duration() + ENEMY_ATTACK_COOLDOWN;
+        }
+    }
+}
+
+/// A hitbox that's out this frame
+struct Strike {
+    attacker: Entity,
+    /// Attacker position (guards must face it)
+    origin: Vec3,
+    center: Vec3,
+    radius: f32,
+    damage: i32,
+    poise_damage: f32,
+    /// Already hit by this swing
+    hit: Vec<Entity>,
+    /// Player swings hit enemies, everyone else's hit the player
+    by_player: bool,
+}
+
+/// Move every swing on by `delta`, ending finished ones; the hitboxes out this frame
+fn advance_swings(world: &mut World, delta: f32) -> Vec<Strike> {
+    let mut strikes = Vec::new();
+    for (attacker, (transform, combat, player)) in world.query::<(&Transform, &mut Combat, Option<&Player>)>() {
+        let Some(swing) = &mut combat.swing else {
+            continue;
+        };
+        swing.elapsed += delta;
+        if swing.elapsed >= swing.attack.duration() {
+            combat.swing = None;
+            continue;
+        }
+        if swing.attack.is_active(swing.elapsed) {
+            strikes.push(Strike {
+                attacker,
+                origin: transform.position,
+                center: transform.position + swing.direction * swing.attack.reach,
+                radius: swing.attack.radius,
+                damage: swing.damage,
+                poise_damage: swing.attack.poise_damage,
+                hit: swing.hit.clone(),
+                by_player: player.is_some(),
+            });
+        }
+    }
+    strikes
+}
+
+fn hurtbox_radius(hurtbox: &Hurtbox) -> f32 {
+    match hurtbox.shape {
+        CollisionShape::Sphere { radius } | CollisionShape::Capsule { radius, .. } => radius,
+        CollisionShape::Box { half_extents } => half_extents.x.max(half_extents.z),
+    }
+}
+
+/// Hit everything on the other side inside a strike's hitbox
+fn resolve(world: &mut World, events: &mut Events, strike: &Strike) {
+    let targets: Vec<(Entity, Vec3, f32)> = world.query_ref::<(&Transform, &Hurtbox, &Health)>()
+        .filter(|(entity, (_, _, health))| {
+            *entity != strike.attacker
+                && !health.is_dead()
+                && !strike.hit.contains(entity)
+                && world.players.contains(*entity) != strike.by_player
+        })
+        .filter_map(|(entity, (transform, hurtbox, _))| {
+            let offset = transform.position - strike.center;
+            let reach = strike.radius + hurtbox_radius(hurtbox);
+            let inside = offset.x * offset.x + offset.z * offset.z <= reach * reach && offset.y.abs() <= VERTICAL_REACH;
+            inside.then_some((entity, transform.position, hurtbox.damage_multiplier))
+        })
+        .collect();
+
+    for (target, position, multiplier) in targets {
+        // Rolled through: the swing can still catch the target once the i-frames end
+        if world.combat.get(target).is_some_and(|c| c.invulnerable()) {
+            continue;
+        }
+        if let Some(swing) = world.combat.get_mut(strike.attacker).and_then(|c| c.swing.as_mut()) {
+            swing.hit.push(target);
+        }
+        hit(world, events, strike, target, position, multiplier);
+    }
+}
+
+/// Land a strike on `target`: less its armor's defense, blocked by a guard
+/// facing the attacker, else full damage plus poise damage
+fn hit(world: &mut World, events: &mut Events, strike: &Strike, target: Entity, position: Vec3, multiplier: f32) {
+    let defense = world.combat.get(target).map_or(0.0, |c| c.defense);
+    let damage = (strike.damage as f32 * multiplier * (1.0 - defense)).round() as i32;
+    let blocked = world.combat.get(target).is_some_and(|c| c.guarding)
+        && facing(world, target).dot(strike.origin - position) > 0.0;
+    let amount = if blocked {
+        block(world, target, damage)
+    } else {
+        if world.poise.get_mut(target).is_some_and(|p| p.hit(strike.poise_damage)) {
+            stagger(world, target);
+        }
+        damage
+    };
+    if amount <= 0 {
+        return;
+    }
+    let Some(health) = world.health.get_mut(target) else {
+        return;
+    };
+    if health.invincible_frames > 0 {
+        return;
+    }
+    let died = health.damage(amount);
+    events.damage.send(DamageEvent { target, source: Some(strike.attacker), amount, position });
+    if died {
+        events.death.send(DeathEvent { entity: target, killer: Some(strike.attacker), position });
+        // The player's death is the game's to handle; enemies drop out of the world
+        if !world.players.contains(target) {
+            world.despawn(target);
+        }
+    }
+}
+
+/// Take a hit on the guard: the blocked part costs stamina and the rest gets
+/// through as chip damage (returned). Too little stamina breaks the guard.
+fn block(world: &mut World, target: Entity, damage: i32) -> i32 {
+    let Some(combat) = world.combat.get(target) else {
+        return damage;
+    };
+    let chip = (damage as f32 * (1.0 - combat.guard_absorption)).round() as i32;
+    let cost = (damage - chip).max(0) as f32 * combat.guard_stamina;
+    let broken = match world.stamina.get_mut(target) {
+        Some(stamina) => {
+            let short = stamina.current < cost;
+            stamina.drain(cost);
+            short
+        }
+        None => false,
+    };
+    if broken {
+        stagger(world, target);
+    }
+    chip
+}
+
+/// Break off whatever the entity was doing and stagger it
+fn stagger(world: &mut World, entity: Entity) {
+    if let Some(combat) = world.combat.get_mut(entity) {
+        combat.stagger = STAGGER_TIME;
+        combat.swing = None;
+        combat.dodge = None;
+        combat.guarding = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::AttackDef;
+    use crate::world::PlayerSettings;
+    use crate::game::components::EnemyType;
+
+    const FRAME: f32 = 1.0 / 30.0;
+
+    /// Player at the origin facing +z, an enemy 600 units in front of it
+    /// that won't swing on its own
+    fn duel(enemy_damage: i32) -> (World, Events, Entity, Entity) {
+        let mut world = World::new();
+        let player = world.spawn_player(Vec3::ZERO, 100, &PlayerSettings::default());
+        world.combat.insert(player, Combat::new(10, AttackDef::light(), AttackDef::heavy()));
+        let enemy = world.spawn_enemy(Vec3::new(0.0, 0.0, 600.0), 100, EnemyType::Grunt);
+        let mut enemy_combat = Combat::new(enemy_damage, AttackDef::light(), AttackDef::heavy());
+        enemy_combat.cooldown = 100.0;
+        world.combat.insert(enemy, enemy_combat);
+        world.poise.insert(enemy, Poise::new(30.0));
+        (world, Events::new(), player, enemy)
+    }
+
+    fn run(world: &mut World, events: &mut Events, frames: usize) {
+        for _ in 0..frames {
+            update(world, events, FRAME);
+        }
+    }
+
+    #[test]
+    fn test_attack_hits_once_in_its_active_frames() {
+        let (mut world, mut events, player, enemy) = duel(10);
+        assert!(start_attack(&mut world, player, false, Vec3::ZERO));
+        assert_eq!(world.stamina.get(player).unwrap().current, 85.0);
+        // Can't start another swing mid-swing
+        assert!(!start_attack(&mut world, player, true, Vec3::ZERO));
+
+        run(&mut world, &mut events, 5);
+        assert_eq!(world.health.get(enemy).unwrap().current, 100, "still winding up");
+        run(&mut world, &mut events, 25);
+        assert_eq!(world.health.get(enemy).unwrap().current, 90);
+        assert_eq!(world.poise.get(enemy).unwrap().current, 15.0);
+        assert!(world.combat.get(player).unwrap().swing.is_none());
+    }
+
+    #[test]
+    fn test_poise_break_staggers() {
+        let (mut world, mut events, player, enemy) = duel(10);
+        world.poise.insert(enemy, Poise::new(10.0));
+        start_attack(&mut world, enemy, false, Vec3::new(0.0, 0.0, -1.0));
+        start_attack(&mut world, player, true, Vec3::ZERO);
+        // The heavy swing lands after the enemy's light one
+        run(&mut world, &mut events, 20);
+        assert_eq!(world.health.get(enemy).unwrap().current, 82);
+        let combat = world.combat.get(enemy).unwrap();
+        assert!(combat.staggered() && combat.swing.is_none());
+        assert!(movement_override(&world, enemy).is_some());
+        assert!(!start_attack(&mut world, enemy, false, Vec3::ZERO));
+    }
+
+    #[test]
+    fn test_guard_takes_chip_damage_and_breaks() {
+        let (mut world, mut events, player, enemy) = duel(20);
+        set_guard(&mut world, player, true);
+        start_attack(&mut world, enemy, false, Vec3::new(0.0, 0.0, -1.0));
+        run(&mut world, &mut events, 30);
+        // 70% absorbed: 6 chip damage, the blocked 14 off stamina
+        assert_eq!(world.health.get(player).unwrap().current, 94);
+        assert_eq!(world.stamina.get(player).unwrap().current, 86.0);
+        assert!(world.combat.get(player).unwrap().guarding);
+
+        world.stamina.get_mut(player).unwrap().current = 5.0;
+        start_attack(&mut world, enemy, false, Vec3::new(0.0, 0.0, -1.0));
+        run(&mut world, &mut events, 30);
+        assert_eq!(world.health.get(player).unwrap().current, 88);
+        let combat = world.combat.get(player).unwrap();
+        assert!(combat.staggered() && !combat.guarding);
+    }
+
+    #[test]
+    fn test_dodge_iframes_avoid_hits() {
+        let (mut world, mut events, player, enemy) = duel(10);
+        start_attack(&mut world, enemy, false, Vec3::new(0.0, 0.0, -1.0));
+        run(&mut world, &mut events, 6);
+        assert!(start_dodge(&mut world, player, Vec3::new(1.0, 0.0, 0.0)));
+        assert_eq!(world.stamina.get(player).unwrap().current, 80.0);
+        let roll = movement_override(&world, player).unwrap();
+        assert_eq!((roll.x, roll.z), (DODGE_SPEED, 0.0));
+
+        run(&mut world, &mut events, 24);
+        assert_eq!(world.health.get(player).unwrap().current, 100);
+        assert!(movement_override(&world, player).is_none());
+    }
+
+    #[test]
+    fn test_armor_defense_and_heavy_rolls() {
+        let (mut world, mut events, player, enemy) = duel(20);
+        let combat = world.combat.get_mut(player).unwrap();
+        combat.defense = 0.25;
+        combat.roll = RollTier::from_weight(40.0);
+        start_attack(&mut world, enemy, false, Vec3::new(0.0, 0.0, -1.0));
+        run(&mut world, &mut events, 30);
+        assert_eq!(world.health.get(player).unwrap().current, 85);
+
+        assert!(start_dodge(&mut world, player, Vec3::new(1.0, 0.0, 0.0)));
+        assert_eq!(world.stamina.get(player).unwrap().current, 70.0);
+        let roll = movement_override(&world, player).unwrap();
+        assert_eq!(roll.x, DODGE_SPEED * 0.6);
+        assert_eq!(RollTier::from_weight(LIGHT_LOAD), RollTier::Light);
+    }
+
+    #[test]
+    fn test_enemy_swings_at_player_in_reach_and_dies() {
+        let (mut world, mut events, player, enemy) = duel(10);
+        world.combat.get_mut(enemy).unwrap().cooldown = 0.0;
+        run(&mut world, &mut events, 1);
+        assert!(world.combat.get(enemy).unwrap().swing.is_some());
+        assert!(facing(&world, enemy).z < -0.99, "turned to face the player");
+
+        world.health.get_mut(enemy).unwrap().current = 5;
+        start_attack(&mut world, player, false, Vec3::ZERO);
+        run(&mut world, &mut events, 12);
+        assert!(events.death.iter().any(|d| d.entity == enemy && d.killer == Some(player)));
+        world.flush_despawns();
+        assert!(!world.is_alive(enemy));
+    }
+}
@@ -7,8 +7,36 @@
 //! Unlike Bevy's archetype system (which groups entities by component sets),
 //! we use simple sparse storage. For PS1-scale games (hundreds of entities),
 //! the simpler approach is fine and easier to reason about.
+//!
+//! `Component` ties a component type to its storage field in `World`, which
+//! is what lets queries (`world.query::<(&Transform, &mut Health)>()`) find
+//! storages by type without runtime registration.
 
 use super::entity::Entity;
+use super::world::World;
+
+/// A component type with a storage field in `World`.
+///
+/// Implemented for every storage by the `world_components!` list in world.rs.
+pub trait Component: Sized + 'static {
+    /// Pointer to this component's storage inside `world`, without creating a reference.
+    ///
+    /// # Safety
+    /// `world` must point to a live `World`.
+    unsafe fn storage_ptr(world: *mut World) -> *mut ComponentStorage<Self>;
+
+    /// This component's storage in `world`
+    fn storage(world: &World) -> &ComponentStorage<Self> {
+        // SAFETY: the pointer comes from a live shared reference and is only read
+        unsafe { &*Self::storage_ptr(world as *const World as *mut World) }
+    }
+
+    /// This component's storage in `world`, mutably
+    fn storage_mut(world: &mut World) -> &mut ComponentStorage<Self> {
+        // SAFETY: the pointer comes from a live unique reference
+        unsafe { &mut *Self::storage_ptr(world) }
+    }
+}
 
 /// Sparse storage for a single component type.
 ///
@@ -113,6 +141,17 @@ impl<T> ComponentStorage<T> {
     pub fn count(&self) -> usize {
         self.data.iter().filter(|opt| opt.is_some()).count()
     }
+
+    /// Raw slot array for queries: (first slot, slot count).
+    /// Queries index it directly so items for different entities never alias.
+    pub(crate) fn raw_slots(&self) -> (*const Option<T>, usize) {
+        (self.data.as_ptr(), self.data.len())
+    }
+
+    /// Mutable raw slot array for queries: (first slot, slot count).
+    pub(crate) fn raw_slots_mut(&mut self) -> (*mut Option<T>, usize) {
+        (self.data.as_mut_ptr(), self.data.len())
+    }
 }
 
 impl<T> Default for ComponentStorage<T> {
@@ -4,8 +4,10 @@
 //! Components are plain data structs - behavior lives in systems.
 
 use serde::{Serialize, Deserialize};
+use crate::asset::AttackDef;
 use crate::rasterizer::Vec3;
 use super::entity::Entity;
+use super::combat::RollTier;
 
 // =============================================================================
 // Physics / Movement
@@ -64,6 +66,12 @@ pub struct CharacterController {
     pub vertical_velocity: f32,
     /// Facing direction (yaw in radians)
     pub facing: f32,
+    /// Ladder being climbed (see `climb`); gravity is off while set
+    #[serde(default)]
+    pub climbing: Option<super::climb::LadderGrip>,
+    /// Water being swum in (see `swim`); gravity is off while set
+    #[serde(default)]
+    pub swimming: Option<super::swim::SwimState>,
 }
 
 impl CharacterController {
@@ -77,6 +85,8 @@ impl CharacterController {
             current_room: 0,
             vertical_velocity: 0.0,
             facing: 0.0,
+            climbing: None,
+            swimming: None,
         }
     }
 
@@ -90,6 +100,8 @@ impl CharacterController {
             current_room: 0,
             vertical_velocity: 0.0,
             facing: 0.0,
+            climbing: None,
+            swimming: None,
         }
     }
 }
@@ -141,6 +153,176 @@ impl Health {
     }
 }
 
+/// Stamina drained by sprinting, refilled after a short pause
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+    /// Seconds until regeneration resumes
+    pub regen_delay: f32,
+}
+
+impl Stamina {
+    /// Points regained per second
+    pub const REGEN_RATE: f32 = 30.0;
+    /// Pause after spending stamina before it refills (seconds)
+    pub const REGEN_DELAY: f32 = 0.8;
+    /// Points drained per second of sprinting
+    pub const SPRINT_COST: f32 = 20.0;
+
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen_delay: 0.0,
+        }
+    }
+
+    /// Spend up to `amount`; false if there was none left
+    pub fn drain(&mut self, amount: f32) -> bool {
+        if self.current <= 0.0 {
+            return false;
+        }
+        self.current = (self.current - amount).max(0.0);
+        self.regen_delay = Self::REGEN_DELAY;
+        true
+    }
+
+    pub fn regenerate(&mut self, delta: f32) {
+        if self.regen_delay > 0.0 {
+            self.regen_delay = (self.regen_delay - delta).max(0.0);
+        } else {
+            self.current = (self.current + Self::REGEN_RATE * delta).min(self.max);
+        }
+    }
+}
+
+/// Poise worn down by hits; breaking it staggers the entity
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Poise {
+    pub current: f32,
+    pub max: f32,
+    /// Seconds until poise starts refilling
+    pub regen_delay: f32,
+}
+
+impl Poise {
+    /// Points regained per second
+    pub const REGEN_RATE: f32 = 15.0;
+    /// Pause after a hit before poise refills (seconds)
+    pub const REGEN_DELAY: f32 = 2.0;
+
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen_delay: 0.0,
+        }
+    }
+
+    /// Take poise damage; true if this hit broke poise (which refills it).
+    /// Zero max poise never breaks.
+    pub fn hit(&mut self, amount: f32) -> bool {
+        if self.max <= 0.0 {
+            return false;
+        }
+        self.regen_delay = Self::REGEN_DELAY;
+        self.current -= amount;
+        if self.current > 0.0 {
+            return false;
+        }
+        self.current = self.max;
+        true
+    }
+
+    pub fn regenerate(&mut self, delta: f32) {
+        if self.regen_delay > 0.0 {
+            self.regen_delay = (self.regen_delay - delta).max(0.0);
+        } else {
+            self.current = (self.current + Self::REGEN_RATE * delta).min(self.max);
+        }
+    }
+}
+
+/// Melee moveset and what the entity is doing with it right now
+///
+/// Built from a Weapon asset component (player) or an Enemy one; driven by
+/// `combat::combat`. The player's equipped armor sets `defense` and `roll`.
+#[derive(Debug, Clone)]
+pub struct Combat {
+    /// Base damage, scaled per attack
+    pub damage: i32,
+    pub light: AttackDef,
+    pub heavy: AttackDef,
+    /// Fraction of a blocked hit's damage the guard absorbs
+    pub guard_absorption: f32,
+    /// Stamina lost per point of damage blocked
+    pub guard_stamina: f32,
+    /// Fraction of every hit's damage armor takes off (0 = none)
+    pub defense: f32,
+    /// Dodge roll speed and cost tier
+    pub roll: RollTier,
+    /// Attack in progress
+    pub swing: Option<Swing>,
+    /// Holding guard up
+    pub guarding: bool,
+    /// Dodge roll in progress: direction and seconds left
+    pub dodge: Option<(Vec3, f32)>,
+    /// Seconds of dodge invulnerability left
+    pub iframes: f32,
+    /// Seconds of stagger left (poise or guard broken: can't move or act)
+    pub stagger: f32,
+    /// Seconds before the AI swings again
+    pub cooldown: f32,
+}
+
+impl Combat {
+    pub fn new(damage: i32, light: AttackDef, heavy: AttackDef) -> Self {
+        Self {
+            damage,
+            light,
+            heavy,
+            guard_absorption: 0.7,
+            guard_stamina: 1.0,
+            defense: 0.0,
+            roll: RollTier::default(),
+            swing: None,
+            guarding: false,
+            dodge: None,
+            iframes: 0.0,
+            stagger: 0.0,
+            cooldown: 0.0,
+        }
+    }
+
+    /// Swinging, rolling or staggered: can't start anything else
+    pub fn busy(&self) -> bool {
+        self.swing.is_some() || self.dodge.is_some() || self.staggered()
+    }
+
+    pub fn staggered(&self) -> bool {
+        self.stagger > 0.0
+    }
+
+    pub fn invulnerable(&self) -> bool {
+        self.iframes > 0.0
+    }
+}
+
+/// One attack being swung
+#[derive(Debug, Clone)]
+pub struct Swing {
+    pub attack: AttackDef,
+    /// Damage dealt on hit (base damage times the attack's scale)
+    pub damage: i32,
+    /// Seconds since the swing started
+    pub elapsed: f32,
+    /// Horizontal direction the hitbox is thrown in
+    pub direction: Vec3,
+    /// Entities already hit, so each takes one hit per swing
+    pub hit: Vec<Entity>,
+}
+
 /// Hitbox - an area that deals damage (weapon, projectile)
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Hitbox {
@@ -283,6 +465,9 @@ pub struct Door {
     pub is_open: bool,
     /// If Some, requires this key type to open
     pub required_key: Option<KeyType>,
+    /// Animation progress (0 = closed, 1 = fully open); blocks movement until 1
+    #[serde(default)]
+    pub open_amount: f32,
 }
 
 impl Door {
@@ -290,6 +475,7 @@ impl Door {
         Self {
             is_open: false,
             required_key: Some(key),
+            open_amount: 0.0,
         }
     }
 
@@ -297,7 +483,132 @@ impl Door {
         Self {
             is_open: false,
             required_key: None,
+            open_amount: 0.0,
+        }
+    }
+}
+
+/// Moves an entity along world-space waypoints (enemy patrols, moving platforms)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathFollower {
+    /// Waypoints in world space, in travel order
+    pub points: Vec<Vec3>,
+    pub mode: crate::world::PathMode,
+    /// World units per second
+    pub speed: f32,
+    /// Distance covered since the first waypoint
+    #[serde(default)]
+    pub travelled: f32,
+    /// Turn to face the direction of travel (enemies; platforms keep their facing)
+    pub face_travel: bool,
+}
+
+/// A platform or elevator travelling between waypoints, stopping at each one.
+/// Characters standing on its deck are carried along (see `platform`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovingPlatform {
+    /// Waypoints in world space, in travel order
+    pub points: Vec<Vec3>,
+    pub mode: crate::world::PathMode,
+    /// World units per second
+    pub speed: f32,
+    /// Seconds to stand at each waypoint
+    pub wait: f32,
+    /// Deck box relative to the entity position (min, max)
+    pub deck: (Vec3, Vec3),
+    /// Only leaves a waypoint when called (by a trigger or lever)
+    pub on_call: bool,
+    /// Called and not yet arrived
+    #[serde(default)]
+    pub called: bool,
+    /// Distance covered since the first waypoint
+    #[serde(default)]
+    pub travelled: f32,
+    /// Seconds left standing at the current waypoint
+    #[serde(default)]
+    pub waiting: f32,
+    /// How far the platform moved this tick (what riders are carried by)
+    #[serde(default)]
+    pub moved: Vec3,
+}
+
+impl MovingPlatform {
+    pub fn new(points: Vec<Vec3>, mode: crate::world::PathMode, speed: f32, wait: f32, deck: (Vec3, Vec3), on_call: bool) -> Self {
+        Self { points, mode, speed, wait, deck, on_call, called: false, travelled: 0.0, waiting: 0.0, moved: Vec3::ZERO }
+    }
+
+    /// Travel toward the next waypoint, stopping there for `wait` seconds.
+    /// A platform on call stays put until `called` is set.
+    pub fn advance(&mut self, delta: f32) {
+        let stops = crate::world::path_stops(&self.points, self.mode);
+        let total = stops.last().copied().unwrap_or(0.0);
+        if total <= f32::EPSILON {
+            return;
+        }
+        if self.waiting > 0.0 {
+            self.waiting -= delta;
+            return;
+        }
+        if self.on_call && !self.called {
+            return;
         }
+        let next = stops.iter().copied().find(|&stop| stop > self.travelled + 0.01).unwrap_or(total);
+        let step = self.speed * delta;
+        if self.travelled + step >= next {
+            // Arrived: the end of the trip is the first waypoint again
+            self.travelled = if next >= total { 0.0 } else { next };
+            self.waiting = self.wait;
+            self.called = false;
+        } else {
+            self.travelled += step;
+        }
+    }
+
+    /// World-space deck box with the platform at `position`
+    pub fn deck_at(&self, position: Vec3) -> (Vec3, Vec3) {
+        (position + self.deck.0, position + self.deck.1)
+    }
+}
+
+/// Wanders between random spots around a home point, walking navgraph paths
+/// (enemies with a patrol radius and no path)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Patrol {
+    /// World-space point the spots are picked around
+    pub home: Vec3,
+    /// Spots are at most this far from home (world units)
+    pub radius: f32,
+    /// World units per second
+    pub speed: f32,
+    /// Height above the floor (the instance's height offset)
+    pub lift: f32,
+    /// Floor points left to walk through
+    #[serde(default)]
+    pub path: Vec<Vec3>,
+    /// Seconds to stand still before heading to the next spot
+    #[serde(default)]
+    pub wait: f32,
+    /// Random state for picking spots
+    pub seed: u32,
+}
+
+impl Patrol {
+    pub fn new(home: Vec3, radius: f32, speed: f32, lift: f32, seed: u32) -> Self {
+        Self { home, radius, speed, lift, path: Vec::new(), wait: 0.0, seed: seed.max(1) }
+    }
+
+    /// Next random spot within the radius of home (xorshift)
+    pub fn next_spot(&mut self) -> Vec3 {
+        let mut next = || {
+            self.seed ^= self.seed << 13;
+            self.seed ^= self.seed >> 17;
+            self.seed ^= self.seed << 5;
+            self.seed as f32 / u32::MAX as f32
+        };
+        let angle = next() * std::f32::consts::TAU;
+        // sqrt keeps spots evenly spread over the disc
+        let distance = next().sqrt() * self.radius;
+        self.home + Vec3::new(angle.cos() * distance, 0.0, angle.sin() * distance)
     }
 }
 
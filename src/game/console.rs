@@ -0,0 +1,517 @@
+//! Test Console
+//!
+//! A line-based command layer over the Test tab so an external runner can
+//! drive gameplay: load a level, hold buttons, step frames, fire script
+//! events, read game state back and capture frames. The native MCP server
+//! (`crate::mcp`) forwards its tool calls here; anything else that can send
+//! a `ConsoleRequest` down the channel gets the same commands.
+//!
+//! ```text
+//! load assets/userdata/levels/vault.ron
+//! press forward
+//! step 30
+//! release forward
+//! tap jump
+//! step 60
+//! get player.pos
+//! fire vault_open
+//! capture
+//! ```
+//!
+//! Commands run at the start of the Test tab's frame, before the simulation
+//! ticks. While a `step` is running the game advances at a fixed 1/60s per
+//! frame so runs are repeatable, and later commands wait until it finishes.
+//! `load` and `capture` need the app (storage, framebuffer), so the main loop
+//! executes those itself.
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use crate::input::{Action, InputState};
+use crate::rasterizer::{Framebuffer, Vec3};
+use super::runtime::GameToolState;
+use super::script::ScriptEvent;
+use super::replay::{Replay, ReplayMode};
+
+/// Simulation step used while a `step` command runs
+pub const CONSOLE_STEP_DELTA: f32 = 1.0 / 60.0;
+/// Longest single `step` (ten minutes of game time)
+pub const CONSOLE_MAX_STEP: u32 = 60 * 60 * 10;
+
+/// Help text for the `help` command
+pub const CONSOLE_HELP: &str = "\
+load <path>             load a level and start playing
+play | stop | reset     start, stop or restart the test session
+press <action>          hold an action (forward, back, left, right, jump, attack, ...)
+release <action|all>    let go of an action
+tap <action>            press an action for a single frame
+step [frames]           advance the game (fixed 1/60s per frame)
+get <key>               read state: playing, frame, player.pos, player.health, var.<name>, messages
+fire <event>            fire a script event (`on event <name>` handlers)
+teleport <x> <y> <z>    move the player
+record                  restart the session and record a replay
+save [name]             stop recording and save the replay (assets/userdata/replays)
+replay [path]           restart and play a replay file (or the last recording)
+capture                 PNG of the current frame";
+
+/// A parsed console command
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsoleCommand {
+    Help,
+    Load(String),
+    Play,
+    Stop,
+    Reset,
+    Press(Action),
+    Release(Action),
+    ReleaseAll,
+    Tap(Action),
+    Step(u32),
+    Get(String),
+    Fire(String),
+    Teleport(f32, f32, f32),
+    Record,
+    SaveReplay(String),
+    Replay(Option<String>),
+    Capture,
+}
+
+/// Result of a console command
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsoleReply {
+    /// Plain text or a JSON value
+    Text(String),
+    /// PNG-encoded frame
+    Png(Vec<u8>),
+    Error(String),
+}
+
+/// Action by console name: the script names plus the four move directions
+pub fn parse_action(name: &str) -> Option<Action> {
+    match name {
+        "forward" => Some(Action::MoveForward),
+        "back" => Some(Action::MoveBackward),
+        "left" => Some(Action::MoveLeft),
+        "right" => Some(Action::MoveRight),
+        "fly_up" => Some(Action::FlyUp),
+        "fly_down" => Some(Action::FlyDown),
+        _ => Action::from_name(name),
+    }
+}
+
+impl ConsoleCommand {
+    /// Parse one command line
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let Some(verb) = words.next() else {
+            return Err("empty command".to_string());
+        };
+        let rest: Vec<&str> = words.collect();
+        let one = |what: &str| match rest.as_slice() {
+            [arg] => Ok(arg.to_string()),
+            _ => Err(format!("usage: {} <{}>", verb, what)),
+        };
+        let action = |what: &str| {
+            let name = one(what)?;
+            parse_action(&name).ok_or_else(|| format!("unknown action '{}'", name))
+        };
+
+        match verb {
+            "help" => Ok(ConsoleCommand::Help),
+            "load" if !rest.is_empty() => Ok(ConsoleCommand::Load(rest.join(" "))),
+            "load" => Err("usage: load <path>".to_string()),
+            "play" => Ok(ConsoleCommand::Play),
+            "stop" => Ok(ConsoleCommand::Stop),
+            "reset" => Ok(ConsoleCommand::Reset),
+            "press" => action("action").map(ConsoleCommand::Press),
+            "release" if rest == ["all"] => Ok(ConsoleCommand::ReleaseAll),
+            "release" => action("action").map(ConsoleCommand::Release),
+            "tap" => action("action").map(ConsoleCommand::Tap),
+            "step" => {
+                let frames = match rest.as_slice() {
+                    [] => 1,
+                    [n] => n.parse::<u32>().map_err(|_| format!("bad frame count '{}'", n))?,
+                    _ => return Err("usage: step [frames]".to_string()),
+                };
+                Ok(ConsoleCommand::Step(frames.clamp(1, CONSOLE_MAX_STEP)))
+            }
+            "get" => one("key").map(ConsoleCommand::Get),
+            "fire" => one("event").map(ConsoleCommand::Fire),
+            "teleport" => match rest.as_slice() {
+                [x, y, z] => {
+                    let num = |s: &str| s.parse::<f32>().map_err(|_| format!("bad coordinate '{}'", s));
+                    Ok(ConsoleCommand::Teleport(num(x)?, num(y)?, num(z)?))
+                }
+                _ => Err("usage: teleport <x> <y> <z>".to_string()),
+            },
+            "record" => Ok(ConsoleCommand::Record),
+            "save" => match rest.as_slice() {
+                [] => Ok(ConsoleCommand::SaveReplay("replay".to_string())),
+                [name] => Ok(ConsoleCommand::SaveReplay(name.to_string())),
+                _ => Err("usage: save [name]".to_string()),
+            },
+            "replay" if rest.is_empty() => Ok(ConsoleCommand::Replay(None)),
+            "replay" => Ok(ConsoleCommand::Replay(Some(rest.join(" ")))),
+            "capture" => Ok(ConsoleCommand::Capture),
+            _ => Err(format!("unknown command '{}' (try help)", verb)),
+        }
+    }
+}
+
+/// A command waiting for the game loop, with where to send its reply
+pub struct ConsoleRequest {
+    pub command: ConsoleCommand,
+    reply: Sender<ConsoleReply>,
+}
+
+impl ConsoleRequest {
+    /// Request plus the receiver its reply will arrive on
+    pub fn new(command: ConsoleCommand) -> (Self, Receiver<ConsoleReply>) {
+        let (reply, rx) = channel();
+        (Self { command, reply }, rx)
+    }
+
+    /// Send the reply (the requester may have given up; that's fine)
+    pub fn respond(self, reply: ConsoleReply) {
+        let _ = self.reply.send(reply);
+    }
+}
+
+/// Format a float for a JSON reply (NaN/inf aren't valid JSON)
+fn json_num(v: f32) -> String {
+    if v.is_finite() { format!("{}", v) } else { "null".to_string() }
+}
+
+/// Escape a string for a JSON reply
+fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Encode the framebuffer as a PNG (for `capture`)
+pub fn encode_frame_png(fb: &Framebuffer) -> Result<Vec<u8>, String> {
+    use image::ImageEncoder;
+    let mut bytes = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut bytes)
+        .write_image(&fb.pixels, fb.width as u32, fb.height as u32, image::ExtendedColorType::Rgba8)
+        .map_err(|e| format!("PNG encode failed: {}", e))?;
+    Ok(bytes)
+}
+
+/// Read a piece of game state as JSON text
+pub fn query(game: &GameToolState, frame: u64, key: &str) -> Result<String, String> {
+    let player = game.player_entity;
+    match key {
+        "playing" => Ok(game.playing.to_string()),
+        "frame" => Ok(frame.to_string()),
+        "player.pos" => Ok(match game.get_player_position() {
+            Some(p) => format!("{{\"x\":{},\"y\":{},\"z\":{}}}", json_num(p.x), json_num(p.y), json_num(p.z)),
+            None => "null".to_string(),
+        }),
+        "player.health" => Ok(match player.and_then(|p| game.world.health.get(p)) {
+            Some(h) => format!("{{\"current\":{},\"max\":{}}}", h.current, h.max),
+            None => "null".to_string(),
+        }),
+        "messages" => {
+            let items: Vec<String> = game.logic.messages().map(json_str).collect();
+            Ok(format!("[{}]", items.join(",")))
+        }
+        "replay" => {
+            let mode = match game.replay.mode() {
+                ReplayMode::Off => "off",
+                ReplayMode::Recording => "recording",
+                ReplayMode::Playing => "playing",
+            };
+            let (frame, frames) = game.replay.progress();
+            let desync = game.replay.desync.map_or("null".to_string(), |f| f.to_string());
+            Ok(format!("{{\"mode\":\"{}\",\"frame\":{},\"frames\":{},\"desync\":{}}}", mode, frame, frames, desync))
+        }
+        _ => match key.strip_prefix("var.") {
+            Some(name) if !name.is_empty() => Ok(json_num(game.logic.scripts.var(name))),
+            _ => Err(format!("unknown key '{}'", key)),
+        },
+    }
+}
+
+/// Stop recording and write the replay, replying with its path
+fn save_replay(game: &mut GameToolState, name: &str) -> ConsoleReply {
+    if !game.replay.is_recording() {
+        return ConsoleReply::Error("not recording (record first)".to_string());
+    }
+    game.replay.stop();
+    match write_replay(game.replay.replay(), name) {
+        Ok(path) => ConsoleReply::Text(path),
+        Err(e) => ConsoleReply::Error(e),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_replay(replay: &Replay, name: &str) -> Result<String, String> {
+    replay.save(name).map(|path| path.display().to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_replay(_replay: &Replay, _name: &str) -> Result<String, String> {
+    Err("replays can't be saved on the web build".to_string())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load_replay(path: &str) -> Result<Replay, String> {
+    Replay::load(std::path::Path::new(path))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn load_replay(path: &str) -> Result<Replay, String> {
+    Err(format!("can't read {} on the web build", path))
+}
+
+/// Queue of console requests and the state of a running `step`
+pub struct Console {
+    incoming: Receiver<ConsoleRequest>,
+    sender: Sender<ConsoleRequest>,
+    /// Requests received but not yet run
+    queued: VecDeque<ConsoleRequest>,
+    /// Running `step`: frames left and the request to answer when done
+    stepping: Option<(u32, ConsoleRequest)>,
+    /// Tapped actions to release at the start of the next frame
+    taps: Vec<Action>,
+    /// Captures waiting for this frame's render
+    captures: Vec<ConsoleRequest>,
+    /// Frames simulated under console control
+    frame: u64,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Console {
+    pub fn new() -> Self {
+        let (sender, incoming) = channel();
+        Self {
+            incoming,
+            sender,
+            queued: VecDeque::new(),
+            stepping: None,
+            taps: Vec::new(),
+            captures: Vec::new(),
+            frame: 0,
+        }
+    }
+
+    /// Handle for another thread to send requests on
+    pub fn sender(&self) -> Sender<ConsoleRequest> {
+        self.sender.clone()
+    }
+
+    /// Start of a Test tab frame: release last frame's taps. Returns the fixed
+    /// delta to simulate with while a `step` is running.
+    pub fn begin_frame(&mut self, input: &mut InputState) -> Option<f32> {
+        for action in self.taps.drain(..) {
+            input.inject(action, false);
+        }
+        self.stepping.as_ref().map(|_| CONSOLE_STEP_DELTA)
+    }
+
+    /// Pick up newly sent requests. Returns true if any are waiting to run
+    /// (the main loop switches to the Test tab so they can).
+    pub fn poll(&mut self) -> bool {
+        self.queued.extend(self.incoming.try_iter());
+        !self.queued.is_empty()
+    }
+
+    /// Next request to run this frame (none while a `step` is in progress)
+    pub fn next_request(&mut self) -> Option<ConsoleRequest> {
+        if self.stepping.is_some() {
+            return None;
+        }
+        self.poll();
+        self.queued.pop_front()
+    }
+
+    /// Run a command that only needs the game and input. `load` and `capture`
+    /// are handed back for the main loop.
+    pub fn execute(
+        &mut self,
+        request: ConsoleRequest,
+        game: &mut GameToolState,
+        input: &mut InputState,
+    ) -> Option<ConsoleRequest> {
+        let reply = match &request.command {
+            ConsoleCommand::Load(_) => return Some(request),
+            ConsoleCommand::Capture => {
+                self.captures.push(request);
+                return None;
+            }
+            ConsoleCommand::Step(frames) => {
+                self.stepping = Some((*frames, request));
+                return None;
+            }
+            ConsoleCommand::Help => ConsoleReply::Text(CONSOLE_HELP.to_string()),
+            ConsoleCommand::Play => {
+                game.playing = true;
+                ConsoleReply::Text("ok".to_string())
+            }
+            ConsoleCommand::Stop => {
+                if game.playing {
+                    game.toggle_playing();
+                }
+                input.clear_injected();
+                ConsoleReply::Text("ok".to_string())
+            }
+            ConsoleCommand::Reset => {
+                game.reset();
+                game.playing = true;
+                input.clear_injected();
+                self.frame = 0;
+                ConsoleReply::Text("ok".to_string())
+            }
+            ConsoleCommand::Press(action) => {
+                input.inject(*action, true);
+                ConsoleReply::Text("ok".to_string())
+            }
+            ConsoleCommand::Release(action) => {
+                input.inject(*action, false);
+                ConsoleReply::Text("ok".to_string())
+            }
+            ConsoleCommand::ReleaseAll => {
+                input.clear_injected();
+                ConsoleReply::Text("ok".to_string())
+            }
+            ConsoleCommand::Tap(action) => {
+                input.inject(*action, true);
+                self.taps.push(*action);
+                ConsoleReply::Text("ok".to_string())
+            }
+            ConsoleCommand::Get(key) => match query(game, self.frame, key) {
+                Ok(value) => ConsoleReply::Text(value),
+                Err(e) => ConsoleReply::Error(e),
+            },
+            ConsoleCommand::Fire(event) => {
+                if game.logic.started() {
+                    game.logic.scripts.fire(&ScriptEvent::Custom(event.clone()));
+                    ConsoleReply::Text("ok".to_string())
+                } else {
+                    ConsoleReply::Error("level logic is not running (play first)".to_string())
+                }
+            }
+            ConsoleCommand::Teleport(x, y, z) => {
+                match game.player_entity.and_then(|p| game.world.transforms.get_mut(p)) {
+                    Some(t) => {
+                        t.position = Vec3::new(*x, *y, *z);
+                        ConsoleReply::Text("ok".to_string())
+                    }
+                    None => ConsoleReply::Error("no player (play first)".to_string()),
+                }
+            }
+            ConsoleCommand::Record => {
+                game.start_recording();
+                input.clear_injected();
+                self.frame = 0;
+                ConsoleReply::Text("ok".to_string())
+            }
+            ConsoleCommand::SaveReplay(name) => save_replay(game, name),
+            ConsoleCommand::Replay(path) => {
+                let replay = match path {
+                    Some(path) => load_replay(path),
+                    None if game.replay.replay().frames.is_empty() => Err("nothing recorded yet".to_string()),
+                    None => Ok(game.replay.replay().clone()),
+                };
+                match replay {
+                    Ok(replay) => {
+                        game.start_replay(replay);
+                        input.clear_injected();
+                        self.frame = 0;
+                        ConsoleReply::Text("ok".to_string())
+                    }
+                    Err(e) => ConsoleReply::Error(e),
+                }
+            }
+        };
+        request.respond(reply);
+        None
+    }
+
+    /// Reset console state for a freshly loaded level
+    pub fn level_loaded(&mut self, input: &mut InputState) {
+        input.clear_injected();
+        self.taps.clear();
+        self.frame = 0;
+    }
+
+    /// End of a Test tab frame, after rendering. `capture` encodes the frame
+    /// (only called when a capture is waiting).
+    pub fn end_frame(&mut self, game: &GameToolState, capture: impl FnOnce() -> Result<Vec<u8>, String>) {
+        if game.playing {
+            self.frame += 1;
+        }
+
+        if !self.captures.is_empty() {
+            let reply = match capture() {
+                Ok(png) => ConsoleReply::Png(png),
+                Err(e) => ConsoleReply::Error(e),
+            };
+            for request in self.captures.drain(..) {
+                request.respond(reply.clone());
+            }
+        }
+
+        if let Some((frames, _)) = self.stepping.as_mut() {
+            *frames = frames.saturating_sub(1);
+            if *frames == 0 {
+                let (_, request) = self.stepping.take().unwrap();
+                request.respond(ConsoleReply::Text(self.frame.to_string()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(ConsoleCommand::parse("press forward"), Ok(ConsoleCommand::Press(Action::MoveForward)));
+        assert_eq!(ConsoleCommand::parse("  tap   jump "), Ok(ConsoleCommand::Tap(Action::Jump)));
+        assert_eq!(ConsoleCommand::parse("release all"), Ok(ConsoleCommand::ReleaseAll));
+        assert_eq!(ConsoleCommand::parse("step"), Ok(ConsoleCommand::Step(1)));
+        assert_eq!(ConsoleCommand::parse("step 0"), Ok(ConsoleCommand::Step(1)));
+        assert_eq!(ConsoleCommand::parse("load levels/my level.ron"), Ok(ConsoleCommand::Load("levels/my level.ron".to_string())));
+        assert_eq!(ConsoleCommand::parse("teleport 1 2.5 -3"), Ok(ConsoleCommand::Teleport(1.0, 2.5, -3.0)));
+        assert_eq!(ConsoleCommand::parse("get var.gems"), Ok(ConsoleCommand::Get("var.gems".to_string())));
+        assert_eq!(ConsoleCommand::parse("save"), Ok(ConsoleCommand::SaveReplay("replay".to_string())));
+        assert_eq!(ConsoleCommand::parse("replay"), Ok(ConsoleCommand::Replay(None)));
+        assert_eq!(ConsoleCommand::parse("replay bug 12.ron"), Ok(ConsoleCommand::Replay(Some("bug 12.ron".to_string()))));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(ConsoleCommand::parse("").is_err());
+        assert!(ConsoleCommand::parse("press").is_err());
+        assert!(ConsoleCommand::parse("press moonwalk").is_err());
+        assert!(ConsoleCommand::parse("step lots").is_err());
+        assert!(ConsoleCommand::parse("teleport 1 2").is_err());
+        assert!(ConsoleCommand::parse("save a b").is_err());
+        assert!(ConsoleCommand::parse("dance").is_err());
+    }
+
+    #[test]
+    fn test_json_helpers() {
+        assert_eq!(json_str("say \"hi\"\n"), "\"say \\\"hi\\\"\\n\"");
+        assert_eq!(json_num(1.5), "1.5");
+        assert_eq!(json_num(f32::NAN), "null");
+    }
+}
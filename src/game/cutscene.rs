@@ -0,0 +1,321 @@
+//! Cutscene Playback
+//!
+//! Plays a level's `world::Cutscene`: the camera flies along the cutscene's
+//! spline while its timeline fires events. The timeline is one event per
+//! line, starting with the time in seconds:
+//!
+//! ```text
+//! # The gate opens, the guard walks out
+//! 0    music theme 2
+//! 0.5  subtitle "The gate grinds open..." 3
+//! 1    move guard 0 0 2048 2
+//! 3    animate guard salute
+//! ```
+//!
+//! Commands:
+//! - `move <name> <x> <y> <z> [<seconds>]` - slide an instance by an offset
+//!   (world units) over a time (default 1s); it stays there afterwards
+//! - `animate <name> <clip>` - ask an instance to play an animation clip
+//! - `subtitle "<text>" [<seconds>]` - caption at the bottom (default 3s)
+//! - `music <song> [<pattern>]` - play a tracker song from a position in its
+//!   arrangement (default 0, the start)
+//!
+//! While a cutscene plays the player stands still, the HUD is hidden and
+//! Interact skips it (if it's skippable). Skipping still applies its moves
+//! and music, so the level ends up the same either way. `level_logic` starts
+//! cutscenes and applies what the player hands back.
+
+use super::script::{tokenize, LineParser, Token};
+use crate::rasterizer::Vec3;
+use crate::world::Cutscene;
+
+/// Default `move` time (seconds)
+const DEFAULT_MOVE_TIME: f32 = 1.0;
+/// Default `subtitle` time (seconds)
+const DEFAULT_SUBTITLE_TIME: f32 = 3.0;
+
+/// Something a cutscene does at a point in its timeline
+#[derive(Debug, Clone, PartialEq)]
+pub enum CutsceneAction {
+    /// Slide instances with this name by `offset` (world units) over `duration` seconds
+    MoveEntity { name: String, offset: [f32; 3], duration: f32 },
+    /// Play an animation clip on instances with this name
+    PlayAnimation { name: String, clip: String },
+    /// Show a caption for `duration` seconds
+    Subtitle { text: String, duration: f32 },
+    /// Play a tracker song from a position in its arrangement
+    PlayMusic { song: String, pattern: usize },
+}
+
+impl CutsceneAction {
+    /// How long the action keeps the cutscene going
+    fn duration(&self) -> f32 {
+        match self {
+            CutsceneAction::MoveEntity { duration, .. } | CutsceneAction::Subtitle { duration, .. } => *duration,
+            CutsceneAction::PlayAnimation { .. } | CutsceneAction::PlayMusic { .. } => 0.0,
+        }
+    }
+
+    /// Whether the action still matters once the cutscene is skipped
+    fn lasting(&self) -> bool {
+        matches!(self, CutsceneAction::MoveEntity { .. } | CutsceneAction::PlayMusic { .. })
+    }
+}
+
+/// A timeline entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct CutsceneEvent {
+    /// Seconds from the start of the cutscene
+    pub time: f32,
+    pub action: CutsceneAction,
+}
+
+/// Parse a timeline into events, in time order (lines with the same time
+/// keep their order). Errors carry the 1-based line.
+pub fn parse_timeline(source: &str) -> Result<Vec<CutsceneEvent>, (usize, String)> {
+    let mut events = Vec::new();
+    for (i, text) in source.lines().enumerate() {
+        let tokens = tokenize(text).map_err(|e| (i + 1, e))?;
+        if tokens.is_empty() {
+            continue;
+        }
+        events.push(parse_event(&tokens).map_err(|e| (i + 1, e))?);
+    }
+    events.sort_by(|a, b| a.time.total_cmp(&b.time));
+    Ok(events)
+}
+
+fn parse_event(tokens: &[Token]) -> Result<CutsceneEvent, String> {
+    let mut p = LineParser::new(tokens);
+    let time = p.number("time in seconds")?;
+    let command = match p.advance() {
+        Some(Token::Word(w)) => w.as_str(),
+        _ => return Err("expected a command after the time".to_string()),
+    };
+    let action = match command {
+        "move" => {
+            let name = p.name("instance name")?;
+            let offset = [signed(&mut p, "x offset")?, signed(&mut p, "y offset")?, signed(&mut p, "z offset")?];
+            let duration = optional_seconds(&mut p, DEFAULT_MOVE_TIME)?;
+            CutsceneAction::MoveEntity { name, offset, duration }
+        }
+        "animate" => {
+            let name = p.name("instance name")?;
+            let clip = p.name("animation clip")?;
+            CutsceneAction::PlayAnimation { name, clip }
+        }
+        "subtitle" => {
+            let text = p.name("subtitle text")?;
+            let duration = optional_seconds(&mut p, DEFAULT_SUBTITLE_TIME)?;
+            CutsceneAction::Subtitle { text, duration }
+        }
+        "music" => {
+            let song = p.name("song name")?;
+            let pattern = match p.peek() {
+                Some(Token::Number(n, _)) if *n >= 0.0 && n.fract() == 0.0 => {
+                    p.advance();
+                    *n as usize
+                }
+                Some(_) => return Err("pattern must be a whole number".to_string()),
+                None => 0,
+            };
+            CutsceneAction::PlayMusic { song, pattern }
+        }
+        other => return Err(format!("unknown cutscene command '{}'", other)),
+    };
+    p.expect_end()?;
+    Ok(CutsceneEvent { time, action })
+}
+
+/// A number with an optional minus sign
+fn signed(p: &mut LineParser<'_>, what: &str) -> Result<f32, String> {
+    if p.eat_op("-") {
+        Ok(-p.number(what)?)
+    } else {
+        p.number(what)
+    }
+}
+
+/// Trailing duration, if given
+fn optional_seconds(p: &mut LineParser<'_>, default: f32) -> Result<f32, String> {
+    if p.peek().is_none() {
+        return Ok(default);
+    }
+    p.number("seconds")
+}
+
+/// Check a timeline for errors without playing it. Returns the number of events.
+pub fn validate_timeline(source: &str) -> Result<usize, (usize, String)> {
+    parse_timeline(source).map(|events| events.len())
+}
+
+/// A cutscene being played
+#[derive(Debug, Clone)]
+pub struct CutscenePlayer {
+    cutscene: Cutscene,
+    events: Vec<CutsceneEvent>,
+    /// Index of the next event to fire
+    next: usize,
+    time: f32,
+    duration: f32,
+    /// Captions on screen (text, seconds left)
+    subtitles: Vec<(String, f32)>,
+}
+
+impl CutscenePlayer {
+    /// Start a cutscene (fails if its timeline doesn't parse)
+    pub fn new(cutscene: &Cutscene) -> Result<Self, (usize, String)> {
+        let events = parse_timeline(&cutscene.timeline)?;
+        let duration = events.iter()
+            .map(|e| e.time + e.action.duration())
+            .fold(cutscene.camera_duration(), f32::max);
+        Ok(Self { cutscene: cutscene.clone(), events, next: 0, time: 0.0, duration, subtitles: Vec::new() })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.cutscene.name
+    }
+
+    /// Advance by `delta` seconds. Returns the actions that came due, except
+    /// subtitles, which the player shows itself.
+    pub fn update(&mut self, delta: f32) -> Vec<CutsceneAction> {
+        self.time += delta;
+        for subtitle in &mut self.subtitles {
+            subtitle.1 -= delta;
+        }
+        self.subtitles.retain(|s| s.1 > 0.0);
+
+        let mut due = Vec::new();
+        while let Some(event) = self.events.get(self.next).filter(|e| e.time <= self.time) {
+            self.next += 1;
+            match &event.action {
+                CutsceneAction::Subtitle { text, duration } => {
+                    // Time already past the event's start counts toward its duration
+                    let left = duration - (self.time - event.time);
+                    if left > 0.0 {
+                        self.subtitles.push((text.clone(), left));
+                    }
+                }
+                action => due.push(action.clone()),
+            }
+        }
+        due
+    }
+
+    /// Jump to the end. Returns the events that hadn't fired yet and still
+    /// matter afterwards (moves and music).
+    pub fn skip(&mut self) -> Vec<CutsceneAction> {
+        let remaining = self.events[self.next..].iter()
+            .filter(|e| e.action.lasting())
+            .map(|e| e.action.clone())
+            .collect();
+        self.next = self.events.len();
+        self.time = self.time.max(self.duration);
+        self.subtitles.clear();
+        remaining
+    }
+
+    /// Every event has fired and the camera reached its last key
+    pub fn finished(&self) -> bool {
+        self.next >= self.events.len() && self.time >= self.duration
+    }
+
+    /// Camera (position, look target) right now; None if the cutscene has no camera keys
+    pub fn camera(&self) -> Option<(Vec3, Vec3)> {
+        self.cutscene.camera_at(self.time)
+    }
+
+    /// Newest caption on screen
+    pub fn subtitle(&self) -> Option<&str> {
+        self.subtitles.last().map(|(text, _)| text.as_str())
+    }
+
+    pub fn letterbox(&self) -> bool {
+        self.cutscene.letterbox
+    }
+
+    pub fn skippable(&self) -> bool {
+        self.cutscene.skippable
+    }
+
+    /// Seconds played
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cutscene(timeline: &str) -> Cutscene {
+        let mut cutscene = Cutscene::new("intro");
+        cutscene.timeline = timeline.to_string();
+        cutscene
+    }
+
+    #[test]
+    fn test_parse_timeline() {
+        let events = parse_timeline(
+            "# comment\n\
+             2 animate \"old guard\" wave\n\
+             0 music theme 2\n\
+             0.5 subtitle \"Hello\"\n\
+             1 move guard -512 0 1024.5 2\n",
+        ).unwrap();
+        assert_eq!(events.iter().map(|e| e.time).collect::<Vec<_>>(), vec![0.0, 0.5, 1.0, 2.0]);
+        assert_eq!(events[0].action, CutsceneAction::PlayMusic { song: "theme".to_string(), pattern: 2 });
+        assert_eq!(events[1].action, CutsceneAction::Subtitle { text: "Hello".to_string(), duration: DEFAULT_SUBTITLE_TIME });
+        assert_eq!(events[2].action, CutsceneAction::MoveEntity {
+            name: "guard".to_string(),
+            offset: [-512.0, 0.0, 1024.5],
+            duration: 2.0,
+        });
+        assert_eq!(events[3].action, CutsceneAction::PlayAnimation { name: "old guard".to_string(), clip: "wave".to_string() });
+    }
+
+    #[test]
+    fn test_timeline_errors_report_line() {
+        assert_eq!(validate_timeline("0 music a\n\n1 dance").unwrap_err().0, 3);
+        assert!(validate_timeline("subtitle \"no time\"").is_err());
+        assert!(validate_timeline("0 move guard 1 2").is_err());
+        assert!(validate_timeline("0 music theme 1.5").is_err());
+        assert!(validate_timeline("0 subtitle \"hi\" 2 extra").is_err());
+        assert_eq!(validate_timeline(""), Ok(0));
+    }
+
+    #[test]
+    fn test_playback_fires_events_in_time() {
+        let mut c = cutscene("0 music theme\n1 subtitle \"Hi\" 2\n1.5 animate guard wave\n");
+        c.push_camera_key(Vec3::ZERO, Vec3::new(0.0, 0.0, 1.0));
+        c.push_camera_key(Vec3::new(1000.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut player = CutscenePlayer::new(&c).unwrap();
+
+        assert_eq!(player.update(0.1), vec![CutsceneAction::PlayMusic { song: "theme".to_string(), pattern: 0 }]);
+        assert!(player.update(1.0).is_empty());
+        assert_eq!(player.subtitle(), Some("Hi"));
+        assert_eq!(player.update(0.5).len(), 1);
+        // The camera flight (2s) and the subtitle (ends at 3s) keep it going
+        assert!(!player.finished());
+        player.update(1.0);
+        assert!(player.subtitle().is_some() && !player.finished());
+        player.update(0.5);
+        assert!(player.subtitle().is_none() && player.finished());
+        let (position, _) = player.camera().unwrap();
+        assert_eq!(position.x, 1000.0);
+    }
+
+    #[test]
+    fn test_skip_keeps_lasting_events() {
+        let c = cutscene("0 subtitle \"Hi\"\n1 move guard 0 0 1024\n2 animate guard wave\n3 music outro\n");
+        let mut player = CutscenePlayer::new(&c).unwrap();
+        player.update(0.1);
+        assert!(player.subtitle().is_some());
+        let remaining = player.skip();
+        assert_eq!(remaining.len(), 2);
+        assert!(matches!(remaining[0], CutsceneAction::MoveEntity { .. }));
+        assert!(matches!(remaining[1], CutsceneAction::PlayMusic { .. }));
+        assert!(player.finished() && player.subtitle().is_none());
+        assert!(player.camera().is_none());
+    }
+}
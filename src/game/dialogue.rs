@@ -0,0 +1,223 @@
+//! Dialogue Playback
+//!
+//! Plays a Dialogue component's tree (`asset::DialogueNode`) when the player
+//! talks to an NPC. Lines type out a few characters at a time; Interact first
+//! shows the whole line, then picks the highlighted choice (or moves on to
+//! the node's `next`). Node and choice effects set world flags in the level's
+//! `ScriptRuntime` as they happen, so scripts and later conversations see
+//! them. `level_logic` starts conversations and fires `on dialogue_end` when
+//! one ends.
+
+use super::script::ScriptRuntime;
+use crate::asset::{DialogueNode, FlagCondition, FlagEffect};
+
+/// Typewriter speed (characters per second)
+pub const TYPE_SPEED: f32 = 40.0;
+
+/// A conversation in progress
+#[derive(Debug, Clone)]
+pub struct DialoguePlayer {
+    /// Instance name of the speaker
+    name: String,
+    nodes: Vec<DialogueNode>,
+    /// Node being shown
+    node: usize,
+    /// Characters of the line typed so far
+    shown: f32,
+    /// The node's choices that are offered (indices into its `choices`)
+    choices: Vec<usize>,
+    /// Highlighted entry of `choices`
+    selected: usize,
+    finished: bool,
+}
+
+impl DialoguePlayer {
+    /// Start at the first node whose condition holds (None if none does)
+    pub fn new(name: &str, nodes: &[DialogueNode], scripts: &mut ScriptRuntime) -> Option<Self> {
+        let start = nodes.iter().position(|n| holds(&n.condition, scripts))?;
+        let mut player = Self {
+            name: name.to_string(),
+            nodes: nodes.to_vec(),
+            node: start,
+            shown: 0.0,
+            choices: Vec::new(),
+            selected: 0,
+            finished: false,
+        };
+        player.enter(start, scripts);
+        Some(player)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Type out more of the line
+    pub fn update(&mut self, delta: f32) {
+        self.shown = (self.shown + delta * TYPE_SPEED).min(self.line_len() as f32);
+    }
+
+    /// Interact: finish typing the line, or pick the highlighted choice (or
+    /// continue to the node's `next`). Leading nowhere ends the conversation.
+    pub fn advance(&mut self, scripts: &mut ScriptRuntime) {
+        if self.finished {
+            return;
+        }
+        if self.typing() {
+            self.shown = self.line_len() as f32;
+            return;
+        }
+        let next = match self.choices.get(self.selected) {
+            Some(&i) => {
+                let choice = &self.nodes[self.node].choices[i];
+                apply(&choice.effects, scripts);
+                choice.next.clone()
+            }
+            None => self.nodes[self.node].next.clone(),
+        };
+        match next.and_then(|id| self.nodes.iter().position(|n| n.id == id)) {
+            Some(node) => self.enter(node, scripts),
+            None => self.finished = true,
+        }
+    }
+
+    /// Move the highlight up (-1) or down (+1), wrapping around
+    pub fn move_selection(&mut self, step: i32) {
+        if self.choices.is_empty() || self.typing() {
+            return;
+        }
+        let count = self.choices.len() as i32;
+        self.selected = (self.selected as i32 + step).rem_euclid(count) as usize;
+    }
+
+    /// Who's speaking (empty for narration)
+    pub fn speaker(&self) -> &str {
+        &self.nodes[self.node].speaker
+    }
+
+    /// The part of the line typed so far
+    pub fn text(&self) -> &str {
+        let text = &self.nodes[self.node].text;
+        let end = text.char_indices().nth(self.shown as usize).map(|(i, _)| i).unwrap_or(text.len());
+        &text[..end]
+    }
+
+    /// The whole line (for laying it out before it has typed out)
+    pub fn line(&self) -> &str {
+        &self.nodes[self.node].text
+    }
+
+    /// The line is still typing out
+    pub fn typing(&self) -> bool {
+        (self.shown as usize) < self.line_len()
+    }
+
+    /// Texts of the choices on offer (shown once the line has typed out)
+    pub fn choices(&self) -> Vec<&str> {
+        let node = &self.nodes[self.node];
+        self.choices.iter().map(|&i| node.choices[i].text.as_str()).collect()
+    }
+
+    /// Highlighted entry of `choices`
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    fn enter(&mut self, node: usize, scripts: &mut ScriptRuntime) {
+        self.node = node;
+        self.shown = 0.0;
+        self.selected = 0;
+        let node = &self.nodes[node];
+        apply(&node.effects, scripts);
+        self.choices = node.choices.iter().enumerate()
+            .filter(|(_, c)| holds(&c.condition, scripts))
+            .map(|(i, _)| i)
+            .collect();
+    }
+
+    fn line_len(&self) -> usize {
+        self.nodes[self.node].text.chars().count()
+    }
+}
+
+fn holds(condition: &Option<FlagCondition>, scripts: &ScriptRuntime) -> bool {
+    condition.as_ref().map(|c| c.holds(|flag| scripts.var(flag))).unwrap_or(true)
+}
+
+fn apply(effects: &[FlagEffect], scripts: &mut ScriptRuntime) {
+    for effect in effects {
+        scripts.set_var(&effect.flag, effect.value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::DialogueChoice;
+
+    fn guard() -> Vec<DialogueNode> {
+        let mut greet = DialogueNode::new("greet", "Guard", "Halt!");
+        greet.condition = FlagCondition::parse("met == 0");
+        greet.effects.push(FlagEffect { flag: "met".to_string(), value: 1.0 });
+        greet.choices.push(DialogueChoice::new("A friend.", Some("friend".to_string())));
+        let mut bribe = DialogueChoice::new("Let me through.", None);
+        bribe.condition = FlagCondition::parse("gems >= 3");
+        bribe.effects.push(FlagEffect { flag: "gate".to_string(), value: 1.0 });
+        greet.choices.push(bribe);
+        let again = DialogueNode::new("again", "Guard", "You again?");
+        let friend = DialogueNode::new("friend", "Guard", "Pass, friend.");
+        vec![greet, again, friend]
+    }
+
+    #[test]
+    fn test_typewriter() {
+        let mut scripts = ScriptRuntime::new();
+        let mut player = DialoguePlayer::new("guard", &guard(), &mut scripts).unwrap();
+        assert_eq!(player.text(), "");
+        player.update(2.5 / TYPE_SPEED);
+        assert_eq!(player.text(), "Ha");
+        assert!(player.typing());
+        // The first Interact only finishes the line
+        player.advance(&mut scripts);
+        assert_eq!(player.text(), "Halt!");
+        assert!(!player.typing() && !player.finished());
+    }
+
+    #[test]
+    fn test_choices_branch_and_set_flags() {
+        let mut scripts = ScriptRuntime::new();
+        let mut player = DialoguePlayer::new("guard", &guard(), &mut scripts).unwrap();
+        assert_eq!(scripts.var("met"), 1.0);
+        // Too few gems: the bribe isn't offered
+        assert_eq!(player.choices(), vec!["A friend."]);
+        player.update(1.0);
+        player.move_selection(1);
+        assert_eq!(player.selected(), 0);
+        player.advance(&mut scripts);
+        assert_eq!(player.text(), "");
+        player.update(1.0);
+        assert_eq!(player.text(), "Pass, friend.");
+        player.advance(&mut scripts);
+        assert!(player.finished());
+
+        // Met already: the greeting is skipped
+        scripts.set_var("gems", 3.0);
+        let player = DialoguePlayer::new("guard", &guard(), &mut scripts).unwrap();
+        assert_eq!(player.speaker(), "Guard");
+        assert_eq!(player.nodes[player.node].id, "again");
+
+        scripts.set_var("met", 0.0);
+        let mut player = DialoguePlayer::new("guard", &guard(), &mut scripts).unwrap();
+        assert_eq!(player.choices().len(), 2);
+        player.update(1.0);
+        player.move_selection(-1);
+        assert_eq!(player.selected(), 1);
+        player.advance(&mut scripts);
+        assert_eq!(scripts.var("gate"), 1.0);
+        assert!(player.finished());
+    }
+}
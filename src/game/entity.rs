@@ -132,6 +132,16 @@ impl EntityAllocator {
         idx < self.generations.len() && self.generations[idx] == entity.generation
     }
 
+    /// The live entity occupying a slot index, if the slot is in use.
+    /// Lets systems turn component storage indices back into full entities.
+    pub fn entity_at(&self, index: u32) -> Option<Entity> {
+        let generation = *self.generations.get(index as usize)?;
+        if self.free_indices.contains(&index) {
+            return None;
+        }
+        Some(Entity::new(index, generation))
+    }
+
     /// Get the number of currently alive entities.
     pub fn alive_count(&self) -> u32 {
         self.alive_count
@@ -0,0 +1,192 @@
+//! Equipment
+//!
+//! Weapons and armor the player carries. Assets with a Weapon or Armor
+//! component become gear: the player start's own gear is carried from the
+//! start, and placed instances of other gear assets are picked up by walking
+//! over them (`level_logic`). One weapon and one armor piece are equipped at
+//! a time, from the inventory screen; equipping copies the weapon's moveset
+//! and the armor's defense and roll tier onto the player's `Combat`.
+//!
+//! A weapon's `model_part` names the mesh part that goes in the player's
+//! hand. The player has no animated model to hold it yet, so it's carried
+//! along but not drawn.
+
+use super::combat::{self, RollTier, UNARMED_DAMAGE};
+use super::components::Combat;
+use crate::asset::{AssetComponent, AttackDef};
+
+/// What a piece of gear does when equipped
+#[derive(Debug, Clone)]
+pub enum GearKind {
+    Weapon {
+        /// Moveset, with the weapon's speed applied
+        moveset: Combat,
+        /// Swing speed multiplier (for display)
+        speed: f32,
+        /// Mesh part held in the hand (None = the whole mesh)
+        model_part: Option<String>,
+    },
+    Armor {
+        /// Fraction of every hit's damage taken off
+        defense: f32,
+        weight: f32,
+    },
+}
+
+/// A weapon or armor piece, named after its asset
+#[derive(Debug, Clone)]
+pub struct Gear {
+    pub name: String,
+    pub kind: GearKind,
+}
+
+impl Gear {
+    /// A Weapon or Armor component of the asset named `name` as gear
+    pub fn from_component(name: &str, component: &AssetComponent) -> Option<Self> {
+        let kind = match component {
+            AssetComponent::Weapon { speed, model_part, .. } => GearKind::Weapon {
+                moveset: combat::weapon(component)?,
+                speed: *speed,
+                model_part: model_part.clone(),
+            },
+            AssetComponent::Armor { defense, weight } => GearKind::Armor {
+                defense: defense.clamp(0.0, 1.0),
+                weight: weight.max(0.0),
+            },
+            _ => return None,
+        };
+        Some(Self { name: name.to_string(), kind })
+    }
+
+    pub fn is_weapon(&self) -> bool {
+        matches!(self.kind, GearKind::Weapon { .. })
+    }
+}
+
+/// The player's gear and what's equipped
+#[derive(Debug, Clone, Default)]
+pub struct Equipment {
+    items: Vec<Gear>,
+    /// Equipped weapon (index into `items`)
+    weapon: Option<usize>,
+    /// Equipped armor (index into `items`)
+    armor: Option<usize>,
+}
+
+impl Equipment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Carry a piece of gear, equipping it if its slot is empty. Each asset's
+    /// weapon and armor are only carried once; false if it was already.
+    pub fn add(&mut self, gear: Gear) -> bool {
+        if self.items.iter().any(|g| g.name == gear.name && g.is_weapon() == gear.is_weapon()) {
+            return false;
+        }
+        let index = self.items.len();
+        let slot = if gear.is_weapon() { &mut self.weapon } else { &mut self.armor };
+        if slot.is_none() {
+            *slot = Some(index);
+        }
+        self.items.push(gear);
+        true
+    }
+
+    /// Equip the item at `index`, or take it off if it's equipped
+    pub fn toggle(&mut self, index: usize) {
+        let Some(gear) = self.items.get(index) else {
+            return;
+        };
+        let slot = if gear.is_weapon() { &mut self.weapon } else { &mut self.armor };
+        *slot = if *slot == Some(index) { None } else { Some(index) };
+    }
+
+    pub fn is_equipped(&self, index: usize) -> bool {
+        self.weapon == Some(index) || self.armor == Some(index)
+    }
+
+    /// Carried gear, in pickup order
+    pub fn items(&self) -> &[Gear] {
+        &self.items
+    }
+
+    /// The equipped weapon, if any
+    pub fn weapon(&self) -> Option<&Gear> {
+        self.weapon.and_then(|i| self.items.get(i))
+    }
+
+    /// Give `combat` the equipped weapon's moveset (unarmed without one) and
+    /// the equipped armor's defense and roll tier. An attack already being
+    /// swung plays out as it started.
+    pub fn apply(&self, combat: &mut Combat) {
+        let moveset = match self.weapon().map(|g| &g.kind) {
+            Some(GearKind::Weapon { moveset, .. }) => moveset.clone(),
+            _ => Combat::new(UNARMED_DAMAGE, AttackDef::light(), AttackDef::heavy()),
+        };
+        combat.damage = moveset.damage;
+        combat.light = moveset.light;
+        combat.heavy = moveset.heavy;
+        combat.guard_absorption = moveset.guard_absorption;
+        combat.guard_stamina = moveset.guard_stamina;
+        // Without armor the player rolls as before armor existed
+        let (defense, roll) = match self.armor.and_then(|i| self.items.get(i)).map(|g| &g.kind) {
+            Some(GearKind::Armor { defense, weight }) => (*defense, RollTier::from_weight(*weight)),
+            _ => (0.0, RollTier::default()),
+        };
+        combat.defense = defense;
+        combat.roll = roll;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gear(name: &str, component: &str) -> Gear {
+        Gear::from_component(name, &ron::from_str(component).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_gear_from_component() {
+        let sword = gear("sword", "Weapon(damage: 20, speed: 2.0, model_part: Some(\"blade\"))");
+        let GearKind::Weapon { moveset, model_part, .. } = &sword.kind else {
+            panic!("expected a weapon");
+        };
+        assert_eq!(moveset.damage, 20);
+        assert_eq!(moveset.light.fps, AttackDef::light().fps * 2.0);
+        assert_eq!(model_part.as_deref(), Some("blade"));
+        assert!(!gear("mail", "Armor(defense: 0.3, weight: 30.0)").is_weapon());
+        let lamp = AssetComponent::Light { color: [255; 3], intensity: 1.0, radius: 512.0, offset: [0.0; 3] };
+        assert!(Gear::from_component("lamp", &lamp).is_none());
+    }
+
+    #[test]
+    fn test_equip_and_apply() {
+        let mut equipment = Equipment::new();
+        assert!(equipment.add(gear("sword", "Weapon(damage: 20)")));
+        assert!(!equipment.add(gear("sword", "Weapon(damage: 20)")));
+        equipment.add(gear("axe", "Weapon(damage: 35)"));
+        equipment.add(gear("mail", "Armor(defense: 0.3, weight: 30.0)"));
+        // The first of each kind is equipped on pickup
+        assert!(equipment.is_equipped(0) && !equipment.is_equipped(1) && equipment.is_equipped(2));
+
+        let mut combat = Combat::new(UNARMED_DAMAGE, AttackDef::light(), AttackDef::heavy());
+        equipment.toggle(1);
+        equipment.apply(&mut combat);
+        assert_eq!(combat.damage, 35);
+        assert_eq!((combat.defense, combat.roll), (0.3, RollTier::Heavy));
+
+        // Taking everything off leaves the player unarmed and unarmored
+        equipment.toggle(1);
+        equipment.toggle(2);
+        assert!(!equipment.is_equipped(0));
+        equipment.apply(&mut combat);
+        assert_eq!(combat.damage, UNARMED_DAMAGE);
+        assert_eq!((combat.defense, combat.roll), (0.0, RollTier::Medium));
+
+        // An asset's armor is carried alongside its weapon
+        assert!(equipment.add(gear("sword", "Armor(defense: 0.1)")));
+        assert!(equipment.is_equipped(3));
+    }
+}
@@ -82,6 +82,15 @@ pub struct Events {
     /// Door opened
     pub door_opened: EventQueue<DoorEvent>,
 
+    /// Door closed
+    pub door_closed: EventQueue<DoorEvent>,
+
+    /// Tried to open a locked door without its key
+    pub door_locked: EventQueue<DoorEvent>,
+
+    /// A destructible prop was broken
+    pub prop_broken: EventQueue<PropBrokenEvent>,
+
     /// Item collected
     pub item_collected: EventQueue<ItemCollectedEvent>,
 
@@ -90,6 +99,18 @@ pub struct Events {
 
     /// Player respawn requested
     pub respawn: EventQueue<RespawnEvent>,
+
+    /// Sound requested (by scripts), for the audio system
+    pub sound: EventQueue<SoundEvent>,
+
+    /// A character grabbed, climbed off or let go of a ladder (animation hook)
+    pub climb: EventQueue<ClimbEvent>,
+
+    /// A character entered, left, dived or surfaced in water (animation hook)
+    pub swim: EventQueue<SwimEvent>,
+
+    /// A cutscene asked an instance to play an animation clip
+    pub animation: EventQueue<AnimationEvent>,
 }
 
 impl Events {
@@ -100,9 +121,16 @@ impl Events {
             spawn: EventQueue::new(),
             checkpoint_activated: EventQueue::new(),
             door_opened: EventQueue::new(),
+            door_closed: EventQueue::new(),
+            door_locked: EventQueue::new(),
+            prop_broken: EventQueue::new(),
             item_collected: EventQueue::new(),
             collision: EventQueue::new(),
             respawn: EventQueue::new(),
+            sound: EventQueue::new(),
+            climb: EventQueue::new(),
+            swim: EventQueue::new(),
+            animation: EventQueue::new(),
         }
     }
 
@@ -113,9 +141,16 @@ impl Events {
         self.spawn.clear();
         self.checkpoint_activated.clear();
         self.door_opened.clear();
+        self.door_closed.clear();
+        self.door_locked.clear();
+        self.prop_broken.clear();
         self.item_collected.clear();
         self.collision.clear();
         self.respawn.clear();
+        self.sound.clear();
+        self.climb.clear();
+        self.swim.clear();
+        self.animation.clear();
     }
 }
 
@@ -171,15 +206,26 @@ pub struct CheckpointEvent {
     pub player: Entity,
 }
 
-/// A door was opened
+/// A door was opened, closed, or found locked
 #[derive(Debug, Clone, Copy)]
 pub struct DoorEvent {
     /// The door entity
     pub door: Entity,
-    /// Who opened it
+    /// Who opened, closed, or tried it (the door itself when a script did)
     pub opener: Entity,
 }
 
+/// A destructible prop broke
+#[derive(Debug, Clone, Copy)]
+pub struct PropBrokenEvent {
+    /// The prop entity
+    pub prop: Entity,
+    /// Who broke it
+    pub breaker: Entity,
+    /// Where it stood
+    pub position: Vec3,
+}
+
 /// An item was collected
 #[derive(Debug, Clone, Copy)]
 pub struct ItemCollectedEvent {
@@ -211,6 +257,46 @@ pub struct RespawnEvent {
     pub position: Vec3,
 }
 
+/// A sound should be played
+#[derive(Debug, Clone)]
+pub struct SoundEvent {
+    /// Sound identifier
+    pub name: String,
+    /// World position (None = non-positional)
+    pub position: Option<Vec3>,
+}
+
+/// A character started or stopped climbing a ladder
+#[derive(Debug, Clone, Copy)]
+pub struct ClimbEvent {
+    /// The climber
+    pub entity: Entity,
+    /// What happened
+    pub action: super::climb::ClimbAction,
+    /// Where the climber is afterwards (feet)
+    pub position: Vec3,
+}
+
+/// A character's swimming changed
+#[derive(Debug, Clone, Copy)]
+pub struct SwimEvent {
+    /// The swimmer
+    pub entity: Entity,
+    /// What happened
+    pub action: super::swim::SwimAction,
+    /// Where the swimmer is afterwards (feet)
+    pub position: Vec3,
+}
+
+/// An instance should play an animation clip
+#[derive(Debug, Clone)]
+pub struct AnimationEvent {
+    /// Instance name
+    pub name: String,
+    /// Clip name
+    pub clip: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
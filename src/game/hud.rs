@@ -0,0 +1,599 @@
+//! HUD
+//!
+//! Health and stamina bars, key item slots, the interaction prompt, a boss
+//! bar, a breath bar while swimming and status effect icons, drawn straight into the framebuffer after the 3D scene so they get the
+//! same chunky low-res pixels as everything else. Text uses a built-in 3x5
+//! pixel font (uppercase, digits and a little punctuation); accented Latin
+//! letters from translated prompts are drawn without their accents.
+//!
+//! The layout is part of the level (`Level::hud`) so it ships with the
+//! exported game: each element has an anchor on the screen plus an offset in
+//! 320x240 pixels, edited in the World Editor's HUD panel. At 640x480 every
+//! HUD pixel is drawn 2x2.
+
+use serde::{Deserialize, Serialize};
+
+use super::components::{Enemy, EnemyType, Health};
+use super::transform::Transform;
+use super::runtime::GameToolState;
+use super::{Entity, World};
+use crate::rasterizer::{BlendMode, Color, Framebuffer, Vec3};
+use crate::locale;
+use crate::world::Level;
+
+/// Resolution the layout offsets and element sizes are authored at
+pub const HUD_BASE_WIDTH: usize = 320;
+pub const HUD_BASE_HEIGHT: usize = 240;
+/// Item slots shown by the item bar
+pub const ITEM_SLOTS: usize = 4;
+/// Status effect icons shown at once
+pub const STATUS_SLOTS: usize = 4;
+
+const HEALTH_COLOR: Color = Color { r: 200, g: 40, b: 40, blend: BlendMode::Opaque };
+const STAMINA_COLOR: Color = Color { r: 60, g: 170, b: 70, blend: BlendMode::Opaque };
+const BOSS_COLOR: Color = Color { r: 150, g: 20, b: 20, blend: BlendMode::Opaque };
+const OXYGEN_COLOR: Color = Color { r: 70, g: 150, b: 220, blend: BlendMode::Opaque };
+const FRAME_COLOR: Color = Color { r: 0, g: 0, b: 0, blend: BlendMode::Opaque };
+const TEXT_COLOR: Color = Color { r: 235, g: 230, b: 210, blend: BlendMode::Opaque };
+const KEY_COLOR: Color = Color { r: 255, g: 210, b: 110, blend: BlendMode::Opaque };
+
+/// Where on the screen an element is pinned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HudAnchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl HudAnchor {
+    /// Row by row, as drawn in the editor's 3x3 picker
+    pub const ALL: [HudAnchor; 9] = [
+        HudAnchor::TopLeft, HudAnchor::Top, HudAnchor::TopRight,
+        HudAnchor::Left, HudAnchor::Center, HudAnchor::Right,
+        HudAnchor::BottomLeft, HudAnchor::Bottom, HudAnchor::BottomRight,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HudAnchor::TopLeft => "Top Left",
+            HudAnchor::Top => "Top",
+            HudAnchor::TopRight => "Top Right",
+            HudAnchor::Left => "Left",
+            HudAnchor::Center => "Center",
+            HudAnchor::Right => "Right",
+            HudAnchor::BottomLeft => "Bottom Left",
+            HudAnchor::Bottom => "Bottom",
+            HudAnchor::BottomRight => "Bottom Right",
+        }
+    }
+
+    /// Anchor position as a fraction of the screen (0, 1/2 or 1 per axis)
+    fn fraction(&self) -> (i32, i32) {
+        let index = Self::ALL.iter().position(|a| a == self).unwrap_or(0) as i32;
+        (index % 3, index / 3)
+    }
+}
+
+/// The HUD widgets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HudElementKind {
+    Health,
+    Stamina,
+    Items,
+    Prompt,
+    BossBar,
+    Oxygen,
+    Status,
+}
+
+impl HudElementKind {
+    pub const ALL: [HudElementKind; 7] = [
+        HudElementKind::Health,
+        HudElementKind::Stamina,
+        HudElementKind::Items,
+        HudElementKind::Prompt,
+        HudElementKind::BossBar,
+        HudElementKind::Oxygen,
+        HudElementKind::Status,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HudElementKind::Health => "Health",
+            HudElementKind::Stamina => "Stamina",
+            HudElementKind::Items => "Items",
+            HudElementKind::Prompt => "Prompt",
+            HudElementKind::BossBar => "Boss Bar",
+            HudElementKind::Oxygen => "Oxygen",
+            HudElementKind::Status => "Status",
+        }
+    }
+
+    /// Size in base (320x240) pixels
+    pub fn size(&self) -> (i32, i32) {
+        match self {
+            HudElementKind::Health => (80, 6),
+            HudElementKind::Stamina => (60, 4),
+            HudElementKind::Items => (ITEM_SLOTS as i32 * 18 - 2, 16),
+            HudElementKind::Prompt => (120, 9),
+            HudElementKind::BossBar => (160, 10),
+            HudElementKind::Oxygen => (60, 4),
+            HudElementKind::Status => (STATUS_SLOTS as i32 * 20 - 2, 9),
+        }
+    }
+}
+
+/// One placed HUD widget
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HudElement {
+    pub kind: HudElementKind,
+    pub anchor: HudAnchor,
+    /// Offset from the anchor in base pixels (+x right, +y down)
+    pub offset: (i32, i32),
+    #[serde(default = "default_true")]
+    pub visible: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl HudElement {
+    pub fn new(kind: HudElementKind, anchor: HudAnchor, offset: (i32, i32)) -> Self {
+        Self { kind, anchor, offset, visible: true }
+    }
+
+    /// Screen rect (x, y, w, h) in framebuffer pixels
+    pub fn rect(&self, fb_width: usize, fb_height: usize) -> (i32, i32, i32, i32) {
+        let scale = hud_scale(fb_height);
+        let (w, h) = self.kind.size();
+        let (w, h) = (w * scale, h * scale);
+        let (fx, fy) = self.anchor.fraction();
+        // 0 = left/top edge, 1 = centered, 2 = right/bottom edge
+        let x = (fb_width as i32 - w) * fx / 2 + self.offset.0 * scale;
+        let y = (fb_height as i32 - h) * fy / 2 + self.offset.1 * scale;
+        (x, y, w, h)
+    }
+}
+
+/// Per-level HUD layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HudLayout {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub elements: Vec<HudElement>,
+}
+
+impl Default for HudLayout {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            elements: vec![
+                HudElement::new(HudElementKind::Health, HudAnchor::TopLeft, (8, 8)),
+                HudElement::new(HudElementKind::Stamina, HudAnchor::TopLeft, (8, 16)),
+                HudElement::new(HudElementKind::Items, HudAnchor::BottomLeft, (8, -8)),
+                HudElement::new(HudElementKind::Prompt, HudAnchor::Center, (0, 40)),
+                HudElement::new(HudElementKind::BossBar, HudAnchor::Bottom, (0, -30)),
+                HudElement::new(HudElementKind::Oxygen, HudAnchor::TopLeft, (8, 22)),
+                HudElement::new(HudElementKind::Status, HudAnchor::TopLeft, (8, 28)),
+            ],
+        }
+    }
+}
+
+impl HudLayout {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn element(&self, kind: HudElementKind) -> Option<&HudElement> {
+        self.elements.iter().find(|e| e.kind == kind)
+    }
+
+    /// Topmost visible element under a framebuffer pixel
+    pub fn element_at(&self, x: i32, y: i32, fb_width: usize, fb_height: usize) -> Option<usize> {
+        self.elements.iter().rposition(|e| {
+            let (ex, ey, ew, eh) = e.rect(fb_width, fb_height);
+            e.visible && x >= ex && x < ex + ew && y >= ey && y < ey + eh
+        })
+    }
+}
+
+/// HUD pixel size for a framebuffer height (1 at 240 lines, 2 at 480)
+pub fn hud_scale(fb_height: usize) -> i32 {
+    (fb_height / HUD_BASE_HEIGHT).max(1) as i32
+}
+
+/// What the HUD shows this frame
+#[derive(Debug, Clone, Default)]
+pub struct HudFrame {
+    /// Player health (current, max)
+    pub health: Option<(i32, i32)>,
+    /// Player stamina (current, max)
+    pub stamina: Option<(f32, f32)>,
+    /// Collected key items, oldest first
+    pub items: Vec<String>,
+    /// Interaction prompt ("[E] Open")
+    pub prompt: Option<String>,
+    /// Boss in the fight: (name, current, max)
+    pub boss: Option<(String, i32, i32)>,
+    /// Breath left (0..1), while swimming without a full breath
+    pub oxygen: Option<f32>,
+    /// Player status effects: running ones first, then filling buildup meters
+    pub statuses: Vec<StatusIcon>,
+}
+
+/// A status effect icon
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusIcon {
+    pub icon: String,
+    pub color: [u8; 3],
+    pub stacks: u32,
+    /// Time left (0..1) while running, meter fill (0..1) while building up
+    pub fraction: f32,
+    /// Still building up (drawn dim)
+    pub building: bool,
+}
+
+impl HudFrame {
+    /// Read the player's state, inventory, nearby door and boss from the game
+    pub fn gather(game: &GameToolState, level: &Level, interact_key: &str) -> Self {
+        let player = game.player_entity;
+        let player_pos = game.get_player_position();
+        Self {
+            health: player.and_then(|p| game.world.health.get(p)).map(|h| (h.current, h.max)),
+            stamina: player.and_then(|p| game.world.stamina.get(p)).map(|s| (s.current, s.max)),
+            items: game.logic.keys().to_vec(),
+            prompt: player_pos
+                .and_then(|pos| game.logic.interaction_prompt(&game.world, pos))
+                .map(|verb| {
+                    // Verbs are looked up as "hud.open", "hud.close", ...
+                    let verb = locale::t_or(&format!("hud.{}", verb.to_lowercase()), verb);
+                    let prompt = locale::t_or("hud.prompt", "[{key}] {verb}");
+                    locale::fill_placeholders(&prompt, &[("key", interact_key), ("verb", &verb)])
+                }),
+            boss: player_pos.and_then(|pos| {
+                boss_target(&game.world, game.lock_on.target, pos, level.player_settings.lock_on_range)
+            }),
+            oxygen: player
+                .and_then(|p| game.world.controllers.get(p))
+                .and_then(|c| c.swimming)
+                .map(|swim| swim.oxygen_fraction())
+                .filter(|&fraction| fraction < 1.0),
+            statuses: player.map(|p| status_icons(&game.world, p)).unwrap_or_default(),
+        }
+    }
+
+    /// Placeholder values for the editor preview (every element visible)
+    pub fn sample() -> Self {
+        Self {
+            health: Some((70, 100)),
+            stamina: Some((45.0, 100.0)),
+            items: vec!["Red Key".to_string(), "Gate Key".to_string()],
+            prompt: Some("[E] Open".to_string()),
+            boss: Some(("Boss".to_string(), 600, 1000)),
+            oxygen: Some(0.6),
+            statuses: vec![
+                StatusIcon { icon: "PSN".to_string(), color: [120, 200, 60], stacks: 2, fraction: 0.7, building: false },
+                StatusIcon { icon: "BLD".to_string(), color: [200, 30, 30], stacks: 1, fraction: 0.4, building: true },
+            ],
+        }
+    }
+}
+
+/// HUD icons for an entity's status effects
+fn status_icons(world: &World, entity: Entity) -> Vec<StatusIcon> {
+    let Some(effects) = world.status_effects.get(entity) else {
+        return Vec::new();
+    };
+    let running = effects.active.iter().map(|a| StatusIcon {
+        icon: a.def.icon.clone(),
+        color: a.def.color,
+        stacks: a.stacks,
+        fraction: a.remaining / a.def.duration.max(0.01),
+        building: false,
+    });
+    let building = effects.buildup.iter().map(|b| StatusIcon {
+        icon: b.def.icon.clone(),
+        color: b.def.color,
+        stacks: 1,
+        fraction: b.amount / b.def.buildup.max(0.01),
+        building: true,
+    });
+    running.chain(building).collect()
+}
+
+/// The boss to show a bar for: the locked-on target if it's a boss or elite,
+/// else the nearest living one within `range`
+fn boss_target(world: &World, locked: Option<Entity>, player_pos: Vec3, range: f32) -> Option<(String, i32, i32)> {
+    let bar = |entity: Entity| -> Option<(String, i32, i32)> {
+        let name = match world.enemies.get(entity)?.enemy_type {
+            EnemyType::Boss => "Boss",
+            EnemyType::Elite => "Elite",
+            _ => return None,
+        };
+        let health = world.health.get(entity).filter(|h| !h.is_dead())?;
+        Some((name.to_string(), health.current, health.max))
+    };
+    if let Some(locked) = locked.and_then(bar) {
+        return Some(locked);
+    }
+    world.query_ref::<(&Transform, &Enemy, &Health)>()
+        .filter(|(_, (_, enemy, health))| matches!(enemy.enemy_type, EnemyType::Boss | EnemyType::Elite) && !health.is_dead())
+        .map(|(entity, (transform, _, _))| (entity, (transform.position - player_pos).len()))
+        .filter(|&(_, distance)| distance <= range)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .and_then(|(entity, _)| bar(entity))
+}
+
+/// Draw the HUD into the framebuffer
+pub fn draw(fb: &mut Framebuffer, layout: &HudLayout, frame: &HudFrame) {
+    if !layout.enabled {
+        return;
+    }
+    let s = hud_scale(fb.height);
+    for element in layout.elements.iter().filter(|e| e.visible) {
+        let (x, y, w, h) = element.rect(fb.width, fb.height);
+        match element.kind {
+            HudElementKind::Health => {
+                if let Some((current, max)) = frame.health {
+                    draw_bar(fb, (x, y, w, h), current as f32 / max.max(1) as f32, HEALTH_COLOR, s);
+                }
+            }
+            HudElementKind::Stamina => {
+                if let Some((current, max)) = frame.stamina {
+                    draw_bar(fb, (x, y, w, h), current / max.max(1.0), STAMINA_COLOR, s);
+                }
+            }
+            HudElementKind::Items => {
+                let slot = 16 * s;
+                for i in 0..ITEM_SLOTS {
+                    let sx = x + i as i32 * 18 * s;
+                    fill(fb, sx, y, slot, slot, FRAME_COLOR, 150);
+                    outline(fb, sx, y, slot, slot, Color::new(110, 100, 80), s);
+                    if let Some(name) = frame.items.get(i) {
+                        let short: String = name.chars().filter(|c| c.is_ascii_alphanumeric()).take(3).collect();
+                        let tx = sx + (slot - text_width(&short) * s) / 2;
+                        draw_text(fb, tx, y + 5 * s, &short, KEY_COLOR, s);
+                    }
+                }
+            }
+            HudElementKind::Prompt => {
+                if let Some(text) = &frame.prompt {
+                    let tw = text_width(text) * s;
+                    let bx = x + (w - tw) / 2 - 3 * s;
+                    fill(fb, bx, y, tw + 6 * s, h, FRAME_COLOR, 160);
+                    draw_text(fb, bx + 3 * s, y + 2 * s, text, TEXT_COLOR, s);
+                }
+            }
+            HudElementKind::BossBar => {
+                if let Some((name, current, max)) = &frame.boss {
+                    draw_text(fb, x, y, name, TEXT_COLOR, s);
+                    draw_bar(fb, (x, y + 6 * s, w, h - 6 * s), *current as f32 / (*max).max(1) as f32, BOSS_COLOR, s);
+                }
+            }
+            HudElementKind::Oxygen => {
+                if let Some(fraction) = frame.oxygen {
+                    draw_bar(fb, (x, y, w, h), fraction, OXYGEN_COLOR, s);
+                }
+            }
+            HudElementKind::Status => {
+                let size = 14 * s;
+                for (i, status) in frame.statuses.iter().take(STATUS_SLOTS).enumerate() {
+                    let sx = x + i as i32 * 20 * s;
+                    let [r, g, b] = status.color;
+                    let color = if status.building { Color::new(r / 2, g / 2, b / 2) } else { Color::new(r, g, b) };
+                    fill(fb, sx, y, size, h, FRAME_COLOR, 150);
+                    let icon: String = status.icon.chars().take(3).collect();
+                    draw_text(fb, sx + (size - text_width(&icon) * s) / 2, y + s, &icon, color, s);
+                    // Time left (or buildup) along the bottom edge
+                    let filled = (size as f32 * status.fraction.clamp(0.0, 1.0)).round() as i32;
+                    fill(fb, sx, y + h - s, filled, s, color, 255);
+                    if status.stacks > 1 {
+                        draw_text(fb, sx + size + s, y + s, &status.stacks.min(9).to_string(), TEXT_COLOR, s);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Dark frame with a `fraction`-filled bar inside
+fn draw_bar(fb: &mut Framebuffer, (x, y, w, h): (i32, i32, i32, i32), fraction: f32, color: Color, s: i32) {
+    fill(fb, x, y, w, h, FRAME_COLOR, 170);
+    let inner = w - 2 * s;
+    let filled = (inner as f32 * fraction.clamp(0.0, 1.0)).round() as i32;
+    fill(fb, x + s, y + s, filled, h - 2 * s, color, 255);
+}
+
+/// Alpha-blended rectangle, clipped to the framebuffer
+fn fill(fb: &mut Framebuffer, x: i32, y: i32, w: i32, h: i32, color: Color, alpha: u8) {
+    for py in y.max(0)..(y + h).min(fb.height as i32) {
+        for px in x.max(0)..(x + w).min(fb.width as i32) {
+            fb.set_pixel_alpha(px as usize, py as usize, color, alpha);
+        }
+    }
+}
+
+/// Rectangle border `s` pixels thick
+fn outline(fb: &mut Framebuffer, x: i32, y: i32, w: i32, h: i32, color: Color, s: i32) {
+    fill(fb, x, y, w, s, color, 255);
+    fill(fb, x, y + h - s, w, s, color, 255);
+    fill(fb, x, y, s, h, color, 255);
+    fill(fb, x + w - s, y, s, h, color, 255);
+}
+
+/// Width of `text` in base pixels (4 per glyph, no trailing gap)
+pub fn text_width(text: &str) -> i32 {
+    (text.chars().count() as i32 * 4 - 1).max(0)
+}
+
+/// Draw text in the 3x5 font with a 1-pixel drop shadow, `s` framebuffer pixels per font pixel
+pub fn draw_text(fb: &mut Framebuffer, x: i32, y: i32, text: &str, color: Color, s: i32) {
+    for (shadow, color) in [(s, FRAME_COLOR), (0, color)] {
+        for (i, c) in text.chars().enumerate() {
+            let bits = glyph(c);
+            let gx = x + i as i32 * 4 * s + shadow;
+            for row in 0..5 {
+                for col in 0..3 {
+                    if bits & (1 << (14 - row * 3 - col)) != 0 {
+                        fill(fb, gx + col * s, y + row * s + shadow, s, s, color, 255);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Accented Latin letters as their base letter (no room for accents in 3x5)
+fn fold_accent(c: char) -> char {
+    match c {
+        'À'..='Å' | 'à'..='å' => 'A',
+        'Ç' | 'ç' => 'C',
+        'È'..='Ë' | 'è'..='ë' => 'E',
+        'Ì'..='Ï' | 'ì'..='ï' => 'I',
+        'Ñ' | 'ñ' => 'N',
+        'Ò'..='Ö' | 'Ø' | 'ò'..='ö' | 'ø' => 'O',
+        'Ù'..='Ü' | 'ù'..='ü' => 'U',
+        'Ý' | 'ý' | 'ÿ' => 'Y',
+        '¡' => '!',
+        _ => c,
+    }
+}
+
+/// 3x5 glyph bits, top row first, most significant bit on the left
+fn glyph(c: char) -> u16 {
+    match fold_accent(c).to_ascii_uppercase() {
+        'A' => 0b010_101_111_101_101,
+        'B' => 0b110_101_110_101_110,
+        'C' => 0b011_100_100_100_011,
+        'D' => 0b110_101_101_101_110,
+        'E' => 0b111_100_110_100_111,
+        'F' => 0b111_100_110_100_100,
+        'G' => 0b011_100_101_101_011,
+        'H' => 0b101_101_111_101_101,
+        'I' => 0b111_010_010_010_111,
+        'J' => 0b001_001_001_101_010,
+        'K' => 0b101_101_110_101_101,
+        'L' => 0b100_100_100_100_111,
+        'M' => 0b101_111_111_101_101,
+        'N' => 0b110_101_101_101_101,
+        'O' => 0b010_101_101_101_010,
+        'P' => 0b110_101_110_100_100,
+        'Q' => 0b010_101_101_110_011,
+        'R' => 0b110_101_110_101_101,
+        'S' => 0b011_100_010_001_110,
+        'T' => 0b111_010_010_010_010,
+        'U' => 0b101_101_101_101_111,
+        'V' => 0b101_101_101_101_010,
+        'W' => 0b101_101_111_111_101,
+        'X' => 0b101_101_010_101_101,
+        'Y' => 0b101_101_010_010_010,
+        'Z' => 0b111_001_010_100_111,
+        '0' => 0b111_101_101_101_111,
+        '1' => 0b010_110_010_010_111,
+        '2' => 0b110_001_010_100_111,
+        '3' => 0b110_001_010_001_110,
+        '4' => 0b101_101_111_001_001,
+        '5' => 0b111_100_110_001_110,
+        '6' => 0b011_100_111_101_111,
+        '7' => 0b111_001_010_010_010,
+        '8' => 0b111_101_111_101_111,
+        '9' => 0b111_101_111_001_110,
+        ' ' => 0,
+        '.' => 0b000_000_000_000_010,
+        ',' => 0b000_000_000_010_100,
+        ':' => 0b000_010_000_010_000,
+        '!' => 0b010_010_010_000_010,
+        '-' => 0b000_000_111_000_000,
+        '+' => 0b000_010_111_010_000,
+        '/' => 0b001_001_010_100_100,
+        '\'' => 0b010_010_000_000_000,
+        '(' => 0b010_100_100_100_010,
+        ')' => 0b010_001_001_001_010,
+        '[' => 0b110_100_100_100_110,
+        ']' => 0b011_001_001_001_011,
+        _ => 0b110_001_010_000_010, // '?'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_rects() {
+        let corner = HudElement::new(HudElementKind::Health, HudAnchor::BottomRight, (-8, -8));
+        assert_eq!(corner.rect(320, 240), (320 - 80 - 8, 240 - 6 - 8, 80, 6));
+        // Twice the resolution: everything doubles
+        assert_eq!(corner.rect(640, 480), (640 - 160 - 16, 480 - 12 - 16, 160, 12));
+
+        let centered = HudElement::new(HudElementKind::Prompt, HudAnchor::Center, (0, 40));
+        assert_eq!(centered.rect(320, 240), (100, 115 + 40, 120, 9));
+
+        let layout = HudLayout::default();
+        assert_eq!(layout.element_at(10, 10, 320, 240), Some(0));
+        assert_eq!(layout.element_at(300, 10, 320, 240), None);
+    }
+
+    #[test]
+    fn test_draw_respects_visibility() {
+        let mut layout = HudLayout::default();
+        let mut fb = Framebuffer::new(320, 240);
+        let frame = HudFrame { health: Some((50, 100)), ..HudFrame::default() };
+        let (x, y, _, _) = layout.element(HudElementKind::Health).unwrap().rect(320, 240);
+        let pixel = |fb: &Framebuffer| {
+            let idx = (((y + 2) as usize) * fb.width + (x + 2) as usize) * 4;
+            fb.pixels[idx]
+        };
+
+        draw(&mut fb, &layout, &frame);
+        assert_eq!(pixel(&fb), HEALTH_COLOR.r);
+
+        let mut fb = Framebuffer::new(320, 240);
+        layout.elements[0].visible = false;
+        draw(&mut fb, &layout, &frame);
+        assert_eq!(pixel(&fb), 0);
+    }
+
+    #[test]
+    fn test_status_icons() {
+        let mut world = World::new();
+        let player = world.spawn();
+        assert!(status_icons(&world, player).is_empty());
+
+        let mut effects = crate::game::status::StatusEffects::default();
+        let poison = crate::asset::StatusEffectDef::preset("poison").unwrap();
+        let bleed = crate::asset::StatusEffectDef::preset("bleed").unwrap();
+        effects.apply("poison", &poison, 0.0);
+        effects.apply("poison", &poison, 0.0);
+        effects.apply("bleed", &bleed, bleed.buildup / 4.0);
+        world.status_effects.insert(player, effects);
+
+        let icons = status_icons(&world, player);
+        assert_eq!(icons.len(), 2);
+        assert_eq!((icons[0].icon.as_str(), icons[0].stacks, icons[0].fraction, icons[0].building), ("PSN", 2, 1.0, false));
+        assert_eq!((icons[1].fraction, icons[1].building), (0.25, true));
+    }
+
+    #[test]
+    fn test_text_width() {
+        assert_eq!(text_width(""), 0);
+        assert_eq!(text_width("[E] OPEN"), 31);
+    }
+
+    #[test]
+    fn test_accents_fold_to_base_letters() {
+        assert_eq!(glyph('é'), glyph('E'));
+        assert_eq!(glyph('Ñ'), glyph('N'));
+        assert_eq!(glyph('¡'), glyph('!'));
+        // Anything else still draws as '?'
+        assert_eq!(glyph('Ж'), glyph('?'));
+    }
+}
@@ -0,0 +1,392 @@
+//! Entity Inspector
+//!
+//! A debug overlay for the Test tab: the world's live entities with the
+//! components they carry, and for the selected one its transform, velocity,
+//! health, controller, AI state and collision shapes (drawn as wireframes in
+//! the 3D view). The inspector can also hold the simulation still and step
+//! it a frame at a time, so a bug can be watched as it happens.
+//!
+//! F3 opens it (or the options menu's "Inspector"); while it's open F5
+//! pauses/resumes and F6 steps one frame.
+
+use super::components::{AiState, CollisionShape};
+use super::console::CONSOLE_STEP_DELTA;
+use super::{Entity, World};
+use crate::rasterizer::Vec3;
+
+/// Simulation step taken by `step` (same fixed step as the test console)
+pub const INSPECTOR_STEP_DELTA: f32 = CONSOLE_STEP_DELTA;
+/// Segments in a wireframe circle
+const CIRCLE_SEGMENTS: usize = 16;
+
+/// Inspector overlay state (kept across play sessions)
+#[derive(Debug, Clone, Default)]
+pub struct Inspector {
+    /// Overlay shown
+    pub open: bool,
+    /// Simulation held still
+    pub paused: bool,
+    /// Frames queued by `step` while paused
+    steps: u32,
+    /// Entity whose components are shown
+    pub selected: Option<Entity>,
+    /// Entity list scroll offset (rows)
+    pub scroll: usize,
+}
+
+impl Inspector {
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.steps = 0;
+    }
+
+    /// Advance one frame (pauses first if running)
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.steps += 1;
+    }
+
+    /// Delta to simulate this frame; None holds the world still (paused
+    /// without a queued step). Steps use a fixed delta so they repeat.
+    pub fn frame_delta(&mut self, delta: f32) -> Option<f32> {
+        if !self.paused {
+            return Some(delta);
+        }
+        if self.steps == 0 {
+            return None;
+        }
+        self.steps -= 1;
+        Some(INSPECTOR_STEP_DELTA)
+    }
+
+    /// Drop the selection once its entity is gone
+    pub fn validate(&mut self, world: &World) {
+        if self.selected.is_some_and(|e| !world.is_alive(e)) {
+            self.selected = None;
+        }
+    }
+}
+
+/// Names of the components an entity has, in `World` field order
+pub fn component_names(world: &World, entity: Entity) -> Vec<&'static str> {
+    let checks = [
+        ("Transform", world.transforms.contains(entity)),
+        ("GlobalTransform", world.global_transforms.contains(entity)),
+        ("Parent", world.parents.contains(entity)),
+        ("Children", world.children.contains(entity)),
+        ("Velocity", world.velocities.contains(entity)),
+        ("Controller", world.controllers.contains(entity)),
+        ("Health", world.health.contains(entity)),
+        ("Stamina", world.stamina.contains(entity)),
+        ("Poise", world.poise.contains(entity)),
+        ("Combat", world.combat.contains(entity)),
+        ("Hitbox", world.hitboxes.contains(entity)),
+        ("Hurtbox", world.hurtboxes.contains(entity)),
+        ("StatusEffects", world.status_effects.contains(entity)),
+        ("Player", world.players.contains(entity)),
+        ("Enemy", world.enemies.contains(entity)),
+        ("Projectile", world.projectiles.contains(entity)),
+        ("Item", world.items.contains(entity)),
+        ("Door", world.doors.contains(entity)),
+        ("PathFollower", world.path_followers.contains(entity)),
+        ("MovingPlatform", world.moving_platforms.contains(entity)),
+        ("Patrol", world.patrols.contains(entity)),
+        ("Key", world.keys.contains(entity)),
+        ("Checkpoint", world.checkpoints.contains(entity)),
+        ("SpawnPoint", world.spawn_points.contains(entity)),
+    ];
+    checks.iter().filter(|(_, has)| *has).map(|(name, _)| *name).collect()
+}
+
+/// One-line list label: index and what kind of entity it is
+pub fn entity_label(world: &World, entity: Entity) -> String {
+    let kind = if world.players.contains(entity) {
+        "Player".to_string()
+    } else if let Some(enemy) = world.enemies.get(entity) {
+        format!("Enemy ({:?})", enemy.enemy_type)
+    } else if let Some(item) = world.items.get(entity) {
+        format!("Item ({:?})", item.item_type)
+    } else if world.projectiles.contains(entity) {
+        "Projectile".to_string()
+    } else if world.doors.contains(entity) {
+        "Door".to_string()
+    } else if world.path_followers.contains(entity) {
+        "Path follower".to_string()
+    } else if world.moving_platforms.contains(entity) {
+        "Moving platform".to_string()
+    } else if world.checkpoints.contains(entity) {
+        "Checkpoint".to_string()
+    } else if world.spawn_points.contains(entity) {
+        "Spawn point".to_string()
+    } else {
+        "Entity".to_string()
+    };
+    format!("#{} {}", entity.index(), kind)
+}
+
+/// What an enemy is doing. Enemies have no AI component yet: ones on a path
+/// or with a patrol radius patrol, the rest idle, and dead ones are dead.
+pub fn ai_state(world: &World, entity: Entity) -> Option<AiState> {
+    world.enemies.get(entity)?;
+    Some(if world.health.get(entity).is_some_and(|h| h.current <= 0) {
+        AiState::Dead
+    } else if world.path_followers.contains(entity) || world.patrols.contains(entity) {
+        AiState::Patrol
+    } else {
+        AiState::Idle
+    })
+}
+
+/// Field rows (label, value) for the selected entity
+pub fn inspect(world: &World, entity: Entity) -> Vec<(&'static str, String)> {
+    let mut rows = vec![("Entity", format!("{} (gen {})", entity.index(), entity.generation()))];
+    if let Some(t) = world.transforms.get(entity) {
+        rows.push(("Position", format!("{:.0}, {:.0}, {:.0}", t.position.x, t.position.y, t.position.z)));
+        rows.push(("Rotation", format!("{:.0}, {:.0}, {:.0}", t.rotation.x, t.rotation.y, t.rotation.z)));
+        if t.scale != 1.0 {
+            rows.push(("Scale", format!("{:.2}", t.scale)));
+        }
+    }
+    if let Some(parent) = world.parents.get(entity) {
+        rows.push(("Parent", format!("#{}", parent.index())));
+    }
+    if let Some(v) = world.velocities.get(entity) {
+        let v = v.0;
+        rows.push(("Velocity", format!("{:.0}, {:.0}, {:.0}", v.x, v.y, v.z)));
+    }
+    if let Some(c) = world.controllers.get(entity) {
+        let state = if c.climbing.is_some() {
+            "climbing"
+        } else if c.swimming.is_some() {
+            "swimming"
+        } else if c.grounded {
+            "grounded"
+        } else {
+            "airborne"
+        };
+        rows.push(("Controller", format!("{}, room {}", state, c.current_room)));
+        rows.push(("Vert vel", format!("{:.1}", c.vertical_velocity)));
+        rows.push(("Facing", format!("{:.0} deg", c.facing.to_degrees())));
+    }
+    if let Some(h) = world.health.get(entity) {
+        let iframes = if h.invincible_frames > 0 { format!(" (invincible {})", h.invincible_frames) } else { String::new() };
+        rows.push(("Health", format!("{}/{}{}", h.current, h.max, iframes)));
+    }
+    if let Some(s) = world.stamina.get(entity) {
+        rows.push(("Stamina", format!("{:.0}/{:.0}", s.current, s.max)));
+    }
+    if let Some(p) = world.poise.get(entity) {
+        rows.push(("Poise", format!("{:.0}/{:.0}", p.current, p.max)));
+    }
+    if let Some(c) = world.combat.get(entity) {
+        let state = match (&c.swing, &c.dodge) {
+            _ if c.staggered() => format!("staggered {:.1}s", c.stagger),
+            (Some(swing), _) => format!("swinging, frame {}", swing.attack.frame_at(swing.elapsed)),
+            (None, Some(_)) => "dodging".to_string(),
+            _ if c.guarding => "guarding".to_string(),
+            _ => "ready".to_string(),
+        };
+        rows.push(("Combat", state));
+    }
+    if let Some(ai) = ai_state(world, entity) {
+        rows.push(("AI", format!("{:?}", ai)));
+    }
+    if let Some(f) = world.path_followers.get(entity) {
+        rows.push(("Path", format!("{} points, {:.0} travelled", f.points.len(), f.travelled)));
+    }
+    if let Some(p) = world.moving_platforms.get(entity) {
+        let state = if p.waiting > 0.0 {
+            format!("waiting {:.1}s", p.waiting)
+        } else if p.on_call && !p.called {
+            "stopped".to_string()
+        } else {
+            "moving".to_string()
+        };
+        rows.push(("Platform", format!("{:.0} travelled, {}", p.travelled, state)));
+    }
+    if let Some(p) = world.patrols.get(entity) {
+        let walking = if p.path.is_empty() { format!("waiting {:.1}s", p.wait.max(0.0)) } else { format!("{} points left", p.path.len()) };
+        rows.push(("Patrol", format!("r{:.0}, {}", p.radius, walking)));
+    }
+    if let Some(d) = world.doors.get(entity) {
+        let state = if d.is_open { "open" } else { "closed" };
+        rows.push(("Door", format!("{} ({:.0}%)", state, d.open_amount * 100.0)));
+    }
+    if let Some(h) = world.hitboxes.get(entity) {
+        let active = if h.active { "" } else { ", inactive" };
+        rows.push(("Hitbox", format!("{}, {} dmg, {:?}{}", shape_label(&h.shape), h.damage, h.team, active)));
+    }
+    if let Some(h) = world.hurtboxes.get(entity) {
+        rows.push(("Hurtbox", format!("{}, x{:.1}", shape_label(&h.shape), h.damage_multiplier)));
+    }
+    if let Some(s) = world.status_effects.get(entity).filter(|s| !s.is_empty()) {
+        let active = s.active.iter().map(|a| format!("{} x{} {:.1}s", a.name, a.stacks, a.remaining));
+        let building = s.buildup.iter().map(|b| format!("{} {:.0}/{:.0}", b.name, b.amount, b.def.buildup));
+        rows.push(("Status", active.chain(building).collect::<Vec<_>>().join(", ")));
+    }
+    rows
+}
+
+fn shape_label(shape: &CollisionShape) -> String {
+    match shape {
+        CollisionShape::Sphere { radius } => format!("sphere r{:.0}", radius),
+        CollisionShape::Box { half_extents: e } => format!("box {:.0}x{:.0}x{:.0}", e.x * 2.0, e.y * 2.0, e.z * 2.0),
+        CollisionShape::Capsule { radius, height } => format!("capsule r{:.0} h{:.0}", radius, height),
+    }
+}
+
+/// What a collision wireframe shows (picks its color)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShapeKind {
+    Controller,
+    Hitbox,
+    Hurtbox,
+}
+
+/// World-space line segments outlining an entity's collision shapes: the
+/// controller cylinder (standing on the position) and hit/hurtboxes
+/// (centered on it)
+pub fn collision_lines(world: &World, entity: Entity) -> Vec<(ShapeKind, Vec3, Vec3)> {
+    let Some(position) = world.transforms.get(entity).map(|t| t.position) else {
+        return Vec::new();
+    };
+    let mut lines = Vec::new();
+    if let Some(c) = world.controllers.get(entity) {
+        for (a, b) in cylinder_lines(position, c.radius, c.height) {
+            lines.push((ShapeKind::Controller, a, b));
+        }
+    }
+    let shapes = [
+        world.hitboxes.get(entity).filter(|h| h.active).map(|h| (ShapeKind::Hitbox, h.shape)),
+        world.hurtboxes.get(entity).map(|h| (ShapeKind::Hurtbox, h.shape)),
+    ];
+    for (kind, shape) in shapes.into_iter().flatten() {
+        for (a, b) in shape_lines(&shape, position) {
+            lines.push((kind, a, b));
+        }
+    }
+    lines
+}
+
+/// Outline of a hit/hurtbox shape centered on `center`
+fn shape_lines(shape: &CollisionShape, center: Vec3) -> Vec<(Vec3, Vec3)> {
+    match *shape {
+        CollisionShape::Sphere { radius } => {
+            let mut lines = circle_lines(center, radius, Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+            lines.extend(circle_lines(center, radius, Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)));
+            lines.extend(circle_lines(center, radius, Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 1.0, 0.0)));
+            lines
+        }
+        CollisionShape::Box { half_extents } => box_lines(center - half_extents, center + half_extents),
+        CollisionShape::Capsule { radius, height } => cylinder_lines(center - Vec3::new(0.0, height * 0.5, 0.0), radius, height),
+    }
+}
+
+/// Circle in the plane spanned by unit vectors `u` and `v`
+fn circle_lines(center: Vec3, radius: f32, u: Vec3, v: Vec3) -> Vec<(Vec3, Vec3)> {
+    let point = |i: usize| {
+        let angle = i as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+        center + u * (radius * angle.cos()) + v * (radius * angle.sin())
+    };
+    (0..CIRCLE_SEGMENTS).map(|i| (point(i), point(i + 1))).collect()
+}
+
+/// Upright cylinder standing on `base`: two rings and four posts
+fn cylinder_lines(base: Vec3, radius: f32, height: f32) -> Vec<(Vec3, Vec3)> {
+    let (u, v) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+    let top = base + Vec3::new(0.0, height, 0.0);
+    let mut lines = circle_lines(base, radius, u, v);
+    lines.extend(circle_lines(top, radius, u, v));
+    for offset in [u * radius, u * -radius, v * radius, v * -radius] {
+        lines.push((base + offset, top + offset));
+    }
+    lines
+}
+
+/// The 12 edges of an axis-aligned box
+fn box_lines(min: Vec3, max: Vec3) -> Vec<(Vec3, Vec3)> {
+    let corner = |i: usize| Vec3::new(
+        if i & 1 == 0 { min.x } else { max.x },
+        if i & 2 == 0 { min.y } else { max.y },
+        if i & 4 == 0 { min.z } else { max.z },
+    );
+    let mut lines = Vec::with_capacity(12);
+    for i in 0..8 {
+        // Each edge once: from the corner with the bit clear to the one with it set
+        for bit in [1, 2, 4] {
+            if i & bit == 0 {
+                lines.push((corner(i), corner(i | bit)));
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::components::{EnemyType, Hurtbox};
+
+    #[test]
+    fn test_pause_and_step() {
+        let mut inspector = Inspector::default();
+        assert_eq!(inspector.frame_delta(0.03), Some(0.03));
+        inspector.toggle_pause();
+        assert_eq!(inspector.frame_delta(0.03), None);
+        inspector.step();
+        inspector.step();
+        assert_eq!(inspector.frame_delta(0.03), Some(INSPECTOR_STEP_DELTA));
+        assert_eq!(inspector.frame_delta(0.03), Some(INSPECTOR_STEP_DELTA));
+        assert_eq!(inspector.frame_delta(0.03), None);
+        inspector.toggle_pause();
+        assert_eq!(inspector.frame_delta(0.03), Some(0.03));
+        // Stepping while running pauses
+        inspector.step();
+        assert!(inspector.paused);
+        assert_eq!(inspector.frame_delta(0.03), Some(INSPECTOR_STEP_DELTA));
+        assert_eq!(inspector.frame_delta(0.03), None);
+    }
+
+    #[test]
+    fn test_inspect_enemy() {
+        let mut world = World::new();
+        let player = world.spawn_player(Vec3::ZERO, 100, &crate::world::PlayerSettings::default());
+        let enemy = world.spawn_enemy(Vec3::new(100.0, 0.0, 200.0), 30, EnemyType::Grunt);
+        world.hurtboxes.insert(enemy, Hurtbox::sphere(50.0));
+
+        assert_eq!(entity_label(&world, player), format!("#{} Player", player.index()));
+        assert_eq!(entity_label(&world, enemy), format!("#{} Enemy (Grunt)", enemy.index()));
+        let names = component_names(&world, enemy);
+        assert!(names.contains(&"Enemy") && names.contains(&"Hurtbox") && !names.contains(&"Player"));
+
+        let rows = inspect(&world, enemy);
+        let value = |label: &str| rows.iter().find(|(l, _)| *l == label).map(|(_, v)| v.clone());
+        assert_eq!(value("Position").as_deref(), Some("100, 0, 200"));
+        assert_eq!(value("Health").as_deref(), Some("30/30"));
+        assert_eq!(value("AI").as_deref(), Some("Idle"));
+        assert!(value("Hurtbox").is_some_and(|v| v.starts_with("sphere r50")));
+        assert!(value("AI").is_some() && inspect(&world, player).iter().all(|(l, _)| *l != "AI"));
+
+        world.health.get_mut(enemy).unwrap().current = 0;
+        assert!(matches!(ai_state(&world, enemy), Some(AiState::Dead)));
+    }
+
+    #[test]
+    fn test_collision_lines_and_selection() {
+        let mut world = World::new();
+        let player = world.spawn_player(Vec3::ZERO, 100, &crate::world::PlayerSettings::default());
+        let lines = collision_lines(&world, player);
+        // Controller cylinder (two rings and four posts) and the hurtbox sphere (three rings)
+        let controller = lines.iter().filter(|(kind, _, _)| *kind == ShapeKind::Controller).count();
+        assert_eq!(controller, CIRCLE_SEGMENTS * 2 + 4);
+        assert_eq!(lines.len() - controller, CIRCLE_SEGMENTS * 3);
+        assert_eq!(box_lines(Vec3::ZERO, Vec3::new(1.0, 1.0, 1.0)).len(), 12);
+
+        let mut inspector = Inspector { selected: Some(player), ..Default::default() };
+        inspector.validate(&world);
+        assert_eq!(inspector.selected, Some(player));
+        world.despawn_immediate(player);
+        inspector.validate(&world);
+        assert_eq!(inspector.selected, None);
+    }
+}
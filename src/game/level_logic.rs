@@ -0,0 +1,1879 @@
+//! Level Logic
+//!
+//! Connects placed asset instances to the script runtime while playing:
+//! - Trigger instances fire `enter`/`exit` (and their named on_enter/on_exit events)
+//!   when the player walks into or out of the trigger's sector; trigger volumes
+//!   drawn in the editor do the same for their box
+//! - Door instances become ECS door entities that open with Interact, their
+//!   linked trigger, or a script. A locked door needs its key pickup collected
+//!   first. Doors slide or swing open over `open_time` (`door_poses()`) and
+//!   block characters until fully open (`door_blockers()`)
+//! - Pickup instances are collected by walking over them; keys go into the
+//!   player's inventory. Weapon and Armor instances (other than the player
+//!   start) are picked up the same way and wait in `take_gear()` for the
+//!   player's equipment
+//! - Instances attached to a path (enemies, moving platforms) become path
+//!   follower entities; enemies with a patrol radius and no path wander
+//!   around where they were placed (`Patrol`, walking navgraph paths) and
+//!   fight the player (`combat`). `path_poses()` places both for rendering
+//! - MovingPlatform instances become platform entities riding their path (or
+//!   rising to their elevator offset) and carrying the player (see `platform`).
+//!   Ones with a trigger or lever wait to be called: entering the trigger, or
+//!   Interact next to a lever platform, sends them to their next stop
+//!
+//! - Hazard instances hurt the player standing in them; status effects (see
+//!   `status`) come from the asset's StatusEffect: hazards apply it on every
+//!   hit, pickups when collected, enemies on whatever they damage
+//! - Destructible instances take damage from the player's attacks in front of
+//!   them (`request_attack`). Broken props burst into debris (`take_debris()`),
+//!   leave their drop behind as a pickup (`dropped_assets()`) and swap to their
+//!   broken mesh (`broken_objects()`)
+//!
+//! Collected pickups and gear, script-disabled instances, slain enemies and broken props without a
+//! broken mesh are hidden from rendering via `hidden_objects()`. A `weather` command overrides the level's
+//! weather preset until play stops (`weather_override()`).
+//!
+//! Tutorial hints: `prompt` shows a button prompt until the player performs its
+//! action (`dismiss_prompts`), `tutorial` queues a popup that shows once per
+//! play session and waits for `dismiss_tutorial`.
+//!
+//! Cutscenes: a `cutscene` command or a trigger volume's cutscene (first enter
+//! only) starts one of the level's cutscenes (`cutscene()`). Its moves slide
+//! instances for rendering (`cutscene_poses()`), its music waits in
+//! `take_music()` for the audio side (along with scripts' `music` commands),
+//! and `request_skip` ends it early. When it
+//! ends, scripts hear `on cutscene_end <name>`.
+//!
+//! Dialogue: Interact next to an instance with a Dialogue component (and no
+//! door or lever in reach) starts a conversation (`dialogue()`). While it
+//! runs Interact advances it instead and `move_choice` picks answers; its
+//! effects set script variables. When it ends, scripts hear
+//! `on dialogue_end <name>`.
+
+use std::collections::{HashMap, HashSet};
+
+use super::components::{Combat, Door, Health, Item, ItemType, MovingPlatform, PathFollower, Patrol, Poise};
+use super::cutscene::{CutsceneAction, CutscenePlayer};
+use super::dialogue::DialoguePlayer;
+use super::equipment::Gear;
+use super::music::MusicRequest;
+use super::event::{AnimationEvent, DoorEvent, Events, ItemCollectedEvent, PropBrokenEvent, SoundEvent};
+use super::nav::PATROL_SPEED;
+use super::script::{ScriptAction, ScriptError, ScriptEvent, ScriptRuntime};
+use super::status::{self, StatusLibrary};
+use super::{Entity, World};
+use crate::asset::{AssetComponent, AssetLibrary, AttackDef, CollisionShapeDef, DialogueNode, DoorMotion, ParticleEmitterDef};
+use crate::input::Action;
+use crate::rasterizer::Vec3;
+use crate::world::{Cutscene, Level, PathMode, WeatherPreset, SECTOR_SIZE};
+
+/// How close (horizontally) the player must get to collect a pickup
+const PICKUP_RADIUS: f32 = SECTOR_SIZE * 0.5;
+/// Vertical reach of triggers and pickups above/below the instance
+const VERTICAL_REACH: f32 = SECTOR_SIZE * 1.5;
+/// How close (horizontally) the player must be to a door to use it
+const INTERACT_RADIUS: f32 = SECTOR_SIZE;
+/// How far (horizontally) the player's attacks reach
+const ATTACK_REACH: f32 = SECTOR_SIZE;
+/// Damage each attack deals to breakable props
+pub const ATTACK_DAMAGE: i32 = 10;
+/// How long script messages stay on screen (seconds)
+const MESSAGE_DURATION: f32 = 3.0;
+
+/// Instance address: (room index, object index)
+pub type ObjectRef = (usize, usize);
+
+/// Area a trigger reacts to
+#[derive(Debug, Clone, Copy)]
+enum ZoneShape {
+    /// Trigger instance: the sector around its position
+    Sector(Vec3),
+    /// Trigger volume: world-space min/max
+    Volume(Vec3, Vec3),
+}
+
+impl ZoneShape {
+    fn contains(&self, point: Vec3) -> bool {
+        match *self {
+            ZoneShape::Sector(center) => zone_contains(center, point),
+            ZoneShape::Volume(min, max) => {
+                point.x >= min.x && point.x <= max.x
+                    && point.y >= min.y && point.y <= max.y
+                    && point.z >= min.z && point.z <= max.z
+            }
+        }
+    }
+}
+
+/// A trigger instance or trigger volume
+#[derive(Debug, Clone)]
+struct TriggerZone {
+    /// Instance that placed it (None for volumes, which can't be disabled)
+    object: Option<ObjectRef>,
+    name: String,
+    trigger_id: String,
+    on_enter: Option<String>,
+    on_exit: Option<String>,
+    /// Cutscene to play on the next enter (cleared once played)
+    cutscene: Option<String>,
+    shape: ZoneShape,
+    inside: bool,
+}
+
+#[derive(Debug, Clone)]
+struct DoorInstance {
+    object: ObjectRef,
+    name: String,
+    entity: Entity,
+    /// Key pickup (instance name) needed to open; None = unlocked
+    required_key: Option<String>,
+    /// Trigger id that opens the door when entered
+    open_trigger: Option<String>,
+    motion: DoorMotion,
+    open_time: f32,
+    position: Vec3,
+    facing: f32,
+    /// World-space box that blocks movement until the door is fully open
+    bounds: (Vec3, Vec3),
+}
+
+#[derive(Debug, Clone)]
+struct PickupInstance {
+    object: ObjectRef,
+    name: String,
+    entity: Option<Entity>,
+    item_type: ItemType,
+    position: Vec3,
+    respawn_time: Option<f32>,
+    /// Seconds until respawn while collected
+    respawn_in: Option<f32>,
+    /// Status effect given to the collector (the asset's name)
+    effect: Option<String>,
+    /// Pickup asset drawn in place of the instance (loot from a broken prop)
+    dropped: Option<u64>,
+}
+
+/// A weapon or armor lying in the level
+#[derive(Debug, Clone)]
+struct GearInstance {
+    object: ObjectRef,
+    name: String,
+    position: Vec3,
+    /// The asset's weapon and/or armor
+    gear: Vec<Gear>,
+    collected: bool,
+}
+
+/// Pickup asset a broken prop leaves behind
+#[derive(Debug, Clone)]
+struct Loot {
+    asset_id: u64,
+    name: String,
+    item_type: ItemType,
+    effect: Option<String>,
+}
+
+/// A breakable prop
+#[derive(Debug, Clone)]
+struct DestructibleInstance {
+    object: ObjectRef,
+    name: String,
+    entity: Entity,
+    position: Vec3,
+    /// Particles burst when it breaks
+    debris: Option<ParticleEmitterDef>,
+    drop: Option<Loot>,
+    /// Swaps to a broken mesh instead of disappearing
+    has_broken_mesh: bool,
+    broken: bool,
+}
+
+/// A trap or harmful area
+#[derive(Debug, Clone)]
+struct HazardInstance {
+    object: ObjectRef,
+    position: Vec3,
+    radius: f32,
+    interval: f32,
+    damage: i32,
+    buildup: f32,
+    /// Status effect applied on each hit (the asset's name)
+    effect: Option<String>,
+    /// Seconds until the next hit while the player is inside
+    cooldown: f32,
+}
+
+/// A path follower or patrolling enemy
+#[derive(Debug, Clone)]
+struct PathMover {
+    object: ObjectRef,
+    entity: Entity,
+    /// Where the instance stands in the editor
+    position: Vec3,
+    facing: f32,
+}
+
+/// A moving platform and what calls it
+#[derive(Debug, Clone)]
+struct PlatformInstance {
+    object: ObjectRef,
+    entity: Entity,
+    /// Trigger id that calls the platform when entered
+    activate_trigger: Option<String>,
+    /// Interact next to it calls it
+    lever: bool,
+}
+
+/// An instance the player can talk to
+#[derive(Debug, Clone)]
+struct TalkerInstance {
+    object: ObjectRef,
+    name: String,
+    /// Where it was placed (path followers and patrols are found by `object`)
+    position: Vec3,
+    nodes: Vec<DialogueNode>,
+}
+
+/// An instance sliding to a new offset for a cutscene
+#[derive(Debug, Clone)]
+struct CutsceneMove {
+    object: ObjectRef,
+    from: Vec3,
+    to: Vec3,
+    elapsed: f32,
+    duration: f32,
+}
+
+/// Script-driven level state for one play session
+#[derive(Debug, Clone, Default)]
+pub struct LevelLogic {
+    started: bool,
+    triggers: Vec<TriggerZone>,
+    doors: Vec<DoorInstance>,
+    pickups: Vec<PickupInstance>,
+    gear: Vec<GearInstance>,
+    hazards: Vec<HazardInstance>,
+    destructibles: Vec<DestructibleInstance>,
+    movers: Vec<PathMover>,
+    platforms: Vec<PlatformInstance>,
+    talkers: Vec<TalkerInstance>,
+    /// Patrolling enemies killed in combat (no longer drawn)
+    slain: HashSet<ObjectRef>,
+    /// Status effects by name: presets and StatusEffect assets
+    statuses: StatusLibrary,
+    /// Enemy entities and the status effect they inflict on hit
+    inflicts: HashMap<Entity, String>,
+    /// Key pickups collected this session (instance names)
+    keys: Vec<String>,
+    /// Interact was pressed since the last update
+    interact: bool,
+    /// Attack was pressed since the last update
+    attack: bool,
+    /// Gear picked up since the last `take_gear`
+    found_gear: Vec<Gear>,
+    /// Debris bursts of props broken since the last `take_debris`
+    debris: Vec<(ParticleEmitterDef, Vec3)>,
+    /// All named instances, for enable/disable
+    named: Vec<(String, ObjectRef)>,
+    disabled: HashSet<ObjectRef>,
+    /// On-screen messages (text, seconds left)
+    messages: Vec<(String, f32)>,
+    /// Weather preset set by a script (replaces the level's)
+    weather: Option<WeatherPreset>,
+    /// Button prompts shown until their action is performed
+    prompts: Vec<(Action, String)>,
+    /// Tutorial popups waiting to be shown (front is on screen)
+    tutorials: Vec<String>,
+    /// Tutorial ids already shown this session
+    seen_tutorials: HashSet<String>,
+    /// The level's cutscenes
+    cutscenes: Vec<Cutscene>,
+    /// Cutscene playing, if any
+    cutscene: Option<CutscenePlayer>,
+    /// Interact was pressed to skip the cutscene since the last update
+    skip: bool,
+    /// Instances moved by cutscenes (offset from where they were placed)
+    offsets: HashMap<ObjectRef, Vec3>,
+    moves: Vec<CutsceneMove>,
+    /// Music changes cutscenes and scripts asked for, until `take_music`
+    music: Vec<MusicRequest>,
+    /// Conversation in progress, if any
+    dialogue: Option<DialoguePlayer>,
+    pub scripts: ScriptRuntime,
+    pub errors: Vec<ScriptError>,
+}
+
+/// The Pickup component of the asset named `name`, as loot
+fn loot(asset_library: &AssetLibrary, name: &str) -> Option<Loot> {
+    let asset = asset_library.get(name)?;
+    let item_type = asset.components.iter().find_map(|c| match c {
+        AssetComponent::Pickup { item_type, .. } => Some(*item_type),
+        _ => None,
+    })?;
+    Some(Loot {
+        asset_id: asset.id,
+        name: asset.name.clone(),
+        item_type,
+        effect: asset.status_effect().map(|_| asset.name.clone()),
+    })
+}
+
+/// Is `target` within attack reach of `pos`, in front of a character facing `facing`
+/// (yaw, 0 = +Z)? Up close anything counts; further out it must be within 60° of facing.
+fn in_swing(pos: Vec3, facing: f32, target: Vec3) -> bool {
+    let dx = target.x - pos.x;
+    let dz = target.z - pos.z;
+    let distance = (dx * dx + dz * dz).sqrt();
+    if distance > ATTACK_REACH || (target.y - pos.y).abs() > VERTICAL_REACH {
+        return false;
+    }
+    let (sin_f, cos_f) = facing.sin_cos();
+    distance <= SECTOR_SIZE * 0.25 || (dx * sin_f + dz * cos_f) / distance >= 0.5
+}
+
+/// Is `point` inside the sector-sized zone around `center`?
+fn zone_contains(center: Vec3, point: Vec3) -> bool {
+    let half = SECTOR_SIZE * 0.5;
+    (point.x - center.x).abs() <= half
+        && (point.z - center.z).abs() <= half
+        && (point.y - center.y).abs() <= VERTICAL_REACH
+}
+
+impl LevelLogic {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Has `start` run for this play session?
+    pub fn started(&self) -> bool {
+        self.started
+    }
+
+    /// Scan the level's instances, spawn doors/pickups/patrolling enemies into the world, compile
+    /// the level's scripts and run their `on start` handlers.
+    pub fn start(level: &Level, asset_library: &AssetLibrary, world: &mut World) -> Self {
+        let mut logic = Self { started: true, statuses: StatusLibrary::new(asset_library), ..Self::default() };
+
+        for (room_idx, room) in level.rooms.iter().enumerate() {
+            for (obj_idx, obj) in room.objects.iter().enumerate() {
+                if !obj.enabled {
+                    continue;
+                }
+                let Some(asset) = asset_library.get_by_id(obj.asset_id) else {
+                    continue;
+                };
+                let object = (room_idx, obj_idx);
+                let name = if obj.name.is_empty() { asset.name.clone() } else { obj.name.clone() };
+                let position = obj.world_position(room);
+                logic.named.push((name.clone(), object));
+                let effect = asset.status_effect().map(|_| asset.name.clone());
+                // The attached path, if the asset can follow one
+                let path = obj.path.as_ref().filter(|_| asset.can_follow_path()).and_then(|follow| {
+                    level.rooms.iter()
+                        .find_map(|r| r.paths.iter().find(|p| p.path_id == follow.path_id).map(|p| (r, p)))
+                        .map(|(path_room, path)| (follow, path_room, path))
+                });
+                // The player start's gear is carried from the start; enemies keep theirs
+                if !asset.has_spawn_point(true) && !asset.has_enemy() {
+                    let gear: Vec<Gear> = asset.components.iter()
+                        .filter_map(|c| Gear::from_component(&asset.name, c))
+                        .collect();
+                    if !gear.is_empty() {
+                        logic.gear.push(GearInstance { object, name: name.clone(), position, gear, collected: false });
+                    }
+                }
+
+                for component in &asset.components {
+                    match component {
+                        AssetComponent::Trigger { trigger_id, on_enter, on_exit } => {
+                            logic.triggers.push(TriggerZone {
+                                object: Some(object),
+                                name: name.clone(),
+                                trigger_id: trigger_id.clone(),
+                                on_enter: on_enter.clone(),
+                                on_exit: on_exit.clone(),
+                                cutscene: None,
+                                shape: ZoneShape::Sector(position),
+                                inside: false,
+                            });
+                        }
+                        AssetComponent::Door { .. } => {
+                            let AssetComponent::Door { required_key, start_open, motion, open_time, open_trigger } =
+                                obj.overrides.apply(component)
+                            else {
+                                continue;
+                            };
+                            let entity = world.spawn_at(position);
+                            world.doors.insert(entity, Door {
+                                is_open: start_open,
+                                required_key: None,
+                                open_amount: if start_open { 1.0 } else { 0.0 },
+                            });
+                            logic.doors.push(DoorInstance {
+                                object,
+                                name: name.clone(),
+                                entity,
+                                required_key,
+                                open_trigger,
+                                motion,
+                                open_time,
+                                position,
+                                facing: obj.facing,
+                                bounds: door_bounds(&asset.components, position, obj.facing),
+                            });
+                        }
+                        AssetComponent::Pickup { item_type, respawn_time } => {
+                            let entity = world.spawn_at(position);
+                            world.items.insert(entity, Item { item_type: *item_type });
+                            logic.pickups.push(PickupInstance {
+                                object,
+                                name: name.clone(),
+                                entity: Some(entity),
+                                item_type: *item_type,
+                                position,
+                                respawn_time: *respawn_time,
+                                respawn_in: None,
+                                effect: effect.clone(),
+                                dropped: None,
+                            });
+                        }
+                        AssetComponent::Hazard { radius, interval, damage, buildup } => {
+                            logic.hazards.push(HazardInstance {
+                                object,
+                                position,
+                                radius: *radius,
+                                interval: *interval,
+                                damage: *damage,
+                                buildup: *buildup,
+                                effect: effect.clone(),
+                                cooldown: 0.0,
+                            });
+                        }
+                        AssetComponent::Destructible { health, debris, drop, .. } => {
+                            let entity = world.spawn_at(position);
+                            world.health.insert(entity, Health::new((*health).max(1)));
+                            logic.destructibles.push(DestructibleInstance {
+                                object,
+                                name: name.clone(),
+                                entity,
+                                position,
+                                debris: ParticleEmitterDef::preset(debris),
+                                drop: drop.as_deref().and_then(|pickup| loot(asset_library, pickup)),
+                                has_broken_mesh: asset.broken_mesh().is_some(),
+                                broken: false,
+                            });
+                        }
+                        AssetComponent::Enemy { .. } if obj.path.is_none() => {
+                            let AssetComponent::Enemy { enemy_type, health, damage, patrol_radius, poise, attack } = obj.overrides.apply(component) else {
+                                continue;
+                            };
+                            if patrol_radius <= 0.0 {
+                                continue;
+                            }
+                            let entity = world.spawn_enemy(position, health, enemy_type);
+                            world.combat.insert(entity, Combat::new(damage, attack, AttackDef::heavy()));
+                            world.poise.insert(entity, Poise::new(poise));
+                            if let Some(transform) = world.transforms.get_mut(entity) {
+                                transform.rotation.y = obj.facing.to_degrees();
+                            }
+                            // Spots are picked around the floor below the instance
+                            let home = position - Vec3::new(0.0, obj.height, 0.0);
+                            let seed = (room_idx as u32).wrapping_mul(7919) ^ (obj_idx as u32).wrapping_mul(104_729);
+                            world.patrols.insert(entity, Patrol::new(home, patrol_radius, PATROL_SPEED, obj.height, seed));
+                            logic.movers.push(PathMover { object, entity, position, facing: obj.facing });
+                            if let Some(effect) = &effect {
+                                logic.inflicts.insert(entity, effect.clone());
+                            }
+                        }
+                        AssetComponent::MovingPlatform { .. } => {
+                            let AssetComponent::MovingPlatform { route, speed, wait, activate_trigger, lever } =
+                                obj.overrides.apply(component)
+                            else {
+                                continue;
+                            };
+                            // Path platforms ride from the first waypoint at the instance's height;
+                            // elevators go back and forth between the instance and its offset
+                            let (points, mode) = match (route.end_offset(obj.facing), path) {
+                                (Some([dx, dy, dz]), _) => (vec![position, position + Vec3::new(dx, dy, dz)], PathMode::PingPong),
+                                (None, Some((_, path_room, path))) => {
+                                    let lift = Vec3::new(0.0, obj.height, 0.0);
+                                    (path.world_points(path_room.position).into_iter().map(|p| p + lift).collect(), path.mode)
+                                }
+                                (None, None) => continue,
+                            };
+                            let Some(&start) = points.first() else {
+                                continue;
+                            };
+                            let deck = platform_deck(&asset.components, obj.facing);
+                            let on_call = activate_trigger.is_some() || lever;
+                            let entity = world.spawn_at(start);
+                            world.moving_platforms.insert(entity, MovingPlatform::new(points, mode, speed, wait, deck, on_call));
+                            logic.movers.push(PathMover { object, entity, position, facing: obj.facing });
+                            logic.platforms.push(PlatformInstance { object, entity, activate_trigger, lever });
+                        }
+                        AssetComponent::Dialogue { nodes } => {
+                            logic.talkers.push(TalkerInstance { object, name: name.clone(), position, nodes: nodes.clone() });
+                        }
+                        _ => {}
+                    }
+                }
+
+                // Follow the attached path from its first waypoint, at the instance's height
+                // (moving platforms ride their path themselves)
+                if let Some((follow, path_room, path)) = path.filter(|_| !asset.has_moving_platform()) {
+                    let lift = Vec3::new(0.0, obj.height, 0.0);
+                    let points: Vec<Vec3> = path.world_points(path_room.position).into_iter().map(|p| p + lift).collect();
+                    if let Some(&start) = points.first() {
+                        let entity = world.spawn_at(start);
+                        if let Some(transform) = world.transforms.get_mut(entity) {
+                            transform.rotation.y = obj.facing.to_degrees();
+                        }
+                        world.path_followers.insert(entity, PathFollower {
+                            points,
+                            mode: path.mode,
+                            speed: follow.speed,
+                            travelled: 0.0,
+                            face_travel: asset.has_enemy(),
+                        });
+                        logic.movers.push(PathMover { object, entity, position, facing: obj.facing });
+                        if let Some(effect) = effect.as_ref().filter(|_| asset.has_enemy()) {
+                            logic.inflicts.insert(entity, effect.clone());
+                        }
+                    }
+                }
+            }
+
+            for volume in &room.triggers {
+                let (min, max) = volume.world_bounds(room.position);
+                logic.triggers.push(TriggerZone {
+                    object: None,
+                    name: volume.trigger_id.clone(),
+                    trigger_id: volume.trigger_id.clone(),
+                    on_enter: volume.on_enter.clone(),
+                    on_exit: volume.on_exit.clone(),
+                    cutscene: volume.cutscene.clone(),
+                    shape: ZoneShape::Volume(min, max),
+                    inside: false,
+                });
+            }
+        }
+
+        logic.cutscenes = level.cutscenes.clone();
+        let (scripts, errors) = ScriptRuntime::compile(&level.scripts);
+        logic.scripts = scripts;
+        logic.errors = errors;
+        logic.scripts.fire(&ScriptEvent::Start);
+        logic
+    }
+
+    /// Try the nearest door (or lever, or NPC) on the next `update`, or advance the
+    /// conversation (the player pressed Interact)
+    pub fn request_interact(&mut self) {
+        self.interact = true;
+    }
+
+    /// Swing at props in front of the player on the next `update` (the player pressed Attack)
+    pub fn request_attack(&mut self) {
+        self.attack = true;
+    }
+
+    /// End the playing cutscene on the next `update`, if it can be skipped
+    pub fn request_skip(&mut self) {
+        if self.cutscene.as_ref().is_some_and(|c| c.skippable()) {
+            self.skip = true;
+        }
+    }
+
+    /// Run one frame: triggers, pickups, doors, timers, then apply script actions
+    pub fn update(&mut self, world: &mut World, events: &mut Events, player: Option<(Entity, Vec3)>, delta: f32) {
+        if !self.started {
+            return;
+        }
+        let mut interact = std::mem::take(&mut self.interact);
+        let attack = std::mem::take(&mut self.attack);
+
+        // A conversation takes Interact for itself
+        if let Some(dialogue) = self.dialogue.as_mut() {
+            dialogue.update(delta);
+            if std::mem::take(&mut interact) {
+                dialogue.advance(&mut self.scripts);
+            }
+            if dialogue.finished() {
+                let name = dialogue.name().to_string();
+                self.dialogue = None;
+                self.scripts.fire(&ScriptEvent::DialogueEnd(name));
+            }
+        }
+
+        // Enemies inflict their status effect on what they hit; the hit's damage is the buildup
+        let hits: Vec<(Entity, String, i32)> = events.damage.iter()
+            .filter_map(|hit| Some((hit.target, self.inflicts.get(&hit.source?)?.clone(), hit.amount)))
+            .collect();
+        for (target, name, amount) in hits {
+            if let Some(def) = self.statuses.get(&name) {
+                status::apply_status(world, events, target, &name, def, amount as f32);
+            }
+        }
+        for death in events.death.iter() {
+            if let Some(mover) = self.movers.iter().find(|m| m.entity == death.entity) {
+                self.slain.insert(mover.object);
+            }
+        }
+
+        if let Some((player, pos)) = player {
+            let mut entered = Vec::new();
+            let mut cutscenes = Vec::new();
+            for zone in &mut self.triggers {
+                let enabled = !zone.object.is_some_and(|object| self.disabled.contains(&object));
+                let inside = enabled && zone.shape.contains(pos);
+                if inside == zone.inside {
+                    continue;
+                }
+                zone.inside = inside;
+                if inside {
+                    entered.push(zone.trigger_id.clone());
+                    cutscenes.extend(zone.cutscene.take());
+                }
+                let (event, named) = if inside {
+                    (ScriptEvent::Enter(zone.trigger_id.clone()), &zone.on_enter)
+                } else {
+                    (ScriptEvent::Exit(zone.trigger_id.clone()), &zone.on_exit)
+                };
+                self.scripts.fire(&event);
+                if let Some(name) = named {
+                    self.scripts.fire(&ScriptEvent::Custom(name.clone()));
+                }
+            }
+            for name in cutscenes {
+                self.play_cutscene(&name, events);
+            }
+
+            for pickup in &mut self.pickups {
+                let Some(entity) = pickup.entity else {
+                    continue;
+                };
+                let dx = pickup.position.x - pos.x;
+                let dz = pickup.position.z - pos.z;
+                if self.disabled.contains(&pickup.object)
+                    || dx * dx + dz * dz > PICKUP_RADIUS * PICKUP_RADIUS
+                    || (pickup.position.y - pos.y).abs() > VERTICAL_REACH
+                {
+                    continue;
+                }
+                events.item_collected.send(ItemCollectedEvent {
+                    item: entity,
+                    collector: player,
+                    item_type: pickup.item_type,
+                });
+                world.despawn(entity);
+                pickup.entity = None;
+                pickup.respawn_in = pickup.respawn_time;
+                if matches!(pickup.item_type, ItemType::Key(_)) && !self.keys.contains(&pickup.name) {
+                    self.keys.push(pickup.name.clone());
+                    self.messages.push((format!("Got {}", pickup.name), MESSAGE_DURATION));
+                }
+                if let Some((name, def)) = pickup.effect.as_ref().and_then(|name| Some((name, self.statuses.get(name)?))) {
+                    status::apply_status(world, events, player, name, def, def.buildup);
+                }
+                self.scripts.fire(&ScriptEvent::Pickup(pickup.name.clone()));
+            }
+
+            for item in &mut self.gear {
+                let dx = item.position.x - pos.x;
+                let dz = item.position.z - pos.z;
+                if item.collected
+                    || self.disabled.contains(&item.object)
+                    || dx * dx + dz * dz > PICKUP_RADIUS * PICKUP_RADIUS
+                    || (item.position.y - pos.y).abs() > VERTICAL_REACH
+                {
+                    continue;
+                }
+                item.collected = true;
+                self.found_gear.extend(item.gear.iter().cloned());
+                self.messages.push((format!("Got {}", item.name), MESSAGE_DURATION));
+                self.scripts.fire(&ScriptEvent::Pickup(item.name.clone()));
+            }
+
+            // Hazards hit the player as soon as they step in, then every `interval`
+            for hazard in &mut self.hazards {
+                let dx = hazard.position.x - pos.x;
+                let dz = hazard.position.z - pos.z;
+                if self.disabled.contains(&hazard.object)
+                    || dx * dx + dz * dz > hazard.radius * hazard.radius
+                    || (hazard.position.y - pos.y).abs() > VERTICAL_REACH
+                {
+                    hazard.cooldown = 0.0;
+                    continue;
+                }
+                hazard.cooldown -= delta;
+                if hazard.cooldown > 0.0 {
+                    continue;
+                }
+                hazard.cooldown = hazard.interval.max(0.1);
+                status::hurt(world, events, player, hazard.damage);
+                if let Some((name, def)) = hazard.effect.as_ref().and_then(|name| Some((name, self.statuses.get(name)?))) {
+                    status::apply_status(world, events, player, name, def, hazard.buildup);
+                }
+            }
+
+            if attack {
+                let facing = world.controllers.get(player).map(|c| c.facing).unwrap_or(0.0);
+                self.attack_props(world, events, player, pos, facing);
+            }
+
+            // Linked triggers open their doors and call their platforms
+            for trigger_id in entered {
+                for i in 0..self.doors.len() {
+                    if self.doors[i].open_trigger.as_deref() == Some(trigger_id.as_str()) {
+                        self.try_open(i, world, events, player);
+                    }
+                }
+                for platform in self.platforms.iter().filter(|p| p.activate_trigger.as_deref() == Some(trigger_id.as_str())) {
+                    if let Some(component) = world.moving_platforms.get_mut(platform.entity) {
+                        component.called = true;
+                    }
+                }
+            }
+
+            if interact {
+                if let Some(i) = self.nearest_door(pos) {
+                    let is_open = world.doors.get(self.doors[i].entity).is_some_and(|d| d.is_open);
+                    if is_open {
+                        self.set_door(i, false, world, events, Some(player));
+                    } else {
+                        self.try_open(i, world, events, player);
+                    }
+                } else if let Some(entity) = self.nearest_lever(world, pos) {
+                    if let Some(component) = world.moving_platforms.get_mut(entity) {
+                        component.called = true;
+                    }
+                } else if let Some(i) = self.nearest_talker(world, pos) {
+                    // Nothing to say (no node's condition holds) leaves it silent
+                    let talker = &self.talkers[i];
+                    self.dialogue = DialoguePlayer::new(&talker.name, &talker.nodes, &mut self.scripts);
+                }
+            }
+        }
+
+        // Respawn collected pickups
+        for pickup in &mut self.pickups {
+            if let Some(t) = pickup.respawn_in.as_mut() {
+                *t -= delta;
+                if *t <= 0.0 {
+                    pickup.respawn_in = None;
+                    let entity = world.spawn_at(pickup.position);
+                    world.items.insert(entity, Item { item_type: pickup.item_type });
+                    pickup.entity = Some(entity);
+                }
+            }
+        }
+
+        self.scripts.update(delta);
+        let opener = player.map(|(e, _)| e);
+        for action in self.scripts.drain_actions() {
+            self.apply(action, world, events, opener);
+        }
+
+        self.update_cutscene(events, delta);
+
+        // Animate doors toward their open/closed state
+        for door in &self.doors {
+            if let Some(component) = world.doors.get_mut(door.entity) {
+                let target = if component.is_open { 1.0 } else { 0.0 };
+                let step = delta / door.open_time.max(0.01);
+                component.open_amount = if component.open_amount < target {
+                    (component.open_amount + step).min(target)
+                } else {
+                    (component.open_amount - step).max(target)
+                };
+            }
+        }
+
+        for message in &mut self.messages {
+            message.1 -= delta;
+        }
+        self.messages.retain(|m| m.1 > 0.0);
+    }
+
+    fn apply(&mut self, action: ScriptAction, world: &mut World, events: &mut Events, opener: Option<Entity>) {
+        match action {
+            ScriptAction::OpenDoor(name) | ScriptAction::CloseDoor(name) | ScriptAction::ToggleDoor(name)
+                if !self.doors.iter().any(|d| d.name == name) =>
+            {
+                self.messages.push((format!("Script: no door named '{}'", name), MESSAGE_DURATION));
+            }
+            ScriptAction::OpenDoor(name) => self.set_doors(&name, |_| true, world, events, opener),
+            ScriptAction::CloseDoor(name) => self.set_doors(&name, |_| false, world, events, opener),
+            ScriptAction::ToggleDoor(name) => self.set_doors(&name, |open| !open, world, events, opener),
+            ScriptAction::Enable(name) => {
+                for (_, object) in self.named.iter().filter(|(n, _)| *n == name) {
+                    self.disabled.remove(object);
+                }
+            }
+            ScriptAction::Disable(name) => {
+                for (_, object) in self.named.iter().filter(|(n, _)| *n == name) {
+                    self.disabled.insert(*object);
+                }
+            }
+            ScriptAction::PlaySound(name) => events.sound.send(SoundEvent { name, position: None }),
+            ScriptAction::Message(text) => self.messages.push((text, MESSAGE_DURATION)),
+            ScriptAction::SetWeather(preset) => self.weather = Some(preset),
+            ScriptAction::ShowPrompt { action, text } => {
+                self.prompts.retain(|(a, _)| *a != action);
+                self.prompts.push((action, text));
+            }
+            ScriptAction::ClearPrompt(action) => self.prompts.retain(|(a, _)| *a != action),
+            ScriptAction::Tutorial { id, text } => {
+                if self.seen_tutorials.insert(id) {
+                    self.tutorials.push(text);
+                }
+            }
+            ScriptAction::PlayCutscene(name) => self.play_cutscene(&name, events),
+            ScriptAction::Music(request) => self.music.push(request),
+            ScriptAction::ApplyStatus(name) => match (self.statuses.get(&name), opener) {
+                (Some(def), Some(player)) => status::apply_status(world, events, player, &name, def, def.buildup),
+                (Some(_), None) => {}
+                (None, _) => self.messages.push((format!("Script: no status effect named '{}'", name), MESSAGE_DURATION)),
+            },
+            ScriptAction::CureStatus(name) => {
+                if let Some(effects) = opener.and_then(|player| world.status_effects.get_mut(player)) {
+                    effects.remove(&name);
+                }
+            }
+        }
+    }
+
+    /// Hit every intact prop in reach in front of `pos`; props that run out of health break
+    fn attack_props(&mut self, world: &mut World, events: &mut Events, player: Entity, pos: Vec3, facing: f32) {
+        for i in 0..self.destructibles.len() {
+            let prop = &self.destructibles[i];
+            if prop.broken || self.disabled.contains(&prop.object) || !in_swing(pos, facing, prop.position) {
+                continue;
+            }
+            status::hurt(world, events, prop.entity, ATTACK_DAMAGE);
+            if world.health.get(prop.entity).is_some_and(|h| h.is_dead()) {
+                self.break_prop(i, world, events, player);
+            }
+        }
+    }
+
+    /// Break a prop: queue its debris, drop its loot and tell scripts
+    fn break_prop(&mut self, index: usize, world: &mut World, events: &mut Events, breaker: Entity) {
+        let prop = &mut self.destructibles[index];
+        prop.broken = true;
+        world.despawn(prop.entity);
+        events.prop_broken.send(PropBrokenEvent { prop: prop.entity, breaker, position: prop.position });
+        if let Some(def) = prop.debris.clone() {
+            self.debris.push((def, prop.position));
+        }
+        if let Some(loot) = prop.drop.clone() {
+            let entity = world.spawn_at(prop.position);
+            world.items.insert(entity, Item { item_type: loot.item_type });
+            self.pickups.push(PickupInstance {
+                object: prop.object,
+                name: loot.name,
+                entity: Some(entity),
+                item_type: loot.item_type,
+                position: prop.position,
+                respawn_time: None,
+                respawn_in: None,
+                effect: loot.effect,
+                dropped: Some(loot.asset_id),
+            });
+        }
+        self.scripts.fire(&ScriptEvent::Break(prop.name.clone()));
+    }
+
+    /// Start a cutscene by name (a playing one is skipped first)
+    fn play_cutscene(&mut self, name: &str, events: &mut Events) {
+        let Some(cutscene) = self.cutscenes.iter().find(|c| c.name == name) else {
+            self.messages.push((format!("Script: no cutscene named '{}'", name), MESSAGE_DURATION));
+            return;
+        };
+        match CutscenePlayer::new(cutscene) {
+            Ok(player) => {
+                if self.cutscene.is_some() {
+                    self.skip = true;
+                    self.update_cutscene(events, 0.0);
+                }
+                self.cutscene = Some(player);
+            }
+            Err((line, message)) => {
+                self.errors.push(ScriptError { script: format!("cutscene {}", name), line, message });
+            }
+        }
+    }
+
+    /// Slide moved instances and run the cutscene's timeline; a skip lands
+    /// every move and plays the music it would have
+    fn update_cutscene(&mut self, events: &mut Events, delta: f32) {
+        for m in &mut self.moves {
+            m.elapsed += delta;
+            let t = if m.duration <= 0.0 { 1.0 } else { (m.elapsed / m.duration).min(1.0) };
+            self.offsets.insert(m.object, m.from + (m.to - m.from) * t);
+        }
+        self.moves.retain(|m| m.elapsed < m.duration);
+
+        let skip = std::mem::take(&mut self.skip);
+        let due = match self.cutscene.as_mut() {
+            Some(player) if skip => player.skip(),
+            Some(player) => player.update(delta),
+            None => Vec::new(),
+        };
+        for action in due {
+            self.apply_cutscene(action, events);
+        }
+        if skip {
+            for m in self.moves.drain(..) {
+                self.offsets.insert(m.object, m.to);
+            }
+        }
+
+        if self.cutscene.as_ref().is_some_and(|c| c.finished()) {
+            if let Some(player) = self.cutscene.take() {
+                self.scripts.fire(&ScriptEvent::CutsceneEnd(player.name().to_string()));
+            }
+        }
+    }
+
+    fn apply_cutscene(&mut self, action: CutsceneAction, events: &mut Events) {
+        match action {
+            CutsceneAction::MoveEntity { name, offset, duration } => {
+                let offset = Vec3::new(offset[0], offset[1], offset[2]);
+                let objects: Vec<ObjectRef> = self.named.iter().filter(|(n, _)| *n == name).map(|(_, o)| *o).collect();
+                if objects.is_empty() {
+                    self.messages.push((format!("Cutscene: no instance named '{}'", name), MESSAGE_DURATION));
+                }
+                for object in objects {
+                    let from = self.offsets.get(&object).copied().unwrap_or(Vec3::ZERO);
+                    self.moves.retain(|m| m.object != object);
+                    self.moves.push(CutsceneMove { object, from, to: from + offset, elapsed: 0.0, duration });
+                }
+            }
+            CutsceneAction::PlayAnimation { name, clip } => events.animation.send(AnimationEvent { name, clip }),
+            CutsceneAction::PlayMusic { song, pattern } => self.music.push(MusicRequest::play_now(song, pattern)),
+            // The player shows its own subtitles
+            CutsceneAction::Subtitle { .. } => {}
+        }
+    }
+
+    /// Script door commands: ignore locks
+    fn set_doors(
+        &mut self,
+        name: &str,
+        state: impl Fn(bool) -> bool,
+        world: &mut World,
+        events: &mut Events,
+        opener: Option<Entity>,
+    ) {
+        for i in 0..self.doors.len() {
+            if self.doors[i].name != name {
+                continue;
+            }
+            if let Some(was_open) = world.doors.get(self.doors[i].entity).map(|d| d.is_open) {
+                self.set_door(i, state(was_open), world, events, opener);
+            }
+        }
+    }
+
+    /// Open or close a door, sending its event when the state changes
+    fn set_door(&mut self, index: usize, open: bool, world: &mut World, events: &mut Events, opener: Option<Entity>) {
+        let door = &self.doors[index];
+        let Some(component) = world.doors.get_mut(door.entity) else {
+            return;
+        };
+        if component.is_open == open {
+            return;
+        }
+        component.is_open = open;
+        let event = DoorEvent { door: door.entity, opener: opener.unwrap_or(door.entity) };
+        if open {
+            events.door_opened.send(event);
+            self.scripts.fire(&ScriptEvent::DoorOpen(door.name.clone()));
+        } else {
+            events.door_closed.send(event);
+            self.scripts.fire(&ScriptEvent::DoorClose(door.name.clone()));
+        }
+    }
+
+    /// Player tries to open a door: locked doors need their key in the inventory
+    fn try_open(&mut self, index: usize, world: &mut World, events: &mut Events, player: Entity) {
+        let door = &self.doors[index];
+        if self.disabled.contains(&door.object) {
+            return;
+        }
+        if let Some(key) = door.required_key.as_ref().filter(|key| !self.keys.contains(*key)) {
+            // Only complain when the player could have opened it
+            if world.doors.get(door.entity).is_some_and(|d| !d.is_open) {
+                self.messages.push((format!("Locked: needs {}", key), MESSAGE_DURATION));
+                events.door_locked.send(DoorEvent { door: door.entity, opener: player });
+                self.scripts.fire(&ScriptEvent::Locked(door.name.clone()));
+            }
+            return;
+        }
+        self.set_door(index, true, world, events, Some(player));
+    }
+
+    /// Closest enabled door within reach of `pos`
+    fn nearest_door(&self, pos: Vec3) -> Option<usize> {
+        self.doors.iter().enumerate()
+            .filter(|(_, d)| !self.disabled.contains(&d.object) && (d.position.y - pos.y).abs() <= VERTICAL_REACH)
+            .map(|(i, d)| {
+                let dx = d.position.x - pos.x;
+                let dz = d.position.z - pos.z;
+                (i, dx * dx + dz * dz)
+            })
+            .filter(|&(_, dist_sq)| dist_sq <= INTERACT_RADIUS * INTERACT_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// Lever platform within reach of `pos` (its deck, not where it was placed)
+    fn nearest_lever(&self, world: &World, pos: Vec3) -> Option<Entity> {
+        self.platforms.iter()
+            .filter(|p| p.lever && !self.disabled.contains(&p.object))
+            .filter_map(|p| {
+                let (min, max) = world.moving_platforms.get(p.entity)?.deck_at(world.transforms.get(p.entity)?.position);
+                // Distance from the deck's edge
+                let dx = (min.x - pos.x).max(pos.x - max.x).max(0.0);
+                let dz = (min.z - pos.z).max(pos.z - max.z).max(0.0);
+                let reachable = (max.y - pos.y).abs() <= VERTICAL_REACH && dx * dx + dz * dz <= INTERACT_RADIUS * INTERACT_RADIUS;
+                reachable.then_some((p.entity, dx * dx + dz * dz))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entity, _)| entity)
+    }
+
+    /// Closest enabled talker within reach of `pos` (where it walked to, if it moves)
+    fn nearest_talker(&self, world: &World, pos: Vec3) -> Option<usize> {
+        self.talkers.iter().enumerate()
+            .filter(|(_, t)| !self.disabled.contains(&t.object) && !self.slain.contains(&t.object))
+            .map(|(i, t)| {
+                let position = self.movers.iter()
+                    .find(|m| m.object == t.object)
+                    .and_then(|m| world.transforms.get(m.entity))
+                    .map(|transform| transform.position)
+                    .unwrap_or(t.position);
+                (i, position)
+            })
+            .filter(|(_, p)| (p.y - pos.y).abs() <= VERTICAL_REACH)
+            .map(|(i, p)| (i, (p.x - pos.x) * (p.x - pos.x) + (p.z - pos.z) * (p.z - pos.z)))
+            .filter(|&(_, dist_sq)| dist_sq <= INTERACT_RADIUS * INTERACT_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// Key pickups collected this session (instance names, in pickup order)
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// What Interact would do at `pos` ("Open", "Close" or "Locked"), if a door is in reach,
+    /// "Call" next to a lever platform or "Talk" next to an NPC
+    pub fn interaction_prompt(&self, world: &World, pos: Vec3) -> Option<&'static str> {
+        let Some(i) = self.nearest_door(pos) else {
+            return self.nearest_lever(world, pos).map(|_| "Call")
+                .or_else(|| self.nearest_talker(world, pos).map(|_| "Talk"));
+        };
+        let door = &self.doors[i];
+        let is_open = world.doors.get(door.entity)?.is_open;
+        let locked = door.required_key.as_ref().is_some_and(|key| !self.keys.contains(key));
+        Some(if is_open {
+            "Close"
+        } else if locked {
+            "Locked"
+        } else {
+            "Open"
+        })
+    }
+
+    /// Instances that shouldn't be drawn: collected pickups and gear, disabled instances,
+    /// slain enemies, broken props without a broken mesh
+    pub fn hidden_objects(&self) -> Vec<ObjectRef> {
+        let mut hidden: Vec<ObjectRef> = self.disabled.iter().copied().collect();
+        hidden.extend(self.slain.iter().copied());
+        hidden.extend(self.pickups.iter().filter(|p| p.entity.is_none() && p.dropped.is_none()).map(|p| p.object));
+        hidden.extend(self.gear.iter().filter(|g| g.collected).map(|g| g.object));
+        hidden.extend(self.destructibles.iter().filter(|d| d.broken && !d.has_broken_mesh).map(|d| d.object));
+        hidden
+    }
+
+    /// Broken props to draw with their broken mesh
+    pub fn broken_objects(&self) -> Vec<ObjectRef> {
+        self.destructibles.iter().filter(|d| d.broken && d.has_broken_mesh).map(|d| d.object).collect()
+    }
+
+    /// Uncollected loot from broken props: (room index, pickup asset id, world position)
+    pub fn dropped_assets(&self) -> Vec<(usize, u64, Vec3)> {
+        self.pickups.iter()
+            .filter(|p| p.entity.is_some() && !self.disabled.contains(&p.object))
+            .filter_map(|p| Some((p.object.0, p.dropped?, p.position)))
+            .collect()
+    }
+
+    /// Take the weapons and armor picked up since the last call
+    pub fn take_gear(&mut self) -> Vec<Gear> {
+        std::mem::take(&mut self.found_gear)
+    }
+
+    /// Take the debris bursts of props broken since the last call: (particle settings, world position)
+    pub fn take_debris(&mut self) -> Vec<(ParticleEmitterDef, Vec3)> {
+        std::mem::take(&mut self.debris)
+    }
+
+    /// Doors away from their closed pose: (instance, world offset, extra yaw)
+    pub fn door_poses(&self, world: &World) -> Vec<(ObjectRef, Vec3, f32)> {
+        self.doors.iter()
+            .filter_map(|door| {
+                let amount = world.doors.get(door.entity)?.open_amount;
+                if amount <= 0.0 {
+                    return None;
+                }
+                let (offset, yaw) = door.motion.pose(amount);
+                // Same rotation as render_asset_parts applies for the instance's facing
+                let (sin_f, cos_f) = door.facing.sin_cos();
+                let world_offset = Vec3::new(
+                    offset[0] * cos_f - offset[2] * sin_f,
+                    offset[1],
+                    offset[0] * sin_f + offset[2] * cos_f,
+                );
+                Some((door.object, world_offset, yaw))
+            })
+            .collect()
+    }
+
+    /// Path followers and patrolling enemies away from where they were placed:
+    /// (instance, world offset, extra yaw)
+    pub fn path_poses(&self, world: &World) -> Vec<(ObjectRef, Vec3, f32)> {
+        self.movers.iter()
+            .filter_map(|mover| {
+                let transform = world.transforms.get(mover.entity)?;
+                let face_travel = match world.path_followers.get(mover.entity) {
+                    Some(follower) => follower.face_travel,
+                    None => world.patrols.contains(mover.entity),
+                };
+                let yaw = if face_travel { transform.rotation.y.to_radians() - mover.facing } else { 0.0 };
+                Some((mover.object, transform.position - mover.position, yaw))
+            })
+            .collect()
+    }
+
+    /// Instances moved by cutscenes: (instance, world offset, extra yaw)
+    pub fn cutscene_poses(&self) -> Vec<(ObjectRef, Vec3, f32)> {
+        self.offsets.iter().map(|(&object, &offset)| (object, offset, 0.0)).collect()
+    }
+
+    /// World-space boxes of doors that aren't fully open
+    pub fn door_blockers(&self, world: &World) -> Vec<(Vec3, Vec3)> {
+        self.doors.iter()
+            .filter(|d| !self.disabled.contains(&d.object))
+            .filter(|d| world.doors.get(d.entity).is_some_and(|c| c.open_amount < 1.0))
+            .map(|d| d.bounds)
+            .collect()
+    }
+
+    /// Weather preset set by a script this session, if any
+    pub fn weather_override(&self) -> Option<WeatherPreset> {
+        self.weather
+    }
+
+    /// Button prompts currently on screen (oldest first)
+    pub fn prompts(&self) -> impl Iterator<Item = (Action, &str)> {
+        self.prompts.iter().map(|(action, text)| (*action, text.as_str()))
+    }
+
+    /// Remove prompts whose action the player just performed
+    pub fn dismiss_prompts(&mut self, performed: impl Fn(Action) -> bool) {
+        self.prompts.retain(|(action, _)| !performed(*action));
+    }
+
+    /// Tutorial popup on screen, if any
+    pub fn tutorial(&self) -> Option<&str> {
+        self.tutorials.first().map(|t| t.as_str())
+    }
+
+    /// Close the current tutorial popup (the next queued one shows)
+    pub fn dismiss_tutorial(&mut self) {
+        if !self.tutorials.is_empty() {
+            self.tutorials.remove(0);
+        }
+    }
+
+    /// Messages currently on screen (oldest first)
+    pub fn messages(&self) -> impl Iterator<Item = &str> {
+        self.messages.iter().map(|(text, _)| text.as_str())
+    }
+
+    /// Cutscene playing, if any
+    pub fn cutscene(&self) -> Option<&CutscenePlayer> {
+        self.cutscene.as_ref()
+    }
+
+    /// Conversation in progress, if any
+    pub fn dialogue(&self) -> Option<&DialoguePlayer> {
+        self.dialogue.as_ref()
+    }
+
+    /// Move the conversation's highlighted choice up (-1) or down (+1)
+    pub fn move_choice(&mut self, step: i32) {
+        if let Some(dialogue) = self.dialogue.as_mut() {
+            dialogue.move_selection(step);
+        }
+    }
+
+    /// Music changes asked for since the last call, in order
+    pub fn take_music(&mut self) -> Vec<MusicRequest> {
+        std::mem::take(&mut self.music)
+    }
+}
+
+/// Box a closed door blocks: its solid Box collision if it has one, else its sector column
+fn door_bounds(components: &[AssetComponent], position: Vec3, facing: f32) -> (Vec3, Vec3) {
+    solid_bounds(components, position, facing).unwrap_or_else(|| {
+        let half = SECTOR_SIZE * 0.5;
+        (
+            Vec3::new(position.x - half, position.y, position.z - half),
+            Vec3::new(position.x + half, position.y + SECTOR_SIZE, position.z + half),
+        )
+    })
+}
+
+/// Deck of a moving platform relative to its position: its solid collision box,
+/// else a sector-wide slab with its top at the instance's origin
+fn platform_deck(components: &[AssetComponent], facing: f32) -> (Vec3, Vec3) {
+    solid_bounds(components, Vec3::ZERO, facing).unwrap_or_else(|| {
+        let half = SECTOR_SIZE * 0.5;
+        (Vec3::new(-half, -SECTOR_SIZE / 8.0, -half), Vec3::new(half, 0.0, half))
+    })
+}
+
+/// World-space box around an asset's first solid collision box, if it has one
+fn solid_bounds(components: &[AssetComponent], position: Vec3, facing: f32) -> Option<(Vec3, Vec3)> {
+    let (half, offset) = components.iter().find_map(|c| match c {
+        AssetComponent::Collision { shape: CollisionShapeDef::Box { half_extents }, is_trigger: false, offset } => {
+            Some((*half_extents, *offset))
+        }
+        _ => None,
+    })?;
+    let (sin_f, cos_f) = facing.sin_cos();
+    let center = Vec3::new(
+        position.x + offset[0] * cos_f - offset[2] * sin_f,
+        position.y + offset[1],
+        position.z + offset[0] * sin_f + offset[2] * cos_f,
+    );
+    // Axis-aligned box around the rotated one
+    let hx = (half[0] * cos_f).abs() + (half[2] * sin_f).abs();
+    let hz = (half[0] * sin_f).abs() + (half[2] * cos_f).abs();
+    Some((
+        Vec3::new(center.x - hx, center.y - half[1], center.z - hz),
+        Vec3::new(center.x + hx, center.y + half[1], center.z + hz),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_contains() {
+        let center = Vec3::new(1536.0, 0.0, 512.0);
+        assert!(zone_contains(center, Vec3::new(1536.0, 100.0, 512.0)));
+        assert!(zone_contains(center, Vec3::new(1100.0, 0.0, 900.0)));
+        assert!(!zone_contains(center, Vec3::new(1000.0, 0.0, 512.0)));
+        assert!(!zone_contains(center, Vec3::new(1536.0, SECTOR_SIZE * 2.0, 512.0)));
+    }
+
+    #[test]
+    fn test_trigger_volume_fires_enter_and_exit() {
+        let mut level = Level::new();
+        let mut room = crate::world::Room::new(0, Vec3::new(SECTOR_SIZE, 0.0, 0.0), 4, 4);
+        let mut volume = crate::world::TriggerVolume::from_corners("hall", (0.0, 0.0), (SECTOR_SIZE * 2.0, SECTOR_SIZE));
+        volume.on_exit = Some("left_hall".to_string());
+        room.triggers.push(volume);
+        level.rooms.push(room);
+        level.scripts.push(crate::world::LevelScript {
+            name: "s".to_string(),
+            source: "on enter hall\n  message \"in\"\nend\non event left_hall\n  message \"out\"\nend".to_string(),
+            enabled: true,
+        });
+
+        let mut world = World::new();
+        let mut events = Events::new();
+        let mut logic = LevelLogic::start(&level, &AssetLibrary::default(), &mut world);
+        assert!(logic.errors.is_empty());
+        let player = world.spawn();
+
+        // Room-relative box: world x 1024..3072
+        logic.update(&mut world, &mut events, Some((player, Vec3::new(500.0, 0.0, 500.0))), 0.1);
+        assert_eq!(logic.messages().count(), 0);
+        logic.update(&mut world, &mut events, Some((player, Vec3::new(2500.0, 0.0, 500.0))), 0.1);
+        assert_eq!(logic.messages().collect::<Vec<_>>(), vec!["in"]);
+        logic.update(&mut world, &mut events, Some((player, Vec3::new(3500.0, 0.0, 500.0))), 0.1);
+        assert_eq!(logic.messages().collect::<Vec<_>>(), vec!["in", "out"]);
+    }
+
+    #[test]
+    fn test_path_follower_starts_at_first_waypoint() {
+        let mut library = AssetLibrary::default();
+        let mut guard = crate::asset::Asset::empty("guard");
+        guard.add_component(AssetComponent::Enemy {
+            enemy_type: crate::game::components::EnemyType::Grunt,
+            health: 10,
+            damage: 1,
+            patrol_radius: 0.0,
+            poise: 30.0,
+            attack: AttackDef::default(),
+        });
+        let guard_id = guard.id;
+        library.add(guard);
+
+        let mut level = Level::new();
+        let mut room = crate::world::Room::new(0, Vec3::new(SECTOR_SIZE, 0.0, 0.0), 4, 4);
+        let mut path = crate::world::EntityPath::new("patrol");
+        path.points = vec![Vec3::new(0.0, 0.0, SECTOR_SIZE), Vec3::new(SECTOR_SIZE * 2.0, 0.0, SECTOR_SIZE)];
+        room.paths.push(path);
+        let mut follower = crate::world::AssetInstance::new(0, 0, guard_id).with_height(100.0);
+        follower.path = Some(crate::world::PathFollow::new("patrol"));
+        room.objects.push(follower);
+        // Attached to a path that doesn't exist: stays put
+        let mut lost = crate::world::AssetInstance::new(1, 0, guard_id);
+        lost.path = Some(crate::world::PathFollow::new("nowhere"));
+        room.objects.push(lost);
+        let placed = room.objects[0].world_position(&room);
+        level.rooms.push(room);
+
+        let mut world = World::new();
+        let logic = LevelLogic::start(&level, &library, &mut world);
+        assert_eq!(logic.movers.len(), 1);
+        let follower = world.path_followers.get(logic.movers[0].entity).unwrap();
+        assert!(follower.face_travel);
+        assert_eq!(follower.speed, crate::world::DEFAULT_PATH_SPEED);
+
+        let start = Vec3::new(SECTOR_SIZE, 100.0, SECTOR_SIZE);
+        let poses = logic.path_poses(&world);
+        assert_eq!(poses.len(), 1);
+        let (object, offset, yaw) = poses[0];
+        assert_eq!(object, (0, 0));
+        assert_eq!((offset.x, offset.y, offset.z), (start.x - placed.x, start.y - placed.y, start.z - placed.z));
+        assert_eq!(yaw, 0.0);
+    }
+
+    #[test]
+    fn test_elevator_waits_to_be_called() {
+        let mut library = AssetLibrary::default();
+        let mut lift = crate::asset::Asset::empty("lift");
+        lift.add_component(AssetComponent::MovingPlatform {
+            route: crate::asset::PlatformRoute::default(),
+            speed: SECTOR_SIZE,
+            wait: 0.0,
+            activate_trigger: Some("call_lift".to_string()),
+            lever: true,
+        });
+        let lift_id = lift.id;
+        library.add(lift);
+
+        let mut level = Level::new();
+        let mut room = crate::world::Room::new(0, Vec3::ZERO, 4, 4);
+        room.objects.push(crate::world::AssetInstance::new(0, 0, lift_id));
+        room.triggers.push(crate::world::TriggerVolume::from_corners("call_lift", (SECTOR_SIZE * 2.0, 0.0), (SECTOR_SIZE * 3.0, SECTOR_SIZE)));
+        level.rooms.push(room);
+
+        let mut world = World::new();
+        let mut events = Events::new();
+        let mut logic = LevelLogic::start(&level, &library, &mut world);
+        assert_eq!(logic.platforms.len(), 1);
+        let entity = logic.platforms[0].entity;
+        let platform = world.moving_platforms.get(entity).unwrap();
+        assert!(platform.on_call && !platform.called);
+        // Elevators rise to their offset above the instance
+        assert_eq!(platform.points[1].y, 2048.0);
+        // The deck is in reach of the instance's sector: Interact calls it
+        let player = world.spawn();
+        assert_eq!(logic.interaction_prompt(&world, Vec3::new(SECTOR_SIZE * 0.5, 0.0, SECTOR_SIZE * 0.5)), Some("Call"));
+
+        logic.update(&mut world, &mut events, Some((player, Vec3::new(2500.0, 0.0, 500.0))), 0.1);
+        assert!(world.moving_platforms.get(entity).unwrap().called);
+        assert!(logic.path_poses(&world).iter().any(|(object, _, _)| *object == (0, 0)));
+    }
+
+    #[test]
+    fn test_talking_to_npc_runs_dialogue() {
+        let mut library = AssetLibrary::default();
+        let mut guard = crate::asset::Asset::empty("guard");
+        let mut greet = DialogueNode::new("greet", "Guard", "Halt!");
+        greet.effects.push(crate::asset::FlagEffect { flag: "met".to_string(), value: 1.0 });
+        greet.choices.push(crate::asset::DialogueChoice::new("Bye", None));
+        guard.add_component(AssetComponent::Dialogue { nodes: vec![greet] });
+        let guard_id = guard.id;
+        library.add(guard);
+
+        let mut level = Level::new();
+        let mut room = crate::world::Room::new(0, Vec3::ZERO, 4, 4);
+        room.objects.push(crate::world::AssetInstance::new(0, 0, guard_id));
+        let near = room.objects[0].world_position(&room);
+        level.rooms.push(room);
+        level.scripts.push(crate::world::LevelScript {
+            name: "s".to_string(),
+            source: "on dialogue_end guard\n  message \"bye\"\nend".to_string(),
+            enabled: true,
+        });
+
+        let mut world = World::new();
+        let mut events = Events::new();
+        let mut logic = LevelLogic::start(&level, &library, &mut world);
+        let player = world.spawn();
+        assert_eq!(logic.interaction_prompt(&world, near), Some("Talk"));
+        logic.request_interact();
+        logic.update(&mut world, &mut events, Some((player, near)), 0.1);
+        assert_eq!(logic.dialogue().map(|d| d.speaker()), Some("Guard"));
+        assert_eq!(logic.scripts.var("met"), 1.0);
+
+        // The first Interact finishes typing, the second picks "Bye" and ends it
+        logic.request_interact();
+        logic.update(&mut world, &mut events, Some((player, near)), 0.0);
+        assert!(logic.dialogue().is_some_and(|d| !d.typing()));
+        logic.request_interact();
+        logic.update(&mut world, &mut events, Some((player, near)), 0.0);
+        assert!(logic.dialogue().is_none());
+        assert_eq!(logic.messages().collect::<Vec<_>>(), vec!["bye"]);
+    }
+
+    #[test]
+    fn test_enemy_with_patrol_radius_patrols() {
+        let mut library = AssetLibrary::default();
+        let mut guard = crate::asset::Asset::empty("guard");
+        guard.add_component(AssetComponent::Enemy {
+            enemy_type: crate::game::components::EnemyType::Grunt,
+            health: 10,
+            damage: 1,
+            patrol_radius: SECTOR_SIZE * 2.0,
+            poise: 30.0,
+            attack: AttackDef::default(),
+        });
+        let guard_id = guard.id;
+        library.add(guard);
+        let mut sentry = crate::asset::Asset::empty("sentry");
+        sentry.add_component(AssetComponent::Enemy {
+            enemy_type: crate::game::components::EnemyType::Grunt,
+            health: 10,
+            damage: 1,
+            patrol_radius: 0.0,
+            poise: 30.0,
+            attack: AttackDef::default(),
+        });
+        let sentry_id = sentry.id;
+        library.add(sentry);
+
+        let mut level = Level::new();
+        let mut room = crate::world::Room::new(0, Vec3::ZERO, 4, 4);
+        room.objects.push(crate::world::AssetInstance::new(1, 1, guard_id).with_height(50.0));
+        // No radius: stays where it was placed
+        room.objects.push(crate::world::AssetInstance::new(2, 2, sentry_id));
+        let placed = room.objects[0].world_position(&room);
+        level.rooms.push(room);
+
+        let mut world = World::new();
+        let mut logic = LevelLogic::start(&level, &library, &mut world);
+        assert_eq!(logic.movers.len(), 1);
+        let entity = logic.movers[0].entity;
+        assert!(world.enemies.contains(entity));
+        assert!(world.combat.contains(entity) && world.poise.contains(entity));
+        let patrol = world.patrols.get(entity).unwrap();
+        assert_eq!((patrol.radius, patrol.lift, patrol.home.y), (SECTOR_SIZE * 2.0, 50.0, placed.y - 50.0));
+
+        // Turned to walk somewhere: moved by the offset, yaw follows the transform
+        world.transforms.get_mut(entity).unwrap().rotation.y = 90.0;
+        world.transforms.get_mut(entity).unwrap().position = placed + Vec3::new(SECTOR_SIZE, 0.0, 0.0);
+        let poses = logic.path_poses(&world);
+        assert_eq!(poses.len(), 1);
+        let (object, offset, yaw) = poses[0];
+        assert_eq!((object, offset.x), ((0, 0), SECTOR_SIZE));
+        assert!((yaw - std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+
+        // Killed in combat: no longer drawn
+        let mut events = Events::new();
+        events.death.send(crate::game::event::DeathEvent { entity, killer: None, position: placed });
+        logic.update(&mut world, &mut events, None, 0.1);
+        assert!(logic.hidden_objects().contains(&(0, 0)));
+    }
+
+    /// Unlocked sliding door at the origin
+    fn door_instance(object: ObjectRef, name: &str, entity: Entity) -> DoorInstance {
+        DoorInstance {
+            object,
+            name: name.to_string(),
+            entity,
+            required_key: None,
+            open_trigger: None,
+            motion: DoorMotion::default(),
+            open_time: 1.0,
+            position: Vec3::ZERO,
+            facing: 0.0,
+            bounds: door_bounds(&[], Vec3::ZERO, 0.0),
+        }
+    }
+
+    #[test]
+    fn test_script_opens_door() {
+        let mut world = World::new();
+        let mut events = Events::new();
+        let door = world.spawn();
+        world.doors.insert(door, Door { is_open: false, required_key: None, open_amount: 0.0 });
+
+        let mut logic = LevelLogic {
+            started: true,
+            doors: vec![door_instance((0, 3), "gate", door)],
+            ..LevelLogic::default()
+        };
+        let (scripts, errors) = ScriptRuntime::compile(&[crate::world::LevelScript {
+            name: "s".to_string(),
+            source: "on timer 1\n  open_door gate\n  message hi\n  weather snow\nend".to_string(),
+            enabled: true,
+        }]);
+        assert!(errors.is_empty());
+        logic.scripts = scripts;
+
+        logic.update(&mut world, &mut events, None, 0.5);
+        assert!(logic.door_poses(&world).is_empty());
+        assert_eq!(logic.door_blockers(&world).len(), 1);
+        assert_eq!(logic.weather_override(), None);
+
+        logic.update(&mut world, &mut events, None, 0.6);
+        assert!(world.doors.get(door).unwrap().is_open);
+        assert_eq!(events.door_opened.len(), 1);
+        assert_eq!(logic.interaction_prompt(&world, Vec3::new(0.0, 0.0, SECTOR_SIZE)), Some("Close"));
+        assert_eq!(logic.interaction_prompt(&world, Vec3::new(SECTOR_SIZE * 4.0, 0.0, 0.0)), None);
+        assert!(logic.hidden_objects().is_empty());
+        // Halfway through sliding up; still blocking until fully open
+        let poses = logic.door_poses(&world);
+        assert_eq!(poses.len(), 1);
+        assert_eq!(poses[0].0, (0, 3));
+        assert!((poses[0].1.y - 0.6 * SECTOR_SIZE).abs() < 1.0);
+        assert_eq!(logic.door_blockers(&world).len(), 1);
+        logic.update(&mut world, &mut events, None, 0.5);
+        assert!(logic.door_blockers(&world).is_empty());
+        assert_eq!(logic.messages().collect::<Vec<_>>(), vec!["hi"]);
+        assert_eq!(logic.weather_override(), Some(WeatherPreset::Snow));
+    }
+
+    #[test]
+    fn test_tutorial_hints() {
+        let mut world = World::new();
+        let mut events = Events::new();
+        let mut logic = LevelLogic { started: true, ..LevelLogic::default() };
+        let (scripts, errors) = ScriptRuntime::compile(&[crate::world::LevelScript {
+            name: "s".to_string(),
+            source: "on timer 1 repeat\n  prompt jump \"Jump\"\n  tutorial intro \"Welcome\"\nend".to_string(),
+            enabled: true,
+        }]);
+        assert!(errors.is_empty());
+        logic.scripts = scripts;
+
+        logic.update(&mut world, &mut events, None, 1.1);
+        logic.update(&mut world, &mut events, None, 1.0);
+        // Re-running the handler replaces the prompt and skips the seen tutorial
+        assert_eq!(logic.prompts().collect::<Vec<_>>(), vec![(Action::Jump, "Jump")]);
+        assert_eq!(logic.tutorial(), Some("Welcome"));
+        logic.dismiss_tutorial();
+        assert_eq!(logic.tutorial(), None);
+
+        logic.dismiss_prompts(|action| action == Action::Attack);
+        assert_eq!(logic.prompts().count(), 1);
+        logic.dismiss_prompts(|action| action == Action::Jump);
+        assert_eq!(logic.prompts().count(), 0);
+    }
+
+    #[test]
+    fn test_locked_door_needs_key() {
+        let mut world = World::new();
+        let mut events = Events::new();
+        let player = world.spawn();
+        let door = world.spawn();
+        world.doors.insert(door, Door { is_open: false, required_key: None, open_amount: 0.0 });
+        let key = world.spawn();
+
+        let mut instance = door_instance((0, 0), "vault", door);
+        instance.required_key = Some("red key".to_string());
+        instance.open_trigger = Some("vault_step".to_string());
+        let mut logic = LevelLogic {
+            started: true,
+            doors: vec![instance],
+            pickups: vec![PickupInstance {
+                object: (0, 1),
+                name: "red key".to_string(),
+                entity: Some(key),
+                item_type: ItemType::Key(crate::game::components::KeyType::Generic(1)),
+                position: Vec3::new(SECTOR_SIZE * 4.0, 0.0, 0.0),
+                respawn_time: None,
+                respawn_in: None,
+                effect: None,
+                dropped: None,
+            }],
+            triggers: vec![TriggerZone {
+                object: None,
+                name: "vault_step".to_string(),
+                trigger_id: "vault_step".to_string(),
+                on_enter: None,
+                on_exit: None,
+                cutscene: None,
+                shape: ZoneShape::Sector(Vec3::new(0.0, 0.0, SECTOR_SIZE)),
+                inside: false,
+            }],
+            ..LevelLogic::default()
+        };
+        let (scripts, errors) = ScriptRuntime::compile(&[crate::world::LevelScript {
+            name: "s".to_string(),
+            source: "on locked vault\n  sound buzz\nend\non door_open vault\n  sound creak\nend".to_string(),
+            enabled: true,
+        }]);
+        assert!(errors.is_empty());
+        logic.scripts = scripts;
+
+        // Interact without the key: stays shut and says why
+        logic.request_interact();
+        logic.update(&mut world, &mut events, Some((player, Vec3::new(300.0, 0.0, 0.0))), 0.1);
+        assert!(!world.doors.get(door).unwrap().is_open);
+        assert_eq!(events.door_locked.len(), 1);
+        assert_eq!(logic.messages().collect::<Vec<_>>(), vec!["Locked: needs red key"]);
+        assert_eq!(logic.interaction_prompt(&world, Vec3::new(300.0, 0.0, 0.0)), Some("Locked"));
+
+        // Collect the key, then step on the linked trigger
+        logic.update(&mut world, &mut events, Some((player, Vec3::new(SECTOR_SIZE * 4.0, 0.0, 0.0))), 0.1);
+        assert_eq!(logic.keys, vec!["red key".to_string()]);
+        logic.update(&mut world, &mut events, Some((player, Vec3::new(0.0, 0.0, SECTOR_SIZE))), 0.1);
+        assert!(world.doors.get(door).unwrap().is_open);
+        assert_eq!(events.door_opened.len(), 1);
+        assert_eq!(logic.interaction_prompt(&world, Vec3::new(0.0, 0.0, SECTOR_SIZE)), Some("Close"));
+        assert_eq!(logic.interaction_prompt(&world, Vec3::new(SECTOR_SIZE * 4.0, 0.0, 0.0)), None);
+
+        // Interact closes it again
+        logic.request_interact();
+        logic.update(&mut world, &mut events, Some((player, Vec3::new(0.0, 0.0, SECTOR_SIZE))), 0.1);
+        assert!(!world.doors.get(door).unwrap().is_open);
+        assert_eq!(events.door_closed.len(), 1);
+
+        let sounds: Vec<String> = events.sound.drain().map(|s| s.name).collect();
+        assert_eq!(sounds, vec!["buzz".to_string(), "creak".to_string()]);
+    }
+
+    #[test]
+    fn test_attacks_break_props_and_drop_loot() {
+        let mut library = AssetLibrary::default();
+        let mut crate_asset = crate::asset::Asset::empty("Crate");
+        crate_asset.add_component(AssetComponent::Destructible {
+            health: ATTACK_DAMAGE * 2,
+            debris: "dust".to_string(),
+            drop: Some("Coin".to_string()),
+            broken: Vec::new(),
+        });
+        let crate_id = crate_asset.id;
+        library.add(crate_asset);
+        let mut coin = crate::asset::Asset::empty("Coin");
+        coin.add_component(AssetComponent::Pickup { item_type: ItemType::Currency { amount: 5 }, respawn_time: None });
+        let coin_id = coin.id;
+        library.add(coin);
+
+        let mut level = Level::new();
+        let mut room = crate::world::Room::new(0, Vec3::ZERO, 4, 4);
+        room.objects.push(crate::world::AssetInstance::new(1, 1, crate_id));
+        let crate_pos = room.objects[0].world_position(&room);
+        level.rooms.push(room);
+        level.scripts.push(crate::world::LevelScript {
+            name: "s".to_string(),
+            source: "on break Crate
+  message smashed
+end".to_string(),
+            enabled: true,
+        });
+
+        let mut world = World::new();
+        let mut events = Events::new();
+        let mut logic = LevelLogic::start(&level, &library, &mut world);
+        let player = world.spawn_at(crate_pos);
+        let reach = Vec3::new(0.0, 0.0, SECTOR_SIZE * 0.75);
+
+        // Facing +Z: a swing with the crate behind misses
+        logic.request_attack();
+        logic.update(&mut world, &mut events, Some((player, crate_pos + reach)), 0.1);
+        assert_eq!(events.damage.len(), 0);
+
+        // From the other side the first hit damages, the second breaks it
+        logic.request_attack();
+        logic.update(&mut world, &mut events, Some((player, crate_pos - reach)), 0.1);
+        assert_eq!(events.damage.len(), 1);
+        assert!(logic.hidden_objects().is_empty());
+        logic.request_attack();
+        logic.update(&mut world, &mut events, Some((player, crate_pos - reach)), 0.1);
+        assert_eq!(events.prop_broken.len(), 1);
+        assert_eq!(logic.messages().collect::<Vec<_>>(), vec!["smashed"]);
+
+        // No broken mesh: the crate disappears, bursts into dust and leaves its coin
+        assert_eq!(logic.hidden_objects(), vec![(0, 0)]);
+        assert!(logic.broken_objects().is_empty());
+        assert_eq!(logic.take_debris().len(), 1);
+        assert!(logic.take_debris().is_empty());
+        let dropped = logic.dropped_assets();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!((dropped[0].0, dropped[0].1), (0, coin_id));
+        assert!((dropped[0].2 - crate_pos).len() < 1.0);
+
+        // Broken props take no more hits; the coin is collected by walking over it
+        logic.request_attack();
+        logic.update(&mut world, &mut events, Some((player, crate_pos - reach)), 0.1);
+        assert_eq!(events.damage.len(), 2);
+        logic.update(&mut world, &mut events, Some((player, crate_pos)), 0.1);
+        assert_eq!(events.item_collected.len(), 1);
+        assert!(logic.dropped_assets().is_empty());
+        assert_eq!(logic.hidden_objects(), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn test_walking_over_gear_picks_it_up() {
+        let mut library = AssetLibrary::default();
+        let mut sword = crate::asset::Asset::empty("Longsword");
+        sword.add_component(ron::from_str("Weapon(damage: 25)").unwrap());
+        let sword_id = sword.id;
+        library.add(sword);
+        let mut hero = crate::asset::Asset::empty("Hero");
+        hero.add_component(ron::from_str("SpawnPoint(is_player: true)").unwrap());
+        hero.add_component(ron::from_str("Armor(defense: 0.2)").unwrap());
+        let hero_id = hero.id;
+        library.add(hero);
+
+        let mut level = Level::new();
+        let mut room = crate::world::Room::new(0, Vec3::ZERO, 4, 4);
+        room.objects.push(crate::world::AssetInstance::new(0, 0, hero_id));
+        room.objects.push(crate::world::AssetInstance::new(3, 3, sword_id));
+        let sword_pos = room.objects[1].world_position(&room);
+        level.rooms.push(room);
+
+        let mut world = World::new();
+        let mut events = Events::new();
+        let mut logic = LevelLogic::start(&level, &library, &mut world);
+        let player = world.spawn();
+        // The player start's armor isn't lying around to be picked up
+        logic.update(&mut world, &mut events, Some((player, Vec3::ZERO)), 0.1);
+        assert!(logic.take_gear().is_empty());
+
+        logic.update(&mut world, &mut events, Some((player, sword_pos)), 0.1);
+        let gear = logic.take_gear();
+        assert_eq!(gear.len(), 1);
+        assert!(gear[0].is_weapon() && gear[0].name == "Longsword");
+        assert_eq!(logic.messages().collect::<Vec<_>>(), vec!["Got Longsword"]);
+        assert_eq!(logic.hidden_objects(), vec![(0, 1)]);
+        logic.update(&mut world, &mut events, Some((player, sword_pos)), 0.1);
+        assert!(logic.take_gear().is_empty());
+    }
+
+    #[test]
+    fn test_hazard_and_pickup_apply_status_effects() {
+        use crate::asset::StatusEffectDef;
+        let mut library = AssetLibrary::default();
+        let mut trap = crate::asset::Asset::empty("Gas Trap");
+        trap.add_component(AssetComponent::Hazard { radius: SECTOR_SIZE, interval: 1.0, damage: 5, buildup: 0.0 });
+        trap.add_component(AssetComponent::StatusEffect {
+            preset: "poison".to_string(),
+            effect: StatusEffectDef::preset("poison").unwrap(),
+        });
+        let trap_id = trap.id;
+        library.add(trap);
+        let mut tonic = crate::asset::Asset::empty("Tonic");
+        tonic.add_component(AssetComponent::Pickup { item_type: ItemType::Upgrade, respawn_time: None });
+        tonic.add_component(AssetComponent::StatusEffect {
+            preset: "haste".to_string(),
+            effect: StatusEffectDef::preset("haste").unwrap(),
+        });
+        let tonic_id = tonic.id;
+        library.add(tonic);
+
+        let mut level = Level::new();
+        let mut room = crate::world::Room::new(0, Vec3::ZERO, 4, 4);
+        room.objects.push(crate::world::AssetInstance::new(0, 0, trap_id));
+        room.objects.push(crate::world::AssetInstance::new(3, 3, tonic_id));
+        let trap_pos = room.objects[0].world_position(&room);
+        let tonic_pos = room.objects[1].world_position(&room);
+        level.rooms.push(room);
+        level.scripts.push(crate::world::LevelScript {
+            name: "s".to_string(),
+            source: "on start
+  status frostbite
+end".to_string(),
+            enabled: true,
+        });
+
+        let mut world = World::new();
+        let mut events = Events::new();
+        let mut logic = LevelLogic::start(&level, &library, &mut world);
+        let player = world.spawn_at(trap_pos);
+        world.health.insert(player, crate::game::components::Health::new(100));
+        let health = |world: &World| world.health.get(player).unwrap().current;
+        let poison_stacks = |world: &World| {
+            world.status_effects.get(player).and_then(|s| s.active.iter().find(|a| a.name == "Gas Trap")).map(|a| a.stacks)
+        };
+
+        // Hit on stepping in, then once per interval
+        logic.update(&mut world, &mut events, Some((player, trap_pos)), 0.5);
+        assert_eq!(health(&world), 95);
+        assert_eq!(poison_stacks(&world), Some(1));
+        assert_eq!(logic.messages().collect::<Vec<_>>(), vec!["Script: no status effect named 'frostbite'"]);
+        logic.update(&mut world, &mut events, Some((player, trap_pos)), 0.5);
+        assert_eq!(health(&world), 95);
+        logic.update(&mut world, &mut events, Some((player, trap_pos)), 0.6);
+        assert_eq!(health(&world), 90);
+        assert_eq!(poison_stacks(&world), Some(2));
+
+        // The tonic is a buff pickup
+        logic.update(&mut world, &mut events, Some((player, tonic_pos)), 0.1);
+        assert!(status::speed_multiplier(&world, player) > 1.0);
+    }
+
+    #[test]
+    fn test_trigger_volume_plays_cutscene_once() {
+        let mut level = Level::new();
+        let mut room = crate::world::Room::new(0, Vec3::ZERO, 4, 4);
+        let mut volume = crate::world::TriggerVolume::from_corners("gate", (0.0, 0.0), (SECTOR_SIZE, SECTOR_SIZE));
+        volume.cutscene = Some("opening".to_string());
+        room.triggers.push(volume);
+        level.rooms.push(room);
+        let mut cutscene = Cutscene::new("opening");
+        cutscene.timeline = "0 music theme 1\n0 animate guard wave\n0.5 move guard 0 0 1024 1\n".to_string();
+        level.cutscenes.push(cutscene);
+        level.scripts.push(crate::world::LevelScript {
+            name: "s".to_string(),
+            source: "on cutscene_end opening\n  message \"done\"\nend".to_string(),
+            enabled: true,
+        });
+
+        let mut world = World::new();
+        let mut events = Events::new();
+        let mut logic = LevelLogic::start(&level, &AssetLibrary::default(), &mut world);
+        logic.named.push(("guard".to_string(), (0, 7)));
+        let player = world.spawn();
+        let inside = Some((player, Vec3::new(500.0, 0.0, 500.0)));
+
+        logic.update(&mut world, &mut events, inside, 0.1);
+        assert_eq!(logic.cutscene().map(|c| c.name()), Some("opening"));
+        assert_eq!(logic.take_music(), vec![MusicRequest::play_now("theme".to_string(), 1)]);
+        assert!(logic.take_music().is_empty());
+        assert_eq!(events.animation.len(), 1);
+
+        // Halfway through the move, then skipped: the guard lands at the end
+        logic.update(&mut world, &mut events, inside, 0.5);
+        assert!(logic.cutscene_poses().is_empty());
+        logic.update(&mut world, &mut events, inside, 0.5);
+        let (object, offset, _) = logic.cutscene_poses()[0];
+        assert_eq!(object, (0, 7));
+        assert!((offset.z - 512.0).abs() < 1.0);
+        logic.request_skip();
+        logic.update(&mut world, &mut events, inside, 0.0);
+        assert!(logic.cutscene().is_none());
+        assert_eq!(logic.cutscene_poses()[0].1.z, 1024.0);
+
+        // Scripts hear the end; walking back in doesn't replay it
+        logic.update(&mut world, &mut events, None, 0.1);
+        assert_eq!(logic.messages().collect::<Vec<_>>(), vec!["done"]);
+        logic.update(&mut world, &mut events, Some((player, Vec3::new(3000.0, 0.0, 3000.0))), 0.1);
+        logic.update(&mut world, &mut events, inside, 0.1);
+        assert!(logic.cutscene().is_none());
+    }
+}
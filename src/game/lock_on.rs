@@ -0,0 +1,208 @@
+//! Lock-On Targeting
+//!
+//! Souls-style target lock for the character camera. Pressing LockOn picks the
+//! nearest targetable entity in front of the camera; while locked, the camera
+//! swings behind the player to frame both player and target, movement becomes
+//! strafe-relative to the target, and right-stick flicks cycle to the next
+//! target on that side. The lock breaks when the target dies, despawns or
+//! gets too far away.
+
+use crate::rasterizer::Vec3;
+use super::{Entity, World};
+
+/// Half-angle of the view cone a new target must be inside (cosine, ~50 degrees)
+const VIEW_CONE_COS: f32 = 0.64;
+
+/// Lock breaks when the target is this much further than the lock-on range
+const BREAK_RANGE_FACTOR: f32 = 1.25;
+
+/// Right-stick deflection that counts as a flick
+const FLICK_THRESHOLD: f32 = 0.7;
+
+/// Deflection the stick must return under before the next flick
+const FLICK_RESET: f32 = 0.3;
+
+/// How far the camera look-at point moves from the player toward the target
+pub const FRAMING_BLEND: f32 = 0.35;
+
+/// Camera yaw turn rate while locked (fraction of the remaining angle per second)
+pub const YAW_FOLLOW_RATE: f32 = 8.0;
+
+/// Lock-on state for the player
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LockOn {
+    /// Locked target, if any
+    pub target: Option<Entity>,
+    /// Cleared after a flick until the stick returns to center
+    flick_latched: bool,
+}
+
+impl LockOn {
+    pub fn is_locked(&self) -> bool {
+        self.target.is_some()
+    }
+
+    pub fn release(&mut self) {
+        self.target = None;
+        self.flick_latched = false;
+    }
+
+    /// Turn right-stick X into a flick direction (-1 left, +1 right).
+    /// One flick per deflection: the stick has to return to center first.
+    pub fn flick(&mut self, stick_x: f32) -> Option<f32> {
+        if stick_x.abs() < FLICK_RESET {
+            self.flick_latched = false;
+            return None;
+        }
+        if !self.flick_latched && stick_x.abs() >= FLICK_THRESHOLD {
+            self.flick_latched = true;
+            return Some(stick_x.signum());
+        }
+        None
+    }
+
+    /// Drop the target if it died, despawned or went out of range
+    pub fn validate(&mut self, world: &World, player_pos: Vec3, range: f32) {
+        let Some(target) = self.target else { return };
+        let keep = target_position(world, target)
+            .map(|pos| (pos - player_pos).len() <= range * BREAK_RANGE_FACTOR)
+            .unwrap_or(false);
+        if !keep {
+            self.release();
+        }
+    }
+}
+
+/// Position of a targetable entity (alive enemy with a transform)
+pub fn target_position(world: &World, entity: Entity) -> Option<Vec3> {
+    if !world.is_alive(entity) || !world.enemies.contains(entity) {
+        return None;
+    }
+    if world.health.get(entity).map(|h| h.is_dead()).unwrap_or(false) {
+        return None;
+    }
+    world.transforms.get(entity).map(|t| t.position)
+}
+
+/// All targetable entities and their positions
+pub fn targetable(world: &World) -> Vec<(Entity, Vec3)> {
+    world.enemies
+        .iter()
+        .filter_map(|(idx, _)| {
+            let entity = world.entity_at(idx)?;
+            target_position(world, entity).map(|pos| (entity, pos))
+        })
+        .collect()
+}
+
+/// Nearest candidate to the player within `range` that is inside the camera's
+/// view cone
+pub fn pick_target(
+    candidates: &[(Entity, Vec3)],
+    player_pos: Vec3,
+    camera_pos: Vec3,
+    camera_forward: Vec3,
+    range: f32,
+) -> Option<Entity> {
+    candidates
+        .iter()
+        .filter(|(_, pos)| {
+            let to_target = *pos - camera_pos;
+            let dist = to_target.len();
+            dist > 0.0 && to_target.dot(camera_forward) / dist >= VIEW_CONE_COS
+        })
+        .map(|&(entity, pos)| (entity, (pos - player_pos).len()))
+        .filter(|&(_, dist)| dist <= range)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _)| entity)
+}
+
+/// Next candidate around the player from the current target, turning toward
+/// increasing yaw (`direction` > 0) or decreasing yaw (< 0) by the smallest
+/// angle. None if there is nothing on that side within `range`.
+pub fn cycle_target(
+    candidates: &[(Entity, Vec3)],
+    current: Entity,
+    player_pos: Vec3,
+    direction: f32,
+    range: f32,
+) -> Option<Entity> {
+    let current_pos = candidates.iter().find(|(e, _)| *e == current)?.1;
+    let current_yaw = yaw_towards(player_pos, current_pos);
+    candidates
+        .iter()
+        .filter(|&&(entity, pos)| entity != current && (pos - player_pos).len() <= range)
+        .filter_map(|&(entity, pos)| {
+            let turn = wrap_angle(yaw_towards(player_pos, pos) - current_yaw) * direction.signum();
+            (turn > 0.0).then_some((entity, turn))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _)| entity)
+}
+
+/// Camera yaw that looks from `from` toward `to` (same convention as the
+/// character camera: forward = (sin yaw, 0, cos yaw))
+pub fn yaw_towards(from: Vec3, to: Vec3) -> f32 {
+    let d = to - from;
+    d.x.atan2(d.z)
+}
+
+/// Wrap an angle to -PI..PI
+pub fn wrap_angle(angle: f32) -> f32 {
+    let a = angle.rem_euclid(std::f32::consts::TAU);
+    if a > std::f32::consts::PI {
+        a - std::f32::consts::TAU
+    } else {
+        a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> Vec<(Entity, Vec3)> {
+        vec![
+            (Entity::new(1, 0), Vec3::new(0.0, 0.0, 2000.0)),    // ahead
+            (Entity::new(2, 0), Vec3::new(1500.0, 0.0, 3000.0)), // ahead, +X
+            (Entity::new(3, 0), Vec3::new(-1500.0, 0.0, 3000.0)), // ahead, -X
+            (Entity::new(4, 0), Vec3::new(0.0, 0.0, -1000.0)),   // behind the camera (closest)
+        ]
+    }
+
+    #[test]
+    fn picks_nearest_in_view() {
+        let forward = Vec3::new(0.0, 0.0, 1.0);
+        let camera = Vec3::new(0.0, 0.0, -500.0);
+        let picked = pick_target(&candidates(), Vec3::ZERO, camera, forward, 10000.0);
+        assert_eq!(picked, Some(Entity::new(1, 0)));
+        assert_eq!(pick_target(&candidates(), Vec3::ZERO, camera, forward, 1000.0), None);
+    }
+
+    #[test]
+    fn cycles_left_and_right() {
+        let c = candidates();
+        let current = Entity::new(1, 0);
+        // With +Z ahead, +X is the larger yaw
+        assert_eq!(cycle_target(&c, current, Vec3::ZERO, 1.0, 10000.0), Some(Entity::new(2, 0)));
+        assert_eq!(cycle_target(&c, current, Vec3::ZERO, -1.0, 10000.0), Some(Entity::new(3, 0)));
+        assert_eq!(cycle_target(&c, current, Vec3::ZERO, 1.0, 100.0), None);
+    }
+
+    #[test]
+    fn flick_needs_stick_to_recenter() {
+        let mut lock = LockOn::default();
+        assert_eq!(lock.flick(0.9), Some(1.0));
+        assert_eq!(lock.flick(0.95), None);
+        assert_eq!(lock.flick(0.5), None);
+        assert_eq!(lock.flick(0.0), None);
+        assert_eq!(lock.flick(-0.8), Some(-1.0));
+    }
+
+    #[test]
+    fn wraps_angles() {
+        let pi = std::f32::consts::PI;
+        assert!((wrap_angle(1.5 * pi) + 0.5 * pi).abs() < 1e-5);
+        assert!((wrap_angle(-1.5 * pi) - 0.5 * pi).abs() < 1e-5);
+    }
+}
@@ -8,7 +8,31 @@
 //! - Entity: Generational index for safe entity references
 //! - Component: Plain data structs attached to entities
 //! - World: Container for all entities and their components
+//! - Query: Typed iteration over entities with a set of components
+//! - Schedule: Ordered, named gameplay systems run every tick
 //! - Event: Decoupled communication between systems
+//! - Script: Level logic scripts stored in the level, run by LevelLogic
+//! - Analytics: Opt-in playtest recording for the editor's heatmaps
+//! - Lock-on: Souls-style camera target lock for the character controller
+//! - Rail camera: fixed and spline-rail shots from the level's camera volumes, blended on enter/exit
+//! - Weather: rain/snow/ash/dust particles from the level's weather preset
+//! - Particles: emitter components of placed assets drawn as camera-facing quads
+//! - Console: text commands for driving the Test tab from external test runners
+//! - HUD: health/stamina/item/prompt/boss widgets drawn into the framebuffer
+//! - Sound: gameplay events and footsteps mapped to the level's sound effects
+//! - Spatial: panning, distance attenuation and room reverb sends for positional sounds
+//! - Climb: ladder grabbing, climbing and dismounts for the character controller
+//! - Swim: swimming, breath and drowning in room water for the character controller
+//! - Cutscene: camera flights with timed events, started by triggers and scripts
+//! - Inspector: live entity/component overlay with pause and frame stepping
+//! - Replay: per-tick input and RNG seed recording, saved to a file and played back deterministically
+//! - Nav: walkable-sector graph and A* paths for patrolling enemies
+//! - Combat: stamina-costed attacks with per-frame hitboxes, poise and stagger, guarding and dodge rolls
+//! - Status: data-driven poison/bleed/slow/buff effects applied by hazards, pickups, enemies and scripts
+//! - Platform: moving platforms and elevators that carry the characters standing on them
+//! - Dialogue: branching NPC conversations with typewriter text, choices and world flags
+//! - Equipment: weapons and armor the player picks up and equips from the inventory screen
+//! - Music: scripts and cutscenes switch songs, jump sections and mute stems on the tracker's beat
 //!
 //! Design philosophy:
 //! - Simple over flexible (we know what game we're making)
@@ -21,17 +45,42 @@
 pub mod entity;
 pub mod component;
 pub mod world;
+pub mod query;
+pub mod schedule;
 pub mod event;
 pub mod transform;
 pub mod components;
 pub mod collision;
 pub mod runtime;
 pub mod renderer;
+pub mod script;
+pub mod level_logic;
+pub mod analytics;
+pub mod lock_on;
+pub mod rail_camera;
+pub mod weather;
+pub mod particles;
+pub mod console;
+pub mod hud;
+pub mod sound;
+pub mod spatial;
+pub mod climb;
+pub mod swim;
+pub mod cutscene;
+pub mod inspector;
+pub mod replay;
+pub mod nav;
+pub mod status;
+pub mod combat;
+pub mod platform;
+pub mod dialogue;
+pub mod equipment;
+pub mod music;
 
 // Re-export main types
 pub use entity::Entity;
 pub use world::World;
 pub use event::Events;
-pub use transform::GlobalTransform;
 pub use runtime::GameToolState;
 pub use renderer::draw_test_viewport;
+pub use console::{Console, ConsoleCommand, ConsoleReply, ConsoleRequest};
@@ -0,0 +1,176 @@
+//! Music Director
+//!
+//! Lets the level drive its soundtrack. Scripts (and so triggers, through
+//! their `on enter` handlers) and cutscenes ask for song changes, jumps to
+//! another arrangement entry - the combat section, say - and stem mutes:
+//!
+//! ```text
+//! on enter arena
+//!     music jump 4 bar        # combat section, on the next bar
+//!     music unmute 5          # drums layer, on the next beat
+//! end
+//! ```
+//!
+//! - `music play <song> [<pattern>] [<sync>]` - switch song, starting at an
+//!   arrangement position (default 0)
+//! - `music jump <pattern> [<sync>]` - continue from an arrangement position
+//! - `music mute <channel> [<sync>]`, `music unmute <channel> [<sync>]` -
+//!   silence or bring back a channel (1-based, as in the tracker)
+//! - `music stop [<sync>]`
+//!
+//! Sync is `now` (next row), `beat` (the default), `bar` or `pattern` (start
+//! of the next arrangement entry). Requests wait in `LevelLogic::take_music`;
+//! the host runs them through `MusicDirector` and queues what's left as
+//! tracker cues, which land on the tracker's row clock (`tracker::cue`).
+
+use super::script::{LineParser, Token};
+use crate::tracker::CueSync;
+
+/// A change to the level's music
+#[derive(Debug, Clone, PartialEq)]
+pub enum MusicCommand {
+    Play { song: String, pattern: usize },
+    Jump(usize),
+    /// Channel (0-based) and whether it's muted
+    Mute(usize, bool),
+    Stop,
+}
+
+/// A change and where it may land
+#[derive(Debug, Clone, PartialEq)]
+pub struct MusicRequest {
+    pub command: MusicCommand,
+    pub sync: CueSync,
+}
+
+impl MusicRequest {
+    /// Cutscene music starts on the next row, in step with the camera
+    pub fn play_now(song: String, pattern: usize) -> Self {
+        Self { command: MusicCommand::Play { song, pattern }, sync: CueSync::Now }
+    }
+}
+
+/// Parse the arguments of a `music` script command
+pub(super) fn parse_music(p: &mut LineParser<'_>) -> Result<MusicRequest, String> {
+    let command = p.name("music command (play, jump, mute, unmute, stop)")?;
+    let command = match command.as_str() {
+        "play" => {
+            let song = p.name("song name")?;
+            let pattern = match p.peek() {
+                Some(Token::Number(..)) => whole(p, "pattern")?,
+                _ => 0,
+            };
+            MusicCommand::Play { song, pattern }
+        }
+        "jump" => MusicCommand::Jump(whole(p, "pattern")?),
+        "mute" | "unmute" => {
+            let muted = command == "mute";
+            let channel = whole(p, "channel")?;
+            if channel == 0 {
+                return Err("channels start at 1".to_string());
+            }
+            MusicCommand::Mute(channel - 1, muted)
+        }
+        "stop" => MusicCommand::Stop,
+        other => return Err(format!("unknown music command '{}' (play, jump, mute, unmute, stop)", other)),
+    };
+    let sync = match p.peek() {
+        Some(Token::Word(w)) => {
+            p.advance();
+            CueSync::from_name(w).ok_or_else(|| format!("unknown sync '{}' (now, beat, bar, pattern)", w))?
+        }
+        _ => CueSync::default(),
+    };
+    Ok(MusicRequest { command, sync })
+}
+
+fn whole(p: &mut LineParser<'_>, what: &str) -> Result<usize, String> {
+    let n = p.number(what)?;
+    if n < 0.0 || n.fract() != 0.0 {
+        return Err(format!("{} must be a whole number", what));
+    }
+    Ok(n as usize)
+}
+
+/// What the level's music is doing, so requests can be made sensible before
+/// they reach the tracker
+#[derive(Debug, Clone, Default)]
+pub struct MusicDirector {
+    /// Song playing (None = silent)
+    song: Option<String>,
+}
+
+impl MusicDirector {
+    pub fn song(&self) -> Option<&str> {
+        self.song.as_deref()
+    }
+
+    /// The request to pass on to the tracker, if any. Playing the song that's
+    /// already on jumps instead of restarting it; with nothing playing, jumps,
+    /// mutes and stops have nothing to change.
+    pub fn direct(&mut self, request: MusicRequest) -> Option<MusicRequest> {
+        let command = match request.command {
+            MusicCommand::Play { song, pattern } if self.song() == Some(song.as_str()) => MusicCommand::Jump(pattern),
+            MusicCommand::Play { song, pattern } => {
+                self.song = Some(song.clone());
+                MusicCommand::Play { song, pattern }
+            }
+            _ if self.song.is_none() => return None,
+            MusicCommand::Stop => {
+                self.song = None;
+                MusicCommand::Stop
+            }
+            command => command,
+        };
+        Some(MusicRequest { command, sync: request.sync })
+    }
+
+    /// The tracker stopped (the song ended, failed to load or play ended)
+    pub fn stopped(&mut self) {
+        self.song = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::script::tokenize;
+
+    fn parse(line: &str) -> Result<MusicRequest, String> {
+        let tokens = tokenize(line)?;
+        let mut p = LineParser::new(&tokens);
+        let request = parse_music(&mut p)?;
+        p.expect_end()?;
+        Ok(request)
+    }
+
+    #[test]
+    fn test_parse_music() {
+        let play = MusicCommand::Play { song: "theme".to_string(), pattern: 2 };
+        assert_eq!(parse("play theme 2 bar"), Ok(MusicRequest { command: play, sync: CueSync::Bar }));
+        assert_eq!(parse("unmute 5").unwrap(), MusicRequest { command: MusicCommand::Mute(4, false), sync: CueSync::Beat });
+        assert_eq!(parse("mute 1 now").unwrap().command, MusicCommand::Mute(0, true));
+        assert!(parse("mute 0").is_err());
+        assert!(parse("jump 1.5").is_err());
+        assert!(parse("stop soon").is_err());
+        assert!(parse("fade").is_err());
+    }
+
+    #[test]
+    fn test_director() {
+        let mut director = MusicDirector::default();
+        // Nothing playing: only a song can start
+        assert_eq!(director.direct(MusicRequest { command: MusicCommand::Jump(1), sync: CueSync::Bar }), None);
+        let play = MusicRequest::play_now("theme".to_string(), 0);
+        assert_eq!(director.direct(play.clone()), Some(play));
+        assert_eq!(director.song(), Some("theme"));
+
+        // The same song again continues it from the pattern
+        let again = MusicRequest { command: MusicCommand::Play { song: "theme".to_string(), pattern: 3 }, sync: CueSync::Bar };
+        assert_eq!(director.direct(again).unwrap().command, MusicCommand::Jump(3));
+
+        let stop = MusicRequest { command: MusicCommand::Stop, sync: CueSync::Pattern };
+        assert_eq!(director.direct(stop.clone()), Some(stop));
+        assert_eq!(director.song(), None);
+    }
+}
@@ -0,0 +1,419 @@
+//! Navigation
+//!
+//! Enemies find their way around a graph of walkable sectors, built from the
+//! level's floors when play starts. A sector is a node when it has a floor
+//! and enough headroom for a character; neighbouring sectors (in the same
+//! room, or across a portal into the next one) are linked when the floor step
+//! between them is climbable and no solid wall stands in the way. Diagonal
+//! links need both of their side links, so paths don't cut corners.
+//!
+//! `NavGraph::find_path` runs A* over the graph. Patrolling enemies
+//! (`Patrol`) use it to walk to spots around their home instead of straight
+//! into walls. The Test tab's debug menu ("Nav Graph") draws the graph and the
+//! paths being walked.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::rasterizer::Vec3;
+use crate::world::{Direction, Level, Room, Sector, SECTOR_SIZE};
+
+/// Seconds a patrolling enemy rests between walks
+pub const PATROL_REST: f32 = 2.0;
+/// Walking speed of patrolling enemies (world units per second)
+pub const PATROL_SPEED: f32 = SECTOR_SIZE;
+
+/// Straight neighbour directions, in the order `straight` links are stored
+const STRAIGHT: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+/// A walkable sector
+#[derive(Debug, Clone, Copy)]
+pub struct NavNode {
+    pub room: usize,
+    pub x: usize,
+    pub z: usize,
+    /// World-space floor point at the sector's center
+    pub position: Vec3,
+}
+
+/// Walkable sectors and the links between them
+#[derive(Debug, Clone, Default)]
+pub struct NavGraph {
+    pub nodes: Vec<NavNode>,
+    /// Links per node: (node, cost in world units)
+    edges: Vec<Vec<(usize, f32)>>,
+    /// (room, x, z) -> node
+    lookup: HashMap<(usize, usize, usize), usize>,
+}
+
+impl NavGraph {
+    /// Build the graph for a character of the player's size
+    pub fn build(level: &Level) -> Self {
+        let settings = &level.player_settings;
+        Self::build_for(level, settings.height, settings.step_height)
+    }
+
+    /// Build the graph for characters `height` tall that can step up `step`
+    pub fn build_for(level: &Level, height: f32, step: f32) -> Self {
+        let mut graph = Self::default();
+        for (r, room) in level.rooms.iter().enumerate() {
+            for x in 0..room.width {
+                for z in 0..room.depth {
+                    let Some(position) = room.get_sector(x, z).and_then(|s| walkable(room, s, x, z, height)) else {
+                        continue;
+                    };
+                    graph.lookup.insert((r, x, z), graph.nodes.len());
+                    graph.nodes.push(NavNode { room: r, x, z, position });
+                    graph.edges.push(Vec::new());
+                }
+            }
+        }
+
+        let straight: Vec<[Option<usize>; 4]> = (0..graph.nodes.len())
+            .map(|i| STRAIGHT.map(|dir| graph.neighbour(level, i, dir, height, step)))
+            .collect();
+        for (i, links) in straight.iter().enumerate() {
+            for &j in links.iter().flatten() {
+                if j > i {
+                    graph.link(i, j);
+                }
+            }
+        }
+
+        // Diagonals: both ways around the corner must be open
+        for (i, links) in straight.iter().enumerate() {
+            for (a, b) in [(1, 0), (1, 2), (3, 0), (3, 2)] {
+                let (Some(side_a), Some(side_b)) = (links[a], links[b]) else {
+                    continue;
+                };
+                if let Some(corner) = straight[side_a][b].filter(|&c| c > i && straight[side_b][a] == Some(c)) {
+                    graph.link(i, corner);
+                }
+            }
+        }
+        graph
+    }
+
+    fn link(&mut self, a: usize, b: usize) {
+        let cost = (self.nodes[a].position - self.nodes[b].position).len();
+        self.edges[a].push((b, cost));
+        self.edges[b].push((a, cost));
+    }
+
+    /// The node next to `i` in a straight direction, if the way there is open.
+    /// Past the edge of its room, looks in the rooms its portals lead to.
+    fn neighbour(&self, level: &Level, i: usize, dir: Direction, height: f32, step: f32) -> Option<usize> {
+        let node = self.nodes[i];
+        let room = &level.rooms[node.room];
+        let sector = room.get_sector(node.x, node.z)?;
+        let (dx, dz) = dir.offset();
+        let (nx, nz) = (node.x as i32 + dx, node.z as i32 + dz);
+
+        let in_room = if nx >= 0 && nz >= 0 { room.get_sector(nx as usize, nz as usize) } else { None };
+        if let Some(other) = in_room {
+            let j = *self.lookup.get(&(node.room, nx as usize, nz as usize))?;
+            return passable(room, sector, room, other, dir, height, step).then_some(j);
+        }
+
+        // The sector's center in world space, looked up in each connected room
+        let center = room.position + Vec3::new((nx as f32 + 0.5) * SECTOR_SIZE, 0.0, (nz as f32 + 0.5) * SECTOR_SIZE);
+        let mut targets: Vec<usize> = room.portals.iter().map(|p| p.target_room).collect();
+        targets.sort_unstable();
+        targets.dedup();
+        targets.into_iter().find_map(|r| {
+            let target = level.rooms.get(r)?;
+            let local = center - target.position;
+            let (tx, tz) = ((local.x / SECTOR_SIZE).floor(), (local.z / SECTOR_SIZE).floor());
+            if tx < 0.0 || tz < 0.0 {
+                return None;
+            }
+            let (tx, tz) = (tx as usize, tz as usize);
+            let other = target.get_sector(tx, tz)?;
+            let j = *self.lookup.get(&(r, tx, tz))?;
+            passable(room, sector, target, other, dir, height, step).then_some(j)
+        })
+    }
+
+    /// Nodes linked to `i`, with the cost of each link
+    pub fn links(&self, i: usize) -> &[(usize, f32)] {
+        &self.edges[i]
+    }
+
+    /// Every link once, as world-space (from, to) floor points
+    pub fn edges(&self) -> impl Iterator<Item = (Vec3, Vec3)> + '_ {
+        self.edges.iter().enumerate().flat_map(move |(i, links)| {
+            links.iter()
+                .filter(move |(j, _)| *j > i)
+                .map(move |(j, _)| (self.nodes[i].position, self.nodes[*j].position))
+        })
+    }
+
+    /// Node for a sector, if it's walkable
+    pub fn node_at(&self, room: usize, x: usize, z: usize) -> Option<usize> {
+        self.lookup.get(&(room, x, z)).copied()
+    }
+
+    /// Node whose floor point is closest to `point`
+    pub fn nearest_node(&self, point: Vec3) -> Option<usize> {
+        self.nodes.iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let d = node.position - point;
+                (i, d.dot(d))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// A* from one node to another: the nodes to walk through, both ends
+    /// included. None when the goal can't be reached.
+    pub fn find_node_path(&self, start: usize, goal: usize) -> Option<Vec<usize>> {
+        if start >= self.nodes.len() || goal >= self.nodes.len() {
+            return None;
+        }
+        let goal_position = self.nodes[goal].position;
+        let heuristic = |i: usize| (self.nodes[i].position - goal_position).len();
+
+        let mut cost = vec![f32::INFINITY; self.nodes.len()];
+        let mut came_from = vec![usize::MAX; self.nodes.len()];
+        let mut open = BinaryHeap::new();
+        cost[start] = 0.0;
+        open.push(OpenNode { node: start, estimate: heuristic(start) });
+
+        while let Some(OpenNode { node, estimate }) = open.pop() {
+            if node == goal {
+                let mut path = vec![goal];
+                let mut at = goal;
+                while at != start {
+                    at = came_from[at];
+                    path.push(at);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            // Stale entry: a cheaper way here was already expanded
+            if estimate > cost[node] + heuristic(node) + 1e-3 {
+                continue;
+            }
+            for &(next, step) in &self.edges[node] {
+                let through = cost[node] + step;
+                if through < cost[next] {
+                    cost[next] = through;
+                    came_from[next] = node;
+                    open.push(OpenNode { node: next, estimate: through + heuristic(next) });
+                }
+            }
+        }
+        None
+    }
+
+    /// World-space floor points from the node nearest `from` to the node
+    /// nearest `to`. None when there's no way there.
+    pub fn find_path(&self, from: Vec3, to: Vec3) -> Option<Vec<Vec3>> {
+        let start = self.nearest_node(from)?;
+        let goal = self.nearest_node(to)?;
+        let nodes = self.find_node_path(start, goal)?;
+        Some(nodes.into_iter().map(|i| self.nodes[i].position).collect())
+    }
+}
+
+/// A* frontier entry; the heap pops the lowest estimate first
+#[derive(Debug, Clone, Copy)]
+struct OpenNode {
+    node: usize,
+    estimate: f32,
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+/// The sector's center floor point, if a character `height` tall can stand in it.
+/// Sectors split by a solid diagonal wall are left out.
+fn walkable(room: &Room, sector: &Sector, x: usize, z: usize, height: f32) -> Option<Vec3> {
+    let floor = sector.floor.as_ref()?;
+    let split = [Direction::NwSe, Direction::NeSw].iter().any(|&d| sector.walls(d).iter().any(|w| w.solid));
+    if split {
+        return None;
+    }
+    if let Some(ceiling) = &sector.ceiling {
+        let lowest = ceiling.heights.iter().copied().fold(f32::INFINITY, f32::min);
+        let highest = floor.heights.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        if lowest - highest < height {
+            return None;
+        }
+    }
+    let y = floor.interpolate_height(0.5, 0.5);
+    Some(room.position + Vec3::new((x as f32 + 0.5) * SECTOR_SIZE, y, (z as f32 + 0.5) * SECTOR_SIZE))
+}
+
+/// Can a character walk from sector `a` to the sector `b` next to it in `dir`?
+fn passable(room_a: &Room, a: &Sector, room_b: &Room, b: &Sector, dir: Direction, height: f32, step: f32) -> bool {
+    let back = dir.opposite();
+    let (Some(floor_a), Some(floor_b)) = (a.floor_height_at_edge(dir), b.floor_height_at_edge(back)) else {
+        return false;
+    };
+    let (floor_a, floor_b) = (room_a.position.y + floor_a, room_b.position.y + floor_b);
+    if (floor_a - floor_b).abs() > step {
+        return false;
+    }
+    let floor = floor_a.max(floor_b);
+    let ceiling_a = a.ceiling_height_at_edge(dir).map_or(f32::INFINITY, |c| room_a.position.y + c);
+    let ceiling_b = b.ceiling_height_at_edge(back).map_or(f32::INFINITY, |c| room_b.position.y + c);
+    if ceiling_a.min(ceiling_b) - floor < height {
+        return false;
+    }
+
+    // Solid walls between the step height and head height block the way
+    let blocks = |room: &Room, sector: &Sector, d: Direction| {
+        sector.walls(d).iter().any(|w| {
+            w.solid && room.position.y + w.y_min() < floor + height && room.position.y + w.y_max() > floor + step
+        })
+    };
+    !blocks(room_a, a, dir) && !blocks(room_b, b, back)
+}
+
+/// Move along a path's points by `distance`, dropping the points reached.
+/// Returns the new position and the direction of travel (zero once there).
+pub fn walk_path(path: &mut Vec<Vec3>, position: Vec3, distance: f32) -> (Vec3, Vec3) {
+    let mut position = position;
+    let mut left = distance;
+    let mut direction = Vec3::ZERO;
+    while let Some(&next) = path.first() {
+        let to_next = next - position;
+        let length = to_next.len();
+        if length > 1e-3 {
+            direction = to_next * (1.0 / length);
+        }
+        if length > left {
+            return (position + direction * left, direction);
+        }
+        position = next;
+        left -= length;
+        path.remove(0);
+    }
+    (position, direction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{Portal, TextureRef};
+
+    const HEIGHT: f32 = 1024.0;
+    const STEP: f32 = 384.0;
+
+    fn open_room(position: Vec3, width: usize, depth: usize) -> Room {
+        let mut room = Room::new(0, position, width, depth);
+        for x in 0..width {
+            for z in 0..depth {
+                room.set_floor(x, z, 0.0, TextureRef::none());
+                room.set_ceiling(x, z, SECTOR_SIZE * 2.0, TextureRef::none());
+            }
+        }
+        room
+    }
+
+    fn level(rooms: Vec<Room>) -> Level {
+        let mut level = Level::new();
+        level.rooms = rooms;
+        level
+    }
+
+    fn path_cells(graph: &NavGraph, path: &[usize]) -> Vec<(usize, usize)> {
+        path.iter().map(|&i| (graph.nodes[i].x, graph.nodes[i].z)).collect()
+    }
+
+    #[test]
+    fn test_open_room_paths_go_diagonally() {
+        let graph = NavGraph::build_for(&level(vec![open_room(Vec3::ZERO, 3, 3)]), HEIGHT, STEP);
+        assert_eq!(graph.nodes.len(), 9);
+        // Center: 4 straight + 4 diagonal links
+        assert_eq!(graph.links(graph.node_at(0, 1, 1).unwrap()).len(), 8);
+        assert_eq!(graph.edges().count(), 12 + 8);
+
+        let path = graph.find_node_path(graph.node_at(0, 0, 0).unwrap(), graph.node_at(0, 2, 2).unwrap()).unwrap();
+        assert_eq!(path_cells(&graph, &path), vec![(0, 0), (1, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn test_walls_and_high_steps_are_walked_around() {
+        // A wall between columns 0 and 1 on rows 0-1, and a ledge too high to step onto at (1, 2)
+        let mut room = open_room(Vec3::ZERO, 3, 3);
+        for z in 0..2 {
+            room.add_wall(0, z, Direction::East, 0.0, SECTOR_SIZE * 2.0, TextureRef::none());
+        }
+        room.set_floor(1, 2, STEP * 2.0, TextureRef::none());
+        let graph = NavGraph::build_for(&level(vec![room]), HEIGHT, STEP);
+
+        let start = graph.node_at(0, 0, 0).unwrap();
+        assert!(graph.links(start).iter().all(|&(j, _)| graph.nodes[j].x == 0));
+        // Around the wall's open end is the ledge, so there's no way across
+        assert!(graph.find_node_path(start, graph.node_at(0, 2, 0).unwrap()).is_none());
+
+        // A low step is fine
+        let mut room = open_room(Vec3::ZERO, 3, 3);
+        for z in 0..2 {
+            room.add_wall(0, z, Direction::East, 0.0, SECTOR_SIZE * 2.0, TextureRef::none());
+        }
+        room.set_floor(1, 2, STEP * 0.5, TextureRef::none());
+        let graph = NavGraph::build_for(&level(vec![room]), HEIGHT, STEP);
+        let path = graph.find_node_path(graph.node_at(0, 0, 0).unwrap(), graph.node_at(0, 1, 0).unwrap()).unwrap();
+        assert_eq!(path_cells(&graph, &path), vec![(0, 0), (0, 1), (0, 2), (1, 2), (1, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn test_low_ceilings_and_portals() {
+        // Two rooms side by side; only linked through a portal
+        let mut west = open_room(Vec3::ZERO, 2, 1);
+        let east = open_room(Vec3::new(SECTOR_SIZE * 2.0, 0.0, 0.0), 2, 1);
+        west.set_ceiling(0, 0, HEIGHT * 0.5, TextureRef::none());
+        let mut rooms = vec![west, east];
+        let graph = NavGraph::build_for(&level(rooms.clone()), HEIGHT, STEP);
+        assert!(graph.node_at(0, 0, 0).is_none());
+        assert!(graph.find_node_path(graph.node_at(0, 1, 0).unwrap(), graph.node_at(1, 0, 0).unwrap()).is_none());
+
+        rooms[0].portals.push(Portal::new(1, [Vec3::ZERO; 4], Vec3::new(-1.0, 0.0, 0.0)));
+        rooms[1].portals.push(Portal::new(0, [Vec3::ZERO; 4], Vec3::new(1.0, 0.0, 0.0)));
+        let graph = NavGraph::build_for(&level(rooms), HEIGHT, STEP);
+        let path = graph.find_path(Vec3::new(SECTOR_SIZE * 1.5, 0.0, 0.0), Vec3::new(SECTOR_SIZE * 3.9, 0.0, 0.0)).unwrap();
+        assert_eq!(path.len(), 3);
+        assert_eq!(path[2].x, SECTOR_SIZE * 3.5);
+    }
+
+    #[test]
+    fn test_patrol_spots_stay_within_radius() {
+        let home = Vec3::new(5000.0, 100.0, -2000.0);
+        let mut patrol = crate::game::components::Patrol::new(home, 1500.0, PATROL_SPEED, 0.0, 42);
+        let spots: Vec<Vec3> = (0..100).map(|_| patrol.next_spot()).collect();
+        assert!(spots.iter().all(|s| (*s - home).len() <= 1500.0 + 1e-2 && s.y == home.y));
+        // Not stuck on one spot
+        assert!(spots.windows(2).all(|w| (w[0] - w[1]).len() > 1e-3));
+    }
+
+    #[test]
+    fn test_walk_path_consumes_points() {
+        let mut path = vec![Vec3::new(100.0, 0.0, 0.0), Vec3::new(100.0, 0.0, 100.0)];
+        let (position, direction) = walk_path(&mut path, Vec3::ZERO, 150.0);
+        assert_eq!(path.len(), 1);
+        assert!((position.z - 50.0).abs() < 1e-3 && (direction.z - 1.0).abs() < 1e-6);
+        let (position, _) = walk_path(&mut path, position, 500.0);
+        assert!(path.is_empty() && (position.z - 100.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,388 @@
+//! Particles
+//!
+//! Simulates the Particle components of placed asset instances while playing
+//! and draws them as camera-facing quads. Each particle is projected to the
+//! screen and filled as a square sized by its distance, depth-tested against
+//! the scene (without writing depth) and blended by opacity or added on top.
+//! Quads are drawn back to front so alpha particles layer correctly.
+//!
+//! `ParticleEmitter` also runs on its own for the asset editor's preview.
+
+use super::level_logic::ObjectRef;
+use crate::asset::{AssetComponent, AssetLibrary, ParticleBlend, ParticleEmitterDef};
+use crate::rasterizer::{
+    BlendMode, Camera, Color as RasterColor, Framebuffer, Vec3, perspective_transform, project,
+};
+use crate::world::Level;
+
+/// Particles closer to the camera than this are not drawn
+const NEAR_CLIP: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+    position: Vec3,
+    velocity: Vec3,
+    /// Seconds since spawning
+    age: f32,
+}
+
+/// A particle ready to fill: screen center, half size in pixels, depth and look
+#[derive(Debug, Clone, Copy)]
+struct ScreenQuad {
+    x: f32,
+    y: f32,
+    half: f32,
+    depth: f32,
+    color: RasterColor,
+    alpha: u8,
+    blend: ParticleBlend,
+}
+
+/// One emitter and its live particles
+#[derive(Debug, Clone)]
+pub struct ParticleEmitter {
+    pub def: ParticleEmitterDef,
+    /// World-space spawn point
+    pub position: Vec3,
+    particles: Vec<Particle>,
+    /// Fraction of a particle carried over to the next update
+    pending: f32,
+    seed: u32,
+}
+
+impl ParticleEmitter {
+    pub fn new(def: ParticleEmitterDef, position: Vec3, seed: u32) -> Self {
+        Self { def, position, particles: Vec::new(), pending: 0.0, seed }
+    }
+
+    /// Live particle count
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Advance particles by `delta` seconds. With `spawning` off the live
+    /// particles play out but no new ones start.
+    pub fn update(&mut self, delta: f32, spawning: bool) {
+        let gravity = self.def.gravity;
+        for particle in &mut self.particles {
+            particle.age += delta;
+            particle.velocity.y -= gravity * delta;
+            particle.position = particle.position + particle.velocity * delta;
+        }
+        let lifetime = self.def.lifetime;
+        self.particles.retain(|p| p.age < lifetime);
+
+        if !spawning {
+            self.pending = 0.0;
+            return;
+        }
+        self.pending += self.def.rate.max(0.0) * delta;
+        let max_alive = self.def.max_alive();
+        while self.pending >= 1.0 {
+            self.pending -= 1.0;
+            if self.particles.len() < max_alive {
+                let velocity = self.launch_velocity();
+                self.particles.push(Particle { position: self.position, velocity, age: 0.0 });
+            }
+        }
+    }
+
+    /// Launch a full emitter's worth of particles at once (debris from a
+    /// broken prop). Update with spawning off to let them play out.
+    pub fn burst(&mut self) {
+        let count = self.def.max_alive().max(1).saturating_sub(self.particles.len());
+        for _ in 0..count {
+            let velocity = self.launch_velocity();
+            self.particles.push(Particle { position: self.position, velocity, age: 0.0 });
+        }
+    }
+
+    /// Next value in 0..1 from the emitter's own sequence
+    fn random(&mut self) -> f32 {
+        self.seed = self.seed.wrapping_mul(1103515245).wrapping_add(12345);
+        (self.seed >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Random direction inside the launch cone, at launch speed
+    fn launch_velocity(&mut self) -> Vec3 {
+        let [dx, dy, dz] = self.def.direction;
+        let axis = Vec3::new(dx, dy, dz).normalize();
+        let axis = if axis.len() > 0.0 { axis } else { Vec3::UP };
+        // Two axes perpendicular to the cone axis
+        let helper = if axis.y.abs() < 0.9 { Vec3::UP } else { Vec3::new(1.0, 0.0, 0.0) };
+        let side = axis.cross(helper).normalize();
+        let up = side.cross(axis);
+
+        // Uniform over the cone's cap of the unit sphere
+        let cos_spread = self.def.spread.clamp(0.0, 180.0).to_radians().cos();
+        let cos_theta = 1.0 - self.random() * (1.0 - cos_spread);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = self.random() * std::f32::consts::TAU;
+        let direction = axis * cos_theta + (side * phi.cos() + up * phi.sin()) * sin_theta;
+        direction * self.def.speed
+    }
+
+    /// Screen quads for the live particles
+    fn collect_quads(&self, camera: &Camera, width: usize, height: usize, out: &mut Vec<ScreenQuad>) {
+        let def = &self.def;
+        let lifetime = def.lifetime.max(f32::EPSILON);
+        for particle in &self.particles {
+            let cam = perspective_transform(
+                particle.position - camera.position,
+                camera.basis_x,
+                camera.basis_y,
+                camera.basis_z,
+            );
+            if cam.z < NEAR_CLIP {
+                continue;
+            }
+            let t = (particle.age / lifetime).clamp(0.0, 1.0);
+            let size = def.start_size + (def.end_size - def.start_size) * t;
+            let center = project(cam, width, height);
+            let edge = project(Vec3::new(cam.x + size * 0.5, cam.y, cam.z), width, height);
+
+            let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+            let [r0, g0, b0] = def.start_color;
+            let [r1, g1, b1] = def.end_color;
+            let (r, g, b) = (lerp(r0, r1), lerp(g0, g1), lerp(b0, b1));
+            let fade = 1.0 - t;
+            let (color, alpha) = match def.blend {
+                ParticleBlend::Alpha => (RasterColor::new(r, g, b), (def.opacity as f32 * fade) as u8),
+                // Additive particles fade by dimming what they add
+                ParticleBlend::Additive => {
+                    let scale = fade * def.opacity as f32 / 255.0;
+                    let dim = |c: u8| (c as f32 * scale) as u8;
+                    (RasterColor::new(dim(r), dim(g), dim(b)), 255)
+                }
+            };
+            out.push(ScreenQuad {
+                x: center.x,
+                y: center.y,
+                half: (edge.x - center.x).abs().max(0.5),
+                depth: cam.z,
+                color,
+                alpha,
+                blend: def.blend,
+            });
+        }
+    }
+}
+
+/// Draw emitters' particles into the framebuffer, after the scene so geometry
+/// occludes them
+pub fn draw_particles<'a>(fb: &mut Framebuffer, camera: &Camera, emitters: impl IntoIterator<Item = &'a ParticleEmitter>) {
+    let mut quads = Vec::new();
+    for emitter in emitters {
+        emitter.collect_quads(camera, fb.width, fb.height, &mut quads);
+    }
+    // Back to front
+    quads.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+    for quad in &quads {
+        fill_quad(fb, quad);
+    }
+}
+
+/// Fill a particle's square, skipping pixels where the scene is in front
+fn fill_quad(fb: &mut Framebuffer, quad: &ScreenQuad) {
+    if quad.blend == ParticleBlend::Alpha && quad.alpha == 0 {
+        return;
+    }
+    let x0 = ((quad.x - quad.half).round() as i32).max(0);
+    let y0 = ((quad.y - quad.half).round() as i32).max(0);
+    // At least one pixel, however far away
+    let x1 = ((quad.x + quad.half).round() as i32).max(x0 + 1).min(fb.width as i32);
+    let y1 = ((quad.y + quad.half).round() as i32).max(y0 + 1).min(fb.height as i32);
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let (x, y) = (x as usize, y as usize);
+            if quad.depth > fb.zbuffer[y * fb.width + x] {
+                continue;
+            }
+            match quad.blend {
+                ParticleBlend::Alpha => fb.set_pixel_alpha(x, y, quad.color, quad.alpha),
+                ParticleBlend::Additive => fb.set_pixel_blended(x, y, quad.color, BlendMode::Add),
+            }
+        }
+    }
+}
+
+/// Emitters of the level's asset instances for one play session
+#[derive(Debug, Clone, Default)]
+pub struct ParticleSystem {
+    emitters: Vec<(ObjectRef, ParticleEmitter)>,
+    /// One-shot bursts, dropped once their particles die
+    bursts: Vec<ParticleEmitter>,
+}
+
+impl ParticleSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Collect the Particle components of enabled instances (with overrides applied)
+    pub fn start(level: &Level, asset_library: &AssetLibrary) -> Self {
+        let mut emitters = Vec::new();
+        for (room_index, room) in level.rooms.iter().enumerate() {
+            for (object_index, obj) in room.objects.iter().enumerate() {
+                if !obj.enabled {
+                    continue;
+                }
+                let Some(asset) = asset_library.get_by_id(obj.asset_id) else {
+                    continue;
+                };
+                for component in &asset.components {
+                    if let AssetComponent::Particle { offset, emitter, .. } = obj.overrides.apply(component) {
+                        let position = obj.world_position(room) + Vec3::new(offset[0], offset[1], offset[2]);
+                        // Distinct but repeatable sequences per emitter
+                        let seed = (room_index as u32).wrapping_mul(7919) ^ (object_index as u32).wrapping_mul(104729)
+                            ^ emitters.len() as u32;
+                        emitters.push(((room_index, object_index), ParticleEmitter::new(emitter, position, seed)));
+                    }
+                }
+            }
+        }
+        Self { emitters, bursts: Vec::new() }
+    }
+
+    /// Live particles across all emitters
+    pub fn particle_count(&self) -> usize {
+        self.emitters.iter().map(|(_, e)| e.len()).sum::<usize>() + self.bursts.iter().map(|e| e.len()).sum::<usize>()
+    }
+
+    /// Burst `def` once at `position` (debris from a broken prop)
+    pub fn burst(&mut self, def: ParticleEmitterDef, position: Vec3) {
+        let seed = position.x.to_bits() ^ position.z.to_bits().rotate_left(16);
+        let mut emitter = ParticleEmitter::new(def, position, seed);
+        emitter.burst();
+        self.bursts.push(emitter);
+    }
+
+    /// Advance every emitter. Hidden instances (collected pickups, disabled
+    /// by a script) stop spawning.
+    pub fn update(&mut self, delta: f32, hidden: &[ObjectRef]) {
+        for (object, emitter) in &mut self.emitters {
+            emitter.update(delta, !hidden.contains(object));
+        }
+        for burst in &mut self.bursts {
+            burst.update(delta, false);
+        }
+        self.bursts.retain(|b| !b.is_empty());
+    }
+
+    pub fn draw(&self, fb: &mut Framebuffer, camera: &Camera) {
+        draw_particles(fb, camera, self.emitters.iter().map(|(_, e)| e).chain(&self.bursts));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn straight_up() -> ParticleEmitterDef {
+        ParticleEmitterDef {
+            rate: 10.0,
+            lifetime: 1.0,
+            speed: 100.0,
+            direction: [0.0, 1.0, 0.0],
+            spread: 0.0,
+            gravity: 0.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_spawn_rate_lifetime_and_cap() {
+        let mut emitter = ParticleEmitter::new(straight_up(), Vec3::ZERO, 1);
+        emitter.update(0.25, true);
+        // 2.5 particles due: two now, the half carries over
+        assert_eq!(emitter.len(), 2);
+        emitter.update(0.25, true);
+        assert_eq!(emitter.len(), 5);
+
+        // Steady state never exceeds rate x lifetime
+        for _ in 0..40 {
+            emitter.update(0.1, true);
+            assert!(emitter.len() <= emitter.def.max_alive());
+        }
+
+        // Without spawning everything dies off within a lifetime
+        emitter.update(0.5, false);
+        emitter.update(0.6, false);
+        assert!(emitter.is_empty());
+    }
+
+    #[test]
+    fn test_velocity_cone_and_gravity() {
+        let mut emitter = ParticleEmitter::new(
+            ParticleEmitterDef { spread: 30.0, ..straight_up() },
+            Vec3::ZERO,
+            7,
+        );
+        let min_cos = 30.0f32.to_radians().cos() - 0.001;
+        for _ in 0..100 {
+            let v = emitter.launch_velocity();
+            assert!((v.len() - 100.0).abs() < 0.1);
+            assert!(v.y / v.len() >= min_cos);
+        }
+
+        // Gravity pulls a launched particle back down
+        let mut falling = ParticleEmitter::new(
+            ParticleEmitterDef { gravity: 1000.0, lifetime: 5.0, ..straight_up() },
+            Vec3::ZERO,
+            3,
+        );
+        falling.update(0.1, true);
+        for _ in 0..10 {
+            falling.update(0.1, false);
+        }
+        let p = falling.particles[0];
+        assert!(p.velocity.y < 0.0);
+    }
+
+    #[test]
+    fn test_burst_plays_out_once() {
+        let mut system = ParticleSystem::new();
+        system.burst(straight_up(), Vec3::new(512.0, 0.0, 512.0));
+        // A full lifetime's worth launches at once and nothing follows
+        assert_eq!(system.particle_count(), straight_up().max_alive());
+        system.update(0.5, &[]);
+        assert_eq!(system.particle_count(), straight_up().max_alive());
+        system.update(0.6, &[]);
+        assert_eq!(system.particle_count(), 0);
+        assert!(system.bursts.is_empty());
+    }
+
+    #[test]
+    fn test_quads_depth_tested_and_blended() {
+        let mut fb = Framebuffer::new(64, 64);
+        fb.clear(RasterColor::new(0, 0, 0));
+        let camera = Camera::new();
+        let mut emitter = ParticleEmitter::new(
+            ParticleEmitterDef {
+                start_size: 400.0,
+                end_size: 400.0,
+                start_color: [200, 100, 0],
+                end_color: [200, 100, 0],
+                opacity: 255,
+                blend: ParticleBlend::Additive,
+                ..straight_up()
+            },
+            Vec3::new(0.0, 0.0, 2000.0),
+            5,
+        );
+        emitter.update(0.1, true);
+        draw_particles(&mut fb, &camera, [&emitter]);
+        let center = (32 * 64 + 32) * 4;
+        assert!(fb.pixels[center] > 0);
+
+        // Geometry in front hides the particle
+        fb.clear(RasterColor::new(0, 0, 0));
+        fb.zbuffer.iter_mut().for_each(|z| *z = 10.0);
+        draw_particles(&mut fb, &camera, [&emitter]);
+        assert_eq!(fb.pixels[center], 0);
+    }
+}
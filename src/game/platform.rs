@@ -0,0 +1,131 @@
+//! Moving Platforms
+//!
+//! Platforms and elevators from the MovingPlatform asset component. Each tick
+//! `moving_platforms` advances them along their waypoints (stopping at every
+//! one, and waiting to be called if a trigger or lever works them), then
+//! carries the characters standing on their decks by the distance they moved.
+//! `character_controllers` then lands falling or stepping characters on the
+//! decks with `land_on_deck` and keeps them from walking through the sides.
+
+use crate::rasterizer::Vec3;
+use super::components::{CharacterController, MovingPlatform};
+use super::schedule::SystemContext;
+use super::transform::Transform;
+use super::world::World;
+
+/// How far feet can be from a deck's top and still ride it
+pub const RIDE_TOLERANCE: f32 = 32.0;
+
+/// Move platforms along their waypoints and carry their riders
+pub fn moving_platforms(ctx: &mut SystemContext<'_>) {
+    let delta = ctx.delta;
+    // Deck before the move, and how far it went
+    let mut carried: Vec<((Vec3, Vec3), Vec3)> = Vec::new();
+    for (_, (transform, platform)) in ctx.world.query::<(&mut Transform, &mut MovingPlatform)>() {
+        platform.advance(delta);
+        let Some((position, _)) = crate::world::point_along_path(&platform.points, platform.mode, platform.travelled) else {
+            continue;
+        };
+        let old = transform.position;
+        platform.moved = position - old;
+        transform.position = position;
+        if platform.moved.len() > 0.0 {
+            carried.push((platform.deck_at(old), platform.moved));
+        }
+    }
+    if carried.is_empty() {
+        return;
+    }
+    for (_, (transform, controller)) in ctx.world.query::<(&mut Transform, &CharacterController)>() {
+        if controller.climbing.is_some() || controller.swimming.is_some() || controller.vertical_velocity > 0.0 {
+            continue;
+        }
+        if let Some((_, moved)) = carried.iter().find(|(deck, _)| rides(*deck, transform.position)) {
+            transform.position = transform.position + *moved;
+        }
+    }
+}
+
+/// World-space deck boxes of every platform
+pub fn decks(world: &World) -> Vec<(Vec3, Vec3)> {
+    world.query_ref::<(&Transform, &MovingPlatform)>()
+        .map(|(_, (transform, platform))| platform.deck_at(transform.position))
+        .collect()
+}
+
+/// Whether feet at `feet` stand on `deck`
+pub fn rides(deck: (Vec3, Vec3), feet: Vec3) -> bool {
+    let (min, max) = deck;
+    feet.x >= min.x && feet.x <= max.x && feet.z >= min.z && feet.z <= max.z
+        && (feet.y - max.y).abs() <= RIDE_TOLERANCE
+}
+
+/// Height of the deck top that feet moving `from` → `to` land on, if any.
+/// Feet that were above a top, or at most `step_height` below it (stepping
+/// up), and end up at or below it within its XZ land on it.
+pub fn land_on_deck(from: Vec3, to: Vec3, step_height: f32, decks: &[(Vec3, Vec3)]) -> Option<f32> {
+    decks.iter()
+        .filter(|(min, max)| to.x >= min.x && to.x <= max.x && to.z >= min.z && to.z <= max.z)
+        .filter(|(_, max)| from.y >= max.y - step_height && to.y <= max.y)
+        .map(|(_, max)| max.y)
+        .reduce(f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{PathMode, SECTOR_SIZE};
+
+    fn elevator(on_call: bool, wait: f32) -> MovingPlatform {
+        let points = vec![Vec3::ZERO, Vec3::new(0.0, SECTOR_SIZE, 0.0)];
+        let deck = (Vec3::new(-512.0, -64.0, -512.0), Vec3::new(512.0, 0.0, 512.0));
+        MovingPlatform::new(points, PathMode::PingPong, SECTOR_SIZE, wait, deck, on_call)
+    }
+
+    #[test]
+    fn test_platform_waits_at_each_stop() {
+        let mut platform = elevator(false, 1.0);
+        platform.advance(0.5);
+        assert_eq!(platform.travelled, SECTOR_SIZE * 0.5);
+        // Arriving at the top stops there instead of overshooting
+        platform.advance(0.75);
+        assert_eq!(platform.travelled, SECTOR_SIZE);
+        assert_eq!(platform.waiting, 1.0);
+        platform.advance(0.5);
+        assert_eq!(platform.travelled, SECTOR_SIZE);
+        platform.advance(0.5);
+        platform.advance(0.25);
+        assert_eq!(platform.travelled, SECTOR_SIZE * 1.25);
+        // The end of the trip is the bottom again
+        platform.advance(1.0);
+        assert_eq!(platform.travelled, 0.0);
+    }
+
+    #[test]
+    fn test_platform_on_call_waits_to_be_called() {
+        let mut platform = elevator(true, 0.0);
+        platform.advance(1.0);
+        assert_eq!(platform.travelled, 0.0);
+        platform.called = true;
+        platform.advance(0.5);
+        platform.advance(0.75);
+        assert_eq!(platform.travelled, SECTOR_SIZE);
+        assert!(!platform.called);
+        platform.advance(1.0);
+        assert_eq!(platform.travelled, SECTOR_SIZE);
+    }
+
+    #[test]
+    fn test_land_on_deck() {
+        let decks = [(Vec3::new(0.0, 0.0, 0.0), Vec3::new(1024.0, 256.0, 1024.0))];
+        // Falling onto the top
+        assert_eq!(land_on_deck(Vec3::new(512.0, 300.0, 512.0), Vec3::new(512.0, 200.0, 512.0), 128.0, &decks), Some(256.0));
+        // Stepping up from just below the top
+        assert_eq!(land_on_deck(Vec3::new(-10.0, 200.0, 512.0), Vec3::new(10.0, 200.0, 512.0), 128.0, &decks), Some(256.0));
+        // Too far below to step up, or beside the deck
+        assert_eq!(land_on_deck(Vec3::new(-10.0, 0.0, 512.0), Vec3::new(10.0, 0.0, 512.0), 128.0, &decks), None);
+        assert_eq!(land_on_deck(Vec3::new(2000.0, 300.0, 512.0), Vec3::new(2000.0, 200.0, 512.0), 128.0, &decks), None);
+        assert!(rides(decks[0], Vec3::new(100.0, 260.0, 100.0)));
+        assert!(!rides(decks[0], Vec3::new(100.0, 400.0, 100.0)));
+    }
+}
@@ -0,0 +1,244 @@
+//! Component Queries
+//!
+//! Typed iteration over the entities that have a set of components, so
+//! systems don't hand-roll index loops over several storages:
+//!
+//! ```ignore
+//! for (entity, (transform, health)) in world.query::<(&Transform, &mut Health)>() {
+//!     if transform.position.y < KILL_PLANE {
+//!         health.current = 0;
+//!     }
+//! }
+//! ```
+//!
+//! A query is `&T`, `&mut T`, `Option<&T>`, `Option<&mut T>`, or a tuple of
+//! up to four of those. It visits every live entity that has all required
+//! (non-`Option`) components, in index order, and yields the full `Entity`
+//! (with its generation) alongside the components.
+//!
+//! `World::query` takes the world mutably; `World::query_ref` runs read-only
+//! queries on a shared world. Asking for the same component twice where one
+//! of them is `&mut` panics, like a second `RefCell::borrow_mut`.
+
+use std::any::TypeId;
+use std::marker::PhantomData;
+
+use super::component::Component;
+use super::entity::{Entity, EntityAllocator};
+use super::world::World;
+
+/// Something that can be fetched per entity: a component reference or a tuple of them
+pub trait QueryData {
+    /// What one matching entity yields
+    type Item<'w>;
+    /// Raw storage pointers, resolved once per query
+    type State: Copy;
+
+    /// Resolve the storages this query reads.
+    ///
+    /// # Safety
+    /// `world` must point to a live `World`; mutable parts must only be used
+    /// when the caller holds it uniquely.
+    unsafe fn state(world: *mut World) -> Self::State;
+
+    /// The components of the entity in slot `index`, None if one is missing.
+    ///
+    /// # Safety
+    /// `state` must come from a world borrowed for `'w`, and each index must
+    /// be fetched at most once while earlier items are alive.
+    unsafe fn fetch<'w>(state: Self::State, index: u32) -> Option<Self::Item<'w>>;
+
+    /// Append the (component, mutable) pairs this query touches
+    fn access(out: &mut Vec<(TypeId, bool)>);
+}
+
+/// Queries that only read, usable through `World::query_ref`.
+///
+/// # Safety
+/// Implementors must never write through their state.
+pub unsafe trait ReadOnlyQuery: QueryData {}
+
+impl<T: Component> QueryData for &T {
+    type Item<'w> = &'w T;
+    type State = (*const Option<T>, usize);
+
+    unsafe fn state(world: *mut World) -> Self::State {
+        (*T::storage_ptr(world)).raw_slots()
+    }
+
+    unsafe fn fetch<'w>((slots, len): Self::State, index: u32) -> Option<&'w T> {
+        let index = index as usize;
+        if index < len { (*slots.add(index)).as_ref() } else { None }
+    }
+
+    fn access(out: &mut Vec<(TypeId, bool)>) {
+        out.push((TypeId::of::<T>(), false));
+    }
+}
+
+impl<T: Component> QueryData for &mut T {
+    type Item<'w> = &'w mut T;
+    type State = (*mut Option<T>, usize);
+
+    unsafe fn state(world: *mut World) -> Self::State {
+        (*T::storage_ptr(world)).raw_slots_mut()
+    }
+
+    unsafe fn fetch<'w>((slots, len): Self::State, index: u32) -> Option<&'w mut T> {
+        let index = index as usize;
+        if index < len { (*slots.add(index)).as_mut() } else { None }
+    }
+
+    fn access(out: &mut Vec<(TypeId, bool)>) {
+        out.push((TypeId::of::<T>(), true));
+    }
+}
+
+impl<Q: QueryData> QueryData for Option<Q> {
+    type Item<'w> = Option<Q::Item<'w>>;
+    type State = Q::State;
+
+    unsafe fn state(world: *mut World) -> Self::State {
+        Q::state(world)
+    }
+
+    unsafe fn fetch<'w>(state: Self::State, index: u32) -> Option<Self::Item<'w>> {
+        Some(Q::fetch(state, index))
+    }
+
+    fn access(out: &mut Vec<(TypeId, bool)>) {
+        Q::access(out);
+    }
+}
+
+unsafe impl<T: Component> ReadOnlyQuery for &T {}
+unsafe impl<Q: ReadOnlyQuery> ReadOnlyQuery for Option<Q> {}
+
+macro_rules! tuple_query {
+    ($($name:ident),+) => {
+        impl<$($name: QueryData),+> QueryData for ($($name,)+) {
+            type Item<'w> = ($($name::Item<'w>,)+);
+            type State = ($($name::State,)+);
+
+            unsafe fn state(world: *mut World) -> Self::State {
+                ($($name::state(world),)+)
+            }
+
+            #[allow(non_snake_case)]
+            unsafe fn fetch<'w>(state: Self::State, index: u32) -> Option<Self::Item<'w>> {
+                let ($($name,)+) = state;
+                Some(($($name::fetch($name, index)?,)+))
+            }
+
+            fn access(out: &mut Vec<(TypeId, bool)>) {
+                $($name::access(out);)+
+            }
+        }
+
+        unsafe impl<$($name: ReadOnlyQuery),+> ReadOnlyQuery for ($($name,)+) {}
+    };
+}
+
+tuple_query!(A);
+tuple_query!(A, B);
+tuple_query!(A, B, C);
+tuple_query!(A, B, C, D);
+
+/// Panic if `Q` asks for a component mutably and also any other way
+pub(crate) fn check_access<Q: QueryData>() {
+    let mut access = Vec::new();
+    Q::access(&mut access);
+    for (i, (ty, mutable)) in access.iter().enumerate() {
+        if access[..i].iter().any(|(other, other_mut)| other == ty && (*mutable || *other_mut)) {
+            panic!("query {} borrows a component mutably more than once", std::any::type_name::<Q>());
+        }
+    }
+}
+
+/// Iterator over (entity, components) for a query
+pub struct QueryIter<'w, Q: QueryData> {
+    state: Q::State,
+    entities: &'w EntityAllocator,
+    next: u32,
+    end: u32,
+    _world: PhantomData<&'w mut World>,
+}
+
+impl<'w, Q: QueryData> QueryIter<'w, Q> {
+    /// # Safety
+    /// `state` must come from a world borrowed for `'w` (uniquely if `Q` has
+    /// mutable parts) and `check_access::<Q>()` must have passed.
+    pub(crate) unsafe fn new(state: Q::State, entities: &'w EntityAllocator) -> Self {
+        Self { state, entities, next: 0, end: entities.capacity(), _world: PhantomData }
+    }
+}
+
+impl<'w, Q: QueryData> Iterator for QueryIter<'w, Q> {
+    type Item = (Entity, Q::Item<'w>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next < self.end {
+            let index = self.next;
+            self.next += 1;
+            let Some(entity) = self.entities.entity_at(index) else {
+                continue;
+            };
+            // SAFETY: each index is visited once, and `new`'s contract covers the borrow
+            if let Some(item) = unsafe { Q::fetch(self.state, index) } {
+                return Some((entity, item));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::components::{Health, Velocity};
+    use super::super::transform::Transform;
+    use super::*;
+    use crate::rasterizer::Vec3;
+
+    #[test]
+    fn test_query_matches_entities_with_all_components() {
+        let mut world = World::new();
+        let moving = world.spawn_at(Vec3::new(1.0, 0.0, 0.0));
+        world.velocities.insert(moving, Velocity(Vec3::new(0.0, 0.0, 2.0)));
+        let still = world.spawn();
+        let hurt = world.spawn();
+        world.health.insert(hurt, Health::new(10));
+        world.velocities.insert(hurt, Velocity(Vec3::new(3.0, 0.0, 0.0)));
+
+        for (_, (transform, velocity)) in world.query::<(&mut Transform, &Velocity)>() {
+            transform.position = transform.position + velocity.0;
+        }
+        assert_eq!(world.transforms.get(moving).unwrap().position.z, 2.0);
+        assert_eq!(world.transforms.get(hurt).unwrap().position.x, 3.0);
+        assert_eq!(world.transforms.get(still).unwrap().position.x, 0.0);
+
+        let healthy: Vec<(Entity, bool)> = world.query_ref::<(&Velocity, Option<&Health>)>()
+            .map(|(entity, (_, health))| (entity, health.is_some()))
+            .collect();
+        assert_eq!(healthy, vec![(moving, false), (hurt, true)]);
+    }
+
+    #[test]
+    fn test_query_skips_despawned_and_yields_generations() {
+        let mut world = World::new();
+        let old = world.spawn();
+        world.despawn_immediate(old);
+        let reused = world.spawn();
+        assert_eq!(reused.index(), old.index());
+
+        let found: Vec<Entity> = world.query_ref::<&Transform>().map(|(e, _)| e).collect();
+        assert_eq!(found, vec![reused]);
+    }
+
+    #[test]
+    #[should_panic(expected = "mutably more than once")]
+    fn test_aliasing_query_panics() {
+        let mut world = World::new();
+        world.spawn();
+        let _ = world.query::<(&mut Transform, &Transform)>().count();
+    }
+}
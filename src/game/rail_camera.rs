@@ -0,0 +1,147 @@
+//! Rail Camera
+//!
+//! Hands the character camera to the level's camera volumes: while the player
+//! stands in one, the camera sits at its fixed position or slides along its
+//! rail, looking at the player. Entering, leaving or switching volumes blends
+//! from wherever the camera was to the new shot with a smoothstep, so the
+//! change reads as a camera move instead of a cut (a zero blend time cuts).
+
+use crate::rasterizer::Vec3;
+use crate::world::Level;
+
+/// Camera position and look-at target
+pub type CameraPose = (Vec3, Vec3);
+
+/// Which camera volume has the camera, and the blend into its shot
+#[derive(Debug, Clone, Default)]
+pub struct RailCamera {
+    /// (room, index) of the volume the player is in
+    pub active: Option<(usize, usize)>,
+    /// Pose the current blend started from
+    blend_from: Option<CameraPose>,
+    blend_elapsed: f32,
+    blend_time: f32,
+    /// Pose handed out last frame
+    last_pose: Option<CameraPose>,
+}
+
+/// Volume containing `point`; `current` wins where volumes overlap so the
+/// camera doesn't flicker between them
+pub fn volume_at(level: &Level, point: Vec3, current: Option<(usize, usize)>) -> Option<(usize, usize)> {
+    let inside = |(room_idx, idx): (usize, usize)| {
+        level.rooms.get(room_idx)
+            .and_then(|room| room.cameras.get(idx).map(|c| c.contains(room.position, point)))
+            .unwrap_or(false)
+    };
+    if current.is_some_and(inside) {
+        return current;
+    }
+    level.rooms.iter().enumerate().find_map(|(room_idx, room)| {
+        room.cameras.iter().position(|c| c.contains(room.position, point)).map(|idx| (room_idx, idx))
+    })
+}
+
+fn smoothstep(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+impl RailCamera {
+    /// Forget the active volume and any blend (play stopped or reset)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Camera pose for this frame. `player` is the player's feet, `look_at`
+    /// the point the follow camera aims at and `follow` the follow camera's
+    /// pose, used outside volumes and for shots that can't place a camera.
+    pub fn update(&mut self, level: &Level, player: Vec3, look_at: Vec3, follow: CameraPose, dt: f32) -> CameraPose {
+        let volume = volume_at(level, player, self.active);
+        if volume != self.active {
+            // Blend with the entered volume's time, or the left one's on exit
+            let timing = volume.or(self.active)
+                .and_then(|(room, idx)| level.rooms.get(room)?.cameras.get(idx))
+                .map_or(0.0, |c| c.blend_time);
+            self.blend_from = self.last_pose;
+            self.blend_elapsed = 0.0;
+            self.blend_time = timing.max(0.0);
+            self.active = volume;
+        }
+
+        let shot = self.active
+            .and_then(|(room_idx, idx)| {
+                let room = level.rooms.get(room_idx)?;
+                room.cameras.get(idx)?.camera_position(room.position, look_at)
+            })
+            .map(|position| (position, look_at));
+        let target = shot.unwrap_or(follow);
+
+        let pose = match self.blend_from {
+            Some(from) if self.blend_elapsed < self.blend_time => {
+                self.blend_elapsed += dt;
+                let t = smoothstep(self.blend_elapsed / self.blend_time);
+                (from.0 + (target.0 - from.0) * t, from.1 + (target.1 - from.1) * t)
+            }
+            _ => {
+                self.blend_from = None;
+                target
+            }
+        };
+        self.last_pose = Some(pose);
+        pose
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{CameraShot, CameraVolume, Room, SECTOR_SIZE};
+
+    fn level_with_volume(blend_time: f32) -> Level {
+        let mut level = Level::new();
+        let mut room = Room::new(0, Vec3::ZERO, 4, 4);
+        let mut volume = CameraVolume::from_corners("hall", (0.0, 0.0), (SECTOR_SIZE * 2.0, SECTOR_SIZE * 2.0));
+        volume.shot = CameraShot::Fixed { position: Vec3::new(0.0, 3000.0, 0.0) };
+        volume.blend_time = blend_time;
+        room.cameras.push(volume);
+        level.rooms.push(room);
+        level
+    }
+
+    #[test]
+    fn test_cuts_into_and_out_of_volume_without_blend() {
+        let level = level_with_volume(0.0);
+        let mut rail = RailCamera::default();
+        let follow = (Vec3::new(9000.0, 500.0, 9000.0), Vec3::ZERO);
+
+        let inside = Vec3::new(500.0, 0.0, 500.0);
+        let (pos, target) = rail.update(&level, inside, inside, follow, 0.016);
+        assert_eq!(rail.active, Some((0, 0)));
+        assert_eq!((pos.x, pos.y, pos.z), (0.0, 3000.0, 0.0));
+        assert_eq!((target.x, target.z), (500.0, 500.0));
+
+        let outside = Vec3::new(SECTOR_SIZE * 3.0, 0.0, 500.0);
+        let (pos, _) = rail.update(&level, outside, outside, follow, 0.016);
+        assert_eq!(rail.active, None);
+        assert_eq!(pos.x, 9000.0);
+    }
+
+    #[test]
+    fn test_blends_into_volume_shot() {
+        let level = level_with_volume(1.0);
+        let mut rail = RailCamera::default();
+        let follow = (Vec3::new(1000.0, 3000.0, 0.0), Vec3::ZERO);
+
+        // Outside first, so there's a pose to blend from
+        let outside = Vec3::new(SECTOR_SIZE * 3.0, 0.0, 500.0);
+        rail.update(&level, outside, outside, follow, 0.1);
+
+        let inside = Vec3::new(500.0, 0.0, 500.0);
+        let (pos, _) = rail.update(&level, inside, inside, follow, 0.5);
+        assert!((pos.x - 500.0).abs() < 1e-3, "halfway through the blend: {}", pos.x);
+        let (pos, _) = rail.update(&level, inside, inside, follow, 0.5);
+        assert!(pos.x.abs() < 1e-3);
+        let (pos, _) = rail.update(&level, inside, inside, follow, 0.5);
+        assert!(pos.x.abs() < 1e-3);
+    }
+}
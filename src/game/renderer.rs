@@ -14,6 +14,21 @@ use crate::ui::Rect;
 use crate::world::Level;
 use crate::input::{InputState, Action};
 use super::runtime::{GameToolState, CameraMode, FrameTimings};
+use super::lock_on;
+use super::climb::{self, ClimbAction};
+use super::swim;
+use super::combat;
+use super::status;
+use super::event::ClimbEvent;
+use super::components::{Patrol, Stamina};
+use super::transform::Transform;
+use super::hud::{self, HudFrame};
+use super::inspector::{self, ShapeKind};
+use super::replay::{InputFrame, ReplayMode};
+use super::equipment::GearKind;
+use crate::display::fit_scaled;
+use crate::locale::{self, t};
+use crate::tracker::MixGroup;
 
 /// Draw the test viewport (full area, no properties panel)
 /// Player settings are now edited in the World Editor properties panel when PlayerStart is selected.
@@ -35,8 +50,14 @@ pub fn draw_test_viewport(
         // Stretch mode: keep vertical resolution fixed, scale horizontal to match viewport aspect ratio
         // This maintains consistent pixel size while utilizing full screen width
         let base_h = if game.raster_settings.low_resolution { HEIGHT } else { HEIGHT_HI };
-        let viewport_aspect = rect.w / rect.h;
-        let scaled_w = (base_h as f32 * viewport_aspect) as usize;
+        let scaled_w = if game.display.integer_scaling {
+            // Whole-pixel scale from the height, width fills the viewport at that scale
+            let scale = (rect.h / base_h as f32).floor().max(1.0);
+            (rect.w / scale) as usize
+        } else {
+            let viewport_aspect = rect.w / rect.h;
+            (base_h as f32 * viewport_aspect) as usize
+        };
         (scaled_w.max(1), base_h)
     } else {
         // 4:3 mode: fixed PS1 resolution
@@ -56,6 +77,33 @@ pub fn draw_test_viewport(
         game.options_menu_open = !game.options_menu_open;
     }
 
+    // Entity inspector: F3 shows it; while shown F5 pauses/resumes and F6 steps a frame
+    if is_key_pressed(KeyCode::F3) {
+        game.inspector.open = !game.inspector.open;
+    }
+    if game.inspector.open {
+        if is_key_pressed(KeyCode::F5) {
+            game.inspector.toggle_pause();
+        }
+        if is_key_pressed(KeyCode::F6) {
+            game.inspector.step();
+        }
+    }
+
+    // Replays: F7 restarts and records (again to stop and save), F8 plays the last one back
+    if is_key_pressed(KeyCode::F7) {
+        if game.replay.is_recording() {
+            game.replay.stop();
+            save_replay(game);
+        } else {
+            game.start_recording();
+        }
+    }
+    if is_key_pressed(KeyCode::F8) && !game.replay.replay().frames.is_empty() {
+        let replay = game.replay.replay().clone();
+        game.start_replay(replay);
+    }
+
     // Auto-start playing when entering game tab
     if !game.playing {
         game.toggle_playing();
@@ -64,14 +112,88 @@ pub fn draw_test_viewport(
     // === INPUT PHASE ===
     let input_start = FrameTimings::start();
 
+    // Gameplay input: the devices (nothing while the menu is open), or the
+    // replay's recorded input while one plays back
+    let live = if game.options_menu_open {
+        InputFrame::idle(get_frame_time())
+    } else {
+        InputFrame::capture(input, mouse_look(game, &rect, ctx), get_frame_time())
+    };
+    let frame = game.replay.input(live);
+    let controls_blocked = game.options_menu_open && !game.replay.is_playing();
+
     // Handle input (camera, player movement) - blocked when debug menu is open
-    if !game.options_menu_open {
+    if game.logic.cutscene().is_some() {
+        // Cutscene: the player stands still and Interact skips; without camera
+        // keys the camera keeps following the player
+        if !controls_blocked && frame.action_pressed(Action::Interact) {
+            game.logic.request_skip();
+        }
+        game.hold_player();
+        match game.logic.cutscene().and_then(|c| c.camera()) {
+            Some((position, target)) => game.point_camera(position, target),
+            None => {
+                game.update_camera_follow_player(level);
+            }
+        }
+    } else if game.logic.dialogue().is_some() {
+        // Conversation: the player stands still, Interact advances it and the
+        // D-pad picks an answer
+        if !controls_blocked {
+            if frame.action_pressed(Action::Interact) {
+                game.logic.request_interact();
+            }
+            if frame.action_pressed(Action::SwitchSpell) {
+                game.logic.move_choice(-1);
+            }
+            if frame.action_pressed(Action::SwitchItem) {
+                game.logic.move_choice(1);
+            }
+        }
+        game.hold_player();
+        game.update_camera_follow_player(level);
+    } else if game.inventory_open {
+        // Inventory: the player stands still, the D-pad picks gear, Interact
+        // equips it (or takes it off) and the inventory button closes it
+        if !controls_blocked {
+            let count = game.equipment.items().len();
+            if frame.action_pressed(Action::OpenInventory) {
+                game.inventory_open = false;
+            } else if count > 0 {
+                if frame.action_pressed(Action::SwitchSpell) {
+                    game.inventory_selected = (game.inventory_selected + count - 1) % count;
+                }
+                if frame.action_pressed(Action::SwitchItem) {
+                    game.inventory_selected = (game.inventory_selected + 1) % count;
+                }
+                if frame.action_pressed(Action::Interact) {
+                    game.equipment.toggle(game.inventory_selected);
+                    game.apply_equipment();
+                }
+            }
+        }
+        game.hold_player();
+        game.update_camera_follow_player(level);
+    } else if !controls_blocked {
+        if frame.action_pressed(Action::OpenInventory) {
+            game.inventory_open = true;
+            game.inventory_selected = 0;
+        }
+        // Tutorial hints: Interact closes a popup, performing an action clears its prompt
+        if game.logic.tutorial().is_some() && frame.action_pressed(Action::Interact) {
+            game.logic.dismiss_tutorial();
+        } else if frame.action_pressed(Action::Interact) {
+            // Try the nearest door on the next tick
+            game.logic.request_interact();
+        }
+        game.logic.dismiss_prompts(|action| frame.action_pressed(action));
+
         match game.camera_mode {
             CameraMode::Character => {
                 // Third-person camera follows player
                 game.update_camera_follow_player(level);
                 // Handle Dark Souls style player input
-                handle_player_input(game, level, &rect, input, ctx);
+                handle_player_input(game, level, &frame, ctx);
             }
             CameraMode::FreeFly => {
                 // Free-fly noclip camera
@@ -133,7 +255,21 @@ pub fn draw_test_viewport(
     }
     let render_texconv_ms = FrameTimings::elapsed_ms(texconv_start);
 
+    // Collected pickups, script-disabled instances and broken props without a
+    // broken mesh aren't drawn; cutscenes move instances (ahead of everything
+    // else), doors slide or swing and path followers travel their paths
+    let hidden_objects = game.logic.hidden_objects();
+    let broken_objects = game.logic.broken_objects();
+    let dropped_assets = game.logic.dropped_assets();
+    let mut moved_objects = game.logic.cutscene_poses();
+    moved_objects.extend(game.logic.door_poses(&game.world));
+    moved_objects.extend(game.logic.path_poses(&game.world));
+
+    // The project's vertex snapping
+    game.raster_settings.vertex_snap = level.vertex_snap;
+
     // Render rooms + asset meshes
+    let water_time = macroquad::prelude::get_time() as f32;
     crate::scene::render_scene(
         fb,
         &level.rooms,
@@ -147,11 +283,68 @@ pub fn draw_test_viewport(
         &resolve_texture,
         &crate::scene::SceneRenderOptions {
             use_fog: true,
+            atmosphere: Some(&level.atmosphere),
             render_assets: true,
             skip_rooms: &[],
+            lod_scale: Some(game.lod_scale),
+            hidden_objects: &hidden_objects,
+            moved_objects: &moved_objects,
+            broken_objects: &broken_objects,
+            dropped_assets: &dropped_assets,
+            baked_lighting: true,
+            water_time: Some(water_time),
         },
     );
 
+    // Mirror: re-render the camera's room reflected about the nearest mirror
+    let camera_room = level.find_room_at(game.camera.position);
+    if let Some((room, mirror)) = camera_room
+        .and_then(|room| Some((room, level.active_mirror(room, game.camera.position)?)))
+    {
+        let reflected = mirror[0].reflect_camera(&game.camera);
+        let scratch = &mut game.mirror_fb;
+        scratch.resize(fb.width, fb.height);
+        if let Some(skybox) = &level.skybox {
+            scratch.clear(RasterColor::new(0, 0, 0));
+            scratch.render_skybox(skybox, &reflected, macroquad::prelude::get_time() as f32);
+        } else {
+            scratch.clear(RasterColor::new(20, 22, 28));
+        }
+        let other_rooms: Vec<usize> = (0..level.rooms.len()).filter(|&r| r != room).collect();
+        crate::scene::render_scene(
+            scratch,
+            &level.rooms,
+            asset_library,
+            user_textures,
+            &reflected,
+            &game.raster_settings,
+            &lights,
+            textures,
+            &game.textures_15_cache,
+            &resolve_texture,
+            &crate::scene::SceneRenderOptions {
+                use_fog: true,
+                atmosphere: Some(&level.atmosphere),
+                render_assets: true,
+                skip_rooms: &other_rooms,
+                lod_scale: Some(game.lod_scale),
+                hidden_objects: &hidden_objects,
+                moved_objects: &moved_objects,
+                broken_objects: &broken_objects,
+                dropped_assets: &dropped_assets,
+                baked_lighting: true,
+                water_time: Some(water_time),
+            },
+        );
+        crate::scene::composite_mirror(fb, &game.mirror_fb, &game.camera, &mirror);
+    }
+
+    // Weather particles (depth-tested against the scene)
+    game.weather.draw(fb, &game.camera, level);
+
+    // Emitter particles (depth-tested, alpha ones back to front)
+    game.particles.draw(fb, &game.camera);
+
     // Render player wireframe cylinder if playing
     if game.playing {
         if let Some(player_pos) = game.get_player_position() {
@@ -168,6 +361,22 @@ pub fn draw_test_viewport(
             );
             render_raster_ms += FrameTimings::elapsed_ms(raster_start);
         }
+
+        // Navgraph and the paths enemies are walking (debug menu)
+        if game.show_nav_graph {
+            draw_nav_overlay(fb, game);
+        }
+
+        // Inspector: outline the selected entity's collision shapes
+        if let Some(entity) = game.inspector.selected.filter(|_| game.inspector.open) {
+            draw_wireframe_lines(fb, &game.camera, &inspector::collision_lines(&game.world, entity));
+        }
+
+        // HUD goes in last so it sits on top of the scene at framebuffer resolution
+        // (hidden during cutscenes, conversations and the inventory)
+        if game.logic.cutscene().is_none() && game.logic.dialogue().is_none() && !game.inventory_open {
+            hud::draw(fb, &level.hud, &HudFrame::gather(game, level, input.prompt_label(Action::Interact)));
+        }
     }
 
     let render_ms = FrameTimings::elapsed_ms(render_start);
@@ -175,78 +384,95 @@ pub fn draw_test_viewport(
     // --- Sub-timing: Texture upload ---
     let upload_start = FrameTimings::start();
 
-    // Convert framebuffer to texture and draw to viewport
-    let texture = Texture2D::from_rgba8(fb.width as u16, fb.height as u16, &fb.pixels);
-    texture.set_filter(FilterMode::Nearest);
+    // Upload what changed in the framebuffer (the texture is kept between frames)
+    let texture = game.frame_texture.upload(fb);
 
     // Calculate draw area - framebuffer matches viewport in stretch mode, needs letterboxing in 4:3
-    let (draw_w, draw_h, draw_x, draw_y) = if game.raster_settings.stretch_to_fill {
+    // (integer scaling letterboxes both, so every framebuffer pixel covers whole screen pixels)
+    let (draw_w, draw_h, draw_x, draw_y) = if game.raster_settings.stretch_to_fill && !game.display.integer_scaling {
         // Framebuffer already sized to viewport aspect, draw at full size
         (rect.w, rect.h, rect.x, rect.y)
     } else {
         // Maintain aspect ratio (4:3 for PS1) with letterboxing
-        let fb_aspect = fb.width as f32 / fb.height as f32;
-        let rect_aspect = rect.w / rect.h;
-        if fb_aspect > rect_aspect {
-            let w = rect.w;
-            let h = rect.w / fb_aspect;
-            (w, h, rect.x, rect.y + (rect.h - h) * 0.5)
-        } else {
-            let h = rect.h;
-            let w = rect.h * fb_aspect;
-            (w, h, rect.x + (rect.w - w) * 0.5, rect.y)
-        }
+        let (w, h, x, y) = fit_scaled(rect.w, rect.h, fb.width, fb.height, game.display.integer_scaling);
+        (w, h, rect.x + x, rect.y + y)
     };
 
     // Draw letterbox bars (background for non-rendered area)
     draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(10, 10, 12, 255));
 
-    // Draw the rendered frame
-    draw_texture_ex(
-        &texture,
-        draw_x,
-        draw_y,
-        WHITE,
-        DrawTextureParams {
-            dest_size: Some(vec2(draw_w, draw_h)),
-            ..Default::default()
-        },
-    );
+    // Draw the rendered frame (through the CRT filter if it's on)
+    game.crt.draw(texture, draw_x, draw_y, draw_w, draw_h, &game.display);
 
     let render_upload_ms = FrameTimings::elapsed_ms(upload_start);
 
     // === UI PHASE ===
     let ui_start = FrameTimings::start();
 
+    // Cutscene letterbox bars, subtitle and skip hint over the frame
+    draw_cutscene_overlay(game, &Rect::new(draw_x, draw_y, draw_w, draw_h), input);
+    // Conversation box, typed-out line and choices
+    draw_dialogue_overlay(game, &Rect::new(draw_x, draw_y, draw_w, draw_h), input);
+    // Equipment list and the selected piece's stats
+    if game.inventory_open {
+        draw_inventory_menu(game, &Rect::new(draw_x, draw_y, draw_w, draw_h), input);
+    }
+
+    // Script messages (bottom-center) and script compile errors (top-center)
+    draw_script_messages(game, &rect);
+    // Tutorial prompts (bottom-left) and hint popup (center)
+    draw_tutorial_hints(game, &rect, input);
+
+    // Lock-on reticle on the target's chest
+    if let Some(target_pos) = game.lock_on_target_position() {
+        let chest = target_pos + Vec3::new(0.0, level.player_settings.height * 0.6, 0.0);
+        let cam = &game.camera;
+        if let Some((sx, sy)) = crate::rasterizer::world_to_screen(
+            chest, cam.position, cam.basis_x, cam.basis_y, cam.basis_z, fb.width, fb.height,
+        ) {
+            let x = draw_x + sx * draw_w / fb.width as f32;
+            let y = draw_y + sy * draw_h / fb.height as f32;
+            draw_circle(x, y, 4.0, Color::from_rgba(255, 255, 255, 220));
+            draw_circle_lines(x, y, 7.0, 1.5, Color::from_rgba(255, 200, 80, 220));
+        }
+    }
+
     // Draw debug overlay HUD if enabled (top-right, always visible during gameplay)
     if game.show_debug_overlay {
         draw_debug_overlay(game, &rect, input, level);
     }
 
+    // Entity inspector (right side, left of the debug overlay)
+    if game.inspector.open {
+        draw_inspector(game, &rect, ctx);
+    }
+
+    draw_replay_status(game, &rect);
+
     // Draw debug menu overlay if open (top-left, blocks gameplay for D-pad navigation)
     if game.options_menu_open {
         draw_debug_menu(game, &rect, input, level, asset_library);
     } else {
         // Show collapsed hint when menu is closed
-        let hint = "[ESC] Menu";
-        let hint_w = 70.0;
+        let hint = t("game.menu_hint");
+        let hint_w = locale::measure_text(&hint, 11.0) + 8.0;
         let hint_h = 16.0;
         let hint_x = rect.x + 4.0;
         let hint_y = rect.y + 4.0;
         draw_rectangle(hint_x, hint_y, hint_w, hint_h, Color::from_rgba(0, 0, 0, 120));
-        draw_text(hint, hint_x + 4.0, hint_y + 12.0, 11.0, Color::from_rgba(180, 180, 180, 200));
+        locale::draw_text(&hint, hint_x + 4.0, hint_y + 12.0, 11.0, Color::from_rgba(180, 180, 180, 200));
     }
 
     // Show warning if no player start exists in level
     if level.get_player_start(asset_library).is_none() {
-        let msg = "No Player Start in level";
-        let hint = "Add a PlayerStart spawn point in World Editor";
+        let msg = t("game.no_player_start");
+        let hint = t("game.no_player_start_hint");
         let font_size = 16.0;
         let hint_size = 12.0;
 
         // Center the message
-        let msg_width = msg.len() as f32 * font_size * 0.5;
-        let hint_width = hint.len() as f32 * hint_size * 0.5;
+        let msg_width = locale::measure_text(&msg, font_size);
+        let hint_width = locale::measure_text(&hint, hint_size);
         let center_x = rect.x + rect.w / 2.0;
         let center_y = rect.y + rect.h / 2.0;
 
@@ -262,15 +488,15 @@ pub fn draw_test_viewport(
         );
 
         // Draw warning text
-        draw_text(
-            msg,
+        locale::draw_text(
+            &msg,
             center_x - msg_width / 2.0,
             center_y - 5.0,
             font_size,
             Color::from_rgba(255, 200, 50, 255),
         );
-        draw_text(
-            hint,
+        locale::draw_text(
+            &hint,
             center_x - hint_width / 2.0,
             center_y + 18.0,
             hint_size,
@@ -305,25 +531,39 @@ pub fn draw_test_viewport(
     };
 }
 
+/// Mouse-look drag since last frame, while the right button drags in the viewport
+fn mouse_look(game: &GameToolState, rect: &Rect, ctx: &crate::ui::UiContext) -> Option<(f32, f32)> {
+    let (x, y) = (ctx.mouse.x, ctx.mouse.y);
+    let inside = x >= rect.x && x < rect.x + rect.w && y >= rect.y && y < rect.y + rect.h;
+    if inside && ctx.mouse.right_down {
+        Some((x - game.viewport_last_mouse.0, y - game.viewport_last_mouse.1))
+    } else {
+        None
+    }
+}
+
 /// Handle player input during gameplay (Dark Souls style character controls)
 /// Camera orbits around player with right stick, movement is relative to camera direction.
-fn handle_player_input(game: &mut GameToolState, level: &Level, rect: &Rect, input: &InputState, ctx: &crate::ui::UiContext) {
+/// Reads a frame of input (live or from a replay) so replays drive it exactly.
+fn handle_player_input(game: &mut GameToolState, level: &Level, input: &InputFrame, ctx: &crate::ui::UiContext) {
     let mouse_pos = (ctx.mouse.x, ctx.mouse.y);
-    let inside = mouse_pos.0 >= rect.x
-        && mouse_pos.0 < rect.x + rect.w
-        && mouse_pos.1 >= rect.y
-        && mouse_pos.1 < rect.y + rect.h;
-
-    let delta = get_frame_time();
+    let delta = input.delta;
     let settings = &level.player_settings;
     let look_sensitivity = 2.5;
 
-    // Mouse look to rotate camera around player (RMB drag)
-    if inside && ctx.mouse.right_down {
-        let dx = mouse_pos.0 - game.viewport_last_mouse.0;
-        let dy = mouse_pos.1 - game.viewport_last_mouse.1;
+    // Lock-on (R3 / Tab): lock the nearest target in view, press again to release
+    if input.action_pressed(Action::LockOn) {
+        game.toggle_lock_on(level);
+    }
+    let lock_target = game.lock_on_target_position();
+    let player_pos = game.get_player_position();
 
-        game.char_cam_yaw -= dx * 0.005;
+    // Mouse look to rotate camera around player (RMB drag)
+    if let Some((dx, dy)) = input.look {
+        // Yaw is driven by the target while locked on
+        if lock_target.is_none() {
+            game.char_cam_yaw -= dx * 0.005;
+        }
         game.char_cam_pitch = (game.char_cam_pitch + dy * 0.005)
             .clamp(settings.camera_pitch_min, settings.camera_pitch_max);
 
@@ -332,21 +572,55 @@ fn handle_player_input(game: &mut GameToolState, level: &Level, rect: &Rect, inp
         game.viewport_mouse_captured = false;
     }
 
-    // Gamepad right stick: orbit camera around player (Y inverted for natural feel)
     let right_stick = input.right_stick();
-    if right_stick.length() > 0.0 {
+    if let (Some(target_pos), Some(player_pos)) = (lock_target, player_pos) {
+        // Locked on: right-stick flicks cycle targets, camera swings behind the player
+        if let Some(direction) = game.lock_on.flick(right_stick.x) {
+            // Screen right is decreasing yaw (same inversion as the orbit controls)
+            game.cycle_lock_on(level, -direction);
+        }
+        let target_yaw = lock_on::yaw_towards(player_pos, target_pos);
+        let yaw_diff = lock_on::wrap_angle(target_yaw - game.char_cam_yaw);
+        game.char_cam_yaw += yaw_diff * (lock_on::YAW_FOLLOW_RATE * delta).min(1.0);
+    } else if right_stick.length() > 0.0 {
+        // Gamepad right stick: orbit camera around player (Y inverted for natural feel)
         game.char_cam_yaw -= right_stick.x * look_sensitivity * delta;
         game.char_cam_pitch = (game.char_cam_pitch - right_stick.y * look_sensitivity * delta)
             .clamp(settings.camera_pitch_min, settings.camera_pitch_max);
     }
 
-    // Get camera-relative directions for movement
-    let cam_forward = game.get_camera_forward_xz();
-    let cam_right = game.get_camera_right_xz();
+    // Get camera-relative directions for movement (strafe around the target when locked on)
+    let (cam_forward, cam_right) = match (lock_target, player_pos) {
+        (Some(target_pos), Some(player_pos)) => {
+            let yaw = lock_on::yaw_towards(player_pos, target_pos);
+            (Vec3::new(yaw.sin(), 0.0, yaw.cos()), Vec3::new(yaw.cos(), 0.0, -yaw.sin()))
+        }
+        _ => (game.get_camera_forward_xz(), game.get_camera_right_xz()),
+    };
 
     // Movement input: combine keyboard WASD with gamepad left stick
     let left_stick = input.left_stick();
     if let Some(player) = game.player_entity {
+        // On a ladder: up/down climbs, jump lets go, the camera stays behind the climber
+        if let Some(grip) = game.world.controllers.get(player).and_then(|c| c.climbing) {
+            game.char_cam_yaw = climb::constrain_camera_yaw(game.char_cam_yaw, &grip);
+            let climb_speed = if left_stick.y.abs() > 0.1 { left_stick.y * climb::CLIMB_SPEED } else { 0.0 };
+            let mut climb_velocity = Vec3::new(0.0, climb_speed, 0.0);
+            if input.action_pressed(Action::Jump) {
+                if let Some(controller) = game.world.controllers.get_mut(player) {
+                    climb_velocity = climb::let_go(controller, settings.jump_velocity * 0.5);
+                }
+                if let Some(position) = player_pos {
+                    game.events.climb.send(ClimbEvent { entity: player, action: ClimbAction::LetGo, position });
+                }
+            }
+            if let Some(velocity) = game.world.velocities.get_mut(player) {
+                velocity.0 = climb_velocity;
+            }
+            game.viewport_last_mouse = mouse_pos;
+            return;
+        }
+
         let mut move_dir = Vec3::ZERO;
 
         // Movement relative to camera direction (Dark Souls style)
@@ -357,17 +631,48 @@ fn handle_player_input(game: &mut GameToolState, level: &Level, rect: &Rect, inp
             move_dir = move_dir + cam_right * -left_stick.x;
         }
 
-        // Check sprint state (Elden Ring: hold B to run)
+        // Check sprint state (Elden Ring: hold B to run, while stamina lasts; not while swimming)
         let move_len = move_dir.len();
-        let sprinting = input.action_down(Action::Dodge) && move_len > 0.1;
+        let swimming = game.world.controllers.get(player).is_some_and(|c| c.swimming.is_some());
+        let sprinting = !swimming && input.action_down(Action::Dodge) && move_len > 0.1
+            && game.world.stamina.get_mut(player).is_some_and(|s| s.drain(Stamina::SPRINT_COST * delta));
+
+        // Combat (not while swimming): Attack/Strong Attack swing (at the lock-on
+        // target if any), Guard is held up, a tap of Dodge rolls
+        if !swimming {
+            let aim = match (lock_target, player_pos) {
+                (Some(target_pos), Some(player_pos)) => target_pos - player_pos,
+                _ => Vec3::ZERO,
+            };
+            if input.action_pressed(Action::Attack) && combat::start_attack(&mut game.world, player, false, aim) {
+                // The swing also hits breakable props in front of the player on the next tick
+                game.logic.request_attack();
+            }
+            if input.action_pressed(Action::StrongAttack) {
+                combat::start_attack(&mut game.world, player, true, aim);
+            }
+            combat::set_guard(&mut game.world, player, input.action_down(Action::Guard));
+            if input.action_down(Action::Dodge) {
+                game.dodge_held += delta;
+            } else {
+                if game.dodge_held > 0.0 && game.dodge_held <= combat::DODGE_TAP_TIME {
+                    combat::start_dodge(&mut game.world, player, move_dir);
+                }
+                game.dodge_held = 0.0;
+            }
+        }
 
         // Apply movement to velocity
         if move_len > 0.1 {
             move_dir = move_dir.normalize();
 
-            // Update player facing to match movement direction (Dark Souls: character turns to face movement)
+            // Update player facing to match movement direction (Dark Souls: character turns to face movement).
+            // Locked on, the character keeps facing the target unless sprinting.
             if let Some(controller) = game.world.controllers.get_mut(player) {
-                let target_facing = move_dir.x.atan2(move_dir.z);
+                let target_facing = match (lock_target, player_pos) {
+                    (Some(target_pos), Some(player_pos)) if !sprinting => lock_on::yaw_towards(player_pos, target_pos),
+                    _ => move_dir.x.atan2(move_dir.z),
+                };
                 // Smooth rotation toward movement direction
                 let facing_diff = (target_facing - controller.facing).rem_euclid(std::f32::consts::TAU);
                 let facing_diff = if facing_diff > std::f32::consts::PI {
@@ -378,11 +683,15 @@ fn handle_player_input(game: &mut GameToolState, level: &Level, rect: &Rect, inp
                 controller.facing += facing_diff * 10.0 * delta; // Smooth turn speed
             }
 
-            let speed = if sprinting {
+            let speed = if swimming {
+                swim::SWIM_SPEED
+            } else if sprinting {
                 settings.run_speed
             } else {
                 settings.walk_speed
             };
+            // Slows and haste from status effects
+            let speed = speed * status::speed_multiplier(&game.world, player);
 
             if let Some(velocity) = game.world.velocities.get_mut(player) {
                 velocity.0.x = move_dir.x * speed;
@@ -396,6 +705,52 @@ fn handle_player_input(game: &mut GameToolState, level: &Level, rect: &Rect, inp
             }
         }
 
+        // Rolling carries the player; swinging and staggering root them
+        if let Some(held) = combat::movement_override(&game.world, player) {
+            if let Some(velocity) = game.world.velocities.get_mut(player) {
+                velocity.0.x = held.x;
+                velocity.0.z = held.z;
+            }
+        }
+
+        // Swimming: jump rises, crouch dives (the controller floats back up on its own)
+        if swimming {
+            let rise = if input.action_down(Action::Jump) {
+                swim::SWIM_VERTICAL_SPEED
+            } else if input.action_down(Action::Crouch) {
+                -swim::SWIM_VERTICAL_SPEED
+            } else {
+                0.0
+            };
+            if let Some(velocity) = game.world.velocities.get_mut(player) {
+                velocity.0.y = rise;
+            }
+            game.viewport_last_mouse = mouse_pos;
+            return;
+        }
+
+        // Grab a ladder: walk into it from below, or crouch on the ledge at its top
+        let from_top = input.action_pressed(Action::Crouch);
+        if let (Some(position), true) = (player_pos, move_len > 0.1 || from_top) {
+            if let Some(controller) = game.world.controllers.get_mut(player) {
+                let grip = climb::find_ladder(level, position, controller.radius, move_dir, from_top)
+                    .filter(|_| controller.grounded || !from_top);
+                if let Some(grip) = grip {
+                    let hold = climb::grab(controller, grip, position, from_top);
+                    if let Some(transform) = game.world.transforms.get_mut(player) {
+                        transform.position = hold;
+                    }
+                    if let Some(velocity) = game.world.velocities.get_mut(player) {
+                        velocity.0 = Vec3::ZERO;
+                    }
+                    let action = if from_top { ClimbAction::GrabFromTop } else { ClimbAction::Grab };
+                    game.events.climb.send(ClimbEvent { entity: player, action, position: hold });
+                    game.viewport_last_mouse = mouse_pos;
+                    return;
+                }
+            }
+        }
+
         // Jump (Elden Ring: A button / Space key)
         // Can only jump when grounded
         if input.action_pressed(Action::Jump) {
@@ -497,21 +852,35 @@ fn draw_debug_menu(game: &mut GameToolState, rect: &Rect, input: &InputState, le
     let menu_w = 180.0;
     let row_height = 20.0;
 
-    // Menu items: Camera, Overlay, PS1 features, Reset
+    // Menu items (string table keys): Camera, Overlay, Inspector, Nav Graph, PS1 features, Language, Reset
     let items = [
-        "Camera",        // 0
-        "Overlay",       // 1
-        "---",           // 2 - Separator
-        "Affine UV",     // 3 - PS1 texture warping
-        "Fixed-Point",   // 4 - PS1 fixed-point math (jitter)
-        "Low Res",       // 5 - 320x240
-        "4:3 Aspect",    // 6 - 4:3 aspect ratio (vs stretch to fill)
-        "RGB555",        // 7 - PS1 15-bit color
-        "Dithering",     // 8 - PS1 dithering
-        "Shading",       // 9 - None/Flat/Gouraud
-        "FPS",           // 10 - 30/60/Unlocked
-        "---",           // 11 - Separator
-        "Reset",         // 12
+        "menu.camera",      // 0
+        "menu.overlay",     // 1
+        "menu.inspector",   // 2 - Entity inspector (F3)
+        "menu.nav_graph",   // 3 - Enemy navgraph and paths
+        "---",              // 4 - Separator
+        "menu.affine_uv",   // 5 - PS1 texture warping
+        "menu.fixed_point", // 6 - PS1 fixed-point math (jitter)
+        "menu.resolution",  // 7 - 320x240 / 640x480
+        "menu.aspect",      // 8 - 4:3 aspect ratio (vs stretch to fill)
+        "menu.rgb555",      // 9 - PS1 15-bit color
+        "menu.dithering",   // 10 - PS1 dithering
+        "menu.shading",     // 11 - None/Flat/Gouraud
+        "menu.fps",         // 12 - 24/30/60/Unlocked
+        "menu.lod_dist",    // 13 - LOD switch distance multiplier
+        "menu.window",      // 14 - Windowed/Maximized/Borderless
+        "menu.int_scale",   // 15 - Integer scaling of the game view
+        "menu.crt_filter",  // 16 - GPU scanlines/curvature/chroma bleed
+        "menu.crt_look",    // 17 - Sharp/CRT/Composite
+        "menu.sharpness",   // 18 - CRT filter pixel edges
+        "menu.language",    // 19 - String table used for player-facing text
+        "---",              // 20 - Separator
+        "menu.music",       // 21 - Mix group volumes (override the level's defaults)
+        "menu.sfx",         // 22
+        "menu.ambient",     // 23
+        "menu.dialogue",    // 24
+        "---",              // 25 - Separator
+        "menu.reset",       // 26
     ];
     let menu_h = 20.0 + items.len() as f32 * row_height + 14.0;
     let selected = game.debug_menu_selection;
@@ -567,7 +936,7 @@ fn draw_debug_menu(game: &mut GameToolState, rect: &Rect, input: &InputState, le
             draw_text(">", menu_x + 4.0, y, 12.0, Color::from_rgba(100, 180, 255, 255));
         }
 
-        draw_text(item, menu_x + 16.0, y, 12.0, label_color);
+        locale::draw_text(&t(item), menu_x + 16.0, y, 12.0, label_color);
 
         // Item-specific value/action
         match i {
@@ -601,28 +970,43 @@ fn draw_debug_menu(game: &mut GameToolState, rect: &Rect, input: &InputState, le
                     game.show_debug_overlay = !game.show_debug_overlay;
                 }
             }
+            2 => {
+                // Entity inspector toggle
+                draw_toggle(menu_x, y, game.inspector.open);
+                if is_selected && toggle_pressed(input) {
+                    game.inspector.open = !game.inspector.open;
+                }
+            }
             3 => {
+                // Navgraph and enemy path overlay
+                draw_toggle(menu_x, y, game.show_nav_graph);
+                if is_selected && toggle_pressed(input) {
+                    game.show_nav_graph = !game.show_nav_graph;
+                }
+            }
+            5 => {
                 // Affine textures (PS1 UV warping)
                 draw_toggle(menu_x, y, game.raster_settings.affine_textures);
                 if is_selected && toggle_pressed(input) {
                     game.raster_settings.affine_textures = !game.raster_settings.affine_textures;
                 }
             }
-            4 => {
+            6 => {
                 // Fixed-point math (PS1 jitter)
                 draw_toggle(menu_x, y, game.raster_settings.use_fixed_point);
                 if is_selected && toggle_pressed(input) {
                     game.raster_settings.use_fixed_point = !game.raster_settings.use_fixed_point;
                 }
             }
-            5 => {
-                // Low resolution (320x240)
-                draw_toggle(menu_x, y, game.raster_settings.low_resolution);
+            7 => {
+                // Internal resolution: PS1 native 320x240 or 640x480
+                let resolution = if game.raster_settings.low_resolution { "320x240" } else { "640x480" };
+                draw_text(resolution, menu_x + 100.0, y, 12.0, Color::from_rgba(100, 180, 255, 255));
                 if is_selected && toggle_pressed(input) {
                     game.raster_settings.low_resolution = !game.raster_settings.low_resolution;
                 }
             }
-            6 => {
+            8 => {
                 // 4:3 aspect ratio (vs stretch to fill)
                 // Note: toggle shows ON when NOT stretching (i.e., maintaining 4:3)
                 draw_toggle(menu_x, y, !game.raster_settings.stretch_to_fill);
@@ -630,21 +1014,21 @@ fn draw_debug_menu(game: &mut GameToolState, rect: &Rect, input: &InputState, le
                     game.raster_settings.stretch_to_fill = !game.raster_settings.stretch_to_fill;
                 }
             }
-            7 => {
+            9 => {
                 // RGB555 (PS1 15-bit color)
                 draw_toggle(menu_x, y, game.raster_settings.use_rgb555);
                 if is_selected && toggle_pressed(input) {
                     game.raster_settings.use_rgb555 = !game.raster_settings.use_rgb555;
                 }
             }
-            8 => {
+            10 => {
                 // Dithering (PS1 ordered dithering)
                 draw_toggle(menu_x, y, game.raster_settings.dithering);
                 if is_selected && toggle_pressed(input) {
                     game.raster_settings.dithering = !game.raster_settings.dithering;
                 }
             }
-            9 => {
+            11 => {
                 // Shading mode (cycle: None -> Flat -> Gouraud)
                 let mode_name = match game.raster_settings.shading {
                     ShadingMode::None => "None",
@@ -672,8 +1056,8 @@ fn draw_debug_menu(game: &mut GameToolState, rect: &Rect, input: &InputState, le
                     }
                 }
             }
-            10 => {
-                // FPS limit (cycle: 30 -> 60 -> Unlocked)
+            12 => {
+                // FPS limit (cycle: 24 -> 30 -> 60 -> Unlocked), applies to every tab
                 draw_text(game.fps_limit.label(), menu_x + 100.0, y, 12.0, Color::from_rgba(100, 180, 255, 255));
 
                 if is_selected {
@@ -687,9 +1071,126 @@ fn draw_debug_menu(game: &mut GameToolState, rect: &Rect, input: &InputState, le
                     }
                 }
             }
-            12 => {
+            13 => {
+                // LOD distance scale (0.25x - 4x)
+                draw_text(&format!("x{:.2}", game.lod_scale), menu_x + 100.0, y, 12.0, Color::from_rgba(100, 180, 255, 255));
+
+                if is_selected {
+                    if input.action_pressed(Action::SwitchLeftWeapon) || is_key_pressed(KeyCode::Left) {
+                        game.lod_scale = (game.lod_scale - 0.25).max(0.25);
+                    }
+                    if input.action_pressed(Action::SwitchRightWeapon) || is_key_pressed(KeyCode::Right) {
+                        game.lod_scale = (game.lod_scale + 0.25).min(4.0);
+                    }
+                }
+            }
+            14 => {
+                // Window mode (cycle: Windowed -> Maximized -> Borderless), saved per user
+                draw_text(game.display.window_mode.label(), menu_x + 100.0, y, 12.0, Color::from_rgba(100, 180, 255, 255));
+
+                if is_selected {
+                    if input.action_pressed(Action::SwitchLeftWeapon) || is_key_pressed(KeyCode::Left) {
+                        let mode = game.display.window_mode.prev();
+                        game.display.set_window_mode(mode);
+                    }
+                    if input.action_pressed(Action::SwitchRightWeapon) || is_key_pressed(KeyCode::Right)
+                        || input.action_pressed(Action::Jump) || is_key_pressed(KeyCode::Enter)
+                    {
+                        let mode = game.display.window_mode.next();
+                        game.display.set_window_mode(mode);
+                    }
+                }
+            }
+            15 => {
+                // Integer scaling (whole-pixel upscaling, letterboxed)
+                draw_toggle(menu_x, y, game.display.integer_scaling);
+                if is_selected && toggle_pressed(input) {
+                    let enabled = !game.display.integer_scaling;
+                    game.display.set_integer_scaling(enabled);
+                }
+            }
+            16 => {
+                // CRT filter on/off, saved per user
+                draw_toggle(menu_x, y, game.display.crt_filter);
+                if is_selected && toggle_pressed(input) {
+                    let enabled = !game.display.crt_filter;
+                    game.display.set_crt_filter(enabled);
+                }
+            }
+            17 => {
+                // CRT preset (cycle: Sharp -> CRT -> Composite)
+                draw_text(game.display.crt_preset.label(), menu_x + 100.0, y, 12.0, Color::from_rgba(100, 180, 255, 255));
+
+                if is_selected {
+                    if input.action_pressed(Action::SwitchLeftWeapon) || is_key_pressed(KeyCode::Left) {
+                        let preset = game.display.crt_preset.prev();
+                        game.display.set_crt_preset(preset);
+                    }
+                    if input.action_pressed(Action::SwitchRightWeapon) || is_key_pressed(KeyCode::Right)
+                        || input.action_pressed(Action::Jump) || is_key_pressed(KeyCode::Enter)
+                    {
+                        let preset = game.display.crt_preset.next();
+                        game.display.set_crt_preset(preset);
+                    }
+                }
+            }
+            18 => {
+                // CRT sharpness (0 = soft, 1 = hard pixel edges)
+                draw_text(&format!("{:.0}%", game.display.crt_sharpness * 100.0), menu_x + 100.0, y, 12.0, Color::from_rgba(100, 180, 255, 255));
+
+                if is_selected {
+                    let step = if input.action_pressed(Action::SwitchLeftWeapon) || is_key_pressed(KeyCode::Left) {
+                        -0.1
+                    } else if input.action_pressed(Action::SwitchRightWeapon) || is_key_pressed(KeyCode::Right) {
+                        0.1
+                    } else {
+                        0.0
+                    };
+                    if step != 0.0 {
+                        let sharpness = ((game.display.crt_sharpness + step) * 10.0).round() / 10.0;
+                        game.display.set_crt_sharpness(sharpness);
+                    }
+                }
+            }
+            19 => {
+                // Language (cycles through the string tables), saved per user
+                locale::draw_text(&locale::language_name(), menu_x + 100.0, y, 12.0, Color::from_rgba(100, 180, 255, 255));
+
+                if is_selected {
+                    let backwards = input.action_pressed(Action::SwitchLeftWeapon) || is_key_pressed(KeyCode::Left);
+                    if backwards || input.action_pressed(Action::SwitchRightWeapon) || is_key_pressed(KeyCode::Right)
+                        || input.action_pressed(Action::Jump) || is_key_pressed(KeyCode::Enter)
+                    {
+                        if let Some(code) = locale::cycle_language(backwards) {
+                            game.display.set_language(&code);
+                        }
+                    }
+                }
+            }
+            21..=24 => {
+                // Mix group volume (10% steps); the first change copies the level's defaults
+                let group = MixGroup::ALL[i - 21];
+                let mut levels = game.mix_levels(level);
+                let volume = levels.volume(group);
+                draw_text(&format!("{:.0}%", volume * 100.0), menu_x + 100.0, y, 12.0, Color::from_rgba(100, 180, 255, 255));
+
+                if is_selected {
+                    let step = if input.action_pressed(Action::SwitchLeftWeapon) || is_key_pressed(KeyCode::Left) {
+                        -0.1
+                    } else if input.action_pressed(Action::SwitchRightWeapon) || is_key_pressed(KeyCode::Right) {
+                        0.1
+                    } else {
+                        0.0
+                    };
+                    if step != 0.0 {
+                        levels.set_volume(group, ((volume + step) * 10.0).round() / 10.0);
+                        game.mix_override = Some(levels);
+                    }
+                }
+            }
+            26 => {
                 // Reset game
-                draw_text("[Press A]", menu_x + 100.0, y, 12.0, Color::from_rgba(80, 80, 90, 255));
+                locale::draw_text(&t("menu.press_a"), menu_x + 100.0, y, 12.0, Color::from_rgba(80, 80, 90, 255));
 
                 if is_selected {
                     if input.action_pressed(Action::Jump) || is_key_pressed(KeyCode::Enter) {
@@ -710,18 +1211,18 @@ fn draw_debug_menu(game: &mut GameToolState, rect: &Rect, input: &InputState, le
     }
 
     // Hint at bottom
-    draw_text("D-Pad: Navigate  A: Toggle", menu_x + 8.0, menu_y + menu_h - 8.0, 10.0, Color::from_rgba(80, 80, 90, 255));
+    locale::draw_text(&t("menu.hint"), menu_x + 8.0, menu_y + menu_h - 8.0, 10.0, Color::from_rgba(80, 80, 90, 255));
 }
 
 /// Helper: draw ON/OFF toggle at position
 fn draw_toggle(menu_x: f32, y: f32, enabled: bool) {
-    let state = if enabled { "ON" } else { "OFF" };
+    let state = t(if enabled { "menu.on" } else { "menu.off" });
     let color = if enabled {
         Color::from_rgba(100, 255, 100, 255)
     } else {
         Color::from_rgba(100, 180, 255, 255)
     };
-    draw_text(state, menu_x + 100.0, y, 12.0, color);
+    locale::draw_text(&state, menu_x + 100.0, y, 12.0, color);
 }
 
 /// Helper: check if toggle action was pressed
@@ -731,6 +1232,273 @@ fn toggle_pressed(input: &InputState) -> bool {
         || input.action_pressed(Action::SwitchRightWeapon) || is_key_pressed(KeyCode::Right)
 }
 
+/// Draw on-screen messages queued by level scripts, plus script compile errors
+fn draw_script_messages(game: &GameToolState, rect: &Rect) {
+    let font_size = 16.0;
+    let line_h = 20.0;
+
+    // Message text may be a string table key
+    let messages: Vec<String> = game.logic.messages().map(t).collect();
+    let mut y = rect.y + rect.h - 40.0 - (messages.len() as f32 - 1.0).max(0.0) * line_h;
+    for text in messages {
+        let w = locale::measure_text(&text, font_size);
+        let x = rect.x + (rect.w - w) * 0.5;
+        draw_rectangle(x - 8.0, y - 15.0, w + 16.0, line_h, Color::from_rgba(0, 0, 0, 160));
+        locale::draw_text(&text, x, y, font_size, WHITE);
+        y += line_h;
+    }
+
+    let mut y = rect.y + 28.0;
+    for error in &game.logic.errors {
+        let text = format!("Script error: {}", error);
+        let w = measure_text(&text, None, 12, 1.0).width;
+        let x = rect.x + (rect.w - w) * 0.5;
+        draw_rectangle(x - 6.0, y - 11.0, w + 12.0, 15.0, Color::from_rgba(60, 0, 0, 200));
+        draw_text(&text, x, y, 12.0, Color::from_rgba(255, 120, 120, 255));
+        y += 16.0;
+    }
+}
+
+/// Save the finished recording as `REPLAY_DIR/replay-<unix time>.ron`
+fn save_replay(game: &GameToolState) {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        match game.replay.replay().save(&format!("replay-{}", stamp)) {
+            Ok(path) => println!("Saved replay to {}", path.display()),
+            Err(e) => eprintln!("Failed to save replay: {}", e),
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    let _ = game;
+}
+
+/// Recording/playback indicator below the menu hint, with the first desynced frame
+fn draw_replay_status(game: &GameToolState, rect: &Rect) {
+    let (done, total) = game.replay.progress();
+    let (text, color) = match game.replay.mode() {
+        ReplayMode::Recording => (
+            format!("REC {} frames [F7 stop]", total),
+            Color::from_rgba(255, 90, 90, 255),
+        ),
+        ReplayMode::Playing => (
+            format!("REPLAY {}/{}", done, total),
+            Color::from_rgba(120, 220, 120, 255),
+        ),
+        // Keep reporting a desync after playback ends
+        ReplayMode::Off if game.replay.desync.is_some() => (
+            format!("REPLAY ended {}/{}", done, total),
+            Color::from_rgba(255, 200, 50, 255),
+        ),
+        ReplayMode::Off => return,
+    };
+    let text = match game.replay.desync {
+        Some(frame) => format!("{}  DESYNC at frame {}", text, frame),
+        None => text,
+    };
+    let w = measure_text(&text, None, 11, 1.0).width + 8.0;
+    let (x, y) = (rect.x + 4.0, rect.y + 24.0);
+    draw_rectangle(x, y, w, 16.0, Color::from_rgba(0, 0, 0, 160));
+    draw_text(&text, x + 4.0, y + 12.0, 11.0, color);
+}
+
+/// Draw a playing cutscene's letterbox bars, subtitle and skip hint over the
+/// frame's screen area
+fn draw_cutscene_overlay(game: &GameToolState, frame: &Rect, input: &InputState) {
+    let Some(cutscene) = game.logic.cutscene() else {
+        return;
+    };
+    let bar_h = if cutscene.letterbox() { (frame.h * 0.12).floor() } else { 0.0 };
+    if bar_h > 0.0 {
+        draw_rectangle(frame.x, frame.y, frame.w, bar_h, BLACK);
+        draw_rectangle(frame.x, frame.bottom() - bar_h, frame.w, bar_h, BLACK);
+    }
+
+    if let Some(text) = cutscene.subtitle().map(t) {
+        let font_size = 18.0;
+        let w = locale::measure_text(&text, font_size);
+        let x = frame.x + (frame.w - w) * 0.5;
+        // Centered in the bottom bar, or just above the bottom edge without one
+        let y = if bar_h > 0.0 { frame.bottom() - bar_h * 0.5 + 6.0 } else { frame.bottom() - 32.0 };
+        if bar_h <= 0.0 {
+            draw_rectangle(x - 8.0, y - 17.0, w + 16.0, 24.0, Color::from_rgba(0, 0, 0, 160));
+        }
+        locale::draw_text(&text, x, y, font_size, WHITE);
+    }
+
+    if cutscene.skippable() {
+        let hint = format!("[{}] {}", input.prompt_label(Action::Interact), t("game.skip"));
+        let w = locale::measure_text(&hint, 12.0);
+        locale::draw_text(&hint, frame.right() - w - 10.0, frame.y + bar_h.max(16.0) - 6.0, 12.0, Color::from_rgba(180, 180, 180, 200));
+    }
+}
+
+/// Draw a conversation over the frame's screen area: letterbox bars, the
+/// speaker and the line typing out in the bottom bar, then the choices
+/// (D-pad to pick) or a continue hint once it has typed out
+fn draw_dialogue_overlay(game: &GameToolState, frame: &Rect, input: &InputState) {
+    let Some(dialogue) = game.logic.dialogue() else {
+        return;
+    };
+    let font_size = 16.0;
+    let line_h = 20.0;
+    let key_color = Color::from_rgba(255, 220, 120, 255);
+    let top_h = (frame.h * 0.12).floor();
+    let bottom_h = (frame.h * 0.3).floor().max(line_h * 4.0 + 16.0);
+    let bottom = frame.bottom() - bottom_h;
+    draw_rectangle(frame.x, frame.y, frame.w, top_h, BLACK);
+    draw_rectangle(frame.x, bottom, frame.w, bottom_h, BLACK);
+
+    let x = frame.x + frame.w * 0.08;
+    let mut y = bottom + 24.0;
+    if !dialogue.speaker().is_empty() {
+        locale::draw_text(&t(dialogue.speaker()), x, y, font_size, key_color);
+        y += line_h + 4.0;
+    }
+    // Lay out the whole line so words don't jump to the next row as they type
+    let mut typed = dialogue.text().chars().count();
+    for row in wrap_words(dialogue.line(), font_size, frame.w * 0.84) {
+        if typed == 0 {
+            break;
+        }
+        let shown: String = row.chars().take(typed).collect();
+        locale::draw_text(&shown, x, y, font_size, WHITE);
+        typed = typed.saturating_sub(row.chars().count() + 1);
+        y += line_h;
+    }
+    if dialogue.typing() {
+        return;
+    }
+
+    let choices = dialogue.choices();
+    if choices.is_empty() {
+        let hint = format!("[{}] {}", input.prompt_label(Action::Interact), t("game.continue"));
+        let w = locale::measure_text(&hint, 12.0);
+        locale::draw_text(&hint, frame.right() - w - 16.0, frame.bottom() - 12.0, 12.0, key_color);
+        return;
+    }
+    // Choices stack in a box above the bottom bar, right-aligned
+    let texts: Vec<String> = choices.iter().map(|c| t(c)).collect();
+    let w = texts.iter().map(|c| locale::measure_text(c, font_size)).fold(0.0, f32::max) + 40.0;
+    let h = texts.len() as f32 * line_h + 12.0;
+    let (box_x, box_y) = (frame.right() - w - 16.0, bottom - h - 8.0);
+    draw_rectangle(box_x, box_y, w, h, Color::from_rgba(16, 18, 26, 230));
+    draw_rectangle_lines(box_x, box_y, w, h, 1.0, key_color);
+    for (i, text) in texts.iter().enumerate() {
+        let row_y = box_y + 6.0 + line_h * (i as f32 + 0.75);
+        let selected = i == dialogue.selected();
+        if selected {
+            locale::draw_text(">", box_x + 10.0, row_y, font_size, key_color);
+        }
+        locale::draw_text(text, box_x + 26.0, row_y, font_size, if selected { WHITE } else { GRAY });
+    }
+}
+
+/// Draw the inventory screen: carried weapons and armor with their stats,
+/// the highlighted one marked and equipped ones tagged
+fn draw_inventory_menu(game: &GameToolState, frame: &Rect, input: &InputState) {
+    let font_size = 16.0;
+    let line_h = 22.0;
+    let key_color = Color::from_rgba(255, 220, 120, 255);
+    let items = game.equipment.items();
+    let w = (frame.w * 0.6).max(360.0).min(frame.w);
+    let h = line_h * (items.len().max(1) as f32 + 2.0) + 24.0;
+    let (x, y) = (frame.x + (frame.w - w) / 2.0, frame.y + (frame.h - h) / 2.0);
+    draw_rectangle(x, y, w, h, Color::from_rgba(20, 22, 28, 230));
+    draw_rectangle_lines(x, y, w, h, 1.0, Color::from_rgba(60, 65, 75, 255));
+    locale::draw_text(&t("inventory.title"), x + 12.0, y + 22.0, font_size, key_color);
+
+    let mut row_y = y + 22.0 + line_h + 4.0;
+    if items.is_empty() {
+        locale::draw_text(&t("inventory.empty"), x + 28.0, row_y, font_size, GRAY);
+    }
+    for (i, gear) in items.iter().enumerate() {
+        let selected = i == game.inventory_selected;
+        if selected {
+            locale::draw_text(">", x + 12.0, row_y, font_size, key_color);
+        }
+        locale::draw_text(&t(&gear.name), x + 28.0, row_y, font_size, if selected { WHITE } else { GRAY });
+        let stats = match &gear.kind {
+            GearKind::Weapon { moveset, speed, .. } => format!("DMG {}  SPD {:.1}", moveset.damage, speed),
+            GearKind::Armor { defense, weight } => format!(
+                "DEF {:.0}%  WT {:.0}  {}",
+                defense * 100.0,
+                weight,
+                combat::RollTier::from_weight(*weight).label(),
+            ),
+        };
+        let stats_x = x + w * 0.45;
+        draw_text(&stats, stats_x, row_y, 14.0, Color::from_rgba(180, 180, 190, 255));
+        if game.equipment.is_equipped(i) {
+            let tag = t("inventory.equipped");
+            let tag_w = locale::measure_text(&tag, 12.0);
+            locale::draw_text(&tag, x + w - tag_w - 12.0, row_y, 12.0, key_color);
+        }
+        row_y += line_h;
+    }
+
+    let hint = t("inventory.hint").replace("{key}", input.prompt_label(Action::Interact));
+    let hint_w = locale::measure_text(&hint, 12.0);
+    locale::draw_text(&hint, x + w - hint_w - 12.0, y + h - 10.0, 12.0, Color::from_rgba(150, 150, 160, 255));
+}
+
+/// Split text into rows no wider than `max_width` at word boundaries
+fn wrap_words(text: &str, font_size: f32, max_width: f32) -> Vec<String> {
+    let mut rows: Vec<String> = Vec::new();
+    let mut row = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if row.is_empty() { word.to_string() } else { format!("{} {}", row, word) };
+        if !row.is_empty() && locale::measure_text(&candidate, font_size) > max_width {
+            rows.push(std::mem::replace(&mut row, word.to_string()));
+        } else {
+            row = candidate;
+        }
+    }
+    if !row.is_empty() {
+        rows.push(row);
+    }
+    rows
+}
+
+/// Draw script button prompts ("[A] Jump") and the current tutorial popup
+fn draw_tutorial_hints(game: &GameToolState, rect: &Rect, input: &InputState) {
+    let font_size = 16.0;
+    let line_h = 22.0;
+    let key_color = Color::from_rgba(255, 220, 120, 255);
+
+    // Prompt and popup text may be string table keys
+    let prompts: Vec<(Action, String)> = game.logic.prompts().map(|(action, text)| (action, t(text))).collect();
+    let mut y = rect.y + rect.h - 40.0 - (prompts.len() as f32 - 1.0).max(0.0) * line_h;
+    for (action, text) in prompts {
+        let key = format!("[{}]", input.prompt_label(action));
+        let key_w = measure_text(&key, None, font_size as u16, 1.0).width;
+        let text_w = locale::measure_text(&text, font_size);
+        let x = rect.x + 16.0;
+        draw_rectangle(x - 6.0, y - 15.0, key_w + text_w + 20.0, line_h - 2.0, Color::from_rgba(0, 0, 0, 160));
+        draw_text(&key, x, y, font_size, key_color);
+        locale::draw_text(&text, x + key_w + 8.0, y, font_size, WHITE);
+        y += line_h;
+    }
+
+    let Some(text) = game.logic.tutorial().map(t) else {
+        return;
+    };
+    let hint = format!("[{}] {}", input.prompt_label(Action::Interact), t("game.continue"));
+    let text_w = locale::measure_text(&text, font_size);
+    let hint_w = locale::measure_text(&hint, 12.0);
+    let w = text_w.max(hint_w).max(160.0) + 32.0;
+    let h = 64.0;
+    let x = rect.x + (rect.w - w) * 0.5;
+    let y = rect.y + rect.h * 0.35;
+    draw_rectangle(x, y, w, h, Color::from_rgba(16, 18, 26, 230));
+    draw_rectangle_lines(x, y, w, h, 1.0, key_color);
+    locale::draw_text(&text, x + (w - text_w) * 0.5, y + 28.0, font_size, WHITE);
+    locale::draw_text(&hint, x + (w - hint_w) * 0.5, y + h - 12.0, 12.0, key_color);
+}
+
 /// Draw debug overlay HUD (top-right, shows player/collision stats)
 fn draw_debug_overlay(game: &GameToolState, rect: &Rect, input: &InputState, level: &Level) {
     // Scale factor for the entire overlay (1.5x for compact display)
@@ -1049,3 +1817,153 @@ fn draw_wireframe_cylinder(
     }
 }
 
+
+/// Project a world point into the framebuffer: (x, y, depth); None behind the camera
+fn project_to_fb(fb: &Framebuffer, camera: &crate::rasterizer::Camera, p: Vec3) -> Option<(i32, i32, f32)> {
+    let cam = perspective_transform(p - camera.position, camera.basis_x, camera.basis_y, camera.basis_z);
+    if cam.z < 0.1 {
+        return None;
+    }
+    let proj = project(cam, fb.width, fb.height);
+    Some((proj.x as i32, proj.y as i32, cam.z))
+}
+
+/// Draw a world-space line in the 3D view (skipped if an end is behind the camera)
+fn draw_world_line(fb: &mut Framebuffer, camera: &crate::rasterizer::Camera, a: Vec3, b: Vec3, color: RasterColor) {
+    if let (Some((x0, y0, z0)), Some((x1, y1, z1))) = (project_to_fb(fb, camera, a), project_to_fb(fb, camera, b)) {
+        fb.draw_line_3d(x0, y0, z0, x1, y1, z1, color);
+    }
+}
+
+/// Draw world-space collision outlines in the 3D view (segments behind the camera are skipped)
+fn draw_wireframe_lines(fb: &mut Framebuffer, camera: &crate::rasterizer::Camera, lines: &[(ShapeKind, Vec3, Vec3)]) {
+    for &(kind, a, b) in lines {
+        let color = match kind {
+            ShapeKind::Controller => RasterColor::new(80, 200, 255),
+            ShapeKind::Hitbox => RasterColor::new(255, 80, 80),
+            ShapeKind::Hurtbox => RasterColor::new(255, 220, 80),
+        };
+        draw_world_line(fb, camera, a, b, color);
+    }
+}
+
+/// Draw the navgraph's links just above the floor, and the paths patrolling
+/// enemies are walking from where they stand
+fn draw_nav_overlay(fb: &mut Framebuffer, game: &GameToolState) {
+    let lift = Vec3::new(0.0, 16.0, 0.0);
+    for (a, b) in game.nav.edges() {
+        draw_world_line(fb, &game.camera, a + lift, b + lift, RasterColor::new(60, 120, 160));
+    }
+    for (_, (transform, patrol)) in game.world.query_ref::<(&Transform, &Patrol)>() {
+        let mut from = transform.position - Vec3::new(0.0, patrol.lift, 0.0);
+        for &to in &patrol.path {
+            draw_world_line(fb, &game.camera, from + lift, to + lift, RasterColor::new(255, 160, 40));
+            from = to;
+        }
+    }
+}
+
+/// Draw the entity inspector: pause/step controls, the live entity list
+/// (click to select, wheel to scroll) and the selected entity's components
+fn draw_inspector(game: &mut GameToolState, rect: &Rect, ctx: &crate::ui::UiContext) {
+    let panel_w = 250.0;
+    let row_h = 14.0;
+    let text_size = 12.0;
+    let list_rows = 10;
+    // Left of the debug overlay when that's shown
+    let right = if game.show_debug_overlay { rect.right() - 160.0 * 1.5 - 20.0 } else { rect.right() - 10.0 };
+    let x = right - panel_w;
+    let mut y = rect.y + 10.0;
+
+    let label_color = Color::from_rgba(120, 120, 130, 255);
+    let value_color = Color::from_rgba(200, 200, 210, 255);
+    let accent = Color::from_rgba(100, 180, 255, 255);
+
+    let entities: Vec<_> = game.world.entities().collect();
+    let rows = game.inspector.selected.map(|e| inspector::inspect(&game.world, e)).unwrap_or_default();
+    let components = game.inspector.selected.map(|e| inspector::component_names(&game.world, e)).unwrap_or_default();
+    // Components three to a line
+    let component_lines: Vec<String> = components.chunks(3).map(|c| c.join(", ")).collect();
+    let details_h = if game.inspector.selected.is_some() {
+        (rows.len() + component_lines.len()) as f32 * row_h + 8.0
+    } else {
+        row_h + 8.0
+    };
+    let panel_h = 20.0 + row_h * 2.0 + list_rows as f32 * row_h + 8.0 + details_h;
+    draw_rectangle(x, y, panel_w, panel_h, Color::from_rgba(20, 22, 28, 220));
+    draw_rectangle_lines(x, y, panel_w, panel_h, 1.0, Color::from_rgba(60, 65, 75, 255));
+
+    // Title and pause/step buttons
+    draw_text(&format!("Inspector ({} entities)", entities.len()), x + 6.0, y + 14.0, text_size, WHITE);
+    y += 20.0;
+    let (status, status_color) = if game.inspector.paused {
+        ("PAUSED", Color::from_rgba(255, 180, 80, 255))
+    } else {
+        ("RUNNING", Color::from_rgba(100, 255, 100, 255))
+    };
+    draw_text(status, x + 6.0, y + 10.0, text_size, status_color);
+    let buttons = [
+        (if game.inspector.paused { "[F5] Resume" } else { "[F5] Pause" }, x + 70.0),
+        ("[F6] Step", x + 160.0),
+    ];
+    for (i, (label, bx)) in buttons.iter().enumerate() {
+        let button = Rect::new(*bx - 3.0, y - 1.0, 80.0, row_h);
+        let hovered = ctx.mouse.inside(&button);
+        draw_text(label, *bx, y + 10.0, text_size, if hovered { WHITE } else { accent });
+        if hovered && ctx.mouse.left_pressed {
+            if i == 0 {
+                game.inspector.toggle_pause();
+            } else {
+                game.inspector.step();
+            }
+        }
+    }
+    y += row_h * 2.0;
+
+    // Entity list
+    let list_rect = Rect::new(x, y, panel_w, list_rows as f32 * row_h);
+    let max_scroll = entities.len().saturating_sub(list_rows);
+    if ctx.mouse.inside(&list_rect) && ctx.mouse.scroll != 0.0 {
+        game.inspector.scroll = if ctx.mouse.scroll > 0.0 {
+            game.inspector.scroll.saturating_sub(1)
+        } else {
+            game.inspector.scroll + 1
+        };
+    }
+    game.inspector.scroll = game.inspector.scroll.min(max_scroll);
+    for (i, &entity) in entities.iter().skip(game.inspector.scroll).take(list_rows).enumerate() {
+        let row = Rect::new(x + 2.0, y + i as f32 * row_h, panel_w - 4.0, row_h);
+        let selected = game.inspector.selected == Some(entity);
+        if selected {
+            draw_rectangle(row.x, row.y, row.w, row.h, Color::from_rgba(60, 80, 120, 255));
+        } else if ctx.mouse.inside(&row) {
+            draw_rectangle(row.x, row.y, row.w, row.h, Color::from_rgba(45, 48, 58, 255));
+        }
+        if ctx.mouse.inside(&row) && ctx.mouse.left_pressed {
+            game.inspector.selected = if selected { None } else { Some(entity) };
+        }
+        let label = inspector::entity_label(&game.world, entity);
+        draw_text(&label, row.x + 4.0, row.y + 11.0, text_size, if selected { WHITE } else { value_color });
+    }
+    if entities.len() > list_rows {
+        let shown = format!("{}-{}", game.inspector.scroll + 1, (game.inspector.scroll + list_rows).min(entities.len()));
+        draw_text(&shown, x + panel_w - 50.0, y - 4.0, 10.0, label_color);
+    }
+    y += list_rect.h + 8.0;
+    draw_line(x + 6.0, y - 4.0, x + panel_w - 6.0, y - 4.0, 1.0, Color::from_rgba(60, 65, 75, 255));
+
+    // Selected entity's fields and components
+    if game.inspector.selected.is_none() {
+        draw_text("Click an entity to inspect it", x + 6.0, y + 10.0, text_size, label_color);
+        return;
+    }
+    for (label, value) in &rows {
+        draw_text(label, x + 6.0, y + 10.0, text_size, label_color);
+        draw_text(value, x + 76.0, y + 10.0, text_size, value_color);
+        y += row_h;
+    }
+    for line in &component_lines {
+        draw_text(line, x + 6.0, y + 10.0, text_size, accent);
+        y += row_h;
+    }
+}
@@ -0,0 +1,369 @@
+//! Replay Recording
+//!
+//! Records a Test tab session as the input the character controls read each
+//! tick plus the session's RNG seed, so the run can be played back exactly:
+//! attach the file to a bug report ("it happened in this replay"), or drive a
+//! ghost from it later.
+//!
+//! Recording and playback both start from a fresh session (the game resets).
+//! Each frame stores the tick's delta and the input read after that tick;
+//! during playback the tick uses the recorded delta and the controls read the
+//! recorded input instead of the devices. The player's position after every
+//! tick is stored too, so playback can report the first frame where the
+//! simulation drifted from the recording.
+//!
+//! Frames only advance while the world ticks: pausing in the inspector, the
+//! options menu and the free-fly camera aren't part of a replay.
+
+use serde::{Deserialize, Serialize};
+use macroquad::prelude::Vec2;
+use crate::input::{Action, InputState};
+use crate::rasterizer::Vec3;
+
+/// Where saved replays are written (native only)
+pub const REPLAY_DIR: &str = "assets/userdata/replays";
+/// Replay file format version
+pub const REPLAY_VERSION: u32 = 1;
+/// Player drift (world units) from the recorded position that counts as a desync
+pub const DESYNC_TOLERANCE: f32 = 0.5;
+
+/// Input the character controls read for one frame
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputFrame {
+    /// Seconds the tick before this input advanced
+    pub delta: f32,
+    /// Held button actions (script names, see `Action::name`)
+    #[serde(default)]
+    pub down: Vec<String>,
+    /// Button actions that went down this frame
+    #[serde(default)]
+    pub pressed: Vec<String>,
+    #[serde(default)]
+    pub left_stick: (f32, f32),
+    #[serde(default)]
+    pub right_stick: (f32, f32),
+    /// Mouse-look drag in pixels (None = not dragging)
+    #[serde(default)]
+    pub look: Option<(f32, f32)>,
+    /// Player position after the tick (desync check)
+    #[serde(default)]
+    pub player: Option<(f32, f32, f32)>,
+}
+
+impl InputFrame {
+    /// No input (before the first tick, or while a menu has the controls)
+    pub fn idle(delta: f32) -> Self {
+        Self { delta, ..Self::default() }
+    }
+
+    /// Read the devices. `look` is the mouse-look drag, if the mouse is
+    /// dragging in the viewport.
+    pub fn capture(input: &InputState, look: Option<(f32, f32)>, delta: f32) -> Self {
+        let names = |test: &dyn Fn(Action) -> bool| -> Vec<String> {
+            Action::NAMED.iter()
+                .filter(|&&action| test(action))
+                .filter_map(|action| action.name().map(String::from))
+                .collect()
+        };
+        let (left, right) = (input.left_stick(), input.right_stick());
+        Self {
+            delta,
+            down: names(&|action| input.action_down(action)),
+            pressed: names(&|action| input.action_pressed(action)),
+            left_stick: (left.x, left.y),
+            right_stick: (right.x, right.y),
+            look,
+            player: None,
+        }
+    }
+
+    /// Is a button action held?
+    pub fn action_down(&self, action: Action) -> bool {
+        action.name().is_some_and(|name| self.down.iter().any(|d| d == name))
+    }
+
+    /// Did a button action go down this frame?
+    pub fn action_pressed(&self, action: Action) -> bool {
+        action.name().is_some_and(|name| self.pressed.iter().any(|p| p == name))
+    }
+
+    /// Movement stick (keyboard WASD folded in)
+    pub fn left_stick(&self) -> Vec2 {
+        Vec2::new(self.left_stick.0, self.left_stick.1)
+    }
+
+    /// Camera stick
+    pub fn right_stick(&self) -> Vec2 {
+        Vec2::new(self.right_stick.0, self.right_stick.1)
+    }
+}
+
+/// A recorded session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    /// Session RNG seed (see `GameToolState::rng_seed`)
+    pub seed: u32,
+    /// Character camera (yaw, pitch) when the session started
+    pub camera: (f32, f32),
+    pub frames: Vec<InputFrame>,
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self::new(0, (0.0, 0.0))
+    }
+}
+
+impl Replay {
+    pub fn new(seed: u32, camera: (f32, f32)) -> Self {
+        Self { version: REPLAY_VERSION, seed, camera, frames: Vec::new() }
+    }
+
+    /// Recorded game time in seconds
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|f| f.delta).sum()
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string(self)
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, String> {
+        let replay: Self = ron::from_str(text).map_err(|e| e.to_string())?;
+        if replay.version > REPLAY_VERSION {
+            return Err(format!("replay version {} is newer than this build ({})", replay.version, REPLAY_VERSION));
+        }
+        Ok(replay)
+    }
+
+    /// Write to `REPLAY_DIR/<name>.ron`, returning the file path
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, name: &str) -> Result<std::path::PathBuf, String> {
+        std::fs::create_dir_all(REPLAY_DIR).map_err(|e| e.to_string())?;
+        let path = std::path::PathBuf::from(REPLAY_DIR).join(format!("{}.ron", name));
+        let contents = self.to_ron().map_err(|e| e.to_string())?;
+        std::fs::write(&path, contents).map_err(|e| e.to_string())?;
+        Ok(path)
+    }
+
+    /// Read a replay file
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::from_ron(&text)
+    }
+}
+
+/// What the replay system is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayMode {
+    #[default]
+    Off,
+    Recording,
+    Playing,
+}
+
+/// Recorder and player for the Test tab session
+#[derive(Debug, Clone, Default)]
+pub struct ReplayState {
+    mode: ReplayMode,
+    /// Replay being recorded or played (kept after it ends so it can be
+    /// saved or played again)
+    replay: Replay,
+    /// Frames played so far
+    cursor: usize,
+    /// First frame where playback drifted from the recording
+    pub desync: Option<usize>,
+}
+
+impl ReplayState {
+    pub fn mode(&self) -> ReplayMode {
+        self.mode
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.mode == ReplayMode::Recording
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.mode == ReplayMode::Playing
+    }
+
+    /// The last recorded or played replay
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// (frames played, total frames) while playing; frames recorded otherwise
+    pub fn progress(&self) -> (usize, usize) {
+        (self.cursor, self.replay.frames.len())
+    }
+
+    /// Start recording a session with this seed and starting camera
+    pub fn start_recording(&mut self, seed: u32, camera: (f32, f32)) {
+        self.replay = Replay::new(seed, camera);
+        self.mode = ReplayMode::Recording;
+        self.cursor = 0;
+        self.desync = None;
+    }
+
+    /// Start playing a replay back
+    pub fn start_playback(&mut self, replay: Replay) {
+        self.replay = replay;
+        self.mode = ReplayMode::Playing;
+        self.cursor = 0;
+        self.desync = None;
+    }
+
+    /// Stop recording or playing (the replay is kept)
+    pub fn stop(&mut self) {
+        self.mode = ReplayMode::Off;
+    }
+
+    /// Delta for the tick about to run: recording stores it as a new frame,
+    /// playback hands out the recorded one (and stops once the replay ends)
+    pub fn tick_delta(&mut self, live: f32) -> f32 {
+        match self.mode {
+            ReplayMode::Off => live,
+            ReplayMode::Recording => {
+                self.replay.frames.push(InputFrame::idle(live));
+                live
+            }
+            ReplayMode::Playing => match self.replay.frames.get(self.cursor) {
+                Some(frame) => {
+                    self.cursor += 1;
+                    frame.delta
+                }
+                None => {
+                    self.mode = ReplayMode::Off;
+                    live
+                }
+            },
+        }
+    }
+
+    /// After the tick: store the player's position, or check it against the recording
+    pub fn after_tick(&mut self, player: Option<Vec3>) {
+        let player = player.map(|p| (p.x, p.y, p.z));
+        match self.mode {
+            ReplayMode::Off => {}
+            ReplayMode::Recording => {
+                if let Some(frame) = self.replay.frames.last_mut() {
+                    frame.player = player;
+                }
+            }
+            ReplayMode::Playing => {
+                let Some(index) = self.cursor.checked_sub(1) else {
+                    return;
+                };
+                let recorded = self.replay.frames.get(index).and_then(|f| f.player);
+                let drifted = match (recorded, player) {
+                    (Some(a), Some(b)) => {
+                        let d = (a.0 - b.0, a.1 - b.1, a.2 - b.2);
+                        (d.0 * d.0 + d.1 * d.1 + d.2 * d.2).sqrt() > DESYNC_TOLERANCE
+                    }
+                    (None, None) => false,
+                    _ => true,
+                };
+                if drifted && self.desync.is_none() {
+                    self.desync = Some(index);
+                }
+            }
+        }
+    }
+
+    /// Input for the controls this frame: `live` while recording (stored with
+    /// the last tick's frame) or off, the recorded input while playing. Before
+    /// the first tick there is no input either way.
+    pub fn input(&mut self, live: InputFrame) -> InputFrame {
+        match self.mode {
+            ReplayMode::Off => live,
+            ReplayMode::Recording => match self.replay.frames.last_mut() {
+                Some(frame) => {
+                    *frame = InputFrame { delta: frame.delta, player: frame.player, ..live };
+                    frame.clone()
+                }
+                None => InputFrame::idle(live.delta),
+            },
+            ReplayMode::Playing => match self.cursor.checked_sub(1) {
+                Some(index) => self.replay.frames.get(index).cloned().unwrap_or_else(|| InputFrame::idle(live.delta)),
+                None => InputFrame::idle(live.delta),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jump_frame() -> InputFrame {
+        InputFrame {
+            delta: 1.0,
+            down: vec!["jump".to_string()],
+            pressed: vec!["jump".to_string()],
+            left_stick: (0.0, 1.0),
+            ..InputFrame::default()
+        }
+    }
+
+    #[test]
+    fn test_recording_pairs_ticks_with_input() {
+        let mut state = ReplayState::default();
+        state.start_recording(7, (0.5, 0.2));
+
+        // Input before the first tick isn't recorded (and isn't applied)
+        assert_eq!(state.input(jump_frame()), InputFrame::idle(1.0));
+
+        assert_eq!(state.tick_delta(0.02), 0.02);
+        state.after_tick(Some(Vec3::new(1.0, 2.0, 3.0)));
+        let frame = state.input(jump_frame());
+        assert!(frame.action_pressed(Action::Jump) && frame.action_down(Action::Jump));
+        assert!(!frame.action_down(Action::Dodge));
+        assert_eq!(frame.delta, 0.02);
+
+        state.stop();
+        let replay = state.replay();
+        assert_eq!((replay.seed, replay.frames.len()), (7, 1));
+        assert_eq!(replay.frames[0].player, Some((1.0, 2.0, 3.0)));
+        assert_eq!(replay.frames[0].left_stick, (0.0, 1.0));
+
+        let text = replay.to_ron().unwrap();
+        assert_eq!(&Replay::from_ron(&text).unwrap(), replay);
+    }
+
+    #[test]
+    fn test_playback_replays_deltas_and_flags_desync() {
+        let mut recorder = ReplayState::default();
+        recorder.start_recording(0, (0.0, 0.0));
+        for (delta, x) in [(0.1, 0.0), (0.2, 10.0), (0.3, 20.0)] {
+            recorder.tick_delta(delta);
+            recorder.after_tick(Some(Vec3::new(x, 0.0, 0.0)));
+            recorder.input(jump_frame());
+        }
+        recorder.stop();
+
+        let mut player = ReplayState::default();
+        player.start_playback(recorder.replay().clone());
+        assert_eq!(player.input(InputFrame::idle(0.5)), InputFrame::idle(0.5));
+
+        assert_eq!(player.tick_delta(1.0), 0.1);
+        player.after_tick(Some(Vec3::new(0.0, 0.0, 0.0)));
+        assert!(player.input(InputFrame::idle(1.0)).action_pressed(Action::Jump));
+        assert_eq!(player.tick_delta(1.0), 0.2);
+        player.after_tick(Some(Vec3::new(10.0, 0.0, 0.0)));
+        assert_eq!(player.desync, None);
+
+        // Drifting from the recorded position marks the first bad frame
+        assert_eq!(player.tick_delta(1.0), 0.3);
+        player.after_tick(Some(Vec3::new(25.0, 0.0, 0.0)));
+        assert_eq!(player.desync, Some(2));
+        assert_eq!(player.progress(), (3, 3));
+
+        // Past the end, playback stops and live input takes over
+        assert_eq!(player.tick_delta(1.0), 1.0);
+        assert_eq!(player.mode(), ReplayMode::Off);
+        assert_eq!(player.input(InputFrame::idle(1.0)), InputFrame::idle(1.0));
+    }
+}
@@ -4,9 +4,30 @@
 //! Reads level data from ProjectData for rendering, uses ECS World for entities.
 //! Player settings are stored in Level.player_settings and edited in the World Editor.
 
-use crate::rasterizer::{Camera, Vec3, RasterSettings, Texture15};
-use crate::world::Level;
+use crate::rasterizer::{Camera, Framebuffer, FramebufferTexture, Vec3, RasterSettings, Texture15};
+use crate::world::{ChunkStreamer, Level};
+use crate::frame_pacer::FpsLimit;
+use crate::crt::CrtFilter;
+use crate::display::DisplaySettings;
+use crate::tracker::MixLevels;
 use super::{World, Events, Entity};
+use super::level_logic::LevelLogic;
+use super::analytics::SessionRecorder;
+use super::lock_on::{self, LockOn};
+use super::rail_camera::RailCamera;
+use super::replay::{Replay, ReplayState};
+use super::weather::WeatherState;
+use super::particles::ParticleSystem;
+use super::sound::GameSounds;
+use super::inspector::Inspector;
+use super::equipment::{Equipment, Gear};
+use super::music::MusicDirector;
+use super::nav::{self, NavGraph};
+use super::status::{self, StatusEffects};
+use super::schedule::{Schedule, SystemContext};
+use super::event::{ClimbEvent, DamageEvent, SwimEvent};
+use super::components::{CharacterController, Combat, Health, PathFollower, Patrol, Stamina, Velocity};
+use super::transform::{GlobalTransform, Transform};
 
 /// Frame timing data for performance profiling
 #[derive(Debug, Clone, Default)]
@@ -75,56 +96,6 @@ pub enum CameraMode {
     FreeFly,
 }
 
-/// FPS limit setting
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum FpsLimit {
-    /// 30 FPS (authentic PS1 for many games)
-    Fps30,
-    /// 60 FPS (smooth gameplay)
-    #[default]
-    Fps60,
-    /// Unlocked (as fast as possible)
-    Unlocked,
-}
-
-impl FpsLimit {
-    /// Get the target frame time in seconds (None = unlocked)
-    pub fn frame_time(&self) -> Option<f64> {
-        match self {
-            FpsLimit::Fps30 => Some(1.0 / 30.0),
-            FpsLimit::Fps60 => Some(1.0 / 60.0),
-            FpsLimit::Unlocked => None,
-        }
-    }
-
-    /// Cycle to next value
-    pub fn next(self) -> Self {
-        match self {
-            FpsLimit::Fps30 => FpsLimit::Fps60,
-            FpsLimit::Fps60 => FpsLimit::Unlocked,
-            FpsLimit::Unlocked => FpsLimit::Fps30,
-        }
-    }
-
-    /// Cycle to previous value
-    pub fn prev(self) -> Self {
-        match self {
-            FpsLimit::Fps30 => FpsLimit::Unlocked,
-            FpsLimit::Fps60 => FpsLimit::Fps30,
-            FpsLimit::Unlocked => FpsLimit::Fps60,
-        }
-    }
-
-    /// Display name
-    pub fn label(&self) -> &'static str {
-        match self {
-            FpsLimit::Fps30 => "30",
-            FpsLimit::Fps60 => "60",
-            FpsLimit::Unlocked => "Unlocked",
-        }
-    }
-}
-
 /// State for the Test tool (play mode)
 pub struct GameToolState {
     /// ECS world containing all dynamic entities
@@ -170,6 +141,9 @@ pub struct GameToolState {
     /// Show debug overlay (top-right HUD with player stats)
     pub show_debug_overlay: bool,
 
+    /// Draw the navgraph and the paths enemies are walking
+    pub show_nav_graph: bool,
+
     /// Free-fly camera parameters (when in FreeFly mode)
     pub freefly_yaw: f32,
     pub freefly_pitch: f32,
@@ -179,14 +153,89 @@ pub struct GameToolState {
     /// Character mode: camera orbit pitch (elevation)
     pub char_cam_pitch: f32,
 
-    /// FPS limit setting (30/60/Unlocked)
+    /// FPS limit setting (24/30/60/Unlocked), applied engine-wide by the frame pacer
     pub fps_limit: FpsLimit,
 
+    /// Window mode, integer scaling and window placement (engine-wide, saved per user)
+    pub display: DisplaySettings,
+    /// GPU post-process for the game view (see `crate::crt`)
+    pub crt: CrtFilter,
+    /// GPU copy of the game framebuffer (re-uploaded only where it changed)
+    pub frame_texture: FramebufferTexture,
+
+    /// Multiplier for asset LOD switch distances (higher = detail kept further out)
+    pub lod_scale: f32,
+
     /// Frame timing data for performance profiling
     pub frame_timings: FrameTimings,
 
     /// Cached RGB555 textures (lazy-populated, invalidated when texture count changes)
     pub textures_15_cache: Vec<Texture15>,
+
+    /// Flipbook frame key of user textures (cache is cleared when it changes)
+    pub texture_anim_key: u64,
+
+    /// Triggers, doors, pickups and level scripts for the current play session
+    pub logic: LevelLogic,
+
+    /// World systems run every tick, in order
+    pub systems: Schedule,
+
+    /// Opt-in playtest recorder (deaths, damage, time per room)
+    pub analytics: SessionRecorder,
+
+    /// Lock-on target for the character camera
+    pub lock_on: LockOn,
+
+    /// Camera volume shot overriding the character camera, and its blend
+    pub rail_camera: RailCamera,
+
+    /// Rain/snow/ash/dust particles for the level's (or a script's) weather preset
+    pub weather: WeatherState,
+
+    /// Particle emitters of the level's asset instances
+    pub particles: ParticleSystem,
+
+    /// Scratch framebuffer the active mirror's reflection is rendered into
+    pub mirror_fb: Framebuffer,
+
+    /// Volumes set in the options menu (None = the level's project defaults)
+    pub mix_override: Option<MixLevels>,
+
+    /// Level chunks streamed in around the player (levels with `streaming` chunks)
+    pub streamer: ChunkStreamer,
+
+    /// Sound effects the last tick asked for (played by the host)
+    pub sounds: GameSounds,
+
+    /// The level's song on the tracker (the host stops it when play ends)
+    pub music: MusicDirector,
+
+    /// Entity inspector overlay; can pause and frame-step the simulation
+    pub inspector: Inspector,
+
+    /// Walkable sectors of the level, built when play starts
+    pub nav: NavGraph,
+
+    /// Seed mixed into the session's gameplay RNG (patrol wandering; 0 keeps
+    /// the level's own seeds). Replays record it and restore it on playback.
+    pub rng_seed: u32,
+
+    /// Replay recorder/player (input and deltas per tick)
+    pub replay: ReplayState,
+
+    /// The player's weapons and armor: the player start asset's own, plus
+    /// gear picked up this session
+    pub equipment: Equipment,
+
+    /// Inventory screen is open (the player is held still)
+    pub inventory_open: bool,
+
+    /// Highlighted entry of the inventory screen
+    pub inventory_selected: usize,
+
+    /// Seconds Dodge has been held (a short tap rolls, holding sprints)
+    pub dodge_held: f32,
 }
 
 impl GameToolState {
@@ -224,13 +273,39 @@ impl GameToolState {
             options_menu_open: false,
             debug_menu_selection: 0,
             show_debug_overlay: false,
+            show_nav_graph: false,
             freefly_yaw: 0.0,
             freefly_pitch: 0.0,
             char_cam_yaw: 0.0,
             char_cam_pitch: 0.2, // Slight downward pitch by default
             fps_limit: FpsLimit::default(),
+            display: DisplaySettings::load(),
+            crt: CrtFilter::default(),
+            frame_texture: FramebufferTexture::new(),
+            lod_scale: 1.0,
             frame_timings: FrameTimings::default(),
             textures_15_cache: Vec::new(),
+            texture_anim_key: 0,
+            logic: LevelLogic::new(),
+            systems: core_systems(),
+            analytics: SessionRecorder::default(),
+            lock_on: LockOn::default(),
+            rail_camera: RailCamera::default(),
+            weather: WeatherState::new(),
+            particles: ParticleSystem::new(),
+            mirror_fb: Framebuffer::new(0, 0),
+            mix_override: None,
+            streamer: ChunkStreamer::default(),
+            sounds: GameSounds::default(),
+            music: MusicDirector::default(),
+            inspector: Inspector::default(),
+            nav: NavGraph::default(),
+            rng_seed: 0,
+            replay: ReplayState::default(),
+            equipment: Equipment::new(),
+            inventory_open: false,
+            inventory_selected: 0,
+            dodge_held: 0.0,
         }
     }
 
@@ -311,7 +386,8 @@ impl GameToolState {
     }
 
     /// Update camera to follow player in Dark Souls-style orbit view.
-    /// Camera orbits around player independently of player facing.
+    /// Camera orbits around player independently of player facing; inside a
+    /// camera volume its fixed or rail shot takes over (see `rail_camera`).
     /// Returns the player position if player exists.
     pub fn update_camera_follow_player(&mut self, level: &Level) -> Option<Vec3> {
         let player = self.player_entity?;
@@ -322,7 +398,14 @@ impl GameToolState {
         let settings = &level.player_settings;
 
         // Target point: player position + vertical offset (shoulder/chest height)
-        let look_at = player_pos + Vec3::new(0.0, settings.camera_vertical_offset, 0.0);
+        let mut look_at = player_pos + Vec3::new(0.0, settings.camera_vertical_offset, 0.0);
+
+        // Locked on: shift the look-at toward the target so both stay framed
+        // (yaw is turned toward the target by the player input)
+        if let Some(target_pos) = self.lock_on_target_position() {
+            let target_look = target_pos + Vec3::new(0.0, settings.camera_vertical_offset, 0.0);
+            look_at = look_at + (target_look - look_at) * lock_on::FRAMING_BLEND;
+        }
 
         // Calculate camera position using spherical coordinates around player
         // yaw = horizontal rotation, pitch = vertical angle
@@ -340,15 +423,64 @@ impl GameToolState {
             -yaw.cos() * horizontal_dist,
         );
 
-        self.camera.position = look_at + cam_offset;
+        let dt = macroquad::prelude::get_frame_time();
+        let (position, target) = self.rail_camera.update(level, player_pos, look_at, (look_at + cam_offset, look_at), dt);
+        self.point_camera(position, target);
 
-        // Point camera at target
-        let to_target = (look_at - self.camera.position).normalize();
+        Some(player_pos)
+    }
+
+    /// Place the camera at `position`, looking at `target`
+    pub fn point_camera(&mut self, position: Vec3, target: Vec3) {
+        self.camera.position = position;
+        let to_target = (target - position).normalize();
         self.camera.rotation_y = to_target.x.atan2(to_target.z);
         self.camera.rotation_x = (-to_target.y).asin();
         self.camera.update_basis();
+    }
 
-        Some(player_pos)
+    /// Stop the player walking (a cutscene has the controls); falling carries on
+    pub fn hold_player(&mut self) {
+        if let Some(velocity) = self.player_entity.and_then(|p| self.world.velocities.get_mut(p)) {
+            velocity.0.x = 0.0;
+            velocity.0.z = 0.0;
+        }
+    }
+
+    /// Lock onto the nearest targetable entity in view, or release the current lock.
+    /// Returns true if a target is locked afterwards.
+    pub fn toggle_lock_on(&mut self, level: &Level) -> bool {
+        if self.lock_on.is_locked() {
+            self.lock_on.release();
+            return false;
+        }
+        let Some(player_pos) = self.get_player_position() else { return false };
+        let candidates = lock_on::targetable(&self.world);
+        self.lock_on.target = lock_on::pick_target(
+            &candidates,
+            player_pos,
+            self.camera.position,
+            self.camera.basis_z,
+            level.player_settings.lock_on_range,
+        );
+        self.lock_on.is_locked()
+    }
+
+    /// Move the lock to the next target on the left (direction < 0) or right
+    pub fn cycle_lock_on(&mut self, level: &Level, direction: f32) {
+        let (Some(current), Some(player_pos)) = (self.lock_on.target, self.get_player_position()) else {
+            return;
+        };
+        let candidates = lock_on::targetable(&self.world);
+        let range = level.player_settings.lock_on_range;
+        if let Some(next) = lock_on::cycle_target(&candidates, current, player_pos, direction, range) {
+            self.lock_on.target = Some(next);
+        }
+    }
+
+    /// Position of the locked target, if locked and still targetable
+    pub fn lock_on_target_position(&self) -> Option<Vec3> {
+        lock_on::target_position(&self.world, self.lock_on.target?)
     }
 
     /// Get the camera forward direction projected onto XZ plane (for movement)
@@ -374,20 +506,61 @@ impl GameToolState {
         self.playing = !self.playing;
         if !self.playing {
             // Reset ECS world when stopping
+            self.analytics.end_session();
             self.world = World::new();
             self.events = Events::new();
             self.player_entity = None;
+            self.logic = LevelLogic::new();
+            self.lock_on.release();
+            self.rail_camera.reset();
+            self.weather = WeatherState::new();
+            self.particles = ParticleSystem::new();
+            self.streamer.clear();
+            self.sounds.reset();
+            self.inspector.selected = None;
+            self.nav = NavGraph::default();
+            self.replay.stop();
         }
     }
 
     /// Reset the game state (clear entities, respawn player)
     pub fn reset(&mut self) {
+        self.analytics.end_session();
         self.world = World::new();
         self.events = Events::new();
         self.player_entity = None;
+        self.logic = LevelLogic::new();
+        self.lock_on.release();
+        self.rail_camera.reset();
+        self.weather = WeatherState::new();
+        self.particles = ParticleSystem::new();
+        self.streamer.clear();
+        self.sounds.reset();
+        self.inspector.selected = None;
+        self.nav = NavGraph::default();
+        self.replay.stop();
         self.playing = false;
     }
 
+    /// Restart the session and record it from the first tick
+    pub fn start_recording(&mut self) {
+        self.reset();
+        self.replay.start_recording(self.rng_seed, (self.char_cam_yaw, self.char_cam_pitch));
+    }
+
+    /// Restart the session and play `replay` back from the first tick
+    pub fn start_replay(&mut self, replay: Replay) {
+        self.reset();
+        self.rng_seed = replay.seed;
+        (self.char_cam_yaw, self.char_cam_pitch) = replay.camera;
+        self.replay.start_playback(replay);
+    }
+
+    /// Group volumes to play with: the options menu's, else the level's defaults
+    pub fn mix_levels(&self, level: &Level) -> MixLevels {
+        self.mix_override.unwrap_or(level.audio_mix)
+    }
+
     /// Full reset for loading a new level (resets entities, camera, and texture cache)
     pub fn reset_for_new_level(&mut self) {
         self.reset();
@@ -399,6 +572,43 @@ impl GameToolState {
     pub fn spawn_player(&mut self, position: Vec3, level: &Level) {
         let player = self.world.spawn_player(position, 100, &level.player_settings);
         self.player_entity = Some(player);
+        self.apply_equipment();
+    }
+
+    /// Set up triggers, doors, pickups, enemies, scripts, particle emitters, the
+    /// player's equipment and the navgraph for this play session
+    pub fn start_level_logic(&mut self, level: &Level, asset_library: &crate::asset::AssetLibrary) {
+        self.nav = NavGraph::build(level);
+        self.logic = LevelLogic::start(level, asset_library, &mut self.world);
+        self.particles = ParticleSystem::start(level, asset_library);
+
+        // The player starts with the Weapon and Armor on the player start's asset
+        self.equipment = Equipment::new();
+        self.inventory_open = false;
+        self.inventory_selected = 0;
+        if let Some(asset) = level.get_player_start(asset_library).and_then(|(_, spawn)| asset_library.get_by_id(spawn.asset_id)) {
+            for component in &asset.components {
+                if let Some(gear) = Gear::from_component(&asset.name, component) {
+                    self.equipment.add(gear);
+                }
+            }
+        }
+        self.apply_equipment();
+
+        // Vary the patrols' wandering with the session seed
+        let seed = self.rng_seed;
+        if seed != 0 {
+            for (_, patrol) in self.world.patrols.iter_mut() {
+                patrol.seed = (patrol.seed ^ seed).max(1);
+            }
+        }
+    }
+
+    /// Give the player's `Combat` the equipped weapon and armor
+    pub fn apply_equipment(&mut self) {
+        if let Some(combat) = self.player_entity.and_then(|e| self.world.combat.get_mut(e)) {
+            self.equipment.apply(combat);
+        }
     }
 
     /// Run one frame of game simulation
@@ -406,80 +616,93 @@ impl GameToolState {
         if !self.playing {
             return;
         }
+        // The inspector can hold the world still or step it a frame at a time
+        let Some(delta_time) = self.inspector.frame_delta(delta_time) else {
+            return;
+        };
+        // A replay being played back supplies its recorded delta
+        let delta_time = self.replay.tick_delta(delta_time);
 
         // =====================================================================
-        // Character Controller System: Apply gravity and collision
+        // World systems: platforms, controllers, movement, paths, patrols, combat, transforms, health, stamina, status effects (see `core_systems`)
         // =====================================================================
-        // Collect entities with controllers to avoid borrow issues
-        let controller_entities: Vec<(u32, super::components::CharacterController)> = self.world.controllers
-            .iter()
-            .map(|(idx, ctrl)| (idx, *ctrl))
-            .collect();
-
-        for (idx, mut controller) in controller_entities {
-            let entity = Entity::new(idx, 0);
-
-            // Get current position and velocity
-            let position = self.world.transforms.get(entity)
-                .map(|t| t.position)
-                .unwrap_or(Vec3::ZERO);
-            let velocity = self.world.velocities.get(entity)
-                .map(|v| v.0)
-                .unwrap_or(Vec3::ZERO);
-
-            // Apply collision and movement
-            let new_pos = super::collision::move_and_slide(
-                level,
-                position,
-                velocity,
-                &mut controller,
-                delta_time,
-            );
+        // Closed (or still moving) doors block characters
+        let door_blockers = self.logic.door_blockers(&self.world);
+        self.systems.run(&mut SystemContext {
+            world: &mut self.world,
+            events: &mut self.events,
+            level,
+            nav: &self.nav,
+            blockers: &door_blockers,
+            delta: delta_time,
+        });
 
-            // Update transform
-            if let Some(transform) = self.world.transforms.get_mut(entity) {
-                transform.position = new_pos;
+        // =====================================================================
+        // Level Logic: triggers, pickups, doors, script timers and actions
+        // =====================================================================
+        // The schedule times each system itself; the rest of the tick is
+        // profiled phase by phase
+        let mut phase = crate::profiler::scope(crate::profiler::Subsystem::Tick, "level logic");
+        let player = self.player_entity
+            .and_then(|e| self.world.transforms.get(e).map(|t| (e, t.position)));
+        self.logic.update(&mut self.world, &mut self.events, player, delta_time);
+        let found = self.logic.take_gear();
+        if !found.is_empty() {
+            for gear in found {
+                self.equipment.add(gear);
             }
-
-            // Update controller state
-            self.world.controllers.insert(entity, controller);
+            self.apply_equipment();
         }
 
         // =====================================================================
-        // Simple Movement System: Apply velocity (for entities without controllers)
+        // Weather: scripts can override the level's preset
         // =====================================================================
-        for (idx, velocity) in self.world.velocities.iter() {
-            let entity = Entity::new(idx, 0);
-            // Skip entities with controllers (already handled above)
-            if self.world.controllers.contains(entity) {
-                continue;
-            }
-            if let Some(transform) = self.world.transforms.get_mut(entity) {
-                transform.position = transform.position + velocity.0 * delta_time;
-            }
-        }
+        phase.next("weather");
+        let preset = self.logic.weather_override().unwrap_or(level.weather.preset);
+        self.weather.update(level, preset, &mut self.events, delta_time);
 
         // =====================================================================
-        // Update global transforms (for rendering)
+        // Particles: emitters of collected or script-disabled instances stop
+        // spawning; broken props burst into debris
         // =====================================================================
-        for (idx, transform) in self.world.transforms.iter() {
-            let entity = Entity::new(idx, 0);
-            let global = super::GlobalTransform::from_transform(transform);
-            self.world.global_transforms.insert(entity, global);
+        phase.next("particles");
+        for (def, position) in self.logic.take_debris() {
+            self.particles.burst(def, position);
         }
+        self.particles.update(delta_time, &self.logic.hidden_objects());
 
         // =====================================================================
-        // Health System: Tick invincibility frames
+        // Analytics: record this frame's player position, damage and deaths
+        // =====================================================================
+        phase.next("analytics");
+        self.analytics.record(level, &self.world, &self.events, player, delta_time);
+
         // =====================================================================
-        for (_idx, health) in self.world.health.iter_mut() {
-            health.tick_invincibility();
+        // Lock-On: release targets that died, despawned or left the range
+        // =====================================================================
+        if let Some((_, player_pos)) = player {
+            self.lock_on.validate(&self.world, player_pos, level.player_settings.lock_on_range);
         }
 
-        // Process pending despawns
+        // =====================================================================
+        // Sounds: script/weather requests, bound gameplay events, footsteps
+        // =====================================================================
+        phase.next("sounds");
+        let listener = super::spatial::Listener::from_camera(&self.camera, level);
+        self.sounds.collect(&level.sounds, &self.world, &self.events, self.player_entity, &listener);
+        self.sounds.fade_reverb(&listener, delta_time);
+
+        // Process pending despawns (the inspector lets go of despawned entities)
+        phase.next("despawns");
         self.world.flush_despawns();
+        self.inspector.validate(&self.world);
 
         // Clear events for next frame
         self.events.clear_all();
+
+        // Replays store (or check) where the player ended up
+        let player_pos = self.get_player_position();
+        self.replay.after_tick(player_pos);
     }
 }
 
@@ -488,3 +711,173 @@ impl Default for GameToolState {
         Self::new()
     }
 }
+
+// =============================================================================
+// World Systems
+// =============================================================================
+
+/// The systems every tick runs, in order. New gameplay systems slot in with
+/// `systems.add_after(...)`; level logic and weather run after the schedule.
+pub fn core_systems() -> Schedule {
+    let mut schedule = Schedule::new();
+    schedule
+        .add("moving_platforms", super::platform::moving_platforms)
+        .add("character_controllers", character_controllers)
+        .add("movement", movement)
+        .add("follow_paths", follow_paths)
+        .add("patrols", patrols)
+        .add("combat", super::combat::combat)
+        .add("global_transforms", global_transforms)
+        .add("invincibility", invincibility)
+        .add("stamina", stamina)
+        .add("status_effects", status_effects);
+    schedule
+}
+
+/// Gravity and TR-style cylinder collision for characters; closed doors block
+/// them, and moving platform decks hold them up
+fn character_controllers(ctx: &mut SystemContext<'_>) {
+    let (level, blockers, delta) = (ctx.level, ctx.blockers, ctx.delta);
+    let decks = super::platform::decks(ctx.world);
+    let solids: Vec<(Vec3, Vec3)> = blockers.iter().chain(&decks).copied().collect();
+    let events = &mut *ctx.events;
+    let characters = ctx.world.query::<(&mut Transform, Option<&Velocity>, &mut CharacterController, Option<&mut Health>)>();
+    for (entity, (transform, velocity, controller, health)) in characters {
+        let position = transform.position;
+        let velocity = velocity.map(|v| v.0).unwrap_or(Vec3::ZERO);
+        if controller.climbing.is_some() {
+            // On a ladder: velocity.y is the climbing speed, no gravity or wall sliding
+            let (new_pos, action) = super::climb::climb_step(level, position, velocity.y, controller, delta);
+            transform.position = new_pos;
+            if let Some(action) = action {
+                events.climb.send(ClimbEvent { entity, action, position: new_pos });
+            }
+            continue;
+        }
+        if let Some(action) = super::swim::update_water(level, position, controller) {
+            events.swim.send(SwimEvent { entity, action, position });
+        }
+        if controller.swimming.is_some() {
+            // In water: velocity.y is rising/diving, no gravity; breath runs out underwater
+            let (swim_pos, action, drown_damage) = super::swim::swim_step(level, position, velocity, controller, delta);
+            let new_pos = if blockers.is_empty() {
+                swim_pos
+            } else {
+                super::collision::block_by_boxes(position, swim_pos, controller.radius, controller.height, blockers)
+            };
+            transform.position = new_pos;
+            if let Some(action) = action {
+                events.swim.send(SwimEvent { entity, action, position: new_pos });
+            }
+            if let (Some(health), true) = (health, drown_damage > 0) {
+                health.damage(drown_damage);
+                events.damage.send(DamageEvent { target: entity, source: None, amount: drown_damage, position: new_pos });
+            }
+            continue;
+        }
+        let mut new_pos = super::collision::move_and_slide(level, position, velocity, controller, delta);
+        if let Some(top) = super::platform::land_on_deck(position, new_pos, controller.step_height, &decks) {
+            new_pos.y = top;
+            controller.grounded = true;
+            controller.vertical_velocity = 0.0;
+        }
+        transform.position = if solids.is_empty() {
+            new_pos
+        } else {
+            super::collision::block_by_boxes(position, new_pos, controller.radius, controller.height, &solids)
+        };
+    }
+}
+
+/// Apply velocity to entities without a character controller
+fn movement(ctx: &mut SystemContext<'_>) {
+    let delta = ctx.delta;
+    for (_, (transform, velocity, controller)) in ctx.world.query::<(&mut Transform, &Velocity, Option<&CharacterController>)>() {
+        if controller.is_none() {
+            transform.position = transform.position + velocity.0 * delta;
+        }
+    }
+}
+
+/// Move path followers along their waypoints; enemies turn to face where they're going
+fn follow_paths(ctx: &mut SystemContext<'_>) {
+    let delta = ctx.delta;
+    for (_, (transform, follower)) in ctx.world.query::<(&mut Transform, &mut PathFollower)>() {
+        follower.travelled += follower.speed * delta;
+        let Some((position, direction)) = crate::world::point_along_path(&follower.points, follower.mode, follower.travelled) else {
+            continue;
+        };
+        transform.position = position;
+        if follower.face_travel && (direction.x != 0.0 || direction.z != 0.0) {
+            // Same convention as instance facing: 0 = +Z
+            transform.rotation.y = (-direction.x).atan2(direction.z).to_degrees();
+        }
+    }
+}
+
+/// Walk patrolling enemies along A* paths over the navgraph to random spots
+/// around their home, resting between walks (status effects can slow them)
+fn patrols(ctx: &mut SystemContext<'_>) {
+    let (graph, delta) = (ctx.nav, ctx.delta);
+    for (_, (transform, patrol, status, combat)) in ctx.world.query::<(&mut Transform, &mut Patrol, Option<&StatusEffects>, Option<&Combat>)>() {
+        // Enemies stand their ground while swinging or staggered
+        if combat.is_some_and(|c| c.busy()) {
+            continue;
+        }
+        let lift = Vec3::new(0.0, patrol.lift, 0.0);
+        if patrol.path.is_empty() {
+            patrol.wait -= delta;
+            if patrol.wait > 0.0 {
+                continue;
+            }
+            patrol.wait = nav::PATROL_REST;
+            let spot = patrol.next_spot();
+            // Unreachable spots are skipped; another is picked after the rest
+            patrol.path = graph.find_path(transform.position - lift, spot).unwrap_or_default();
+            continue;
+        }
+        let speed = patrol.speed * status.map_or(1.0, |s| s.speed_multiplier());
+        let (feet, direction) = nav::walk_path(&mut patrol.path, transform.position - lift, speed * delta);
+        transform.position = feet + lift;
+        if direction.x != 0.0 || direction.z != 0.0 {
+            transform.rotation.y = (-direction.x).atan2(direction.z).to_degrees();
+        }
+    }
+}
+
+/// Update global transforms (for rendering)
+fn global_transforms(ctx: &mut SystemContext<'_>) {
+    for (_, (transform, global)) in ctx.world.query::<(&Transform, &mut GlobalTransform)>() {
+        *global = GlobalTransform::from_transform(transform);
+    }
+}
+
+/// Tick invincibility frames
+fn invincibility(ctx: &mut SystemContext<'_>) {
+    for (_, health) in ctx.world.query::<&mut Health>() {
+        health.tick_invincibility();
+    }
+}
+
+/// Refill stamina after its pause
+fn stamina(ctx: &mut SystemContext<'_>) {
+    let delta = ctx.delta;
+    for (_, stamina) in ctx.world.query::<&mut Stamina>() {
+        stamina.regenerate(delta);
+    }
+}
+
+/// Tick status effects: damage or heal over time, expire, drain buildup
+fn status_effects(ctx: &mut SystemContext<'_>) {
+    let delta = ctx.delta;
+    let mut changes = Vec::new();
+    for (entity, effects) in ctx.world.query::<&mut StatusEffects>() {
+        let amount = effects.update(delta);
+        if amount != 0 {
+            changes.push((entity, amount));
+        }
+    }
+    for (entity, amount) in changes {
+        status::hurt(ctx.world, ctx.events, entity, amount);
+    }
+}
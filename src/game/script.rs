@@ -0,0 +1,960 @@
+//! Level Scripting
+//!
+//! A small event-driven language for level logic. Scripts are stored as source
+//! text inside the level (`world::LevelScript`), edited in the World Editor's
+//! Scripts panel, and compiled when the Test tab starts playing.
+//!
+//! ```text
+//! # Open the vault when the player walks in
+//! on enter vault_trigger
+//!     open_door vault_door
+//!     sound chime
+//!     message "The vault creaks open"
+//! end
+//!
+//! on pickup gem
+//!     set gems = gems + 1
+//!     if gems >= 3
+//!         open_door gate
+//!     else
+//!         message "Find more gems"
+//!     end
+//! end
+//!
+//! on timer 2.5 repeat
+//!     toggle_door piston
+//! end
+//! ```
+//!
+//! Events:
+//! - `on start` - when play begins
+//! - `on enter <trigger_id>` / `on exit <trigger_id>` - player enters/leaves a Trigger
+//! - `on event <name>` - a Trigger's on_enter/on_exit event name
+//! - `on pickup <name>` - player collects a Pickup (matched by instance name)
+//! - `on door_open <name>` / `on door_close <name>` - a Door starts opening/closing
+//! - `on locked <name>` - player tried a locked Door without its key
+//! - `on break <name>` - player broke a Destructible prop
+//! - `on timer <seconds> [repeat]` - after a delay, optionally repeating
+//! - `on cutscene_end <name>` - a cutscene finished or was skipped
+//! - `on dialogue_end <name>` - a conversation ended (instance name of the NPC)
+//!
+//! Commands:
+//! - `open_door <name>`, `close_door <name>`, `toggle_door <name>`
+//! - `enable <name>`, `disable <name>` - show/hide an instance (disabled triggers and pickups do nothing)
+//! - `sound <name>`, `message "<text>"`
+//! - `weather <rain|snow|ash|dust|off>` - change the level's weather preset
+//! - `prompt <action> "<text>"` - show a button prompt ("[A] Jump") until the
+//!   player performs the action; `clear_prompt <action>` removes it early
+//! - `tutorial <id> "<text>"` - one-time hint popup, dismissed with Interact;
+//!   each id shows once per play session
+//! - `cutscene <name>` - play one of the level's cutscenes (see `cutscene`)
+//! - `music play|jump|mute|unmute|stop ... [now|beat|bar|pattern]` - change
+//!   the level's music on the beat (see `music`)
+//! - `status <name>` - give the player a status effect (a preset or a
+//!   StatusEffect asset's name, see `status`); `cure <name>` ends it
+//! - `set <var> = <expr>` - variables are numbers, start at 0, shared by all scripts in the level
+//! - `if <expr> [<op> <expr>] ... [else ...] end` - ops: `== != < <= > >=`, no op means "not zero"
+//! - `stop` - end the handler early
+//!
+//! Actions are named in snake_case: `jump`, `dodge`, `attack`, `strong_attack`,
+//! `skill`, `guard`, `use_item`, `interact`, `crouch`, `lock_on`, `switch_left`,
+//! `switch_right`, `switch_spell`, `switch_item`, `menu`, `map`, `inventory`.
+//!
+//! Names can be quoted to include spaces: `open_door "north gate"`.
+//! Scripts never touch the world directly: they queue `ScriptAction`s that the
+//! game runtime applies (see `level_logic`).
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::music::{parse_music, MusicCommand, MusicRequest};
+use crate::input::Action;
+use crate::world::{LevelScript, WeatherPreset};
+
+/// A world event that can run script handlers
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptEvent {
+    /// Play started
+    Start,
+    /// Player entered a trigger (trigger_id)
+    Enter(String),
+    /// Player left a trigger (trigger_id)
+    Exit(String),
+    /// Named event fired by a trigger's on_enter/on_exit
+    Custom(String),
+    /// Player collected a pickup (instance name)
+    Pickup(String),
+    /// A door started opening (instance name)
+    DoorOpen(String),
+    /// A door started closing (instance name)
+    DoorClose(String),
+    /// Player tried a locked door without its key (instance name)
+    Locked(String),
+    /// Player broke a destructible prop (instance name)
+    Break(String),
+    /// A cutscene finished or was skipped (cutscene name)
+    CutsceneEnd(String),
+    /// A conversation ended (instance name of the speaker)
+    DialogueEnd(String),
+}
+
+/// A command produced by a script for the game runtime to apply
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptAction {
+    OpenDoor(String),
+    CloseDoor(String),
+    ToggleDoor(String),
+    Enable(String),
+    Disable(String),
+    PlaySound(String),
+    Message(String),
+    SetWeather(WeatherPreset),
+    ShowPrompt { action: Action, text: String },
+    ClearPrompt(Action),
+    Tutorial { id: String, text: String },
+    PlayCutscene(String),
+    Music(MusicRequest),
+    ApplyStatus(String),
+    CureStatus(String),
+}
+
+/// A compile error with its location
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    /// Script name
+    pub script: String,
+    /// 1-based source line
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.script, self.line, self.message)
+    }
+}
+
+// =============================================================================
+// Syntax tree
+// =============================================================================
+
+/// What runs a handler
+#[derive(Debug, Clone, PartialEq)]
+enum Trigger {
+    Start,
+    Enter(String),
+    Exit(String),
+    Custom(String),
+    Pickup(String),
+    DoorOpen(String),
+    DoorClose(String),
+    Locked(String),
+    Break(String),
+    CutsceneEnd(String),
+    DialogueEnd(String),
+    Timer { seconds: f32, repeat: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f32),
+    Var(String),
+    Neg(Box<Expr>),
+    Binary(Box<Expr>, BinOp, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Stmt {
+    Emit(ScriptAction),
+    Set(String, Expr),
+    If {
+        lhs: Expr,
+        cmp: CmpOp,
+        rhs: Expr,
+        then_body: Vec<Stmt>,
+        else_body: Vec<Stmt>,
+    },
+    Stop,
+}
+
+#[derive(Debug, Clone)]
+struct Handler {
+    trigger: Trigger,
+    body: Vec<Stmt>,
+}
+
+// =============================================================================
+// Lexer
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Token {
+    /// Identifier or keyword
+    Word(String),
+    /// Number (original text kept so it can also be used as a name)
+    Number(f32, String),
+    /// Quoted string
+    Str(String),
+    /// Operator or parenthesis
+    Op(&'static str),
+}
+
+const OPERATORS: [&str; 12] = ["==", "!=", "<=", ">=", "<", ">", "=", "+", "-", "*", "/", "("];
+
+pub(super) fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().peekable();
+
+    while let Some(&(i, ch)) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+        } else if ch == '#' {
+            break;
+        } else if ch == '"' {
+            chars.next();
+            let mut text = String::new();
+            let mut closed = false;
+            for (_, c) in chars.by_ref() {
+                if c == '"' {
+                    closed = true;
+                    break;
+                }
+                text.push(c);
+            }
+            if !closed {
+                return Err("unterminated string".to_string());
+            }
+            tokens.push(Token::Str(text));
+        } else if ch.is_alphanumeric() || ch == '_' || ch == '.' {
+            let mut end = i;
+            while let Some(&(j, c)) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' || c == '.' {
+                    end = j + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let word = &line[i..end];
+            match word.parse::<f32>() {
+                Ok(n) if word.starts_with(|c: char| c.is_ascii_digit() || c == '.') => {
+                    tokens.push(Token::Number(n, word.to_string()))
+                }
+                _ => tokens.push(Token::Word(word.to_string())),
+            }
+        } else if ch == ')' {
+            chars.next();
+            tokens.push(Token::Op(")"));
+        } else if let Some(op) = OPERATORS.iter().find(|op| line[i..].starts_with(**op)) {
+            for _ in 0..op.len() {
+                chars.next();
+            }
+            tokens.push(Token::Op(*op));
+        } else {
+            return Err(format!("unexpected character '{}'", ch));
+        }
+    }
+    Ok(tokens)
+}
+
+// =============================================================================
+// Parser
+// =============================================================================
+
+/// Token cursor for one source line
+pub(super) struct LineParser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> LineParser<'a> {
+    pub(super) fn new(tokens: &'a [Token]) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    pub(super) fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos)
+    }
+
+    pub(super) fn advance(&mut self) -> Option<&'a Token> {
+        let t = self.tokens.get(self.pos);
+        self.pos += 1;
+        t
+    }
+
+    pub(super) fn eat_op(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Op(o)) if *o == op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(super) fn expect_end(&self) -> Result<(), String> {
+        match self.peek() {
+            None => Ok(()),
+            Some(t) => Err(format!("unexpected {}", describe(t))),
+        }
+    }
+
+    /// A name: bare word, quoted string, or number
+    pub(super) fn name(&mut self, what: &str) -> Result<String, String> {
+        match self.advance() {
+            Some(Token::Word(w)) | Some(Token::Str(w)) | Some(Token::Number(_, w)) => Ok(w.clone()),
+            _ => Err(format!("expected {}", what)),
+        }
+    }
+
+    pub(super) fn number(&mut self, what: &str) -> Result<f32, String> {
+        match self.advance() {
+            Some(Token::Number(n, _)) => Ok(*n),
+            _ => Err(format!("expected {}", what)),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = if self.eat_op("+") {
+                BinOp::Add
+            } else if self.eat_op("-") {
+                BinOp::Sub
+            } else {
+                return Ok(lhs);
+            };
+            let rhs = self.term()?;
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.factor()?;
+        loop {
+            let op = if self.eat_op("*") {
+                BinOp::Mul
+            } else if self.eat_op("/") {
+                BinOp::Div
+            } else {
+                return Ok(lhs);
+            };
+            let rhs = self.factor()?;
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        }
+    }
+
+    fn factor(&mut self) -> Result<Expr, String> {
+        if self.eat_op("-") {
+            return Ok(Expr::Neg(Box::new(self.factor()?)));
+        }
+        if self.eat_op("(") {
+            let inner = self.expr()?;
+            if !self.eat_op(")") {
+                return Err("expected ')'".to_string());
+            }
+            return Ok(inner);
+        }
+        match self.advance() {
+            Some(Token::Number(n, _)) => Ok(Expr::Number(*n)),
+            Some(Token::Word(w)) if !is_keyword(w) => Ok(Expr::Var(w.clone())),
+            Some(t) => Err(format!("expected a value, found {}", describe(t))),
+            None => Err("expected a value".to_string()),
+        }
+    }
+
+    fn cmp_op(&mut self) -> Option<CmpOp> {
+        let op = match self.peek()? {
+            Token::Op("==") => CmpOp::Eq,
+            Token::Op("!=") => CmpOp::Ne,
+            Token::Op("<") => CmpOp::Lt,
+            Token::Op("<=") => CmpOp::Le,
+            Token::Op(">") => CmpOp::Gt,
+            Token::Op(">=") => CmpOp::Ge,
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(op)
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    matches!(word, "on" | "end" | "else" | "if" | "set" | "stop")
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Word(w) => format!("'{}'", w),
+        Token::Number(_, s) => format!("'{}'", s),
+        Token::Str(s) => format!("\"{}\"", s),
+        Token::Op(op) => format!("'{}'", op),
+    }
+}
+
+/// One non-empty source line
+struct Line {
+    number: usize,
+    tokens: Vec<Token>,
+}
+
+impl Line {
+    fn keyword(&self) -> Option<&str> {
+        match self.tokens.first() {
+            Some(Token::Word(w)) => Some(w.as_str()),
+            _ => None,
+        }
+    }
+}
+
+type ParseResult<T> = Result<T, (usize, String)>;
+
+fn parse(source: &str) -> ParseResult<Vec<Handler>> {
+    let mut lines = Vec::new();
+    for (i, text) in source.lines().enumerate() {
+        let tokens = tokenize(text).map_err(|e| (i + 1, e))?;
+        if !tokens.is_empty() {
+            lines.push(Line { number: i + 1, tokens });
+        }
+    }
+
+    let mut handlers = Vec::new();
+    let mut pos = 0;
+    while pos < lines.len() {
+        let line = &lines[pos];
+        if line.keyword() != Some("on") {
+            return Err((line.number, "expected 'on <event>'".to_string()));
+        }
+        let trigger = parse_trigger(line).map_err(|e| (line.number, e))?;
+        let start_line = line.number;
+        pos += 1;
+        let (body, _) = parse_block(&lines, &mut pos, &["end"], start_line)?;
+        handlers.push(Handler { trigger, body });
+    }
+    Ok(handlers)
+}
+
+fn parse_trigger(line: &Line) -> Result<Trigger, String> {
+    let mut p = LineParser::new(&line.tokens[1..]);
+    let kind = match p.advance() {
+        Some(Token::Word(w)) => w.as_str(),
+        _ => return Err("expected event after 'on'".to_string()),
+    };
+    let trigger = match kind {
+        "start" => Trigger::Start,
+        "enter" => Trigger::Enter(p.name("trigger id")?),
+        "exit" => Trigger::Exit(p.name("trigger id")?),
+        "event" => Trigger::Custom(p.name("event name")?),
+        "pickup" => Trigger::Pickup(p.name("pickup name")?),
+        "door_open" => Trigger::DoorOpen(p.name("door name")?),
+        "door_close" => Trigger::DoorClose(p.name("door name")?),
+        "locked" => Trigger::Locked(p.name("door name")?),
+        "break" => Trigger::Break(p.name("prop name")?),
+        "cutscene_end" => Trigger::CutsceneEnd(p.name("cutscene name")?),
+        "dialogue_end" => Trigger::DialogueEnd(p.name("speaker name")?),
+        "timer" => {
+            let seconds = p.number("timer seconds")?;
+            if seconds <= 0.0 {
+                return Err("timer must be longer than 0 seconds".to_string());
+            }
+            let repeat = match p.peek() {
+                Some(Token::Word(w)) if w == "repeat" => {
+                    p.advance();
+                    true
+                }
+                _ => false,
+            };
+            Trigger::Timer { seconds, repeat }
+        }
+        other => return Err(format!("unknown event '{}'", other)),
+    };
+    p.expect_end()?;
+    Ok(trigger)
+}
+
+/// Parse statements until one of `terminators`; returns the body and the terminator found
+fn parse_block(
+    lines: &[Line],
+    pos: &mut usize,
+    terminators: &[&'static str],
+    opened_at: usize,
+) -> ParseResult<(Vec<Stmt>, &'static str)> {
+    let mut body = Vec::new();
+    while let Some(line) = lines.get(*pos) {
+        *pos += 1;
+        if let Some(term) = terminators.iter().find(|t| line.keyword() == Some(**t)) {
+            if line.tokens.len() > 1 {
+                return Err((line.number, format!("unexpected text after '{}'", term)));
+            }
+            return Ok((body, *term));
+        }
+        body.push(parse_stmt(lines, pos, line)?);
+    }
+    Err((opened_at, "missing 'end'".to_string()))
+}
+
+fn parse_stmt(lines: &[Line], pos: &mut usize, line: &Line) -> ParseResult<Stmt> {
+    let err = |e: String| (line.number, e);
+    let Some(keyword) = line.keyword() else {
+        return Err(err("expected a command".to_string()));
+    };
+    let mut p = LineParser::new(&line.tokens[1..]);
+
+    let stmt = match keyword {
+        "open_door" => Stmt::Emit(ScriptAction::OpenDoor(p.name("door name").map_err(err)?)),
+        "close_door" => Stmt::Emit(ScriptAction::CloseDoor(p.name("door name").map_err(err)?)),
+        "toggle_door" => Stmt::Emit(ScriptAction::ToggleDoor(p.name("door name").map_err(err)?)),
+        "enable" => Stmt::Emit(ScriptAction::Enable(p.name("instance name").map_err(err)?)),
+        "disable" => Stmt::Emit(ScriptAction::Disable(p.name("instance name").map_err(err)?)),
+        "sound" => Stmt::Emit(ScriptAction::PlaySound(p.name("sound name").map_err(err)?)),
+        "message" => Stmt::Emit(ScriptAction::Message(p.name("message text").map_err(err)?)),
+        "weather" => {
+            let name = p.name("weather preset").map_err(err)?;
+            let preset = WeatherPreset::from_name(&name)
+                .ok_or_else(|| err(format!("unknown weather '{}' (rain, snow, ash, dust, off)", name)))?;
+            Stmt::Emit(ScriptAction::SetWeather(preset))
+        }
+        "prompt" => {
+            let action = parse_action(&mut p).map_err(err)?;
+            let text = p.name("prompt text").map_err(err)?;
+            Stmt::Emit(ScriptAction::ShowPrompt { action, text })
+        }
+        "clear_prompt" => Stmt::Emit(ScriptAction::ClearPrompt(parse_action(&mut p).map_err(err)?)),
+        "tutorial" => {
+            let id = p.name("tutorial id").map_err(err)?;
+            let text = p.name("tutorial text").map_err(err)?;
+            Stmt::Emit(ScriptAction::Tutorial { id, text })
+        }
+        "cutscene" => Stmt::Emit(ScriptAction::PlayCutscene(p.name("cutscene name").map_err(err)?)),
+        "music" => Stmt::Emit(ScriptAction::Music(parse_music(&mut p).map_err(err)?)),
+        "status" => Stmt::Emit(ScriptAction::ApplyStatus(p.name("status effect name").map_err(err)?)),
+        "cure" => Stmt::Emit(ScriptAction::CureStatus(p.name("status effect name").map_err(err)?)),
+        "stop" => Stmt::Stop,
+        "set" => {
+            let var = match p.advance() {
+                Some(Token::Word(w)) if !is_keyword(w) => w.clone(),
+                _ => return Err(err("expected variable name".to_string())),
+            };
+            if !p.eat_op("=") {
+                return Err(err("expected '=' after variable".to_string()));
+            }
+            Stmt::Set(var, p.expr().map_err(err)?)
+        }
+        "if" => {
+            let lhs = p.expr().map_err(err)?;
+            let (cmp, rhs) = match p.cmp_op() {
+                Some(cmp) => (cmp, p.expr().map_err(err)?),
+                None => (CmpOp::Ne, Expr::Number(0.0)),
+            };
+            p.expect_end().map_err(err)?;
+            let (then_body, term) = parse_block(lines, pos, &["else", "end"], line.number)?;
+            let else_body = if term == "else" {
+                parse_block(lines, pos, &["end"], line.number)?.0
+            } else {
+                Vec::new()
+            };
+            return Ok(Stmt::If { lhs, cmp, rhs, then_body, else_body });
+        }
+        "on" => return Err(err("missing 'end' before next 'on'".to_string())),
+        other => return Err(err(format!("unknown command '{}'", other))),
+    };
+    p.expect_end().map_err(err)?;
+    Ok(stmt)
+}
+
+fn parse_action(p: &mut LineParser<'_>) -> Result<Action, String> {
+    let name = p.name("action name")?;
+    Action::from_name(&name).ok_or_else(|| format!("unknown action '{}' (jump, attack, interact, ...)", name))
+}
+
+/// Check a script for errors without running it. Returns the number of handlers.
+pub fn validate(source: &str) -> Result<usize, (usize, String)> {
+    parse(source).map(|h| h.len())
+}
+
+/// Songs a script's `music play` commands name (for project dependencies)
+pub fn music_songs(source: &str) -> Vec<String> {
+    fn collect(body: &[Stmt], songs: &mut Vec<String>) {
+        for stmt in body {
+            match stmt {
+                Stmt::Emit(ScriptAction::Music(MusicRequest { command: MusicCommand::Play { song, .. }, .. })) => {
+                    songs.push(song.clone());
+                }
+                Stmt::If { then_body, else_body, .. } => {
+                    collect(then_body, songs);
+                    collect(else_body, songs);
+                }
+                _ => {}
+            }
+        }
+    }
+    let mut songs = Vec::new();
+    for handler in parse(source).unwrap_or_default() {
+        collect(&handler.body, &mut songs);
+    }
+    songs
+}
+
+// =============================================================================
+// Runtime
+// =============================================================================
+
+/// Pending timer for a `on timer` handler
+#[derive(Debug, Clone)]
+struct TimerState {
+    handler: usize,
+    remaining: f32,
+}
+
+/// Compiled scripts plus their shared variables and timers
+#[derive(Debug, Clone, Default)]
+pub struct ScriptRuntime {
+    handlers: Vec<Handler>,
+    vars: HashMap<String, f32>,
+    timers: Vec<TimerState>,
+    actions: Vec<ScriptAction>,
+}
+
+impl ScriptRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compile all enabled scripts. Scripts with errors are skipped; the rest still run.
+    pub fn compile(scripts: &[LevelScript]) -> (Self, Vec<ScriptError>) {
+        let mut runtime = Self::new();
+        let mut errors = Vec::new();
+
+        for script in scripts.iter().filter(|s| s.enabled) {
+            match parse(&script.source) {
+                Ok(handlers) => runtime.handlers.extend(handlers),
+                Err((line, message)) => errors.push(ScriptError {
+                    script: script.name.clone(),
+                    line,
+                    message,
+                }),
+            }
+        }
+
+        runtime.timers = runtime.handlers.iter().enumerate()
+            .filter_map(|(i, h)| match h.trigger {
+                Trigger::Timer { seconds, .. } => Some(TimerState { handler: i, remaining: seconds }),
+                _ => None,
+            })
+            .collect();
+
+        (runtime, errors)
+    }
+
+    /// Number of compiled handlers
+    pub fn handler_count(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Current value of a variable (unset variables are 0)
+    pub fn var(&self, name: &str) -> f32 {
+        self.vars.get(name).copied().unwrap_or(0.0)
+    }
+
+    /// Set a variable (dialogue effects share the scripts' variables)
+    pub fn set_var(&mut self, name: &str, value: f32) {
+        self.vars.insert(name.to_string(), value);
+    }
+
+    /// Run every handler listening for `event`
+    pub fn fire(&mut self, event: &ScriptEvent) {
+        let matching: Vec<usize> = self.handlers.iter().enumerate()
+            .filter(|(_, h)| match (&h.trigger, event) {
+                (Trigger::Start, ScriptEvent::Start) => true,
+                (Trigger::Enter(a), ScriptEvent::Enter(b))
+                | (Trigger::Exit(a), ScriptEvent::Exit(b))
+                | (Trigger::Custom(a), ScriptEvent::Custom(b))
+                | (Trigger::Pickup(a), ScriptEvent::Pickup(b))
+                | (Trigger::DoorOpen(a), ScriptEvent::DoorOpen(b))
+                | (Trigger::DoorClose(a), ScriptEvent::DoorClose(b))
+                | (Trigger::Locked(a), ScriptEvent::Locked(b))
+                | (Trigger::Break(a), ScriptEvent::Break(b))
+                | (Trigger::CutsceneEnd(a), ScriptEvent::CutsceneEnd(b))
+                | (Trigger::DialogueEnd(a), ScriptEvent::DialogueEnd(b)) => a == b,
+                _ => false,
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        for i in matching {
+            self.run(i);
+        }
+    }
+
+    /// Advance timers by `delta` seconds, running handlers that come due
+    pub fn update(&mut self, delta: f32) {
+        let mut due = Vec::new();
+        self.timers.retain_mut(|timer| {
+            timer.remaining -= delta;
+            if timer.remaining > 0.0 {
+                return true;
+            }
+            due.push(timer.handler);
+            false
+        });
+
+        for handler in due {
+            self.run(handler);
+            if let Trigger::Timer { seconds, repeat: true } = self.handlers[handler].trigger {
+                self.timers.push(TimerState { handler, remaining: seconds });
+            }
+        }
+    }
+
+    /// Take the actions queued by handlers since the last call
+    pub fn drain_actions(&mut self) -> Vec<ScriptAction> {
+        std::mem::take(&mut self.actions)
+    }
+
+    fn run(&mut self, handler: usize) {
+        let body = std::mem::take(&mut self.handlers[handler].body);
+        self.exec(&body);
+        self.handlers[handler].body = body;
+    }
+
+    /// Execute statements; returns false if `stop` was hit
+    fn exec(&mut self, body: &[Stmt]) -> bool {
+        for stmt in body {
+            match stmt {
+                Stmt::Emit(action) => self.actions.push(action.clone()),
+                Stmt::Set(var, expr) => {
+                    let value = self.eval(expr);
+                    self.vars.insert(var.clone(), value);
+                }
+                Stmt::If { lhs, cmp, rhs, then_body, else_body } => {
+                    let (a, b) = (self.eval(lhs), self.eval(rhs));
+                    let taken = match cmp {
+                        CmpOp::Eq => a == b,
+                        CmpOp::Ne => a != b,
+                        CmpOp::Lt => a < b,
+                        CmpOp::Le => a <= b,
+                        CmpOp::Gt => a > b,
+                        CmpOp::Ge => a >= b,
+                    };
+                    let branch = if taken { then_body } else { else_body };
+                    if !self.exec(branch) {
+                        return false;
+                    }
+                }
+                Stmt::Stop => return false,
+            }
+        }
+        true
+    }
+
+    fn eval(&self, expr: &Expr) -> f32 {
+        match expr {
+            Expr::Number(n) => *n,
+            Expr::Var(name) => self.var(name),
+            Expr::Neg(inner) => -self.eval(inner),
+            Expr::Binary(lhs, op, rhs) => {
+                let (a, b) = (self.eval(lhs), self.eval(rhs));
+                match op {
+                    BinOp::Add => a + b,
+                    BinOp::Sub => a - b,
+                    BinOp::Mul => a * b,
+                    // Division by zero yields 0 rather than inf/NaN
+                    BinOp::Div => if b == 0.0 { 0.0 } else { a / b },
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(source: &str) -> LevelScript {
+        LevelScript { name: "test".to_string(), source: source.to_string(), enabled: true }
+    }
+
+    fn compile(source: &str) -> ScriptRuntime {
+        let (runtime, errors) = ScriptRuntime::compile(&[script(source)]);
+        assert!(errors.is_empty(), "{:?}", errors);
+        runtime
+    }
+
+    #[test]
+    fn test_trigger_actions() {
+        let mut rt = compile(
+            "# vault\n\
+             on enter vault\n\
+             \topen_door \"vault door\"\n\
+             \tsound chime\n\
+             end\n",
+        );
+        rt.fire(&ScriptEvent::Enter("other".to_string()));
+        assert!(rt.drain_actions().is_empty());
+
+        rt.fire(&ScriptEvent::Enter("vault".to_string()));
+        assert_eq!(rt.drain_actions(), vec![
+            ScriptAction::OpenDoor("vault door".to_string()),
+            ScriptAction::PlaySound("chime".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_variables_and_if() {
+        let mut rt = compile(
+            "on pickup gem\n\
+               set gems = gems + 1\n\
+               if gems >= 3\n\
+                 open_door gate\n\
+               else\n\
+                 message \"more\"\n\
+               end\n\
+             end\n",
+        );
+        for _ in 0..3 {
+            rt.fire(&ScriptEvent::Pickup("gem".to_string()));
+        }
+        assert_eq!(rt.var("gems"), 3.0);
+        assert_eq!(rt.drain_actions(), vec![
+            ScriptAction::Message("more".to_string()),
+            ScriptAction::Message("more".to_string()),
+            ScriptAction::OpenDoor("gate".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_expressions_and_stop() {
+        let mut rt = compile(
+            "on start\n\
+               set a = 2 + 3 * (4 - 1)\n\
+               set b = -a / 0\n\
+               if a\n\
+                 stop\n\
+               end\n\
+               set c = 1\n\
+             end\n",
+        );
+        rt.fire(&ScriptEvent::Start);
+        assert_eq!(rt.var("a"), 11.0);
+        assert_eq!(rt.var("b"), 0.0);
+        assert_eq!(rt.var("c"), 0.0);
+    }
+
+    #[test]
+    fn test_timers() {
+        let mut rt = compile(
+            "on timer 1 repeat\n  toggle_door piston\nend\n\
+             on timer 1.5\n  message once\nend\n",
+        );
+        rt.update(0.9);
+        assert!(rt.drain_actions().is_empty());
+        rt.update(0.2); // 1.1s
+        assert_eq!(rt.drain_actions(), vec![ScriptAction::ToggleDoor("piston".to_string())]);
+        rt.update(1.0); // 2.1s
+        let actions = rt.drain_actions();
+        assert_eq!(actions.len(), 2);
+        rt.update(5.0);
+        assert_eq!(rt.drain_actions(), vec![ScriptAction::ToggleDoor("piston".to_string())]);
+    }
+
+    #[test]
+    fn test_errors_report_line() {
+        assert_eq!(validate("on enter a\n  fly away\nend").unwrap_err().0, 2);
+        assert_eq!(validate("on enter a\n  open_door x\n").unwrap_err(), (1, "missing 'end'".to_string()));
+        assert_eq!(validate("on sunrise\nend").unwrap_err().0, 1);
+        assert_eq!(validate("on start\n  message \"oops\nend").unwrap_err().0, 2);
+        assert_eq!(validate("on start\n  weather hail\nend").unwrap_err().0, 2);
+        assert_eq!(validate("on start\n  prompt fly \"Fly\"\nend").unwrap_err().0, 2);
+        assert_eq!(validate("on start\n  tutorial intro\nend").unwrap_err().0, 2);
+
+        // A broken script doesn't stop the others from loading
+        let (rt, errors) = ScriptRuntime::compile(&[
+            script("on start\n  set x = \nend"),
+            script("on start\n  set y = 1\nend"),
+        ]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(rt.handler_count(), 1);
+    }
+
+    #[test]
+    fn test_tutorial_commands() {
+        let mut rt = compile(
+            "on enter ledge\n\
+               prompt jump \"Jump across\"\n\
+               tutorial ledges \"Hold forward while jumping\"\n\
+             end\n\
+             on exit ledge\n  clear_prompt jump\nend\n",
+        );
+        rt.fire(&ScriptEvent::Enter("ledge".to_string()));
+        rt.fire(&ScriptEvent::Exit("ledge".to_string()));
+        assert_eq!(rt.drain_actions(), vec![
+            ScriptAction::ShowPrompt { action: Action::Jump, text: "Jump across".to_string() },
+            ScriptAction::Tutorial { id: "ledges".to_string(), text: "Hold forward while jumping".to_string() },
+            ScriptAction::ClearPrompt(Action::Jump),
+        ]);
+    }
+
+    #[test]
+    fn test_cutscene_command_and_end_event() {
+        let mut rt = compile("on enter gate\n  cutscene \"gate opens\"\nend\non cutscene_end \"gate opens\"\n  open_door gate\nend\n");
+        rt.fire(&ScriptEvent::Enter("gate".to_string()));
+        rt.fire(&ScriptEvent::CutsceneEnd("gate opens".to_string()));
+        assert_eq!(rt.drain_actions(), vec![
+            ScriptAction::PlayCutscene("gate opens".to_string()),
+            ScriptAction::OpenDoor("gate".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_music_command() {
+        let source = "on enter arena\n  music jump 4 bar\n  if boss\n    music play \"boss theme\"\n  end\nend\n";
+        let mut rt = compile(source);
+        rt.fire(&ScriptEvent::Enter("arena".to_string()));
+        assert_eq!(rt.drain_actions(), vec![ScriptAction::Music(MusicRequest {
+            command: MusicCommand::Jump(4),
+            sync: crate::tracker::CueSync::Bar,
+        })]);
+        assert_eq!(music_songs(source), vec!["boss theme".to_string()]);
+        assert!(validate("on start\n  music mute\nend").is_err());
+    }
+
+    #[test]
+    fn test_dialogue_end_reads_dialogue_flags() {
+        let mut rt = compile("on dialogue_end guard\n  if met\n    open_door gate\n  end\nend\n");
+        rt.fire(&ScriptEvent::DialogueEnd("guard".to_string()));
+        assert!(rt.drain_actions().is_empty());
+        rt.set_var("met", 1.0);
+        rt.fire(&ScriptEvent::DialogueEnd("guard".to_string()));
+        assert_eq!(rt.drain_actions(), vec![ScriptAction::OpenDoor("gate".to_string())]);
+    }
+
+    #[test]
+    fn test_status_commands() {
+        let mut rt = compile("on pickup antidote
+  cure poison
+  status \"Troll Blood\"
+end
+");
+        rt.fire(&ScriptEvent::Pickup("antidote".to_string()));
+        assert_eq!(rt.drain_actions(), vec![
+            ScriptAction::CureStatus("poison".to_string()),
+            ScriptAction::ApplyStatus("Troll Blood".to_string()),
+        ]);
+    }
+}
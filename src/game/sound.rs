@@ -0,0 +1,294 @@
+//! Game Sounds
+//!
+//! Turns each tick's events into sound effects to play. Level scripts and
+//! weather request sounds by name (`SoundEvent`); damage, deaths, pickups,
+//! doors, broken props, checkpoints and the player's footsteps play the sound the level
+//! binds to them. Names refer to `.sfx` files made in the SFX tab, and
+//! positional sounds are placed around the camera (see `spatial`). Walking
+//! into a room with a different reverb fades the old one out before the new
+//! one fades in, so the echo never jumps.
+
+use serde::{Deserialize, Serialize};
+
+use super::entity::Entity;
+use super::event::Events;
+use super::spatial::{spatialize, Listener};
+use super::World;
+use crate::rasterizer::Vec3;
+use crate::tracker::{PcmPlacement, ReverbType};
+use crate::world::SECTOR_SIZE;
+
+/// Distance the player walks between footsteps
+pub const FOOTSTEP_STRIDE: f32 = SECTOR_SIZE * 1.25;
+/// Positional sounds fade to silence at this distance from the listener
+pub const HEARING_RANGE: f32 = SECTOR_SIZE * 16.0;
+/// Seconds for the SFX reverb to fade fully out or in between rooms
+pub const REVERB_FADE_TIME: f32 = 0.4;
+
+/// Sound effect names played for gameplay events (empty = silent)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundBindings {
+    pub footstep: String,
+    pub hit: String,
+    pub death: String,
+    pub pickup: String,
+    pub door_open: String,
+    pub door_close: String,
+    pub door_locked: String,
+    pub prop_break: String,
+    pub checkpoint: String,
+}
+
+impl Default for SoundBindings {
+    fn default() -> Self {
+        Self {
+            footstep: "footstep".to_string(),
+            hit: "hit".to_string(),
+            death: "death".to_string(),
+            pickup: "pickup".to_string(),
+            door_open: "door_open".to_string(),
+            door_close: "door_close".to_string(),
+            door_locked: "door_locked".to_string(),
+            prop_break: "break".to_string(),
+            checkpoint: "checkpoint".to_string(),
+        }
+    }
+}
+
+impl SoundBindings {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// (event label, sound name) for every binding
+    pub fn all(&self) -> [(&'static str, &str); 9] {
+        [
+            ("Footstep", &self.footstep),
+            ("Hit", &self.hit),
+            ("Death", &self.death),
+            ("Pickup", &self.pickup),
+            ("Door Open", &self.door_open),
+            ("Door Close", &self.door_close),
+            ("Door Locked", &self.door_locked),
+            ("Prop Break", &self.prop_break),
+            ("Checkpoint", &self.checkpoint),
+        ]
+    }
+
+    /// Labels of the events that play `name`
+    pub fn events_for(&self, name: &str) -> Vec<&'static str> {
+        self.all().into_iter().filter(|(_, sound)| !sound.is_empty() && *sound == name).map(|(label, _)| label).collect()
+    }
+}
+
+/// Gain for a sound at `position` heard from `listener` (non-positional = full)
+pub fn falloff(position: Option<Vec3>, listener: Vec3) -> f32 {
+    let Some(position) = position else {
+        return 1.0;
+    };
+    let distance = (position - listener).len();
+    let t = (distance / HEARING_RANGE).min(1.0);
+    (1.0 - t) * (1.0 - t)
+}
+
+/// A sound effect to play, by name
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedSound {
+    pub name: String,
+    /// Gain, pan and reverb send heard from the listener
+    pub placement: PcmPlacement,
+}
+
+/// Sounds requested by the last tick, plus footstep tracking
+#[derive(Debug, Default)]
+pub struct GameSounds {
+    queued: Vec<QueuedSound>,
+    /// Distance walked since the last footstep
+    stride: f32,
+    last_player_position: Option<Vec3>,
+    /// Reverb the SFX currently send to (lags the listener's room while fading)
+    reverb: ReverbType,
+    /// Wet level of `reverb`, faded toward the listener's room
+    reverb_level: f32,
+}
+
+impl GameSounds {
+    /// Queue this tick's sounds, replacing the previous tick's (call before
+    /// the events are cleared)
+    pub fn collect(&mut self, bindings: &SoundBindings, world: &World, events: &Events, player: Option<Entity>, listener: &Listener) {
+        self.queued.clear();
+        let position = |entity: Entity| world.transforms.get(entity).map(|t| t.position);
+        let mut queue = |name: &str, at: Option<Vec3>| {
+            let placement = spatialize(at, listener);
+            if !name.is_empty() && placement.gain > 0.0 {
+                self.queued.push(QueuedSound { name: name.to_string(), placement });
+            }
+        };
+
+        for sound in events.sound.iter() {
+            queue(&sound.name, sound.position);
+        }
+        for damage in events.damage.iter() {
+            queue(&bindings.hit, position(damage.target));
+        }
+        for death in events.death.iter() {
+            queue(&bindings.death, Some(death.position));
+        }
+        for item in events.item_collected.iter() {
+            queue(&bindings.pickup, position(item.collector));
+        }
+        for door in events.door_opened.iter() {
+            queue(&bindings.door_open, position(door.door));
+        }
+        for door in events.door_closed.iter() {
+            queue(&bindings.door_close, position(door.door));
+        }
+        for door in events.door_locked.iter() {
+            queue(&bindings.door_locked, position(door.door));
+        }
+        for prop in events.prop_broken.iter() {
+            queue(&bindings.prop_break, Some(prop.position));
+        }
+        for checkpoint in events.checkpoint_activated.iter() {
+            queue(&bindings.checkpoint, position(checkpoint.checkpoint));
+        }
+
+        // Footsteps: one per stride walked on the ground
+        let walker = player.and_then(|p| Some((position(p)?, world.controllers.get(p)?.grounded)));
+        if let Some((now, grounded)) = walker {
+            let walked = self.last_player_position.map_or(0.0, |last| {
+                let d = now - last;
+                (d.x * d.x + d.z * d.z).sqrt()
+            });
+            // Respawns and teleports aren't steps
+            if grounded && walked < FOOTSTEP_STRIDE {
+                self.stride += walked;
+                if self.stride >= FOOTSTEP_STRIDE {
+                    self.stride -= FOOTSTEP_STRIDE;
+                    queue(&bindings.footstep, Some(now));
+                }
+            }
+        }
+        self.last_player_position = walker.map(|(now, _)| now);
+    }
+
+    /// Take the sounds queued by the last tick
+    pub fn take(&mut self) -> Vec<QueuedSound> {
+        std::mem::take(&mut self.queued)
+    }
+
+    /// Crossfade the SFX reverb toward the listener's room: a different
+    /// preset fades the current one out first, then the new one fades in to
+    /// the room's wet level
+    pub fn fade_reverb(&mut self, listener: &Listener, delta: f32) {
+        let step = delta / REVERB_FADE_TIME;
+        if self.reverb != listener.reverb {
+            self.reverb_level = (self.reverb_level - step).max(0.0);
+            if self.reverb_level <= 0.0 {
+                self.reverb = listener.reverb;
+            }
+            return;
+        }
+        let target = if self.reverb == ReverbType::Off { 0.0 } else { listener.reverb_wet };
+        self.reverb_level += (target - self.reverb_level).clamp(-step, step);
+    }
+
+    /// Reverb the SFX should send to (the listener's room, once faded in)
+    pub fn reverb(&self) -> ReverbType {
+        self.reverb
+    }
+
+    /// Wet level of `reverb` (0.0 to 1.0)
+    pub fn reverb_level(&self) -> f32 {
+        self.reverb_level
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::components::CharacterController;
+    use crate::game::event::{DoorEvent, SoundEvent};
+    use crate::game::transform::Transform;
+
+    #[test]
+    fn test_events_play_bound_sounds() {
+        let mut world = World::new();
+        let mut events = Events::new();
+        let door = world.spawn();
+        world.transforms.insert(door, Transform::from_position(Vec3::new(HEARING_RANGE * 2.0, 0.0, 0.0)));
+        events.sound.send(SoundEvent { name: "chime".to_string(), position: None });
+        events.door_opened.send(DoorEvent { door, opener: door });
+        events.door_locked.send(DoorEvent { door, opener: door });
+
+        let bindings = SoundBindings { door_locked: String::new(), ..Default::default() };
+        let mut sounds = GameSounds::default();
+        sounds.collect(&bindings, &world, &events, None, &Listener::at(Vec3::ZERO));
+        // The door is out of earshot and the locked sound is unbound
+        assert_eq!(sounds.take(), vec![QueuedSound { name: "chime".to_string(), placement: PcmPlacement::centered(1.0) }]);
+
+        sounds.collect(&bindings, &world, &events, None, &Listener::at(Vec3::new(HEARING_RANGE * 2.0, 0.0, 0.0)));
+        let names: Vec<String> = sounds.take().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["chime".to_string(), "door_open".to_string()]);
+        assert_eq!(bindings.events_for("door_open"), vec!["Door Open"]);
+    }
+
+    #[test]
+    fn test_footsteps_every_stride() {
+        let mut world = World::new();
+        let events = Events::new();
+        let player = world.spawn();
+        world.transforms.insert(player, Transform::from_position(Vec3::ZERO));
+        let mut controller = CharacterController::player();
+        controller.grounded = true;
+        world.controllers.insert(player, controller);
+
+        let bindings = SoundBindings::default();
+        let mut sounds = GameSounds::default();
+        let mut steps = 0;
+        for i in 0..=10 {
+            let x = i as f32 * FOOTSTEP_STRIDE * 0.5;
+            world.transforms.get_mut(player).unwrap().position = Vec3::new(x, 0.0, 0.0);
+            sounds.collect(&bindings, &world, &events, Some(player), &Listener::at(Vec3::ZERO));
+            steps += sounds.take().iter().filter(|s| s.name == "footstep").count();
+        }
+        assert_eq!(steps, 5);
+
+        // A teleport isn't a step
+        world.transforms.get_mut(player).unwrap().position = Vec3::new(0.0, 0.0, 0.0);
+        sounds.collect(&bindings, &world, &events, Some(player), &Listener::at(Vec3::ZERO));
+        assert!(sounds.take().is_empty());
+    }
+
+    #[test]
+    fn test_reverb_crossfades_between_rooms() {
+        let corridor = Listener { reverb: ReverbType::Room, reverb_wet: 0.5, ..Listener::at(Vec3::ZERO) };
+        let cave = Listener { reverb: ReverbType::Hall, ..corridor };
+        let tick = REVERB_FADE_TIME / 4.0;
+        let mut sounds = GameSounds::default();
+
+        // From silence the new preset switches in at once, then fades up
+        sounds.fade_reverb(&corridor, tick);
+        assert_eq!((sounds.reverb(), sounds.reverb_level()), (ReverbType::Room, 0.0));
+        for _ in 0..4 {
+            sounds.fade_reverb(&corridor, tick);
+        }
+        assert_eq!(sounds.reverb_level(), 0.5);
+
+        // A different preset fades the old one out before switching
+        sounds.fade_reverb(&cave, tick);
+        assert_eq!(sounds.reverb(), ReverbType::Room);
+        assert!(sounds.reverb_level() > 0.0 && sounds.reverb_level() < 0.5);
+        sounds.fade_reverb(&cave, tick);
+        assert_eq!((sounds.reverb(), sounds.reverb_level()), (ReverbType::Hall, 0.0));
+        for _ in 0..4 {
+            sounds.fade_reverb(&cave, tick);
+        }
+        assert_eq!(sounds.reverb_level(), 0.5);
+    }
+}
@@ -0,0 +1,114 @@
+//! Spatial Audio
+//!
+//! Places positional sound effects around the listener (the game camera):
+//! distance attenuation, stereo panning from the camera's right vector, and a
+//! reverb send when the listener stands in a room with a reverb preset. The
+//! audio engine applies the result to the SFX voice (`PcmPlacement`).
+
+use super::sound::falloff;
+use crate::rasterizer::{Camera, Vec3};
+use crate::tracker::{PcmPlacement, ReverbType};
+use crate::world::{Level, SECTOR_SIZE};
+
+/// Furthest a sound is panned; fully one-sided audio sounds unnatural
+pub const MAX_PAN: f32 = 0.8;
+/// Sounds closer than this stay centered (the player's own footsteps)
+pub const PAN_NEAR: f32 = SECTOR_SIZE;
+/// Reverb send of a sound right next to the listener
+pub const REVERB_SEND_NEAR: f32 = 0.2;
+/// Reverb send at the edge of hearing range (distant sounds are mostly echo)
+pub const REVERB_SEND_FAR: f32 = 0.7;
+
+/// Where sounds are heard from
+#[derive(Debug, Clone, Copy)]
+pub struct Listener {
+    pub position: Vec3,
+    /// Unit vector pointing to the listener's right
+    pub right: Vec3,
+    /// Reverb of the room the listener is in
+    pub reverb: ReverbType,
+    /// Wet level of that room's reverb (0.0 to 1.0)
+    pub reverb_wet: f32,
+}
+
+impl Listener {
+    /// A listener facing +Z with no reverb
+    pub fn at(position: Vec3) -> Self {
+        Self { position, right: Vec3::new(1.0, 0.0, 0.0), reverb: ReverbType::Off, reverb_wet: 1.0 }
+    }
+
+    /// The game camera, in whichever room contains it
+    pub fn from_camera(camera: &Camera, level: &Level) -> Self {
+        let (reverb, reverb_wet) = level.find_room_at(camera.position)
+            .and_then(|i| level.rooms.get(i))
+            .map_or((ReverbType::Off, 1.0), |room| (ReverbType::from_index(room.reverb), room.reverb_wet.clamp(0.0, 1.0)));
+        Self { position: camera.position, right: camera.basis_x, reverb, reverb_wet }
+    }
+}
+
+/// Gain, pan and reverb send for a sound at `position` (None for sounds
+/// without a place in the world, which play centered and dry)
+pub fn spatialize(position: Option<Vec3>, listener: &Listener) -> PcmPlacement {
+    let gain = falloff(position, listener.position);
+    let Some(position) = position else {
+        return PcmPlacement::centered(gain);
+    };
+
+    let offset = position - listener.position;
+    let distance = offset.len();
+    let pan = if distance > f32::EPSILON {
+        let side = offset.dot(listener.right) / distance;
+        side * (distance / PAN_NEAR).min(1.0) * MAX_PAN
+    } else {
+        0.0
+    };
+
+    let reverb_send = if listener.reverb == ReverbType::Off {
+        0.0
+    } else {
+        let t = (distance / super::sound::HEARING_RANGE).min(1.0);
+        REVERB_SEND_NEAR + (REVERB_SEND_FAR - REVERB_SEND_NEAR) * t
+    };
+
+    PcmPlacement { gain, pan, reverb_send }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pan_follows_listener_right() {
+        let listener = Listener::at(Vec3::ZERO);
+        let right = spatialize(Some(Vec3::new(SECTOR_SIZE * 4.0, 0.0, 0.0)), &listener);
+        assert!((right.pan - MAX_PAN).abs() < 1e-5);
+        let (left_gain, right_gain) = right.channel_gains();
+        assert!(right_gain > left_gain);
+
+        let ahead = spatialize(Some(Vec3::new(0.0, 0.0, SECTOR_SIZE * 4.0)), &listener);
+        assert!(ahead.pan.abs() < 1e-5);
+
+        // Turned around, the same sound is on the left
+        let turned = Listener { right: Vec3::new(-1.0, 0.0, 0.0), ..listener };
+        assert!(spatialize(Some(Vec3::new(SECTOR_SIZE * 4.0, 0.0, 0.0)), &turned).pan < 0.0);
+
+        // Right next to the listener the pan shrinks toward the center
+        let close = spatialize(Some(Vec3::new(PAN_NEAR * 0.25, 0.0, 0.0)), &listener);
+        assert!((close.pan - MAX_PAN * 0.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_reverb_send_grows_with_distance() {
+        let dry = Listener::at(Vec3::ZERO);
+        assert_eq!(spatialize(Some(Vec3::new(SECTOR_SIZE, 0.0, 0.0)), &dry).reverb_send, 0.0);
+
+        let hall = Listener { reverb: ReverbType::Hall, ..dry };
+        let near = spatialize(Some(Vec3::new(SECTOR_SIZE, 0.0, 0.0)), &hall);
+        let far = spatialize(Some(Vec3::new(SECTOR_SIZE * 12.0, 0.0, 0.0)), &hall);
+        assert!(near.reverb_send >= REVERB_SEND_NEAR && far.reverb_send > near.reverb_send);
+        assert!(far.gain < near.gain);
+
+        // Sounds without a position stay centered and dry
+        assert_eq!(spatialize(None, &hall), PcmPlacement::centered(1.0));
+    }
+}
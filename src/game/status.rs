@@ -0,0 +1,302 @@
+//! Status Effects
+//!
+//! Timed effects on characters: damage or healing over time, movement speed
+//! changes, and buildup effects like bleed that fill a meter with each hit and
+//! take hold once it's full. Effects are data (`asset::StatusEffectDef`): the
+//! built-in presets plus the StatusEffect component of every asset, named
+//! after the asset, so new ones are made in the asset editor.
+//!
+//! What applies them (see `level_logic`):
+//! - Hazard instances, every `interval` while the player is inside
+//! - Pickup instances, when collected (item buffs)
+//! - Enemy instances, when they deal damage (the hit's damage counts as buildup)
+//! - Scripts: `status <name>`
+//!
+//! The `status_effects` system ticks them, `StatusEffects::speed_multiplier`
+//! scales walking speed, and the HUD's Status element shows their icons.
+
+use std::collections::HashMap;
+
+use super::event::{DamageEvent, Events};
+use super::{Entity, World};
+use crate::asset::{AssetLibrary, StatusEffectDef, StatusStacking, STATUS_PRESETS};
+
+/// An effect that has taken hold
+#[derive(Debug, Clone)]
+pub struct ActiveStatus {
+    pub name: String,
+    pub def: StatusEffectDef,
+    /// Seconds left
+    pub remaining: f32,
+    pub stacks: u32,
+    /// Damage over time not yet dealt (less than one point)
+    pending: f32,
+}
+
+/// A buildup meter filling toward its effect
+#[derive(Debug, Clone)]
+pub struct StatusBuildup {
+    pub name: String,
+    pub def: StatusEffectDef,
+    pub amount: f32,
+}
+
+/// Status effects on an entity
+#[derive(Debug, Clone, Default)]
+pub struct StatusEffects {
+    pub active: Vec<ActiveStatus>,
+    pub buildup: Vec<StatusBuildup>,
+}
+
+impl StatusEffects {
+    /// Apply an effect. Buildup effects add `buildup` to their meter and take
+    /// hold when it fills; others take hold at once. Returns the burst damage
+    /// to deal now (0 if the effect didn't take hold).
+    pub fn apply(&mut self, name: &str, def: &StatusEffectDef, buildup: f32) -> i32 {
+        if def.buildup > 0.0 {
+            let index = match self.buildup.iter().position(|b| b.name == name) {
+                Some(index) => index,
+                None => {
+                    self.buildup.push(StatusBuildup { name: name.to_string(), def: def.clone(), amount: 0.0 });
+                    self.buildup.len() - 1
+                }
+            };
+            self.buildup[index].amount += buildup.max(0.0);
+            if self.buildup[index].amount < def.buildup {
+                return 0;
+            }
+            self.buildup.remove(index);
+        }
+        self.activate(name, def);
+        def.burst_damage
+    }
+
+    /// Start the effect's timer, following its stacking rule if it's already running
+    fn activate(&mut self, name: &str, def: &StatusEffectDef) {
+        if def.duration <= 0.0 {
+            return;
+        }
+        let max_stacks = def.max_stacks.max(1);
+        let Some(status) = self.active.iter_mut().find(|s| s.name == name) else {
+            self.active.push(ActiveStatus {
+                name: name.to_string(),
+                def: def.clone(),
+                remaining: def.duration,
+                stacks: 1,
+                pending: 0.0,
+            });
+            return;
+        };
+        match def.stacking {
+            StatusStacking::Refresh => status.remaining = status.remaining.max(def.duration),
+            StatusStacking::Extend => {
+                status.remaining = (status.remaining + def.duration).min(def.duration * max_stacks as f32);
+            }
+            StatusStacking::Stack => {
+                status.stacks = (status.stacks + 1).min(max_stacks);
+                status.remaining = def.duration;
+            }
+            StatusStacking::Ignore => {}
+        }
+    }
+
+    /// End an effect early (and empty its meter)
+    pub fn remove(&mut self, name: &str) {
+        self.active.retain(|s| s.name != name);
+        self.buildup.retain(|b| b.name != name);
+    }
+
+    /// Advance by `delta` seconds: expire effects and drain meters. Returns
+    /// the whole health points to take (negative heals); fractions carry over.
+    pub fn update(&mut self, delta: f32) -> i32 {
+        let mut total = 0;
+        for status in &mut self.active {
+            let seconds = delta.min(status.remaining.max(0.0));
+            status.pending += status.def.damage_per_second * status.stacks as f32 * seconds;
+            let whole = status.pending.trunc();
+            status.pending -= whole;
+            total += whole as i32;
+            status.remaining -= delta;
+        }
+        self.active.retain(|s| s.remaining > 0.0);
+        for meter in &mut self.buildup {
+            meter.amount -= meter.def.buildup_decay * delta;
+        }
+        self.buildup.retain(|b| b.amount > 0.0);
+        total
+    }
+
+    /// Movement speed factor of every running effect combined
+    pub fn speed_multiplier(&self) -> f32 {
+        self.active.iter()
+            .map(|s| s.def.speed_multiplier.max(0.0).powi(s.stacks as i32))
+            .product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty() && self.buildup.is_empty()
+    }
+}
+
+/// Speed factor for an entity (1 without status effects)
+pub fn speed_multiplier(world: &World, entity: Entity) -> f32 {
+    world.status_effects.get(entity).map_or(1.0, |s| s.speed_multiplier())
+}
+
+/// Every status effect a level can use, by name
+#[derive(Debug, Clone, Default)]
+pub struct StatusLibrary {
+    effects: HashMap<String, StatusEffectDef>,
+}
+
+impl StatusLibrary {
+    /// The built-in presets plus each asset's StatusEffect (an asset named
+    /// like a preset replaces it)
+    pub fn new(asset_library: &AssetLibrary) -> Self {
+        let mut effects: HashMap<String, StatusEffectDef> = STATUS_PRESETS.iter()
+            .filter_map(|name| Some((name.to_string(), StatusEffectDef::preset(name)?)))
+            .collect();
+        for (_, asset) in asset_library.iter() {
+            if let Some(def) = asset.status_effect() {
+                effects.insert(asset.name.clone(), def.clone());
+            }
+        }
+        Self { effects }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&StatusEffectDef> {
+        self.effects.get(name)
+    }
+}
+
+/// Apply an effect to `target`, giving it a `StatusEffects` component if it
+/// has none, and deal any burst damage. See `StatusEffects::apply`.
+pub fn apply_status(
+    world: &mut World,
+    events: &mut Events,
+    target: Entity,
+    name: &str,
+    def: &StatusEffectDef,
+    buildup: f32,
+) {
+    if !world.is_alive(target) {
+        return;
+    }
+    if !world.status_effects.contains(target) {
+        world.status_effects.insert(target, StatusEffects::default());
+    }
+    let burst = world.status_effects.get_mut(target).map_or(0, |s| s.apply(name, def, buildup));
+    hurt(world, events, target, burst);
+}
+
+/// Take `amount` health (negative heals); damage is sent as a `DamageEvent`
+pub fn hurt(world: &mut World, events: &mut Events, target: Entity, amount: i32) {
+    let Some(health) = world.health.get_mut(target) else {
+        return;
+    };
+    if amount < 0 {
+        health.heal(-amount);
+    } else if amount > 0 {
+        health.damage(amount);
+        let position = world.transforms.get(target).map(|t| t.position).unwrap_or_default();
+        events.damage.send(DamageEvent { target, source: None, amount, position });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rasterizer::Vec3;
+
+    fn preset(name: &str) -> StatusEffectDef {
+        StatusEffectDef::preset(name).unwrap()
+    }
+
+    #[test]
+    fn test_poison_stacks_and_expires() {
+        let poison = preset("poison");
+        let mut effects = StatusEffects::default();
+        for _ in 0..5 {
+            assert_eq!(effects.apply("poison", &poison, 0.0), 0);
+        }
+        assert_eq!(effects.active[0].stacks, poison.max_stacks);
+
+        // 2 per second per stack, half-second ticks carry the fractions
+        let mut total = 0;
+        for _ in 0..20 {
+            total += effects.update(0.5);
+        }
+        assert_eq!(total, (poison.damage_per_second * poison.duration) as i32 * poison.max_stacks as i32);
+        assert!(effects.is_empty());
+    }
+
+    #[test]
+    fn test_stacking_rules() {
+        let base = StatusEffectDef { duration: 4.0, max_stacks: 2, damage_per_second: 0.0, ..preset("poison") };
+        let run = |stacking: StatusStacking| {
+            let def = StatusEffectDef { stacking, ..base.clone() };
+            let mut effects = StatusEffects::default();
+            effects.apply("x", &def, 0.0);
+            effects.update(1.0);
+            for _ in 0..3 {
+                effects.apply("x", &def, 0.0);
+            }
+            (effects.active[0].remaining, effects.active[0].stacks)
+        };
+        assert_eq!(run(StatusStacking::Refresh), (4.0, 1));
+        // Capped at max_stacks durations
+        assert_eq!(run(StatusStacking::Extend), (8.0, 1));
+        assert_eq!(run(StatusStacking::Stack), (4.0, 2));
+        assert_eq!(run(StatusStacking::Ignore), (3.0, 1));
+    }
+
+    #[test]
+    fn test_bleed_builds_up_then_bursts() {
+        let bleed = preset("bleed");
+        let mut effects = StatusEffects::default();
+        assert_eq!(effects.apply("bleed", &bleed, 60.0), 0);
+        // The meter drains between hits
+        effects.update(2.0);
+        assert_eq!(effects.buildup[0].amount, 60.0 - bleed.buildup_decay * 2.0);
+        assert_eq!(effects.apply("bleed", &bleed, 30.0), 0);
+        assert_eq!(effects.apply("bleed", &bleed, 30.0), bleed.burst_damage);
+        // Bleed has no lasting part: nothing is left once it bursts
+        assert!(effects.is_empty());
+
+        effects.apply("bleed", &bleed, 10.0);
+        effects.update(5.0);
+        assert!(effects.is_empty());
+    }
+
+    #[test]
+    fn test_speed_multiplier_and_healing() {
+        let mut effects = StatusEffects::default();
+        effects.apply("slow", &preset("slow"), 0.0);
+        effects.apply("haste", &preset("haste"), 0.0);
+        assert!((effects.speed_multiplier() - 0.5 * 1.4).abs() < 1e-5);
+        effects.remove("slow");
+        assert!((effects.speed_multiplier() - 1.4).abs() < 1e-5);
+
+        let mut regen = StatusEffects::default();
+        regen.apply("regen", &preset("regen"), 0.0);
+        assert_eq!(regen.update(1.0), -3);
+    }
+
+    #[test]
+    fn test_apply_status_adds_component_and_deals_burst() {
+        let mut world = World::new();
+        let mut events = Events::new();
+        let target = world.spawn_at(Vec3::ZERO);
+        world.health.insert(target, crate::game::components::Health::new(100));
+        let bleed = preset("bleed");
+
+        apply_status(&mut world, &mut events, target, "bleed", &bleed, bleed.buildup);
+        assert!(world.status_effects.contains(target));
+        assert_eq!(world.health.get(target).unwrap().current, 100 - bleed.burst_damage);
+        assert_eq!(events.damage.len(), 1);
+
+        hurt(&mut world, &mut events, target, -10);
+        assert_eq!(world.health.get(target).unwrap().current, 100 - bleed.burst_damage + 10);
+        assert_eq!(speed_multiplier(&world, target), 1.0);
+    }
+}
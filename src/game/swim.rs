@@ -0,0 +1,312 @@
+//! Swimming
+//!
+//! The character controller's swimming state. A character starts swimming
+//! once a room's water (see `world::water`) is deep enough over its feet, and
+//! `character_controllers` moves it with `swim_step` instead of gravity:
+//! slower, with jump/crouch rising and diving, floating back up to tread
+//! water at the surface (bobbing) when idle. Swimming into a low ledge at the
+//! surface climbs out; wading into the shallows walks out.
+//!
+//! Breath runs out while the head is underwater; with none left the swimmer
+//! takes drowning damage every `DROWN_INTERVAL`. Transitions are sent as a
+//! `SwimEvent` for animations and splash sounds.
+
+use serde::{Serialize, Deserialize};
+use crate::rasterizer::Vec3;
+use crate::world::Level;
+use super::collision::collide_cylinder;
+use super::components::CharacterController;
+
+/// Horizontal swimming speed (units per second)
+pub const SWIM_SPEED: f32 = 450.0;
+/// Rising and diving speed (units per second)
+pub const SWIM_VERTICAL_SPEED: f32 = 400.0;
+/// How fast an idle swimmer floats back up (units per second)
+pub const FLOAT_RISE: f32 = 150.0;
+/// Water this deep over the feet (fraction of the character's height) starts swimming
+pub const SWIM_DEPTH: f32 = 0.6;
+/// Water shallower than this (fraction of height) over the feet walks out
+pub const WADE_DEPTH: f32 = 0.4;
+/// Treading water, the feet hang this far below the surface (fraction of height)
+pub const FLOAT_DEPTH: f32 = 0.75;
+/// Surface bobbing amplitude (units)
+pub const BOB_HEIGHT: f32 = 24.0;
+/// Surface bobbing rate (cycles per second)
+pub const BOB_RATE: f32 = 0.6;
+/// Seconds of breath underwater
+pub const OXYGEN_MAX: f32 = 20.0;
+/// Breath refills this many times faster than it runs out
+pub const OXYGEN_REFILL: f32 = 4.0;
+/// Drowning damage per interval once out of breath
+pub const DROWN_DAMAGE: i32 = 10;
+/// Seconds between drowning damage
+pub const DROWN_INTERVAL: f32 = 1.0;
+/// Highest ledge above the surface a swimmer can climb out onto
+pub const CLIMB_OUT_HEIGHT: f32 = 256.0;
+/// How far past its radius a swimmer reaches for a ledge
+pub const CLIMB_OUT_REACH: f32 = 128.0;
+
+/// A character's swimming state (world space)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SwimState {
+    /// Height of the water surface
+    pub surface: f32,
+    /// Height of the water's bottom
+    pub bottom: f32,
+    /// Seconds of breath left
+    pub oxygen: f32,
+    /// Seconds since entering the water (drives the bobbing)
+    pub time: f32,
+    /// Seconds without breath since the last drowning damage
+    pub drowning: f32,
+    /// Whether the head was underwater last step
+    pub submerged: bool,
+}
+
+impl SwimState {
+    /// Breath left (0..1)
+    pub fn oxygen_fraction(&self) -> f32 {
+        (self.oxygen / OXYGEN_MAX).clamp(0.0, 1.0)
+    }
+
+    /// Feet height while treading water, bobbing
+    pub fn float_height(&self, height: f32) -> f32 {
+        let bob = (self.time * BOB_RATE * std::f32::consts::TAU).sin() * BOB_HEIGHT;
+        self.surface - height * FLOAT_DEPTH + bob
+    }
+}
+
+/// What a swimmer just did (for animations and sounds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwimAction {
+    /// Fell or waded into deep water
+    Enter,
+    /// Head went under
+    Dive,
+    /// Head came back up for air
+    Surface,
+    /// Waded out into the shallows
+    Leave,
+    /// Pulled up onto a ledge at the surface
+    ClimbOut,
+}
+
+/// Start or stop swimming for a character at `position` (feet), by how deep
+/// the water is over its feet. Returns the transition, if any.
+pub fn update_water(level: &Level, position: Vec3, controller: &mut CharacterController) -> Option<SwimAction> {
+    let water = level.water_at(position).map(|(room, water)| {
+        let room_position = level.rooms[room].position;
+        (water.world_surface(room_position), room_position.y + water.bottom)
+    });
+    let depth = water.map_or(0.0, |(surface, _)| surface - position.y);
+
+    match water {
+        Some((surface, bottom)) if controller.swimming.is_none() => {
+            if depth < controller.height * SWIM_DEPTH {
+                return None;
+            }
+            controller.swimming = Some(SwimState { surface, bottom, oxygen: OXYGEN_MAX, time: 0.0, drowning: 0.0, submerged: false });
+            controller.grounded = false;
+            controller.vertical_velocity = 0.0;
+            Some(SwimAction::Enter)
+        }
+        Some((surface, bottom)) if depth >= controller.height * WADE_DEPTH => {
+            // Still deep enough (possibly another room's water)
+            if let Some(state) = &mut controller.swimming {
+                state.surface = surface;
+                state.bottom = bottom;
+            }
+            None
+        }
+        _ if controller.swimming.is_some() => {
+            controller.swimming = None;
+            Some(SwimAction::Leave)
+        }
+        _ => None,
+    }
+}
+
+/// Move a swimmer by `velocity` (XZ swims, Y rises or dives; no vertical
+/// input floats back up to the surface). Returns the new position, the
+/// transition that happened (if any) and drowning damage taken this step.
+pub fn swim_step(
+    level: &Level,
+    position: Vec3,
+    velocity: Vec3,
+    controller: &mut CharacterController,
+    delta: f32,
+) -> (Vec3, Option<SwimAction>, i32) {
+    let Some(mut state) = controller.swimming else {
+        return (position, None, 0);
+    };
+    state.time += delta;
+
+    // Vertical: dive and rise, or drift up to tread water; never above the float height
+    let float = state.float_height(controller.height);
+    let mut y = if velocity.y != 0.0 {
+        position.y + velocity.y * delta
+    } else if position.y < float {
+        (position.y + FLOAT_RISE * delta).min(float)
+    } else {
+        (position.y - FLOAT_RISE * delta).max(float)
+    };
+    y = y.min(float).max(state.bottom.min(float));
+
+    // Walls, floor and ceiling as on land, without gravity
+    let probe = CharacterController {
+        grounded: true,
+        vertical_velocity: if delta > 0.0 { (y - position.y) / delta } else { 0.0 },
+        ..*controller
+    };
+    let result = collide_cylinder(level, position, Vec3::new(velocity.x, 0.0, velocity.z), &probe, delta);
+    controller.current_room = result.room;
+    controller.grounded = false;
+    controller.vertical_velocity = 0.0;
+
+    // Swimming into a wall at the surface: pull up onto the ledge if it's low enough
+    let horizontal = Vec3::new(velocity.x, 0.0, velocity.z);
+    if result.hit_wall && horizontal.len() > 0.0 && result.position.y + controller.height > state.surface {
+        let reach = horizontal.normalize() * (controller.radius * 2.0 + CLIMB_OUT_REACH);
+        let ahead = Vec3::new(position.x + reach.x, state.surface + CLIMB_OUT_HEIGHT, position.z + reach.z);
+        let ledge = level.get_floor_info(ahead, Some(controller.current_room))
+            .filter(|info| info.floor >= state.surface - controller.step_height
+                && info.floor <= state.surface + CLIMB_OUT_HEIGHT
+                && info.ceiling - info.floor >= controller.height);
+        if let Some(info) = ledge {
+            controller.swimming = None;
+            controller.grounded = true;
+            controller.current_room = info.room;
+            return (Vec3::new(ahead.x, info.floor, ahead.z), Some(SwimAction::ClimbOut), 0);
+        }
+    }
+
+    // Breath: runs out with the head underwater, refills above it
+    let submerged = result.position.y + controller.height < state.surface;
+    let mut damage = 0;
+    if submerged {
+        state.oxygen = (state.oxygen - delta).max(0.0);
+        if state.oxygen <= 0.0 {
+            state.drowning += delta;
+            if state.drowning >= DROWN_INTERVAL {
+                state.drowning -= DROWN_INTERVAL;
+                damage = DROWN_DAMAGE;
+            }
+        }
+    } else {
+        state.oxygen = (state.oxygen + delta * OXYGEN_REFILL).min(OXYGEN_MAX);
+        state.drowning = 0.0;
+    }
+    let action = match (state.submerged, submerged) {
+        (false, true) => Some(SwimAction::Dive),
+        (true, false) => Some(SwimAction::Surface),
+        _ => None,
+    };
+    state.submerged = submerged;
+    controller.swimming = Some(state);
+
+    (result.position, action, damage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{Room, RoomWater, TextureRef, SECTOR_SIZE};
+
+    /// A pool three sectors deep with a ledge (east) just above the surface
+    fn pool_level() -> Level {
+        let mut level = Level::new();
+        let mut room = Room::new(0, Vec3::ZERO, 2, 1);
+        room.set_floor(0, 0, -SECTOR_SIZE * 3.0, TextureRef::none());
+        room.set_floor(1, 0, 128.0, TextureRef::none());
+        room.set_ceiling(0, 0, SECTOR_SIZE * 2.0, TextureRef::none());
+        room.set_ceiling(1, 0, SECTOR_SIZE * 2.0, TextureRef::none());
+        room.water = Some(RoomWater::new(0.0, -SECTOR_SIZE * 3.0));
+        room.recalculate_bounds();
+        level.rooms.push(room);
+        level
+    }
+
+    fn swimmer(level: &Level, position: Vec3) -> CharacterController {
+        let mut controller = CharacterController::player();
+        assert_eq!(update_water(level, position, &mut controller), Some(SwimAction::Enter));
+        controller
+    }
+
+    #[test]
+    fn test_enter_float_up_and_bob() {
+        let level = pool_level();
+        let mut pos = Vec3::new(SECTOR_SIZE * 0.5, -SECTOR_SIZE, SECTOR_SIZE * 0.5);
+        let mut controller = swimmer(&level, pos);
+        assert!(controller.swimming.is_some() && !controller.grounded);
+
+        // Idle: drifts up to tread water, head above the surface
+        for _ in 0..200 {
+            pos = swim_step(&level, pos, Vec3::ZERO, &mut controller, 0.05).0;
+        }
+        let height = controller.height;
+        let rest = -height * FLOAT_DEPTH;
+        assert!((pos.y - rest).abs() <= BOB_HEIGHT + 1e-3);
+        assert!(pos.y + height > 0.0);
+
+        // Jumping can't lift a swimmer out of the water
+        let (up, ..) = swim_step(&level, pos, Vec3::new(0.0, SWIM_VERTICAL_SPEED, 0.0), &mut controller, 0.5);
+        assert!(up.y <= rest + BOB_HEIGHT + 1e-3);
+    }
+
+    #[test]
+    fn test_breath_runs_out_underwater() {
+        let level = pool_level();
+        let mut pos = Vec3::new(SECTOR_SIZE * 0.5, -SECTOR_SIZE * 2.0, SECTOR_SIZE * 0.5);
+        let mut controller = swimmer(&level, pos);
+        let dive = Vec3::new(0.0, -1.0, 0.0);
+
+        let (next, action, damage) = swim_step(&level, pos, dive, &mut controller, 1.0);
+        pos = next;
+        assert_eq!((action, damage), (Some(SwimAction::Dive), 0));
+        let mut total = 0;
+        for _ in 0..(OXYGEN_MAX as usize + 3) {
+            let (next, _, damage) = swim_step(&level, pos, dive, &mut controller, 1.0);
+            pos = next;
+            total += damage;
+        }
+        assert_eq!(controller.swimming.unwrap().oxygen, 0.0);
+        assert!(total >= DROWN_DAMAGE * 2);
+
+        // Surfacing refills it
+        let mut surfaced = false;
+        for _ in 0..200 {
+            let (next, action, _) = swim_step(&level, pos, Vec3::ZERO, &mut controller, 0.1);
+            pos = next;
+            surfaced |= action == Some(SwimAction::Surface);
+        }
+        assert!(surfaced);
+        assert_eq!(controller.swimming.unwrap().oxygen_fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_climb_out_onto_low_ledge() {
+        let level = pool_level();
+        let mut pos = Vec3::new(SECTOR_SIZE * 0.5, -SECTOR_SIZE, SECTOR_SIZE * 0.5);
+        let mut controller = swimmer(&level, pos);
+        for _ in 0..200 {
+            pos = swim_step(&level, pos, Vec3::ZERO, &mut controller, 0.05).0;
+        }
+
+        let east = Vec3::new(SWIM_SPEED, 0.0, 0.0);
+        let mut climbed = None;
+        for _ in 0..100 {
+            let (next, action, _) = swim_step(&level, pos, east, &mut controller, 0.05);
+            pos = next;
+            if action == Some(SwimAction::ClimbOut) {
+                climbed = Some(pos);
+                break;
+            }
+        }
+        let ledge = climbed.expect("swimmer should climb out");
+        assert_eq!(ledge.y, 128.0);
+        assert!(ledge.x > SECTOR_SIZE && controller.grounded && controller.swimming.is_none());
+
+        // Dry land: nothing to swim in
+        assert_eq!(update_water(&level, ledge, &mut controller), None);
+    }
+}
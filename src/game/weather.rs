@@ -0,0 +1,183 @@
+//! Weather Particles
+//!
+//! Simulates and draws the level's weather preset (`world::LevelWeather`) while
+//! playing. Particles live in a cube of `FIELD_SIZE` that tiles space around
+//! the camera, so a few hundred of them cover any view without ever being
+//! respawned. They are drawn depth-tested into the framebuffer after the
+//! scene, and with `outdoor_only` set, particles inside rooms that aren't
+//! marked outdoor are skipped.
+
+use super::event::{Events, SoundEvent};
+use crate::rasterizer::{
+    Camera, Color as RasterColor, Framebuffer, Vec3, perspective_transform, project,
+};
+use crate::world::{Level, WeatherPreset, SECTOR_SIZE};
+
+/// Edge length of the particle cube that tiles around the camera
+const FIELD_SIZE: f32 = SECTOR_SIZE * 4.0;
+/// Dots closer than this are drawn two pixels wide
+const NEAR_DISTANCE: f32 = SECTOR_SIZE;
+
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+    /// Position inside the field cube (0..FIELD_SIZE on each axis)
+    pos: Vec3,
+    /// Sway phase offset (radians)
+    phase: f32,
+}
+
+/// Running weather for one play session
+#[derive(Debug, Clone, Default)]
+pub struct WeatherState {
+    preset: WeatherPreset,
+    particles: Vec<Particle>,
+    time: f32,
+    seed: u32,
+}
+
+/// Map a field coordinate to the world copy nearest the camera
+fn wrap_to_camera(field: f32, camera: f32) -> f32 {
+    let half = FIELD_SIZE * 0.5;
+    camera + (field - camera + half).rem_euclid(FIELD_SIZE) - half
+}
+
+impl WeatherState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Preset currently falling
+    pub fn preset(&self) -> WeatherPreset {
+        self.preset
+    }
+
+    /// Advance particles. `preset` is the level's preset or a script override.
+    /// Starting a new preset sends its ambience as a sound event.
+    pub fn update(&mut self, level: &Level, preset: WeatherPreset, events: &mut Events, delta: f32) {
+        let count = level.weather.particle_count(preset);
+        if preset != self.preset {
+            if let Some(name) = preset.sound_name() {
+                events.sound.send(SoundEvent { name: name.to_string(), position: None });
+            }
+            self.preset = preset;
+            self.particles.clear();
+        }
+        if count != self.particles.len() {
+            self.respawn(count);
+        }
+
+        self.time += delta;
+        let params = preset.params();
+        for particle in &mut self.particles {
+            let sway_x = (self.time * 1.7 + particle.phase).sin() * params.sway;
+            let sway_z = (self.time * 1.3 + particle.phase).cos() * params.sway * 0.5;
+            let pos = &mut particle.pos;
+            pos.x = (pos.x + (params.drift + sway_x) * delta).rem_euclid(FIELD_SIZE);
+            pos.y = (pos.y - params.fall_speed * delta).rem_euclid(FIELD_SIZE);
+            pos.z = (pos.z + sway_z * delta).rem_euclid(FIELD_SIZE);
+        }
+    }
+
+    /// Scatter `count` particles through the field
+    fn respawn(&mut self, count: usize) {
+        let mut random = || {
+            self.seed = self.seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (self.seed >> 8) as f32 / (1u32 << 24) as f32
+        };
+        self.particles.clear();
+        for _ in 0..count {
+            let pos = Vec3::new(random() * FIELD_SIZE, random() * FIELD_SIZE, random() * FIELD_SIZE);
+            let phase = random() * std::f32::consts::TAU;
+            self.particles.push(Particle { pos, phase });
+        }
+    }
+
+    /// Draw particles into the framebuffer (after the scene, so geometry occludes them)
+    pub fn draw(&self, fb: &mut Framebuffer, camera: &Camera, level: &Level) {
+        if self.particles.is_empty() {
+            return;
+        }
+        let params = self.preset.params();
+        let (r, g, b) = params.color;
+        let color = RasterColor::new(r, g, b);
+        let streak = Vec3::new(-params.drift * params.streak, params.fall_speed * params.streak, 0.0);
+
+        let project_point = |p: Vec3, fb: &Framebuffer| -> Option<(i32, i32, f32)> {
+            let cam = perspective_transform(p - camera.position, camera.basis_x, camera.basis_y, camera.basis_z);
+            if cam.z < 0.1 {
+                return None;
+            }
+            let proj = project(cam, fb.width, fb.height);
+            Some((proj.x as i32, proj.y as i32, cam.z))
+        };
+
+        let mut room_hint = None;
+        for particle in &self.particles {
+            let world = Vec3::new(
+                wrap_to_camera(particle.pos.x, camera.position.x),
+                wrap_to_camera(particle.pos.y, camera.position.y),
+                wrap_to_camera(particle.pos.z, camera.position.z),
+            );
+            if level.weather.outdoor_only {
+                if let Some(room) = level.find_room_at_with_hint(world, room_hint) {
+                    room_hint = Some(room);
+                    if !level.rooms[room].outdoor {
+                        continue;
+                    }
+                }
+            }
+
+            let Some((x0, y0, z0)) = project_point(world, fb) else {
+                continue;
+            };
+            let (x1, y1, z1) = if params.streak > 0.0 {
+                project_point(world + streak, fb).unwrap_or((x0, y0, z0))
+            } else if z0 < NEAR_DISTANCE {
+                (x0 + 1, y0, z0)
+            } else {
+                (x0, y0, z0)
+            };
+            fb.draw_line_3d_alpha(x0, y0, z0, x1, y1, z1, color, params.alpha);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_stays_near_camera() {
+        for camera in [-10000.0, 0.0, 123.0, 9000.0] {
+            for field in [0.0, 1000.0, FIELD_SIZE - 1.0] {
+                let w = wrap_to_camera(field, camera);
+                assert!((w - camera).abs() <= FIELD_SIZE * 0.5);
+                // Same point of the tiling, whole fields away
+                let tiles = (w - field) / FIELD_SIZE;
+                assert!((tiles - tiles.round()).abs() < 0.001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_preset_change_respawns_and_plays_sound() {
+        let mut level = Level::new();
+        level.weather.preset = WeatherPreset::Rain;
+        let mut events = Events::new();
+        let mut weather = WeatherState::new();
+
+        weather.update(&level, WeatherPreset::Rain, &mut events, 0.1);
+        assert_eq!(weather.particles.len(), level.weather.particle_count(WeatherPreset::Rain));
+        assert_eq!(events.sound.len(), 1);
+        assert!(weather.particles.iter().all(|p| (0.0..=FIELD_SIZE).contains(&p.pos.y)));
+
+        // Same preset: no new sound
+        weather.update(&level, WeatherPreset::Rain, &mut events, 0.1);
+        assert_eq!(events.sound.len(), 1);
+
+        // Script turns it off
+        weather.update(&level, WeatherPreset::Clear, &mut events, 0.1);
+        assert!(weather.particles.is_empty());
+        assert_eq!(weather.preset(), WeatherPreset::Clear);
+    }
+}
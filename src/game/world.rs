@@ -4,15 +4,19 @@
 //! - Entity allocation and lifetime tracking
 //! - Component storage for all component types
 //! - Deferred entity despawn (to avoid iterator invalidation)
+//! - Typed queries over several components (`query`, `query_ref`)
 //!
 //! Unlike Bevy which uses runtime type registration, we define all
 //! component types at compile time. This is simpler and sufficient
 //! for our known game requirements.
 
 use super::entity::{Entity, EntityAllocator};
-use super::component::ComponentStorage;
+use super::component::{Component, ComponentStorage};
+use super::query::{self, QueryData, QueryIter, ReadOnlyQuery};
 use super::transform::{Transform, GlobalTransform};
 use super::components::*;
+use super::status::StatusEffects;
+use crate::asset::AttackDef;
 use crate::rasterizer::Vec3;
 
 /// The game world containing all entities and their components.
@@ -55,12 +59,24 @@ pub struct World {
     /// Health and damage tracking
     pub health: ComponentStorage<Health>,
 
+    /// Stamina pool (sprinting)
+    pub stamina: ComponentStorage<Stamina>,
+
+    /// Poise (stagger resistance)
+    pub poise: ComponentStorage<Poise>,
+
+    /// Melee moveset and attack/guard/dodge state
+    pub combat: ComponentStorage<Combat>,
+
     /// Hitbox for collision/damage
     pub hitboxes: ComponentStorage<Hitbox>,
 
     /// Hurtbox (area that can receive damage)
     pub hurtboxes: ComponentStorage<Hurtbox>,
 
+    /// Poison, bleed buildup, slows and buffs
+    pub status_effects: ComponentStorage<StatusEffects>,
+
     // =========================================================================
     // Entity Type Markers (zero-sized, just for identification)
     // =========================================================================
@@ -84,6 +100,15 @@ pub struct World {
     /// Doors and gates that can be opened
     pub doors: ComponentStorage<Door>,
 
+    /// Entities moving along editor-placed paths
+    pub path_followers: ComponentStorage<PathFollower>,
+
+    /// Platforms and elevators that carry characters
+    pub moving_platforms: ComponentStorage<MovingPlatform>,
+
+    /// Enemies wandering around their home on navgraph paths
+    pub patrols: ComponentStorage<Patrol>,
+
     /// Keys and items that unlock doors
     pub keys: ComponentStorage<Key>,
 
@@ -94,6 +119,45 @@ pub struct World {
     pub spawn_points: ComponentStorage<SpawnPoint>,
 }
 
+/// Map each component type to its `World` storage field (for queries).
+/// Hierarchy storages (`parents`, `children`) are left out: use the hierarchy helpers.
+macro_rules! world_components {
+    ($($ty:ty => $field:ident),* $(,)?) => {
+        $(
+            impl Component for $ty {
+                unsafe fn storage_ptr(world: *mut World) -> *mut ComponentStorage<Self> {
+                    std::ptr::addr_of_mut!((*world).$field)
+                }
+            }
+        )*
+    };
+}
+
+world_components! {
+    Transform => transforms,
+    GlobalTransform => global_transforms,
+    Velocity => velocities,
+    CharacterController => controllers,
+    Health => health,
+    Stamina => stamina,
+    Poise => poise,
+    Combat => combat,
+    Hitbox => hitboxes,
+    Hurtbox => hurtboxes,
+    StatusEffects => status_effects,
+    Player => players,
+    Enemy => enemies,
+    Projectile => projectiles,
+    Item => items,
+    Door => doors,
+    PathFollower => path_followers,
+    MovingPlatform => moving_platforms,
+    Patrol => patrols,
+    Key => keys,
+    Checkpoint => checkpoints,
+    SpawnPoint => spawn_points,
+}
+
 impl World {
     /// Create a new empty world.
     pub fn new() -> Self {
@@ -111,8 +175,12 @@ impl World {
             velocities: ComponentStorage::new(),
             controllers: ComponentStorage::new(),
             health: ComponentStorage::new(),
+            stamina: ComponentStorage::new(),
+            poise: ComponentStorage::new(),
+            combat: ComponentStorage::new(),
             hitboxes: ComponentStorage::new(),
             hurtboxes: ComponentStorage::new(),
+            status_effects: ComponentStorage::new(),
 
             // Markers
             players: ComponentStorage::new(),
@@ -122,6 +190,9 @@ impl World {
 
             // World interaction
             doors: ComponentStorage::new(),
+            path_followers: ComponentStorage::new(),
+            moving_platforms: ComponentStorage::new(),
+            patrols: ComponentStorage::new(),
             keys: ComponentStorage::new(),
             checkpoints: ComponentStorage::new(),
             spawn_points: ComponentStorage::new(),
@@ -187,13 +258,20 @@ impl World {
         self.velocities.clear_slot(idx);
         self.controllers.clear_slot(idx);
         self.health.clear_slot(idx);
+        self.stamina.clear_slot(idx);
+        self.poise.clear_slot(idx);
+        self.combat.clear_slot(idx);
         self.hitboxes.clear_slot(idx);
         self.hurtboxes.clear_slot(idx);
+        self.status_effects.clear_slot(idx);
         self.players.clear_slot(idx);
         self.enemies.clear_slot(idx);
         self.projectiles.clear_slot(idx);
         self.items.clear_slot(idx);
         self.doors.clear_slot(idx);
+        self.path_followers.clear_slot(idx);
+        self.moving_platforms.clear_slot(idx);
+        self.patrols.clear_slot(idx);
         self.keys.clear_slot(idx);
         self.checkpoints.clear_slot(idx);
         self.spawn_points.clear_slot(idx);
@@ -212,11 +290,53 @@ impl World {
         self.entities.is_alive(entity)
     }
 
+    /// The live entity at a component storage index, if any
+    pub fn entity_at(&self, index: u32) -> Option<Entity> {
+        self.entities.entity_at(index)
+    }
+
     /// Get the number of alive entities.
     pub fn entity_count(&self) -> u32 {
         self.entities.alive_count()
     }
 
+    /// Every live entity, in slot order
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        (0..self.entities.capacity()).filter_map(|index| self.entities.entity_at(index))
+    }
+
+    // =========================================================================
+    // Queries
+    // =========================================================================
+
+    /// Iterate live entities that have every component in `Q`, e.g.
+    /// `world.query::<(&Transform, &mut Health)>()` (see `game::query`).
+    /// Panics if `Q` borrows a component mutably more than once.
+    pub fn query<Q: QueryData>(&mut self) -> QueryIter<'_, Q> {
+        query::check_access::<Q>();
+        let world: *mut World = self;
+        // SAFETY: the world stays uniquely borrowed for the iterator's lifetime,
+        // and check_access ruled out two items aliasing one storage slot
+        unsafe { QueryIter::new(Q::state(world), &(*world).entities) }
+    }
+
+    /// Read-only `query` on a shared world
+    pub fn query_ref<Q: ReadOnlyQuery>(&self) -> QueryIter<'_, Q> {
+        let world = self as *const World as *mut World;
+        // SAFETY: read-only queries never write through their state
+        unsafe { QueryIter::new(Q::state(world), &self.entities) }
+    }
+
+    /// A component storage by type (`world.storage::<Health>()`)
+    pub fn storage<T: Component>(&self) -> &ComponentStorage<T> {
+        T::storage(self)
+    }
+
+    /// A component storage by type, mutably
+    pub fn storage_mut<T: Component>(&mut self) -> &mut ComponentStorage<T> {
+        T::storage_mut(self)
+    }
+
     // =========================================================================
     // Hierarchy Helpers
     // =========================================================================
@@ -269,18 +389,24 @@ impl World {
         controller.step_height = settings.step_height;
         self.controllers.insert(entity, controller);
         self.health.insert(entity, Health::new(max_health));
+        self.stamina.insert(entity, Stamina::new(100.0));
+        self.poise.insert(entity, Poise::new(super::combat::PLAYER_POISE));
+        self.combat.insert(entity, Combat::new(super::combat::UNARMED_DAMAGE, AttackDef::light(), AttackDef::heavy()));
         self.velocities.insert(entity, Velocity::default());
         self.hurtboxes.insert(entity, Hurtbox::sphere(settings.radius));
         entity
     }
 
+    /// Body radius of spawned enemies (world units)
+    pub const ENEMY_RADIUS: f32 = 300.0;
+
     /// Spawn an enemy entity.
     pub fn spawn_enemy(&mut self, position: Vec3, max_health: i32, enemy_type: EnemyType) -> Entity {
         let entity = self.spawn_at(position);
         self.enemies.insert(entity, Enemy { enemy_type });
         self.health.insert(entity, Health::new(max_health));
         self.velocities.insert(entity, Velocity::default());
-        self.hurtboxes.insert(entity, Hurtbox::sphere(1.0));
+        self.hurtboxes.insert(entity, Hurtbox::sphere(Self::ENEMY_RADIUS));
         entity
     }
 
@@ -299,6 +425,7 @@ impl World {
         self.doors.insert(entity, Door {
             is_open: false,
             required_key,
+            open_amount: 0.0,
         });
         entity
     }
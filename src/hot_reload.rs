@@ -0,0 +1,281 @@
+//! Hot reload of files changed on disk (native only)
+//!
+//! Twice a second the user model, texture and song directories are checked
+//! for new, modified or deleted files, along with the open level, its streamed
+//! chunks and the open song. Changed resources are swapped in place so the
+//! Game tab keeps running (the player, enemies and level logic are untouched)
+//! and the editor viewports show the new version on the next frame.
+//!
+//! Changes are detected by comparing modification times, so no watcher thread
+//! or platform API is involved. A file the app saved itself reloads to the
+//! same data and is skipped, and a level or song with unsaved edits is never
+//! replaced (the status bar says it changed instead).
+//!
+//! Cloud storage has no local files to watch, so nothing happens while signed in.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Serialize;
+
+use crate::app::AppState;
+use crate::asset::{Asset, AssetSource, USER_ASSETS_DIR};
+use crate::texture::{TextureSource, UserTexture, USER_TEXTURES_DIR};
+use crate::tracker::{SongCategory, USER_SONGS_DIR};
+
+/// Seconds between checks
+pub const POLL_INTERVAL: f64 = 0.5;
+
+/// Modification times of a set of files, compared between updates
+#[derive(Debug, Default)]
+pub struct FileWatcher {
+    stamps: HashMap<PathBuf, SystemTime>,
+    primed: bool,
+}
+
+impl FileWatcher {
+    /// Check `files` and return the ones added, modified or removed since the
+    /// last update, sorted. The first update only records the current state.
+    pub fn update(&mut self, files: impl IntoIterator<Item = PathBuf>) -> Vec<PathBuf> {
+        let stamps: HashMap<PathBuf, SystemTime> = files
+            .into_iter()
+            .filter_map(|path| {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                Some((path, modified))
+            })
+            .collect();
+
+        let mut changed = Vec::new();
+        if self.primed {
+            changed.extend(stamps.iter().filter(|(path, time)| self.stamps.get(*path) != Some(*time)).map(|(path, _)| path.clone()));
+            changed.extend(self.stamps.keys().filter(|path| !stamps.contains_key(*path)).cloned());
+            changed.sort();
+        }
+        self.stamps = stamps;
+        self.primed = true;
+        changed
+    }
+
+    /// Forget the recorded state; the next update records it again
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// `.ron` files directly inside `dir`
+fn ron_files(dir: &str) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ron")))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// True if both serialize to the same RON (used to skip reloading our own saves)
+fn same_data<T: Serialize>(a: &T, b: &T) -> bool {
+    matches!((ron::to_string(a), ron::to_string(b)), (Ok(a), Ok(b)) if a == b)
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_stem().and_then(|s| s.to_str()).unwrap_or_default()
+}
+
+/// Watches the project's files and reloads what changed
+#[derive(Debug, Default)]
+pub struct HotReloader {
+    last_poll: f64,
+    models: FileWatcher,
+    textures: FileWatcher,
+    songs: FileWatcher,
+    /// Open level, its streamed chunks and the open song
+    open_files: FileWatcher,
+    /// Files `open_files` was recorded for (a different set starts over)
+    open_paths: Vec<PathBuf>,
+}
+
+impl HotReloader {
+    /// Check for changes once per interval and reload them
+    pub fn tick(&mut self, app: &mut AppState) {
+        let now = macroquad::time::get_time();
+        if now - self.last_poll < POLL_INTERVAL || app.storage.has_cloud() {
+            return;
+        }
+        self.last_poll = now;
+
+        let models = self.models.update(ron_files(USER_ASSETS_DIR));
+        if !models.is_empty() {
+            reload_models(app, &models);
+        }
+
+        let textures = self.textures.update(ron_files(USER_TEXTURES_DIR));
+        if !textures.is_empty() {
+            reload_textures(app, &textures);
+        }
+
+        if !self.songs.update(ron_files(USER_SONGS_DIR)).is_empty() {
+            app.tracker.song_browser.user_songs = crate::tracker::discover_songs_from_dir(USER_SONGS_DIR, SongCategory::User);
+        }
+
+        let open_paths: Vec<PathBuf> = app.world_editor.editor_state.current_file.iter()
+            .cloned()
+            .chain(app.world_editor.editor_state.level.streaming.chunks.iter().map(|chunk| PathBuf::from(&chunk.path)))
+            .chain(app.tracker.current_file.iter().cloned())
+            .collect();
+        if open_paths != self.open_paths {
+            self.open_files.reset();
+            self.open_paths = open_paths.clone();
+        }
+        for path in self.open_files.update(open_paths) {
+            if !path.exists() {
+                continue;
+            }
+            if app.world_editor.editor_state.current_file.as_ref() == Some(&path) {
+                reload_level(app, &path);
+            } else if app.tracker.current_file.as_ref() == Some(&path) {
+                reload_song(app, &path);
+            } else {
+                // A streamed chunk: drop the loaded chunks, the ones around the player load again
+                app.game.streamer.clear();
+                app.world_editor.editor_state.set_status(&format!("Reloaded chunk {}", file_name(&path)), 2.0);
+            }
+        }
+    }
+}
+
+/// Reload changed user models into the World Editor's library (used by the Game tab too)
+fn reload_models(app: &mut AppState, paths: &[PathBuf]) {
+    let library = &mut app.world_editor.editor_state.asset_library;
+    let mut reloaded = Vec::new();
+    for path in paths {
+        let key = format!("{}{}", AssetSource::User.prefix(), file_name(path));
+        if !path.exists() {
+            if library.remove(&key).is_some() {
+                reloaded.push(file_name(path));
+            }
+            continue;
+        }
+        match Asset::load(path) {
+            Ok(asset) if library.get(&key).is_some_and(|old| same_data(old, &asset)) => {}
+            Ok(mut asset) => {
+                if library.contains(&key) {
+                    let _ = library.reload_asset(&key);
+                } else {
+                    asset.source = AssetSource::User;
+                    library.add(asset);
+                }
+                reloaded.push(file_name(path));
+            }
+            Err(e) => eprintln!("Hot reload of {:?} failed: {}", path, e),
+        }
+    }
+    match reloaded.as_slice() {
+        [] => {}
+        [name] => app.world_editor.editor_state.set_status(&format!("Reloaded model {}", name), 2.0),
+        names => app.world_editor.editor_state.set_status(&format!("Reloaded {} models", names.len()), 2.0),
+    }
+}
+
+/// Reload changed user textures into the World Editor's and Modeler's libraries
+fn reload_textures(app: &mut AppState, paths: &[PathBuf]) {
+    let editor = &mut app.world_editor.editor_state;
+    let modeler = &mut app.modeler.modeler_state;
+    let mut reloaded = Vec::new();
+    for path in paths {
+        if !path.exists() {
+            modeler.user_textures.remove(file_name(path));
+            if editor.user_textures.remove(file_name(path)).is_some() {
+                reloaded.push(file_name(path));
+            }
+            continue;
+        }
+        match UserTexture::load(path) {
+            Ok(texture) if editor.user_textures.get(&texture.name).is_some_and(|old| same_data(old, &texture)) => {}
+            Ok(mut texture) => {
+                texture.source = TextureSource::User;
+                modeler.user_textures.add(texture.clone());
+                editor.user_textures.add(texture);
+                reloaded.push(file_name(path));
+            }
+            Err(e) => eprintln!("Hot reload of {:?} failed: {}", path, e),
+        }
+    }
+    if reloaded.is_empty() {
+        return;
+    }
+    // RGB555 copies are rebuilt from the new pixels
+    editor.texture_generation = editor.texture_generation.wrapping_add(1);
+    app.game.textures_15_cache.clear();
+    match reloaded.as_slice() {
+        [name] => editor.set_status(&format!("Reloaded texture {}", name), 2.0),
+        names => editor.set_status(&format!("Reloaded {} textures", names.len()), 2.0),
+    }
+}
+
+/// Reload the open level unless it has unsaved edits
+fn reload_level(app: &mut AppState, path: &Path) {
+    let editor = &mut app.world_editor.editor_state;
+    match crate::world::load_level(path) {
+        Ok(level) if same_data(&level, &editor.level) => {}
+        Ok(_) if editor.dirty => editor.set_status("Level changed on disk (not reloaded: unsaved edits)", 4.0),
+        Ok(level) => {
+            editor.reload_level(level);
+            editor.set_status("Level reloaded from disk", 2.0);
+        }
+        Err(e) => eprintln!("Hot reload of {:?} failed: {}", path, e),
+    }
+}
+
+/// Reload the open song unless it has unsaved edits
+fn reload_song(app: &mut AppState, path: &Path) {
+    let tracker = &mut app.tracker;
+    match crate::tracker::load_song(path) {
+        Ok(song) if same_data(&song, &tracker.song) => {}
+        Ok(_) if tracker.dirty => tracker.set_status("Song changed on disk (not reloaded: unsaved edits)", 4.0),
+        Ok(song) => {
+            tracker.reload_song(song);
+            tracker.set_status("Song reloaded from disk", 2.0);
+        }
+        Err(e) => eprintln!("Hot reload of {:?} failed: {}", path, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn touch(path: &Path, time: SystemTime) {
+        std::fs::write(path, b"()").unwrap();
+        std::fs::File::options().write(true).open(path).unwrap().set_modified(time).unwrap();
+    }
+
+    #[test]
+    fn test_watcher_reports_added_modified_and_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_str = dir.path().to_str().unwrap();
+        let (a, b) = (dir.path().join("a.ron"), dir.path().join("b.ron"));
+        let start = SystemTime::now() - Duration::from_secs(60);
+        touch(&a, start);
+        std::fs::write(dir.path().join("notes.txt"), b"ignored").unwrap();
+
+        let mut watcher = FileWatcher::default();
+        assert!(watcher.update(ron_files(dir_str)).is_empty());
+        assert!(watcher.update(ron_files(dir_str)).is_empty());
+
+        touch(&a, start + Duration::from_secs(1));
+        touch(&b, start);
+        assert_eq!(watcher.update(ron_files(dir_str)), vec![a.clone(), b]);
+
+        std::fs::remove_file(&a).unwrap();
+        assert_eq!(watcher.update(ron_files(dir_str)), vec![a]);
+
+        watcher.reset();
+        touch(&dir.path().join("c.ron"), start);
+        assert!(watcher.update(ron_files(dir_str)).is_empty());
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! Based on Elden Ring controller layout for familiar Souls-like controls.
 
+use super::controller_type::ButtonPosition;
+
 /// All possible game/editor actions that can be triggered by input
 ///
 /// Button mappings (Xbox/PlayStation):
@@ -56,8 +58,129 @@ pub enum Action {
     // System
     OpenMenu,       // Start - opens options/pause menu
     OpenMap,        // Select/Back
+    OpenInventory,  // Select/Back - equipment (shares the button until there's a map)
 
     // Free-fly mode (editor + game option)
     FlyUp,          // LB in free-fly / Q on keyboard
     FlyDown,        // LT in free-fly / E on keyboard
 }
+
+impl Action {
+    /// Button actions that scripts can name (tutorial prompts)
+    pub const NAMED: [Action; 17] = [
+        Action::Jump,
+        Action::Dodge,
+        Action::Attack,
+        Action::StrongAttack,
+        Action::Skill,
+        Action::Guard,
+        Action::UseItem,
+        Action::Interact,
+        Action::Crouch,
+        Action::LockOn,
+        Action::SwitchLeftWeapon,
+        Action::SwitchRightWeapon,
+        Action::SwitchSpell,
+        Action::SwitchItem,
+        Action::OpenMenu,
+        Action::OpenMap,
+        Action::OpenInventory,
+    ];
+
+    /// Script name (snake_case), or None for analog actions
+    pub fn name(&self) -> Option<&'static str> {
+        Some(match self {
+            Action::Jump => "jump",
+            Action::Dodge => "dodge",
+            Action::Attack => "attack",
+            Action::StrongAttack => "strong_attack",
+            Action::Skill => "skill",
+            Action::Guard => "guard",
+            Action::UseItem => "use_item",
+            Action::Interact => "interact",
+            Action::Crouch => "crouch",
+            Action::LockOn => "lock_on",
+            Action::SwitchLeftWeapon => "switch_left",
+            Action::SwitchRightWeapon => "switch_right",
+            Action::SwitchSpell => "switch_spell",
+            Action::SwitchItem => "switch_item",
+            Action::OpenMenu => "menu",
+            Action::OpenMap => "map",
+            Action::OpenInventory => "inventory",
+            _ => return None,
+        })
+    }
+
+    /// Parse a script name (see `name`)
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::NAMED.iter().copied().find(|a| a.name() == Some(name))
+    }
+
+    /// Keyboard key shown in prompts (matches `InputState`'s bindings)
+    pub fn key_label(&self) -> &'static str {
+        match self {
+            Action::Jump => "Space",
+            Action::Dodge => "Shift",
+            Action::Attack => "J",
+            Action::StrongAttack => "K",
+            Action::Guard => "L",
+            Action::Skill => "I",
+            Action::UseItem => "R",
+            Action::Interact => "E",
+            Action::Crouch => "C",
+            Action::LockOn => "Tab",
+            Action::OpenMenu => "Esc",
+            Action::OpenInventory => "B",
+            Action::FlyUp => "Q",
+            Action::FlyDown => "E",
+            Action::MoveForward => "W",
+            Action::MoveBackward => "S",
+            Action::MoveLeft => "A",
+            Action::MoveRight => "D",
+            Action::SwitchLeftWeapon => "Left",
+            Action::SwitchRightWeapon => "Right",
+            Action::SwitchSpell => "Up",
+            Action::SwitchItem => "Down",
+            _ => "?",
+        }
+    }
+
+    /// Gamepad button bound to this action (Elden Ring layout)
+    pub fn button(&self) -> Option<ButtonPosition> {
+        Some(match self {
+            Action::Jump => ButtonPosition::South,
+            Action::Dodge => ButtonPosition::East,
+            Action::UseItem => ButtonPosition::West,
+            Action::Interact => ButtonPosition::North,
+            Action::Guard | Action::FlyUp => ButtonPosition::LeftBumper,
+            Action::Skill | Action::FlyDown => ButtonPosition::LeftTrigger,
+            Action::Attack => ButtonPosition::RightBumper,
+            Action::StrongAttack => ButtonPosition::RightTrigger,
+            Action::Crouch => ButtonPosition::LeftStick,
+            Action::LockOn => ButtonPosition::RightStick,
+            Action::SwitchLeftWeapon => ButtonPosition::DPadLeft,
+            Action::SwitchRightWeapon => ButtonPosition::DPadRight,
+            Action::SwitchSpell => ButtonPosition::DPadUp,
+            Action::SwitchItem => ButtonPosition::DPadDown,
+            Action::OpenMenu => ButtonPosition::Start,
+            Action::OpenMap | Action::OpenInventory => ButtonPosition::Select,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_round_trip() {
+        for action in Action::NAMED {
+            let name = action.name().unwrap();
+            assert_eq!(Action::from_name(name), Some(action));
+            assert!(action.button().is_some(), "{} has no button", name);
+        }
+        assert_eq!(Action::from_name("move_forward"), None);
+        assert_eq!(Action::MoveForward.name(), None);
+    }
+}
@@ -150,6 +150,7 @@ fn build_action_labels(labels: &ButtonLabels) -> Vec<(Action, String)> {
         (Action::LockOn, format!("Lock-On ({})", labels.right_stick())),
         (Action::OpenMenu, format!("Menu ({})", labels.start())),
         (Action::OpenMap, format!("Map ({})", labels.select())),
+        (Action::OpenInventory, format!("Inventory ({})", labels.select())),
         (Action::SwitchLeftWeapon, labels.dpad_left().to_string()),
         (Action::SwitchRightWeapon, labels.dpad_right().to_string()),
         (Action::SwitchSpell, labels.dpad_up().to_string()),
@@ -5,6 +5,7 @@
 //!
 //! Native: Uses gilrs crate for cross-platform gamepad input
 //! WASM: Uses custom Web Gamepad API bindings (avoids RefCell conflicts)
+//! Touch: on-screen stick and buttons over the game view (see `touch`)
 
 // Allow unused - input system scaffolding for future game runtime
 #![allow(dead_code)]
@@ -15,6 +16,7 @@ mod gamepad;
 mod midi;
 mod state;
 mod debug;
+mod touch;
 
 pub use actions::*;
 pub use controller_type::{ControllerType, ButtonLabels};
@@ -23,3 +25,4 @@ pub use gamepad::{Gamepad, button};
 pub use midi::{MidiInput, MidiMessage};
 pub use state::*;
 pub use debug::draw_controller_debug;
+pub use touch::TouchControls;
@@ -3,24 +3,54 @@
 //! Polls both keyboard (macroquad) and gamepad input, combining them into
 //! a unified action-based API.
 
+use std::collections::HashSet;
 use macroquad::prelude::*;
-use super::{Action, Gamepad, button, ControllerType, ButtonLabels};
+use super::{Action, Gamepad, button, ControllerType, ButtonLabels, TouchControls};
+use crate::ui::{GamepadNavInput, NavDirection};
 
 /// Unified input state that handles both keyboard/mouse and gamepad
 pub struct InputState {
     gamepad: Gamepad,
+    /// On-screen controls for touch devices
+    touch: TouchControls,
+    /// Actions held by the test console (see `game::console`)
+    injected_down: HashSet<Action>,
+    /// Injected actions that went down this frame
+    injected_pressed: HashSet<Action>,
 }
 
 impl InputState {
     pub fn new() -> Self {
         Self {
             gamepad: Gamepad::new(),
+            touch: TouchControls::new(),
+            injected_down: HashSet::new(),
+            injected_pressed: HashSet::new(),
         }
     }
 
     /// Call once per frame before checking actions
     pub fn poll(&mut self) {
         self.gamepad.poll();
+        self.touch.update(&touches(), get_frame_time());
+        self.injected_pressed.clear();
+    }
+
+    /// Hold or release an action as if a button were pressed (test console)
+    pub fn inject(&mut self, action: Action, down: bool) {
+        if down {
+            if self.injected_down.insert(action) {
+                self.injected_pressed.insert(action);
+            }
+        } else {
+            self.injected_down.remove(&action);
+        }
+    }
+
+    /// Release every injected action
+    pub fn clear_injected(&mut self) {
+        self.injected_down.clear();
+        self.injected_pressed.clear();
     }
 
     /// Get left stick as Vec2 (movement)
@@ -34,10 +64,17 @@ impl InputState {
         if is_key_down(KeyCode::A) { result.x -= 1.0; }
         if is_key_down(KeyCode::D) { result.x += 1.0; }
 
-        // Gamepad left stick (take if larger magnitude)
-        let gp_stick = self.gamepad.left_stick();
-        if gp_stick.length() > result.length() {
-            result = gp_stick;
+        // Injected movement (test console)
+        if self.injected_down.contains(&Action::MoveForward) { result.y += 1.0; }
+        if self.injected_down.contains(&Action::MoveBackward) { result.y -= 1.0; }
+        if self.injected_down.contains(&Action::MoveLeft) { result.x -= 1.0; }
+        if self.injected_down.contains(&Action::MoveRight) { result.x += 1.0; }
+
+        // Gamepad and touch sticks (take if larger magnitude)
+        for stick in [self.gamepad.left_stick(), self.touch.left_stick()] {
+            if stick.length() > result.length() {
+                result = stick;
+            }
         }
 
         // Normalize if > 1 (diagonal keyboard input)
@@ -48,9 +85,11 @@ impl InputState {
     }
 
     /// Get right stick as Vec2 (camera look)
-    /// Only from gamepad - mouse handled separately
+    /// From gamepad or touch swipes - mouse handled separately
     pub fn right_stick(&self) -> Vec2 {
-        self.gamepad.right_stick()
+        let stick = self.gamepad.right_stick();
+        let swipe = self.touch.right_stick();
+        if swipe.length() > stick.length() { swipe } else { stick }
     }
 
     /// Get left stick from gamepad only (no keyboard WASD)
@@ -59,14 +98,39 @@ impl InputState {
         self.gamepad.left_stick()
     }
 
+    /// Gamepad input for the editors' virtual cursor (gamepad only: the
+    /// keyboard and mouse keep working as usual)
+    pub fn gamepad_nav(&self) -> GamepadNavInput {
+        let pad = &self.gamepad;
+        let direction = [
+            (button::DPAD_UP, NavDirection::Up),
+            (button::DPAD_DOWN, NavDirection::Down),
+            (button::DPAD_LEFT, NavDirection::Left),
+            (button::DPAD_RIGHT, NavDirection::Right),
+        ]
+        .into_iter()
+        .find(|&(b, _)| pad.is_button_pressed(b))
+        .map(|(_, direction)| direction);
+        GamepadNavInput {
+            left_stick: pad.left_stick(),
+            right_stick: pad.right_stick(),
+            direction,
+            confirm_down: pad.is_button_down(button::A),
+            cancel_pressed: pad.is_button_pressed(button::B),
+            camera_held: pad.is_button_down(button::RB),
+        }
+    }
+
     /// Check if action is currently held down
     pub fn action_down(&self, action: Action) -> bool {
-        self.keyboard_down(action) || self.gamepad_down(action)
+        self.keyboard_down(action) || self.gamepad_down(action) || self.touch.action_down(action)
+            || self.injected_down.contains(&action)
     }
 
     /// Check if action was just pressed this frame
     pub fn action_pressed(&self, action: Action) -> bool {
-        self.keyboard_pressed(action) || self.gamepad_pressed(action)
+        self.keyboard_pressed(action) || self.gamepad_pressed(action) || self.touch.action_pressed(action)
+            || self.injected_pressed.contains(&action)
     }
 
     fn keyboard_down(&self, action: Action) -> bool {
@@ -91,6 +155,7 @@ impl InputState {
 
             // System
             Action::OpenMenu => is_key_down(KeyCode::Escape),
+            Action::OpenInventory => is_key_down(KeyCode::B),
 
             // Free-fly
             Action::FlyUp => is_key_down(KeyCode::Q),
@@ -126,7 +191,7 @@ impl InputState {
 
             // System
             Action::OpenMenu => self.gamepad.is_button_down(button::START),
-            Action::OpenMap => self.gamepad.is_button_down(button::SELECT),
+            Action::OpenMap | Action::OpenInventory => self.gamepad.is_button_down(button::SELECT),
 
             // Free-fly mode (reuses LB/LT)
             Action::FlyUp => self.gamepad.is_button_down(button::LB),
@@ -144,8 +209,16 @@ impl InputState {
             Action::StrongAttack => is_key_pressed(KeyCode::K),
             Action::Interact => is_key_pressed(KeyCode::E),
             Action::OpenMenu => is_key_pressed(KeyCode::Escape),
+            Action::OpenInventory => is_key_pressed(KeyCode::B),
             Action::LockOn => is_key_pressed(KeyCode::Tab),
             Action::Crouch => is_key_pressed(KeyCode::C),
+            Action::Guard => is_key_pressed(KeyCode::L),
+            Action::Skill => is_key_pressed(KeyCode::I),
+            Action::UseItem => is_key_pressed(KeyCode::R),
+            Action::SwitchLeftWeapon => is_key_pressed(KeyCode::Left),
+            Action::SwitchRightWeapon => is_key_pressed(KeyCode::Right),
+            Action::SwitchSpell => is_key_pressed(KeyCode::Up),
+            Action::SwitchItem => is_key_pressed(KeyCode::Down),
             _ => false,
         }
     }
@@ -158,6 +231,7 @@ impl InputState {
             Action::StrongAttack => self.gamepad.is_button_pressed(button::RT),
             Action::Interact => self.gamepad.is_button_pressed(button::Y),
             Action::OpenMenu => self.gamepad.is_button_pressed(button::START),
+            Action::OpenInventory => self.gamepad.is_button_pressed(button::SELECT),
             Action::LockOn => self.gamepad.is_button_pressed(button::R3),
             Action::Crouch => self.gamepad.is_button_pressed(button::L3),
             Action::UseItem => self.gamepad.is_button_pressed(button::X),
@@ -171,6 +245,15 @@ impl InputState {
         }
     }
 
+    /// On-screen touch controls (the game view sets their area and draws them)
+    pub fn touch(&self) -> &TouchControls {
+        &self.touch
+    }
+
+    pub fn touch_mut(&mut self) -> &mut TouchControls {
+        &mut self.touch
+    }
+
     /// Check if any gamepad is connected
     pub fn has_gamepad(&self) -> bool {
         self.gamepad.has_gamepad()
@@ -191,6 +274,15 @@ impl InputState {
         ButtonLabels::new(self.controller_type())
     }
 
+    /// Button or key to show in a prompt for `action`: the gamepad label when
+    /// a controller is connected, otherwise the keyboard key
+    pub fn prompt_label(&self, action: Action) -> &'static str {
+        match action.button() {
+            Some(button) if self.has_gamepad() => button.label(self.controller_type()),
+            _ => action.key_label(),
+        }
+    }
+
     /// Get the current stick deadzone (0.0-0.5)
     pub fn deadzone(&self) -> f32 {
         self.gamepad.deadzone()
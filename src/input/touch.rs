@@ -0,0 +1,315 @@
+//! Touch controls for phones and tablets
+//!
+//! A floating movement stick on the left of the game view, a camera swipe
+//! area on the right and on-screen action buttons. They feed `InputState`
+//! the same way the gamepad does, so the game runtime does not care where
+//! input comes from.
+//!
+//! The overlay only runs while the game view hands it an area each frame
+//! (`set_area`) and stays hidden until the first touch, so desktop users
+//! never see it.
+
+use std::collections::{HashMap, HashSet};
+use macroquad::prelude::*;
+use super::Action;
+
+/// Stick travel for full deflection, as a fraction of the area height
+const STICK_RADIUS: f32 = 0.12;
+/// Button radius, as a fraction of the area height
+const BUTTON_RADIUS: f32 = 0.065;
+/// Touches this far outside a button (relative to its radius) still hit it
+const BUTTON_SLOP: f32 = 1.25;
+/// Share of the area width, from the left, where a touch grabs the stick
+const STICK_ZONE: f32 = 0.45;
+/// Swipe speed (pixels per second) that turns the camera as fast as a fully
+/// deflected right stick
+const SWIPE_FULL_SPEED: f32 = 500.0;
+
+/// An on-screen button and the action it triggers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchButton {
+    pub action: Action,
+    /// Center as a fraction of the area (0,0 top-left, 1,1 bottom-right)
+    pub x: f32,
+    pub y: f32,
+}
+
+impl TouchButton {
+    pub const fn new(action: Action, x: f32, y: f32) -> Self {
+        Self { action, x, y }
+    }
+}
+
+/// Which buttons are on screen and where
+#[derive(Debug, Clone, PartialEq)]
+pub struct TouchLayout {
+    pub buttons: Vec<TouchButton>,
+}
+
+impl Default for TouchLayout {
+    /// Face buttons in a diamond at the bottom right (like a gamepad),
+    /// shoulder actions above them and the menu in the top corner
+    fn default() -> Self {
+        Self {
+            buttons: vec![
+                TouchButton::new(Action::Jump, 0.86, 0.88),
+                TouchButton::new(Action::Dodge, 0.94, 0.76),
+                TouchButton::new(Action::UseItem, 0.78, 0.76),
+                TouchButton::new(Action::Interact, 0.86, 0.64),
+                TouchButton::new(Action::Attack, 0.94, 0.50),
+                TouchButton::new(Action::Guard, 0.78, 0.50),
+                TouchButton::new(Action::LockOn, 0.66, 0.88),
+                TouchButton::new(Action::OpenMenu, 0.94, 0.22),
+            ],
+        }
+    }
+}
+
+impl TouchLayout {
+    /// Point the button at `index` at a different action
+    pub fn bind(&mut self, index: usize, action: Action) {
+        if let Some(button) = self.buttons.get_mut(index) {
+            button.action = action;
+        }
+    }
+
+    /// Button under a screen position in `area`
+    fn button_at(&self, area: Rect, pos: Vec2) -> Option<&TouchButton> {
+        let radius = BUTTON_RADIUS * area.h * BUTTON_SLOP;
+        self.buttons.iter().find(|b| button_center(area, b).distance(pos) <= radius)
+    }
+}
+
+fn button_center(area: Rect, button: &TouchButton) -> Vec2 {
+    vec2(area.x + button.x * area.w, area.y + button.y * area.h)
+}
+
+/// Touch state turned into stick and button input
+#[derive(Debug, Default)]
+pub struct TouchControls {
+    pub layout: TouchLayout,
+    /// Area handed over by the game view for the coming frame
+    next_area: Option<Rect>,
+    /// Area the touches of the current frame were read against
+    area: Option<Rect>,
+    /// A touch was seen since startup: show the overlay
+    seen_touch: bool,
+    /// Movement stick: touch id, where it went down, where it is now
+    stick: Option<(u64, Vec2, Vec2)>,
+    /// Camera swipe: touch id and its last position
+    look: Option<(u64, Vec2)>,
+    look_stick: Vec2,
+    /// Buttons held, by touch id
+    held: HashMap<u64, Action>,
+    down: HashSet<Action>,
+    pressed: HashSet<Action>,
+}
+
+impl TouchControls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable the controls over `area` (screen pixels) for the next frame.
+    /// Call every frame the game view is drawn.
+    pub fn set_area(&mut self, area: Rect) {
+        self.next_area = Some(area);
+    }
+
+    /// Whether the overlay should be drawn (a touch screen is in use)
+    pub fn visible(&self) -> bool {
+        self.seen_touch && self.area.is_some()
+    }
+
+    /// Read this frame's touches. `delta` is the frame time in seconds.
+    pub fn update(&mut self, touches: &[Touch], delta: f32) {
+        self.area = self.next_area.take();
+        self.pressed.clear();
+        self.look_stick = Vec2::ZERO;
+        if !touches.is_empty() {
+            self.seen_touch = true;
+        }
+        let Some(area) = self.area else {
+            self.release_all();
+            return;
+        };
+
+        for touch in touches {
+            let pos = touch.position;
+            match touch.phase {
+                TouchPhase::Started => {
+                    if let Some(button) = self.layout.button_at(area, pos) {
+                        self.held.insert(touch.id, button.action);
+                    } else if !area.contains(pos) {
+                        // Toolbars and tabs outside the game view
+                    } else if pos.x < area.x + area.w * STICK_ZONE {
+                        if self.stick.is_none() {
+                            self.stick = Some((touch.id, pos, pos));
+                        }
+                    } else if self.look.is_none() {
+                        self.look = Some((touch.id, pos));
+                    }
+                }
+                TouchPhase::Moved | TouchPhase::Stationary => {
+                    if let Some((id, _, current)) = &mut self.stick {
+                        if *id == touch.id {
+                            *current = pos;
+                        }
+                    }
+                    if let Some((id, last)) = &mut self.look {
+                        if *id == touch.id {
+                            let moved = pos - *last;
+                            *last = pos;
+                            // Y inverted like a stick, so swipes turn the camera like mouse drags
+                            self.look_stick = vec2(moved.x, -moved.y) / (SWIPE_FULL_SPEED * delta.max(1e-4));
+                        }
+                    }
+                }
+                TouchPhase::Ended | TouchPhase::Cancelled => self.end_touch(touch.id),
+            }
+        }
+
+        // Touches that vanished without an end event
+        let alive: HashSet<u64> = touches.iter().map(|t| t.id).collect();
+        self.held.retain(|id, _| alive.contains(id));
+        if self.stick.is_some_and(|(id, _, _)| !alive.contains(&id)) {
+            self.stick = None;
+        }
+        if self.look.is_some_and(|(id, _)| !alive.contains(&id)) {
+            self.look = None;
+        }
+
+        let down: HashSet<Action> = self.held.values().copied().collect();
+        self.pressed = down.difference(&self.down).copied().collect();
+        self.down = down;
+    }
+
+    fn end_touch(&mut self, id: u64) {
+        self.held.remove(&id);
+        if self.stick.is_some_and(|(stick_id, _, _)| stick_id == id) {
+            self.stick = None;
+        }
+        if self.look.is_some_and(|(look_id, _)| look_id == id) {
+            self.look = None;
+        }
+    }
+
+    fn release_all(&mut self) {
+        self.stick = None;
+        self.look = None;
+        self.held.clear();
+        self.down.clear();
+    }
+
+    /// Movement stick, -1..1 per axis (up is +Y like the gamepad)
+    pub fn left_stick(&self) -> Vec2 {
+        let (Some((_, origin, current)), Some(area)) = (self.stick, self.area) else {
+            return Vec2::ZERO;
+        };
+        let offset = (current - origin) / (STICK_RADIUS * area.h);
+        vec2(offset.x, -offset.y).clamp_length_max(1.0)
+    }
+
+    /// Camera swipe as a right stick value
+    pub fn right_stick(&self) -> Vec2 {
+        self.look_stick
+    }
+
+    pub fn action_down(&self, action: Action) -> bool {
+        self.down.contains(&action)
+    }
+
+    pub fn action_pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    /// Draw the stick and buttons over the game view
+    pub fn draw(&self) {
+        let Some(area) = self.area.filter(|_| self.seen_touch) else {
+            return;
+        };
+        let fill = Color::new(1.0, 1.0, 1.0, 0.15);
+        let held_fill = Color::new(1.0, 1.0, 1.0, 0.4);
+        let outline = Color::new(1.0, 1.0, 1.0, 0.5);
+
+        if let Some((_, origin, _)) = self.stick {
+            let radius = STICK_RADIUS * area.h;
+            draw_circle(origin.x, origin.y, radius, fill);
+            draw_circle_lines(origin.x, origin.y, radius, 2.0, outline);
+            let knob = self.left_stick() * radius;
+            draw_circle(origin.x + knob.x, origin.y - knob.y, radius * 0.4, held_fill);
+        }
+
+        let radius = BUTTON_RADIUS * area.h;
+        for button in &self.layout.buttons {
+            let center = button_center(area, button);
+            let color = if self.down.contains(&button.action) { held_fill } else { fill };
+            draw_circle(center.x, center.y, radius, color);
+            draw_circle_lines(center.x, center.y, radius, 2.0, outline);
+            let label = button.action.name().unwrap_or("?");
+            let dims = measure_text(label, None, 14, 1.0);
+            draw_text(label, (center.x - dims.width / 2.0).floor(), (center.y + dims.height / 2.0).floor(), 14.0, WHITE);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(id: u64, phase: TouchPhase, x: f32, y: f32) -> Touch {
+        Touch { id, phase, position: vec2(x, y) }
+    }
+
+    fn controls() -> TouchControls {
+        let mut controls = TouchControls::new();
+        controls.set_area(Rect::new(0.0, 0.0, 1000.0, 500.0));
+        controls
+    }
+
+    #[test]
+    fn test_stick_follows_drag() {
+        let mut c = controls();
+        c.update(&[touch(1, TouchPhase::Started, 100.0, 300.0)], 0.016);
+        assert_eq!(c.left_stick(), Vec2::ZERO);
+        c.set_area(Rect::new(0.0, 0.0, 1000.0, 500.0));
+        // Half the stick radius to the right, a full radius up (clamped to 1)
+        c.update(&[touch(1, TouchPhase::Moved, 130.0, 180.0)], 0.016);
+        let stick = c.left_stick();
+        assert!(stick.y > 0.9 && stick.x > 0.0 && stick.length() <= 1.0);
+        c.set_area(Rect::new(0.0, 0.0, 1000.0, 500.0));
+        c.update(&[touch(1, TouchPhase::Ended, 130.0, 180.0)], 0.016);
+        assert_eq!(c.left_stick(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_button_press_and_hold() {
+        let mut c = controls();
+        let jump = c.layout.buttons[0];
+        let (x, y) = (jump.x * 1000.0, jump.y * 500.0);
+        c.update(&[touch(7, TouchPhase::Started, x, y)], 0.016);
+        assert!(c.action_pressed(Action::Jump) && c.action_down(Action::Jump));
+        c.set_area(Rect::new(0.0, 0.0, 1000.0, 500.0));
+        c.update(&[touch(7, TouchPhase::Stationary, x, y)], 0.016);
+        assert!(!c.action_pressed(Action::Jump) && c.action_down(Action::Jump));
+        // Rebinding changes what the button triggers
+        c.layout.bind(0, Action::Crouch);
+        c.set_area(Rect::new(0.0, 0.0, 1000.0, 500.0));
+        c.update(&[touch(8, TouchPhase::Started, x, y)], 0.016);
+        assert!(c.action_pressed(Action::Crouch));
+    }
+
+    #[test]
+    fn test_swipe_and_release_without_area() {
+        let mut c = controls();
+        c.update(&[touch(2, TouchPhase::Started, 500.0, 200.0)], 0.5);
+        c.set_area(Rect::new(0.0, 0.0, 1000.0, 500.0));
+        c.update(&[touch(2, TouchPhase::Moved, 750.0, 200.0)], 0.5);
+        assert_eq!(c.right_stick(), vec2(1.0, 0.0));
+        assert!(c.visible());
+        // The game view stopped drawing: everything lets go
+        c.update(&[touch(2, TouchPhase::Moved, 760.0, 200.0)], 0.5);
+        assert!(!c.visible());
+        assert_eq!(c.right_stick(), Vec2::ZERO);
+    }
+}
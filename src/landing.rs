@@ -1,9 +1,11 @@
 //! Landing page / Home tab
 //!
-//! Displays introduction, motivation, and FAQ for BONNIE-32.
+//! Displays introduction, motivation, and FAQ for BONNIE-32, plus the
+//! editor appearance settings.
 
 use macroquad::prelude::*;
-use crate::ui::{Rect, draw_link_row};
+use crate::display::DisplaySettings;
+use crate::ui::{Rect, draw_link_row, theme, ThemePreset};
 use crate::VERSION;
 
 /// Wrap text to fit within a given pixel width
@@ -53,13 +55,22 @@ fn wrap_text(text: &str, font_size: f32, max_width: f32) -> Vec<String> {
     lines
 }
 
-/// Colors matching the editor theme
-const BG_COLOR: Color = Color::new(0.10, 0.10, 0.12, 1.0);
-const TEXT_COLOR: Color = Color::new(0.9, 0.9, 0.9, 1.0);
+/// Landing-only colors (the rest come from the editor theme)
 const MUTED_COLOR: Color = Color::new(0.6, 0.6, 0.65, 1.0);
-const ACCENT_COLOR: Color = Color::new(0.0, 0.75, 0.9, 1.0);
 const SECTION_BG: Color = Color::new(0.12, 0.12, 0.14, 1.0);
 
+/// Accent colors offered in the appearance section (RGB)
+const ACCENT_SWATCHES: [[u8; 3]; 8] = [
+    [0, 191, 230],   // Cyan (default)
+    [80, 140, 255],  // Blue
+    [160, 100, 255], // Purple
+    [255, 90, 170],  // Pink
+    [255, 80, 80],   // Red
+    [255, 150, 40],  // Orange
+    [255, 217, 0],   // Yellow
+    [90, 210, 110],  // Green
+];
+
 /// State for the landing page (scroll position)
 pub struct LandingState {
     pub scroll_y: f32,
@@ -78,9 +89,9 @@ impl LandingState {
 }
 
 /// Draw the landing page
-pub fn draw_landing(rect: Rect, state: &mut LandingState, ctx: &crate::ui::UiContext) {
+pub fn draw_landing(rect: Rect, state: &mut LandingState, ctx: &crate::ui::UiContext, display: &mut DisplaySettings) {
     // Background
-    draw_rectangle(rect.x, rect.y, rect.w, rect.h, BG_COLOR);
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().bg);
 
     // Handle scrolling - clamp immediately using previous frame's bounds
     let scroll_delta = ctx.mouse.scroll * 3.0;
@@ -117,7 +128,7 @@ pub fn draw_landing(rect: Rect, state: &mut LandingState, ctx: &crate::ui::UiCon
     } else {
         // Fallback to text title
         let title = format!("BONNIE-32 v{}", VERSION);
-        draw_text(&title, content_x, y + 32.0, 32.0, ACCENT_COLOR);
+        draw_text(&title, content_x, y + 32.0, 32.0, theme().accent);
         y += 44.0;
     }
 
@@ -142,8 +153,11 @@ pub fn draw_landing(rect: Rect, state: &mut LandingState, ctx: &crate::ui::UiCon
         "Use the tabs at the top to switch between the available tools:\n\nWorld - Build levels using a sector-based editor in the style of the Tomb Raider Level Editor. Features a 2D grid view, 3D preview, and portals.\n\nAssets - A low-poly mesh modeler featuring Blender-style controls, extrusion, multi-object editing, and a shared texture atlas. Heavily influenced by PicoCAD.\n\nPaint - Create indexed textures with limited palettes. Draw with 4-bit or 8-bit color depth, apply dithering patterns, and manage a library of reusable textures.\n\nMusic - A pattern-based tracker for composing music. Supports SF2 soundfonts, up to 8 channels, and classic tracker effects like arpeggio and vibrato."
     );
 
+    // === APPEARANCE SECTION ===
+    y = draw_appearance(content_x, y, content_width, ctx, display);
+
     // === FAQ SECTION ===
-    draw_text("FAQ", content_x, y + 16.0, 16.0, ACCENT_COLOR);
+    draw_text("FAQ", content_x, y + 16.0, 16.0, theme().accent);
     y += 30.0;
 
     y = draw_faq_item(content_x, y, content_width,
@@ -183,12 +197,12 @@ pub fn draw_landing(rect: Rect, state: &mut LandingState, ctx: &crate::ui::UiCon
 
     // === FOOTER ===
     y += 20.0;
-    draw_text("Created by Emanuele Bonura", content_x, y + 16.0, 16.0, TEXT_COLOR);
+    draw_text("Created by Emanuele Bonura", content_x, y + 16.0, 16.0, theme().text);
     y += 28.0;
 
     // Clickable links row
     let link_color = MUTED_COLOR;
-    let hover_color = ACCENT_COLOR;
+    let hover_color = theme().accent;
     draw_link_row(
         content_x,
         y + 14.0,
@@ -229,17 +243,116 @@ fn draw_section(x: f32, y: f32, width: f32, title: &str, text: &str) -> f32 {
 
     draw_rectangle(x, y, width.round(), section_height, SECTION_BG);
 
-    draw_text(title, text_x, y + padding + 16.0, font_size, ACCENT_COLOR);
+    draw_text(title, text_x, y + padding + 16.0, font_size, theme().accent);
 
     let mut text_y = y + padding + title_height;
     for line in &lines {
-        draw_text(line, text_x, text_y + 16.0, font_size, TEXT_COLOR);
+        draw_text(line, text_x, text_y + 16.0, font_size, theme().text);
         text_y += line_height;
     }
 
     y + section_height + 20.0
 }
 
+/// Draw the editor appearance settings: theme preset, accent swatches and
+/// RGB sliders for a custom accent. Changes apply immediately.
+fn draw_appearance(x: f32, y: f32, width: f32, ctx: &crate::ui::UiContext, display: &mut DisplaySettings) -> f32 {
+    let x = x.round();
+    let y = y.round();
+    let padding = 16.0;
+    let text_x = x + padding;
+    let control_x = text_x + 70.0;
+    let row_height = 34.0;
+    let slider_height = 18.0;
+    let section_height = padding + 26.0 + row_height * 2.0 + slider_height * 3.0 + padding;
+    let theme = theme();
+
+    draw_rectangle(x, y, width.round(), section_height, SECTION_BG);
+    draw_text("Appearance", text_x, y + padding + 16.0, 16.0, theme.accent);
+
+    let mut settings = display.theme;
+    let mut row_y = y + padding + 26.0;
+
+    // Theme presets
+    draw_text("Theme", text_x, row_y + 17.0, 14.0, MUTED_COLOR);
+    let mut button_x = control_x;
+    for preset in ThemePreset::ALL {
+        let label = preset.label();
+        let w = (measure_text(label, None, 14, 1.0).width + 20.0).round();
+        let rect = Rect::new(button_x, row_y, w, 24.0);
+        let active = settings.preset == preset;
+        let bg = if active {
+            theme.accent
+        } else if ctx.mouse.inside(&rect) {
+            theme.button_hover
+        } else {
+            theme.bg
+        };
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, bg);
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, theme.dropdown_border);
+        draw_text(label, rect.x + 10.0, rect.y + 17.0, 14.0, if active { theme.bg } else { theme.text });
+        if ctx.mouse.clicked(&rect) {
+            settings.preset = preset;
+        }
+        button_x += w + 8.0;
+    }
+    row_y += row_height;
+
+    // Accent swatches, plus going back to the preset's own accent
+    draw_text("Accent", text_x, row_y + 17.0, 14.0, MUTED_COLOR);
+    let current = settings.accent_rgb();
+    let mut swatch_x = control_x;
+    for rgb in ACCENT_SWATCHES {
+        let rect = Rect::new(swatch_x, row_y, 24.0, 24.0);
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(rgb[0], rgb[1], rgb[2], 255));
+        if current == rgb {
+            draw_rectangle_lines(rect.x - 2.0, rect.y - 2.0, rect.w + 4.0, rect.h + 4.0, 2.0, theme.text_bright);
+        }
+        if ctx.mouse.clicked(&rect) {
+            settings.accent = Some(rgb);
+        }
+        swatch_x += 30.0;
+    }
+    let reset_rect = Rect::new(swatch_x + 8.0, row_y, 70.0, 24.0);
+    let reset_color = if settings.accent.is_none() {
+        theme.text_dim
+    } else if ctx.mouse.inside(&reset_rect) {
+        theme.accent
+    } else {
+        theme.text
+    };
+    draw_rectangle_lines(reset_rect.x, reset_rect.y, reset_rect.w, reset_rect.h, 1.0, theme.dropdown_border);
+    draw_text("Default", reset_rect.x + 10.0, reset_rect.y + 17.0, 14.0, reset_color);
+    if ctx.mouse.clicked(&reset_rect) {
+        settings.accent = None;
+    }
+    row_y += row_height;
+
+    // Fine-tune the accent per channel
+    let bar_w = (width - padding * 2.0 - 70.0 - 40.0).clamp(60.0, 300.0);
+    let mut rgb = settings.accent_rgb();
+    for (i, label) in ["R", "G", "B"].into_iter().enumerate() {
+        draw_text(label, text_x, row_y + 13.0, 14.0, MUTED_COLOR);
+        let bar = Rect::new(control_x, row_y + 4.0, bar_w, 10.0);
+        let mut channel = [0.0; 3];
+        channel[i] = 1.0;
+        draw_rectangle(bar.x, bar.y, bar.w, bar.h, theme.bg);
+        draw_rectangle(bar.x, bar.y, bar.w * rgb[i] as f32 / 255.0, bar.h, Color::new(channel[0], channel[1], channel[2], 0.8));
+        draw_text(&rgb[i].to_string(), bar.right() + 8.0, row_y + 13.0, 14.0, theme.text);
+        if ctx.mouse.clicking(&Rect::new(bar.x, row_y, bar.w, slider_height)) {
+            rgb[i] = (((ctx.mouse.x - bar.x) / bar.w).clamp(0.0, 1.0) * 255.0).round() as u8;
+            settings.accent = Some(rgb);
+        }
+        row_y += slider_height;
+    }
+
+    if settings != display.theme {
+        display.set_theme(settings);
+    }
+
+    y + section_height + 20.0
+}
+
 /// Draw an FAQ item (auto-wrapping)
 fn draw_faq_item(x: f32, y: f32, width: f32, question: &str, answer: &str) -> f32 {
     let x = x.round();
@@ -257,7 +370,7 @@ fn draw_faq_item(x: f32, y: f32, width: f32, question: &str, answer: &str) -> f3
 
     draw_rectangle(x, y, width.round(), section_height, SECTION_BG);
 
-    draw_text(question, text_x, y + padding + 16.0, font_size, ACCENT_COLOR);
+    draw_text(question, text_x, y + padding + 16.0, font_size, theme().accent);
 
     let mut text_y = y + padding + 26.0;
     for line in &answer_lines {
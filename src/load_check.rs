@@ -0,0 +1,253 @@
+//! Load-time file checks
+//!
+//! RON's own errors give a line and column and name the serde construct that
+//! failed, which says little to someone who hand-edited a level or a texture.
+//! `describe_ron_error` turns a parse error into a message naming the field
+//! it happened in (`rooms[2] > objects[0] > asset_id`), quoting the line and
+//! explaining the common causes (a cut-off file, a field from a newer build,
+//! a value of the wrong type).
+//!
+//! Past parsing, loaders check what serde can't (ranges, indices into other
+//! lists) and report each problem as a `LoadIssue` naming the field. Common
+//! problems can be repaired in place; repaired issues come back with `fixed`
+//! set so the caller can tell the user what changed.
+
+use ron::error::{Error, SpannedError};
+
+/// Most issues listed in a load error; the rest are counted
+pub const MAX_LISTED_ISSUES: usize = 8;
+
+/// A problem found in a loaded file
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadIssue {
+    /// Where it is (`rooms[2] > portals[0] > target_room`)
+    pub field: String,
+    pub message: String,
+    /// The loader can repair it (see `fixed`)
+    pub fixable: bool,
+    /// It was repaired while loading
+    pub fixed: bool,
+}
+
+impl LoadIssue {
+    /// An issue loading can't get past
+    pub fn error(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { field: field.into(), message: message.into(), fixable: false, fixed: false }
+    }
+
+    /// An issue the loader repairs when asked to; `fix` says whether it did
+    pub fn repairable(field: impl Into<String>, message: impl Into<String>, fix: bool) -> Self {
+        Self { field: field.into(), message: message.into(), fixable: true, fixed: fix }
+    }
+}
+
+impl std::fmt::Display for LoadIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)?;
+        if self.fixed {
+            write!(f, " (fixed)")
+        } else if self.fixable {
+            write!(f, " (can be fixed automatically)")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Issues that are still there after loading, one per line (None if all were fixed)
+pub fn unresolved(issues: &[LoadIssue]) -> Option<String> {
+    let open: Vec<&LoadIssue> = issues.iter().filter(|i| !i.fixed).collect();
+    if open.is_empty() {
+        return None;
+    }
+    let mut lines: Vec<String> = open.iter().take(MAX_LISTED_ISSUES).map(|i| i.to_string()).collect();
+    if open.len() > MAX_LISTED_ISSUES {
+        lines.push(format!("...and {} more", open.len() - MAX_LISTED_ISSUES));
+    }
+    Some(lines.join("\n"))
+}
+
+/// A RON parse error as a readable message: position, the field it happened
+/// in, what went wrong, and the offending line with a caret under the column
+pub fn describe_ron_error(source: &str, error: &SpannedError) -> String {
+    let position = error.position;
+    let mut message = format!("line {}, column {}", position.line, position.col);
+    let path = field_path(source, byte_offset(source, position.line, position.col));
+    if !path.is_empty() {
+        message.push_str(&format!(" in {}", path.join(" > ")));
+    }
+    message.push_str(&format!(": {}", explain(&error.code)));
+
+    if let Some(line) = source.lines().nth(position.line.saturating_sub(1)) {
+        // Long lines (packed geometry, pixel data) are cut around the column
+        let col = position.col.saturating_sub(1).min(line.len());
+        let start = floor_char_boundary(line, col.saturating_sub(40));
+        let end = floor_char_boundary(line, (col + 40).min(line.len()));
+        let gutter = format!("{:>5} | ", position.line);
+        message.push_str(&format!("\n{}{}", gutter, &line[start..end]));
+        let caret = line[start..floor_char_boundary(line, col)].chars().count();
+        message.push_str(&format!("\n{}^", " ".repeat(gutter.len() + caret)));
+    }
+    message
+}
+
+/// What went wrong, in words for whoever edited the file
+fn explain(error: &Error) -> String {
+    match error {
+        Error::Eof => "the file ends too early (cut off, or a bracket is never closed)".to_string(),
+        Error::MissingStructField { field, .. } => format!("`{}` is missing", field),
+        Error::DuplicateStructField { field, .. } => format!("`{}` is set twice", field),
+        Error::NoSuchStructField { found, .. } => {
+            format!("unknown field `{}` (misspelt, or saved by a newer version?)", found)
+        }
+        Error::NoSuchEnumVariant { found, expected, .. } => {
+            format!("unknown value `{}`, expected one of: {}", found, expected.join(", "))
+        }
+        Error::ExpectedFloat | Error::ExpectedInteger => "expected a number".to_string(),
+        Error::IntegerOutOfBounds => "number out of range for this field".to_string(),
+        Error::ExpectedBoolean => "expected `true` or `false`".to_string(),
+        Error::ExpectedString => "expected text in double quotes".to_string(),
+        Error::ExpectedStringEnd => "text is missing its closing quote".to_string(),
+        Error::ExpectedComma => "missing comma between values".to_string(),
+        Error::TrailingCharacters => "unexpected text after the end of the data".to_string(),
+        Error::InvalidValueForType { expected, found } => format!("expected {}, found {}", expected, found),
+        other => other.to_string(),
+    }
+}
+
+/// Byte offset of a 1-based line and (byte) column
+fn byte_offset(source: &str, line: usize, col: usize) -> usize {
+    let line_start: usize = source.split_inclusive('\n').take(line.saturating_sub(1)).map(str::len).sum();
+    floor_char_boundary(source, (line_start + col.saturating_sub(1)).min(source.len()))
+}
+
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Fields and list positions enclosing `offset`, outermost first
+/// (`["rooms[2]", "objects[0]", "asset_id"]`)
+fn field_path(source: &str, offset: usize) -> Vec<String> {
+    struct Frame {
+        name: Option<String>,
+        list: bool,
+        index: usize,
+    }
+    let mut frames: Vec<Frame> = Vec::new();
+    // Field whose value is being read at this nesting level
+    let mut field: Option<String> = None;
+    let mut word = String::new();
+    // Last identifier, kept across whitespace (`name : value`)
+    let mut ended = String::new();
+    let mut chars = source[..offset].chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        if !word.is_empty() {
+            ended = std::mem::take(&mut word);
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        let name = std::mem::take(&mut ended);
+        match c {
+            ':' if !name.is_empty() => field = Some(name),
+            '"' => {
+                // Skip the string, escapes included
+                while let Some(s) = chars.next() {
+                    match s {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for s in chars.by_ref() {
+                    if s == '\n' {
+                        break;
+                    }
+                }
+            }
+            '(' | '[' | '{' => frames.push(Frame { name: field.take(), list: c == '[', index: 0 }),
+            ')' | ']' | '}' => {
+                frames.pop();
+                field = None;
+            }
+            ',' => {
+                field = None;
+                if let Some(frame) = frames.last_mut() {
+                    frame.index += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut path = Vec::new();
+    for frame in &frames {
+        match (&frame.name, frame.list) {
+            (Some(name), true) => path.push(format!("{}[{}]", name, frame.index)),
+            (None, true) => path.push(format!("[{}]", frame.index)),
+            (Some(name), false) => path.push(name.clone()),
+            (None, false) => {}
+        }
+    }
+    path.extend(field);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_path() {
+        let source = "(\n  rooms: [\n    (id: 0),\n    (id: 1, objects: [(asset_id: 5), (asset_id: x";
+        assert_eq!(field_path(source, source.len()), vec!["rooms[1]", "objects[1]", "asset_id"]);
+        // Strings and comments don't confuse it
+        let source = "(name: \"a, [b\", // c: (\n  pos: (";
+        assert_eq!(field_path(source, source.len()), vec!["pos"]);
+    }
+
+    #[test]
+    fn test_describe_ron_error() {
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Point {
+            x: f32,
+            y: f32,
+        }
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Shape {
+            points: Vec<Point>,
+        }
+        let source = "(\n  points: [\n    (x: 1.0, y: 2.0),\n    (x: 1.0, y: oops),\n  ],\n)";
+        let error = ron::from_str::<Shape>(source).unwrap_err();
+        let message = describe_ron_error(source, &error);
+        assert!(message.starts_with("line 4, column"), "{}", message);
+        assert!(message.contains("in points[1] > y: expected a number"), "{}", message);
+        assert!(message.contains("    4 |     (x: 1.0, y: oops),"), "{}", message);
+
+        let error = ron::from_str::<Shape>("(points: [(x: 1.0, y: 2.0)").unwrap_err();
+        assert!(describe_ron_error("(points: [(x: 1.0, y: 2.0)", &error).contains("ends too early"));
+    }
+
+    #[test]
+    fn test_unresolved_issues() {
+        let issues = vec![
+            LoadIssue::repairable("rooms[0] > portals[1]", "points at a missing room", true),
+            LoadIssue::error("rooms[1] > width", "too large"),
+        ];
+        assert_eq!(unresolved(&issues).as_deref(), Some("rooms[1] > width: too large"));
+        assert_eq!(unresolved(&issues[..1]), None);
+    }
+}
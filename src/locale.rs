@@ -0,0 +1,335 @@
+//! Localization
+//!
+//! Player-facing text (HUD prompts, the options menu, script messages,
+//! cutscene subtitles and tutorial popups) goes through `t("key")`, which looks
+//! the key up in the current language's string table. Missing keys fall back
+//! to English and then to the key itself, so script text that isn't a key
+//! shows up unchanged: levels can be written in plain text and localized later.
+//!
+//! String tables are RON files at `assets/runtime/locale/<code>.ron`, listed in
+//! `languages.ron` (WASM can't list directories). The chosen language is saved
+//! with the display settings.
+//!
+//! macroquad's built-in font only covers ASCII, so text with other characters
+//! is drawn with the language's own font (`fonts` in its table, the first one
+//! that loads), falling back to the bundled VT323, which covers Latin-1. A
+//! language in a non-Latin script only needs to ship a font with its glyphs.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use macroquad::prelude::{draw_text_ex, Color, Font, TextParams};
+use serde::{Deserialize, Serialize};
+
+/// Folder holding the string tables
+pub const LOCALE_DIR: &str = "assets/runtime/locale";
+/// List of language codes with a string table
+pub const LANGUAGES_PATH: &str = "assets/runtime/locale/languages.ron";
+/// Language used for keys the current language is missing
+pub const DEFAULT_LANGUAGE: &str = "en";
+/// Font for non-ASCII text when the language names none
+pub const FALLBACK_FONT: &str = "assets/runtime/fonts/VT323-Regular.ttf";
+
+/// One language's strings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StringTable {
+    /// Language name as its speakers write it ("Español")
+    pub name: String,
+    /// Fonts (TTF paths) for text outside ASCII, in order of preference
+    #[serde(default)]
+    pub fonts: Vec<String>,
+    /// Translated text by key ("menu.camera" -> "Cámara")
+    pub strings: HashMap<String, String>,
+}
+
+/// Path of a language's string table
+pub fn table_path(code: &str) -> String {
+    format!("{}/{}.ron", LOCALE_DIR, code)
+}
+
+/// String tables by language code, plus the current language and loaded fonts
+#[derive(Default)]
+pub struct Localization {
+    languages: Vec<(String, StringTable)>,
+    current: usize,
+    /// Loaded fonts by path
+    fonts: HashMap<String, Font>,
+}
+
+impl Localization {
+    /// Start in the default language (or the first one, without English)
+    pub fn new(languages: Vec<(String, StringTable)>) -> Self {
+        let current = languages.iter().position(|(code, _)| code == DEFAULT_LANGUAGE).unwrap_or(0);
+        Self { languages, current, fonts: HashMap::new() }
+    }
+
+    /// Current language name
+    pub fn language_name(&self) -> &str {
+        self.languages.get(self.current).map_or("English", |(_, table)| table.name.as_str())
+    }
+
+    /// Switch language; false if there is no table for `code`
+    pub fn set_language(&mut self, code: &str) -> bool {
+        match self.languages.iter().position(|(c, _)| c == code) {
+            Some(index) => {
+                self.current = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Code of the language after (or before) the current one, wrapping around
+    pub fn next_language(&self, backwards: bool) -> Option<&str> {
+        let count = self.languages.len();
+        if count == 0 {
+            return None;
+        }
+        let index = if backwards { (self.current + count - 1) % count } else { (self.current + 1) % count };
+        Some(self.languages[index].0.as_str())
+    }
+
+    /// Translation of `key` in the current language, else in English
+    pub fn get(&self, key: &str) -> Option<&str> {
+        fn lookup<'a>(table: &'a StringTable, key: &str) -> Option<&'a str> {
+            table.strings.get(key).map(String::as_str)
+        }
+        self.languages
+            .get(self.current)
+            .and_then(|(_, table)| lookup(table, key))
+            .or_else(|| {
+                self.languages
+                    .iter()
+                    .find(|(code, _)| code == DEFAULT_LANGUAGE)
+                    .and_then(|(_, table)| lookup(table, key))
+            })
+    }
+
+    /// Translation of `key`, or `key` itself when no table has it
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        self.get(key).unwrap_or(key)
+    }
+
+    /// Font to draw `text` with (None = macroquad's default font)
+    fn font_for(&self, text: &str) -> Option<&Font> {
+        if text.is_ascii() {
+            return None;
+        }
+        let preferred = self.languages.get(self.current).map_or(&[][..], |(_, table)| table.fonts.as_slice());
+        preferred
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(FALLBACK_FONT))
+            .find_map(|path| self.fonts.get(path))
+    }
+}
+
+/// Replace `{name}` placeholders with their values
+pub fn fill_placeholders(text: &str, args: &[(&str, &str)]) -> String {
+    let mut text = text.to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+// ============================================================================
+// Shared instance
+// ============================================================================
+
+thread_local! {
+    static LOCALE: RefCell<Localization> = RefCell::new(Localization::default());
+}
+
+/// Run `f` with the shared localization
+pub fn with_locale<R>(f: impl FnOnce(&mut Localization) -> R) -> R {
+    LOCALE.with(|l| f(&mut l.borrow_mut()))
+}
+
+/// Translate `key` (returned unchanged when no table has it)
+pub fn t(key: &str) -> String {
+    with_locale(|l| l.text(key).to_string())
+}
+
+/// Translate `key`, or use `default` when no table has it
+pub fn t_or(key: &str, default: &str) -> String {
+    with_locale(|l| l.get(key).unwrap_or(default).to_string())
+}
+
+/// Switch the shared language; false if there is no table for `code`
+pub fn set_language(code: &str) -> bool {
+    with_locale(|l| l.set_language(code))
+}
+
+/// Step to the next (or previous) language and return its code
+pub fn cycle_language(backwards: bool) -> Option<String> {
+    with_locale(|l| {
+        let code = l.next_language(backwards)?.to_string();
+        l.set_language(&code);
+        Some(code)
+    })
+}
+
+/// Current language name, for settings menus
+pub fn language_name() -> String {
+    with_locale(|l| l.language_name().to_string())
+}
+
+/// Draw text with the font that covers its characters
+pub fn draw_text(text: &str, x: f32, y: f32, font_size: f32, color: Color) {
+    with_locale(|l| {
+        let params = TextParams { font: l.font_for(text), font_size: font_size as u16, color, ..Default::default() };
+        draw_text_ex(text, x, y, params);
+    });
+}
+
+/// Width of `text` as `draw_text` draws it
+pub fn measure_text(text: &str, font_size: f32) -> f32 {
+    with_locale(|l| macroquad::text::measure_text(text, l.font_for(text), font_size as u16, 1.0).width)
+}
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+fn parse_languages(text: &str) -> Vec<String> {
+    ron::from_str(text).unwrap_or_else(|e| {
+        eprintln!("Failed to parse {}: {}", LANGUAGES_PATH, e);
+        Vec::new()
+    })
+}
+
+fn parse_table(path: &str, text: &str) -> Option<StringTable> {
+    ron::from_str(text).map_err(|e| eprintln!("Failed to parse {}: {}", path, e)).ok()
+}
+
+/// Every font the tables name, plus the fallback
+fn font_paths(languages: &[(String, StringTable)]) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for path in languages.iter().flat_map(|(_, table)| &table.fonts).map(String::as_str).chain([FALLBACK_FONT]) {
+        if !paths.iter().any(|p| p == path) {
+            paths.push(path.to_string());
+        }
+    }
+    paths
+}
+
+fn install(languages: Vec<(String, StringTable)>, fonts: HashMap<String, Font>) {
+    println!("Loaded {} languages", languages.len());
+    let mut locale = Localization::new(languages);
+    locale.fonts = fonts;
+    with_locale(|l| *l = locale);
+}
+
+/// Load the string tables and their fonts from loose files
+#[cfg(not(feature = "player"))]
+pub async fn load() {
+    use macroquad::prelude::{load_string, load_ttf_font};
+
+    let codes = match load_string(LANGUAGES_PATH).await {
+        Ok(text) => parse_languages(&text),
+        Err(e) => {
+            eprintln!("No string tables: {}", e);
+            Vec::new()
+        }
+    };
+    let mut languages = Vec::new();
+    for code in codes {
+        let path = table_path(&code);
+        match load_string(&path).await {
+            Ok(text) => {
+                if let Some(table) = parse_table(&path, &text) {
+                    languages.push((code, table));
+                }
+            }
+            Err(e) => eprintln!("Failed to load {}: {}", path, e),
+        }
+    }
+    let mut fonts = HashMap::new();
+    for path in font_paths(&languages) {
+        match load_ttf_font(&path).await {
+            Ok(font) => {
+                fonts.insert(path, font);
+            }
+            Err(e) => eprintln!("Failed to load font {}: {}", path, e),
+        }
+    }
+    install(languages, fonts);
+}
+
+/// Load the string tables and their fonts from an exported game's bundle
+#[cfg(feature = "player")]
+pub fn load_bundle(bundle: &crate::bundle::Bundle) {
+    use macroquad::prelude::load_ttf_font_from_bytes;
+
+    let codes = bundle.read_string(LANGUAGES_PATH).map(|text| parse_languages(&text)).unwrap_or_default();
+    let languages: Vec<(String, StringTable)> = codes
+        .into_iter()
+        .filter_map(|code| {
+            let path = table_path(&code);
+            let text = bundle.read_string(&path).ok()?;
+            parse_table(&path, &text).map(|table| (code, table))
+        })
+        .collect();
+    let fonts = font_paths(&languages)
+        .into_iter()
+        .filter_map(|path| {
+            let bytes = bundle.read(&path).ok()?;
+            load_ttf_font_from_bytes(&bytes).ok().map(|font| (path, font))
+        })
+        .collect();
+    install(languages, fonts);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(name: &str, strings: &[(&str, &str)]) -> StringTable {
+        StringTable {
+            name: name.to_string(),
+            fonts: Vec::new(),
+            strings: strings.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+
+    fn sample() -> Localization {
+        Localization::new(vec![
+            ("en".to_string(), table("English", &[("menu.camera", "Camera"), ("prompt.open", "Open")])),
+            ("es".to_string(), table("Español", &[("menu.camera", "Cámara")])),
+        ])
+    }
+
+    #[test]
+    fn test_lookup_falls_back_to_english_then_key() {
+        let mut locale = sample();
+        assert_eq!(locale.language_name(), "English");
+        assert!(locale.set_language("es"));
+        assert!(!locale.set_language("xx"));
+        assert_eq!(locale.language_name(), "Español");
+
+        assert_eq!(locale.text("menu.camera"), "Cámara");
+        assert_eq!(locale.text("prompt.open"), "Open");
+        // Plain script text isn't a key and comes back unchanged
+        assert_eq!(locale.text("The gate is locked"), "The gate is locked");
+    }
+
+    #[test]
+    fn test_cycle_and_placeholders() {
+        let locale = sample();
+        assert_eq!(locale.next_language(false), Some("es"));
+        assert_eq!(locale.next_language(true), Some("es"));
+        assert_eq!(Localization::default().next_language(false), None);
+
+        assert_eq!(fill_placeholders("[{key}] {verb}", &[("key", "E"), ("verb", "Abrir")]), "[E] Abrir");
+    }
+
+    #[test]
+    fn test_table_parses_and_lists_fonts() {
+        let text = r#"(name: "Test", fonts: ["a.ttf"], strings: {"hud.open": "Open"})"#;
+        let parsed = parse_table("test.ron", text).unwrap();
+        assert_eq!(parsed.strings.get("hud.open").map(String::as_str), Some("Open"));
+
+        let languages = vec![("xx".to_string(), parsed), ("en".to_string(), table("English", &[]))];
+        assert_eq!(font_paths(&languages), vec!["a.ttf".to_string(), FALLBACK_FONT.to_string()]);
+    }
+}
@@ -6,6 +6,12 @@
 //! - Gouraud shading
 //! - Low resolution (320x240)
 //! - TR1-style room-based levels with portal culling
+//!
+//! Built with `--features player`, the binary is a standalone game runtime
+//! (see `player.rs`) instead of the editor.
+
+// The player build only uses the game runtime; the editor code stays compiled but idle
+#![cfg_attr(feature = "player", allow(dead_code, unused_imports))]
 
 /// Version from Cargo.toml
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -17,6 +23,7 @@ mod editor;
 mod landing;
 mod modeler;
 mod tracker;
+mod sfx;
 mod app;
 mod game;
 mod project;
@@ -26,28 +33,56 @@ mod asset;
 mod storage;
 mod auth;
 mod scene;
+mod frame_pacer;
+mod profiler;
+mod display;
+mod crt;
+mod bundle;
+mod clipboard_history;
+mod tags;
+mod thumbnails;
+mod browser_list;
+mod crash;
+mod cloud_sync;
+mod locale;
+mod load_check;
+#[cfg(feature = "player")]
+mod player;
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "player")))]
+mod mcp;
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "player")))]
+mod hot_reload;
 
 use macroquad::prelude::*;
 use rasterizer::{Framebuffer, Texture, HEIGHT, WIDTH};
 use world::{create_empty_level, load_level_with_storage, serialize_level, save_level_with_storage};
-use storage::{save_async, list_async, load_async, Storage};
+use storage::{save_async, list_async, load_async, Storage, StorageError};
+use storage::versions::{self, RemoteVersion};
+use cloud_sync::{ConflictChoice, RevisionHistory, SyncConflict, SyncTarget};
 use ui::{UiContext, MouseState, Rect, draw_fixed_tabs_with_auth, TabBarAction, TabEntry, layout as tab_layout, icon};
-use editor::{EditorAction, draw_editor, draw_level_browser, BrowserAction, LevelCategory, discover_sample_levels, discover_user_levels};
+use editor::{EditorAction, draw_editor, draw_level_browser, BrowserAction, LevelCategory, discover_sample_levels, discover_user_levels, draw_prefab_browser, PrefabBrowserAction, draw_dungeon_wizard, DungeonWizardAction};
 use modeler::{ModelerAction, ModelBrowserAction, ObjImportAction, draw_model_browser, draw_obj_importer, discover_models, discover_meshes, ObjImporter, TextureImportResult};
+#[cfg(not(target_arch = "wasm32"))]
+use modeler::GltfImporter;
 use app::{AppState, Tool};
+use game::music::MusicCommand;
 use std::path::PathBuf;
 
 fn window_conf() -> Conf {
+    // Window mode and size come from the saved display settings
+    // (Maximized requests oversized dimensions so the OS clamps to screen bounds)
+    let (window_width, window_height, fullscreen) = display::DisplaySettings::load().startup_window();
+    #[cfg(target_arch = "wasm32")]
+    let _ = fullscreen;
     Conf {
-        window_title: format!("BONNIE-32 v{}", VERSION),
-        // Request oversized dimensions so macOS clamps to screen bounds (pseudo-maximize)
-        window_width: 3840,
-        window_height: 2160,
+        window_title: window_title(),
+        window_width,
+        window_height,
         window_resizable: true,
         high_dpi: true,
-        // Start windowed on all platforms (WASM: browser handles sizing)
+        // WASM: browser handles sizing
         #[cfg(not(target_arch = "wasm32"))]
-        fullscreen: false,
+        fullscreen,
         icon: Some(miniquad::conf::Icon {
             small: *include_bytes!("../assets/runtime/icons/icon16.rgba"),
             medium: *include_bytes!("../assets/runtime/icons/icon32.rgba"),
@@ -57,11 +92,35 @@ fn window_conf() -> Conf {
     }
 }
 
+#[cfg(not(feature = "player"))]
+fn window_title() -> String {
+    format!("BONNIE-32 v{}", VERSION)
+}
+
+#[cfg(feature = "player")]
+fn window_title() -> String {
+    player::window_title()
+}
+
+/// Standalone player: boot straight into the exported game
+#[cfg(feature = "player")]
+#[macroquad::main(window_conf)]
+async fn main() {
+    #[cfg(not(target_arch = "wasm32"))]
+    crashlog::setup!(crashlog::cargo_metadata!().capitalized(), false);
+
+    player::run().await;
+}
+
+#[cfg(not(feature = "player"))]
 #[macroquad::main(window_conf)]
 async fn main() {
     // Initialize crash logging FIRST (before any other code)
     #[cfg(not(target_arch = "wasm32"))]
     crashlog::setup!(crashlog::cargo_metadata!().capitalized(), false);
+    // Our own report (actions, open files) and the crash marker for the next launch
+    #[cfg(not(target_arch = "wasm32"))]
+    crash::install();
 
     // Note: console_error_panic_hook was removed because it requires wasm-bindgen
     // which conflicts with macroquad's JS bundle. Panics still show in browser console.
@@ -111,6 +170,17 @@ async fn main() {
     // App state with all tools
     let mut app = AppState::new(level, None, icon_font, logo_texture);
 
+    // Mount the packed asset bundle if one ships with the build (read-only overlay)
+    #[cfg(not(target_arch = "wasm32"))]
+    let has_bundle = std::path::Path::new(storage::bundle::DEFAULT_BUNDLE).exists();
+    #[cfg(target_arch = "wasm32")]
+    let has_bundle = true;
+    if has_bundle {
+        if let Err(e) = app.storage.mount_file(storage::bundle::DEFAULT_BUNDLE).await {
+            println!("No asset bundle mounted: {}", e);
+        }
+    }
+
     // Initialize GCP authentication (loads Google Identity Services on WASM)
     auth::init();
 
@@ -135,11 +205,41 @@ async fn main() {
         }
     }
 
+    // Reopen on the monitor the window was last moved to
+    app.game.display.restore_position();
+
+    // String tables and their fonts, in the language saved with the display settings
+    locale::load().await;
+    locale::set_language(&app.game.display.language);
+
+    // Editor colors from the saved theme
+    app.game.display.apply_theme();
+
+    // Asset tags and smart collections (shared by all browsers)
+    tags::load(&app.storage);
+
+    // Offer to restore unsaved autosaves (and report the crash, if there was one)
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut crash_recovery = crash::take_pending();
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut autosaver = crash::Autosaver::default();
+
+    // Reload models, textures, songs and the open level when they change on disk
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut hot_reloader = hot_reload::HotReloader::default();
+
+    // MCP server for end-to-end tests (--mcp <port> or B32_MCP_PORT)
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(port) = mcp::port_from_env() {
+        if let Err(e) = mcp::start(port, app.console.sender()) {
+            eprintln!("{}", e);
+        }
+    }
+
     println!("=== BONNIE-32 ===");
 
     loop {
-        // Track frame start time for FPS limiting
-        let frame_start = get_time();
+        profiler::begin_frame();
 
         // Update authentication state (checks for sign-in/sign-out)
         // When auth state changes, refresh browser's user levels to avoid stale data
@@ -226,6 +326,8 @@ async fn main() {
                     }
                 }
             }
+            // Tags follow the storage backend
+            tags::load(&app.storage);
             // Tracker's song browser
             {
                 let sb = &mut app.tracker.song_browser;
@@ -325,14 +427,25 @@ async fn main() {
             double_clicked,
         };
         ui_ctx.begin_frame(mouse_state);
+        ui_ctx.update_keyboard_focus();
 
         // Poll gamepad input
         app.input.poll();
 
+        // Gamepad virtual cursor in the editor tabs (the game and input
+        // tester read the gamepad themselves)
+        if !matches!(app.active_tool, Tool::Test | Tool::InputTest) {
+            ui_ctx.update_gamepad_cursor(&app.input.gamepad_nav(), get_frame_time());
+        } else {
+            ui_ctx.gamepad.active = false;
+        }
+        // Widgets and modals below see the cursor as the mouse
+        let mouse_state = ui_ctx.mouse;
+
         // Block background input if level browser modal is open
         // Save the real mouse state so we can restore it for the modal
         let real_mouse = mouse_state;
-        if app.world_editor.level_browser.open {
+        if app.world_editor.level_browser.open || app.world_editor.prefab_browser.open || app.world_editor.dungeon_wizard.open {
             ui_ctx.begin_modal();
         }
 
@@ -350,6 +463,7 @@ async fn main() {
             TabEntry::new(icon::PLAY, "Game"),
             TabEntry::new(icon::PERSON_STANDING, "Assets"),
             TabEntry::new(icon::MUSIC, "Music"),
+            TabEntry::new(icon::WAVES, "SFX"),
             TabEntry::new(icon::GAMEPAD_2, "Input"),
         ];
 
@@ -392,11 +506,24 @@ async fn main() {
                 }
                 if tool == Tool::Test {
                     app.game.reset();
+                    app.game.analytics.set_enabled(app.world_editor.editor_state.playtest.recording);
+                } else {
+                    app.game.analytics.end_session();
+                    stop_level_music(&mut app);
+                }
+                for session in app.game.analytics.take_finished() {
+                    app.world_editor.editor_state.playtest.add_session(session);
                 }
                 app.set_active_tool(tool);
             }
         }
 
+        // Console requests (MCP server) run in the Test tab
+        if app.console.poll() && app.active_tool != Tool::Test {
+            app.game.reset();
+            app.set_active_tool(Tool::Test);
+        }
+
         // Content area below tab bar
         let content_rect = Rect::new(0.0, tab_layout::BAR_HEIGHT, screen_w, screen_h - tab_layout::BAR_HEIGHT);
 
@@ -404,10 +531,37 @@ async fn main() {
         // This ensures Game tab always sees the current editor state
         app.project.level = app.world_editor.editor_state.level.clone();
 
-        // Draw active tool content
+        // Clipboard history popup (Ctrl+Shift+V) blocks the editor underneath while open
+        let clip_kinds = clipboard_kinds(app.active_tool);
+        if clip_kinds.is_empty() {
+            app.clipboard_popup.open = false;
+        } else if ui::Shortcut::ctrl_shift(KeyCode::V).is_pressed() {
+            app.clipboard_popup.open = !app.clipboard_popup.open;
+        }
+        let real_mouse_clipboard = mouse_state;
+        if app.clipboard_popup.open {
+            ui_ctx.begin_modal();
+        }
+        // Cloud save conflict dialog blocks the editors until answered
+        let real_mouse_sync = mouse_state;
+        if app.sync_conflict.is_some() {
+            app.clipboard_popup.open = false;
+            ui_ctx.begin_modal();
+        }
+        // Crash recovery dialog blocks everything until answered
+        #[cfg(not(target_arch = "wasm32"))]
+        let real_mouse_recovery = mouse_state;
+        #[cfg(not(target_arch = "wasm32"))]
+        if crash_recovery.is_some() {
+            app.clipboard_popup.open = false;
+            ui_ctx.begin_modal();
+        }
+
+        // Draw active tool content (nested raster and tick timers take their share)
+        let ui_timer = profiler::scope(profiler::Subsystem::Ui, app.active_tool.label());
         match app.active_tool {
             Tool::Home => {
-                landing::draw_landing(content_rect, &mut app.landing, &ui_ctx);
+                landing::draw_landing(content_rect, &mut app.landing, &ui_ctx, &mut app.game.display);
             }
 
             Tool::WorldEditor => {
@@ -417,6 +571,9 @@ async fn main() {
                 if ws.editor_state.portals_dirty {
                     ws.editor_state.level.recalculate_portals();
                     ws.editor_state.portals_dirty = false;
+                    if ws.editor_state.portal_debug {
+                        ws.editor_state.check_portals();
+                    }
                 }
 
                 // Check for pending import from browser (WASM only)
@@ -462,9 +619,9 @@ async fn main() {
                             let data = String::from_utf8_lossy(&data_buf).to_string();
                             let filename = String::from_utf8_lossy(&filename_buf).to_string();
 
-                            // Use load_level_from_str which includes validation
-                            match world::load_level_from_str(&data) {
-                                Ok(level) => {
+                            // Validates the level, repairing what it can
+                            match world::repair_level_from_str(&data) {
+                                Ok((level, issues)) => {
                                     ws.editor_layout.apply_config(&level.editor_layout);
                                     ws.editor_state.grid_offset_x = level.editor_layout.grid_offset_x;
                                     ws.editor_state.grid_offset_y = level.editor_layout.grid_offset_y;
@@ -481,10 +638,10 @@ async fn main() {
                                     ws.editor_state.load_level(level, PathBuf::from(&filename));
                                     // Reset game state for the new level
                                     app.game.reset_for_new_level();
-                                    ws.editor_state.set_status(&format!("Uploaded {}", filename), 3.0);
+                                    ws.editor_state.set_status(&format!("Uploaded {}{}", filename, repaired_note(&issues)), 3.0);
                                 }
                                 Err(e) => {
-                                    ws.editor_state.set_status(&format!("Upload failed: {}", e), 5.0);
+                                    ws.editor_state.set_status(&format!("Upload failed: {}", load_error_summary(&e)), 5.0);
                                 }
                             }
                         }
@@ -500,12 +657,21 @@ async fn main() {
 
                 // Append user textures (they'll be indexed after pack textures)
                 // These are updated in real-time when editing, so the 3D view shows live changes
+                // Flipbook textures show the frame for the current time
+                let now = get_time();
                 for name in ws.editor_state.user_textures.names() {
                     if let Some(user_tex) = ws.editor_state.user_textures.get(name) {
-                        editor_textures.push(user_tex.to_raster_texture());
+                        editor_textures.push(user_tex.to_raster_texture_at(now));
                     }
                 }
 
+                // Rebuild the RGB555 cache when an animated texture advances a frame
+                let anim_key = ws.editor_state.user_textures.animation_key(now);
+                if anim_key != ws.editor_state.texture_anim_key {
+                    ws.editor_state.texture_anim_key = anim_key;
+                    ws.editor_state.texture_generation = ws.editor_state.texture_generation.wrapping_add(1);
+                }
+
                 // Draw editor UI
                 let action = draw_editor(
                     &mut ui_ctx,
@@ -590,23 +756,7 @@ async fn main() {
                                 let (name, path) = ws.level_browser.selected_level()
                                     .map(|e| (e.name.clone(), e.path.clone()))
                                     .unwrap_or_else(|| ("level".to_string(), PathBuf::from("assets/userdata/levels/untitled.ron")));
-                                ws.editor_layout.apply_config(&level.editor_layout);
-                                ws.editor_state.grid_offset_x = level.editor_layout.grid_offset_x;
-                                ws.editor_state.grid_offset_y = level.editor_layout.grid_offset_y;
-                                ws.editor_state.grid_zoom = level.editor_layout.grid_zoom;
-                                ws.editor_state.orbit_target = rasterizer::Vec3::new(
-                                    level.editor_layout.orbit_target_x,
-                                    level.editor_layout.orbit_target_y,
-                                    level.editor_layout.orbit_target_z,
-                                );
-                                ws.editor_state.orbit_distance = level.editor_layout.orbit_distance;
-                                ws.editor_state.orbit_azimuth = level.editor_layout.orbit_azimuth;
-                                ws.editor_state.orbit_elevation = level.editor_layout.orbit_elevation;
-                                ws.editor_state.sync_camera_from_orbit();
-                                // Use load_level to preserve texture packs (important for WASM)
-                                ws.editor_state.load_level(level, path);
-                                // Reset game state for the new level
-                                app.game.reset_for_new_level();
+                                open_level(ws, &mut app.game, level, path);
                                 ws.editor_state.set_status(&format!("Opened: {}", name), 3.0);
                                 ws.level_browser.close();
                             }
@@ -645,8 +795,10 @@ async fn main() {
                             if let Some(info) = ws.level_browser.selected_level() {
                                 let path_str = info.path.to_string_lossy().to_string();
                                 let name = info.name.clone();
+                                let key = info.library_key();
                                 match app.storage.delete_sync(&path_str) {
                                     Ok(()) => {
+                                        thumbnails::remove(&app.storage, tags::TagKind::Level, &key);
                                         ws.editor_state.set_status(&format!("Deleted: {}", name), 3.0);
                                         // Clear selection
                                         ws.level_browser.selected_category = None;
@@ -682,6 +834,7 @@ async fn main() {
                                     let old_path = info.path.clone();
                                     let old_name = info.name.clone();
                                     let is_sample = info.category == LevelCategory::Sample;
+                                    let category = info.category;
 
                                     if new_name.is_empty() {
                                         ws.editor_state.set_status("Name cannot be empty", 3.0);
@@ -705,6 +858,7 @@ async fn main() {
                                                             ws.editor_state.set_status(&format!("Rename failed: {}", e), 3.0);
                                                         } else {
                                                             let _ = app.storage.delete_sync(&old_str);
+                                                            rename_level_library_entries(&app.storage, category, &old_name, &new_name);
                                                             if ws.editor_state.current_file.as_ref() == Some(&old_path) {
                                                                 ws.editor_state.current_file = Some(new_path);
                                                             }
@@ -725,6 +879,7 @@ async fn main() {
                                                 // Local rename (user levels without cloud, or sample levels)
                                                 match std::fs::rename(&old_path, &new_path) {
                                                     Ok(()) => {
+                                                        rename_level_library_entries(&app.storage, category, &old_name, &new_name);
                                                         if ws.editor_state.current_file.as_ref() == Some(&old_path) {
                                                             ws.editor_state.current_file = Some(new_path);
                                                         }
@@ -799,21 +954,151 @@ async fn main() {
                                 ws.editor_state.set_status("Refreshing...", 2.0);
                             }
                         }
+                        BrowserAction::ShowHistory => {
+                            if let Some(info) = ws.level_browser.selected_level() {
+                                let path = info.path.clone();
+                                ws.level_browser.history = Some(RevisionHistory::load(&app.storage, path));
+                            }
+                        }
+                        BrowserAction::OpenRevision(revision) => {
+                            if let Some(history) = ws.level_browser.history.take() {
+                                let path_str = history.path.to_string_lossy().to_string();
+                                let loaded = app.storage.read_revision(&path_str, revision).take();
+                                match loaded.map(|r| r.map(|data| world::parse_level_data(&data))) {
+                                    Some(Ok(Ok(level))) => {
+                                        open_level(ws, &mut app.game, level, history.path.clone());
+                                        // Saving it makes the old revision current again
+                                        if let Some(head) = history.head() {
+                                            versions::record_base(&path_str, head);
+                                        }
+                                        ws.editor_state.dirty = true;
+                                        ws.editor_state.set_status(&format!("Opened revision {} (save to restore it)", revision), 3.0);
+                                        ws.level_browser.close();
+                                    }
+                                    Some(Ok(Err(e))) => ws.editor_state.set_status(&format!("Failed to open revision: {}", e), 3.0),
+                                    Some(Err(e)) => ws.editor_state.set_status(&format!("Failed to open revision: {}", e), 3.0),
+                                    None => ws.editor_state.set_status("Failed to open revision: still loading", 3.0),
+                                }
+                            }
+                        }
+                        BrowserAction::OpenLink => {
+                            // Download a shared level and import it with its textures
+                            let link = ws.level_browser.link_dialog.take().map(|input| input.text).unwrap_or_default();
+                            let shared = editor::share_token(&link)
+                                .ok_or_else(|| "not a share link".to_string())
+                                .and_then(|token| match app.storage.read_shared(&token).take() {
+                                    Some(Ok(bytes)) => Ok(bytes),
+                                    Some(Err(e)) => Err(e.to_string()),
+                                    None => Err("download still in progress".to_string()),
+                                })
+                                .and_then(editor::unpack_shared_level);
+                            match shared {
+                                Ok(mut shared) => {
+                                    let added = editor::import_shared_textures(&mut shared, &mut ws.editor_state.user_textures);
+                                    let unsaved = added.iter()
+                                        .filter(|name| ws.editor_state.user_textures.save_texture_with_storage(name, &app.storage).is_err())
+                                        .count();
+                                    if !added.is_empty() {
+                                        ws.editor_state.texture_generation = ws.editor_state.texture_generation.wrapping_add(1);
+                                        ws.editor_state.pending_texture_refresh = true;
+                                    }
+                                    let level = shared.level;
+                                    open_level(ws, &mut app.game, level, PathBuf::from(format!("assets/userdata/levels/{}.ron", shared.name)));
+                                    // Mark as unsaved (no current file) so user must save
+                                    ws.editor_state.current_file = None;
+                                    let mut status = format!("Opened shared level '{}'", shared.name);
+                                    if !added.is_empty() {
+                                        status.push_str(&format!(" - {} textures added", added.len()));
+                                    }
+                                    if unsaved > 0 {
+                                        status.push_str(&format!(" ({} not saved, sign in to keep them)", unsaved));
+                                    }
+                                    ws.editor_state.set_status(&format!("{} (save to keep)", status), 5.0);
+                                    ws.level_browser.close();
+                                }
+                                Err(e) => ws.editor_state.set_status(&format!("Open link failed: {}", e), 5.0),
+                            }
+                        }
                         BrowserAction::Cancel => {
                             ws.level_browser.close();
                         }
                         BrowserAction::None => {}
                     }
                 }
+
+                // Draw prefab browser overlay if open
+                if ws.prefab_browser.open {
+                    ui_ctx.end_modal(real_mouse);
+                    let prefab_action = draw_prefab_browser(&mut ui_ctx, &mut ws.prefab_browser, app.icon_font.as_ref());
+                    handle_prefab_browser_action(prefab_action, ws);
+                }
+
+                // Draw dungeon wizard overlay if open
+                if ws.dungeon_wizard.open {
+                    ui_ctx.end_modal(real_mouse);
+                    match draw_dungeon_wizard(&mut ui_ctx, &mut ws.dungeon_wizard, app.icon_font.as_ref()) {
+                        DungeonWizardAction::Generate => {
+                            let settings = ws.dungeon_wizard.settings;
+                            let layout = ws.dungeon_wizard.preview.take()
+                                .unwrap_or_else(|| world::generate_dungeon_layout(&settings));
+                            let rooms = world::build_dungeon_rooms(
+                                &layout,
+                                &settings,
+                                &world::DungeonTextures::default(),
+                                ws.editor_state.free_room_origin(),
+                                ws.editor_state.level.rooms.len(),
+                            );
+                            let count = ws.editor_state.append_rooms(rooms);
+                            ws.editor_state.set_status(&format!("Generated dungeon: {} rooms (seed {})", count, settings.seed), 3.0);
+                            ws.dungeon_wizard.close();
+                        }
+                        DungeonWizardAction::Cancel => ws.dungeon_wizard.close(),
+                        DungeonWizardAction::None => {}
+                    }
+                }
             }
 
             Tool::Test => {
-                // Build textures array from World Editor texture packs
-                let game_textures: Vec<Texture> = app.world_editor.editor_state.texture_packs
+                // Build textures array from the level's texture packs (in priority order) + user textures
+                let packs = &app.world_editor.editor_state.texture_packs;
+                let pack_order = world::ordered_pack_indices(
+                    packs.iter().map(|p| p.name.as_str()),
+                    &app.world_editor.editor_state.level.texture_packs,
+                );
+                let mut game_textures: Vec<Texture> = pack_order
                     .iter()
-                    .flat_map(|pack| &pack.textures)
+                    .flat_map(|&i| &packs[i].textures)
                     .cloned()
                     .collect();
+                let user_textures = &app.world_editor.editor_state.user_textures;
+                let now = get_time();
+                for name in user_textures.names() {
+                    if let Some(user_tex) = user_textures.get(name) {
+                        game_textures.push(user_tex.to_raster_texture_at(now));
+                    }
+                }
+
+                // Flipbook frame changed: drop the RGB555 cache so it is rebuilt
+                let anim_key = user_textures.animation_key(now);
+                if anim_key != app.game.texture_anim_key {
+                    app.game.texture_anim_key = anim_key;
+                    app.game.textures_15_cache.clear();
+                }
+
+                // Stream level chunks around the player; the game plays the composed level
+                if !app.project.level.streaming.is_off() {
+                    let player = app.game.get_player_position();
+                    let storage = &app.storage;
+                    app.game.streamer.update(&app.project.level, player, |path| {
+                        load_level_with_storage(path, storage).map_err(|e| e.to_string())
+                    });
+                    for e in app.game.streamer.take_errors() {
+                        eprintln!("Chunk load failed: {}", e);
+                    }
+                    if let Some(level) = app.game.streamer.level() {
+                        app.project.level.clone_from(level);
+                    }
+                }
 
                 // Spawn player if playing and no player exists
                 if app.game.playing && app.game.player_entity.is_none() {
@@ -825,10 +1110,70 @@ async fn main() {
                     }
                 }
 
+                // Set up triggers, doors, pickups and level scripts once per play session
+                // (the last session's music stops)
+                if app.game.playing && !app.game.logic.started() {
+                    stop_level_music(&mut app);
+                    app.game.start_level_logic(&app.project.level, &app.world_editor.editor_state.asset_library);
+                }
+
+                // Test console commands (MCP server); a running `step` fixes the delta
+                let console_delta = app.console.begin_frame(&mut app.input);
+                while let Some(request) = app.console.next_request() {
+                    if let Some(request) = app.console.execute(request, &mut app.game, &mut app.input) {
+                        run_console_host_command(&mut app, request);
+                    }
+                }
+
                 // Run game simulation
-                let delta = get_frame_time();
+                let delta = console_delta.unwrap_or_else(get_frame_time);
                 app.game.tick(&app.project.level, delta);
 
+                // Play the sound effects the tick asked for
+                let sfx_timer = profiler::scope(profiler::Subsystem::Audio, "sfx");
+                app.tracker.audio.set_sfx_reverb(app.game.sounds.reverb());
+                app.tracker.audio.set_sfx_reverb_level(app.game.sounds.reverb_level());
+                for sound in app.game.sounds.take() {
+                    app.sfx.library.play_placed(&sound.name, sound.placement, &app.tracker.audio);
+                }
+                drop(sfx_timer);
+
+                // Level music from cutscenes and scripts, queued as cues that
+                // land on the song's beat (user songs first, then the samples)
+                for request in app.game.logic.take_music() {
+                    let Some(request) = app.game.music.direct(request) else {
+                        continue;
+                    };
+                    let change = match request.command {
+                        MusicCommand::Play { song: name, pattern } => {
+                            let song = tracker::load_song_with_storage(&format!("{}/{}.ron", tracker::USER_SONGS_DIR, name), &app.storage)
+                                .or_else(|_| tracker::load_song_with_storage(&format!("{}/{}.ron", tracker::SAMPLES_SONGS_DIR, name), &app.storage));
+                            match song {
+                                Ok(song) => tracker::CueChange::Song(Box::new(song), pattern),
+                                Err(e) => {
+                                    eprintln!("Level music '{}': {}", name, e);
+                                    app.game.music.stopped();
+                                    continue;
+                                }
+                            }
+                        }
+                        MusicCommand::Jump(pattern) => tracker::CueChange::Jump(pattern),
+                        MusicCommand::Mute(channel, muted) => tracker::CueChange::Mute(channel, muted),
+                        MusicCommand::Stop => tracker::CueChange::Stop,
+                    };
+                    app.tracker.queue_cue(tracker::MusicCue { change, sync: request.sync });
+                }
+                if app.game.music.song().is_some() || app.tracker.has_cues() {
+                    // Sequences the song and its cues (and renders audio on WASM)
+                    app.tracker.update_playback(delta as f64);
+                    if !app.tracker.playing {
+                        app.game.music.stopped();
+                    }
+                } else {
+                    #[cfg(target_arch = "wasm32")]
+                    app.tracker.audio.render_audio(delta as f64);
+                }
+
                 // Render the test viewport (player settings edited in World Editor)
                 game::draw_test_viewport(
                     content_rect,
@@ -841,6 +1186,10 @@ async fn main() {
                     &app.world_editor.editor_state.asset_library,
                     &app.world_editor.editor_state.user_textures,
                 );
+                // On-screen stick and buttons on touch devices
+                app.input.touch_mut().set_area(macroquad::math::Rect::new(content_rect.x, content_rect.y, content_rect.w, content_rect.h));
+                app.input.touch().draw();
+                app.console.end_frame(&app.game, || game::console::encode_frame_png(&fb));
             }
 
             Tool::Modeler => {
@@ -890,6 +1239,9 @@ async fn main() {
                     let ms = &mut app.modeler;
                     handle_modeler_action(action, &mut ms.modeler_state, &mut ms.model_browser, &mut ms.obj_importer);
                 }
+
+                // Version history: confirmed checkpoints and timed auto snapshots
+                handle_modeler_snapshots(&mut app);
                 let ms = &mut app.modeler;
 
                 // Draw model browser overlay if open
@@ -913,6 +1265,10 @@ async fn main() {
                             };
                             if let Some(asset_info) = asset_info {
                                 let path = asset_info.path.clone();
+                                // User assets show their snapshot history next to the preview
+                                if category == modeler::AssetCategory::User {
+                                    ms.model_browser.history = modeler::snapshots::load_history(&app.storage, &asset_info.name);
+                                }
                                 #[cfg(not(target_arch = "wasm32"))]
                                 {
                                     match asset::Asset::load(&path) {
@@ -936,17 +1292,7 @@ async fn main() {
                                 let path = ms.model_browser.selected_asset()
                                     .map(|a| a.path.clone())
                                     .unwrap_or_else(|| PathBuf::from("assets/userdata/assets/untitled.ron"));
-                                // Set the asset directly in the modeler
-                                ms.modeler_state.asset = asset;
-                                ms.modeler_state.selected_object = if ms.modeler_state.objects().is_empty() { None } else { Some(0) };
-                                // Auto-select first mesh component so it's visible
-                                ms.modeler_state.selected_component = ms.modeler_state.asset.components.iter()
-                                    .position(|c| c.is_mesh());
-                                // Resolve ID-based texture refs using the texture library
-                                ms.modeler_state.resolve_all_texture_refs();
-                                ms.modeler_state.current_file = Some(path.clone());
-                                ms.modeler_state.dirty = false;
-                                ms.modeler_state.selection = modeler::ModelerSelection::None;
+                                open_asset(&mut ms.modeler_state, asset, path.clone());
                                 ms.modeler_state.set_status(&format!("Opened: {}", path.display()), 3.0);
                                 ms.model_browser.close();
                             }
@@ -973,9 +1319,61 @@ async fn main() {
                                 ms.model_browser.close();
                             }
                         }
+                        ModelBrowserAction::SelectSnapshot(entry) => {
+                            let model = ms.model_browser.selected_asset().map(|a| a.name.clone());
+                            ms.model_browser.history_selected = entry;
+                            ms.model_browser.snapshot_asset = None;
+                            if let (Some(model), Some(meta)) = (model, ms.model_browser.selected_snapshot().cloned()) {
+                                match modeler::snapshots::load_snapshot(&app.storage, &model, &meta) {
+                                    Ok(asset) => ms.model_browser.snapshot_asset = Some(asset),
+                                    Err(e) => {
+                                        ms.model_browser.history_selected = None;
+                                        ms.modeler_state.set_status(&format!("Failed to load snapshot: {}", e), 3.0);
+                                    }
+                                }
+                            }
+                        }
+                        ModelBrowserAction::RestoreSnapshot => {
+                            let path = ms.model_browser.selected_asset().map(|a| a.path.clone());
+                            let title = ms.model_browser.selected_snapshot().map(|m| m.title());
+                            if let (Some(path), Some(title), Some(asset)) = (path, title, ms.model_browser.snapshot_asset.take()) {
+                                // Keep unsaved work on this model recoverable before replacing it
+                                if ms.modeler_state.dirty && ms.modeler_state.current_file.as_ref() == Some(&path) {
+                                    if let Some(model) = modeler::snapshots::model_key(&path) {
+                                        let _ = modeler::snapshots::save_snapshot(
+                                            &app.storage, &model, &ms.modeler_state.asset,
+                                            modeler::snapshots::SnapshotKind::Checkpoint, "Before restore",
+                                        );
+                                    }
+                                }
+                                ms.modeler_state.asset = asset;
+                                ms.modeler_state.selected_object = if ms.modeler_state.objects().is_empty() { None } else { Some(0) };
+                                ms.modeler_state.selected_component = ms.modeler_state.asset.components.iter()
+                                    .position(|c| c.is_mesh());
+                                ms.modeler_state.resolve_all_texture_refs();
+                                ms.modeler_state.current_file = Some(path);
+                                ms.modeler_state.dirty = true; // Restored version becomes current once saved
+                                ms.modeler_state.selection = modeler::ModelerSelection::None;
+                                ms.modeler_state.set_status(&format!("Restored {} (save to keep)", title), 3.0);
+                                ms.model_browser.close();
+                            }
+                        }
+                        ModelBrowserAction::FindUsages => {
+                            if let Some(name) = ms.model_browser.selected_asset().map(|a| a.name.clone()) {
+                                let graph = app.world_editor.editor_state.dependency_graph(&app.storage);
+                                ms.model_browser.usages = Some(project::UsagesPanel::find(&graph, project::Dependency::Asset(name)));
+                            }
+                        }
                         ModelBrowserAction::DeleteAsset => {
-                            // Delete user asset
-                            if let Some(asset_info) = ms.model_browser.selected_asset() {
+                            // Levels and assets still using it are listed first; a second delete goes ahead
+                            let graph = app.world_editor.editor_state.dependency_graph(&app.storage);
+                            let in_use = ms.model_browser.selected_asset()
+                                .map(|a| project::Dependency::Asset(a.name.clone()))
+                                .is_some_and(|dep| !project::confirm_delete(&mut ms.model_browser.usages, &graph, dep));
+                            if in_use {
+                                ms.modeler_state.set_status("Asset is still in use (Delete again to delete anyway)", 3.0);
+                            } else if let Some(asset_info) = ms.model_browser.selected_asset() {
+                                // Delete user asset
                                 let path = asset_info.path.clone();
                                 #[cfg(not(target_arch = "wasm32"))]
                                 {
@@ -983,6 +1381,8 @@ async fn main() {
                                         eprintln!("Failed to delete asset: {}", e);
                                         ms.modeler_state.set_status(&format!("Failed to delete: {}", e), 3.0);
                                     } else {
+                                        modeler::snapshots::delete_history(&app.storage, &asset_info.name);
+                                        thumbnails::remove(&app.storage, tags::TagKind::Asset, &asset_info.library_key());
                                         ms.modeler_state.set_status("Asset deleted", 2.0);
                                         // Refresh the browser
                                         ms.model_browser.user_assets = modeler::discover_user_assets();
@@ -1031,6 +1431,20 @@ async fn main() {
                                                                                 ms.modeler_state.current_file = Some(new_path);
                                                                                 ms.modeler_state.asset.name = new_name.clone();
                                                                             }
+                                                                            if let Err(e) = modeler::snapshots::rename_history(&app.storage, &old_name, &new_name) {
+                                                                                eprintln!("Failed to move snapshot history: {}", e);
+                                                                            }
+                                                                            tags::with_tags(|t| t.rename_item(
+                                                                                tags::TagKind::Asset,
+                                                                                &format!("{}{}", modeler::AssetCategory::User.prefix(), old_name),
+                                                                                &format!("{}{}", modeler::AssetCategory::User.prefix(), new_name),
+                                                                            ));
+                                                                            thumbnails::rename(
+                                                                                &app.storage,
+                                                                                tags::TagKind::Asset,
+                                                                                &format!("{}{}", modeler::AssetCategory::User.prefix(), old_name),
+                                                                                &format!("{}{}", modeler::AssetCategory::User.prefix(), new_name),
+                                                                            );
                                                                             ms.modeler_state.set_status(&format!("Renamed to '{}'", new_name), 2.0);
                                                                             ms.model_browser.user_assets = modeler::discover_user_assets();
                                                                             ms.model_browser.preview_asset = None;
@@ -1120,7 +1534,17 @@ async fn main() {
 
                                 #[cfg(not(target_arch = "wasm32"))]
                                 {
-                                    match ObjImporter::load_from_file(&path) {
+                                    // glTF carries its texture inside the file
+                                    let mut gltf_image = None;
+                                    let loaded = if GltfImporter::is_gltf_path(&path) {
+                                        GltfImporter::load_from_file(&path).map(|mut scene| {
+                                            gltf_image = scene.base_color_image.take();
+                                            scene.merged_mesh()
+                                        })
+                                    } else {
+                                        ObjImporter::load_from_file(&path)
+                                    };
+                                    match loaded {
                                         Ok(mut mesh) => {
                                             // Apply scale to preview
                                             for vertex in &mut mesh.vertices {
@@ -1155,6 +1579,14 @@ async fn main() {
                                                 }
                                             }
 
+                                            if let Some(bytes) = gltf_image {
+                                                let depth = ms.obj_importer.clut_depth_override;
+                                                match GltfImporter::quantize_texture(&bytes, depth, &ms.obj_importer.quantize_options, "preview") {
+                                                    Ok((indexed, clut, color_count)) => textures.push(TextureImportResult { indexed, clut, color_count }),
+                                                    Err(e) => eprintln!("Failed to load glTF texture: {}", e),
+                                                }
+                                            }
+
                                             // Load additional textures (_tex0.png, _tex1.png, etc.)
                                             for tex_path in additional_textures {
                                                 match ObjImporter::load_png_to_indexed(&tex_path, "preview") {
@@ -1190,7 +1622,7 @@ async fn main() {
                                         let flip_h = ms.obj_importer.flip_horizontal;
                                         let flip_v = ms.obj_importer.flip_vertical;
 
-                                        if let Ok(mut mesh) = ObjImporter::load_from_file(&path) {
+                                        if let Ok(mut mesh) = modeler::load_mesh_file(&path) {
                                             // Apply scale to preview
                                             for vertex in &mut mesh.vertices {
                                                 vertex.pos = vertex.pos * scale;
@@ -1243,89 +1675,93 @@ async fn main() {
 
                             #[cfg(not(target_arch = "wasm32"))]
                             {
-                                // Import with texture - either auto-detect or forced CLUT depth
-                                let import_result = if let Some(depth) = clut_depth_override {
-                                    // Force specific CLUT depth
-                                    ObjImporter::import_with_texture(&path, scale, Some(depth))
+                                if GltfImporter::is_gltf_path(&path) {
+                                    import_gltf(&mut ms.modeler_state, &ms.obj_importer, &path);
                                 } else {
-                                    // Auto-detect optimal CLUT depth
-                                    ObjImporter::import_with_auto_quantize(&path, scale)
-                                };
-                                match import_result {
-                                    Ok(mut result) => {
-                                        // Flip normals if requested
-                                        if flip_normals {
-                                            // Flip vertex normals
-                                            for vertex in &mut result.mesh.vertices {
-                                                vertex.normal = vertex.normal * -1.0;
+                                    // Import with texture - either auto-detect or forced CLUT depth
+                                    let import_result = if let Some(depth) = clut_depth_override {
+                                        // Force specific CLUT depth
+                                        ObjImporter::import_with_texture(&path, scale, Some(depth))
+                                    } else {
+                                        // Auto-detect optimal CLUT depth
+                                        ObjImporter::import_with_auto_quantize(&path, scale)
+                                    };
+                                    match import_result {
+                                        Ok(mut result) => {
+                                            // Flip normals if requested
+                                            if flip_normals {
+                                                // Flip vertex normals
+                                                for vertex in &mut result.mesh.vertices {
+                                                    vertex.normal = vertex.normal * -1.0;
+                                                }
+                                                // Swap v1 and v2 to flip winding order
+                                                for face in &mut result.mesh.faces {
+                                                    face.vertices.reverse();
+                                                }
                                             }
-                                            // Swap v1 and v2 to flip winding order
-                                            for face in &mut result.mesh.faces {
-                                                face.vertices.reverse();
+
+                                            // Flip horizontal (mirror X)
+                                            if flip_h {
+                                                modeler::apply_mesh_flip_horizontal(&mut result.mesh);
                                             }
-                                        }
 
-                                        // Flip horizontal (mirror X)
-                                        if flip_h {
-                                            modeler::apply_mesh_flip_horizontal(&mut result.mesh);
-                                        }
+                                            // Flip vertical (mirror Y)
+                                            if flip_v {
+                                                modeler::apply_mesh_flip_vertical(&mut result.mesh);
+                                            }
 
-                                        // Flip vertical (mirror Y)
-                                        if flip_v {
-                                            modeler::apply_mesh_flip_vertical(&mut result.mesh);
-                                        }
+                                            // Set the editable mesh directly in project (single source of truth)
+                                            if let Some(mesh) = ms.modeler_state.mesh_mut() {
+                                                *mesh = result.mesh;
+                                            }
+                                            // Don't set current_file to OBJ path - this is an IMPORT, not opening a project
+                                            // User must "Save As" to create a .ron project file
+                                            ms.modeler_state.current_file = None;
+                                            ms.modeler_state.dirty = true;  // Needs saving
+                                            ms.modeler_state.selection = modeler::ModelerSelection::None;
+
+                                            // Handle texture import
+                                            let mut texture_status = String::new();
+                                            if let Some(tex_result) = result.texture {
+                                                let TextureImportResult { mut indexed, clut, color_count } = tex_result;
+                                                // Clear existing CLUTs and add only the imported one
+                                                ms.modeler_state.clut_pool.clear();
+                                                let clut_id = ms.modeler_state.clut_pool.add_clut(clut);
+                                                indexed.default_clut = clut_id;
+                                                let depth_label = indexed.depth.short_label();
+                                                // Set the indexed atlas on the selected object
+                                                if let Some(atlas) = ms.modeler_state.atlas_mut() {
+                                                    *atlas = indexed;
+                                                }
+                                                ms.modeler_state.selected_clut = Some(clut_id);
+                                                // Show "(forced)" if user manually selected the depth
+                                                let forced = if clut_depth_override.is_some() { " forced" } else { "" };
+                                                texture_status = format!(" + CLUT {}{} ({} colors)", depth_label, forced, color_count);
+                                            }
 
-                                        // Set the editable mesh directly in project (single source of truth)
-                                        if let Some(mesh) = ms.modeler_state.mesh_mut() {
-                                            *mesh = result.mesh;
+                                            // Reset camera to fit the scaled mesh
+                                            ms.modeler_state.orbit_target = crate::rasterizer::Vec3::new(0.0, 50.0, 0.0);
+                                            ms.modeler_state.orbit_distance = scale * 3.0;
+                                            ms.modeler_state.sync_camera_from_orbit();
+
+                                            // Build flip status string
+                                            let mut flips = Vec::new();
+                                            if flip_normals { flips.push("N"); }
+                                            if flip_h { flips.push("H"); }
+                                            if flip_v { flips.push("V"); }
+                                            let flip_status = if flips.is_empty() {
+                                                String::new()
+                                            } else {
+                                                format!(" (flip: {})", flips.join("+"))
+                                            };
+                                            ms.modeler_state.set_status(
+                                                &format!("Imported: {} ({}x){}{}", path.display(), scale, texture_status, flip_status),
+                                                3.0
+                                            );
                                         }
-                                        // Don't set current_file to OBJ path - this is an IMPORT, not opening a project
-                                        // User must "Save As" to create a .ron project file
-                                        ms.modeler_state.current_file = None;
-                                        ms.modeler_state.dirty = true;  // Needs saving
-                                        ms.modeler_state.selection = modeler::ModelerSelection::None;
-
-                                        // Handle texture import
-                                        let mut texture_status = String::new();
-                                        if let Some(tex_result) = result.texture {
-                                            let TextureImportResult { mut indexed, clut, color_count } = tex_result;
-                                            // Clear existing CLUTs and add only the imported one
-                                            ms.modeler_state.clut_pool.clear();
-                                            let clut_id = ms.modeler_state.clut_pool.add_clut(clut);
-                                            indexed.default_clut = clut_id;
-                                            let depth_label = indexed.depth.short_label();
-                                            // Set the indexed atlas on the selected object
-                                            if let Some(atlas) = ms.modeler_state.atlas_mut() {
-                                                *atlas = indexed;
-                                            }
-                                            ms.modeler_state.selected_clut = Some(clut_id);
-                                            // Show "(forced)" if user manually selected the depth
-                                            let forced = if clut_depth_override.is_some() { " forced" } else { "" };
-                                            texture_status = format!(" + CLUT {}{} ({} colors)", depth_label, forced, color_count);
+                                        Err(e) => {
+                                            ms.modeler_state.set_status(&format!("Import failed: {}", e), 3.0);
                                         }
-
-                                        // Reset camera to fit the scaled mesh
-                                        ms.modeler_state.orbit_target = crate::rasterizer::Vec3::new(0.0, 50.0, 0.0);
-                                        ms.modeler_state.orbit_distance = scale * 3.0;
-                                        ms.modeler_state.sync_camera_from_orbit();
-
-                                        // Build flip status string
-                                        let mut flips = Vec::new();
-                                        if flip_normals { flips.push("N"); }
-                                        if flip_h { flips.push("H"); }
-                                        if flip_v { flips.push("V"); }
-                                        let flip_status = if flips.is_empty() {
-                                            String::new()
-                                        } else {
-                                            format!(" (flip: {})", flips.join("+"))
-                                        };
-                                        ms.modeler_state.set_status(
-                                            &format!("Imported: {} ({}x){}{}", path.display(), scale, texture_status, flip_status),
-                                            3.0
-                                        );
-                                    }
-                                    Err(e) => {
-                                        ms.modeler_state.set_status(&format!("Import failed: {}", e), 3.0);
                                     }
                                 }
                             }
@@ -1388,14 +1824,85 @@ async fn main() {
                 }
             }
 
+            Tool::Sfx => {
+                // On WASM, audio is rendered each frame to feed Web Audio
+                #[cfg(target_arch = "wasm32")]
+                app.tracker.audio.render_audio(get_frame_time() as f64);
+
+                sfx::draw_sfx_editor(
+                    &mut ui_ctx,
+                    content_rect,
+                    &mut app.sfx,
+                    &app.tracker.audio,
+                    &app.world_editor.editor_state.level.sounds,
+                    app.icon_font.as_ref(),
+                    &app.storage,
+                );
+            }
+
             Tool::InputTest => {
                 // Draw controller debug view
                 input::draw_controller_debug(content_rect, &mut app.input);
             }
         }
+        drop(ui_timer);
+
+        if app.clipboard_popup.open {
+            ui_ctx.end_modal(real_mouse_clipboard);
+            match clipboard_history::draw_clipboard_popup(&mut ui_ctx, clip_kinds) {
+                clipboard_history::ClipboardPopupAction::Restore(data) => {
+                    restore_clipboard(&mut app, data);
+                    app.clipboard_popup.open = false;
+                }
+                clipboard_history::ClipboardPopupAction::Close => app.clipboard_popup.open = false,
+                clipboard_history::ClipboardPopupAction::None => {}
+            }
+        }
+
+        if let Some(conflict) = app.sync_conflict.clone() {
+            ui_ctx.end_modal(real_mouse_sync);
+            let choice = cloud_sync::draw_conflict_dialog(&mut ui_ctx, &conflict);
+            if choice != ConflictChoice::None {
+                app.sync_conflict = None;
+                resolve_sync_conflict(&mut app, conflict, choice);
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(recovery) = &crash_recovery {
+            ui_ctx.end_modal(real_mouse_recovery);
+            match crash::draw_recovery_dialog(&mut ui_ctx, recovery) {
+                crash::RecoveryAction::Restore => {
+                    recovery.restore(&mut app);
+                    crash_recovery = None;
+                }
+                crash::RecoveryAction::Report => {
+                    let _ = webbrowser::open(&recovery.issue_url());
+                }
+                crash::RecoveryAction::Dismiss => crash_recovery = None,
+                crash::RecoveryAction::None => {}
+            }
+        }
 
-        // Draw tooltips last (on top of everything)
+        // Draw keyboard focus ring, gamepad cursor, tooltips and the profiler last (on top of everything)
+        ui_ctx.draw_focus_ring();
+        ui_ctx.gamepad.draw();
         ui_ctx.draw_tooltip();
+        profiler::draw_overlay();
+
+        // Ctrl+F1: dump the widget descriptions for assistive tooling
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+            if ctrl && is_key_pressed(KeyCode::F1) {
+                let snapshot = ui_ctx.accessibility_snapshot();
+                print!("{}", snapshot.to_text());
+                if let Ok(ron) = snapshot.to_ron() {
+                    let _ = std::fs::create_dir_all("assets/userdata");
+                    let _ = std::fs::write("assets/userdata/ui_snapshot.ron", ron);
+                }
+            }
+        }
 
         // Handle pending async level load (WASM) - after all drawing is complete
         #[cfg(target_arch = "wasm32")]
@@ -1628,6 +2135,7 @@ async fn main() {
                     app.modeler.model_browser.open = false;
                     app.modeler.obj_importer.open = false;
                     app.tracker.song_browser.open = false;
+                    crash::record(&format!("Switched to {}", tool.label()));
                     app.set_active_tool(tool);
                 }
             }
@@ -1636,37 +2144,39 @@ async fn main() {
             TabBarAction::None => {}
         }
 
-        // FPS limiting (only when in game tab)
-        if let Tool::Test = app.active_tool {
-            if let Some(target_frame_time) = app.game.fps_limit.frame_time() {
-                let elapsed = get_time() - frame_start;
-                let remaining = target_frame_time - elapsed;
+        // Apply window mode changes and remember the window position
+        app.game.display.update();
 
-                if remaining > 0.0 {
-                    // Native: use sleep for bulk, then spin-wait for precision
-                    #[cfg(not(target_arch = "wasm32"))]
-                    {
-                        let spin_margin = 0.002; // 2ms
-                        while get_time() - frame_start + spin_margin < target_frame_time {
-                            std::thread::sleep(std::time::Duration::from_millis(1));
-                        }
-                        // Spin-wait for precise timing
-                        while get_time() - frame_start < target_frame_time {
-                            std::hint::spin_loop();
-                        }
-                    }
-                    // WASM: just spin-wait (no thread::sleep available)
-                    #[cfg(target_arch = "wasm32")]
-                    {
-                        while get_time() - frame_start < target_frame_time {
-                            // Busy wait - browser will handle frame pacing
-                        }
-                    }
-                }
-            }
+        // Persist tag edits (also ticks the tag widgets' frame counter)
+        tags::end_frame(&app.storage);
+
+        // Crash report context and periodic autosave (held back while recovery is pending)
+        crash::set_open_file(Tool::WorldEditor.label(), app.world_editor.editor_state.current_file.as_deref());
+        crash::set_open_file(Tool::Modeler.label(), app.modeler.modeler_state.current_file.as_deref());
+        crash::set_open_file(Tool::Tracker.label(), app.tracker.current_file.as_deref());
+        #[cfg(not(target_arch = "wasm32"))]
+        if crash_recovery.is_none() {
+            autosaver.tick(&app);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        hot_reloader.tick(&mut app);
+
+        // Group volumes: the project's defaults unless overridden in the Test tab's menu
+        let mix = app.game.mix_levels(&app.project.level);
+        if mix != app.tracker.audio.mix_levels() {
+            app.tracker.audio.set_mix_levels(mix);
         }
 
+        // Audio thread mixing time, then close the profiler's frame (F9 shows it)
+        profiler::record(profiler::Subsystem::Audio, "mix", app.tracker.audio.take_mix_ms());
+        profiler::end_frame();
+
+        // Frame pacing (all tools; the limit is set from the Test tab's debug menu)
+        app.frame_pacer.set_limit(app.game.fps_limit);
+        app.frame_pacer.wait();
+
         next_frame().await;
+        app.frame_pacer.frame_presented(get_time());
     }
 }
 
@@ -1684,6 +2194,14 @@ fn poll_pending_ops(app: &mut AppState) {
                         3.0,
                     );
                 }
+                Some(Err(StorageError::Conflict { revision, updated_at })) => {
+                    app.world_editor.editor_state.set_status("Not saved: the cloud copy changed", 5.0);
+                    app.sync_conflict = Some(SyncConflict {
+                        target: SyncTarget::Level,
+                        path: pending.path.clone(),
+                        remote: RemoteVersion { revision, updated_at, size: 0 },
+                    });
+                }
                 Some(Err(e)) => {
                     app.world_editor.editor_state.set_status(&format!("Save failed: {}", e), 5.0);
                 }
@@ -1710,6 +2228,14 @@ fn poll_pending_ops(app: &mut AppState) {
                         3.0,
                     );
                 }
+                Some(Err(StorageError::Conflict { revision, updated_at })) => {
+                    app.modeler.modeler_state.set_status("Not saved: the cloud copy changed", 5.0);
+                    app.sync_conflict = Some(SyncConflict {
+                        target: SyncTarget::Asset,
+                        path: pending.path.clone(),
+                        remote: RemoteVersion { revision, updated_at, size: 0 },
+                    });
+                }
                 Some(Err(e)) => {
                     app.modeler.modeler_state.set_status(&format!("Save failed: {}", e), 5.0);
                 }
@@ -2405,6 +2931,25 @@ fn next_available_asset_path() -> PathBuf {
 }
 
 /// Handle save action with async support for cloud storage
+/// Run a console command that needs more than the game state (see `Console::execute`)
+fn run_console_host_command(app: &mut AppState, request: game::ConsoleRequest) {
+    let reply = match &request.command {
+        game::ConsoleCommand::Load(path) => match load_level_with_storage(path, &app.storage) {
+            Ok(level) => {
+                app.world_editor.editor_state.load_level(level, PathBuf::from(path));
+                app.project.level = app.world_editor.editor_state.level.clone();
+                app.game.reset_for_new_level();
+                app.game.playing = true;
+                app.console.level_loaded(&mut app.input);
+                game::ConsoleReply::Text("ok".to_string())
+            }
+            Err(e) => game::ConsoleReply::Error(format!("Load failed: {}", e)),
+        },
+        _ => game::ConsoleReply::Error("not a host command".to_string()),
+    };
+    request.respond(reply);
+}
+
 fn handle_save_action(app: &mut AppState) {
     // Don't start a new save if one is already in progress
     if app.pending_ops.save.is_some() {
@@ -2438,6 +2983,10 @@ fn handle_save_action(app: &mut AppState) {
         default_path
     };
 
+    // Keep baked lighting in sync with the geometry being saved
+    scene::bake_rooms_lighting(&mut ws.editor_state.level.rooms, &ws.editor_state.asset_library, true);
+    let budget_warnings = ws.editor_state.check_budget(app.tracker.song.num_channels());
+
     // Serialize level to bytes
     let data = match serialize_level(&ws.editor_state.level) {
         Ok(data) => data,
@@ -2463,7 +3012,7 @@ fn handle_save_action(app: &mut AppState) {
                 app.world_editor.editor_state.dirty = false;
                 let mode_label = storage.mode().label();
                 app.world_editor.editor_state.set_status(
-                    &format!("Saved ({}) {}", mode_label, save_path.display()),
+                    &format!("Saved ({}) {}{}", mode_label, save_path.display(), budget_note(budget_warnings)),
                     3.0,
                 );
             }
@@ -2474,7 +3023,115 @@ fn handle_save_action(app: &mut AppState) {
     }
 }
 
+/// Clipboard history entries each tab can restore
+fn clipboard_kinds(tool: Tool) -> &'static [clipboard_history::ClipKind] {
+    use clipboard_history::ClipKind;
+    match tool {
+        Tool::WorldEditor => &[ClipKind::Tiles, ClipKind::Texture],
+        Tool::Modeler => &[ClipKind::Mesh, ClipKind::Texture],
+        Tool::Tracker => &[ClipKind::Pattern],
+        Tool::Home | Tool::Test | Tool::Sfx | Tool::InputTest => &[],
+    }
+}
+
+/// Put a clipboard history entry back into the active editor's clipboard
+fn restore_clipboard(app: &mut AppState, data: clipboard_history::ClipData) {
+    use clipboard_history::ClipData;
+    let msg = format!("Restored {} ({}) - paste with Ctrl+V", data.kind().label(), data.describe());
+    match data {
+        ClipData::Texture(c) if app.active_tool == Tool::Modeler => {
+            app.modeler.modeler_state.texture_editor.clipboard = Some(c);
+            app.modeler.modeler_state.set_status(&msg, 3.0);
+        }
+        ClipData::Texture(c) => {
+            app.world_editor.editor_state.texture_editor.clipboard = Some(c);
+            app.world_editor.editor_state.set_status(&msg, 3.0);
+        }
+        ClipData::Tiles(gc) => {
+            app.world_editor.editor_state.geometry_clipboard = Some(gc);
+            app.world_editor.editor_state.set_status(&msg, 3.0);
+        }
+        ClipData::Pattern(notes) => {
+            app.tracker.clipboard = Some(notes);
+            app.tracker.set_status(&msg, 3.0);
+        }
+        ClipData::Mesh(clipboard) => {
+            app.modeler.modeler_state.clipboard = clipboard;
+            app.modeler.modeler_state.set_status(&msg, 3.0);
+        }
+    }
+}
+
+/// Status suffix for content budget violations found on save
+fn budget_note(violations: usize) -> String {
+    match violations {
+        0 => String::new(),
+        1 => " - 1 budget violation (see Debug panel)".to_string(),
+        n => format!(" - {} budget violations (see Debug panel)", n),
+    }
+}
+
+/// Status suffix for problems repaired while loading a level (each is logged)
+fn repaired_note(issues: &[load_check::LoadIssue]) -> String {
+    for issue in issues {
+        eprintln!("Repaired {}", issue);
+    }
+    match issues.len() {
+        0 => String::new(),
+        1 => " - repaired 1 problem (see log)".to_string(),
+        n => format!(" - repaired {} problems (see log)", n),
+    }
+}
+
+/// First line of a level load error for the status bar; the whole error
+/// (parse errors quote the offending line) goes to the log
+fn load_error_summary(error: &world::LevelError) -> String {
+    let message = error.to_string();
+    eprintln!("Level load failed: {}", message);
+    message.lines().next().unwrap_or_default().to_string()
+}
+
 /// Handle modeler save action with async support for cloud storage
+/// Save a confirmed checkpoint, and take an auto snapshot when the interval
+/// has passed and the open model changed since the last one
+fn handle_modeler_snapshots(app: &mut AppState) {
+    use modeler::snapshots::{self, SnapshotKind};
+
+    let state = &mut app.modeler.modeler_state;
+    let Some(model) = state.current_file.as_deref().and_then(snapshots::model_key) else {
+        state.pending_checkpoint = None;
+        return;
+    };
+    let now = get_time();
+
+    if let Some(label) = state.pending_checkpoint.take() {
+        match snapshots::save_snapshot(&app.storage, &model, &state.asset, SnapshotKind::Checkpoint, &label) {
+            Ok(meta) => {
+                state.auto_snapshot.reset(now, snapshots::content_hash(&state.asset));
+                state.set_status(&format!("{} saved", meta.title()), 2.0);
+            }
+            Err(e) => state.set_status(&format!("Checkpoint failed: {}", e), 3.0),
+        }
+        return;
+    }
+
+    if state.auto_snapshot.model.as_deref() != Some(model.as_str()) {
+        let hash = snapshots::content_hash(&state.asset);
+        state.auto_snapshot.switch_model(&model, now, hash);
+        return;
+    }
+    if !state.auto_snapshot.due(now) {
+        return;
+    }
+    let hash = snapshots::content_hash(&state.asset);
+    if state.auto_snapshot.changed(hash) {
+        if let Err(e) = snapshots::save_snapshot(&app.storage, &model, &state.asset, SnapshotKind::Auto, "") {
+            eprintln!("Auto snapshot failed: {}", e);
+        }
+    }
+    state.auto_snapshot.reset(now, hash);
+}
+
 fn handle_modeler_save_action(app: &mut AppState) {
     // Don't start a new save if one is already in progress
     if app.pending_ops.modeler_save.is_some() {
@@ -2530,6 +3187,116 @@ fn handle_modeler_save_action(app: &mut AppState) {
     }
 }
 
+/// Open a level in the World Editor, restoring its saved layout and camera
+fn open_level(ws: &mut app::WorldEditorState, game: &mut game::GameToolState, level: world::Level, path: PathBuf) {
+    ws.editor_layout.apply_config(&level.editor_layout);
+    ws.editor_state.grid_offset_x = level.editor_layout.grid_offset_x;
+    ws.editor_state.grid_offset_y = level.editor_layout.grid_offset_y;
+    ws.editor_state.grid_zoom = level.editor_layout.grid_zoom;
+    ws.editor_state.orbit_target = rasterizer::Vec3::new(
+        level.editor_layout.orbit_target_x,
+        level.editor_layout.orbit_target_y,
+        level.editor_layout.orbit_target_z,
+    );
+    ws.editor_state.orbit_distance = level.editor_layout.orbit_distance;
+    ws.editor_state.orbit_azimuth = level.editor_layout.orbit_azimuth;
+    ws.editor_state.orbit_elevation = level.editor_layout.orbit_elevation;
+    ws.editor_state.sync_camera_from_orbit();
+    // Use load_level to preserve texture packs (important for WASM)
+    ws.editor_state.load_level(level, path);
+    // Reset game state for the new level
+    game.reset_for_new_level();
+}
+
+/// Open an asset in the modeler
+fn open_asset(state: &mut modeler::ModelerState, asset: asset::Asset, path: PathBuf) {
+    // Set the asset directly in the modeler
+    state.asset = asset;
+    state.selected_object = if state.objects().is_empty() { None } else { Some(0) };
+    // Auto-select first mesh component so it's visible
+    state.selected_component = state.asset.components.iter().position(|c| c.is_mesh());
+    // Resolve ID-based texture refs using the texture library
+    state.resolve_all_texture_refs();
+    state.current_file = Some(path);
+    state.dirty = false;
+    state.selection = modeler::ModelerSelection::None;
+}
+
+/// Act on the answer to the cloud save conflict dialog
+fn resolve_sync_conflict(app: &mut AppState, conflict: SyncConflict, choice: ConflictChoice) {
+    let path_str = conflict.path.to_string_lossy().to_string();
+    match choice {
+        ConflictChoice::KeepMine => {
+            // Base the save on the newer copy so it overwrites it
+            versions::record_base(&path_str, conflict.remote);
+            match conflict.target {
+                SyncTarget::Level => handle_save_action(app),
+                SyncTarget::Asset => handle_modeler_save_action(app),
+            }
+        }
+        ConflictChoice::KeepTheirs => {
+            let data = match app.storage.read(&path_str).take() {
+                Some(Ok(data)) => data,
+                Some(Err(e)) => {
+                    set_sync_status(app, conflict.target, &format!("Reload failed: {}", e));
+                    return;
+                }
+                None => {
+                    set_sync_status(app, conflict.target, "Reload failed: still loading");
+                    return;
+                }
+            };
+            match conflict.target {
+                SyncTarget::Level => match world::parse_level_data(&data) {
+                    Ok(level) => {
+                        open_level(&mut app.world_editor, &mut app.game, level, conflict.path.clone());
+                        app.world_editor.editor_state.set_status(&format!("Reloaded cloud copy: {}", conflict.path.display()), 3.0);
+                    }
+                    Err(e) => app.world_editor.editor_state.set_status(&format!("Reload failed: {}", e), 5.0),
+                },
+                SyncTarget::Asset => match asset::Asset::load_from_bytes(&data) {
+                    Ok(asset) => {
+                        open_asset(&mut app.modeler.modeler_state, asset, conflict.path.clone());
+                        app.modeler.modeler_state.set_status(&format!("Reloaded cloud copy: {}", conflict.path.display()), 3.0);
+                    }
+                    Err(e) => app.modeler.modeler_state.set_status(&format!("Reload failed: {}", e), 5.0),
+                },
+            }
+        }
+        ConflictChoice::SaveAsCopy => {
+            let dir = conflict.path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+            let taken = app.storage.list(&dir).take().and_then(|r| r.ok()).unwrap_or_default();
+            let copy = PathBuf::from(versions::copy_path(&path_str, &taken));
+            match conflict.target {
+                SyncTarget::Level => {
+                    app.world_editor.editor_state.current_file = Some(copy);
+                    handle_save_action(app);
+                }
+                SyncTarget::Asset => {
+                    let state = &mut app.modeler.modeler_state;
+                    // A copy is a new asset, not another revision of the original
+                    state.asset.id = asset::generate_asset_id();
+                    if let Some(stem) = copy.file_stem() {
+                        state.asset.name = stem.to_string_lossy().to_string();
+                    }
+                    state.current_file = Some(copy);
+                    handle_modeler_save_action(app);
+                }
+            }
+        }
+        ConflictChoice::Cancel => set_sync_status(app, conflict.target, "Not saved: the cloud copy changed"),
+        ConflictChoice::None => {}
+    }
+}
+
+/// Status line of the editor a sync conflict belongs to
+fn set_sync_status(app: &mut AppState, target: SyncTarget, message: &str) {
+    match target {
+        SyncTarget::Level => app.world_editor.editor_state.set_status(message, 5.0),
+        SyncTarget::Asset => app.modeler.modeler_state.set_status(message, 5.0),
+    }
+}
+
 fn handle_editor_action(action: EditorAction, app: &mut AppState) {
     let storage = &app.storage;
     let ws = &mut app.world_editor;
@@ -2584,12 +3351,17 @@ fn handle_editor_action(action: EditorAction, app: &mut AppState) {
 
             if let Some(save_path) = dialog.save_file() {
                 let path_str = save_path.to_string_lossy();
+                scene::bake_rooms_lighting(&mut ws.editor_state.level.rooms, &ws.editor_state.asset_library, true);
+                let budget_warnings = ws.editor_state.check_budget(app.tracker.song.num_channels());
                 match save_level_with_storage(&ws.editor_state.level, &path_str, storage) {
                     Ok(()) => {
                         ws.editor_state.current_file = Some(save_path.clone());
                         ws.editor_state.dirty = false;
                         let mode_label = storage.mode().label();
-                        ws.editor_state.set_status(&format!("Saved ({}) {}", mode_label, save_path.display()), 3.0);
+                        ws.editor_state.set_status(
+                            &format!("Saved ({}) {}{}", mode_label, save_path.display(), budget_note(budget_warnings)),
+                            3.0,
+                        );
                     }
                     Err(e) => {
                         ws.editor_state.set_status(&format!("Save failed: {}", e), 5.0);
@@ -2612,8 +3384,8 @@ fn handle_editor_action(action: EditorAction, app: &mut AppState) {
 
             if let Some(path) = dialog.pick_file() {
                 let path_str = path.to_string_lossy();
-                match load_level_with_storage(&path_str, storage) {
-                    Ok(level) => {
+                match world::repair_level_with_storage(&path_str, storage) {
+                    Ok((level, issues)) => {
                         ws.editor_layout.apply_config(&level.editor_layout);
                         ws.editor_state.grid_offset_x = level.editor_layout.grid_offset_x;
                         ws.editor_state.grid_offset_y = level.editor_layout.grid_offset_y;
@@ -2630,10 +3402,10 @@ fn handle_editor_action(action: EditorAction, app: &mut AppState) {
                         ws.editor_state.load_level(level, path.clone());
                         // Reset game state for the new level
                         game.reset_for_new_level();
-                        ws.editor_state.set_status(&format!("Loaded {}", path.display()), 3.0);
+                        ws.editor_state.set_status(&format!("Loaded {}{}", path.display(), repaired_note(&issues)), 3.0);
                     }
                     Err(e) => {
-                        ws.editor_state.set_status(&format!("Load failed: {}", e), 5.0);
+                        ws.editor_state.set_status(&format!("Load failed: {}", load_error_summary(&e)), 5.0);
                     }
                 }
             }
@@ -2654,7 +3426,7 @@ fn handle_editor_action(action: EditorAction, app: &mut AppState) {
                 ws.editor_state.orbit_elevation,
             );
 
-            match ron::ser::to_string_pretty(&ws.editor_state.level, ron::ser::PrettyConfig::default()) {
+            match world::level_to_ron(&ws.editor_state.level) {
                 Ok(ron_str) => {
                     let filename = ws.editor_state.current_file
                         .as_ref()
@@ -2701,8 +3473,8 @@ fn handle_editor_action(action: EditorAction, app: &mut AppState) {
         }
         EditorAction::Load(path_str) => {
             let path = PathBuf::from(&path_str);
-            match load_level_with_storage(&path_str, storage) {
-                Ok(level) => {
+            match world::repair_level_with_storage(&path_str, storage) {
+                Ok((level, issues)) => {
                     ws.editor_layout.apply_config(&level.editor_layout);
                     ws.editor_state.grid_offset_x = level.editor_layout.grid_offset_x;
                     ws.editor_state.grid_offset_y = level.editor_layout.grid_offset_y;
@@ -2719,10 +3491,10 @@ fn handle_editor_action(action: EditorAction, app: &mut AppState) {
                     ws.editor_state.load_level(level, path.clone());
                     // Reset game state for the new level
                     game.reset_for_new_level();
-                    ws.editor_state.set_status(&format!("Loaded {}", path.display()), 3.0);
+                    ws.editor_state.set_status(&format!("Loaded {}{}", path.display(), repaired_note(&issues)), 3.0);
                 }
                 Err(e) => {
-                    ws.editor_state.set_status(&format!("Load failed: {}", e), 5.0);
+                    ws.editor_state.set_status(&format!("Load failed: {}", load_error_summary(&e)), 5.0);
                 }
             }
         }
@@ -2746,6 +3518,42 @@ fn handle_editor_action(action: EditorAction, app: &mut AppState) {
             }
             ws.editor_state.set_status("Browse levels", 2.0);
         }
+        EditorAction::Share => {
+            // Bundle the level with its user textures and publish it
+            let name = ws.editor_state.current_file.as_ref()
+                .and_then(|p| p.file_stem())
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "shared".to_string());
+            let shared = editor::pack_shared_level(&name, &ws.editor_state.level, &ws.editor_state.user_textures)
+                .and_then(|data| match storage.share(&data).take() {
+                    Some(Ok(token)) => Ok(token),
+                    Some(Err(e)) => Err(e.to_string()),
+                    None => Err("upload still in progress".to_string()),
+                });
+            match shared {
+                Ok(token) => {
+                    let link = storage::gcp::GcpStorage::shared_url(&token);
+                    macroquad::miniquad::window::clipboard_set(&link);
+                    ws.editor_state.set_status(&format!("Share link copied: {}", link), 8.0);
+                }
+                Err(e) => ws.editor_state.set_status(&format!("Share failed: {}", e), 5.0),
+            }
+        }
+        EditorAction::SavePrefab => {
+            match editor::clipboard_from_selection(&ws.editor_state, true) {
+                Some(gc) => {
+                    let prefab = editor::Prefab::from_clipboard("prefab", &gc);
+                    ws.prefab_browser.open_save(prefab, editor::discover_prefabs());
+                }
+                None => ws.editor_state.set_status("Select sectors, faces or objects to save as a prefab", 3.0),
+            }
+        }
+        EditorAction::OpenPrefabBrowser => {
+            ws.prefab_browser.open_with_prefabs(editor::discover_prefabs());
+        }
+        EditorAction::OpenDungeonWizard => {
+            ws.dungeon_wizard.open();
+        }
         EditorAction::SwitchToModeler => {
             // Switch to Asset Editor and create a new asset
             app.active_tool = Tool::Modeler;
@@ -2756,6 +3564,235 @@ fn handle_editor_action(action: EditorAction, app: &mut AppState) {
     }
 }
 
+/// Handle prefab browser actions (saving, stamping and deleting prefabs)
+fn handle_prefab_browser_action(action: PrefabBrowserAction, ws: &mut app::WorldEditorState) {
+    match action {
+        PrefabBrowserAction::SelectPreview(index) => {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                if let Some(info) = ws.prefab_browser.prefabs.get(index) {
+                    match editor::load_prefab(&info.path) {
+                        Ok(prefab) => ws.prefab_browser.preview = Some(prefab),
+                        Err(e) => {
+                            ws.prefab_browser.preview = None;
+                            ws.editor_state.set_status(&format!("Failed to load prefab: {}", e), 3.0);
+                        }
+                    }
+                }
+            }
+            #[cfg(target_arch = "wasm32")]
+            let _ = index;
+        }
+        PrefabBrowserAction::Stamp => {
+            if let Some(mut prefab) = ws.prefab_browser.preview.take() {
+                let remap = prefab.remap_textures(&ws.editor_state.texture_packs, &ws.editor_state.user_textures);
+                ws.editor_state.geometry_clipboard = Some(prefab.to_clipboard());
+                ws.editor_state.tool = editor::EditorTool::Select;
+                let mut status = format!("Click in the 3D view to stamp '{}' (R rotate, H/V flip, Esc cancel)", prefab.name);
+                if remap.missing > 0 {
+                    status.push_str(&format!(" - {} missing textures", remap.missing));
+                } else if remap.remapped > 0 {
+                    status.push_str(&format!(" - {} textures remapped", remap.remapped));
+                }
+                ws.editor_state.set_status(&status, 5.0);
+                ws.prefab_browser.close();
+            }
+        }
+        PrefabBrowserAction::Delete => {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                if let Some(info) = ws.prefab_browser.selected_prefab() {
+                    let name = info.name.clone();
+                    match std::fs::remove_file(&info.path) {
+                        Ok(()) => {
+                            ws.editor_state.set_status(&format!("Deleted prefab '{}'", name), 2.0);
+                            ws.prefab_browser.open_with_prefabs(editor::discover_prefabs());
+                        }
+                        Err(e) => ws.editor_state.set_status(&format!("Delete failed: {}", e), 3.0),
+                    }
+                }
+            }
+        }
+        PrefabBrowserAction::Save => {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                let name = ws.prefab_browser.save_name().unwrap_or_default();
+                if editor::prefab_file_stem(&name).is_none() {
+                    ws.editor_state.set_status("Name cannot be empty", 3.0);
+                } else if let Some(mut prefab) = ws.prefab_browser.pending_save.take() {
+                    prefab.name = name;
+                    match editor::save_prefab(&prefab) {
+                        Ok(_) => {
+                            ws.editor_state.set_status(&format!("Saved prefab '{}'", prefab.name), 2.0);
+                            ws.prefab_browser.close();
+                        }
+                        Err(e) => {
+                            ws.editor_state.set_status(&format!("Failed to save prefab: {}", e), 3.0);
+                            ws.prefab_browser.pending_save = Some(prefab);
+                        }
+                    }
+                }
+            }
+        }
+        PrefabBrowserAction::Cancel => ws.prefab_browser.close(),
+        PrefabBrowserAction::None => {}
+    }
+}
+
+/// Import a glTF file from the mesh browser: each mesh node becomes a part,
+/// all parts share the quantized base color texture
+#[cfg(not(target_arch = "wasm32"))]
+fn import_gltf(state: &mut modeler::ModelerState, browser: &modeler::ObjImportBrowser, path: &std::path::Path) {
+    let scale = browser.import_scale;
+    let result = match GltfImporter::import(path, scale, browser.clut_depth_override, &browser.quantize_options) {
+        Ok(result) => result,
+        Err(e) => {
+            state.set_status(&format!("Import failed: {}", e), 3.0);
+            return;
+        }
+    };
+
+    // Texture goes into a fresh CLUT pool, like OBJ imports
+    let mut atlas = None;
+    let mut texture_status = String::new();
+    if let Some(TextureImportResult { mut indexed, clut, color_count }) = result.texture {
+        state.clut_pool.clear();
+        let clut_id = state.clut_pool.add_clut(clut);
+        indexed.default_clut = clut_id;
+        state.selected_clut = Some(clut_id);
+        texture_status = format!(" + CLUT {} ({} colors)", indexed.depth.short_label(), color_count);
+        atlas = Some(indexed);
+    }
+
+    let part_count = result.parts.len();
+    let parts: Vec<modeler::MeshPart> = result.parts.into_iter().map(|part| {
+        let mut mesh = part.mesh;
+        if browser.flip_normals {
+            for vertex in &mut mesh.vertices {
+                vertex.normal = vertex.normal * -1.0;
+            }
+            for face in &mut mesh.faces {
+                face.vertices.reverse();
+            }
+        }
+        if browser.flip_horizontal {
+            modeler::apply_mesh_flip_horizontal(&mut mesh);
+        }
+        if browser.flip_vertical {
+            modeler::apply_mesh_flip_vertical(&mut mesh);
+        }
+        match &atlas {
+            Some(atlas) => modeler::MeshPart::with_mesh_and_atlas(part.name, mesh, atlas.clone()),
+            None => modeler::MeshPart::with_mesh(part.name, mesh),
+        }
+    }).collect();
+
+    if let Some(objects) = state.objects_mut() {
+        *objects = parts;
+    } else {
+        for part in parts {
+            state.add_object(part);
+        }
+    }
+    state.selected_object = Some(0);
+    state.current_file = None;
+    state.dirty = true;
+    state.selection = modeler::ModelerSelection::None;
+    state.orbit_target = crate::rasterizer::Vec3::new(0.0, 50.0, 0.0);
+    state.orbit_distance = scale * 3.0;
+    state.sync_camera_from_orbit();
+    state.set_status(
+        &format!("Imported: {} ({} parts, {}x){}", path.display(), part_count, scale, texture_status),
+        3.0,
+    );
+}
+
+/// Render the modeler asset into a sprite sheet and save it as PNG
+#[cfg(not(target_arch = "wasm32"))]
+fn export_sprite_sheet(state: &mut modeler::ModelerState, settings: &modeler::SpriteSheetSettings) {
+    // Assets don't carry animation clips yet, so this renders the bind pose
+    let sheet = modeler::render_sprite_sheet(
+        state.objects(),
+        state.skeleton(),
+        &state.clut_pool,
+        None,
+        settings,
+        &state.raster_settings,
+        get_time,
+    );
+    let png = match sheet.encode_png() {
+        Ok(png) => png,
+        Err(e) => {
+            state.set_status(&format!("Sprite sheet export failed: {}", e), 5.0);
+            return;
+        }
+    };
+    let filename = format!("{}_sheet.png", state.asset.name);
+    let summary = format!(
+        "{}x{} sheet ({} angles x {} frames, {} colors)",
+        sheet.width, sheet.height, sheet.rows, sheet.columns, modeler::sprite_sheet_color_count(&sheet),
+    );
+
+    let dialog = rfd::FileDialog::new()
+        .add_filter("PNG Image", &["png"])
+        .set_file_name(&filename);
+    if let Some(path) = dialog.save_file() {
+        match std::fs::write(&path, &png) {
+            Ok(()) => state.set_status(&format!("Exported {} to {}", summary, path.display()), 3.0),
+            Err(e) => state.set_status(&format!("Sprite sheet export failed: {}", e), 5.0),
+        }
+    }
+}
+
+/// Export the modeler asset as OBJ (+MTL, PNG) or glTF. Native asks where to
+/// save and writes side files next to the main one; the browser downloads
+/// every file.
+fn export_model(state: &mut modeler::ModelerState, format: modeler::ModelExportFormat) {
+    #[cfg(not(target_arch = "wasm32"))]
+    let (stem, dir) = {
+        let dialog = rfd::FileDialog::new()
+            .add_filter(format.label(), &[format.extension()])
+            .set_file_name(&format!("{}.{}", state.asset.name, format.extension()));
+        let Some(path) = dialog.save_file() else {
+            return;
+        };
+        let stem = path.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| state.asset.name.clone());
+        (stem, path.parent().map(|p| p.to_path_buf()).unwrap_or_default())
+    };
+    #[cfg(target_arch = "wasm32")]
+    let stem = state.asset.name.clone();
+
+    let files = match modeler::export_model(format, state.objects(), state.skeleton(), &state.clut_pool, &stem) {
+        Ok(files) => files,
+        Err(e) => {
+            state.set_status(&format!("{} export failed: {}", format.label(), e), 5.0);
+            return;
+        }
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    for file in &files {
+        if let Err(e) = std::fs::write(dir.join(&file.name), &file.bytes) {
+            state.set_status(&format!("{} export failed: {}", format.label(), e), 5.0);
+            return;
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    for file in &files {
+        extern "C" {
+            fn b32_download_bytes(name_ptr: *const u8, name_len: usize, data_ptr: *const u8, data_len: usize);
+        }
+        unsafe {
+            b32_download_bytes(file.name.as_ptr(), file.name.len(), file.bytes.as_ptr(), file.bytes.len());
+        }
+    }
+
+    let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+    state.set_status(&format!("Exported {}", names.join(", ")), 3.0);
+}
+
 fn handle_modeler_action(
     action: ModelerAction,
     state: &mut modeler::ModelerState,
@@ -2889,6 +3926,35 @@ fn handle_modeler_action(
         ModelerAction::Import => {
             state.set_status("Import is for browser - use Open", 3.0);
         }
+        #[cfg(not(target_arch = "wasm32"))]
+        ModelerAction::ExportSpriteSheet(settings) => {
+            export_sprite_sheet(state, &settings);
+        }
+        #[cfg(target_arch = "wasm32")]
+        ModelerAction::ExportSpriteSheet(_) => {
+            // The browser download bridge only carries text
+            state.set_status("Sprite sheet export not yet available in browser", 3.0);
+        }
+        ModelerAction::ExportModel(format) => {
+            export_model(state, format);
+        }
         ModelerAction::None => {}
     }
 }
+
+/// Move a renamed level's tags and thumbnail to its new name
+#[cfg(not(target_arch = "wasm32"))]
+fn rename_level_library_entries(storage: &Storage, category: LevelCategory, old_name: &str, new_name: &str) {
+    let old_key = format!("{}{}", category.prefix(), old_name);
+    let new_key = format!("{}{}", category.prefix(), new_name);
+    tags::with_tags(|t| t.rename_item(tags::TagKind::Level, &old_key, &new_key));
+    thumbnails::rename(storage, tags::TagKind::Level, &old_key, &new_key);
+}
+
+/// Stop the level's song, if one is playing
+fn stop_level_music(app: &mut AppState) {
+    if app.game.music.song().is_some() || app.tracker.has_cues() {
+        app.tracker.stop_preview_playback();
+        app.game.music.stopped();
+    }
+}
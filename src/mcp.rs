@@ -0,0 +1,225 @@
+//! MCP server for end-to-end tests (native only)
+//!
+//! Serves the Model Context Protocol over HTTP (JSON-RPC 2.0 POSTed to
+//! `/mcp`) so an external test runner can play levels. Every tool is a thin
+//! wrapper over a `game::console` command, run by the main loop on its next
+//! Test tab frame; the server thread blocks until the reply comes back.
+//!
+//! Off by default. Start the editor with `--mcp <port>` or set
+//! `B32_MCP_PORT` to listen on localhost.
+
+use std::sync::mpsc::{RecvTimeoutError, Sender};
+use std::time::Duration;
+use serde_json::{json, Value};
+use crate::game::{ConsoleCommand, ConsoleReply, ConsoleRequest};
+
+/// Environment variable that enables the server
+pub const MCP_PORT_ENV: &str = "B32_MCP_PORT";
+/// MCP revision this server speaks
+const PROTOCOL_VERSION: &str = "2025-03-26";
+/// Longest wait for the game loop (a maximal `step` at 60 fps, plus slack)
+const REPLY_TIMEOUT: Duration = Duration::from_secs(11 * 60);
+
+/// Port from `--mcp <port>` or `B32_MCP_PORT`, if either is set
+pub fn port_from_env() -> Option<u16> {
+    let args: Vec<String> = std::env::args().collect();
+    let from_args = args.iter()
+        .position(|a| a == "--mcp")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|p| p.parse().ok());
+    from_args.or_else(|| std::env::var(MCP_PORT_ENV).ok()?.parse().ok())
+}
+
+/// Start the server on a background thread
+pub fn start(port: u16, console: Sender<ConsoleRequest>) -> Result<(), String> {
+    let server = tiny_http::Server::http(("127.0.0.1", port))
+        .map_err(|e| format!("Failed to start MCP server on port {}: {}", port, e))?;
+    std::thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            if request.method() != &tiny_http::Method::Post || request.url() != "/mcp" {
+                let _ = request.respond(tiny_http::Response::from_string("Not Found").with_status_code(404));
+                continue;
+            }
+            let mut body = String::new();
+            if request.as_reader().read_to_string(&mut body).is_err() {
+                let _ = request.respond(tiny_http::Response::from_string("Bad Request").with_status_code(400));
+                continue;
+            }
+            let response = match handle_message(&body, &console) {
+                Some(reply) => tiny_http::Response::from_string(reply.to_string())
+                    .with_header(tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap()),
+                // Notifications get no body
+                None => tiny_http::Response::from_string("").with_status_code(202),
+            };
+            let _ = request.respond(response);
+        }
+    });
+    println!("MCP server listening on http://127.0.0.1:{}/mcp", port);
+    Ok(())
+}
+
+/// Handle one JSON-RPC message. Returns None for notifications.
+fn handle_message(body: &str, console: &Sender<ConsoleRequest>) -> Option<Value> {
+    let message: Value = match serde_json::from_str(body) {
+        Ok(v) => v,
+        Err(e) => return Some(rpc_error(Value::Null, -32700, &format!("Parse error: {}", e))),
+    };
+    let id = message.get("id").cloned()?;
+    let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "bonnie-32", "version": env!("CARGO_PKG_VERSION") },
+        }),
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tool_list() }),
+        "tools/call" => {
+            let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+            let args = params.get("arguments").cloned().unwrap_or(Value::Null);
+            match tool_command(name, &args) {
+                Ok(line) => run_command(&line, console),
+                Err(e) => tool_result_error(&e),
+            }
+        }
+        _ => return Some(rpc_error(id, -32601, &format!("Method not found: {}", method))),
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+fn rpc_error(id: Value, code: i32, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn tool_result_error(message: &str) -> Value {
+    json!({ "content": [{ "type": "text", "text": message }], "isError": true })
+}
+
+/// Tool descriptions for `tools/list`
+fn tool_list() -> Value {
+    let string_arg = |name: &str, description: &str| json!({
+        "type": "object",
+        "properties": { name: { "type": "string", "description": description } },
+        "required": [name],
+    });
+    json!([
+        {
+            "name": "console",
+            "description": format!("Run a raw console command.\n{}", crate::game::console::CONSOLE_HELP),
+            "inputSchema": string_arg("command", "Console command line"),
+        },
+        {
+            "name": "load_level",
+            "description": "Load a level file and start playing it in the Test tab",
+            "inputSchema": string_arg("path", "Level path (.ron)"),
+        },
+        {
+            "name": "input",
+            "description": "Press, release or tap an action (forward, back, left, right, jump, attack, interact, ...)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "action": { "type": "string" },
+                    "state": { "type": "string", "enum": ["press", "release", "tap"] },
+                },
+                "required": ["action", "state"],
+            },
+        },
+        {
+            "name": "step",
+            "description": "Advance the game a number of frames at a fixed 1/60s; returns the frame counter",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "frames": { "type": "integer", "minimum": 1 } },
+            },
+        },
+        {
+            "name": "get_state",
+            "description": "Read game state as JSON: playing, frame, player.pos, player.health, var.<name>, messages",
+            "inputSchema": string_arg("key", "State key"),
+        },
+        {
+            "name": "capture_frame",
+            "description": "Capture the Test tab's current frame as a PNG",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+    ])
+}
+
+/// Required string argument of a tool call
+fn str_arg<'a>(args: &'a Value, key: &str) -> Result<&'a str, String> {
+    args.get(key).and_then(Value::as_str).ok_or_else(|| format!("missing argument '{}'", key))
+}
+
+/// Console line for a tool call
+fn tool_command(name: &str, args: &Value) -> Result<String, String> {
+    let arg = |key: &str| str_arg(args, key);
+    match name {
+        "console" => arg("command").map(str::to_string),
+        "load_level" => Ok(format!("load {}", arg("path")?)),
+        "input" => Ok(format!("{} {}", arg("state")?, arg("action")?)),
+        "step" => Ok(format!("step {}", args.get("frames").and_then(Value::as_u64).unwrap_or(1))),
+        "get_state" => Ok(format!("get {}", arg("key")?)),
+        "capture_frame" => Ok("capture".to_string()),
+        _ => Err(format!("unknown tool '{}'", name)),
+    }
+}
+
+/// Parse a console line, run it on the game loop and wrap the reply
+fn run_command(line: &str, console: &Sender<ConsoleRequest>) -> Value {
+    let command = match ConsoleCommand::parse(line) {
+        Ok(c) => c,
+        Err(e) => return tool_result_error(&e),
+    };
+    let (request, reply) = ConsoleRequest::new(command);
+    if console.send(request).is_err() {
+        return tool_result_error("game loop is not running");
+    }
+    match reply.recv_timeout(REPLY_TIMEOUT) {
+        Ok(ConsoleReply::Text(text)) => json!({ "content": [{ "type": "text", "text": text }] }),
+        Ok(ConsoleReply::Png(png)) => {
+            let data = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &png);
+            json!({ "content": [{ "type": "image", "data": data, "mimeType": "image/png" }] })
+        }
+        Ok(ConsoleReply::Error(e)) => tool_result_error(&e),
+        Err(RecvTimeoutError::Timeout) => tool_result_error("timed out waiting for the game (is the Test tab open?)"),
+        Err(RecvTimeoutError::Disconnected) => tool_result_error("game loop dropped the request"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_command_lines() {
+        assert_eq!(tool_command("input", &json!({ "action": "jump", "state": "tap" })), Ok("tap jump".to_string()));
+        assert_eq!(tool_command("step", &json!({})), Ok("step 1".to_string()));
+        assert_eq!(tool_command("load_level", &json!({ "path": "a.ron" })), Ok("load a.ron".to_string()));
+        assert!(tool_command("get_state", &json!({})).is_err());
+        assert!(tool_command("fly", &json!({})).is_err());
+    }
+
+    #[test]
+    fn test_protocol_messages() {
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let init = handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#, &tx).unwrap();
+        assert_eq!(init["result"]["protocolVersion"], PROTOCOL_VERSION);
+
+        let list = handle_message(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#, &tx).unwrap();
+        assert_eq!(list["result"]["tools"].as_array().unwrap().len(), 6);
+
+        assert!(handle_message(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#, &tx).is_none());
+        let missing = handle_message(r#"{"jsonrpc":"2.0","id":3,"method":"bogus"}"#, &tx).unwrap();
+        assert_eq!(missing["error"]["code"], -32601);
+
+        // Bad commands fail before reaching the game loop
+        let bad = handle_message(
+            r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"console","arguments":{"command":"dance"}}}"#,
+            &tx,
+        ).unwrap();
+        assert_eq!(bad["result"]["isError"], true);
+    }
+}
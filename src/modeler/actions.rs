@@ -71,6 +71,15 @@ pub fn create_modeler_actions() -> ActionRegistry {
             .category("File"),
     );
 
+    registry.register(
+        Action::new("file.checkpoint")
+            .label("Checkpoint")
+            .shortcut(Shortcut::ctrl(KeyCode::K))
+            .icon(icon::MAP_PIN)
+            .status_tip("Save a snapshot to the model's version history")
+            .category("File"),
+    );
+
     registry.register(
         Action::new("file.browse_models")
             .label("Browse Assets")
@@ -239,6 +248,15 @@ pub fn create_modeler_actions() -> ActionRegistry {
             .category("Transform"),
     );
 
+    registry.register(
+        Action::new("transform.toggle_pivot")
+            .label("Toggle Pivot")
+            .shortcut(Shortcut::key(KeyCode::Period))
+            .icon(icon::CIRCLE_DOT)
+            .status_tip("Toggle between Median and Individual Origins pivot (.)")
+            .category("Transform"),
+    );
+
     // ========================================================================
     // View Actions
     // ========================================================================
@@ -277,6 +295,14 @@ pub fn create_modeler_actions() -> ActionRegistry {
             .category("Mesh"),
     );
 
+    registry.register(
+        Action::new("mesh.mirror_weld")
+            .label("Mirror and Weld")
+            .shortcut(Shortcut::shift(KeyCode::M))
+            .status_tip("Mirror the + side across the symmetry plane and weld the seam (Shift+M)")
+            .category("Mesh"),
+    );
+
     registry.register(
         Action::new("mesh.merge_to_center")
             .label("Merge to Center")
@@ -90,6 +90,16 @@ impl DragManager {
         self.active.is_active()
     }
 
+    /// Vertex positions captured when the current move/rotate/scale started
+    pub fn initial_positions(&self) -> Option<&[(usize, Vec3)]> {
+        match &self.active {
+            ActiveDrag::Move(t) => Some(&t.initial_positions),
+            ActiveDrag::Rotate(t) => Some(&t.initial_positions),
+            ActiveDrag::Scale(t) => Some(&t.initial_positions),
+            ActiveDrag::BoxSelect(_) | ActiveDrag::None => None,
+        }
+    }
+
     /// Start a move drag operation
     /// `bone_rotation` - If Some, deltas will be transformed from world to bone-local space
     pub fn start_move(
@@ -426,6 +436,16 @@ impl DragManager {
         }
     }
 
+    /// Give each vertex of a rotate/scale drag its own pivot
+    /// (parallel to the drag's initial positions; moves ignore pivots)
+    pub fn set_origins(&mut self, origins: Vec<Vec3>) {
+        match &mut self.active {
+            ActiveDrag::Rotate(tracker) => tracker.origins = origins,
+            ActiveDrag::Scale(tracker) => tracker.origins = origins,
+            _ => {}
+        }
+    }
+
     /// Toggle snapping mid-drag
     pub fn set_snap(&mut self, enabled: bool, grid_size: f32) {
         if let Some(config) = &mut self.config {
@@ -16,6 +16,9 @@ pub struct RotateTracker {
     pub vertex_indices: Vec<usize>,
     /// Initial positions of vertices (index, position)
     pub initial_positions: Vec<(usize, Vec3)>,
+    /// Per-vertex rotation pivots, parallel to `initial_positions`
+    /// (empty = everything uses `center`)
+    pub origins: Vec<Vec3>,
 }
 
 impl RotateTracker {
@@ -30,9 +33,16 @@ impl RotateTracker {
             center,
             vertex_indices,
             initial_positions,
+            origins: Vec::new(),
         }
     }
 
+    /// Pivot each vertex about its own origin instead of the shared center
+    pub fn with_origins(mut self, origins: Vec<Vec3>) -> Self {
+        self.origins = origins;
+        self
+    }
+
     /// Create drag config for this rotation operation
     pub fn create_config(&self, snap_enabled: bool, snap_degrees: f32) -> DragConfig {
         // Reference vector for angle=0 (perpendicular to rotation axis)
@@ -61,9 +71,12 @@ impl RotateTracker {
 
         self.initial_positions
             .iter()
-            .map(|(idx, pos)| {
+            .enumerate()
+            .map(|(i, (idx, pos))| {
+                let center = self.origins.get(i).copied().unwrap_or(self.center);
+
                 // Translate to origin (center)
-                let p = *pos - self.center;
+                let p = *pos - center;
 
                 // Rodrigues' rotation formula:
                 // v_rot = v * cos(θ) + (k × v) * sin(θ) + k * (k · v) * (1 - cos(θ))
@@ -74,7 +87,7 @@ impl RotateTracker {
                 let rotated = p * cos_a + k_cross_p * sin_a + k * k_dot_p * (1.0 - cos_a);
 
                 // Translate back
-                (*idx, rotated + self.center)
+                (*idx, rotated + center)
             })
             .collect()
     }
@@ -102,4 +115,23 @@ mod tests {
         assert!(new_pos.y.abs() < 0.001, "y={}", new_pos.y);
         assert!((new_pos.z - -10.0).abs() < 0.001, "z={}", new_pos.z);
     }
+
+    #[test]
+    fn test_rotate_individual_origins() {
+        // Two parts, each spun in place about its own center
+        let tracker = RotateTracker::new(
+            Axis::Y,
+            Vec3::ZERO,
+            vec![0, 1],
+            vec![(0, Vec3::new(110.0, 0.0, 0.0)), (1, Vec3::new(-90.0, 0.0, 0.0))],
+        )
+        .with_origins(vec![Vec3::new(100.0, 0.0, 0.0), Vec3::new(-100.0, 0.0, 0.0)]);
+
+        let positions = tracker.compute_new_positions(std::f32::consts::PI);
+        let (_, a) = positions[0];
+        let (_, b) = positions[1];
+
+        assert!((a.x - 90.0).abs() < 0.001, "a.x={}", a.x);
+        assert!((b.x - -110.0).abs() < 0.001, "b.x={}", b.x);
+    }
 }
@@ -16,6 +16,9 @@ pub struct ScaleTracker {
     pub vertex_indices: Vec<usize>,
     /// Initial positions of vertices (index, position)
     pub initial_positions: Vec<(usize, Vec3)>,
+    /// Per-vertex scaling pivots, parallel to `initial_positions`
+    /// (empty = everything uses `center`)
+    pub origins: Vec<Vec3>,
 }
 
 impl ScaleTracker {
@@ -30,9 +33,16 @@ impl ScaleTracker {
             center,
             vertex_indices,
             initial_positions,
+            origins: Vec::new(),
         }
     }
 
+    /// Pivot each vertex about its own origin instead of the shared center
+    pub fn with_origins(mut self, origins: Vec<Vec3>) -> Self {
+        self.origins = origins;
+        self
+    }
+
     /// Create drag config for this scale operation
     pub fn create_config(&self, _center: Vec3) -> DragConfig {
         // Scale uses screen-space movement, not 3D picking
@@ -47,9 +57,12 @@ impl ScaleTracker {
     pub fn compute_new_positions(&self, factor: f32) -> Vec<(usize, Vec3)> {
         self.initial_positions
             .iter()
-            .map(|(idx, pos)| {
+            .enumerate()
+            .map(|(i, (idx, pos))| {
+                let center = self.origins.get(i).copied().unwrap_or(self.center);
+
                 // Vector from center to vertex
-                let offset = *pos - self.center;
+                let offset = *pos - center;
 
                 // Apply scale based on axis constraint
                 let scaled_offset = match self.axis {
@@ -59,7 +72,7 @@ impl ScaleTracker {
                     Some(Axis::Z) => Vec3::new(offset.x, offset.y, offset.z * factor),
                 };
 
-                (*idx, self.center + scaled_offset)
+                (*idx, center + scaled_offset)
             })
             .collect()
     }
@@ -103,4 +116,21 @@ mod tests {
         assert!((new_pos.y - 5.0).abs() < 0.001);
         assert!((new_pos.z - 2.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_scale_individual_origins() {
+        let tracker = ScaleTracker::new(
+            None,
+            Vec3::ZERO,
+            vec![0, 1],
+            vec![(0, Vec3::new(12.0, 0.0, 0.0)), (1, Vec3::new(-8.0, 0.0, 0.0))],
+        )
+        .with_origins(vec![Vec3::new(10.0, 0.0, 0.0), Vec3::new(-10.0, 0.0, 0.0)]);
+
+        let positions = tracker.compute_new_positions(2.0);
+
+        // Each vertex moves away from its own origin, not the shared center
+        assert!((positions[0].1.x - 14.0).abs() < 0.001);
+        assert!((positions[1].1.x - -6.0).abs() < 0.001);
+    }
 }
@@ -0,0 +1,691 @@
+//! glTF 2.0 import (.gltf with embedded/external buffers, and binary .glb)
+//!
+//! Reads triangle meshes with UVs and vertex colors. Every node that holds a
+//! mesh becomes one modeler part; the node hierarchy is flattened by baking
+//! each node's world transform into its vertices, and the part remembers its
+//! nearest mesh-carrying ancestor. The first base color texture found is
+//! quantized to an indexed atlas through `QuantizeOptions`.
+//!
+//! Native only: parsing relies on serde_json.
+
+use crate::rasterizer::{Clut, ClutDepth, Color, Vec2, Vec3, Vertex};
+use super::mesh_editor::{EditFace, EditableMesh, IndexedAtlas};
+use super::obj_import::{ObjError, ObjImporter, TextureImportResult};
+use super::quantize::{count_unique_colors, optimal_clut_depth, quantize_image_with_options, QuantizeOptions};
+use serde::Deserialize;
+use std::path::Path;
+
+/// glTF file importer
+pub struct GltfImporter;
+
+/// One mesh-carrying node, in world space
+#[derive(Debug, Clone)]
+pub struct GltfPart {
+    /// Node name (falls back to mesh name, then "Node N")
+    pub name: String,
+    pub mesh: EditableMesh,
+    /// Index (into the part list) of the nearest ancestor that is also a part
+    pub parent: Option<usize>,
+}
+
+/// Parsed glTF scene before scaling/quantizing
+#[derive(Debug, Clone, Default)]
+pub struct GltfScene {
+    pub parts: Vec<GltfPart>,
+    /// Encoded base color image (PNG/JPEG bytes), if any material has one
+    pub base_color_image: Option<Vec<u8>>,
+}
+
+impl GltfScene {
+    /// All parts merged into one mesh (for previews)
+    pub fn merged_mesh(&self) -> EditableMesh {
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        for part in &self.parts {
+            let base = vertices.len();
+            vertices.extend(part.mesh.vertices.iter().copied());
+            faces.extend(part.mesh.faces.iter().map(|f| {
+                let mut face = f.clone();
+                for v in &mut face.vertices {
+                    *v += base;
+                }
+                face
+            }));
+        }
+        EditableMesh::from_parts(vertices, faces)
+    }
+}
+
+/// Complete glTF import: scaled parts plus the quantized texture
+#[derive(Debug)]
+pub struct GltfImportResult {
+    pub parts: Vec<GltfPart>,
+    pub texture: Option<TextureImportResult>,
+}
+
+// ============================================================================
+// Document structure (only the fields we use)
+// ============================================================================
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct Document {
+    scene: Option<usize>,
+    scenes: Vec<SceneDef>,
+    nodes: Vec<NodeDef>,
+    meshes: Vec<MeshDef>,
+    accessors: Vec<AccessorDef>,
+    #[serde(rename = "bufferViews")]
+    buffer_views: Vec<BufferViewDef>,
+    buffers: Vec<BufferDef>,
+    materials: Vec<MaterialDef>,
+    textures: Vec<TextureDef>,
+    images: Vec<ImageDef>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct SceneDef {
+    nodes: Vec<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct NodeDef {
+    name: Option<String>,
+    mesh: Option<usize>,
+    children: Vec<usize>,
+    matrix: Option<[f32; 16]>,
+    translation: Option<[f32; 3]>,
+    rotation: Option<[f32; 4]>,
+    scale: Option<[f32; 3]>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct MeshDef {
+    name: Option<String>,
+    primitives: Vec<PrimitiveDef>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PrimitiveDef {
+    attributes: std::collections::HashMap<String, usize>,
+    indices: Option<usize>,
+    material: Option<usize>,
+    mode: Option<u32>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct AccessorDef {
+    #[serde(rename = "bufferView")]
+    buffer_view: Option<usize>,
+    #[serde(rename = "byteOffset")]
+    byte_offset: usize,
+    #[serde(rename = "componentType")]
+    component_type: u32,
+    normalized: bool,
+    count: usize,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct BufferViewDef {
+    buffer: usize,
+    #[serde(rename = "byteOffset")]
+    byte_offset: usize,
+    #[serde(rename = "byteLength")]
+    byte_length: usize,
+    #[serde(rename = "byteStride")]
+    byte_stride: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct BufferDef {
+    uri: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct MaterialDef {
+    #[serde(rename = "pbrMetallicRoughness")]
+    pbr: Option<PbrDef>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PbrDef {
+    #[serde(rename = "baseColorTexture")]
+    base_color_texture: Option<TextureInfoDef>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TextureInfoDef {
+    index: usize,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TextureDef {
+    source: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ImageDef {
+    uri: Option<String>,
+    #[serde(rename = "bufferView")]
+    buffer_view: Option<usize>,
+}
+
+const GLB_MAGIC: &[u8; 4] = b"glTF";
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+const MODE_TRIANGLES: u32 = 4;
+
+/// Column-major 4x4 matrix, as glTF stores it
+type Mat4 = [f32; 16];
+
+const IDENTITY: Mat4 = [
+    1.0, 0.0, 0.0, 0.0,
+    0.0, 1.0, 0.0, 0.0,
+    0.0, 0.0, 1.0, 0.0,
+    0.0, 0.0, 0.0, 1.0,
+];
+
+fn mat_mul(a: &Mat4, b: &Mat4) -> Mat4 {
+    let mut out = [0.0; 16];
+    for col in 0..4 {
+        for row in 0..4 {
+            out[col * 4 + row] = (0..4).map(|k| a[k * 4 + row] * b[col * 4 + k]).sum();
+        }
+    }
+    out
+}
+
+fn transform_point(m: &Mat4, p: Vec3) -> Vec3 {
+    Vec3::new(
+        m[0] * p.x + m[4] * p.y + m[8] * p.z + m[12],
+        m[1] * p.x + m[5] * p.y + m[9] * p.z + m[13],
+        m[2] * p.x + m[6] * p.y + m[10] * p.z + m[14],
+    )
+}
+
+fn transform_dir(m: &Mat4, d: Vec3) -> Vec3 {
+    Vec3::new(
+        m[0] * d.x + m[4] * d.y + m[8] * d.z,
+        m[1] * d.x + m[5] * d.y + m[9] * d.z,
+        m[2] * d.x + m[6] * d.y + m[10] * d.z,
+    )
+}
+
+impl NodeDef {
+    /// Local transform: explicit matrix, or T * R * S
+    fn local_matrix(&self) -> Mat4 {
+        if let Some(m) = self.matrix {
+            return m;
+        }
+        let [tx, ty, tz] = self.translation.unwrap_or([0.0; 3]);
+        let [x, y, z, w] = self.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+        let [sx, sy, sz] = self.scale.unwrap_or([1.0; 3]);
+        [
+            (1.0 - 2.0 * (y * y + z * z)) * sx, (2.0 * (x * y + z * w)) * sx, (2.0 * (x * z - y * w)) * sx, 0.0,
+            (2.0 * (x * y - z * w)) * sy, (1.0 - 2.0 * (x * x + z * z)) * sy, (2.0 * (y * z + x * w)) * sy, 0.0,
+            (2.0 * (x * z + y * w)) * sz, (2.0 * (y * z - x * w)) * sz, (1.0 - 2.0 * (x * x + y * y)) * sz, 0.0,
+            tx, ty, tz, 1.0,
+        ]
+    }
+}
+
+fn parse_err(msg: impl Into<String>) -> ObjError {
+    ObjError::Parse(msg.into())
+}
+
+/// Decode a `data:...;base64,` URI
+fn decode_data_uri(uri: &str) -> Option<Result<Vec<u8>, ObjError>> {
+    let rest = uri.strip_prefix("data:")?;
+    let (_, payload) = rest.split_once(";base64,")?;
+    Some(
+        base64::Engine::decode(&base64::engine::general_purpose::STANDARD, payload)
+            .map_err(|e| parse_err(format!("Invalid base64 data URI: {}", e))),
+    )
+}
+
+/// Load a buffer/image URI (data URI or a path relative to the glTF file)
+fn load_uri(uri: &str, base_dir: Option<&Path>) -> Result<Vec<u8>, ObjError> {
+    if let Some(decoded) = decode_data_uri(uri) {
+        return decoded;
+    }
+    let dir = base_dir.ok_or_else(|| parse_err(format!("External file '{}' without a base directory", uri)))?;
+    let path = dir.join(uri.replace("%20", " "));
+    std::fs::read(&path).map_err(|e| ObjError::Io(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+/// Split a .glb container into its JSON and BIN chunks
+fn split_glb(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>), ObjError> {
+    let read_u32 = |at: usize| -> Result<u32, ObjError> {
+        bytes
+            .get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| parse_err("Truncated GLB"))
+    };
+    if read_u32(4)? != 2 {
+        return Err(parse_err("Only glTF 2.0 binaries are supported"));
+    }
+
+    let mut json = None;
+    let mut bin = None;
+    let mut at = 12;
+    while at + 8 <= bytes.len() {
+        let len = read_u32(at)? as usize;
+        let kind = read_u32(at + 4)?;
+        let data = bytes.get(at + 8..at + 8 + len).ok_or_else(|| parse_err("Truncated GLB chunk"))?;
+        match kind {
+            GLB_CHUNK_JSON => json = Some(data),
+            GLB_CHUNK_BIN => bin = Some(data),
+            _ => {}
+        }
+        at += 8 + len;
+    }
+    Ok((json.ok_or_else(|| parse_err("GLB has no JSON chunk"))?, bin))
+}
+
+/// Buffers and accessors of a loaded document
+struct Reader<'a> {
+    doc: &'a Document,
+    buffers: Vec<Vec<u8>>,
+}
+
+impl Reader<'_> {
+    fn view_bytes(&self, view: usize) -> Result<(&[u8], Option<usize>), ObjError> {
+        let view = self.doc.buffer_views.get(view).ok_or_else(|| parse_err("Bad bufferView index"))?;
+        let buffer = self.buffers.get(view.buffer).ok_or_else(|| parse_err("Bad buffer index"))?;
+        let bytes = buffer
+            .get(view.byte_offset..view.byte_offset + view.byte_length)
+            .ok_or_else(|| parse_err("bufferView out of range"))?;
+        Ok((bytes, view.byte_stride))
+    }
+
+    /// Read an accessor as `count` rows of float components (normalized
+    /// integers are mapped to 0..1 / -1..1, plain integers are kept as-is)
+    fn read_floats(&self, index: usize) -> Result<Vec<Vec<f32>>, ObjError> {
+        let acc = self.doc.accessors.get(index).ok_or_else(|| parse_err("Bad accessor index"))?;
+        let comps = match acc.kind.as_str() {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" => 4,
+            other => return Err(parse_err(format!("Unsupported accessor type {}", other))),
+        };
+        let size = match acc.component_type {
+            5120 | 5121 => 1,
+            5122 | 5123 => 2,
+            5125 | 5126 => 4,
+            other => return Err(parse_err(format!("Unsupported component type {}", other))),
+        };
+        let Some(view) = acc.buffer_view else {
+            // No buffer view: all zeros per the spec
+            return Ok(vec![vec![0.0; comps]; acc.count]);
+        };
+        let (bytes, stride) = self.view_bytes(view)?;
+        let stride = stride.unwrap_or(comps * size);
+
+        let mut rows = Vec::with_capacity(acc.count);
+        for i in 0..acc.count {
+            let start = acc.byte_offset + i * stride;
+            let mut row = Vec::with_capacity(comps);
+            for c in 0..comps {
+                let at = start + c * size;
+                let b = bytes.get(at..at + size).ok_or_else(|| parse_err("Accessor out of range"))?;
+                let (value, max) = match acc.component_type {
+                    5120 => (b[0] as i8 as f32, 127.0),
+                    5121 => (b[0] as f32, 255.0),
+                    5122 => (i16::from_le_bytes([b[0], b[1]]) as f32, 32767.0),
+                    5123 => (u16::from_le_bytes([b[0], b[1]]) as f32, 65535.0),
+                    5125 => (u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32, 1.0),
+                    _ => (f32::from_le_bytes([b[0], b[1], b[2], b[3]]), 1.0),
+                };
+                row.push(if acc.normalized { (value / max).max(-1.0) } else { value });
+            }
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Read an index accessor
+    fn read_indices(&self, index: usize) -> Result<Vec<usize>, ObjError> {
+        Ok(self.read_floats(index)?.into_iter().map(|r| r[0] as usize).collect())
+    }
+
+    /// Bytes of the base color image used by the first textured material
+    fn base_color_image(&self, base_dir: Option<&Path>) -> Option<Vec<u8>> {
+        let image = self.doc.materials.iter().find_map(|m| {
+            let tex = m.pbr.as_ref()?.base_color_texture.as_ref()?;
+            let source = self.doc.textures.get(tex.index)?.source?;
+            self.doc.images.get(source)
+        })?;
+        if let Some(uri) = &image.uri {
+            load_uri(uri, base_dir).ok()
+        } else {
+            self.view_bytes(image.buffer_view?).ok().map(|(b, _)| b.to_vec())
+        }
+    }
+
+    /// Build one mesh from all triangle primitives, transformed to world space
+    fn build_mesh(&self, mesh: &MeshDef, world: &Mat4) -> Result<EditableMesh, ObjError> {
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+
+        for prim in &mesh.primitives {
+            if prim.mode.unwrap_or(MODE_TRIANGLES) != MODE_TRIANGLES {
+                continue;
+            }
+            let Some(&pos_acc) = prim.attributes.get("POSITION") else {
+                continue;
+            };
+            let positions = self.read_floats(pos_acc)?;
+            let attr = |name: &str| prim.attributes.get(name).map(|&a| self.read_floats(a)).transpose();
+            let normals = attr("NORMAL")?;
+            let uvs = attr("TEXCOORD_0")?;
+            let colors = attr("COLOR_0")?;
+
+            let base = vertices.len();
+            for (i, p) in positions.iter().enumerate() {
+                let pos = transform_point(world, Vec3::new(p[0], p[1], p[2]));
+                let normal = normals
+                    .as_ref()
+                    .and_then(|n| n.get(i))
+                    .map(|n| transform_dir(world, Vec3::new(n[0], n[1], n[2])).normalize())
+                    .unwrap_or(Vec3::ZERO);
+                // glTF UVs start top-left; ours start bottom-left like OBJ
+                let uv = uvs
+                    .as_ref()
+                    .and_then(|t| t.get(i))
+                    .map(|t| Vec2::new(t[0], 1.0 - t[1]))
+                    .unwrap_or_default();
+                // Vertex colors multiply the texture: 1.0 maps to neutral 128
+                let color = colors
+                    .as_ref()
+                    .and_then(|c| c.get(i))
+                    .map(|c| {
+                        let ch = |v: f32| (v.clamp(0.0, 2.0) * 128.0).round().min(255.0) as u8;
+                        Color::new(ch(c[0]), ch(c[1]), ch(c[2]))
+                    })
+                    .unwrap_or(Color::NEUTRAL);
+                vertices.push(Vertex::with_color(pos, uv, normal, color));
+            }
+
+            let indices = match prim.indices {
+                Some(acc) => self.read_indices(acc)?,
+                None => (0..positions.len()).collect(),
+            };
+            for tri in indices.chunks_exact(3) {
+                if tri.iter().any(|&i| i >= positions.len()) {
+                    return Err(parse_err("Index out of range"));
+                }
+                // glTF uses CCW winding, our rasterizer expects CW
+                faces.push(EditFace::tri(base + tri[0], base + tri[2], base + tri[1]));
+            }
+        }
+
+        Ok(EditableMesh::from_parts(vertices, faces))
+    }
+}
+
+impl GltfImporter {
+    /// Whether a path looks like a glTF file (.gltf or .glb)
+    pub fn is_gltf_path(path: &Path) -> bool {
+        path.extension()
+            .map(|e| e.eq_ignore_ascii_case("gltf") || e.eq_ignore_ascii_case("glb"))
+            .unwrap_or(false)
+    }
+
+    /// Load a .gltf or .glb file
+    pub fn load_from_file(path: &Path) -> Result<GltfScene, ObjError> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| ObjError::Io(format!("Failed to read file: {}", e)))?;
+        Self::parse(&bytes, path.parent())
+    }
+
+    /// Parse glTF JSON or GLB bytes. External buffers and images are resolved
+    /// relative to `base_dir`.
+    pub fn parse(bytes: &[u8], base_dir: Option<&Path>) -> Result<GltfScene, ObjError> {
+        let (json, glb_bin) = if bytes.starts_with(GLB_MAGIC) {
+            split_glb(bytes)?
+        } else {
+            (bytes, None)
+        };
+        let doc: Document = serde_json::from_slice(json)
+            .map_err(|e| parse_err(format!("Invalid glTF JSON: {}", e)))?;
+
+        let mut buffers = Vec::with_capacity(doc.buffers.len());
+        for buffer in &doc.buffers {
+            buffers.push(match (&buffer.uri, glb_bin) {
+                (Some(uri), _) => load_uri(uri, base_dir)?,
+                (None, Some(bin)) => bin.to_vec(),
+                (None, None) => return Err(parse_err("Buffer has no data")),
+            });
+        }
+        let reader = Reader { doc: &doc, buffers };
+
+        // Roots: the chosen scene, or every node nobody lists as a child
+        let roots: Vec<usize> = match doc.scenes.get(doc.scene.unwrap_or(0)) {
+            Some(scene) => scene.nodes.clone(),
+            None => (0..doc.nodes.len())
+                .filter(|i| !doc.nodes.iter().any(|n| n.children.contains(i)))
+                .collect(),
+        };
+
+        // Depth-first walk: (node, parent world matrix, parent part)
+        let mut parts: Vec<GltfPart> = Vec::new();
+        let mut stack: Vec<(usize, Mat4, Option<usize>)> =
+            roots.iter().rev().map(|&n| (n, IDENTITY, None)).collect();
+        let mut visited = vec![false; doc.nodes.len()];
+        while let Some((index, parent_world, parent_part)) = stack.pop() {
+            let Some(node) = doc.nodes.get(index) else {
+                return Err(parse_err(format!("Bad node index {}", index)));
+            };
+            if std::mem::replace(&mut visited[index], true) {
+                continue;
+            }
+            let world = mat_mul(&parent_world, &node.local_matrix());
+
+            let mut this_part = parent_part;
+            if let Some(mesh_index) = node.mesh {
+                let mesh_def = doc.meshes.get(mesh_index).ok_or_else(|| parse_err("Bad mesh index"))?;
+                let mesh = reader.build_mesh(mesh_def, &world)?;
+                if !mesh.faces.is_empty() {
+                    let name = node.name.clone()
+                        .or_else(|| mesh_def.name.clone())
+                        .unwrap_or_else(|| format!("Node {}", index));
+                    this_part = Some(parts.len());
+                    parts.push(GltfPart { name, mesh, parent: parent_part });
+                }
+            }
+            for &child in node.children.iter().rev() {
+                stack.push((child, world, this_part));
+            }
+        }
+
+        if parts.is_empty() {
+            return Err(parse_err("No triangle meshes found in glTF file"));
+        }
+
+        let base_color_image = reader.base_color_image(base_dir);
+        Ok(GltfScene { parts, base_color_image })
+    }
+
+    /// Decode and quantize an image to an indexed atlas. `depth` of None picks
+    /// 4-bit or 8-bit from the color count, like the OBJ auto-quantize path.
+    pub fn quantize_texture(
+        bytes: &[u8],
+        depth: Option<ClutDepth>,
+        opts: &QuantizeOptions,
+        name: &str,
+    ) -> Result<(IndexedAtlas, Clut, usize), ObjError> {
+        let img = image::load_from_memory(bytes)
+            .map_err(|e| ObjError::Io(format!("Failed to decode texture: {}", e)))?;
+        let rgba = img.to_rgba8();
+        let (width, height) = rgba.dimensions();
+
+        // Determine target atlas size (power of 2, max 512)
+        let dim = match width.max(height) {
+            0..=64 => 64,
+            65..=128 => 128,
+            129..=256 => 256,
+            _ => 512,
+        };
+
+        let mut pixels = Vec::with_capacity(dim * dim * 4);
+        for y in 0..dim {
+            for x in 0..dim {
+                let src_x = (x * width as usize / dim).min(width as usize - 1);
+                let src_y = (y * height as usize / dim).min(height as usize - 1);
+                pixels.extend_from_slice(&rgba.get_pixel(src_x as u32, src_y as u32).0);
+            }
+        }
+
+        let unique_colors = count_unique_colors(&pixels);
+        let depth = depth.unwrap_or_else(|| optimal_clut_depth(unique_colors));
+        let result = quantize_image_with_options(&pixels, dim, dim, depth, name, opts);
+
+        let indexed = IndexedAtlas {
+            width: result.texture.width,
+            height: result.texture.height,
+            depth: result.texture.depth,
+            indices: result.texture.indices,
+            default_clut: crate::rasterizer::ClutId::NONE, // Will be set when added to pool
+        };
+        Ok((indexed, result.clut, unique_colors))
+    }
+
+    /// Complete import: parts scaled to world units, normals recomputed, and
+    /// the base color texture quantized
+    pub fn import(
+        path: &Path,
+        scale: f32,
+        depth: Option<ClutDepth>,
+        opts: &QuantizeOptions,
+    ) -> Result<GltfImportResult, ObjError> {
+        let scene = Self::load_from_file(path)?;
+        let name = path.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Imported".to_string());
+
+        let mut parts = scene.parts;
+        for part in &mut parts {
+            for vertex in &mut part.mesh.vertices {
+                vertex.pos = vertex.pos * scale;
+            }
+            ObjImporter::compute_face_normals(&mut part.mesh);
+        }
+
+        let texture = scene.base_color_image
+            .and_then(|bytes| Self::quantize_texture(&bytes, depth, opts, &name).ok())
+            .map(|(indexed, clut, color_count)| TextureImportResult { indexed, clut, color_count });
+
+        Ok(GltfImportResult { parts, texture })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One triangle with UVs and colors, as a child of a translated node
+    fn triangle_gltf() -> String {
+        let mut bin = Vec::new();
+        for f in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0] {
+            bin.extend_from_slice(&f.to_le_bytes());
+        }
+        for f in [0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0] {
+            bin.extend_from_slice(&f.to_le_bytes());
+        }
+        for f in [1.0f32, 0.5, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0] {
+            bin.extend_from_slice(&f.to_le_bytes());
+        }
+        let data = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bin);
+        format!(
+            r#"{{
+                "asset": {{"version": "2.0"}},
+                "scene": 0,
+                "scenes": [{{"nodes": [0]}}],
+                "nodes": [
+                    {{"name": "body", "mesh": 0, "children": [1]}},
+                    {{"name": "arm", "mesh": 0, "translation": [2, 0, 0]}}
+                ],
+                "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 0, "TEXCOORD_0": 1, "COLOR_0": 2}}}}]}}],
+                "accessors": [
+                    {{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3"}},
+                    {{"bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC2"}},
+                    {{"bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC4"}}
+                ],
+                "bufferViews": [
+                    {{"buffer": 0, "byteOffset": 0, "byteLength": 36}},
+                    {{"buffer": 0, "byteOffset": 36, "byteLength": 24}},
+                    {{"buffer": 0, "byteOffset": 60, "byteLength": 48}}
+                ],
+                "buffers": [{{"byteLength": 108, "uri": "data:application/octet-stream;base64,{}"}}]
+            }}"#,
+            data
+        )
+    }
+
+    #[test]
+    fn nodes_become_parts_in_world_space() {
+        let scene = GltfImporter::parse(triangle_gltf().as_bytes(), None).unwrap();
+        assert_eq!(scene.parts.len(), 2);
+        assert_eq!(scene.parts[0].name, "body");
+        assert_eq!(scene.parts[1].name, "arm");
+        assert_eq!(scene.parts[1].parent, Some(0));
+
+        let arm = &scene.parts[1].mesh;
+        assert_eq!(arm.vertices[1].pos.x, 3.0);
+        // Winding flipped to CW
+        assert_eq!(arm.faces[0].vertices, vec![0, 2, 1]);
+        // V flipped, color 1.0 -> neutral
+        assert_eq!(arm.vertices[2].uv.y, 0.0);
+        assert_eq!(arm.vertices[0].color.r, 128);
+        assert_eq!(arm.vertices[0].color.g, 64);
+        assert_eq!(arm.vertices[0].color.b, 0);
+
+        assert_eq!(scene.merged_mesh().faces[1].vertices, vec![3, 5, 4]);
+    }
+
+    #[test]
+    fn reads_glb_container() {
+        let mut json = triangle_gltf().into_bytes();
+        while json.len() % 4 != 0 {
+            json.push(b' ');
+        }
+        let mut glb = Vec::new();
+        glb.extend_from_slice(GLB_MAGIC);
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&((12 + 8 + json.len()) as u32).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(&GLB_CHUNK_JSON.to_le_bytes());
+        glb.extend_from_slice(&json);
+
+        let scene = GltfImporter::parse(&glb, None).unwrap();
+        assert_eq!(scene.parts.len(), 2);
+        assert!(scene.base_color_image.is_none());
+    }
+
+    #[test]
+    fn rotation_and_scale_compose() {
+        let node = NodeDef {
+            // 90 degrees about Y, then doubled
+            rotation: Some([0.0, std::f32::consts::FRAC_1_SQRT_2, 0.0, std::f32::consts::FRAC_1_SQRT_2]),
+            scale: Some([2.0, 2.0, 2.0]),
+            ..Default::default()
+        };
+        let p = transform_point(&node.local_matrix(), Vec3::new(1.0, 0.0, 0.0));
+        assert!(p.x.abs() < 1e-5 && (p.z + 2.0).abs() < 1e-5);
+    }
+}
@@ -2,30 +2,28 @@
 
 use macroquad::prelude::*;
 use crate::storage::Storage;
-use crate::ui::{Rect, UiContext, SplitPanel, draw_panel, panel_content_rect, draw_collapsible_panel, Toolbar, icon, icon_button, ActionRegistry, draw_icon_centered, TextInputState, draw_text_input, dropdown_block_clicks, draw_dropdown_trigger, begin_dropdown, dropdown_item, dropdown_menu_rect};
+use crate::project::{draw_usages_panel, Dependency, DependencyGraph, UsagesPanel};
+use crate::ui::{Rect, UiContext, SplitPanel, draw_panel, panel_content_rect, draw_collapsible_panel, Toolbar, icon, icon_button, ActionRegistry, draw_icon_centered, TextInputState, draw_text_input, dropdown_block_clicks, draw_dropdown_trigger, begin_dropdown, dropdown_item, dropdown_menu_rect, theme};
 use crate::rasterizer::{Framebuffer, render_mesh, render_mesh_15, Camera, OrthoProjection, point_in_triangle_2d};
 use crate::rasterizer::{Vertex as RasterVertex, Face as RasterFace, Color as RasterColor};
 use crate::rasterizer::{ClutDepth, Clut, Color15};
 use super::state::{ModelerState, SelectMode, ViewportId, ContextMenu, ModalTransform, CameraMode, Axis, MirrorSettings, rotate_by_euler, inverse_rotate_by_euler};
-use crate::asset::AssetComponent;
+use crate::asset::{AssetComponent, LodLevel};
 use crate::texture::{
     UserTexture, TextureSize, generate_texture_id,
     draw_texture_canvas, draw_tool_panel, draw_palette_panel_constrained, draw_mode_tabs,
     TextureEditorMode, UvOverlayData, UvVertex, UvFace, draw_import_dialog, ImportAction,
-    load_png_to_import_state,
+    load_png_to_import_state, draw_palette_import_dialog, apply_palette, handle_palette_file_request,
+    apply_wrap_shift,
 };
 use super::tools::ModelerToolId;
 use super::viewport::{draw_modeler_viewport, draw_modeler_viewport_ext};
 use super::mesh_editor::{EditableMesh, MeshPart, TextureRef};
 use super::actions::{create_modeler_actions, build_context};
+use super::sprite_sheet::{SpriteSheetSettings, SPRITE_CELL_SIZES};
+use super::model_export::ModelExportFormat;
 use crate::rasterizer::{Vec3, Vec2 as RastVec2};
-
-// Colors (matching tracker/editor style)
-const BG_COLOR: Color = Color::new(0.11, 0.11, 0.13, 1.0);
-const HEADER_COLOR: Color = Color::new(0.15, 0.15, 0.18, 1.0);
-const TEXT_COLOR: Color = Color::new(0.8, 0.8, 0.85, 1.0);
-const TEXT_DIM: Color = Color::new(0.4, 0.4, 0.45, 1.0);
-const ACCENT_COLOR: Color = Color::new(0.0, 0.75, 0.9, 1.0);
+use crate::tags::{TagKind, draw_tag_filter, draw_item_tags, TAG_FILTER_HEIGHT, TAG_ROW_HEIGHT};
 
 /// Standard font sizes for consistent UI (matching World Editor)
 const FONT_SIZE_TITLE: f32 = 16.0;
@@ -62,6 +60,8 @@ pub enum ModelerAction {
     Import,         // Browser: upload file
     BrowseModels,   // Open model browser
     ImportObj,      // Import OBJ file
+    ExportSpriteSheet(SpriteSheetSettings), // Render angles x frames to a PNG sheet
+    ExportModel(ModelExportFormat), // Write OBJ/glTF for other tools
 }
 
 /// Modeler layout state (split panel ratios)
@@ -159,7 +159,8 @@ pub fn draw_modeler(
     draw_status_bar(status_rect, state);
 
     // Handle keyboard shortcuts using action registry (but not when a dialog is open)
-    let dialog_open = state.rename_dialog.is_some() || state.delete_dialog.is_some();
+    let dialog_open = state.rename_dialog.is_some() || state.delete_dialog.is_some() || state.checkpoint_dialog.is_some()
+        || state.sprite_sheet_dialog.is_some() || state.component_field_edit.is_some();
     let keyboard_action = if dialog_open {
         ModelerAction::None
     } else {
@@ -182,6 +183,10 @@ pub fn draw_modeler(
     // Draw rename/delete dialogs (modal, on top of everything)
     draw_object_dialogs(ctx, state, icon_font);
 
+    if let Some(settings) = draw_sprite_sheet_dialog(ctx, state) {
+        return ModelerAction::ExportSpriteSheet(settings);
+    }
+
     action
 }
 
@@ -231,6 +236,15 @@ fn draw_toolbar(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState, icon_
         }
     }
 
+    // Version history checkpoint (needs a saved model to attach the history to)
+    if state.current_file.is_some() {
+        if toolbar.icon_button(ctx, icon::MAP_PIN, icon_font, "Checkpoint (Ctrl+K)") {
+            state.open_checkpoint_dialog();
+        }
+    } else {
+        toolbar.icon_button_disabled(ctx, icon::MAP_PIN, icon_font, "Checkpoint (save the model first)");
+    }
+
     // Asset browser (works on both native and WASM)
     if toolbar.icon_button(ctx, icon::BOOK_OPEN, icon_font, "Browse Assets") {
         action = ModelerAction::BrowseModels;
@@ -241,6 +255,19 @@ fn draw_toolbar(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState, icon_
         action = ModelerAction::ImportObj;
     }
 
+    // Export geometry and textures for other tools
+    if toolbar.icon_button(ctx, icon::BOX, icon_font, "Export OBJ (+MTL, PNG)") {
+        action = ModelerAction::ExportModel(ModelExportFormat::Obj);
+    }
+    if toolbar.icon_button(ctx, icon::GIT_BRANCH, icon_font, "Export glTF (parts as nodes)") {
+        action = ModelerAction::ExportModel(ModelExportFormat::Gltf);
+    }
+
+    // Render the model from several angles into a sprite sheet
+    if toolbar.icon_button(ctx, icon::GRID, icon_font, "Export Sprite Sheet") {
+        state.open_sprite_sheet_dialog();
+    }
+
     toolbar.separator();
 
     // Transform tools with gizmos (using new tool system)
@@ -269,6 +296,17 @@ fn draw_toolbar(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState, icon_
         }
     }
 
+    // Pivot toggle (Median/Individual Origins) for group rotate/scale
+    {
+        use super::state::PivotMode;
+        let individual = state.pivot_mode == PivotMode::IndividualOrigins;
+        let tooltip = if individual { "Pivot: Individual Origins (click for Median)" } else { "Pivot: Median (click for Individual Origins)" };
+        if toolbar.icon_button_active(ctx, icon::CIRCLE_DOT, icon_font, tooltip, individual) {
+            state.pivot_mode = state.pivot_mode.toggle();
+            state.set_status(&format!("Pivot: {}", state.pivot_mode.label()), 1.5);
+        }
+    }
+
     toolbar.separator();
 
     // Selection mode buttons (Vertex/Edge/Face)
@@ -294,6 +332,25 @@ fn draw_toolbar(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState, icon_
         }
     }
 
+    // Symmetry plane (cycles Off -> X -> Y -> Z)
+    {
+        use super::state::Axis;
+        let tooltip = match state.symmetry {
+            Some(axis) => format!("Symmetry: {} (click to cycle, Shift+M to mirror and weld)", axis.label()),
+            None => "Symmetry: Off (click to cycle)".to_string(),
+        };
+        if toolbar.icon_button_active(ctx, icon::COLUMNS_2, icon_font, &tooltip, state.symmetry.is_some()) {
+            state.symmetry = match state.symmetry {
+                None => Some(Axis::X),
+                Some(Axis::X) => Some(Axis::Y),
+                Some(Axis::Y) => Some(Axis::Z),
+                Some(Axis::Z) => None,
+            };
+            let mode = state.symmetry.map_or("Off", |axis| axis.label());
+            state.set_status(&format!("Symmetry: {}", mode), 1.5);
+        }
+    }
+
     toolbar.separator();
 
     // PS1 effect toggles
@@ -472,7 +529,7 @@ fn draw_overview_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState
     draw_text(
         &format!("{} objects | {} verts | {} faces",
             state.objects().len(), total_verts, total_faces),
-        rect.x, y + 14.0, 12.0, TEXT_DIM,
+        rect.x, y + 14.0, 12.0, theme().text_dim,
     );
     y += row_height;
 
@@ -508,9 +565,9 @@ fn draw_overview_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState
         let eye_rect = Rect { x: rect.x + 2.0, y, w: icon_width, h: row_height };
         let eye_hovered = eye_rect.contains(mouse_pos.0, mouse_pos.1);
         let eye_color = if obj.visible {
-            if eye_hovered { ACCENT_COLOR } else { TEXT_COLOR }
+            if eye_hovered { theme().accent } else { theme().text }
         } else {
-            TEXT_DIM
+            theme().text_dim
         };
         let eye_icon = if obj.visible { icon::EYE } else { icon::EYE_OFF };
         draw_icon_centered(icon_font, eye_icon, &eye_rect, 14.0, eye_color);
@@ -523,7 +580,7 @@ fn draw_overview_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState
 
         // Object name
         let name_x = lock_rect.x + icon_width;
-        let name_color = if obj.visible { TEXT_COLOR } else { TEXT_DIM };
+        let name_color = if obj.visible { theme().text } else { theme().text_dim };
         let display_name = if obj.name.len() > 20 {
             format!("{}...", &obj.name[..17])
         } else {
@@ -576,19 +633,19 @@ fn draw_overview_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState
                 super::state::ModelerSelection::Vertices(indices) => {
                     draw_text(
                         &format!("{} vertices selected", indices.len()),
-                        rect.x, info_y + 12.0, 12.0, ACCENT_COLOR,
+                        rect.x, info_y + 12.0, 12.0, theme().accent,
                     );
                 }
                 super::state::ModelerSelection::Edges(indices) => {
                     draw_text(
                         &format!("{} edges selected", indices.len()),
-                        rect.x, info_y + 12.0, 12.0, ACCENT_COLOR,
+                        rect.x, info_y + 12.0, 12.0, theme().accent,
                     );
                 }
                 super::state::ModelerSelection::Faces(indices) => {
                     draw_text(
                         &format!("{} faces selected", indices.len()),
-                        rect.x, info_y + 12.0, 12.0, ACCENT_COLOR,
+                        rect.x, info_y + 12.0, 12.0, theme().accent,
                     );
                 }
                 _ => {
@@ -636,6 +693,12 @@ fn draw_left_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState, ic
 
     let mut y = rect.y;
 
+    // A component text edit only lives while that component's properties are shown
+    let edit_owner = state.component_field_edit.as_ref().map(|(id, _)| id / COMPONENT_FIELDS);
+    if edit_owner.is_some() && (!state.properties_section_expanded || edit_owner != state.selected_component) {
+        state.component_field_edit = None;
+    }
+
     // === COMPONENTS SECTION ===
     let comp_collapsed = !state.components_section_expanded;
     let comp_h = if comp_collapsed { COLLAPSED_HEADER_HEIGHT } else { expanded_panel_height };
@@ -682,7 +745,7 @@ fn draw_left_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState, ic
                 }
             }
         } else {
-            draw_text("Select a component", content.x + 4.0, content.y + 12.0, FONT_SIZE_HEADER, TEXT_DIM);
+            draw_text("Select a component", content.x + 4.0, content.y + 12.0, FONT_SIZE_HEADER, theme().text_dim);
         }
     }
     y += props_h;
@@ -710,12 +773,21 @@ fn component_icon(comp: &AssetComponent) -> char {
         AssetComponent::Trigger { .. } => icon::MAP_PIN,
         AssetComponent::Pickup { .. } => icon::PLUS,
         AssetComponent::Enemy { .. } => icon::PERSON_STANDING,
+        AssetComponent::Weapon { .. } => icon::SWORD,
+        AssetComponent::Armor { .. } => icon::SHIELD,
         AssetComponent::Door { .. } => icon::DOOR_CLOSED,
+        AssetComponent::MovingPlatform { .. } => icon::ARROW_DOWN_UP,
+        AssetComponent::Dialogue { .. } => icon::BOOK_OPEN,
         AssetComponent::Audio { .. } => icon::MUSIC,
         AssetComponent::Particle { .. } => icon::BLEND,
+        AssetComponent::Sprite { .. } => icon::IMAGE,
+        AssetComponent::StatusEffect { .. } => icon::DROPLET,
+        AssetComponent::Hazard { .. } => icon::CIRCLE_X,
+        AssetComponent::Destructible { .. } => icon::BRICK_WALL,
         AssetComponent::CharacterController { .. } => icon::GAMEPAD_2,
         AssetComponent::SpawnPoint { .. } => icon::FOOTPRINTS,
         AssetComponent::Skeleton { .. } => icon::BONE,
+        AssetComponent::Lod { .. } => icon::LAYERS,
     }
 }
 
@@ -726,7 +798,7 @@ fn draw_components_section(ctx: &mut UiContext, x: f32, y: &mut f32, width: f32,
 
     // Component count and add/remove buttons
     let comp_count = state.asset.components.len();
-    draw_text(&format!("{} component(s)", comp_count), x + 4.0, *y + 13.0, FONT_SIZE_HEADER, TEXT_COLOR);
+    draw_text(&format!("{} component(s)", comp_count), x + 4.0, *y + 13.0, FONT_SIZE_HEADER, theme().text);
 
     // Add button (opens add component dropdown)
     let add_rect = Rect::new(x + width - btn_size * 2.0 - 8.0, *y, btn_size, btn_size);
@@ -746,12 +818,13 @@ fn draw_components_section(ctx: &mut UiContext, x: f32, y: &mut f32, width: f32,
             if let Some(idx) = state.selected_component {
                 state.asset.components.remove(idx);
                 state.selected_component = None;
+                state.dirty = true;
             }
         }
     } else {
         // Draw disabled button
         draw_rectangle(rem_rect.x, rem_rect.y, rem_rect.w, rem_rect.h, Color::from_rgba(40, 40, 45, 255));
-        draw_icon_centered(icon_font, icon::MINUS, &rem_rect, 12.0, TEXT_DIM);
+        draw_icon_centered(icon_font, icon::MINUS, &rem_rect, 12.0, theme().text_dim);
     }
 
     *y += btn_size + 4.0;
@@ -762,6 +835,7 @@ fn draw_components_section(ctx: &mut UiContext, x: f32, y: &mut f32, width: f32,
 
     // Ensure opacity vec is sized correctly
     state.ensure_opacity_vec();
+    let issues = state.asset.component_issues();
 
     for (i, comp) in state.asset.components.iter().enumerate() {
         let is_selected = state.selected_component == Some(i);
@@ -820,26 +894,26 @@ fn draw_components_section(ctx: &mut UiContext, x: f32, y: &mut f32, width: f32,
         let is_dimmed = opacity > 0 && !is_hidden;
         let dimmed_color = Color::new(0.55, 0.55, 0.6, 1.0);
         let icon_color = if is_hidden {
-            TEXT_DIM
+            theme().text_dim
         } else if is_selected {
-            ACCENT_COLOR
+            theme().accent
         } else if is_dimmed {
             dimmed_color
         } else {
-            TEXT_COLOR
+            theme().text
         };
         draw_icon_centered(icon_font, icon_char, &icon_rect, 11.0, icon_color);
 
         // Component type name
         let type_name = comp.type_name();
         let name_color = if is_hidden {
-            TEXT_DIM
+            theme().text_dim
         } else if is_selected {
-            ACCENT_COLOR
+            theme().accent
         } else if is_dimmed {
             dimmed_color
         } else {
-            TEXT_COLOR
+            theme().text
         };
 
         // For Mesh, show object count
@@ -850,12 +924,21 @@ fn draw_components_section(ctx: &mut UiContext, x: f32, y: &mut f32, width: f32,
         };
         draw_text(&label, x + 40.0, *y + 13.0, FONT_SIZE_CONTENT, name_color);
 
+        // Validation warning (first problem as tooltip, all of them in Properties)
+        if let Some((_, message)) = issues.iter().find(|(idx, _)| *idx == i) {
+            let warn_rect = Rect::new(x + width - 36.0, *y + 1.0, 16.0, 16.0);
+            draw_icon_centered(icon_font, icon::TRIANGLE_ALERT, &warn_rect, 11.0, COMPONENT_WARNING_COLOR);
+            if ctx.mouse.inside(&warn_rect) {
+                ctx.set_tooltip(message, ctx.mouse.x, ctx.mouse.y);
+            }
+        }
+
         // Delete button (show on hover/selection)
         let show_delete = is_selected || is_hovered;
         if show_delete {
             let delete_rect = Rect::new(x + width - 18.0, *y + 1.0, 16.0, 16.0);
             let delete_hover = ctx.mouse.inside(&delete_rect);
-            let delete_color = if delete_hover { Color::from_rgba(255, 100, 100, 255) } else { TEXT_DIM };
+            let delete_color = if delete_hover { Color::from_rgba(255, 100, 100, 255) } else { theme().text_dim };
             draw_icon_centered(icon_font, icon::TRASH, &delete_rect, 11.0, delete_color);
 
             // Use clicked() (press + release) to avoid triggering on same frame as add
@@ -943,7 +1026,7 @@ fn draw_opacity_slider_popup(ctx: &mut UiContext, state: &mut ModelerState) {
 
     // Draw current position indicator (horizontal line)
     let indicator_y = track_y + (new_opacity as f32 + 0.5) * segment_height;
-    draw_rectangle(track_x - 2.0, indicator_y - 1.0, slider_width + 4.0, 3.0, ACCENT_COLOR);
+    draw_rectangle(track_x - 2.0, indicator_y - 1.0, slider_width + 4.0, 3.0, theme().accent);
 
     // Draw label at top
     let label = match new_opacity {
@@ -952,9 +1035,9 @@ fn draw_opacity_slider_popup(ctx: &mut UiContext, state: &mut ModelerState) {
         _ => "",
     };
     if !label.is_empty() {
-        draw_text(label, popup_x + padding, popup_y + padding + 10.0, 12.0, TEXT_COLOR);
+        draw_text(label, popup_x + padding, popup_y + padding + 10.0, 12.0, theme().text);
     } else {
-        draw_text(&format!("{}%", ((7 - new_opacity) as f32 / 7.0 * 100.0) as u8), popup_x + padding, popup_y + padding + 10.0, 12.0, TEXT_COLOR);
+        draw_text(&format!("{}%", ((7 - new_opacity) as f32 / 7.0 * 100.0) as u8), popup_x + padding, popup_y + padding + 10.0, 12.0, theme().text);
     }
 
     // End drag on mouse release
@@ -975,6 +1058,7 @@ fn create_default_component(type_name: &str) -> AssetComponent {
         "Collision" => AssetComponent::Collision {
             shape: CollisionShapeDef::FromMesh,
             is_trigger: false,
+            offset: [0.0, 0.0, 0.0],
         },
         "Light" => AssetComponent::Light {
             color: [255, 255, 200],
@@ -996,10 +1080,35 @@ fn create_default_component(type_name: &str) -> AssetComponent {
             health: 100,
             damage: 10,
             patrol_radius: 512.0,
+            poise: 30.0,
+            attack: Default::default(),
+        },
+        "Weapon" => AssetComponent::Weapon {
+            damage: 20,
+            light: crate::asset::AttackDef::light(),
+            heavy: crate::asset::AttackDef::heavy(),
+            guard_absorption: 0.7,
+            guard_stamina: 1.0,
+            speed: 1.0,
+            model_part: None,
         },
+        "Armor" => AssetComponent::Armor { defense: 0.2, weight: 10.0 },
         "Door" => AssetComponent::Door {
             required_key: None,
             start_open: false,
+            motion: Default::default(),
+            open_time: 1.0,
+            open_trigger: None,
+        },
+        "MovingPlatform" => AssetComponent::MovingPlatform {
+            route: Default::default(),
+            speed: 1024.0,
+            wait: 1.0,
+            activate_trigger: None,
+            lever: false,
+        },
+        "Dialogue" => AssetComponent::Dialogue {
+            nodes: vec![crate::asset::DialogueNode::new("start", "", "Hello there.")],
         },
         "Audio" => AssetComponent::Audio {
             sound: "ambient".to_string(),
@@ -1010,6 +1119,29 @@ fn create_default_component(type_name: &str) -> AssetComponent {
         "Particle" => AssetComponent::Particle {
             effect: "smoke".to_string(),
             offset: [0.0, 0.0, 0.0],
+            emitter: Default::default(),
+        },
+        "Sprite" => AssetComponent::Sprite {
+            texture: None,
+            scale: 16.0,
+            blend: crate::rasterizer::BlendMode::Opaque,
+            offset: [0.0, 0.0, 0.0],
+        },
+        "StatusEffect" => AssetComponent::StatusEffect {
+            preset: "poison".to_string(),
+            effect: Default::default(),
+        },
+        "Hazard" => AssetComponent::Hazard {
+            radius: 512.0,
+            interval: 1.0,
+            damage: 10,
+            buildup: 0.0,
+        },
+        "Destructible" => AssetComponent::Destructible {
+            health: 20,
+            debris: "dust".to_string(),
+            drop: None,
+            broken: Vec::new(),
         },
         "CharacterController" => AssetComponent::CharacterController {
             height: 1536.0,
@@ -1034,9 +1166,16 @@ fn create_default_component(type_name: &str) -> AssetComponent {
                 }],
             }
         },
+        "Lod" => AssetComponent::Lod {
+            reduced: Vec::new(),
+            reduced_distance: 8192.0,
+            impostor: Vec::new(),
+            impostor_distance: 16384.0,
+        },
         _ => AssetComponent::Collision {
             shape: CollisionShapeDef::FromMesh,
             is_trigger: false,
+            offset: [0.0, 0.0, 0.0],
         },
     }
 }
@@ -1046,7 +1185,7 @@ fn draw_component_editor(ctx: &mut UiContext, x: f32, y: &mut f32, width: f32, s
     let comp_idx = match state.selected_component {
         Some(idx) => idx,
         None => {
-            draw_text("No component selected", x + 4.0, *y + 12.0, FONT_SIZE_HEADER, TEXT_DIM);
+            draw_text("No component selected", x + 4.0, *y + 12.0, FONT_SIZE_HEADER, theme().text_dim);
             *y += 18.0;
             return;
         }
@@ -1061,34 +1200,60 @@ fn draw_component_editor(ctx: &mut UiContext, x: f32, y: &mut f32, width: f32, s
         }
     };
 
+    let mut lod_request: Option<LodMeshRequest> = None;
+    let mut broken_request: Option<BrokenMeshRequest> = None;
+    let fields = comp_idx * COMPONENT_FIELDS;
+    let editing = &mut state.component_field_edit;
     let modified = match &mut component {
         AssetComponent::Mesh { .. } => {
             // Mesh is handled specially by draw_mesh_editor_content, should not reach here
             return;
         }
-        AssetComponent::Collision { shape, is_trigger } => {
-            draw_collision_editor(ctx, x, y, width, shape, is_trigger, icon_font)
+        AssetComponent::Collision { shape, is_trigger, offset } => {
+            draw_collision_editor(ctx, x, y, width, shape, is_trigger, offset)
         }
         AssetComponent::Light { color, intensity, radius, offset } => {
             draw_light_component_editor(ctx, x, y, width, color, intensity, radius, offset, &mut state.light_color_slider, icon_font)
         }
         AssetComponent::Trigger { trigger_id, on_enter, on_exit } => {
-            draw_trigger_editor(ctx, x, y, width, trigger_id, on_enter, on_exit, icon_font)
+            draw_trigger_editor(ctx, x, y, width, trigger_id, on_enter, on_exit, fields, editing)
         }
         AssetComponent::Pickup { item_type, respawn_time } => {
             draw_pickup_editor(ctx, x, y, width, item_type, respawn_time, icon_font)
         }
-        AssetComponent::Enemy { enemy_type, health, damage, patrol_radius } => {
-            draw_enemy_editor(ctx, x, y, width, enemy_type, health, damage, patrol_radius, icon_font)
+        AssetComponent::Enemy { enemy_type, health, damage, patrol_radius, poise, attack } => {
+            draw_enemy_editor(ctx, x, y, width, enemy_type, health, damage, patrol_radius, poise, attack, icon_font)
+        }
+        AssetComponent::Weapon { damage, light, heavy, guard_absorption, guard_stamina, speed, model_part } => {
+            draw_weapon_editor(ctx, x, y, width, damage, light, heavy, guard_absorption, guard_stamina, speed, model_part, fields, editing)
+        }
+        AssetComponent::Armor { defense, weight } => draw_armor_editor(ctx, x, y, width, defense, weight),
+        AssetComponent::Door { required_key, start_open, motion, open_time, open_trigger } => {
+            draw_door_editor(ctx, x, y, width, required_key, start_open, motion, open_time, open_trigger, fields, editing)
+        }
+        AssetComponent::MovingPlatform { route, speed, wait, activate_trigger, lever } => {
+            draw_moving_platform_editor(ctx, x, y, width, route, speed, wait, activate_trigger, lever, fields, editing)
         }
-        AssetComponent::Door { required_key, start_open } => {
-            draw_door_editor(ctx, x, y, width, required_key, start_open, icon_font)
+        AssetComponent::Dialogue { nodes } => {
+            draw_dialogue_editor(ctx, x, y, width, nodes, &mut state.dialogue_node, fields, editing, icon_font)
         }
         AssetComponent::Audio { sound, volume, radius, looping } => {
-            draw_audio_editor(ctx, x, y, width, sound, volume, radius, looping, icon_font)
+            draw_audio_editor(ctx, x, y, width, sound, volume, radius, looping, fields, editing)
+        }
+        AssetComponent::Particle { effect, offset, emitter } => {
+            draw_particle_editor(ctx, x, y, width, effect, offset, emitter, &mut state.particle_preview, icon_font)
+        }
+        AssetComponent::Sprite { texture, scale, blend, offset } => {
+            draw_sprite_editor(ctx, x, y, width, texture, scale, blend, offset, &state.user_textures)
+        }
+        AssetComponent::StatusEffect { preset, effect } => {
+            draw_status_effect_editor(ctx, x, y, width, preset, effect, icon_font)
+        }
+        AssetComponent::Hazard { radius, interval, damage, buildup } => {
+            draw_hazard_editor(ctx, x, y, width, radius, interval, damage, buildup, icon_font)
         }
-        AssetComponent::Particle { effect, offset } => {
-            draw_particle_editor(ctx, x, y, width, effect, offset, icon_font)
+        AssetComponent::Destructible { health, debris, drop, broken } => {
+            draw_destructible_editor(ctx, x, y, width, health, debris, drop, broken, &mut broken_request, fields, editing)
         }
         AssetComponent::CharacterController { height, radius, step_height } => {
             draw_character_controller_editor(ctx, x, y, width, height, radius, step_height, icon_font)
@@ -1101,14 +1266,132 @@ fn draw_component_editor(ctx: &mut UiContext, x: f32, y: &mut f32, width: f32, s
             // TODO: Implement skeleton editor
             false
         }
+        AssetComponent::Lod { reduced, reduced_distance, impostor, impostor_distance } => {
+            draw_lod_editor(ctx, x, y, width, reduced, reduced_distance, impostor, impostor_distance, &mut lod_request)
+        }
     };
 
     // Apply changes back to the asset
     if modified {
         if let Some(comp) = state.asset.components.get_mut(comp_idx) {
             *comp = component;
+            state.dirty = true;
+        }
+    }
+
+    // Validation problems for this component
+    for (_, message) in state.asset.component_issues().into_iter().filter(|(idx, _)| *idx == comp_idx) {
+        let icon_rect = Rect::new(x + 2.0, *y + 2.0, 14.0, 14.0);
+        draw_icon_centered(icon_font, icon::TRIANGLE_ALERT, &icon_rect, 11.0, COMPONENT_WARNING_COLOR);
+        draw_text(&message, x + 20.0, *y + 14.0, FONT_SIZE_CONTENT, COMPONENT_WARNING_COLOR);
+        *y += 18.0;
+    }
+
+    // Quick-add more collision shapes next to the selected one
+    if state.asset.components.get(comp_idx).map(|c| c.is_collision()).unwrap_or(false) {
+        draw_add_collision_shape_row(ctx, x, y, width, state, comp_idx);
+    }
+
+    // Copy/swap between the Mesh component and a LOD level
+    if let Some(request) = lod_request {
+        apply_lod_mesh_request(state, comp_idx, request);
+    }
+
+    // Copy/swap between the Mesh component and the broken mesh
+    if let Some(request) = broken_request {
+        apply_broken_mesh_request(state, comp_idx, request);
+    }
+}
+
+/// Copy or swap request between the Mesh component and a LOD level
+#[derive(Debug, Clone, Copy)]
+enum LodMeshRequest {
+    /// Copy the full mesh into the level (starting point for simplifying)
+    Copy(LodLevel),
+    /// Exchange the level with the full mesh so it can be edited in the viewport
+    Swap(LodLevel),
+}
+
+/// Apply a LOD copy/swap to the asset's Mesh and Lod components
+fn apply_lod_mesh_request(state: &mut ModelerState, lod_idx: usize, request: LodMeshRequest) {
+    let mesh_idx = match state.asset.components.iter().position(|c| c.is_mesh()) {
+        Some(idx) => idx,
+        None => {
+            state.set_status("Asset has no Mesh component", 2.0);
+            return;
+        }
+    };
+    let full = match &state.asset.components[mesh_idx] {
+        AssetComponent::Mesh { parts } => parts.clone(),
+        _ => return,
+    };
+
+    let (level, swap) = match request {
+        LodMeshRequest::Copy(level) => (level, false),
+        LodMeshRequest::Swap(level) => (level, true),
+    };
+    let previous = match state.asset.components.get_mut(lod_idx) {
+        Some(AssetComponent::Lod { reduced, impostor, .. }) => {
+            let target = if level == LodLevel::Impostor { impostor } else { reduced };
+            std::mem::replace(target, full)
+        }
+        _ => return,
+    };
+
+    if swap {
+        if let AssetComponent::Mesh { parts } = &mut state.asset.components[mesh_idx] {
+            *parts = previous;
+        }
+        // Mesh indices changed underneath the selection
+        state.selection.clear();
+        state.selected_object = None;
+        state.set_status(&format!("Swapped {} LOD with Mesh (swap again to restore)", level.label()), 2.0);
+    } else {
+        state.set_status(&format!("Copied Mesh to {} LOD", level.label()), 1.5);
+    }
+    state.dirty = true;
+}
+
+/// Copy or swap request between the Mesh component and a Destructible's broken mesh
+#[derive(Debug, Clone, Copy)]
+enum BrokenMeshRequest {
+    /// Copy the intact mesh into the broken one (starting point for smashing it up)
+    Copy,
+    /// Exchange the broken mesh with the intact one so it can be edited in the viewport
+    Swap,
+}
+
+/// Apply a broken mesh copy/swap to the asset's Mesh and Destructible components
+fn apply_broken_mesh_request(state: &mut ModelerState, destructible_idx: usize, request: BrokenMeshRequest) {
+    let mesh_idx = match state.asset.components.iter().position(|c| c.is_mesh()) {
+        Some(idx) => idx,
+        None => {
+            state.set_status("Asset has no Mesh component", 2.0);
+            return;
+        }
+    };
+    let full = match &state.asset.components[mesh_idx] {
+        AssetComponent::Mesh { parts } => parts.clone(),
+        _ => return,
+    };
+    let previous = match state.asset.components.get_mut(destructible_idx) {
+        Some(AssetComponent::Destructible { broken, .. }) => std::mem::replace(broken, full),
+        _ => return,
+    };
+
+    match request {
+        BrokenMeshRequest::Swap => {
+            if let AssetComponent::Mesh { parts } = &mut state.asset.components[mesh_idx] {
+                *parts = previous;
+            }
+            // Mesh indices changed underneath the selection
+            state.selection.clear();
+            state.selected_object = None;
+            state.set_status("Swapped broken mesh with Mesh (swap again to restore)", 2.0);
         }
+        BrokenMeshRequest::Copy => state.set_status("Copied Mesh to broken mesh", 1.5),
     }
+    state.dirty = true;
 }
 
 /// Draw mesh component content (object list + per-object properties)
@@ -1124,6 +1407,7 @@ fn draw_mesh_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Modeler
     let mut toggle_vis_idx: Option<usize> = None;
     let mut rename_idx: Option<usize> = None;
     let mut delete_idx: Option<usize> = None;
+    let mut group_idx: Option<usize> = None;
 
     let obj_count = state.objects().len();
 
@@ -1142,12 +1426,16 @@ fn draw_mesh_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Modeler
             None => continue,
         };
         let is_selected = state.selected_object == Some(idx);
+        let in_group = state.selection.group()
+            .is_some_and(|members| members.iter().any(|m| m.object() == idx));
         let item_rect = Rect::new(x, y, width, line_height);
         let is_hovered = ctx.mouse.inside(&item_rect);
 
         // Selection highlight
         if is_selected {
             draw_rectangle(item_rect.x, item_rect.y, item_rect.w, item_rect.h, Color::from_rgba(60, 80, 100, 255));
+        } else if in_group {
+            draw_rectangle(item_rect.x, item_rect.y, item_rect.w, item_rect.h, Color::from_rgba(55, 65, 80, 255));
         } else if is_hovered {
             draw_rectangle(item_rect.x, item_rect.y, item_rect.w, item_rect.h, Color::from_rgba(50, 50, 55, 255));
         }
@@ -1155,7 +1443,7 @@ fn draw_mesh_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Modeler
         // Visibility toggle (eye icon)
         let vis_rect = Rect::new(x + 2.0, y + 1.0, 16.0, 16.0);
         let vis_icon = if obj.visible { icon::EYE } else { icon::EYE_OFF };
-        let vis_color = if obj.visible { TEXT_COLOR } else { TEXT_DIM };
+        let vis_color = if obj.visible { theme().text } else { theme().text_dim };
         draw_icon_centered(icon_font, vis_icon, &vis_rect, 11.0, vis_color);
 
         if ctx.mouse.inside(&vis_rect) && ctx.mouse.left_pressed {
@@ -1171,7 +1459,7 @@ fn draw_mesh_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Modeler
         if show_icons {
             // Rename icon (pencil)
             let rename_hover = ctx.mouse.inside(&rename_rect);
-            let rename_color = if rename_hover { ACCENT_COLOR } else { TEXT_DIM };
+            let rename_color = if rename_hover { theme().accent } else { theme().text_dim };
             draw_icon_centered(icon_font, icon::PENCIL, &rename_rect, 11.0, rename_color);
             if rename_hover && ctx.mouse.left_pressed {
                 rename_idx = Some(idx);
@@ -1179,7 +1467,7 @@ fn draw_mesh_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Modeler
 
             // Delete icon (trash)
             let delete_hover = ctx.mouse.inside(&delete_rect);
-            let delete_color = if delete_hover { Color::from_rgba(255, 100, 100, 255) } else { TEXT_DIM };
+            let delete_color = if delete_hover { Color::from_rgba(255, 100, 100, 255) } else { theme().text_dim };
             draw_icon_centered(icon_font, icon::TRASH, &delete_rect, 11.0, delete_color);
             if delete_hover && ctx.mouse.left_pressed {
                 delete_idx = Some(idx);
@@ -1192,10 +1480,15 @@ fn draw_mesh_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Modeler
         draw_text(&format!("{} ({})", obj.name, fc), x + 20.0, y + 13.0, FONT_SIZE_HEADER, name_color);
 
         // Handle selection click (not on visibility toggle or icons)
+        // Shift+click adds/removes the whole part from a group selection
         let name_rect = Rect::new(x + 20.0, y, width - 60.0, line_height);
         if ctx.mouse.inside(&name_rect) && ctx.mouse.left_pressed
             && toggle_vis_idx.is_none() && rename_idx.is_none() && delete_idx.is_none() {
-            select_idx = Some(idx);
+            if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) {
+                group_idx = Some(idx);
+            } else {
+                select_idx = Some(idx);
+            }
         }
 
         y += line_height;
@@ -1211,6 +1504,8 @@ fn draw_mesh_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Modeler
         state.rename_dialog = Some((idx, TextInputState::new(name)));
     } else if let Some(idx) = delete_idx {
         state.delete_dialog = Some(idx);
+    } else if let Some(idx) = group_idx {
+        state.toggle_group_part(idx);
     } else if let Some(idx) = select_idx {
         state.select_object(idx);
     }
@@ -1230,16 +1525,16 @@ fn draw_mesh_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Modeler
         };
 
         // Object name header
-        draw_text(&obj_name, x + 4.0, y + 12.0, FONT_SIZE_HEADER, ACCENT_COLOR);
+        draw_text(&obj_name, x + 4.0, y + 12.0, FONT_SIZE_HEADER, theme().accent);
         y += line_height;
 
         // Double-Sided Toggle
         let toggle_size = 16.0;
         let ds_rect = Rect::new(x + 4.0, y, toggle_size, toggle_size);
         let ds_icon = if double_sided { icon::SQUARE_CHECK } else { icon::SQUARE };
-        let ds_color = if double_sided { ACCENT_COLOR } else { TEXT_DIM };
+        let ds_color = if double_sided { theme().accent } else { theme().text_dim };
         draw_icon_centered(icon_font, ds_icon, &ds_rect, 12.0, ds_color);
-        draw_text("Double-Sided", x + 24.0, y + 12.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+        draw_text("Double-Sided", x + 24.0, y + 12.0, FONT_SIZE_CONTENT, theme().text);
 
         if ctx.mouse.inside(&Rect::new(x, y, width, line_height)) && ctx.mouse.left_pressed {
             if let Some(obj) = state.objects_mut().and_then(|v| v.get_mut(selected_idx)) {
@@ -1255,9 +1550,9 @@ fn draw_mesh_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Modeler
 
         let mir_rect = Rect::new(x + 4.0, y, toggle_size, toggle_size);
         let mir_icon = if mirror_enabled { icon::SQUARE_CHECK } else { icon::SQUARE };
-        let mir_color = if mirror_enabled { ACCENT_COLOR } else { TEXT_DIM };
+        let mir_color = if mirror_enabled { theme().accent } else { theme().text_dim };
         draw_icon_centered(icon_font, mir_icon, &mir_rect, 12.0, mir_color);
-        draw_text("Mirror", x + 24.0, y + 12.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+        draw_text("Mirror", x + 24.0, y + 12.0, FONT_SIZE_CONTENT, theme().text);
 
         if ctx.mouse.inside(&Rect::new(x, y, 70.0, line_height)) && ctx.mouse.left_pressed {
             let new_enabled = !mirror_enabled;
@@ -1292,7 +1587,7 @@ fn draw_mesh_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Modeler
                     Color::from_rgba(45, 45, 55, 255)
                 };
                 draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg_color);
-                draw_text(axis.label(), btn_x + 6.0, y + 12.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+                draw_text(axis.label(), btn_x + 6.0, y + 12.0, FONT_SIZE_CONTENT, theme().text);
 
                 if ctx.mouse.inside(&btn_rect) && ctx.mouse.left_pressed {
                     if let Some(obj) = state.objects_mut().and_then(|v| v.get_mut(selected_idx)) {
@@ -1311,7 +1606,7 @@ fn draw_mesh_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Modeler
         // Bone Assignment (only if skeleton exists)
         let skeleton = state.skeleton();
         if !skeleton.is_empty() {
-            draw_text("Bone", x + 4.0, y + 12.0, FONT_SIZE_CONTENT, TEXT_DIM);
+            draw_text("Bone", x + 4.0, y + 12.0, FONT_SIZE_CONTENT, theme().text_dim);
 
             // Get current bone name
             let bone_name = bone_index
@@ -1341,9 +1636,9 @@ fn draw_skeleton_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Mod
     // --- BONE TREE ---
     let skeleton = state.skeleton();
     if skeleton.is_empty() {
-        draw_text("No bones", x + 4.0, y + 12.0, FONT_SIZE_CONTENT, TEXT_DIM);
-        draw_text("Add Skeleton component", x + 4.0, y + 26.0, FONT_SIZE_CONTENT, TEXT_DIM);
-        draw_text("to create root bone", x + 4.0, y + 40.0, FONT_SIZE_CONTENT, TEXT_DIM);
+        draw_text("No bones", x + 4.0, y + 12.0, FONT_SIZE_CONTENT, theme().text_dim);
+        draw_text("Add Skeleton component", x + 4.0, y + 26.0, FONT_SIZE_CONTENT, theme().text_dim);
+        draw_text("to create root bone", x + 4.0, y + 40.0, FONT_SIZE_CONTENT, theme().text_dim);
         return;
     }
 
@@ -1406,7 +1701,7 @@ fn draw_skeleton_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Mod
         } else if is_selected {
             Color::from_rgba(80, 255, 80, 255) // Green when selected
         } else {
-            TEXT_COLOR
+            theme().text
         };
         draw_icon_centered(icon_font, icon::BONE, &icon_rect, 11.0, icon_color);
 
@@ -1420,7 +1715,7 @@ fn draw_skeleton_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Mod
             // Delete icon (rightmost)
             let delete_rect = Rect::new(icon_x, *y + 2.0, icon_size, icon_size);
             let delete_hover = ctx.mouse.inside(&delete_rect);
-            let delete_color = if delete_hover { Color::from_rgba(255, 100, 100, 255) } else { TEXT_DIM };
+            let delete_color = if delete_hover { Color::from_rgba(255, 100, 100, 255) } else { theme().text_dim };
             draw_icon_centered(icon_font, icon::TRASH, &delete_rect, 11.0, delete_color);
             if delete_hover && ctx.mouse.left_pressed {
                 *delete_idx = Some(bone_idx);
@@ -1430,7 +1725,7 @@ fn draw_skeleton_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Mod
             // Rename icon
             let rename_rect = Rect::new(icon_x, *y + 2.0, icon_size, icon_size);
             let rename_hover = ctx.mouse.inside(&rename_rect);
-            let rename_color = if rename_hover { ACCENT_COLOR } else { TEXT_DIM };
+            let rename_color = if rename_hover { theme().accent } else { theme().text_dim };
             draw_icon_centered(icon_font, icon::PENCIL, &rename_rect, 11.0, rename_color);
             if rename_hover && ctx.mouse.left_pressed {
                 *rename_idx = Some(bone_idx);
@@ -1440,7 +1735,7 @@ fn draw_skeleton_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Mod
             // Add child icon
             let add_rect = Rect::new(icon_x, *y + 2.0, icon_size, icon_size);
             let add_hover = ctx.mouse.inside(&add_rect);
-            let add_color = if add_hover { Color::from_rgba(100, 255, 100, 255) } else { TEXT_DIM };
+            let add_color = if add_hover { Color::from_rgba(100, 255, 100, 255) } else { theme().text_dim };
             draw_icon_centered(icon_font, icon::PLUS, &add_rect, 11.0, add_color);
             if add_hover && ctx.mouse.left_pressed {
                 *add_idx = Some(bone_idx);
@@ -1448,7 +1743,7 @@ fn draw_skeleton_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Mod
         }
 
         // Bone name
-        let name_color = if is_selected { ACCENT_COLOR } else { TEXT_COLOR };
+        let name_color = if is_selected { theme().accent } else { theme().text };
         draw_text(&bone.name, x + 20.0 + indent, *y + 13.0, FONT_SIZE_HEADER, name_color);
 
         // Handle selection click (not on action icons)
@@ -1535,7 +1830,7 @@ fn draw_skeleton_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Mod
             // Draw text input for rename
             let input_rect = Rect::new(x + 4.0, y, width - 8.0, line_height);
             draw_rectangle(input_rect.x, input_rect.y, input_rect.w, input_rect.h, Color::from_rgba(40, 45, 55, 255));
-            draw_rectangle_lines(input_rect.x, input_rect.y, input_rect.w, input_rect.h, 1.0, ACCENT_COLOR);
+            draw_rectangle_lines(input_rect.x, input_rect.y, input_rect.w, input_rect.h, 1.0, theme().accent);
 
             // Handle text input
             while let Some(ch) = get_char_pressed() {
@@ -1549,7 +1844,7 @@ fn draw_skeleton_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Mod
 
             // Draw the text with cursor
             let display_text = format!("{}|", state.bone_rename_buffer);
-            draw_text(&display_text, x + 6.0, y + 13.0, FONT_SIZE_HEADER, ACCENT_COLOR);
+            draw_text(&display_text, x + 6.0, y + 13.0, FONT_SIZE_HEADER, theme().accent);
 
             // Handle Enter to confirm or Escape to cancel
             if is_key_pressed(KeyCode::Enter) {
@@ -1568,23 +1863,23 @@ fn draw_skeleton_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Mod
                 state.bone_rename_buffer.clear();
             }
         } else {
-            draw_text(&bone_name, x + 4.0, y + 12.0, FONT_SIZE_HEADER, ACCENT_COLOR);
+            draw_text(&bone_name, x + 4.0, y + 12.0, FONT_SIZE_HEADER, theme().accent);
         }
         y += line_height;
 
         // Parent info
-        draw_text(&format!("Parent: {}", parent_name), x + 4.0, y + 12.0, FONT_SIZE_CONTENT, TEXT_DIM);
+        draw_text(&format!("Parent: {}", parent_name), x + 4.0, y + 12.0, FONT_SIZE_CONTENT, theme().text_dim);
         y += line_height;
 
         // Length info
-        draw_text(&format!("Length: {:.0}", length), x + 4.0, y + 12.0, FONT_SIZE_CONTENT, TEXT_DIM);
+        draw_text(&format!("Length: {:.0}", length), x + 4.0, y + 12.0, FONT_SIZE_CONTENT, theme().text_dim);
         y += line_height;
 
         // Width slider (drag left/right to adjust)
         {
             let label = format!("Width: {:.0}", bone_width);
             let label_w = 55.0;
-            draw_text(&label, x + 4.0, y + 12.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+            draw_text(&label, x + 4.0, y + 12.0, FONT_SIZE_CONTENT, theme().text);
 
             // Slider bar
             let slider_x = x + label_w + 4.0;
@@ -1627,11 +1922,11 @@ fn draw_skeleton_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Mod
 
         if !attached_meshes.is_empty() {
             y += 4.0;
-            draw_text("Attached:", x + 4.0, y + 12.0, FONT_SIZE_CONTENT, TEXT_DIM);
+            draw_text("Attached:", x + 4.0, y + 12.0, FONT_SIZE_CONTENT, theme().text_dim);
             y += line_height;
 
             for name in attached_meshes {
-                draw_text(&format!("• {}", name), x + 8.0, y + 12.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+                draw_text(&format!("• {}", name), x + 8.0, y + 12.0, FONT_SIZE_CONTENT, theme().text);
                 y += line_height;
             }
         }
@@ -1640,14 +1935,14 @@ fn draw_skeleton_editor_content(ctx: &mut UiContext, rect: Rect, state: &mut Mod
         let vertex_count = state.count_vertices_for_bone(selected_idx);
         if vertex_count > 0 {
             y += 4.0;
-            draw_text(&format!("Vertices: {}", vertex_count), x + 4.0, y + 12.0, FONT_SIZE_CONTENT, TEXT_DIM);
+            draw_text(&format!("Vertices: {}", vertex_count), x + 4.0, y + 12.0, FONT_SIZE_CONTENT, theme().text_dim);
 
             // "Select" button to select all vertices for this bone
             let btn_rect = Rect::new(x + 70.0, y, 50.0, line_height - 2.0);
             let btn_hover = ctx.mouse.inside(&btn_rect);
             let btn_color = if btn_hover { Color::from_rgba(80, 100, 120, 255) } else { Color::from_rgba(50, 60, 70, 255) };
             draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, btn_color);
-            draw_text("Select", btn_rect.x + 6.0, btn_rect.y + 12.0, FONT_SIZE_CONTENT, if btn_hover { ACCENT_COLOR } else { TEXT_COLOR });
+            draw_text("Select", btn_rect.x + 6.0, btn_rect.y + 12.0, FONT_SIZE_CONTENT, if btn_hover { theme().accent } else { theme().text });
 
             if btn_hover && ctx.mouse.left_pressed {
                 state.select_vertices_for_bone(selected_idx);
@@ -1780,16 +2075,16 @@ fn draw_collision_editor(
     width: f32,
     shape: &mut crate::asset::CollisionShapeDef,
     is_trigger: &mut bool,
-    _icon_font: Option<&Font>,
+    offset: &[f32; 3],
 ) -> bool {
     use crate::asset::CollisionShapeDef;
     let mut modified = false;
     let line_height = 20.0;
 
     // Shape type dropdown (simplified - just show current)
-    draw_text("Shape:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Shape:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     let shape_desc = shape.description();
-    draw_text(&shape_desc, x + 50.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    draw_text(&shape_desc, x + 50.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
     *y += line_height;
 
     // Shape type buttons
@@ -1809,7 +2104,7 @@ fn draw_collision_editor(
         let hovered = ctx.mouse.inside(&btn_rect);
 
         let bg = if is_active {
-            ACCENT_COLOR
+            theme().accent
         } else if hovered {
             Color::from_rgba(60, 60, 70, 255)
         } else {
@@ -1817,7 +2112,7 @@ fn draw_collision_editor(
         };
         draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
 
-        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { TEXT_COLOR };
+        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { theme().text };
         draw_text(name, btn_x + 4.0, *y + 13.0, 11.0, text_color);
 
         if hovered && ctx.mouse.left_pressed && !is_active {
@@ -1829,13 +2124,13 @@ fn draw_collision_editor(
 
     // Is Trigger toggle
     let _trigger_rect = Rect::new(x + 4.0, *y, width - 8.0, 18.0);
-    draw_text("Is Trigger:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Is Trigger:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
 
     let toggle_x = x + width - 40.0;
     let toggle_rect = Rect::new(toggle_x, *y + 2.0, 32.0, 14.0);
-    let toggle_color = if *is_trigger { ACCENT_COLOR } else { Color::from_rgba(60, 60, 65, 255) };
+    let toggle_color = if *is_trigger { theme().accent } else { Color::from_rgba(60, 60, 65, 255) };
     draw_rectangle(toggle_rect.x, toggle_rect.y, toggle_rect.w, toggle_rect.h, toggle_color);
-    draw_text(if *is_trigger { "ON" } else { "OFF" }, toggle_x + 6.0, *y + 13.0, 11.0, TEXT_COLOR);
+    draw_text(if *is_trigger { "ON" } else { "OFF" }, toggle_x + 6.0, *y + 13.0, 11.0, theme().text);
 
     if ctx.mouse.inside(&toggle_rect) && ctx.mouse.left_pressed {
         *is_trigger = !*is_trigger;
@@ -1853,12 +2148,12 @@ fn draw_collision_editor(
     match shape {
         CollisionShapeDef::Sphere { radius } => {
             // Radius slider
-            draw_text("Radius:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+            draw_text("Radius:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
             let sr = Rect::new(slider_x, *y + 4.0, slider_w, slider_h);
             draw_rectangle(sr.x, sr.y, sr.w, sr.h, track_bg);
             let fill = (radius.clamp(0.0, max_dim) / max_dim) * slider_w;
-            draw_rectangle(sr.x, sr.y, fill, sr.h, ACCENT_COLOR);
-            draw_text(&format!("{:.0}", *radius), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+            draw_rectangle(sr.x, sr.y, fill, sr.h, theme().accent);
+            draw_text(&format!("{:.0}", *radius), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
             if ctx.mouse.inside(&sr) && ctx.mouse.left_down {
                 let t = ((ctx.mouse.x - sr.x) / slider_w).clamp(0.0, 1.0);
                 *radius = t * max_dim;
@@ -1869,14 +2164,14 @@ fn draw_collision_editor(
         CollisionShapeDef::Box { half_extents } => {
             let labels = ["Width:", "Height:", "Depth:"];
             for (i, label) in labels.iter().enumerate() {
-                draw_text(label, x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+                draw_text(label, x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
                 let sr = Rect::new(slider_x, *y + 4.0, slider_w, slider_h);
                 draw_rectangle(sr.x, sr.y, sr.w, sr.h, track_bg);
                 let val = half_extents[i];
                 let fill = (val.clamp(0.0, max_dim) / max_dim) * slider_w;
-                draw_rectangle(sr.x, sr.y, fill, sr.h, ACCENT_COLOR);
+                draw_rectangle(sr.x, sr.y, fill, sr.h, theme().accent);
                 // Display as full extent (double the half)
-                draw_text(&format!("{:.0}", val * 2.0), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+                draw_text(&format!("{:.0}", val * 2.0), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
                 if ctx.mouse.inside(&sr) && ctx.mouse.left_down {
                     let t = ((ctx.mouse.x - sr.x) / slider_w).clamp(0.0, 1.0);
                     half_extents[i] = t * max_dim;
@@ -1887,12 +2182,12 @@ fn draw_collision_editor(
         }
         CollisionShapeDef::Capsule { radius, height } | CollisionShapeDef::Cylinder { radius, height } => {
             // Radius slider
-            draw_text("Radius:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+            draw_text("Radius:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
             let sr = Rect::new(slider_x, *y + 4.0, slider_w, slider_h);
             draw_rectangle(sr.x, sr.y, sr.w, sr.h, track_bg);
             let fill = (radius.clamp(0.0, max_dim) / max_dim) * slider_w;
-            draw_rectangle(sr.x, sr.y, fill, sr.h, ACCENT_COLOR);
-            draw_text(&format!("{:.0}", *radius), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+            draw_rectangle(sr.x, sr.y, fill, sr.h, theme().accent);
+            draw_text(&format!("{:.0}", *radius), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
             if ctx.mouse.inside(&sr) && ctx.mouse.left_down {
                 let t = ((ctx.mouse.x - sr.x) / slider_w).clamp(0.0, 1.0);
                 *radius = t * max_dim;
@@ -1901,13 +2196,13 @@ fn draw_collision_editor(
             *y += line_height;
 
             // Height slider
-            draw_text("Height:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+            draw_text("Height:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
             let sr = Rect::new(slider_x, *y + 4.0, slider_w, slider_h);
             draw_rectangle(sr.x, sr.y, sr.w, sr.h, track_bg);
             let max_h = 4096.0;
             let fill = (height.clamp(0.0, max_h) / max_h) * slider_w;
-            draw_rectangle(sr.x, sr.y, fill, sr.h, ACCENT_COLOR);
-            draw_text(&format!("{:.0}", *height), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+            draw_rectangle(sr.x, sr.y, fill, sr.h, theme().accent);
+            draw_text(&format!("{:.0}", *height), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
             if ctx.mouse.inside(&sr) && ctx.mouse.left_down {
                 let t = ((ctx.mouse.x - sr.x) / slider_w).clamp(0.0, 1.0);
                 *height = t * max_h;
@@ -1916,14 +2211,53 @@ fn draw_collision_editor(
             *y += line_height;
         }
         CollisionShapeDef::FromMesh => {
-            draw_text("Auto-fit to mesh bounds", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+            draw_text("Auto-fit to mesh bounds", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
             *y += line_height;
         }
     }
 
+    // Placement is edited in the viewport (Move tool = offset, Scale tool = size handles)
+    if !matches!(shape, CollisionShapeDef::FromMesh) {
+        draw_text("Offset:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+        draw_text(&format!("{:.0}, {:.0}, {:.0}", offset[0], offset[1], offset[2]),
+            slider_x, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+        *y += line_height;
+        draw_text("Move (G): offset  Scale (T): size", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+        *y += line_height;
+    }
+
     modified
 }
 
+/// Buttons that add another Collision component (box, sphere or capsule) at the
+/// selected shape's offset and select it, so compound colliders can be built up
+fn draw_add_collision_shape_row(ctx: &mut UiContext, x: f32, y: &mut f32, width: f32, state: &mut ModelerState, comp_idx: usize) {
+    use crate::asset::CollisionShapeDef;
+
+    draw_text("Add:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let shapes = [
+        ("+Box", CollisionShapeDef::box_shape(256.0, 256.0, 256.0)),
+        ("+Sphere", CollisionShapeDef::sphere(256.0)),
+        ("+Capsule", CollisionShapeDef::capsule(128.0, 512.0)),
+    ];
+    let btn_w = (width - 50.0) / shapes.len() as f32;
+    for (i, (name, shape)) in shapes.into_iter().enumerate() {
+        let btn_rect = Rect::new(x + 44.0 + i as f32 * btn_w, *y, btn_w - 2.0, 18.0);
+        let hovered = ctx.mouse.inside(&btn_rect);
+        let bg = if hovered { Color::from_rgba(60, 60, 70, 255) } else { Color::from_rgba(45, 45, 50, 255) };
+        draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
+        draw_text(name, btn_rect.x + 4.0, *y + 13.0, 11.0, theme().text);
+
+        if hovered && ctx.mouse.left_pressed {
+            let offset = state.asset.components.get(comp_idx).and_then(|c| c.offset()).unwrap_or([0.0; 3]);
+            state.asset.components.push(AssetComponent::Collision { shape, is_trigger: false, offset });
+            state.selected_component = Some(state.asset.components.len() - 1);
+            state.set_status("Added collision shape (Move to place, Scale to resize)", 2.0);
+        }
+    }
+    *y += 20.0;
+}
+
 /// Draw light component editor
 fn draw_light_component_editor(
     ctx: &mut UiContext,
@@ -1945,7 +2279,7 @@ fn draw_light_component_editor(
     let track_bg = Color::new(0.12, 0.12, 0.14, 1.0);
 
     // Color preview
-    draw_text("Color:", x + 4.0, *y + 12.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Color:", x + 4.0, *y + 12.0, FONT_SIZE_CONTENT, theme().text_dim);
     let preview_rect = Rect::new(x + 50.0, *y + 2.0, 40.0, 14.0);
     draw_rectangle(preview_rect.x, preview_rect.y, preview_rect.w, preview_rect.h,
         Color::from_rgba(color[0], color[1], color[2], 255));
@@ -1976,7 +2310,7 @@ fn draw_light_component_editor(
         draw_rectangle(handle_x.max(track_rect.x), track_rect.y, 4.0, slider_height, WHITE);
 
         // Value text
-        draw_text(&format!("{}", value), track_rect.x + track_rect.w + 4.0, *y + 9.0, 11.0, TEXT_DIM);
+        draw_text(&format!("{}", value), track_rect.x + track_rect.w + 4.0, *y + 9.0, 11.0, theme().text_dim);
 
         // Slider interaction - start drag
         if ctx.mouse.inside(&track_rect) && ctx.mouse.left_down && color_slider.is_none() {
@@ -2003,7 +2337,7 @@ fn draw_light_component_editor(
     }
 
     // Intensity slider
-    draw_text("Intensity:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Intensity:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     let slider_x = x + 70.0;
     let slider_w = width - 110.0;
     let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
@@ -2011,9 +2345,9 @@ fn draw_light_component_editor(
 
     let max_intensity = 5.0;
     let fill_w = (intensity.clamp(0.0, max_intensity) / max_intensity) * slider_w;
-    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, ACCENT_COLOR);
+    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, theme().accent);
 
-    draw_text(&format!("{:.1}", intensity), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    draw_text(&format!("{:.1}", intensity), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
 
     if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
         let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
@@ -2023,15 +2357,15 @@ fn draw_light_component_editor(
     *y += line_height;
 
     // Radius slider
-    draw_text("Radius:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Radius:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
     draw_rectangle(slider_rect.x, slider_rect.y, slider_rect.w, slider_rect.h, Color::from_rgba(40, 40, 45, 255));
 
     let max_radius = 8192.0; // 8 meters
     let fill_w = (radius.clamp(0.0, max_radius) / max_radius) * slider_w;
-    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, ACCENT_COLOR);
+    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, theme().accent);
 
-    draw_text(&format!("{:.0}", radius), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    draw_text(&format!("{:.0}", radius), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
 
     if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
         let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
@@ -2041,41 +2375,114 @@ fn draw_light_component_editor(
     *y += line_height;
 
     // Offset XYZ
-    draw_text("Offset:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Offset:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     draw_text(&format!("X:{:.0} Y:{:.0} Z:{:.0}", offset[0], offset[1], offset[2]),
-        x + 50.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+        x + 50.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
     *y += line_height;
 
     modified
 }
 
+/// Text fields each component can have in `component_field_edit` ids
+/// (a field's id is `component index * COMPONENT_FIELDS + field`; Dialogue
+/// uses the most: 6 for its selected node plus 4 per choice)
+const COMPONENT_FIELDS: usize = 24;
+
+/// Validation warnings in the component list and properties
+const COMPONENT_WARNING_COLOR: Color = Color::new(0.95, 0.75, 0.3, 1.0);
+
+/// Draw a click-to-edit text field for a component property.
+/// Returns the new text when an edit is confirmed (Enter or click outside).
+fn draw_component_text_field(
+    ctx: &mut UiContext,
+    rect: Rect,
+    value: &str,
+    placeholder: &str,
+    field_id: usize,
+    editing: &mut Option<(usize, TextInputState)>,
+) -> Option<String> {
+    let hovered = ctx.mouse.inside(&rect);
+
+    if let Some((_, input)) = editing.as_mut().filter(|(id, _)| *id == field_id) {
+        draw_text_input(rect, input, 12.0);
+        let confirmed = is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter)
+            || (ctx.mouse.left_pressed && !hovered);
+        if confirmed {
+            let text = input.text.trim().to_string();
+            *editing = None;
+            return Some(text);
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            *editing = None;
+        }
+        return None;
+    }
+
+    let bg = if hovered { Color::from_rgba(55, 55, 65, 255) } else { Color::from_rgba(45, 45, 55, 255) };
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, bg);
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, Color::from_rgba(60, 60, 65, 255));
+    let (text, color) = if value.is_empty() { (placeholder, theme().text_dim) } else { (value, theme().text) };
+    draw_text(text, rect.x + 4.0, (rect.y + 13.0).floor(), 12.0, color);
+
+    if hovered && ctx.mouse.left_pressed {
+        *editing = Some((field_id, TextInputState::new(value)));
+    }
+    None
+}
+
+/// Labelled text field row for an optional name (empty text = None);
+/// returns true if the value changed
+fn draw_optional_name_field(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    label: &str,
+    value: &mut Option<String>,
+    placeholder: &str,
+    field_id: usize,
+    editing: &mut Option<(usize, TextInputState)>,
+) -> bool {
+    draw_text(label, x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let rect = Rect::new(x + 70.0, *y + 1.0, width - 74.0, 18.0);
+    *y += 20.0;
+    let Some(text) = draw_component_text_field(ctx, rect, value.as_deref().unwrap_or(""), placeholder, field_id, editing) else {
+        return false;
+    };
+    let new_value = if text.is_empty() { None } else { Some(text) };
+    if new_value == *value {
+        return false;
+    }
+    *value = new_value;
+    true
+}
+
 /// Draw trigger component editor
 fn draw_trigger_editor(
-    _ctx: &mut UiContext,
+    ctx: &mut UiContext,
     x: f32,
     y: &mut f32,
-    _width: f32,
+    width: f32,
     trigger_id: &mut String,
     on_enter: &mut Option<String>,
     on_exit: &mut Option<String>,
-    _icon_font: Option<&Font>,
+    fields: usize,
+    editing: &mut Option<(usize, TextInputState)>,
 ) -> bool {
-    let line_height = 20.0;
-
-    draw_text("Trigger ID:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    draw_text(trigger_id, x + 70.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
-    *y += line_height;
+    let mut modified = false;
 
-    draw_text("On Enter:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    draw_text(on_enter.as_deref().unwrap_or("(none)"), x + 70.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
-    *y += line_height;
+    draw_text("Trigger ID:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let id_rect = Rect::new(x + 70.0, *y + 1.0, width - 74.0, 18.0);
+    if let Some(text) = draw_component_text_field(ctx, id_rect, trigger_id, "(none)", fields, editing) {
+        modified = text != *trigger_id;
+        *trigger_id = text;
+    }
+    *y += 20.0;
 
-    draw_text("On Exit:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    draw_text(on_exit.as_deref().unwrap_or("(none)"), x + 70.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
-    *y += line_height;
+    modified |= draw_optional_name_field(ctx, x, y, width, "On Enter:", on_enter, "(none)", fields + 1, editing);
+    modified |= draw_optional_name_field(ctx, x, y, width, "On Exit:", on_exit, "(none)", fields + 2, editing);
 
-    // TODO: Add text input for editing
-    false
+    modified
 }
 
 /// Draw pickup component editor
@@ -2088,19 +2495,19 @@ fn draw_pickup_editor(
     respawn_time: &mut Option<f32>,
     _icon_font: Option<&Font>,
 ) -> bool {
-    use crate::game::components::ItemType;
+    use crate::game::components::{ItemType, KeyType};
     let mut modified = false;
     let line_height = 20.0;
 
     // Item type
-    draw_text("Type:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Type:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     let type_name = match item_type {
         ItemType::HealthPickup { amount } => format!("Health ({})", amount),
         ItemType::Currency { amount } => format!("Currency ({})", amount),
         ItemType::Key(_) => "Key".to_string(),
         ItemType::Upgrade => "Upgrade".to_string(),
     };
-    draw_text(&type_name, x + 50.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    draw_text(&type_name, x + 50.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
     *y += line_height;
 
     // Item type buttons (simplified)
@@ -2119,7 +2526,7 @@ fn draw_pickup_editor(
         let hovered = ctx.mouse.inside(&btn_rect);
 
         let bg = if is_active {
-            ACCENT_COLOR
+            theme().accent
         } else if hovered {
             Color::from_rgba(60, 60, 70, 255)
         } else {
@@ -2127,7 +2534,7 @@ fn draw_pickup_editor(
         };
         draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
 
-        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { TEXT_COLOR };
+        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { theme().text };
         draw_text(name, btn_x + 2.0, *y + 13.0, 10.0, text_color);
 
         if hovered && ctx.mouse.left_pressed && !is_active {
@@ -2137,15 +2544,79 @@ fn draw_pickup_editor(
     }
     *y += line_height;
 
-    // Respawn time
-    draw_text("Respawn:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    let respawn_text = respawn_time.map(|t| format!("{:.0}s", t)).unwrap_or("Never".to_string());
-    draw_text(&respawn_text, x + 60.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    // Amount healed or given, or which key (click to cycle)
+    match item_type {
+        ItemType::HealthPickup { amount } | ItemType::Currency { amount } => {
+            let mut value = *amount as f32;
+            modified |= draw_particle_slider(ctx, x, y, width, "Amount:", &mut value, 1.0, 500.0);
+            *amount = (value.round() as i32).max(1);
+        }
+        ItemType::Key(key) => {
+            draw_text("Key:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+            let key_rect = Rect::new(x + 60.0, *y + 1.0, width - 64.0, 18.0);
+            let hovered = ctx.mouse.inside(&key_rect);
+            let bg = if hovered { Color::from_rgba(60, 60, 70, 255) } else { Color::from_rgba(45, 45, 50, 255) };
+            draw_rectangle(key_rect.x, key_rect.y, key_rect.w, key_rect.h, bg);
+            draw_text(&key_type_label(*key), key_rect.x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+            if hovered && ctx.mouse.left_pressed {
+                *key = next_key_type(*key);
+                modified = true;
+            }
+            *y += line_height;
+            if let KeyType::Generic(number) = key {
+                let mut value = *number as f32;
+                modified |= draw_particle_slider(ctx, x, y, width, "Key #:", &mut value, 1.0, 16.0);
+                *number = (value.round() as u32).max(1);
+            }
+        }
+        ItemType::Upgrade => {}
+    }
+
+    // Respawn toggle and time (off = collected for good)
+    draw_text("Respawn:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let toggle_rect = Rect::new(x + width - 40.0, *y + 2.0, 32.0, 14.0);
+    let respawns = respawn_time.is_some();
+    let toggle_color = if respawns { theme().accent } else { Color::from_rgba(60, 60, 65, 255) };
+    draw_rectangle(toggle_rect.x, toggle_rect.y, toggle_rect.w, toggle_rect.h, toggle_color);
+    draw_text(if respawns { "ON" } else { "OFF" }, toggle_rect.x + 6.0, *y + 13.0, 11.0, theme().text);
+    if ctx.mouse.inside(&toggle_rect) && ctx.mouse.left_pressed {
+        *respawn_time = if respawns { None } else { Some(30.0) };
+        modified = true;
+    }
     *y += line_height;
+    if let Some(time) = respawn_time {
+        modified |= draw_particle_slider(ctx, x, y, width, "Every:", time, 1.0, 300.0);
+    }
 
     modified
 }
 
+/// Editor label for a key pickup
+fn key_type_label(key: crate::game::components::KeyType) -> String {
+    use crate::game::components::KeyType;
+    match key {
+        KeyType::Generic(n) => format!("Generic {}", n),
+        KeyType::BossKey => "Boss Key".to_string(),
+        KeyType::MasterKey => "Master Key".to_string(),
+        KeyType::DoubleJump => "Double Jump".to_string(),
+        KeyType::WallClimb => "Wall Climb".to_string(),
+        KeyType::Dash => "Dash".to_string(),
+    }
+}
+
+/// The next key kind, for cycling in the pickup editor
+fn next_key_type(key: crate::game::components::KeyType) -> crate::game::components::KeyType {
+    use crate::game::components::KeyType;
+    match key {
+        KeyType::Generic(_) => KeyType::BossKey,
+        KeyType::BossKey => KeyType::MasterKey,
+        KeyType::MasterKey => KeyType::DoubleJump,
+        KeyType::DoubleJump => KeyType::WallClimb,
+        KeyType::WallClimb => KeyType::Dash,
+        KeyType::Dash => KeyType::Generic(1),
+    }
+}
+
 /// Draw enemy component editor
 fn draw_enemy_editor(
     ctx: &mut UiContext,
@@ -2156,6 +2627,8 @@ fn draw_enemy_editor(
     health: &mut i32,
     damage: &mut i32,
     patrol_radius: &mut f32,
+    poise: &mut f32,
+    attack: &mut crate::asset::AttackDef,
     _icon_font: Option<&Font>,
 ) -> bool {
     use crate::game::components::EnemyType;
@@ -2163,7 +2636,7 @@ fn draw_enemy_editor(
     let line_height = 20.0;
 
     // Enemy type
-    draw_text("Type:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Type:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     let type_name = match enemy_type {
         EnemyType::Grunt => "Grunt",
         EnemyType::Archer => "Archer",
@@ -2172,7 +2645,7 @@ fn draw_enemy_editor(
         EnemyType::Elite => "Elite",
         EnemyType::Boss => "Boss",
     };
-    draw_text(type_name, x + 50.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    draw_text(type_name, x + 50.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
     *y += line_height;
 
     // Enemy type buttons (first row)
@@ -2190,7 +2663,7 @@ fn draw_enemy_editor(
         let hovered = ctx.mouse.inside(&btn_rect);
 
         let bg = if is_active {
-            ACCENT_COLOR
+            theme().accent
         } else if hovered {
             Color::from_rgba(60, 60, 70, 255)
         } else {
@@ -2198,7 +2671,7 @@ fn draw_enemy_editor(
         };
         draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
 
-        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { TEXT_COLOR };
+        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { theme().text };
         draw_text(name, btn_x + 4.0, *y + 13.0, 11.0, text_color);
 
         if hovered && ctx.mouse.left_pressed && !is_active {
@@ -2222,7 +2695,150 @@ fn draw_enemy_editor(
         let hovered = ctx.mouse.inside(&btn_rect);
 
         let bg = if is_active {
-            ACCENT_COLOR
+            theme().accent
+        } else if hovered {
+            Color::from_rgba(60, 60, 70, 255)
+        } else {
+            Color::from_rgba(45, 45, 50, 255)
+        };
+        draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
+
+        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { theme().text };
+        draw_text(name, btn_x + 4.0, *y + 13.0, 11.0, text_color);
+
+        if hovered && ctx.mouse.left_pressed && !is_active {
+            *enemy_type = *new_type;
+            modified = true;
+        }
+    }
+    *y += line_height;
+
+    // Health, base damage and patrol radius
+    let mut hp = *health as f32;
+    let mut hit = *damage as f32;
+    modified |= draw_particle_slider(ctx, x, y, width, "Health:", &mut hp, 1.0, 1000.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Damage:", &mut hit, 0.0, 200.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Patrol:", patrol_radius, 0.0, 8192.0);
+    *health = (hp.round() as i32).max(1);
+    *damage = hit.round() as i32;
+
+    // Poise and the attack it swings at the player
+    modified |= draw_particle_slider(ctx, x, y, width, "Poise:", poise, 0.0, 200.0);
+    draw_text("Attack", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    *y += line_height;
+    modified |= draw_attack_editor(ctx, x, y, width, attack);
+
+    modified
+}
+
+/// Draw weapon component editor: base damage, speed, held part, guard and both attacks
+fn draw_weapon_editor(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    damage: &mut i32,
+    light: &mut crate::asset::AttackDef,
+    heavy: &mut crate::asset::AttackDef,
+    guard_absorption: &mut f32,
+    guard_stamina: &mut f32,
+    speed: &mut f32,
+    model_part: &mut Option<String>,
+    fields: usize,
+    editing: &mut Option<(usize, TextInputState)>,
+) -> bool {
+    let mut modified = false;
+    let mut base = *damage as f32;
+    modified |= draw_particle_slider(ctx, x, y, width, "Damage:", &mut base, 0.0, 200.0);
+    *damage = base.round() as i32;
+    modified |= draw_particle_slider(ctx, x, y, width, "Speed:", speed, 0.25, 3.0);
+    // Mesh part held in the hand once the player has an animated model
+    modified |= draw_optional_name_field(ctx, x, y, width, "Part:", model_part, "(whole mesh)", fields, editing);
+    modified |= draw_particle_slider(ctx, x, y, width, "Absorb:", guard_absorption, 0.0, 1.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Guard st:", guard_stamina, 0.0, 3.0);
+
+    draw_text("Light attack", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    *y += 20.0;
+    modified |= draw_attack_editor(ctx, x, y, width, light);
+    draw_text("Heavy attack", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    *y += 20.0;
+    modified |= draw_attack_editor(ctx, x, y, width, heavy);
+
+    modified
+}
+
+/// Draw armor component editor: defense and weight (the roll tier it puts the player in)
+fn draw_armor_editor(ctx: &mut UiContext, x: f32, y: &mut f32, width: f32, defense: &mut f32, weight: &mut f32) -> bool {
+    let mut modified = false;
+    modified |= draw_particle_slider(ctx, x, y, width, "Defense:", defense, 0.0, 1.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Weight:", weight, 0.0, 50.0);
+    let roll = crate::game::combat::RollTier::from_weight(*weight);
+    draw_text(&format!("Roll: {}", roll.label()), x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    *y += 20.0;
+    modified
+}
+
+/// Sliders for one attack: length and active frames, damage, poise, stamina and hitbox
+fn draw_attack_editor(ctx: &mut UiContext, x: f32, y: &mut f32, width: f32, attack: &mut crate::asset::AttackDef) -> bool {
+    let mut modified = false;
+    let mut frames = attack.frames as f32;
+    let mut start = attack.active_start as f32;
+    let mut end = attack.active_end as f32;
+    modified |= draw_particle_slider(ctx, x, y, width, "Frames:", &mut frames, 1.0, 120.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Active:", &mut start, 0.0, frames);
+    modified |= draw_particle_slider(ctx, x, y, width, "Until:", &mut end, start, frames.max(start + 1.0));
+    attack.frames = frames.round() as u32;
+    attack.active_start = start.round() as u32;
+    attack.active_end = (end.round() as u32).max(attack.active_start);
+    modified |= draw_particle_slider(ctx, x, y, width, "Scale:", &mut attack.damage_scale, 0.0, 5.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Poise:", &mut attack.poise_damage, 0.0, 100.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Stamina:", &mut attack.stamina_cost, 0.0, 100.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Reach:", &mut attack.reach, 0.0, 2048.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Radius:", &mut attack.radius, 64.0, 1024.0);
+    modified
+}
+
+/// Draw door component editor
+fn draw_door_editor(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    required_key: &mut Option<String>,
+    start_open: &mut bool,
+    motion: &mut crate::asset::DoorMotion,
+    open_time: &mut f32,
+    open_trigger: &mut Option<String>,
+    fields: usize,
+    editing: &mut Option<(usize, TextInputState)>,
+) -> bool {
+    use crate::asset::DoorMotion;
+    let mut modified = false;
+    let line_height = 20.0;
+
+    // Default key and opening trigger (instances can relink them in the World Editor)
+    modified |= draw_optional_name_field(ctx, x, y, width, "Key:", required_key, "(unlocked)", fields, editing);
+    modified |= draw_optional_name_field(ctx, x, y, width, "Trigger:", open_trigger, "(interact)", fields + 1, editing);
+
+    // Motion presets: slide up into the ceiling, slide sideways, swing either way
+    draw_text("Motion:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    draw_text(motion.label(), x + 60.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+    *y += line_height;
+    let presets = [
+        ("Up", DoorMotion::Slide { offset: [0.0, 1024.0, 0.0] }),
+        ("Side", DoorMotion::Slide { offset: [1024.0, 0.0, 0.0] }),
+        ("Swing L", DoorMotion::Rotate { angle: 90.0 }),
+        ("Swing R", DoorMotion::Rotate { angle: -90.0 }),
+    ];
+    let btn_w = (width - 12.0) / presets.len() as f32;
+    for (i, (name, preset)) in presets.iter().enumerate() {
+        let btn_x = x + 4.0 + i as f32 * btn_w;
+        let btn_rect = Rect::new(btn_x, *y, btn_w - 2.0, 18.0);
+        let is_active = motion == preset;
+        let hovered = ctx.mouse.inside(&btn_rect);
+
+        let bg = if is_active {
+            theme().accent
         } else if hovered {
             Color::from_rgba(60, 60, 70, 255)
         } else {
@@ -2230,170 +2846,870 @@ fn draw_enemy_editor(
         };
         draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
 
-        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { TEXT_COLOR };
+        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { theme().text };
         draw_text(name, btn_x + 4.0, *y + 13.0, 11.0, text_color);
 
-        if hovered && ctx.mouse.left_pressed && !is_active {
-            *enemy_type = *new_type;
-            modified = true;
+        if hovered && ctx.mouse.left_pressed && !is_active {
+            *motion = *preset;
+            modified = true;
+        }
+    }
+    *y += line_height;
+
+    // Open time slider (0.1 - 4 seconds)
+    draw_text("Time:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let slider_x = x + 60.0;
+    let slider_w = width - 100.0;
+    let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
+    draw_rectangle(slider_rect.x, slider_rect.y, slider_rect.w, slider_rect.h, Color::from_rgba(40, 40, 45, 255));
+    let max_time = 4.0;
+    let fill_w = (open_time.clamp(0.0, max_time) / max_time) * slider_w;
+    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, theme().accent);
+    draw_text(&format!("{:.1}s", *open_time), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+
+    if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
+        let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
+        *open_time = (t * max_time).max(0.1);
+        modified = true;
+    }
+    *y += line_height;
+
+    // Start open toggle
+    draw_text("Start Open:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+
+    let toggle_x = x + width - 40.0;
+    let toggle_rect = Rect::new(toggle_x, *y + 2.0, 32.0, 14.0);
+    let toggle_color = if *start_open { theme().accent } else { Color::from_rgba(60, 60, 65, 255) };
+    draw_rectangle(toggle_rect.x, toggle_rect.y, toggle_rect.w, toggle_rect.h, toggle_color);
+    draw_text(if *start_open { "ON" } else { "OFF" }, toggle_x + 6.0, *y + 13.0, 11.0, theme().text);
+
+    if ctx.mouse.inside(&toggle_rect) && ctx.mouse.left_pressed {
+        *start_open = !*start_open;
+        modified = true;
+    }
+    *y += line_height;
+
+    modified
+}
+
+/// Draw moving platform component editor
+fn draw_moving_platform_editor(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    route: &mut crate::asset::PlatformRoute,
+    speed: &mut f32,
+    wait: &mut f32,
+    activate_trigger: &mut Option<String>,
+    lever: &mut bool,
+    fields: usize,
+    editing: &mut Option<(usize, TextInputState)>,
+) -> bool {
+    use crate::asset::PlatformRoute;
+    let mut modified = false;
+    let line_height = 20.0;
+
+    // Route: the instance's attached path, or up and down to an offset
+    draw_text("Route:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let routes = [("Path", PlatformRoute::Path), ("Elevator", PlatformRoute::default())];
+    let btn_w = (width - 64.0) / routes.len() as f32;
+    for (i, (name, preset)) in routes.iter().enumerate() {
+        let btn_x = x + 60.0 + i as f32 * btn_w;
+        let btn_rect = Rect::new(btn_x, *y, btn_w - 2.0, 18.0);
+        let is_active = std::mem::discriminant(route) == std::mem::discriminant(preset);
+        let hovered = ctx.mouse.inside(&btn_rect);
+
+        let bg = if is_active {
+            theme().accent
+        } else if hovered {
+            Color::from_rgba(60, 60, 70, 255)
+        } else {
+            Color::from_rgba(45, 45, 50, 255)
+        };
+        draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
+
+        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { theme().text };
+        draw_text(name, btn_x + 4.0, *y + 13.0, 11.0, text_color);
+
+        if hovered && ctx.mouse.left_pressed && !is_active {
+            *route = *preset;
+            modified = true;
+        }
+    }
+    *y += line_height;
+
+    if let PlatformRoute::Elevator { offset } = route {
+        modified |= draw_particle_slider(ctx, x, y, width, "Rise:", &mut offset[1], -4096.0, 4096.0);
+        modified |= draw_particle_slider(ctx, x, y, width, "Side X:", &mut offset[0], -4096.0, 4096.0);
+        modified |= draw_particle_slider(ctx, x, y, width, "Side Z:", &mut offset[2], -4096.0, 4096.0);
+    } else {
+        draw_text("Attach a path in the World Editor", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+        *y += line_height;
+    }
+    modified |= draw_particle_slider(ctx, x, y, width, "Speed:", speed, 64.0, 4096.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Wait:", wait, 0.0, 10.0);
+
+    // Calling: a linked trigger, or Interact next to the platform (lever)
+    modified |= draw_optional_name_field(ctx, x, y, width, "Trigger:", activate_trigger, "(always moving)", fields, editing);
+    draw_text("Lever:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let toggle_x = x + width - 40.0;
+    let toggle_rect = Rect::new(toggle_x, *y + 2.0, 32.0, 14.0);
+    let toggle_color = if *lever { theme().accent } else { Color::from_rgba(60, 60, 65, 255) };
+    draw_rectangle(toggle_rect.x, toggle_rect.y, toggle_rect.w, toggle_rect.h, toggle_color);
+    draw_text(if *lever { "ON" } else { "OFF" }, toggle_x + 6.0, *y + 13.0, 11.0, theme().text);
+
+    if ctx.mouse.inside(&toggle_rect) && ctx.mouse.left_pressed {
+        *lever = !*lever;
+        modified = true;
+    }
+    *y += line_height;
+
+    modified
+}
+
+/// Draw dialogue component editor: an outline of the tree (click a node to
+/// select it) above the selected node's fields and its choices
+fn draw_dialogue_editor(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    nodes: &mut Vec<crate::asset::DialogueNode>,
+    selected: &mut usize,
+    fields: usize,
+    editing: &mut Option<(usize, TextInputState)>,
+    icon_font: Option<&Font>,
+) -> bool {
+    use crate::asset::{DialogueChoice, DialogueNode, MAX_DIALOGUE_CHOICES};
+    let mut modified = false;
+    let line_height = 18.0;
+    let btn_size = 18.0;
+    *selected = (*selected).min(nodes.len().saturating_sub(1));
+
+    // Structural changes wait until the fields are drawn, so a text edit
+    // confirmed by this click still lands in the node it was started on
+    let mut select = None;
+    draw_text("Nodes:", x + 4.0, *y + 13.0, FONT_SIZE_HEADER, theme().text);
+    let add_rect = Rect::new(x + width - btn_size * 2.0 - 4.0, *y, btn_size, btn_size);
+    let add_node = icon_button(ctx, add_rect, icon::PLUS, icon_font, "Add node");
+    let rem_rect = Rect::new(x + width - btn_size - 2.0, *y, btn_size, btn_size);
+    let remove_node = nodes.len() > 1 && icon_button(ctx, rem_rect, icon::MINUS, icon_font, "Remove node");
+    *y += line_height + 2.0;
+
+    // Outline: each node with its condition and speaker, then where it leads
+    for (i, node) in nodes.iter().enumerate() {
+        let rows = node.choices.len().max(1) as f32 + 1.0;
+        let rect = Rect::new(x + 2.0, *y, width - 4.0, line_height * rows);
+        let is_selected = i == *selected;
+        let hovered = ctx.mouse.inside(&rect);
+        if is_selected {
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(55, 55, 68, 255));
+            draw_rectangle(rect.x, rect.y, 2.0, rect.h, theme().accent);
+        } else if hovered {
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(45, 45, 52, 255));
+        }
+        if hovered && ctx.mouse.left_pressed && !is_selected {
+            select = Some(i);
+        }
+
+        let condition = node.condition.as_ref().map(|c| format!(" [{}]", c)).unwrap_or_default();
+        let speaker = if node.speaker.is_empty() { String::new() } else { format!(" {}:", node.speaker) };
+        let head = format!("{}{}{} {}", node.id, condition, speaker, node.text);
+        draw_text(&clip_chars(&head, 40), x + 8.0, *y + 13.0, FONT_SIZE_CONTENT, theme().text);
+        *y += line_height;
+        let target = |next: &Option<String>| next.clone().unwrap_or_else(|| "(end)".to_string());
+        if node.choices.is_empty() {
+            draw_text(&format!("-> {}", target(&node.next)), x + 20.0, *y + 13.0, FONT_SIZE_CONTENT, theme().text_dim);
+            *y += line_height;
+        }
+        for choice in &node.choices {
+            let line = format!("> {} -> {}", clip_chars(&choice.text, 24), target(&choice.next));
+            draw_text(&line, x + 20.0, *y + 13.0, FONT_SIZE_CONTENT, theme().text_dim);
+            *y += line_height;
+        }
+    }
+    *y += 4.0;
+
+    // The selected node
+    let mut renamed = None;
+    if let Some(node) = nodes.get_mut(*selected) {
+        let old_id = node.id.clone();
+        if draw_text_field_row(ctx, x, y, width, "ID:", &mut node.id, "(none)", fields, editing) {
+            renamed = Some((old_id, node.id.clone()));
+            modified = true;
+        }
+        modified |= draw_text_field_row(ctx, x, y, width, "Speaker:", &mut node.speaker, "(narration)", fields + 1, editing);
+        modified |= draw_text_field_row(ctx, x, y, width, "Text:", &mut node.text, "(empty)", fields + 2, editing);
+        modified |= draw_condition_field(ctx, x, y, width, &mut node.condition, fields + 3, editing);
+        modified |= draw_effects_field(ctx, x, y, width, &mut node.effects, fields + 4, editing);
+        if node.choices.is_empty() {
+            modified |= draw_optional_name_field(ctx, x, y, width, "Next:", &mut node.next, "(end)", fields + 5, editing);
+        }
+
+        *y += 4.0;
+        draw_text("Choices:", x + 4.0, *y + 13.0, FONT_SIZE_HEADER, theme().text);
+        let add_rect = Rect::new(x + width - btn_size - 2.0, *y, btn_size, btn_size);
+        if node.choices.len() < MAX_DIALOGUE_CHOICES && icon_button(ctx, add_rect, icon::PLUS, icon_font, "Add choice") {
+            node.choices.push(DialogueChoice::new("...", None));
+            modified = true;
+        }
+        *y += line_height + 2.0;
+
+        let mut remove_choice = None;
+        for (c, choice) in node.choices.iter_mut().enumerate().take(MAX_DIALOGUE_CHOICES) {
+            let field = fields + 6 + c * 4;
+            draw_text(&format!("{}.", c + 1), x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+            let rect = Rect::new(x + 70.0, *y + 1.0, width - 76.0 - btn_size, 18.0);
+            if let Some(text) = draw_component_text_field(ctx, rect, &choice.text, "(empty)", field, editing) {
+                modified |= text != choice.text;
+                choice.text = text;
+            }
+            let rem_rect = Rect::new(x + width - btn_size - 2.0, *y + 1.0, btn_size, btn_size);
+            if icon_button(ctx, rem_rect, icon::MINUS, icon_font, "Remove choice") {
+                remove_choice = Some(c);
+            }
+            *y += 20.0;
+            modified |= draw_optional_name_field(ctx, x, y, width, "Goto:", &mut choice.next, "(end)", field + 1, editing);
+            modified |= draw_condition_field(ctx, x, y, width, &mut choice.condition, field + 2, editing);
+            modified |= draw_effects_field(ctx, x, y, width, &mut choice.effects, field + 3, editing);
+        }
+        if let Some(c) = remove_choice {
+            node.choices.remove(c);
+            *editing = None;
+            modified = true;
+        }
+    }
+
+    // Keep links pointing at a renamed node
+    if let Some((old, new)) = renamed {
+        for node in nodes.iter_mut() {
+            let links = std::iter::once(&mut node.next).chain(node.choices.iter_mut().map(|c| &mut c.next));
+            for next in links.filter(|next| next.as_deref() == Some(old.as_str())) {
+                *next = Some(new.clone());
+            }
+        }
+    }
+    if add_node {
+        let id = (1..).map(|n| format!("node{}", n)).find(|id| !nodes.iter().any(|n| n.id == *id)).unwrap_or_default();
+        let at = (*selected + 1).min(nodes.len());
+        nodes.insert(at, DialogueNode::new(id, "", "..."));
+        select = Some(at);
+        modified = true;
+    } else if remove_node {
+        nodes.remove(*selected);
+        select = Some((*selected).min(nodes.len() - 1));
+        modified = true;
+    }
+    if let Some(i) = select {
+        // Field ids are per slot, not per node: drop an edit in progress
+        *selected = i;
+        *editing = None;
+    }
+
+    modified
+}
+
+/// Labelled text field row; returns true if the value changed
+fn draw_text_field_row(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    label: &str,
+    value: &mut String,
+    placeholder: &str,
+    field_id: usize,
+    editing: &mut Option<(usize, TextInputState)>,
+) -> bool {
+    draw_text(label, x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let rect = Rect::new(x + 70.0, *y + 1.0, width - 74.0, 18.0);
+    *y += 20.0;
+    match draw_component_text_field(ctx, rect, value, placeholder, field_id, editing) {
+        Some(text) if text != *value => {
+            *value = text;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// "If:" row for a dialogue condition (`gems >= 3`); text that isn't a
+/// condition keeps the old one
+fn draw_condition_field(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    condition: &mut Option<crate::asset::FlagCondition>,
+    field_id: usize,
+    editing: &mut Option<(usize, TextInputState)>,
+) -> bool {
+    use crate::asset::FlagCondition;
+    let mut text = condition.as_ref().map(|c| c.to_string());
+    if !draw_optional_name_field(ctx, x, y, width, "If:", &mut text, "(always)", field_id, editing) {
+        return false;
+    }
+    match text.as_deref().map(FlagCondition::parse) {
+        None => *condition = None,
+        Some(Some(parsed)) => *condition = Some(parsed),
+        Some(None) => return false,
+    }
+    true
+}
+
+/// "Sets:" row for dialogue effects (`met = 1, gold = 5`); text that doesn't
+/// parse keeps the old effects
+fn draw_effects_field(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    effects: &mut Vec<crate::asset::FlagEffect>,
+    field_id: usize,
+    editing: &mut Option<(usize, TextInputState)>,
+) -> bool {
+    use crate::asset::{effects_text, parse_effects};
+    let mut text = Some(effects_text(effects)).filter(|t| !t.is_empty());
+    if !draw_optional_name_field(ctx, x, y, width, "Sets:", &mut text, "(nothing)", field_id, editing) {
+        return false;
+    }
+    match parse_effects(text.as_deref().unwrap_or("")) {
+        Some(parsed) => {
+            *effects = parsed;
+            true
+        }
+        None => false,
+    }
+}
+
+/// First `max` characters of `text`, with "..." if it was longer
+fn clip_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    format!("{}...", text.chars().take(max).collect::<String>())
+}
+
+/// Draw audio component editor
+fn draw_audio_editor(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    sound: &mut String,
+    volume: &mut f32,
+    radius: &mut f32,
+    looping: &mut bool,
+    fields: usize,
+    editing: &mut Option<(usize, TextInputState)>,
+) -> bool {
+    let mut modified = false;
+    let line_height = 20.0;
+
+    // Sound name
+    draw_text("Sound:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let sound_rect = Rect::new(x + 70.0, *y + 1.0, width - 74.0, 18.0);
+    if let Some(text) = draw_component_text_field(ctx, sound_rect, sound, "(none)", fields, editing) {
+        modified = text != *sound;
+        *sound = text;
+    }
+    *y += line_height;
+
+    // Volume slider
+    draw_text("Volume:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let slider_x = x + 60.0;
+    let slider_w = width - 100.0;
+    let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
+    draw_rectangle(slider_rect.x, slider_rect.y, slider_rect.w, slider_rect.h, Color::from_rgba(40, 40, 45, 255));
+
+    let fill_w = volume.clamp(0.0, 1.0) * slider_w;
+    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, theme().accent);
+
+    draw_text(&format!("{:.0}%", *volume * 100.0), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+
+    if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
+        let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
+        *volume = t;
+        modified = true;
+    }
+    *y += line_height;
+
+    // Radius slider
+    draw_text("Radius:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
+    draw_rectangle(slider_rect.x, slider_rect.y, slider_rect.w, slider_rect.h, Color::from_rgba(40, 40, 45, 255));
+
+    let max_radius = 8192.0; // 8 meters
+    let fill_w = (radius.clamp(0.0, max_radius) / max_radius) * slider_w;
+    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, theme().accent);
+
+    draw_text(&format!("{:.0}", radius), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+
+    if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
+        let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
+        *radius = t * max_radius;
+        modified = true;
+    }
+    *y += line_height;
+
+    // Looping toggle
+    draw_text("Looping:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+
+    let toggle_x = x + width - 40.0;
+    let toggle_rect = Rect::new(toggle_x, *y + 2.0, 32.0, 14.0);
+    let toggle_color = if *looping { theme().accent } else { Color::from_rgba(60, 60, 65, 255) };
+    draw_rectangle(toggle_rect.x, toggle_rect.y, toggle_rect.w, toggle_rect.h, toggle_color);
+    draw_text(if *looping { "ON" } else { "OFF" }, toggle_x + 6.0, *y + 13.0, 11.0, theme().text);
+
+    if ctx.mouse.inside(&toggle_rect) && ctx.mouse.left_pressed {
+        *looping = !*looping;
+        modified = true;
+    }
+    *y += line_height;
+
+    modified
+}
+
+/// Preview panel height for the particle editor
+const PARTICLE_PREVIEW_HEIGHT: f32 = 96.0;
+
+/// Draw particle component editor: live preview, preset, blend mode and emitter sliders
+fn draw_particle_editor(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    effect: &mut String,
+    offset: &mut [f32; 3],
+    emitter: &mut crate::asset::ParticleEmitterDef,
+    preview: &mut Option<super::state::ParticlePreview>,
+    _icon_font: Option<&Font>,
+) -> bool {
+    use crate::asset::{ParticleEmitterDef, PARTICLE_PRESETS};
+    let mut modified = false;
+    let line_height = 20.0;
+
+    // Live preview through the game's particle renderer
+    let preview_rect = Rect::new(x + 4.0, *y, width - 8.0, PARTICLE_PREVIEW_HEIGHT);
+    draw_particle_preview(preview_rect, emitter, preview);
+    *y += PARTICLE_PREVIEW_HEIGHT + 4.0;
+
+    // Preset buttons (load the preset's settings)
+    draw_text("Preset:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    *y += line_height;
+    let btn_w = (width - 8.0) / PARTICLE_PRESETS.len() as f32;
+    for (i, name) in PARTICLE_PRESETS.iter().enumerate() {
+        let btn_x = x + 4.0 + i as f32 * btn_w;
+        let btn_rect = Rect::new(btn_x, *y, btn_w - 2.0, 18.0);
+        let is_active = effect == name;
+        let hovered = ctx.mouse.inside(&btn_rect);
+
+        let bg = if is_active {
+            theme().accent
+        } else if hovered {
+            Color::from_rgba(60, 60, 70, 255)
+        } else {
+            Color::from_rgba(45, 45, 50, 255)
+        };
+        draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
+
+        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { theme().text };
+        draw_text(name, btn_x + 3.0, *y + 13.0, 11.0, text_color);
+
+        if hovered && ctx.mouse.left_pressed {
+            if let Some(def) = ParticleEmitterDef::preset(name) {
+                *effect = name.to_string();
+                *emitter = def;
+                modified = true;
+            }
+        }
+    }
+    *y += line_height;
+
+    // Blend mode toggle
+    draw_text("Blend:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let toggle_rect = Rect::new(x + width - 72.0, *y + 2.0, 64.0, 14.0);
+    let hovered = ctx.mouse.inside(&toggle_rect);
+    let bg = if hovered { Color::from_rgba(60, 60, 70, 255) } else { Color::from_rgba(45, 45, 50, 255) };
+    draw_rectangle(toggle_rect.x, toggle_rect.y, toggle_rect.w, toggle_rect.h, bg);
+    draw_text(emitter.blend.label(), toggle_rect.x + 6.0, *y + 13.0, 11.0, theme().text);
+    if hovered && ctx.mouse.left_pressed {
+        emitter.blend = emitter.blend.toggled();
+        modified = true;
+    }
+    *y += line_height;
+
+    // Emitter sliders: label, value, range
+    let mut opacity = emitter.opacity as f32;
+    let rows: [(&str, &mut f32, f32, f32); 8] = [
+        ("Rate:", &mut emitter.rate, 0.0, 100.0),
+        ("Life:", &mut emitter.lifetime, 0.1, 10.0),
+        ("Speed:", &mut emitter.speed, 0.0, 3000.0),
+        ("Spread:", &mut emitter.spread, 0.0, 180.0),
+        ("Gravity:", &mut emitter.gravity, -1000.0, 3000.0),
+        ("Size:", &mut emitter.start_size, 1.0, 1024.0),
+        ("End:", &mut emitter.end_size, 1.0, 1024.0),
+        ("Opacity:", &mut opacity, 0.0, 255.0),
+    ];
+    for (label, value, min, max) in rows {
+        modified |= draw_particle_slider(ctx, x, y, width, label, value, min, max);
+    }
+    emitter.opacity = opacity.round() as u8;
+
+    draw_text("Offset:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    draw_text(&format!("X:{:.0} Y:{:.0} Z:{:.0}", offset[0], offset[1], offset[2]),
+        x + 60.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+    *y += line_height;
+
+    modified
+}
+
+/// Draw sprite component editor: texture picker, size, blend mode and offset
+fn draw_sprite_editor(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    texture: &mut Option<u64>,
+    scale: &mut f32,
+    blend: &mut crate::rasterizer::BlendMode,
+    offset: &mut [f32; 3],
+    user_textures: &crate::texture::TextureLibrary,
+) -> bool {
+    use crate::asset::SPRITE_BLENDS;
+    let mut modified = false;
+    let line_height = 20.0;
+
+    // Texture: click to cycle through the user textures
+    let textures: Vec<(&str, &crate::texture::UserTexture)> = user_textures.iter().collect();
+    let current = texture.and_then(|id| textures.iter().position(|(_, t)| t.id == id));
+    draw_text("Texture:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let picker_rect = Rect::new(x + 60.0, *y + 2.0, width - 68.0, 16.0);
+    let hovered = ctx.mouse.inside(&picker_rect);
+    let bg = if hovered { Color::from_rgba(60, 60, 70, 255) } else { Color::from_rgba(45, 45, 50, 255) };
+    draw_rectangle(picker_rect.x, picker_rect.y, picker_rect.w, picker_rect.h, bg);
+    let label = match current {
+        Some(i) => format!("{} ({}x{})", textures[i].0, textures[i].1.width, textures[i].1.height),
+        None if texture.is_some() => "(missing)".to_string(),
+        None => "(none)".to_string(),
+    };
+    draw_text(&label, picker_rect.x + 4.0, *y + 14.0, 11.0, if current.is_some() { theme().text } else { theme().text_dim });
+    if hovered && ctx.mouse.left_pressed && !textures.is_empty() {
+        let next = current.map(|i| (i + 1) % textures.len()).unwrap_or(0);
+        *texture = Some(textures[next].1.id);
+        modified = true;
+    }
+    *y += line_height;
+
+    // Blend mode buttons
+    draw_text("Blend:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    *y += line_height;
+    let btn_w = (width - 8.0) / SPRITE_BLENDS.len() as f32;
+    for (i, (mode, name)) in SPRITE_BLENDS.iter().enumerate() {
+        let btn_x = x + 4.0 + i as f32 * btn_w;
+        let btn_rect = Rect::new(btn_x, *y, btn_w - 2.0, 18.0);
+        let is_active = blend == mode;
+        let hovered = ctx.mouse.inside(&btn_rect);
+
+        let bg = if is_active {
+            theme().accent
+        } else if hovered {
+            Color::from_rgba(60, 60, 70, 255)
+        } else {
+            Color::from_rgba(45, 45, 50, 255)
+        };
+        draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
+
+        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { theme().text };
+        draw_text(name, btn_x + 3.0, *y + 13.0, 11.0, text_color);
+
+        if hovered && ctx.mouse.left_pressed && !is_active {
+            *blend = *mode;
+            modified = true;
+        }
+    }
+    *y += line_height;
+
+    // World units per texel
+    modified |= draw_particle_slider(ctx, x, y, width, "Scale:", scale, 1.0, 64.0);
+
+    draw_text("Offset:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    draw_text(&format!("X:{:.0} Y:{:.0} Z:{:.0}", offset[0], offset[1], offset[2]),
+        x + 60.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+    *y += line_height;
+
+    modified
+}
+
+/// Draw status effect component editor: preset, stacking rule, sliders and the HUD icon.
+/// The effect is named after the asset.
+fn draw_status_effect_editor(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    preset: &mut String,
+    effect: &mut crate::asset::StatusEffectDef,
+    _icon_font: Option<&Font>,
+) -> bool {
+    use crate::asset::{StatusEffectDef, STATUS_PRESETS};
+    let mut modified = false;
+    let line_height = 20.0;
+
+    // Preset buttons (load the preset's settings)
+    draw_text("Preset:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    *y += line_height;
+    let btn_w = (width - 8.0) / STATUS_PRESETS.len() as f32;
+    for (i, name) in STATUS_PRESETS.iter().enumerate() {
+        let btn_x = x + 4.0 + i as f32 * btn_w;
+        let btn_rect = Rect::new(btn_x, *y, btn_w - 2.0, 18.0);
+        let is_active = preset == name;
+        let hovered = ctx.mouse.inside(&btn_rect);
+
+        let bg = if is_active {
+            theme().accent
+        } else if hovered {
+            Color::from_rgba(60, 60, 70, 255)
+        } else {
+            Color::from_rgba(45, 45, 50, 255)
+        };
+        draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
+
+        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { theme().text };
+        draw_text(name, btn_x + 3.0, *y + 13.0, 11.0, text_color);
+
+        if hovered && ctx.mouse.left_pressed {
+            if let Some(def) = StatusEffectDef::preset(name) {
+                *preset = name.to_string();
+                *effect = def;
+                modified = true;
+            }
         }
     }
     *y += line_height;
 
-    // Health
-    draw_text("Health:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    draw_text(&format!("{}", health), x + 60.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    // HUD icon as it shows in game
+    draw_text("Icon:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let [r, g, b] = effect.color;
+    draw_text(&effect.icon, x + 60.0, *y + 14.0, FONT_SIZE_CONTENT, Color::from_rgba(r, g, b, 255));
     *y += line_height;
 
-    // Damage
-    draw_text("Damage:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    draw_text(&format!("{}", damage), x + 60.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    // Stacking rule (click to cycle)
+    draw_text("Stacking:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let toggle_rect = Rect::new(x + width - 72.0, *y + 2.0, 64.0, 14.0);
+    let hovered = ctx.mouse.inside(&toggle_rect);
+    let bg = if hovered { Color::from_rgba(60, 60, 70, 255) } else { Color::from_rgba(45, 45, 50, 255) };
+    draw_rectangle(toggle_rect.x, toggle_rect.y, toggle_rect.w, toggle_rect.h, bg);
+    draw_text(effect.stacking.label(), toggle_rect.x + 6.0, *y + 13.0, 11.0, theme().text);
+    if hovered && ctx.mouse.left_pressed {
+        effect.stacking = effect.stacking.next();
+        modified = true;
+    }
     *y += line_height;
 
-    // Patrol radius
-    draw_text("Patrol:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    draw_text(&format!("{:.0}", patrol_radius), x + 60.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
-    *y += line_height;
+    // Effect sliders: label, value, range
+    let mut max_stacks = effect.max_stacks as f32;
+    let mut burst = effect.burst_damage as f32;
+    let rows: [(&str, &mut f32, f32, f32); 7] = [
+        ("Time:", &mut effect.duration, 0.0, 60.0),
+        ("Stacks:", &mut max_stacks, 1.0, 10.0),
+        ("DPS:", &mut effect.damage_per_second, -10.0, 10.0),
+        ("Speed:", &mut effect.speed_multiplier, 0.0, 2.0),
+        ("Buildup:", &mut effect.buildup, 0.0, 200.0),
+        ("Decay:", &mut effect.buildup_decay, 0.0, 50.0),
+        ("Burst:", &mut burst, 0.0, 100.0),
+    ];
+    for (label, value, min, max) in rows {
+        modified |= draw_particle_slider(ctx, x, y, width, label, value, min, max);
+    }
+    effect.max_stacks = max_stacks.round() as u32;
+    effect.burst_damage = burst.round() as i32;
 
     modified
 }
 
-/// Draw door component editor
-fn draw_door_editor(
+/// Draw hazard component editor (the asset's StatusEffect is what it applies)
+fn draw_hazard_editor(
     ctx: &mut UiContext,
     x: f32,
     y: &mut f32,
     width: f32,
-    required_key: &mut Option<String>,
-    start_open: &mut bool,
+    radius: &mut f32,
+    interval: &mut f32,
+    damage: &mut i32,
+    buildup: &mut f32,
     _icon_font: Option<&Font>,
 ) -> bool {
     let mut modified = false;
-    let line_height = 20.0;
-
-    // Required key
-    draw_text("Key:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    let key_text = required_key.as_deref().unwrap_or("(unlocked)");
-    draw_text(key_text, x + 40.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
-    *y += line_height;
-
-    // Start open toggle
-    draw_text("Start Open:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-
-    let toggle_x = x + width - 40.0;
-    let toggle_rect = Rect::new(toggle_x, *y + 2.0, 32.0, 14.0);
-    let toggle_color = if *start_open { ACCENT_COLOR } else { Color::from_rgba(60, 60, 65, 255) };
-    draw_rectangle(toggle_rect.x, toggle_rect.y, toggle_rect.w, toggle_rect.h, toggle_color);
-    draw_text(if *start_open { "ON" } else { "OFF" }, toggle_x + 6.0, *y + 13.0, 11.0, TEXT_COLOR);
+    let mut hit = *damage as f32;
+    modified |= draw_particle_slider(ctx, x, y, width, "Radius:", radius, 64.0, 4096.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Every:", interval, 0.1, 10.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Damage:", &mut hit, 0.0, 100.0);
+    modified |= draw_particle_slider(ctx, x, y, width, "Buildup:", buildup, 0.0, 200.0);
+    *damage = hit.round() as i32;
 
-    if ctx.mouse.inside(&toggle_rect) && ctx.mouse.left_pressed {
-        *start_open = !*start_open;
-        modified = true;
-    }
-    *y += line_height;
+    draw_text("Add a StatusEffect to apply one", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    *y += 20.0;
 
     modified
 }
 
-/// Draw audio component editor
-fn draw_audio_editor(
+/// Draw destructible component editor: health, debris preset, drop and the broken mesh
+fn draw_destructible_editor(
     ctx: &mut UiContext,
     x: f32,
     y: &mut f32,
     width: f32,
-    sound: &mut String,
-    volume: &mut f32,
-    radius: &mut f32,
-    looping: &mut bool,
-    _icon_font: Option<&Font>,
+    health: &mut i32,
+    debris: &mut String,
+    drop: &mut Option<String>,
+    broken: &mut Vec<MeshPart>,
+    request: &mut Option<BrokenMeshRequest>,
+    fields: usize,
+    editing: &mut Option<(usize, TextInputState)>,
 ) -> bool {
+    use crate::asset::PARTICLE_PRESETS;
     let mut modified = false;
     let line_height = 20.0;
 
-    // Sound name
-    draw_text("Sound:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    draw_text(sound, x + 50.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
-    *y += line_height;
-
-    // Volume slider
-    draw_text("Volume:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    let slider_x = x + 60.0;
-    let slider_w = width - 100.0;
-    let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
-    draw_rectangle(slider_rect.x, slider_rect.y, slider_rect.w, slider_rect.h, Color::from_rgba(40, 40, 45, 255));
-
-    let fill_w = volume.clamp(0.0, 1.0) * slider_w;
-    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, ACCENT_COLOR);
-
-    draw_text(&format!("{:.0}%", *volume * 100.0), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    let mut hp = *health as f32;
+    modified |= draw_particle_slider(ctx, x, y, width, "Health:", &mut hp, 1.0, 200.0);
+    *health = (hp.round() as i32).max(1);
 
-    if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
-        let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
-        *volume = t;
-        modified = true;
-    }
+    // Debris preset buttons ("none" = no burst)
+    draw_text("Debris:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     *y += line_height;
+    let names: Vec<&str> = std::iter::once("none").chain(PARTICLE_PRESETS).collect();
+    let btn_w = (width - 8.0) / names.len() as f32;
+    for (i, name) in names.iter().enumerate() {
+        let btn_x = x + 4.0 + i as f32 * btn_w;
+        let btn_rect = Rect::new(btn_x, *y, btn_w - 2.0, 18.0);
+        let value = if *name == "none" { "" } else { *name };
+        let is_active = debris == value;
+        let hovered = ctx.mouse.inside(&btn_rect);
 
-    // Radius slider
-    draw_text("Radius:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
-    draw_rectangle(slider_rect.x, slider_rect.y, slider_rect.w, slider_rect.h, Color::from_rgba(40, 40, 45, 255));
-
-    let max_radius = 8192.0; // 8 meters
-    let fill_w = (radius.clamp(0.0, max_radius) / max_radius) * slider_w;
-    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, ACCENT_COLOR);
+        let bg = if is_active {
+            theme().accent
+        } else if hovered {
+            Color::from_rgba(60, 60, 70, 255)
+        } else {
+            Color::from_rgba(45, 45, 50, 255)
+        };
+        draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
 
-    draw_text(&format!("{:.0}", radius), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+        let text_color = if is_active { Color::from_rgba(20, 20, 25, 255) } else { theme().text };
+        draw_text(name, btn_x + 3.0, *y + 13.0, 11.0, text_color);
 
-    if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
-        let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
-        *radius = t * max_radius;
-        modified = true;
+        if hovered && ctx.mouse.left_pressed && !is_active {
+            *debris = value.to_string();
+            modified = true;
+        }
     }
     *y += line_height;
 
-    // Looping toggle
-    draw_text("Looping:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-
-    let toggle_x = x + width - 40.0;
-    let toggle_rect = Rect::new(toggle_x, *y + 2.0, 32.0, 14.0);
-    let toggle_color = if *looping { ACCENT_COLOR } else { Color::from_rgba(60, 60, 65, 255) };
-    draw_rectangle(toggle_rect.x, toggle_rect.y, toggle_rect.w, toggle_rect.h, toggle_color);
-    draw_text(if *looping { "ON" } else { "OFF" }, toggle_x + 6.0, *y + 13.0, 11.0, TEXT_COLOR);
+    // Drop: a Pickup asset by name
+    modified |= draw_optional_name_field(ctx, x, y, width, "Drop:", drop, "(nothing)", fields, editing);
 
-    if ctx.mouse.inside(&toggle_rect) && ctx.mouse.left_pressed {
-        *looping = !*looping;
-        modified = true;
+    // Broken mesh summary + copy/swap buttons
+    let faces: usize = broken.iter().map(|p| p.mesh.face_count()).sum();
+    let summary = if broken.is_empty() {
+        "Broken: (disappears)".to_string()
+    } else {
+        format!("Broken: {} faces", faces)
+    };
+    draw_text(&summary, x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+    let btn_w = 36.0;
+    let buttons = [("Copy", BrokenMeshRequest::Copy, "Copy the intact mesh into the broken mesh"),
+                   ("Swap", BrokenMeshRequest::Swap, "Swap with Mesh to edit in the viewport")];
+    for (i, (label, req, tooltip)) in buttons.into_iter().enumerate() {
+        let btn_rect = Rect::new(x + width - (btn_w + 4.0) * (2 - i) as f32, *y + 2.0, btn_w, 14.0);
+        let hovered = ctx.mouse.inside(&btn_rect);
+        let bg = if hovered { Color::from_rgba(80, 80, 90, 255) } else { Color::from_rgba(60, 60, 65, 255) };
+        draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
+        draw_text(label, btn_rect.x + 5.0, *y + 13.0, 11.0, theme().text);
+        if hovered {
+            ctx.set_tooltip(tooltip, ctx.mouse.x, ctx.mouse.y);
+        }
+        if ctx.mouse.clicked(&btn_rect) {
+            *request = Some(req);
+        }
     }
     *y += line_height;
 
     modified
 }
 
-/// Draw particle component editor
-fn draw_particle_editor(
-    _ctx: &mut UiContext,
+/// Slider row for one emitter value; returns true while dragged
+fn draw_particle_slider(
+    ctx: &mut UiContext,
     x: f32,
     y: &mut f32,
-    _width: f32,
-    effect: &mut String,
-    offset: &mut [f32; 3],
-    _icon_font: Option<&Font>,
+    width: f32,
+    label: &str,
+    value: &mut f32,
+    min: f32,
+    max: f32,
 ) -> bool {
-    let line_height = 20.0;
+    let slider_x = x + 60.0;
+    let slider_w = width - 100.0;
 
-    draw_text("Effect:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    draw_text(effect, x + 50.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
-    *y += line_height;
+    draw_text(label, x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+    let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
+    draw_rectangle(slider_rect.x, slider_rect.y, slider_rect.w, slider_rect.h, Color::from_rgba(40, 40, 45, 255));
+    let fill_w = ((*value - min) / (max - min)).clamp(0.0, 1.0) * slider_w;
+    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, theme().accent);
 
-    draw_text("Offset:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
-    draw_text(&format!("X:{:.0} Y:{:.0} Z:{:.0}", offset[0], offset[1], offset[2]),
-        x + 50.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
-    *y += line_height;
+    let text = if max - min <= 20.0 { format!("{:.1}", value) } else { format!("{:.0}", value) };
+    draw_text(&text, x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+    *y += 20.0;
 
+    if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
+        let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
+        *value = min + t * (max - min);
+        return true;
+    }
     false
 }
 
+/// Run the preview emitter one frame and draw it: a side view of the emitter
+/// at the origin, framed to how far its particles travel
+fn draw_particle_preview(
+    rect: Rect,
+    def: &crate::asset::ParticleEmitterDef,
+    preview: &mut Option<super::state::ParticlePreview>,
+) {
+    use crate::game::particles::{draw_particles, ParticleEmitter};
+
+    let fb_w = (rect.w / 2.0).max(1.0) as usize;
+    let fb_h = (rect.h / 2.0).max(1.0) as usize;
+    let preview = preview.get_or_insert_with(|| super::state::ParticlePreview {
+        emitter: ParticleEmitter::new(def.clone(), Vec3::ZERO, 1),
+        fb: Framebuffer::new(fb_w, fb_h),
+    });
+    preview.emitter.def = def.clone();
+    preview.emitter.update(get_frame_time().min(0.1), true);
+
+    // Frame the travel distance plus the largest quad
+    let reach = (def.speed * def.lifetime).abs().max(def.start_size.max(def.end_size)) + def.end_size;
+    let mut camera = Camera::new();
+    camera.position = Vec3::new(0.0, reach * 0.4, -reach * 2.5);
+
+    let fb = &mut preview.fb;
+    fb.resize(fb_w, fb_h);
+    fb.clear(RasterColor::new(18, 18, 22));
+    draw_particles(fb, &camera, [&preview.emitter]);
+
+    let texture = Texture2D::from_rgba8(fb.width as u16, fb.height as u16, &fb.pixels);
+    texture.set_filter(FilterMode::Nearest);
+    draw_texture_ex(&texture, rect.x, rect.y, WHITE, DrawTextureParams {
+        dest_size: Some(vec2(rect.w, rect.h)),
+        ..Default::default()
+    });
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, Color::from_rgba(60, 60, 70, 255));
+    draw_text(&format!("{} particles", preview.emitter.len()), rect.x + 4.0, rect.bottom() - 4.0, 11.0, theme().text_dim);
+}
+
 /// Draw character controller component editor
 fn draw_character_controller_editor(
     ctx: &mut UiContext,
@@ -2412,14 +3728,14 @@ fn draw_character_controller_editor(
     let max_val = 3072.0;
 
     // Height slider
-    draw_text("Height:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Height:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
     draw_rectangle(slider_rect.x, slider_rect.y, slider_rect.w, slider_rect.h, Color::from_rgba(40, 40, 45, 255));
 
     let fill_w = (height.clamp(0.0, max_val) / max_val) * slider_w;
-    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, ACCENT_COLOR);
+    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, theme().accent);
 
-    draw_text(&format!("{:.0}", height), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    draw_text(&format!("{:.0}", height), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
 
     if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
         let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
@@ -2429,14 +3745,14 @@ fn draw_character_controller_editor(
     *y += line_height;
 
     // Radius slider
-    draw_text("Radius:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Radius:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
     draw_rectangle(slider_rect.x, slider_rect.y, slider_rect.w, slider_rect.h, Color::from_rgba(40, 40, 45, 255));
 
     let fill_w = (radius.clamp(0.0, max_val) / max_val) * slider_w;
-    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, ACCENT_COLOR);
+    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, theme().accent);
 
-    draw_text(&format!("{:.0}", radius), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    draw_text(&format!("{:.0}", radius), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
 
     if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
         let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
@@ -2446,15 +3762,15 @@ fn draw_character_controller_editor(
     *y += line_height;
 
     // Step height slider
-    draw_text("Step:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Step:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
     draw_rectangle(slider_rect.x, slider_rect.y, slider_rect.w, slider_rect.h, Color::from_rgba(40, 40, 45, 255));
 
     let max_step = 1024.0;
     let fill_w = (step_height.clamp(0.0, max_step) / max_step) * slider_w;
-    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, ACCENT_COLOR);
+    draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, theme().accent);
 
-    draw_text(&format!("{:.0}", step_height), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    draw_text(&format!("{:.0}", step_height), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
 
     if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
         let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
@@ -2466,6 +3782,84 @@ fn draw_character_controller_editor(
     modified
 }
 
+/// Draw LOD component editor (switch distances + copy/swap of level meshes)
+fn draw_lod_editor(
+    ctx: &mut UiContext,
+    x: f32,
+    y: &mut f32,
+    width: f32,
+    reduced: &mut Vec<MeshPart>,
+    reduced_distance: &mut f32,
+    impostor: &mut Vec<MeshPart>,
+    impostor_distance: &mut f32,
+    request: &mut Option<LodMeshRequest>,
+) -> bool {
+    let mut modified = false;
+    let line_height = 20.0;
+    let slider_x = x + 70.0;
+    let slider_w = width - 110.0;
+    let max_val = 32768.0;
+
+    let levels: [(LodLevel, &mut Vec<MeshPart>, &mut f32); 2] = [
+        (LodLevel::Reduced, &mut *reduced, &mut *reduced_distance),
+        (LodLevel::Impostor, &mut *impostor, &mut *impostor_distance),
+    ];
+    for (level, parts, distance) in levels {
+        // Level header: name + face count + copy/swap buttons
+        let faces: usize = parts.iter().map(|p| p.mesh.face_count()).sum();
+        let summary = if parts.is_empty() {
+            format!("{}: (empty)", level.label())
+        } else {
+            format!("{}: {} faces", level.label(), faces)
+        };
+        draw_text(&summary, x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+
+        let btn_w = 36.0;
+        let buttons = [("Copy", LodMeshRequest::Copy(level), "Copy full mesh into this level"),
+                       ("Swap", LodMeshRequest::Swap(level), "Swap with Mesh to edit in the viewport")];
+        for (i, (label, req, tooltip)) in buttons.into_iter().enumerate() {
+            let btn_rect = Rect::new(x + width - (btn_w + 4.0) * (2 - i) as f32, *y + 2.0, btn_w, 14.0);
+            let hovered = ctx.mouse.inside(&btn_rect);
+            let bg = if hovered { Color::from_rgba(80, 80, 90, 255) } else { Color::from_rgba(60, 60, 65, 255) };
+            draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
+            draw_text(label, btn_rect.x + 5.0, *y + 13.0, 11.0, theme().text);
+            if hovered {
+                ctx.set_tooltip(tooltip, ctx.mouse.x, ctx.mouse.y);
+            }
+            if ctx.mouse.clicked(&btn_rect) {
+                *request = Some(req);
+            }
+        }
+        *y += line_height;
+
+        // Switch distance slider
+        draw_text("Distance:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
+        let slider_rect = Rect::new(slider_x, *y + 4.0, slider_w, 10.0);
+        draw_rectangle(slider_rect.x, slider_rect.y, slider_rect.w, slider_rect.h, Color::from_rgba(40, 40, 45, 255));
+
+        let fill_w = (distance.clamp(0.0, max_val) / max_val) * slider_w;
+        draw_rectangle(slider_rect.x, slider_rect.y, fill_w, slider_rect.h, theme().accent);
+
+        draw_text(&format!("{:.0}", distance), x + width - 35.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text);
+
+        if ctx.mouse.inside(&slider_rect) && ctx.mouse.left_down {
+            let t = ((ctx.mouse.x - slider_rect.x) / slider_w).clamp(0.0, 1.0);
+            // Snap to quarter sectors
+            *distance = (t * max_val / 256.0).round() * 256.0;
+            modified = true;
+        }
+        *y += line_height;
+    }
+
+    // Keep the impostor switch beyond the reduced switch
+    if *impostor_distance < *reduced_distance {
+        *impostor_distance = *reduced_distance;
+        modified = true;
+    }
+
+    modified
+}
+
 /// Draw spawn point component editor
 fn draw_spawn_point_editor(
     ctx: &mut UiContext,
@@ -2481,11 +3875,11 @@ fn draw_spawn_point_editor(
     let toggle_x = x + width - 40.0;
 
     // Is player toggle
-    draw_text("Player Start:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Player Start:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     let toggle_rect = Rect::new(toggle_x, *y + 2.0, 32.0, 14.0);
-    let toggle_color = if *is_player { ACCENT_COLOR } else { Color::from_rgba(60, 60, 65, 255) };
+    let toggle_color = if *is_player { theme().accent } else { Color::from_rgba(60, 60, 65, 255) };
     draw_rectangle(toggle_rect.x, toggle_rect.y, toggle_rect.w, toggle_rect.h, toggle_color);
-    draw_text(if *is_player { "ON" } else { "OFF" }, toggle_x + 6.0, *y + 13.0, 11.0, TEXT_COLOR);
+    draw_text(if *is_player { "ON" } else { "OFF" }, toggle_x + 6.0, *y + 13.0, 11.0, theme().text);
     if ctx.mouse.inside(&toggle_rect) && ctx.mouse.left_pressed {
         *is_player = !*is_player;
         modified = true;
@@ -2493,11 +3887,11 @@ fn draw_spawn_point_editor(
     *y += line_height;
 
     // Respawns toggle
-    draw_text("Respawns:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, TEXT_DIM);
+    draw_text("Respawns:", x + 4.0, *y + 14.0, FONT_SIZE_CONTENT, theme().text_dim);
     let respawn_rect = Rect::new(toggle_x, *y + 2.0, 32.0, 14.0);
-    let respawn_color = if *respawns { ACCENT_COLOR } else { Color::from_rgba(60, 60, 65, 255) };
+    let respawn_color = if *respawns { theme().accent } else { Color::from_rgba(60, 60, 65, 255) };
     draw_rectangle(respawn_rect.x, respawn_rect.y, respawn_rect.w, respawn_rect.h, respawn_color);
-    draw_text(if *respawns { "ON" } else { "OFF" }, toggle_x + 6.0, *y + 13.0, 11.0, TEXT_COLOR);
+    draw_text(if *respawns { "ON" } else { "OFF" }, toggle_x + 6.0, *y + 13.0, 11.0, theme().text);
     if ctx.mouse.inside(&respawn_rect) && ctx.mouse.left_pressed {
         *respawns = !*respawns;
         modified = true;
@@ -2636,8 +4030,8 @@ fn draw_collapsible_header(
     draw_rectangle(header_rect.x, header_rect.y, header_rect.w, header_rect.h, bg);
 
     // Text/indicator color - cyan when focused
-    let text_color = if focused { ACCENT_COLOR } else { WHITE };
-    let indicator_color = if focused { ACCENT_COLOR } else { Color::from_rgba(180, 180, 180, 255) };
+    let text_color = if focused { theme().accent } else { WHITE };
+    let indicator_color = if focused { theme().accent } else { Color::from_rgba(180, 180, 180, 255) };
 
     // Triangle indicator (matching World Editor style)
     let indicator_x = x + 6.0;
@@ -2694,6 +4088,8 @@ fn create_editing_texture(state: &ModelerState) -> UserTexture {
         indices: indexed.indices.clone(),
         palette: clut.colors.clone(),
         blend_mode: crate::rasterizer::BlendMode::Opaque,
+        animation: None,
+        layers: Vec::new(),
         source: crate::texture::TextureSource::User,
     }
 }
@@ -2760,7 +4156,7 @@ fn draw_paint_section(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState,
         draw_paint_texture_editor(ctx, rect, state, icon_font, storage);
     } else {
         // Show the texture browser with New/Edit buttons
-        draw_paint_texture_browser(ctx, rect, state, icon_font);
+        draw_paint_texture_browser(ctx, rect, state, icon_font, storage);
     }
 
     // Draw import dialog (modal overlay) if active
@@ -2794,6 +4190,13 @@ fn draw_paint_section(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState,
     }
 
     // Draw delete texture confirmation dialog (modal overlay) if active
+    if let Some(name) = &state.texture_pending_delete {
+        let dependency = Dependency::Texture(name.clone());
+        if state.texture_delete_usages.as_ref().map(|u| &u.dependency) != Some(&dependency) {
+            let graph = DependencyGraph::scan(storage, &state.user_textures);
+            state.texture_delete_usages = Some(UsagesPanel::find(&graph, dependency));
+        }
+    }
     if let Some(action) = draw_delete_texture_dialog(ctx, state, icon_font) {
         match action {
             DeleteTextureAction::Confirm => {
@@ -2817,6 +4220,7 @@ fn draw_paint_section(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState,
                 state.texture_pending_delete = None;
             }
         }
+        state.texture_delete_usages = None;
     }
 
     // Draw unsaved texture changes dialog (modal overlay) if active
@@ -2829,6 +4233,7 @@ fn draw_paint_section(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState,
                     // Sync editing_texture to user_textures library
                     if let Some(lib_tex) = state.user_textures.get_mut(&tex_name) {
                         lib_tex.indices = editing_tex.indices.clone();
+                        lib_tex.layers = editing_tex.layers.clone();
                         lib_tex.palette = editing_tex.palette.clone();
                         lib_tex.depth = editing_tex.depth;
                         lib_tex.width = editing_tex.width;
@@ -2878,9 +4283,11 @@ fn draw_delete_texture_dialog(
     // Dark overlay
     draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
 
-    // Dialog dimensions
+    // Dialog dimensions (taller when levels or assets still use the texture)
+    let usages = state.texture_delete_usages.as_ref().filter(|u| !u.users.is_empty());
+    let usages_h = usages.map(|u| (24.0 + u.users.len() as f32 * 16.0).min(120.0)).unwrap_or(0.0);
     let dialog_w = 300.0;
-    let dialog_h = 120.0;
+    let dialog_h = 120.0 + usages_h;
     let dialog_x = (screen_width() - dialog_w) / 2.0;
     let dialog_y = (screen_height() - dialog_h) / 2.0;
 
@@ -2897,6 +4304,9 @@ fn draw_delete_texture_dialog(
     let msg_dims = measure_text(&msg, None, 14, 1.0);
     draw_text(&msg, dialog_x + (dialog_w - msg_dims.width) / 2.0, dialog_y + 55.0, 14.0, WHITE);
     draw_text("This cannot be undone.", dialog_x + (dialog_w - measure_text("This cannot be undone.", None, 12, 1.0).width) / 2.0, dialog_y + 75.0, 12.0, Color::from_rgba(180, 150, 150, 255));
+    if let Some(usages) = usages {
+        draw_usages_panel(Rect::new(dialog_x + 10.0, dialog_y + 84.0, dialog_w - 20.0, usages_h - 4.0), usages);
+    }
 
     // Buttons
     let btn_w = 80.0;
@@ -3038,7 +4448,7 @@ fn draw_unsaved_texture_dialog(
 }
 
 /// Draw the texture browser header with Import/New/Edit/Delete and Zoom buttons (unified icon toolbar)
-fn draw_paint_header(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState, icon_font: Option<&Font>) {
+fn draw_paint_header(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState, icon_font: Option<&Font>, storage: &Storage) {
     use crate::ui::Toolbar;
 
     draw_rectangle(rect.x.floor(), rect.y.floor(), rect.w, rect.h, Color::from_rgba(40, 40, 45, 255));
@@ -3119,6 +4529,21 @@ fn draw_paint_header(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState,
         toolbar.icon_button_danger_disabled(ctx, icon::TRASH, icon_font, tooltip);
     }
 
+    // Atlas button - packs the asset's textures into one 256x256 texture
+    if toolbar.icon_button(ctx, icon::GRID, icon_font, "Pack Asset Textures into Atlas") {
+        match state.pack_textures_into_atlas() {
+            Ok(name) => {
+                if let Err(e) = state.user_textures.save_texture_with_storage(&name, storage) {
+                    state.set_status(&format!("Atlas built but not saved: {}", e), 3.0);
+                } else {
+                    state.selected_user_texture = Some(name.clone());
+                    state.set_status(&format!("Packed textures into '{}'", name), 2.0);
+                }
+            }
+            Err(e) => state.set_status(&e, 3.0),
+        }
+    }
+
     toolbar.separator();
 
     // Zoom buttons
@@ -3131,7 +4556,7 @@ fn draw_paint_header(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState,
 }
 
 /// Draw the texture browser grid with two sections: SAMPLES and MY TEXTURES (matches World Editor)
-fn draw_paint_texture_browser(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState, icon_font: Option<&Font>) {
+fn draw_paint_texture_browser(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState, icon_font: Option<&Font>, storage: &Storage) {
     const HEADER_HEIGHT: f32 = 28.0;
     const THUMB_PADDING: f32 = 4.0;
     const SECTION_HEADER_HEIGHT: f32 = 24.0;
@@ -3141,18 +4566,31 @@ fn draw_paint_texture_browser(ctx: &mut UiContext, rect: Rect, state: &mut Model
 
     // Header with New/Edit buttons
     let header_rect = Rect::new(rect.x, rect.y, rect.w, HEADER_HEIGHT);
-    draw_paint_header(ctx, header_rect, state, icon_font);
+    draw_paint_header(ctx, header_rect, state, icon_font, storage);
+
+    // Tag filter, then the selected texture's tags
+    let mut top = header_rect.bottom();
+    let filter_rect = Rect::new(rect.x, top, rect.w, TAG_FILTER_HEIGHT);
+    draw_tag_filter(ctx, filter_rect, &mut state.texture_tag_filter);
+    top += TAG_FILTER_HEIGHT;
+    if let Some(name) = state.selected_user_texture.clone() {
+        let tags_rect = Rect::new(rect.x, top, rect.w, TAG_ROW_HEIGHT);
+        draw_item_tags(ctx, tags_rect, &mut state.texture_tag_filter, TagKind::Texture, &name);
+        top += TAG_ROW_HEIGHT;
+    }
 
     // Content area for texture grid
-    let content_rect = Rect::new(rect.x, rect.y + HEADER_HEIGHT, rect.w, rect.h - HEADER_HEIGHT);
+    let content_rect = Rect::new(rect.x, top, rect.w, rect.bottom() - top);
 
     // Calculate columns
     let cols = ((content_rect.w - THUMB_PADDING) / (thumb_size + THUMB_PADDING)).floor() as usize;
     let cols = cols.max(1);
 
-    // Collect texture names for both sections
-    let sample_names: Vec<String> = state.user_textures.sample_names().map(|s| s.to_string()).collect();
-    let user_names: Vec<String> = state.user_textures.user_names().map(|s| s.to_string()).collect();
+    // Collect texture names for both sections (hiding those the tag filter rejects)
+    let filter = &state.texture_tag_filter;
+    let visible = |name: &&str| filter.matches(TagKind::Texture, name, name);
+    let sample_names: Vec<String> = state.user_textures.sample_names().filter(visible).map(|s| s.to_string()).collect();
+    let user_names: Vec<String> = state.user_textures.user_names().filter(visible).map(|s| s.to_string()).collect();
 
     // Calculate content heights for each section
     let sample_rows = if state.paint_samples_collapsed { 0 } else { (sample_names.len() + cols - 1) / cols.max(1) };
@@ -3665,6 +5103,36 @@ fn draw_paint_texture_editor(ctx: &mut UiContext, rect: Rect, state: &mut Modele
         auto_unwrap_selected_faces(state);
     }
 
+    // Wrap-shift from arrow keys / offset button (snapshot undo first)
+    if let Some(shift) = state.texture_editor.wrap_shift_pending.take() {
+        state.save_texture_undo();
+        if let Some(tex) = state.editing_texture.as_mut() {
+            apply_wrap_shift(tex, &mut state.texture_editor, shift);
+        }
+    }
+
+    // Palette file import/export requested from the palette panel
+    if let Some(request) = state.texture_editor.palette_file_request.take() {
+        if let Some(tex) = state.editing_texture.as_ref() {
+            match handle_palette_file_request(request, tex, &mut state.texture_editor.palette_import) {
+                Ok(Some(msg)) => state.set_status(&msg, 2.0),
+                Ok(None) => {}
+                Err(e) => state.set_status(&e, 3.0),
+            }
+        }
+    }
+
+    // Palette import dialog (modal overlay)
+    if let Some(ImportAction::Confirm) = draw_palette_import_dialog(ctx, &mut state.texture_editor.palette_import, icon_font) {
+        state.save_texture_undo();
+        if let Some(tex) = state.editing_texture.as_mut() {
+            let import = &state.texture_editor.palette_import;
+            apply_palette(tex, &import.preview, import.remap_pixels);
+        }
+        state.set_status(&format!("Imported palette '{}'", state.texture_editor.palette_import.file_name), 2.0);
+        state.texture_editor.palette_import.reset();
+    }
+
     // Sync editing_texture back to ALL objects that use this texture (not just selected)
     // This ensures texture changes are visible on all objects sharing the same texture
     if state.editing_indexed_atlas {
@@ -3707,6 +5175,7 @@ fn draw_paint_texture_editor(ctx: &mut UiContext, rect: Rect, state: &mut Modele
         if let Some(ref editing_tex) = state.editing_texture {
             if let Some(lib_tex) = state.user_textures.get_mut(&tex_name) {
                 lib_tex.indices = editing_tex.indices.clone();
+                lib_tex.layers = editing_tex.layers.clone();
                 lib_tex.palette = editing_tex.palette.clone();
                 lib_tex.depth = editing_tex.depth;
                 lib_tex.width = editing_tex.width;
@@ -4200,7 +5669,7 @@ fn draw_atlas_size_selector(ctx: &mut UiContext, x: f32, y: &mut f32, _width: f3
     let btn_h = 18.0;
     let btn_spacing = 2.0;
 
-    draw_text("Size:", x + 4.0, *y + 12.0, 12.0, TEXT_DIM);
+    draw_text("Size:", x + 4.0, *y + 12.0, 12.0, theme().text_dim);
 
     let atlas_width = state.atlas().width;
     let mut btn_x = x + 32.0;
@@ -4211,7 +5680,7 @@ fn draw_atlas_size_selector(ctx: &mut UiContext, x: f32, y: &mut f32, _width: f3
         let hovered = ctx.mouse.inside(&btn_rect);
 
         let bg = if is_current {
-            ACCENT_COLOR
+            theme().accent
         } else if hovered {
             Color::from_rgba(70, 70, 75, 255)
         } else {
@@ -4219,7 +5688,7 @@ fn draw_atlas_size_selector(ctx: &mut UiContext, x: f32, y: &mut f32, _width: f3
         };
         draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
 
-        let text_color = if is_current { WHITE } else { TEXT_DIM };
+        let text_color = if is_current { WHITE } else { theme().text_dim };
         draw_text(label, btn_x + 3.0, *y + 12.0, 12.0, text_color);
 
         if hovered && ctx.mouse.left_pressed && !is_current {
@@ -4263,7 +5732,7 @@ fn draw_face_properties(ctx: &mut UiContext, x: f32, y: &mut f32, _width: f32, s
         .all(|f| f.blend_mode == current_blend);
 
     // Blend mode label
-    draw_text("Blend:", x + 4.0, *y + 12.0, FONT_SIZE_HEADER, TEXT_DIM);
+    draw_text("Blend:", x + 4.0, *y + 12.0, FONT_SIZE_HEADER, theme().text_dim);
 
     // Blend mode buttons (inline row)
     let btn_modes = [
@@ -4295,7 +5764,7 @@ fn draw_face_properties(ctx: &mut UiContext, x: f32, y: &mut f32, _width: f32, s
         draw_rectangle_lines(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, 1.0, Color::from_rgba(80, 80, 90, 255));
 
         // Draw label
-        let text_color = if is_selected { WHITE } else { TEXT_COLOR };
+        let text_color = if is_selected { WHITE } else { theme().text };
         let text_x = btn_rect.x + (btn_rect.w - measure_text(label, None, 10, 1.0).width) / 2.0;
         draw_text(label, text_x, btn_rect.y + 13.0, 12.0, text_color);
 
@@ -4347,7 +5816,7 @@ fn draw_clut_editor_panel(
     // ========================================================================
     // Section 1: CLUT Pool List with buttons
     // ========================================================================
-    draw_text("CLUT Pool", x + padding, cur_y + 10.0, FONT_SIZE_HEADER, TEXT_DIM);
+    draw_text("CLUT Pool", x + padding, cur_y + 10.0, FONT_SIZE_HEADER, theme().text_dim);
     cur_y += LINE_HEIGHT;
 
     // Buttons to add new CLUTs
@@ -4363,7 +5832,7 @@ fn draw_clut_editor_panel(
         Color::from_rgba(50, 50, 55, 255)
     };
     draw_rectangle(btn_4bit_rect.x, btn_4bit_rect.y, btn_4bit_rect.w, btn_4bit_rect.h, bg_4bit);
-    draw_text("+ 4-bit", x + padding + 4.0, cur_y + 13.0, 12.0, TEXT_COLOR);
+    draw_text("+ 4-bit", x + padding + 4.0, cur_y + 13.0, 12.0, theme().text);
     if hovered_4bit {
         ctx.set_tooltip("Add 4-bit CLUT (16 colors)", ctx.mouse.x, ctx.mouse.y);
     }
@@ -4384,7 +5853,7 @@ fn draw_clut_editor_panel(
         Color::from_rgba(50, 50, 55, 255)
     };
     draw_rectangle(btn_8bit_rect.x, btn_8bit_rect.y, btn_8bit_rect.w, btn_8bit_rect.h, bg_8bit);
-    draw_text("+ 8-bit", btn_8bit_rect.x + 4.0, cur_y + 13.0, 12.0, TEXT_COLOR);
+    draw_text("+ 8-bit", btn_8bit_rect.x + 4.0, cur_y + 13.0, 12.0, theme().text);
     if hovered_8bit {
         ctx.set_tooltip("Add 8-bit CLUT (256 colors)", ctx.mouse.x, ctx.mouse.y);
     }
@@ -4408,7 +5877,7 @@ fn draw_clut_editor_panel(
     // Draw CLUT items
     let clut_count = state.clut_pool.len();
     if clut_count == 0 {
-        draw_text("(empty)", x + padding + 4.0, cur_y + 12.0, 12.0, TEXT_DIM);
+        draw_text("(empty)", x + padding + 4.0, cur_y + 12.0, 12.0, theme().text_dim);
     } else {
         let mut item_y = cur_y + 2.0;
         for clut in state.clut_pool.iter() {
@@ -4422,7 +5891,7 @@ fn draw_clut_editor_panel(
 
             // Background
             let bg = if is_selected {
-                ACCENT_COLOR
+                theme().accent
             } else if hovered {
                 Color::from_rgba(50, 50, 55, 255)
             } else {
@@ -4431,14 +5900,14 @@ fn draw_clut_editor_panel(
             draw_rectangle(item_rect.x, item_rect.y, item_rect.w, item_rect.h, bg);
 
             // Name + depth badge
-            let text_color = if is_selected { WHITE } else { TEXT_COLOR };
+            let text_color = if is_selected { WHITE } else { theme().text };
             draw_text(&clut.name, item_rect.x + 2.0, item_y + 11.0, 12.0, text_color);
 
             // Depth badge
             let badge_text = clut.depth.short_label();
             let badge_x = item_rect.x + item_rect.w - 24.0;
             draw_rectangle(badge_x, item_y + 2.0, 20.0, 12.0, Color::from_rgba(60, 60, 70, 255));
-            draw_text(badge_text, badge_x + 2.0, item_y + 11.0, FONT_SIZE_CONTENT, TEXT_DIM);
+            draw_text(badge_text, badge_x + 2.0, item_y + 11.0, FONT_SIZE_CONTENT, theme().text_dim);
 
             // Handle click
             if hovered && ctx.mouse.left_pressed {
@@ -4541,19 +6010,19 @@ fn draw_clut_editor_panel(
                     x + padding,
                     cur_y + 10.0,
                     10.0,
-                    TEXT_DIM,
+                    theme().text_dim,
                 );
 
                 // Semi-transparent toggle
                 let semi_x = x + padding + 60.0;
                 let semi_rect = Rect::new(semi_x, cur_y, 14.0, 14.0);
                 let is_semi = color.is_semi_transparent();
-                let semi_bg = if is_semi { ACCENT_COLOR } else { Color::from_rgba(50, 50, 55, 255) };
+                let semi_bg = if is_semi { theme().accent } else { Color::from_rgba(50, 50, 55, 255) };
                 draw_rectangle(semi_rect.x, semi_rect.y, semi_rect.w, semi_rect.h, semi_bg);
                 if is_semi {
                     draw_text("✓", semi_x + 2.0, cur_y + 11.0, 12.0, WHITE);
                 }
-                draw_text("Semi-trans", semi_x + 18.0, cur_y + 10.0, 12.0, TEXT_COLOR);
+                draw_text("Semi-trans", semi_x + 18.0, cur_y + 10.0, 12.0, theme().text);
 
                 if ctx.mouse.inside(&semi_rect) && ctx.mouse.left_pressed {
                     // Toggle semi-transparent bit
@@ -4595,7 +6064,7 @@ fn draw_clut_editor_panel(
                     draw_rectangle(handle_x.max(track_rect.x), track_rect.y, 4.0, slider_h, WHITE);
 
                     // Value
-                    draw_text(&format!("{}", value), track_rect.x + track_rect.w + 4.0, cur_y + 8.0, 12.0, TEXT_DIM);
+                    draw_text(&format!("{}", value), track_rect.x + track_rect.w + 4.0, cur_y + 8.0, 12.0, theme().text_dim);
 
                     // Handle slider interaction
                     let hovered = ctx.mouse.inside(&track_rect);
@@ -4630,7 +6099,7 @@ fn draw_clut_editor_panel(
         }
     } else {
         // No CLUT selected - show hint
-        draw_text("Select or create a CLUT", x + padding, cur_y + 10.0, 12.0, TEXT_DIM);
+        draw_text("Select or create a CLUT", x + padding, cur_y + 10.0, 12.0, theme().text_dim);
     }
 }
 
@@ -4749,7 +6218,7 @@ fn draw_single_viewport(ctx: &mut UiContext, rect: Rect, state: &mut ModelerStat
 
     // Header text - cyan when active, white when inactive (font size 16 to match World Editor)
     let label = viewport_id.label();
-    let text_color = if is_active { ACCENT_COLOR } else { WHITE };
+    let text_color = if is_active { theme().accent } else { WHITE };
     draw_text(label, header_rect.x + 6.0, header_rect.y + 14.0, 16.0, text_color);
 
     // X-RAY label in header when enabled
@@ -5474,14 +6943,8 @@ fn draw_ortho_viewport(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState
                 // Only become ortho move if moved at least 3 pixels
                 if dx > 3.0 || dy > 3.0 {
                     // Collect starting positions
-                    let mesh = state.mesh();
-                    let mut indices = state.selection.get_affected_vertex_indices(mesh);
-                    if state.vertex_linking {
-                        indices = mesh.expand_to_coincident(&indices, 0.001);
-                    }
-                    let initial_positions: Vec<(usize, crate::rasterizer::Vec3)> = indices.iter()
-                        .filter_map(|&idx| mesh.vertices.get(idx).map(|v| (idx, v.pos)))
-                        .collect();
+                    let targets = state.transform_targets();
+                    let (indices, initial_positions) = (targets.indices, targets.initial_positions);
 
                     if !initial_positions.is_empty() {
                         // Calculate center
@@ -5503,6 +6966,7 @@ fn draw_ortho_viewport(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState
 
                         // Get bone rotation for world-to-local delta transformation (bone-bound meshes)
                         let bone_rotation = state.selected_object()
+                            .filter(|_| !state.selection.is_group())
                             .and_then(|obj| obj.default_bone_index)
                             .map(|bone_idx| state.get_bone_world_transform(bone_idx).1);
 
@@ -5584,23 +7048,8 @@ fn draw_ortho_viewport(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState
                     let updates: Vec<_> = tracker.initial_positions.iter()
                         .map(|(idx, start_pos)| (*idx, *start_pos + delta))
                         .collect();
-                    // Capture snap settings before borrowing mesh
-                    let snap_enabled = state.snap_settings.enabled && !is_key_down(KeyCode::Z);
-                    let snap_size = state.snap_settings.grid_size;
-                    if let Some(mesh) = state.mesh_mut() {
-                        for (idx, new_pos) in updates {
-                            if let Some(vert) = mesh.vertices.get_mut(idx) {
-                                vert.pos = new_pos;
-
-                                // Apply grid snapping if enabled
-                                if snap_enabled {
-                                    vert.pos.x = (vert.pos.x / snap_size).round() * snap_size;
-                                    vert.pos.y = (vert.pos.y / snap_size).round() * snap_size;
-                                    vert.pos.z = (vert.pos.z / snap_size).round() * snap_size;
-                                }
-                            }
-                        }
-                    }
+                    // Apply grid snapping if enabled
+                    state.write_drag_positions(updates, !is_key_down(KeyCode::Z), false);
                     state.dirty = true;
                 }
             }
@@ -5974,7 +7423,7 @@ fn draw_atlas_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState) {
         rect.x + 4.0,
         atlas_y + atlas_screen_h + 14.0,
         11.0,
-        TEXT_DIM,
+        theme().text_dim,
     );
 
     // Brush size indicator
@@ -5983,7 +7432,7 @@ fn draw_atlas_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerState) {
         rect.x + rect.w - 60.0,
         atlas_y + atlas_screen_h + 14.0,
         11.0,
-        TEXT_DIM,
+        theme().text_dim,
     );
 }
 
@@ -5991,37 +7440,40 @@ fn draw_properties_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerSta
     let mut y = rect.y;
     let line_height = 18.0;
 
-    draw_text("Selection:", rect.x, y + 14.0, 12.0, TEXT_DIM);
+    draw_text("Selection:", rect.x, y + 14.0, 12.0, theme().text_dim);
     y += line_height;
 
     match &state.selection {
         super::state::ModelerSelection::None => {
-            draw_text("Nothing selected", rect.x, y + 14.0, 12.0, TEXT_COLOR);
+            draw_text("Nothing selected", rect.x, y + 14.0, 12.0, theme().text);
         }
         super::state::ModelerSelection::Mesh => {
-            draw_text("Mesh (whole)", rect.x, y + 14.0, 12.0, TEXT_COLOR);
+            draw_text("Mesh (whole)", rect.x, y + 14.0, 12.0, theme().text);
         }
         super::state::ModelerSelection::Vertices(verts) => {
-            draw_text(&format!("{} vertex(es)", verts.len()), rect.x, y + 14.0, 12.0, TEXT_COLOR);
+            draw_text(&format!("{} vertex(es)", verts.len()), rect.x, y + 14.0, 12.0, theme().text);
         }
         super::state::ModelerSelection::Edges(edges) => {
-            draw_text(&format!("{} edge(s)", edges.len()), rect.x, y + 14.0, 12.0, TEXT_COLOR);
+            draw_text(&format!("{} edge(s)", edges.len()), rect.x, y + 14.0, 12.0, theme().text);
         }
         super::state::ModelerSelection::Faces(faces) => {
-            draw_text(&format!("{} face(s)", faces.len()), rect.x, y + 14.0, 12.0, TEXT_COLOR);
+            draw_text(&format!("{} face(s)", faces.len()), rect.x, y + 14.0, 12.0, theme().text);
         }
         super::state::ModelerSelection::Bones(bones) => {
-            draw_text(&format!("{} bone(s)", bones.len()), rect.x, y + 14.0, 12.0, TEXT_COLOR);
+            draw_text(&format!("{} bone(s)", bones.len()), rect.x, y + 14.0, 12.0, theme().text);
         }
         super::state::ModelerSelection::BoneTips(tips) => {
-            draw_text(&format!("{} bone tip(s)", tips.len()), rect.x, y + 14.0, 12.0, TEXT_COLOR);
+            draw_text(&format!("{} bone tip(s)", tips.len()), rect.x, y + 14.0, 12.0, theme().text);
+        }
+        super::state::ModelerSelection::Group(members) => {
+            draw_text(&format!("Group: {} part(s)", members.len()), rect.x, y + 14.0, 12.0, theme().text);
         }
     }
 
     y += line_height * 2.0;
 
     // Tool info (using new tool system)
-    draw_text("Tool:", rect.x, y + 14.0, 12.0, TEXT_DIM);
+    draw_text("Tool:", rect.x, y + 14.0, 12.0, theme().text_dim);
     y += line_height;
     let tool_label = match state.tool_box.active_transform_tool() {
         Some(ModelerToolId::Move) => "Move (G)",
@@ -6029,12 +7481,12 @@ fn draw_properties_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerSta
         Some(ModelerToolId::Scale) => "Scale (T)",
         _ => "Select",
     };
-    draw_text(tool_label, rect.x, y + 14.0, 12.0, TEXT_COLOR);
+    draw_text(tool_label, rect.x, y + 14.0, 12.0, theme().text);
 
     y += line_height * 2.0;
 
     // Keyboard shortcuts help
-    draw_text("Shortcuts:", rect.x, y + 14.0, 12.0, TEXT_DIM);
+    draw_text("Shortcuts:", rect.x, y + 14.0, 12.0, theme().text_dim);
     y += line_height;
 
     let shortcuts = [
@@ -6046,6 +7498,8 @@ fn draw_properties_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerSta
         ("S", "Scale"),
         ("X/Del", "Delete"),
         ("1/2/3", "Vert/Edge/Face"),
+        ("Alt+Drag", "Box select all parts"),
+        (".", "Toggle pivot"),
         ("V", "Toggle Build/UV"),
         ("Space", "Fullscreen"),
     ];
@@ -6054,14 +7508,14 @@ fn draw_properties_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerSta
         if y + line_height > rect.bottom() {
             break;
         }
-        draw_text(&format!("{}: {}", key, desc), rect.x, y + 12.0, 12.0, TEXT_DIM);
+        draw_text(&format!("{}: {}", key, desc), rect.x, y + 12.0, 12.0, theme().text_dim);
         y += line_height * 0.8;
     }
     y += line_height;
 
     // Ambient light slider (Light components add point lights on top)
     if y + line_height * 2.0 < rect.bottom() {
-        draw_text("Ambient:", rect.x, y + 14.0, 12.0, TEXT_DIM);
+        draw_text("Ambient:", rect.x, y + 14.0, 12.0, theme().text_dim);
         y += line_height;
 
         let slider_height = 12.0;
@@ -6109,8 +7563,8 @@ fn draw_properties_panel(ctx: &mut UiContext, rect: Rect, state: &mut ModelerSta
 
 fn draw_timeline(_ctx: &mut UiContext, rect: Rect, _state: &mut ModelerState, _icon_font: Option<&Font>) {
     // Timeline disabled in mesh-only mode
-    draw_rectangle(rect.x, rect.y, rect.w, rect.h, HEADER_COLOR);
-    draw_text("Timeline (disabled)", rect.x + 10.0, rect.y + 20.0, 14.0, TEXT_DIM);
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().header);
+    draw_text("Timeline (disabled)", rect.x + 10.0, rect.y + 20.0, 14.0, theme().text_dim);
 }
 
 fn draw_status_bar(rect: Rect, state: &ModelerState) {
@@ -6448,7 +7902,7 @@ fn handle_actions(actions: &ActionRegistry, state: &mut ModelerState, ui_ctx: &c
         has_face_selection,
         has_vertex_selection,
         select_mode_str,
-        false, // text_editing - would need to track this
+        state.texture_tag_filter.is_typing(), // text_editing
         state.dirty,
         is_dragging,
         is_paint_mode,
@@ -6474,6 +7928,9 @@ fn handle_actions(actions: &ActionRegistry, state: &mut ModelerState, ui_ctx: &c
     if actions.triggered("file.save_as", &ctx) {
         action = ModelerAction::SaveAs;
     }
+    if actions.triggered("file.checkpoint", &ctx) {
+        state.open_checkpoint_dialog();
+    }
 
     // ========================================================================
     // Edit Actions
@@ -6555,6 +8012,8 @@ fn handle_actions(actions: &ActionRegistry, state: &mut ModelerState, ui_ctx: &c
                         }
                     }
                 }
+                // Put the mirror side back too, before a new drag starts
+                state.sync_symmetry();
             }
             // Pop undo since we're canceling (the push happened when drag started)
             state.undo_stack.pop();
@@ -6591,8 +8050,15 @@ fn handle_actions(actions: &ActionRegistry, state: &mut ModelerState, ui_ctx: &c
                 state.push_undo("Extrude");
                 // Use 2x grid size for clearly visible extrusion
                 let extrude_amount = state.snap_settings.grid_size * 2.0;
+                let symmetry = state.symmetry;
                 let new_faces = if let Some(mesh) = state.mesh_mut() {
-                    mesh.extrude_faces(&indices, extrude_amount)
+                    // Extrude the mirror faces separately so each side keeps its own normal
+                    let mirrored = symmetry
+                        .map(|axis| mesh.mirror_faces(&indices, axis, super::state::SYMMETRY_EPSILON))
+                        .unwrap_or_default();
+                    let new_faces = mesh.extrude_faces(&indices, extrude_amount);
+                    mesh.extrude_faces(&mirrored, extrude_amount);
+                    new_faces
                 } else {
                     vec![]
                 };
@@ -6610,6 +8076,10 @@ fn handle_actions(actions: &ActionRegistry, state: &mut ModelerState, ui_ctx: &c
         state.transform_orientation = state.transform_orientation.toggle();
         state.set_status(&format!("Transform orientation: {}", state.transform_orientation.label()), 1.5);
     }
+    if actions.triggered("transform.toggle_pivot", &ctx) {
+        state.pivot_mode = state.pivot_mode.toggle();
+        state.set_status(&format!("Pivot: {}", state.pivot_mode.label()), 1.5);
+    }
 
     // ========================================================================
     // Mesh Cleanup Actions
@@ -6630,6 +8100,18 @@ fn handle_actions(actions: &ActionRegistry, state: &mut ModelerState, ui_ctx: &c
         }
     }
 
+    if actions.triggered("mesh.mirror_weld", &ctx) {
+        let axis = state.symmetry.unwrap_or(super::state::Axis::X);
+        let threshold = state.snap_settings.grid_size * 0.1; // 10% of grid size
+        state.push_undo("Mirror and Weld");
+        let result = state.mesh_mut().map(|mesh| (mesh.mirror_and_weld(axis, threshold), mesh.faces.len()));
+        if let Some((welded, faces)) = result {
+            state.selection = super::state::ModelerSelection::None;
+            state.dirty = true;
+            state.set_status(&format!("Mirrored across {} ({} faces, welded {} seam vertices)", axis.label(), faces, welded), 2.0);
+        }
+    }
+
     if actions.triggered("mesh.merge_to_center", &ctx) {
         if let super::state::ModelerSelection::Vertices(vert_indices) = &state.selection {
             if vert_indices.len() >= 2 {
@@ -7103,7 +8585,8 @@ fn select_loop(state: &mut ModelerState) {
             }
         }
 
-        super::state::ModelerSelection::None | super::state::ModelerSelection::Mesh | super::state::ModelerSelection::Bones(_) | super::state::ModelerSelection::BoneTips(_) => {
+        super::state::ModelerSelection::None | super::state::ModelerSelection::Mesh | super::state::ModelerSelection::Bones(_) | super::state::ModelerSelection::BoneTips(_)
+        | super::state::ModelerSelection::Group(_) => {
             state.set_status("No selection for loop select", 1.0);
         }
     }
@@ -7124,6 +8607,9 @@ fn delete_selection(state: &mut ModelerState) {
 
             // Sort indices in reverse order so we can remove without index shifting issues
             let mut indices = face_indices.clone();
+            if let Some(axis) = state.symmetry {
+                indices.extend(state.mesh().mirror_faces(&face_indices, axis, super::state::SYMMETRY_EPSILON));
+            }
             indices.sort();
             indices.reverse();
 
@@ -7321,6 +8807,9 @@ fn copy_selection(state: &mut ModelerState) {
             state.set_status("Copied entire mesh", 1.0);
         }
     }
+    if state.clipboard.has_content() {
+        crate::clipboard_history::record(crate::clipboard_history::ClipData::Mesh(state.clipboard.clone()));
+    }
 }
 
 /// Paste clipboard contents as a new object
@@ -7499,15 +8988,29 @@ fn draw_add_component_popup(ctx: &mut UiContext, _left_rect: Rect, state: &mut M
         ("Trigger", icon::MAP_PIN),
         ("Pickup", icon::PLUS),
         ("Enemy", icon::PERSON_STANDING),
+        ("Weapon", icon::SWORD),
+        ("Armor", icon::SHIELD),
         ("Door", icon::DOOR_CLOSED),
+        ("MovingPlatform", icon::ARROW_DOWN_UP),
+        ("Dialogue", icon::BOOK_OPEN),
         ("Audio", icon::MUSIC),
         ("Particle", icon::BLEND),
+        ("Sprite", icon::IMAGE),
+        ("StatusEffect", icon::DROPLET),
+        ("Hazard", icon::CIRCLE_X),
+        ("Destructible", icon::BRICK_WALL),
         ("CharacterController", icon::GAMEPAD_2),
         ("SpawnPoint", icon::FOOTPRINTS),
+        ("Lod", icon::LAYERS),
     ];
 
     let item_height = 20.0;
     let menu_rect = dropdown_menu_rect(trigger_rect, component_types.len(), item_height, Some(140.0));
+    // Types an asset holds only once (already present: selecting goes to that one)
+    let existing: Vec<&'static str> = state.asset.components.iter()
+        .filter(|c| !c.allows_multiple())
+        .map(|c| c.type_name())
+        .collect();
 
     if !begin_dropdown(ctx, &mut state.dropdown, "add_component", menu_rect) {
         return;
@@ -7517,12 +9020,19 @@ fn draw_add_component_popup(ctx: &mut UiContext, _left_rect: Rect, state: &mut M
     for (type_name, icon_char) in component_types {
         let item_rect = Rect::new(menu_rect.x + 2.0, item_y, menu_rect.w - 4.0, item_height);
 
-        if dropdown_item(ctx, item_rect, type_name, Some((icon_char, icon_font)), false) {
+        let present = existing.contains(&type_name);
+        if dropdown_item(ctx, item_rect, type_name, Some((icon_char, icon_font)), present) {
+            state.dropdown.close();
+            if present {
+                state.selected_component = state.asset.components.iter().position(|c| c.type_name() == type_name);
+                state.set_status(&format!("Asset already has a {} component", type_name), 1.5);
+                return;
+            }
             let new_component = create_default_component(type_name);
             let is_skeleton = new_component.is_skeleton();
             state.asset.components.push(new_component);
             state.selected_component = Some(state.asset.components.len() - 1);
-            state.dropdown.close();
+            state.dirty = true;
 
             // For Skeleton, also select the default Root bone
             if is_skeleton {
@@ -7724,7 +9234,7 @@ fn draw_vertex_ops_context_menu(ctx: &mut UiContext, state: &mut ModelerState, m
         }
         _ => 0,
     };
-    draw_text(&format!("{} vertices selected", vert_count), menu_rect.x + 8.0, y + 14.0, 12.0, TEXT_DIM);
+    draw_text(&format!("{} vertices selected", vert_count), menu_rect.x + 8.0, y + 14.0, 12.0, theme().text_dim);
     y += item_height;
 
     // Track actions
@@ -7734,7 +9244,7 @@ fn draw_vertex_ops_context_menu(ctx: &mut UiContext, state: &mut ModelerState, m
 
     if has_bones {
         // "Assign to Bone" section header
-        draw_text("Assign to Bone:", menu_rect.x + 8.0, y + 14.0, 12.0, ACCENT_COLOR);
+        draw_text("Assign to Bone:", menu_rect.x + 8.0, y + 14.0, 12.0, theme().accent);
         y += item_height;
 
         // List all bones
@@ -7757,17 +9267,17 @@ fn draw_vertex_ops_context_menu(ctx: &mut UiContext, state: &mut ModelerState, m
             let icon_color = if bone.parent.is_none() {
                 Color::from_rgba(255, 220, 100, 255) // Yellow for root
             } else {
-                TEXT_COLOR
+                theme().text
             };
             draw_text("◆", item_rect.x + 8.0, item_rect.y + 15.0, 10.0, icon_color);
-            draw_text(&bone.name, item_rect.x + 22.0, item_rect.y + 16.0, 14.0, TEXT_COLOR);
+            draw_text(&bone.name, item_rect.x + 22.0, item_rect.y + 16.0, 14.0, theme().text);
 
             y += item_height;
         }
     } else {
         // No bones available
         let item_rect = Rect::new(menu_rect.x + 2.0, y, menu_width - 4.0, item_height);
-        draw_text("No bones (add skeleton)", item_rect.x + 8.0, item_rect.y + 16.0, 12.0, TEXT_DIM);
+        draw_text("No bones (add skeleton)", item_rect.x + 8.0, item_rect.y + 16.0, 12.0, theme().text_dim);
         y += item_height;
     }
 
@@ -7784,7 +9294,7 @@ fn draw_vertex_ops_context_menu(ctx: &mut UiContext, state: &mut ModelerState, m
             unbind_clicked = true;
         }
     }
-    draw_text("Unbind from Bone", unbind_rect.x + 8.0, unbind_rect.y + 16.0, 14.0, TEXT_COLOR);
+    draw_text("Unbind from Bone", unbind_rect.x + 8.0, unbind_rect.y + 16.0, 14.0, theme().text);
 
     // Update hovered bone for viewport highlighting
     if let Some(cm) = &mut state.context_menu {
@@ -7836,7 +9346,7 @@ fn draw_primitives_context_menu(ctx: &mut UiContext, state: &mut ModelerState, m
     let mut y = menu_rect.y + 4.0;
 
     // Header
-    draw_text("Add Primitive", menu_rect.x + 8.0, y + 14.0, 12.0, TEXT_DIM);
+    draw_text("Add Primitive", menu_rect.x + 8.0, y + 14.0, 12.0, theme().text_dim);
     y += item_height;
 
     // Primitive items
@@ -7853,7 +9363,7 @@ fn draw_primitives_context_menu(ctx: &mut UiContext, state: &mut ModelerState, m
             }
         }
 
-        draw_text(prim.label(), item_rect.x + 8.0, item_rect.y + 16.0, 14.0, TEXT_COLOR);
+        draw_text(prim.label(), item_rect.x + 8.0, item_rect.y + 16.0, 14.0, theme().text);
         y += item_height;
     }
 
@@ -7871,7 +9381,7 @@ fn draw_primitives_context_menu(ctx: &mut UiContext, state: &mut ModelerState, m
             clone_clicked = true;
         }
     }
-    draw_text("Clone Mesh", clone_rect.x + 8.0, clone_rect.y + 16.0, 14.0, TEXT_COLOR);
+    draw_text("Clone Mesh", clone_rect.x + 8.0, clone_rect.y + 16.0, 14.0, theme().text);
     y += item_height;
 
     // Clear mesh option
@@ -7950,6 +9460,93 @@ fn draw_primitives_context_menu(ctx: &mut UiContext, state: &mut ModelerState, m
     }
 }
 
+/// Draw the sprite sheet export dialog. Returns the settings when confirmed.
+fn draw_sprite_sheet_dialog(ctx: &mut UiContext, state: &mut ModelerState) -> Option<SpriteSheetSettings> {
+    let settings = state.sprite_sheet_dialog.as_mut()?;
+
+    let dialog_w = 300.0;
+    let dialog_h = 220.0;
+    let dialog_x = ((screen_width() - dialog_w) / 2.0).floor();
+    let dialog_y = ((screen_height() - dialog_h) / 2.0).floor();
+
+    draw_rectangle(dialog_x, dialog_y, dialog_w, dialog_h, Color::from_rgba(45, 45, 50, 255));
+    draw_rectangle_lines(dialog_x, dialog_y, dialog_w, dialog_h, 2.0, Color::from_rgba(80, 80, 90, 255));
+    draw_text("Export Sprite Sheet", dialog_x + 12.0, dialog_y + 22.0, 16.0, WHITE);
+
+    // One row per setting: label, value, [-] [+]
+    let row_h = 24.0;
+    let btn = 20.0;
+    let mut y = dialog_y + 38.0;
+    let mut stepper = |ctx: &mut UiContext, label: &str, value: String| -> i32 {
+        draw_text(label, dialog_x + 12.0, y + 15.0, 14.0, theme().text);
+        draw_text(&value, dialog_x + 140.0, y + 15.0, 14.0, WHITE);
+        let minus = Rect::new(dialog_x + dialog_w - btn * 2.0 - 18.0, y, btn, btn);
+        let plus = Rect::new(dialog_x + dialog_w - btn - 12.0, y, btn, btn);
+        let mut delta = 0;
+        if crate::ui::text_button(ctx, minus, "-", "") {
+            delta = -1;
+        }
+        if crate::ui::text_button(ctx, plus, "+", "") {
+            delta = 1;
+        }
+        y += row_h;
+        delta
+    };
+
+    let delta = stepper(ctx, "Angles", settings.angles.to_string());
+    settings.angles = (settings.angles as i32 + delta).max(1) as usize;
+
+    let delta = stepper(ctx, "Frames", settings.frames.to_string());
+    settings.frames = (settings.frames as i32 + delta).max(1) as usize;
+
+    let delta = stepper(ctx, "Cell size", format!("{}px", settings.cell_size));
+    let size_idx = SPRITE_CELL_SIZES.iter().position(|&s| s == settings.cell_size).unwrap_or(2) as i32;
+    settings.cell_size = SPRITE_CELL_SIZES[(size_idx + delta).clamp(0, SPRITE_CELL_SIZES.len() as i32 - 1) as usize];
+
+    let delta = stepper(ctx, "Elevation", format!("{:.0} deg", settings.elevation));
+    settings.elevation += delta as f32 * 5.0;
+
+    let palette = match settings.depth {
+        ClutDepth::Bpp4 => "16 colors",
+        ClutDepth::Bpp8 => "256 colors",
+    };
+    if stepper(ctx, "Palette", palette.to_string()) != 0 {
+        settings.depth = match settings.depth {
+            ClutDepth::Bpp4 => ClutDepth::Bpp8,
+            ClutDepth::Bpp8 => ClutDepth::Bpp4,
+        };
+    }
+    settings.sanitize();
+
+    draw_text("Frames sample the animation (1 column if none)", dialog_x + 12.0, y + 12.0, 12.0, theme().text_dim);
+
+    let btn_w = 80.0;
+    let btn_h = 28.0;
+    let btn_y = dialog_y + dialog_h - btn_h - 12.0;
+
+    let cancel_rect = Rect::new(dialog_x + dialog_w - btn_w * 2.0 - 20.0, btn_y, btn_w, btn_h);
+    let cancel_hover = ctx.mouse.inside(&cancel_rect);
+    draw_rectangle(cancel_rect.x, cancel_rect.y, cancel_rect.w, cancel_rect.h,
+        if cancel_hover { Color::from_rgba(70, 70, 75, 255) } else { Color::from_rgba(55, 55, 60, 255) });
+    draw_text("Cancel", cancel_rect.x + 18.0, cancel_rect.y + 18.0, 14.0, theme().text);
+
+    let confirm_rect = Rect::new(dialog_x + dialog_w - btn_w - 12.0, btn_y, btn_w, btn_h);
+    let confirm_hover = ctx.mouse.inside(&confirm_rect);
+    draw_rectangle(confirm_rect.x, confirm_rect.y, confirm_rect.w, confirm_rect.h,
+        if confirm_hover { Color::from_rgba(60, 100, 140, 255) } else { theme().accent });
+    draw_text("Export", confirm_rect.x + 18.0, confirm_rect.y + 18.0, 14.0, WHITE);
+
+    if ctx.mouse.clicked(&cancel_rect) || is_key_pressed(KeyCode::Escape) {
+        state.sprite_sheet_dialog = None;
+    } else if ctx.mouse.clicked(&confirm_rect) || is_key_pressed(KeyCode::Enter) {
+        if let Some(settings) = state.sprite_sheet_dialog.take() {
+            state.sprite_sheet_settings = settings.clone();
+            return Some(settings);
+        }
+    }
+    None
+}
+
 /// Draw rename and delete dialogs for objects
 fn draw_object_dialogs(ctx: &mut UiContext, state: &mut ModelerState, icon_font: Option<&Font>) {
     // Handle rename dialog
@@ -7982,13 +9579,13 @@ fn draw_object_dialogs(ctx: &mut UiContext, state: &mut ModelerState, icon_font:
         let cancel_hover = ctx.mouse.inside(&cancel_rect);
         draw_rectangle(cancel_rect.x, cancel_rect.y, cancel_rect.w, cancel_rect.h,
             if cancel_hover { Color::from_rgba(70, 70, 75, 255) } else { Color::from_rgba(55, 55, 60, 255) });
-        draw_text("Cancel", cancel_rect.x + 18.0, cancel_rect.y + 18.0, 14.0, TEXT_COLOR);
+        draw_text("Cancel", cancel_rect.x + 18.0, cancel_rect.y + 18.0, 14.0, theme().text);
 
         // Confirm button
         let confirm_rect = Rect::new(dialog_x + dialog_w - btn_w - 12.0, btn_y, btn_w, btn_h);
         let confirm_hover = ctx.mouse.inside(&confirm_rect);
         draw_rectangle(confirm_rect.x, confirm_rect.y, confirm_rect.w, confirm_rect.h,
-            if confirm_hover { Color::from_rgba(60, 100, 140, 255) } else { ACCENT_COLOR });
+            if confirm_hover { Color::from_rgba(60, 100, 140, 255) } else { theme().accent });
         draw_text("Rename", confirm_rect.x + 14.0, confirm_rect.y + 18.0, 14.0, WHITE);
 
         // Handle button clicks
@@ -8009,6 +9606,48 @@ fn draw_object_dialogs(ctx: &mut UiContext, state: &mut ModelerState, icon_font:
         }
     }
 
+    // Handle checkpoint name dialog
+    if state.checkpoint_dialog.is_some() {
+        let dialog_w = 300.0;
+        let dialog_h = 120.0;
+        let dialog_x = (screen_width() - dialog_w) / 2.0;
+        let dialog_y = (screen_height() - dialog_h) / 2.0;
+
+        draw_rectangle(dialog_x, dialog_y, dialog_w, dialog_h, Color::from_rgba(45, 45, 50, 255));
+        draw_rectangle_lines(dialog_x, dialog_y, dialog_w, dialog_h, 2.0, Color::from_rgba(80, 80, 90, 255));
+        draw_text("Checkpoint Name", dialog_x + 12.0, dialog_y + 22.0, 16.0, WHITE);
+
+        let input_rect = Rect::new(dialog_x + 12.0, dialog_y + 40.0, dialog_w - 24.0, 28.0);
+        if let Some(ref mut input_state) = state.checkpoint_dialog {
+            draw_text_input(input_rect, input_state, 14.0);
+        }
+
+        let btn_w = 80.0;
+        let btn_h = 28.0;
+        let btn_y = dialog_y + dialog_h - btn_h - 12.0;
+
+        let cancel_rect = Rect::new(dialog_x + dialog_w - btn_w * 2.0 - 20.0, btn_y, btn_w, btn_h);
+        let cancel_hover = ctx.mouse.inside(&cancel_rect);
+        draw_rectangle(cancel_rect.x, cancel_rect.y, cancel_rect.w, cancel_rect.h,
+            if cancel_hover { Color::from_rgba(70, 70, 75, 255) } else { Color::from_rgba(55, 55, 60, 255) });
+        draw_text("Cancel", cancel_rect.x + 18.0, cancel_rect.y + 18.0, 14.0, theme().text);
+
+        let confirm_rect = Rect::new(dialog_x + dialog_w - btn_w - 12.0, btn_y, btn_w, btn_h);
+        let confirm_hover = ctx.mouse.inside(&confirm_rect);
+        draw_rectangle(confirm_rect.x, confirm_rect.y, confirm_rect.w, confirm_rect.h,
+            if confirm_hover { Color::from_rgba(60, 100, 140, 255) } else { theme().accent });
+        draw_text("Save", confirm_rect.x + 24.0, confirm_rect.y + 18.0, 14.0, WHITE);
+
+        if ctx.mouse.clicked(&cancel_rect) || is_key_pressed(KeyCode::Escape) {
+            state.checkpoint_dialog = None;
+        } else if ctx.mouse.clicked(&confirm_rect) || is_key_pressed(KeyCode::Enter) {
+            // Written by the main loop, which owns storage
+            if let Some(input_state) = state.checkpoint_dialog.take() {
+                state.pending_checkpoint = Some(input_state.text.trim().to_string());
+            }
+        }
+    }
+
     // Handle delete confirmation dialog
     if let Some(idx) = state.delete_dialog {
         let obj_name = state.objects().get(idx)
@@ -8031,8 +9670,8 @@ fn draw_object_dialogs(ctx: &mut UiContext, state: &mut ModelerState, icon_font:
         draw_text("Delete Object?", dialog_x + 36.0, dialog_y + 26.0, 16.0, WHITE);
 
         // Message
-        draw_text(&format!("Delete '{}'?", obj_name), dialog_x + 12.0, dialog_y + 55.0, 14.0, TEXT_COLOR);
-        draw_text("This cannot be undone.", dialog_x + 12.0, dialog_y + 72.0, 12.0, TEXT_DIM);
+        draw_text(&format!("Delete '{}'?", obj_name), dialog_x + 12.0, dialog_y + 55.0, 14.0, theme().text);
+        draw_text("This cannot be undone.", dialog_x + 12.0, dialog_y + 72.0, 12.0, theme().text_dim);
 
         // Buttons
         let btn_w = 80.0;
@@ -8044,7 +9683,7 @@ fn draw_object_dialogs(ctx: &mut UiContext, state: &mut ModelerState, icon_font:
         let cancel_hover = ctx.mouse.inside(&cancel_rect);
         draw_rectangle(cancel_rect.x, cancel_rect.y, cancel_rect.w, cancel_rect.h,
             if cancel_hover { Color::from_rgba(70, 70, 75, 255) } else { Color::from_rgba(55, 55, 60, 255) });
-        draw_text("Cancel", cancel_rect.x + 18.0, cancel_rect.y + 18.0, 14.0, TEXT_COLOR);
+        draw_text("Cancel", cancel_rect.x + 18.0, cancel_rect.y + 18.0, 14.0, theme().text);
 
         // Delete button (red)
         let delete_rect = Rect::new(dialog_x + dialog_w - btn_w - 12.0, btn_y, btn_w, btn_h);
@@ -8106,8 +9745,8 @@ fn draw_object_dialogs(ctx: &mut UiContext, state: &mut ModelerState, icon_font:
         draw_text("Delete Component?", dialog_x + 36.0, dialog_y + 26.0, 16.0, WHITE);
 
         // Message
-        draw_text(&format!("Delete '{}' component?", comp_name), dialog_x + 12.0, dialog_y + 55.0, 14.0, TEXT_COLOR);
-        draw_text("This cannot be undone.", dialog_x + 12.0, dialog_y + 72.0, 12.0, TEXT_DIM);
+        draw_text(&format!("Delete '{}' component?", comp_name), dialog_x + 12.0, dialog_y + 55.0, 14.0, theme().text);
+        draw_text("This cannot be undone.", dialog_x + 12.0, dialog_y + 72.0, 12.0, theme().text_dim);
 
         // Buttons
         let btn_w = 80.0;
@@ -8119,7 +9758,7 @@ fn draw_object_dialogs(ctx: &mut UiContext, state: &mut ModelerState, icon_font:
         let cancel_hover = ctx.mouse.inside(&cancel_rect);
         draw_rectangle(cancel_rect.x, cancel_rect.y, cancel_rect.w, cancel_rect.h,
             if cancel_hover { Color::from_rgba(70, 70, 75, 255) } else { Color::from_rgba(55, 55, 60, 255) });
-        draw_text("Cancel", cancel_rect.x + 18.0, cancel_rect.y + 18.0, 14.0, TEXT_COLOR);
+        draw_text("Cancel", cancel_rect.x + 18.0, cancel_rect.y + 18.0, 14.0, theme().text);
 
         // Delete button (red)
         let delete_rect = Rect::new(dialog_x + dialog_w - btn_w - 12.0, btn_y, btn_w, btn_h);
@@ -8135,6 +9774,7 @@ fn draw_object_dialogs(ctx: &mut UiContext, state: &mut ModelerState, icon_font:
             // Delete the component
             if idx < state.asset.components.len() {
                 state.asset.components.remove(idx);
+                state.component_field_edit = None;
 
                 // Update selected_component
                 if state.asset.components.is_empty() {
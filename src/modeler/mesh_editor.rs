@@ -7,6 +7,7 @@
 //! - Reading: Auto-detects format by checking for valid RON start
 //! - Writing: Always uses brotli compression
 
+use crate::load_check::describe_ron_error;
 use crate::rasterizer::{Vec3, Vec2, Vertex, Color15, Texture15, BlendMode, ClutDepth, ClutId, Clut, IndexedTexture};
 use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
@@ -436,7 +437,7 @@ impl MeshProject {
     /// Load project from string (.ron format) - works on all platforms including WASM
     pub fn load_from_str(ron_data: &str) -> Result<Self, MeshEditorError> {
         let mut project: MeshProject = ron::from_str(ron_data)
-            .map_err(|e| MeshEditorError::Serialization(e.to_string()))?;
+            .map_err(|e| MeshEditorError::Serialization(describe_ron_error(ron_data, &e)))?;
 
         // Process each object: migrate old format and populate runtime atlas
         for obj in &mut project.objects {
@@ -838,7 +839,7 @@ impl MeshEditorModel {
         };
 
         let model: MeshEditorModel = ron::from_str(&ron_data)
-            .map_err(|e| MeshEditorError::Serialization(e.to_string()))?;
+            .map_err(|e| MeshEditorError::Serialization(describe_ron_error(&ron_data, &e)))?;
         Ok(model)
     }
 
@@ -1576,7 +1577,7 @@ impl EditableMesh {
         };
 
         let mesh: EditableMesh = ron::from_str(&ron_data)
-            .map_err(|e| MeshEditorError::Serialization(e.to_string()))?;
+            .map_err(|e| MeshEditorError::Serialization(describe_ron_error(&ron_data, &e)))?;
         Ok(mesh)
     }
 
@@ -2020,6 +2021,102 @@ impl EditableMesh {
         self.faces.extend(new_faces);
     }
 
+    /// Mirror the + side across `axis` and weld the seam.
+    ///
+    /// Faces reaching into the − side are dropped first so repeating the
+    /// command doesn't stack copies. Vertices within `threshold` of the plane
+    /// are snapped onto it and coincident seam vertices merged.
+    /// Returns the number of seam vertices welded.
+    pub fn mirror_and_weld(&mut self, axis: super::state::Axis, threshold: f32) -> usize {
+        let vertices = &self.vertices;
+        self.faces.retain(|face| {
+            face.vertices.iter().all(|&vi| vertices.get(vi).map_or(false, |v| axis.coord(v.pos) >= -threshold))
+        });
+        self.compact_vertices();
+
+        for vert in &mut self.vertices {
+            if axis.coord(vert.pos).abs() <= threshold {
+                vert.pos = vert.pos - axis.to_vec3() * axis.coord(vert.pos);
+            }
+        }
+
+        self.apply_mirror(axis, threshold);
+
+        // Weld coincident seam vertices into the first one found
+        let seam: Vec<usize> = (0..self.vertices.len())
+            .filter(|&vi| axis.coord(self.vertices[vi].pos) == 0.0)
+            .collect();
+        let mut remap: Vec<usize> = (0..self.vertices.len()).collect();
+        let mut welded = 0;
+        for (i, &vi) in seam.iter().enumerate() {
+            let pos = self.vertices[vi].pos;
+            let target = seam[..i].iter().copied()
+                .find(|&other| remap[other] == other && (self.vertices[other].pos - pos).len() <= threshold);
+            if let Some(target) = target {
+                remap[vi] = target;
+                welded += 1;
+            }
+        }
+        if welded > 0 {
+            for face in &mut self.faces {
+                for vi in &mut face.vertices {
+                    *vi = remap[*vi];
+                }
+            }
+            self.compact_vertices();
+        }
+        welded
+    }
+
+    /// For each (vertex, original position) being moved, the vertices sitting
+    /// at its mirror image across `axis`. Vertices on the plane pair with
+    /// themselves; vertices that are moved anyway are never partners.
+    pub fn symmetry_partners(&self, moved: &[(usize, Vec3)], axis: super::state::Axis, epsilon: f32) -> Vec<(usize, usize)> {
+        let moving: std::collections::HashSet<usize> = moved.iter().map(|(vi, _)| *vi).collect();
+        let mut pairs = Vec::new();
+        for &(vi, pos) in moved {
+            if axis.coord(pos).abs() <= epsilon {
+                pairs.push((vi, vi));
+                continue;
+            }
+            let target = axis.mirror(pos);
+            for (other, vert) in self.vertices.iter().enumerate() {
+                if !moving.contains(&other) && (vert.pos - target).len() <= epsilon {
+                    pairs.push((vi, other));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Faces that are the mirror image of `face_indices` across `axis`,
+    /// excluding faces already in the list and faces that mirror onto themselves.
+    pub fn mirror_faces(&self, face_indices: &[usize], axis: super::state::Axis, epsilon: f32) -> Vec<usize> {
+        let mut found = Vec::new();
+        for &fi in face_indices {
+            let Some(face) = self.faces.get(fi) else { continue };
+            let targets: Vec<Vec3> = face.vertices.iter()
+                .filter_map(|&vi| self.vertices.get(vi))
+                .map(|v| axis.mirror(v.pos))
+                .collect();
+            let mirror = (0..self.faces.len()).find(|&other| {
+                let candidate = &self.faces[other];
+                candidate.vertices.len() == targets.len()
+                    && !face_indices.contains(&other)
+                    && !found.contains(&other)
+                    && targets.iter().all(|&target| {
+                        candidate.vertices.iter().any(|&vi| {
+                            self.vertices.get(vi).map_or(false, |v| (v.pos - target).len() <= epsilon)
+                        })
+                    })
+            });
+            if let Some(mirror) = mirror {
+                found.push(mirror);
+            }
+        }
+        found
+    }
+
     /// Get all faces that contain a given edge (pair of vertex indices).
     /// Returns face indices.
     fn faces_with_edge(&self, v0: usize, v1: usize) -> Vec<usize> {
@@ -3018,3 +3115,41 @@ pub fn auto_unwrap_faces(
     }
     println!("=== END AUTO UNWRAP ===");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::state::Axis;
+
+    #[test]
+    fn test_symmetry_partners_and_mirror_faces() {
+        let mesh = EditableMesh::cube(100.0);
+        // Right face corner (50, -50, -50) pairs with every copy of (-50, -50, -50)
+        let corner = mesh.vertices[17].pos;
+        let mut pairs = mesh.symmetry_partners(&[(17, corner)], Axis::X, 1.0);
+        pairs.sort();
+        assert_eq!(pairs, vec![(17, 5), (17, 12), (17, 20)]);
+
+        // Right mirrors to Left; Front straddles the plane and has no partner
+        assert_eq!(mesh.mirror_faces(&[4], Axis::X, 1.0), vec![5]);
+        assert!(mesh.mirror_faces(&[0], Axis::X, 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_mirror_and_weld() {
+        let mut mesh = EditableMesh::cube(100.0);
+        for vert in &mut mesh.vertices {
+            vert.pos.x += 50.0;
+        }
+
+        // 4 seam corners, each shared by three faces' vertices
+        assert_eq!(mesh.mirror_and_weld(Axis::X, 1.0), 8);
+        assert_eq!(mesh.faces.len(), 11);
+        let min_x = mesh.vertices.iter().map(|v| v.pos.x).fold(f32::MAX, f32::min);
+        assert_eq!(min_x, -100.0);
+
+        // Repeating replaces the mirror side instead of stacking another copy
+        mesh.mirror_and_weld(Axis::X, 1.0);
+        assert_eq!(mesh.faces.len(), 11);
+    }
+}
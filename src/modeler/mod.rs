@@ -15,12 +15,17 @@ mod model_browser;
 mod mesh_editor;
 mod obj_import;
 mod obj_importer;
+#[cfg(not(target_arch = "wasm32"))]
+mod gltf_import;
 mod quantize;
 mod skeleton;
+mod sprite_sheet;
+mod model_export;
 pub mod actions;
 pub mod drag;
 pub mod tools;
 pub mod radial_menu;
+pub mod snapshots;
 
 // Re-export public API
 #[allow(unused_imports)]
@@ -34,6 +39,11 @@ pub use model_browser::*;
 pub use mesh_editor::*;
 pub use obj_import::*;
 pub use obj_importer::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use gltf_import::*;
 #[allow(unused_imports)]
 pub use quantize::*;
+#[allow(unused_imports)]
+pub use sprite_sheet::*;
+pub use model_export::*;
 // Actions used internally by layout.rs
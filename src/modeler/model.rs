@@ -64,6 +64,31 @@ impl Animation {
     pub fn remove_keyframe(&mut self, frame: u32) {
         self.keyframes.retain(|kf| kf.frame != frame);
     }
+
+    /// Bone transforms at a (fractional) frame, interpolating between the
+    /// surrounding keyframes. Bones missing from a keyframe stay at rest.
+    pub fn sample(&self, frame: f32, num_bones: usize) -> Vec<BoneTransform> {
+        let transform_at = |kf: &Keyframe, bone: usize| kf.transforms.get(bone).copied().unwrap_or_default();
+
+        let next_idx = self.keyframes.iter().position(|kf| kf.frame as f32 > frame);
+        let (prev, next) = match next_idx {
+            None => (self.keyframes.last(), None),
+            Some(0) => (None, self.keyframes.first()),
+            Some(i) => (self.keyframes.get(i - 1), self.keyframes.get(i)),
+        };
+
+        (0..num_bones)
+            .map(|bone| match (prev, next) {
+                (Some(a), Some(b)) => {
+                    let span = (b.frame - a.frame).max(1) as f32;
+                    let t = ((frame - a.frame as f32) / span).clamp(0.0, 1.0);
+                    transform_at(a, bone).lerp(&transform_at(b, bone), t)
+                }
+                (Some(kf), None) | (None, Some(kf)) => transform_at(kf, bone),
+                (None, None) => BoneTransform::default(),
+            })
+            .collect()
+    }
 }
 
 /// Single keyframe (stores transform for each bone)
@@ -6,13 +6,26 @@
 //! Two-section layout matching the level browser:
 //! - SAMPLES: bundled read-only sample assets
 //! - MY ASSETS: user-created assets (editable, cloud-synced)
+//!
+//! User assets with snapshots show a HISTORY column next to the preview:
+//! picking a snapshot previews it (with vertex/face deltas vs the current
+//! version) and Restore opens it in the modeler.
+//!
+//! The filter bar above the list narrows it by name, tag or smart collection,
+//! and the selected asset's tags are edited under the preview. List rows show
+//! a thumbnail taken from the asset's first preview frame.
 
 use macroquad::prelude::*;
 use crate::storage::{PendingLoad, PendingList};
-use crate::ui::{Rect, UiContext, draw_icon_centered, ACCENT_COLOR, TextInputState, draw_text_input};
-use crate::rasterizer::{Framebuffer, Camera, Color as RasterColor, Vec3, RasterSettings, render_mesh, render_mesh_15, draw_floor_grid};
+use crate::ui::{Rect, UiContext, draw_icon_centered, theme, TextInputState, draw_text_input};
+use crate::rasterizer::{Framebuffer, FramebufferTexture, RedrawGate, redraw_key, Camera, Color as RasterColor, Vec3, RasterSettings, render_mesh, render_mesh_15, draw_floor_grid};
 use crate::world::SECTOR_SIZE;
 use crate::asset::{Asset, SAMPLES_ASSETS_DIR, USER_ASSETS_DIR};
+use crate::tags::{TagFilter, TagKind, draw_tag_filter, draw_item_tags, TAG_FILTER_HEIGHT, TAG_ROW_HEIGHT};
+use crate::browser_list::{draw_browser_list, ListItem, ListSection};
+use crate::thumbnails;
+use crate::project::{draw_usages_panel, UsagesPanel};
+use super::snapshots::{SnapshotMeta, format_age, unix_now};
 use std::path::PathBuf;
 
 /// Category of asset (sample or user-created)
@@ -220,8 +233,24 @@ pub struct AssetBrowser {
     pub pending_refresh: bool,
     /// Active rename dialog (TextInputState for the new name)
     pub rename_dialog: Option<TextInputState>,
+    /// Snapshot history of the selected user asset (newest first)
+    pub history: Vec<SnapshotMeta>,
+    /// Selected history entry (None = current version)
+    pub history_selected: Option<usize>,
+    /// Loaded asset of the selected history entry
+    pub snapshot_asset: Option<Asset>,
+    /// Tag filter and tag editing state
+    pub tag_filter: TagFilter,
+    /// Levels and assets using the selected asset (find usages / delete warning)
+    pub usages: Option<UsagesPanel>,
+    /// The next preview frame becomes the selected asset's thumbnail
+    thumbnail_pending: bool,
     /// Local framebuffer for preview rendering
     preview_fb: Framebuffer,
+    /// GPU copy of the preview framebuffer
+    preview_texture: FramebufferTexture,
+    /// Skips re-rendering the preview while the view is unchanged
+    preview_gate: RedrawGate,
 }
 
 impl Default for AssetBrowser {
@@ -249,7 +278,15 @@ impl Default for AssetBrowser {
             pending_user_list: None,
             pending_refresh: false,
             rename_dialog: None,
+            history: Vec::new(),
+            history_selected: None,
+            snapshot_asset: None,
+            tag_filter: TagFilter::default(),
+            usages: None,
+            thumbnail_pending: false,
             preview_fb: Framebuffer::new(320, 240), // Initial size, will resize as needed
+            preview_texture: FramebufferTexture::new(),
+            preview_gate: RedrawGate::default(),
         }
     }
 }
@@ -264,6 +301,8 @@ impl AssetBrowser {
         self.selected_index = None;
         self.preview_asset = None;
         self.scroll_offset = 0.0;
+        self.preview_gate.invalidate();
+        self.clear_history();
     }
 
     /// Open the browser with just sample assets (legacy compatibility)
@@ -276,6 +315,25 @@ impl AssetBrowser {
         self.open = false;
         self.preview_asset = None;
         self.pending_preview_load = None;
+        self.clear_history();
+    }
+
+    /// Forget the snapshot history and usages (selection changed)
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.history_selected = None;
+        self.snapshot_asset = None;
+        self.usages = None;
+    }
+
+    /// Asset shown in the preview: the selected snapshot, else the current version
+    pub fn shown_asset(&self) -> Option<&Asset> {
+        self.snapshot_asset.as_ref().or(self.preview_asset.as_ref())
+    }
+
+    /// Selected history entry, if a snapshot is being previewed
+    pub fn selected_snapshot(&self) -> Option<&SnapshotMeta> {
+        self.history_selected.and_then(|i| self.history.get(i))
     }
 
     /// Check if the selected asset is a sample (read-only)
@@ -345,6 +403,8 @@ impl AssetBrowser {
         }
 
         self.preview_asset = Some(asset);
+        self.thumbnail_pending = true;
+        self.preview_gate.invalidate();
         self.orbit_yaw = 0.8;
         self.orbit_pitch = 0.3;
     }
@@ -371,12 +431,18 @@ pub enum AssetBrowserAction {
     OpenCopy,
     /// User wants to delete the selected user asset
     DeleteAsset,
+    /// User wants to see which levels and assets use the selected user asset
+    FindUsages,
     /// User wants to rename the selected user asset
     RenameAsset,
     /// User wants to start with a new empty asset
     NewAsset,
     /// User wants to refresh the asset list
     Refresh,
+    /// User picked a history entry to preview (None = back to the current version)
+    SelectSnapshot(Option<usize>),
+    /// User wants to open the previewed snapshot in the modeler
+    RestoreSnapshot,
     /// User cancelled
     Cancel,
 }
@@ -424,37 +490,81 @@ pub fn draw_asset_browser(
     let content_h = dialog_h - header_h - 60.0; // Leave room for footer
     let list_w = 220.0;
 
-    // List panel (left) - custom two-section list
-    let list_rect = Rect::new(dialog_x + 8.0, content_y, list_w, content_h);
-    draw_rectangle(list_rect.x, list_rect.y, list_rect.w, list_rect.h, Color::from_rgba(25, 25, 30, 255));
-
-    let item_h = 26.0;
-    let section_h = 28.0;
-    let has_cloud = storage.has_cloud();
+    // Tag filter above the list
+    let filter_rect = Rect::new(dialog_x + 8.0, content_y, list_w, TAG_FILTER_HEIGHT);
+    draw_tag_filter(ctx, filter_rect, &mut browser.tag_filter);
 
-    // Draw two-section list
-    let list_action = draw_two_section_list(
+    // List panel (left) - custom two-section list
+    let list_top = filter_rect.bottom() + 4.0;
+    let list_rect = Rect::new(dialog_x + 8.0, list_top, list_w, content_y + content_h - list_top);
+    let sections = [
+        ListSection {
+            title: "SAMPLE ASSETS",
+            items: browser.samples.iter().map(|a| ListItem { name: &a.name, key: a.library_key() }).collect(),
+            collapsed: browser.samples_collapsed,
+            loading: false,
+            empty_text: None,
+            cloud: false,
+        },
+        ListSection {
+            title: "MY ASSETS",
+            items: browser.user_assets.iter().map(|a| ListItem { name: &a.name, key: a.library_key() }).collect(),
+            collapsed: browser.user_collapsed,
+            loading: browser.is_loading_user_assets(),
+            empty_text: Some("(no saved assets)"),
+            cloud: storage.has_cloud(),
+        },
+    ];
+    let selected = match (browser.selected_category, browser.selected_index) {
+        (Some(AssetCategory::Sample), Some(i)) => Some((0, i)),
+        (Some(AssetCategory::User), Some(i)) => Some((1, i)),
+        _ => None,
+    };
+    let list = draw_browser_list(
         ctx,
         list_rect,
-        browser,
-        item_h,
-        section_h,
-        has_cloud,
+        &mut browser.scroll_offset,
+        &sections,
+        &browser.tag_filter,
+        TagKind::Asset,
+        selected,
+        Some(storage),
     );
 
     // Handle list actions
-    if let Some((category, idx)) = list_action {
+    match list.toggled {
+        Some(0) => browser.samples_collapsed = !browser.samples_collapsed,
+        Some(_) => browser.user_collapsed = !browser.user_collapsed,
+        None => {}
+    }
+    if let Some((section, idx)) = list.clicked {
+        let category = if section == 0 { AssetCategory::Sample } else { AssetCategory::User };
         if browser.selected_category != Some(category) || browser.selected_index != Some(idx) {
             browser.selected_category = Some(category);
             browser.selected_index = Some(idx);
+            browser.clear_history();
             action = AssetBrowserAction::SelectPreview(category, idx);
         }
     }
 
-    // Preview panel (right)
+    // Preview panel (right), with the history column when the asset has snapshots
     let preview_x = dialog_x + list_w + 16.0;
-    let preview_w = dialog_w - list_w - 24.0;
-    let preview_rect = Rect::new(preview_x, content_y, preview_w, content_h);
+    let history_w = if browser.history.is_empty() { 0.0 } else { 190.0 };
+    let preview_w = dialog_w - list_w - 24.0 - if history_w > 0.0 { history_w + 8.0 } else { 0.0 };
+    let preview_rect = Rect::new(preview_x, content_y, preview_w, content_h - TAG_ROW_HEIGHT - 4.0);
+
+    // Tags of the selected asset, under the preview
+    if let Some(key) = browser.selected_asset().map(|info| info.library_key()) {
+        let tags_rect = Rect::new(preview_x, preview_rect.bottom() + 4.0, preview_w, TAG_ROW_HEIGHT);
+        draw_item_tags(ctx, tags_rect, &mut browser.tag_filter, TagKind::Asset, &key);
+    }
+
+    if history_w > 0.0 {
+        let history_rect = Rect::new(preview_rect.right() + 8.0, content_y, history_w, content_h);
+        if let Some(selected) = draw_history_list(ctx, history_rect, browser) {
+            action = AssetBrowserAction::SelectSnapshot(selected);
+        }
+    }
 
     draw_rectangle(preview_rect.x, preview_rect.y, preview_rect.w, preview_rect.h, Color::from_rgba(20, 20, 25, 255));
 
@@ -464,11 +574,24 @@ pub fn draw_asset_browser(
 
     if has_preview {
         // Render 3D preview with orbit camera (uses browser's local framebuffer)
-        draw_orbit_preview_internal(ctx, browser, preview_rect, user_textures);
+        draw_orbit_preview_internal(ctx, browser, preview_rect, storage, user_textures);
 
         // Draw stats at bottom of preview
-        if let Some(asset) = &browser.preview_asset {
-            let stats_y = preview_rect.bottom() - 24.0;
+        let stats_y = preview_rect.bottom() - 24.0;
+        if let (Some(snapshot), Some(current)) = (&browser.snapshot_asset, &browser.preview_asset) {
+            // Diff against the current version
+            draw_rectangle(preview_rect.x, stats_y, preview_rect.w, 24.0, Color::from_rgba(45, 40, 25, 220));
+            let age = browser.selected_snapshot()
+                .map(|meta| format_age(unix_now(), meta.timestamp))
+                .unwrap_or_default();
+            let stats_text = format!(
+                "Snapshot ({}): Vertices {} ({})  Faces {} ({})",
+                age,
+                snapshot.total_vertices(), signed_delta(snapshot.total_vertices(), current.total_vertices()),
+                snapshot.total_faces(), signed_delta(snapshot.total_faces(), current.total_faces()),
+            );
+            draw_text(&stats_text, preview_rect.x + 8.0, stats_y + 17.0, 14.0, Color::from_rgba(230, 200, 130, 255));
+        } else if let Some(asset) = &browser.preview_asset {
             draw_rectangle(preview_rect.x, stats_y, preview_rect.w, 24.0, Color::from_rgba(30, 30, 35, 200));
 
             let obj_count = asset.mesh().map(|m| m.len()).unwrap_or(0);
@@ -479,6 +602,13 @@ pub fn draw_asset_browser(
             );
             draw_text(&stats_text, preview_rect.x + 8.0, stats_y + 17.0, 14.0, Color::from_rgba(180, 180, 180, 255));
         }
+
+        // Usages above the stats bar
+        if let Some(usages) = &browser.usages {
+            let usages_h = (38.0 + usages.users.len() as f32 * 16.0).min(preview_rect.h * 0.5);
+            let usages_rect = Rect::new(preview_rect.x + 8.0, stats_y - usages_h - 8.0, preview_rect.w - 16.0, usages_h);
+            draw_usages_panel(usages_rect, usages);
+        }
     } else if browser.is_loading_preview() {
         // Loading indicator with animated spinner
         let time = get_time() as f32;
@@ -526,6 +656,20 @@ pub fn draw_asset_browser(
         action = AssetBrowserAction::Refresh;
     }
 
+    // Restore button (only while previewing a snapshot)
+    let restore_rect = Rect::new(dialog_x + 330.0, footer_y + 8.0, 70.0, 28.0);
+    let restore_enabled = browser.snapshot_asset.is_some();
+    if draw_text_button_enabled(ctx, restore_rect, "Restore", Color::from_rgba(100, 80, 40, 255), restore_enabled) {
+        action = AssetBrowserAction::RestoreSnapshot;
+    }
+
+    // Usages button (only for user assets - samples can't be deleted)
+    let usages_rect = Rect::new(dialog_x + 410.0, footer_y + 8.0, 70.0, 28.0);
+    let usages_enabled = browser.is_user_selected() && browser.preview_asset.is_some();
+    if draw_text_button_enabled(ctx, usages_rect, "Usages", Color::from_rgba(60, 60, 70, 255), usages_enabled) {
+        action = AssetBrowserAction::FindUsages;
+    }
+
     // Cancel button
     let cancel_rect = Rect::new(dialog_x + dialog_w - 270.0, footer_y + 8.0, 70.0, 28.0);
     if draw_text_button(ctx, cancel_rect, "Cancel", Color::from_rgba(60, 60, 70, 255)) {
@@ -542,7 +686,7 @@ pub fn draw_asset_browser(
     // Open button (enabled if something is selected and loaded)
     let open_rect = Rect::new(dialog_x + dialog_w - 90.0, footer_y + 8.0, 80.0, 28.0);
     let open_enabled = browser.preview_asset.is_some();
-    if draw_text_button_enabled(ctx, open_rect, "Open", ACCENT_COLOR, open_enabled) {
+    if draw_text_button_enabled(ctx, open_rect, "Open", theme().accent, open_enabled) {
         action = AssetBrowserAction::OpenAsset;
     }
 
@@ -575,7 +719,7 @@ pub fn draw_asset_browser(
         let confirm_rect = Rect::new(rdx + rdw - btn_w - 12.0, btn_y, btn_w, btn_h);
         let confirm_hover = ctx.mouse.inside(&confirm_rect);
         draw_rectangle(confirm_rect.x, confirm_rect.y, confirm_rect.w, confirm_rect.h,
-            if confirm_hover { Color::from_rgba(60, 100, 140, 255) } else { ACCENT_COLOR });
+            if confirm_hover { Color::from_rgba(60, 100, 140, 255) } else { theme().accent });
         draw_text("Rename", confirm_rect.x + 14.0, confirm_rect.y + 18.0, 14.0, WHITE);
 
         if ctx.mouse.clicked(&cancel_rect) || is_key_pressed(KeyCode::Escape) {
@@ -584,8 +728,8 @@ pub fn draw_asset_browser(
             action = AssetBrowserAction::RenameAsset;
         }
     } else {
-        // Handle Escape to close (only when rename dialog is not open)
-        if is_key_pressed(KeyCode::Escape) {
+        // Handle Escape to close (only when no dialog or tag field is taking keys)
+        if is_key_pressed(KeyCode::Escape) && !browser.tag_filter.is_typing() {
             action = AssetBrowserAction::Cancel;
         }
     }
@@ -593,162 +737,79 @@ pub fn draw_asset_browser(
     action
 }
 
-/// Draw the two-section list (Samples + My Assets)
-fn draw_two_section_list(
-    ctx: &mut UiContext,
-    rect: Rect,
-    browser: &mut AssetBrowser,
-    item_h: f32,
-    section_h: f32,
-    has_cloud: bool,
-) -> Option<(AssetCategory, usize)> {
-    let mut clicked: Option<(AssetCategory, usize)> = None;
-    let mut y = rect.y - browser.scroll_offset;
-
-    let section_bg = Color::from_rgba(40, 40, 50, 255);
-    let item_bg = Color::from_rgba(30, 30, 38, 255);
-    let item_hover = Color::from_rgba(50, 50, 60, 255);
-    let item_selected = Color::from_rgba(60, 80, 120, 255);
+/// Draw the HISTORY column: "Current" followed by the snapshots, newest first.
+/// Returns the clicked entry (Some(None) = current version).
+fn draw_history_list(ctx: &mut UiContext, rect: Rect, browser: &AssetBrowser) -> Option<Option<usize>> {
+    let item_h = 34.0;
+    let header_h = 24.0;
     let text_color = Color::from_rgba(200, 200, 200, 255);
     let text_dim = Color::from_rgba(140, 140, 140, 255);
-    let cloud_color = Color::from_rgba(100, 180, 255, 255);
-
-    // Calculate total content height for scroll
-    let samples_content_h = if browser.samples_collapsed { 0.0 } else { browser.samples.len() as f32 * item_h };
-    let user_content_h = if browser.user_collapsed { 0.0 } else { browser.user_assets.len() as f32 * item_h };
-    let total_h = section_h * 2.0 + samples_content_h + user_content_h;
-
-    // Handle scroll within list bounds
-    if ctx.mouse.inside(&rect) && ctx.mouse.scroll != 0.0 {
-        browser.scroll_offset = (browser.scroll_offset - ctx.mouse.scroll * 30.0)
-            .clamp(0.0, (total_h - rect.h).max(0.0));
-    }
-
-    // SAMPLES section header
-    let samples_header_rect = Rect::new(rect.x, y, rect.w, section_h);
-    if y + section_h > rect.y && y < rect.bottom() {
-        draw_rectangle(samples_header_rect.x, samples_header_rect.y.max(rect.y),
-                      samples_header_rect.w, section_h.min(rect.bottom() - samples_header_rect.y.max(rect.y)), section_bg);
-
-        let arrow = if browser.samples_collapsed { ">" } else { "v" };
-        draw_text(&format!("{} SAMPLE ASSETS ({})", arrow, browser.samples.len()),
-                 rect.x + 8.0, y + 18.0, 14.0, text_color);
-
-        // Toggle collapse on click
-        if ctx.mouse.inside(&samples_header_rect) && ctx.mouse.left_pressed {
-            browser.samples_collapsed = !browser.samples_collapsed;
+    let checkpoint_color = Color::from_rgba(230, 200, 130, 255);
+
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(25, 25, 30, 255));
+    draw_rectangle(rect.x, rect.y, rect.w, header_h, Color::from_rgba(40, 40, 50, 255));
+    draw_text(&format!("HISTORY ({})", browser.history.len()), rect.x + 8.0, rect.y + 16.0, 14.0, text_color);
+
+    let now = unix_now();
+    let mut clicked = None;
+    let mut y = rect.y + header_h;
+    let rows = std::iter::once(None).chain((0..browser.history.len()).map(Some));
+    for entry in rows {
+        if y + item_h > rect.bottom() {
+            break;
         }
-    }
-    y += section_h;
-
-    // SAMPLES items
-    if !browser.samples_collapsed {
-        for (i, asset) in browser.samples.iter().enumerate() {
-            let item_rect = Rect::new(rect.x, y, rect.w, item_h);
-
-            if y + item_h > rect.y && y < rect.bottom() {
-                let is_selected = browser.selected_category == Some(AssetCategory::Sample)
-                    && browser.selected_index == Some(i);
-                let is_hovered = ctx.mouse.inside(&item_rect) && item_rect.y >= rect.y;
-
-                let bg = if is_selected { item_selected }
-                        else if is_hovered { item_hover }
-                        else { item_bg };
-
-                // Clip to list bounds
-                let draw_y = item_rect.y.max(rect.y);
-                let draw_h = item_h.min(rect.bottom() - draw_y);
-                if draw_h > 0.0 {
-                    draw_rectangle(item_rect.x + 2.0, draw_y, item_rect.w - 4.0, draw_h, bg);
-
-                    if y >= rect.y {
-                        draw_text(&asset.name, rect.x + 20.0, y + 17.0, 13.0, text_color);
-                    }
-                }
-
-                // Handle click
-                if is_hovered && ctx.mouse.left_pressed && item_rect.y >= rect.y {
-                    clicked = Some((AssetCategory::Sample, i));
-                }
+        let item_rect = Rect::new(rect.x + 2.0, y, rect.w - 4.0, item_h - 2.0);
+        let selected = browser.history_selected == entry;
+        let hovered = ctx.mouse.inside(&item_rect);
+        let bg = if selected {
+            Color::from_rgba(60, 80, 120, 255)
+        } else if hovered {
+            Color::from_rgba(50, 50, 60, 255)
+        } else {
+            Color::from_rgba(30, 30, 38, 255)
+        };
+        draw_rectangle(item_rect.x, item_rect.y, item_rect.w, item_rect.h, bg);
+
+        match entry.and_then(|i| browser.history.get(i)) {
+            Some(meta) => {
+                let color = if meta.kind == super::snapshots::SnapshotKind::Checkpoint { checkpoint_color } else { text_color };
+                draw_text(&truncate_to_width(&meta.title(), rect.w - 16.0, 13.0), rect.x + 8.0, y + 14.0, 13.0, color);
+                let detail = format!("{}  {}v {}f", format_age(now, meta.timestamp), meta.vertices, meta.faces);
+                draw_text(&detail, rect.x + 8.0, y + 27.0, 12.0, text_dim);
+            }
+            None => {
+                draw_text("Current", rect.x + 8.0, y + 14.0, 13.0, text_color);
+                draw_text("saved version", rect.x + 8.0, y + 27.0, 12.0, text_dim);
             }
-            y += item_h;
         }
-    }
 
-    // MY ASSETS section header
-    let user_header_rect = Rect::new(rect.x, y, rect.w, section_h);
-    if y + section_h > rect.y && y < rect.bottom() {
-        draw_rectangle(user_header_rect.x, user_header_rect.y.max(rect.y),
-                      user_header_rect.w, section_h.min(rect.bottom() - user_header_rect.y.max(rect.y)), section_bg);
-
-        let arrow = if browser.user_collapsed { ">" } else { "v" };
-        let cloud_indicator = if has_cloud { " [cloud]" } else { "" };
-        draw_text(&format!("{} MY ASSETS ({}){}", arrow, browser.user_assets.len(), cloud_indicator),
-                 rect.x + 8.0, y + 18.0, 14.0, text_color);
-
-        // Toggle collapse on click
-        if ctx.mouse.inside(&user_header_rect) && ctx.mouse.left_pressed && user_header_rect.y >= rect.y {
-            browser.user_collapsed = !browser.user_collapsed;
+        if hovered && ctx.mouse.left_pressed && !selected {
+            clicked = Some(entry);
         }
+        y += item_h;
     }
-    y += section_h;
-
-    // MY ASSETS items
-    if !browser.user_collapsed {
-        if browser.is_loading_user_assets() {
-            // Show loading indicator
-            if y + item_h > rect.y && y < rect.bottom() {
-                let time = get_time() as f32;
-                let spinner_chars = ['|', '/', '-', '\\'];
-                let spinner_idx = (time * 8.0) as usize % spinner_chars.len();
-                let loading_text = format!("  {} Loading...", spinner_chars[spinner_idx]);
-                draw_text(&loading_text, rect.x + 8.0, y + 17.0, 12.0, text_dim);
-            }
-        } else if browser.user_assets.is_empty() {
-            // Show empty state message
-            if y + item_h > rect.y && y < rect.bottom() {
-                draw_text("  (no saved assets)", rect.x + 8.0, y + 17.0, 12.0, text_dim);
-            }
-        } else {
-            for (i, asset) in browser.user_assets.iter().enumerate() {
-                let item_rect = Rect::new(rect.x, y, rect.w, item_h);
-
-                if y + item_h > rect.y && y < rect.bottom() {
-                    let is_selected = browser.selected_category == Some(AssetCategory::User)
-                        && browser.selected_index == Some(i);
-                    let is_hovered = ctx.mouse.inside(&item_rect) && item_rect.y >= rect.y;
-
-                    let bg = if is_selected { item_selected }
-                            else if is_hovered { item_hover }
-                            else { item_bg };
-
-                    // Clip to list bounds
-                    let draw_y = item_rect.y.max(rect.y);
-                    let draw_h = item_h.min(rect.bottom() - draw_y);
-                    if draw_h > 0.0 {
-                        draw_rectangle(item_rect.x + 2.0, draw_y, item_rect.w - 4.0, draw_h, bg);
-
-                        if y >= rect.y {
-                            draw_text(&asset.name, rect.x + 20.0, y + 17.0, 13.0, text_color);
-
-                            // Cloud icon for user assets when cloud storage is active
-                            if has_cloud {
-                                draw_text("*", rect.x + rect.w - 20.0, y + 17.0, 13.0, cloud_color);
-                            }
-                        }
-                    }
-
-                    // Handle click
-                    if is_hovered && ctx.mouse.left_pressed && item_rect.y >= rect.y {
-                        clicked = Some((AssetCategory::User, i));
-                    }
-                }
-                y += item_h;
-            }
-        }
+    clicked
+}
+
+/// "+4", "-12" or "=" for a count compared to the current version
+fn signed_delta(value: usize, current: usize) -> String {
+    match value.cmp(&current) {
+        std::cmp::Ordering::Greater => format!("+{}", value - current),
+        std::cmp::Ordering::Less => format!("-{}", current - value),
+        std::cmp::Ordering::Equal => "=".to_string(),
     }
+}
 
-    clicked
+/// Cut text with "..." so it fits `max_w` pixels
+fn truncate_to_width(text: &str, max_w: f32, font_size: f32) -> String {
+    if measure_text(text, None, font_size as u16, 1.0).width <= max_w {
+        return text.to_string();
+    }
+    let mut out: String = text.to_string();
+    while !out.is_empty() && measure_text(&format!("{}...", out), None, font_size as u16, 1.0).width > max_w {
+        out.pop();
+    }
+    format!("{}...", out)
 }
 
 /// Draw the orbit preview of an asset (uses browser's internal framebuffer)
@@ -756,9 +817,16 @@ fn draw_orbit_preview_internal(
     ctx: &mut UiContext,
     browser: &mut AssetBrowser,
     rect: Rect,
+    storage: &crate::storage::Storage,
     user_textures: &crate::texture::TextureLibrary,
 ) {
-    let asset = match &browser.preview_asset {
+    // Only the current version (not a snapshot) becomes the list thumbnail
+    let thumbnail_key = browser.selected_asset()
+        .filter(|_| browser.snapshot_asset.is_none())
+        .map(|info| info.library_key());
+
+    // Field access (not shown_asset()) so the orbit state below stays mutable
+    let asset = match browser.snapshot_asset.as_ref().or(browser.preview_asset.as_ref()) {
         Some(a) => a,
         None => return,
     };
@@ -821,12 +889,57 @@ fn draw_orbit_preview_internal(
     camera.rotation_y = n.x.atan2(n.z);
     camera.update_basis();
 
-    // Resize local preview framebuffer
+    // Preview framebuffer size
     let preview_h = rect.h - 24.0;
     let target_w = (rect.w as usize).min(640);
     let target_h = (preview_h as usize).min(target_w * 3 / 4);
+
+    // Nothing to re-render while the same asset is shown from the same angle
+    let view_key = redraw_key((
+        &asset.name,
+        browser.history_selected,
+        [browser.orbit_yaw, browser.orbit_pitch, browser.orbit_distance].map(f32::to_bits),
+        [browser.orbit_center.x, browser.orbit_center.y, browser.orbit_center.z].map(f32::to_bits),
+        (target_w, target_h),
+    ));
     let fb = &mut browser.preview_fb;
-    fb.resize(target_w, target_h);
+    if browser.preview_gate.needs_redraw(view_key) {
+        render_orbit_preview(fb, asset, objects, &camera, (target_w, target_h), user_textures);
+    }
+
+    // First frame of a newly loaded asset: keep it as the list thumbnail
+    if browser.thumbnail_pending {
+        if let Some(key) = &thumbnail_key {
+            thumbnails::capture(storage, TagKind::Asset, key, fb);
+            browser.thumbnail_pending = false;
+        }
+    }
+
+    // Draw framebuffer to screen (an unchanged frame uploads nothing)
+    let fb_texture = browser.preview_texture.upload(fb);
+
+    draw_texture_ex(
+        fb_texture,
+        rect.x,
+        rect.y,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(vec2(rect.w, preview_h)),
+            ..Default::default()
+        },
+    );
+}
+
+/// Render the asset, its skeleton and the floor grid into the preview framebuffer
+fn render_orbit_preview(
+    fb: &mut Framebuffer,
+    asset: &Asset,
+    objects: &[crate::modeler::MeshPart],
+    camera: &Camera,
+    (width, height): (usize, usize),
+    user_textures: &crate::texture::TextureLibrary,
+) {
+    fb.resize(width, height);
     fb.clear(RasterColor::new(25, 25, 35));
 
     let settings = RasterSettings::default();
@@ -834,7 +947,7 @@ fn draw_orbit_preview_internal(
 
     // Render mesh parts with per-part double_sided handling
     crate::scene::render_asset_parts(
-        fb, objects, &camera, &settings,
+        fb, objects, camera, &settings,
         0.0, Vec3::ZERO, None, user_textures,
     );
 
@@ -848,9 +961,9 @@ fn draw_orbit_preview_internal(
             if !bone_verts.is_empty() {
                 let bone_settings = RasterSettings { backface_cull: false, ..settings.clone() };
                 if use_rgb555 {
-                    render_mesh_15(fb, &bone_verts, &bone_faces, &[], &camera, &bone_settings, None);
+                    render_mesh_15(fb, &bone_verts, &bone_faces, &[], camera, &bone_settings, None);
                 } else {
-                    render_mesh(fb, &bone_verts, &bone_faces, &[], &camera, &bone_settings);
+                    render_mesh(fb, &bone_verts, &bone_faces, &[], camera, &bone_settings);
                 }
             }
 
@@ -860,29 +973,14 @@ fn draw_orbit_preview_internal(
                 if let Some(parent_idx) = bone.parent {
                     let (child_pos, _) = bone_world_transform(bones, idx);
                     let (parent_pos, _) = bone_world_transform(bones, parent_idx);
-                    draw_3d_line_clipped(fb, &camera, parent_pos, child_pos, line_color);
+                    draw_3d_line_clipped(fb, camera, parent_pos, child_pos, line_color);
                 }
             }
         }
     }
 
     // Draw a simple floor plane indicator using the grid drawing
-    draw_preview_grid(fb, &camera);
-
-    // Draw framebuffer to screen
-    let fb_texture = Texture2D::from_rgba8(fb.width as u16, fb.height as u16, &fb.pixels);
-    fb_texture.set_filter(FilterMode::Nearest);
-
-    draw_texture_ex(
-        &fb_texture,
-        rect.x,
-        rect.y,
-        WHITE,
-        DrawTextureParams {
-            dest_size: Some(vec2(rect.w, preview_h)),
-            ..Default::default()
-        },
-    );
+    draw_preview_grid(fb, camera);
 }
 
 /// Draw a floor grid matching the world editor
@@ -0,0 +1,546 @@
+//! OBJ and glTF 2.0 export
+//!
+//! Writes the modeler's parts back out for use in other tools:
+//!
+//! - **OBJ**: one `o` object per part in world space (bind pose), an MTL file
+//!   with one material per part, and each part's atlas baked through its CLUT
+//!   to a PNG. Polygons keep their quads/n-gons.
+//! - **glTF**: a single self-contained `.gltf` (buffers and PNGs embedded as
+//!   data URIs). Bones become nodes and each part is a mesh node under the
+//!   bone it is bound to, so the part hierarchy survives the trip.
+//!
+//! Our rasterizer winds faces clockwise and both formats expect
+//! counter-clockwise, so polygons are written reversed (the importers undo
+//! this). Palette index 0 is exported as transparent.
+
+use crate::rasterizer::{Vec2, Vec3};
+use super::mesh_editor::{ClutPool, MeshPart};
+use super::skeleton::bone_world_transform;
+use super::state::{RigBone, inverse_rotate_by_euler, rotate_by_euler};
+
+/// File formats the modeler can export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelExportFormat {
+    Obj,
+    Gltf,
+}
+
+impl ModelExportFormat {
+    /// Extension of the main file
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ModelExportFormat::Obj => "obj",
+            ModelExportFormat::Gltf => "gltf",
+        }
+    }
+
+    /// Name for file dialogs and status messages
+    pub fn label(&self) -> &'static str {
+        match self {
+            ModelExportFormat::Obj => "Wavefront OBJ",
+            ModelExportFormat::Gltf => "glTF 2.0",
+        }
+    }
+}
+
+/// One file produced by an export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportFile {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+/// A part's atlas expanded to RGBA through its CLUT
+#[derive(Debug, Clone, PartialEq)]
+struct BakedTexture {
+    width: usize,
+    height: usize,
+    rgba: Vec<u8>,
+}
+
+impl BakedTexture {
+    fn encode_png(&self) -> Result<Vec<u8>, String> {
+        use image::ImageEncoder;
+        let mut bytes = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut bytes)
+            .write_image(&self.rgba, self.width as u32, self.height as u32, image::ExtendedColorType::Rgba8)
+            .map_err(|e| format!("PNG encode failed: {}", e))?;
+        Ok(bytes)
+    }
+}
+
+/// Bake every part's atlas. Parts sharing identical pixels share a texture.
+/// Returns the textures and, per part, its texture index.
+fn bake_textures(parts: &[MeshPart], clut_pool: &ClutPool) -> (Vec<BakedTexture>, Vec<Option<usize>>) {
+    let fallback_clut = clut_pool.first_id().and_then(|id| clut_pool.get(id));
+    let mut textures: Vec<BakedTexture> = Vec::new();
+    let part_textures = parts.iter().map(|part| {
+        let atlas = &part.atlas;
+        if atlas.width == 0 || atlas.height == 0 || atlas.indices.len() < atlas.width * atlas.height {
+            return None;
+        }
+        let clut = if atlas.default_clut.is_valid() {
+            clut_pool.get(atlas.default_clut).or(fallback_clut)
+        } else {
+            fallback_clut
+        }?;
+        let rgba = atlas.indices.iter()
+            .flat_map(|&i| if i == 0 { [0, 0, 0, 0] } else { clut.lookup(i).to_rgba() })
+            .collect();
+        let baked = BakedTexture { width: atlas.width, height: atlas.height, rgba };
+        Some(match textures.iter().position(|t| *t == baked) {
+            Some(i) => i,
+            None => {
+                textures.push(baked);
+                textures.len() - 1
+            }
+        })
+    }).collect();
+    (textures, part_textures)
+}
+
+/// A part's geometry in the frame it is written in
+struct PartGeometry {
+    positions: Vec<Vec3>,
+    normals: Vec<Vec3>,
+    uvs: Vec<Vec2>,
+    /// Counter-clockwise polygons (indices into the vertex arrays)
+    polygons: Vec<Vec<usize>>,
+}
+
+impl PartGeometry {
+    /// Fan-triangulated polygons
+    fn triangles(&self) -> Vec<[usize; 3]> {
+        self.polygons.iter()
+            .flat_map(|poly| (1..poly.len().saturating_sub(1)).map(move |i| [poly[0], poly[i], poly[i + 1]]))
+            .collect()
+    }
+}
+
+/// Geometry of `part` in the local frame of bone `frame` (None = world space).
+/// Vertices bound to that same bone are already in its frame and stay as-is.
+fn part_geometry(part: &MeshPart, bone_transforms: &[(Vec3, Vec3)], frame: Option<usize>) -> PartGeometry {
+    let frame_transform = frame.and_then(|i| bone_transforms.get(i)).copied();
+    let mut positions = Vec::with_capacity(part.mesh.vertices.len());
+    let mut normals = Vec::with_capacity(part.mesh.vertices.len());
+    for v in &part.mesh.vertices {
+        let bone = v.bone_index.or(part.default_bone_index)
+            .filter(|&i| i < bone_transforms.len());
+        let (pos, normal) = if bone.is_some() && bone == frame {
+            (v.pos, v.normal)
+        } else {
+            let (world_pos, world_normal) = match bone.map(|i| bone_transforms[i]) {
+                Some((bone_pos, bone_rot)) => (rotate_by_euler(v.pos, bone_rot) + bone_pos, rotate_by_euler(v.normal, bone_rot)),
+                None => (v.pos, v.normal),
+            };
+            match frame_transform {
+                Some((frame_pos, frame_rot)) => (
+                    inverse_rotate_by_euler(world_pos - frame_pos, frame_rot),
+                    inverse_rotate_by_euler(world_normal, frame_rot),
+                ),
+                None => (world_pos, world_normal),
+            }
+        };
+        positions.push(pos);
+        // Both formats want unit normals
+        let normal = normal.normalize();
+        normals.push(if normal.len() > 0.5 { normal } else { Vec3::UP });
+    }
+    let polygons = part.mesh.faces.iter()
+        .filter(|f| f.vertices.len() >= 3 && f.vertices.iter().all(|&i| i < positions.len()))
+        .map(|f| f.vertices.iter().rev().copied().collect())
+        .collect();
+    PartGeometry {
+        positions,
+        normals,
+        uvs: part.mesh.vertices.iter().map(|v| v.uv).collect(),
+        polygons,
+    }
+}
+
+/// Name safe to use in OBJ/MTL statements (no whitespace)
+fn obj_name(name: &str, index: usize) -> String {
+    let cleaned: String = name.trim().chars()
+        .map(|c| if c.is_whitespace() || c == '#' { '_' } else { c })
+        .collect();
+    if cleaned.is_empty() { format!("part_{}", index) } else { cleaned }
+}
+
+/// PNG file names for the baked textures
+fn texture_file_names(stem: &str, count: usize) -> Vec<String> {
+    if count == 1 {
+        vec![format!("{}.png", stem)]
+    } else {
+        (0..count).map(|i| format!("{}_{}.png", stem, i)).collect()
+    }
+}
+
+/// Export to OBJ: `<stem>.obj` first, then `<stem>.mtl` and the texture PNGs
+pub fn export_obj(
+    parts: &[MeshPart],
+    bones: &[RigBone],
+    clut_pool: &ClutPool,
+    stem: &str,
+) -> Result<Vec<ExportFile>, String> {
+    use std::fmt::Write;
+
+    let bone_transforms: Vec<(Vec3, Vec3)> = (0..bones.len())
+        .map(|i| bone_world_transform(bones, i))
+        .collect();
+    let (textures, part_textures) = bake_textures(parts, clut_pool);
+    let png_names = texture_file_names(stem, textures.len());
+
+    let mut obj = String::new();
+    let mut mtl = String::new();
+    let _ = writeln!(obj, "# Exported from Bonnie-32\nmtllib {}.mtl", stem);
+    let _ = writeln!(mtl, "# Exported from Bonnie-32");
+
+    // OBJ indices are 1-based and global across objects
+    let mut base = 1;
+    for (i, part) in parts.iter().enumerate() {
+        let name = obj_name(&part.name, i);
+        let material = format!("{}_mat", name);
+        let geometry = part_geometry(part, &bone_transforms, None);
+
+        let _ = writeln!(mtl, "\nnewmtl {}", material);
+        match part_textures[i] {
+            Some(tex) => {
+                let _ = writeln!(mtl, "Kd 1.000 1.000 1.000\nmap_Kd {}", png_names[tex]);
+            }
+            None => {
+                let [r, g, b] = part.color.unwrap_or([204, 204, 204]);
+                let _ = writeln!(mtl, "Kd {:.3} {:.3} {:.3}", r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+            }
+        }
+        let _ = writeln!(mtl, "illum 1");
+
+        let _ = writeln!(obj, "\no {}", name);
+        for p in &geometry.positions {
+            let _ = writeln!(obj, "v {} {} {}", p.x, p.y, p.z);
+        }
+        for uv in &geometry.uvs {
+            let _ = writeln!(obj, "vt {} {}", uv.x, uv.y);
+        }
+        for n in &geometry.normals {
+            let _ = writeln!(obj, "vn {} {} {}", n.x, n.y, n.z);
+        }
+        let _ = writeln!(obj, "usemtl {}\ns off", material);
+        for poly in &geometry.polygons {
+            obj.push('f');
+            for &v in poly {
+                let _ = write!(obj, " {0}/{0}/{0}", base + v);
+            }
+            obj.push('\n');
+        }
+        base += geometry.positions.len();
+    }
+
+    let mut files = vec![
+        ExportFile { name: format!("{}.obj", stem), bytes: obj.into_bytes() },
+        ExportFile { name: format!("{}.mtl", stem), bytes: mtl.into_bytes() },
+    ];
+    for (texture, name) in textures.iter().zip(png_names) {
+        files.push(ExportFile { name, bytes: texture.encode_png()? });
+    }
+    Ok(files)
+}
+
+/// Quote and escape a string for JSON
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_list(items: &[String]) -> String {
+    format!("[{}]", items.join(","))
+}
+
+/// Column-major matrix of a bone relative to its parent bone. Our bones
+/// accumulate euler angles down the chain, so the local transform is derived
+/// from the two world transforms rather than from `local_rotation`.
+fn bone_local_matrix(world: (Vec3, Vec3), parent: Option<(Vec3, Vec3)>) -> [f32; 16] {
+    let (pos, rot) = world;
+    let (parent_pos, parent_rot) = parent.unwrap_or((Vec3::ZERO, Vec3::ZERO));
+    let axis = |a: Vec3| inverse_rotate_by_euler(rotate_by_euler(a, rot), parent_rot);
+    let x = axis(Vec3::new(1.0, 0.0, 0.0));
+    let y = axis(Vec3::new(0.0, 1.0, 0.0));
+    let z = axis(Vec3::new(0.0, 0.0, 1.0));
+    let t = inverse_rotate_by_euler(pos - parent_pos, parent_rot);
+    [
+        x.x, x.y, x.z, 0.0,
+        y.x, y.y, y.z, 0.0,
+        z.x, z.y, z.z, 0.0,
+        t.x, t.y, t.z, 1.0,
+    ]
+}
+
+/// Binary buffer plus the bufferViews/accessors describing it
+#[derive(Default)]
+struct GltfBuffer {
+    bytes: Vec<u8>,
+    views: Vec<String>,
+    accessors: Vec<String>,
+}
+
+impl GltfBuffer {
+    const ARRAY_BUFFER: u32 = 34962;
+    const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+    const FLOAT: u32 = 5126;
+    const UNSIGNED_INT: u32 = 5125;
+
+    /// Append one accessor and its view. Returns the accessor index.
+    fn push(&mut self, data: &[u8], target: u32, component: u32, kind: &str, count: usize, bounds: Option<(Vec3, Vec3)>) -> usize {
+        let offset = self.bytes.len();
+        self.bytes.extend_from_slice(data);
+        self.views.push(format!(
+            "{{\"buffer\":0,\"byteOffset\":{},\"byteLength\":{},\"target\":{}}}",
+            offset, data.len(), target,
+        ));
+        let bounds = bounds.map_or(String::new(), |(min, max)| format!(
+            ",\"min\":[{},{},{}],\"max\":[{},{},{}]",
+            min.x, min.y, min.z, max.x, max.y, max.z,
+        ));
+        self.accessors.push(format!(
+            "{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"{}\"{}}}",
+            self.views.len() - 1, component, count, kind, bounds,
+        ));
+        self.accessors.len() - 1
+    }
+
+    fn push_floats(&mut self, values: impl Iterator<Item = f32>, kind: &str, count: usize, bounds: Option<(Vec3, Vec3)>) -> usize {
+        let data: Vec<u8> = values.flat_map(f32::to_le_bytes).collect();
+        self.push(&data, Self::ARRAY_BUFFER, Self::FLOAT, kind, count, bounds)
+    }
+}
+
+/// Export to a self-contained glTF 2.0 file (`<stem>.gltf`)
+pub fn export_gltf(
+    parts: &[MeshPart],
+    bones: &[RigBone],
+    clut_pool: &ClutPool,
+    stem: &str,
+) -> Result<ExportFile, String> {
+    use base64::Engine;
+    let b64 = &base64::engine::general_purpose::STANDARD;
+
+    let bone_transforms: Vec<(Vec3, Vec3)> = (0..bones.len())
+        .map(|i| bone_world_transform(bones, i))
+        .collect();
+    let (textures, part_textures) = bake_textures(parts, clut_pool);
+
+    // Node 0 is the model root, then one node per bone, then one per part
+    let bone_node = |i: usize| 1 + i;
+    let part_node = |i: usize| 1 + bones.len() + i;
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); 1 + bones.len()];
+    for (i, bone) in bones.iter().enumerate() {
+        let parent = bone.parent.filter(|&p| p < bones.len()).map_or(0, bone_node);
+        children[parent].push(bone_node(i));
+    }
+
+    let mut buffer = GltfBuffer::default();
+    let mut meshes = Vec::new();
+    let mut materials = Vec::new();
+    let mut part_nodes = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        let bone = part.default_bone_index.filter(|&b| b < bones.len());
+        children[bone.map_or(0, bone_node)].push(part_node(i));
+
+        let geometry = part_geometry(part, &bone_transforms, bone);
+        let triangles = geometry.triangles();
+        if triangles.is_empty() {
+            part_nodes.push(format!("{{\"name\":{}}}", json_string(&part.name)));
+            continue;
+        }
+
+        let mut min = Vec3::new(f32::MAX, f32::MAX, f32::MAX);
+        let mut max = Vec3::new(f32::MIN, f32::MIN, f32::MIN);
+        for p in &geometry.positions {
+            min = Vec3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Vec3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+        }
+        let count = geometry.positions.len();
+        let position = buffer.push_floats(geometry.positions.iter().flat_map(|p| [p.x, p.y, p.z]), "VEC3", count, Some((min, max)));
+        let normal = buffer.push_floats(geometry.normals.iter().flat_map(|n| [n.x, n.y, n.z]), "VEC3", count, None);
+        // glTF UVs start top-left; ours start bottom-left
+        let uv = buffer.push_floats(geometry.uvs.iter().flat_map(|uv| [uv.x, 1.0 - uv.y]), "VEC2", count, None);
+        let index_data: Vec<u8> = triangles.iter().flatten().flat_map(|&i| (i as u32).to_le_bytes()).collect();
+        let indices = buffer.push(&index_data, GltfBuffer::ELEMENT_ARRAY_BUFFER, GltfBuffer::UNSIGNED_INT, "SCALAR", triangles.len() * 3, None);
+
+        let pbr = match part_textures[i] {
+            Some(tex) => format!("\"baseColorTexture\":{{\"index\":{}}}", tex),
+            None => {
+                let [r, g, b] = part.color.unwrap_or([204, 204, 204]);
+                format!("\"baseColorFactor\":[{},{},{},1]", r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+            }
+        };
+        materials.push(format!(
+            "{{\"name\":{},\"pbrMetallicRoughness\":{{{},\"metallicFactor\":0,\"roughnessFactor\":1}},\"alphaMode\":\"MASK\",\"doubleSided\":{}}}",
+            json_string(&part.name), pbr, part.double_sided,
+        ));
+        meshes.push(format!(
+            "{{\"name\":{},\"primitives\":[{{\"attributes\":{{\"POSITION\":{},\"NORMAL\":{},\"TEXCOORD_0\":{}}},\"indices\":{},\"material\":{},\"mode\":4}}]}}",
+            json_string(&part.name), position, normal, uv, indices, materials.len() - 1,
+        ));
+        part_nodes.push(format!("{{\"name\":{},\"mesh\":{}}}", json_string(&part.name), meshes.len() - 1));
+    }
+
+    let children_field = |list: &[usize]| if list.is_empty() {
+        String::new()
+    } else {
+        format!(",\"children\":{}", json_list(&list.iter().map(|c| c.to_string()).collect::<Vec<_>>()))
+    };
+    let mut nodes = vec![format!("{{\"name\":{}{}}}", json_string(stem), children_field(&children[0][..]))];
+    for (i, bone) in bones.iter().enumerate() {
+        let parent = bone.parent.filter(|&p| p < bones.len()).map(|p| bone_transforms[p]);
+        let matrix = bone_local_matrix(bone_transforms[i], parent);
+        nodes.push(format!(
+            "{{\"name\":{},\"matrix\":{}{}}}",
+            json_string(&bone.name),
+            json_list(&matrix.iter().map(|m| m.to_string()).collect::<Vec<_>>()),
+            children_field(&children[bone_node(i)][..]),
+        ));
+    }
+    nodes.extend(part_nodes);
+
+    let mut images = Vec::new();
+    for texture in &textures {
+        images.push(format!("{{\"uri\":\"data:image/png;base64,{}\"}}", b64.encode(texture.encode_png()?)));
+    }
+    let texture_defs: Vec<String> = (0..textures.len())
+        .map(|i| format!("{{\"sampler\":0,\"source\":{}}}", i))
+        .collect();
+
+    let mut json = String::from("{\"asset\":{\"version\":\"2.0\",\"generator\":\"Bonnie-32\"},\"scene\":0,\"scenes\":[{\"nodes\":[0]}]");
+    json.push_str(&format!(",\"nodes\":{}", json_list(&nodes)));
+    if !meshes.is_empty() {
+        json.push_str(&format!(
+            ",\"meshes\":{},\"materials\":{},\"accessors\":{},\"bufferViews\":{},\"buffers\":[{{\"byteLength\":{},\"uri\":\"data:application/octet-stream;base64,{}\"}}]",
+            json_list(&meshes), json_list(&materials), json_list(&buffer.accessors), json_list(&buffer.views),
+            buffer.bytes.len(), b64.encode(&buffer.bytes),
+        ));
+    }
+    if !textures.is_empty() {
+        // Nearest filtering keeps the texels crisp
+        json.push_str(&format!(
+            ",\"images\":{},\"textures\":{},\"samplers\":[{{\"magFilter\":9728,\"minFilter\":9728}}]",
+            json_list(&images), json_list(&texture_defs),
+        ));
+    }
+    json.push('}');
+
+    Ok(ExportFile { name: format!("{}.gltf", stem), bytes: json.into_bytes() })
+}
+
+/// Export in `format`. The main file comes first, followed by any side files.
+pub fn export_model(
+    format: ModelExportFormat,
+    parts: &[MeshPart],
+    bones: &[RigBone],
+    clut_pool: &ClutPool,
+    stem: &str,
+) -> Result<Vec<ExportFile>, String> {
+    match format {
+        ModelExportFormat::Obj => export_obj(parts, bones, clut_pool, stem),
+        ModelExportFormat::Gltf => export_gltf(parts, bones, clut_pool, stem).map(|file| vec![file]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::obj_import::ObjImporter;
+
+    fn text(file: &ExportFile) -> &str {
+        std::str::from_utf8(&file.bytes).unwrap()
+    }
+
+    #[test]
+    fn test_obj_export_writes_mtl_and_png() {
+        let parts = vec![MeshPart::cube("hull part", 100.0), MeshPart::cube("wing", 50.0)];
+        let files = export_obj(&parts, &[], &ClutPool::default(), "ship").unwrap();
+        let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+        // Both cubes use the same checkerboard, so there is a single texture
+        assert_eq!(names, ["ship.obj", "ship.mtl", "ship.png"]);
+        assert!(files[2].bytes.starts_with(b"\x89PNG"));
+
+        let obj = text(&files[0]);
+        assert!(obj.contains("mtllib ship.mtl") && obj.contains("o hull_part") && obj.contains("usemtl wing_mat"));
+        assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), parts[0].mesh.faces.len() * 2);
+        assert_eq!(text(&files[1]).matches("map_Kd ship.png").count(), 2);
+
+        // Our importer reads it back facing the same way
+        let mesh = ObjImporter::parse(obj).unwrap();
+        assert_eq!(mesh.vertices.len(), parts[0].mesh.vertices.len() * 2);
+        let facing = |verts: &[crate::rasterizer::Vertex], tri: &[usize]| {
+            let (a, b, c) = (verts[tri[0]].pos, verts[tri[1]].pos, verts[tri[2]].pos);
+            (b - a).cross(c - a).normalize()
+        };
+        let original = &parts[0].mesh;
+        let expected = facing(&original.vertices, &original.faces[0].triangulate()[0]);
+        assert!(facing(&mesh.vertices, &mesh.faces[0].vertices).dot(expected) > 0.99);
+    }
+
+    #[test]
+    fn test_gltf_export_nests_parts_under_bones() {
+        let mut root = RigBone::new("root");
+        root.local_position = Vec3::new(0.0, 100.0, 0.0);
+        let mut arm = RigBone::with_parent("arm", 0);
+        arm.local_position = Vec3::new(0.0, 50.0, 0.0);
+        arm.local_rotation = Vec3::new(0.0, 0.0, 90.0);
+        let bones = vec![root, arm];
+
+        let mut hand = MeshPart::cube("hand \"left\"", 20.0);
+        hand.default_bone_index = Some(1);
+        let parts = vec![MeshPart::cube("body", 40.0), hand];
+        let file = export_gltf(&parts, &bones, &ClutPool::default(), "robot").unwrap();
+        let json = text(&file);
+        assert_eq!(file.name, "robot.gltf");
+        // Root holds the root bone and the unbound part; the hand hangs off the arm
+        assert!(json.contains("{\"name\":\"robot\",\"children\":[1,3]}"));
+        assert!(json.contains("\"name\":\"root\"") && json.contains("\"children\":[2]"));
+        assert!(json.contains("\"children\":[4]}"));
+        assert!(json.contains("\"name\":\"hand \\\"left\\\"\",\"mesh\":1"));
+        assert!(json.contains("data:image/png;base64,"));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_gltf_round_trip_keeps_world_positions_and_uvs() {
+        use super::super::gltf_import::GltfImporter;
+
+        let mut root = RigBone::new("root");
+        root.local_position = Vec3::new(10.0, 100.0, 0.0);
+        root.local_rotation = Vec3::new(30.0, 0.0, 0.0);
+        let mut arm = RigBone::with_parent("arm", 0);
+        arm.local_position = Vec3::new(0.0, 50.0, 0.0);
+        arm.local_rotation = Vec3::new(0.0, 0.0, 45.0);
+        let bones = vec![root, arm];
+        let mut part = MeshPart::cube("hand", 20.0);
+        part.default_bone_index = Some(1);
+        let parts = vec![part];
+
+        let file = export_gltf(&parts, &bones, &ClutPool::default(), "arm").unwrap();
+        let scene = GltfImporter::parse(&file.bytes, None).unwrap();
+        assert_eq!(scene.parts.len(), 1);
+        assert!(scene.base_color_image.is_some());
+
+        let (bone_pos, bone_rot) = bone_world_transform(&bones, 1);
+        let imported = &scene.parts[0].mesh;
+        for (src, dst) in parts[0].mesh.vertices.iter().zip(&imported.vertices) {
+            let expected = rotate_by_euler(src.pos, bone_rot) + bone_pos;
+            assert!((expected - dst.pos).len() < 1e-3, "{:?} vs {:?}", expected, dst.pos);
+            assert!((src.uv.x - dst.uv.x).abs() < 1e-6 && (src.uv.y - dst.uv.y).abs() < 1e-6);
+        }
+        assert_eq!(imported.faces.len(), 12);
+    }
+}
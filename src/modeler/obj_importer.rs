@@ -3,12 +3,15 @@
 //! Modal dialog for importing OBJ mesh files into the asset editor.
 
 use macroquad::prelude::*;
-use crate::ui::{Rect, UiContext, draw_icon_centered, draw_scrollable_list, icon, icon_button, icon_button_active, ACCENT_COLOR, TEXT_COLOR};
+use crate::ui::{Rect, UiContext, draw_icon_centered, draw_scrollable_list, icon, icon_button, icon_button_active, theme};
 use crate::rasterizer::{Framebuffer, Camera, Color as RasterColor, Vec3, RasterSettings, render_mesh, render_mesh_15, draw_floor_grid, ClutDepth};
 use crate::world::SECTOR_SIZE;
 use super::mesh_editor::EditableMesh;
 use super::obj_import::TextureImportResult;
 use super::obj_import::ObjImporter;
+#[cfg(not(target_arch = "wasm32"))]
+use super::gltf_import::GltfImporter;
+use super::quantize::{QuantizeMode, QuantizeOptions};
 use std::path::PathBuf;
 
 /// Info about a mesh file (and its associated texture)
@@ -16,7 +19,7 @@ use std::path::PathBuf;
 pub struct MeshInfo {
     /// Display name (file stem)
     pub name: String,
-    /// Full path to the OBJ file
+    /// Full path to the OBJ or glTF file
     pub path: PathBuf,
     /// Primary texture path (PNG with same name)
     pub texture_path: Option<PathBuf>,
@@ -69,7 +72,7 @@ pub fn discover_meshes() -> Vec<MeshInfo> {
         (primary, additional)
     }
 
-    // Helper to scan a directory for OBJ files
+    // Helper to scan a directory for OBJ and glTF files
     fn scan_dir(dir: &PathBuf, meshes: &mut Vec<MeshInfo>) {
         if let Ok(entries) = std::fs::read_dir(dir) {
             for entry in entries.flatten() {
@@ -77,6 +80,20 @@ pub fn discover_meshes() -> Vec<MeshInfo> {
                 if path.is_dir() {
                     // Recursively scan subdirectories
                     scan_dir(&path, meshes);
+                } else if GltfImporter::is_gltf_path(&path) {
+                    let name = path.file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "unknown".to_string());
+
+                    // Textures are embedded or referenced by the file itself
+                    let (vertex_count, face_count) = if let Ok(scene) = GltfImporter::load_from_file(&path) {
+                        let mesh = scene.merged_mesh();
+                        (mesh.vertices.len(), mesh.faces.len())
+                    } else {
+                        (0, 0)
+                    };
+
+                    meshes.push(MeshInfo { name, path, texture_path: None, additional_textures: Vec::new(), vertex_count, face_count });
                 } else if path.extension().map_or(false, |ext| ext == "obj") {
                     let name = path.file_stem()
                         .map(|s| s.to_string_lossy().to_string())
@@ -105,6 +122,16 @@ pub fn discover_meshes() -> Vec<MeshInfo> {
     meshes
 }
 
+/// Load an OBJ or glTF file as a single mesh (glTF parts are merged)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_mesh_file(path: &std::path::Path) -> Result<EditableMesh, super::obj_import::ObjError> {
+    if GltfImporter::is_gltf_path(path) {
+        GltfImporter::load_from_file(path).map(|scene| scene.merged_mesh())
+    } else {
+        ObjImporter::load_from_file(path)
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 pub fn discover_meshes() -> Vec<MeshInfo> {
     // WASM: return empty, load async from manifest
@@ -209,6 +236,8 @@ pub struct ObjImportBrowser {
     pub texture_scroll_offset: f32,
     /// CLUT depth override for import (None = auto-detect based on color count)
     pub clut_depth_override: Option<ClutDepth>,
+    /// Quantization settings for glTF textures
+    pub quantize_options: QuantizeOptions,
 }
 
 impl Default for ObjImportBrowser {
@@ -238,6 +267,7 @@ impl Default for ObjImportBrowser {
             show_texture: true, // Show textures by default
             texture_scroll_offset: 0.0,
             clut_depth_override: None, // Auto-detect by default
+            quantize_options: QuantizeOptions::default(),
         }
     }
 }
@@ -374,7 +404,7 @@ pub fn draw_obj_importer(
     // Header
     let header_h = 40.0;
     draw_rectangle(dialog_x, dialog_y, dialog_w, header_h, Color::from_rgba(45, 45, 55, 255));
-    draw_text("Import OBJ", dialog_x + 16.0, dialog_y + 26.0, 20.0, WHITE);
+    draw_text("Import Mesh", dialog_x + 16.0, dialog_y + 26.0, 20.0, WHITE);
 
     // Close button
     let close_rect = Rect::new(dialog_x + dialog_w - 36.0, dialog_y + 4.0, 32.0, 32.0);
@@ -466,7 +496,7 @@ pub fn draw_obj_importer(
         draw_text("Loading preview...", preview_rect.x + 20.0, preview_rect.y + 40.0, 16.0, Color::from_rgba(150, 150, 150, 255));
     } else if browser.meshes.is_empty() {
         draw_text("No meshes found in assets/samples/meshes/", preview_rect.x + 20.0, preview_rect.y + 40.0, 16.0, Color::from_rgba(100, 100, 100, 255));
-        draw_text("Add OBJ files to that folder!", preview_rect.x + 20.0, preview_rect.y + 60.0, 14.0, Color::from_rgba(80, 80, 80, 255));
+        draw_text("Add OBJ or glTF files to that folder!", preview_rect.x + 20.0, preview_rect.y + 60.0, 14.0, Color::from_rgba(80, 80, 80, 255));
     } else {
         draw_text("Select a mesh to preview", preview_rect.x + 20.0, preview_rect.y + 40.0, 16.0, Color::from_rgba(100, 100, 100, 255));
     }
@@ -598,7 +628,7 @@ pub fn draw_obj_importer(
     draw_rectangle(dialog_x, footer_y, dialog_w, 44.0, Color::from_rgba(40, 40, 48, 255));
 
     // Scale control on the left side of footer
-    draw_text("Scale:", dialog_x + 12.0, footer_y + 22.0, 14.0, TEXT_COLOR);
+    draw_text("Scale:", dialog_x + 12.0, footer_y + 22.0, 14.0, theme().text);
     let scale_minus_rect = Rect::new(dialog_x + 60.0, footer_y + 8.0, 28.0, 28.0);
     let scale_plus_rect = Rect::new(dialog_x + 150.0, footer_y + 8.0, 28.0, 28.0);
 
@@ -619,7 +649,7 @@ pub fn draw_obj_importer(
         format!("{:.3}", browser.import_scale)
     };
     let text_width = measure_text(&scale_text, None, 14, 1.0).width;
-    draw_text(&scale_text, dialog_x + 104.0 - text_width / 2.0, footer_y + 22.0, 14.0, TEXT_COLOR);
+    draw_text(&scale_text, dialog_x + 104.0 - text_width / 2.0, footer_y + 22.0, 14.0, theme().text);
 
     if icon_button(ctx, scale_plus_rect, icon::PLUS, icon_font, "Increase Scale (double)") {
         // Allow scaling up to 1,000,000 for very small source models
@@ -677,7 +707,7 @@ pub fn draw_obj_importer(
 
     // CLUT depth selector (Auto / 4-bit / 8-bit)
     let clut_label_x = dialog_x + 340.0;
-    draw_text("CLUT:", clut_label_x, footer_y + 22.0, 12.0, TEXT_COLOR);
+    draw_text("CLUT:", clut_label_x, footer_y + 22.0, 12.0, theme().text);
 
     let clut_btn_w = 36.0;
     let clut_btn_h = 20.0;
@@ -686,9 +716,9 @@ pub fn draw_obj_importer(
     // Auto button
     let auto_rect = Rect::new(clut_label_x + 40.0, clut_btn_y, clut_btn_w, clut_btn_h);
     let auto_selected = browser.clut_depth_override.is_none();
-    let auto_bg = if auto_selected { ACCENT_COLOR } else { Color::from_rgba(60, 60, 70, 255) };
+    let auto_bg = if auto_selected { theme().accent } else { Color::from_rgba(60, 60, 70, 255) };
     draw_rectangle(auto_rect.x, auto_rect.y, auto_rect.w, auto_rect.h, auto_bg);
-    draw_text("Auto", auto_rect.x + 4.0, auto_rect.y + 14.0, 11.0, if auto_selected { WHITE } else { TEXT_COLOR });
+    draw_text("Auto", auto_rect.x + 4.0, auto_rect.y + 14.0, 11.0, if auto_selected { WHITE } else { theme().text });
     if ctx.mouse.inside(&auto_rect) {
         ctx.set_tooltip("Auto-detect CLUT depth based on color count", ctx.mouse.x, ctx.mouse.y);
         if ctx.mouse.left_pressed {
@@ -699,9 +729,9 @@ pub fn draw_obj_importer(
     // 4-bit button
     let bpp4_rect = Rect::new(auto_rect.x + clut_btn_w + 2.0, clut_btn_y, clut_btn_w, clut_btn_h);
     let bpp4_selected = browser.clut_depth_override == Some(ClutDepth::Bpp4);
-    let bpp4_bg = if bpp4_selected { ACCENT_COLOR } else { Color::from_rgba(60, 60, 70, 255) };
+    let bpp4_bg = if bpp4_selected { theme().accent } else { Color::from_rgba(60, 60, 70, 255) };
     draw_rectangle(bpp4_rect.x, bpp4_rect.y, bpp4_rect.w, bpp4_rect.h, bpp4_bg);
-    draw_text("4-bit", bpp4_rect.x + 4.0, bpp4_rect.y + 14.0, 11.0, if bpp4_selected { WHITE } else { TEXT_COLOR });
+    draw_text("4-bit", bpp4_rect.x + 4.0, bpp4_rect.y + 14.0, 11.0, if bpp4_selected { WHITE } else { theme().text });
     if ctx.mouse.inside(&bpp4_rect) {
         ctx.set_tooltip("Force 4-bit CLUT (16 colors) - reduces dithering artifacts", ctx.mouse.x, ctx.mouse.y);
         if ctx.mouse.left_pressed {
@@ -712,9 +742,9 @@ pub fn draw_obj_importer(
     // 8-bit button
     let bpp8_rect = Rect::new(bpp4_rect.x + clut_btn_w + 2.0, clut_btn_y, clut_btn_w, clut_btn_h);
     let bpp8_selected = browser.clut_depth_override == Some(ClutDepth::Bpp8);
-    let bpp8_bg = if bpp8_selected { ACCENT_COLOR } else { Color::from_rgba(60, 60, 70, 255) };
+    let bpp8_bg = if bpp8_selected { theme().accent } else { Color::from_rgba(60, 60, 70, 255) };
     draw_rectangle(bpp8_rect.x, bpp8_rect.y, bpp8_rect.w, bpp8_rect.h, bpp8_bg);
-    draw_text("8-bit", bpp8_rect.x + 4.0, bpp8_rect.y + 14.0, 11.0, if bpp8_selected { WHITE } else { TEXT_COLOR });
+    draw_text("8-bit", bpp8_rect.x + 4.0, bpp8_rect.y + 14.0, 11.0, if bpp8_selected { WHITE } else { theme().text });
     if ctx.mouse.inside(&bpp8_rect) {
         ctx.set_tooltip("Force 8-bit CLUT (256 colors) - preserves more detail", ctx.mouse.x, ctx.mouse.y);
         if ctx.mouse.left_pressed {
@@ -722,6 +752,38 @@ pub fn draw_obj_importer(
         }
     }
 
+    // glTF texture quantization (mode cycles Standard / Detail / Smooth)
+    let mode_rect = Rect::new(bpp8_rect.right() + 8.0, clut_btn_y, 52.0, clut_btn_h);
+    let opts = &mut browser.quantize_options;
+    let mode_label = match opts.mode {
+        QuantizeMode::Standard => "Std",
+        QuantizeMode::PreserveDetail => "Detail",
+        QuantizeMode::Smooth => "Smooth",
+    };
+    draw_rectangle(mode_rect.x, mode_rect.y, mode_rect.w, mode_rect.h, Color::from_rgba(60, 60, 70, 255));
+    draw_text(mode_label, mode_rect.x + 4.0, mode_rect.y + 14.0, 11.0, theme().text);
+    if ctx.mouse.inside(&mode_rect) {
+        ctx.set_tooltip("glTF texture quantize mode (click to cycle)", ctx.mouse.x, ctx.mouse.y);
+        if ctx.mouse.left_pressed {
+            opts.mode = match opts.mode {
+                QuantizeMode::Standard => QuantizeMode::PreserveDetail,
+                QuantizeMode::PreserveDetail => QuantizeMode::Smooth,
+                QuantizeMode::Smooth => QuantizeMode::Standard,
+            };
+        }
+    }
+
+    let lab_rect = Rect::new(mode_rect.right() + 2.0, clut_btn_y, 30.0, clut_btn_h);
+    let lab_bg = if opts.use_lab { theme().accent } else { Color::from_rgba(60, 60, 70, 255) };
+    draw_rectangle(lab_rect.x, lab_rect.y, lab_rect.w, lab_rect.h, lab_bg);
+    draw_text("LAB", lab_rect.x + 4.0, lab_rect.y + 14.0, 11.0, if opts.use_lab { WHITE } else { theme().text });
+    if ctx.mouse.inside(&lab_rect) {
+        ctx.set_tooltip("Quantize glTF textures in LAB color space", ctx.mouse.x, ctx.mouse.y);
+        if ctx.mouse.left_pressed {
+            opts.use_lab = !opts.use_lab;
+        }
+    }
+
     // Cancel button
     let cancel_rect = Rect::new(dialog_x + dialog_w - 180.0, footer_y + 8.0, 80.0, 28.0);
     if draw_text_button(ctx, cancel_rect, "Cancel", Color::from_rgba(60, 60, 70, 255)) {
@@ -731,7 +793,7 @@ pub fn draw_obj_importer(
     // Open button
     let open_rect = Rect::new(dialog_x + dialog_w - 90.0, footer_y + 8.0, 80.0, 28.0);
     let open_enabled = browser.preview_mesh.is_some();
-    if draw_text_button_enabled(ctx, open_rect, "Open", ACCENT_COLOR, open_enabled) {
+    if draw_text_button_enabled(ctx, open_rect, "Open", theme().accent, open_enabled) {
         action = ObjImportAction::OpenMesh;
     }
 
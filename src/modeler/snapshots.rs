@@ -0,0 +1,334 @@
+//! Model Snapshots
+//!
+//! Named version history per model, kept on disk so it survives restarts
+//! (unlike the undo stack). Two kinds of snapshot:
+//! - Checkpoint: taken manually (Ctrl+K / toolbar)
+//! - Auto: taken every few minutes while the model keeps changing
+//!
+//! Layout: `assets/userdata/snapshots/<model>/history.ron` indexes the
+//! snapshots, each stored as a compressed asset next to it. Only the newest
+//! `MAX_AUTO_SNAPSHOTS` auto snapshots are kept; checkpoints are never pruned.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use crate::asset::Asset;
+use crate::storage::Storage;
+
+/// Root directory for snapshot histories
+pub const SNAPSHOTS_DIR: &str = "assets/userdata/snapshots";
+
+/// Default time between auto snapshots (seconds)
+pub const DEFAULT_AUTO_INTERVAL: f64 = 300.0;
+
+/// Auto snapshots kept per model (oldest are deleted first)
+pub const MAX_AUTO_SNAPSHOTS: usize = 20;
+
+/// How a snapshot was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapshotKind {
+    Checkpoint,
+    Auto,
+}
+
+impl SnapshotKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SnapshotKind::Checkpoint => "Checkpoint",
+            SnapshotKind::Auto => "Auto",
+        }
+    }
+}
+
+/// Index entry for one snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotMeta {
+    pub kind: SnapshotKind,
+    /// User label (checkpoints) or empty
+    #[serde(default)]
+    pub label: String,
+    /// Unix time (seconds)
+    pub timestamp: u64,
+    /// Asset file name inside the model's snapshot directory
+    pub file: String,
+    /// Stats at snapshot time, shown in the browser without loading the asset
+    pub vertices: usize,
+    pub faces: usize,
+}
+
+impl SnapshotMeta {
+    /// List label, e.g. "Checkpoint: before UVs" or "Auto"
+    pub fn title(&self) -> String {
+        if self.label.is_empty() {
+            self.kind.label().to_string()
+        } else {
+            format!("{}: {}", self.kind.label(), self.label)
+        }
+    }
+}
+
+/// Snapshot directory for a model
+pub fn history_dir(model: &str) -> String {
+    format!("{}/{}", SNAPSHOTS_DIR, model)
+}
+
+fn index_path(model: &str) -> String {
+    format!("{}/history.ron", history_dir(model))
+}
+
+/// Current unix time in seconds (works on WASM, unlike SystemTime)
+pub fn unix_now() -> u64 {
+    macroquad::miniquad::date::now() as u64
+}
+
+/// Relative age for display, e.g. "just now", "12m ago", "3d ago"
+pub fn format_age(now: u64, timestamp: u64) -> String {
+    let secs = now.saturating_sub(timestamp);
+    if secs < 60 {
+        "just now".to_string()
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h ago", secs / 3600)
+    } else {
+        format!("{}d ago", secs / 86400)
+    }
+}
+
+/// Snapshots need synchronous, writable storage (local disk).
+/// Cloud-backed userdata is async, so history is unavailable there.
+fn check_storage(storage: &Storage, model: &str) -> Result<(), String> {
+    if !storage.can_write() || !storage.is_sync(&history_dir(model)) {
+        return Err("Snapshots need local storage".to_string());
+    }
+    Ok(())
+}
+
+/// Load a model's snapshot index, newest first (empty if it has none)
+pub fn load_history(storage: &Storage, model: &str) -> Vec<SnapshotMeta> {
+    if check_storage(storage, model).is_err() {
+        return Vec::new();
+    }
+    let mut entries: Vec<SnapshotMeta> = storage.read_string_sync(&index_path(model))
+        .ok()
+        .and_then(|text| ron::from_str(&text).ok())
+        .unwrap_or_default();
+    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    entries
+}
+
+fn save_history(storage: &Storage, model: &str, entries: &[SnapshotMeta]) -> Result<(), String> {
+    let text = ron::ser::to_string_pretty(entries, ron::ser::PrettyConfig::default())
+        .map_err(|e| e.to_string())?;
+    storage.write_string_sync(&index_path(model), &text).map_err(|e| e.to_string())
+}
+
+/// Remove the oldest auto snapshots beyond `max`, returning the removed entries.
+/// `entries` must be sorted newest first.
+pub fn prune_autos(entries: &mut Vec<SnapshotMeta>, max: usize) -> Vec<SnapshotMeta> {
+    let mut kept_autos = 0;
+    let mut removed = Vec::new();
+    entries.retain(|e| {
+        if e.kind != SnapshotKind::Auto {
+            return true;
+        }
+        kept_autos += 1;
+        if kept_autos > max {
+            removed.push(e.clone());
+            false
+        } else {
+            true
+        }
+    });
+    removed
+}
+
+/// Write a snapshot of `asset` and add it to the model's history
+pub fn save_snapshot(
+    storage: &Storage,
+    model: &str,
+    asset: &Asset,
+    kind: SnapshotKind,
+    label: &str,
+) -> Result<SnapshotMeta, String> {
+    check_storage(storage, model)?;
+    let timestamp = unix_now();
+    let mut entries = load_history(storage, model);
+
+    // Unique file name even for several snapshots in the same second
+    let base = format!("{}_{}", timestamp, kind.label().to_lowercase());
+    let mut file = format!("{}.ron", base);
+    let mut n = 1;
+    while entries.iter().any(|e| e.file == file) {
+        n += 1;
+        file = format!("{}_{}.ron", base, n);
+    }
+
+    let bytes = asset.to_bytes().map_err(|e| e.to_string())?;
+    storage.write_sync(&format!("{}/{}", history_dir(model), file), &bytes)
+        .map_err(|e| e.to_string())?;
+
+    let meta = SnapshotMeta {
+        kind,
+        label: label.to_string(),
+        timestamp,
+        file,
+        vertices: asset.total_vertices(),
+        faces: asset.total_faces(),
+    };
+    entries.insert(0, meta.clone());
+    for old in prune_autos(&mut entries, MAX_AUTO_SNAPSHOTS) {
+        let _ = storage.delete_sync(&format!("{}/{}", history_dir(model), old.file));
+    }
+    save_history(storage, model, &entries)?;
+    Ok(meta)
+}
+
+/// Load the asset stored in a snapshot
+pub fn load_snapshot(storage: &Storage, model: &str, meta: &SnapshotMeta) -> Result<Asset, String> {
+    check_storage(storage, model)?;
+    let bytes = storage.read_sync(&format!("{}/{}", history_dir(model), meta.file))
+        .map_err(|e| e.to_string())?;
+    Asset::load_from_bytes(&bytes).map_err(|e| e.to_string())
+}
+
+/// Move a model's history when the model is renamed
+pub fn rename_history(storage: &Storage, old: &str, new: &str) -> Result<(), String> {
+    check_storage(storage, old)?;
+    let entries = load_history(storage, old);
+    for entry in &entries {
+        let bytes = storage.read_sync(&format!("{}/{}", history_dir(old), entry.file))
+            .map_err(|e| e.to_string())?;
+        storage.write_sync(&format!("{}/{}", history_dir(new), entry.file), &bytes)
+            .map_err(|e| e.to_string())?;
+    }
+    if !entries.is_empty() {
+        save_history(storage, new, &entries)?;
+    }
+    delete_history(storage, old);
+    Ok(())
+}
+
+/// Delete a model's whole history (when the model is deleted)
+pub fn delete_history(storage: &Storage, model: &str) {
+    if check_storage(storage, model).is_err() {
+        return;
+    }
+    for entry in load_history(storage, model) {
+        let _ = storage.delete_sync(&format!("{}/{}", history_dir(model), entry.file));
+    }
+    let _ = storage.delete_sync(&index_path(model));
+}
+
+/// History key for a model file (its file stem)
+pub fn model_key(path: &std::path::Path) -> Option<String> {
+    path.file_stem().map(|s| s.to_string_lossy().to_string())
+}
+
+/// Hash of the serialized asset, to skip auto snapshots of unchanged models
+pub fn content_hash(asset: &Asset) -> Option<u64> {
+    let text = ron::to_string(asset).ok()?;
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Timer for automatic snapshots of the open model
+#[derive(Debug, Clone)]
+pub struct AutoSnapshot {
+    pub enabled: bool,
+    /// Seconds between snapshots
+    pub interval: f64,
+    /// Model the timer is running for (switching models restarts it)
+    pub model: Option<String>,
+    /// Time (get_time) of the last snapshot or model switch
+    last_time: f64,
+    /// Content hash at the last snapshot
+    last_hash: Option<u64>,
+}
+
+impl Default for AutoSnapshot {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: DEFAULT_AUTO_INTERVAL,
+            model: None,
+            last_time: 0.0,
+            last_hash: None,
+        }
+    }
+}
+
+impl AutoSnapshot {
+    /// Is a snapshot due at `now`? (checked before hashing the asset)
+    pub fn due(&self, now: f64) -> bool {
+        self.enabled && now - self.last_time >= self.interval
+    }
+
+    /// Restart the interval (new model opened, or a snapshot was just taken)
+    pub fn reset(&mut self, now: f64, hash: Option<u64>) {
+        self.last_time = now;
+        self.last_hash = hash;
+    }
+
+    /// Start timing a newly opened model from its loaded content
+    pub fn switch_model(&mut self, model: &str, now: f64, hash: Option<u64>) {
+        self.model = Some(model.to_string());
+        self.reset(now, hash);
+    }
+
+    /// Has the content changed since the last snapshot?
+    pub fn changed(&self, hash: Option<u64>) -> bool {
+        hash.is_some() && hash != self.last_hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(kind: SnapshotKind, timestamp: u64) -> SnapshotMeta {
+        SnapshotMeta {
+            kind,
+            label: String::new(),
+            timestamp,
+            file: format!("{}.ron", timestamp),
+            vertices: 0,
+            faces: 0,
+        }
+    }
+
+    #[test]
+    fn test_prune_keeps_checkpoints() {
+        let mut entries = vec![
+            meta(SnapshotKind::Auto, 50),
+            meta(SnapshotKind::Checkpoint, 40),
+            meta(SnapshotKind::Auto, 30),
+            meta(SnapshotKind::Auto, 20),
+            meta(SnapshotKind::Checkpoint, 10),
+        ];
+        let removed = prune_autos(&mut entries, 2);
+        assert_eq!(removed.iter().map(|e| e.timestamp).collect::<Vec<_>>(), vec![20]);
+        assert_eq!(entries.iter().map(|e| e.timestamp).collect::<Vec<_>>(), vec![50, 40, 30, 10]);
+    }
+
+    #[test]
+    fn test_auto_timer() {
+        let mut auto = AutoSnapshot { interval: 60.0, ..Default::default() };
+        auto.reset(100.0, Some(1));
+        assert!(!auto.due(150.0));
+        assert!(auto.due(160.0));
+        assert!(!auto.changed(Some(1)));
+        assert!(auto.changed(Some(2)));
+        auto.enabled = false;
+        assert!(!auto.due(1000.0));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(100, 90), "just now");
+        assert_eq!(format_age(1000, 100), "15m ago");
+        assert_eq!(format_age(10_000, 100), "2h ago");
+        assert_eq!(format_age(200_000, 100), "2d ago");
+    }
+}
@@ -0,0 +1,394 @@
+//! Sprite sheet export
+//!
+//! Renders the current asset headlessly with the software rasterizer from a
+//! ring of camera angles across an animation's frames, packs the renders into
+//! one sheet (one row per angle, one column per frame) and quantizes it to an
+//! indexed palette. Useful for billboards/impostors of distant objects, or for
+//! promotional pixel art.
+//!
+//! Renders are orthographic and framed on the bounds of every sampled pose,
+//! so the model stays the same size and position in every cell.
+
+use crate::rasterizer::{
+    Camera, Clut, ClutDepth, Color15, Face, IndexedTexture, OrthoProjection,
+    RasterSettings, Texture, Vec3, Vertex, Framebuffer, render_mesh_with_clock,
+};
+use super::mesh_editor::{ClutPool, MeshPart};
+use super::model::{Animation, BoneTransform};
+use super::quantize::quantize_image;
+use super::skeleton::bone_world_transform;
+use super::state::{RigBone, rotate_by_euler};
+
+/// Cell sizes offered in the export dialog
+pub const SPRITE_CELL_SIZES: [usize; 5] = [16, 32, 64, 96, 128];
+/// Largest sheet edge we'll render (keeps memory and PNG size sane)
+pub const SPRITE_SHEET_MAX_EDGE: usize = 4096;
+
+/// Export options (edited in the sprite sheet dialog)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteSheetSettings {
+    /// Camera angles around the model (rows)
+    pub angles: usize,
+    /// Animation frames to sample (columns); ignored without an animation
+    pub frames: usize,
+    /// Width and height of one cell in pixels
+    pub cell_size: usize,
+    /// Camera elevation above the horizon in degrees
+    pub elevation: f32,
+    /// Palette size of the quantized sheet
+    pub depth: ClutDepth,
+}
+
+impl Default for SpriteSheetSettings {
+    fn default() -> Self {
+        Self {
+            angles: 8,
+            frames: 4,
+            cell_size: 64,
+            elevation: 20.0,
+            depth: ClutDepth::Bpp8,
+        }
+    }
+}
+
+impl SpriteSheetSettings {
+    /// Columns actually rendered (one when there is nothing to animate)
+    pub fn columns(&self, animation: Option<&Animation>) -> usize {
+        match animation {
+            Some(anim) if !anim.keyframes.is_empty() => self.frames.max(1),
+            _ => 1,
+        }
+    }
+
+    /// Clamp counts so the sheet fits within `SPRITE_SHEET_MAX_EDGE`
+    pub fn sanitize(&mut self) {
+        self.cell_size = self.cell_size.clamp(8, 256);
+        let max_cells = (SPRITE_SHEET_MAX_EDGE / self.cell_size).max(1);
+        self.angles = self.angles.clamp(1, max_cells);
+        self.frames = self.frames.clamp(1, max_cells);
+        self.elevation = self.elevation.clamp(-89.0, 89.0);
+    }
+}
+
+/// A rendered, quantized sprite sheet
+pub struct SpriteSheet {
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: usize,
+    /// Cells per row (animation frames)
+    pub columns: usize,
+    /// Rows (camera angles)
+    pub rows: usize,
+    pub indexed: IndexedTexture,
+    pub clut: Clut,
+}
+
+impl SpriteSheet {
+    /// Expand the palette indices to RGBA (index 0 = transparent)
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.indexed.indices.iter()
+            .flat_map(|&i| self.clut.lookup(i).to_rgba())
+            .collect()
+    }
+
+    /// Encode as a PNG file
+    pub fn encode_png(&self) -> Result<Vec<u8>, String> {
+        use image::ImageEncoder;
+        let mut bytes = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut bytes)
+            .write_image(&self.to_rgba(), self.width as u32, self.height as u32, image::ExtendedColorType::Rgba8)
+            .map_err(|e| format!("PNG encode failed: {}", e))?;
+        Ok(bytes)
+    }
+}
+
+/// Bones with an animation pose applied on top of the bind pose
+fn posed_bones(bones: &[RigBone], pose: &[BoneTransform]) -> Vec<RigBone> {
+    bones.iter().enumerate().map(|(i, bone)| {
+        let mut posed = bone.clone();
+        if let Some(t) = pose.get(i) {
+            posed.local_position = posed.local_position + t.position;
+            posed.local_rotation = posed.local_rotation + t.rotation;
+        }
+        posed
+    }).collect()
+}
+
+/// Triangulated geometry for all visible parts in one pose.
+/// `part_textures[i]` is the texture index of part i (None = vertex color only).
+fn posed_geometry(
+    parts: &[MeshPart],
+    part_textures: &[Option<usize>],
+    bones: &[RigBone],
+) -> (Vec<Vertex>, Vec<Face>) {
+    let bone_transforms: Vec<(Vec3, Vec3)> = (0..bones.len())
+        .map(|i| bone_world_transform(bones, i))
+        .collect();
+
+    let mut vertices = Vec::new();
+    let mut faces = Vec::new();
+    for (part, texture_id) in parts.iter().zip(part_textures) {
+        if !part.visible {
+            continue;
+        }
+        let offset = vertices.len();
+        for v in &part.mesh.vertices {
+            let bone = v.bone_index.or(part.default_bone_index)
+                .and_then(|idx| bone_transforms.get(idx))
+                .copied();
+            let (pos, normal) = match bone {
+                Some((bone_pos, bone_rot)) => (rotate_by_euler(v.pos, bone_rot) + bone_pos, rotate_by_euler(v.normal, bone_rot)),
+                None => (v.pos, v.normal),
+            };
+            vertices.push(Vertex { pos, uv: v.uv, normal, color: v.color, bone_index: None });
+        }
+        for edit_face in &part.mesh.faces {
+            for [v0, v1, v2] in edit_face.triangulate() {
+                faces.push(Face {
+                    v0: offset + v0,
+                    v1: offset + v1,
+                    v2: offset + v2,
+                    texture_id: *texture_id,
+                    black_transparent: edit_face.black_transparent,
+                    blend_mode: edit_face.blend_mode,
+                    editor_alpha: 255,
+                });
+                if part.double_sided {
+                    faces.push(Face {
+                        v0: offset + v0,
+                        v1: offset + v2,
+                        v2: offset + v1,
+                        texture_id: *texture_id,
+                        black_transparent: edit_face.black_transparent,
+                        blend_mode: edit_face.blend_mode,
+                        editor_alpha: 255,
+                    });
+                }
+            }
+        }
+    }
+    (vertices, faces)
+}
+
+/// Orthographic camera orbiting `center`, looking at it from `yaw` around the
+/// Y axis and `pitch` above the horizon (radians). Angle 0 faces the model's
+/// front (camera on +Z, like the Front ortho view).
+fn orbit_camera(center: Vec3, distance: f32, yaw: f32, pitch: f32) -> Camera {
+    let mut camera = Camera::new();
+    camera.rotation_x = pitch;
+    camera.rotation_y = std::f32::consts::PI + yaw;
+    camera.update_basis();
+    camera.position = center - camera.basis_z * distance;
+    // project_ortho flips Y itself, so the up vector must point up
+    camera.basis_y = camera.basis_y * -1.0;
+    camera
+}
+
+/// Render `parts` (posed by `bones` and the optional animation) into a sprite sheet.
+/// `clock` times the render passes; pass macroquad's `get_time` from the app.
+pub fn render_sprite_sheet(
+    parts: &[MeshPart],
+    bones: &[RigBone],
+    clut_pool: &ClutPool,
+    animation: Option<&Animation>,
+    settings: &SpriteSheetSettings,
+    raster: &RasterSettings,
+    clock: fn() -> f64,
+) -> SpriteSheet {
+    let mut settings = settings.clone();
+    settings.sanitize();
+    let columns = settings.columns(animation);
+    let rows = settings.angles;
+    let cell = settings.cell_size;
+
+    // Textures once, shared by every pose
+    let fallback_clut = clut_pool.first_id().and_then(|id| clut_pool.get(id));
+    let mut textures: Vec<Texture> = Vec::new();
+    let part_textures: Vec<Option<usize>> = parts.iter().enumerate().map(|(i, part)| {
+        let clut = if part.atlas.default_clut.is_valid() {
+            clut_pool.get(part.atlas.default_clut).or(fallback_clut)
+        } else {
+            fallback_clut
+        };
+        clut.map(|clut| {
+            textures.push(part.atlas.to_raster_texture(clut, &format!("sprite_atlas_{}", i)));
+            textures.len() - 1
+        })
+    }).collect();
+
+    // Pose every frame up front so the framing covers the whole animation
+    let poses: Vec<(Vec<Vertex>, Vec<Face>)> = (0..columns).map(|col| {
+        let pose = match animation {
+            Some(anim) if columns > 1 => {
+                let last = anim.last_frame() as f32;
+                // Looping clips wrap back to the first frame, so don't repeat it
+                let span = if anim.looping { columns } else { columns - 1 };
+                anim.sample(last * col as f32 / span.max(1) as f32, bones.len())
+            }
+            Some(anim) => anim.sample(0.0, bones.len()),
+            None => Vec::new(),
+        };
+        posed_geometry(parts, &part_textures, &posed_bones(bones, &pose))
+    }).collect();
+
+    let mut min = Vec3::new(f32::MAX, f32::MAX, f32::MAX);
+    let mut max = Vec3::new(f32::MIN, f32::MIN, f32::MIN);
+    for v in poses.iter().flat_map(|(verts, _)| verts.iter()) {
+        min = Vec3::new(min.x.min(v.pos.x), min.y.min(v.pos.y), min.z.min(v.pos.z));
+        max = Vec3::new(max.x.max(v.pos.x), max.y.max(v.pos.y), max.z.max(v.pos.z));
+    }
+    let (center, radius) = if min.x <= max.x {
+        let center = (min + max) * 0.5;
+        (center, ((max - min) * 0.5).len().max(1.0))
+    } else {
+        (Vec3::ZERO, 1.0)
+    };
+
+    let mut raster = raster.clone();
+    raster.ortho_projection = Some(OrthoProjection {
+        // Leave a pixel of margin so silhouettes don't touch the cell edge
+        zoom: (cell as f32 * 0.5 - 1.0).max(1.0) / radius,
+        center_x: 0.0,
+        center_y: 0.0,
+    });
+    raster.use_rgb555 = false;
+    raster.backface_wireframe = false;
+    raster.wireframe_overlay = false;
+    raster.xray_mode = false;
+    raster.low_resolution = false;
+
+    let width = columns * cell;
+    let height = rows * cell;
+    let mut rgba = vec![0u8; width * height * 4];
+    let mut fb = Framebuffer::new(cell, cell);
+    for row in 0..rows {
+        let yaw = std::f32::consts::TAU * row as f32 / rows as f32;
+        let camera = orbit_camera(center, radius * 4.0, yaw, settings.elevation.to_radians());
+        for (col, (vertices, faces)) in poses.iter().enumerate() {
+            fb.clear_transparent();
+            render_mesh_with_clock(&mut fb, vertices, faces, &textures, &camera, &raster, clock);
+            for y in 0..cell {
+                let src = y * cell * 4;
+                let dst = ((row * cell + y) * width + col * cell) * 4;
+                rgba[dst..dst + cell * 4].copy_from_slice(&fb.pixels[src..src + cell * 4]);
+            }
+        }
+    }
+
+    let quantized = quantize_image(&rgba, width, height, settings.depth, "sprite_sheet");
+    SpriteSheet {
+        width,
+        height,
+        cell_size: cell,
+        columns,
+        rows,
+        indexed: quantized.texture,
+        clut: quantized.clut,
+    }
+}
+
+/// Colors used by a sheet, excluding transparency (for the status message)
+pub fn sprite_sheet_color_count(sheet: &SpriteSheet) -> usize {
+    let mut used = vec![false; sheet.clut.len()];
+    for &i in &sheet.indexed.indices {
+        if let Some(slot) = used.get_mut(i as usize) {
+            *slot = true;
+        }
+    }
+    used.iter().enumerate()
+        .filter(|&(i, &u)| u && sheet.clut.colors.get(i).is_some_and(|c: &Color15| !c.is_transparent()))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::model::Keyframe;
+
+    fn gray_pool() -> ClutPool {
+        let mut pool = ClutPool::new();
+        let id = pool.first_id().unwrap();
+        let clut = pool.get_mut(id).unwrap();
+        for color in clut.colors.iter_mut() {
+            *color = Color15::from_rgb888(160, 160, 160);
+        }
+        pool
+    }
+
+    fn cell_has_pixels(sheet: &SpriteSheet, row: usize, col: usize) -> bool {
+        let cell = sheet.cell_size;
+        (0..cell).any(|y| (0..cell).any(|x| {
+            let idx = (row * cell + y) * sheet.width + col * cell + x;
+            sheet.indexed.indices[idx] != 0
+        }))
+    }
+
+    #[test]
+    fn test_sheet_layout_and_every_cell_rendered() {
+        let parts = vec![MeshPart::cube("box", 512.0)];
+        let settings = SpriteSheetSettings { angles: 4, frames: 3, cell_size: 16, ..Default::default() };
+        let raster = RasterSettings { multithreaded: false, ..Default::default() };
+        let clock = || 0.0;
+
+        // No animation: one column per angle
+        let sheet = render_sprite_sheet(&parts, &[], &gray_pool(), None, &settings, &raster, clock);
+        assert_eq!((sheet.columns, sheet.rows), (1, 4));
+        assert_eq!((sheet.width, sheet.height), (16, 64));
+        for row in 0..4 {
+            assert!(cell_has_pixels(&sheet, row, 0), "angle {} is empty", row);
+        }
+
+        // With a clip: one column per sampled frame
+        let mut anim = Animation::new("Spin");
+        anim.set_keyframe(Keyframe::new(0, 0));
+        anim.set_keyframe(Keyframe::new(10, 0));
+        let sheet = render_sprite_sheet(&parts, &[], &gray_pool(), Some(&anim), &settings, &raster, clock);
+        assert_eq!((sheet.columns, sheet.rows), (3, 4));
+        assert_eq!((sheet.width, sheet.height), (48, 64));
+        for row in 0..4 {
+            for col in 0..3 {
+                assert!(cell_has_pixels(&sheet, row, col), "cell ({}, {}) is empty", row, col);
+            }
+        }
+        assert_eq!(sheet.to_rgba().len(), 48 * 64 * 4);
+    }
+
+    #[test]
+    fn test_sheet_layout() {
+        let settings = SpriteSheetSettings { angles: 4, frames: 3, cell_size: 16, ..Default::default() };
+
+        // No animation (or a clip without keys): a single column
+        assert_eq!(settings.columns(None), 1);
+        assert_eq!(settings.columns(Some(&Animation::new("Empty"))), 1);
+
+        // With a clip: one column per sampled frame
+        let mut anim = Animation::new("Spin");
+        anim.set_keyframe(Keyframe::new(0, 0));
+        anim.set_keyframe(Keyframe::new(10, 0));
+        assert_eq!(settings.columns(Some(&anim)), 3);
+    }
+
+    #[test]
+    fn test_sanitize_caps_sheet_size() {
+        let mut settings = SpriteSheetSettings { angles: 1000, frames: 0, cell_size: 128, ..Default::default() };
+        settings.sanitize();
+        assert_eq!(settings.angles, SPRITE_SHEET_MAX_EDGE / 128);
+        assert_eq!(settings.frames, 1);
+    }
+
+    #[test]
+    fn test_animation_sample_interpolates() {
+        let mut anim = Animation::new("Wave");
+        let mut a = Keyframe::new(0, 1);
+        a.transforms[0].rotation = Vec3::new(0.0, 0.0, 0.0);
+        let mut b = Keyframe::new(10, 1);
+        b.transforms[0].rotation = Vec3::new(90.0, 0.0, 0.0);
+        anim.set_keyframe(a);
+        anim.set_keyframe(b);
+
+        assert!((anim.sample(5.0, 1)[0].rotation.x - 45.0).abs() < 1e-4);
+        assert_eq!(anim.sample(20.0, 1)[0].rotation.x, 90.0);
+        // Bones the keyframes don't cover stay at rest
+        assert_eq!(anim.sample(5.0, 2)[1].rotation.x, 0.0);
+    }
+}
@@ -8,7 +8,7 @@
 use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 use crate::rasterizer::{Camera, Vec2, Vec3, Color, RasterSettings, BlendMode, Color15, Clut, ClutId};
-use crate::texture::{TextureLibrary, TextureEditorState, UserTexture};
+use crate::texture::{TextureLibrary, TextureEditorState, TextureLayer, UserTexture};
 use crate::asset::Asset;
 use crate::ui::{TextInputState, DropdownState};
 use super::mesh_editor::{
@@ -155,6 +155,11 @@ impl ViewportId {
     pub fn is_ortho(&self) -> bool {
         !matches!(self, ViewportId::Perspective)
     }
+
+    /// Position in `ALL`
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
 }
 
 /// Which panel has keyboard focus (for routing shortcuts)
@@ -462,6 +467,71 @@ pub enum ModelerSelection {
     Bones(Vec<usize>),
     /// Skeleton mode: selected bone tips (G changes direction/length)
     BoneTips(Vec<usize>),
+    /// Vertices and whole parts spread over several objects, transformed together
+    Group(Vec<GroupMember>),
+}
+
+/// One object's share of a `ModelerSelection::Group`
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupMember {
+    /// Every vertex of the object
+    Part(usize),
+    /// Some vertices of the object
+    Vertices { object: usize, vertices: Vec<usize> },
+}
+
+impl GroupMember {
+    /// Index of the object this member belongs to
+    pub fn object(&self) -> usize {
+        match self {
+            GroupMember::Part(object) => *object,
+            GroupMember::Vertices { object, .. } => *object,
+        }
+    }
+
+    /// Vertex indices of this member in its object's mesh
+    pub fn vertex_indices(&self, mesh: &EditableMesh) -> Vec<usize> {
+        match self {
+            GroupMember::Part(_) => (0..mesh.vertices.len()).collect(),
+            GroupMember::Vertices { vertices, .. } => vertices.clone(),
+        }
+    }
+}
+
+/// Add `member` to a group, merging with an existing member of the same object
+/// (a whole part absorbs any vertices of that part).
+pub fn merge_group_member(members: &mut Vec<GroupMember>, member: GroupMember) {
+    let object = member.object();
+    let Some(existing) = members.iter_mut().find(|m| m.object() == object) else {
+        members.push(member);
+        return;
+    };
+    match (existing, member) {
+        (GroupMember::Part(_), _) => {}
+        (existing, GroupMember::Part(_)) => *existing = GroupMember::Part(object),
+        (GroupMember::Vertices { vertices, .. }, GroupMember::Vertices { vertices: added, .. }) => {
+            for v in added {
+                if !vertices.contains(&v) {
+                    vertices.push(v);
+                }
+            }
+        }
+    }
+}
+
+/// Bits of a group drag key that hold the vertex index
+const GROUP_KEY_VERTEX_BITS: u32 = 20;
+
+/// Drag key for a vertex of a group selection.
+/// Drag trackers only carry one index per vertex, so group drags pack the
+/// object index above the vertex index.
+pub fn group_vertex_key(object: usize, vertex: usize) -> usize {
+    (object << GROUP_KEY_VERTEX_BITS) | vertex
+}
+
+/// Split a `group_vertex_key` back into (object, vertex)
+pub fn split_group_vertex_key(key: usize) -> (usize, usize) {
+    (key >> GROUP_KEY_VERTEX_BITS, key & ((1 << GROUP_KEY_VERTEX_BITS) - 1))
 }
 
 impl ModelerSelection {
@@ -474,6 +544,7 @@ impl ModelerSelection {
             ModelerSelection::Faces(v) => v.is_empty(),
             ModelerSelection::Bones(v) => v.is_empty(),
             ModelerSelection::BoneTips(v) => v.is_empty(),
+            ModelerSelection::Group(m) => m.is_empty(),
         }
     }
 
@@ -493,6 +564,35 @@ impl ModelerSelection {
         }
     }
 
+    /// Get group members if this selection spans several objects
+    pub fn group(&self) -> Option<&[GroupMember]> {
+        match self {
+            ModelerSelection::Group(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// Check if this is a group selection
+    pub fn is_group(&self) -> bool {
+        matches!(self, ModelerSelection::Group(_))
+    }
+
+    /// This selection as group members of `object` (empty for bone selections)
+    pub fn to_group_members(&self, object: usize, mesh: &EditableMesh) -> Vec<GroupMember> {
+        match self {
+            ModelerSelection::Group(members) => members.clone(),
+            ModelerSelection::Mesh => vec![GroupMember::Part(object)],
+            _ => {
+                let vertices = self.get_affected_vertex_indices(mesh);
+                if vertices.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![GroupMember::Vertices { object, vertices }]
+                }
+            }
+        }
+    }
+
     /// Check if this is a bone base or tip selection
     pub fn is_bone_selection(&self) -> bool {
         matches!(self, ModelerSelection::Bones(_) | ModelerSelection::BoneTips(_))
@@ -530,9 +630,11 @@ impl ModelerSelection {
     /// For edges, returns both vertices of each edge
     /// For faces, returns all vertices of each face
     /// For bones/tips, returns empty (bones don't affect mesh vertices)
+    /// For groups, returns empty (indices span several meshes, see `ModelerState::transform_targets`)
     pub fn get_affected_vertex_indices(&self, mesh: &EditableMesh) -> Vec<usize> {
         match self {
-            ModelerSelection::None | ModelerSelection::Mesh | ModelerSelection::Bones(_) | ModelerSelection::BoneTips(_) => Vec::new(),
+            ModelerSelection::None | ModelerSelection::Mesh | ModelerSelection::Bones(_) | ModelerSelection::BoneTips(_)
+            | ModelerSelection::Group(_) => Vec::new(),
             ModelerSelection::Vertices(verts) => verts.clone(),
             ModelerSelection::Edges(edges) => {
                 let mut indices: Vec<usize> = edges.iter()
@@ -564,6 +666,7 @@ impl ModelerSelection {
             ModelerSelection::Faces(f) => format!("Faces({:?})", f),
             ModelerSelection::Bones(b) => format!("Bones({:?})", b),
             ModelerSelection::BoneTips(t) => format!("BoneTips({:?})", t),
+            ModelerSelection::Group(m) => format!("Group({} parts)", m.len()),
         }
     }
 
@@ -614,6 +717,43 @@ impl TransformOrientation {
     }
 }
 
+/// Pivot for rotating and scaling a selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PivotMode {
+    /// Everything turns about the average of the selected vertices
+    #[default]
+    Median,
+    /// Each part of a group selection turns about its own center
+    IndividualOrigins,
+}
+
+impl PivotMode {
+    pub fn toggle(&self) -> Self {
+        match self {
+            PivotMode::Median => PivotMode::IndividualOrigins,
+            PivotMode::IndividualOrigins => PivotMode::Median,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PivotMode::Median => "Median",
+            PivotMode::IndividualOrigins => "Individual Origins",
+        }
+    }
+}
+
+/// Vertices a move/rotate/scale drag works on, ready for the `DragManager`
+#[derive(Debug, Clone, Default)]
+pub struct TransformTargets {
+    /// Drag keys: vertex indices, or `group_vertex_key`s for group selections
+    pub indices: Vec<usize>,
+    /// Starting position for each key
+    pub initial_positions: Vec<(usize, Vec3)>,
+    /// Pivot for each entry of `initial_positions` (empty = the drag center)
+    pub origins: Vec<Vec3>,
+}
+
 /// Modal transform mode (Blender-style G/S/R)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ModalTransform {
@@ -704,6 +844,24 @@ impl Axis {
             Axis::Z => Vec3::new(0.0, 0.0, 1.0),
         }
     }
+
+    /// This axis' coordinate of a position
+    pub fn coord(&self, pos: Vec3) -> f32 {
+        match self {
+            Axis::X => pos.x,
+            Axis::Y => pos.y,
+            Axis::Z => pos.z,
+        }
+    }
+
+    /// Reflect a position across the plane through the origin perpendicular to this axis
+    pub fn mirror(&self, pos: Vec3) -> Vec3 {
+        match self {
+            Axis::X => Vec3::new(-pos.x, pos.y, pos.z),
+            Axis::Y => Vec3::new(pos.x, -pos.y, pos.z),
+            Axis::Z => Vec3::new(pos.x, pos.y, -pos.z),
+        }
+    }
 }
 
 /// Gizmo handle types - single axis or plane (two axes)
@@ -849,6 +1007,17 @@ impl MirrorSettings {
     }
 }
 
+/// Distance within which a vertex counts as another's mirror image, or as on the symmetry plane
+pub const SYMMETRY_EPSILON: f32 = 1.0;
+
+/// Dragged vertex -> mirror partner pairs for one drag on one object
+#[derive(Debug, Clone)]
+struct SymmetryLink {
+    object: usize,
+    vertex_count: usize,
+    pairs: Vec<(usize, usize)>,
+}
+
 /// Clipboard for copy/paste operations
 /// Stores geometry that can be pasted as a new object
 #[derive(Clone, Debug, Default)]
@@ -974,17 +1143,24 @@ pub struct ModelerState {
 
     // File state
     pub current_file: Option<PathBuf>,
+    /// Timer for automatic version-history snapshots of the open model
+    pub auto_snapshot: super::snapshots::AutoSnapshot,
 
     // View/edit state
     pub select_mode: SelectMode,
     pub selection: ModelerSelection,
     /// Transform gizmo orientation (Global = world axes, Local = selection normal)
     pub transform_orientation: TransformOrientation,
+    /// Rotate/scale pivot for group selections
+    pub pivot_mode: PivotMode,
 
     // Camera (free or orbit mode for perspective view)
     pub camera: Camera,
     pub camera_mode: CameraMode,
     pub raster_settings: RasterSettings,
+    /// GPU copies of the viewport framebuffers, one per `ViewportId`
+    /// (the panels share a framebuffer but each needs its own texture)
+    pub viewport_textures: [crate::rasterizer::FramebufferTexture; 4],
     pub orbit_target: Vec3,      // Point the camera orbits around (orbit mode)
     pub orbit_distance: f32,     // Distance from target (orbit mode)
     pub orbit_azimuth: f32,      // Horizontal angle in radians (orbit mode)
@@ -1019,6 +1195,7 @@ pub struct ModelerState {
     pub paint_texture_scroll: f32,    // Scroll position in paint texture browser
     pub paint_samples_collapsed: bool,  // SAMPLES section collapsed state
     pub paint_user_collapsed: bool,     // MY TEXTURES section collapsed state
+    pub texture_tag_filter: crate::tags::TagFilter, // Tag filter for the texture browser
 
     // Component management UI
     pub selected_component: Option<usize>,      // Index in asset.components for editing
@@ -1042,6 +1219,7 @@ pub struct ModelerState {
     pub component_gizmo_drag_axis: Option<Axis>,      // Which axis is being dragged
     pub component_gizmo_drag_start: Option<(f32, f32)>, // Starting mouse position
     pub component_gizmo_start_offset: [f32; 3],       // Starting offset value
+    pub component_gizmo_start_extent: f32,            // Starting collision handle extent (Scale tool)
     pub component_gizmo_drag_viewport: Option<ViewportId>, // Which viewport owns the drag
 
     // CLUT editing state
@@ -1165,6 +1343,11 @@ pub struct ModelerState {
     // Unified drag manager (new system - replaces scattered gizmo_drag_* fields)
     pub drag_manager: DragManager,
 
+    /// Symmetry plane for live mirrored editing (None = off)
+    pub symmetry: Option<Axis>,
+    /// Mirror partners of the vertices being dragged (resolved on the first drag frame)
+    symmetry_link: Option<SymmetryLink>,
+
     // Tool system (TrenchBroom-inspired)
     pub tool_box: ModelerToolBox,
 
@@ -1187,6 +1370,9 @@ pub struct ModelerState {
     // Texture pending deletion (shows confirmation dialog when Some)
     pub texture_pending_delete: Option<String>,
 
+    // Files still using the texture pending deletion (listed in the dialog)
+    pub texture_delete_usages: Option<crate::project::UsagesPanel>,
+
     // Thumbnail size for paint texture grid (32, 48, 64, 96)
     pub paint_thumb_size: f32,
 
@@ -1203,6 +1389,16 @@ pub struct ModelerState {
     // Object delete confirmation dialog (object index)
     pub delete_dialog: Option<usize>,
 
+    // Checkpoint name dialog (version history snapshot)
+    pub checkpoint_dialog: Option<TextInputState>,
+    // Confirmed checkpoint label, saved by the main loop (needs storage)
+    pub pending_checkpoint: Option<String>,
+
+    // Sprite sheet export dialog (settings being edited)
+    pub sprite_sheet_dialog: Option<super::sprite_sheet::SpriteSheetSettings>,
+    // Last confirmed sprite sheet settings (dialog starts from these)
+    pub sprite_sheet_settings: super::sprite_sheet::SpriteSheetSettings,
+
     // Unsaved texture changes - pending object switch (shows save/discard dialog)
     pub unsaved_texture_pending_switch: Option<usize>,
 
@@ -1211,6 +1407,21 @@ pub struct ModelerState {
 
     // Light component RGB slider being dragged (0=R, 1=G, 2=B)
     pub light_color_slider: Option<usize>,
+
+    // Component text field being edited (field id, input), e.g. a trigger id
+    pub component_field_edit: Option<(usize, TextInputState)>,
+
+    // Dialogue component node selected in its outline
+    pub dialogue_node: usize,
+
+    // Particle component preview (created when the panel is first drawn)
+    pub particle_preview: Option<ParticlePreview>,
+}
+
+/// Running emitter and framebuffer behind the Particle component's preview
+pub struct ParticlePreview {
+    pub emitter: crate::game::particles::ParticleEmitter,
+    pub fb: crate::rasterizer::Framebuffer,
 }
 
 /// Type of context menu being displayed (auto-detected from selection)
@@ -1281,10 +1492,11 @@ pub enum UndoEvent {
     },
     /// Selection change only
     Selection(ModelerSelection),
-    /// Texture paint edit (pixel indices, palette)
+    /// Texture paint edit (pixel indices, palette, paint layers)
     Texture {
         indices: Vec<u8>,
         palette: Vec<Color15>,
+        layers: Vec<TextureLayer>,
     },
     /// Skeleton edit (bone transforms)
     Skeleton {
@@ -1343,14 +1555,17 @@ impl ModelerState {
             preview_clut: None,
 
             current_file: None,
+            auto_snapshot: super::snapshots::AutoSnapshot::default(),
 
             select_mode: SelectMode::Face, // PicoCAD: face-centric
             selection: ModelerSelection::None,
             transform_orientation: TransformOrientation::Global,
+            pivot_mode: PivotMode::Median,
 
             camera,
             camera_mode: CameraMode::Free, // Default to free camera (like world editor)
             raster_settings: RasterSettings::modeler(), // Ambient lighting, placed lights shine through
+            viewport_textures: Default::default(),
             orbit_target,
             orbit_distance,
             orbit_azimuth,
@@ -1384,6 +1599,7 @@ impl ModelerState {
             paint_texture_scroll: 0.0,
             paint_samples_collapsed: false,
             paint_user_collapsed: false,
+            texture_tag_filter: crate::tags::TagFilter::default(),
 
             // Component management UI
             selected_component: None,
@@ -1400,6 +1616,7 @@ impl ModelerState {
             component_gizmo_drag_axis: None,
             component_gizmo_drag_start: None,
             component_gizmo_start_offset: [0.0, 0.0, 0.0],
+            component_gizmo_start_extent: 0.0,
             component_gizmo_drag_viewport: None,
 
             // CLUT editing defaults
@@ -1474,6 +1691,8 @@ impl ModelerState {
             radial_menu: super::radial_menu::RadialMenuState::new(),
 
             drag_manager: DragManager::new(),
+            symmetry: None,
+            symmetry_link: None,
 
             tool_box: ModelerToolBox::new(),
 
@@ -1484,6 +1703,7 @@ impl ModelerState {
             editing_texture,
             selected_user_texture,
             texture_pending_delete: None,
+            texture_delete_usages: None,
             paint_thumb_size: 64.0,  // Default thumbnail size
             pending_user_texture_list: None,
             pending_texture_loads: Vec::new(),
@@ -1491,9 +1711,16 @@ impl ModelerState {
 
             rename_dialog: None,
             delete_dialog: None,
+            checkpoint_dialog: None,
+            pending_checkpoint: None,
+            sprite_sheet_dialog: None,
+            sprite_sheet_settings: Default::default(),
             unsaved_texture_pending_switch: None,
             ambient_slider_active: false,
             light_color_slider: None,
+            component_field_edit: None,
+            dialogue_node: 0,
+            particle_preview: None,
         }
     }
 
@@ -1574,6 +1801,7 @@ impl ModelerState {
     pub fn set_status(&mut self, message: &str, duration_secs: f64) {
         let expiry = macroquad::time::get_time() + duration_secs;
         self.status_message = Some((message.to_string(), expiry));
+        crate::crash::record(message);
     }
 
     /// Get current status message if not expired
@@ -1688,6 +1916,46 @@ impl ModelerState {
         self.objects_mut()?.get_mut(idx).map(|obj| &mut obj.mesh)
     }
 
+    /// Keep the mirror side in step with the vertices being dragged.
+    ///
+    /// Call after anything that moves vertices through the drag manager.
+    /// Partners are found from the drag's starting positions on the first
+    /// frame; once the drag ends (or is cancelled and restored) they get one
+    /// last update and the link is dropped.
+    pub fn sync_symmetry(&mut self) {
+        let Some(axis) = self.symmetry else {
+            self.symmetry_link = None;
+            return;
+        };
+        let Some(object) = self.selected_object.filter(|_| !self.selection.is_group()) else {
+            // Group drags span several objects and aren't mirrored
+            self.symmetry_link = None;
+            return;
+        };
+        let dragging = self.drag_manager.is_dragging();
+        if dragging && self.symmetry_link.is_none() {
+            if let Some(initial) = self.drag_manager.initial_positions() {
+                let pairs = self.mesh().symmetry_partners(initial, axis, SYMMETRY_EPSILON);
+                let vertex_count = self.mesh().vertices.len();
+                self.symmetry_link = Some(SymmetryLink { object, vertex_count, pairs });
+            }
+        }
+        let Some(link) = self.symmetry_link.take() else { return };
+        if let Some(mesh) = self.mesh_mut().filter(|m| link.object == object && m.vertices.len() == link.vertex_count) {
+            for &(from, to) in &link.pairs {
+                let pos = mesh.vertices[from].pos;
+                if from == to {
+                    mesh.vertices[to].pos = pos - axis.to_vec3() * axis.coord(pos);
+                } else {
+                    mesh.vertices[to].pos = axis.mirror(pos);
+                }
+            }
+        }
+        if dragging {
+            self.symmetry_link = Some(link);
+        }
+    }
+
     /// Toggle interaction mode (Object <-> Edit)
     pub fn toggle_interaction_mode(&mut self) {
         self.interaction_mode = self.interaction_mode.toggle();
@@ -1707,6 +1975,24 @@ impl ModelerState {
         self.set_status("New mesh", 1.0);
     }
 
+    /// Open the sprite sheet export dialog with the last used settings
+    pub fn open_sprite_sheet_dialog(&mut self) {
+        if self.objects().iter().any(|obj| obj.visible && !obj.mesh.faces.is_empty()) {
+            self.sprite_sheet_dialog = Some(self.sprite_sheet_settings.clone());
+        } else {
+            self.set_status("Nothing to render - add a visible mesh first", 2.0);
+        }
+    }
+
+    /// Ask for a checkpoint name (the model needs a file to attach its history to)
+    pub fn open_checkpoint_dialog(&mut self) {
+        if self.current_file.is_some() {
+            self.checkpoint_dialog = Some(TextInputState::new(""));
+        } else {
+            self.set_status("Save the model before adding a checkpoint", 2.0);
+        }
+    }
+
     /// Save asset to file (includes mesh + components)
     #[cfg(not(target_arch = "wasm32"))]
     pub fn save_project(&mut self, path: &std::path::Path) -> Result<(), String> {
@@ -1722,8 +2008,11 @@ impl ModelerState {
     /// Load asset from file (includes mesh + components)
     #[cfg(not(target_arch = "wasm32"))]
     pub fn load_project(&mut self, path: &std::path::Path) -> Result<(), String> {
-        let asset = Asset::load(path)
+        let (asset, issues) = Asset::load_repaired(path)
             .map_err(|e| format!("{}", e))?;
+        for issue in &issues {
+            eprintln!("Repaired {}: {}", path.display(), issue);
+        }
         self.asset = asset;
         self.selected_object = if self.objects().is_empty() { None } else { Some(0) };
         // Resolve ID-based texture references using the texture library
@@ -1731,7 +2020,11 @@ impl ModelerState {
         self.current_file = Some(path.to_path_buf());
         self.selection.clear();
         self.dirty = false;
-        self.set_status(&format!("Loaded: {}", path.display()), 2.0);
+        if issues.is_empty() {
+            self.set_status(&format!("Loaded: {}", path.display()), 2.0);
+        } else {
+            self.set_status(&format!("Loaded: {} (repaired {} problems, see log)", path.display(), issues.len()), 4.0);
+        }
         Ok(())
     }
 
@@ -1780,6 +2073,59 @@ impl ModelerState {
         }
     }
 
+    /// Pack every library texture the asset's parts use (mesh, LOD and broken
+    /// meshes) into one atlas, add it to the library and point the parts at it.
+    ///
+    /// Returns the atlas texture's name; the caller saves it.
+    pub fn pack_textures_into_atlas(&mut self) -> Result<String, String> {
+        use crate::asset::AssetComponent;
+        use crate::texture::{build_atlas, remap_parts};
+
+        let mut ids: Vec<u64> = Vec::new();
+        for component in &self.asset.components {
+            let lists: Vec<&Vec<MeshPart>> = match component {
+                AssetComponent::Mesh { parts } => vec![parts],
+                AssetComponent::Lod { reduced, impostor, .. } => vec![reduced, impostor],
+                AssetComponent::Destructible { broken, .. } => vec![broken],
+                _ => Vec::new(),
+            };
+            for part in lists.into_iter().flatten() {
+                if let TextureRef::Id(id) = &part.texture_ref {
+                    if !ids.contains(id) && self.user_textures.get_by_id(*id).is_some() {
+                        ids.push(*id);
+                    }
+                }
+            }
+        }
+        if ids.len() < 2 {
+            return Err("Nothing to pack (the asset uses fewer than 2 textures)".to_string());
+        }
+
+        let textures: Vec<&UserTexture> = ids.iter().filter_map(|&id| self.user_textures.get_by_id(id)).collect();
+        let name = self.user_textures.generate_unique_name(&format!("{}_atlas", self.asset.name));
+        let atlas = build_atlas(&name, &textures)?;
+
+        for component in &mut self.asset.components {
+            match component {
+                AssetComponent::Mesh { parts } => {
+                    remap_parts(parts, &atlas);
+                }
+                AssetComponent::Lod { reduced, impostor, .. } => {
+                    remap_parts(reduced, &atlas);
+                    remap_parts(impostor, &atlas);
+                }
+                AssetComponent::Destructible { broken, .. } => {
+                    remap_parts(broken, &atlas);
+                }
+                _ => {}
+            }
+        }
+        self.user_textures.add(atlas.texture);
+        self.resolve_all_texture_refs();
+        self.dirty = true;
+        Ok(name)
+    }
+
     // ========================================================================
     // Asset Helpers
     // ========================================================================
@@ -2080,6 +2426,7 @@ impl ModelerState {
             self.undo_stack.push(UndoEvent::Texture {
                 indices: tex.indices.clone(),
                 palette: tex.palette.clone(),
+                layers: tex.layers.clone(),
             });
             self.redo_stack.clear();
             self.texture_editor.dirty = true;
@@ -2150,18 +2497,20 @@ impl ModelerState {
                     self.selection = prev_sel;
                     self.set_status("Undo selection", 1.0);
                 }
-                UndoEvent::Texture { indices, palette } => {
+                UndoEvent::Texture { indices, palette, layers } => {
                     // Save current state to redo stack
                     if let Some(ref tex) = self.editing_texture {
                         self.redo_stack.push(UndoEvent::Texture {
                             indices: tex.indices.clone(),
                             palette: tex.palette.clone(),
+                            layers: tex.layers.clone(),
                         });
                     }
                     // Restore previous state
                     if let Some(ref mut tex) = self.editing_texture {
                         tex.indices = indices;
                         tex.palette = palette;
+                        tex.layers = layers;
                     }
                     self.set_status("Undo paint", 1.0);
                 }
@@ -2229,18 +2578,20 @@ impl ModelerState {
                     self.selection = next_sel;
                     self.set_status("Redo selection", 1.0);
                 }
-                UndoEvent::Texture { indices, palette } => {
+                UndoEvent::Texture { indices, palette, layers } => {
                     // Save current state to undo stack
                     if let Some(ref tex) = self.editing_texture {
                         self.undo_stack.push(UndoEvent::Texture {
                             indices: tex.indices.clone(),
                             palette: tex.palette.clone(),
+                            layers: tex.layers.clone(),
                         });
                     }
                     // Apply redo state
                     if let Some(ref mut tex) = self.editing_texture {
                         tex.indices = indices;
                         tex.palette = palette;
+                        tex.layers = layers;
                     }
                     self.set_status("Redo paint", 1.0);
                 }
@@ -2298,6 +2649,31 @@ impl ModelerState {
         self.set_selection(ModelerSelection::None);
     }
 
+    /// Add a whole part to a group selection, or drop it if already there.
+    /// A plain selection in the selected object becomes the group's first member.
+    pub fn toggle_group_part(&mut self, index: usize) {
+        let unlocked = self.objects().get(index).is_some_and(|obj| !obj.locked);
+        if !unlocked {
+            return;
+        }
+        let mut members = match self.selected_object {
+            Some(object) => self.selection.to_group_members(object, self.mesh()),
+            None => Vec::new(),
+        };
+        if let Some(pos) = members.iter().position(|m| *m == GroupMember::Part(index)) {
+            members.remove(pos);
+        } else {
+            merge_group_member(&mut members, GroupMember::Part(index));
+        }
+        let count = members.len();
+        if members.is_empty() {
+            self.set_selection(ModelerSelection::None);
+        } else {
+            self.set_selection(ModelerSelection::Group(members));
+        }
+        self.set_status(&format!("Group: {} part(s)", count), 1.0);
+    }
+
     // ========================================================================
     // Skeleton/Bone Operations (TR-style: fixed offsets, not animated)
     // Bones are stored in AssetComponent::Skeleton, accessed via asset.skeleton()
@@ -2346,6 +2722,15 @@ impl ModelerState {
                 let sum: Vec3 = positions.iter().fold(Vec3::ZERO, |acc, &p| acc + p);
                 Some(sum * (1.0 / positions.len() as f32))
             }
+            ModelerSelection::Group(_) => {
+                // Group selection - average of every member vertex (mesh space)
+                let targets = self.transform_targets();
+                if targets.initial_positions.is_empty() {
+                    return None;
+                }
+                let sum = targets.initial_positions.iter().fold(Vec3::ZERO, |acc, (_, p)| acc + *p);
+                Some(sum * (1.0 / targets.initial_positions.len() as f32))
+            }
             _ => {
                 // Mesh element selection (vertices, edges, faces)
                 // Apply bone transform if the selected object is bound to a bone
@@ -2375,6 +2760,74 @@ impl ModelerState {
         }
     }
 
+    /// Vertices the current mesh selection transforms, for starting a drag.
+    ///
+    /// Coincident vertices are pulled in when vertex linking is on. Group
+    /// selections key their vertices with `group_vertex_key` and, with
+    /// individual origins, pivot each part about its own center.
+    pub fn transform_targets(&self) -> TransformTargets {
+        let Some(members) = self.selection.group() else {
+            let mesh = self.mesh();
+            let mut indices = self.selection.get_affected_vertex_indices(mesh);
+            if self.vertex_linking {
+                indices = mesh.expand_to_coincident(&indices, 0.001);
+            }
+            let initial_positions = indices.iter()
+                .filter_map(|&idx| mesh.vertices.get(idx).map(|v| (idx, v.pos)))
+                .collect();
+            return TransformTargets { indices, initial_positions, origins: Vec::new() };
+        };
+
+        let mut targets = TransformTargets::default();
+        for member in members {
+            let object = member.object();
+            let Some(mesh) = self.objects().get(object).map(|o| &o.mesh) else { continue };
+            let mut vertices = member.vertex_indices(mesh);
+            if self.vertex_linking {
+                vertices = mesh.expand_to_coincident(&vertices, 0.001);
+            }
+            let positions: Vec<(usize, Vec3)> = vertices.iter()
+                .filter_map(|&v| mesh.vertices.get(v).map(|vert| (group_vertex_key(object, v), vert.pos)))
+                .collect();
+            if positions.is_empty() {
+                continue;
+            }
+            if self.pivot_mode == PivotMode::IndividualOrigins {
+                let sum = positions.iter().fold(Vec3::ZERO, |acc, (_, p)| acc + *p);
+                let origin = sum * (1.0 / positions.len() as f32);
+                targets.origins.extend(std::iter::repeat(origin).take(positions.len()));
+            }
+            targets.indices.extend(positions.iter().map(|(key, _)| *key));
+            targets.initial_positions.extend(positions);
+        }
+        targets
+    }
+
+    /// Write positions from a transform drag back to their meshes.
+    ///
+    /// Keys are those from `transform_targets`. `snap` snaps to the grid (when
+    /// enabled) and `constrain_mirror` keeps center vertices on their
+    /// object's mirror plane.
+    pub fn write_drag_positions(&mut self, positions: Vec<(usize, Vec3)>, snap: bool, constrain_mirror: bool) {
+        let grouped = self.selection.is_group();
+        let selected = self.selected_object;
+        let snap_settings = self.snap_settings.clone();
+        let Some(objects) = self.objects_mut() else { return };
+        for (key, pos) in positions {
+            let (object, vertex) = match (grouped, selected) {
+                (true, _) => split_group_vertex_key(key),
+                (false, Some(object)) => (object, key),
+                (false, None) => return,
+            };
+            let Some(obj) = objects.get_mut(object) else { continue };
+            let mirror = obj.mirror.unwrap_or_default();
+            if let Some(vert) = obj.mesh.vertices.get_mut(vertex) {
+                let pos = if snap { snap_settings.snap_vec3(pos) } else { pos };
+                vert.pos = if constrain_mirror { mirror.constrain_to_plane(pos) } else { pos };
+            }
+        }
+    }
+
     /// Compute the local orientation basis for transform gizmo
     /// Returns (x_axis, y_axis, z_axis) - three orthonormal vectors
     ///
@@ -2389,7 +2842,8 @@ impl ModelerState {
         let world_y = Vec3::new(0.0, 1.0, 0.0);
         let world_z = Vec3::new(0.0, 0.0, 1.0);
 
-        if self.transform_orientation == TransformOrientation::Global {
+        // Group selections span parts with different orientations: stay on world axes
+        if self.transform_orientation == TransformOrientation::Global || self.selection.is_group() {
             return (world_x, world_y, world_z);
         }
 
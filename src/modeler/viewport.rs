@@ -11,7 +11,10 @@ use crate::rasterizer::{
     OrthoProjection, Camera, draw_3d_line_clipped,
     screen_to_ray, ray_circle_angle,
 };
-use super::state::{ModelerState, ModelerSelection, SelectMode, Axis, ModalTransform, CameraMode, ViewportId, rotate_by_euler};
+use super::state::{
+    ModelerState, ModelerSelection, SelectMode, Axis, ModalTransform, CameraMode, ViewportId, rotate_by_euler,
+    GroupMember, merge_group_member, split_group_vertex_key,
+};
 use super::drag::{DragUpdateResult, ActiveDrag};
 use super::tools::ModelerToolId;
 use super::skeleton::{draw_skeleton, draw_bone_dots, ray_bone_intersect, skeleton_to_triangles};
@@ -291,43 +294,15 @@ fn handle_modal_transform(state: &mut ModelerState, mouse_pos: (f32, f32), ctx:
         None,
     );
 
-    // Apply the updated positions
-    let mut made_changes = false;
-    let mirror_settings = state.current_mirror_settings();
-    if let Some(mesh) = state.mesh_mut() {
-        match result {
-            DragUpdateResult::Move { positions, .. } => {
-                for (vert_idx, new_pos) in positions {
-                    if let Some(vert) = mesh.vertices.get_mut(vert_idx) {
-                        // Constrain center vertices to mirror plane
-                        vert.pos = mirror_settings.constrain_to_plane(new_pos);
-                    }
-                }
-                made_changes = true;
-            }
-            DragUpdateResult::Scale { positions, .. } => {
-                for (vert_idx, new_pos) in positions {
-                    if let Some(vert) = mesh.vertices.get_mut(vert_idx) {
-                        // Constrain center vertices to mirror plane
-                        vert.pos = mirror_settings.constrain_to_plane(new_pos);
-                    }
-                }
-                made_changes = true;
-            }
-            DragUpdateResult::Rotate { positions, .. } => {
-                for (vert_idx, new_pos) in positions {
-                    if let Some(vert) = mesh.vertices.get_mut(vert_idx) {
-                        // Constrain center vertices to mirror plane
-                        vert.pos = mirror_settings.constrain_to_plane(new_pos);
-                    }
-                }
-                made_changes = true;
-            }
-            _ => {}
+    // Apply the updated positions (center vertices stay on the mirror plane)
+    match result {
+        DragUpdateResult::Move { positions, .. }
+        | DragUpdateResult::Scale { positions, .. }
+        | DragUpdateResult::Rotate { positions, .. } => {
+            state.write_drag_positions(positions, false, true);
+            state.dirty = true;
         }
-    }
-    if made_changes {
-        state.dirty = true;
+        _ => {}
     }
 
     // Confirm on left click
@@ -356,13 +331,7 @@ fn handle_modal_transform(state: &mut ModelerState, mouse_pos: (f32, f32), ctx:
             ModalTransform::None => {}
         }
         if let Some(original_positions) = state.drag_manager.cancel() {
-            if let Some(mesh) = state.mesh_mut() {
-                for (vert_idx, original_pos) in original_positions {
-                    if let Some(vert) = mesh.vertices.get_mut(vert_idx) {
-                        vert.pos = original_pos;
-                    }
-                }
-            }
+            state.write_drag_positions(original_positions, false, false);
         }
         state.modal_transform = ModalTransform::None;
         state.set_status("Transform cancelled", 1.0);
@@ -417,25 +386,12 @@ fn handle_drag_move(
                     // Apply delta to initial positions
                     if let super::drag::ActiveDrag::Move(tracker) = &state.drag_manager.active {
                         let snap_disabled = is_key_down(KeyCode::Z);
-                        let snap_enabled = state.snap_settings.enabled && !snap_disabled;
-                        let snap_size = state.snap_settings.grid_size;
 
                         let updates: Vec<_> = tracker.initial_positions.iter()
                             .map(|(idx, start_pos)| (*idx, *start_pos + delta))
                             .collect();
 
-                        if let Some(mesh) = state.mesh_mut() {
-                            for (idx, mut new_pos) in updates {
-                                if snap_enabled {
-                                    new_pos.x = (new_pos.x / snap_size).round() * snap_size;
-                                    new_pos.y = (new_pos.y / snap_size).round() * snap_size;
-                                    new_pos.z = (new_pos.z / snap_size).round() * snap_size;
-                                }
-                                if let Some(vert) = mesh.vertices.get_mut(idx) {
-                                    vert.pos = new_pos;
-                                }
-                            }
-                        }
+                        state.write_drag_positions(updates, !snap_disabled, false);
                         state.dirty = true;
                     }
                 }
@@ -467,20 +423,7 @@ fn handle_drag_move(
                 }
                 if let DragUpdateResult::Move { positions, .. } = result {
                     let snap_disabled = is_key_down(KeyCode::Z);
-                    // Capture snap settings before borrowing mesh
-                    let snap_enabled = state.snap_settings.enabled && !snap_disabled;
-                    let snap_settings = state.snap_settings.clone();
-                    if let Some(mesh) = state.mesh_mut() {
-                        for (vert_idx, new_pos) in positions {
-                            if let Some(vert) = mesh.vertices.get_mut(vert_idx) {
-                                vert.pos = if snap_enabled {
-                                    snap_settings.snap_vec3(new_pos)
-                                } else {
-                                    new_pos
-                                };
-                            }
-                        }
-                    }
+                    state.write_drag_positions(positions, !snap_disabled, false);
                     state.dirty = true;
                 }
             }
@@ -496,13 +439,7 @@ fn handle_drag_move(
         // Cancel drag on right-click
         if ctx.mouse.right_pressed {
             if let Some(original_positions) = state.drag_manager.cancel() {
-                if let Some(mesh) = state.mesh_mut() {
-                    for (vert_idx, original_pos) in original_positions {
-                        if let Some(vert) = mesh.vertices.get_mut(vert_idx) {
-                            vert.pos = original_pos;
-                        }
-                    }
-                }
+                state.write_drag_positions(original_positions, false, false);
             }
             if owns_ortho_drag {
                 state.ortho_drag_viewport = None;
@@ -526,15 +463,8 @@ fn handle_drag_move(
                 // Only become free move if moved at least 3 pixels (distinguish from click)
                 if dx > 3.0 || dy > 3.0 {
                     // Get vertex indices and initial positions
-                    let mesh = state.mesh();
-                    let mut indices = state.selection.get_affected_vertex_indices(mesh);
-                    if state.vertex_linking {
-                        indices = mesh.expand_to_coincident(&indices, 0.001);
-                    }
-
-                    let initial_positions: Vec<(usize, Vec3)> = indices.iter()
-                        .filter_map(|&idx| mesh.vertices.get(idx).map(|v| (idx, v.pos)))
-                        .collect();
+                    let targets = state.transform_targets();
+                    let (indices, initial_positions) = (targets.indices, targets.initial_positions);
 
                     if !initial_positions.is_empty() {
                         // Calculate center
@@ -563,6 +493,7 @@ fn handle_drag_move(
 
                         // Get bone rotation for world-to-local delta transformation (bone-bound meshes)
                         let bone_rotation = state.selected_object()
+                            .filter(|_| !state.selection.is_group())
                             .and_then(|obj| obj.default_bone_index)
                             .map(|bone_idx| state.get_bone_world_transform(bone_idx).1);
 
@@ -892,6 +823,21 @@ pub fn draw_modeler_viewport_ext(
                 state.viewport_mouse_captured = false;
             }
 
+            // Gamepad cursor over the viewport: right stick looks, RB + left stick moves
+            if inside_viewport && ctx.gamepad.active {
+                let delta = get_frame_time();
+                let look = ctx.gamepad.look;
+                if look.length() > 0.0 {
+                    state.camera.rotate(look.y * 2.5 * delta, -look.x * 2.5 * delta);
+                }
+                let fly = ctx.gamepad.fly;
+                if fly.length() > 0.1 {
+                    let pad_speed = 1500.0 * delta;
+                    state.camera.position = state.camera.position + state.camera.basis_z * fly.y * pad_speed;
+                    state.camera.position = state.camera.position + state.camera.basis_x * fly.x * pad_speed;
+                }
+            }
+
             // Keyboard camera movement (WASD + Q/E) - only while right-click held (like Unity/Unreal)
             // This prevents conflicts with editing shortcuts like E for extrude
             // Shift increases movement speed
@@ -943,6 +889,22 @@ pub fn draw_modeler_viewport_ext(
                 state.viewport_mouse_captured = false;
             }
 
+            // Gamepad cursor over the viewport: right stick orbits, RB + left stick zooms
+            if inside_viewport && ctx.gamepad.active {
+                let delta = get_frame_time();
+                let look = ctx.gamepad.look;
+                if look.length() > 0.0 {
+                    state.orbit_azimuth -= look.x * 2.0 * delta;
+                    state.orbit_elevation = (state.orbit_elevation + look.y * 1.5 * delta).clamp(-1.4, 1.4);
+                    state.sync_camera_from_orbit();
+                }
+                let fly = ctx.gamepad.fly;
+                if fly.y.abs() > 0.1 {
+                    state.orbit_distance = (state.orbit_distance * (1.0 - fly.y * 1.5 * delta)).clamp(1024.0, 40960.0);
+                    state.sync_camera_from_orbit();
+                }
+            }
+
             // Mouse wheel: zoom in/out (change orbit distance)
             if inside_viewport {
                 let scroll = ctx.mouse.scroll;
@@ -981,15 +943,8 @@ pub fn draw_modeler_viewport_ext(
         let mode = state.modal_transform;
 
         // Get vertex indices and initial positions (same as gizmo drags)
-        let mesh = state.mesh();
-        let mut indices = state.selection.get_affected_vertex_indices(mesh);
-        if state.vertex_linking {
-            indices = mesh.expand_to_coincident(&indices, 0.001);
-        }
-
-        let initial_positions: Vec<(usize, Vec3)> = indices.iter()
-            .filter_map(|&idx| mesh.vertices.get(idx).map(|v| (idx, v.pos)))
-            .collect();
+        let targets = state.transform_targets();
+        let (indices, initial_positions) = (targets.indices, targets.initial_positions);
 
         if !initial_positions.is_empty() {
             // Calculate center
@@ -998,6 +953,7 @@ pub fn draw_modeler_viewport_ext(
 
             // Get bone rotation for world-to-local delta transformation (for bone-bound meshes)
             let bone_rotation = state.selected_object()
+                .filter(|_| !state.selection.is_group())
                 .and_then(|obj| obj.default_bone_index)
                 .map(|bone_idx| state.get_bone_world_transform(bone_idx).1);
 
@@ -1030,6 +986,7 @@ pub fn draw_modeler_viewport_ext(
                         initial_positions,
                         mouse_pos, // Use mouse_pos as center for screen-space scaling
                     );
+                    state.drag_manager.set_origins(targets.origins);
                 }
                 ModalTransform::Rotate => {
                     // Convert screen mouse to framebuffer coordinates for initial angle calculation
@@ -1062,6 +1019,7 @@ pub fn draw_modeler_viewport_ext(
                         fb_height,
                         (draw_x, draw_y, draw_w, draw_h), // viewport transform
                     );
+                    state.drag_manager.set_origins(targets.origins);
                 }
                 ModalTransform::None => {}
             }
@@ -1078,6 +1036,9 @@ pub fn draw_modeler_viewport_ext(
     // Handle left-click drag to move selection (if not in modal transform)
     handle_drag_move(ctx, state, mouse_pos, inside_viewport, fb_width, fb_height, viewport_id);
 
+    // Mirror dragged vertices across the symmetry plane
+    state.sync_symmetry();
+
     // Clear and render
     fb.clear(RasterColor::new(30, 30, 35));
 
@@ -1424,15 +1385,10 @@ pub fn draw_modeler_viewport_ext(
         }
     }
 
-    // Blit framebuffer to screen
-    let texture = Texture2D::from_rgba8(
-        fb.width as u16,
-        fb.height as u16,
-        &fb.pixels,
-    );
-    texture.set_filter(FilterMode::Nearest);
+    // Blit framebuffer to screen (uploading only what changed)
+    let texture = state.viewport_textures[viewport_id.index()].upload(fb);
     draw_texture_ex(
-        &texture,
+        texture,
         draw_x,
         draw_y,
         WHITE,
@@ -1453,6 +1409,7 @@ pub fn draw_modeler_viewport_ext(
     if non_mesh_component_selected {
         // Component editing mode: only component gizmo, no mesh interaction
         handle_component_move_gizmo(ctx, state, draw_x, draw_y, draw_w, draw_h, fb_width, fb_height, viewport_id);
+        handle_collision_scale_gizmo(ctx, state, Rect::new(draw_x, draw_y, draw_w, draw_h), fb_width, fb_height, viewport_id);
 
         // Still update hover state for click-through to mesh/skeleton
         let is_active_viewport = state.active_viewport == viewport_id;
@@ -1462,6 +1419,7 @@ pub fn draw_modeler_viewport_ext(
     } else {
         // Mesh editing mode: normal mesh tools and interaction
         handle_transform_gizmo(ctx, state, mouse_pos, inside_viewport, draw_x, draw_y, draw_w, draw_h, fb_width, fb_height, viewport_id);
+        state.sync_symmetry();
 
         // Update hover state every frame (like world editor) - but not when gizmo is active
         let is_active_viewport = state.active_viewport == viewport_id;
@@ -1671,6 +1629,30 @@ fn apply_box_selection(
         state.camera.clone()
     };
 
+    // Check if adding to selection (Shift or X held)
+    let add_to_selection = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)
+                        || is_key_down(KeyCode::X);
+
+    // Alt: select across every visible part into a group selection
+    if is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt) {
+        let hits = box_select_group_members(state, &camera, ortho, (fb_x0, fb_y0, fb_x1, fb_y1), fb_width, fb_height);
+        let mut members = match (add_to_selection, state.selected_object) {
+            (true, Some(object)) => state.selection.to_group_members(object, state.mesh()),
+            _ => Vec::new(),
+        };
+        for member in hits {
+            merge_group_member(&mut members, member);
+        }
+        if !members.is_empty() {
+            let parts = members.len();
+            state.set_selection(ModelerSelection::Group(members));
+            state.set_status(&format!("Selected across {} part(s)", parts), 0.5);
+        } else if !add_to_selection {
+            state.set_selection(ModelerSelection::None);
+        }
+        return;
+    }
+
     let mesh = state.mesh();
 
     // Pre-compute all bone transforms for per-vertex skinning (same as find_hovered_element)
@@ -1693,10 +1675,6 @@ fn apply_box_selection(
         }
     };
 
-    // Check if adding to selection (Shift or X held)
-    let add_to_selection = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)
-                        || is_key_down(KeyCode::X);
-
     match state.select_mode {
         SelectMode::Vertex => {
             let mut selected = if add_to_selection {
@@ -1778,6 +1756,72 @@ fn apply_box_selection(
     }
 }
 
+/// Vertices of every visible, unlocked part inside a framebuffer rectangle,
+/// one group member per part. Face mode takes whole faces by their centers.
+fn box_select_group_members(
+    state: &ModelerState,
+    camera: &Camera,
+    ortho: Option<&OrthoProjection>,
+    (fb_x0, fb_y0, fb_x1, fb_y1): (f32, f32, f32, f32),
+    fb_width: usize,
+    fb_height: usize,
+) -> Vec<GroupMember> {
+    let bone_transforms: Vec<(Vec3, Vec3)> = (0..state.skeleton().len())
+        .map(|i| state.get_bone_world_transform(i))
+        .collect();
+    let in_box = |world_pos: Vec3| {
+        world_to_screen_with_ortho(
+            world_pos,
+            camera.position,
+            camera.basis_x,
+            camera.basis_y,
+            camera.basis_z,
+            fb_width,
+            fb_height,
+            ortho,
+        )
+        .is_some_and(|(sx, sy)| sx >= fb_x0 && sx <= fb_x1 && sy >= fb_y0 && sy <= fb_y1)
+    };
+
+    let mut members = Vec::new();
+    for (object, obj) in state.visible_objects().filter(|(_, o)| !o.locked) {
+        let mesh = &obj.mesh;
+        let world: Vec<Vec3> = mesh.vertices.iter()
+            .map(|v| {
+                match v.bone_index.or(obj.default_bone_index).and_then(|idx| bone_transforms.get(idx)) {
+                    Some(&(bone_pos, bone_rot)) => rotate_by_euler(v.pos, bone_rot) + bone_pos,
+                    None => v.pos,
+                }
+            })
+            .collect();
+
+        let mut vertices: Vec<usize> = Vec::new();
+        match state.select_mode {
+            SelectMode::Face => {
+                for face in &mesh.faces {
+                    let corners: Vec<Vec3> = face.vertices.iter().filter_map(|&vi| world.get(vi).copied()).collect();
+                    if corners.is_empty() {
+                        continue;
+                    }
+                    let center = corners.iter().fold(Vec3::ZERO, |acc, &p| acc + p) * (1.0 / corners.len() as f32);
+                    if in_box(center) {
+                        vertices.extend(face.vertices.iter().copied());
+                    }
+                }
+                vertices.sort();
+                vertices.dedup();
+            }
+            _ => {
+                vertices = (0..world.len()).filter(|&vi| in_box(world[vi])).collect();
+            }
+        }
+        if !vertices.is_empty() {
+            members.push(GroupMember::Vertices { object, vertices });
+        }
+    }
+    members
+}
+
 /// Draw corner brackets around the selected object's bounding box
 fn draw_selected_object_brackets(state: &ModelerState, fb: &mut Framebuffer) {
     // Only draw if an object is selected
@@ -2102,6 +2146,36 @@ fn draw_mesh_selection_overlays(state: &ModelerState, fb: &mut Framebuffer, worl
             }
         }
     }
+
+    // =========================================================================
+    // Draw group selection - blue dots on every member vertex, in any part
+    // =========================================================================
+    if state.selection.is_group() {
+        for (key, pos) in state.transform_targets().initial_positions {
+            let (object, vertex) = split_group_vertex_key(key);
+            let Some(obj) = state.objects().get(object).filter(|o| o.visible) else { continue };
+            let bone = obj.mesh.vertices.get(vertex).and_then(|v| v.bone_index).or(obj.default_bone_index);
+            let world_pos = match bone {
+                Some(bone_idx) => {
+                    let (bone_pos, bone_rot) = state.get_bone_world_transform(bone_idx);
+                    rotate_by_euler(pos, bone_rot) + bone_pos
+                }
+                None => pos,
+            };
+            if let Some((sx, sy)) = world_to_screen_with_ortho(
+                world_pos,
+                camera.position,
+                camera.basis_x,
+                camera.basis_y,
+                camera.basis_z,
+                fb.width,
+                fb.height,
+                ortho,
+            ) {
+                fb.draw_circle(sx as i32, sy as i32, 4, select_color);
+            }
+        }
+    }
 }
 
 /// Draw preview highlights for elements inside the box selection rectangle
@@ -3590,21 +3664,8 @@ fn handle_move_gizmo(
                         }
                     }
                 } else {
-                    // Apply to mesh vertices
-                    let mirror_settings = state.current_mirror_settings();
-                    if let Some(mesh) = state.mesh_mut() {
-                        for (vert_idx, new_pos) in positions {
-                            if let Some(vert) = mesh.vertices.get_mut(vert_idx) {
-                                let snapped = if snap_enabled {
-                                    snap_settings.snap_vec3(new_pos)
-                                } else {
-                                    new_pos
-                                };
-                                // Constrain center vertices to mirror plane
-                                vert.pos = mirror_settings.constrain_to_plane(snapped);
-                            }
-                        }
-                    }
+                    // Apply to mesh vertices (center vertices stay on the mirror plane)
+                    state.write_drag_positions(positions, snap_enabled, true);
                 }
                 state.dirty = true;
             }
@@ -3665,15 +3726,8 @@ fn handle_move_gizmo(
             (indices, positions, false, true)
         } else {
             // Vertex selection - get vertex indices and positions
-            let mesh = state.mesh();
-            let mut indices = state.selection.get_affected_vertex_indices(mesh);
-            if state.vertex_linking {
-                indices = mesh.expand_to_coincident(&indices, 0.001);
-            }
-            let positions: Vec<(usize, Vec3)> = indices.iter()
-                .filter_map(|&idx| mesh.vertices.get(idx).map(|v| (idx, v.pos)))
-                .collect();
-            (indices, positions, false, false)
+            let targets = state.transform_targets();
+            (targets.indices, targets.initial_positions, false, false)
         };
 
         // Track whether this is a bone drag
@@ -3698,7 +3752,7 @@ fn handle_move_gizmo(
         state.tool_box.tools.move_tool.start_drag(Some(ui_axis));
 
         // Get bone rotation for world-to-local delta transformation (vertex moves on bone-bound meshes)
-        let bone_rotation = if !is_bone_drag && !is_bone_tip_drag {
+        let bone_rotation = if !is_bone_drag && !is_bone_tip_drag && !state.selection.is_group() {
             state.selected_object()
                 .and_then(|obj| obj.default_bone_index)
                 .map(|bone_idx| state.get_bone_world_transform(bone_idx).1)
@@ -3815,20 +3869,7 @@ fn handle_scale_gizmo(
 
             if let DragUpdateResult::Scale { positions, .. } = result {
                 let snap_disabled = is_key_down(KeyCode::Z);
-                // Capture snap settings before borrowing mesh
-                let snap_enabled = state.snap_settings.enabled && !snap_disabled;
-                let snap_settings = state.snap_settings.clone();
-                if let Some(mesh) = state.mesh_mut() {
-                    for (vert_idx, new_pos) in positions {
-                        if let Some(vert) = mesh.vertices.get_mut(vert_idx) {
-                            vert.pos = if snap_enabled {
-                                snap_settings.snap_vec3(new_pos)
-                            } else {
-                                new_pos
-                            };
-                        }
-                    }
-                }
+                state.write_drag_positions(positions, !snap_disabled, false);
                 state.dirty = true;
             }
         } else {
@@ -3871,15 +3912,8 @@ fn handle_scale_gizmo(
     let can_start_drag = state.gizmo_hovered_axis.is_some() || center_hovered;
     if ctx.mouse.left_pressed && inside_viewport && can_start_drag && !is_dragging {
         // Get vertex indices and initial positions
-        let mesh = state.mesh();
-        let mut indices = state.selection.get_affected_vertex_indices(mesh);
-        if state.vertex_linking {
-            indices = mesh.expand_to_coincident(&indices, 0.001);
-        }
-
-        let initial_positions: Vec<(usize, Vec3)> = indices.iter()
-            .filter_map(|&idx| mesh.vertices.get(idx).map(|v| (idx, v.pos)))
-            .collect();
+        let targets = state.transform_targets();
+        let (indices, initial_positions) = (targets.indices, targets.initial_positions);
 
         // Save undo state BEFORE starting the gizmo drag
         state.push_undo("Gizmo Scale");
@@ -3898,6 +3932,7 @@ fn handle_scale_gizmo(
             initial_positions,
             setup.center_screen, // screen-space center for distance calculation
         );
+        state.drag_manager.set_origins(targets.origins);
     }
 
     // Draw scale gizmo (lines with cubes)
@@ -3983,20 +4018,7 @@ fn handle_rotate_gizmo(
 
             if let DragUpdateResult::Rotate { positions, .. } = result {
                 let snap_disabled = is_key_down(KeyCode::Z);
-                // Capture snap settings before borrowing mesh
-                let snap_enabled = state.snap_settings.enabled && !snap_disabled;
-                let snap_settings = state.snap_settings.clone();
-                if let Some(mesh) = state.mesh_mut() {
-                    for (vert_idx, new_pos) in positions {
-                        if let Some(vert) = mesh.vertices.get_mut(vert_idx) {
-                            vert.pos = if snap_enabled {
-                                snap_settings.snap_vec3(new_pos)
-                            } else {
-                                new_pos
-                            };
-                        }
-                    }
-                }
+                state.write_drag_positions(positions, !snap_disabled, false);
                 state.dirty = true;
             }
         } else {
@@ -4058,15 +4080,8 @@ fn handle_rotate_gizmo(
         let axis = state.gizmo_hovered_axis.unwrap();
 
         // Get vertex indices and initial positions
-        let mesh = state.mesh();
-        let mut indices = state.selection.get_affected_vertex_indices(mesh);
-        if state.vertex_linking {
-            indices = mesh.expand_to_coincident(&indices, 0.001);
-        }
-
-        let initial_positions: Vec<(usize, Vec3)> = indices.iter()
-            .filter_map(|&idx| mesh.vertices.get(idx).map(|v| (idx, v.pos)))
-            .collect();
+        let targets = state.transform_targets();
+        let (indices, initial_positions) = (targets.indices, targets.initial_positions);
 
         // Calculate initial angle using ray-circle intersection (arc-following)
         // Convert screen mouse to framebuffer coordinates for initial angle calculation
@@ -4113,6 +4128,7 @@ fn handle_rotate_gizmo(
             fb_height,
             (draw_x, draw_y, draw_w, draw_h), // viewport transform for consistent coordinate conversion
         );
+        state.drag_manager.set_origins(targets.origins);
     }
 
     // Draw rotation circles
@@ -4202,7 +4218,7 @@ fn draw_component_gizmos(
 
                 draw_filled_octahedron(fb, camera, ortho, light_pos, size, gizmo_color);
             }
-            crate::asset::AssetComponent::Collision { shape, is_trigger } => {
+            crate::asset::AssetComponent::Collision { shape, is_trigger, offset } => {
                 let is_selected = state.selected_component == Some(comp_idx);
                 let wire_color = if is_selected {
                     RasterColor::new(255, 255, 255)
@@ -4212,7 +4228,8 @@ fn draw_component_gizmos(
                     RasterColor::new(100, 150, 255)
                 };
 
-                draw_collision_wireframe(fb, camera, ortho, shape, wire_color, state.asset.bounds());
+                let origin = Vec3::new(offset[0], offset[1], offset[2]);
+                draw_collision_wireframe(fb, camera, ortho, shape, origin, wire_color, state.asset.bounds());
             }
             _ => {}
         }
@@ -4226,6 +4243,7 @@ fn draw_collision_wireframe(
     camera: &Camera,
     ortho: Option<&OrthoProjection>,
     shape: &crate::asset::CollisionShapeDef,
+    origin: Vec3,
     color: RasterColor,
     mesh_bounds: Option<(Vec3, Vec3)>,
 ) {
@@ -4233,10 +4251,12 @@ fn draw_collision_wireframe(
 
     let fb_w = fb.width;
     let fb_h = fb.height;
+    // FromMesh bounds are already in asset space
+    let origin = if matches!(shape, CollisionShapeDef::FromMesh) { Vec3::ZERO } else { origin };
 
     let project = |p: Vec3| -> Option<(i32, i32, f32)> {
         world_to_screen_with_ortho_depth(
-            p, camera.position, camera.basis_x, camera.basis_y, camera.basis_z,
+            p + origin, camera.position, camera.basis_x, camera.basis_y, camera.basis_z,
             fb_w, fb_h, ortho,
         ).map(|(x, y, z)| (x as i32, y as i32, z))
     };
@@ -4378,11 +4398,8 @@ fn handle_component_move_gizmo(
 
     let Some(comp_idx) = state.selected_component else { return };
 
-    // Get the component's offset position
-    let offset = match state.asset.components.get(comp_idx) {
-        Some(crate::asset::AssetComponent::Light { offset, .. }) => *offset,
-        _ => return, // No gizmo for non-offset components
-    };
+    // Get the component's offset position (no gizmo for non-offset components)
+    let Some(offset) = state.asset.components.get(comp_idx).and_then(|c| c.offset()) else { return };
 
     let center = Vec3::new(offset[0], offset[1], offset[2]);
     let camera = &state.camera;
@@ -4491,7 +4508,7 @@ fn handle_component_move_gizmo(
                 new_offset[2] = (new_offset[2] / snap_size).round() * snap_size;
             }
 
-            if let Some(crate::asset::AssetComponent::Light { offset, .. }) = state.asset.components.get_mut(comp_idx) {
+            if let Some(offset) = state.asset.components.get_mut(comp_idx).and_then(|c| c.offset_mut()) {
                 *offset = new_offset;
             }
         }
@@ -4571,6 +4588,124 @@ fn handle_component_move_gizmo(
     draw_circle(center_screen.0, center_screen.1, 4.0, WHITE);
 }
 
+/// Handle resize handles for the selected collision shape (Scale tool).
+/// Each axis gets a square handle on the shape's surface; dragging it along the
+/// axis changes that dimension (box half-extent, radius, or capsule/cylinder height).
+fn handle_collision_scale_gizmo(
+    ctx: &UiContext,
+    state: &mut ModelerState,
+    draw_rect: Rect,
+    fb_width: usize,
+    fb_height: usize,
+    viewport_id: ViewportId,
+) {
+    if state.tool_box.active_transform_tool() != Some(ModelerToolId::Scale) {
+        return;
+    }
+    let Some(comp_idx) = state.selected_component else { return };
+    let Some(crate::asset::AssetComponent::Collision { shape, offset, .. }) = state.asset.components.get(comp_idx) else {
+        return;
+    };
+    let shape = shape.clone();
+    let origin = Vec3::new(offset[0], offset[1], offset[2]);
+
+    let camera = &state.camera;
+    let ortho = state.raster_settings.ortho_projection.as_ref();
+    let to_screen = |p: Vec3| -> Option<(f32, f32)> {
+        world_to_screen_with_ortho(
+            p, camera.position, camera.basis_x, camera.basis_y, camera.basis_z,
+            fb_width, fb_height, ortho,
+        ).map(|(sx, sy)| (
+            draw_rect.x + sx / fb_width as f32 * draw_rect.w,
+            draw_rect.y + sy / fb_height as f32 * draw_rect.h,
+        ))
+    };
+
+    // Handle base (shape center along the axis) and tip, per axis
+    let axes = [(Axis::X, 0, RED), (Axis::Y, 1, GREEN), (Axis::Z, 2, BLUE)];
+    let mut handles: Vec<(Axis, usize, (f32, f32), (f32, f32), Color)> = Vec::new();
+    for (axis, idx, color) in axes {
+        let Some(pos) = shape.handle_position(idx) else { continue };
+        let tip = origin + Vec3::new(pos[0], pos[1], pos[2]);
+        let mut base_pos = pos;
+        base_pos[idx] = 0.0;
+        let base = origin + Vec3::new(base_pos[0], base_pos[1], base_pos[2]);
+        if let (Some(base_s), Some(tip_s)) = (to_screen(base), to_screen(tip)) {
+            handles.push((axis, idx, base_s, tip_s, color));
+        }
+    }
+    if handles.is_empty() {
+        return;
+    }
+
+    let mouse_pos = (ctx.mouse.x, ctx.mouse.y);
+    let is_dragging = state.component_gizmo_drag_axis.is_some();
+
+    // Ongoing drag: move the handle along its axis by the mouse movement projected onto it
+    if is_dragging && ctx.mouse.left_down && state.component_gizmo_drag_viewport == Some(viewport_id) {
+        if let (Some(drag_axis), Some(drag_start)) = (state.component_gizmo_drag_axis, state.component_gizmo_drag_start) {
+            if let Some((_, idx, base_s, tip_s, _)) = handles.iter().find(|h| h.0 == drag_axis) {
+                let (dx, dy) = (tip_s.0 - base_s.0, tip_s.1 - base_s.1);
+                let len = (dx * dx + dy * dy).sqrt();
+                let dir = if len > 0.001 { (dx / len, dy / len) } else { (1.0, 0.0) };
+                let screen_movement = (mouse_pos.0 - drag_start.0) * dir.0 + (mouse_pos.1 - drag_start.1) * dir.1;
+                let zoom = match ortho {
+                    Some(ortho) => ortho.zoom,
+                    None => 500.0 / (origin - camera.position).len(),
+                };
+                let mut extent = state.component_gizmo_start_extent + screen_movement / zoom;
+
+                let snap_enabled = state.snap_settings.enabled && !is_key_down(KeyCode::Z);
+                if snap_enabled {
+                    let snap_size = state.snap_settings.grid_size;
+                    extent = (extent / snap_size).round() * snap_size;
+                }
+
+                let idx = *idx;
+                if let Some(crate::asset::AssetComponent::Collision { shape, .. }) = state.asset.components.get_mut(comp_idx) {
+                    shape.set_handle_extent(idx, extent);
+                }
+            }
+        }
+    }
+
+    if is_dragging && !ctx.mouse.left_down {
+        state.component_gizmo_drag_axis = None;
+        state.component_gizmo_drag_start = None;
+        state.component_gizmo_drag_viewport = None;
+    }
+
+    // Hover and start drag
+    let handle_size = 8.0;
+    let mut hovered_axis: Option<Axis> = None;
+    if !is_dragging {
+        for (axis, _, _, tip_s, _) in &handles {
+            if (mouse_pos.0 - tip_s.0).abs() <= handle_size && (mouse_pos.1 - tip_s.1).abs() <= handle_size {
+                hovered_axis = Some(*axis);
+                break;
+            }
+        }
+        if let Some(axis) = hovered_axis {
+            if ctx.mouse.left_pressed {
+                let idx = handles.iter().find(|h| h.0 == axis).map(|h| h.1).unwrap_or(0);
+                state.component_gizmo_drag_axis = Some(axis);
+                state.component_gizmo_drag_start = Some(mouse_pos);
+                state.component_gizmo_start_extent = shape.handle_extent(idx).unwrap_or(0.0);
+                state.component_gizmo_drag_viewport = Some(viewport_id);
+            }
+        }
+    }
+
+    // Draw stems and square handles
+    for (axis, _, base_s, tip_s, base_color) in &handles {
+        let active = state.component_gizmo_drag_axis == Some(*axis);
+        let color = if active { YELLOW } else if hovered_axis == Some(*axis) { WHITE } else { *base_color };
+        draw_line(base_s.0, base_s.1, tip_s.0, tip_s.1, 1.0, color);
+        let half = handle_size * 0.5;
+        draw_rectangle(tip_s.0 - half, tip_s.1 - half, handle_size, handle_size, color);
+    }
+}
+
 /// Draw a filled octahedron in 3D (classic light gizmo)
 fn draw_filled_octahedron(
     fb: &mut Framebuffer,
@@ -4676,6 +4811,10 @@ fn draw_filled_triangle_3d(
 
     if y2 == y0 { return; } // Degenerate triangle
 
+    // Writes pixels directly, so report the covered area
+    let (min_x, max_x) = (x0.min(x1).min(x2).max(0) as usize, x0.max(x1).max(x2).max(0) as usize);
+    fb.mark_dirty(min_x, y0.max(0) as usize, max_x + 1, y2.max(0) as usize + 1);
+
     let total_height = (y2 - y0) as f32;
 
     for y in y0.max(0)..=y2.min(fb.height as i32 - 1) {
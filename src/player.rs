@@ -0,0 +1,286 @@
+//! Standalone Player
+//!
+//! Built with `--features player` (see `cargo xtask export-game`). Boots
+//! straight into the Game runtime with the level named in the bundle's
+//! `game/player.ron` - no Home/World/Assets/Music tabs. Everything the game
+//! needs (level, texture packs, CLUT textures, assets) is read from the
+//! packed `game.bpk` next to the executable (or the web page) instead of
+//! loose files.
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::asset::{Asset, AssetLibrary, AssetSource, SAMPLES_ASSETS_DIR, USER_ASSETS_DIR};
+use crate::bundle::{Bundle, PLAYER_BUNDLE, PLAYER_INFO_ENTRY};
+use crate::frame_pacer::FramePacer;
+use crate::game::{self, GameToolState};
+use crate::input::InputState;
+use crate::rasterizer::{Framebuffer, Texture, HEIGHT, WIDTH};
+use crate::texture::{TextureLibrary, TextureSource, UserTexture, SAMPLES_TEXTURES_DIR, USER_TEXTURES_DIR};
+use crate::ui::{MouseState, Rect, UiContext};
+use crate::world::{parse_level_data, Level};
+
+/// Texture packs inside the bundle (same layout as the loose assets)
+const TEXTURE_PACKS_DIR: &str = "assets/samples/texture-packs";
+
+/// Exported game description, stored in the bundle as `game/player.ron`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerInfo {
+    /// Window title
+    pub title: String,
+    /// Bundle path of the level to boot into
+    pub level: String,
+}
+
+/// Everything loaded from the bundle
+pub struct GameContent {
+    pub info: PlayerInfo,
+    pub level: Level,
+    /// Texture pack textures (PNG), quantized to 15-bit
+    pub pack_textures: Vec<Texture>,
+    pub user_textures: TextureLibrary,
+    pub asset_library: AssetLibrary,
+}
+
+impl GameContent {
+    /// Load the game described by the bundle's player info
+    pub fn from_bundle(bundle: &Bundle) -> Result<Self, String> {
+        let info = read_info(bundle)?;
+        let level_bytes = bundle.read(&info.level).map_err(|e| e.to_string())?;
+        let level = parse_level_data(&level_bytes).map_err(|e| format!("{}: {}", info.level, e))?;
+
+        let mut pack_textures = Vec::new();
+        for path in bundle.files_in(TEXTURE_PACKS_DIR).filter(|p| has_extension(p, &["png", "jpg", "jpeg"])) {
+            let loaded = bundle.read(path).map_err(|e| e.to_string())
+                .and_then(|bytes| Texture::from_bytes(&bytes, file_stem(path).to_string()));
+            match loaded {
+                Ok(mut tex) => {
+                    tex.quantize_15bit();
+                    pack_textures.push(tex);
+                }
+                Err(e) => eprintln!("Failed to load texture {}: {}", path, e),
+            }
+        }
+
+        let mut user_textures = TextureLibrary::new();
+        for (dir, source) in [(SAMPLES_TEXTURES_DIR, TextureSource::Sample), (USER_TEXTURES_DIR, TextureSource::User)] {
+            for path in bundle.files_in(dir).filter(|p| has_extension(p, &["ron"])) {
+                match bundle.read(path).map_err(|e| e.to_string())
+                    .and_then(|bytes| UserTexture::load_from_bytes(&bytes).map_err(|e| e.to_string()))
+                {
+                    Ok(mut tex) => {
+                        tex.source = source;
+                        user_textures.add(tex);
+                    }
+                    Err(e) => eprintln!("Failed to load texture {}: {}", path, e),
+                }
+            }
+        }
+
+        let mut asset_library = AssetLibrary::new();
+        for (dir, source) in [(SAMPLES_ASSETS_DIR, AssetSource::Sample), (USER_ASSETS_DIR, AssetSource::User)] {
+            for path in bundle.files_in(dir).filter(|p| has_extension(p, &["ron"])) {
+                match bundle.read(path).map_err(|e| e.to_string())
+                    .and_then(|bytes| Asset::load_from_bytes(&bytes).map_err(|e| e.to_string()))
+                {
+                    Ok(mut asset) => {
+                        // Keyed by file name, like the loose-file library
+                        asset.name = file_stem(path).to_string();
+                        asset.source = source;
+                        asset_library.add(asset);
+                    }
+                    Err(e) => eprintln!("Failed to load asset {}: {}", path, e),
+                }
+            }
+        }
+
+        Ok(Self { info, level, pack_textures, user_textures, asset_library })
+    }
+
+    /// Textures for the renderer: packs first, then CLUT textures at `time`
+    fn game_textures(&self, time: f64) -> Vec<Texture> {
+        let mut textures = self.pack_textures.clone();
+        for name in self.user_textures.names() {
+            if let Some(tex) = self.user_textures.get(name) {
+                textures.push(tex.to_raster_texture_at(time));
+            }
+        }
+        textures
+    }
+}
+
+fn read_info(bundle: &Bundle) -> Result<PlayerInfo, String> {
+    let text = bundle.read_string(PLAYER_INFO_ENTRY).map_err(|e| e.to_string())?;
+    ron::from_str(&text).map_err(|e| format!("{}: {}", PLAYER_INFO_ENTRY, e))
+}
+
+fn file_stem(path: &str) -> &str {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name)
+}
+
+fn has_extension(path: &str, extensions: &[&str]) -> bool {
+    path.rsplit_once('.')
+        .map(|(_, ext)| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+        .unwrap_or(false)
+}
+
+/// Where the bundle lives: next to the executable, else the working directory
+#[cfg(not(target_arch = "wasm32"))]
+fn bundle_path() -> std::path::PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(PLAYER_BUNDLE)))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| std::path::PathBuf::from(PLAYER_BUNDLE))
+}
+
+async fn load_bundle() -> Result<Bundle, String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let bytes = {
+        let path = bundle_path();
+        std::fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?
+    };
+    #[cfg(target_arch = "wasm32")]
+    let bytes = load_file(PLAYER_BUNDLE).await.map_err(|e| format!("{}: {}", PLAYER_BUNDLE, e))?;
+    Bundle::from_bytes(bytes).map_err(|e| e.to_string())
+}
+
+/// Window title from the bundle (native reads it before the window opens)
+pub fn window_title() -> String {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if let Some(info) = std::fs::read(bundle_path()).ok()
+            .and_then(|bytes| Bundle::from_bytes(bytes).ok())
+            .and_then(|bundle| read_info(&bundle).ok())
+        {
+            return info.title;
+        }
+    }
+    "BONNIE-32".to_string()
+}
+
+/// Run the exported game until the window closes
+pub async fn run() {
+    let content = match load_bundle().await.and_then(|bundle| {
+        crate::locale::load_bundle(&bundle);
+        GameContent::from_bundle(&bundle)
+    }) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Failed to load game: {}", e);
+            show_error(&e).await;
+            return;
+        }
+    };
+    println!("=== {} ===", content.info.title);
+
+    let mut fb = Framebuffer::new(WIDTH, HEIGHT);
+    let mut ui_ctx = UiContext::new();
+    let mut input = InputState::new();
+    let mut game = GameToolState::new();
+    let mut frame_pacer = FramePacer::new();
+    game.display.restore_position();
+    crate::locale::set_language(&game.display.language);
+
+    let mut game_textures = content.game_textures(get_time());
+    game.texture_anim_key = content.user_textures.animation_key(get_time());
+
+    loop {
+        let (x, y) = mouse_position();
+        ui_ctx.begin_frame(MouseState {
+            x,
+            y,
+            left_down: is_mouse_button_down(MouseButton::Left),
+            right_down: is_mouse_button_down(MouseButton::Right),
+            left_pressed: is_mouse_button_pressed(MouseButton::Left),
+            left_released: is_mouse_button_released(MouseButton::Left),
+            right_pressed: is_mouse_button_pressed(MouseButton::Right),
+            scroll: mouse_wheel().1,
+            double_clicked: false,
+        });
+        input.poll();
+
+        // Flipbook frame changed: rebuild textures and the RGB555 cache
+        let now = get_time();
+        let anim_key = content.user_textures.animation_key(now);
+        if anim_key != game.texture_anim_key {
+            game.texture_anim_key = anim_key;
+            game.textures_15_cache.clear();
+            game_textures = content.game_textures(now);
+        }
+
+        if game.playing && game.player_entity.is_none() {
+            if let Some((room_idx, spawn)) = content.level.get_player_start(&content.asset_library) {
+                if let Some(room) = content.level.rooms.get(room_idx) {
+                    let pos = spawn.world_position(room);
+                    game.spawn_player(pos, &content.level);
+                }
+            }
+        }
+        if game.playing && !game.logic.started() {
+            game.start_level_logic(&content.level, &content.asset_library);
+        }
+
+        game.tick(&content.level, get_frame_time());
+
+        let screen = Rect::new(0.0, 0.0, screen_width(), screen_height());
+        game::draw_test_viewport(
+            screen,
+            &mut game,
+            &content.level,
+            &game_textures,
+            &mut fb,
+            &input,
+            &ui_ctx,
+            &content.asset_library,
+            &content.user_textures,
+        );
+        input.touch_mut().set_area(macroquad::math::Rect::new(screen.x, screen.y, screen.w, screen.h));
+        input.touch().draw();
+
+        game.display.update();
+        frame_pacer.set_limit(game.fps_limit);
+        frame_pacer.wait();
+        next_frame().await;
+        frame_pacer.frame_presented(get_time());
+    }
+}
+
+/// Show a load error until the window is closed
+async fn show_error(message: &str) {
+    loop {
+        clear_background(Color::from_rgba(20, 20, 25, 255));
+        draw_text("Failed to load game", 20.0, 40.0, 24.0, Color::from_rgba(255, 120, 120, 255));
+        draw_text(message, 20.0, 70.0, 16.0, Color::from_rgba(200, 200, 200, 255));
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::BundleWriter;
+
+    #[test]
+    fn test_content_from_bundle() {
+        let info = PlayerInfo { title: "Demo".to_string(), level: "game/level.ron".to_string() };
+        let level = crate::world::create_empty_level();
+        let mut writer = BundleWriter::new();
+        writer.add(PLAYER_INFO_ENTRY, ron::to_string(&info).unwrap().into_bytes());
+        writer.add("game/level.ron", ron::to_string(&level).unwrap().into_bytes());
+        let bundle = Bundle::from_bytes(writer.finish().unwrap()).unwrap();
+
+        let content = GameContent::from_bundle(&bundle).unwrap();
+        assert_eq!(content.info, info);
+        assert_eq!(content.level.rooms.len(), level.rooms.len());
+        assert!(content.pack_textures.is_empty());
+    }
+
+    #[test]
+    fn test_path_helpers() {
+        assert_eq!(file_stem("assets/samples/assets/crate.ron"), "crate");
+        assert_eq!(file_stem("noext"), "noext");
+        assert!(has_extension("a/b/Stone.PNG", &["png"]));
+        assert!(!has_extension("a/b.c/readme", &["png"]));
+    }
+}
@@ -0,0 +1,408 @@
+//! Frame profiler
+//!
+//! Scoped timers around the engine's subsystems (game tick phases, raster
+//! passes, UI drawing, audio), collected per frame and drawn as an overlay in
+//! any tab. F9 shows or hides it; while it's hidden the timers don't touch
+//! the clock.
+//!
+//! ```ignore
+//! let mut phase = profiler::scope(Subsystem::Tick, "physics");
+//! step_physics();
+//! phase.next("scripts"); // same subsystem, next label
+//! run_scripts();
+//! ```
+//!
+//! Timers nest and only count their own time: a raster pass inside the World
+//! Editor's panel drawing is raster time, not UI time. Like the tag library,
+//! the profiler lives in a thread-local so timers can sit anywhere without
+//! threading state through.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use macroquad::prelude::*;
+use crate::rasterizer::RasterTimings;
+
+/// Frames kept for the history graph
+pub const HISTORY_FRAMES: usize = 180;
+/// Frames averaged for the numbers
+pub const AVERAGE_FRAMES: usize = 30;
+/// Key that shows or hides the overlay
+pub const PROFILER_KEY: KeyCode = KeyCode::F9;
+
+/// What a timer's time is charged to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Tick,
+    Raster,
+    Ui,
+    Audio,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 4] = [Subsystem::Tick, Subsystem::Raster, Subsystem::Ui, Subsystem::Audio];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Subsystem::Tick => "Game tick",
+            Subsystem::Raster => "Raster",
+            Subsystem::Ui => "UI",
+            Subsystem::Audio => "Audio",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Subsystem::Tick => Color::new(0.45, 0.85, 0.45, 1.0),
+            Subsystem::Raster => Color::new(1.0, 0.6, 0.2, 1.0),
+            Subsystem::Ui => Color::new(0.4, 0.6, 1.0, 1.0),
+            Subsystem::Audio => Color::new(0.8, 0.5, 1.0, 1.0),
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Color of the time outside every timer
+const OTHER_COLOR: Color = Color::new(0.5, 0.5, 0.55, 1.0);
+
+/// One frame's measurements (all in ms)
+#[derive(Debug, Clone, Default)]
+pub struct FrameSample {
+    /// Wall time from `begin_frame` to `end_frame`
+    pub total_ms: f32,
+    /// Own time per subsystem, in `Subsystem::ALL` order
+    pub subsystem_ms: [f32; 4],
+    /// Own time per timer label
+    pub labels: Vec<(Subsystem, &'static str, f32)>,
+    /// The rasterizer's stage breakdown, summed over every mesh drawn
+    pub raster: RasterTimings,
+}
+
+impl FrameSample {
+    fn add(&mut self, subsystem: Subsystem, label: &'static str, ms: f32) {
+        self.subsystem_ms[subsystem.index()] += ms;
+        match self.labels.iter_mut().find(|(s, l, _)| *s == subsystem && *l == label) {
+            Some(entry) => entry.2 += ms,
+            None => self.labels.push((subsystem, label, ms)),
+        }
+    }
+
+    /// Frame time outside every timer
+    pub fn other_ms(&self) -> f32 {
+        (self.total_ms - self.subsystem_ms.iter().sum::<f32>()).max(0.0)
+    }
+}
+
+struct OpenTimer {
+    subsystem: Subsystem,
+    label: &'static str,
+    start: f64,
+    /// Time spent in timers nested inside this one (ms)
+    child_ms: f32,
+}
+
+/// Open timers, the frame being measured and the frames before it
+#[derive(Default)]
+pub struct FrameProfiler {
+    pub visible: bool,
+    frame: FrameSample,
+    frame_start: Option<f64>,
+    open: Vec<OpenTimer>,
+    history: VecDeque<FrameSample>,
+}
+
+impl FrameProfiler {
+    fn open(&mut self, subsystem: Subsystem, label: &'static str, now: f64) {
+        self.open.push(OpenTimer { subsystem, label, start: now, child_ms: 0.0 });
+    }
+
+    /// Close the innermost timer, charging its own time and handing the
+    /// whole of it to its parent as child time
+    fn close(&mut self, now: f64) {
+        let Some(timer) = self.open.pop() else {
+            return;
+        };
+        let elapsed = ((now - timer.start) * 1000.0) as f32;
+        self.frame.add(timer.subsystem, timer.label, (elapsed - timer.child_ms).max(0.0));
+        if let Some(parent) = self.open.last_mut() {
+            parent.child_ms += elapsed;
+        }
+    }
+
+    /// Close the innermost timer and open the next phase of its subsystem
+    fn relabel(&mut self, label: &'static str, now: f64) {
+        if let Some(subsystem) = self.open.last().map(|t| t.subsystem) {
+            self.close(now);
+            self.open(subsystem, label, now);
+        }
+    }
+
+    fn begin_frame(&mut self, now: f64) {
+        self.frame_start = Some(now);
+    }
+
+    fn end_frame(&mut self, now: f64) {
+        // A guard kept alive past the frame is charged up to here
+        while !self.open.is_empty() {
+            self.close(now);
+        }
+        let mut frame = std::mem::take(&mut self.frame);
+        if let Some(start) = self.frame_start.take() {
+            frame.total_ms = ((now - start) * 1000.0) as f32;
+        }
+        if self.history.len() >= HISTORY_FRAMES {
+            self.history.pop_front();
+        }
+        self.history.push_back(frame);
+    }
+
+    /// Measured frames, oldest first
+    pub fn history(&self) -> impl Iterator<Item = &FrameSample> {
+        self.history.iter()
+    }
+
+    /// Average of the last `AVERAGE_FRAMES` frames
+    pub fn average(&self) -> FrameSample {
+        let mut average = FrameSample::default();
+        let count = self.history.len().min(AVERAGE_FRAMES);
+        if count == 0 {
+            return average;
+        }
+        for frame in self.history.iter().rev().take(count) {
+            average.total_ms += frame.total_ms;
+            for &(subsystem, label, ms) in &frame.labels {
+                average.add(subsystem, label, ms);
+            }
+            average.raster.accumulate(&frame.raster);
+        }
+        let scale = 1.0 / count as f32;
+        average.total_ms *= scale;
+        for ms in &mut average.subsystem_ms {
+            *ms *= scale;
+        }
+        for entry in &mut average.labels {
+            entry.2 *= scale;
+        }
+        let raster = &mut average.raster;
+        for ms in [&mut raster.transform_ms, &mut raster.fog_ms, &mut raster.cull_ms, &mut raster.sort_ms, &mut raster.draw_ms, &mut raster.wireframe_ms] {
+            *ms *= scale;
+        }
+        raster.triangles_drawn /= count as u32;
+        average
+    }
+}
+
+thread_local! {
+    static PROFILER: RefCell<FrameProfiler> = RefCell::new(FrameProfiler::default());
+}
+
+/// Run `f` with the shared profiler
+pub fn with_profiler<R>(f: impl FnOnce(&mut FrameProfiler) -> R) -> R {
+    PROFILER.with(|p| f(&mut p.borrow_mut()))
+}
+
+/// A running timer; stops when dropped
+#[must_use = "the timer stops as soon as the guard is dropped"]
+pub struct Scope {
+    active: bool,
+}
+
+impl Scope {
+    /// Stop this timer and start the next phase of the same subsystem
+    pub fn next(&mut self, label: &'static str) {
+        if self.active {
+            with_profiler(|p| p.relabel(label, get_time()));
+        }
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        if self.active {
+            with_profiler(|p| p.close(get_time()));
+        }
+    }
+}
+
+/// Time `label` under `subsystem` until the returned guard drops
+pub fn scope(subsystem: Subsystem, label: &'static str) -> Scope {
+    let active = with_profiler(|p| {
+        if p.visible {
+            p.open(subsystem, label, get_time());
+        }
+        p.visible
+    });
+    Scope { active }
+}
+
+/// Add time measured elsewhere, e.g. on the audio thread
+pub fn record(subsystem: Subsystem, label: &'static str, ms: f32) {
+    with_profiler(|p| {
+        if p.visible {
+            p.frame.add(subsystem, label, ms);
+        }
+    });
+}
+
+/// Add a raster pass's stage breakdown to this frame
+pub fn add_raster_timings(timings: &RasterTimings) {
+    with_profiler(|p| {
+        if p.visible {
+            p.frame.raster.accumulate(timings);
+        }
+    });
+}
+
+/// Call at the top of the main loop
+pub fn begin_frame() {
+    with_profiler(|p| {
+        if p.visible {
+            p.begin_frame(get_time());
+        }
+    });
+}
+
+/// Call once all the frame's work is done (before frame pacing): stores the
+/// frame and handles the show/hide key
+pub fn end_frame() {
+    with_profiler(|p| {
+        if p.visible {
+            p.end_frame(get_time());
+        }
+        if is_key_pressed(PROFILER_KEY) {
+            p.visible = !p.visible;
+            p.frame = FrameSample::default();
+            p.frame_start = None;
+        }
+    });
+}
+
+/// Draw the overlay in the top-right corner (call last, on top of everything)
+pub fn draw_overlay() {
+    with_profiler(|p| {
+        if p.visible {
+            draw_panel(p);
+        }
+    });
+}
+
+fn draw_panel(profiler: &FrameProfiler) {
+    const WIDTH: f32 = 300.0;
+    const GRAPH_HEIGHT: f32 = 60.0;
+    const LINE: f32 = 14.0;
+    const PAD: f32 = 6.0;
+    let text = Color::new(0.85, 0.85, 0.85, 1.0);
+    let dim = Color::new(0.6, 0.6, 0.65, 1.0);
+
+    // Subsystems with their three biggest labels, then the raster stages
+    let average = profiler.average();
+    let mut lines: Vec<(String, Color, Option<Color>)> = Vec::new();
+    for subsystem in Subsystem::ALL {
+        lines.push((format!("{:<10} {:6.2} ms", subsystem.label(), average.subsystem_ms[subsystem.index()]), text, Some(subsystem.color())));
+        let mut labels: Vec<_> = average.labels.iter().filter(|(s, _, _)| *s == subsystem).collect();
+        labels.sort_by(|a, b| b.2.total_cmp(&a.2));
+        for (_, label, ms) in labels.into_iter().take(3) {
+            lines.push((format!("  {:<20} {:6.2}", label, ms), dim, None));
+        }
+    }
+    lines.push((format!("{:<10} {:6.2} ms", "Other", average.other_ms()), text, Some(OTHER_COLOR)));
+    let raster = &average.raster;
+    if raster.triangles_drawn > 0 {
+        lines.push((
+            format!("xform {:.2} cull {:.2} sort {:.2} fill {:.2}", raster.transform_ms, raster.cull_ms, raster.sort_ms, raster.draw_ms),
+            dim,
+            None,
+        ));
+        lines.push((format!("{} triangles", raster.triangles_drawn), dim, None));
+    }
+
+    let height = PAD * 3.0 + LINE + GRAPH_HEIGHT + lines.len() as f32 * LINE;
+    let x = screen_width() - WIDTH - 8.0;
+    let y = 40.0;
+    draw_rectangle(x, y, WIDTH, height, Color::new(0.05, 0.05, 0.07, 0.85));
+    draw_rectangle_lines(x, y, WIDTH, height, 1.0, Color::new(0.3, 0.3, 0.35, 1.0));
+
+    let fps = if average.total_ms > 0.0 { get_fps() } else { 0 };
+    draw_text(&format!("Profiler (F9)  {:.2} ms  {} fps", average.total_ms, fps), x + PAD, y + PAD + 10.0, 13.0, WHITE);
+
+    // Rolling history: one stacked bar per frame, newest on the right
+    let graph = (x + PAD, y + PAD * 2.0 + LINE, WIDTH - PAD * 2.0, GRAPH_HEIGHT);
+    draw_rectangle(graph.0, graph.1, graph.2, graph.3, Color::new(0.1, 0.1, 0.12, 1.0));
+    let peak = profiler.history().map(|f| f.total_ms).fold(1000.0 / 30.0, f32::max);
+    let bar_width = graph.2 / HISTORY_FRAMES as f32;
+    let bottom = graph.1 + graph.3;
+    let first_x = graph.0 + graph.2 - profiler.history.len() as f32 * bar_width;
+    for (i, frame) in profiler.history().enumerate() {
+        let bar_x = first_x + i as f32 * bar_width;
+        let mut top = bottom;
+        let segments = Subsystem::ALL.iter().map(|s| (frame.subsystem_ms[s.index()], s.color()))
+            .chain(std::iter::once((frame.other_ms(), OTHER_COLOR)));
+        for (ms, color) in segments {
+            let h = ms / peak * graph.3;
+            draw_rectangle(bar_x, top - h, bar_width.max(1.0), h, color);
+            top -= h;
+        }
+    }
+    // 60 and 30 fps budgets
+    for budget in [1000.0 / 60.0, 1000.0 / 30.0] {
+        let line_y = bottom - budget / peak * graph.3;
+        draw_line(graph.0, line_y, graph.0 + graph.2, line_y, 1.0, Color::new(1.0, 1.0, 1.0, 0.25));
+    }
+
+    let mut line_y = graph.1 + graph.3 + PAD + 10.0;
+    for (line, color, swatch) in &lines {
+        if let Some(swatch) = swatch {
+            draw_rectangle(x + PAD, line_y - 8.0, 8.0, 8.0, *swatch);
+        }
+        draw_text(line, x + PAD + 14.0, line_y, 12.0, *color);
+        line_y += LINE;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_timers_count_own_time() {
+        let mut profiler = FrameProfiler::default();
+        profiler.begin_frame(0.0);
+        profiler.open(Subsystem::Ui, "World", 0.0);
+        profiler.open(Subsystem::Raster, "meshes", 0.002);
+        profiler.close(0.005);
+        profiler.open(Subsystem::Tick, "logic", 0.006);
+        profiler.relabel("sounds", 0.007);
+        profiler.close(0.008);
+        profiler.close(0.010);
+        profiler.end_frame(0.012);
+
+        let frame = profiler.history().last().unwrap();
+        let ms = |s: Subsystem| (frame.subsystem_ms[s.index()] * 1000.0).round() / 1000.0;
+        assert_eq!(ms(Subsystem::Raster), 3.0);
+        assert_eq!(ms(Subsystem::Tick), 2.0);
+        assert_eq!(ms(Subsystem::Ui), 5.0);
+        assert_eq!(frame.labels.len(), 4);
+        assert!((frame.total_ms - 12.0).abs() < 0.001);
+        assert!((frame.other_ms() - 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_history_rolls_and_averages() {
+        let mut profiler = FrameProfiler::default();
+        for i in 0..HISTORY_FRAMES + 10 {
+            let start = i as f64;
+            profiler.begin_frame(start);
+            profiler.frame.add(Subsystem::Audio, "mix", if i % 2 == 0 { 1.0 } else { 3.0 });
+            profiler.open(Subsystem::Tick, "systems", start);
+            profiler.end_frame(start + 0.004);
+        }
+        assert_eq!(profiler.history().count(), HISTORY_FRAMES);
+        let average = profiler.average();
+        assert!((average.subsystem_ms[Subsystem::Audio.index()] - 2.0).abs() < 0.001);
+        // The timer left open is closed at the end of its frame
+        assert!((average.subsystem_ms[Subsystem::Tick.index()] - 4.0).abs() < 0.001);
+        assert!((average.total_ms - 4.0).abs() < 0.001);
+    }
+}
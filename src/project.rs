@@ -4,14 +4,31 @@
 //! All editor tools reference ProjectData rather than owning copies.
 //! This enables live editing: changes in any editor are immediately
 //! visible in all other views including the game preview.
+//!
+//! Also tracks dependencies between project files. Levels use textures,
+//! assets and songs (via cutscene and script `music`), and assets use textures and drop
+//! other assets, all by name or id. `DependencyGraph` scans the saved user
+//! levels and assets so deletes can warn about files that still reference
+//! what is being removed, and the browsers can list an item's usages.
 
 // Allow unused - project structure for future use
 #![allow(dead_code)]
 
-use crate::world::Level;
-use crate::modeler::{RiggedModel, EditableMesh};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use macroquad::prelude::*;
+use crate::asset::{Asset, AssetComponent, USER_ASSETS_DIR};
+use crate::game::cutscene::{parse_timeline, CutsceneAction};
+use crate::game::script::music_songs;
+use crate::storage::Storage;
+use crate::texture::TextureLibrary;
+use crate::ui::{Rect, theme};
+use crate::world::{load_level_with_storage, Direction, Level, USER_TEXTURE_PACK};
+use crate::modeler::{RiggedModel, EditableMesh, MeshPart, TextureRef as MeshTextureRef};
 use crate::tracker::Song;
 
+/// Where user levels are saved
+pub const USER_LEVELS_DIR: &str = "assets/userdata/levels";
+
 /// Central container for all project data.
 ///
 /// This is the shared state that all editor tools reference.
@@ -58,3 +75,314 @@ impl Default for ProjectData {
         Self::new()
     }
 }
+
+/// A project file that other files can reference
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dependency {
+    /// User texture, by name
+    Texture(String),
+    /// User asset (model), by name
+    Asset(String),
+    /// Tracker song, by file name without extension
+    Song(String),
+}
+
+impl std::fmt::Display for Dependency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dependency::Texture(name) => write!(f, "texture '{}'", name),
+            Dependency::Asset(name) => write!(f, "asset '{}'", name),
+            Dependency::Song(name) => write!(f, "song '{}'", name),
+        }
+    }
+}
+
+/// A project file that references dependencies
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Referrer {
+    /// Level, by storage path
+    Level(String),
+    /// User asset, by name
+    Asset(String),
+}
+
+impl std::fmt::Display for Referrer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Referrer::Level(path) => write!(f, "level '{}'", file_stem(path)),
+            Referrer::Asset(name) => write!(f, "asset '{}'", name),
+        }
+    }
+}
+
+/// File name without directory and extension
+fn file_stem(path: &str) -> &str {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    name.strip_suffix(".ron").unwrap_or(name)
+}
+
+/// Which files use which textures, assets and songs
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    uses: BTreeMap<Referrer, BTreeSet<Dependency>>,
+    /// Asset names by id, for resolving level objects
+    asset_names: HashMap<u64, String>,
+}
+
+impl DependencyGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scan the saved user assets and levels. `textures` names the textures
+    /// assets reference by id. Cloud storage lists asynchronously, so there
+    /// the graph stays empty.
+    pub fn scan(storage: &Storage, textures: &TextureLibrary) -> Self {
+        let mut graph = Self::new();
+        for path in list_ron_files(storage, USER_ASSETS_DIR) {
+            let asset = storage.read_sync(&path).ok().and_then(|bytes| Asset::load_from_bytes(&bytes).ok());
+            if let Some(asset) = asset {
+                graph.add_asset(file_stem(&path), &asset, textures);
+            }
+        }
+        for path in list_ron_files(storage, USER_LEVELS_DIR) {
+            if let Ok(level) = load_level_with_storage(&path, storage) {
+                graph.add_level(&path, &level);
+            }
+        }
+        graph
+    }
+
+    /// Record (or replace) what an asset uses. Add assets before the levels
+    /// that place them, so level objects resolve to asset names.
+    pub fn add_asset(&mut self, name: &str, asset: &Asset, textures: &TextureLibrary) {
+        self.asset_names.insert(asset.id, name.to_string());
+        self.set_uses(Referrer::Asset(name.to_string()), asset_dependencies(asset, textures));
+    }
+
+    /// Record (or replace) what a level uses, e.g. the open level with
+    /// unsaved edits. Objects of assets the graph doesn't know are skipped.
+    pub fn add_level(&mut self, path: &str, level: &Level) {
+        self.set_uses(Referrer::Level(path.to_string()), level_dependencies(level, &self.asset_names));
+    }
+
+    /// Replace what a file uses (nothing removes it)
+    pub fn set_uses(&mut self, referrer: Referrer, dependencies: BTreeSet<Dependency>) {
+        if dependencies.is_empty() {
+            self.uses.remove(&referrer);
+        } else {
+            self.uses.insert(referrer, dependencies);
+        }
+    }
+
+    /// What a file uses
+    pub fn uses(&self, referrer: &Referrer) -> impl Iterator<Item = &Dependency> {
+        self.uses.get(referrer).into_iter().flatten()
+    }
+
+    /// Files that use a dependency, sorted (levels first)
+    pub fn usages(&self, dependency: &Dependency) -> Vec<&Referrer> {
+        self.uses.iter()
+            .filter(|(_, deps)| deps.contains(dependency))
+            .map(|(referrer, _)| referrer)
+            .collect()
+    }
+}
+
+/// Storage paths of the .ron files in a directory (empty while cloud-backed)
+fn list_ron_files(storage: &Storage, dir: &str) -> Vec<String> {
+    if !storage.is_sync(dir) {
+        return Vec::new();
+    }
+    storage.list_sync(dir).unwrap_or_default()
+        .into_iter()
+        .filter(|f| f.ends_with(".ron"))
+        // Cloud listings return full paths, local ones just file names
+        .map(|f| if f.contains('/') { f } else { format!("{}/{}", dir, f) })
+        .collect()
+}
+
+/// User textures on faces, placed assets and the songs cutscenes and scripts play
+pub fn level_dependencies(level: &Level, asset_names: &HashMap<u64, String>) -> BTreeSet<Dependency> {
+    let mut deps = BTreeSet::new();
+    for room in &level.rooms {
+        for (_, _, sector) in room.iter_sectors() {
+            let floors = sector.floor.iter().chain(sector.ceiling.iter())
+                .flat_map(|face| std::iter::once(&face.texture).chain(face.texture_2.as_ref()));
+            let walls = Direction::ALL.into_iter()
+                .flat_map(|dir| sector.walls(dir).iter().map(|wall| &wall.texture));
+            for tex in floors.chain(walls) {
+                if tex.pack == USER_TEXTURE_PACK && !tex.name.is_empty() {
+                    deps.insert(Dependency::Texture(tex.name.clone()));
+                }
+            }
+        }
+        for obj in &room.objects {
+            if let Some(name) = asset_names.get(&obj.asset_id) {
+                deps.insert(Dependency::Asset(name.clone()));
+            }
+        }
+    }
+    for cutscene in &level.cutscenes {
+        for event in parse_timeline(&cutscene.timeline).unwrap_or_default() {
+            if let CutsceneAction::PlayMusic { song, .. } = event.action {
+                deps.insert(Dependency::Song(song));
+            }
+        }
+    }
+    for script in &level.scripts {
+        deps.extend(music_songs(&script.source).into_iter().map(Dependency::Song));
+    }
+    deps
+}
+
+/// Textures on mesh parts and sprites, and dropped assets
+pub fn asset_dependencies(asset: &Asset, textures: &TextureLibrary) -> BTreeSet<Dependency> {
+    let mut deps = BTreeSet::new();
+    let mut texture = |id: u64| {
+        if let Some(tex) = textures.get_by_id(id) {
+            deps.insert(Dependency::Texture(tex.name.clone()));
+        }
+    };
+    let mut drops = Vec::new();
+    for component in &asset.components {
+        let parts: Vec<&Vec<MeshPart>> = match component {
+            AssetComponent::Mesh { parts } => vec![parts],
+            AssetComponent::Lod { reduced, impostor, .. } => vec![reduced, impostor],
+            AssetComponent::Destructible { broken, drop, .. } => {
+                drops.extend(drop.iter().filter(|d| !d.is_empty()).cloned());
+                vec![broken]
+            }
+            AssetComponent::Sprite { texture: Some(id), .. } => {
+                texture(*id);
+                Vec::new()
+            }
+            _ => Vec::new(),
+        };
+        for part in parts.into_iter().flatten() {
+            if let MeshTextureRef::Id(id) = &part.texture_ref {
+                texture(*id);
+            }
+        }
+    }
+    deps.extend(drops.into_iter().map(Dependency::Asset));
+    deps
+}
+
+/// Find-usages result for one dependency, as shown by the browsers
+#[derive(Debug, Clone)]
+pub struct UsagesPanel {
+    pub dependency: Dependency,
+    pub users: Vec<Referrer>,
+    /// Shown because of a delete: deleting again goes ahead
+    pub delete_warning: bool,
+}
+
+impl UsagesPanel {
+    pub fn find(graph: &DependencyGraph, dependency: Dependency) -> Self {
+        let users = graph.usages(&dependency).into_iter().cloned().collect();
+        Self { dependency, users, delete_warning: false }
+    }
+}
+
+/// Gate a delete behind a usages warning. Returns true when the delete can
+/// go ahead: nothing uses the dependency, or its warning is already showing
+/// (delete was pressed twice). Otherwise `panel` becomes the warning.
+pub fn confirm_delete(panel: &mut Option<UsagesPanel>, graph: &DependencyGraph, dependency: Dependency) -> bool {
+    if panel.as_ref().is_some_and(|p| p.delete_warning && p.dependency == dependency) {
+        *panel = None;
+        return true;
+    }
+    let usages = UsagesPanel::find(graph, dependency);
+    if usages.users.is_empty() {
+        *panel = None;
+        return true;
+    }
+    *panel = Some(UsagesPanel { delete_warning: true, ..usages });
+    false
+}
+
+/// Draw a usages list into `rect`; rows that don't fit are counted instead
+pub fn draw_usages_panel(rect: Rect, panel: &UsagesPanel) {
+    let line_height = 16.0;
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(30, 30, 36, 235));
+    let border = if panel.delete_warning { Color::from_rgba(160, 80, 80, 255) } else { Color::from_rgba(70, 70, 80, 255) };
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, border);
+
+    let (header, color) = match (panel.users.len(), panel.delete_warning) {
+        (0, _) => (format!("{} is not used by any level or asset", panel.dependency), theme().text_dim),
+        (n, true) => (format!("Still used by {} file(s), delete again to remove anyway", n), Color::from_rgba(230, 140, 120, 255)),
+        (n, false) => (format!("{} is used by {} file(s)", panel.dependency, n), theme().text),
+    };
+    draw_text(&header, rect.x + 6.0, rect.y + 14.0, 13.0, color);
+
+    let rows = (((rect.h - 22.0) / line_height).floor().max(0.0) as usize).min(panel.users.len());
+    let shown = if rows < panel.users.len() { rows.saturating_sub(1) } else { rows };
+    let mut y = rect.y + 20.0;
+    for referrer in &panel.users[..shown] {
+        draw_text(&referrer.to_string(), rect.x + 14.0, y + 12.0, 12.0, theme().text);
+        y += line_height;
+    }
+    if shown < panel.users.len() {
+        draw_text(&format!("... and {} more", panel.users.len() - shown), rect.x + 14.0, y + 12.0, 12.0, theme().text_dim);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{create_empty_level, AssetInstance, Cutscene, LevelScript, TextureRef};
+
+    #[test]
+    fn test_level_dependencies() {
+        let mut level = create_empty_level();
+        let room = &mut level.rooms[0];
+        room.get_sector_mut(0, 0).unwrap().floor.as_mut().unwrap().texture = TextureRef::new(USER_TEXTURE_PACK, "bricks");
+        room.objects.push(AssetInstance::new(0, 0, 7));
+        room.objects.push(AssetInstance::new(0, 0, 99));
+        let mut cutscene = Cutscene::new("intro");
+        cutscene.timeline = "0 music theme".to_string();
+        level.cutscenes.push(cutscene);
+        let mut script = LevelScript::new("arena");
+        script.source = "on enter arena\n  music play boss bar\nend\n".to_string();
+        level.scripts.push(script);
+
+        let names = HashMap::from([(7, "crate".to_string())]);
+        let deps = level_dependencies(&level, &names);
+        assert!(deps.contains(&Dependency::Texture("bricks".to_string())));
+        assert!(deps.contains(&Dependency::Asset("crate".to_string())));
+        assert!(deps.contains(&Dependency::Song("theme".to_string())));
+        assert!(deps.contains(&Dependency::Song("boss".to_string())));
+        // Sample pack textures and unknown (sample) assets are left out
+        assert_eq!(deps.len(), 4);
+    }
+
+    #[test]
+    fn test_usages_and_delete_warning() {
+        let mut graph = DependencyGraph::new();
+        let crate_asset = Dependency::Asset("crate".to_string());
+        graph.set_uses(Referrer::Level("assets/userdata/levels/b.ron".to_string()), BTreeSet::from([crate_asset.clone()]));
+        graph.set_uses(Referrer::Level("assets/userdata/levels/a.ron".to_string()), BTreeSet::from([crate_asset.clone()]));
+        graph.set_uses(Referrer::Asset("barrel".to_string()), BTreeSet::from([Dependency::Texture("wood".to_string())]));
+
+        let users = graph.usages(&crate_asset);
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].to_string(), "level 'a'");
+        assert!(graph.usages(&Dependency::Song("theme".to_string())).is_empty());
+
+        // Unused: deletes right away
+        let mut panel = None;
+        assert!(confirm_delete(&mut panel, &graph, Dependency::Texture("stone".to_string())));
+        assert!(panel.is_none());
+
+        // Used: warns first, the second delete goes ahead
+        assert!(!confirm_delete(&mut panel, &graph, crate_asset.clone()));
+        assert!(panel.as_ref().is_some_and(|p| p.delete_warning && p.users.len() == 2));
+        assert!(confirm_delete(&mut panel, &graph, crate_asset.clone()));
+        assert!(panel.is_none());
+
+        // Replacing a file's uses drops stale entries
+        graph.set_uses(Referrer::Asset("barrel".to_string()), BTreeSet::new());
+        assert!(graph.usages(&Dependency::Texture("wood".to_string())).is_empty());
+    }
+}
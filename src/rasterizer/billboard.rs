@@ -0,0 +1,136 @@
+//! Billboards
+//!
+//! Camera-facing textured quads for the things old games drew flat: pickups,
+//! flames, grass. A billboard is projected around its center, sized by
+//! distance and filled with its texture unfiltered, like the rest of the
+//! rasterizer. Every pixel is depth-tested against the z-buffer so sprites
+//! sort among level geometry. Opaque billboards also write depth; blended
+//! ones don't, so draw them after opaque geometry, back to front.
+
+use super::camera::Camera;
+use super::math::{perspective_transform, project, Vec3, NEAR_PLANE};
+use super::render::Framebuffer;
+use super::types::{BlendMode, Texture};
+
+/// A camera-facing quad
+#[derive(Debug, Clone, Copy)]
+pub struct Billboard {
+    /// World-space center
+    pub position: Vec3,
+    /// World-space width
+    pub width: f32,
+    /// World-space height
+    pub height: f32,
+    /// How the texture combines with the scene (Opaque writes depth)
+    pub blend: BlendMode,
+}
+
+impl Billboard {
+    /// Camera-space depth of the center, or None behind the near plane
+    pub fn depth(&self, camera: &Camera) -> Option<f32> {
+        let cam = perspective_transform(self.position - camera.position, camera.basis_x, camera.basis_y, camera.basis_z);
+        (cam.z >= NEAR_PLANE).then_some(cam.z)
+    }
+}
+
+/// Fill a billboard with `texture`. Transparent texels are skipped and
+/// pixels behind the scene are left alone.
+pub fn draw_billboard(fb: &mut Framebuffer, camera: &Camera, billboard: &Billboard, texture: &Texture) {
+    if texture.width == 0 || texture.height == 0 {
+        return;
+    }
+    let cam = perspective_transform(billboard.position - camera.position, camera.basis_x, camera.basis_y, camera.basis_z);
+    if cam.z < NEAR_PLANE {
+        return;
+    }
+    let center = project(cam, fb.width, fb.height);
+    let right = project(Vec3::new(cam.x + billboard.width * 0.5, cam.y, cam.z), fb.width, fb.height);
+    let top = project(Vec3::new(cam.x, cam.y + billboard.height * 0.5, cam.z), fb.width, fb.height);
+    let half_w = (right.x - center.x).abs().max(0.5);
+    let half_h = (top.y - center.y).abs().max(0.5);
+
+    let (left, top) = (center.x - half_w, center.y - half_h);
+    let x0 = (left.round() as i32).max(0);
+    let y0 = (top.round() as i32).max(0);
+    let x1 = ((center.x + half_w).round() as i32).min(fb.width as i32);
+    let y1 = ((center.y + half_h).round() as i32).min(fb.height as i32);
+    let depth = cam.z;
+
+    for y in y0..y1 {
+        let v = (y as f32 + 0.5 - top) / (half_h * 2.0);
+        for x in x0..x1 {
+            let (px, py) = (x as usize, y as usize);
+            if depth > fb.zbuffer[py * fb.width + px] {
+                continue;
+            }
+            let u = (x as f32 + 0.5 - left) / (half_w * 2.0);
+            let texel = texture.sample(u.clamp(0.0, 0.999), v.clamp(0.0, 0.999));
+            if texel.is_transparent() {
+                continue;
+            }
+            match billboard.blend {
+                BlendMode::Opaque => {
+                    fb.set_pixel_with_depth(px, py, depth, texel);
+                }
+                mode => fb.set_pixel_blended(px, py, texel, mode),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rasterizer::Color;
+
+    fn solid(color: Color) -> Texture {
+        let mut texture = Texture::new(4, 4);
+        texture.pixels.iter_mut().for_each(|p| *p = color);
+        texture
+    }
+
+    #[test]
+    fn test_billboard_depth_tested_and_writes_depth() {
+        let mut fb = Framebuffer::new(64, 64);
+        fb.clear(Color::new(0, 0, 0));
+        let camera = Camera::new();
+        let billboard = Billboard {
+            position: Vec3::new(0.0, 0.0, 2000.0),
+            width: 400.0,
+            height: 400.0,
+            blend: BlendMode::Opaque,
+        };
+        let center = 32 * 64 + 32;
+        draw_billboard(&mut fb, &camera, &billboard, &solid(Color::new(200, 0, 0)));
+        assert_eq!(fb.pixels[center * 4], 200);
+        assert!(fb.zbuffer[center] < f32::MAX);
+        assert_eq!(Some(fb.zbuffer[center]), billboard.depth(&camera));
+
+        // Geometry in front hides it
+        fb.clear(Color::new(0, 0, 0));
+        fb.zbuffer.iter_mut().for_each(|z| *z = 10.0);
+        draw_billboard(&mut fb, &camera, &billboard, &solid(Color::new(200, 0, 0)));
+        assert_eq!(fb.pixels[center * 4], 0);
+    }
+
+    #[test]
+    fn test_billboard_skips_transparent_texels_and_blends() {
+        let mut fb = Framebuffer::new(64, 64);
+        fb.clear(Color::new(10, 10, 10));
+        let camera = Camera::new();
+        let mut billboard = Billboard {
+            position: Vec3::new(0.0, 0.0, 2000.0),
+            width: 400.0,
+            height: 400.0,
+            blend: BlendMode::Add,
+        };
+        let center = 32 * 64 + 32;
+        draw_billboard(&mut fb, &camera, &billboard, &solid(Color::new(100, 0, 0)));
+        assert_eq!(fb.pixels[center * 4], 110);
+
+        fb.clear(Color::new(10, 10, 10));
+        billboard.blend = BlendMode::Opaque;
+        draw_billboard(&mut fb, &camera, &billboard, &solid(Color::TRANSPARENT));
+        assert_eq!(fb.pixels[center * 4], 10);
+    }
+}
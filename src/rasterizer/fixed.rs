@@ -2,7 +2,9 @@
 //!
 //! The PS1's GTE (Geometry Transform Engine) used specific fixed-point formats:
 //! - 1.3.12 format for coordinates and matrices (16-bit: 1 sign, 3 integer, 12 fractional)
-//! - Screen coordinates output as integers (no subpixel precision)
+//! - Screen coordinates output as integers (no subpixel precision), snapped to
+//!   whole PS1 pixels at higher resolutions (see `snap_grid`); projects can
+//!   coarsen or soften the snapping (`VertexSnap`)
 //! - UNR (Unsigned Newton-Raphson) division which introduces precision errors
 //!
 //! This module replicates these limitations to achieve authentic PS1 vertex jitter.
@@ -380,18 +382,26 @@ pub fn transform_to_camera_space(
     FixedVec3::new(cx, cy, cz)
 }
 
+/// Vertex snapping step in framebuffer pixels: one PS1 pixel, so a 640x480
+/// framebuffer snaps to a 2x2 grid and wobbles like 320x240 does
+pub fn snap_grid(height: usize) -> i32 {
+    (height / super::HEIGHT).max(1) as i32
+}
+
 /// Project camera-space coordinates to screen using PS1-style fixed-point math
-/// Returns integer screen coordinates (no subpixel precision) and depth
+/// Returns snapped screen coordinates (whole pixels at full jitter) and depth
 ///
 /// This is where the famous PS1 jitter comes from:
 /// 1. All math done in 4.12 fixed-point
 /// 2. Division uses inaccurate UNR algorithm
-/// 3. Final coordinates are integer-only (floored)
+/// 3. Final coordinates are integer-only (floored to the snap grid, then
+///    moved `snap.jitter` of the way there from the exact position)
 pub fn project_to_screen(
     cam_pos: FixedVec3,
     width: usize,
     height: usize,
-) -> (i32, i32, Fixed32) {
+    snap: super::VertexSnap,
+) -> (f32, f32, Fixed32) {
     // Projection constants (same as float version)
     let distance = Fixed32::from_f32(5.0);
     let scale = Fixed32::from_f32(4.0); // us = distance - 1
@@ -404,7 +414,7 @@ pub fn project_to_screen(
 
     // Check for near-zero denominator
     if denom.0.abs() < 256 { // ~0.0625 in 4.12
-        return (half_w.floor(), half_h.floor(), cam_pos.z);
+        return (half_w.floor() as f32, half_h.floor() as f32, cam_pos.z);
     }
 
     // This is the key: PS1-style UNR division (inaccurate!)
@@ -415,12 +425,20 @@ pub fn project_to_screen(
     let screen_x = proj_x * viewport_scale + half_w;
     let screen_y = proj_y * viewport_scale + half_h;
 
-    // Floor to integers - PS1 GPU only accepts integer coordinates
-    (screen_x.floor(), screen_y.floor(), cam_pos.z)
+    // Floor to integers - PS1 GPU only accepts integer coordinates (whole
+    // PS1 pixels above 240 lines)
+    let grid = snap.grid(height);
+    let jitter = snap.jitter.clamp(0.0, 1.0);
+    let snapped = |v: Fixed32| {
+        let exact = v.to_f32();
+        let snapped = (v.floor().div_euclid(grid) * grid) as f32;
+        exact + (snapped - exact) * jitter
+    };
+    (snapped(screen_x), snapped(screen_y), cam_pos.z)
 }
 
 /// Complete PS1-style vertex transformation pipeline
-/// Takes world coordinates and returns integer screen coordinates
+/// Takes world coordinates and returns snapped screen coordinates
 pub fn project_fixed(
     world_pos: super::Vec3,
     camera_pos: super::Vec3,
@@ -429,14 +447,15 @@ pub fn project_fixed(
     basis_z: super::Vec3,
     width: usize,
     height: usize,
-) -> (i32, i32, f32) {
+    snap: super::VertexSnap,
+) -> (f32, f32, f32) {
     // Transform to camera space (in fixed-point)
     let cam_pos = transform_to_camera_space(world_pos, camera_pos, basis_x, basis_y, basis_z);
 
-    // Project to screen (in fixed-point, returns integers)
-    let (sx, sy, depth) = project_to_screen(cam_pos, width, height);
+    // Project to screen (in fixed-point, snapped)
+    let (sx, sy, depth) = project_to_screen(cam_pos, width, height, snap);
 
-    // Return screen integers + depth as float (for z-buffer compatibility)
+    // Return screen position + depth as float (for z-buffer compatibility)
     (sx, sy, depth.to_f32())
 }
 
@@ -473,6 +492,7 @@ impl FixedVec2 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::VertexSnap;
 
     #[test]
     fn test_fixed32_precision() {
@@ -540,10 +560,47 @@ mod tests {
         let basis_y = Vec3::new(0.0, 1.0, 0.0);
         let basis_z = Vec3::new(0.0, 0.0, 1.0);
 
-        let (x, y, _z) = project_fixed(world_pos, camera_pos, basis_x, basis_y, basis_z, 320, 240);
+        let (x, y, _z) = project_fixed(world_pos, camera_pos, basis_x, basis_y, basis_z, 320, 240, VertexSnap::default());
 
         // Result should be reasonable screen coordinates
-        assert!(x > -1000 && x < 1000);
-        assert!(y > -1000 && y < 1000);
+        assert!(x > -1000.0 && x < 1000.0);
+        assert!(y > -1000.0 && y < 1000.0);
+        assert_eq!((x.fract(), y.fract()), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_high_resolution_snaps_to_ps1_pixels() {
+        use super::super::Vec3;
+
+        assert_eq!(snap_grid(240), 1);
+        assert_eq!(snap_grid(480), 2);
+        let basis = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        for i in 0..16 {
+            let world_pos = Vec3::new(i as f32 * 0.173 - 1.3, 0.911 - i as f32 * 0.127, 3.0 + i as f32 * 0.31);
+            let (x, y, _z) = project_fixed(world_pos, Vec3::ZERO, basis.0, basis.1, basis.2, 640, 480, VertexSnap::default());
+            assert_eq!((x.rem_euclid(2.0), y.rem_euclid(2.0)), (0.0, 0.0), "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_vertex_snap_size_and_jitter() {
+        use super::super::Vec3;
+
+        let basis = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let coarse = VertexSnap { size: 4, jitter: 1.0 };
+        let smooth = VertexSnap { size: 4, jitter: 0.0 };
+        let half = VertexSnap { size: 4, jitter: 0.5 };
+        assert_eq!(coarse.grid(480), 8);
+        assert_eq!(VertexSnap { size: 0, jitter: 1.0 }.grid(240), 1);
+        for i in 0..16 {
+            let world_pos = Vec3::new(i as f32 * 0.173 - 1.3, 0.911 - i as f32 * 0.127, 3.0 + i as f32 * 0.31);
+            let project = |snap| project_fixed(world_pos, Vec3::ZERO, basis.0, basis.1, basis.2, 640, 480, snap);
+            let (x, y, _) = project(coarse);
+            assert_eq!((x.rem_euclid(8.0), y.rem_euclid(8.0)), (0.0, 0.0), "({}, {})", x, y);
+            // No jitter keeps the exact position; half jitter lands halfway
+            let (ex, ey, _) = project(smooth);
+            let (hx, hy, _) = project(half);
+            assert!((hx - (x + ex) / 2.0).abs() < 1e-3 && (hy - (y + ey) / 2.0).abs() < 1e-3);
+        }
     }
 }
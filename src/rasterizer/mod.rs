@@ -14,6 +14,9 @@
 //! - `math` - Vec3, Vec2, projection functions, clipping, geometry utilities
 //! - `camera` - Camera struct for 3D rendering
 //! - `render` - Framebuffer and mesh rendering functions
+//! - `present` - Framebuffer upload to the GPU (dirty rects, redraw gating)
+//! - `billboard` - Camera-facing textured quads (sprites)
+//! - `simd` - SSE2/NEON span setup used by the triangle rasterizers
 //! - `draw` - Drawing utilities (lines, grids, test geometry)
 //! - `constants` - Screen resolution constants
 //! - `ray` - Ray casting utilities
@@ -22,13 +25,16 @@
 #![allow(dead_code)]
 
 // Sub-modules (exposed for namespaced access)
+pub mod billboard;
 pub mod camera;
 pub mod constants;
 pub mod draw;
 pub mod fixed;
 pub mod math;
+pub mod present;
 pub mod ray;
 pub mod render;
+pub mod simd;
 pub mod types;
 
 // =============================================================================
@@ -40,7 +46,7 @@ pub use types::{
     BlendMode, Clut, ClutDepth, ClutId, Color, Color15,
     Face, IndexedTexture, Light, LightType, OrthoProjection,
     RasterSettings, RasterTimings, ShadingMode,
-    Texture, Texture15, Vertex,
+    Texture, Texture15, Vertex, VertexSnap, MAX_SNAP_SIZE,
 };
 
 // Math - vectors, matrices, and projection
@@ -60,7 +66,13 @@ pub use math::{
 pub use camera::Camera;
 
 // Render - framebuffer and mesh rendering
-pub use render::{Framebuffer, render_mesh, render_mesh_15};
+pub use render::{Framebuffer, render_mesh, render_mesh_with_clock, render_mesh_15};
+
+// Presentation - framebuffer upload and redraw skipping
+pub use present::{FramebufferTexture, RedrawGate, redraw_key};
+
+// Billboards - camera-facing sprites
+pub use billboard::{Billboard, draw_billboard};
 
 // Draw utilities
 pub use draw::{draw_3d_line_clipped, draw_floor_grid, create_test_cube};
@@ -0,0 +1,156 @@
+//! Framebuffer presentation
+//!
+//! Gets finished framebuffers onto the GPU without creating a texture every
+//! frame. `FramebufferTexture` keeps one texture per view and uploads only the
+//! region the framebuffer marked dirty since the last upload, so a frame where
+//! nothing was drawn costs no upload at all. `RedrawGate` lets a view skip
+//! rasterizing altogether while the inputs it renders from stay the same.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use macroquad::prelude::{FilterMode, Image, Texture2D};
+use super::render::{DirtyRect, Framebuffer};
+
+/// A GPU texture mirroring a framebuffer
+#[derive(Default)]
+pub struct FramebufferTexture {
+    texture: Option<Texture2D>,
+    size: (usize, usize),
+    /// Reused buffer for partial uploads
+    scratch: Vec<u8>,
+}
+
+impl FramebufferTexture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bring the texture up to date with `fb` and return it. Only the dirty
+    /// region is uploaded; a clean framebuffer uploads nothing.
+    pub fn upload(&mut self, fb: &mut Framebuffer) -> &Texture2D {
+        let size = (fb.width, fb.height);
+        let dirty = fb.take_dirty();
+        let texture = match self.texture.take() {
+            Some(texture) if self.size == size => {
+                if let Some(rect) = dirty {
+                    self.update(&texture, fb, rect);
+                }
+                texture
+            }
+            _ => {
+                let texture = Texture2D::from_rgba8(fb.width as u16, fb.height as u16, &fb.pixels);
+                texture.set_filter(FilterMode::Nearest);
+                texture
+            }
+        };
+        self.size = size;
+        self.texture.insert(texture)
+    }
+
+    fn update(&mut self, texture: &Texture2D, fb: &Framebuffer, rect: DirtyRect) {
+        if rect.width() == fb.width && rect.height() == fb.height {
+            texture.update_from_bytes(fb.width as u32, fb.height as u32, &fb.pixels);
+            return;
+        }
+        copy_region(fb, rect, &mut self.scratch);
+        let image = Image {
+            bytes: std::mem::take(&mut self.scratch),
+            width: rect.width() as u16,
+            height: rect.height() as u16,
+        };
+        texture.update_part(&image, rect.x0 as i32, rect.y0 as i32, rect.width() as i32, rect.height() as i32);
+        self.scratch = image.bytes;
+    }
+
+    /// Forget the texture; the next upload recreates it in full
+    pub fn reset(&mut self) {
+        self.texture = None;
+    }
+}
+
+/// Copy the RGBA rows of `rect` out of `fb` into `out` (tightly packed)
+fn copy_region(fb: &Framebuffer, rect: DirtyRect, out: &mut Vec<u8>) {
+    out.clear();
+    for y in rect.y0..rect.y1 {
+        let row = (y * fb.width + rect.x0) * 4;
+        out.extend_from_slice(&fb.pixels[row..row + rect.width() * 4]);
+    }
+}
+
+/// Decides whether a view has to be re-rendered, by comparing a key built
+/// from everything the render depends on (camera, selection, size, ...)
+#[derive(Debug, Default)]
+pub struct RedrawGate {
+    last_key: Option<u64>,
+}
+
+impl RedrawGate {
+    /// True if `key` differs from the last rendered one (or after `invalidate`)
+    pub fn needs_redraw(&mut self, key: u64) -> bool {
+        if self.last_key == Some(key) {
+            return false;
+        }
+        self.last_key = Some(key);
+        true
+    }
+
+    /// Force the next `needs_redraw` to return true (e.g. the content changed)
+    pub fn invalidate(&mut self) {
+        self.last_key = None;
+    }
+}
+
+/// Hash render inputs into a `RedrawGate` key
+pub fn redraw_key(inputs: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    inputs.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rasterizer::Color;
+
+    #[test]
+    fn test_dirty_rect_tracking() {
+        let mut fb = Framebuffer::new(8, 8);
+        // A new framebuffer has never been presented
+        assert!(fb.take_dirty().is_some());
+        assert!(!fb.is_dirty());
+
+        fb.set_pixel(2, 3, Color::new(255, 0, 0));
+        fb.set_pixel(5, 1, Color::new(255, 0, 0));
+        // Out of bounds writes are ignored
+        fb.set_pixel(20, 20, Color::new(255, 0, 0));
+        assert_eq!(fb.take_dirty(), Some(DirtyRect { x0: 2, y0: 1, x1: 6, y1: 4 }));
+
+        fb.clear(Color::new(0, 0, 0));
+        assert_eq!(fb.take_dirty(), Some(DirtyRect { x0: 0, y0: 0, x1: 8, y1: 8 }));
+        assert_eq!(fb.take_dirty(), None);
+    }
+
+    #[test]
+    fn test_copy_region() {
+        let mut fb = Framebuffer::new(4, 4);
+        fb.clear(Color::new(0, 0, 0));
+        fb.set_pixel(1, 1, Color::new(10, 20, 30));
+        fb.set_pixel(2, 2, Color::new(40, 50, 60));
+        let mut out = Vec::new();
+        copy_region(&fb, DirtyRect { x0: 1, y0: 1, x1: 3, y1: 3 }, &mut out);
+        assert_eq!(out.len(), 2 * 2 * 4);
+        assert_eq!(&out[0..3], &[10, 20, 30]);
+        assert_eq!(&out[12..15], &[40, 50, 60]);
+    }
+
+    #[test]
+    fn test_redraw_gate() {
+        let mut gate = RedrawGate::default();
+        let key = redraw_key((1u32, 2.5f32.to_bits()));
+        assert!(gate.needs_redraw(key));
+        assert!(!gate.needs_redraw(key));
+        assert!(gate.needs_redraw(redraw_key((1u32, 3.0f32.to_bits()))));
+        gate.invalidate();
+        assert!(gate.needs_redraw(redraw_key((1u32, 3.0f32.to_bits()))));
+    }
+}
@@ -4,14 +4,51 @@
 use macroquad::prelude::get_time;
 use super::camera::Camera;
 use super::math::{perspective_transform, project, project_ortho, Vec3, NEAR_PLANE};
+use super::simd::{SpanSetup, LANES};
 use super::types::{BlendMode, Color, Color15, Clut, Face, IndexedTexture, Light, LightType, RasterSettings, RasterTimings, ShadingMode, Texture, Texture15, Vertex};
 
+/// Pixel region changed since a framebuffer was last presented
+/// (half-open bounds: x0..x1, y0..y1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirtyRect {
+    pub x0: usize,
+    pub y0: usize,
+    pub x1: usize,
+    pub y1: usize,
+}
+
+impl DirtyRect {
+    pub fn width(&self) -> usize {
+        self.x1 - self.x0
+    }
+
+    pub fn height(&self) -> usize {
+        self.y1 - self.y0
+    }
+
+    /// Smallest rect covering both
+    pub fn union(self, other: DirtyRect) -> DirtyRect {
+        DirtyRect {
+            x0: self.x0.min(other.x0),
+            y0: self.y0.min(other.y0),
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+        }
+    }
+}
+
 /// Framebuffer for software rendering
+///
+/// Writes through the Framebuffer methods are tracked as a dirty rect so the
+/// presenter (`rasterizer::present`) can skip unchanged frames. Code writing
+/// `pixels` directly must call `mark_dirty` for what it touched.
 pub struct Framebuffer {
     pub pixels: Vec<u8>,    // RGBA, 4 bytes per pixel
     pub zbuffer: Vec<f32>,  // Depth buffer
     pub width: usize,
     pub height: usize,
+    /// Region written since the last `take_dirty`
+    dirty: Option<DirtyRect>,
 }
 
 impl Framebuffer {
@@ -21,6 +58,7 @@ impl Framebuffer {
             zbuffer: vec![f32::MAX; width * height],
             width,
             height,
+            dirty: Some(DirtyRect { x0: 0, y0: 0, x1: width, y1: height }),
         }
     }
 
@@ -30,10 +68,40 @@ impl Framebuffer {
             self.height = height;
             self.pixels = vec![0; width * height * 4];
             self.zbuffer = vec![f32::MAX; width * height];
+            self.mark_all_dirty();
+        }
+    }
+
+    /// Record that pixels in x0..x1, y0..y1 changed (clamped to the framebuffer)
+    pub fn mark_dirty(&mut self, x0: usize, y0: usize, x1: usize, y1: usize) {
+        let (x1, y1) = (x1.min(self.width), y1.min(self.height));
+        if x0 >= x1 || y0 >= y1 {
+            return;
         }
+        let rect = DirtyRect { x0, y0, x1, y1 };
+        self.dirty = Some(match self.dirty {
+            Some(dirty) => dirty.union(rect),
+            None => rect,
+        });
+    }
+
+    /// Record that the whole framebuffer changed
+    pub fn mark_all_dirty(&mut self) {
+        self.mark_dirty(0, 0, self.width, self.height);
+    }
+
+    /// Whether anything changed since the last `take_dirty`
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.is_some()
+    }
+
+    /// Region changed since the last call, resetting the tracking
+    pub fn take_dirty(&mut self) -> Option<DirtyRect> {
+        self.dirty.take()
     }
 
     pub fn clear(&mut self, color: Color) {
+        self.mark_all_dirty();
         for i in 0..(self.width * self.height) {
             let bytes = color.to_bytes();
             self.pixels[i * 4] = bytes[0];
@@ -46,6 +114,7 @@ impl Framebuffer {
 
     /// Clear framebuffer with transparent black (for alpha compositing)
     pub fn clear_transparent(&mut self) {
+        self.mark_all_dirty();
         for i in 0..(self.width * self.height) {
             self.pixels[i * 4] = 0;
             self.pixels[i * 4 + 1] = 0;
@@ -58,6 +127,7 @@ impl Framebuffer {
     /// Clear framebuffer with a vertical gradient (skybox effect)
     /// top_color at y=0, bottom_color at y=height-1
     pub fn clear_gradient(&mut self, top_color: Color, bottom_color: Color) {
+        self.mark_all_dirty();
         let h = self.height;
         for y in 0..h {
             // Linear interpolation factor (0.0 at top, 1.0 at bottom)
@@ -85,6 +155,7 @@ impl Framebuffer {
         time: f32,
     ) {
         use super::math::{perspective_transform, project};
+        self.mark_all_dirty();
 
         // 1. Render base skybox sphere (gradient + sun glow + clouds baked in vertex colors)
         let cam_pos = (camera.position.x, camera.position.y, camera.position.z);
@@ -298,40 +369,65 @@ impl Framebuffer {
         }
     }
 
-    pub fn set_pixel(&mut self, x: usize, y: usize, color: Color) {
-        if x < self.width && y < self.height {
-            let idx = (y * self.width + x) * 4;
-            let bytes = color.to_bytes();
-            self.pixels[idx] = bytes[0];
-            self.pixels[idx + 1] = bytes[1];
-            self.pixels[idx + 2] = bytes[2];
-            self.pixels[idx + 3] = bytes[3];
+    /// View of the whole framebuffer as a single band (marks it all dirty)
+    #[inline]
+    pub fn band(&mut self) -> FramebufferBand<'_> {
+        self.mark_all_dirty();
+        FramebufferBand {
+            pixels: &mut self.pixels,
+            zbuffer: &mut self.zbuffer,
+            width: self.width,
+            y_start: 0,
+            y_end: self.height,
         }
     }
 
-    /// Set pixel with PS1-style blending
-    pub fn set_pixel_blended(&mut self, x: usize, y: usize, color: Color, mode: BlendMode) {
-        if x < self.width && y < self.height {
-            let idx = (y * self.width + x) * 4;
-
-            // Read existing pixel (back) - framebuffer stores RGBA with 255 = opaque
-            let back = Color::with_blend(
-                self.pixels[idx],
-                self.pixels[idx + 1],
-                self.pixels[idx + 2],
-                BlendMode::Opaque, // Framebuffer pixels are always opaque
-            );
+    /// Split the framebuffer into `count` horizontal bands of (nearly) equal height.
+    /// Bands don't overlap, so each one can be rasterized on its own thread.
+    pub fn bands(&mut self, count: usize) -> Vec<FramebufferBand<'_>> {
+        self.mark_all_dirty();
+        let width = self.width;
+        let rows_per_band = self.height.div_ceil(count.max(1)).max(1);
+        self.pixels
+            .chunks_mut(rows_per_band * width * 4)
+            .zip(self.zbuffer.chunks_mut(rows_per_band * width))
+            .enumerate()
+            .map(|(i, (pixels, zbuffer))| {
+                let y_start = i * rows_per_band;
+                FramebufferBand {
+                    y_end: y_start + zbuffer.len() / width.max(1),
+                    pixels,
+                    zbuffer,
+                    width,
+                    y_start,
+                }
+            })
+            .collect()
+    }
 
-            // Blend and write
-            let blended = color.blend(back, mode);
-            let bytes = blended.to_bytes();
-            self.pixels[idx] = bytes[0];
-            self.pixels[idx + 1] = bytes[1];
-            self.pixels[idx + 2] = bytes[2];
-            self.pixels[idx + 3] = bytes[3];
+    /// The whole framebuffer as a band, for writing the single pixel (x, y):
+    /// only that pixel is marked dirty
+    #[inline]
+    fn pixel_band(&mut self, x: usize, y: usize) -> FramebufferBand<'_> {
+        self.mark_dirty(x, y, x + 1, y + 1);
+        FramebufferBand {
+            pixels: &mut self.pixels,
+            zbuffer: &mut self.zbuffer,
+            width: self.width,
+            y_start: 0,
+            y_end: self.height,
         }
     }
 
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Color) {
+        self.pixel_band(x, y).set_pixel(x, y, color)
+    }
+
+    /// Set pixel with PS1-style blending
+    pub fn set_pixel_blended(&mut self, x: usize, y: usize, color: Color, mode: BlendMode) {
+        self.pixel_band(x, y).set_pixel_blended(x, y, color, mode)
+    }
+
     /// Set pixel with PS1-style blending + editor alpha multiplier
     /// Final = lerp(back, ps1_blend_result, editor_alpha/255)
     /// editor_alpha=255: game-accurate, editor_alpha<255: fade for editor visualization
@@ -339,38 +435,7 @@ impl Framebuffer {
         &mut self, x: usize, y: usize,
         color: Color, mode: BlendMode, editor_alpha: u8
     ) {
-        if editor_alpha == 0 { return; } // Fully invisible
-        if x >= self.width || y >= self.height { return; }
-
-        let idx = (y * self.width + x) * 4;
-
-        // Read existing pixel (back)
-        let back_r = self.pixels[idx];
-        let back_g = self.pixels[idx + 1];
-        let back_b = self.pixels[idx + 2];
-        let back = Color::with_blend(back_r, back_g, back_b, BlendMode::Opaque);
-
-        // Step 1: PS1 blend
-        let ps1_result = color.blend(back, mode);
-
-        // Step 2: Editor alpha (skip lerp if fully opaque)
-        let final_color = if editor_alpha < 255 {
-            let a = editor_alpha as f32 / 255.0;
-            let inv_a = 1.0 - a;
-            Color::new(
-                (ps1_result.r as f32 * a + back_r as f32 * inv_a) as u8,
-                (ps1_result.g as f32 * a + back_g as f32 * inv_a) as u8,
-                (ps1_result.b as f32 * a + back_b as f32 * inv_a) as u8,
-            )
-        } else {
-            ps1_result
-        };
-
-        let bytes = final_color.to_bytes();
-        self.pixels[idx] = bytes[0];
-        self.pixels[idx + 1] = bytes[1];
-        self.pixels[idx + 2] = bytes[2];
-        self.pixels[idx + 3] = bytes[3];
+        self.pixel_band(x, y).set_pixel_with_editor_alpha(x, y, color, mode, editor_alpha)
     }
 
     /// Set pixel with depth test + PS1 blend + editor alpha
@@ -378,62 +443,11 @@ impl Framebuffer {
         &mut self, x: usize, y: usize, z: f32,
         color: Color, mode: BlendMode, editor_alpha: u8
     ) -> bool {
-        if editor_alpha == 0 { return false; }
-        if x >= self.width || y >= self.height { return false; }
-
-        let depth_idx = y * self.width + x;
-        if z >= self.zbuffer[depth_idx] { return false; }
-
-        // Depth test passed - update zbuffer
-        self.zbuffer[depth_idx] = z;
-
-        let idx = depth_idx * 4;
-
-        // Read existing pixel (back)
-        let back_r = self.pixels[idx];
-        let back_g = self.pixels[idx + 1];
-        let back_b = self.pixels[idx + 2];
-        let back = Color::with_blend(back_r, back_g, back_b, BlendMode::Opaque);
-
-        // Step 1: PS1 blend
-        let ps1_result = color.blend(back, mode);
-
-        // Step 2: Editor alpha
-        let final_color = if editor_alpha < 255 {
-            let a = editor_alpha as f32 / 255.0;
-            let inv_a = 1.0 - a;
-            Color::new(
-                (ps1_result.r as f32 * a + back_r as f32 * inv_a) as u8,
-                (ps1_result.g as f32 * a + back_g as f32 * inv_a) as u8,
-                (ps1_result.b as f32 * a + back_b as f32 * inv_a) as u8,
-            )
-        } else {
-            ps1_result
-        };
-
-        let bytes = final_color.to_bytes();
-        self.pixels[idx] = bytes[0];
-        self.pixels[idx + 1] = bytes[1];
-        self.pixels[idx + 2] = bytes[2];
-        self.pixels[idx + 3] = bytes[3];
-        true
+        self.pixel_band(x, y).set_pixel_with_depth_and_editor_alpha(x, y, z, color, mode, editor_alpha)
     }
 
     pub fn set_pixel_with_depth(&mut self, x: usize, y: usize, z: f32, color: Color) -> bool {
-        if x < self.width && y < self.height {
-            let idx = y * self.width + x;
-            if z < self.zbuffer[idx] {
-                self.zbuffer[idx] = z;
-                let pixel_idx = idx * 4;
-                let bytes = color.to_bytes();
-                self.pixels[pixel_idx] = bytes[0];
-                self.pixels[pixel_idx + 1] = bytes[1];
-                self.pixels[pixel_idx + 2] = bytes[2];
-                self.pixels[pixel_idx + 3] = bytes[3];
-                return true;
-            }
-        }
-        false
+        self.pixel_band(x, y).set_pixel_with_depth(x, y, z, color)
     }
 
     // =========================================================================
@@ -443,33 +457,13 @@ impl Framebuffer {
     /// Set pixel using Color15 (RGB555)
     #[inline]
     pub fn set_pixel_15(&mut self, x: usize, y: usize, color: Color15) {
-        if x < self.width && y < self.height {
-            let idx = (y * self.width + x) * 4;
-            let rgba = color.to_rgba();
-            self.pixels[idx] = rgba[0];
-            self.pixels[idx + 1] = rgba[1];
-            self.pixels[idx + 2] = rgba[2];
-            self.pixels[idx + 3] = rgba[3];
-        }
+        self.pixel_band(x, y).set_pixel_15(x, y, color)
     }
 
     /// Set pixel with depth test using Color15 (RGB555)
     #[inline]
     pub fn set_pixel_with_depth_15(&mut self, x: usize, y: usize, z: f32, color: Color15) -> bool {
-        if x < self.width && y < self.height {
-            let idx = y * self.width + x;
-            if z < self.zbuffer[idx] {
-                self.zbuffer[idx] = z;
-                let pixel_idx = idx * 4;
-                let rgba = color.to_rgba();
-                self.pixels[pixel_idx] = rgba[0];
-                self.pixels[pixel_idx + 1] = rgba[1];
-                self.pixels[pixel_idx + 2] = rgba[2];
-                self.pixels[pixel_idx + 3] = rgba[3];
-                return true;
-            }
-        }
-        false
+        self.pixel_band(x, y).set_pixel_with_depth_15(x, y, z, color)
     }
 
     /// PS1-authentic blending using Color15
@@ -477,52 +471,14 @@ impl Framebuffer {
     /// Otherwise, write directly (opaque)
     #[inline]
     pub fn set_pixel_blended_15(&mut self, x: usize, y: usize, color: Color15, face_blend_mode: BlendMode) {
-        if x < self.width && y < self.height {
-            let idx = (y * self.width + x) * 4;
-
-            // Read existing pixel (back) from framebuffer
-            let back_r = self.pixels[idx];
-            let back_g = self.pixels[idx + 1];
-            let back_b = self.pixels[idx + 2];
-
-            // Apply blending based on semi-transparency bit and face blend mode
-            let (r, g, b) = if color.is_semi_transparent() {
-                // Apply the face's blend mode
-                blend_rgb555(color.r8(), color.g8(), color.b8(), back_r, back_g, back_b, face_blend_mode)
-            } else {
-                // Opaque - write directly
-                (color.r8(), color.g8(), color.b8())
-            };
-
-            self.pixels[idx] = r;
-            self.pixels[idx + 1] = g;
-            self.pixels[idx + 2] = b;
-            self.pixels[idx + 3] = 255;
-        }
+        self.pixel_band(x, y).set_pixel_blended_15(x, y, color, face_blend_mode)
     }
 
     /// Set pixel with X-ray mode: 50% alpha blend, no depth test
     /// Always blends incoming color at 50% with existing pixel
     #[inline]
     pub fn set_pixel_xray_15(&mut self, x: usize, y: usize, color: Color15) {
-        if x < self.width && y < self.height {
-            let idx = (y * self.width + x) * 4;
-
-            // Read existing pixel
-            let back_r = self.pixels[idx];
-            let back_g = self.pixels[idx + 1];
-            let back_b = self.pixels[idx + 2];
-
-            // 50% blend: (front + back) / 2
-            let r = ((color.r8() as u16 + back_r as u16) / 2) as u8;
-            let g = ((color.g8() as u16 + back_g as u16) / 2) as u8;
-            let b = ((color.b8() as u16 + back_b as u16) / 2) as u8;
-
-            self.pixels[idx] = r;
-            self.pixels[idx + 1] = g;
-            self.pixels[idx + 2] = b;
-            self.pixels[idx + 3] = 255;
-        }
+        self.pixel_band(x, y).set_pixel_xray_15(x, y, color)
     }
 
     /// Set pixel with depth test and PS1-authentic blending using Color15
@@ -535,30 +491,7 @@ impl Framebuffer {
         color: Color15,
         face_blend_mode: BlendMode,
     ) -> bool {
-        if x < self.width && y < self.height {
-            let idx = y * self.width + x;
-            if z < self.zbuffer[idx] {
-                self.zbuffer[idx] = z;
-
-                let pixel_idx = idx * 4;
-                let back_r = self.pixels[pixel_idx];
-                let back_g = self.pixels[pixel_idx + 1];
-                let back_b = self.pixels[pixel_idx + 2];
-
-                let (r, g, b) = if color.is_semi_transparent() {
-                    blend_rgb555(color.r8(), color.g8(), color.b8(), back_r, back_g, back_b, face_blend_mode)
-                } else {
-                    (color.r8(), color.g8(), color.b8())
-                };
-
-                self.pixels[pixel_idx] = r;
-                self.pixels[pixel_idx + 1] = g;
-                self.pixels[pixel_idx + 2] = b;
-                self.pixels[pixel_idx + 3] = 255;
-                return true;
-            }
-        }
-        false
+        self.pixel_band(x, y).set_pixel_with_depth_blended_15(x, y, z, color, face_blend_mode)
     }
 
     /// RGB555 pixel write with editor alpha blending (no depth test)
@@ -568,27 +501,7 @@ impl Framebuffer {
         &mut self, x: usize, y: usize,
         color: Color15, blend_mode: BlendMode, editor_alpha: u8,
     ) {
-        if editor_alpha == 0 || x >= self.width || y >= self.height { return; }
-        let idx = (y * self.width + x) * 4;
-
-        let back_r = self.pixels[idx];
-        let back_g = self.pixels[idx + 1];
-        let back_b = self.pixels[idx + 2];
-
-        // Step 1: PS1 blend if semi-transparent
-        let (ps1_r, ps1_g, ps1_b) = if color.is_semi_transparent() && blend_mode != BlendMode::Opaque {
-            blend_rgb555(color.r8(), color.g8(), color.b8(), back_r, back_g, back_b, blend_mode)
-        } else {
-            (color.r8(), color.g8(), color.b8())
-        };
-
-        // Step 2: editor alpha lerp
-        let a = editor_alpha as u16;
-        let inv_a = 255 - a;
-        self.pixels[idx]     = ((ps1_r as u16 * a + back_r as u16 * inv_a) / 255) as u8;
-        self.pixels[idx + 1] = ((ps1_g as u16 * a + back_g as u16 * inv_a) / 255) as u8;
-        self.pixels[idx + 2] = ((ps1_b as u16 * a + back_b as u16 * inv_a) / 255) as u8;
-        self.pixels[idx + 3] = 255;
+        self.pixel_band(x, y).set_pixel_with_editor_alpha_15(x, y, color, blend_mode, editor_alpha)
     }
 
     /// RGB555 pixel write with depth test + editor alpha blending
@@ -598,34 +511,8 @@ impl Framebuffer {
         color: Color15, blend_mode: BlendMode, editor_alpha: u8,
         skip_z_write: bool,
     ) -> bool {
-        if editor_alpha == 0 || x >= self.width || y >= self.height { return false; }
-        let depth_idx = y * self.width + x;
-        if z >= self.zbuffer[depth_idx] { return false; }
-        if !skip_z_write {
-            self.zbuffer[depth_idx] = z;
-        }
-
-        let idx = depth_idx * 4;
-        let back_r = self.pixels[idx];
-        let back_g = self.pixels[idx + 1];
-        let back_b = self.pixels[idx + 2];
-
-        // Step 1: PS1 blend if semi-transparent
-        let (ps1_r, ps1_g, ps1_b) = if color.is_semi_transparent() && blend_mode != BlendMode::Opaque {
-            blend_rgb555(color.r8(), color.g8(), color.b8(), back_r, back_g, back_b, blend_mode)
-        } else {
-            (color.r8(), color.g8(), color.b8())
-        };
-
-        // Step 2: editor alpha lerp
-        let a = editor_alpha as u16;
-        let inv_a = 255 - a;
-        self.pixels[idx]     = ((ps1_r as u16 * a + back_r as u16 * inv_a) / 255) as u8;
-        self.pixels[idx + 1] = ((ps1_g as u16 * a + back_g as u16 * inv_a) / 255) as u8;
-        self.pixels[idx + 2] = ((ps1_b as u16 * a + back_b as u16 * inv_a) / 255) as u8;
-        self.pixels[idx + 3] = 255;
-        true
-    }
+        self.pixel_band(x, y).set_pixel_with_depth_and_editor_alpha_15(x, y, z, color, blend_mode, editor_alpha, skip_z_write)
+    }
 
     /// Draw a filled circle at (cx, cy) with given radius and color
     pub fn draw_circle(&mut self, cx: i32, cy: i32, radius: i32, color: Color) {
@@ -645,6 +532,7 @@ impl Framebuffer {
     #[inline]
     pub fn set_pixel_alpha(&mut self, x: usize, y: usize, color: Color, alpha: u8) {
         if x < self.width && y < self.height {
+            self.mark_dirty(x, y, x + 1, y + 1);
             let idx = (y * self.width + x) * 4;
 
             // Read existing pixel
@@ -910,64 +798,418 @@ impl Framebuffer {
         let min_y = min_y.max(0);
         let max_y = max_y.min(self.height as i32 - 1);
 
-        // Early exit if completely off-screen
-        if min_x > max_x || min_y > max_y {
-            return;
-        }
+        // Early exit if completely off-screen
+        if min_x > max_x || min_y > max_y {
+            return;
+        }
+
+        // Rasterize quad using scanline - test each pixel in bounding box
+        for py in min_y..=max_y {
+            for px in min_x..=max_x {
+                // Point-in-quad test using cross products (convex quad)
+                let p = (px as f32 + 0.5, py as f32 + 0.5);
+                let mut inside = true;
+                for i in 0..4 {
+                    let a = corners[i];
+                    let b = corners[(i + 1) % 4];
+                    let cross = (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0);
+                    if cross < 0.0 {
+                        inside = false;
+                        break;
+                    }
+                }
+                if inside {
+                    self.set_pixel(px as usize, py as usize, color);
+                }
+            }
+        }
+    }
+
+    /// Draw a rectangle outline from (x0, y0) to (x1, y1)
+    pub fn draw_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
+        // Normalize coordinates
+        let (min_x, max_x) = if x0 < x1 { (x0, x1) } else { (x1, x0) };
+        let (min_y, max_y) = if y0 < y1 { (y0, y1) } else { (y1, y0) };
+
+        // Draw four edges
+        self.draw_line(min_x, min_y, max_x, min_y, color); // Top
+        self.draw_line(max_x, min_y, max_x, max_y, color); // Right
+        self.draw_line(max_x, max_y, min_x, max_y, color); // Bottom
+        self.draw_line(min_x, max_y, min_x, min_y, color); // Left
+    }
+
+    /// Draw a filled rectangle from (x0, y0) to (x1, y1) with semi-transparent color
+    pub fn draw_filled_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
+        // Normalize coordinates
+        let (min_x, max_x) = if x0 < x1 { (x0, x1) } else { (x1, x0) };
+        let (min_y, max_y) = if y0 < y1 { (y0, y1) } else { (y1, y0) };
+
+        // Clamp to framebuffer bounds
+        let min_x = min_x.max(0);
+        let min_y = min_y.max(0);
+        let max_x = max_x.min(self.width as i32 - 1);
+        let max_y = max_y.min(self.height as i32 - 1);
+
+        // Fill rectangle
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                self.set_pixel(x as usize, y as usize, color);
+            }
+        }
+    }
+}
+
+/// Mutable view of a horizontal band of framebuffer rows `y_start..y_end`.
+///
+/// Coordinates are in full-framebuffer space; writes outside the band are
+/// ignored, so triangles crossing band edges are clipped naturally.
+pub struct FramebufferBand<'a> {
+    pixels: &'a mut [u8],
+    zbuffer: &'a mut [f32],
+    width: usize,
+    y_start: usize,
+    y_end: usize,
+}
+
+impl FramebufferBand<'_> {
+    #[inline]
+    fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y >= self.y_start && y < self.y_end
+    }
+
+    /// Index into this band's zbuffer (multiply by 4 for pixels)
+    #[inline]
+    fn index(&self, x: usize, y: usize) -> usize {
+        (y - self.y_start) * self.width + x
+    }
+
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Color) {
+        if self.contains(x, y) {
+            let idx = self.index(x, y) * 4;
+            let bytes = color.to_bytes();
+            self.pixels[idx] = bytes[0];
+            self.pixels[idx + 1] = bytes[1];
+            self.pixels[idx + 2] = bytes[2];
+            self.pixels[idx + 3] = bytes[3];
+        }
+    }
+
+    /// Set pixel with PS1-style blending
+    pub fn set_pixel_blended(&mut self, x: usize, y: usize, color: Color, mode: BlendMode) {
+        if self.contains(x, y) {
+            let idx = self.index(x, y) * 4;
+
+            // Read existing pixel (back) - framebuffer stores RGBA with 255 = opaque
+            let back = Color::with_blend(
+                self.pixels[idx],
+                self.pixels[idx + 1],
+                self.pixels[idx + 2],
+                BlendMode::Opaque, // Framebuffer pixels are always opaque
+            );
+
+            // Blend and write
+            let blended = color.blend(back, mode);
+            let bytes = blended.to_bytes();
+            self.pixels[idx] = bytes[0];
+            self.pixels[idx + 1] = bytes[1];
+            self.pixels[idx + 2] = bytes[2];
+            self.pixels[idx + 3] = bytes[3];
+        }
+    }
+
+    /// Set pixel with PS1-style blending + editor alpha multiplier
+    /// Final = lerp(back, ps1_blend_result, editor_alpha/255)
+    /// editor_alpha=255: game-accurate, editor_alpha<255: fade for editor visualization
+    pub fn set_pixel_with_editor_alpha(
+        &mut self, x: usize, y: usize,
+        color: Color, mode: BlendMode, editor_alpha: u8
+    ) {
+        if editor_alpha == 0 { return; } // Fully invisible
+        if !self.contains(x, y) { return; }
+
+        let idx = self.index(x, y) * 4;
+
+        // Read existing pixel (back)
+        let back_r = self.pixels[idx];
+        let back_g = self.pixels[idx + 1];
+        let back_b = self.pixels[idx + 2];
+        let back = Color::with_blend(back_r, back_g, back_b, BlendMode::Opaque);
+
+        // Step 1: PS1 blend
+        let ps1_result = color.blend(back, mode);
+
+        // Step 2: Editor alpha (skip lerp if fully opaque)
+        let final_color = if editor_alpha < 255 {
+            let a = editor_alpha as f32 / 255.0;
+            let inv_a = 1.0 - a;
+            Color::new(
+                (ps1_result.r as f32 * a + back_r as f32 * inv_a) as u8,
+                (ps1_result.g as f32 * a + back_g as f32 * inv_a) as u8,
+                (ps1_result.b as f32 * a + back_b as f32 * inv_a) as u8,
+            )
+        } else {
+            ps1_result
+        };
+
+        let bytes = final_color.to_bytes();
+        self.pixels[idx] = bytes[0];
+        self.pixels[idx + 1] = bytes[1];
+        self.pixels[idx + 2] = bytes[2];
+        self.pixels[idx + 3] = bytes[3];
+    }
+
+    /// Set pixel with depth test + PS1 blend + editor alpha
+    pub fn set_pixel_with_depth_and_editor_alpha(
+        &mut self, x: usize, y: usize, z: f32,
+        color: Color, mode: BlendMode, editor_alpha: u8
+    ) -> bool {
+        if editor_alpha == 0 { return false; }
+        if !self.contains(x, y) { return false; }
+
+        let depth_idx = self.index(x, y);
+        if z >= self.zbuffer[depth_idx] { return false; }
+
+        // Depth test passed - update zbuffer
+        self.zbuffer[depth_idx] = z;
+
+        let idx = depth_idx * 4;
+
+        // Read existing pixel (back)
+        let back_r = self.pixels[idx];
+        let back_g = self.pixels[idx + 1];
+        let back_b = self.pixels[idx + 2];
+        let back = Color::with_blend(back_r, back_g, back_b, BlendMode::Opaque);
+
+        // Step 1: PS1 blend
+        let ps1_result = color.blend(back, mode);
+
+        // Step 2: Editor alpha
+        let final_color = if editor_alpha < 255 {
+            let a = editor_alpha as f32 / 255.0;
+            let inv_a = 1.0 - a;
+            Color::new(
+                (ps1_result.r as f32 * a + back_r as f32 * inv_a) as u8,
+                (ps1_result.g as f32 * a + back_g as f32 * inv_a) as u8,
+                (ps1_result.b as f32 * a + back_b as f32 * inv_a) as u8,
+            )
+        } else {
+            ps1_result
+        };
+
+        let bytes = final_color.to_bytes();
+        self.pixels[idx] = bytes[0];
+        self.pixels[idx + 1] = bytes[1];
+        self.pixels[idx + 2] = bytes[2];
+        self.pixels[idx + 3] = bytes[3];
+        true
+    }
+
+    pub fn set_pixel_with_depth(&mut self, x: usize, y: usize, z: f32, color: Color) -> bool {
+        if self.contains(x, y) {
+            let idx = self.index(x, y);
+            if z < self.zbuffer[idx] {
+                self.zbuffer[idx] = z;
+                let pixel_idx = idx * 4;
+                let bytes = color.to_bytes();
+                self.pixels[pixel_idx] = bytes[0];
+                self.pixels[pixel_idx + 1] = bytes[1];
+                self.pixels[pixel_idx + 2] = bytes[2];
+                self.pixels[pixel_idx + 3] = bytes[3];
+                return true;
+            }
+        }
+        false
+    }
+
+    // =========================================================================
+    // RGB555 (Color15) methods for PS1-authentic rendering
+    // =========================================================================
+
+    /// Set pixel using Color15 (RGB555)
+    #[inline]
+    pub fn set_pixel_15(&mut self, x: usize, y: usize, color: Color15) {
+        if self.contains(x, y) {
+            let idx = self.index(x, y) * 4;
+            let rgba = color.to_rgba();
+            self.pixels[idx] = rgba[0];
+            self.pixels[idx + 1] = rgba[1];
+            self.pixels[idx + 2] = rgba[2];
+            self.pixels[idx + 3] = rgba[3];
+        }
+    }
+
+    /// Set pixel with depth test using Color15 (RGB555)
+    #[inline]
+    pub fn set_pixel_with_depth_15(&mut self, x: usize, y: usize, z: f32, color: Color15) -> bool {
+        if self.contains(x, y) {
+            let idx = self.index(x, y);
+            if z < self.zbuffer[idx] {
+                self.zbuffer[idx] = z;
+                let pixel_idx = idx * 4;
+                let rgba = color.to_rgba();
+                self.pixels[pixel_idx] = rgba[0];
+                self.pixels[pixel_idx + 1] = rgba[1];
+                self.pixels[pixel_idx + 2] = rgba[2];
+                self.pixels[pixel_idx + 3] = rgba[3];
+                return true;
+            }
+        }
+        false
+    }
+
+    /// PS1-authentic blending using Color15
+    /// If pixel's semi-transparency bit is set, apply face_blend_mode
+    /// Otherwise, write directly (opaque)
+    #[inline]
+    pub fn set_pixel_blended_15(&mut self, x: usize, y: usize, color: Color15, face_blend_mode: BlendMode) {
+        if self.contains(x, y) {
+            let idx = self.index(x, y) * 4;
+
+            // Read existing pixel (back) from framebuffer
+            let back_r = self.pixels[idx];
+            let back_g = self.pixels[idx + 1];
+            let back_b = self.pixels[idx + 2];
+
+            // Apply blending based on semi-transparency bit and face blend mode
+            let (r, g, b) = if color.is_semi_transparent() {
+                // Apply the face's blend mode
+                blend_rgb555(color.r8(), color.g8(), color.b8(), back_r, back_g, back_b, face_blend_mode)
+            } else {
+                // Opaque - write directly
+                (color.r8(), color.g8(), color.b8())
+            };
+
+            self.pixels[idx] = r;
+            self.pixels[idx + 1] = g;
+            self.pixels[idx + 2] = b;
+            self.pixels[idx + 3] = 255;
+        }
+    }
+
+    /// Set pixel with X-ray mode: 50% alpha blend, no depth test
+    /// Always blends incoming color at 50% with existing pixel
+    #[inline]
+    pub fn set_pixel_xray_15(&mut self, x: usize, y: usize, color: Color15) {
+        if self.contains(x, y) {
+            let idx = self.index(x, y) * 4;
+
+            // Read existing pixel
+            let back_r = self.pixels[idx];
+            let back_g = self.pixels[idx + 1];
+            let back_b = self.pixels[idx + 2];
+
+            // 50% blend: (front + back) / 2
+            let r = ((color.r8() as u16 + back_r as u16) / 2) as u8;
+            let g = ((color.g8() as u16 + back_g as u16) / 2) as u8;
+            let b = ((color.b8() as u16 + back_b as u16) / 2) as u8;
+
+            self.pixels[idx] = r;
+            self.pixels[idx + 1] = g;
+            self.pixels[idx + 2] = b;
+            self.pixels[idx + 3] = 255;
+        }
+    }
+
+    /// Set pixel with depth test and PS1-authentic blending using Color15
+    #[inline]
+    pub fn set_pixel_with_depth_blended_15(
+        &mut self,
+        x: usize,
+        y: usize,
+        z: f32,
+        color: Color15,
+        face_blend_mode: BlendMode,
+    ) -> bool {
+        if self.contains(x, y) {
+            let idx = self.index(x, y);
+            if z < self.zbuffer[idx] {
+                self.zbuffer[idx] = z;
+
+                let pixel_idx = idx * 4;
+                let back_r = self.pixels[pixel_idx];
+                let back_g = self.pixels[pixel_idx + 1];
+                let back_b = self.pixels[pixel_idx + 2];
+
+                let (r, g, b) = if color.is_semi_transparent() {
+                    blend_rgb555(color.r8(), color.g8(), color.b8(), back_r, back_g, back_b, face_blend_mode)
+                } else {
+                    (color.r8(), color.g8(), color.b8())
+                };
 
-        // Rasterize quad using scanline - test each pixel in bounding box
-        for py in min_y..=max_y {
-            for px in min_x..=max_x {
-                // Point-in-quad test using cross products (convex quad)
-                let p = (px as f32 + 0.5, py as f32 + 0.5);
-                let mut inside = true;
-                for i in 0..4 {
-                    let a = corners[i];
-                    let b = corners[(i + 1) % 4];
-                    let cross = (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0);
-                    if cross < 0.0 {
-                        inside = false;
-                        break;
-                    }
-                }
-                if inside {
-                    self.set_pixel(px as usize, py as usize, color);
-                }
+                self.pixels[pixel_idx] = r;
+                self.pixels[pixel_idx + 1] = g;
+                self.pixels[pixel_idx + 2] = b;
+                self.pixels[pixel_idx + 3] = 255;
+                return true;
             }
         }
+        false
     }
 
-    /// Draw a rectangle outline from (x0, y0) to (x1, y1)
-    pub fn draw_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
-        // Normalize coordinates
-        let (min_x, max_x) = if x0 < x1 { (x0, x1) } else { (x1, x0) };
-        let (min_y, max_y) = if y0 < y1 { (y0, y1) } else { (y1, y0) };
+    /// RGB555 pixel write with editor alpha blending (no depth test)
+    /// Step 1: Apply PS1 blend if semi-transparent, Step 2: Lerp with background by editor_alpha
+    #[inline]
+    pub fn set_pixel_with_editor_alpha_15(
+        &mut self, x: usize, y: usize,
+        color: Color15, blend_mode: BlendMode, editor_alpha: u8,
+    ) {
+        if editor_alpha == 0 || !self.contains(x, y) { return; }
+        let idx = self.index(x, y) * 4;
 
-        // Draw four edges
-        self.draw_line(min_x, min_y, max_x, min_y, color); // Top
-        self.draw_line(max_x, min_y, max_x, max_y, color); // Right
-        self.draw_line(max_x, max_y, min_x, max_y, color); // Bottom
-        self.draw_line(min_x, max_y, min_x, min_y, color); // Left
-    }
+        let back_r = self.pixels[idx];
+        let back_g = self.pixels[idx + 1];
+        let back_b = self.pixels[idx + 2];
 
-    /// Draw a filled rectangle from (x0, y0) to (x1, y1) with semi-transparent color
-    pub fn draw_filled_rect(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) {
-        // Normalize coordinates
-        let (min_x, max_x) = if x0 < x1 { (x0, x1) } else { (x1, x0) };
-        let (min_y, max_y) = if y0 < y1 { (y0, y1) } else { (y1, y0) };
+        // Step 1: PS1 blend if semi-transparent
+        let (ps1_r, ps1_g, ps1_b) = if color.is_semi_transparent() && blend_mode != BlendMode::Opaque {
+            blend_rgb555(color.r8(), color.g8(), color.b8(), back_r, back_g, back_b, blend_mode)
+        } else {
+            (color.r8(), color.g8(), color.b8())
+        };
 
-        // Clamp to framebuffer bounds
-        let min_x = min_x.max(0);
-        let min_y = min_y.max(0);
-        let max_x = max_x.min(self.width as i32 - 1);
-        let max_y = max_y.min(self.height as i32 - 1);
+        // Step 2: editor alpha lerp
+        let a = editor_alpha as u16;
+        let inv_a = 255 - a;
+        self.pixels[idx]     = ((ps1_r as u16 * a + back_r as u16 * inv_a) / 255) as u8;
+        self.pixels[idx + 1] = ((ps1_g as u16 * a + back_g as u16 * inv_a) / 255) as u8;
+        self.pixels[idx + 2] = ((ps1_b as u16 * a + back_b as u16 * inv_a) / 255) as u8;
+        self.pixels[idx + 3] = 255;
+    }
 
-        // Fill rectangle
-        for y in min_y..=max_y {
-            for x in min_x..=max_x {
-                self.set_pixel(x as usize, y as usize, color);
-            }
+    /// RGB555 pixel write with depth test + editor alpha blending
+    #[inline]
+    pub fn set_pixel_with_depth_and_editor_alpha_15(
+        &mut self, x: usize, y: usize, z: f32,
+        color: Color15, blend_mode: BlendMode, editor_alpha: u8,
+        skip_z_write: bool,
+    ) -> bool {
+        if editor_alpha == 0 || !self.contains(x, y) { return false; }
+        let depth_idx = self.index(x, y);
+        if z >= self.zbuffer[depth_idx] { return false; }
+        if !skip_z_write {
+            self.zbuffer[depth_idx] = z;
         }
+
+        let idx = depth_idx * 4;
+        let back_r = self.pixels[idx];
+        let back_g = self.pixels[idx + 1];
+        let back_b = self.pixels[idx + 2];
+
+        // Step 1: PS1 blend if semi-transparent
+        let (ps1_r, ps1_g, ps1_b) = if color.is_semi_transparent() && blend_mode != BlendMode::Opaque {
+            blend_rgb555(color.r8(), color.g8(), color.b8(), back_r, back_g, back_b, blend_mode)
+        } else {
+            (color.r8(), color.g8(), color.b8())
+        };
+
+        // Step 2: editor alpha lerp
+        let a = editor_alpha as u16;
+        let inv_a = 255 - a;
+        self.pixels[idx]     = ((ps1_r as u16 * a + back_r as u16 * inv_a) / 255) as u8;
+        self.pixels[idx + 1] = ((ps1_g as u16 * a + back_g as u16 * inv_a) / 255) as u8;
+        self.pixels[idx + 2] = ((ps1_b as u16 * a + back_b as u16 * inv_a) / 255) as u8;
+        self.pixels[idx + 3] = 255;
+        true
     }
 }
 
@@ -1010,10 +1252,9 @@ fn shade_intensity_directional(normal: Vec3, light_dir: Vec3, ambient: f32) -> f
 /// Calculate shading color from multiple lights (with colored light support)
 /// Returns RGB values 0.0-1.0 for each channel
 /// For per-vertex shading (Gouraud), world_pos can be approximate (vertex position)
-fn shade_multi_light_color(normal: Vec3, world_pos: Vec3, lights: &[Light], ambient: f32) -> (f32, f32, f32) {
-    let mut total_r = ambient;
-    let mut total_g = ambient;
-    let mut total_b = ambient;
+/// Also used by light baking, so baked vertex colors match the dynamic result
+pub fn shade_multi_light_color(normal: Vec3, world_pos: Vec3, lights: &[Light], ambient: [f32; 3]) -> (f32, f32, f32) {
+    let [mut total_r, mut total_g, mut total_b] = ambient;
 
     for light in lights.iter().filter(|l| l.enabled) {
         let contribution = match &light.light_type {
@@ -1200,16 +1441,19 @@ fn apply_dither(color: Color, x: usize, y: usize) -> Color {
 /// Uses edge function increments instead of recalculating barycentric
 /// coordinates per-pixel for better performance.
 fn rasterize_triangle(
-    fb: &mut Framebuffer,
+    fb: &mut FramebufferBand,
     surface: &Surface,
     texture: Option<&Texture>,
     settings: &RasterSettings,
 ) {
+    // The face's blend mode wins over the texture's (as in rasterize_triangle_15)
+    let face_translucent = surface.blend_mode != BlendMode::Opaque;
+
     // Bounding box (same as original)
     let min_x = surface.v1.x.min(surface.v2.x).min(surface.v3.x).max(0.0) as usize;
     let max_x = (surface.v1.x.max(surface.v2.x).max(surface.v3.x) + 1.0).min(fb.width as f32) as usize;
-    let min_y = surface.v1.y.min(surface.v2.y).min(surface.v3.y).max(0.0) as usize;
-    let max_y = (surface.v1.y.max(surface.v2.y).max(surface.v3.y) + 1.0).min(fb.height as f32) as usize;
+    let min_y = surface.v1.y.min(surface.v2.y).min(surface.v3.y).max(fb.y_start as f32) as usize;
+    let max_y = (surface.v1.y.max(surface.v2.y).max(surface.v3.y) + 1.0).min(fb.y_end as f32) as usize;
 
     // Early exit for degenerate/off-screen triangles
     if min_x >= max_x || min_y >= max_y {
@@ -1220,7 +1464,7 @@ fn rasterize_triangle(
     let flat_shade = if settings.shading == ShadingMode::Flat {
         let center_pos = (surface.w1 + surface.w2 + surface.w3).scale(1.0 / 3.0);
         let world_normal = (surface.wn1 + surface.wn2 + surface.wn3).scale(1.0 / 3.0).normalize();
-        shade_multi_light_color(world_normal, center_pos, &settings.lights, settings.ambient)
+        shade_multi_light_color(world_normal, center_pos, &settings.lights, settings.ambient_rgb())
     } else {
         (1.0, 1.0, 1.0)
     };
@@ -1228,9 +1472,9 @@ fn rasterize_triangle(
     // Pre-compute Gouraud vertex shading if needed
     let gouraud_shades = if settings.shading == ShadingMode::Gouraud {
         Some((
-            shade_multi_light_color(surface.wn1, surface.w1, &settings.lights, settings.ambient),
-            shade_multi_light_color(surface.wn2, surface.w2, &settings.lights, settings.ambient),
-            shade_multi_light_color(surface.wn3, surface.w3, &settings.lights, settings.ambient),
+            shade_multi_light_color(surface.wn1, surface.w1, &settings.lights, settings.ambient_rgb()),
+            shade_multi_light_color(surface.wn2, surface.w2, &settings.lights, settings.ambient_rgb()),
+            shade_multi_light_color(surface.wn3, surface.w3, &settings.lights, settings.ambient_rgb()),
         ))
     } else {
         None
@@ -1278,46 +1522,35 @@ fn rasterize_triangle(
     let w0_row_start = a0 * (start_x - v3.x) + b0 * (start_y - v3.y);
     let w1_row_start = a1 * (start_x - v3.x) + b1 * (start_y - v3.y);
 
-    // Step increments
-    let a0_step = a0; // x step for w0
-    let b0_step = b0; // y step for w0
-    let a1_step = a1; // x step for w1
-    let b1_step = b1; // y step for w1
-
     let mut w0_row = w0_row_start;
     let mut w1_row = w1_row_start;
 
+    // Coverage, 1/z and the depth test run LANES pixels at a time (see simd)
+    let span = SpanSetup::new(
+        inv_area,
+        a0,
+        a1,
+        [1.0 / v1.z, 1.0 / v2.z, 1.0 / v3.z],
+        settings.use_zbuffer && !settings.xray_mode,
+        settings.simd,
+    );
+    let [inv_z1, inv_z2, inv_z3] = span.inv_z;
+
     // Rasterize using incremental edge functions
     for y in min_y..max_y {
-        let mut w0 = w0_row;
-        let mut w1 = w1_row;
-
-        for x in min_x..max_x {
-            // Convert to barycentric coordinates
-            let bc_x = w0 * inv_area;
-            let bc_y = w1 * inv_area;
-            let bc_z = 1.0 - bc_x - bc_y;
-
-            // Check if inside triangle (same threshold as original)
-            const ERR: f32 = -0.0001;
-            if bc_x >= ERR && bc_y >= ERR && bc_z >= ERR {
-                // Perspective-correct depth interpolation
-                // In screen space after perspective divide, 1/z interpolates linearly, not z itself
-                let inv_z1 = 1.0 / v1.z;
-                let inv_z2 = 1.0 / v2.z;
-                let inv_z3 = 1.0 / v3.z;
-                let inv_z_interp = bc_x * inv_z1 + bc_y * inv_z2 + bc_z * inv_z3;
-                let z = 1.0 / inv_z_interp;
-
-                // Z-buffer test (skip in xray mode - render all faces regardless of depth)
-                if settings.use_zbuffer && !settings.xray_mode {
-                    let idx = y * fb.width + x;
-                    if z >= fb.zbuffer[idx] {
-                        w0 += a0_step;
-                        w1 += a1_step;
-                        continue;
-                    }
-                }
+        for x0 in (min_x..max_x).step_by(LANES) {
+            let lanes = (max_x - x0).min(LANES);
+            let row = fb.index(x0, y);
+            let offset = (x0 - min_x) as f32;
+            let quad = span.quad(w0_row + a0 * offset, w1_row + a1 * offset, &fb.zbuffer[row..row + lanes]);
+
+            for lane in quad.covered() {
+                let x = x0 + lane;
+                let bc_x = quad.bc_x[lane];
+                let bc_y = quad.bc_y[lane];
+                let bc_z = 1.0 - bc_x - bc_y;
+                let inv_z_interp = quad.inv_z[lane];
+                let z = quad.z[lane];
 
                 // Interpolate UV coordinates
                 let (u, v) = if settings.affine_textures {
@@ -1347,8 +1580,6 @@ fn rasterize_triangle(
 
                 // Skip transparent pixels
                 if color.is_transparent() {
-                    w0 += a0_step;
-                    w1 += a1_step;
                     continue;
                 }
 
@@ -1386,12 +1617,13 @@ fn rasterize_triangle(
                     color = apply_dither(color, x, y);
                 }
 
+                // A translucent face blends every pixel, not only semi-transparent texels
+                let blend = if face_translucent { surface.blend_mode } else { color.blend };
+
                 // Write pixel (with editor alpha support)
                 let editor_alpha = surface.editor_alpha;
                 if editor_alpha == 0 {
                     // Fully invisible - skip
-                    w0 += a0_step;
-                    w1 += a1_step;
                     continue;
                 }
 
@@ -1399,46 +1631,43 @@ fn rasterize_triangle(
                     // Z-buffer mode: test depth before writing
                     if editor_alpha < 255 {
                         // Use editor alpha blending
-                        fb.set_pixel_with_depth_and_editor_alpha(x, y, z, color, color.blend, editor_alpha);
-                    } else if color.blend == BlendMode::Opaque {
+                        fb.set_pixel_with_depth_and_editor_alpha(x, y, z, color, blend, editor_alpha);
+                    } else if blend == BlendMode::Opaque {
                         fb.set_pixel_with_depth(x, y, z, color);
                     } else {
-                        let idx = y * fb.width + x;
+                        let idx = fb.index(x, y);
                         if z < fb.zbuffer[idx] {
                             fb.zbuffer[idx] = z;
-                            fb.set_pixel_blended(x, y, color, color.blend);
+                            fb.set_pixel_blended(x, y, color, blend);
                         }
                     }
                 } else {
                     // Painter's algorithm: just write (surfaces are pre-sorted)
                     if editor_alpha < 255 {
-                        fb.set_pixel_with_editor_alpha(x, y, color, color.blend, editor_alpha);
-                    } else if color.blend == BlendMode::Opaque {
+                        fb.set_pixel_with_editor_alpha(x, y, color, blend, editor_alpha);
+                    } else if blend == BlendMode::Opaque {
                         fb.set_pixel(x, y, color);
                     } else {
-                        fb.set_pixel_blended(x, y, color, color.blend);
+                        fb.set_pixel_blended(x, y, color, blend);
                     }
                 }
             }
-
-            // Step to next pixel (x increment)
-            w0 += a0_step;
-            w1 += a1_step;
         }
 
         // Step to next row (y increment)
-        w0_row += b0_step;
-        w1_row += b1_step;
+        w0_row += b0;
+        w1_row += b1;
     }
 }
 
 /// Rasterize a single triangle using RGB555 (PS1-authentic mode)
 /// Uses Color15 for texture sampling and Color15 for pixel output
-/// Texture's blend_mode is used when texture pixel has semi-transparency bit set
-/// Falls back to face_blend_mode if texture has no blend mode
+/// A translucent face (face_blend_mode not Opaque) blends every pixel with its
+/// own mode; otherwise the texture's blend_mode applies to pixels with the
+/// semi-transparency bit set
 /// black_transparent: if true, pure black pixels (before shading) are skipped as transparent
 fn rasterize_triangle_15(
-    fb: &mut Framebuffer,
+    fb: &mut FramebufferBand,
     surface: &Surface,
     texture: Option<&Texture15>,
     face_blend_mode: BlendMode,
@@ -1446,16 +1675,19 @@ fn rasterize_triangle_15(
     settings: &RasterSettings,
     skip_z_write: bool,  // If true, don't update z-buffer (for semi-transparent pass)
 ) {
-    // Use texture's blend mode if available, otherwise face_blend_mode
-    let blend_mode = texture
-        .map(|t| t.blend_mode)
-        .unwrap_or(face_blend_mode);
+    // The face's blend mode wins over the texture's
+    let face_translucent = face_blend_mode != BlendMode::Opaque;
+    let blend_mode = if face_translucent {
+        face_blend_mode
+    } else {
+        texture.map(|t| t.blend_mode).unwrap_or(BlendMode::Opaque)
+    };
 
     // Bounding box
     let min_x = surface.v1.x.min(surface.v2.x).min(surface.v3.x).max(0.0) as usize;
     let max_x = (surface.v1.x.max(surface.v2.x).max(surface.v3.x) + 1.0).min(fb.width as f32) as usize;
-    let min_y = surface.v1.y.min(surface.v2.y).min(surface.v3.y).max(0.0) as usize;
-    let max_y = (surface.v1.y.max(surface.v2.y).max(surface.v3.y) + 1.0).min(fb.height as f32) as usize;
+    let min_y = surface.v1.y.min(surface.v2.y).min(surface.v3.y).max(fb.y_start as f32) as usize;
+    let max_y = (surface.v1.y.max(surface.v2.y).max(surface.v3.y) + 1.0).min(fb.y_end as f32) as usize;
 
     // Early exit for degenerate/off-screen triangles
     if min_x >= max_x || min_y >= max_y {
@@ -1466,7 +1698,7 @@ fn rasterize_triangle_15(
     let flat_shade = if settings.shading == ShadingMode::Flat {
         let center_pos = (surface.w1 + surface.w2 + surface.w3).scale(1.0 / 3.0);
         let world_normal = (surface.wn1 + surface.wn2 + surface.wn3).scale(1.0 / 3.0).normalize();
-        shade_multi_light_color(world_normal, center_pos, &settings.lights, settings.ambient)
+        shade_multi_light_color(world_normal, center_pos, &settings.lights, settings.ambient_rgb())
     } else {
         (1.0, 1.0, 1.0)
     };
@@ -1474,9 +1706,9 @@ fn rasterize_triangle_15(
     // Pre-compute Gouraud vertex shading if needed
     let gouraud_shades = if settings.shading == ShadingMode::Gouraud {
         Some((
-            shade_multi_light_color(surface.wn1, surface.w1, &settings.lights, settings.ambient),
-            shade_multi_light_color(surface.wn2, surface.w2, &settings.lights, settings.ambient),
-            shade_multi_light_color(surface.wn3, surface.w3, &settings.lights, settings.ambient),
+            shade_multi_light_color(surface.wn1, surface.w1, &settings.lights, settings.ambient_rgb()),
+            shade_multi_light_color(surface.wn2, surface.w2, &settings.lights, settings.ambient_rgb()),
+            shade_multi_light_color(surface.wn3, surface.w3, &settings.lights, settings.ambient_rgb()),
         ))
     } else {
         None
@@ -1517,47 +1749,35 @@ fn rasterize_triangle_15(
     let w0_row_start = a0 * (start_x - v3.x) + b0 * (start_y - v3.y);
     let w1_row_start = a1 * (start_x - v3.x) + b1 * (start_y - v3.y);
 
-    // Step increments
-    let a0_step = a0;
-    let b0_step = b0;
-    let a1_step = a1;
-    let b1_step = b1;
-
     let mut w0_row = w0_row_start;
     let mut w1_row = w1_row_start;
 
+    // Coverage, 1/z and the depth test run LANES pixels at a time (see simd)
+    let span = SpanSetup::new(
+        inv_area,
+        a0,
+        a1,
+        [1.0 / v1.z, 1.0 / v2.z, 1.0 / v3.z],
+        settings.use_zbuffer && !settings.xray_mode,
+        settings.simd,
+    );
+    let [inv_z1, inv_z2, inv_z3] = span.inv_z;
+
     // Rasterize using incremental edge functions
     for y in min_y..max_y {
-        let mut w0 = w0_row;
-        let mut w1 = w1_row;
-
-        for x in min_x..max_x {
-            // Convert to barycentric coordinates
-            let bc_x = w0 * inv_area;
-            let bc_y = w1 * inv_area;
-            let bc_z = 1.0 - bc_x - bc_y;
-
-            // Check if inside triangle
-            const ERR: f32 = -0.0001;
-            if bc_x >= ERR && bc_y >= ERR && bc_z >= ERR {
-                // Perspective-correct depth interpolation
-                // In screen space after perspective divide, 1/z interpolates linearly, not z itself
-                // z_correct = 1 / (bc_x/z1 + bc_y/z2 + bc_z/z3)
-                let inv_z1 = 1.0 / v1.z;
-                let inv_z2 = 1.0 / v2.z;
-                let inv_z3 = 1.0 / v3.z;
-                let inv_z_interp = bc_x * inv_z1 + bc_y * inv_z2 + bc_z * inv_z3;
-                let z = 1.0 / inv_z_interp;
-
-                // Z-buffer test (skip in xray mode - render all faces regardless of depth)
-                if settings.use_zbuffer && !settings.xray_mode {
-                    let idx = y * fb.width + x;
-                    if z >= fb.zbuffer[idx] {
-                        w0 += a0_step;
-                        w1 += a1_step;
-                        continue;
-                    }
-                }
+        for x0 in (min_x..max_x).step_by(LANES) {
+            let lanes = (max_x - x0).min(LANES);
+            let row = fb.index(x0, y);
+            let offset = (x0 - min_x) as f32;
+            let quad = span.quad(w0_row + a0 * offset, w1_row + a1 * offset, &fb.zbuffer[row..row + lanes]);
+
+            for lane in quad.covered() {
+                let x = x0 + lane;
+                let bc_x = quad.bc_x[lane];
+                let bc_y = quad.bc_y[lane];
+                let bc_z = 1.0 - bc_x - bc_y;
+                let inv_z_interp = quad.inv_z[lane];
+                let z = quad.z[lane];
 
                 // Interpolate UV coordinates
                 let (u, v) = if settings.affine_textures {
@@ -1595,14 +1815,10 @@ fn rasterize_triangle_15(
                         color = Color15::BLACK_DRAWABLE;
                     } else {
                         // Skip truly transparent pixels
-                        w0 += a0_step;
-                        w1 += a1_step;
                         continue;
                     }
                 } else if black_transparent && is_black {
                     // Skip black pixels when black_transparent is enabled
-                    w0 += a0_step;
-                    w1 += a1_step;
                     continue;
                 }
 
@@ -1654,17 +1870,16 @@ fn rasterize_triangle_15(
                 };
 
                 // Create final color, preserving semi-transparency from original texture
+                // (every pixel of a translucent face is semi-transparent)
                 // IMPORTANT: If final color is all-black (r5=g5=b5=0), we must set bit 15
                 // to make it "drawable black" (0x8000) instead of "transparent black" (0x0000)
                 let is_all_black = r5 == 0 && g5 == 0 && b5 == 0;
-                let semi = color.is_semi_transparent() || is_all_black;
+                let semi = color.is_semi_transparent() || is_all_black || face_translucent;
                 let color = Color15::new_semi(r5, g5, b5, semi);
 
                 // Write pixel (with editor alpha support)
                 let editor_alpha = surface.editor_alpha;
                 if editor_alpha == 0 {
-                    w0 += a0_step;
-                    w1 += a1_step;
                     continue;
                 }
 
@@ -1680,7 +1895,7 @@ fn rasterize_triangle_15(
                     }
                 } else if settings.use_zbuffer {
                     // Z-buffer mode: test depth before writing
-                    let idx = y * fb.width + x;
+                    let idx = fb.index(x, y);
                     if z < fb.zbuffer[idx] {
                         // Only update z-buffer if not skipping (opaque pass updates, transparent pass doesn't)
                         if !skip_z_write {
@@ -1701,15 +1916,11 @@ fn rasterize_triangle_15(
                     }
                 }
             }
-
-            // Step to next pixel (x increment)
-            w0 += a0_step;
-            w1 += a1_step;
         }
 
         // Step to next row (y increment)
-        w0_row += b0_step;
-        w1_row += b1_step;
+        w0_row += b0;
+        w1_row += b1;
     }
 }
 
@@ -1720,7 +1931,7 @@ fn rasterize_triangle_15(
 /// 2. Look up actual COLOR in CLUT
 /// 3. Continue with standard PS1 pipeline (modulation, shading, dithering)
 fn rasterize_triangle_indexed(
-    fb: &mut Framebuffer,
+    fb: &mut FramebufferBand,
     surface: &Surface,
     indexed_texture: Option<&IndexedTexture>,
     clut: Option<&Clut>,
@@ -1731,8 +1942,8 @@ fn rasterize_triangle_indexed(
     // Bounding box
     let min_x = surface.v1.x.min(surface.v2.x).min(surface.v3.x).max(0.0) as usize;
     let max_x = (surface.v1.x.max(surface.v2.x).max(surface.v3.x) + 1.0).min(fb.width as f32) as usize;
-    let min_y = surface.v1.y.min(surface.v2.y).min(surface.v3.y).max(0.0) as usize;
-    let max_y = (surface.v1.y.max(surface.v2.y).max(surface.v3.y) + 1.0).min(fb.height as f32) as usize;
+    let min_y = surface.v1.y.min(surface.v2.y).min(surface.v3.y).max(fb.y_start as f32) as usize;
+    let max_y = (surface.v1.y.max(surface.v2.y).max(surface.v3.y) + 1.0).min(fb.y_end as f32) as usize;
 
     // Early exit for degenerate/off-screen triangles
     if min_x >= max_x || min_y >= max_y {
@@ -1743,7 +1954,7 @@ fn rasterize_triangle_indexed(
     let flat_shade = if settings.shading == ShadingMode::Flat {
         let center_pos = (surface.w1 + surface.w2 + surface.w3).scale(1.0 / 3.0);
         let world_normal = (surface.wn1 + surface.wn2 + surface.wn3).scale(1.0 / 3.0).normalize();
-        shade_multi_light_color(world_normal, center_pos, &settings.lights, settings.ambient)
+        shade_multi_light_color(world_normal, center_pos, &settings.lights, settings.ambient_rgb())
     } else {
         (1.0, 1.0, 1.0)
     };
@@ -1751,9 +1962,9 @@ fn rasterize_triangle_indexed(
     // Pre-compute Gouraud vertex shading if needed
     let gouraud_shades = if settings.shading == ShadingMode::Gouraud {
         Some((
-            shade_multi_light_color(surface.wn1, surface.w1, &settings.lights, settings.ambient),
-            shade_multi_light_color(surface.wn2, surface.w2, &settings.lights, settings.ambient),
-            shade_multi_light_color(surface.wn3, surface.w3, &settings.lights, settings.ambient),
+            shade_multi_light_color(surface.wn1, surface.w1, &settings.lights, settings.ambient_rgb()),
+            shade_multi_light_color(surface.wn2, surface.w2, &settings.lights, settings.ambient_rgb()),
+            shade_multi_light_color(surface.wn3, surface.w3, &settings.lights, settings.ambient_rgb()),
         ))
     } else {
         None
@@ -1794,46 +2005,35 @@ fn rasterize_triangle_indexed(
     let w0_row_start = a0 * (start_x - v3.x) + b0 * (start_y - v3.y);
     let w1_row_start = a1 * (start_x - v3.x) + b1 * (start_y - v3.y);
 
-    // Step increments
-    let a0_step = a0;
-    let b0_step = b0;
-    let a1_step = a1;
-    let b1_step = b1;
-
     let mut w0_row = w0_row_start;
     let mut w1_row = w1_row_start;
 
+    // Coverage, 1/z and the depth test run LANES pixels at a time (see simd)
+    let span = SpanSetup::new(
+        inv_area,
+        a0,
+        a1,
+        [1.0 / v1.z, 1.0 / v2.z, 1.0 / v3.z],
+        settings.use_zbuffer && !settings.xray_mode,
+        settings.simd,
+    );
+    let [inv_z1, inv_z2, inv_z3] = span.inv_z;
+
     // Rasterize using incremental edge functions
     for y in min_y..max_y {
-        let mut w0 = w0_row;
-        let mut w1 = w1_row;
-
-        for x in min_x..max_x {
-            // Convert to barycentric coordinates
-            let bc_x = w0 * inv_area;
-            let bc_y = w1 * inv_area;
-            let bc_z = 1.0 - bc_x - bc_y;
-
-            // Check if inside triangle
-            const ERR: f32 = -0.0001;
-            if bc_x >= ERR && bc_y >= ERR && bc_z >= ERR {
-                // Perspective-correct depth interpolation
-                // In screen space after perspective divide, 1/z interpolates linearly, not z itself
-                let inv_z1 = 1.0 / v1.z;
-                let inv_z2 = 1.0 / v2.z;
-                let inv_z3 = 1.0 / v3.z;
-                let inv_z_interp = bc_x * inv_z1 + bc_y * inv_z2 + bc_z * inv_z3;
-                let z = 1.0 / inv_z_interp;
-
-                // Z-buffer test (skip in xray mode - render all faces regardless of depth)
-                if settings.use_zbuffer && !settings.xray_mode {
-                    let idx = y * fb.width + x;
-                    if z >= fb.zbuffer[idx] {
-                        w0 += a0_step;
-                        w1 += a1_step;
-                        continue;
-                    }
-                }
+        for x0 in (min_x..max_x).step_by(LANES) {
+            let lanes = (max_x - x0).min(LANES);
+            let row = fb.index(x0, y);
+            let offset = (x0 - min_x) as f32;
+            let quad = span.quad(w0_row + a0 * offset, w1_row + a1 * offset, &fb.zbuffer[row..row + lanes]);
+
+            for lane in quad.covered() {
+                let x = x0 + lane;
+                let bc_x = quad.bc_x[lane];
+                let bc_y = quad.bc_y[lane];
+                let bc_z = 1.0 - bc_x - bc_y;
+                let inv_z_interp = quad.inv_z[lane];
+                let z = quad.z[lane];
 
                 // Interpolate UV coordinates
                 let (u, v) = if settings.affine_textures {
@@ -1875,13 +2075,9 @@ fn rasterize_triangle_indexed(
                     if is_black && !black_transparent {
                         color = Color15::BLACK_DRAWABLE;
                     } else {
-                        w0 += a0_step;
-                        w1 += a1_step;
                         continue;
                     }
                 } else if black_transparent && is_black {
-                    w0 += a0_step;
-                    w1 += a1_step;
                     continue;
                 }
 
@@ -1938,7 +2134,7 @@ fn rasterize_triangle_indexed(
                     fb.set_pixel_xray_15(x, y, color);
                 } else if settings.use_zbuffer {
                     // Z-buffer mode: test depth before writing
-                    let idx = y * fb.width + x;
+                    let idx = fb.index(x, y);
                     if z < fb.zbuffer[idx] {
                         fb.zbuffer[idx] = z;
                         if color.is_semi_transparent() && face_blend_mode != BlendMode::Opaque {
@@ -1956,16 +2152,100 @@ fn rasterize_triangle_indexed(
                     }
                 }
             }
-
-            w0 += a0_step;
-            w1 += a1_step;
         }
 
-        w0_row += b0_step;
-        w1_row += b1_step;
+        w0_row += b0;
+        w1_row += b1;
     }
 }
 
+/// Minimum rows per band; thinner bands spend more time on setup than filling
+const MIN_BAND_ROWS: usize = 16;
+/// Upper bound on rasterizer threads (more bands mostly repeat triangle setup)
+const MAX_RASTER_THREADS: usize = 8;
+/// Below this many surfaces, spawning threads costs more than it saves
+const MIN_PARALLEL_SURFACES: usize = 256;
+
+/// Number of hardware threads available for rasterization (queried once)
+fn raster_threads() -> usize {
+    static THREADS: std::sync::OnceLock<usize> = std::sync::OnceLock::new();
+    *THREADS.get_or_init(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(MAX_RASTER_THREADS)
+    })
+}
+
+/// How many bands to split a draw into (1 = single-threaded)
+fn band_count(height: usize, surface_count: usize, settings: &RasterSettings) -> usize {
+    // WASM has no threads: always take the single-threaded path
+    if cfg!(target_arch = "wasm32") || !settings.multithreaded || surface_count < MIN_PARALLEL_SURFACES {
+        return 1;
+    }
+    raster_threads().min(height / MIN_BAND_ROWS).max(1)
+}
+
+/// Run `draw` over the framebuffer. On native, large draws are split into
+/// horizontal bands rasterized in parallel on scoped threads. Every band sees
+/// every surface in the same order, so the image matches the single-threaded path.
+fn draw_banded<F>(fb: &mut Framebuffer, surface_count: usize, settings: &RasterSettings, draw: F)
+where
+    F: Fn(&mut FramebufferBand) + Sync,
+{
+    let count = band_count(fb.height, surface_count, settings);
+    if count <= 1 {
+        draw(&mut fb.band());
+        return;
+    }
+
+    let mut bands = fb.bands(count);
+    let draw = &draw;
+    std::thread::scope(|scope| {
+        let (first, rest) = bands.split_first_mut().expect("at least one band");
+        for band in rest {
+            scope.spawn(move || draw(band));
+        }
+        // The calling thread takes the first band instead of idling
+        draw(first);
+    });
+}
+
+/// Rasterize RGB555 surfaces: opaque pass (z-buffer writes enabled) then
+/// semi-transparent pass (depth-tested, no z writes). Both passes run per
+/// band, so the transparent pass still sees that band's finished opaque pass.
+fn draw_surfaces_15(
+    fb: &mut Framebuffer,
+    opaque_surfaces: &[Surface],
+    transparent_surfaces: &[Surface],
+    faces: &[Face],
+    textures: &[Texture15],
+    settings: &RasterSettings,
+) {
+    let surface_count = opaque_surfaces.len() + transparent_surfaces.len();
+    draw_banded(fb, surface_count, settings, |band| {
+        // PASS 1: Render opaque surfaces (z-buffer writes enabled)
+        // Establishes depth buffer for correct occlusion
+        for surface in opaque_surfaces {
+            let texture = faces[surface.face_idx]
+                .texture_id
+                .and_then(|id| textures.get(id));
+
+            rasterize_triangle_15(band, surface, texture, surface.blend_mode, surface.black_transparent, settings, false);
+        }
+
+        // PASS 2: Render semi-transparent surfaces (z-buffer writes DISABLED)
+        // Sorted back-to-front for correct blending, depth-tested but doesn't occlude
+        for surface in transparent_surfaces {
+            let texture = faces[surface.face_idx]
+                .texture_id
+                .and_then(|id| textures.get(id));
+
+            rasterize_triangle_15(band, surface, texture, surface.blend_mode, surface.black_transparent, settings, true);
+        }
+    });
+}
+
 /// Render a mesh to the framebuffer
 /// Returns timing breakdown for profiling
 pub fn render_mesh(
@@ -1976,6 +2256,22 @@ pub fn render_mesh(
     camera: &Camera,
     settings: &RasterSettings,
 ) -> RasterTimings {
+    render_mesh_with_clock(fb, vertices, faces, textures, camera, settings, get_time)
+}
+
+/// Render a mesh to the framebuffer, timing the phases with `clock`
+/// (seconds). Lets offline renders run without a macroquad window.
+pub fn render_mesh_with_clock(
+    fb: &mut Framebuffer,
+    vertices: &[Vertex],
+    faces: &[Face],
+    textures: &[Texture],
+    camera: &Camera,
+    settings: &RasterSettings,
+    clock: fn() -> f64,
+) -> RasterTimings {
+    let _timer = crate::profiler::scope(crate::profiler::Subsystem::Raster, "meshes");
+    let get_time = clock;
     let mut timings = RasterTimings::default();
 
     // === TRANSFORM PHASE ===
@@ -2004,13 +2300,14 @@ pub fn render_mesh(
                 camera.basis_z,
                 fb.width,
                 fb.height,
+                settings.vertex_snap,
             );
             // Store cam_pos.z + 5.0 (perspective divide denominator) for correct interpolation
             // This matches the float path's project() which returns z = denom = cam_z + DISTANCE
             let rel_pos = v.pos - camera.position;
             let cam_pos = perspective_transform(rel_pos, camera.basis_x, camera.basis_y, camera.basis_z);
             const DISTANCE: f32 = 5.0;
-            (Vec3::new(sx as f32, sy as f32, cam_pos.z + DISTANCE), cam_pos)
+            (Vec3::new(sx, sy, cam_pos.z + DISTANCE), cam_pos)
         } else {
             // Standard float path
             let rel_pos = v.pos - camera.position;
@@ -2067,11 +2364,12 @@ pub fn render_mesh(
         let edge2 = cv3 - cv1;
         let normal = edge1.cross(edge2).normalize();
 
-        // Determine if this face uses semi-transparency (8-bit path: check texture's blend_mode or editor_alpha)
-        let has_transparency = face.texture_id
-            .and_then(|id| textures.get(id))
-            .map(|t| t.blend_mode != BlendMode::Opaque)
-            .unwrap_or(false)
+        // Determine if this face uses semi-transparency (8-bit path: check face/texture blend_mode or editor_alpha)
+        let has_transparency = face.blend_mode != BlendMode::Opaque
+            || face.texture_id
+                .and_then(|id| textures.get(id))
+                .map(|t| t.blend_mode != BlendMode::Opaque)
+                .unwrap_or(false)
             || face.editor_alpha < 255;
 
         if is_backface {
@@ -2099,9 +2397,9 @@ pub fn render_mesh(
                     uv1: vertices[face.v0].uv,
                     uv2: vertices[face.v2].uv,  // swapped
                     uv3: vertices[face.v1].uv,  // swapped
-                    vc1: vertices[face.v0].color,
-                    vc2: vertices[face.v2].color,  // swapped
-                    vc3: vertices[face.v1].color,  // swapped
+                    vc1: apply_tint_to_color(vertices[face.v0].color, settings.tint),
+                    vc2: apply_tint_to_color(vertices[face.v2].color, settings.tint),  // swapped
+                    vc3: apply_tint_to_color(vertices[face.v1].color, settings.tint),  // swapped
                     normal: normal.scale(-1.0),
                     face_idx,
                     black_transparent: face.black_transparent,
@@ -2128,9 +2426,9 @@ pub fn render_mesh(
                 uv1: vertices[face.v0].uv,
                 uv2: vertices[face.v1].uv,
                 uv3: vertices[face.v2].uv,
-                vc1: vertices[face.v0].color,
-                vc2: vertices[face.v1].color,
-                vc3: vertices[face.v2].color,
+                vc1: apply_tint_to_color(vertices[face.v0].color, settings.tint),
+                vc2: apply_tint_to_color(vertices[face.v1].color, settings.tint),
+                vc3: apply_tint_to_color(vertices[face.v2].color, settings.tint),
                 normal,
                 face_idx,
                 black_transparent: face.black_transparent,
@@ -2169,13 +2467,15 @@ pub fn render_mesh(
 
     // Rasterize each solid surface (skip if wireframe-only mode)
     if !settings.wireframe_overlay {
-        for surface in &surfaces {
-            let texture = faces[surface.face_idx]
-                .texture_id
-                .and_then(|id| textures.get(id));
+        draw_banded(fb, surfaces.len(), settings, |band| {
+            for surface in &surfaces {
+                let texture = faces[surface.face_idx]
+                    .texture_id
+                    .and_then(|id| textures.get(id));
 
-            rasterize_triangle(fb, surface, texture, settings);
-        }
+                rasterize_triangle(band, surface, texture, settings);
+            }
+        });
     }
 
     timings.draw_ms = ((get_time() - draw_start) * 1000.0) as f32;
@@ -2255,6 +2555,7 @@ pub fn render_mesh(
 
     timings.wireframe_ms = ((get_time() - wireframe_start) * 1000.0) as f32;
 
+    crate::profiler::add_raster_timings(&timings);
     timings
 }
 
@@ -2292,6 +2593,16 @@ fn apply_fog_to_color(color: Color, fog_color: Color, fog_factor: f32) -> Color
     Color::new(r, g, b)
 }
 
+/// Multiply a vertex color by a tint (white leaves it unchanged)
+#[inline]
+fn apply_tint_to_color(color: Color, tint: [f32; 3]) -> Color {
+    if tint == [1.0, 1.0, 1.0] {
+        return color;
+    }
+    let scale = |c: u8, t: f32| (c as f32 * t).clamp(0.0, 255.0) as u8;
+    Color::new(scale(color.r, tint[0]), scale(color.g, tint[1]), scale(color.b, tint[2]))
+}
+
 /// Render a mesh using RGB555 textures (PS1-authentic mode)
 /// Uses Texture15 for texture sampling with proper semi-transparency handling
 ///
@@ -2308,6 +2619,7 @@ pub fn render_mesh_15(
     settings: &RasterSettings,
     fog: Option<(f32, f32, f32, Color)>,
 ) -> RasterTimings {
+    let _timer = crate::profiler::scope(crate::profiler::Subsystem::Raster, "meshes");
     let mut timings = RasterTimings::default();
 
     // === TRANSFORM PHASE ===
@@ -2336,13 +2648,14 @@ pub fn render_mesh_15(
                 camera.basis_z,
                 fb.width,
                 fb.height,
+                settings.vertex_snap,
             );
             // Store cam_pos.z + 5.0 (perspective divide denominator) for correct interpolation
             // This matches the float path's project() which returns z = denom = cam_z + DISTANCE
             let rel_pos = v.pos - camera.position;
             let cam_pos = perspective_transform(rel_pos, camera.basis_x, camera.basis_y, camera.basis_z);
             const DISTANCE: f32 = 5.0;
-            (Vec3::new(sx as f32, sy as f32, cam_pos.z + DISTANCE), cam_pos)
+            (Vec3::new(sx, sy, cam_pos.z + DISTANCE), cam_pos)
         } else {
             // Standard float path
             let rel_pos = v.pos - camera.position;
@@ -2414,8 +2727,14 @@ pub fn render_mesh_15(
             }
         };
 
-        // Apply PS1-style fog to vertex colors (depth cueing) and distance culling
+        // Tint vertex colors (time of day), then apply PS1-style fog (depth cueing)
+        // and distance culling
         let fog_start_time = get_time();
+        let (c1, c2, c3) = (
+            apply_tint_to_color(vertices[face.v0].color, settings.tint),
+            apply_tint_to_color(vertices[face.v1].color, settings.tint),
+            apply_tint_to_color(vertices[face.v2].color, settings.tint),
+        );
         let (vc1, vc2, vc3) = if let Some((fog_start, fog_falloff, cull_distance, fog_color)) = fog {
             // Cull faces where all vertices are beyond cull distance
             if cv1.z > cull_distance && cv2.z > cull_distance && cv3.z > cull_distance {
@@ -2429,16 +2748,12 @@ pub fn render_mesh_15(
             let f3 = calculate_fog_factor(cv3.z, fog_start, fog_falloff);
 
             (
-                apply_fog_to_color(vertices[face.v0].color, fog_color, f1),
-                apply_fog_to_color(vertices[face.v1].color, fog_color, f2),
-                apply_fog_to_color(vertices[face.v2].color, fog_color, f3),
+                apply_fog_to_color(c1, fog_color, f1),
+                apply_fog_to_color(c2, fog_color, f2),
+                apply_fog_to_color(c3, fog_color, f3),
             )
         } else {
-            (
-                vertices[face.v0].color,
-                vertices[face.v1].color,
-                vertices[face.v2].color,
-            )
+            (c1, c2, c3)
         };
         fog_total_time += get_time() - fog_start_time;
 
@@ -2548,25 +2863,7 @@ pub fn render_mesh_15(
     let draw_start = get_time();
 
     if !settings.wireframe_overlay {
-        // PASS 1: Render opaque surfaces (z-buffer writes enabled)
-        // Establishes depth buffer for correct occlusion
-        for surface in &opaque_surfaces {
-            let texture = faces[surface.face_idx]
-                .texture_id
-                .and_then(|id| textures.get(id));
-
-            rasterize_triangle_15(fb, surface, texture, surface.blend_mode, surface.black_transparent, settings, false);
-        }
-
-        // PASS 2: Render semi-transparent surfaces (z-buffer writes DISABLED)
-        // Sorted back-to-front for correct blending, depth-tested but doesn't occlude
-        for surface in &transparent_surfaces {
-            let texture = faces[surface.face_idx]
-                .texture_id
-                .and_then(|id| textures.get(id));
-
-            rasterize_triangle_15(fb, surface, texture, surface.blend_mode, surface.black_transparent, settings, true);
-        }
+        draw_surfaces_15(fb, &opaque_surfaces, &transparent_surfaces, faces, textures, settings);
     }
 
     timings.draw_ms = ((get_time() - draw_start) * 1000.0) as f32;
@@ -2634,5 +2931,215 @@ pub fn render_mesh_15(
 
     timings.wireframe_ms = ((get_time() - wireframe_start) * 1000.0) as f32;
 
+    crate::profiler::add_raster_timings(&timings);
     timings
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::math::Vec2;
+
+    fn surface(v1: Vec3, v2: Vec3, v3: Vec3, color: Color, face_idx: usize) -> Surface {
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        Surface {
+            v1, v2, v3,
+            w1: v1, w2: v2, w3: v3,
+            vn1: up, vn2: up, vn3: up,
+            wn1: up, wn2: up, wn3: up,
+            uv1: Vec2::new(0.0, 0.0),
+            uv2: Vec2::new(1.0, 0.0),
+            uv3: Vec2::new(0.0, 1.0),
+            vc1: color, vc2: color, vc3: color,
+            normal: up,
+            face_idx,
+            black_transparent: false,
+            has_transparency: false,
+            blend_mode: BlendMode::Opaque,
+            editor_alpha: 255,
+        }
+    }
+
+    /// Overlapping triangles of varying size and depth spread over the screen
+    fn test_scene(width: usize, height: usize, count: usize) -> (Vec<Surface>, Vec<Face>) {
+        let mut seed: u32 = 12345;
+        let mut rand = move || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32
+        };
+        let mut surfaces = Vec::with_capacity(count);
+        for i in 0..count {
+            let cx = rand() * width as f32;
+            let cy = rand() * height as f32;
+            let size = 8.0 + rand() * 120.0;
+            let z = 10.0 + rand() * 1000.0;
+            let color = Color::new(40 + (i % 200) as u8, 100, 220 - (i % 180) as u8);
+            // Clockwise in screen space (positive area, as after backface culling)
+            surfaces.push(surface(
+                Vec3::new(cx, cy - size, z),
+                Vec3::new(cx + size, cy + size, z + 5.0),
+                Vec3::new(cx - size, cy + size, z - 5.0),
+                color,
+                i,
+            ));
+        }
+        let faces = (0..count).map(|i| Face::new(i, i, i)).collect();
+        (surfaces, faces)
+    }
+
+    #[test]
+    fn test_bands_cover_framebuffer() {
+        let mut fb = Framebuffer::new(64, 50);
+        let bands = fb.bands(4);
+        assert_eq!(bands.len(), 4);
+        assert_eq!(bands[0].y_start, 0);
+        for pair in bands.windows(2) {
+            assert_eq!(pair[0].y_end, pair[1].y_start);
+        }
+        assert_eq!(bands.last().unwrap().y_end, 50);
+
+        // Writes outside a band are ignored
+        let alpha = (2 * 64 + 3) * 4 + 3;
+        fb.bands(2)[1].set_pixel_15(3, 2, Color15::WHITE);
+        assert_eq!(fb.pixels[alpha], 0);
+        fb.bands(2)[0].set_pixel_15(3, 2, Color15::WHITE);
+        assert_eq!(fb.pixels[alpha], 255);
+    }
+
+    #[test]
+    fn test_multithreaded_matches_single_threaded() {
+        let (surfaces, faces) = test_scene(320, 240, MIN_PARALLEL_SURFACES * 2);
+        let serial_settings = RasterSettings { multithreaded: false, ..RasterSettings::default() };
+        let parallel_settings = RasterSettings { multithreaded: true, ..RasterSettings::default() };
+
+        let mut serial = Framebuffer::new(320, 240);
+        draw_surfaces_15(&mut serial, &surfaces, &[], &faces, &[], &serial_settings);
+        let mut parallel = Framebuffer::new(320, 240);
+        draw_surfaces_15(&mut parallel, &surfaces, &[], &faces, &[], &parallel_settings);
+
+        assert!(serial.pixels.iter().any(|&p| p != 0));
+        assert!(serial.pixels == parallel.pixels);
+        assert!(serial.zbuffer == parallel.zbuffer);
+    }
+
+    /// Before/after comparison of single-threaded vs band-parallel rasterization.
+    /// Run with: cargo test --release bench_band_rasterization -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_band_rasterization() {
+        const FRAMES: u32 = 20;
+        let (width, height) = (640, 480);
+        let (surfaces, faces) = test_scene(width, height, 20_000);
+
+        let time = |multithreaded: bool| {
+            let settings = RasterSettings { multithreaded, ..RasterSettings::default() };
+            let mut fb = Framebuffer::new(width, height);
+            let start = std::time::Instant::now();
+            for _ in 0..FRAMES {
+                fb.clear(Color::new(0, 0, 0));
+                draw_surfaces_15(&mut fb, &surfaces, &[], &faces, &[], &settings);
+            }
+            start.elapsed().as_secs_f64() * 1000.0 / FRAMES as f64
+        };
+
+        let single = time(false);
+        let multi = time(true);
+        println!(
+            "{} triangles @ {}x{}: single-threaded {:.2} ms, {} threads {:.2} ms ({:.2}x)",
+            surfaces.len(), width, height, single, raster_threads(), multi, single / multi
+        );
+    }
+
+    /// Textured copies of the test scene's faces plus a checkerboard texture
+    fn textured(faces: &[Face]) -> (Vec<Face>, Vec<Texture15>) {
+        let faces = faces.iter().map(|f| Face { texture_id: Some(0), ..*f }).collect();
+        let texture = Texture15::checkerboard(64, 64, Color15::WHITE, Color15::from_rgb888(200, 40, 40));
+        (faces, vec![texture])
+    }
+
+    #[test]
+    fn test_simd_matches_scalar() {
+        let (surfaces, faces) = test_scene(320, 240, 400);
+        let (textured_faces, textures) = textured(&faces);
+        for shading in [ShadingMode::None, ShadingMode::Gouraud] {
+            for (faces, textures) in [(&faces, &[][..]), (&textured_faces, &textures[..])] {
+                let render = |simd: bool| {
+                    let settings = RasterSettings { simd, shading, multithreaded: false, ..RasterSettings::default() };
+                    let mut fb = Framebuffer::new(320, 240);
+                    draw_surfaces_15(&mut fb, &surfaces, &[], faces, textures, &settings);
+                    fb
+                };
+                let scalar = render(false);
+                let simd = render(true);
+                assert!(scalar.pixels.iter().any(|&p| p != 0));
+                assert!(scalar.pixels == simd.pixels);
+                assert!(scalar.zbuffer == simd.zbuffer);
+            }
+        }
+    }
+
+    #[test]
+    fn test_face_blend_mode_makes_texture_translucent() {
+        // No semi-transparent texels: only the face's blend mode can blend it
+        let textures = [Texture15::checkerboard(8, 8, Color15::WHITE, Color15::WHITE)];
+        let faces = [Face { texture_id: Some(0), ..Face::new(0, 0, 0) }];
+        let settings = RasterSettings {
+            shading: ShadingMode::None,
+            dithering: false,
+            multithreaded: false,
+            ..RasterSettings::default()
+        };
+        let render = |blend_mode: BlendMode| {
+            let mut fb = Framebuffer::new(32, 32);
+            fb.clear(Color::new(0, 0, 200));
+            let neutral = Color::new(128, 128, 128);
+            let mut tri = surface(Vec3::new(16.0, 0.0, 10.0), Vec3::new(32.0, 32.0, 10.0), Vec3::new(0.0, 32.0, 10.0), neutral, 0);
+            tri.blend_mode = blend_mode;
+            tri.has_transparency = blend_mode != BlendMode::Opaque;
+            let (opaque, transparent) = if tri.has_transparency { (vec![], vec![tri]) } else { (vec![tri], vec![]) };
+            draw_surfaces_15(&mut fb, &opaque, &transparent, &faces, &textures, &settings);
+            let i = (20 * 32 + 16) * 4;
+            [fb.pixels[i], fb.pixels[i + 1], fb.pixels[i + 2]]
+        };
+        assert_eq!(render(BlendMode::Opaque), [255, 255, 255]);
+        let average = render(BlendMode::Average);
+        assert!(average[0] > 100 && average[0] < 160, "{:?}", average);
+        assert!(average[2] > average[0], "{:?}", average);
+    }
+
+    /// Scalar vs SIMD span setup on textured and Gouraud-shaded triangles.
+    /// Run with: cargo test --release bench_simd_span_fill -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_simd_span_fill() {
+        const FRAMES: u32 = 20;
+        let (width, height) = (640, 480);
+        let (surfaces, faces) = test_scene(width, height, 20_000);
+        let (textured_faces, textures) = textured(&faces);
+
+        let time = |simd: bool, faces: &[Face], textures: &[Texture15]| {
+            let settings = RasterSettings {
+                simd,
+                multithreaded: false,
+                shading: ShadingMode::Gouraud,
+                ..RasterSettings::default()
+            };
+            let mut fb = Framebuffer::new(width, height);
+            let start = std::time::Instant::now();
+            for _ in 0..FRAMES {
+                fb.clear(Color::new(0, 0, 0));
+                draw_surfaces_15(&mut fb, &surfaces, &[], faces, textures, &settings);
+            }
+            start.elapsed().as_secs_f64() * 1000.0 / FRAMES as f64
+        };
+
+        for (label, faces, textures) in [("gouraud", &faces[..], &[][..]), ("textured", &textured_faces[..], &textures[..])] {
+            let scalar = time(false, faces, textures);
+            let simd = time(true, faces, textures);
+            println!(
+                "{} triangles ({}) @ {}x{}: scalar {:.2} ms, simd {:.2} ms ({:.2}x, simd available: {})",
+                surfaces.len(), label, width, height, scalar, simd, scalar / simd, super::super::simd::available()
+            );
+        }
+    }
+}
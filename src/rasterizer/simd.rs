@@ -0,0 +1,273 @@
+//! SIMD span setup for the triangle rasterizers
+//!
+//! Most of a frame goes to the span loop: for every pixel in a triangle's
+//! bounding box the rasterizer computes barycentric weights, tests coverage,
+//! interpolates 1/z and tests depth - whether the pixel ends up drawn or not.
+//! `SpanSetup::quad` does that for 4 pixels at once (SSE2 on x86_64, NEON on
+//! aarch64) and returns a mask of the pixels left to shade. Texture sampling,
+//! Gouraud shading and dithering then run only for covered pixels.
+//!
+//! The SIMD paths use the same operations in the same order as the scalar
+//! path, so all three produce bit-identical output. WASM and other targets
+//! always use the scalar path.
+
+use std::sync::OnceLock;
+
+/// Pixels processed per step
+pub const LANES: usize = 4;
+
+/// Barycentric tolerance for the inside test (matches the scalar rasterizer)
+const INSIDE_EPSILON: f32 = -0.0001;
+
+/// Does this CPU support the SIMD path? (checked once)
+pub fn available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        #[cfg(target_arch = "x86_64")]
+        {
+            is_x86_feature_detected!("sse2")
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            std::arch::is_aarch64_feature_detected!("neon")
+        }
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+        {
+            false
+        }
+    })
+}
+
+/// Per-triangle constants for span setup
+#[derive(Debug, Clone, Copy)]
+pub struct SpanSetup {
+    /// 1 / (2 * triangle area)
+    pub inv_area: f32,
+    /// Per-pixel x step of the two edge functions
+    pub a0: f32,
+    pub a1: f32,
+    /// 1/z at each vertex
+    pub inv_z: [f32; 3],
+    /// Reject pixels that fail the depth test
+    pub depth_test: bool,
+    /// Use the SIMD path (only set when `available()`)
+    simd: bool,
+}
+
+/// Span setup results for `LANES` consecutive pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Quad {
+    /// Bit per lane: inside the triangle and passing the depth test
+    pub mask: u32,
+    pub bc_x: [f32; LANES],
+    pub bc_y: [f32; LANES],
+    /// Interpolated 1/z
+    pub inv_z: [f32; LANES],
+    pub z: [f32; LANES],
+}
+
+impl Quad {
+    /// Lanes to shade
+    pub fn covered(&self) -> impl Iterator<Item = usize> {
+        let mask = self.mask;
+        (0..LANES).filter(move |lane| mask & (1 << lane) != 0)
+    }
+}
+
+impl SpanSetup {
+    pub fn new(inv_area: f32, a0: f32, a1: f32, inv_z: [f32; 3], depth_test: bool, simd: bool) -> Self {
+        Self { inv_area, a0, a1, inv_z, depth_test, simd: simd && available() }
+    }
+
+    /// Set up the pixels starting where the edge functions are (`w0`, `w1`).
+    /// `zbuf` holds the depth of each pixel; its length (1..=LANES) is the
+    /// number of pixels left in the span.
+    #[inline]
+    pub fn quad(&self, w0: f32, w1: f32, zbuf: &[f32]) -> Quad {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if self.simd {
+                // SAFETY: `simd` is only set when SSE2 was detected
+                return unsafe { sse2::quad(self, w0, w1, zbuf) };
+            }
+        }
+        #[cfg(target_arch = "aarch64")]
+        {
+            if self.simd {
+                // SAFETY: `simd` is only set when NEON was detected
+                return unsafe { neon::quad(self, w0, w1, zbuf) };
+            }
+        }
+        self.quad_scalar(w0, w1, zbuf)
+    }
+
+    fn quad_scalar(&self, w0: f32, w1: f32, zbuf: &[f32]) -> Quad {
+        // Every lane is filled like in the SIMD paths; only the first `len` can be covered
+        let len = zbuf.len().min(LANES);
+        let mut quad = Quad::default();
+        for (lane, depth) in padded_depth(zbuf).into_iter().enumerate() {
+            let step = lane as f32;
+            let bc_x = (w0 + self.a0 * step) * self.inv_area;
+            let bc_y = (w1 + self.a1 * step) * self.inv_area;
+            let bc_z = 1.0 - bc_x - bc_y;
+            let inv_z = bc_x * self.inv_z[0] + bc_y * self.inv_z[1] + bc_z * self.inv_z[2];
+            let z = 1.0 / inv_z;
+            quad.bc_x[lane] = bc_x;
+            quad.bc_y[lane] = bc_y;
+            quad.inv_z[lane] = inv_z;
+            quad.z[lane] = z;
+
+            let inside = bc_x >= INSIDE_EPSILON && bc_y >= INSIDE_EPSILON && bc_z >= INSIDE_EPSILON;
+            // Fails only when z >= depth, so NaN depths pass like in the SIMD paths
+            let visible = !self.depth_test || z < depth || z.is_nan() || depth.is_nan();
+            if inside && visible && lane < len {
+                quad.mask |= 1 << lane;
+            }
+        }
+        quad
+    }
+}
+
+/// Depth of up to `LANES` pixels, padded with +inf (padding lanes are masked off anyway)
+#[inline]
+fn padded_depth(zbuf: &[f32]) -> [f32; LANES] {
+    let mut depth = [f32::INFINITY; LANES];
+    let count = zbuf.len().min(LANES);
+    depth[..count].copy_from_slice(&zbuf[..count]);
+    depth
+}
+
+#[cfg(target_arch = "x86_64")]
+mod sse2 {
+    use std::arch::x86_64::*;
+    use super::{padded_depth, Quad, SpanSetup, INSIDE_EPSILON, LANES};
+
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn quad(setup: &SpanSetup, w0: f32, w1: f32, zbuf: &[f32]) -> Quad {
+        let steps = _mm_set_ps(3.0, 2.0, 1.0, 0.0);
+        let inv_area = _mm_set1_ps(setup.inv_area);
+        let w0 = _mm_add_ps(_mm_set1_ps(w0), _mm_mul_ps(_mm_set1_ps(setup.a0), steps));
+        let w1 = _mm_add_ps(_mm_set1_ps(w1), _mm_mul_ps(_mm_set1_ps(setup.a1), steps));
+        let bc_x = _mm_mul_ps(w0, inv_area);
+        let bc_y = _mm_mul_ps(w1, inv_area);
+        let bc_z = _mm_sub_ps(_mm_sub_ps(_mm_set1_ps(1.0), bc_x), bc_y);
+
+        let inv_z = _mm_add_ps(
+            _mm_add_ps(
+                _mm_mul_ps(bc_x, _mm_set1_ps(setup.inv_z[0])),
+                _mm_mul_ps(bc_y, _mm_set1_ps(setup.inv_z[1])),
+            ),
+            _mm_mul_ps(bc_z, _mm_set1_ps(setup.inv_z[2])),
+        );
+        let z = _mm_div_ps(_mm_set1_ps(1.0), inv_z);
+
+        let eps = _mm_set1_ps(INSIDE_EPSILON);
+        let inside = _mm_and_ps(
+            _mm_and_ps(_mm_cmpge_ps(bc_x, eps), _mm_cmpge_ps(bc_y, eps)),
+            _mm_cmpge_ps(bc_z, eps),
+        );
+        let mut mask = _mm_movemask_ps(inside) as u32;
+        if setup.depth_test {
+            let depth = padded_depth(zbuf);
+            // "not >=" so NaN depths pass, like the scalar path
+            mask &= _mm_movemask_ps(_mm_cmpnge_ps(z, _mm_loadu_ps(depth.as_ptr()))) as u32;
+        }
+        mask &= (1u32 << zbuf.len().min(LANES)) - 1;
+
+        let mut quad = Quad { mask, ..Quad::default() };
+        _mm_storeu_ps(quad.bc_x.as_mut_ptr(), bc_x);
+        _mm_storeu_ps(quad.bc_y.as_mut_ptr(), bc_y);
+        _mm_storeu_ps(quad.inv_z.as_mut_ptr(), inv_z);
+        _mm_storeu_ps(quad.z.as_mut_ptr(), z);
+        quad
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use std::arch::aarch64::*;
+    use super::{padded_depth, Quad, SpanSetup, INSIDE_EPSILON, LANES};
+
+    #[target_feature(enable = "neon")]
+    pub unsafe fn quad(setup: &SpanSetup, w0: f32, w1: f32, zbuf: &[f32]) -> Quad {
+        let step_values = [0.0f32, 1.0, 2.0, 3.0];
+        let steps = vld1q_f32(step_values.as_ptr());
+        let inv_area = vdupq_n_f32(setup.inv_area);
+        let w0 = vaddq_f32(vdupq_n_f32(w0), vmulq_f32(vdupq_n_f32(setup.a0), steps));
+        let w1 = vaddq_f32(vdupq_n_f32(w1), vmulq_f32(vdupq_n_f32(setup.a1), steps));
+        let bc_x = vmulq_f32(w0, inv_area);
+        let bc_y = vmulq_f32(w1, inv_area);
+        let bc_z = vsubq_f32(vsubq_f32(vdupq_n_f32(1.0), bc_x), bc_y);
+
+        let inv_z = vaddq_f32(
+            vaddq_f32(
+                vmulq_f32(bc_x, vdupq_n_f32(setup.inv_z[0])),
+                vmulq_f32(bc_y, vdupq_n_f32(setup.inv_z[1])),
+            ),
+            vmulq_f32(bc_z, vdupq_n_f32(setup.inv_z[2])),
+        );
+        let z = vdivq_f32(vdupq_n_f32(1.0), inv_z);
+
+        let eps = vdupq_n_f32(INSIDE_EPSILON);
+        let mut pass = vandq_u32(
+            vandq_u32(vcgeq_f32(bc_x, eps), vcgeq_f32(bc_y, eps)),
+            vcgeq_f32(bc_z, eps),
+        );
+        if setup.depth_test {
+            let depth = padded_depth(zbuf);
+            // "not >=" so NaN depths pass, like the scalar path
+            pass = vandq_u32(pass, vmvnq_u32(vcgeq_f32(z, vld1q_f32(depth.as_ptr()))));
+        }
+        let mut lanes = [0u32; LANES];
+        vst1q_u32(lanes.as_mut_ptr(), pass);
+        let mut mask = lanes.iter().enumerate()
+            .filter(|(_, &l)| l != 0)
+            .fold(0u32, |m, (i, _)| m | (1 << i));
+        mask &= (1u32 << zbuf.len().min(LANES)) - 1;
+
+        let mut quad = Quad { mask, ..Quad::default() };
+        vst1q_f32(quad.bc_x.as_mut_ptr(), bc_x);
+        vst1q_f32(quad.bc_y.as_mut_ptr(), bc_y);
+        vst1q_f32(quad.inv_z.as_mut_ptr(), inv_z);
+        vst1q_f32(quad.z.as_mut_ptr(), z);
+        quad
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simd_matches_scalar() {
+        let mut seed: u32 = 987654;
+        let mut rand = move || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0
+        };
+        for _ in 0..1000 {
+            let setup = SpanSetup::new(
+                rand() * 0.01,
+                rand() * 50.0,
+                rand() * 50.0,
+                [1.0 / (10.0 + rand().abs() * 900.0), 1.0 / 200.0, 1.0 / (10.0 + rand().abs() * 50.0)],
+                rand() > 0.0,
+                true,
+            );
+            let zbuf = [rand() * 500.0 + 500.0, 300.0, f32::NAN, rand() * 1000.0];
+            let len = 1 + (rand().abs() * 3.99) as usize;
+            let (w0, w1) = (rand() * 200.0, rand() * 200.0);
+            assert_eq!(setup.quad(w0, w1, &zbuf[..len]), setup.quad_scalar(w0, w1, &zbuf[..len]));
+        }
+    }
+
+    #[test]
+    fn test_mask_covers_inside_pixels() {
+        // bc_x runs 0, 0.25, 0.5, 0.75 across the quad; bc_y fixed at 0.5
+        let setup = SpanSetup::new(0.25, 1.0, 0.0, [1.0, 1.0, 1.0], false, true);
+        let quad = setup.quad(0.0, 2.0, &[0.0; 4]);
+        assert_eq!(quad.covered().collect::<Vec<_>>(), vec![0, 1, 2]);
+        // Only the pixels left in the span
+        assert_eq!(setup.quad(0.0, 2.0, &[0.0; 2]).mask, 0b11);
+    }
+}
@@ -1404,6 +1404,10 @@ pub struct RasterSettings {
     pub lights: Vec<Light>,
     /// Ambient light intensity (0.0-1.0)
     pub ambient: f32,
+    /// Ambient light color, multiplied by `ambient` (white = neutral)
+    pub ambient_color: [f32; 3],
+    /// Color multiplied into every vertex color before fog (time of day; white = neutral)
+    pub tint: [f32; 3],
     /// Use PS1 low resolution (320x240) instead of high resolution
     pub low_resolution: bool,
     /// Enable PS1-style ordered dithering (4x4 Bayer matrix)
@@ -1422,9 +1426,59 @@ pub struct RasterSettings {
     /// When enabled, uses 16.16 fixed-point arithmetic which causes the characteristic
     /// PS1 vertex jitter and texture wobble due to limited precision.
     pub use_fixed_point: bool,
+    /// Snap step and jitter strength of the fixed-point vertex snapping
+    pub vertex_snap: VertexSnap,
     /// X-ray mode: see through geometry (editor feature)
     /// When enabled: disables backface culling, disables z-buffer, blends all pixels at 50%
     pub xray_mode: bool,
+    /// Rasterize horizontal framebuffer bands on worker threads (native only;
+    /// WASM always uses the single-threaded path). Output is identical either way.
+    pub multithreaded: bool,
+    /// Set up spans 4 pixels at a time with SSE2/NEON when the CPU supports it
+    /// (WASM and other targets use the scalar path). Output is identical either way.
+    pub simd: bool,
+}
+
+/// How vertices snap with fixed-point math (`use_fixed_point`). Stored per
+/// project in the level, so its wobble is dialed in once and the editor
+/// viewport and the game both use it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VertexSnap {
+    /// Snap step in PS1 pixels (1 = the console's integer coordinates, up to
+    /// `MAX_SNAP_SIZE` for a coarser, more extreme wobble)
+    #[serde(default = "default_snap_size")]
+    pub size: u8,
+    /// How far vertices move onto the snap grid (0 = smooth, 1 = fully snapped)
+    #[serde(default = "default_jitter")]
+    pub jitter: f32,
+}
+
+/// Coarsest vertex snap step (PS1 pixels)
+pub const MAX_SNAP_SIZE: u8 = 8;
+
+fn default_snap_size() -> u8 {
+    1
+}
+
+fn default_jitter() -> f32 {
+    1.0
+}
+
+impl Default for VertexSnap {
+    fn default() -> Self {
+        Self { size: default_snap_size(), jitter: default_jitter() }
+    }
+}
+
+impl VertexSnap {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Snap step in framebuffer pixels for a framebuffer `height` lines tall
+    pub fn grid(&self, height: usize) -> i32 {
+        super::fixed::snap_grid(height) * self.size.clamp(1, MAX_SNAP_SIZE) as i32
+    }
 }
 
 /// Orthographic projection settings for ortho views
@@ -1438,6 +1492,12 @@ pub struct OrthoProjection {
 }
 
 impl RasterSettings {
+    /// Ambient light per channel (intensity times color)
+    pub fn ambient_rgb(&self) -> [f32; 3] {
+        let [r, g, b] = self.ambient_color;
+        [self.ambient * r, self.ambient * g, self.ambient * b]
+    }
+
     /// Get the primary light direction (for backwards compatibility)
     /// Returns the first directional light's direction, or a default if none exists
     pub fn primary_light_dir(&self) -> Vec3 {
@@ -1482,6 +1542,8 @@ impl Default for RasterSettings {
             backface_wireframe: true, // Editor default: show backfaces as wireframe
             lights: vec![Light::directional(Vec3::new(-1.0, -1.0, -1.0), 0.7)],
             ambient: 0.3,
+            ambient_color: [1.0, 1.0, 1.0],
+            tint: [1.0, 1.0, 1.0],
             low_resolution: false,  // High resolution by default
             dithering: true,        // PS1 default: dithering enabled for smooth gradients
             stretch_to_fill: true,  // Default: stretch to fill viewport
@@ -1489,7 +1551,10 @@ impl Default for RasterSettings {
             ortho_projection: None,  // Default: perspective projection
             use_rgb555: true,        // PS1 default: 15-bit color mode
             use_fixed_point: true,   // PS1 default: fixed-point math (jittery)
+            vertex_snap: VertexSnap::default(), // PS1 default: whole-pixel snapping
             xray_mode: false,        // Default: x-ray off
+            multithreaded: true,     // Default: band-parallel rasterization on native
+            simd: true,              // Default: SIMD span setup where available
         }
     }
 }
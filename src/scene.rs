@@ -8,21 +8,41 @@
 use crate::rasterizer::{
     Framebuffer, Camera, RasterSettings, Vertex,
     Texture as RasterTexture, Texture15, Light, Color as RasterColor,
-    render_mesh, render_mesh_15, Clut, ClutId, Vec3,
+    render_mesh, render_mesh_15, Clut, ClutId, Vec3, ShadingMode,
+    perspective_transform, project, NEAR_PLANE, BlendMode, Billboard, draw_billboard,
 };
-use crate::world::Room;
-use crate::asset::{AssetLibrary, AssetComponent};
+use crate::world::{water_at, LevelAtmosphere, MirrorFace, Room, RoomWater};
+use crate::asset::{AssetLibrary, AssetComponent, LodLevel};
 use crate::modeler::{MeshPart, IndexedAtlas, TextureRef as MeshTextureRef, checkerboard_clut};
-use crate::texture::TextureLibrary;
+use crate::texture::{TextureLibrary, UserTexture};
 
 /// Options controlling what gets rendered in a scene
 pub struct SceneRenderOptions<'a> {
-    /// Whether to build and apply per-room fog
+    /// Whether to build and apply per-room and level fog
     pub use_fog: bool,
+    /// Level fog and time-of-day tint (None = neutral, room fog only)
+    pub atmosphere: Option<&'a LevelAtmosphere>,
     /// Whether to render asset meshes placed in rooms
     pub render_assets: bool,
     /// Room indices to skip (e.g., hidden rooms in the editor)
     pub skip_rooms: &'a [usize],
+    /// Switch asset meshes to their LOD variants by camera distance.
+    /// The value scales each asset's switch distances; None = always full detail.
+    pub lod_scale: Option<f32>,
+    /// Asset instances to skip as (room index, object index), e.g. open doors in play mode
+    pub hidden_objects: &'a [(usize, usize)],
+    /// Asset instances displaced in play mode as ((room, object), world offset, extra yaw), e.g. opening doors
+    pub moved_objects: &'a [((usize, usize), Vec3, f32)],
+    /// Asset instances drawn with their Destructible's broken mesh, e.g. smashed crates
+    pub broken_objects: &'a [(usize, usize)],
+    /// Assets drawn without a placed instance as (room index, asset id, world
+    /// position), e.g. loot left by broken props
+    pub dropped_assets: &'a [(usize, u64, Vec3)],
+    /// Use baked vertex lighting for rooms that have it (off = dynamic preview)
+    pub baked_lighting: bool,
+    /// Draw water surfaces animated to this time (seconds), and tint and fog
+    /// the scene when the camera is underwater. None = no water.
+    pub water_time: Option<f32>,
 }
 
 /// Collect all lights from asset instances placed in rooms.
@@ -41,15 +61,14 @@ pub fn collect_scene_lights(
                         return None;
                     }
                     let asset = asset_library.get_by_id(obj.asset_id)?;
-                    for comp in &asset.components {
-                        if let AssetComponent::Light { color, intensity, radius, offset } = comp {
-                            // Apply per-instance overrides if present
-                            let overrides = &obj.overrides.light;
-                            let final_color = overrides.as_ref().and_then(|o| o.color).unwrap_or(*color);
-                            let final_intensity = overrides.as_ref().and_then(|o| o.intensity).unwrap_or(*intensity);
-                            let final_radius = overrides.as_ref().and_then(|o| o.radius).unwrap_or(*radius);
-                            let final_offset = overrides.as_ref().and_then(|o| o.offset).unwrap_or(*offset);
-
+                    for comp in asset.components.iter().filter(|c| c.is_light()) {
+                        // Apply per-instance overrides if present
+                        if let AssetComponent::Light {
+                            color: final_color,
+                            intensity: final_intensity,
+                            radius: final_radius,
+                            offset: final_offset,
+                        } = obj.overrides.apply(comp) {
                             let base_pos = obj.world_position(room);
                             let light_pos = Vec3::new(
                                 base_pos.x + final_offset[0],
@@ -68,6 +87,44 @@ pub fn collect_scene_lights(
         .collect()
 }
 
+/// Bake each room's own lights and ambient into its vertex colors.
+///
+/// With `refresh_only`, only rooms that already have baked lighting are
+/// rebaked (used when saving, so baked rooms stay in sync with edits).
+/// Returns the number of rooms baked.
+pub fn bake_rooms_lighting(rooms: &mut [Room], asset_library: &AssetLibrary, refresh_only: bool) -> usize {
+    let mut baked = 0;
+    for room in rooms.iter_mut() {
+        if refresh_only && !room.has_baked_lighting() {
+            continue;
+        }
+        let lights = collect_scene_lights(std::slice::from_ref(&*room), asset_library);
+        room.bake_lighting(&lights);
+        baked += 1;
+    }
+    baked
+}
+
+/// Raster settings for a room: its own ambient, or no shading when its light is baked
+fn room_raster_settings(room: &Room, baked: bool, lights: &[Light], base_settings: &RasterSettings) -> RasterSettings {
+    if baked {
+        // Light is already in the vertex colors
+        RasterSettings {
+            shading: ShadingMode::None,
+            lights: Vec::new(),
+            ..base_settings.clone()
+        }
+    } else {
+        let (r, g, b) = room.ambient_color;
+        RasterSettings {
+            lights: lights.to_vec(),
+            ambient: room.ambient,
+            ambient_color: [r, g, b],
+            ..base_settings.clone()
+        }
+    }
+}
+
 /// Resolve atlas and CLUT for a mesh part based on its TextureRef.
 ///
 /// For TextureRef::Id, looks up the actual UserTexture data.
@@ -174,7 +231,9 @@ pub fn render_asset_parts(
 ///
 /// This is the single rendering path shared by the world editor, level browser,
 /// game renderer, and camera preview. All consumers get identical behavior:
-/// - Per-room ambient and fog
+/// - Per-room ambient and fog (falling back to the level fog)
+/// - Level time-of-day tint
+/// - Animated water surfaces, and water tint and fog with the camera underwater
 /// - Per-part double_sided backface handling for asset meshes
 /// - Full texture resolution for asset mesh parts
 pub fn render_scene(
@@ -190,26 +249,49 @@ pub fn render_scene(
     resolve_texture: &dyn Fn(&crate::world::TextureRef) -> Option<(usize, u32)>,
     options: &SceneRenderOptions,
 ) {
+    // Mesh building; the raster passes below count as "meshes"
+    let _timer = crate::profiler::scope(crate::profiler::Subsystem::Raster, "scene");
     let use_rgb555 = base_settings.use_rgb555;
 
+    // Below a water surface everything is seen through the water
+    let underwater = options.water_time.and_then(|_| water_at(rooms, camera.position)).map(|(_, water)| water);
+
+    // Time of day is a vertex color multiplier for everything in the scene
+    let mut tint = options.atmosphere.map_or([1.0, 1.0, 1.0], |atmosphere| atmosphere.light_tint());
+    if let Some(water) = underwater {
+        let water_tint = water.tint();
+        for (channel, t) in tint.iter_mut().zip(water_tint) {
+            *channel *= t;
+        }
+    }
+    let tinted_settings;
+    let base_settings = if tint == [1.0, 1.0, 1.0] {
+        base_settings
+    } else {
+        tinted_settings = RasterSettings { tint, ..base_settings.clone() };
+        &tinted_settings
+    };
+    let room_fog = |room: &Room| match underwater {
+        Some(water) => Some(build_water_fog(water)),
+        None if options.use_fog => build_room_fog(room, options.atmosphere),
+        None => None,
+    };
+
     // === Room geometry ===
     for (room_idx, room) in rooms.iter().enumerate() {
         if options.skip_rooms.contains(&room_idx) {
             continue;
         }
 
-        let render_settings = RasterSettings {
-            lights: lights.to_vec(),
-            ambient: room.ambient,
-            ..base_settings.clone()
-        };
+        let baked = options.baked_lighting && room.has_baked_lighting();
+        let render_settings = room_raster_settings(room, baked, lights, base_settings);
 
-        let (vertices, faces) = room.to_render_data_with_textures(resolve_texture);
+        let (vertices, faces) = room.to_render_data_lit(resolve_texture, baked);
         if vertices.is_empty() {
             continue;
         }
 
-        let fog = if options.use_fog { build_room_fog(room) } else { None };
+        let fog = room_fog(room);
 
         if use_rgb555 {
             render_mesh_15(fb, &vertices, &faces, textures_15, camera, &render_settings, fog);
@@ -219,58 +301,240 @@ pub fn render_scene(
     }
 
     // === Asset meshes placed in rooms ===
-    if !options.render_assets {
-        return;
-    }
+    let mut sprites: Vec<(Billboard, &UserTexture)> = Vec::new();
+    if options.render_assets {
+        for (room_idx, room) in rooms.iter().enumerate() {
+            if options.skip_rooms.contains(&room_idx) {
+                continue;
+            }
 
-    for (room_idx, room) in rooms.iter().enumerate() {
-        if options.skip_rooms.contains(&room_idx) {
-            continue;
-        }
+            let fog = room_fog(room);
 
-        let fog = if options.use_fog { build_room_fog(room) } else { None };
+            for (obj_idx, obj) in room.objects.iter().enumerate() {
+                if !obj.enabled || options.hidden_objects.contains(&(room_idx, obj_idx)) {
+                    continue;
+                }
 
-        for obj in &room.objects {
-            if !obj.enabled {
-                continue;
-            }
+                let asset = match asset_library.get_by_id(obj.asset_id) {
+                    Some(a) => a,
+                    None => continue,
+                };
 
-            let asset = match asset_library.get_by_id(obj.asset_id) {
-                Some(a) => a,
-                None => continue,
-            };
+                let (world_pos, obj_facing) = match options.moved_objects.iter().find(|(o, ..)| *o == (room_idx, obj_idx)) {
+                    Some(&(_, offset, yaw)) => (obj.world_position(room) + offset, obj.facing + yaw),
+                    None => (obj.world_position(room), obj.facing),
+                };
 
-            let mesh_parts = match asset.mesh() {
-                Some(parts) => parts,
-                None => continue,
-            };
+                for component in &asset.components {
+                    if let AssetComponent::Sprite { texture: Some(id), scale, blend, offset } = obj.overrides.apply(component) {
+                        if let Some(texture) = user_textures.get_by_id(id) {
+                            let billboard = Billboard {
+                                position: world_pos + Vec3::new(offset[0], offset[1], offset[2]),
+                                width: texture.width as f32 * scale,
+                                height: texture.height as f32 * scale,
+                                blend,
+                            };
+                            sprites.push((billboard, texture));
+                        }
+                    }
+                }
 
-            let world_pos = obj.world_position(room);
-            let room_settings = RasterSettings {
-                lights: lights.to_vec(),
-                ambient: room.ambient,
-                ..base_settings.clone()
-            };
+                // Pick the LOD variant; impostors turn to face the camera
+                let (lod, facing) = match options.lod_scale {
+                    Some(scale) => {
+                        let distance = (world_pos - camera.position).len();
+                        let lod = asset.lod_level(distance, scale);
+                        let facing = if lod == LodLevel::Impostor {
+                            billboard_facing(camera, world_pos)
+                        } else {
+                            obj_facing
+                        };
+                        (lod, facing)
+                    }
+                    None => (LodLevel::Full, obj_facing),
+                };
+
+                let broken = options.broken_objects.contains(&(room_idx, obj_idx));
+                let mesh_parts = match asset.broken_mesh().filter(|_| broken).or_else(|| asset.lod_mesh(lod)) {
+                    Some(parts) => parts,
+                    None => continue,
+                };
+                // Asset meshes stay dynamically lit, like TR1 objects
+                let room_settings = room_raster_settings(room, false, lights, base_settings);
+
+                render_asset_parts(
+                    fb, mesh_parts, camera, &room_settings,
+                    facing, world_pos, fog, user_textures,
+                );
+            }
+        }
 
+        for &(room_idx, asset_id, world_pos) in options.dropped_assets {
+            let Some(room) = rooms.get(room_idx).filter(|_| !options.skip_rooms.contains(&room_idx)) else {
+                continue;
+            };
+            let Some(mesh_parts) = asset_library.get_by_id(asset_id).and_then(|a| a.mesh()) else {
+                continue;
+            };
+            let room_settings = room_raster_settings(room, false, lights, base_settings);
             render_asset_parts(
                 fb, mesh_parts, camera, &room_settings,
-                obj.facing, world_pos, fog, user_textures,
+                0.0, world_pos, room_fog(room), user_textures,
             );
         }
+
+        // Opaque sprites write depth like the geometry around them
+        for (billboard, texture) in sprites.iter().filter(|(b, _)| b.blend == BlendMode::Opaque) {
+            draw_billboard(fb, camera, billboard, &texture.to_raster_texture());
+        }
     }
+
+    // === Water surfaces (semi-transparent, so after everything solid) ===
+    if let Some(time) = options.water_time {
+        let water_settings = RasterSettings {
+            shading: ShadingMode::None,
+            lights: Vec::new(),
+            // Seen from above and below
+            backface_cull: false,
+            backface_wireframe: false,
+            ..base_settings.clone()
+        };
+        for (room_idx, room) in rooms.iter().enumerate() {
+            if options.skip_rooms.contains(&room_idx) || room.water.is_none() {
+                continue;
+            }
+            let (vertices, faces) = room.water_render_data(time, resolve_texture);
+            if vertices.is_empty() {
+                continue;
+            }
+            if use_rgb555 {
+                render_mesh_15(fb, &vertices, &faces, textures_15, camera, &water_settings, room_fog(room));
+            } else {
+                render_mesh(fb, &vertices, &faces, textures, camera, &water_settings);
+            }
+        }
+    }
+
+    // === Blended sprites (after everything solid, back to front) ===
+    let mut blended: Vec<(f32, &Billboard, &UserTexture)> = sprites.iter()
+        .filter(|(b, _)| b.blend != BlendMode::Opaque)
+        .filter_map(|(b, texture)| b.depth(camera).map(|depth| (depth, b, *texture)))
+        .collect();
+    blended.sort_by(|a, b| b.0.total_cmp(&a.0));
+    for (_, billboard, texture) in blended {
+        draw_billboard(fb, camera, billboard, &texture.to_raster_texture());
+    }
+}
+
+/// Facing angle (Y rotation) that turns a +Z-facing mesh toward the camera.
+fn billboard_facing(camera: &Camera, world_pos: Vec3) -> f32 {
+    let dx = camera.position.x - world_pos.x;
+    let dz = camera.position.z - world_pos.z;
+    // render_asset_parts rotates +Z to (-sin f, 0, cos f)
+    (-dx).atan2(dz)
 }
 
-/// Build fog parameters from a room's fog settings.
-fn build_room_fog(room: &Room) -> Option<(f32, f32, f32, RasterColor)> {
+/// Build fog parameters from a room's fog settings, or the level's when the
+/// room has none. Level fog doesn't cull: fully fogged geometry stays drawn.
+fn build_room_fog(room: &Room, atmosphere: Option<&LevelAtmosphere>) -> Option<(f32, f32, f32, RasterColor)> {
     if !room.fog.enabled {
-        return None;
+        let atmosphere = atmosphere?;
+        let (start, falloff) = atmosphere.fog_range()?;
+        return Some((start, falloff, f32::INFINITY, fog_color(atmosphere.fog_color)));
     }
-    let (r, g, b) = room.fog.color;
-    let fog_color = RasterColor::new(
+    let cull_distance = room.fog.start + room.fog.falloff + room.fog.cull_offset;
+    Some((room.fog.start, room.fog.falloff, cull_distance, fog_color(room.fog.color)))
+}
+
+/// Fog for a camera underwater: close, in the water's color, culling what
+/// has faded out completely
+fn build_water_fog(water: &RoomWater) -> (f32, f32, f32, RasterColor) {
+    let (start, falloff) = water.fog_range();
+    (start, falloff, start + falloff, fog_color(water.color))
+}
+
+/// Fog color (RGB 0.0-1.0) as a raster color
+fn fog_color((r, g, b): (f32, f32, f32)) -> RasterColor {
+    RasterColor::new(
         (r * 255.0) as u8,
         (g * 255.0) as u8,
         (b * 255.0) as u8,
-    );
-    let cull_distance = room.fog.start + room.fog.falloff + room.fog.cull_offset;
-    Some((room.fog.start, room.fog.falloff, cull_distance, fog_color))
+    )
+}
+
+/// Copy a reflection into the pixels where a mirror is visible.
+///
+/// `reflection` must be the same size as `fb`, rendered from
+/// `MirrorFace::reflect_camera`, which leaves it left-right flipped; it is
+/// read back mirrored. Pixels where other geometry sits in front of the
+/// mirror are left alone. The face's own texture shows through at 1/4 so
+/// tinted or dirty mirrors still read.
+pub fn composite_mirror(fb: &mut Framebuffer, reflection: &Framebuffer, camera: &Camera, faces: &[MirrorFace]) {
+    if reflection.width != fb.width || reflection.height != fb.height {
+        return;
+    }
+    let (w, h) = (fb.width, fb.height);
+
+    for face in faces {
+        // Camera space, clipped to the near plane (the mirror may fill the view)
+        let cam: Vec<Vec3> = face.corners.iter()
+            .map(|&p| perspective_transform(p - camera.position, camera.basis_x, camera.basis_y, camera.basis_z))
+            .collect();
+        let mut clipped: Vec<Vec3> = Vec::with_capacity(6);
+        for i in 0..cam.len() {
+            let (a, b) = (cam[i], cam[(i + 1) % cam.len()]);
+            if a.z > NEAR_PLANE {
+                clipped.push(a);
+            }
+            if (a.z > NEAR_PLANE) != (b.z > NEAR_PLANE) {
+                let t = (NEAR_PLANE - a.z) / (b.z - a.z);
+                clipped.push(a + (b - a) * t);
+            }
+        }
+        if clipped.len() < 3 {
+            continue;
+        }
+        let screen: Vec<Vec3> = clipped.iter().map(|&v| project(v, w, h)).collect();
+
+        // Fan triangles, depth interpolated as 1/z like the rasterizer
+        for i in 1..screen.len() - 1 {
+            let (v1, v2, v3) = (screen[0], screen[i], screen[i + 1]);
+            let area = (v2.y - v3.y) * (v1.x - v3.x) + (v3.x - v2.x) * (v1.y - v3.y);
+            if area.abs() < 0.00001 {
+                continue;
+            }
+            let min_x = v1.x.min(v2.x).min(v3.x).floor().max(0.0) as usize;
+            let max_x = (v1.x.max(v2.x).max(v3.x).ceil().max(0.0) as usize).min(w);
+            let min_y = v1.y.min(v2.y).min(v3.y).floor().max(0.0) as usize;
+            let max_y = (v1.y.max(v2.y).max(v3.y).ceil().max(0.0) as usize).min(h);
+            fb.mark_dirty(min_x, min_y, max_x, max_y);
+
+            for y in min_y..max_y {
+                let py = y as f32 + 0.5;
+                for x in min_x..max_x {
+                    let px = x as f32 + 0.5;
+                    let b1 = ((v2.y - v3.y) * (px - v3.x) + (v3.x - v2.x) * (py - v3.y)) / area;
+                    let b2 = ((v3.y - v1.y) * (px - v3.x) + (v1.x - v3.x) * (py - v3.y)) / area;
+                    let b3 = 1.0 - b1 - b2;
+                    if b1 < 0.0 || b2 < 0.0 || b3 < 0.0 {
+                        continue;
+                    }
+                    let z = 1.0 / (b1 / v1.z + b2 / v2.z + b3 / v3.z);
+                    let idx = y * w + x;
+                    // Something closer than the mirror covers this pixel
+                    if fb.zbuffer[idx] < z * 0.98 {
+                        continue;
+                    }
+                    let src = (y * w + (w - 1 - x)) * 4;
+                    let dst = idx * 4;
+                    for c in 0..3 {
+                        let refl = reflection.pixels[src + c] as u16;
+                        let orig = fb.pixels[dst + c] as u16;
+                        fb.pixels[dst + c] = ((refl * 3 + orig) / 4) as u8;
+                    }
+                    fb.zbuffer[idx] = z;
+                }
+            }
+        }
+    }
 }
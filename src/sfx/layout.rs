@@ -0,0 +1,306 @@
+//! SFX editor UI layout and rendering
+
+use macroquad::prelude::*;
+use crate::game::sound::SoundBindings;
+use crate::storage::Storage;
+use crate::tracker::{AudioEngine, ReverbType, SAMPLE_RATE};
+use crate::ui::{
+    Rect, UiContext, Toolbar, Shortcut, TextInputState, icon, draw_knob, draw_text_input, text_button,
+    theme, NOTE_COLOR,
+};
+use super::sound::{SfxSound, SfxSource, Waveform};
+use super::state::{is_valid_name, SfxEditorState};
+
+const TOOLBAR_HEIGHT: f32 = 36.0;
+const STATUS_BAR_HEIGHT: f32 = 22.0;
+const LIST_WIDTH: f32 = 180.0;
+const LIST_ROW_HEIGHT: f32 = 20.0;
+const BUTTON_HEIGHT: f32 = 24.0;
+const ROW_LABEL_WIDTH: f32 = 70.0;
+const KNOB_RADIUS: f32 = 22.0;
+const KNOB_SPACING: f32 = 84.0;
+/// Knob row: label above, knob, value box below
+const KNOB_ROW_HEIGHT: f32 = KNOB_RADIUS * 2.0 + 56.0;
+const WAVEFORM_HEIGHT: f32 = 120.0;
+
+/// Knobs in two rows of four: label, bipolar
+const KNOBS: [(&str, bool); 8] = [
+    ("Note", false),
+    ("Sweep", true),
+    ("Length", false),
+    ("Volume", false),
+    ("Attack", false),
+    ("Decay", false),
+    ("Sustain", false),
+    ("Release", false),
+];
+
+fn knob_value(sound: &mut SfxSound, index: usize) -> &mut u8 {
+    match index {
+        0 => &mut sound.note,
+        1 => &mut sound.sweep,
+        2 => &mut sound.length,
+        3 => &mut sound.volume,
+        4 => &mut sound.attack,
+        5 => &mut sound.decay,
+        6 => &mut sound.sustain,
+        _ => &mut sound.release,
+    }
+}
+
+/// Draw the SFX editor
+pub fn draw_sfx_editor(
+    ctx: &mut UiContext,
+    rect: Rect,
+    state: &mut SfxEditorState,
+    audio: &AudioEngine,
+    bindings: &SoundBindings,
+    icon_font: Option<&Font>,
+    storage: &Storage,
+) {
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().bg);
+
+    let toolbar_rect = Rect::new(rect.x, rect.y, rect.w, TOOLBAR_HEIGHT);
+    let status_rect = Rect::new(rect.x, rect.bottom() - STATUS_BAR_HEIGHT, rect.w, STATUS_BAR_HEIGHT);
+    let body_y = rect.y + TOOLBAR_HEIGHT;
+    let body_h = rect.h - TOOLBAR_HEIGHT - STATUS_BAR_HEIGHT;
+    let list_rect = Rect::new(rect.x, body_y, LIST_WIDTH, body_h);
+    let editor_rect = Rect::new(rect.x + LIST_WIDTH, body_y, rect.w - LIST_WIDTH, body_h);
+
+    draw_toolbar(ctx, toolbar_rect, state, audio, icon_font, storage);
+    draw_sound_list(ctx, list_rect, state, audio);
+    draw_parameters(ctx, editor_rect, state, audio);
+    draw_status_bar(status_rect, state, bindings);
+
+    if state.name_edit.is_none() {
+        handle_input(state, audio, storage);
+    }
+}
+
+fn save(state: &mut SfxEditorState, storage: &Storage, audio: &AudioEngine) {
+    if let Err(e) = state.save(storage, audio) {
+        state.set_status(&format!("Save failed: {}", e), 3.0);
+    }
+}
+
+/// New / save / preview buttons and the sound's name (click to rename)
+fn draw_toolbar(
+    ctx: &mut UiContext,
+    rect: Rect,
+    state: &mut SfxEditorState,
+    audio: &AudioEngine,
+    icon_font: Option<&Font>,
+    storage: &Storage,
+) {
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().header);
+    let mut toolbar = Toolbar::new(rect);
+
+    if toolbar.icon_button(ctx, icon::FILE_PLUS, icon_font, "New Sound (Ctrl+N)") {
+        state.new_sound();
+    }
+    if toolbar.icon_button(ctx, icon::SAVE, icon_font, "Save (Ctrl+S)") {
+        save(state, storage, audio);
+    }
+    toolbar.separator();
+    if toolbar.icon_button(ctx, icon::PLAY, icon_font, "Preview (Space)") {
+        state.play_preview(audio);
+    }
+    if toolbar.icon_button(ctx, icon::SQUARE, icon_font, "Stop") {
+        audio.stop_pcm();
+    }
+    toolbar.separator();
+
+    let label = if state.dirty { format!("{}*", state.name) } else { state.name.clone() };
+    let (clicked, name_rect) = toolbar.clickable_label(ctx, &label, "Rename (the name game events and scripts use)");
+    if clicked && state.name_edit.is_none() {
+        state.name_edit = Some(TextInputState::new(state.name.clone()));
+    }
+
+    let Some(input) = state.name_edit.as_mut() else {
+        return;
+    };
+    let input_rect = Rect::new(name_rect.x, name_rect.y, 180.0, name_rect.h);
+    draw_text_input(input_rect, input, 12.0);
+    let outside_click = ctx.mouse.left_pressed && !ctx.mouse.inside(&input_rect);
+    if is_key_pressed(KeyCode::Escape) {
+        state.name_edit = None;
+    } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) || outside_click {
+        let name = input.text.trim().to_string();
+        state.name_edit = None;
+        if !is_valid_name(&name) {
+            state.set_status("Names use letters, digits, _ and - only", 3.0);
+        } else if name != state.name {
+            state.name = name;
+            state.dirty = true;
+        }
+    }
+}
+
+/// Saved sounds; click one to open and hear it
+fn draw_sound_list(ctx: &mut UiContext, rect: Rect, state: &mut SfxEditorState, audio: &AudioEngine) {
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().row_odd);
+    draw_line(rect.right(), rect.y, rect.right(), rect.bottom(), 1.0, theme().header);
+    draw_text("Sounds", rect.x + 8.0, rect.y + 18.0, 14.0, theme().text_dim);
+
+    let names: Vec<String> = state.library.names().map(String::from).collect();
+    if names.is_empty() {
+        draw_text("No saved sounds", rect.x + 8.0, rect.y + 42.0, 12.0, theme().text_dim);
+        return;
+    }
+    let mut opened = None;
+    for (i, name) in names.iter().enumerate() {
+        let row = Rect::new(rect.x, rect.y + 26.0 + i as f32 * LIST_ROW_HEIGHT, rect.w - 1.0, LIST_ROW_HEIGHT);
+        if row.bottom() > rect.bottom() {
+            break;
+        }
+        let color = if state.current.as_deref() == Some(name.as_str()) {
+            theme().row_highlight
+        } else if i % 2 == 0 {
+            theme().row_even
+        } else {
+            theme().row_odd
+        };
+        draw_rectangle(row.x, row.y, row.w, row.h, color);
+        draw_text(name, row.x + 8.0, row.y + 14.0, 12.0, theme().text);
+        if ctx.mouse.clicked(&row) {
+            opened = Some(name.clone());
+        }
+    }
+    if let Some(name) = opened {
+        state.open(&name);
+        state.play_preview(audio);
+    }
+}
+
+/// Source, envelope, pitch and reverb controls above the rendered waveform
+fn draw_parameters(ctx: &mut UiContext, rect: Rect, state: &mut SfxEditorState, audio: &AudioEngine) {
+    let x0 = rect.x + 16.0;
+    let mut y = rect.y + 16.0;
+
+    // Source: a waveform or a soundfont instrument
+    draw_text("Source", x0, y + 16.0, 14.0, theme().text_dim);
+    let mut x = x0 + ROW_LABEL_WIDTH;
+    for wave in Waveform::ALL {
+        let button = Rect::new(x, y, 70.0, BUTTON_HEIGHT);
+        if text_button(ctx, button, wave.label(), "") {
+            state.edit(|sound| sound.source = SfxSource::Wave(wave));
+        }
+        if state.sound.source == SfxSource::Wave(wave) {
+            draw_rectangle_lines(button.x, button.y, button.w, button.h, 1.0, theme().accent);
+        }
+        x += 74.0;
+    }
+    let program = match state.sound.source {
+        SfxSource::Instrument(program) => Some(program),
+        SfxSource::Wave(_) => None,
+    };
+    let button = Rect::new(x, y, 90.0, BUTTON_HEIGHT);
+    if text_button(ctx, button, "Instrument", "A note of a soundfont instrument") {
+        state.edit(|sound| sound.source = SfxSource::Instrument(program.unwrap_or(0)));
+    }
+    if program.is_some() {
+        draw_rectangle_lines(button.x, button.y, button.w, button.h, 1.0, theme().accent);
+    }
+    y += BUTTON_HEIGHT + 8.0;
+
+    if let Some(program) = program {
+        draw_text("Program", x0, y + 16.0, 14.0, theme().text_dim);
+        let x = x0 + ROW_LABEL_WIDTH;
+        if text_button(ctx, Rect::new(x, y, BUTTON_HEIGHT, BUTTON_HEIGHT), "<", "Previous instrument") {
+            state.edit(|sound| sound.source = SfxSource::Instrument(program.saturating_sub(1)));
+        }
+        if text_button(ctx, Rect::new(x + 28.0, y, BUTTON_HEIGHT, BUTTON_HEIGHT), ">", "Next instrument") {
+            state.edit(|sound| sound.source = SfxSource::Instrument((program + 1).min(127)));
+        }
+        let name = audio.get_preset_names()
+            .into_iter()
+            .find(|(_, p, _)| *p == program)
+            .map(|(_, _, name)| name)
+            .unwrap_or_default();
+        let text = if audio.is_loaded() { format!("{:03} {}", program, name) } else { "No soundfont loaded".to_string() };
+        draw_text(&text, x + 60.0, y + 16.0, 14.0, theme().text);
+        y += BUTTON_HEIGHT + 8.0;
+    }
+
+    // Pitch and envelope knobs
+    y += 12.0;
+    for (i, (label, bipolar)) in KNOBS.iter().enumerate() {
+        let cx = x0 + KNOB_RADIUS + (i % 4) as f32 * KNOB_SPACING;
+        let cy = y + 20.0 + KNOB_RADIUS + (i / 4) as f32 * KNOB_ROW_HEIGHT;
+        let value = *knob_value(&mut state.sound, i);
+        if let Some(value) = draw_knob(ctx, cx, cy, KNOB_RADIUS, value, label, *bipolar, false).value {
+            state.edit(|sound| *knob_value(sound, i) = value);
+        }
+    }
+    y += KNOB_ROW_HEIGHT * 2.0;
+
+    // Reverb preset and wet level
+    draw_text("Reverb", x0, y + 16.0, 14.0, theme().text_dim);
+    let reverb = state.sound.reverb_type();
+    let x = x0 + ROW_LABEL_WIDTH;
+    if text_button(ctx, Rect::new(x, y, BUTTON_HEIGHT, BUTTON_HEIGHT), "<", "Previous reverb") {
+        state.edit(|sound| sound.reverb = reverb.to_index().saturating_sub(1));
+    }
+    if text_button(ctx, Rect::new(x + 28.0, y, BUTTON_HEIGHT, BUTTON_HEIGHT), ">", "Next reverb") {
+        let last = ReverbType::ALL.len() as u8 - 1;
+        state.edit(|sound| sound.reverb = (reverb.to_index() + 1).min(last));
+    }
+    draw_text(reverb.name(), x + 60.0, y + 16.0, 14.0, theme().text);
+    let wet_x = x0 + KNOB_RADIUS + 3.0 * KNOB_SPACING;
+    let wet_y = y + 20.0 + KNOB_RADIUS;
+    if let Some(value) = draw_knob(ctx, wet_x, wet_y, KNOB_RADIUS, state.sound.reverb_wet, "Wet", false, false).value {
+        state.edit(|sound| sound.reverb_wet = value);
+    }
+    y += KNOB_ROW_HEIGHT;
+
+    let height = WAVEFORM_HEIGHT.min(rect.bottom() - y - 16.0);
+    if height > 24.0 {
+        let pcm = state.preview(audio);
+        draw_waveform(Rect::new(x0, y, rect.w - 32.0, height), &pcm);
+    }
+}
+
+/// Min/max envelope of the rendered samples, one column per pixel
+fn draw_waveform(rect: Rect, pcm: &[f32]) {
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().row_even);
+    let mid = rect.y + rect.h * 0.5;
+    draw_line(rect.x, mid, rect.right(), mid, 1.0, theme().header);
+    let columns = rect.w.max(1.0) as usize;
+    let per_column = pcm.len().div_ceil(columns).max(1);
+    for (column, chunk) in pcm.chunks(per_column).enumerate() {
+        let (min, max) = chunk.iter().fold((0.0f32, 0.0f32), |(lo, hi), &s| (lo.min(s), hi.max(s)));
+        let x = rect.x + column as f32 + 0.5;
+        let top = mid - max.clamp(-1.0, 1.0) * rect.h * 0.5;
+        let bottom = mid - min.clamp(-1.0, 1.0) * rect.h * 0.5;
+        draw_line(x, top, x, bottom.max(top + 1.0), 1.0, NOTE_COLOR);
+    }
+    let seconds = pcm.len() as f32 / SAMPLE_RATE as f32;
+    draw_text(&format!("{:.2} s", seconds), rect.x + 6.0, rect.y + 14.0, 12.0, theme().text_dim);
+}
+
+fn draw_status_bar(rect: Rect, state: &SfxEditorState, bindings: &SoundBindings) {
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0.16, 0.16, 0.18, 1.0));
+    if let Some(msg) = state.get_status() {
+        draw_text(msg, rect.x + 10.0, rect.y + 15.0, 14.0, Color::from_rgba(100, 255, 100, 255));
+    }
+
+    // Which game events play this sound in the open level
+    let events = bindings.events_for(&state.name);
+    let help = if events.is_empty() {
+        "Space: Preview | Ctrl+S: Save | Scripts play sounds by name".to_string()
+    } else {
+        format!("Plays on: {} | Space: Preview | Ctrl+S: Save", events.join(", "))
+    };
+    let dims = measure_text(&help, None, 12, 1.0);
+    draw_text(&help, rect.right() - dims.width - 10.0, rect.y + 15.0, 12.0, theme().text_dim);
+}
+
+fn handle_input(state: &mut SfxEditorState, audio: &AudioEngine, storage: &Storage) {
+    if Shortcut::ctrl(KeyCode::S).is_pressed() {
+        save(state, storage, audio);
+    } else if Shortcut::ctrl(KeyCode::N).is_pressed() {
+        state.new_sound();
+    } else if is_key_pressed(KeyCode::Space) {
+        state.play_preview(audio);
+    }
+}
@@ -0,0 +1,135 @@
+//! SFX files and the rendered sound library
+//!
+//! Sounds are saved as RON in `assets/userdata/sfx/NAME.sfx`. The file name is
+//! the sound's name: level scripts (`sound "name"`) and the level's sound
+//! bindings for game events refer to it.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::storage::Storage;
+use crate::tracker::{AudioEngine, MixGroup, PcmPlacement};
+use super::sound::SfxSound;
+
+/// Directory for user sound effects
+pub const USER_SFX_DIR: &str = "assets/userdata/sfx";
+/// Extension of sound effect files
+pub const SFX_EXTENSION: &str = "sfx";
+
+/// Storage path of the sound called `name`
+pub fn sfx_path(name: &str) -> String {
+    format!("{}/{}.{}", USER_SFX_DIR, name, SFX_EXTENSION)
+}
+
+/// Parse a sound from RON
+pub fn load_sfx_from_str(contents: &str) -> Result<SfxSound, String> {
+    ron::from_str(contents).map_err(|e| format!("Failed to parse sound: {}", e))
+}
+
+/// Save a sound using the storage backend
+pub fn save_sfx(sound: &SfxSound, path: &str, storage: &Storage) -> Result<(), String> {
+    let contents = ron::ser::to_string_pretty(sound, ron::ser::PrettyConfig::new().indentor("  ".to_string()))
+        .map_err(|e| format!("Failed to serialize sound: {}", e))?;
+    storage
+        .write_sync(path, contents.as_bytes())
+        .map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Load a sound using the storage backend
+pub fn load_sfx(path: &str, storage: &Storage) -> Result<SfxSound, String> {
+    let contents = storage
+        .read_string_sync(path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    load_sfx_from_str(&contents)
+}
+
+/// Saved sounds by name, rendered and ready to play
+#[derive(Default)]
+pub struct SfxLibrary {
+    sounds: BTreeMap<String, SfxSound>,
+    pcm: HashMap<String, Arc<[f32]>>,
+}
+
+impl SfxLibrary {
+    /// Load and render every sound in the user SFX directory. Cloud storage
+    /// can't be listed synchronously, so it starts empty there.
+    pub fn load(storage: &Storage, audio: &AudioEngine) -> Self {
+        let mut library = Self::default();
+        if !storage.is_sync(USER_SFX_DIR) {
+            return library;
+        }
+        let Ok(files) = storage.list_sync(USER_SFX_DIR) else {
+            return library;
+        };
+        for file in files {
+            let path = Path::new(&file);
+            if path.extension().and_then(|e| e.to_str()) != Some(SFX_EXTENSION) {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            match load_sfx(&sfx_path(name), storage) {
+                Ok(sound) => {
+                    let pcm = sound.render_with(audio);
+                    library.insert(name, sound, pcm);
+                }
+                Err(e) => eprintln!("Failed to load sound {}: {}", name, e),
+            }
+        }
+        library
+    }
+
+    /// Sound names, sorted
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sounds.keys().map(|name| name.as_str())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SfxSound> {
+        self.sounds.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.sounds.contains_key(name)
+    }
+
+    /// Add or replace a sound with its rendered samples
+    pub fn insert(&mut self, name: &str, sound: SfxSound, pcm: impl Into<Arc<[f32]>>) {
+        self.sounds.insert(name.to_string(), sound);
+        self.pcm.insert(name.to_string(), pcm.into());
+    }
+
+    /// Play a sound in the SFX group, panned and with a reverb send
+    pub fn play_placed(&self, name: &str, placement: PcmPlacement, audio: &AudioEngine) -> bool {
+        match self.pcm.get(name) {
+            Some(pcm) => audio.play_pcm_placed(Arc::clone(pcm), placement, MixGroup::Sfx),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sfx::sound::{SfxSource, Waveform};
+
+    #[test]
+    fn test_sound_file_roundtrip() {
+        let sound = SfxSound { source: SfxSource::Wave(Waveform::Noise), sweep: 20, reverb: 3, ..Default::default() };
+        let contents = ron::ser::to_string_pretty(&sound, ron::ser::PrettyConfig::new()).unwrap();
+        assert_eq!(load_sfx_from_str(&contents).unwrap(), sound);
+        assert_eq!(sfx_path("step"), "assets/userdata/sfx/step.sfx");
+    }
+
+    #[test]
+    fn test_library_lookup() {
+        let mut library = SfxLibrary::default();
+        library.insert("hit", SfxSound::default(), vec![0.5f32; 4]);
+        library.insert("coin", SfxSound { note: 72, ..Default::default() }, vec![0.25f32; 2]);
+        assert_eq!(library.names().collect::<Vec<_>>(), vec!["coin", "hit"]);
+        assert_eq!(library.get("coin").map(|sound| sound.note), Some(72));
+        assert_eq!(library.pcm.get("hit").map(|pcm| pcm.len()), Some(4));
+        assert!(!library.contains("step"));
+    }
+}
@@ -0,0 +1,19 @@
+//! Sound Effect Editor
+//!
+//! Authors the short one-shot sounds the game plays: footsteps, hits,
+//! pickups, doors. A sound is a synthesized waveform or a soundfont
+//! instrument note with an ADSR envelope, a pitch sweep and PS1 reverb,
+//! previewed through the same audio engine as the tracker.
+//!
+//! Sounds are saved as `.sfx` files in `assets/userdata/sfx`. Level scripts
+//! and the level's sound bindings refer to them by name; the library renders
+//! them once so the game only has to mix the samples.
+
+mod sound;
+mod library;
+mod state;
+mod layout;
+
+pub use library::SfxLibrary;
+pub use state::SfxEditorState;
+pub use layout::draw_sfx_editor;
@@ -0,0 +1,310 @@
+//! Sound effect definition and synthesis
+//!
+//! A sound effect is one short note: a simple waveform or a soundfont
+//! instrument, shaped by an ADSR envelope, swept in pitch over its length and
+//! optionally sent through the PS1 reverb. Parameters are 0-127 like the
+//! tracker's knobs. Sounds are rendered to mono PCM once (when edited, saved
+//! or loaded), so playing one in game only mixes samples.
+
+use serde::{Deserialize, Serialize};
+
+use crate::tracker::{AudioEngine, PsxReverb, ReverbType, SAMPLE_RATE};
+
+/// Longest attack, decay, release or hold a knob can set, in seconds
+pub const MAX_STAGE_SECONDS: f32 = 2.0;
+/// Pitch sweep at either end of the knob, in semitones
+pub const SWEEP_SEMITONES: f32 = 24.0;
+/// Extra time rendered after the note for the reverb tail, in seconds
+pub const REVERB_TAIL: f32 = 1.5;
+/// Peak level of the synthesized waveforms (leaves headroom when sounds overlap)
+const WAVE_LEVEL: f32 = 0.5;
+/// The noise generator changes value this many times per oscillator cycle
+const NOISE_CLOCK: f32 = 16.0;
+/// Reverb tail below this level is trimmed
+const SILENCE: f32 = 1.0e-4;
+
+/// Synthesized oscillator shape
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Waveform {
+    #[default]
+    Square,
+    Saw,
+    Triangle,
+    Sine,
+    /// SPU-style noise, its pitch sets the clock rate
+    Noise,
+}
+
+impl Waveform {
+    pub const ALL: [Waveform; 5] = [Waveform::Square, Waveform::Saw, Waveform::Triangle, Waveform::Sine, Waveform::Noise];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Waveform::Square => "Square",
+            Waveform::Saw => "Saw",
+            Waveform::Triangle => "Triangle",
+            Waveform::Sine => "Sine",
+            Waveform::Noise => "Noise",
+        }
+    }
+
+    /// One sample at `phase` (0..1) for the pitched shapes
+    fn sample(&self, phase: f32) -> f32 {
+        match self {
+            Waveform::Square => if phase < 0.5 { 1.0 } else { -1.0 },
+            Waveform::Saw => 2.0 * phase - 1.0,
+            Waveform::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+            Waveform::Sine => (phase * std::f32::consts::TAU).sin(),
+            Waveform::Noise => 0.0,
+        }
+    }
+}
+
+/// What the sound is made from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SfxSource {
+    /// A synthesized waveform
+    Wave(Waveform),
+    /// A note of a General MIDI program from the loaded soundfont
+    Instrument(u8),
+}
+
+impl Default for SfxSource {
+    fn default() -> Self {
+        SfxSource::Wave(Waveform::default())
+    }
+}
+
+/// A sound effect asset (`.sfx`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SfxSound {
+    pub source: SfxSource,
+    /// MIDI note the sound starts at (60 = middle C)
+    pub note: u8,
+    /// Pitch change over the sound: 64 = none, 0 / 127 = two octaves down / up
+    pub sweep: u8,
+    pub attack: u8,
+    pub decay: u8,
+    /// Level held after the decay (127 = full)
+    pub sustain: u8,
+    pub release: u8,
+    /// How long the note is held before it is released
+    pub length: u8,
+    pub volume: u8,
+    /// PS1 reverb preset (`ReverbType` index, 0 = off)
+    pub reverb: u8,
+    pub reverb_wet: u8,
+}
+
+impl Default for SfxSound {
+    fn default() -> Self {
+        Self {
+            source: SfxSource::default(),
+            note: 60,
+            sweep: 64,
+            attack: 0,
+            decay: 40,
+            sustain: 0,
+            release: 20,
+            length: 30,
+            volume: 100,
+            reverb: 0,
+            reverb_wet: 48,
+        }
+    }
+}
+
+/// Knob value (0-127) to seconds, squared so short times get most of the range
+pub fn knob_seconds(value: u8) -> f32 {
+    let n = value.min(127) as f32 / 127.0;
+    n * n * MAX_STAGE_SECONDS
+}
+
+/// Frequency of a MIDI note in Hz
+fn note_frequency(note: u8) -> f32 {
+    440.0 * 2f32.powf((note as f32 - 69.0) / 12.0)
+}
+
+/// 15-bit LFSR like the SPU's noise generator (deterministic, so renders repeat)
+struct Noise(u16);
+
+impl Noise {
+    fn step(&mut self) -> f32 {
+        let bit = (self.0 ^ (self.0 >> 1)) & 1;
+        self.0 = (self.0 >> 1) | (bit << 14);
+        if self.0 & 1 == 0 { 1.0 } else { -1.0 }
+    }
+}
+
+impl SfxSound {
+    /// Seconds the note is held, then released
+    pub fn hold_seconds(&self) -> f32 {
+        knob_seconds(self.length)
+    }
+
+    pub fn release_seconds(&self) -> f32 {
+        knob_seconds(self.release)
+    }
+
+    pub fn reverb_type(&self) -> ReverbType {
+        ReverbType::from_index(self.reverb)
+    }
+
+    /// Sweep in semitones over the whole sound
+    fn sweep_semitones(&self) -> f32 {
+        (self.sweep.min(127) as f32 - 64.0) / 64.0 * SWEEP_SEMITONES
+    }
+
+    /// Envelope level `t` seconds into the sound
+    pub fn envelope(&self, t: f32) -> f32 {
+        let (attack, decay) = (knob_seconds(self.attack), knob_seconds(self.decay));
+        let sustain = self.sustain.min(127) as f32 / 127.0;
+        let held = |t: f32| {
+            if t < attack {
+                t / attack
+            } else if t < attack + decay {
+                1.0 - (1.0 - sustain) * (t - attack) / decay
+            } else {
+                sustain
+            }
+        };
+        let hold = self.hold_seconds();
+        if t < hold {
+            return held(t);
+        }
+        let release = self.release_seconds();
+        if release <= 0.0 {
+            return 0.0;
+        }
+        held(hold) * (1.0 - (t - hold) / release).max(0.0)
+    }
+
+    /// Render with instrument notes taken from the engine's soundfont
+    pub fn render_with(&self, audio: &AudioEngine) -> Vec<f32> {
+        let SfxSource::Instrument(program) = self.source else {
+            return self.render(None);
+        };
+        // A rising sweep reads the note faster, so render enough of it
+        let stretch = 2f32.powf(self.sweep_semitones().max(0.0) / 12.0);
+        let hold = self.hold_seconds();
+        let total = (hold + self.release_seconds()) * stretch;
+        let note = audio.render_instrument(program, self.note.min(127), hold, total - hold);
+        self.render(note.as_deref())
+    }
+
+    /// Render to mono PCM at `SAMPLE_RATE`. Instrument sounds need the note
+    /// rendered by the soundfont (`AudioEngine::render_instrument`) as
+    /// `instrument`; without it they render silent.
+    pub fn render(&self, instrument: Option<&[f32]>) -> Vec<f32> {
+        let rate = SAMPLE_RATE as f32;
+        let len = (((self.hold_seconds() + self.release_seconds()) * rate).ceil() as usize).max(1);
+        let gain = self.volume.min(127) as f32 / 127.0;
+        let frequency = note_frequency(self.note.min(127));
+        let sweep = self.sweep_semitones();
+
+        let mut out = Vec::with_capacity(len);
+        let mut phase = 0.0f32;
+        let mut noise = Noise(1);
+        let mut noise_value = noise.step();
+        let mut read = 0.0f32;
+        for i in 0..len {
+            let ratio = 2f32.powf(sweep * (i as f32 / len as f32) / 12.0);
+            let sample = match self.source {
+                SfxSource::Wave(Waveform::Noise) => {
+                    phase += frequency * ratio * NOISE_CLOCK / rate;
+                    if phase >= 1.0 {
+                        phase = phase.fract();
+                        noise_value = noise.step();
+                    }
+                    noise_value * WAVE_LEVEL
+                }
+                SfxSource::Wave(wave) => {
+                    let sample = wave.sample(phase) * WAVE_LEVEL;
+                    phase = (phase + frequency * ratio / rate).fract();
+                    sample
+                }
+                SfxSource::Instrument(_) => {
+                    // The soundfont rendered the start note; the sweep resamples it
+                    let pcm = instrument.unwrap_or_default();
+                    let index = read as usize;
+                    let frac = read - index as f32;
+                    let a = pcm.get(index).copied().unwrap_or(0.0);
+                    let b = pcm.get(index + 1).copied().unwrap_or(0.0);
+                    read += ratio;
+                    a + (b - a) * frac
+                }
+            };
+            out.push(sample * self.envelope(i as f32 / rate) * gain);
+        }
+
+        let reverb_type = self.reverb_type();
+        if reverb_type != ReverbType::Off && self.reverb_wet > 0 {
+            out.resize(len + (REVERB_TAIL * rate) as usize, 0.0);
+            let mut left = out.clone();
+            let mut right = out;
+            let mut reverb = PsxReverb::new(SAMPLE_RATE);
+            reverb.set_preset(reverb_type);
+            reverb.set_wet_level(self.reverb_wet.min(127) as f32 / 127.0);
+            reverb.process(&mut left, &mut right);
+            out = left.iter().zip(&right).map(|(l, r)| (l + r) * 0.5).collect();
+            let end = out.iter().rposition(|s| s.abs() > SILENCE).map_or(len, |i| (i + 1).max(len));
+            out.truncate(end);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_stages() {
+        let sound = SfxSound { attack: 40, decay: 40, sustain: 64, length: 100, release: 40, ..Default::default() };
+        let attack = knob_seconds(40);
+        assert!((sound.envelope(attack * 0.5) - 0.5).abs() < 1e-3);
+        assert!((sound.envelope(attack) - 1.0).abs() < 1e-3);
+        assert!((sound.envelope(attack * 2.5) - 64.0 / 127.0).abs() < 1e-3);
+        // Released: fades from the sustain level to silence
+        let hold = sound.hold_seconds();
+        assert!(sound.envelope(hold + knob_seconds(40) * 0.5) < 0.3);
+        assert_eq!(sound.envelope(hold + knob_seconds(40) + 0.01), 0.0);
+    }
+
+    #[test]
+    fn test_render_length_and_level() {
+        let sound = SfxSound { sustain: 127, ..Default::default() };
+        let pcm = sound.render(None);
+        let expected = ((sound.hold_seconds() + sound.release_seconds()) * SAMPLE_RATE as f32).ceil() as usize;
+        assert_eq!(pcm.len(), expected);
+        assert!(pcm.iter().all(|s| s.abs() <= WAVE_LEVEL + 1e-6));
+        assert!(pcm.iter().any(|s| s.abs() > 0.1));
+        // Same parameters, same samples (noise included)
+        let noise = SfxSound { source: SfxSource::Wave(Waveform::Noise), ..sound.clone() };
+        assert_eq!(noise.render(None), noise.render(None));
+    }
+
+    #[test]
+    fn test_sweep_raises_pitch() {
+        let crossings = |pcm: &[f32]| pcm.windows(2).filter(|w| w[0] <= 0.0 && w[1] > 0.0).count();
+        let flat = SfxSound { source: SfxSource::Wave(Waveform::Sine), sustain: 127, ..Default::default() };
+        let rising = SfxSound { sweep: 127, ..flat.clone() };
+        assert!(crossings(&rising.render(None)) > crossings(&flat.render(None)) * 3 / 2);
+    }
+
+    #[test]
+    fn test_reverb_adds_tail() {
+        let dry = SfxSound::default();
+        let wet = SfxSound { reverb: ReverbType::Hall.to_index(), reverb_wet: 100, ..dry.clone() };
+        assert!(wet.render(None).len() > dry.render(None).len());
+    }
+
+    #[test]
+    fn test_instrument_resamples_source() {
+        let source: Vec<f32> = (0..SAMPLE_RATE).map(|i| i as f32 / SAMPLE_RATE as f32).collect();
+        let sound = SfxSound { source: SfxSource::Instrument(0), attack: 0, sustain: 127, volume: 127, ..Default::default() };
+        let pcm = sound.render(Some(&source));
+        assert!((pcm[100] - source[100]).abs() < 1e-4);
+        assert!(SfxSound { source: SfxSource::Instrument(0), ..Default::default() }.render(None).iter().all(|&s| s == 0.0));
+    }
+}
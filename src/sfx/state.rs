@@ -0,0 +1,163 @@
+//! SFX editor state
+
+use std::sync::Arc;
+
+use crate::storage::Storage;
+use crate::tracker::{AudioEngine, MixGroup};
+use crate::ui::TextInputState;
+use super::library::{save_sfx, sfx_path, SfxLibrary};
+use super::sound::SfxSound;
+
+/// Name for a sound that hasn't been named yet
+const DEFAULT_NAME: &str = "sound";
+
+/// SFX editor state
+pub struct SfxEditorState {
+    /// The sound being edited
+    pub sound: SfxSound,
+    /// Name it is saved under (the file name game events refer to)
+    pub name: String,
+    /// Library entry this sound was opened from (None = not saved yet)
+    pub current: Option<String>,
+    /// Unsaved changes
+    pub dirty: bool,
+    /// Saved sounds, rendered for the game
+    pub library: SfxLibrary,
+    /// Rename field while the name is being edited
+    pub name_edit: Option<TextInputState>,
+    /// Status message and its expiry time
+    pub status_message: Option<(String, f64)>,
+    /// `sound` rendered for preview and the waveform view (None = out of date)
+    preview: Option<Arc<[f32]>>,
+}
+
+impl SfxEditorState {
+    pub fn new(library: SfxLibrary) -> Self {
+        Self {
+            sound: SfxSound::default(),
+            name: next_free_name(&library),
+            current: None,
+            dirty: false,
+            library,
+            name_edit: None,
+            status_message: None,
+            preview: None,
+        }
+    }
+
+    pub fn set_status(&mut self, message: &str, duration: f64) {
+        let expiry = macroquad::time::get_time() + duration;
+        self.status_message = Some((message.to_string(), expiry));
+    }
+
+    /// Current status message if not expired
+    pub fn get_status(&self) -> Option<&str> {
+        match &self.status_message {
+            Some((msg, expiry)) if macroquad::time::get_time() < *expiry => Some(msg),
+            _ => None,
+        }
+    }
+
+    /// Change the sound (marks it dirty and re-renders the preview)
+    pub fn edit(&mut self, change: impl FnOnce(&mut SfxSound)) {
+        let before = self.sound.clone();
+        change(&mut self.sound);
+        if self.sound != before {
+            self.dirty = true;
+            self.preview = None;
+        }
+    }
+
+    /// The rendered sound, rendering it if it changed
+    pub fn preview(&mut self, audio: &AudioEngine) -> Arc<[f32]> {
+        let sound = &self.sound;
+        Arc::clone(self.preview.get_or_insert_with(|| sound.render_with(audio).into()))
+    }
+
+    /// Play the sound being edited
+    pub fn play_preview(&mut self, audio: &AudioEngine) {
+        let pcm = self.preview(audio);
+        audio.play_pcm(pcm, 1.0, MixGroup::Sfx);
+    }
+
+    /// Start a new sound
+    pub fn new_sound(&mut self) {
+        self.sound = SfxSound::default();
+        self.name = next_free_name(&self.library);
+        self.current = None;
+        self.dirty = false;
+        self.preview = None;
+        self.name_edit = None;
+    }
+
+    /// Edit a sound from the library
+    pub fn open(&mut self, name: &str) {
+        let Some(sound) = self.library.get(name) else {
+            return;
+        };
+        self.sound = sound.clone();
+        self.name = name.to_string();
+        self.current = Some(name.to_string());
+        self.dirty = false;
+        self.preview = None;
+        self.name_edit = None;
+    }
+
+    /// Save under the current name and update the library
+    pub fn save(&mut self, storage: &Storage, audio: &AudioEngine) -> Result<(), String> {
+        let name = self.name.trim().to_string();
+        if !is_valid_name(&name) {
+            return Err(format!("'{}' isn't a valid sound name", name));
+        }
+        if !storage.can_write() {
+            return Err("Storage is read-only".to_string());
+        }
+        save_sfx(&self.sound, &sfx_path(&name), storage)?;
+        let pcm = self.preview(audio);
+        self.library.insert(&name, self.sound.clone(), pcm);
+        self.name = name.clone();
+        self.current = Some(name.clone());
+        self.dirty = false;
+        self.set_status(&format!("Saved {}", name), 2.0);
+        Ok(())
+    }
+}
+
+/// Sound names become file names: letters, digits, `_` and `-` only
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// First of sound, sound_2, sound_3... not in the library
+fn next_free_name(library: &SfxLibrary) -> String {
+    (1..)
+        .map(|i| if i == 1 { DEFAULT_NAME.to_string() } else { format!("{}_{}", DEFAULT_NAME, i) })
+        .find(|name| !library.contains(name))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert!(is_valid_name("door_open-2"));
+        assert!(!is_valid_name("") && !is_valid_name("../x") && !is_valid_name("a b"));
+
+        let mut library = SfxLibrary::default();
+        assert_eq!(next_free_name(&library), "sound");
+        library.insert("sound", SfxSound::default(), Vec::<f32>::new());
+        library.insert("sound_2", SfxSound::default(), Vec::<f32>::new());
+        assert_eq!(next_free_name(&library), "sound_3");
+    }
+
+    #[test]
+    fn test_edit_marks_dirty_only_on_change() {
+        let mut state = SfxEditorState::new(SfxLibrary::default());
+        state.edit(|sound| sound.note = 60);
+        assert!(!state.dirty);
+        state.edit(|sound| sound.note = 72);
+        assert!(state.dirty);
+    }
+}
@@ -163,6 +163,7 @@ extern "C" {
         path_len: usize,
         data_ptr: *const u8,
         data_len: usize,
+        if_revision: f64,
     ) -> i32;
     fn b32_gcp_storage_read(path_ptr: *const u8, path_len: usize) -> i32;
     fn b32_gcp_storage_list(path_ptr: *const u8, path_len: usize) -> i32;
@@ -171,6 +172,7 @@ extern "C" {
     fn b32_gcp_storage_copy_result(op_id: i32, dest_ptr: *mut u8, max_len: usize) -> usize;
     fn b32_gcp_storage_get_error_len(op_id: i32) -> usize;
     fn b32_gcp_storage_copy_error(op_id: i32, dest_ptr: *mut u8, max_len: usize) -> usize;
+    fn b32_gcp_storage_copy_version(op_id: i32, dest_ptr: *mut u8, max_len: usize) -> usize;
     fn b32_gcp_storage_free(op_id: i32);
 }
 
@@ -187,6 +189,8 @@ const POLL_ERROR: i32 = 2;
 pub struct AsyncOp<T> {
     op_id: Option<i32>,
     result: Option<AsyncResult<T>>,
+    /// File whose revision base is updated when a read/write finishes
+    path: Option<String>,
     _marker: std::marker::PhantomData<T>,
 }
 
@@ -196,18 +200,39 @@ impl<T> AsyncOp<T> {
         Self {
             op_id: Some(op_id),
             result: None,
+            path: None,
             _marker: std::marker::PhantomData,
         }
     }
 
+    /// Operation on a file: its revision base follows the result
+    fn for_file(op_id: i32, path: &str) -> Self {
+        Self {
+            path: Some(path.to_string()),
+            ..Self::new(op_id)
+        }
+    }
+
     fn with_result(result: AsyncResult<T>) -> Self {
         Self {
             op_id: None,
             result: Some(result),
+            path: None,
             _marker: std::marker::PhantomData,
         }
     }
 
+    /// Record the revision the finished operation left the file at
+    fn record_version(&self, op_id: i32) {
+        let Some(path) = &self.path else { return };
+        let mut buf = [0u8; 64];
+        let copied = unsafe { b32_gcp_storage_copy_version(op_id, buf.as_mut_ptr(), buf.len()) };
+        match super::gcp::parse_version_line(&String::from_utf8_lossy(&buf[..copied])) {
+            Some(version) => super::versions::record_base(path, version),
+            None => super::versions::forget_base(path),
+        }
+    }
+
     pub fn result(&self) -> Option<&AsyncResult<T>> {
         self.result.as_ref()
     }
@@ -231,6 +256,7 @@ impl AsyncOp<()> {
         match status {
             POLL_PENDING => false,
             POLL_READY => {
+                self.record_version(op_id);
                 self.result = Some(Ok(()));
                 true
             }
@@ -272,6 +298,7 @@ impl AsyncOp<Vec<u8>> {
         match status {
             POLL_PENDING => false,
             POLL_READY => {
+                self.record_version(op_id);
                 let data = get_js_result_bytes(op_id);
                 self.result = Some(Ok(data));
                 true
@@ -356,7 +383,12 @@ fn get_js_error(op_id: i32) -> StorageError {
     let msg = String::from_utf8_lossy(&buf).to_string();
 
     // Parse error message to determine type
-    if msg.contains("401") || msg.contains("403") || msg.contains("Not authenticated") {
+    if msg.starts_with("409") {
+        StorageError::Conflict {
+            revision: super::gcp::parse_json_u64(&msg, "revision").unwrap_or(0),
+            updated_at: super::gcp::parse_json_u64(&msg, "updated_at").unwrap_or(0),
+        }
+    } else if msg.contains("401") || msg.contains("403") || msg.contains("Not authenticated") {
         StorageError::AuthRequired
     } else if msg.contains("404") {
         StorageError::NotFound(msg)
@@ -419,21 +451,23 @@ pub fn save_async(path: PathBuf, data: Vec<u8>) -> PendingSave {
         };
     }
 
-    // Start the JS write operation (non-blocking)
+    // Start the JS write operation (non-blocking), refused if the file moved on
     let path_str = path.to_string_lossy();
+    let if_revision = super::versions::base(&path_str).map_or(-1.0, |v| v.revision as f64);
     let op_id = unsafe {
         b32_gcp_storage_write(
             path_str.as_ptr(),
             path_str.len(),
             data.as_ptr(),
             data.len(),
+            if_revision,
         )
     };
 
     eprintln!("[async_ops] save_async started, op_id={}, path={}", op_id, path_str);
 
     PendingSave {
-        op: AsyncOp::new(op_id),
+        op: AsyncOp::for_file(op_id, &path_str),
         path,
     }
 }
@@ -454,7 +488,7 @@ pub fn load_async(path: PathBuf) -> PendingLoad {
     let op_id = unsafe { b32_gcp_storage_read(path_str.as_ptr(), path_str.len()) };
 
     PendingLoad {
-        op: AsyncOp::new(op_id),
+        op: AsyncOp::for_file(op_id, &path_str),
         path,
     }
 }
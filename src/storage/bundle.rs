@@ -0,0 +1,146 @@
+//! Bundle storage backend
+//!
+//! Serves a mounted `.bpk` asset bundle as a read-only virtual filesystem.
+//! Paths inside the bundle mirror the loose layout (e.g.
+//! `assets/samples/levels/a.ron`), so a mounted bundle can stand in for
+//! files that aren't on disk - including on WASM, where there is no
+//! filesystem at all. All operations complete immediately.
+
+use super::{StorageError, StorageHandle};
+use crate::bundle::Bundle;
+
+/// Bundle mounted at startup when it ships alongside the build
+pub const DEFAULT_BUNDLE: &str = "assets.bpk";
+
+/// Read-only storage backend over a loaded bundle
+#[derive(Debug, Clone)]
+pub struct BundleStorage {
+    /// Where the bundle came from (for logs)
+    name: String,
+    bundle: Bundle,
+}
+
+impl BundleStorage {
+    pub fn new(name: &str, bundle: Bundle) -> Self {
+        Self {
+            name: name.to_string(),
+            bundle,
+        }
+    }
+
+    /// Where the bundle was loaded from
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Does the bundle contain this file?
+    pub fn contains(&self, path: &str) -> bool {
+        self.bundle.contains(normalize(path))
+    }
+
+    /// Does the bundle have any files under this directory?
+    pub fn has_dir(&self, path: &str) -> bool {
+        self.bundle.files_in(normalize(path)).next().is_some()
+    }
+
+    /// List files directly inside a directory
+    ///
+    /// Returns filenames (not full paths), like the local backend.
+    pub fn list(&self, path: &str) -> StorageHandle<Vec<String>> {
+        StorageHandle::ready(self.list_names(path))
+    }
+
+    fn list_names(&self, path: &str) -> Vec<String> {
+        let dir = normalize(path).trim_end_matches('/');
+        self.bundle
+            .files_in(dir)
+            .filter_map(|p| p.get(dir.len() + 1..))
+            .filter(|name| !name.contains('/'))
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    /// Read (decompress) a file
+    pub fn read(&self, path: &str) -> StorageHandle<Vec<u8>> {
+        match self.bundle.read(normalize(path)) {
+            Ok(data) => StorageHandle::ready(data),
+            Err(crate::bundle::BundleError::NotFound(p)) => StorageHandle::error(StorageError::NotFound(p)),
+            Err(e) => StorageHandle::error(StorageError::IoError(e.to_string())),
+        }
+    }
+
+    /// Bundles are read-only
+    pub fn write(&self, path: &str, _data: &[u8]) -> StorageHandle<()> {
+        StorageHandle::error(self.read_only(path))
+    }
+
+    /// Bundles are read-only
+    pub fn delete(&self, path: &str) -> StorageHandle<()> {
+        StorageHandle::error(self.read_only(path))
+    }
+
+    fn read_only(&self, path: &str) -> StorageError {
+        StorageError::PermissionDenied(format!("{} is in read-only bundle {}", path, self.name))
+    }
+}
+
+/// Bundle paths are '/' separated and relative (no leading "./")
+fn normalize(path: &str) -> &str {
+    path.trim_start_matches("./")
+}
+
+/// Load a bundle file (native: filesystem, WASM: fetched next to the page)
+pub async fn load_bundle_file(path: &str) -> Result<Bundle, StorageError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    let bytes = std::fs::read(path).map_err(StorageError::from)?;
+    #[cfg(target_arch = "wasm32")]
+    let bytes = macroquad::prelude::load_file(path)
+        .await
+        .map_err(|e| StorageError::NotFound(format!("{}: {}", path, e)))?;
+    Bundle::from_bytes(bytes).map_err(|e| StorageError::SerdeError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::BundleWriter;
+
+    fn test_bundle() -> BundleStorage {
+        let mut writer = BundleWriter::new();
+        writer.add("assets/samples/levels/a.ron", b"(a)".to_vec());
+        writer.add("assets/samples/levels/b.ron", b"(b)".to_vec());
+        writer.add("assets/samples/levels/old/c.ron", b"(c)".to_vec());
+        BundleStorage::new("test.bpk", Bundle::from_bytes(writer.finish().unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_list_and_read() {
+        let storage = test_bundle();
+
+        let mut files = storage.list("assets/samples/levels").take().unwrap().unwrap();
+        files.sort();
+        assert_eq!(files, vec!["a.ron", "b.ron"]);
+        assert!(storage.has_dir("./assets/samples/levels/old/"));
+        assert!(!storage.has_dir("assets/userdata"));
+
+        assert_eq!(storage.read("./assets/samples/levels/b.ron").take().unwrap().unwrap(), b"(b)");
+        assert!(matches!(
+            storage.read("assets/samples/levels/z.ron").take(),
+            Some(Err(StorageError::NotFound(_)))
+        ));
+    }
+
+    #[test]
+    fn test_read_only() {
+        let storage = test_bundle();
+        assert!(matches!(
+            storage.write("assets/samples/levels/a.ron", b"x").take(),
+            Some(Err(StorageError::PermissionDenied(_)))
+        ));
+        assert!(matches!(
+            storage.delete("assets/samples/levels/a.ron").take(),
+            Some(Err(StorageError::PermissionDenied(_)))
+        ));
+        assert!(storage.contains("assets/samples/levels/a.ron"));
+    }
+}
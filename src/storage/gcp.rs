@@ -2,7 +2,13 @@
 //!
 //! Provides storage operations via Cloud Run API.
 //! Uses a fire-and-poll pattern to integrate with async JavaScript fetch.
+//!
+//! Every upload is kept as a revision. Reads and writes report the file's
+//! `revision`/`updated_at`, which become its base (see `versions`); uploads
+//! send the base as `if_revision` and get a 409 when the file moved on.
+//! `/revisions` lists a file's history and `/get` takes a `revision`.
 
+use super::versions::{self, RemoteVersion};
 use super::{StorageError, StorageHandle};
 
 /// Cloud Run API endpoint (deployed via bonnie-32-infra)
@@ -28,8 +34,14 @@ extern "C" {
         path_len: usize,
         data_ptr: *const u8,
         data_len: usize,
+        if_revision: f64,
     ) -> i32;
     fn b32_gcp_storage_delete(path_ptr: *const u8, path_len: usize) -> i32;
+    fn b32_gcp_storage_revisions(path_ptr: *const u8, path_len: usize) -> i32;
+    fn b32_gcp_storage_read_revision(path_ptr: *const u8, path_len: usize, revision: f64) -> i32;
+    fn b32_gcp_storage_copy_version(op_id: i32, dest_ptr: *mut u8, max_len: usize) -> usize;
+    fn b32_gcp_storage_share(data_ptr: *const u8, data_len: usize) -> i32;
+    fn b32_gcp_storage_read_shared(token_ptr: *const u8, token_len: usize) -> i32;
     fn b32_gcp_storage_poll(op_id: i32) -> i32;
     fn b32_gcp_storage_get_result_len(op_id: i32) -> usize;
     fn b32_gcp_storage_copy_result(op_id: i32, dest_ptr: *mut u8, max_len: usize) -> usize;
@@ -97,6 +109,11 @@ impl GcpStorage {
     pub fn can_write(&self) -> bool {
         self.quota_used < USER_QUOTA
     }
+
+    /// Public URL of shared data (what "Share" hands out)
+    pub fn shared_url(token: &str) -> String {
+        format!("{}/shared/{}", CLOUD_RUN_URL, percent_encode(token))
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -175,7 +192,12 @@ impl GcpStorage {
         let msg = String::from_utf8_lossy(&buf).to_string();
 
         // Parse error message to determine type
-        if msg.contains("401") || msg.contains("403") || msg.contains("Not authenticated") {
+        if msg.starts_with("409") {
+            StorageError::Conflict {
+                revision: parse_json_u64(&msg, "revision").unwrap_or(0),
+                updated_at: parse_json_u64(&msg, "updated_at").unwrap_or(0),
+            }
+        } else if msg.contains("401") || msg.contains("403") || msg.contains("Not authenticated") {
             StorageError::AuthRequired
         } else if msg.contains("404") {
             StorageError::NotFound(msg)
@@ -205,6 +227,13 @@ impl GcpStorage {
         String::from_utf8_lossy(&bytes).to_string()
     }
 
+    /// Revision a finished read/write left the file at, if the server sent one
+    fn get_version(&self, op_id: i32) -> Option<RemoteVersion> {
+        let mut buf = [0u8; 64];
+        let copied = unsafe { b32_gcp_storage_copy_version(op_id, buf.as_mut_ptr(), buf.len()) };
+        parse_version_line(&String::from_utf8_lossy(&buf[..copied]))
+    }
+
     /// Get user's current quota usage
     pub fn get_quota(&self) -> Option<QuotaInfo> {
         let op_id = unsafe { b32_gcp_storage_get_quota() };
@@ -262,7 +291,12 @@ impl GcpStorage {
     pub fn read(&self, path: &str) -> StorageHandle<Vec<u8>> {
         let op_id = unsafe { b32_gcp_storage_read(path.as_ptr(), path.len()) };
 
-        self.wait_for_operation(op_id, |id| self.get_result_bytes(id))
+        self.wait_for_operation(op_id, |id| {
+            if let Some(version) = self.get_version(id) {
+                versions::record_base(path, version);
+            }
+            self.get_result_bytes(id)
+        })
     }
 
     /// Write a file
@@ -283,17 +317,39 @@ impl GcpStorage {
             });
         }
 
-        let op_id =
-            unsafe { b32_gcp_storage_write(path.as_ptr(), path.len(), data.as_ptr(), data.len()) };
+        // Refused with a conflict if the file moved on from the revision we have
+        let if_revision = versions::base(path).map_or(-1.0, |v| v.revision as f64);
+        let op_id = unsafe {
+            b32_gcp_storage_write(path.as_ptr(), path.len(), data.as_ptr(), data.len(), if_revision)
+        };
 
-        self.wait_for_operation(op_id, |_| ())
+        self.wait_for_operation(op_id, |id| match self.get_version(id) {
+            Some(version) => versions::record_base(path, version),
+            None => versions::forget_base(path),
+        })
     }
 
     /// Delete a file
     pub fn delete(&self, path: &str) -> StorageHandle<()> {
         let op_id = unsafe { b32_gcp_storage_delete(path.as_ptr(), path.len()) };
 
-        self.wait_for_operation(op_id, |_| ())
+        self.wait_for_operation(op_id, |_| versions::forget_base(path))
+    }
+
+    /// Revision history of a file, newest first
+    pub fn revisions(&self, path: &str) -> StorageHandle<Vec<RemoteVersion>> {
+        let op_id = unsafe { b32_gcp_storage_revisions(path.as_ptr(), path.len()) };
+
+        self.wait_for_operation(op_id, |id| {
+            self.get_result_string(id).lines().filter_map(parse_version_line).collect()
+        })
+    }
+
+    /// Read an older revision of a file
+    pub fn read_revision(&self, path: &str, revision: u64) -> StorageHandle<Vec<u8>> {
+        let op_id = unsafe { b32_gcp_storage_read_revision(path.as_ptr(), path.len(), revision as f64) };
+
+        self.wait_for_operation(op_id, |id| self.get_result_bytes(id))
     }
 
     /// Check if a file exists
@@ -333,6 +389,28 @@ impl GcpStorage {
         unsafe { b32_gcp_storage_free(op_id) };
         StorageHandle::error(StorageError::NetworkError("Operation timed out".into()))
     }
+
+    /// Publish data under a new public token (counts against the quota)
+    pub fn share(&self, data: &[u8]) -> StorageHandle<String> {
+        if data.len() as u64 > MAX_FILE_SIZE {
+            return StorageHandle::error(StorageError::FileTooLarge {
+                size: data.len() as u64,
+                max: MAX_FILE_SIZE,
+            });
+        }
+
+        let op_id = unsafe { b32_gcp_storage_share(data.as_ptr(), data.len()) };
+
+        self.wait_for_operation(op_id, |id| self.get_result_string(id))
+    }
+
+    /// Read shared data by token (no sign-in needed)
+    pub fn read_shared(token: &str) -> StorageHandle<Vec<u8>> {
+        let storage = Self::new();
+        let op_id = unsafe { b32_gcp_storage_read_shared(token.as_ptr(), token.len()) };
+
+        storage.wait_for_operation(op_id, |id| storage.get_result_bytes(id))
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -409,6 +487,14 @@ impl GcpStorage {
                 StorageError::AuthRequired
             }
             ureq::Error::Status(404, _) => StorageError::NotFound("File not found".into()),
+            ureq::Error::Status(409, response) => {
+                // Response format: {"success": false, "data": {"revision": N, "updated_at": N}}
+                let json: serde_json::Value = response.into_json().unwrap_or_default();
+                StorageError::Conflict {
+                    revision: json["data"]["revision"].as_u64().unwrap_or(0),
+                    updated_at: json["data"]["updated_at"].as_u64().unwrap_or(0),
+                }
+            }
             ureq::Error::Status(429, _) => StorageError::RateLimited,
             ureq::Error::Status(code, response) => {
                 let body = response.into_string().unwrap_or_default();
@@ -455,30 +541,41 @@ impl GcpStorage {
     pub fn read(&self, path: &str) -> StorageHandle<Vec<u8>> {
         let endpoint = format!("/get?path={}", urlencoding::encode(path));
         match self.get_request(&endpoint) {
-            Ok(response) => match response.into_json::<serde_json::Value>() {
-                Ok(json) => {
-                    // Response format: {"success": true, "data": {"path": "...", "content": "base64...", "size": N}}
-                    if let Some(content) = json["data"]["content"].as_str() {
-                        // Decode base64 content
-                        match base64::Engine::decode(
-                            &base64::engine::general_purpose::STANDARD,
-                            content,
-                        ) {
-                            Ok(bytes) => StorageHandle::ready(bytes),
-                            Err(e) => StorageHandle::error(StorageError::Other(format!(
-                                "Base64 decode error: {}",
-                                e
-                            ))),
+            Ok(response) => Self::content_bytes(response, Some(path)),
+            Err(e) => StorageHandle::error(e),
+        }
+    }
+
+    /// Decode the file content of a `/get` or `/shared` response. The
+    /// revision it reports becomes the base of `base_path`, if given.
+    fn content_bytes(response: ureq::Response, base_path: Option<&str>) -> StorageHandle<Vec<u8>> {
+        match response.into_json::<serde_json::Value>() {
+            Ok(json) => {
+                // Response format: {"success": true, "data": {"path": "...", "content": "base64...", "size": N, "revision": N, "updated_at": N}}
+                if let Some(content) = json["data"]["content"].as_str() {
+                    // Decode base64 content
+                    match base64::Engine::decode(
+                        &base64::engine::general_purpose::STANDARD,
+                        content,
+                    ) {
+                        Ok(bytes) => {
+                            if let (Some(path), Some(version)) = (base_path, json_version(&json["data"], bytes.len() as u64)) {
+                                versions::record_base(path, version);
+                            }
+                            StorageHandle::ready(bytes)
                         }
-                    } else {
-                        StorageHandle::error(StorageError::Other("No content in response".into()))
+                        Err(e) => StorageHandle::error(StorageError::Other(format!(
+                            "Base64 decode error: {}",
+                            e
+                        ))),
                     }
+                } else {
+                    StorageHandle::error(StorageError::Other("No content in response".into()))
                 }
-                Err(e) => {
-                    StorageHandle::error(StorageError::Other(format!("JSON parse error: {}", e)))
-                }
-            },
-            Err(e) => StorageHandle::error(e),
+            }
+            Err(e) => {
+                StorageHandle::error(StorageError::Other(format!("JSON parse error: {}", e)))
+            }
         }
     }
 
@@ -495,13 +592,25 @@ impl GcpStorage {
         // Encode data as base64
         let content = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, data);
 
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "path": path,
             "content": content
         });
+        // Refused with a conflict if the file moved on from the revision we have
+        if let Some(base) = versions::base(path) {
+            body["if_revision"] = base.revision.into();
+        }
 
         match self.post_request("/upload", &body) {
-            Ok(_) => StorageHandle::ready(()),
+            Ok(response) => {
+                // Response format: {"success": true, "data": {"revision": N, "updated_at": N}}
+                let json: serde_json::Value = response.into_json().unwrap_or_default();
+                match json_version(&json["data"], data.len() as u64) {
+                    Some(version) => versions::record_base(path, version),
+                    None => versions::forget_base(path),
+                }
+                StorageHandle::ready(())
+            }
             Err(e) => StorageHandle::error(e),
         }
     }
@@ -513,7 +622,10 @@ impl GcpStorage {
         });
 
         match self.post_request("/delete", &body) {
-            Ok(_) => StorageHandle::ready(()),
+            Ok(_) => {
+                versions::forget_base(path);
+                StorageHandle::ready(())
+            }
             Err(e) => StorageHandle::error(e),
         }
     }
@@ -527,6 +639,77 @@ impl GcpStorage {
             Err(e) => StorageHandle::error(e),
         }
     }
+
+    /// Revision history of a file, newest first
+    pub fn revisions(&self, path: &str) -> StorageHandle<Vec<RemoteVersion>> {
+        let endpoint = format!("/revisions?path={}", urlencoding::encode(path));
+        match self.get_request(&endpoint) {
+            Ok(response) => match response.into_json::<serde_json::Value>() {
+                Ok(json) => {
+                    // Response format: {"success": true, "data": {"revisions": [{"revision": N, "updated_at": N, "size": N}]}}
+                    let revisions = json["data"]["revisions"]
+                        .as_array()
+                        .map(|arr| {
+                            arr.iter()
+                                .filter_map(|v| json_version(v, v["size"].as_u64().unwrap_or(0)))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    StorageHandle::ready(revisions)
+                }
+                Err(e) => {
+                    StorageHandle::error(StorageError::Other(format!("JSON parse error: {}", e)))
+                }
+            },
+            Err(e) => StorageHandle::error(e),
+        }
+    }
+
+    /// Read an older revision of a file
+    pub fn read_revision(&self, path: &str, revision: u64) -> StorageHandle<Vec<u8>> {
+        let endpoint = format!("/get?path={}&revision={}", urlencoding::encode(path), revision);
+        match self.get_request(&endpoint) {
+            Ok(response) => Self::content_bytes(response, None),
+            Err(e) => StorageHandle::error(e),
+        }
+    }
+
+    /// Publish data under a new public token (counts against the quota)
+    pub fn share(&self, data: &[u8]) -> StorageHandle<String> {
+        if data.len() as u64 > MAX_FILE_SIZE {
+            return StorageHandle::error(StorageError::FileTooLarge {
+                size: data.len() as u64,
+                max: MAX_FILE_SIZE,
+            });
+        }
+
+        let content = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, data);
+        let body = serde_json::json!({
+            "content": content
+        });
+
+        match self.post_request("/share", &body) {
+            Ok(response) => match response.into_json::<serde_json::Value>() {
+                // Response format: {"success": true, "data": {"token": "..."}}
+                Ok(json) => match json["data"]["token"].as_str() {
+                    Some(token) => StorageHandle::ready(token.to_string()),
+                    None => StorageHandle::error(StorageError::Other("No token in response".into())),
+                },
+                Err(e) => {
+                    StorageHandle::error(StorageError::Other(format!("JSON parse error: {}", e)))
+                }
+            },
+            Err(e) => StorageHandle::error(e),
+        }
+    }
+
+    /// Read shared data by token (no sign-in needed)
+    pub fn read_shared(token: &str) -> StorageHandle<Vec<u8>> {
+        match ureq::get(&Self::shared_url(token)).call() {
+            Ok(response) => Self::content_bytes(response, None),
+            Err(e) => StorageHandle::error(Self::convert_error(e)),
+        }
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -566,9 +749,43 @@ impl QuotaInfo {
 // Helpers
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// Revision fields of a response's `data` (native)
+#[cfg(not(target_arch = "wasm32"))]
+fn json_version(data: &serde_json::Value, size: u64) -> Option<RemoteVersion> {
+    Some(RemoteVersion {
+        revision: data["revision"].as_u64()?,
+        updated_at: data["updated_at"].as_u64().unwrap_or(0),
+        size,
+    })
+}
+
+/// Parse a "revision updated_at size" line (how JavaScript hands versions over)
+#[cfg(target_arch = "wasm32")]
+pub(super) fn parse_version_line(line: &str) -> Option<RemoteVersion> {
+    let mut fields = line.split_whitespace().map(|f| f.parse::<u64>().ok());
+    Some(RemoteVersion {
+        revision: fields.next()??,
+        updated_at: fields.next()??,
+        size: fields.next().flatten().unwrap_or(0),
+    })
+}
+
+/// Percent-encode a URL path segment (`urlencoding` is native only)
+fn percent_encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
 /// Simple JSON u64 parser (avoids serde dependency)
 #[cfg(target_arch = "wasm32")]
-fn parse_json_u64(json: &str, key: &str) -> Option<u64> {
+pub(super) fn parse_json_u64(json: &str, key: &str) -> Option<u64> {
     let pattern = format!("\"{}\":", key);
     let start = json.find(&pattern)? + pattern.len();
     let rest = &json[start..];
@@ -576,3 +793,14 @@ fn parse_json_u64(json: &str, key: &str) -> Option<u64> {
     let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
     rest[..end].parse().ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_url_encodes_token() {
+        assert_eq!(GcpStorage::shared_url("Ab3-x_9"), format!("{}/shared/Ab3-x_9", CLOUD_RUN_URL));
+        assert_eq!(GcpStorage::shared_url("a/../b c"), format!("{}/shared/a%2F..%2Fb%20c", CLOUD_RUN_URL));
+    }
+}
@@ -3,19 +3,31 @@
 //! Provides a unified interface for storage operations with path-based routing:
 //! - `assets/userdata/*` → Cloud storage (when authenticated) or error (WASM) or local (native)
 //! - Everything else → Local filesystem
+//! - Files inside a mounted `.bpk` bundle → the bundle (read-only), ahead of both
+//!
+//! Shared data sits outside the paths: signed-in users publish it under a
+//! token (`Storage::share`) and anyone can read it back (`Storage::read_shared`).
 //!
 //! Uses a fire-and-poll async pattern that works with macroquad's single-threaded model.
 //! Native cloud operations can run in background threads to avoid blocking the UI.
+//!
+//! Cloud files keep a revision history; uploads are refused with
+//! `StorageError::Conflict` when the cloud copy changed since it was read
+//! (see `versions`).
 
 pub mod async_ops;
+pub mod bundle;
 pub mod gcp;
 pub mod local;
+pub mod versions;
 
 pub use async_ops::{save_async, load_async, list_async, PendingSave, PendingLoad, PendingList};
 
+use bundle::BundleStorage;
 use gcp::GcpStorage;
 use local::LocalStorage;
 use std::fmt;
+use versions::RemoteVersion;
 
 /// Path prefix for user-created content that should sync to cloud
 const USERDATA_PREFIX: &str = "assets/userdata/";
@@ -75,6 +87,8 @@ pub enum StorageError {
     FileTooLarge { size: u64, max: u64 },
     /// Rate limited
     RateLimited,
+    /// The cloud copy changed since it was read (cloud storage only)
+    Conflict { revision: u64, updated_at: u64 },
     /// Serialization/deserialization error
     SerdeError(String),
     /// Other error
@@ -96,6 +110,9 @@ impl fmt::Display for StorageError {
                 write!(f, "file too large: {} bytes (max: {})", size, max)
             }
             StorageError::RateLimited => write!(f, "rate limited, try again later"),
+            StorageError::Conflict { revision, .. } => {
+                write!(f, "the cloud copy changed (revision {})", revision)
+            }
             StorageError::SerdeError(msg) => write!(f, "serialization error: {}", msg),
             StorageError::Other(msg) => write!(f, "{}", msg),
         }
@@ -208,10 +225,14 @@ impl<T: Clone> StorageHandle<T> {
 /// Routes storage operations based on path prefix:
 /// - `assets/userdata/*` → Cloud (when authenticated) or Local (native) or Error (WASM)
 /// - Everything else → Always Local
+///
+/// Mounted bundles overlay both: a path found in a bundle is served from it
+/// (the most recently mounted bundle wins) and can't be written or deleted.
 #[derive(Debug)]
 pub struct Storage {
     local: LocalStorage,
     cloud: Option<GcpStorage>,
+    mounts: Vec<BundleStorage>,
 }
 
 impl Storage {
@@ -223,6 +244,7 @@ impl Storage {
         Self {
             local: LocalStorage::new(),
             cloud: None,
+            mounts: Vec::new(),
         }
     }
 
@@ -277,12 +299,62 @@ impl Storage {
         }
     }
 
+    // ─────────────────────────────────────────────────────────────────────────
+    // Bundle mounts
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// Mount a bundle as a read-only overlay
+    pub fn mount(&mut self, name: &str, bundle: crate::bundle::Bundle) {
+        #[cfg(not(target_arch = "wasm32"))]
+        println!("Storage: Mounted bundle {} ({} files)", name, bundle.paths().count());
+        self.mounts.retain(|m| m.name() != name);
+        self.mounts.push(BundleStorage::new(name, bundle));
+    }
+
+    /// Load and mount a bundle file (native: filesystem, WASM: fetched)
+    pub async fn mount_file(&mut self, path: &str) -> Result<(), StorageError> {
+        let bundle = bundle::load_bundle_file(path).await?;
+        self.mount(path, bundle);
+        Ok(())
+    }
+
+    /// The mounted bundle that serves this file, if any
+    fn mount_for(&self, path: &str) -> Option<&BundleStorage> {
+        self.mounts.iter().rev().find(|m| m.contains(path))
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Path-based routing
     // ─────────────────────────────────────────────────────────────────────────
 
     /// List files in a directory
+    ///
+    /// Files from mounted bundles are merged with the backend's listing.
     pub fn list(&self, path: &str) -> StorageHandle<Vec<String>> {
+        let mounted: Vec<&BundleStorage> = self.mounts.iter().filter(|m| m.has_dir(path)).collect();
+        if mounted.is_empty() {
+            return self.list_backend(path);
+        }
+
+        let mut files = match self.list_backend(path).take() {
+            Some(Ok(files)) => files,
+            // Backend still loading or has nothing here: the bundles are enough
+            _ => Vec::new(),
+        };
+        for mount in mounted {
+            if let Some(Ok(names)) = mount.list(path).take() {
+                for name in names {
+                    if !files.iter().any(|f| *f == name || f.ends_with(&format!("/{}", name))) {
+                        files.push(name);
+                    }
+                }
+            }
+        }
+        StorageHandle::ready(files)
+    }
+
+    /// List files from the local/cloud backend only
+    fn list_backend(&self, path: &str) -> StorageHandle<Vec<String>> {
         if Self::is_userdata_path(path) {
             if let Some(cloud) = &self.cloud {
                 return cloud.list(path);
@@ -295,6 +367,9 @@ impl Storage {
 
     /// Read a file
     pub fn read(&self, path: &str) -> StorageHandle<Vec<u8>> {
+        if let Some(mount) = self.mount_for(path) {
+            return mount.read(path);
+        }
         if Self::is_userdata_path(path) {
             if let Some(cloud) = &self.cloud {
                 return cloud.read(path);
@@ -307,6 +382,9 @@ impl Storage {
 
     /// Write a file
     pub fn write(&self, path: &str, data: &[u8]) -> StorageHandle<()> {
+        if let Some(mount) = self.mount_for(path) {
+            return mount.write(path, data);
+        }
         if Self::is_userdata_path(path) {
             if let Some(cloud) = &self.cloud {
                 return cloud.write(path, data);
@@ -319,6 +397,9 @@ impl Storage {
 
     /// Delete a file
     pub fn delete(&self, path: &str) -> StorageHandle<()> {
+        if let Some(mount) = self.mount_for(path) {
+            return mount.delete(path);
+        }
         if Self::is_userdata_path(path) {
             if let Some(cloud) = &self.cloud {
                 return cloud.delete(path);
@@ -329,8 +410,29 @@ impl Storage {
         self.local.delete(path)
     }
 
+    /// Revision history of a cloud file, newest first
+    pub fn revisions(&self, path: &str) -> StorageHandle<Vec<RemoteVersion>> {
+        match &self.cloud {
+            Some(cloud) if Self::is_userdata_path(path) && self.mount_for(path).is_none() => cloud.revisions(path),
+            _ => StorageHandle::error(StorageError::Other("Revision history needs cloud storage".into())),
+        }
+    }
+
+    /// Read an older revision of a cloud file
+    pub fn read_revision(&self, path: &str, revision: u64) -> StorageHandle<Vec<u8>> {
+        match &self.cloud {
+            Some(cloud) if Self::is_userdata_path(path) && self.mount_for(path).is_none() => {
+                cloud.read_revision(path, revision)
+            }
+            _ => StorageHandle::error(StorageError::Other("Revision history needs cloud storage".into())),
+        }
+    }
+
     /// Check if a file exists
     pub fn exists(&self, path: &str) -> StorageHandle<bool> {
+        if self.mount_for(path).is_some() {
+            return StorageHandle::ready(true);
+        }
         if Self::is_userdata_path(path) {
             if let Some(cloud) = &self.cloud {
                 return cloud.exists(path);
@@ -341,6 +443,23 @@ impl Storage {
         self.local.exists(path)
     }
 
+    // ─────────────────────────────────────────────────────────────────────────
+    // Sharing
+    // ─────────────────────────────────────────────────────────────────────────
+
+    /// Publish data under a new public token (cloud only, so signed in)
+    pub fn share(&self, data: &[u8]) -> StorageHandle<String> {
+        match &self.cloud {
+            Some(cloud) => cloud.share(data),
+            None => StorageHandle::error(StorageError::AuthRequired),
+        }
+    }
+
+    /// Read shared data by token (works signed out)
+    pub fn read_shared(&self, token: &str) -> StorageHandle<Vec<u8>> {
+        GcpStorage::read_shared(token)
+    }
+
     // ─────────────────────────────────────────────────────────────────────────
     // Synchronous methods (for local storage or when blocking is acceptable)
     // These will panic if called on a cloud path that returns Pending
@@ -413,9 +532,11 @@ impl Storage {
 
     /// Check if operations complete synchronously for a given path
     ///
-    /// Returns true for local paths, false for cloud paths when authenticated.
+    /// Returns true for local and bundled paths, false for cloud paths when authenticated.
     pub fn is_sync(&self, path: &str) -> bool {
-        if Self::is_userdata_path(path) && self.cloud.is_some() {
+        if self.mount_for(path).is_some() {
+            true
+        } else if Self::is_userdata_path(path) && self.cloud.is_some() {
             false
         } else {
             true
@@ -483,4 +604,27 @@ mod tests {
         assert_eq!(storage.mode(), StorageMode::Local);
         assert!(!storage.has_cloud());
     }
+
+    #[test]
+    fn test_bundle_mount_overlay() {
+        let mut writer = crate::bundle::BundleWriter::new();
+        writer.add("bundle_test/levels/packed.ron", b"(packed)".to_vec());
+        let bundle = crate::bundle::Bundle::from_bytes(writer.finish().unwrap()).unwrap();
+
+        let mut storage = Storage::new();
+        storage.mount("test.bpk", bundle);
+
+        // Served from the bundle even though nothing is on disk
+        assert_eq!(storage.list_sync("bundle_test/levels").unwrap(), vec!["packed.ron"]);
+        assert_eq!(storage.read_sync("bundle_test/levels/packed.ron").unwrap(), b"(packed)");
+        assert!(storage.exists_sync("bundle_test/levels/packed.ron").unwrap());
+        assert!(matches!(
+            storage.write_sync("bundle_test/levels/packed.ron", b"x"),
+            Err(StorageError::PermissionDenied(_))
+        ));
+
+        // Remounting under the same name replaces the bundle
+        storage.mount("test.bpk", crate::bundle::Bundle::from_bytes(crate::bundle::BundleWriter::new().finish().unwrap()).unwrap());
+        assert!(!storage.exists_sync("bundle_test/levels/packed.ron").unwrap());
+    }
 }
@@ -0,0 +1,97 @@
+//! Cloud File Versions
+//!
+//! The storage API keeps every upload of a file as a numbered revision. To
+//! avoid blindly overwriting a newer copy saved from another browser or
+//! machine, the revision each file was last read or written at is remembered
+//! here as its "base". Uploads send the base along and the server refuses
+//! them with `StorageError::Conflict` when the file has moved on since.
+//!
+//! Bases are process-wide because native cloud saves run on background
+//! threads with their own `Storage`.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// One stored revision of a cloud file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteVersion {
+    /// Server revision number (grows with every upload)
+    pub revision: u64,
+    /// Upload time (Unix seconds)
+    pub updated_at: u64,
+    /// Size in bytes
+    pub size: u64,
+}
+
+/// Revision the local copy of each file matches, by path
+static BASES: Mutex<BTreeMap<String, RemoteVersion>> = Mutex::new(BTreeMap::new());
+
+/// Remember that the local copy of `path` matches `version`
+pub fn record_base(path: &str, version: RemoteVersion) {
+    if let Ok(mut bases) = BASES.lock() {
+        bases.insert(path.to_string(), version);
+    }
+}
+
+/// Revision the local copy of `path` was read or written at, if known
+pub fn base(path: &str) -> Option<RemoteVersion> {
+    BASES.lock().ok().and_then(|bases| bases.get(path).copied())
+}
+
+/// Forget the base of `path` (deleted, or written without a revision back)
+pub fn forget_base(path: &str) {
+    if let Ok(mut bases) = BASES.lock() {
+        bases.remove(path);
+    }
+}
+
+/// Free path for a copy of `path` ("name_copy.ron", then "name_copy_2.ron", ...)
+/// that isn't one of `taken` (file names or full paths)
+pub fn copy_path(path: &str, taken: &[String]) -> String {
+    let (dir, file) = match path.rfind('/') {
+        Some(i) => (&path[..=i], &path[i + 1..]),
+        None => ("", path),
+    };
+    let (stem, ext) = match file.rfind('.') {
+        Some(i) if i > 0 => (&file[..i], &file[i..]),
+        _ => (file, ""),
+    };
+    let is_taken = |name: &str| taken.iter().any(|t| t == name || t.rsplit('/').next() == Some(name));
+    let mut counter = 1;
+    loop {
+        let name = if counter == 1 {
+            format!("{}_copy{}", stem, ext)
+        } else {
+            format!("{}_copy_{}{}", stem, counter, ext)
+        };
+        if !is_taken(&name) {
+            return format!("{}{}", dir, name);
+        }
+        counter += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_record_and_forget() {
+        let path = "assets/userdata/levels/versions_test.ron";
+        assert_eq!(base(path), None);
+        let version = RemoteVersion { revision: 7, updated_at: 1_700_000_000, size: 120 };
+        record_base(path, version);
+        assert_eq!(base(path), Some(version));
+        forget_base(path);
+        assert_eq!(base(path), None);
+    }
+
+    #[test]
+    fn test_copy_path_skips_taken_names() {
+        let path = "assets/userdata/levels/castle.ron";
+        assert_eq!(copy_path(path, &[]), "assets/userdata/levels/castle_copy.ron");
+        let taken = vec!["castle.ron".to_string(), "assets/userdata/levels/castle_copy.ron".to_string()];
+        assert_eq!(copy_path(path, &taken), "assets/userdata/levels/castle_copy_2.ron");
+        assert_eq!(copy_path("notes", &[]), "notes_copy");
+    }
+}
@@ -0,0 +1,661 @@
+//! Asset tags and smart collections
+//!
+//! Textures, assets (models), songs, levels and prefabs can carry free-form tags, and any
+//! filter query can be saved as a smart collection. Every browser shows the
+//! same filter bar, so a big library can be narrowed down the same way in
+//! each editor.
+//!
+//! Query syntax (terms are ANDed, case-insensitive):
+//! - `rock` — name contains "rock"
+//! - `#wall` or `tag:wall` — has the tag "wall"
+//! - `-#wip` or `-tag:wip` — does not have the tag "wip"
+//! - `kind:texture` — only textures (`asset`, `song`, `level`, `prefab` also work)
+//! - `is:untagged` — has no tags
+//!
+//! Tags are shared across tabs, so the library lives in a thread-local (like
+//! the clipboard history) and is written to `assets/userdata/tags.ron` when it
+//! changes. Cloud-backed userdata is async, so there tags only last for the
+//! session.
+
+use std::cell::{Cell, RefCell};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::storage::Storage;
+use crate::ui::{Rect, UiContext, TextInputState, draw_text_input, theme};
+
+/// Where the tag library is stored
+pub const TAGS_PATH: &str = "assets/userdata/tags.ron";
+
+/// Height of the filter bar (query row + collections row)
+pub const TAG_FILTER_HEIGHT: f32 = 48.0;
+/// Height of the tag row shown for the selected item
+pub const TAG_ROW_HEIGHT: f32 = 22.0;
+
+/// What kind of library item a tag is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TagKind {
+    Texture,
+    Asset,
+    Song,
+    Level,
+    Prefab,
+}
+
+impl TagKind {
+    /// Lowercase name, as used in queries and storage paths
+    pub fn label(&self) -> &'static str {
+        match self {
+            TagKind::Texture => "texture",
+            TagKind::Asset => "asset",
+            TagKind::Song => "song",
+            TagKind::Level => "level",
+            TagKind::Prefab => "prefab",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "texture" | "textures" => Some(TagKind::Texture),
+            "asset" | "assets" | "model" | "models" => Some(TagKind::Asset),
+            "song" | "songs" => Some(TagKind::Song),
+            "level" | "levels" => Some(TagKind::Level),
+            "prefab" | "prefabs" => Some(TagKind::Prefab),
+            _ => None,
+        }
+    }
+}
+
+/// Tags on one library item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TaggedItem {
+    kind: TagKind,
+    /// Item key as used by its browser (texture name, asset library key, ...)
+    key: String,
+    tags: Vec<String>,
+}
+
+/// A saved filter query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartCollection {
+    pub name: String,
+    pub query: String,
+}
+
+/// All tags and smart collections
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagLibrary {
+    #[serde(default)]
+    items: Vec<TaggedItem>,
+    #[serde(default)]
+    pub collections: Vec<SmartCollection>,
+    /// Changed since the last save
+    #[serde(skip)]
+    dirty: bool,
+}
+
+/// Normalize a tag as typed: trimmed, lowercase, spaces become dashes
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').to_lowercase().split_whitespace().collect::<Vec<_>>().join("-")
+}
+
+impl TagLibrary {
+    /// Tags on an item (empty if it has none)
+    pub fn tags_of(&self, kind: TagKind, key: &str) -> &[String] {
+        self.items.iter()
+            .find(|i| i.kind == kind && i.key == key)
+            .map(|i| i.tags.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Add a tag to an item, returns false if it was already there
+    pub fn add_tag(&mut self, kind: TagKind, key: &str, tag: &str) -> bool {
+        let tag = normalize_tag(tag);
+        if tag.is_empty() {
+            return false;
+        }
+        let idx = match self.items.iter().position(|i| i.kind == kind && i.key == key) {
+            Some(idx) => idx,
+            None => {
+                self.items.push(TaggedItem { kind, key: key.to_string(), tags: Vec::new() });
+                self.items.len() - 1
+            }
+        };
+        let tags = &mut self.items[idx].tags;
+        if tags.contains(&tag) {
+            return false;
+        }
+        tags.push(tag);
+        tags.sort();
+        self.dirty = true;
+        true
+    }
+
+    /// Remove a tag from an item
+    pub fn remove_tag(&mut self, kind: TagKind, key: &str, tag: &str) {
+        if let Some(item) = self.items.iter_mut().find(|i| i.kind == kind && i.key == key) {
+            item.tags.retain(|t| t != tag);
+            self.dirty = true;
+        }
+        self.items.retain(|i| !i.tags.is_empty());
+    }
+
+    /// Move an item's tags to a new key (after a rename)
+    pub fn rename_item(&mut self, kind: TagKind, old_key: &str, new_key: &str) {
+        if let Some(item) = self.items.iter_mut().find(|i| i.kind == kind && i.key == old_key) {
+            item.key = new_key.to_string();
+            self.dirty = true;
+        }
+    }
+
+    /// Every tag in use (optionally for one kind), sorted
+    pub fn all_tags(&self, kind: Option<TagKind>) -> Vec<String> {
+        let mut tags: Vec<String> = self.items.iter()
+            .filter(|i| kind.map_or(true, |k| i.kind == k))
+            .flat_map(|i| i.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Save a query as a smart collection (replaces one with the same name)
+    pub fn save_collection(&mut self, name: &str, query: &str) {
+        let name = name.trim();
+        if name.is_empty() || query.trim().is_empty() {
+            return;
+        }
+        self.collections.retain(|c| c.name != name);
+        self.collections.push(SmartCollection { name: name.to_string(), query: query.trim().to_string() });
+        self.dirty = true;
+    }
+
+    pub fn remove_collection(&mut self, index: usize) {
+        if index < self.collections.len() {
+            self.collections.remove(index);
+            self.dirty = true;
+        }
+    }
+
+    /// Whether an item passes a query
+    pub fn matches(&self, query: &TagQuery, kind: TagKind, key: &str, name: &str) -> bool {
+        query.matches(kind, name, self.tags_of(kind, key))
+    }
+}
+
+/// One term of a filter query
+#[derive(Debug, Clone, PartialEq)]
+enum QueryTerm {
+    Text(String),
+    Tag(String),
+    NotTag(String),
+    Kind(TagKind),
+    Untagged,
+}
+
+/// A parsed filter query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TagQuery {
+    terms: Vec<QueryTerm>,
+}
+
+impl TagQuery {
+    pub fn parse(text: &str) -> Self {
+        let terms = text.split_whitespace().map(|word| {
+            let lower = word.to_lowercase();
+            let (negate, rest) = match lower.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => (true, rest),
+                _ => (false, lower.as_str()),
+            };
+            let tag = rest.strip_prefix('#').or_else(|| rest.strip_prefix("tag:"));
+            if let Some(tag) = tag {
+                let tag = normalize_tag(tag);
+                return if negate { QueryTerm::NotTag(tag) } else { QueryTerm::Tag(tag) };
+            }
+            if let Some(kind) = rest.strip_prefix("kind:").and_then(TagKind::from_label) {
+                return QueryTerm::Kind(kind);
+            }
+            if rest == "is:untagged" {
+                return QueryTerm::Untagged;
+            }
+            QueryTerm::Text(lower)
+        }).collect();
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, kind: TagKind, name: &str, tags: &[String]) -> bool {
+        let name = name.to_lowercase();
+        self.terms.iter().all(|term| match term {
+            QueryTerm::Text(text) => name.contains(text.as_str()),
+            QueryTerm::Tag(tag) => tags.contains(tag),
+            QueryTerm::NotTag(tag) => !tags.contains(tag),
+            QueryTerm::Kind(k) => *k == kind,
+            QueryTerm::Untagged => tags.is_empty(),
+        })
+    }
+}
+
+thread_local! {
+    static LIBRARY: RefCell<TagLibrary> = RefCell::new(TagLibrary::default());
+    /// Frame counter, so a filter bar that stopped being drawn lets go of the keyboard
+    static FRAME: Cell<u64> = const { Cell::new(0) };
+}
+
+fn current_frame() -> u64 {
+    FRAME.with(|f| f.get())
+}
+
+/// Run `f` with the shared tag library
+pub fn with_tags<R>(f: impl FnOnce(&mut TagLibrary) -> R) -> R {
+    LIBRARY.with(|l| f(&mut l.borrow_mut()))
+}
+
+/// Tags persist only where userdata is synchronous and writable (local disk)
+fn can_persist(storage: &Storage) -> bool {
+    storage.can_write() && storage.is_sync(TAGS_PATH)
+}
+
+/// Load the tag library from storage (empty if missing or unavailable)
+pub fn load(storage: &Storage) {
+    let library = if can_persist(storage) {
+        storage.read_string_sync(TAGS_PATH)
+            .ok()
+            .and_then(|text| match ron::from_str::<TagLibrary>(&text) {
+                Ok(library) => Some(library),
+                Err(e) => {
+                    eprintln!("Failed to parse tags: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default()
+    } else {
+        TagLibrary::default()
+    };
+    with_tags(|l| *l = library);
+}
+
+/// Write the tag library if it changed and advance the frame counter
+/// (called once per frame)
+pub fn end_frame(storage: &Storage) {
+    FRAME.with(|f| f.set(f.get() + 1));
+    let text = with_tags(|l| {
+        if !l.dirty {
+            return None;
+        }
+        l.dirty = false;
+        ron::ser::to_string_pretty(&*l, ron::ser::PrettyConfig::default()).ok()
+    });
+    if let Some(text) = text {
+        if can_persist(storage) {
+            if let Err(e) = storage.write_string_sync(TAGS_PATH, &text) {
+                eprintln!("Failed to save tags: {}", e);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Browser widgets
+// ============================================================================
+
+/// Filter bar state, one per browser
+#[derive(Debug)]
+pub struct TagFilter {
+    /// Query text
+    pub input: TextInputState,
+    /// Whether the query field has keyboard focus
+    pub focused: bool,
+    /// Name prompt while saving the query as a collection
+    pub collection_name: Option<TextInputState>,
+    /// Tag being typed for the selected item
+    pub new_tag: Option<TextInputState>,
+    /// Parsed form of `input.text`
+    query: TagQuery,
+    /// A text field had focus this frame (browsers skip their own key shortcuts)
+    typing: bool,
+    /// Frame the filter bar was last drawn
+    drawn_frame: u64,
+    /// The item tag row was drawn since the last filter bar
+    tag_row_drawn: bool,
+}
+
+impl Default for TagFilter {
+    fn default() -> Self {
+        let mut input = TextInputState::new("");
+        input.focused = false;
+        Self {
+            input,
+            focused: false,
+            collection_name: None,
+            new_tag: None,
+            query: TagQuery::default(),
+            typing: false,
+            drawn_frame: 0,
+            tag_row_drawn: false,
+        }
+    }
+}
+
+impl TagFilter {
+    /// Whether an item passes the current query
+    pub fn matches(&self, kind: TagKind, key: &str, name: &str) -> bool {
+        self.query.is_empty() || with_tags(|l| l.matches(&self.query, kind, key, name))
+    }
+
+    /// Whether any query is active
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    /// Whether a tag text field is taking keyboard input (Enter/Escape belong to it)
+    pub fn is_typing(&self) -> bool {
+        self.typing && self.drawn_frame + 1 >= current_frame()
+    }
+
+    /// Replace the query text
+    pub fn set_query(&mut self, text: &str) {
+        self.input = TextInputState::new(text);
+        self.query = TagQuery::parse(text);
+    }
+
+    /// Clear the query and any prompts
+    pub fn clear(&mut self) {
+        self.set_query("");
+        self.focused = false;
+        self.collection_name = None;
+        self.new_tag = None;
+    }
+}
+
+/// Draw a small chip, returns (rect, clicked, remove clicked)
+fn draw_chip(ctx: &mut UiContext, x: f32, y: f32, h: f32, label: &str, active: bool, removable: bool) -> (Rect, bool, bool) {
+    let text_w = measure_text(label, None, 11, 1.0).width;
+    let w = text_w + if removable { 22.0 } else { 12.0 };
+    let rect = Rect::new(x, y, w, h);
+    let hovered = ctx.mouse.inside(&rect);
+    let bg = if active {
+        theme().accent
+    } else if hovered {
+        Color::from_rgba(65, 65, 80, 255)
+    } else {
+        Color::from_rgba(50, 50, 62, 255)
+    };
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, bg);
+    draw_text(label, rect.x + 6.0, rect.y + h / 2.0 + 4.0, 11.0, WHITE);
+
+    let mut remove = false;
+    if removable {
+        let x_rect = Rect::new(rect.right() - 14.0, rect.y, 14.0, h);
+        let x_hovered = ctx.mouse.inside(&x_rect);
+        draw_text("x", x_rect.x + 3.0, rect.y + h / 2.0 + 4.0, 11.0,
+            if x_hovered { Color::from_rgba(255, 140, 140, 255) } else { Color::from_rgba(160, 160, 170, 255) });
+        remove = x_hovered && ctx.mouse.left_pressed;
+    }
+    let clicked = hovered && ctx.mouse.left_pressed && !remove;
+    (rect, clicked, remove)
+}
+
+/// Focus-aware single-line field: clicking focuses it, clicking elsewhere or
+/// Escape unfocuses. Returns (changed, submitted with Enter).
+fn draw_focus_field(ctx: &UiContext, rect: Rect, input: &mut TextInputState, focused: &mut bool, placeholder: &str) -> (bool, bool) {
+    if ctx.mouse.left_pressed {
+        *focused = ctx.mouse.inside(&rect);
+    }
+    input.focused = *focused;
+
+    let mut changed = false;
+    let mut submitted = false;
+    if *focused {
+        changed = draw_text_input(rect, input, 12.0);
+        if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            submitted = true;
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            *focused = false;
+        }
+    } else {
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(30, 30, 36, 255));
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, Color::from_rgba(70, 70, 80, 255));
+        let (text, color) = if input.text.is_empty() {
+            (placeholder, Color::from_rgba(110, 110, 120, 255))
+        } else {
+            (input.text.as_str(), Color::from_rgba(210, 210, 210, 255))
+        };
+        draw_text(text, rect.x + 8.0, rect.y + rect.h / 2.0 + 4.0, 12.0, color);
+    }
+    (changed, submitted)
+}
+
+/// Draw the filter bar: query field with Save/Clear, then the saved collections.
+/// Right-click a collection to delete it.
+pub fn draw_tag_filter(ctx: &mut UiContext, rect: Rect, filter: &mut TagFilter) {
+    // Drop focus if the bar was hidden for a while (panel switched away)
+    let frame = current_frame();
+    if filter.drawn_frame + 1 < frame {
+        filter.focused = false;
+        filter.collection_name = None;
+    }
+    filter.drawn_frame = frame;
+
+    // Drop a "+ tag" prompt whose item went away (selection cleared)
+    if !filter.tag_row_drawn {
+        filter.new_tag = None;
+    }
+    filter.tag_row_drawn = false;
+    filter.typing = filter.focused || filter.collection_name.is_some() || filter.new_tag.is_some();
+
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(32, 32, 40, 255));
+    let row_h = 20.0;
+    let btn_w = 40.0;
+    let gap = 4.0;
+    let field_rect = Rect::new(rect.x + gap, rect.y + gap, rect.w - btn_w * 2.0 - gap * 4.0, row_h);
+
+    if let Some(name_input) = &mut filter.collection_name {
+        // Saving: the field asks for the collection name
+        let mut focused = true;
+        let (_, submitted) = draw_focus_field(ctx, field_rect, name_input, &mut focused, "Collection name");
+        if submitted {
+            let name = name_input.text.clone();
+            let query = filter.input.text.clone();
+            with_tags(|l| l.save_collection(&name, &query));
+            filter.collection_name = None;
+        } else if !focused {
+            filter.collection_name = None;
+        }
+    } else {
+        let (changed, _) = draw_focus_field(ctx, field_rect, &mut filter.input, &mut filter.focused, "Filter: name #tag -#tag kind:");
+        if changed {
+            filter.query = TagQuery::parse(&filter.input.text);
+        }
+    }
+
+    // Save / Clear buttons
+    let save_rect = Rect::new(field_rect.right() + gap, field_rect.y, btn_w, row_h);
+    let clear_rect = Rect::new(save_rect.right() + gap, field_rect.y, btn_w, row_h);
+    for (btn, label, enabled) in [(save_rect, "Save", filter.is_active()), (clear_rect, "Clear", filter.is_active())] {
+        let hovered = enabled && ctx.mouse.inside(&btn);
+        draw_rectangle(btn.x, btn.y, btn.w, btn.h,
+            if hovered { Color::from_rgba(70, 70, 85, 255) } else { Color::from_rgba(50, 50, 62, 255) });
+        let dims = measure_text(label, None, 11, 1.0);
+        draw_text(label, btn.x + (btn.w - dims.width) / 2.0, btn.y + 14.0, 11.0,
+            if enabled { WHITE } else { Color::from_rgba(100, 100, 105, 255) });
+        if hovered && ctx.mouse.left_pressed {
+            if label == "Save" {
+                filter.collection_name = Some(TextInputState::new(""));
+                filter.focused = false;
+            } else {
+                filter.clear();
+            }
+        }
+        if hovered {
+            ctx.set_tooltip(if label == "Save" { "Save query as a smart collection" } else { "Clear filter" }, ctx.mouse.x, ctx.mouse.y);
+        }
+    }
+
+    // Smart collections row
+    let chip_h = 16.0;
+    let mut x = rect.x + gap;
+    let y = field_rect.bottom() + gap;
+    let collections = with_tags(|l| l.collections.clone());
+    if collections.is_empty() {
+        draw_text("No saved collections", x, y + 12.0, 11.0, Color::from_rgba(100, 100, 110, 255));
+    }
+    let mut remove = None;
+    for (i, collection) in collections.iter().enumerate() {
+        let active = filter.input.text.trim() == collection.query;
+        let label_w = measure_text(&collection.name, None, 11, 1.0).width + 12.0;
+        if x + label_w > rect.right() {
+            break;
+        }
+        let (chip, clicked, _) = draw_chip(ctx, x, y, chip_h, &collection.name, active, false);
+        if clicked {
+            if active {
+                filter.set_query("");
+            } else {
+                filter.set_query(&collection.query);
+            }
+        }
+        if ctx.mouse.inside(&chip) {
+            if ctx.mouse.right_pressed {
+                remove = Some(i);
+            }
+            ctx.set_tooltip(&format!("{}  (right-click to delete)", collection.query), ctx.mouse.x, ctx.mouse.y);
+        }
+        x = chip.right() + gap;
+    }
+    if let Some(i) = remove {
+        with_tags(|l| l.remove_collection(i));
+    }
+}
+
+/// Draw the selected item's tags with remove buttons and a "+ tag" field.
+/// Tab completes the typed tag from the tags already in use.
+pub fn draw_item_tags(ctx: &mut UiContext, rect: Rect, filter: &mut TagFilter, kind: TagKind, key: &str) {
+    filter.tag_row_drawn = true;
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(30, 30, 38, 255));
+    let chip_h = rect.h - 6.0;
+    let gap = 4.0;
+    let y = rect.y + 3.0;
+    let mut x = rect.x + gap;
+
+    let tags = with_tags(|l| l.tags_of(kind, key).to_vec());
+    let mut removed = None;
+    for tag in &tags {
+        let label = format!("#{}", tag);
+        let label_w = measure_text(&label, None, 11, 1.0).width + 22.0;
+        if x + label_w > rect.right() - 70.0 {
+            draw_text("...", x, y + chip_h / 2.0 + 4.0, 11.0, Color::from_rgba(140, 140, 150, 255));
+            x += 16.0;
+            break;
+        }
+        let (chip, clicked, remove) = draw_chip(ctx, x, y, chip_h, &label, false, true);
+        if remove {
+            removed = Some(tag.clone());
+        } else if clicked {
+            // Clicking a tag filters the browser by it
+            filter.set_query(&label);
+        }
+        x = chip.right() + gap;
+    }
+    if let Some(tag) = removed {
+        with_tags(|l| l.remove_tag(kind, key, &tag));
+    }
+
+    // "+ tag" field
+    let field_rect = Rect::new(x, y, (rect.right() - x - gap).max(60.0), chip_h);
+    match &mut filter.new_tag {
+        Some(input) => {
+            if is_key_pressed(KeyCode::Tab) && !input.text.is_empty() {
+                let typed = normalize_tag(&input.text);
+                let completion = with_tags(|l| l.all_tags(Some(kind)))
+                    .into_iter()
+                    .find(|t| t.starts_with(&typed) && !tags.contains(t));
+                if let Some(tag) = completion {
+                    *input = TextInputState::new(tag);
+                }
+            }
+            let mut focused = true;
+            let (_, submitted) = draw_focus_field(ctx, field_rect, input, &mut focused, "tag (Tab completes)");
+            if submitted {
+                let tag = input.text.clone();
+                with_tags(|l| l.add_tag(kind, key, &tag));
+                filter.new_tag = None;
+            } else if !focused {
+                filter.new_tag = None;
+            }
+        }
+        None => {
+            let add_rect = Rect::new(x, y, 50.0, chip_h);
+            let hovered = ctx.mouse.inside(&add_rect);
+            draw_rectangle(add_rect.x, add_rect.y, add_rect.w, add_rect.h,
+                if hovered { Color::from_rgba(60, 70, 60, 255) } else { Color::from_rgba(45, 52, 45, 255) });
+            draw_text("+ tag", add_rect.x + 8.0, y + chip_h / 2.0 + 4.0, 11.0, Color::from_rgba(180, 220, 180, 255));
+            if hovered && ctx.mouse.left_pressed {
+                filter.new_tag = Some(TextInputState::new(""));
+                filter.focused = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_query_terms() {
+        let q = TagQuery::parse("Rock #wall -tag:wip");
+        assert!(q.matches(TagKind::Texture, "rock_01", &tags(&["wall"])));
+        assert!(!q.matches(TagKind::Texture, "rock_01", &tags(&["wall", "wip"])));
+        assert!(!q.matches(TagKind::Texture, "brick", &tags(&["wall"])));
+        assert!(!q.matches(TagKind::Texture, "rock_01", &[]));
+    }
+
+    #[test]
+    fn test_query_kind_and_untagged() {
+        let q = TagQuery::parse("kind:song is:untagged");
+        assert!(q.matches(TagKind::Song, "theme", &[]));
+        assert!(!q.matches(TagKind::Asset, "theme", &[]));
+        assert!(!q.matches(TagKind::Song, "theme", &tags(&["boss"])));
+        assert!(TagQuery::parse("kind:levels").matches(TagKind::Level, "cave", &[]));
+        assert!(TagQuery::parse("  ").is_empty());
+    }
+
+    #[test]
+    fn test_library_tags() {
+        let mut lib = TagLibrary::default();
+        assert!(lib.add_tag(TagKind::Texture, "stone", " Dungeon Floor "));
+        assert!(!lib.add_tag(TagKind::Texture, "stone", "dungeon-floor"));
+        lib.add_tag(TagKind::Asset, "user:crate", "prop");
+        assert_eq!(lib.tags_of(TagKind::Texture, "stone"), &tags(&["dungeon-floor"])[..]);
+        assert_eq!(lib.all_tags(None), tags(&["dungeon-floor", "prop"]));
+
+        lib.rename_item(TagKind::Asset, "user:crate", "user:box");
+        assert_eq!(lib.tags_of(TagKind::Asset, "user:box"), &tags(&["prop"])[..]);
+
+        lib.remove_tag(TagKind::Texture, "stone", "dungeon-floor");
+        assert!(lib.tags_of(TagKind::Texture, "stone").is_empty());
+        assert_eq!(lib.all_tags(Some(TagKind::Texture)), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_collections_roundtrip() {
+        let mut lib = TagLibrary::default();
+        lib.save_collection("Walls", "#wall");
+        lib.save_collection("Walls", "#wall -#wip");
+        lib.save_collection("", "#x");
+        assert_eq!(lib.collections.len(), 1);
+        assert_eq!(lib.collections[0].query, "#wall -#wip");
+
+        let text = ron::to_string(&lib).unwrap();
+        let back: TagLibrary = ron::from_str(&text).unwrap();
+        assert_eq!(back.collections, lib.collections);
+    }
+}
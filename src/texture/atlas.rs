@@ -0,0 +1,216 @@
+//! Texture atlas builder
+//!
+//! Packs several textures into one 256x256 sheet with a merged palette, so a
+//! mesh can draw from a single texture. Textures are placed on shelves,
+//! tallest first (all sizes are powers of two, so shelves fill without gaps),
+//! and their colors are merged by value: index 0 stays transparent and the
+//! atlas is 4-bit when everything fits in 16 colors.
+//!
+//! `remap_parts` then points mesh parts that used a packed texture at the
+//! atlas, squeezing their UVs into its region. Tiled UVs (outside 0..1) are
+//! clamped, since an atlas region can't repeat.
+
+use crate::modeler::{MeshPart, TextureRef};
+use crate::rasterizer::{ClutDepth, Color15};
+use super::{TextureSize, UserTexture};
+
+/// Atlas width and height in pixels
+pub const ATLAS_SIZE: usize = 256;
+
+/// Where a packed texture landed in the atlas (pixels)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtlasRegion {
+    /// The packed texture's id
+    pub texture_id: u64,
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// A packed atlas texture and the regions of the textures inside it
+#[derive(Debug, Clone)]
+pub struct TextureAtlas {
+    pub texture: UserTexture,
+    pub regions: Vec<AtlasRegion>,
+}
+
+impl TextureAtlas {
+    /// Region of a packed texture
+    pub fn region(&self, texture_id: u64) -> Option<&AtlasRegion> {
+        self.regions.iter().find(|r| r.texture_id == texture_id)
+    }
+}
+
+/// Pack `textures` into a new atlas texture named `name`.
+///
+/// Fails when they don't fit in 256x256, use more than 256 colors between
+/// them, or are animated (each frame would need its own atlas).
+pub fn build_atlas(name: &str, textures: &[&UserTexture]) -> Result<TextureAtlas, String> {
+    if textures.is_empty() {
+        return Err("no textures to pack".to_string());
+    }
+    if let Some(tex) = textures.iter().find(|t| t.is_animated()) {
+        return Err(format!("'{}' is animated and can't be packed", tex.name));
+    }
+
+    // Tallest first, then widest; ties keep the caller's order
+    let mut order: Vec<usize> = (0..textures.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse((textures[i].height, textures[i].width)));
+
+    let mut regions = Vec::with_capacity(textures.len());
+    let (mut x, mut y, mut shelf_height) = (0, 0, 0);
+    for &i in &order {
+        let tex = textures[i];
+        if x + tex.width > ATLAS_SIZE {
+            x = 0;
+            y += shelf_height;
+            shelf_height = 0;
+        }
+        if tex.width > ATLAS_SIZE || y + tex.height > ATLAS_SIZE {
+            return Err(format!("textures don't fit in {}x{}", ATLAS_SIZE, ATLAS_SIZE));
+        }
+        regions.push(AtlasRegion { texture_id: tex.id, x, y, width: tex.width, height: tex.height });
+        x += tex.width;
+        shelf_height = shelf_height.max(tex.height);
+    }
+
+    // Merge the colors the textures actually use
+    let mut palette = vec![Color15::TRANSPARENT];
+    let mut indices = vec![0u8; ATLAS_SIZE * ATLAS_SIZE];
+    for (&i, region) in order.iter().zip(&regions) {
+        let tex = textures[i];
+        let mut remap: Vec<Option<u8>> = vec![None; tex.palette.len().max(1)];
+        for py in 0..tex.height {
+            for px in 0..tex.width {
+                let src = tex.indices[py * tex.width + px] as usize;
+                let atlas_index = match remap.get(src).copied().flatten() {
+                    Some(index) => index,
+                    None => {
+                        let color = tex.palette.get(src).copied().unwrap_or(Color15::TRANSPARENT);
+                        let index = match palette.iter().position(|&c| c == color) {
+                            Some(index) => index,
+                            None if palette.len() < ClutDepth::Bpp8.color_count() => {
+                                palette.push(color);
+                                palette.len() - 1
+                            }
+                            None => return Err("textures use more than 256 colors between them".to_string()),
+                        };
+                        if let Some(slot) = remap.get_mut(src) {
+                            *slot = Some(index as u8);
+                        }
+                        index as u8
+                    }
+                };
+                indices[(region.y + py) * ATLAS_SIZE + region.x + px] = atlas_index;
+            }
+        }
+    }
+
+    let depth = if palette.len() <= ClutDepth::Bpp4.color_count() { ClutDepth::Bpp4 } else { ClutDepth::Bpp8 };
+    palette.resize(depth.color_count(), Color15::TRANSPARENT);
+    let texture = UserTexture::new_with_data(name, TextureSize::Size256x256, depth, indices, palette);
+    Ok(TextureAtlas { texture, regions })
+}
+
+/// Point parts textured with a packed texture at the atlas and move their UVs
+/// into its region. Returns how many parts changed.
+pub fn remap_parts(parts: &mut [MeshPart], atlas: &TextureAtlas) -> usize {
+    let size = ATLAS_SIZE as f32;
+    let mut changed = 0;
+    for part in parts {
+        let TextureRef::Id(id) = &part.texture_ref else {
+            continue;
+        };
+        let Some(region) = atlas.region(*id) else {
+            continue;
+        };
+        // Keep the far edge on the region's last texel instead of the neighbour's first
+        let (w, h) = (region.width as f32, region.height as f32);
+        for vertex in &mut part.mesh.vertices {
+            let u = (vertex.uv.x.clamp(0.0, 1.0) * w).min(w - 0.5);
+            let v = (vertex.uv.y.clamp(0.0, 1.0) * h).min(h - 0.5);
+            vertex.uv.x = (region.x as f32 + u) / size;
+            vertex.uv.y = (region.y as f32 + v) / size;
+        }
+        part.texture_ref = TextureRef::Id(atlas.texture.id);
+        changed += 1;
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A texture filled with one color (palette index 1)
+    fn solid(name: &str, size: TextureSize, color: Color15) -> UserTexture {
+        let mut tex = UserTexture::new(name, size, ClutDepth::Bpp4);
+        tex.palette[1] = color;
+        tex.fill(1);
+        tex
+    }
+
+    #[test]
+    fn test_pack_places_tallest_first_and_merges_palettes() {
+        let red = Color15::new(31, 0, 0);
+        let blue = Color15::new(0, 0, 31);
+        let small = solid("small", TextureSize::Size32x32, red);
+        let big = solid("big", TextureSize::Size128x128, blue);
+        let mid = solid("mid", TextureSize::Size64x64, red);
+        let atlas = build_atlas("atlas", &[&small, &big, &mid]).unwrap();
+
+        assert_eq!(atlas.region(big.id).map(|r| (r.x, r.y)), Some((0, 0)));
+        assert_eq!(atlas.region(mid.id).map(|r| (r.x, r.y)), Some((128, 0)));
+        assert_eq!(atlas.region(small.id).map(|r| (r.x, r.y)), Some((192, 0)));
+
+        // Transparent, blue and red: fits a 4-bit palette, red shared
+        let tex = &atlas.texture;
+        assert_eq!(tex.depth, ClutDepth::Bpp4);
+        assert!(tex.validate().is_ok());
+        assert_eq!(tex.get_color(10, 10), blue);
+        assert_eq!(tex.get_color(130, 10), red);
+        assert_eq!(tex.get_color(200, 10), red);
+        assert_eq!(tex.get_index(130, 10), tex.get_index(200, 10));
+        assert!(tex.get_color(10, 200).is_transparent());
+    }
+
+    #[test]
+    fn test_pack_rejects_overflow_and_animation() {
+        let full: Vec<UserTexture> = (0..5)
+            .map(|i| solid(&format!("t{}", i), TextureSize::Size128x128, Color15::new(i, i, i)))
+            .collect();
+        let refs: Vec<&UserTexture> = full.iter().collect();
+        assert!(build_atlas("atlas", &refs[..4]).is_ok());
+        assert!(build_atlas("atlas", &refs).is_err());
+
+        let mut animated = solid("anim", TextureSize::Size32x32, Color15::new(5, 5, 5));
+        animated.duplicate_frame(0);
+        assert!(build_atlas("atlas", &[&animated]).is_err());
+        assert!(build_atlas("atlas", &[]).is_err());
+    }
+
+    #[test]
+    fn test_remap_moves_uvs_into_region() {
+        let big = solid("big", TextureSize::Size128x128, Color15::new(31, 0, 0));
+        let small = solid("small", TextureSize::Size64x64, Color15::new(0, 31, 0));
+        let atlas = build_atlas("atlas", &[&big, &small]).unwrap();
+
+        let mut parts = vec![MeshPart::cube("a", 1024.0), MeshPart::cube("b", 1024.0)];
+        parts[0].texture_ref = TextureRef::Id(small.id);
+        parts[1].texture_ref = TextureRef::Checkerboard;
+        let before: Vec<(f32, f32)> = parts[0].mesh.vertices.iter().map(|v| (v.uv.x, v.uv.y)).collect();
+        assert_eq!(remap_parts(&mut parts, &atlas), 1);
+
+        assert!(matches!(parts[0].texture_ref, TextureRef::Id(id) if id == atlas.texture.id));
+        assert!(matches!(parts[1].texture_ref, TextureRef::Checkerboard));
+        // The small texture sits right of the big one: u lands in 128..192 texels
+        for (vertex, (u, v)) in parts[0].mesh.vertices.iter().zip(before) {
+            let expected_u = (128.0 + (u.clamp(0.0, 1.0) * 64.0).min(63.5)) / 256.0;
+            let expected_v = (v.clamp(0.0, 1.0) * 64.0).min(63.5) / 256.0;
+            assert!((vertex.uv.x - expected_u).abs() < 1e-6);
+            assert!((vertex.uv.y - expected_v).abs() < 1e-6);
+            assert!(vertex.uv.x >= 0.5 && vertex.uv.x < 0.75);
+        }
+    }
+}
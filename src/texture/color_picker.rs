@@ -0,0 +1,221 @@
+//! CLUT color picker helpers
+//!
+//! Hex entry, 5-bit quantization, HSV conversion and the recently-used swatch
+//! list behind the palette panel's color picker. Colors are handled as RGB555
+//! (0-31 per channel), the format CLUT entries are stored in, so whatever the
+//! picker shows is exactly what ends up in the palette.
+
+use macroquad::prelude::*;
+
+/// Recently-used swatches kept by the palette panel
+pub const MAX_RECENT_COLORS: usize = 16;
+
+/// Expand a 5-bit channel to 8 bits (same expansion as `Color15`)
+pub fn expand_5bit(v: u8) -> u8 {
+    let v = v.min(31);
+    (v << 3) | (v >> 2)
+}
+
+/// Quantize an 8-bit channel to the nearest 5-bit value
+pub fn quantize_5bit(v: u8) -> u8 {
+    (0..32u8)
+        .min_by_key(|&q| (expand_5bit(q) as i16 - v as i16).abs())
+        .unwrap_or(0)
+}
+
+/// Quantize an 8-bit RGB triple to RGB555
+pub fn quantize_rgb(rgb: (u8, u8, u8)) -> (u8, u8, u8) {
+    (quantize_5bit(rgb.0), quantize_5bit(rgb.1), quantize_5bit(rgb.2))
+}
+
+/// Parse `#RRGGBB`, `RRGGBB`, `#RGB` or `RGB` into 8-bit RGB
+pub fn parse_hex(text: &str) -> Option<(u8, u8, u8)> {
+    let s = text.trim();
+    let s = s.strip_prefix('#').unwrap_or(s);
+    if !s.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize, len: usize| u8::from_str_radix(&s[i * len..(i + 1) * len], 16).ok();
+    match s.len() {
+        6 => Some((channel(0, 2)?, channel(1, 2)?, channel(2, 2)?)),
+        3 => Some((channel(0, 1)? * 17, channel(1, 1)? * 17, channel(2, 1)? * 17)),
+        _ => None,
+    }
+}
+
+/// Format an RGB555 color as `#RRGGBB` using the expanded 8-bit values
+pub fn hex_string(rgb5: (u8, u8, u8)) -> String {
+    format!(
+        "#{:02X}{:02X}{:02X}",
+        expand_5bit(rgb5.0),
+        expand_5bit(rgb5.1),
+        expand_5bit(rgb5.2)
+    )
+}
+
+/// Convert RGB555 to HSV (hue in degrees, saturation and value 0-1)
+pub fn rgb5_to_hsv(rgb5: (u8, u8, u8)) -> (f32, f32, f32) {
+    let r = rgb5.0.min(31) as f32 / 31.0;
+    let g = rgb5.1.min(31) as f32 / 31.0;
+    let b = rgb5.2.min(31) as f32 / 31.0;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let d = max - min;
+
+    let s = if max > 0.0 { d / max } else { 0.0 };
+    if d < 0.0001 {
+        return (0.0, s, max);
+    }
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h * 60.0, s, max)
+}
+
+/// Convert HSV (hue in degrees, saturation and value 0-1) to RGB555
+pub fn hsv_to_rgb5(h: f32, s: f32, v: f32) -> (u8, u8, u8) {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let s = s.clamp(0.0, 1.0);
+    let v = v.clamp(0.0, 1.0);
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    let to5 = |f: f32| ((f + m) * 31.0).round().clamp(0.0, 31.0) as u8;
+    (to5(r), to5(g), to5(b))
+}
+
+/// Move `rgb5` to the front of the recent list, dropping duplicates and
+/// anything past `MAX_RECENT_COLORS`
+pub fn push_recent(recent: &mut Vec<(u8, u8, u8)>, rgb5: (u8, u8, u8)) {
+    recent.retain(|&c| c != rgb5);
+    recent.insert(0, rgb5);
+    recent.truncate(MAX_RECENT_COLORS);
+}
+
+/// macroquad color for an RGB555 triple
+pub fn rgb5_color(rgb5: (u8, u8, u8)) -> Color {
+    Color::from_rgba(expand_5bit(rgb5.0), expand_5bit(rgb5.1), expand_5bit(rgb5.2), 255)
+}
+
+/// Hue/saturation under a point of the wheel, or None outside it
+///
+/// Points slightly past the rim clamp to full saturation so dragging along the
+/// edge doesn't drop out.
+pub fn wheel_pick(cx: f32, cy: f32, radius: f32, x: f32, y: f32, clamp: bool) -> Option<(f32, f32)> {
+    let dx = x - cx;
+    let dy = y - cy;
+    let dist = (dx * dx + dy * dy).sqrt();
+    if dist > radius && !clamp {
+        return None;
+    }
+    let hue = dy.atan2(dx).to_degrees().rem_euclid(360.0);
+    Some((hue, (dist / radius).min(1.0)))
+}
+
+/// Draw the hue/saturation wheel at the given value, quantized to RGB555
+///
+/// Cells are drawn in the quantized color so the wheel shows the banding the
+/// CLUT will actually produce.
+pub fn draw_hsv_wheel(cx: f32, cy: f32, radius: f32, value: f32, marker: (f32, f32)) {
+    let cell = 3.0;
+    let steps = (radius * 2.0 / cell).ceil() as i32;
+    for iy in 0..steps {
+        for ix in 0..steps {
+            let px = cx - radius + (ix as f32 + 0.5) * cell;
+            let py = cy - radius + (iy as f32 + 0.5) * cell;
+            if let Some((h, s)) = wheel_pick(cx, cy, radius, px, py, false) {
+                let color = rgb5_color(hsv_to_rgb5(h, s, value));
+                draw_rectangle(px - cell * 0.5, py - cell * 0.5, cell, cell, color);
+            }
+        }
+    }
+    draw_circle_lines(cx, cy, radius, 1.0, Color::new(0.3, 0.3, 0.32, 1.0));
+
+    let (h, s) = marker;
+    let angle = h.to_radians();
+    let mx = cx + angle.cos() * s * radius;
+    let my = cy + angle.sin() * s * radius;
+    draw_circle_lines(mx, my, 3.0, 1.0, BLACK);
+    draw_circle_lines(mx, my, 4.0, 1.0, WHITE);
+}
+
+/// Draw a vertical value (brightness) bar for the given hue/saturation
+pub fn draw_value_bar(x: f32, y: f32, w: f32, h: f32, hue: f32, sat: f32, value: f32) {
+    let bands = 32;
+    let band_h = h / bands as f32;
+    for i in 0..bands {
+        let v = 1.0 - i as f32 / (bands - 1) as f32;
+        let color = rgb5_color(hsv_to_rgb5(hue, sat, v));
+        draw_rectangle(x, y + i as f32 * band_h, w, band_h + 0.5, color);
+    }
+    draw_rectangle_lines(x, y, w, h, 1.0, Color::new(0.3, 0.3, 0.32, 1.0));
+    let marker_y = y + (1.0 - value.clamp(0.0, 1.0)) * h;
+    draw_rectangle(x - 1.0, marker_y - 1.0, w + 2.0, 3.0, WHITE);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_forms() {
+        assert_eq!(parse_hex("#FF8000"), Some((255, 128, 0)));
+        assert_eq!(parse_hex("ff8000"), Some((255, 128, 0)));
+        assert_eq!(parse_hex(" #f80 "), Some((255, 136, 0)));
+        assert_eq!(parse_hex("#FF80"), None);
+        assert_eq!(parse_hex("#GG0000"), None);
+        assert_eq!(parse_hex(""), None);
+    }
+
+    #[test]
+    fn quantization_round_trips_5bit_values() {
+        for v in 0..32u8 {
+            assert_eq!(quantize_5bit(expand_5bit(v)), v);
+        }
+        assert_eq!(quantize_5bit(0), 0);
+        assert_eq!(quantize_5bit(255), 31);
+        assert_eq!(quantize_rgb((255, 128, 0)), (31, 16, 0));
+    }
+
+    #[test]
+    fn hex_string_matches_expansion() {
+        assert_eq!(hex_string((31, 0, 16)), "#FF0084");
+        let rgb5 = (12, 25, 3);
+        assert_eq!(parse_hex(&hex_string(rgb5)).map(quantize_rgb), Some(rgb5));
+    }
+
+    #[test]
+    fn hsv_round_trips_rgb555() {
+        for &rgb5 in &[(31, 0, 0), (0, 31, 0), (0, 0, 31), (12, 25, 3), (7, 7, 7), (31, 31, 31), (0, 0, 0)] {
+            let (h, s, v) = rgb5_to_hsv(rgb5);
+            assert_eq!(hsv_to_rgb5(h, s, v), rgb5);
+        }
+    }
+
+    #[test]
+    fn recent_colors_dedupe_and_cap() {
+        let mut recent = Vec::new();
+        push_recent(&mut recent, (1, 2, 3));
+        push_recent(&mut recent, (4, 5, 6));
+        push_recent(&mut recent, (1, 2, 3));
+        assert_eq!(recent, vec![(1, 2, 3), (4, 5, 6)]);
+
+        for i in 0..40u8 {
+            push_recent(&mut recent, (i % 32, 0, 0));
+        }
+        assert_eq!(recent.len(), MAX_RECENT_COLORS);
+        assert_eq!(recent[0], (39 % 32, 0, 0));
+    }
+}
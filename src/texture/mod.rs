@@ -22,23 +22,45 @@
 //! - CLUT depth (4-bit/16 colors or 8-bit/256 colors)
 //! - Palette indices for each pixel
 //! - RGB555 color palette
+//! - Optional flipbook animation (extra frames of indices + frame rate)
+//! - Optional paint layers (the pixels stay composited, so only the editor
+//!   reads them)
+//!
+//! Palettes can also be imported from and exported to GIMP `.gpl`, JASC `.pal`
+//! and lospec hex lists (see `palette_file`).
+//!
+//! Several textures can be packed into one 256x256 atlas with a merged palette,
+//! and the mesh parts using them remapped onto it (see `atlas`).
+//!
+//! Level faces reference textures by name, so animated textures play back
+//! wherever they are used without any change to the level format.
 
 mod user_texture;
 mod texture_library;
 mod texture_editor;
+mod color_picker;
 mod import;
+mod palette_file;
+mod atlas;
+mod reference;
 
-pub use user_texture::{UserTexture, TextureSize, generate_texture_id};
+pub use user_texture::{UserTexture, TextureSize, TextureAnimation, TextureLayer, generate_texture_id};
 pub use texture_library::{
-    TextureLibrary, TextureSource,
+    TextureLibrary, TextureSource, USER_TEXTURES_DIR,
 };
+#[cfg(feature = "player")]
+pub use texture_library::SAMPLES_TEXTURES_DIR;
 pub use texture_editor::{
-    TextureEditorState,
+    TextureEditorState, ClipboardData,
     TextureEditorMode, UvModalTransform, UvOperation, UvTool,
     UvOverlayData, UvVertex, UvFace,
     draw_texture_canvas, draw_tool_panel, draw_palette_panel, draw_palette_panel_constrained,
-    draw_mode_tabs,
-    ImportAction, draw_import_dialog,
+    draw_mode_tabs, draw_frame_strip, apply_frame_action, FRAME_STRIP_HEIGHT,
+    draw_layer_strip, apply_layer_action, LAYER_STRIP_HEIGHT,
+    apply_wrap_shift,
+    ImportAction, draw_import_dialog, draw_palette_import_dialog,
 };
+pub use palette_file::{apply_palette, handle_palette_file_request};
+pub use atlas::{build_atlas, remap_parts};
 pub use import::load_png_to_import_state;
 // Re-export quantization types from modeler for use with TextureImportState
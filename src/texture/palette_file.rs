@@ -0,0 +1,451 @@
+//! Palette file import/export
+//!
+//! Reads and writes the common palette interchange formats so palettes made
+//! in other tools (or downloaded from lospec) can be used on UserTextures:
+//! - GIMP `.gpl` (`GIMP Palette` header, `R G B name` rows)
+//! - JASC `.pal` (Paint Shop Pro / Aseprite, `JASC-PAL` header)
+//! - Hex lists (`.hex` / `.txt`, one `RRGGBB` per line, as exported by lospec)
+//!
+//! Palettes with more colors than the texture's CLUT has room for are reduced
+//! with the same median-cut quantizer used by PNG import.
+
+use crate::modeler::{quantize_image_with_options, QuantizeMode, QuantizeOptions};
+use crate::rasterizer::{ClutDepth, Color15};
+use super::user_texture::UserTexture;
+
+/// Supported palette file formats
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaletteFormat {
+    /// GIMP palette (.gpl)
+    Gpl,
+    /// JASC-PAL palette (.pal)
+    JascPal,
+    /// Plain hex list, one RRGGBB per line (.hex)
+    Hex,
+}
+
+impl PaletteFormat {
+    pub const ALL: &'static [PaletteFormat] = &[
+        PaletteFormat::Gpl,
+        PaletteFormat::JascPal,
+        PaletteFormat::Hex,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaletteFormat::Gpl => "GIMP (.gpl)",
+            PaletteFormat::JascPal => "JASC (.pal)",
+            PaletteFormat::Hex => "Hex (.hex)",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PaletteFormat::Gpl => "gpl",
+            PaletteFormat::JascPal => "pal",
+            PaletteFormat::Hex => "hex",
+        }
+    }
+
+    /// Pick the format from a file name's extension (anything unknown is a hex list)
+    pub fn from_file_name(name: &str) -> Self {
+        let ext = name.rsplit('.').next().unwrap_or("").to_ascii_lowercase();
+        match ext.as_str() {
+            "gpl" => PaletteFormat::Gpl,
+            "pal" => PaletteFormat::JascPal,
+            _ => PaletteFormat::Hex,
+        }
+    }
+
+    /// Detect the format from file contents
+    pub fn detect(text: &str) -> Self {
+        let first = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+        if first.starts_with("GIMP Palette") {
+            PaletteFormat::Gpl
+        } else if first.starts_with("JASC-PAL") {
+            PaletteFormat::JascPal
+        } else {
+            PaletteFormat::Hex
+        }
+    }
+}
+
+/// Parse a palette file, detecting the format from its contents
+///
+/// Returns 8-bit RGB colors in file order.
+pub fn parse_palette(text: &str) -> Result<Vec<(u8, u8, u8)>, String> {
+    let colors = match PaletteFormat::detect(text) {
+        PaletteFormat::Gpl => parse_gpl(text)?,
+        PaletteFormat::JascPal => parse_jasc(text)?,
+        PaletteFormat::Hex => parse_hex(text)?,
+    };
+    if colors.is_empty() {
+        return Err("Palette has no colors".to_string());
+    }
+    Ok(colors)
+}
+
+fn parse_gpl(text: &str) -> Result<Vec<(u8, u8, u8)>, String> {
+    let mut colors = Vec::new();
+    for (line_no, line) in text.lines().enumerate().skip(1) {
+        let line = line.trim();
+        if line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with("Name:")
+            || line.starts_with("Columns:")
+        {
+            continue;
+        }
+        // "R G B  optional name"
+        let mut parts = line.split_whitespace();
+        let mut channel = || -> Result<u8, String> {
+            parts.next()
+                .and_then(|p| p.parse::<u8>().ok())
+                .ok_or_else(|| format!("Line {}: expected 'R G B'", line_no + 1))
+        };
+        colors.push((channel()?, channel()?, channel()?));
+    }
+    Ok(colors)
+}
+
+fn parse_jasc(text: &str) -> Result<Vec<(u8, u8, u8)>, String> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+    lines.next(); // JASC-PAL
+    lines.next(); // version (0100)
+    let count: usize = lines.next()
+        .and_then(|l| l.parse().ok())
+        .ok_or("Missing color count")?;
+
+    let mut colors = Vec::with_capacity(count);
+    for line in lines.take(count) {
+        let rgb: Vec<u8> = line.split_whitespace().filter_map(|p| p.parse().ok()).collect();
+        if rgb.len() < 3 {
+            return Err(format!("Bad color entry '{}'", line));
+        }
+        colors.push((rgb[0], rgb[1], rgb[2]));
+    }
+    if colors.len() < count {
+        return Err(format!("Expected {} colors, found {}", count, colors.len()));
+    }
+    Ok(colors)
+}
+
+fn parse_hex(text: &str) -> Result<Vec<(u8, u8, u8)>, String> {
+    let mut colors = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        // ';' comments come from paint.net palettes, which are otherwise hex lists
+        if line.is_empty() || line.starts_with(';') || line.starts_with("//") {
+            continue;
+        }
+        let hex = line.trim_start_matches('#');
+        // paint.net writes AARRGGBB; the alpha is dropped
+        let hex = if hex.len() == 8 { &hex[2..] } else { hex };
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Not a hex color: '{}'", line));
+        }
+        let v = u32::from_str_radix(hex, 16).map_err(|e| e.to_string())?;
+        colors.push(((v >> 16) as u8, (v >> 8) as u8, v as u8));
+    }
+    Ok(colors)
+}
+
+/// Write a texture palette in the given format
+///
+/// Index 0 (the transparent color key) is not written.
+pub fn write_palette(palette: &[Color15], format: PaletteFormat, name: &str) -> String {
+    let colors: Vec<[u8; 3]> = palette.iter().skip(1).map(|c| [c.r8(), c.g8(), c.b8()]).collect();
+    let mut out = String::new();
+    match format {
+        PaletteFormat::Gpl => {
+            out.push_str("GIMP Palette\n");
+            out.push_str(&format!("Name: {}\n", name));
+            out.push_str("Columns: 16\n#\n");
+            for [r, g, b] in &colors {
+                out.push_str(&format!("{:3} {:3} {:3}\t#{:02X}{:02X}{:02X}\n", r, g, b, r, g, b));
+            }
+        }
+        PaletteFormat::JascPal => {
+            // JASC readers expect CRLF line endings
+            out.push_str("JASC-PAL\r\n0100\r\n");
+            out.push_str(&format!("{}\r\n", colors.len()));
+            for [r, g, b] in &colors {
+                out.push_str(&format!("{} {} {}\r\n", r, g, b));
+            }
+        }
+        PaletteFormat::Hex => {
+            for [r, g, b] in &colors {
+                out.push_str(&format!("{:02x}{:02x}{:02x}\n", r, g, b));
+            }
+        }
+    }
+    out
+}
+
+/// Fit an imported palette into a CLUT of the given depth
+///
+/// Returns at most `color_count - 1` colors (index 0 stays transparent).
+/// Colors that collapse to the same RGB555 value are merged; if there are
+/// still too many, the palette is reduced with median cut.
+pub fn fit_palette(colors: &[(u8, u8, u8)], depth: ClutDepth, opts: &QuantizeOptions) -> Vec<Color15> {
+    let slots = depth.color_count() - 1;
+
+    let mut unique: Vec<Color15> = Vec::with_capacity(colors.len());
+    for &(r, g, b) in colors {
+        let c = drawable(Color15::from_rgb888(r, g, b));
+        if !unique.contains(&c) {
+            unique.push(c);
+        }
+    }
+    if unique.len() <= slots {
+        return unique;
+    }
+
+    // Quantize the palette as a 1-pixel-high image, one pixel per color
+    let rgba: Vec<u8> = colors.iter().flat_map(|&(r, g, b)| [r, g, b, 255]).collect();
+    let result = quantize_image_with_options(&rgba, colors.len(), 1, depth, "palette", opts);
+    result.clut.colors.iter().skip(1).take(slots).map(|&c| drawable(c)).collect()
+}
+
+/// Pure black is the transparent color key in RGB555, so imported black
+/// gets the STP bit to stay visible
+fn drawable(c: Color15) -> Color15 {
+    if c.is_transparent() { Color15::BLACK_DRAWABLE } else { c }
+}
+
+/// Write fitted colors into a texture's palette starting at index 1
+///
+/// With `remap_pixels`, every pixel (in all animation frames and layers) is
+/// moved to the nearest new color so the image keeps its look; otherwise
+/// indices are left alone and the texture is simply recolored. Slots past the imported colors
+/// keep their current colors.
+pub fn apply_palette(texture: &mut UserTexture, colors: &[Color15], remap_pixels: bool) {
+    if colors.is_empty() {
+        return;
+    }
+
+    if remap_pixels {
+        let mut remap = [0u8; 256];
+        for (old, slot) in texture.palette.iter().zip(remap.iter_mut()).skip(1) {
+            *slot = nearest_color(*old, colors) as u8 + 1;
+        }
+        for idx in &mut texture.indices {
+            if *idx != 0 {
+                *idx = remap[*idx as usize];
+            }
+        }
+        let frames = texture.animation.iter_mut().flat_map(|a| a.frames.iter_mut());
+        let layers = texture.layers.iter_mut().map(|l| &mut l.indices);
+        for idx in frames.chain(layers).flatten() {
+            if *idx != 0 {
+                *idx = remap[*idx as usize];
+            }
+        }
+    }
+
+    for (slot, color) in texture.palette.iter_mut().skip(1).zip(colors) {
+        *slot = *color;
+    }
+}
+
+/// Index of the closest color by squared RGB555 distance
+fn nearest_color(color: Color15, palette: &[Color15]) -> usize {
+    let dist = |c: &Color15| {
+        let dr = color.r5() as i32 - c.r5() as i32;
+        let dg = color.g5() as i32 - c.g5() as i32;
+        let db = color.b5() as i32 - c.b5() as i32;
+        dr * dr + dg * dg + db * db
+    };
+    palette.iter().enumerate().min_by_key(|(_, c)| dist(c)).map(|(i, _)| i).unwrap_or(0)
+}
+
+/// What the palette panel wants the caller to do (file dialogs live outside the panel)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteFileRequest {
+    Import,
+    Export,
+}
+
+/// State for the palette import dialog
+#[derive(Debug)]
+pub struct PaletteImportState {
+    /// Whether the import dialog is open
+    pub active: bool,
+    /// File name the palette came from
+    pub file_name: String,
+    /// Detected file format
+    pub format: PaletteFormat,
+    /// Colors as read from the file
+    pub source: Vec<(u8, u8, u8)>,
+    /// CLUT depth of the texture being edited
+    pub depth: ClutDepth,
+    /// Quantization mode (only used when the file has too many colors)
+    pub quantize_mode: QuantizeMode,
+    /// Use LAB color space when quantizing
+    pub use_lab: bool,
+    /// Move pixels to the nearest new color instead of keeping their indices
+    pub remap_pixels: bool,
+    /// Fitted palette (index 1 onward)
+    pub preview: Vec<Color15>,
+    /// Whether the preview needs to be recomputed
+    pub preview_dirty: bool,
+}
+
+impl Default for PaletteImportState {
+    fn default() -> Self {
+        Self {
+            active: false,
+            file_name: String::new(),
+            format: PaletteFormat::Hex,
+            source: Vec::new(),
+            depth: ClutDepth::default(),
+            quantize_mode: QuantizeMode::default(),
+            use_lab: false,
+            remap_pixels: false,
+            preview: Vec::new(),
+            preview_dirty: false,
+        }
+    }
+}
+
+impl PaletteImportState {
+    /// Reset the import state
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Open the dialog with a parsed palette file
+    pub fn open(&mut self, file_name: &str, text: &str, depth: ClutDepth) -> Result<(), String> {
+        let source = parse_palette(text)?;
+        self.reset();
+        self.active = true;
+        self.file_name = file_name.to_string();
+        self.format = PaletteFormat::detect(text);
+        self.source = source;
+        self.depth = depth;
+        self.preview_dirty = true;
+        Ok(())
+    }
+
+    /// Whether the file has more colors than the CLUT can hold
+    pub fn needs_quantize(&self) -> bool {
+        self.source.len() >= self.depth.color_count()
+    }
+
+    /// Recompute the fitted palette if settings changed
+    pub fn update_preview(&mut self) {
+        if !self.preview_dirty {
+            return;
+        }
+        let opts = QuantizeOptions {
+            use_lab: self.use_lab,
+            ..QuantizeOptions::from_mode(self.quantize_mode)
+        };
+        self.preview = fit_palette(&self.source, self.depth, &opts);
+        self.preview_dirty = false;
+    }
+}
+
+/// Run a palette file request from the palette panel (file dialogs are native only)
+pub fn handle_palette_file_request(
+    request: PaletteFileRequest,
+    texture: &UserTexture,
+    import_state: &mut PaletteImportState,
+) -> Result<Option<String>, String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        match request {
+            PaletteFileRequest::Import => {
+                let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Palettes", &["gpl", "pal", "hex", "txt"])
+                    .pick_file()
+                else {
+                    return Ok(None);
+                };
+                let text = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read palette: {}", e))?;
+                let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                import_state.open(&file_name, &text, texture.depth)?;
+                Ok(None)
+            }
+            PaletteFileRequest::Export => {
+                let mut dialog = rfd::FileDialog::new().set_file_name(format!("{}.gpl", texture.name));
+                for format in PaletteFormat::ALL {
+                    dialog = dialog.add_filter(format.label(), &[format.extension()]);
+                }
+                let Some(path) = dialog.save_file() else {
+                    return Ok(None);
+                };
+                let file_name = path.to_string_lossy().to_string();
+                let format = PaletteFormat::from_file_name(&file_name);
+                let text = write_palette(&texture.palette, format, &texture.name);
+                std::fs::write(&path, text).map_err(|e| format!("Failed to write palette: {}", e))?;
+                Ok(Some(format!("Exported palette as {}", format.label())))
+            }
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = (request, texture, import_state);
+        Err("Palette files not yet available in browser".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gpl() {
+        let text = "GIMP Palette\nName: Test\nColumns: 4\n#\n255   0   0\tRed\n  0 255   0\n";
+        assert_eq!(PaletteFormat::detect(text), PaletteFormat::Gpl);
+        assert_eq!(parse_palette(text).unwrap(), vec![(255, 0, 0), (0, 255, 0)]);
+    }
+
+    #[test]
+    fn test_parse_jasc() {
+        let text = "JASC-PAL\r\n0100\r\n2\r\n0 0 255\r\n10 20 30\r\n";
+        assert_eq!(parse_palette(text).unwrap(), vec![(0, 0, 255), (10, 20, 30)]);
+        assert!(parse_palette("JASC-PAL\n0100\n3\n1 2 3\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        let text = "; paint.net palette\nff0000\n#00ff00\nFF0000FF\n";
+        assert_eq!(parse_palette(text).unwrap(), vec![(255, 0, 0), (0, 255, 0), (0, 0, 255)]);
+        assert!(parse_palette("not a color").is_err());
+        assert!(parse_palette("").is_err());
+    }
+
+    #[test]
+    fn test_export_roundtrip() {
+        let palette = vec![Color15::TRANSPARENT, Color15::new(31, 0, 0), Color15::new(0, 16, 31)];
+        for format in PaletteFormat::ALL {
+            let text = write_palette(&palette, *format, "test");
+            assert_eq!(PaletteFormat::detect(&text), *format);
+            let fitted = fit_palette(&parse_palette(&text).unwrap(), ClutDepth::Bpp4, &QuantizeOptions::default());
+            assert_eq!(fitted, palette[1..].to_vec());
+        }
+    }
+
+    #[test]
+    fn test_fit_large_palette() {
+        let colors: Vec<(u8, u8, u8)> = (0..32u8).map(|i| (i * 8, 255 - i * 8, 128)).collect();
+        let fitted = fit_palette(&colors, ClutDepth::Bpp4, &QuantizeOptions::default());
+        assert!(!fitted.is_empty() && fitted.len() <= 15);
+        assert!(fitted.iter().all(|c| !c.is_transparent()));
+        assert_eq!(fit_palette(&colors, ClutDepth::Bpp8, &QuantizeOptions::default()).len(), 32);
+    }
+
+    #[test]
+    fn test_apply_palette_remap() {
+        let mut tex = UserTexture::new("t", crate::texture::TextureSize::Size8x8, ClutDepth::Bpp4);
+        tex.palette[1] = Color15::new(31, 0, 0);
+        tex.indices[0] = 1;
+        let colors = [Color15::new(0, 0, 31), Color15::new(30, 1, 0)];
+        apply_palette(&mut tex, &colors, true);
+        assert_eq!(tex.indices[0], 2);
+        assert_eq!(tex.indices[1], 0);
+        assert_eq!(tex.palette[1], colors[0]);
+    }
+}
@@ -0,0 +1,203 @@
+//! Reference image for the texture editor
+//!
+//! A PNG shown under (or over) the canvas to paint from. It never touches
+//! the texture's indices or palette: it's only drawn, at an adjustable
+//! opacity. While unlocked it can be dragged into place and scaled with the
+//! scroll wheel; locking it hands the canvas back to the paint tools.
+
+use macroquad::prelude::*;
+
+/// Opacity step of the panel's -/+ buttons
+pub const OPACITY_STEP: f32 = 0.1;
+
+/// Largest side accepted (it becomes one GPU texture)
+const MAX_SIZE: u32 = 4096;
+
+/// A loaded reference image and how it's placed over the texture
+#[derive(Debug)]
+pub struct ReferenceImage {
+    /// File name (for the status line)
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    rgba: Vec<u8>,
+    /// GPU copy, made the first time it's drawn
+    texture: Option<Texture2D>,
+    /// 0 = invisible, 1 = opaque
+    pub opacity: f32,
+    pub visible: bool,
+    /// Drawn over the pixels instead of under them
+    pub above: bool,
+    /// Locked images can't be moved, so the canvas paints as usual
+    pub locked: bool,
+    /// Top-left corner relative to the texture's (in texture pixels)
+    pub offset: (f32, f32),
+    /// Size relative to fitting the texture (1 = fits inside it)
+    pub scale: f32,
+    /// Drag in progress: mouse position and offset when it started
+    drag: Option<((f32, f32), (f32, f32))>,
+}
+
+impl ReferenceImage {
+    /// Decode a PNG (or any format the image crate reads)
+    pub fn from_bytes(name: &str, bytes: &[u8]) -> Result<Self, String> {
+        let image = image::load_from_memory(bytes)
+            .map_err(|e| format!("Failed to decode image: {}", e))?
+            .to_rgba8();
+        if image.width() > MAX_SIZE || image.height() > MAX_SIZE {
+            return Err(format!("Reference image is larger than {}x{}", MAX_SIZE, MAX_SIZE));
+        }
+        Ok(Self::from_rgba(name, image.width() as usize, image.height() as usize, image.into_raw()))
+    }
+
+    fn from_rgba(name: &str, width: usize, height: usize, rgba: Vec<u8>) -> Self {
+        Self {
+            name: name.to_string(),
+            width: width.max(1),
+            height: height.max(1),
+            rgba,
+            texture: None,
+            opacity: 0.5,
+            visible: true,
+            above: false,
+            locked: false,
+            offset: (0.0, 0.0),
+            scale: 1.0,
+            drag: None,
+        }
+    }
+
+    /// Where it lands over a `tex_width` x `tex_height` texture, in texture
+    /// pixels: (x, y, width, height)
+    pub fn placement(&self, tex_width: usize, tex_height: usize) -> (f32, f32, f32, f32) {
+        let fit = (tex_width as f32 / self.width as f32).min(tex_height as f32 / self.height as f32);
+        let scale = fit * self.scale;
+        (self.offset.0, self.offset.1, self.width as f32 * scale, self.height as f32 * scale)
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32) {
+        // Rounded so repeated steps land on whole percents
+        self.opacity = (opacity.clamp(0.0, 1.0) * 100.0).round() / 100.0;
+    }
+
+    /// Drawn at all right now?
+    pub fn shown(&self) -> bool {
+        self.visible && self.opacity > 0.0
+    }
+
+    /// Does it take the canvas's mouse input (it's unlocked and shown)?
+    pub fn movable(&self) -> bool {
+        !self.locked && self.shown()
+    }
+
+    /// Left-drag moves it (the mouse at `mouse`, the canvas at `zoom`)
+    pub fn drag(&mut self, mouse: (f32, f32), pressed: bool, down: bool, zoom: f32) {
+        if pressed {
+            self.drag = Some((mouse, self.offset));
+        }
+        match self.drag {
+            Some((start, offset)) if down => {
+                let zoom = zoom.max(0.01);
+                self.offset = (offset.0 + (mouse.0 - start.0) / zoom, offset.1 + (mouse.1 - start.1) / zoom);
+            }
+            _ => self.drag = None,
+        }
+    }
+
+    /// Scale it by `factor` about a point given in texture pixels, so that
+    /// point stays put under the mouse
+    pub fn zoom_about(&mut self, factor: f32, point: (f32, f32)) {
+        let old = self.scale;
+        self.scale = (self.scale * factor).clamp(0.05, 20.0);
+        let ratio = self.scale / old;
+        self.offset = (
+            point.0 + (self.offset.0 - point.0) * ratio,
+            point.1 + (self.offset.1 - point.1) * ratio,
+        );
+    }
+
+    /// Draw it with the texture's top-left at (`tex_x`, `tex_y`) on screen
+    pub fn draw(&mut self, tex_x: f32, tex_y: f32, zoom: f32, tex_width: usize, tex_height: usize) {
+        if !self.shown() {
+            return;
+        }
+        // Before `texture` below borrows self mutably
+        let (x, y, w, h) = self.placement(tex_width, tex_height);
+        let (width, height, rgba) = (self.width, self.height, &self.rgba);
+        let texture = self.texture.get_or_insert_with(|| {
+            let texture = Texture2D::from_rgba8(width as u16, height as u16, rgba);
+            texture.set_filter(FilterMode::Nearest);
+            texture
+        });
+        draw_texture_ex(
+            texture,
+            tex_x + x * zoom,
+            tex_y + y * zoom,
+            Color::new(1.0, 1.0, 1.0, self.opacity),
+            DrawTextureParams { dest_size: Some(vec2(w * zoom, h * zoom)), ..Default::default() },
+        );
+    }
+}
+
+/// Ask for an image file and load it (file dialogs are native only)
+pub fn pick_reference_image() -> Result<Option<ReferenceImage>, String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Images", &["png", "jpg", "jpeg", "bmp"])
+            .pick_file()
+        else {
+            return Ok(None);
+        };
+        let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read file: {}", e))?;
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        ReferenceImage::from_bytes(&name, &bytes).map(Some)
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        Err("Reference images not yet available in browser".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(width: usize, height: usize) -> ReferenceImage {
+        ReferenceImage::from_rgba("ref.png", width, height, vec![255; width * height * 4])
+    }
+
+    #[test]
+    fn test_placement_fits_texture() {
+        // A wide image fits the texture's width
+        let mut image = reference(256, 128);
+        assert_eq!(image.placement(64, 64), (0.0, 0.0, 64.0, 32.0));
+        image.scale = 2.0;
+        image.offset = (-8.0, 4.0);
+        assert_eq!(image.placement(64, 64), (-8.0, 4.0, 128.0, 64.0));
+        assert!(ReferenceImage::from_bytes("junk.png", b"not an image").is_err());
+    }
+
+    #[test]
+    fn test_drag_and_zoom() {
+        let mut image = reference(64, 64);
+        image.drag((100.0, 100.0), true, true, 4.0);
+        image.drag((140.0, 80.0), false, true, 4.0);
+        assert_eq!(image.offset, (10.0, -5.0));
+        // Releasing ends the drag; later moves don't shift it
+        image.drag((140.0, 80.0), false, false, 4.0);
+        image.drag((200.0, 200.0), false, true, 4.0);
+        assert_eq!(image.offset, (10.0, -5.0));
+
+        // Zooming about its own corner keeps the corner in place
+        image.zoom_about(2.0, (10.0, -5.0));
+        assert_eq!((image.offset, image.scale), ((10.0, -5.0), 2.0));
+        image.zoom_about(0.5, (0.0, 0.0));
+        assert_eq!(image.offset, (5.0, -2.5));
+
+        image.set_opacity(1.5);
+        assert_eq!(image.opacity, 1.0);
+        image.locked = true;
+        assert!(!image.movable() && image.shown());
+    }
+}
@@ -4,13 +4,20 @@
 //! - Canvas with zoom/pan
 //! - Drawing tools (pencil, brush, fill, shapes)
 //! - UV editing with vertex manipulation
-//! - Palette editing with RGB555 sliders
+//! - Palette editing with RGB555 sliders, hex entry and an HSV wheel
+//! - Flipbook frames and paint layers
+//! - A reference image under or over the canvas (see `reference`)
 //! - Undo/redo support
 
 use macroquad::prelude::*;
 use crate::rasterizer::{BlendMode, ClutDepth, Color15, Vec2 as RastVec2};
-use crate::ui::{Rect, UiContext, icon};
-use super::user_texture::UserTexture;
+use crate::ui::{Rect, UiContext, icon, theme};
+use super::user_texture::{composite_layer_indices, UserTexture};
+use super::reference::{pick_reference_image, ReferenceImage, OPACITY_STEP};
+use super::color_picker::{
+    draw_hsv_wheel, draw_value_bar, hex_string, hsv_to_rgb5, parse_hex, push_recent,
+    quantize_rgb, rgb5_color, rgb5_to_hsv, wheel_pick, MAX_RECENT_COLORS,
+};
 
 /// Editor mode - Paint or UV editing
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -65,9 +72,6 @@ pub enum UvTool {
 }
 
 // UI constants
-const TEXT_COLOR: Color = Color::new(0.85, 0.85, 0.85, 1.0);
-const TEXT_DIM: Color = Color::new(0.55, 0.55, 0.55, 1.0);
-const ACCENT_COLOR: Color = Color::new(0.28, 0.51, 0.71, 1.0);
 const PANEL_BG: Color = Color::new(0.18, 0.18, 0.20, 1.0);
 
 /// Drawing tool types
@@ -91,6 +95,9 @@ pub enum DrawTool {
     Ellipse,
     /// Eyedropper - pick color from canvas
     Eyedropper,
+    /// Clone stamp - paint with pixels copied from an offset source (Alt+click
+    /// sets it), wrapping around the edges
+    CloneStamp,
 }
 
 /// Brush shape for the brush tool
@@ -366,6 +373,7 @@ impl DrawTool {
             DrawTool::Rectangle => icon::RECTANGLE_HORIZONTAL,
             DrawTool::Ellipse => icon::CIRCLE,
             DrawTool::Eyedropper => icon::PIPETTE,
+            DrawTool::CloneStamp => icon::STAMP,
         }
     }
 
@@ -380,12 +388,13 @@ impl DrawTool {
             DrawTool::Rectangle => "Rectangle (R)",
             DrawTool::Ellipse => "Ellipse (O)",
             DrawTool::Eyedropper => "Eyedropper (I)",
+            DrawTool::CloneStamp => "Clone Stamp (C, Alt+click sets source)",
         }
     }
 
     /// Whether this tool uses brush size
     pub fn uses_brush_size(&self) -> bool {
-        matches!(self, DrawTool::Brush | DrawTool::Line | DrawTool::Rectangle | DrawTool::Ellipse)
+        matches!(self, DrawTool::Brush | DrawTool::CloneStamp | DrawTool::Line | DrawTool::Rectangle | DrawTool::Ellipse)
     }
 
     /// Whether this tool is a shape tool (requires start/end points)
@@ -395,7 +404,7 @@ impl DrawTool {
 
     /// Whether this tool modifies the texture (requires undo save)
     pub fn modifies_texture(&self) -> bool {
-        matches!(self, DrawTool::Brush | DrawTool::Fill | DrawTool::Line | DrawTool::Rectangle | DrawTool::Ellipse)
+        matches!(self, DrawTool::Brush | DrawTool::CloneStamp | DrawTool::Fill | DrawTool::Line | DrawTool::Rectangle | DrawTool::Ellipse)
     }
 }
 
@@ -459,6 +468,10 @@ pub struct TextureEditorState {
     pub shape_start: Option<(i32, i32)>,
     /// Last drawn position (for line interpolation)
     pub last_draw_pos: Option<(i32, i32)>,
+    /// Clone stamp source pixel (set with Alt+click)
+    pub clone_source: Option<(i32, i32)>,
+    /// Clone stamp stroke in progress
+    pub clone_stroke: Option<CloneStroke>,
     /// Undo stack
     pub undo_stack: Vec<TextureUndoEntry>,
     /// Redo stack
@@ -491,6 +504,8 @@ pub struct TextureEditorState {
     pub show_grid: bool,
     /// Show tiling preview (8 copies around center for seamless texture editing)
     pub show_tiling: bool,
+    /// Highlight pixel columns/rows that meet with a hard edge (see `find_seams`)
+    pub show_seams: bool,
     /// Current selection (None = no selection)
     pub selection: Option<Selection>,
     /// Clipboard for copy/paste
@@ -522,6 +537,12 @@ pub struct TextureEditorState {
     pub palette_gen_hue_shift: f32,
     /// Which palette generator color is being edited (0-2), None if not editing
     pub palette_gen_editing: Option<usize>,
+    /// Hex entry field in the color picker (Some while typing)
+    pub hex_input: Option<crate::ui::TextInputState>,
+    /// Color picker hue/saturation/value, kept so hue survives greys and black
+    pub picker_hsv: (f32, f32, f32),
+    /// Recently used colors (RGB555), shared by every texture in this editor
+    pub recent_colors: Vec<(u8, u8, u8)>,
 
     // === UV Editing State ===
     /// Current editor mode (Paint or UV)
@@ -561,9 +582,31 @@ pub struct TextureEditorState {
     /// Signal to caller that auto-unwrap should be performed
     pub auto_unwrap_requested: bool,
 
+    // === Flipbook Animation State ===
+    /// Animation frame currently being edited (0 = base frame)
+    pub anim_frame: usize,
+    /// Show the previous frame ghosted over the canvas
+    pub onion_skin: bool,
+    /// Play the animation on the canvas
+    pub anim_playing: bool,
+
+    // === Paint Layers ===
+    /// Layer currently being painted (index into `UserTexture::layers`)
+    pub active_layer: usize,
+
+    /// Reference image to paint from (drawn only, never painted into)
+    pub reference: Option<ReferenceImage>,
+
+    /// Wrap-shift requested by arrow keys or the offset button (applied by caller)
+    pub wrap_shift_pending: Option<WrapShift>,
+
     // === Import State ===
     /// State for the texture import dialog
     pub import_state: super::import::TextureImportState,
+    /// Palette file import/export requested from the palette panel (handled by caller)
+    pub palette_file_request: Option<super::palette_file::PaletteFileRequest>,
+    /// State for the palette file import dialog
+    pub palette_import: super::palette_file::PaletteImportState,
 }
 
 /// Edge or corner being resized
@@ -592,6 +635,8 @@ impl Default for TextureEditorState {
             drawing: false,
             shape_start: None,
             last_draw_pos: None,
+            clone_source: None,
+            clone_stroke: None,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             max_undo: 50,
@@ -608,6 +653,7 @@ impl Default for TextureEditorState {
             contiguous_select: true,  // Contiguous selection by default
             show_grid: true, // Grid on by default
             show_tiling: false, // Tiling preview off by default
+            show_seams: false,
             selection: None,
             clipboard: None,
             selection_drag_start: None,
@@ -624,6 +670,9 @@ impl Default for TextureEditorState {
             palette_gen_brightness: 0.7,
             palette_gen_hue_shift: 10.0,
             palette_gen_editing: None,
+            hex_input: None,
+            picker_hsv: (0.0, 0.0, 0.0),
+            recent_colors: Vec::new(),
             // UV editing state
             mode: TextureEditorMode::Paint,
             uv_tool: UvTool::Move,
@@ -642,8 +691,17 @@ impl Default for TextureEditorState {
             uv_scale_original_bounds: (0.0, 0.0, 1.0, 1.0),
             uv_undo_pending: None,
             auto_unwrap_requested: false,
+            // Flipbook animation state
+            anim_frame: 0,
+            onion_skin: true,
+            anim_playing: false,
+            active_layer: 0,
+            reference: None,
+            wrap_shift_pending: None,
             // Import state
             import_state: super::import::TextureImportState::default(),
+            palette_file_request: None,
+            palette_import: super::palette_file::PaletteImportState::default(),
         }
     }
 }
@@ -688,6 +746,7 @@ impl TextureEditorState {
         self.selection_drag_start = None;
         self.creating_selection = false;
         self.palette_gen_editing = None;
+        self.hex_input = None;
         // UV state reset
         self.mode = TextureEditorMode::Paint;
         self.uv_selection.clear();
@@ -695,7 +754,11 @@ impl TextureEditorState {
         self.uv_box_select_start = None;
         self.uv_modal_transform = UvModalTransform::None;
         self.uv_modal_start_uvs.clear();
-        // Note: clipboard and palette_gen_colors are NOT reset - allow reuse across textures
+        // Animation state reset
+        self.anim_frame = 0;
+        self.anim_playing = false;
+        self.active_layer = 0;
+        // Note: clipboard, palette_gen_colors and the reference image are NOT reset - allow reuse across textures
     }
 
     /// Reset zoom and pan to fit texture in view
@@ -885,6 +948,39 @@ fn tex_draw_brush(texture: &mut UserTexture, cx: i32, cy: i32, size: u8, index:
     }
 }
 
+/// A clone stamp stroke: where it copies from and what
+#[derive(Debug, Clone)]
+pub struct CloneStroke {
+    /// Source position minus canvas position (pixels)
+    pub offset: (i32, i32),
+    /// Pixels as they were when the stroke began, so it never copies itself
+    pub source: Vec<u8>,
+}
+
+/// Paint one clone stamp dab: each pixel under the brush takes the source
+/// pixel at the stroke's offset. Sampling and painting both wrap around the
+/// edges, so a stroke carries on across the tiling seams.
+fn tex_clone_dab(texture: &mut UserTexture, cx: i32, cy: i32, size: u8, shape: BrushShape, stroke: &CloneStroke) {
+    let (w, h) = (texture.width as i32, texture.height as i32);
+    if w == 0 || h == 0 {
+        return;
+    }
+    let half = (size as i32 - 1) / 2;
+    for dy in 0..size as i32 {
+        for dx in 0..size as i32 {
+            let (ox, oy) = (dx - half, dy - half);
+            if shape == BrushShape::Circle && half > 0 && ox * ox + oy * oy > half * half {
+                continue;
+            }
+            let (x, y) = ((cx + ox).rem_euclid(w), (cy + oy).rem_euclid(h));
+            let (sx, sy) = ((x + stroke.offset.0).rem_euclid(w), (y + stroke.offset.1).rem_euclid(h));
+            if let Some(&index) = stroke.source.get((sy * w + sx) as usize) {
+                texture.set_index(x as usize, y as usize, index);
+            }
+        }
+    }
+}
+
 /// Flood fill using scanline algorithm
 fn flood_fill(texture: &mut UserTexture, start_x: i32, start_y: i32, fill_index: u8) {
     if start_x < 0 || start_y < 0 {
@@ -1512,11 +1608,11 @@ pub fn draw_mode_tabs(
 
         // Active indicator (bottom line)
         if is_active {
-            draw_rectangle(tab_rect.x, tab_rect.y + TAB_HEIGHT - 2.0, tab_rect.w, 2.0, ACCENT_COLOR);
+            draw_rectangle(tab_rect.x, tab_rect.y + TAB_HEIGHT - 2.0, tab_rect.w, 2.0, theme().accent);
         }
 
         // Tab label
-        let text_color = if is_active { TEXT_COLOR } else { TEXT_DIM };
+        let text_color = if is_active { theme().text } else { theme().text_dim };
         let text_size = 14.0;
         let text_dims = measure_text(label, None, text_size as u16, 1.0);
         let text_x = tab_rect.x + (tab_rect.w - text_dims.width) / 2.0;
@@ -1752,15 +1848,92 @@ pub fn generate_palette_from_keys(
 ///
 /// When `uv_data` is Some and state.mode is Uv, draws UV wireframe overlay on top of texture.
 /// The texture is always drawn as background (useful for seeing UV placement).
+/// For flipbook textures, edits go to `state.anim_frame` and the previous frame
+/// is ghosted when onion skinning is enabled. For layered textures, edits to
+/// frame 0 go to `state.active_layer` and the composite is rebuilt afterwards.
 pub fn draw_texture_canvas(
     ctx: &mut UiContext,
     canvas_rect: Rect,
     texture: &mut UserTexture,
     state: &mut TextureEditorState,
     uv_data: Option<&UvOverlayData>,
+) {
+    // Keep the frame cursor valid (frames may have been removed via undo)
+    state.anim_frame = state.anim_frame.min(texture.frame_count() - 1);
+    if state.anim_playing && !texture.is_animated() {
+        state.anim_playing = false;
+    }
+    let frame = if state.anim_playing {
+        texture.frame_at_time(get_time())
+    } else {
+        state.anim_frame
+    };
+
+    // Previous frame for onion skinning (wraps so frame 0 shows the last frame)
+    let onion: Option<Vec<u8>> = if state.onion_skin && !state.anim_playing && texture.is_animated() {
+        let prev = (frame + texture.frame_count() - 1) % texture.frame_count();
+        Some(texture.frame_indices(prev).to_vec())
+    } else {
+        None
+    };
+
+    // Layers belong to frame 0 (the cursor may be stale after undo too)
+    state.active_layer = state.active_layer.min(texture.layers.len().saturating_sub(1));
+    let layer = (frame == 0 && texture.has_layers()).then_some(state.active_layer);
+    let backdrop = layer.map(|layer| LayerBackdrop::new(texture, layer));
+
+    // Swap the active frame (or layer) into `indices` so all tools edit it transparently
+    texture.swap_frame_to_base(frame);
+    if let Some(layer) = layer {
+        texture.swap_layer_to_base(layer);
+    }
+    draw_texture_canvas_frame(ctx, canvas_rect, texture, state, uv_data, onion.as_deref(), backdrop.as_ref());
+    if let Some(layer) = layer {
+        texture.swap_layer_to_base(layer);
+        texture.composite_layers();
+    }
+    texture.swap_frame_to_base(frame);
+}
+
+/// The visible layers below and above the one being painted, composited
+///
+/// Drawn around the active layer so the canvas shows the whole texture.
+struct LayerBackdrop {
+    below: Vec<u8>,
+    above: Vec<u8>,
+}
+
+impl LayerBackdrop {
+    fn new(texture: &UserTexture, layer: usize) -> Self {
+        let len = texture.width * texture.height;
+        Self {
+            below: composite_layer_indices(&texture.layers[..layer], len),
+            above: composite_layer_indices(&texture.layers[layer + 1..], len),
+        }
+    }
+
+    /// Index shown at pixel `i` given the active layer's index there
+    fn index(&self, i: usize, active: u8) -> u8 {
+        match self.above.get(i).copied().unwrap_or(0) {
+            0 if active != 0 => active,
+            0 => self.below.get(i).copied().unwrap_or(0),
+            above => above,
+        }
+    }
+}
+
+/// Draw and edit the frame (or layer) currently swapped into `texture.indices`
+fn draw_texture_canvas_frame(
+    ctx: &mut UiContext,
+    canvas_rect: Rect,
+    texture: &mut UserTexture,
+    state: &mut TextureEditorState,
+    uv_data: Option<&UvOverlayData>,
+    onion: Option<&[u8]>,
+    backdrop: Option<&LayerBackdrop>,
 ) {
     // Tool keyboard shortcuts (only in Paint mode)
-    if state.mode == TextureEditorMode::Paint && ctx.mouse.inside(&canvas_rect) {
+    if state.mode == TextureEditorMode::Paint && ctx.mouse.inside(&canvas_rect) && state.hex_input.is_none() {
         use macroquad::prelude::{is_key_pressed, KeyCode};
         if is_key_pressed(KeyCode::S) { state.tool = DrawTool::Select; }
         if is_key_pressed(KeyCode::W) { state.tool = DrawTool::SelectByColor; }
@@ -1770,6 +1943,23 @@ pub fn draw_texture_canvas(
         if is_key_pressed(KeyCode::L) { state.tool = DrawTool::Line; }
         if is_key_pressed(KeyCode::R) { state.tool = DrawTool::Rectangle; }
         if is_key_pressed(KeyCode::O) { state.tool = DrawTool::Ellipse; }
+        let cmd_held = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl)
+            || is_key_down(KeyCode::LeftSuper) || is_key_down(KeyCode::RightSuper);
+        if is_key_pressed(KeyCode::C) && !cmd_held { state.tool = DrawTool::CloneStamp; }
+        if is_key_pressed(KeyCode::H) {
+            if let Some(reference) = &mut state.reference {
+                reference.visible = !reference.visible;
+            }
+        }
+
+        // Arrow keys wrap-shift the texture (or selection); Shift moves 8 pixels
+        let step = if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) { 8 } else { 1 };
+        let arrows = [(KeyCode::Left, -step, 0), (KeyCode::Right, step, 0), (KeyCode::Up, 0, -step), (KeyCode::Down, 0, step)];
+        for (key, dx, dy) in arrows {
+            if is_key_pressed(key) {
+                state.wrap_shift_pending = Some(WrapShift::By(dx, dy));
+            }
+        }
     }
 
     // Update selection animation frame
@@ -1843,6 +2033,11 @@ pub fn draw_texture_canvas(
         row += 1;
     }
 
+    // Reference image under the pixels (shows through transparent ones)
+    if let Some(reference) = state.reference.as_mut().filter(|r| !r.above) {
+        reference.draw(tex_x, tex_y, state.zoom, texture.width, texture.height);
+    }
+
     // Draw texture pixels (with optional tiling preview)
     // When tiling is enabled, we draw 9 copies: center + 8 surrounding
     let tile_offsets: &[(i32, i32, f32)] = if state.show_tiling {
@@ -1875,7 +2070,13 @@ pub fn draw_texture_canvas(
                     continue;
                 }
 
-                let color = texture.get_color(px, py);
+                let color = match backdrop {
+                    Some(backdrop) => {
+                        let index = backdrop.index(py * texture.width + px, texture.get_index(px, py));
+                        texture.get_palette_color(index)
+                    }
+                    None => texture.get_color(px, py),
+                };
                 if !color.is_transparent() {
                     let [r, g, b, _] = color.to_rgba();
                     draw_rectangle(
@@ -1895,6 +2096,45 @@ pub fn draw_texture_canvas(
         }
     }
 
+    // Onion skin: ghost the previous animation frame over the center tile
+    if let Some(prev_indices) = onion {
+        for py in 0..texture.height {
+            for px in 0..texture.width {
+                let screen_x = tex_x + px as f32 * state.zoom;
+                let screen_y = tex_y + py as f32 * state.zoom;
+                if screen_x + state.zoom < canvas_rect.x
+                    || screen_x > canvas_rect.x + canvas_rect.w
+                    || screen_y + state.zoom < canvas_rect.y
+                    || screen_y > canvas_rect.y + canvas_rect.h
+                {
+                    continue;
+                }
+
+                let index = prev_indices.get(py * texture.width + px).copied().unwrap_or(0);
+                // Only ghost pixels that differ from the current frame
+                if index == texture.get_index(px, py) {
+                    continue;
+                }
+                let color = texture.get_palette_color(index);
+                if !color.is_transparent() {
+                    let [r, g, b, _] = color.to_rgba();
+                    draw_rectangle(
+                        screen_x,
+                        screen_y,
+                        state.zoom,
+                        state.zoom,
+                        Color::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 0.3),
+                    );
+                }
+            }
+        }
+    }
+
+    // Reference image over the pixels
+    if let Some(reference) = state.reference.as_mut().filter(|r| r.above) {
+        reference.draw(tex_x, tex_y, state.zoom, texture.width, texture.height);
+    }
+
     // Draw pixel grid at high zoom (when enabled)
     // When tiling is on, extend grid to cover 3x3 tile area
     if state.show_grid && state.zoom >= 4.0 {
@@ -1943,6 +2183,33 @@ pub fn draw_texture_canvas(
         }
     }
 
+    // Seam checker: mark column/row edges with much more contrast than the rest
+    if state.show_seams {
+        let seam_color = Color::new(1.0, 0.25, 0.2, 0.8);
+        let report = find_seams(texture);
+        let (top, bottom) = (tex_y.max(canvas_rect.y), (tex_y + tex_screen_h).min(canvas_rect.y + canvas_rect.h));
+        let (left, right) = (tex_x.max(canvas_rect.x), (tex_x + tex_screen_w).min(canvas_rect.x + canvas_rect.w));
+        for &c in &report.columns {
+            let x = tex_x + (c + 1) as f32 * state.zoom;
+            // The wrap edge is both the right and the left border
+            let xs: &[f32] = if c + 1 == texture.width { &[x, tex_x] } else { &[x] };
+            for &x in xs {
+                if x >= canvas_rect.x && x <= canvas_rect.x + canvas_rect.w && top < bottom {
+                    draw_line(x, top, x, bottom, 2.0, seam_color);
+                }
+            }
+        }
+        for &r in &report.rows {
+            let y = tex_y + (r + 1) as f32 * state.zoom;
+            let ys: &[f32] = if r + 1 == texture.height { &[y, tex_y] } else { &[y] };
+            for &y in ys {
+                if y >= canvas_rect.y && y <= canvas_rect.y + canvas_rect.h && left < right {
+                    draw_line(left, y, right, y, 2.0, seam_color);
+                }
+            }
+        }
+    }
+
     // Draw texture border (always shows center tile boundary)
     draw_rectangle_lines(tex_x, tex_y, tex_screen_w, tex_screen_h, 1.0, Color::new(0.5, 0.5, 0.5, 1.0));
 
@@ -2041,8 +2308,24 @@ pub fn draw_texture_canvas(
         }
     }
 
+    // An unlocked reference image takes the left button and scroll wheel:
+    // drag to move it, scroll to scale it about the mouse
+    let moving_reference = state.mode == TextureEditorMode::Paint
+        && state.reference.as_ref().is_some_and(|r| r.movable());
+    if moving_reference {
+        let zoom = state.zoom;
+        if let Some(reference) = &mut state.reference {
+            let mouse = (ctx.mouse.x, ctx.mouse.y);
+            reference.drag(mouse, inside && ctx.mouse.left_pressed, ctx.mouse.left_down, zoom);
+            if inside && ctx.mouse.scroll != 0.0 {
+                let factor = if ctx.mouse.scroll > 0.0 { 1.04 } else { 1.0 / 1.04 };
+                reference.zoom_about(factor, ((mouse.0 - tex_x) / zoom, (mouse.1 - tex_y) / zoom));
+            }
+        }
+    }
+
     // Zoom with scroll wheel (gentle 4% per tick)
-    if inside && ctx.mouse.scroll != 0.0 {
+    if inside && ctx.mouse.scroll != 0.0 && !moving_reference {
         let old_zoom = state.zoom;
         let zoom_factor = 1.04f32;
         if ctx.mouse.scroll > 0.0 {
@@ -2065,7 +2348,7 @@ pub fn draw_texture_canvas(
     let cmd_held = is_key_down(KeyCode::LeftSuper) || is_key_down(KeyCode::RightSuper);
 
     // Escape to deselect or cancel move
-    if is_key_pressed(KeyCode::Escape) && state.selection.is_some() {
+    if is_key_pressed(KeyCode::Escape) && state.selection.is_some() && state.hex_input.is_none() {
         let has_floating = state.selection.as_ref().map_or(false, |s| s.floating.is_some());
 
         // If we have a floating selection being moved, cancel and restore original position
@@ -2094,6 +2377,7 @@ pub fn draw_texture_canvas(
             let clipboard = make_clipboard_from_selection(texture, selection);
             let w = clipboard.width;
             let h = clipboard.height;
+            crate::clipboard_history::record(crate::clipboard_history::ClipData::Texture(clipboard.clone()));
             state.clipboard = Some(clipboard);
             state.set_status(&format!("Copied {}×{} pixels", w, h));
         }
@@ -2105,6 +2389,7 @@ pub fn draw_texture_canvas(
             let clipboard = make_clipboard_from_selection(texture, &selection);
             let w = clipboard.width;
             let h = clipboard.height;
+            crate::clipboard_history::record(crate::clipboard_history::ClipData::Texture(clipboard.clone()));
             state.clipboard = Some(clipboard);
             // Clear the selected area (signal undo to caller)
             state.undo_save_pending = Some("Cut".to_string());
@@ -2113,8 +2398,9 @@ pub fn draw_texture_canvas(
         }
     }
 
-    // Paste (Cmd+V)
-    if cmd_held && is_key_pressed(KeyCode::V) {
+    // Paste (Cmd+V; Cmd+Shift+V opens the clipboard history instead)
+    let shift_held = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    if cmd_held && !shift_held && is_key_pressed(KeyCode::V) {
         if let Some(ref clipboard) = state.clipboard.clone() {
             // Commit any existing floating selection
             let has_floating = state.selection.as_ref().map_or(false, |s| s.floating.is_some());
@@ -2140,7 +2426,10 @@ pub fn draw_texture_canvas(
     }
 
     // Delete selection (Delete or Backspace key) - clear to transparent
-    if (is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace)) && state.selection.is_some() {
+    if (is_key_pressed(KeyCode::Delete) || is_key_pressed(KeyCode::Backspace))
+        && state.selection.is_some()
+        && state.hex_input.is_none()
+    {
         if let Some(ref selection) = state.selection {
             // Signal undo to caller
             state.undo_save_pending = Some("Delete selection".to_string());
@@ -2156,8 +2445,8 @@ pub fn draw_texture_canvas(
         state.selection = None;
     }
 
-    // Drawing and selection
-    if inside && !state.panning {
+    // Drawing and selection (not while placing the reference image)
+    if inside && !state.panning && !moving_reference {
         // UV mode: handle UV-specific input
         if state.mode == TextureEditorMode::Uv {
             handle_uv_input(ctx, &canvas_rect, texture, state, uv_data);
@@ -2396,6 +2685,23 @@ pub fn draw_texture_canvas(
                     }
                 }
 
+                // Clone stamp: mark the pixel being copied from
+                if state.tool == DrawTool::CloneStamp {
+                    let source = match &state.clone_stroke {
+                        Some(stroke) => Some((px + stroke.offset.0, py + stroke.offset.1)),
+                        None => state.clone_source,
+                    };
+                    if let Some((sx, sy)) = source {
+                        let (sx, sy) = (sx.rem_euclid(texture.width as i32), sy.rem_euclid(texture.height as i32));
+                        let cx = tex_x + (sx as f32 + 0.5) * state.zoom;
+                        let cy = tex_y + (sy as f32 + 0.5) * state.zoom;
+                        let arm = state.zoom.max(4.0);
+                        let marker = Color::new(0.3, 1.0, 0.6, 0.9);
+                        draw_line(cx - arm, cy, cx + arm, cy, 1.0, marker);
+                        draw_line(cx, cy - arm, cx, cy + arm, 1.0, marker);
+                    }
+                }
+
                 // Handle drawing
                 if ctx.mouse.left_pressed && !state.drawing {
                     state.drawing = true;
@@ -2418,6 +2724,25 @@ pub fn draw_texture_canvas(
                             DrawTool::Fill => {
                                 flood_fill(texture, px, py, state.selected_index);
                             }
+                            DrawTool::CloneStamp => {
+                                let alt_held = is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt);
+                                // Off the texture only counts on the tiling preview's copies
+                                let on_texture = state.show_tiling
+                                    || (px >= 0 && py >= 0 && (px as usize) < texture.width && (py as usize) < texture.height);
+                                if !on_texture {
+                                    state.set_status("Clone stamp: click on the texture");
+                                } else if alt_held {
+                                    let source = (px.rem_euclid(texture.width as i32), py.rem_euclid(texture.height as i32));
+                                    state.clone_source = Some(source);
+                                    state.set_status(&format!("Clone source {}, {}", source.0, source.1));
+                                } else if let Some((sx, sy)) = state.clone_source {
+                                    let stroke = CloneStroke { offset: (sx - px, sy - py), source: texture.indices.clone() };
+                                    tex_clone_dab(texture, px, py, state.brush_size, state.brush_shape, &stroke);
+                                    state.clone_stroke = Some(stroke);
+                                } else {
+                                    state.set_status("Alt+click to set the clone source");
+                                }
+                            }
                             DrawTool::Eyedropper => {
                                 // Pick color from canvas
                                 let idx = (py as usize) * texture.width + (px as usize);
@@ -2458,7 +2783,7 @@ pub fn draw_texture_canvas(
                     // Continue stroke
                     if let Some((last_x, last_y)) = state.last_draw_pos {
                         if (px, py) != (last_x, last_y) {
-                            if state.tool == DrawTool::Brush {
+                            if state.tool == DrawTool::Brush || state.clone_stroke.is_some() {
                                 // Interpolate brush along line
                                 let dx = (px - last_x).abs();
                                 let dy = (py - last_y).abs();
@@ -2467,7 +2792,10 @@ pub fn draw_texture_canvas(
                                     let t = if steps == 0 { 0.0 } else { i as f32 / steps as f32 };
                                     let ix = last_x + ((px - last_x) as f32 * t) as i32;
                                     let iy = last_y + ((py - last_y) as f32 * t) as i32;
-                                    tex_draw_brush(texture, ix, iy, state.brush_size, state.selected_index, state.brush_shape);
+                                    match &state.clone_stroke {
+                                        Some(stroke) => tex_clone_dab(texture, ix, iy, state.brush_size, state.brush_shape, stroke),
+                                        None => tex_draw_brush(texture, ix, iy, state.brush_size, state.selected_index, state.brush_shape),
+                                    }
                                 }
                             }
                             state.last_draw_pos = Some((px, py));
@@ -2537,6 +2865,7 @@ pub fn draw_texture_canvas(
                     state.drawing = false;
                     state.shape_start = None;
                     state.last_draw_pos = None;
+                    state.clone_stroke = None;
                 }
             }
         }
@@ -2547,7 +2876,400 @@ pub fn draw_texture_canvas(
         state.drawing = false;
         state.shape_start = None;
         state.last_draw_pos = None;
+        state.clone_stroke = None;
+    }
+}
+
+/// Structural flipbook edits requested from the frame strip
+///
+/// Returned to the caller so it can snapshot undo before applying
+/// with `apply_frame_action`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameAction {
+    /// Duplicate the current frame and select the copy
+    Duplicate,
+    /// Delete the current frame
+    Delete,
+}
+
+/// Height of the flipbook frame strip
+pub const FRAME_STRIP_HEIGHT: f32 = 24.0;
+
+/// Draw the flipbook frame strip (frame navigation, onion skin, playback, fps)
+///
+/// Navigation and playback settings are applied directly; adding or removing
+/// frames is returned as a `FrameAction` so the caller can save undo first.
+pub fn draw_frame_strip(
+    ctx: &mut UiContext,
+    rect: Rect,
+    texture: &mut UserTexture,
+    state: &mut TextureEditorState,
+    icon_font: Option<&Font>,
+) -> Option<FrameAction> {
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0.14, 0.14, 0.16, 1.0));
+
+    let btn = rect.h - 4.0;
+    let gap = 2.0;
+    let y = rect.y + 2.0;
+    let mut x = rect.x + 4.0;
+    let count = texture.frame_count();
+    let mut action = None;
+
+    if draw_action_button_small(ctx, x, y, btn, icon::CHEVRON_LEFT, "Previous frame", icon_font) {
+        state.anim_frame = (state.anim_frame + count - 1) % count;
+        state.anim_playing = false;
+    }
+    x += btn + gap;
+
+    let label = format!("{}/{}", state.anim_frame + 1, count);
+    let label_w = 36.0;
+    let dims = measure_text(&label, None, 12, 1.0);
+    draw_text(&label, (x + (label_w - dims.width) / 2.0).floor(), (y + btn / 2.0 + 4.0).floor(), 12.0, theme().text);
+    x += label_w + gap;
+
+    if draw_action_button_small(ctx, x, y, btn, icon::CHEVRON_RIGHT, "Next frame", icon_font) {
+        state.anim_frame = (state.anim_frame + 1) % count;
+        state.anim_playing = false;
+    }
+    x += btn + gap * 4.0;
+
+    if draw_action_button_small(ctx, x, y, btn, icon::PLUS, "Duplicate frame", icon_font) {
+        action = Some(FrameAction::Duplicate);
+    }
+    x += btn + gap;
+
+    if count > 1 && draw_action_button_small(ctx, x, y, btn, icon::MINUS, "Delete frame", icon_font) {
+        action = Some(FrameAction::Delete);
+    }
+    x += btn + gap * 4.0;
+
+    if draw_toggle_button_small(ctx, x, y, btn, icon::LAYERS, "Onion skin", state.onion_skin, icon_font) {
+        state.onion_skin = !state.onion_skin;
+    }
+    x += btn + gap;
+
+    if count > 1 {
+        let (play_icon, play_tip) = if state.anim_playing {
+            (icon::PAUSE, "Pause preview")
+        } else {
+            (icon::PLAY, "Play preview")
+        };
+        if draw_toggle_button_small(ctx, x, y, btn, play_icon, play_tip, state.anim_playing, icon_font) {
+            state.anim_playing = !state.anim_playing;
+        }
+        x += btn + gap * 4.0;
+
+        // Frame rate (1-60 fps)
+        if draw_action_button_small(ctx, x, y, btn, icon::MINUS, "Slower", icon_font) {
+            texture.set_animation_fps(texture.animation_fps() - 1.0);
+            state.dirty = true;
+        }
+        x += btn + gap;
+        let fps_label = format!("{} fps", texture.animation_fps() as u32);
+        draw_text(&fps_label, x.floor(), (y + btn / 2.0 + 4.0).floor(), 12.0, theme().text_dim);
+        x += 40.0;
+        if draw_action_button_small(ctx, x, y, btn, icon::PLUS, "Faster", icon_font) {
+            texture.set_animation_fps(texture.animation_fps() + 1.0);
+            state.dirty = true;
+        }
+    }
+
+    action
+}
+
+/// Apply a frame strip action to the texture (call after saving undo)
+pub fn apply_frame_action(texture: &mut UserTexture, state: &mut TextureEditorState, action: FrameAction) {
+    match action {
+        FrameAction::Duplicate => match texture.duplicate_frame(state.anim_frame) {
+            Some(frame) => {
+                state.anim_frame = frame;
+                state.set_status(&format!("Added frame {}", frame + 1));
+            }
+            None => state.set_status("Frame limit reached"),
+        },
+        FrameAction::Delete => {
+            state.anim_frame = texture.remove_frame(state.anim_frame);
+            state.set_status("Deleted frame");
+        }
+    }
+    state.anim_playing = false;
+    state.selection = None;
+    state.dirty = true;
+}
+
+/// Structural layer edits requested from the layer strip
+///
+/// Returned to the caller so it can snapshot undo before applying
+/// with `apply_layer_action`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayerAction {
+    /// Add an empty layer above the current one (splitting the texture
+    /// into layers the first time)
+    Add,
+    /// Delete the current layer
+    Delete,
+    /// Move the current layer up the stack
+    MoveUp,
+    /// Move the current layer down the stack
+    MoveDown,
+    /// Merge the current layer into the one below
+    MergeDown,
+    /// Show or hide the current layer
+    ToggleVisible,
+}
+
+/// Height of the paint layer strip
+pub const LAYER_STRIP_HEIGHT: f32 = 24.0;
+
+/// Draw the paint layer strip (layer navigation, visibility, reordering)
+///
+/// Switching layers is applied directly; everything that changes the layer
+/// stack is returned as a `LayerAction` so the caller can save undo first.
+/// Layers only exist for frame 0 of a flipbook.
+pub fn draw_layer_strip(
+    ctx: &mut UiContext,
+    rect: Rect,
+    texture: &UserTexture,
+    state: &mut TextureEditorState,
+    icon_font: Option<&Font>,
+) -> Option<LayerAction> {
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0.14, 0.14, 0.16, 1.0));
+
+    let btn = rect.h - 4.0;
+    let gap = 2.0;
+    let y = rect.y + 2.0;
+    let text_y = (y + btn / 2.0 + 4.0).floor();
+    let mut x = rect.x + 4.0;
+
+    if state.anim_frame != 0 {
+        draw_text("Layers: frame 1 only", x.floor(), text_y, 12.0, theme().text_dim);
+        return None;
+    }
+
+    let count = texture.layers.len();
+    let mut action = None;
+
+    if count == 0 {
+        if draw_action_button_small(ctx, x, y, btn, icon::PLUS, "Add layer", icon_font) {
+            action = Some(LayerAction::Add);
+        }
+        x += btn + gap * 2.0;
+        draw_text("No layers", x.floor(), text_y, 12.0, theme().text_dim);
+        return action;
+    }
+
+    let layer = state.active_layer.min(count - 1);
+    let visible = texture.layers[layer].visible;
+    let (eye, eye_tip) = if visible { (icon::EYE, "Hide layer") } else { (icon::EYE_OFF, "Show layer") };
+    if draw_toggle_button_small(ctx, x, y, btn, eye, eye_tip, !visible, icon_font) {
+        action = Some(LayerAction::ToggleVisible);
+    }
+    x += btn + gap * 4.0;
+
+    // Layer 1 is the bottom of the stack, so "previous" goes down
+    if draw_action_button_small(ctx, x, y, btn, icon::CHEVRON_LEFT, "Layer below", icon_font) {
+        state.active_layer = (layer + count - 1) % count;
+    }
+    x += btn + gap;
+
+    let label = format!("{}/{} {}", layer + 1, count, texture.layers[layer].name);
+    let label_w = 96.0;
+    draw_text(&label, (x + 2.0).floor(), text_y, 12.0, if visible { theme().text } else { theme().text_dim });
+    x += label_w + gap;
+
+    if draw_action_button_small(ctx, x, y, btn, icon::CHEVRON_RIGHT, "Layer above", icon_font) {
+        state.active_layer = (layer + 1) % count;
+    }
+    x += btn + gap * 4.0;
+
+    if draw_action_button_small(ctx, x, y, btn, icon::PLUS, "Add layer", icon_font) {
+        action = Some(LayerAction::Add);
+    }
+    x += btn + gap;
+
+    if draw_action_button_small(ctx, x, y, btn, icon::MINUS, "Delete layer", icon_font) {
+        action = Some(LayerAction::Delete);
+    }
+    x += btn + gap * 4.0;
+
+    if layer + 1 < count && draw_action_button_small(ctx, x, y, btn, icon::CHEVRON_UP, "Move layer up", icon_font) {
+        action = Some(LayerAction::MoveUp);
+    }
+    x += btn + gap;
+
+    if layer > 0 && draw_action_button_small(ctx, x, y, btn, icon::CHEVRON_DOWN, "Move layer down", icon_font) {
+        action = Some(LayerAction::MoveDown);
+    }
+    x += btn + gap;
+
+    if layer > 0 && draw_action_button_small(ctx, x, y, btn, icon::LAYERS, "Merge down", icon_font) {
+        action = Some(LayerAction::MergeDown);
+    }
+
+    action
+}
+
+/// Apply a layer strip action to the texture (call after saving undo)
+pub fn apply_layer_action(texture: &mut UserTexture, state: &mut TextureEditorState, action: LayerAction) {
+    let layer = state.active_layer.min(texture.layers.len().saturating_sub(1));
+    match action {
+        LayerAction::Add => match texture.add_layer(layer) {
+            Some(new_layer) => {
+                state.active_layer = new_layer;
+                state.set_status(&format!("Added layer {}", new_layer + 1));
+            }
+            None => state.set_status("Layer limit reached"),
+        },
+        LayerAction::Delete => {
+            state.active_layer = texture.remove_layer(layer);
+            state.set_status("Deleted layer");
+        }
+        LayerAction::MoveUp => state.active_layer = texture.move_layer(layer, true),
+        LayerAction::MoveDown => state.active_layer = texture.move_layer(layer, false),
+        LayerAction::MergeDown => {
+            if let Some(merged) = texture.merge_layer_down(layer) {
+                state.active_layer = merged;
+                state.set_status("Merged layer down");
+            }
+        }
+        LayerAction::ToggleVisible => {
+            let visible = texture.layers.get(layer).is_some_and(|l| l.visible);
+            texture.set_layer_visible(layer, !visible);
+        }
+    }
+    state.selection = None;
+    state.dirty = true;
+}
+
+/// A wraparound offset of the texture (or selection) pixels
+///
+/// Returned through `TextureEditorState::wrap_shift_pending` so the caller
+/// can snapshot undo before applying with `apply_wrap_shift`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapShift {
+    /// Shift by a pixel offset
+    By(i32, i32),
+    /// Shift by half the width and height, moving the tiling seams to the center
+    Half,
+}
+
+/// Shift the current frame or layer (or the selection's area) with wraparound
+///
+/// Pixels pushed off one edge come back on the opposite edge, so with the
+/// tiling preview on the seams can be moved into view and painted over.
+/// A floating selection shifts its own pixels; any other selection shifts
+/// the pixels inside its bounding box.
+pub fn apply_wrap_shift(texture: &mut UserTexture, state: &mut TextureEditorState, shift: WrapShift) {
+    let offset = |w: usize, h: usize| match shift {
+        WrapShift::By(dx, dy) => (dx, dy),
+        WrapShift::Half => ((w / 2) as i32, (h / 2) as i32),
+    };
+
+    if let Some(selection) = &mut state.selection {
+        if let Some(floating) = &mut selection.floating {
+            let (dx, dy) = offset(selection.width, selection.height);
+            wrap_shift_region(floating, selection.width, (0, 0, selection.width, selection.height), dx, dy);
+            return;
+        }
+    }
+
+    // Selection bounds clipped to the texture, or the whole texture
+    let region = match &state.selection {
+        Some(sel) => {
+            let x0 = sel.x.clamp(0, texture.width as i32) as usize;
+            let y0 = sel.y.clamp(0, texture.height as i32) as usize;
+            let x1 = (sel.x + sel.width as i32).clamp(0, texture.width as i32) as usize;
+            let y1 = (sel.y + sel.height as i32).clamp(0, texture.height as i32) as usize;
+            (x0, y0, x1 - x0, y1 - y0)
+        }
+        None => (0, 0, texture.width, texture.height),
+    };
+    let (dx, dy) = offset(region.2, region.3);
+
+    let frame = state.anim_frame;
+    let layer = (frame == 0 && texture.has_layers()).then_some(state.active_layer);
+    texture.swap_frame_to_base(frame);
+    if let Some(layer) = layer {
+        texture.swap_layer_to_base(layer);
     }
+    let width = texture.width;
+    wrap_shift_region(&mut texture.indices, width, region, dx, dy);
+    if let Some(layer) = layer {
+        texture.swap_layer_to_base(layer);
+        texture.composite_layers();
+    }
+    texture.swap_frame_to_base(frame);
+
+    state.dirty = true;
+    state.set_status(&format!("Offset {}, {}", dx, dy));
+}
+
+/// Rotate the pixels of a rectangle (x, y, w, h) inside a row-major buffer
+fn wrap_shift_region(indices: &mut [u8], stride: usize, region: (usize, usize, usize, usize), dx: i32, dy: i32) {
+    let (x0, y0, w, h) = region;
+    if w == 0 || h == 0 {
+        return;
+    }
+    let mut src = Vec::with_capacity(w * h);
+    for y in 0..h {
+        let row = (y0 + y) * stride + x0;
+        src.extend_from_slice(&indices[row..row + w]);
+    }
+    for y in 0..h {
+        let ty = (y as i32 + dy).rem_euclid(h as i32) as usize;
+        for x in 0..w {
+            let tx = (x as i32 + dx).rem_euclid(w as i32) as usize;
+            indices[(y0 + ty) * stride + x0 + tx] = src[y * w + x];
+        }
+    }
+}
+
+/// Pixel boundaries that read as hard edges, found by `find_seams`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeamReport {
+    /// Column edges: `c` is the edge between columns `c` and `c + 1`
+    /// (the last one is the wrap edge back to column 0)
+    pub columns: Vec<usize>,
+    /// Row edges, numbered the same way
+    pub rows: Vec<usize>,
+}
+
+/// An edge is a seam when its contrast is this many times the texture's average...
+const SEAM_CONTRAST_FACTOR: f32 = 2.5;
+/// ...and at least this high (0..1), so flat textures don't flag noise
+const SEAM_MIN_CONTRAST: f32 = 0.12;
+
+/// Color difference of two neighbouring pixels (0..1)
+fn pixel_contrast(a: Color15, b: Color15) -> f32 {
+    match (a.is_transparent(), b.is_transparent()) {
+        (true, true) => 0.0,
+        (false, false) => {
+            let diff = |x: u8, y: u8| (x as i32 - y as i32).unsigned_abs() as f32;
+            (diff(a.r5(), b.r5()) + diff(a.g5(), b.g5()) + diff(a.b5(), b.b5())) / (3.0 * 31.0)
+        }
+        _ => 1.0,
+    }
+}
+
+/// Find columns and rows that meet their neighbour with much more contrast
+/// than the rest of the texture. Edges wrap, so the seams the tiling preview
+/// shows (last column against the first, last row against the first) count.
+pub fn find_seams(texture: &UserTexture) -> SeamReport {
+    let (w, h) = (texture.width, texture.height);
+    if w < 2 || h < 2 {
+        return SeamReport::default();
+    }
+    let columns: Vec<f32> = (0..w)
+        .map(|x| (0..h).map(|y| pixel_contrast(texture.get_color(x, y), texture.get_color((x + 1) % w, y))).sum::<f32>() / h as f32)
+        .collect();
+    let rows: Vec<f32> = (0..h)
+        .map(|y| (0..w).map(|x| pixel_contrast(texture.get_color(x, y), texture.get_color(x, (y + 1) % h))).sum::<f32>() / w as f32)
+        .collect();
+
+    let average = (columns.iter().sum::<f32>() + rows.iter().sum::<f32>()) / (w + h) as f32;
+    let threshold = (average * SEAM_CONTRAST_FACTOR).max(SEAM_MIN_CONTRAST);
+    let above = |edges: &[f32]| edges.iter().enumerate().filter(|(_, &c)| c > threshold).map(|(i, _)| i).collect();
+    SeamReport { columns: above(&columns), rows: above(&rows) }
 }
 
 /// Draw the tool panel in 2-column layout (below canvas)
@@ -2602,8 +3324,63 @@ pub fn draw_tool_panel(
     if draw_toggle_button_small(ctx, col1_x, y, btn_size, icon::SQUARE_SQUARE, "Tiling preview", state.show_tiling, icon_font) {
         state.show_tiling = !state.show_tiling;
     }
+    if draw_action_button_small(ctx, col2_x, y, btn_size, icon::MOVE, "Offset by half (arrows: 1px, Shift: 8px)", icon_font) {
+        state.wrap_shift_pending = Some(WrapShift::Half);
+    }
     y += btn_size + gap;
 
+    // === Row 5: Seam checker ===
+    if draw_toggle_button_small(ctx, col1_x, y, btn_size, icon::SCAN_LINE, "Highlight seams (hard-edged columns/rows)", state.show_seams, icon_font) {
+        state.show_seams = !state.show_seams;
+    }
+    if draw_action_button_small(ctx, col2_x, y, btn_size, icon::IMAGE, "Load reference image", icon_font) {
+        match pick_reference_image() {
+            Ok(Some(reference)) => {
+                state.set_status(&format!("Reference: {} (unlocked: drag to move, scroll to scale)", reference.name));
+                state.reference = Some(reference);
+            }
+            Ok(None) => {}
+            Err(e) => state.set_status(&e),
+        }
+    }
+    y += btn_size + gap;
+
+    // === Reference image: visibility, lock, stacking, opacity ===
+    if let Some(reference) = &mut state.reference {
+        let (eye, eye_tip) = if reference.visible { (icon::EYE, "Hide reference (H)") } else { (icon::EYE_OFF, "Show reference (H)") };
+        if draw_toggle_button_small(ctx, col1_x, y, btn_size, eye, eye_tip, reference.visible, icon_font) {
+            reference.visible = !reference.visible;
+        }
+        let (lock, lock_tip) = if reference.locked { (icon::LOCK, "Unlock reference (to move/scale it)") } else { (icon::LOCK_OPEN, "Lock reference (to paint)") };
+        if draw_toggle_button_small(ctx, col2_x, y, btn_size, lock, lock_tip, reference.locked, icon_font) {
+            reference.locked = !reference.locked;
+        }
+        y += btn_size + gap;
+
+        let above_tip = if reference.above { "Reference over pixels" } else { "Reference under pixels" };
+        if draw_toggle_button_small(ctx, col1_x, y, btn_size, icon::LAYERS, above_tip, reference.above, icon_font) {
+            reference.above = !reference.above;
+        }
+        let mut clear = false;
+        if draw_action_button_small(ctx, col2_x, y, btn_size, icon::TRASH, "Remove reference", icon_font) {
+            clear = true;
+        }
+        y += btn_size + gap;
+
+        let percent = (reference.opacity * 100.0).round();
+        if draw_action_button_small(ctx, col1_x, y, btn_size, icon::MINUS, &format!("Reference opacity - ({}%)", percent), icon_font) {
+            reference.set_opacity(reference.opacity - OPACITY_STEP);
+        }
+        if draw_action_button_small(ctx, col2_x, y, btn_size, icon::PLUS, &format!("Reference opacity + ({}%)", percent), icon_font) {
+            reference.set_opacity(reference.opacity + OPACITY_STEP);
+        }
+        y += btn_size + gap;
+
+        if clear {
+            state.reference = None;
+        }
+    }
+
     // Separator before tools
     y += 2.0;
     draw_line(col1_x, y, col2_x + btn_size, y, 1.0, Color::new(0.3, 0.3, 0.32, 1.0));
@@ -2624,6 +3401,7 @@ pub fn draw_tool_panel(
                 DrawTool::Line,
                 DrawTool::Rectangle,
                 DrawTool::Ellipse,
+                DrawTool::CloneStamp,
             ];
 
             for (i, tool) in all_tools.iter().enumerate() {
@@ -2726,7 +3504,7 @@ pub fn draw_tool_panel(
         let minus_hovered = ctx.mouse.inside(&minus_rect);
         draw_rectangle(minus_rect.x, minus_rect.y, minus_rect.w, minus_rect.h,
             if minus_hovered { Color::new(0.35, 0.35, 0.38, 1.0) } else { Color::new(0.22, 0.22, 0.25, 1.0) });
-        draw_text("-", minus_rect.x + small_btn / 2.0 - 2.0, minus_rect.y + small_btn / 2.0 + 4.0, 12.0, TEXT_COLOR);
+        draw_text("-", minus_rect.x + small_btn / 2.0 - 2.0, minus_rect.y + small_btn / 2.0 + 4.0, 12.0, theme().text);
         if ctx.mouse.clicked(&minus_rect) {
             state.brush_size = (state.brush_size - 1).max(1);
         }
@@ -2742,7 +3520,7 @@ pub fn draw_tool_panel(
         let plus_hovered = ctx.mouse.inside(&plus_rect);
         draw_rectangle(plus_rect.x, plus_rect.y, plus_rect.w, plus_rect.h,
             if plus_hovered { Color::new(0.35, 0.35, 0.38, 1.0) } else { Color::new(0.22, 0.22, 0.25, 1.0) });
-        draw_text("+", plus_rect.x + small_btn / 2.0 - 3.0, plus_rect.y + small_btn / 2.0 + 4.0, 12.0, TEXT_COLOR);
+        draw_text("+", plus_rect.x + small_btn / 2.0 - 3.0, plus_rect.y + small_btn / 2.0 + 4.0, 12.0, theme().text);
         if ctx.mouse.clicked(&plus_rect) {
             state.brush_size = (state.brush_size + 1).min(16);
         }
@@ -2755,7 +3533,7 @@ pub fn draw_tool_panel(
             let sq_hovered = ctx.mouse.inside(&sq_rect);
             let sq_selected = state.brush_shape == BrushShape::Square;
             let sq_bg = if sq_selected {
-                ACCENT_COLOR
+                theme().accent
             } else if sq_hovered {
                 Color::new(0.35, 0.35, 0.38, 1.0)
             } else {
@@ -2763,7 +3541,7 @@ pub fn draw_tool_panel(
             };
             draw_rectangle(sq_rect.x, sq_rect.y, sq_rect.w, sq_rect.h, sq_bg);
             if let Some(font) = icon_font {
-                draw_icon_in_rect(font, icon::SQUARE, &sq_rect, if sq_selected { WHITE } else { TEXT_COLOR });
+                draw_icon_in_rect(font, icon::SQUARE, &sq_rect, if sq_selected { WHITE } else { theme().text });
             }
             if sq_hovered {
                 ctx.set_tooltip("Square brush", ctx.mouse.x, ctx.mouse.y);
@@ -2777,7 +3555,7 @@ pub fn draw_tool_panel(
             let circ_hovered = ctx.mouse.inside(&circ_rect);
             let circ_selected = state.brush_shape == BrushShape::Circle;
             let circ_bg = if circ_selected {
-                ACCENT_COLOR
+                theme().accent
             } else if circ_hovered {
                 Color::new(0.35, 0.35, 0.38, 1.0)
             } else {
@@ -2785,7 +3563,7 @@ pub fn draw_tool_panel(
             };
             draw_rectangle(circ_rect.x, circ_rect.y, circ_rect.w, circ_rect.h, circ_bg);
             if let Some(font) = icon_font {
-                draw_icon_in_rect(font, icon::CIRCLE, &circ_rect, if circ_selected { WHITE } else { TEXT_COLOR });
+                draw_icon_in_rect(font, icon::CIRCLE, &circ_rect, if circ_selected { WHITE } else { theme().text });
             }
             if circ_hovered {
                 ctx.set_tooltip("Circle brush", ctx.mouse.x, ctx.mouse.y);
@@ -2803,7 +3581,7 @@ pub fn draw_tool_panel(
             let fill_hovered = ctx.mouse.inside(&fill_rect);
 
             let bg = if state.fill_shapes {
-                ACCENT_COLOR
+                theme().accent
             } else if fill_hovered {
                 Color::new(0.35, 0.35, 0.38, 1.0)
             } else {
@@ -2812,7 +3590,7 @@ pub fn draw_tool_panel(
             draw_rectangle(fill_rect.x, fill_rect.y, fill_rect.w, fill_rect.h, bg);
 
             if let Some(font) = icon_font {
-                draw_icon_in_rect(font, icon::DROPLET, &fill_rect, if state.fill_shapes { WHITE } else { TEXT_COLOR });
+                draw_icon_in_rect(font, icon::DROPLET, &fill_rect, if state.fill_shapes { WHITE } else { theme().text });
             }
 
             if fill_hovered {
@@ -2835,7 +3613,7 @@ pub fn draw_tool_panel(
         let cont_rect = Rect::new(col1_x, y, btn_size, btn_size);
         let cont_hovered = ctx.mouse.inside(&cont_rect);
         let cont_bg = if state.contiguous_select {
-            ACCENT_COLOR
+            theme().accent
         } else if cont_hovered {
             Color::new(0.35, 0.35, 0.38, 1.0)
         } else {
@@ -2845,7 +3623,7 @@ pub fn draw_tool_panel(
         // Draw link icon when contiguous, layers icon when selecting all
         if let Some(font) = icon_font {
             let icon_char = if state.contiguous_select { icon::LINK } else { icon::LAYERS };
-            draw_icon_in_rect(font, icon_char, &cont_rect, if state.contiguous_select { WHITE } else { TEXT_COLOR });
+            draw_icon_in_rect(font, icon_char, &cont_rect, if state.contiguous_select { WHITE } else { theme().text });
         }
         if cont_hovered {
             ctx.set_tooltip(if state.contiguous_select { "Contiguous" } else { "All matching" }, ctx.mouse.x, ctx.mouse.y);
@@ -2863,7 +3641,7 @@ pub fn draw_tool_panel(
         let minus_hovered = ctx.mouse.inside(&minus_rect);
         draw_rectangle(minus_rect.x, minus_rect.y, minus_rect.w, minus_rect.h,
             if minus_hovered { Color::new(0.35, 0.35, 0.38, 1.0) } else { Color::new(0.22, 0.22, 0.25, 1.0) });
-        draw_text("-", minus_rect.x + small_btn / 2.0 - 2.0, minus_rect.y + small_btn / 2.0 + 4.0, 12.0, TEXT_COLOR);
+        draw_text("-", minus_rect.x + small_btn / 2.0 - 2.0, minus_rect.y + small_btn / 2.0 + 4.0, 12.0, theme().text);
         if ctx.mouse.clicked(&minus_rect) && state.color_tolerance > 0 {
             state.color_tolerance = state.color_tolerance.saturating_sub(1);
         }
@@ -2879,7 +3657,7 @@ pub fn draw_tool_panel(
         let plus_hovered = ctx.mouse.inside(&plus_rect);
         draw_rectangle(plus_rect.x, plus_rect.y, plus_rect.w, plus_rect.h,
             if plus_hovered { Color::new(0.35, 0.35, 0.38, 1.0) } else { Color::new(0.22, 0.22, 0.25, 1.0) });
-        draw_text("+", plus_rect.x + small_btn / 2.0 - 3.0, plus_rect.y + small_btn / 2.0 + 4.0, 12.0, TEXT_COLOR);
+        draw_text("+", plus_rect.x + small_btn / 2.0 - 3.0, plus_rect.y + small_btn / 2.0 + 4.0, 12.0, theme().text);
         if ctx.mouse.clicked(&plus_rect) {
             state.color_tolerance = state.color_tolerance.saturating_add(1).min(16);
         }
@@ -2900,7 +3678,7 @@ fn draw_tool_button_small(
     let hovered = ctx.mouse.inside(&btn_rect);
 
     let bg = if is_selected {
-        ACCENT_COLOR
+        theme().accent
     } else if hovered {
         Color::new(0.35, 0.35, 0.38, 1.0)
     } else {
@@ -2910,7 +3688,7 @@ fn draw_tool_button_small(
 
     if let Some(font) = icon_font {
         let icon_char = tool.icon();
-        draw_icon_in_rect(font, icon_char, &btn_rect, if is_selected { WHITE } else { TEXT_COLOR });
+        draw_icon_in_rect(font, icon_char, &btn_rect, if is_selected { WHITE } else { theme().text });
     }
 
     if hovered {
@@ -2941,7 +3719,7 @@ fn draw_action_button_small(
     draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
 
     if let Some(font) = icon_font {
-        draw_icon_in_rect(font, icon_char, &btn_rect, TEXT_COLOR);
+        draw_icon_in_rect(font, icon_char, &btn_rect, theme().text);
     }
 
     if hovered {
@@ -2966,7 +3744,7 @@ fn draw_toggle_button_small(
     let hovered = ctx.mouse.inside(&btn_rect);
 
     let bg = if is_active {
-        ACCENT_COLOR
+        theme().accent
     } else if hovered {
         Color::new(0.35, 0.35, 0.38, 1.0)
     } else {
@@ -2975,7 +3753,7 @@ fn draw_toggle_button_small(
     draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, bg);
 
     if let Some(font) = icon_font {
-        draw_icon_in_rect(font, icon_char, &btn_rect, if is_active { WHITE } else { TEXT_COLOR });
+        draw_icon_in_rect(font, icon_char, &btn_rect, if is_active { WHITE } else { theme().text });
     }
 
     if hovered {
@@ -3017,7 +3795,7 @@ fn draw_tool_button(
     let hovered = ctx.mouse.inside(&btn_rect);
 
     let bg = if is_selected {
-        ACCENT_COLOR
+        theme().accent
     } else if hovered {
         Color::new(0.35, 0.35, 0.38, 1.0)
     } else {
@@ -3036,7 +3814,7 @@ fn draw_tool_button(
             TextParams {
                 font: Some(font),
                 font_size: icon_size,
-                color: if is_selected { WHITE } else { TEXT_COLOR },
+                color: if is_selected { WHITE } else { theme().text },
                 ..Default::default()
             },
         );
@@ -3078,7 +3856,7 @@ fn draw_action_button(
             TextParams {
                 font: Some(font),
                 font_size: 14,
-                color: TEXT_COLOR,
+                color: theme().text,
                 ..Default::default()
             },
         );
@@ -3108,7 +3886,7 @@ pub fn draw_palette_panel_constrained(
     rect: Rect,
     texture: &mut UserTexture,
     state: &mut TextureEditorState,
-    _icon_font: Option<&Font>,
+    icon_font: Option<&Font>,
     top_section_w: Option<f32>,
 ) {
     let padding = 4.0;
@@ -3117,9 +3895,10 @@ pub fn draw_palette_panel_constrained(
     // Width for top sections (4/8-bit buttons, Gen) - can be constrained to avoid tool panel overlap
     let top_w = top_section_w.unwrap_or(rect.w);
 
-    // CLUT depth toggle buttons
-    let btn_w = (top_w - padding * 3.0) / 2.0;
+    // CLUT depth toggle buttons (leave room for palette file import/export on the right)
     let btn_h = 18.0;
+    let file_btns_w = btn_h * 2.0 + 2.0;
+    let btn_w = (top_w - file_btns_w - padding * 4.0) / 2.0;
 
     let btn_4bit = Rect::new(rect.x + padding, y, btn_w, btn_h);
     let btn_8bit = Rect::new(rect.x + padding * 2.0 + btn_w, y, btn_w, btn_h);
@@ -3127,23 +3906,23 @@ pub fn draw_palette_panel_constrained(
     let is_4bit = texture.depth == ClutDepth::Bpp4;
 
     // 4-bit button
-    let bg_4bit = if is_4bit { ACCENT_COLOR } else { Color::new(0.22, 0.22, 0.24, 1.0) };
+    let bg_4bit = if is_4bit { theme().accent } else { Color::new(0.22, 0.22, 0.24, 1.0) };
     let hover_4bit = ctx.mouse.inside(&btn_4bit) && !is_4bit;
     draw_rectangle(btn_4bit.x, btn_4bit.y, btn_4bit.w, btn_4bit.h,
         if hover_4bit { Color::new(0.28, 0.28, 0.30, 1.0) } else { bg_4bit });
     let text_4bit = "4-bit";
     let tw = text_4bit.len() as f32 * 4.5;
     draw_text(text_4bit, btn_4bit.x + (btn_4bit.w - tw) / 2.0, btn_4bit.y + 13.0, 12.0,
-        if is_4bit { WHITE } else { TEXT_COLOR });
+        if is_4bit { WHITE } else { theme().text });
 
     // 8-bit button
-    let bg_8bit = if !is_4bit { ACCENT_COLOR } else { Color::new(0.22, 0.22, 0.24, 1.0) };
+    let bg_8bit = if !is_4bit { theme().accent } else { Color::new(0.22, 0.22, 0.24, 1.0) };
     let hover_8bit = ctx.mouse.inside(&btn_8bit) && is_4bit;
     draw_rectangle(btn_8bit.x, btn_8bit.y, btn_8bit.w, btn_8bit.h,
         if hover_8bit { Color::new(0.28, 0.28, 0.30, 1.0) } else { bg_8bit });
     let text_8bit = "8-bit";
     draw_text(text_8bit, btn_8bit.x + (btn_8bit.w - tw) / 2.0, btn_8bit.y + 13.0, 12.0,
-        if !is_4bit { WHITE } else { TEXT_COLOR });
+        if !is_4bit { WHITE } else { theme().text });
 
     // Handle depth toggle clicks
     if ctx.mouse.clicked(&btn_4bit) && !is_4bit {
@@ -3162,6 +3941,15 @@ pub fn draw_palette_panel_constrained(
         state.dirty = true;
     }
 
+    // Palette file import/export (file dialogs are run by the caller)
+    let file_x = btn_8bit.right() + padding;
+    if draw_action_button_small(ctx, file_x, y, btn_h, icon::FOLDER_OPEN, "Import palette (.gpl/.pal/.hex)", icon_font) {
+        state.palette_file_request = Some(super::palette_file::PaletteFileRequest::Import);
+    }
+    if draw_action_button_small(ctx, file_x + btn_h + 2.0, y, btn_h, icon::DOWNLOAD, "Export palette", icon_font) {
+        state.palette_file_request = Some(super::palette_file::PaletteFileRequest::Export);
+    }
+
     y += btn_h + 4.0;
 
     // Palette generator section (only for 4-bit mode)
@@ -3420,7 +4208,7 @@ pub fn draw_palette_panel_constrained(
             rect.x + padding,
             y + 11.0,
             12.0,
-            TEXT_DIM,
+            theme().text_dim,
         );
 
         // Sample colors toggle button (small swatch icon on the right)
@@ -3490,13 +4278,8 @@ pub fn draw_palette_panel_constrained(
 
                     // Click to apply color
                     if ctx.mouse.clicked(&cell_rect) {
-                        if let Some(key_idx) = editing_key_color {
-                            state.palette_gen_colors[key_idx] = (r, g, b);
-                        } else if selected_idx < texture.palette.len() {
-                            let semi = texture.palette[selected_idx].is_semi_transparent();
-                            texture.palette[selected_idx] = Color15::new_semi(r, g, b, semi);
-                            state.dirty = true;
-                        }
+                        set_edited_color(state, texture, editing_key_color, selected_idx, (r, g, b));
+                        push_recent(&mut state.recent_colors, (r, g, b));
                     }
                 }
             }
@@ -3533,7 +4316,7 @@ pub fn draw_palette_panel_constrained(
             let handle_x = track_rect.x + track_rect.w * fill_ratio - 2.0;
             draw_rectangle(handle_x.max(track_rect.x), track_rect.y, 4.0, slider_h, WHITE);
 
-            draw_text(&format!("{}", value), track_rect.x + track_rect.w + 4.0, y + 9.0, 11.0, TEXT_DIM);
+            draw_text(&format!("{}", value), track_rect.x + track_rect.w + 4.0, y + 9.0, 11.0, theme().text_dim);
 
             // Slider interaction
             if ctx.mouse.inside(&track_rect) && ctx.mouse.left_down && state.color_slider.is_none() {
@@ -3567,6 +4350,8 @@ pub fn draw_palette_panel_constrained(
                     }
                 } else {
                     state.color_slider = None;
+                    let rgb = edited_color(state, texture, editing_key_color, selected_idx);
+                    push_recent(&mut state.recent_colors, rgb);
                 }
             }
 
@@ -3590,11 +4375,11 @@ pub fn draw_palette_panel_constrained(
             // Checkmark if checked
             if is_stp {
                 draw_rectangle(checkbox_rect.x + 2.0, checkbox_rect.y + 2.0,
-                    checkbox_rect.w - 4.0, checkbox_rect.h - 4.0, ACCENT_COLOR);
+                    checkbox_rect.w - 4.0, checkbox_rect.h - 4.0, theme().accent);
             }
 
             // Label "Effect:"
-            draw_text("Effect:", checkbox_rect.right() + 4.0, y + 10.0, 12.0, TEXT_COLOR);
+            draw_text("Effect:", checkbox_rect.right() + 4.0, y + 10.0, 12.0, theme().text);
 
             // Blend mode dropdown (right next to checkbox)
             let dropdown_x = checkbox_rect.right() + 48.0;
@@ -3620,10 +4405,10 @@ pub fn draw_palette_panel_constrained(
                 Color::new(0.22, 0.22, 0.24, 1.0)
             };
             draw_rectangle(dropdown_rect.x, dropdown_rect.y, dropdown_rect.w, dropdown_rect.h, dropdown_bg);
-            draw_text(current_name, dropdown_rect.x + 4.0, dropdown_rect.y + 11.0, 11.0, TEXT_COLOR);
+            draw_text(current_name, dropdown_rect.x + 4.0, dropdown_rect.y + 11.0, 11.0, theme().text);
 
             // Dropdown arrow
-            draw_text("\u{25BC}", dropdown_rect.right() - 10.0, dropdown_rect.y + 10.0, 9.0, TEXT_DIM);
+            draw_text("\u{25BC}", dropdown_rect.right() - 10.0, dropdown_rect.y + 10.0, 9.0, theme().text_dim);
 
             // Click handlers
             let checkbox_click_area = Rect::new(checkbox_rect.x, y, 54.0, 14.0);
@@ -3648,9 +4433,9 @@ pub fn draw_palette_panel_constrained(
                 for (i, name) in blend_names.iter().enumerate() {
                     let opt_rect = Rect::new(dropdown_rect.x, menu_y + i as f32 * option_h, dropdown_rect.w, option_h);
                     if ctx.mouse.inside(&opt_rect) {
-                        draw_rectangle(opt_rect.x, opt_rect.y, opt_rect.w, opt_rect.h, ACCENT_COLOR);
+                        draw_rectangle(opt_rect.x, opt_rect.y, opt_rect.w, opt_rect.h, theme().accent);
                     }
-                    let text_color = if i == current_idx { WHITE } else { TEXT_COLOR };
+                    let text_color = if i == current_idx { WHITE } else { theme().text };
                     draw_text(name, opt_rect.x + 4.0, opt_rect.y + 13.0, 11.0, text_color);
 
                     if ctx.mouse.clicked(&opt_rect) {
@@ -3675,9 +4460,209 @@ pub fn draw_palette_panel_constrained(
                     }
                 }
             }
+            y += 18.0;
+        }
+
+        // Color picker (hex, HSV wheel, recent swatches) - hidden while the
+        // blend dropdown menu is covering it
+        if !state.blend_dropdown_open {
+            draw_color_picker(ctx, rect, y, padding, texture, state);
+        }
+    }
+
+}
+
+/// Current RGB555 value of the key color or palette entry being edited
+fn edited_color(
+    state: &TextureEditorState,
+    texture: &UserTexture,
+    key_idx: Option<usize>,
+    palette_idx: usize,
+) -> (u8, u8, u8) {
+    if let Some(key_idx) = key_idx {
+        state.palette_gen_colors[key_idx]
+    } else {
+        let c = texture.palette[palette_idx];
+        (c.r5(), c.g5(), c.b5())
+    }
+}
+
+/// Write an RGB555 value to the key color or palette entry being edited,
+/// keeping the entry's semi-transparency bit
+fn set_edited_color(
+    state: &mut TextureEditorState,
+    texture: &mut UserTexture,
+    key_idx: Option<usize>,
+    palette_idx: usize,
+    (r, g, b): (u8, u8, u8),
+) {
+    if let Some(key_idx) = key_idx {
+        state.palette_gen_colors[key_idx] = (r, g, b);
+    } else if palette_idx < texture.palette.len() {
+        let semi = texture.palette[palette_idx].is_semi_transparent();
+        texture.palette[palette_idx] = Color15::new_semi(r, g, b, semi);
+        state.dirty = true;
+    }
+}
+
+/// Hex entry, HSV wheel + value bar and recent swatches below the RGB sliders.
+/// Each row is only drawn if it fits in the panel.
+fn draw_color_picker(
+    ctx: &mut UiContext,
+    rect: Rect,
+    mut y: f32,
+    padding: f32,
+    texture: &mut UserTexture,
+    state: &mut TextureEditorState,
+) {
+    use macroquad::prelude::{is_key_pressed, KeyCode};
+
+    let key_idx = state.palette_gen_editing;
+    let palette_idx = state.selected_index as usize;
+
+    let current = edited_color(state, texture, key_idx, palette_idx);
+
+    // Hex row: click to type, Enter applies the 5-bit quantized color
+    let hex_h = 16.0;
+    if y + hex_h > rect.bottom() - padding {
+        state.hex_input = None;
+        return;
+    }
+    draw_text("Hex", rect.x + padding, y + 12.0, 12.0, theme().text_dim);
+    let field_rect = Rect::new(rect.x + padding + 26.0, y, 70.0, hex_h);
+    let swatch = hex_h - 2.0;
+    let swatch_x = field_rect.right() + 4.0;
+    let mut commit = None;
+    let mut close = false;
+
+    if let Some(input) = state.hex_input.as_mut() {
+        crate::ui::draw_text_input(field_rect, input, 11.0);
+        let typed = parse_hex(&input.text);
+        if let Some(rgb8) = typed {
+            // Typed color next to what the CLUT will actually store
+            let quantized = quantize_rgb(rgb8);
+            draw_rectangle(swatch_x, y + 1.0, swatch, swatch, Color::from_rgba(rgb8.0, rgb8.1, rgb8.2, 255));
+            draw_rectangle(swatch_x + swatch, y + 1.0, swatch, swatch, rgb5_color(quantized));
+            draw_text(&hex_string(quantized), swatch_x + swatch * 2.0 + 4.0, y + 12.0, 11.0, theme().text_dim);
+            let preview_rect = Rect::new(swatch_x, y + 1.0, swatch * 2.0, swatch);
+            if ctx.mouse.inside(&preview_rect) {
+                ctx.set_tooltip("Typed color | 5-bit result", ctx.mouse.x, ctx.mouse.y);
+            }
+        } else {
+            draw_text("invalid", swatch_x, y + 12.0, 11.0, Color::new(0.9, 0.4, 0.4, 1.0));
+        }
+        if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            commit = typed.map(quantize_rgb);
+            close = true;
+        }
+        if is_key_pressed(KeyCode::Escape) || (ctx.mouse.left_pressed && !ctx.mouse.inside(&field_rect)) {
+            close = true;
+        }
+    } else {
+        let hovered = ctx.mouse.inside(&field_rect);
+        let bg = if hovered { Color::new(0.18, 0.18, 0.20, 1.0) } else { Color::new(0.12, 0.12, 0.14, 1.0) };
+        draw_rectangle(field_rect.x, field_rect.y, field_rect.w, field_rect.h, bg);
+        draw_rectangle_lines(field_rect.x, field_rect.y, field_rect.w, field_rect.h, 1.0, Color::new(0.3, 0.3, 0.32, 1.0));
+        draw_text(&hex_string(current), field_rect.x + 4.0, y + 12.0, 11.0, theme().text);
+        draw_rectangle(swatch_x, y + 1.0, swatch, swatch, rgb5_color(current));
+        if hovered {
+            ctx.set_tooltip("Type a hex color (#RRGGBB), quantized to 5 bits per channel", ctx.mouse.x, ctx.mouse.y);
+        }
+        if ctx.mouse.clicked(&field_rect) {
+            state.hex_input = Some(crate::ui::TextInputState::new(hex_string(current)));
+        }
+    }
+
+    if let Some(rgb5) = commit {
+        set_edited_color(state, texture, key_idx, palette_idx, rgb5);
+        push_recent(&mut state.recent_colors, rgb5);
+    }
+    if close {
+        state.hex_input = None;
+    }
+    y += hex_h + 6.0;
+
+    // HSV wheel + value bar
+    let current = edited_color(state, texture, key_idx, palette_idx);
+    let wheel_size = (rect.w - padding * 2.0 - 18.0).min(72.0);
+    if wheel_size >= 32.0 && y + wheel_size <= rect.bottom() - padding {
+        // Resync with the edited color unless the picker already maps to it,
+        // so hue and saturation survive greys and black
+        let (ph, ps, pv) = state.picker_hsv;
+        if hsv_to_rgb5(ph, ps, pv) != current {
+            let (h, s, v) = rgb5_to_hsv(current);
+            state.picker_hsv = if s > 0.0 { (h, s, v) } else { (ph, if v > 0.0 { s } else { ps }, v) };
+        }
+
+        let radius = wheel_size / 2.0;
+        let cx = rect.x + padding + radius;
+        let cy = y + radius;
+        let (h, s, v) = state.picker_hsv;
+        draw_hsv_wheel(cx, cy, radius, v, (h, s));
+
+        let bar_rect = Rect::new(rect.x + padding + wheel_size + 6.0, y, 10.0, wheel_size);
+        draw_value_bar(bar_rect.x, bar_rect.y, bar_rect.w, bar_rect.h, h, s, v);
+
+        if ctx.mouse.left_down && state.color_slider.is_none() {
+            if wheel_pick(cx, cy, radius, ctx.mouse.x, ctx.mouse.y, false).is_some() {
+                state.color_slider = Some(3);
+            } else if ctx.mouse.inside(&bar_rect) {
+                state.color_slider = Some(4);
+            }
+        }
+
+        if let Some(slider @ (3 | 4)) = state.color_slider {
+            if ctx.mouse.left_down {
+                if slider == 3 {
+                    if let Some((nh, ns)) = wheel_pick(cx, cy, radius, ctx.mouse.x, ctx.mouse.y, true) {
+                        state.picker_hsv.0 = nh;
+                        state.picker_hsv.1 = ns;
+                    }
+                } else {
+                    state.picker_hsv.2 = 1.0 - ((ctx.mouse.y - bar_rect.y) / bar_rect.h).clamp(0.0, 1.0);
+                }
+                let (h, s, v) = state.picker_hsv;
+                set_edited_color(state, texture, key_idx, palette_idx, hsv_to_rgb5(h, s, v));
+            } else {
+                state.color_slider = None;
+                push_recent(&mut state.recent_colors, current);
+            }
         }
+        y += wheel_size + 6.0;
     }
 
+    // Recently used swatches (shared by every texture edited in this editor)
+    if state.recent_colors.is_empty() {
+        return;
+    }
+    let gap = 2.0;
+    let cols = MAX_RECENT_COLORS;
+    let cell = ((rect.w - padding * 2.0 - gap * (cols - 1) as f32) / cols as f32).floor().max(4.0);
+    if y + 14.0 + cell > rect.bottom() - padding {
+        return;
+    }
+    draw_text("Recent", rect.x + padding, y + 10.0, 12.0, theme().text_dim);
+    y += 14.0;
+
+    let mut picked = None;
+    for (i, &rgb5) in state.recent_colors.iter().enumerate() {
+        let cell_rect = Rect::new(rect.x + padding + i as f32 * (cell + gap), y, cell, cell);
+        draw_rectangle(cell_rect.x, cell_rect.y, cell, cell, rgb5_color(rgb5));
+        if rgb5 == current {
+            draw_rectangle_lines(cell_rect.x - 1.0, cell_rect.y - 1.0, cell + 2.0, cell + 2.0, 1.0, theme().accent);
+        }
+        if ctx.mouse.inside(&cell_rect) {
+            draw_rectangle_lines(cell_rect.x - 1.0, cell_rect.y - 1.0, cell + 2.0, cell + 2.0, 2.0, WHITE);
+            ctx.set_tooltip(&hex_string(rgb5), ctx.mouse.x, ctx.mouse.y);
+            if ctx.mouse.clicked(&cell_rect) {
+                picked = Some(rgb5);
+            }
+        }
+    }
+    if let Some(rgb5) = picked {
+        set_edited_color(state, texture, key_idx, palette_idx, rgb5);
+        push_recent(&mut state.recent_colors, rgb5);
+    }
 }
 
 /// Calculate bounding box of selected UV vertices in UV space
@@ -5118,3 +6103,307 @@ pub fn draw_import_dialog(
 
     None
 }
+
+// ============================================================================
+// Palette Import Dialog
+// ============================================================================
+
+/// Helper: option button used by the palette import dialog, returns true if clicked
+fn draw_dialog_option(ctx: &mut UiContext, rect: Rect, label: &str, is_selected: bool) -> bool {
+    let hovered = ctx.mouse.inside(&rect);
+    let bg = if is_selected { Color::from_rgba(60, 90, 130, 255) } else if hovered { Color::from_rgba(55, 55, 65, 255) } else { Color::from_rgba(45, 45, 55, 255) };
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, bg);
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, Color::from_rgba(70, 70, 80, 255));
+    let dims = measure_text(label, None, 9, 1.0);
+    draw_text(label, rect.x + (rect.w - dims.width) / 2.0, rect.y + rect.h / 2.0 + 3.0, 9.0,
+        if is_selected { WHITE } else { Color::from_rgba(180, 180, 180, 255) });
+    hovered && ctx.mouse.clicked(&rect) && !is_selected
+}
+
+/// Helper: draw a row-wrapped block of swatches, returns the height used
+fn draw_swatch_block(x: f32, y: f32, w: f32, colors: impl Iterator<Item = Color15>, count: usize) -> f32 {
+    let swatch_size = if count > 64 { 8.0 } else { 16.0 };
+    let cols = ((w / swatch_size) as usize).max(1);
+    for (idx, color) in colors.enumerate() {
+        let sx = x + (idx % cols) as f32 * swatch_size;
+        let sy = y + (idx / cols) as f32 * swatch_size;
+        let [r, g, b, _] = color.to_rgba();
+        draw_rectangle(sx, sy, swatch_size - 1.0, swatch_size - 1.0, Color::from_rgba(r, g, b, 255));
+    }
+    count.div_ceil(cols) as f32 * swatch_size
+}
+
+/// Draw the palette file import dialog overlay
+/// Returns Some(ImportAction) if user clicked a button
+pub fn draw_palette_import_dialog(
+    ctx: &mut UiContext,
+    import_state: &mut super::palette_file::PaletteImportState,
+    _icon_font: Option<&Font>,
+) -> Option<ImportAction> {
+    use crate::modeler::QuantizeMode;
+
+    if !import_state.active {
+        return None;
+    }
+    import_state.update_preview();
+
+    // Darken background
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
+
+    let btn_h = 20.0;
+    let btn_gap = 3.0;
+    let dialog_w = 420.0;
+    let dialog_h = 400.0;
+    let dialog_x = (screen_width() - dialog_w) / 2.0;
+    let dialog_y = (screen_height() - dialog_h) / 2.0;
+
+    draw_rectangle(dialog_x, dialog_y, dialog_w, dialog_h, Color::from_rgba(40, 40, 45, 255));
+    draw_rectangle_lines(dialog_x, dialog_y, dialog_w, dialog_h, 2.0, Color::from_rgba(70, 70, 80, 255));
+
+    // === TITLE BAR with action buttons ===
+    let title_h = 32.0;
+    draw_rectangle(dialog_x, dialog_y, dialog_w, title_h, Color::from_rgba(35, 35, 40, 255));
+    draw_text("Import Palette", dialog_x + 12.0, dialog_y + 21.0, 16.0, WHITE);
+
+    let action_btn_w = 70.0;
+    let action_btn_h = 22.0;
+    let action_btn_y = dialog_y + 5.0;
+
+    let cancel_x = dialog_x + dialog_w - action_btn_w * 2.0 - btn_gap - 10.0;
+    let cancel_rect = Rect::new(cancel_x, action_btn_y, action_btn_w, action_btn_h);
+    let cancel_hovered = ctx.mouse.inside(&cancel_rect);
+    draw_rectangle(cancel_x, action_btn_y, action_btn_w, action_btn_h,
+        if cancel_hovered { Color::from_rgba(80, 55, 55, 255) } else { Color::from_rgba(60, 50, 50, 255) });
+    draw_rectangle_lines(cancel_x, action_btn_y, action_btn_w, action_btn_h, 1.0, Color::from_rgba(90, 70, 70, 255));
+    let cancel_dims = measure_text("Cancel", None, 11, 1.0);
+    draw_text("Cancel", cancel_x + (action_btn_w - cancel_dims.width) / 2.0, action_btn_y + 15.0, 11.0,
+        if cancel_hovered { WHITE } else { Color::from_rgba(200, 180, 180, 255) });
+
+    if cancel_hovered && ctx.mouse.clicked(&cancel_rect) {
+        import_state.reset();
+        return Some(ImportAction::Cancel);
+    }
+
+    let apply_x = dialog_x + dialog_w - action_btn_w - 10.0;
+    let apply_rect = Rect::new(apply_x, action_btn_y, action_btn_w, action_btn_h);
+    let apply_hovered = ctx.mouse.inside(&apply_rect);
+    draw_rectangle(apply_x, action_btn_y, action_btn_w, action_btn_h,
+        if apply_hovered { Color::from_rgba(55, 90, 70, 255) } else { Color::from_rgba(50, 75, 60, 255) });
+    draw_rectangle_lines(apply_x, action_btn_y, action_btn_w, action_btn_h, 1.0, Color::from_rgba(70, 110, 90, 255));
+    let apply_dims = measure_text("Apply", None, 11, 1.0);
+    draw_text("Apply", apply_x + (action_btn_w - apply_dims.width) / 2.0, action_btn_y + 15.0, 11.0,
+        if apply_hovered { WHITE } else { Color::from_rgba(170, 210, 190, 255) });
+
+    if apply_hovered && ctx.mouse.clicked(&apply_rect) {
+        return Some(ImportAction::Confirm);
+    }
+
+    let x = dialog_x + 12.0;
+    let w = dialog_w - 24.0;
+    let mut y = dialog_y + title_h + 8.0;
+    let label_color = Color::from_rgba(150, 150, 150, 255);
+
+    // Source palette as read from the file
+    let info = format!("{} | {} | {} colors", import_state.file_name, import_state.format.label(), import_state.source.len());
+    draw_text(&info, x, y + 10.0, 10.0, label_color);
+    y += 16.0;
+    y += draw_swatch_block(x, y, w,
+        import_state.source.iter().map(|&(r, g, b)| Color15::from_rgb888(r, g, b)),
+        import_state.source.len());
+    y += 10.0;
+
+    // Fitted palette that will be written to the texture
+    let slots = import_state.depth.color_count() - 1;
+    let fitted_label = if import_state.needs_quantize() {
+        format!("Reduced to {} of {} slots", import_state.preview.len(), slots)
+    } else {
+        format!("{} of {} slots", import_state.preview.len(), slots)
+    };
+    draw_text(&fitted_label, x, y + 10.0, 10.0, label_color);
+    y += 16.0;
+    y += draw_swatch_block(x, y, w, import_state.preview.iter().copied(), import_state.preview.len());
+    y += 10.0;
+
+    // Quantize settings only matter when the palette doesn't fit
+    if import_state.needs_quantize() {
+        draw_text("Quantize", x, y + 10.0, 10.0, label_color);
+        y += 14.0;
+        let opt_w = (w - btn_gap * 4.0) / 5.0;
+        let modes = [(QuantizeMode::Standard, "Std"), (QuantizeMode::PreserveDetail, "Detail"), (QuantizeMode::Smooth, "Smooth")];
+        for (i, (mode, label)) in modes.iter().enumerate() {
+            let rect = Rect::new(x + i as f32 * (opt_w + btn_gap), y, opt_w, btn_h);
+            if draw_dialog_option(ctx, rect, label, import_state.quantize_mode == *mode) {
+                import_state.quantize_mode = *mode;
+                import_state.preview_dirty = true;
+            }
+        }
+        for (i, (use_lab, label)) in [(false, "RGB"), (true, "LAB")].iter().enumerate() {
+            let rect = Rect::new(x + (i + 3) as f32 * (opt_w + btn_gap), y, opt_w, btn_h);
+            if draw_dialog_option(ctx, rect, label, import_state.use_lab == *use_lab) {
+                import_state.use_lab = *use_lab;
+                import_state.preview_dirty = true;
+            }
+        }
+        y += btn_h + 8.0;
+    }
+
+    // Keep pixel indices (recolor) or move pixels to the nearest new color
+    draw_text("Pixels", x, y + 10.0, 10.0, label_color);
+    y += 14.0;
+    let opt_w = (w - btn_gap) / 2.0;
+    for (i, (remap, label)) in [(false, "Keep indices"), (true, "Remap to nearest")].iter().enumerate() {
+        let rect = Rect::new(x + i as f32 * (opt_w + btn_gap), y, opt_w, btn_h);
+        if draw_dialog_option(ctx, rect, label, import_state.remap_pixels == *remap) {
+            import_state.remap_pixels = *remap;
+        }
+    }
+
+    // Escape key to cancel
+    if is_key_pressed(KeyCode::Escape) {
+        import_state.reset();
+        return Some(ImportAction::Cancel);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::texture::TextureSize;
+
+    /// 8x8 texture whose pixel (x, y) holds y * 8 + x
+    fn numbered_texture() -> UserTexture {
+        let mut texture = UserTexture::new("test", TextureSize::Size8x8, ClutDepth::Bpp8);
+        for (i, index) in texture.indices.iter_mut().enumerate() {
+            *index = i as u8;
+        }
+        texture
+    }
+
+    fn shifted(shift: WrapShift) -> Vec<u8> {
+        let mut texture = numbered_texture();
+        apply_wrap_shift(&mut texture, &mut TextureEditorState::new(), shift);
+        texture.indices
+    }
+
+    #[test]
+    fn test_wrap_shift_offsets_wrap_around() {
+        let right = shifted(WrapShift::By(1, 0));
+        assert_eq!(right[0], 7);
+        assert_eq!(right[1], 0);
+
+        // Negative offsets pull from the other side
+        let up_left = shifted(WrapShift::By(-1, -1));
+        assert_eq!(up_left[0], 9);
+        assert_eq!(up_left[7 * 8 + 7], 0);
+
+        // Offsets are taken modulo the size
+        assert_eq!(shifted(WrapShift::By(-7, 0)), right);
+        assert_eq!(shifted(WrapShift::By(9, 0)), right);
+        assert_eq!(shifted(WrapShift::By(17, -8)), right);
+        assert_eq!(shifted(WrapShift::By(8, -16)), numbered_texture().indices);
+    }
+
+    #[test]
+    fn test_wrap_shift_half_on_odd_selection() {
+        // 3x5 selection: half rounds down to (1, 2)
+        let mut texture = numbered_texture();
+        let mut state = TextureEditorState::new();
+        state.selection = Some(Selection::from_corners(0, 0, 2, 4));
+        apply_wrap_shift(&mut texture, &mut state, WrapShift::Half);
+        assert_eq!(state.take_status().as_deref(), Some("Offset 1, 2"));
+
+        let mut expected = numbered_texture();
+        state.selection = Some(Selection::from_corners(0, 0, 2, 4));
+        apply_wrap_shift(&mut expected, &mut state, WrapShift::By(1, 2));
+        assert_eq!(texture.indices, expected.indices);
+
+        // Pixel (0, 2) comes from (2, 0); everything outside the selection stays put
+        assert_eq!(texture.indices[2 * 8], 2);
+        assert_eq!(texture.indices[3], 3);
+        assert_eq!(texture.indices[5 * 8], 40);
+    }
+
+    #[test]
+    fn test_wrap_shift_selection_clipped_at_edge() {
+        // Selection hangs off the top-right corner; only (6..8, 0..2) shifts
+        let mut texture = numbered_texture();
+        let mut state = TextureEditorState::new();
+        state.selection = Some(Selection { x: 6, y: -2, width: 4, height: 4, floating: None, mask: None });
+        apply_wrap_shift(&mut texture, &mut state, WrapShift::By(1, 0));
+
+        assert_eq!(&texture.indices[6..8], &[7, 6]);
+        assert_eq!(&texture.indices[14..16], &[15, 14]);
+        assert_eq!(texture.indices[5], 5);
+        assert_eq!(texture.indices[2 * 8 + 6], 22);
+    }
+
+    #[test]
+    fn test_wrap_shift_floating_selection() {
+        // A floating selection shifts its own pixels, not the texture's
+        let mut texture = numbered_texture();
+        let mut state = TextureEditorState::new();
+        state.selection = Some(Selection {
+            x: 2,
+            y: 2,
+            width: 3,
+            height: 2,
+            floating: Some(vec![0, 1, 2, 3, 4, 5]),
+            mask: None,
+        });
+        apply_wrap_shift(&mut texture, &mut state, WrapShift::By(1, 1));
+
+        let floating = state.selection.unwrap().floating.unwrap();
+        assert_eq!(floating, vec![5, 3, 4, 2, 0, 1]);
+        assert_eq!(texture.indices, numbered_texture().indices);
+    }
+
+    #[test]
+    fn test_wrap_shift_animation_frame() {
+        // Shifting frame 1 leaves frame 0 alone and keeps the frame order
+        let mut texture = numbered_texture();
+        assert_eq!(texture.duplicate_frame(0), Some(1));
+        let mut state = TextureEditorState::new();
+        state.anim_frame = 1;
+        apply_wrap_shift(&mut texture, &mut state, WrapShift::By(1, 0));
+
+        assert_eq!(texture.frame_indices(0), &numbered_texture().indices[..]);
+        assert_eq!(texture.frame_indices(1), &shifted(WrapShift::By(1, 0))[..]);
+        assert_eq!(texture.indices, numbered_texture().indices);
+    }
+
+    #[test]
+    fn test_find_seams_flags_hard_edges_including_wrap() {
+        let mut tex = UserTexture::new("halves", TextureSize::Size8x8, ClutDepth::Bpp4);
+        tex.fill(1);
+        assert_eq!(find_seams(&tex), SeamReport::default());
+
+        // Red left half, blue right half: edges after column 3 and at the wrap
+        tex.palette[1] = Color15::new(31, 0, 0);
+        tex.palette[2] = Color15::new(0, 0, 31);
+        for y in 0..8 {
+            for x in 4..8 {
+                tex.set_index(x, y, 2);
+            }
+        }
+        let report = find_seams(&tex);
+        assert_eq!(report.columns, vec![3, 7]);
+        assert!(report.rows.is_empty());
+    }
+
+    #[test]
+    fn test_clone_dab_wraps_and_samples_stroke_start() {
+        let mut tex = UserTexture::new("clone", TextureSize::Size8x8, ClutDepth::Bpp4);
+        tex.fill(1);
+        tex.set_index(0, 0, 2);
+
+        // Copy from (0, 0) onto (7, 7); the 3x3 dab spills over both edges
+        let stroke = CloneStroke { offset: (-7, -7), source: tex.indices.clone() };
+        tex_clone_dab(&mut tex, 7, 7, 3, BrushShape::Square, &stroke);
+        assert_eq!(tex.get_index(7, 7), 2);
+        // (0, 0) is under the wrapped dab and copies (1, 1) from before the stroke
+        assert_eq!(tex.get_index(0, 0), 1);
+        assert_eq!(tex.get_index(6, 6), 1);
+    }
+}
@@ -119,8 +119,11 @@ impl TextureLibrary {
 
         let mut loaded = 0;
         for path in entries {
-            match UserTexture::load(&path) {
-                Ok(mut tex) => {
+            match UserTexture::load_repaired(&path) {
+                Ok((mut tex, issues)) => {
+                    for issue in issues {
+                        eprintln!("Repaired texture {:?}: {}", path, issue);
+                    }
                     // Set the source
                     tex.source = source;
 
@@ -470,6 +473,18 @@ impl TextureLibrary {
         self.samples().filter(|(_, tex)| tex.usable_in_world_editor())
     }
 
+    /// Key identifying which flipbook frame every animated texture shows at `time`
+    ///
+    /// Renderers compare this between frames to know when cached texture
+    /// conversions (e.g. RGB555) need rebuilding.
+    pub fn animation_key(&self, time: f64) -> u64 {
+        self.iter()
+            .filter(|(_, tex)| tex.is_animated())
+            .fold(0u64, |key, (_, tex)| {
+                key.wrapping_mul(31).wrapping_add(tex.frame_at_time(time) as u64 + 1)
+            })
+    }
+
     /// Save a texture to disk (native only)
     ///
     /// Only user textures can be saved. Sample textures are read-only.
@@ -11,6 +11,7 @@ use std::path::Path;
 #[cfg(not(target_arch = "wasm32"))]
 use std::fs;
 
+use crate::load_check::{describe_ron_error, unresolved, LoadIssue};
 use crate::rasterizer::{BlendMode, ClutDepth, Color15};
 use super::texture_library::TextureSource;
 
@@ -134,7 +135,8 @@ impl Default for TextureSize {
 #[derive(Debug)]
 pub enum TextureError {
     IoError(std::io::Error),
-    ParseError(ron::error::SpannedError),
+    /// RON that doesn't parse, described by `describe_ron_error`
+    ParseError(String),
     SerializeError(ron::Error),
     ValidationError(String),
 }
@@ -145,12 +147,6 @@ impl From<std::io::Error> for TextureError {
     }
 }
 
-impl From<ron::error::SpannedError> for TextureError {
-    fn from(e: ron::error::SpannedError) -> Self {
-        TextureError::ParseError(e)
-    }
-}
-
 impl From<ron::Error> for TextureError {
     fn from(e: ron::Error) -> Self {
         TextureError::SerializeError(e)
@@ -168,6 +164,98 @@ impl std::fmt::Display for TextureError {
     }
 }
 
+/// Decode texture file bytes (brotli-compressed or plain RON) to RON text
+fn texture_text(bytes: Vec<u8>) -> Result<String, TextureError> {
+    // Detect format: RON files start with '(' or whitespace, brotli is binary
+    let is_plain_ron = bytes
+        .first()
+        .map(|&b| b == b'(' || b == b' ' || b == b'\n' || b == b'\r' || b == b'\t')
+        .unwrap_or(false);
+
+    if is_plain_ron {
+        String::from_utf8(bytes).map_err(|e| {
+            TextureError::IoError(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid UTF-8: {}", e),
+            ))
+        })
+    } else {
+        // Brotli compressed - decompress first
+        let mut decompressed = Vec::new();
+        brotli::BrotliDecompress(&mut Cursor::new(&bytes), &mut decompressed).map_err(|e| {
+            TextureError::IoError(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("brotli decompression failed: {}", e),
+            ))
+        })?;
+        String::from_utf8(decompressed).map_err(|e| {
+            TextureError::IoError(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid UTF-8 after decompression: {}", e),
+            ))
+        })
+    }
+}
+
+/// Default flipbook playback rate (frames per second)
+pub const DEFAULT_ANIMATION_FPS: f32 = 8.0;
+
+/// Maximum number of frames in a flipbook texture
+pub const MAX_ANIMATION_FRAMES: usize = 32;
+
+/// Flipbook animation data for a texture
+///
+/// Frame 0 is always the texture's own `indices`, so a texture with no extra
+/// frames behaves exactly like a static texture. All frames share the palette.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextureAnimation {
+    /// Palette indices for frames 1..N (same layout as `UserTexture::indices`)
+    pub frames: Vec<Vec<u8>>,
+    /// Playback rate in frames per second
+    #[serde(default = "default_animation_fps")]
+    pub fps: f32,
+}
+
+fn default_animation_fps() -> f32 {
+    DEFAULT_ANIMATION_FPS
+}
+
+/// Maximum number of paint layers in a texture
+pub const MAX_TEXTURE_LAYERS: usize = 8;
+
+/// A paint layer of the base frame
+///
+/// Layers are an editing aid: `UserTexture::indices` always holds their
+/// composite (the topmost visible non-zero index wins), so rendering and
+/// export never look at them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextureLayer {
+    pub name: String,
+    /// Hidden layers are left out of the composite
+    #[serde(default = "default_true")]
+    pub visible: bool,
+    /// Palette indices (same layout as `UserTexture::indices`, 0 = see-through)
+    pub indices: Vec<u8>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Composite the visible `layers` (bottom first) into `len` indices: the
+/// topmost non-zero index wins, 0 where every layer is see-through
+pub fn composite_layer_indices(layers: &[TextureLayer], len: usize) -> Vec<u8> {
+    let mut out = vec![0; len];
+    for layer in layers.iter().filter(|l| l.visible) {
+        for (dst, &src) in out.iter_mut().zip(&layer.indices) {
+            if src != 0 {
+                *dst = src;
+            }
+        }
+    }
+    out
+}
+
 /// A user-created indexed texture with embedded palette
 ///
 /// This is a self-contained texture asset that includes:
@@ -200,6 +288,14 @@ pub struct UserTexture {
     /// Applies to pixels where palette entry has bit 15 (STP) set
     #[serde(default)]
     pub blend_mode: BlendMode,
+    /// Flipbook animation (water, lava, torch flames)
+    /// `indices` holds frame 0; additional frames are stored here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animation: Option<TextureAnimation>,
+    /// Paint layers of frame 0, bottom first (empty for single-layer textures)
+    /// `indices` holds their composite
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<TextureLayer>,
     /// Source/origin of this texture (runtime-only, not serialized)
     /// Determines whether the texture is from samples (read-only) or user-created (editable)
     #[serde(skip)]
@@ -258,6 +354,8 @@ impl UserTexture {
             indices,
             palette,
             blend_mode: BlendMode::Opaque,
+            animation: None,
+            layers: Vec::new(),
             source: TextureSource::User, // New textures are user-created by default
         }
     }
@@ -285,6 +383,8 @@ impl UserTexture {
             indices,
             palette,
             blend_mode: BlendMode::Opaque,
+            animation: None,
+            layers: Vec::new(),
             source: TextureSource::User, // New textures are user-created by default
         }
     }
@@ -365,78 +465,80 @@ impl UserTexture {
     /// Load a texture from a file (supports compressed and uncompressed RON)
     #[cfg(not(target_arch = "wasm32"))]
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TextureError> {
-        let path = path.as_ref();
-        let bytes = fs::read(path)?;
-
-        // Detect format: RON files start with '(' or whitespace, brotli is binary
-        let is_plain_ron = bytes
-            .first()
-            .map(|&b| b == b'(' || b == b' ' || b == b'\n' || b == b'\r' || b == b'\t')
-            .unwrap_or(false);
-
-        let contents = if is_plain_ron {
-            String::from_utf8(bytes).map_err(|e| {
-                TextureError::IoError(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    format!("invalid UTF-8: {}", e),
-                ))
-            })?
-        } else {
-            // Brotli compressed - decompress first
-            let mut decompressed = Vec::new();
-            brotli::BrotliDecompress(&mut Cursor::new(&bytes), &mut decompressed).map_err(|e| {
-                TextureError::IoError(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    format!("brotli decompression failed: {}", e),
-                ))
-            })?;
-            String::from_utf8(decompressed).map_err(|e| {
-                TextureError::IoError(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    format!("invalid UTF-8 after decompression: {}", e),
-                ))
-            })?
-        };
+        let contents = texture_text(fs::read(path)?)?;
+        Self::from_ron(&contents, false).map(|(texture, _)| texture)
+    }
 
-        let texture: UserTexture = ron::from_str(&contents)?;
-        texture.validate()?;
-        Ok(texture)
+    /// Load a texture from a file like `load`, repairing pixel data that
+    /// doesn't fit the palette instead of failing (see `check_pixels`)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_repaired<P: AsRef<Path>>(path: P) -> Result<(Self, Vec<LoadIssue>), TextureError> {
+        let contents = texture_text(fs::read(path)?)?;
+        Self::from_ron(&contents, true)
     }
 
     /// Load from bytes (for WASM async loading)
     pub fn load_from_bytes(bytes: &[u8]) -> Result<Self, TextureError> {
-        // Detect format
-        let is_plain_ron = bytes
-            .first()
-            .map(|&b| b == b'(' || b == b' ' || b == b'\n' || b == b'\r' || b == b'\t')
-            .unwrap_or(false);
-
-        let contents = if is_plain_ron {
-            String::from_utf8(bytes.to_vec()).map_err(|e| {
-                TextureError::IoError(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    format!("invalid UTF-8: {}", e),
-                ))
-            })?
-        } else {
-            let mut decompressed = Vec::new();
-            brotli::BrotliDecompress(&mut Cursor::new(bytes), &mut decompressed).map_err(|e| {
-                TextureError::IoError(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    format!("brotli decompression failed: {}", e),
-                ))
-            })?;
-            String::from_utf8(decompressed).map_err(|e| {
-                TextureError::IoError(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    format!("invalid UTF-8 after decompression: {}", e),
-                ))
-            })?
-        };
+        let contents = texture_text(bytes.to_vec())?;
+        Self::from_ron(&contents, false).map(|(texture, _)| texture)
+    }
 
-        let texture: UserTexture = ron::from_str(&contents)?;
+    /// Parse RON text and check it, repairing what can be repaired with `fix`
+    fn from_ron(contents: &str, fix: bool) -> Result<(Self, Vec<LoadIssue>), TextureError> {
+        let mut texture: UserTexture = ron::from_str(contents)
+            .map_err(|e| TextureError::ParseError(describe_ron_error(contents, &e)))?;
+        let issues = texture.check_pixels(fix);
+        if let Some(problems) = unresolved(&issues) {
+            return Err(TextureError::ValidationError(problems));
+        }
         texture.validate()?;
-        Ok(texture)
+        Ok((texture, issues))
+    }
+
+    /// Check the palette and base frame against the color depth: palette
+    /// length, pixel count and every index. With `fix`, the palette is padded
+    /// (transparent) or cut, missing pixels are added, extra ones dropped and
+    /// out-of-range indices become 0 (transparent).
+    pub fn check_pixels(&mut self, fix: bool) -> Vec<LoadIssue> {
+        let mut issues = Vec::new();
+        let expected_colors = self.depth.color_count();
+        if self.palette.len() != expected_colors {
+            issues.push(LoadIssue::repairable("palette", format!(
+                "{:?} needs {} colors, found {}", self.depth, expected_colors, self.palette.len()
+            ), fix));
+            if fix {
+                self.palette.resize(expected_colors, Color15::TRANSPARENT);
+            }
+        }
+
+        // An invalid size is for `validate` to reject, there's no pixel count to fix to
+        if TextureSize::from_dimensions(self.width, self.height).is_some() {
+            let expected_pixels = self.width * self.height;
+            if self.indices.len() != expected_pixels {
+                issues.push(LoadIssue::repairable("indices", format!(
+                    "a {}x{} texture has {} pixels, found {}", self.width, self.height, expected_pixels, self.indices.len()
+                ), fix));
+                if fix {
+                    self.indices.resize(expected_pixels, 0);
+                }
+            }
+        }
+
+        let max_index = self.depth.max_index();
+        let out_of_range: Vec<usize> = (0..self.indices.len()).filter(|&i| self.indices[i] > max_index).collect();
+        if let Some(&first) = out_of_range.first() {
+            let mut message = format!("index {} is past the {:?} palette (max {})", self.indices[first], self.depth, max_index);
+            if out_of_range.len() > 1 {
+                message.push_str(&format!(", {} more like it", out_of_range.len() - 1));
+            }
+            issues.push(LoadIssue::repairable(format!("indices[{}]", first), message, fix));
+            if fix {
+                for i in out_of_range {
+                    self.indices[i] = 0;
+                }
+            }
+        }
+        issues
     }
 
     /// Save the texture to a file (always uses Brotli compression)
@@ -522,6 +624,67 @@ impl UserTexture {
             }
         }
 
+        // Check animation frames match the base frame
+        if let Some(anim) = &self.animation {
+            if self.frame_count() > MAX_ANIMATION_FRAMES {
+                return Err(TextureError::ValidationError(format!(
+                    "too many animation frames: {} (max {})",
+                    self.frame_count(),
+                    MAX_ANIMATION_FRAMES
+                )));
+            }
+            if !(anim.fps > 0.0 && anim.fps <= 60.0) {
+                return Err(TextureError::ValidationError(format!(
+                    "invalid animation fps {} - must be in 0..=60",
+                    anim.fps
+                )));
+            }
+            for (f, frame) in anim.frames.iter().enumerate() {
+                if frame.len() != expected_pixels {
+                    return Err(TextureError::ValidationError(format!(
+                        "animation frame {} size mismatch: expected {}, got {}",
+                        f + 1,
+                        expected_pixels,
+                        frame.len()
+                    )));
+                }
+                if let Some(&index) = frame.iter().find(|&&i| i > max_index) {
+                    return Err(TextureError::ValidationError(format!(
+                        "animation frame {} index {} exceeds max {} for {:?}",
+                        f + 1,
+                        index,
+                        max_index,
+                        self.depth
+                    )));
+                }
+            }
+        }
+
+        // Check layers match the base frame
+        if self.layers.len() > MAX_TEXTURE_LAYERS {
+            return Err(TextureError::ValidationError(format!(
+                "too many layers: {} (max {})",
+                self.layers.len(),
+                MAX_TEXTURE_LAYERS
+            )));
+        }
+        for layer in &self.layers {
+            if layer.indices.len() != expected_pixels {
+                return Err(TextureError::ValidationError(format!(
+                    "layer '{}' size mismatch: expected {}, got {}",
+                    layer.name,
+                    expected_pixels,
+                    layer.indices.len()
+                )));
+            }
+            if let Some(&index) = layer.indices.iter().find(|&&i| i > max_index) {
+                return Err(TextureError::ValidationError(format!(
+                    "layer '{}' index {} exceeds max {} for {:?}",
+                    layer.name, index, max_index, self.depth
+                )));
+            }
+        }
+
         // Check name is reasonable
         if self.name.is_empty() {
             return Err(TextureError::ValidationError(
@@ -537,6 +700,218 @@ impl UserTexture {
         Ok(())
     }
 
+    /// Number of animation frames (1 for static textures)
+    pub fn frame_count(&self) -> usize {
+        1 + self.animation.as_ref().map_or(0, |a| a.frames.len())
+    }
+
+    /// Check if this texture has more than one frame
+    pub fn is_animated(&self) -> bool {
+        self.frame_count() > 1
+    }
+
+    /// Playback rate in frames per second (0 for static textures)
+    pub fn animation_fps(&self) -> f32 {
+        self.animation.as_ref().map_or(0.0, |a| a.fps)
+    }
+
+    /// Set the playback rate (no-op for static textures)
+    pub fn set_animation_fps(&mut self, fps: f32) {
+        if let Some(anim) = &mut self.animation {
+            anim.fps = fps.clamp(1.0, 60.0);
+        }
+    }
+
+    /// Get the palette indices for a frame (clamped to the last frame)
+    pub fn frame_indices(&self, frame: usize) -> &[u8] {
+        match &self.animation {
+            Some(anim) if frame > 0 && !anim.frames.is_empty() => {
+                let i = (frame - 1).min(anim.frames.len() - 1);
+                &anim.frames[i]
+            }
+            _ => &self.indices,
+        }
+    }
+
+    /// Which frame is shown at the given time (seconds)
+    pub fn frame_at_time(&self, time: f64) -> usize {
+        let count = self.frame_count();
+        let fps = self.animation_fps();
+        if count <= 1 || fps <= 0.0 {
+            return 0;
+        }
+        ((time * fps as f64).max(0.0) as usize) % count
+    }
+
+    /// Insert a copy of `frame` right after it, returning the new frame's index
+    ///
+    /// Returns None when the frame limit is reached.
+    pub fn duplicate_frame(&mut self, frame: usize) -> Option<usize> {
+        if self.frame_count() >= MAX_ANIMATION_FRAMES {
+            return None;
+        }
+        let frame = frame.min(self.frame_count() - 1);
+        let copy = self.frame_indices(frame).to_vec();
+        let anim = self.animation.get_or_insert_with(|| TextureAnimation {
+            frames: Vec::new(),
+            fps: DEFAULT_ANIMATION_FPS,
+        });
+        // frames[k] holds frame k+1, so the new frame (frame+1) goes at index `frame`
+        anim.frames.insert(frame, copy);
+        Some(frame + 1)
+    }
+
+    /// Remove a frame, returning the index of the frame to select afterwards
+    ///
+    /// Removing frame 0 promotes frame 1 to the base indices (flattening any
+    /// layers, which only exist for frame 0). The last
+    /// remaining frame cannot be removed. Dropping to a single frame clears
+    /// the animation entirely.
+    pub fn remove_frame(&mut self, frame: usize) -> usize {
+        let count = self.frame_count();
+        if count <= 1 || frame >= count {
+            return frame.min(count - 1);
+        }
+        if let Some(anim) = &mut self.animation {
+            if frame == 0 {
+                // The layers belonged to the old frame 0
+                self.indices = anim.frames.remove(0);
+                self.layers.clear();
+            } else {
+                anim.frames.remove(frame - 1);
+            }
+            if anim.frames.is_empty() {
+                self.animation = None;
+            }
+        }
+        frame.min(self.frame_count() - 1)
+    }
+
+    /// Swap `frame` into the base `indices` slot (and back when called again)
+    ///
+    /// Lets pixel-editing code work on any frame through `indices` without
+    /// knowing about animation. Calling twice with the same frame restores
+    /// the original layout.
+    pub fn swap_frame_to_base(&mut self, frame: usize) {
+        if frame == 0 {
+            return;
+        }
+        if let Some(anim) = &mut self.animation {
+            if let Some(other) = anim.frames.get_mut(frame - 1) {
+                std::mem::swap(&mut self.indices, other);
+            }
+        }
+    }
+
+    /// Check if the base frame is split into paint layers
+    pub fn has_layers(&self) -> bool {
+        !self.layers.is_empty()
+    }
+
+    /// Rebuild `indices` from the visible layers (topmost non-zero index wins)
+    ///
+    /// Does nothing for textures without layers.
+    pub fn composite_layers(&mut self) {
+        if !self.layers.is_empty() {
+            self.indices = composite_layer_indices(&self.layers, self.width * self.height);
+        }
+    }
+
+    /// Add an empty layer above `layer`, returning the new layer's index
+    ///
+    /// The first call splits the texture into layers: its current pixels
+    /// become the bottom layer. Returns None when the layer limit is reached.
+    pub fn add_layer(&mut self, layer: usize) -> Option<usize> {
+        if self.layers.is_empty() {
+            self.layers.push(TextureLayer {
+                name: "Layer 1".to_string(),
+                visible: true,
+                indices: self.indices.clone(),
+            });
+        }
+        if self.layers.len() >= MAX_TEXTURE_LAYERS {
+            return None;
+        }
+        let at = (layer + 1).min(self.layers.len());
+        let number = self.layers.len() + 1;
+        self.layers.insert(at, TextureLayer {
+            name: format!("Layer {}", number),
+            visible: true,
+            indices: vec![0; self.width * self.height],
+        });
+        Some(at)
+    }
+
+    /// Remove a layer, returning the index of the layer to select afterwards
+    ///
+    /// Dropping to a single layer flattens the texture back to plain
+    /// `indices`, keeping that layer's pixels even if it was hidden.
+    pub fn remove_layer(&mut self, layer: usize) -> usize {
+        if layer >= self.layers.len() {
+            return 0;
+        }
+        self.layers.remove(layer);
+        self.settle_layers();
+        layer.saturating_sub(1)
+    }
+
+    /// Swap a layer with its neighbour (`up` = toward the top), returning
+    /// the layer's new index
+    pub fn move_layer(&mut self, layer: usize, up: bool) -> usize {
+        let target = if up { layer + 1 } else { layer.wrapping_sub(1) };
+        if layer >= self.layers.len() || target >= self.layers.len() {
+            return layer;
+        }
+        self.layers.swap(layer, target);
+        self.composite_layers();
+        target
+    }
+
+    /// Paint a layer's non-zero pixels onto the layer below and remove it,
+    /// returning the merged layer's index
+    ///
+    /// Returns None for the bottom layer.
+    pub fn merge_layer_down(&mut self, layer: usize) -> Option<usize> {
+        if layer == 0 || layer >= self.layers.len() {
+            return None;
+        }
+        let upper = self.layers.remove(layer);
+        for (dst, &src) in self.layers[layer - 1].indices.iter_mut().zip(&upper.indices) {
+            if src != 0 {
+                *dst = src;
+            }
+        }
+        self.settle_layers();
+        Some(layer - 1)
+    }
+
+    /// Show or hide a layer
+    pub fn set_layer_visible(&mut self, layer: usize, visible: bool) {
+        if let Some(l) = self.layers.get_mut(layer) {
+            l.visible = visible;
+            self.composite_layers();
+        }
+    }
+
+    /// Flatten a single remaining layer into `indices`, or re-composite
+    fn settle_layers(&mut self) {
+        if self.layers.len() == 1 {
+            self.indices = self.layers.remove(0).indices;
+        } else {
+            self.composite_layers();
+        }
+    }
+
+    /// Swap a layer into the base `indices` slot (and back when called again)
+    ///
+    /// Like `swap_frame_to_base`, this lets pixel-editing code paint on a
+    /// layer through `indices`. Call `composite_layers` after swapping back.
+    pub fn swap_layer_to_base(&mut self, layer: usize) {
+        if let Some(l) = self.layers.get_mut(layer) {
+            std::mem::swap(&mut self.indices, &mut l.indices);
+        }
+    }
+
     /// Convert to RGBA bytes for display (4 bytes per pixel)
     pub fn to_rgba(&self) -> Vec<u8> {
         let mut rgba = Vec::with_capacity(self.width * self.height * 4);
@@ -564,12 +939,22 @@ impl UserTexture {
         }
 
         // Count how many pixels will lose color info (indices > 15)
-        let affected = self.indices.iter().filter(|&&i| i > 15).count();
+        let affected = self.count_high_indices();
 
-        // Remap indices: modulo 16
+        // Remap indices (all animation frames and layers too): modulo 16
         for idx in &mut self.indices {
             *idx = *idx % 16;
         }
+        if let Some(anim) = &mut self.animation {
+            for idx in anim.frames.iter_mut().flatten() {
+                *idx %= 16;
+            }
+        }
+        for layer in &mut self.layers {
+            for idx in &mut layer.indices {
+                *idx %= 16;
+            }
+        }
 
         // Truncate palette to 16 colors
         self.palette.truncate(16);
@@ -606,34 +991,48 @@ impl UserTexture {
         if self.depth == ClutDepth::Bpp4 {
             return 0;
         }
-        self.indices.iter().filter(|&&i| i > 15).count()
+        (0..self.frame_count())
+            .map(|f| self.frame_indices(f).iter().filter(|&&i| i > 15).count())
+            .sum()
     }
 
     /// Convert to rasterizer Texture for 3D rendering
     ///
     /// Uses the texture's blend_mode for pixels where the palette color has STP bit set.
     pub fn to_raster_texture(&self) -> crate::rasterizer::Texture {
+        self.to_raster_texture_frame(0)
+    }
+
+    /// Convert the frame visible at `time` (seconds) to a rasterizer Texture
+    ///
+    /// Static textures always return frame 0.
+    pub fn to_raster_texture_at(&self, time: f64) -> crate::rasterizer::Texture {
+        self.to_raster_texture_frame(self.frame_at_time(time))
+    }
+
+    /// Convert a specific animation frame to a rasterizer Texture
+    pub fn to_raster_texture_frame(&self, frame: usize) -> crate::rasterizer::Texture {
         use crate::rasterizer::{Texture as RasterTexture, Color as RasterColor};
 
         let tex_blend = self.blend_mode;
 
-        let pixels: Vec<RasterColor> = (0..self.height)
-            .flat_map(|y| {
-                (0..self.width).map(move |x| {
-                    let color = self.get_color(x, y);
-                    // Color15 index 0 with value 0x0000 is transparent
-                    if color.is_transparent() {
-                        RasterColor::with_blend(0, 0, 0, BlendMode::Erase)
+        let pixels: Vec<RasterColor> = self
+            .frame_indices(frame)
+            .iter()
+            .map(|&index| {
+                let color = self.get_palette_color(index);
+                // Color15 index 0 with value 0x0000 is transparent
+                if color.is_transparent() {
+                    RasterColor::with_blend(0, 0, 0, BlendMode::Erase)
+                } else {
+                    let [r, g, b, _] = color.to_rgba();
+                    // If palette color has STP bit set, use texture's blend mode
+                    if color.is_semi_transparent() {
+                        RasterColor::with_blend(r, g, b, tex_blend)
                     } else {
-                        let [r, g, b, _] = color.to_rgba();
-                        // If palette color has STP bit set, use texture's blend mode
-                        if color.is_semi_transparent() {
-                            RasterColor::with_blend(r, g, b, tex_blend)
-                        } else {
-                            RasterColor::new(r, g, b)
-                        }
+                        RasterColor::new(r, g, b)
                     }
-                })
+                }
             })
             .collect();
 
@@ -650,12 +1049,17 @@ impl UserTexture {
     ///
     /// Includes the texture's blend_mode for semi-transparent pixels.
     pub fn to_raster_texture_15(&self) -> crate::rasterizer::Texture15 {
+        self.to_raster_texture_15_frame(0)
+    }
+
+    /// Convert a specific animation frame to a rasterizer Texture15
+    pub fn to_raster_texture_15_frame(&self, frame: usize) -> crate::rasterizer::Texture15 {
         use crate::rasterizer::Texture15;
 
-        let pixels: Vec<Color15> = (0..self.height)
-            .flat_map(|y| {
-                (0..self.width).map(move |x| self.get_color(x, y))
-            })
+        let pixels: Vec<Color15> = self
+            .frame_indices(frame)
+            .iter()
+            .map(|&index| self.get_palette_color(index))
             .collect();
 
         Texture15 {
@@ -734,4 +1138,156 @@ mod tests {
         bad_tex.name = String::new();
         assert!(bad_tex.validate().is_err());
     }
+
+    #[test]
+    fn test_check_pixels_repairs() {
+        let mut tex = UserTexture::new("test", TextureSize::Size8x8, ClutDepth::Bpp4);
+        tex.indices[3] = 20;
+        tex.indices[9] = 16;
+        tex.indices.pop();
+        tex.palette.truncate(10);
+        let ron = ron::to_string(&tex).unwrap();
+
+        let Err(TextureError::ValidationError(message)) = UserTexture::from_ron(&ron, false) else {
+            panic!("expected a validation error");
+        };
+        assert!(message.contains("indices[3]: index 20 is past the Bpp4 palette (max 15), 1 more like it"), "{}", message);
+
+        let (fixed, issues) = UserTexture::from_ron(&ron, true).unwrap();
+        let fields: Vec<&str> = issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(fields, vec!["palette", "indices", "indices[3]"]);
+        assert_eq!(fixed.palette.len(), 16);
+        assert_eq!(fixed.indices.len(), 64);
+        assert_eq!((fixed.indices[3], fixed.indices[9]), (0, 0));
+    }
+
+    #[test]
+    fn test_animation_frames() {
+        let mut tex = UserTexture::new("water", TextureSize::Size32x32, ClutDepth::Bpp4);
+        assert_eq!(tex.frame_count(), 1);
+        assert!(!tex.is_animated());
+
+        tex.fill(3);
+        assert_eq!(tex.duplicate_frame(0), Some(1));
+        assert_eq!(tex.frame_count(), 2);
+        assert_eq!(tex.frame_indices(1)[0], 3);
+
+        // Edit frame 1 through the base slot, then swap back
+        tex.swap_frame_to_base(1);
+        tex.fill(5);
+        tex.swap_frame_to_base(1);
+        assert_eq!(tex.frame_indices(0)[0], 3);
+        assert_eq!(tex.frame_indices(1)[0], 5);
+        assert!(tex.validate().is_ok());
+
+        // Removing frame 0 promotes frame 1 and drops the animation
+        assert_eq!(tex.remove_frame(0), 0);
+        assert_eq!(tex.frame_count(), 1);
+        assert!(tex.animation.is_none());
+        assert_eq!(tex.indices[0], 5);
+    }
+
+    #[test]
+    fn test_animation_playback() {
+        let mut tex = UserTexture::new("lava", TextureSize::Size16x16, ClutDepth::Bpp4);
+        assert_eq!(tex.frame_at_time(10.0), 0);
+
+        tex.duplicate_frame(0);
+        tex.duplicate_frame(1);
+        tex.set_animation_fps(4.0);
+        assert_eq!(tex.frame_at_time(0.0), 0);
+        assert_eq!(tex.frame_at_time(0.26), 1);
+        assert_eq!(tex.frame_at_time(0.51), 2);
+        assert_eq!(tex.frame_at_time(0.76), 0);
+    }
+
+    #[test]
+    fn test_animation_roundtrip_and_validation() {
+        let mut tex = UserTexture::new("torch", TextureSize::Size16x16, ClutDepth::Bpp4);
+        tex.duplicate_frame(0);
+        let ron_string = tex.to_ron_string().unwrap();
+        let loaded: UserTexture = ron::from_str(&ron_string).unwrap();
+        assert_eq!(loaded.animation, tex.animation);
+
+        // Static textures don't serialize an animation field
+        let plain = UserTexture::new("plain", TextureSize::Size16x16, ClutDepth::Bpp4);
+        assert!(!plain.to_ron_string().unwrap().contains("animation"));
+
+        // Frames must match the base frame size
+        tex.animation.as_mut().unwrap().frames[0].pop();
+        assert!(tex.validate().is_err());
+    }
+
+    #[test]
+    fn test_layers_composite_topmost_wins() {
+        let mut tex = UserTexture::new("wall", TextureSize::Size16x16, ClutDepth::Bpp4);
+        tex.fill(2);
+        assert_eq!(tex.add_layer(0), Some(1));
+        assert_eq!(tex.layers.len(), 2);
+        assert_eq!(tex.layers[0].indices[0], 2);
+
+        // Paint on the top layer through the base slot
+        tex.swap_layer_to_base(1);
+        tex.clear();
+        tex.set_index(0, 0, 7);
+        tex.swap_layer_to_base(1);
+        tex.composite_layers();
+        assert_eq!(tex.get_index(0, 0), 7);
+        // Index 0 on the top layer lets the layer below through
+        assert_eq!(tex.get_index(1, 0), 2);
+
+        tex.set_layer_visible(1, false);
+        assert_eq!(tex.get_index(0, 0), 2);
+        tex.set_layer_visible(1, true);
+
+        // Reordering puts the solid layer on top
+        assert_eq!(tex.move_layer(1, false), 0);
+        assert_eq!(tex.get_index(0, 0), 2);
+        assert_eq!(tex.move_layer(0, false), 0);
+        assert!(tex.validate().is_ok());
+    }
+
+    #[test]
+    fn test_merge_and_remove_layers_flatten() {
+        let mut tex = UserTexture::new("crate", TextureSize::Size16x16, ClutDepth::Bpp4);
+        tex.fill(1);
+        tex.add_layer(0);
+        tex.add_layer(1);
+        assert_eq!(tex.layers.len(), 3);
+        tex.layers[2].indices[5] = 9;
+        tex.layers[1].indices[5] = 4;
+        tex.layers[1].indices[6] = 4;
+        tex.composite_layers();
+
+        assert_eq!(tex.merge_layer_down(2), Some(1));
+        assert_eq!(tex.layers[1].indices[5], 9);
+        assert_eq!(tex.layers[1].indices[6], 4);
+        assert_eq!(tex.merge_layer_down(0), None);
+
+        // Down to one layer: its pixels become the plain texture
+        let composite = tex.indices.clone();
+        assert_eq!(tex.remove_layer(0), 0);
+        assert!(!tex.has_layers());
+        assert_eq!(tex.indices[5], 9);
+        assert_ne!(tex.indices, composite);
+    }
+
+    #[test]
+    fn test_layer_limit_and_roundtrip() {
+        let mut tex = UserTexture::new("sign", TextureSize::Size8x8, ClutDepth::Bpp4);
+        let plain = tex.to_ron_string().unwrap();
+        assert!(!plain.contains("layers"));
+
+        for i in 0..MAX_TEXTURE_LAYERS - 1 {
+            assert!(tex.add_layer(i).is_some());
+        }
+        assert_eq!(tex.add_layer(0), None);
+        tex.layers[3].visible = false;
+
+        let loaded: UserTexture = ron::from_str(&tex.to_ron_string().unwrap()).unwrap();
+        assert_eq!(loaded.layers, tex.layers);
+
+        tex.layers[0].indices.pop();
+        assert!(tex.validate().is_err());
+    }
 }
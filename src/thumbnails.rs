@@ -0,0 +1,192 @@
+//! Browser thumbnails
+//!
+//! Levels and assets are only loaded when they're picked in a browser, so
+//! their thumbnails come from the browser's own preview: the first frame
+//! rendered after a preview loads is downscaled and cached, and from then on
+//! the list row shows it. Where userdata is on local disk the thumbnails are
+//! also written as PNGs under `assets/userdata/thumbnails/<kind>/`, so lists
+//! are filled in on the next launch; cloud-backed userdata keeps them for the
+//! session only (like tags).
+//!
+//! Textures don't need any: palettes draw their pixels directly.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use macroquad::prelude::*;
+use crate::rasterizer::Framebuffer;
+use crate::storage::Storage;
+use crate::tags::TagKind;
+
+/// Where thumbnails are stored
+pub const THUMBNAILS_DIR: &str = "assets/userdata/thumbnails";
+
+/// Thumbnail size in pixels (4:3, like the previews they're taken from)
+pub const THUMBNAIL_WIDTH: usize = 48;
+pub const THUMBNAIL_HEIGHT: usize = 36;
+
+thread_local! {
+    /// Loaded thumbnails; `None` remembers that there is nothing on disk
+    static CACHE: RefCell<HashMap<(TagKind, String), Option<Texture2D>>> = RefCell::new(HashMap::new());
+}
+
+/// Storage path of an item's thumbnail
+pub fn thumbnail_path(kind: TagKind, key: &str) -> String {
+    let file: String = key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{}/{}/{}.png", THUMBNAILS_DIR, kind.label(), file)
+}
+
+/// Thumbnails persist only where userdata is synchronous and writable (local disk)
+fn can_persist(storage: &Storage, path: &str) -> bool {
+    storage.can_write() && storage.is_sync(path)
+}
+
+/// Shrink a preview to thumbnail size (RGBA8): the centered 4:3 area,
+/// box-filtered
+pub fn downscale(fb: &Framebuffer) -> Vec<u8> {
+    let mut out = vec![0u8; THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT * 4];
+    if fb.width == 0 || fb.height == 0 {
+        return out;
+    }
+
+    // Crop to the thumbnail's aspect ratio
+    let (crop_w, crop_h) = if fb.width * THUMBNAIL_HEIGHT > fb.height * THUMBNAIL_WIDTH {
+        (fb.height * THUMBNAIL_WIDTH / THUMBNAIL_HEIGHT, fb.height)
+    } else {
+        (fb.width, fb.width * THUMBNAIL_HEIGHT / THUMBNAIL_WIDTH)
+    };
+    let (crop_w, crop_h) = (crop_w.max(1), crop_h.max(1));
+    let x0 = (fb.width - crop_w) / 2;
+    let y0 = (fb.height - crop_h) / 2;
+
+    for ty in 0..THUMBNAIL_HEIGHT {
+        let sy0 = y0 + ty * crop_h / THUMBNAIL_HEIGHT;
+        let sy1 = (y0 + (ty + 1) * crop_h / THUMBNAIL_HEIGHT).max(sy0 + 1);
+        for tx in 0..THUMBNAIL_WIDTH {
+            let sx0 = x0 + tx * crop_w / THUMBNAIL_WIDTH;
+            let sx1 = (x0 + (tx + 1) * crop_w / THUMBNAIL_WIDTH).max(sx0 + 1);
+            let mut sum = [0u32; 4];
+            for sy in sy0..sy1 {
+                for sx in sx0..sx1 {
+                    let i = (sy * fb.width + sx) * 4;
+                    for (c, s) in sum.iter_mut().enumerate() {
+                        *s += fb.pixels[i + c] as u32;
+                    }
+                }
+            }
+            let count = ((sy1 - sy0) * (sx1 - sx0)) as u32;
+            let o = (ty * THUMBNAIL_WIDTH + tx) * 4;
+            for (c, s) in sum.iter().enumerate() {
+                out[o + c] = (s / count) as u8;
+            }
+        }
+    }
+    out
+}
+
+fn make_texture(pixels: &[u8]) -> Texture2D {
+    let texture = Texture2D::from_rgba8(THUMBNAIL_WIDTH as u16, THUMBNAIL_HEIGHT as u16, pixels);
+    texture.set_filter(FilterMode::Nearest);
+    texture
+}
+
+/// Take an item's thumbnail from its rendered preview (replaces any old one)
+pub fn capture(storage: &Storage, kind: TagKind, key: &str, fb: &Framebuffer) {
+    let pixels = downscale(fb);
+    CACHE.with(|c| c.borrow_mut().insert((kind, key.to_string()), Some(make_texture(&pixels))));
+
+    let path = thumbnail_path(kind, key);
+    if !can_persist(storage, &path) {
+        return;
+    }
+    use image::ImageEncoder;
+    let mut bytes = Vec::new();
+    let encoded = image::codecs::png::PngEncoder::new(&mut bytes).write_image(
+        &pixels,
+        THUMBNAIL_WIDTH as u32,
+        THUMBNAIL_HEIGHT as u32,
+        image::ExtendedColorType::Rgba8,
+    );
+    let result = encoded.map_err(|e| e.to_string())
+        .and_then(|_| storage.write_sync(&path, &bytes).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        eprintln!("Failed to save thumbnail {}: {}", path, e);
+    }
+}
+
+/// An item's thumbnail, loading it from storage the first time it's asked for
+pub fn get(storage: &Storage, kind: TagKind, key: &str) -> Option<Texture2D> {
+    let cache_key = (kind, key.to_string());
+    if let Some(cached) = CACHE.with(|c| c.borrow().get(&cache_key).cloned()) {
+        return cached;
+    }
+
+    let path = thumbnail_path(kind, key);
+    let texture = if can_persist(storage, &path) && storage.exists_sync(&path).unwrap_or(false) {
+        storage.read_sync(&path)
+            .ok()
+            .and_then(|bytes| image::load_from_memory(&bytes).ok())
+            .map(|img| img.to_rgba8())
+            .filter(|img| img.width() as usize == THUMBNAIL_WIDTH && img.height() as usize == THUMBNAIL_HEIGHT)
+            .map(|img| make_texture(img.as_raw()))
+    } else {
+        None
+    };
+    CACHE.with(|c| c.borrow_mut().insert(cache_key, texture.clone()));
+    texture
+}
+
+/// Move an item's thumbnail to a new key (after a rename)
+pub fn rename(storage: &Storage, kind: TagKind, old_key: &str, new_key: &str) {
+    let texture = CACHE.with(|c| c.borrow_mut().remove(&(kind, old_key.to_string())));
+    if let Some(texture) = texture {
+        CACHE.with(|c| c.borrow_mut().insert((kind, new_key.to_string()), texture));
+    }
+
+    let (old_path, new_path) = (thumbnail_path(kind, old_key), thumbnail_path(kind, new_key));
+    if can_persist(storage, &old_path) && storage.exists_sync(&old_path).unwrap_or(false) {
+        if let Ok(bytes) = storage.read_sync(&old_path) {
+            if storage.write_sync(&new_path, &bytes).is_ok() {
+                let _ = storage.delete_sync(&old_path);
+            }
+        }
+    }
+}
+
+/// Drop an item's thumbnail (after it was deleted)
+pub fn remove(storage: &Storage, kind: TagKind, key: &str) {
+    CACHE.with(|c| c.borrow_mut().remove(&(kind, key.to_string())));
+    let path = thumbnail_path(kind, key);
+    if can_persist(storage, &path) && storage.exists_sync(&path).unwrap_or(false) {
+        let _ = storage.delete_sync(&path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downscale_crops_to_aspect_and_averages() {
+        // 2:1 preview: left and right quarters are cropped away
+        let (w, h) = (THUMBNAIL_WIDTH * 4, THUMBNAIL_HEIGHT * 2);
+        let mut fb = Framebuffer::new(w, h);
+        for y in 0..h {
+            for x in 0..w {
+                let i = (y * w + x) * 4;
+                let value = if x < w / 4 || x >= w * 3 / 4 { 255 } else if x % 2 == 0 { 100 } else { 200 };
+                fb.pixels[i..i + 4].copy_from_slice(&[value, value, value, 255]);
+            }
+        }
+        let thumb = downscale(&fb);
+        assert_eq!(thumb.len(), THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT * 4);
+        assert!(thumb.chunks(4).all(|p| p == [150, 150, 150, 255]));
+    }
+
+    #[test]
+    fn test_thumbnail_path_is_a_safe_file_name() {
+        assert_eq!(thumbnail_path(TagKind::Asset, "user:my crate"), "assets/userdata/thumbnails/asset/user_my_crate.png");
+        assert_eq!(thumbnail_path(TagKind::Level, "sample:../x"), "assets/userdata/thumbnails/level/sample____x.png");
+    }
+}
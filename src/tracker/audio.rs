@@ -5,6 +5,11 @@
 //! - WASM: Web Audio API via JavaScript FFI
 //!
 //! Features authentic PS1 SPU reverb emulation.
+//!
+//! Music plays through the main synth; SFX, ambient and dialogue notes each get
+//! their own synth, and the mix bus sums the four groups (with volumes and
+//! dialogue ducking) before reverb, SPU resampling and master volume.
+//! Rendered sound effects play as one-shot PCM voices mixed into their group.
 
 use std::sync::{Arc, Mutex};
 #[cfg(not(target_arch = "wasm32"))]
@@ -13,6 +18,9 @@ use std::path::Path;
 use std::fs::File;
 use rustysynth::{SoundFont, Synthesizer, SynthesizerSettings};
 use super::psx_reverb::{PsxReverb, ReverbType};
+use super::voices::{VoiceAllocator, VoicePriority};
+use super::mix::{GroupBuffer, MixBus, MixGroup, MixLevels};
+use super::meters::{ChannelActivity, MeterReadings, ScopeTap};
 
 /// Sample rate for audio output
 pub const SAMPLE_RATE: u32 = 44100;
@@ -378,10 +386,50 @@ fn apply_ps1_degradation(samples: &mut [f32], pitch: SpuPitch) {
     }
 }
 
+/// Most one-shot PCM sounds playing at once; starting another cuts the oldest
+pub const MAX_PCM_VOICES: usize = 8;
+
+/// Where a one-shot sample sits in the mix: level, stereo position and how
+/// much of it feeds the SFX reverb (see `AudioEngine::set_sfx_reverb`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PcmPlacement {
+    pub gain: f32,
+    /// -1.0 = hard left, 0.0 = center, 1.0 = hard right
+    pub pan: f32,
+    /// Share of the signal sent to the SFX reverb (0.0 to 1.0)
+    pub reverb_send: f32,
+}
+
+impl PcmPlacement {
+    /// Centered and dry
+    pub fn centered(gain: f32) -> Self {
+        Self { gain, pan: 0.0, reverb_send: 0.0 }
+    }
+
+    /// Left and right gains. Balance law: the near side stays at full gain
+    /// and the far side fades out, so centered sounds are unchanged.
+    pub fn channel_gains(&self) -> (f32, f32) {
+        let pan = self.pan.clamp(-1.0, 1.0);
+        (self.gain * (1.0 - pan).min(1.0), self.gain * (1.0 + pan).min(1.0))
+    }
+}
+
+/// A one-shot mono sample (a rendered SFX) playing in a mix group
+struct PcmVoice {
+    samples: Arc<[f32]>,
+    position: usize,
+    placement: PcmPlacement,
+    group: MixGroup,
+}
+
 /// Audio engine state shared between main thread and audio callback
 struct AudioState {
-    /// The synthesizer
+    /// The synthesizer (music group)
     synth: Option<Synthesizer>,
+    /// Synths for the SFX, ambient and dialogue groups (empty until a soundfont loads)
+    group_synths: Vec<Synthesizer>,
+    /// Group volumes and ducking between the synths and the output stage
+    bus: MixBus,
     /// Whether audio is playing
     playing: bool,
     /// PS1 SPU reverb processor
@@ -392,6 +440,132 @@ struct AudioState {
     resampler: SpuResampler,
     /// Master volume (0.0 to 2.0, default 1.0)
     master_volume: f32,
+    /// SPU voice allocation shared by music, ambience and SFX
+    voices: VoiceAllocator,
+    /// One-shot samples playing (these don't need a soundfont)
+    pcm_voices: Vec<PcmVoice>,
+    /// Reverb fed by the PCM voices' sends, returned into the SFX group
+    /// (the game sets it from the listener's room)
+    sfx_reverb: PsxReverb,
+    /// Level of the SFX reverb's return (faded between rooms by the game)
+    sfx_reverb_level: f32,
+    /// Reverb send accumulated while mixing the PCM voices
+    sfx_send: GroupBuffer,
+    /// Notes and volumes sent to each channel (for the tracker's VU meters)
+    channel_activity: ChannelActivity,
+    /// Final output per side (for the master meters and oscilloscope)
+    master_taps: [ScopeTap; 2],
+    /// Time spent rendering on the audio thread since `take_mix_ms` (ms)
+    mix_ms: f32,
+}
+
+impl AudioState {
+    /// Every synth, music first. Channel controllers go to all of them so a
+    /// channel sounds the same whichever group plays on it.
+    fn synths_mut(&mut self) -> impl Iterator<Item = &mut Synthesizer> {
+        self.synth.iter_mut().chain(self.group_synths.iter_mut())
+    }
+
+    /// Render synth output through the mix bus, reverb, SPU resampling and
+    /// master gain. Returns false (buffers untouched) when no soundfont is
+    /// loaded and no PCM voice is playing.
+    fn render(&mut self, left: &mut [f32], right: &mut [f32]) -> bool {
+        match self.synth.as_mut() {
+            Some(synth) => synth.render(left, right),
+            None if self.pcm_voices.is_empty() => return false,
+            None => {
+                left.fill(0.0);
+                right.fill(0.0);
+            }
+        }
+
+        // Render the other groups and sum them with the music
+        let len = left.len();
+        for (i, buffer) in self.bus.buffers.iter_mut().enumerate() {
+            buffer.prepare(len);
+            if let Some(group_synth) = self.group_synths.get_mut(i) {
+                group_synth.render(&mut buffer.left[..len], &mut buffer.right[..len]);
+            }
+        }
+        self.sfx_send.prepare(len);
+        self.mix_pcm_voices(left, right);
+        self.mix_sfx_reverb(len);
+        self.bus.mix(left, right);
+
+        // Apply PS1 reverb
+        self.reverb.process(left, right);
+
+        // Apply PS1 SPU Gaussian resampling (authentic sample rate conversion)
+        self.resampler.process(left, right);
+
+        // Apply master volume and output gain
+        let gain = self.master_volume * OUTPUT_GAIN;
+        for sample in left.iter_mut().chain(right.iter_mut()) {
+            *sample *= gain;
+        }
+        self.master_taps[0].push(left);
+        self.master_taps[1].push(right);
+        true
+    }
+
+    /// Add the playing PCM voices to their groups and drop the finished ones
+    fn mix_pcm_voices(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let len = left.len();
+        for voice in &mut self.pcm_voices {
+            let (out_left, out_right) = match MixBus::buffer_index(voice.group) {
+                Some(i) => {
+                    let buffer = &mut self.bus.buffers[i];
+                    (&mut buffer.left[..len], &mut buffer.right[..len])
+                }
+                None => (&mut *left, &mut *right),
+            };
+            let remaining = &voice.samples[voice.position.min(voice.samples.len())..];
+            let (gain_left, gain_right) = voice.placement.channel_gains();
+            for ((l, r), sample) in out_left.iter_mut().zip(out_right.iter_mut()).zip(remaining) {
+                *l += sample * gain_left;
+                *r += sample * gain_right;
+            }
+            let send = voice.placement.reverb_send;
+            if send > 0.0 && self.sfx_reverb.is_enabled() {
+                let send_left = &mut self.sfx_send.left[..len];
+                let send_right = &mut self.sfx_send.right[..len];
+                for ((l, r), sample) in send_left.iter_mut().zip(send_right.iter_mut()).zip(remaining) {
+                    *l += sample * gain_left * send;
+                    *r += sample * gain_right * send;
+                }
+            }
+            voice.position += len;
+        }
+        self.pcm_voices.retain(|voice| voice.position < voice.samples.len());
+    }
+
+    /// Run the accumulated sends through the SFX reverb and add the wet
+    /// signal to the SFX group
+    fn mix_sfx_reverb(&mut self, len: usize) {
+        if !self.sfx_reverb.is_enabled() {
+            return;
+        }
+        let send_left = &mut self.sfx_send.left[..len];
+        let send_right = &mut self.sfx_send.right[..len];
+        self.sfx_reverb.process(send_left, send_right);
+        let level = self.sfx_reverb_level;
+        if let Some(i) = MixBus::buffer_index(MixGroup::Sfx) {
+            let buffer = &mut self.bus.buffers[i];
+            for (out, wet) in buffer.left[..len].iter_mut().zip(send_left.iter()) {
+                *out += wet * level;
+            }
+            for (out, wet) in buffer.right[..len].iter_mut().zip(send_right.iter()) {
+                *out += wet * level;
+            }
+        }
+    }
+}
+
+/// Reverb for PCM voice sends: fully wet, the dry signal is mixed separately
+fn sfx_reverb() -> PsxReverb {
+    let mut reverb = PsxReverb::new(SAMPLE_RATE);
+    reverb.set_wet_level(1.0);
+    reverb
 }
 
 // =============================================================================
@@ -422,26 +596,19 @@ mod native {
             move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
                 let mut state = state.lock().unwrap();
 
-                if let Some(ref mut synth) = state.synth {
-                    let samples_needed = data.len() / 2;
-                    if left_buffer.len() < samples_needed {
-                        left_buffer.resize(samples_needed, 0.0);
-                        right_buffer.resize(samples_needed, 0.0);
-                    }
-
-                    synth.render(&mut left_buffer[..samples_needed], &mut right_buffer[..samples_needed]);
-
-                    // Apply PS1 reverb
-                    state.reverb.process(&mut left_buffer[..samples_needed], &mut right_buffer[..samples_needed]);
-
-                    // Apply PS1 SPU Gaussian resampling (authentic sample rate conversion)
-                    state.resampler.process(&mut left_buffer[..samples_needed], &mut right_buffer[..samples_needed]);
+                let samples_needed = data.len() / 2;
+                if left_buffer.len() < samples_needed {
+                    left_buffer.resize(samples_needed, 0.0);
+                    right_buffer.resize(samples_needed, 0.0);
+                }
 
-                    // Apply master volume and output gain
-                    let gain = state.master_volume * OUTPUT_GAIN;
+                let start = macroquad::miniquad::date::now();
+                let rendered = state.render(&mut left_buffer[..samples_needed], &mut right_buffer[..samples_needed]);
+                state.mix_ms += ((macroquad::miniquad::date::now() - start) * 1000.0) as f32;
+                if rendered {
                     for i in 0..samples_needed {
-                        data[i * 2] = left_buffer[i] * gain;
-                        data[i * 2 + 1] = right_buffer[i] * gain;
+                        data[i * 2] = left_buffer[i];
+                        data[i * 2 + 1] = right_buffer[i];
                     }
                 } else {
                     for sample in data.iter_mut() {
@@ -521,6 +688,8 @@ pub struct AudioEngine {
     _stream: Option<cpal::Stream>,
     /// Loaded soundfont info
     soundfont_name: Option<String>,
+    /// Parsed soundfont (kept so offline engines can build their own synth)
+    soundfont: Option<Arc<SoundFont>>,
     /// Audio render buffers (WASM only - we render on demand)
     #[cfg(target_arch = "wasm32")]
     left_buffer: Vec<f32>,
@@ -536,11 +705,21 @@ impl AudioEngine {
     pub fn new() -> Self {
         let state = Arc::new(Mutex::new(AudioState {
             synth: None,
+            group_synths: Vec::new(),
+            bus: MixBus::new(SAMPLE_RATE),
             playing: false,
             reverb: PsxReverb::new(SAMPLE_RATE),
             output_sample_rate: OutputSampleRate::default(),
             resampler: SpuResampler::new(),
             master_volume: 1.0,
+            voices: VoiceAllocator::new(),
+            pcm_voices: Vec::new(),
+            sfx_reverb: sfx_reverb(),
+            sfx_reverb_level: 1.0,
+            sfx_send: GroupBuffer::default(),
+            channel_activity: ChannelActivity::default(),
+            master_taps: Default::default(),
+            mix_ms: 0.0,
         }));
 
         #[cfg(not(target_arch = "wasm32"))]
@@ -550,6 +729,7 @@ impl AudioEngine {
                 state,
                 _stream: stream,
                 soundfont_name: None,
+                soundfont: None,
             }
         }
 
@@ -559,6 +739,7 @@ impl AudioEngine {
             Self {
                 state,
                 soundfont_name: None,
+                soundfont: None,
                 left_buffer: vec![0.0; 2048],
                 right_buffer: vec![0.0; 2048],
                 sample_accumulator: 0.0,
@@ -588,6 +769,16 @@ impl AudioEngine {
         self.state.lock().unwrap().reverb.wet_level()
     }
 
+    /// Set the reverb that PCM voices send to (the listener's room in game)
+    pub fn set_sfx_reverb(&self, reverb_type: ReverbType) {
+        self.state.lock().unwrap().sfx_reverb.set_preset(reverb_type);
+    }
+
+    /// Set how loud the SFX reverb returns (0.0 = dry, 1.0 = full)
+    pub fn set_sfx_reverb_level(&self, level: f32) {
+        self.state.lock().unwrap().sfx_reverb_level = level.clamp(0.0, 1.0);
+    }
+
     /// Clear reverb buffers (call when stopping playback)
     pub fn clear_reverb(&self) {
         let mut state = self.state.lock().unwrap();
@@ -617,6 +808,23 @@ impl AudioEngine {
         self.state.lock().unwrap().master_volume
     }
 
+    /// Set the group volumes and dialogue ducking
+    pub fn set_mix_levels(&self, levels: MixLevels) {
+        let mut state = self.state.lock().unwrap();
+        state.bus.levels = levels;
+        state.bus.levels.sanitize();
+    }
+
+    /// Get the group volumes and dialogue ducking
+    pub fn mix_levels(&self) -> MixLevels {
+        self.state.lock().unwrap().bus.levels
+    }
+
+    /// Music group gain after ducking (for meters)
+    pub fn music_duck_gain(&self) -> f32 {
+        self.state.lock().unwrap().bus.music_gain()
+    }
+
     /// Enable or disable SPU resampling emulation
     pub fn set_spu_resampling_enabled(&self, enabled: bool) {
         let mut state = self.state.lock().unwrap();
@@ -655,16 +863,74 @@ impl AudioEngine {
         let settings = SynthesizerSettings::new(SAMPLE_RATE as i32);
         let synth = Synthesizer::new(&soundfont, &settings)
             .map_err(|e| format!("Failed to create synthesizer: {:?}", e))?;
+        let group_synths = group_synths(&soundfont, &settings);
 
         self.soundfont_name = name;
+        self.soundfont = Some(soundfont);
 
         let mut state = self.state.lock().unwrap();
         state.synth = Some(synth);
+        state.group_synths = group_synths;
         state.playing = true;
 
         Ok(())
     }
 
+    /// Create an engine with no audio device for offline rendering (song
+    /// export). It gets its own synth from the loaded soundfont and copies the
+    /// current reverb, SPU rate, volume and mix settings.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new_offline(&self) -> Option<AudioEngine> {
+        let soundfont = self.soundfont.as_ref()?;
+        let settings = SynthesizerSettings::new(SAMPLE_RATE as i32);
+        let synth = Synthesizer::new(soundfont, &settings).ok()?;
+
+        let live = self.state.lock().unwrap();
+        let mut reverb = PsxReverb::new(SAMPLE_RATE);
+        reverb.set_preset(live.reverb.reverb_type());
+        reverb.set_wet_level(live.reverb.wet_level());
+        let mut resampler = SpuResampler::new();
+        resampler.set_pitch(live.output_sample_rate);
+        resampler.set_enabled(live.resampler.is_enabled());
+
+        let mut bus = MixBus::new(SAMPLE_RATE);
+        bus.levels = live.bus.levels;
+
+        let state = AudioState {
+            synth: Some(synth),
+            group_synths: group_synths(soundfont, &settings),
+            bus,
+            playing: true,
+            reverb,
+            output_sample_rate: live.output_sample_rate,
+            resampler,
+            master_volume: live.master_volume,
+            voices: VoiceAllocator::new(),
+            pcm_voices: Vec::new(),
+            sfx_reverb: sfx_reverb(),
+            sfx_reverb_level: 1.0,
+            sfx_send: GroupBuffer::default(),
+            channel_activity: ChannelActivity::default(),
+            master_taps: Default::default(),
+            mix_ms: 0.0,
+        };
+        Some(AudioEngine {
+            state: Arc::new(Mutex::new(state)),
+            _stream: None,
+            soundfont_name: self.soundfont_name.clone(),
+            soundfont: Some(Arc::clone(soundfont)),
+        })
+    }
+
+    /// Render the next block of output into `left`/`right` (offline engines;
+    /// a live engine's device callback pulls from the same synth)
+    pub fn render_offline(&self, left: &mut [f32], right: &mut [f32]) {
+        if !self.state.lock().unwrap().render(left, right) {
+            left.fill(0.0);
+            right.fill(0.0);
+        }
+    }
+
     /// Check if a soundfont is loaded
     pub fn is_loaded(&self) -> bool {
         self.state.lock().unwrap().synth.is_some()
@@ -678,8 +944,9 @@ impl AudioEngine {
     /// Render and output audio (WASM only - must be called each frame with delta time)
     #[cfg(target_arch = "wasm32")]
     pub fn render_audio(&mut self, delta: f64) {
+        let _timer = crate::profiler::scope(crate::profiler::Subsystem::Audio, "mix");
         let mut state = self.state.lock().unwrap();
-        if let Some(ref mut synth) = state.synth {
+        if state.synth.is_some() || !state.pcm_voices.is_empty() {
             // Calculate exact samples needed based on actual elapsed time
             // delta is in seconds, sample_rate is 44100 samples/sec
             self.sample_accumulator += delta * SAMPLE_RATE as f64;
@@ -700,45 +967,135 @@ impl AudioEngine {
                 self.left_buffer.resize(samples, 0.0);
                 self.right_buffer.resize(samples, 0.0);
             }
-            synth.render(&mut self.left_buffer[..samples], &mut self.right_buffer[..samples]);
+            state.render(&mut self.left_buffer[..samples], &mut self.right_buffer[..samples]);
 
-            // Apply PS1 reverb
-            state.reverb.process(&mut self.left_buffer[..samples], &mut self.right_buffer[..samples]);
+            wasm::write_audio(&self.left_buffer[..samples], &self.right_buffer[..samples]);
+        }
+    }
 
-            // Apply PS1 SPU Gaussian resampling (authentic sample rate conversion)
-            state.resampler.process(&mut self.left_buffer[..samples], &mut self.right_buffer[..samples]);
+    /// Play a music note (note on)
+    pub fn note_on(&self, channel: i32, key: i32, velocity: i32) {
+        self.note_on_with_priority(channel, key, velocity, VoicePriority::Music);
+    }
 
-            // Apply master volume and output gain
-            let gain = state.master_volume * OUTPUT_GAIN;
-            for i in 0..samples {
-                self.left_buffer[i] *= gain;
-                self.right_buffer[i] *= gain;
-            }
+    /// Play a note on one of the SPU's voices. If all voices are busy the
+    /// least important one is stolen; returns false when every voice holds a
+    /// higher priority note and this one is dropped.
+    pub fn note_on_with_priority(&self, channel: i32, key: i32, velocity: i32, priority: VoicePriority) -> bool {
+        self.note_on_in_group(channel, key, velocity, MixGroup::from_priority(priority))
+    }
 
-            wasm::write_audio(&self.left_buffer[..samples], &self.right_buffer[..samples]);
+    /// Play a note in a mix group (its synth, volume and voice priority)
+    pub fn note_on_in_group(&self, channel: i32, key: i32, velocity: i32, group: MixGroup) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        if state.synth.is_none() {
+            return false;
+        }
+        let Some(grant) = state.voices.allocate(channel, key, group.voice_priority()) else {
+            return false;
+        };
+        if let Some((stolen_channel, stolen_key)) = grant.stolen {
+            for synth in state.synths_mut() {
+                synth.note_off(stolen_channel, stolen_key);
+            }
         }
+        let synth = match MixBus::buffer_index(group) {
+            Some(i) if i < state.group_synths.len() => &mut state.group_synths[i],
+            _ => state.synth.as_mut().unwrap(),
+        };
+        synth.note_on(channel, key, velocity);
+        state.channel_activity.note_on(channel, velocity);
+        true
     }
 
-    /// Play a note (note on)
-    pub fn note_on(&self, channel: i32, key: i32, velocity: i32) {
+    /// Play a one-shot mono sample at `SAMPLE_RATE` (a rendered SFX) in a mix
+    /// group. Works without a soundfont; false if the sample is empty.
+    pub fn play_pcm(&self, samples: Arc<[f32]>, gain: f32, group: MixGroup) -> bool {
+        self.play_pcm_placed(samples, PcmPlacement::centered(gain), group)
+    }
+
+    /// Play a one-shot mono sample panned and with a reverb send (positional
+    /// game sounds, see `game::spatial`)
+    pub fn play_pcm_placed(&self, samples: Arc<[f32]>, placement: PcmPlacement, group: MixGroup) -> bool {
+        if samples.is_empty() {
+            return false;
+        }
         let mut state = self.state.lock().unwrap();
-        if let Some(ref mut synth) = state.synth {
-            synth.note_on(channel, key, velocity);
+        if state.pcm_voices.len() >= MAX_PCM_VOICES {
+            let oldest = state.pcm_voices.iter()
+                .enumerate()
+                .max_by_key(|(_, voice)| voice.position)
+                .map(|(i, _)| i);
+            if let Some(oldest) = oldest {
+                state.pcm_voices.remove(oldest);
+            }
         }
+        state.pcm_voices.push(PcmVoice { samples, position: 0, placement, group });
+        true
+    }
+
+    /// Cut every playing PCM sample
+    pub fn stop_pcm(&self) {
+        self.state.lock().unwrap().pcm_voices.clear();
+    }
+
+    /// Render one note of a GM program to mono PCM at `SAMPLE_RATE` on a
+    /// private synth: held for `hold` seconds, then released for `tail` more
+    /// (SFX built from soundfont samples). None without a soundfont.
+    pub fn render_instrument(&self, program: u8, key: u8, hold: f32, tail: f32) -> Option<Vec<f32>> {
+        let soundfont = self.soundfont.as_ref()?;
+        let settings = SynthesizerSettings::new(SAMPLE_RATE as i32);
+        let mut synth = Synthesizer::new(soundfont, &settings).ok()?;
+        let held = (hold.max(0.0) * SAMPLE_RATE as f32) as usize;
+        let len = held + (tail.max(0.0) * SAMPLE_RATE as f32) as usize;
+        let mut left = vec![0.0; len];
+        let mut right = vec![0.0; len];
+
+        synth.process_midi_message(0, 0xC0, program as i32, 0);
+        synth.note_on(0, key as i32, 127);
+        synth.render(&mut left[..held], &mut right[..held]);
+        synth.note_off(0, key as i32);
+        synth.render(&mut left[held..], &mut right[held..]);
+        Some(left.iter().zip(&right).map(|(l, r)| (l + r) * 0.5 * OUTPUT_GAIN).collect())
     }
 
     /// Stop a note (note off)
     pub fn note_off(&self, channel: i32, key: i32) {
         let mut state = self.state.lock().unwrap();
-        if let Some(ref mut synth) = state.synth {
+        state.voices.release(channel, key);
+        state.channel_activity.note_off(channel);
+        for synth in state.synths_mut() {
             synth.note_off(channel, key);
         }
     }
 
+    /// SPU voices in use: (total, per priority)
+    pub fn voice_usage(&self) -> (usize, [usize; 3]) {
+        let state = self.state.lock().unwrap();
+        let per = VoicePriority::ALL.map(|p| state.voices.active_with(p));
+        (state.voices.active(), per)
+    }
+
+    /// Channel activity and master output since the last call (for meters)
+    pub fn take_meter_readings(&self) -> MeterReadings {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        MeterReadings::take(&mut state.channel_activity, &mut state.master_taps)
+    }
+
+    /// Audio thread rendering time since the last call (ms, for the profiler;
+    /// always 0 on WASM, where audio renders on the main thread)
+    pub fn take_mix_ms(&self) -> f32 {
+        std::mem::take(&mut self.state.lock().unwrap().mix_ms)
+    }
+
     /// Stop all notes
     pub fn all_notes_off(&self) {
         let mut state = self.state.lock().unwrap();
-        if let Some(ref mut synth) = state.synth {
+        state.voices.clear();
+        state.channel_activity.all_notes_off();
+        for synth in state.synths_mut() {
             for channel in 0..16 {
                 for key in 0..128 {
                     synth.note_off(channel, key);
@@ -750,7 +1107,7 @@ impl AudioEngine {
     /// Set the instrument (program) for a channel
     pub fn set_program(&self, channel: i32, program: i32) {
         let mut state = self.state.lock().unwrap();
-        if let Some(ref mut synth) = state.synth {
+        for synth in state.synths_mut() {
             synth.process_midi_message(channel, 0xC0, program, 0);
         }
     }
@@ -758,7 +1115,8 @@ impl AudioEngine {
     /// Set channel volume (CC 7)
     pub fn set_volume(&self, channel: i32, volume: i32) {
         let mut state = self.state.lock().unwrap();
-        if let Some(ref mut synth) = state.synth {
+        state.channel_activity.set_volume(channel, volume);
+        for synth in state.synths_mut() {
             synth.process_midi_message(channel, 0xB0, 7, volume);
         }
     }
@@ -766,7 +1124,7 @@ impl AudioEngine {
     /// Set channel pan (CC 10)
     pub fn set_pan(&self, channel: i32, pan: i32) {
         let mut state = self.state.lock().unwrap();
-        if let Some(ref mut synth) = state.synth {
+        for synth in state.synths_mut() {
             synth.process_midi_message(channel, 0xB0, 10, pan);
         }
     }
@@ -774,7 +1132,7 @@ impl AudioEngine {
     /// Set pitch bend (0-16383, center = 8192)
     pub fn set_pitch_bend(&self, channel: i32, value: i32) {
         let mut state = self.state.lock().unwrap();
-        if let Some(ref mut synth) = state.synth {
+        for synth in state.synths_mut() {
             // Pitch bend is 0xE0, with LSB and MSB as the two data bytes
             let lsb = value & 0x7F;
             let msb = (value >> 7) & 0x7F;
@@ -782,10 +1140,21 @@ impl AudioEngine {
         }
     }
 
+    /// Set pitch bend sensitivity in semitones (RPN 0)
+    pub fn set_pitch_bend_range(&self, channel: i32, semitones: i32) {
+        let mut state = self.state.lock().unwrap();
+        for synth in state.synths_mut() {
+            synth.process_midi_message(channel, 0xB0, 101, 0);
+            synth.process_midi_message(channel, 0xB0, 100, 0);
+            synth.process_midi_message(channel, 0xB0, 6, semitones.clamp(0, 127));
+            synth.process_midi_message(channel, 0xB0, 38, 0);
+        }
+    }
+
     /// Set modulation wheel (CC 1)
     pub fn set_modulation(&self, channel: i32, value: i32) {
         let mut state = self.state.lock().unwrap();
-        if let Some(ref mut synth) = state.synth {
+        for synth in state.synths_mut() {
             synth.process_midi_message(channel, 0xB0, 1, value.clamp(0, 127));
         }
     }
@@ -793,7 +1162,8 @@ impl AudioEngine {
     /// Set expression (CC 11)
     pub fn set_expression(&self, channel: i32, value: i32) {
         let mut state = self.state.lock().unwrap();
-        if let Some(ref mut synth) = state.synth {
+        state.channel_activity.set_expression(channel, value);
+        for synth in state.synths_mut() {
             synth.process_midi_message(channel, 0xB0, 11, value.clamp(0, 127));
         }
     }
@@ -802,7 +1172,8 @@ impl AudioEngine {
     /// Reset all controllers on a channel
     pub fn reset_controllers(&self, channel: i32) {
         let mut state = self.state.lock().unwrap();
-        if let Some(ref mut synth) = state.synth {
+        state.channel_activity.reset_controllers(channel);
+        for synth in state.synths_mut() {
             synth.reset_all_controllers_channel(channel);
         }
     }
@@ -850,6 +1221,11 @@ impl AudioEngine {
     }
 }
 
+/// Synths for the non-music groups, in `MixBus::buffers` order
+fn group_synths(soundfont: &Arc<SoundFont>, settings: &SynthesizerSettings) -> Vec<Synthesizer> {
+    (0..3).filter_map(|_| Synthesizer::new(soundfont, settings).ok()).collect()
+}
+
 impl Default for AudioEngine {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,113 @@
+//! Music cues
+//!
+//! Changes to the playing song that wait for the row clock, so they land
+//! musically: switch song, jump to another arrangement entry (e.g. the combat
+//! section), stop, or mute/unmute a channel (songs written in stems keep each
+//! layer on its own channel). The game's music director (`game::music`)
+//! queues them with `TrackerState::queue_cue`; each one applies at the start
+//! of the first row that meets its sync.
+
+use super::pattern::Song;
+
+/// Beats in a bar (songs only store rows per beat)
+pub const BEATS_PER_BAR: usize = 4;
+
+/// Where a cue is allowed to land
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CueSync {
+    /// The next row
+    Now,
+    /// The next beat
+    #[default]
+    Beat,
+    /// The next bar
+    Bar,
+    /// The start of the next arrangement entry
+    Pattern,
+}
+
+impl CueSync {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "now" => Some(CueSync::Now),
+            "beat" => Some(CueSync::Beat),
+            "bar" => Some(CueSync::Bar),
+            "pattern" => Some(CueSync::Pattern),
+            _ => None,
+        }
+    }
+
+    /// Can a cue land on `row` (of its arrangement entry)?
+    pub fn lands_on(&self, row: usize, rows_per_beat: usize) -> bool {
+        let rows_per_beat = rows_per_beat.max(1);
+        match self {
+            CueSync::Now => true,
+            CueSync::Beat => row % rows_per_beat == 0,
+            CueSync::Bar => row % (rows_per_beat * BEATS_PER_BAR) == 0,
+            CueSync::Pattern => row == 0,
+        }
+    }
+}
+
+/// What a cue does to playback
+#[derive(Debug, Clone)]
+pub enum CueChange {
+    /// Play another song from an arrangement position (stems unmute)
+    Song(Box<Song>, usize),
+    /// Continue from the start of an arrangement position
+    Jump(usize),
+    /// Mute (true) or unmute a channel
+    Mute(usize, bool),
+    Stop,
+}
+
+/// A change waiting for its sync point
+#[derive(Debug, Clone)]
+pub struct MusicCue {
+    pub change: CueChange,
+    pub sync: CueSync,
+}
+
+/// Take the cues due on `row` out of `pending`, in the order they were
+/// queued. A cue waits behind an earlier one that isn't due yet, so a song
+/// change and the stem mutes queued after it land together.
+pub fn due_cues(pending: &mut Vec<MusicCue>, row: usize, rows_per_beat: usize) -> Vec<MusicCue> {
+    let due = pending.iter().take_while(|c| c.sync.lands_on(row, rows_per_beat)).count();
+    pending.drain(..due).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(change: CueChange, sync: CueSync) -> MusicCue {
+        MusicCue { change, sync }
+    }
+
+    #[test]
+    fn test_sync_points() {
+        assert!(CueSync::Now.lands_on(3, 4));
+        assert!(!CueSync::Beat.lands_on(3, 4));
+        assert!(CueSync::Beat.lands_on(8, 4));
+        assert!(!CueSync::Bar.lands_on(8, 4));
+        assert!(CueSync::Bar.lands_on(32, 4));
+        assert!(!CueSync::Pattern.lands_on(32, 4));
+        assert!(CueSync::Pattern.lands_on(0, 4));
+        assert_eq!(CueSync::from_name("bar"), Some(CueSync::Bar));
+        assert_eq!(CueSync::from_name("soon"), None);
+    }
+
+    #[test]
+    fn test_due_cues_keep_their_order() {
+        let mut pending = vec![
+            cue(CueChange::Jump(2), CueSync::Bar),
+            cue(CueChange::Mute(3, true), CueSync::Now),
+        ];
+        // The mute waits for the jump queued before it
+        assert!(due_cues(&mut pending, 5, 4).is_empty());
+        assert_eq!(pending.len(), 2);
+        let due = due_cues(&mut pending, 16, 4);
+        assert!(matches!(due[..], [MusicCue { change: CueChange::Jump(2), .. }, MusicCue { change: CueChange::Mute(3, true), .. }]));
+        assert!(pending.is_empty());
+    }
+}
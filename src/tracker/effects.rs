@@ -0,0 +1,258 @@
+//! Per-tick effect processing
+//!
+//! Each pattern row is split into `TICKS_PER_ROW` ticks, like MOD/XM trackers.
+//! Row-level effects (volume, pan, speed...) are applied once on tick 0, while
+//! slides, vibrato, arpeggio, volume slides and retriggers run on every tick.
+//! `ChannelFx` holds the per-channel state and turns each tick into pitch bend,
+//! channel volume and retrigger requests; `TrackerState` sends those to the synth.
+
+use super::pattern::Effect;
+
+/// Sub-row ticks per pattern row
+pub const TICKS_PER_ROW: u32 = 6;
+
+/// Pitch bend range (semitones) set on every channel during playback, so
+/// arpeggios and slides of up to two octaves fit in the bend wheel
+pub const PITCH_BEND_RANGE: i32 = 24;
+
+/// Center (no bend) pitch wheel value
+pub const PITCH_BEND_CENTER: i32 = 8192;
+
+/// Default channel volume (CC 7) before any Cxx/Axy
+const DEFAULT_CHANNEL_VOLUME: i32 = 100;
+
+/// What a tick asks the synth to do on one channel
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FxOutput {
+    /// New pitch wheel value (only when it changed)
+    pub pitch_bend: Option<i32>,
+    /// New channel volume (only when it changed)
+    pub volume: Option<i32>,
+    /// Re-strike the held note
+    pub retrigger: bool,
+}
+
+/// Effect state for one channel during playback
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelFx {
+    /// Effect on the current row
+    effect: Effect,
+    /// Key held on the synth (what pitch bend is relative to)
+    pub note: Option<u8>,
+    /// Velocity the held note was struck with (for retriggers)
+    pub velocity: u8,
+    /// Persistent pitch offset from slides and portamento, in semitones
+    slide: f32,
+    /// Portamento target offset (semitones from the held key)
+    porta_target: Option<f32>,
+    /// Vibrato phase in radians
+    vibrato_phase: f32,
+    /// Channel volume (CC 7)
+    volume: i32,
+    /// Last pitch wheel value sent
+    bend: i32,
+}
+
+impl Default for ChannelFx {
+    fn default() -> Self {
+        Self {
+            effect: Effect::None,
+            note: None,
+            velocity: 100,
+            slide: 0.0,
+            porta_target: None,
+            vibrato_phase: 0.0,
+            volume: DEFAULT_CHANNEL_VOLUME,
+            bend: PITCH_BEND_CENTER,
+        }
+    }
+}
+
+/// Pitch wheel value for an offset in semitones
+pub fn pitch_bend_for(semitones: f32) -> i32 {
+    let bend = PITCH_BEND_CENTER as f32 + semitones / PITCH_BEND_RANGE as f32 * PITCH_BEND_CENTER as f32;
+    (bend.round() as i32).clamp(0, 16383)
+}
+
+impl ChannelFx {
+    /// A note was struck: slides and vibrato restart from the new pitch
+    pub fn note_on(&mut self, key: u8, velocity: u8) {
+        self.note = Some(key);
+        self.velocity = velocity;
+        self.slide = 0.0;
+        self.porta_target = None;
+        self.vibrato_phase = 0.0;
+    }
+
+    /// The held note was released
+    pub fn note_off(&mut self) {
+        self.note = None;
+        self.porta_target = None;
+    }
+
+    /// Portamento (3xx) toward `key` instead of striking it.
+    /// Returns false if nothing is held, in which case the note should be struck.
+    pub fn portamento_to(&mut self, key: u8) -> bool {
+        match self.note {
+            Some(held) => {
+                self.porta_target = Some(key as f32 - held as f32);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Start a new row with `effect` (Effect::None clears the previous row's
+    /// effect). Runs tick 0: only immediate effects apply, slides wait for tick 1.
+    pub fn start_row(&mut self, effect: Effect) -> FxOutput {
+        self.effect = effect;
+        let mut out = FxOutput::default();
+        if let Effect::SetVolume(v) = effect {
+            self.volume = (v as i32).min(127);
+        }
+        // Arpeggio and vibrato are row-local: fall back to the slide pitch
+        out.pitch_bend = self.emit_bend(self.slide);
+        out
+    }
+
+    /// Run one of ticks 1..TICKS_PER_ROW of the current row
+    pub fn tick(&mut self, tick: u32) -> FxOutput {
+        let mut out = FxOutput::default();
+        let range = PITCH_BEND_RANGE as f32;
+        let mut pitch = self.slide;
+
+        match self.effect {
+            Effect::SlideUp(rate) => {
+                self.slide = (self.slide + rate as f32 / 16.0).min(range);
+                pitch = self.slide;
+            }
+            Effect::SlideDown(rate) => {
+                self.slide = (self.slide - rate as f32 / 16.0).max(-range);
+                pitch = self.slide;
+            }
+            Effect::Portamento(rate) => {
+                if let Some(target) = self.porta_target {
+                    let step = rate as f32 / 16.0;
+                    self.slide = if self.slide < target {
+                        (self.slide + step).min(target)
+                    } else {
+                        (self.slide - step).max(target)
+                    };
+                    pitch = self.slide;
+                }
+            }
+            Effect::Vibrato(speed, depth) => {
+                self.vibrato_phase += speed as f32 * std::f32::consts::TAU / 64.0;
+                pitch = self.slide + self.vibrato_phase.sin() * depth as f32 / 8.0;
+            }
+            Effect::Arpeggio(x, y) => {
+                pitch = self.slide + match tick % 3 {
+                    0 => 0.0,
+                    1 => x as f32,
+                    _ => y as f32,
+                };
+            }
+            Effect::VolumeSlide(up, down) => {
+                let volume = (self.volume + up as i32 - down as i32).clamp(0, 127);
+                if volume != self.volume {
+                    self.volume = volume;
+                    out.volume = Some(volume);
+                }
+            }
+            Effect::Retrigger(interval) => {
+                out.retrigger = interval > 0 && self.note.is_some() && tick % interval as u32 == 0;
+            }
+            _ => {}
+        }
+
+        out.pitch_bend = self.emit_bend(pitch);
+        out
+    }
+
+    /// Pitch wheel value to send for `semitones`, or None if unchanged
+    fn emit_bend(&mut self, semitones: f32) -> Option<i32> {
+        let bend = pitch_bend_for(semitones);
+        if bend == self.bend {
+            return None;
+        }
+        self.bend = bend;
+        Some(bend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_row(fx: &mut ChannelFx, effect: Effect) -> Vec<FxOutput> {
+        let mut out = vec![fx.start_row(effect)];
+        for tick in 1..TICKS_PER_ROW {
+            out.push(fx.tick(tick));
+        }
+        out
+    }
+
+    #[test]
+    fn slide_up_accumulates_across_rows() {
+        let mut fx = ChannelFx::default();
+        fx.note_on(60, 100);
+        run_row(&mut fx, Effect::SlideUp(16));
+        // One semitone per tick, five ticks per row after tick 0
+        assert_eq!(fx.bend, pitch_bend_for(5.0));
+        run_row(&mut fx, Effect::None);
+        assert_eq!(fx.bend, pitch_bend_for(5.0));
+        fx.note_on(62, 100);
+        assert_eq!(fx.start_row(Effect::None).pitch_bend, Some(PITCH_BEND_CENTER));
+    }
+
+    #[test]
+    fn portamento_stops_at_target() {
+        let mut fx = ChannelFx::default();
+        fx.note_on(60, 100);
+        assert!(fx.portamento_to(63));
+        run_row(&mut fx, Effect::Portamento(32));
+        run_row(&mut fx, Effect::Portamento(32));
+        assert_eq!(fx.bend, pitch_bend_for(3.0));
+
+        fx.note_off();
+        assert!(!fx.portamento_to(65));
+    }
+
+    #[test]
+    fn arpeggio_cycles_and_resets() {
+        let mut fx = ChannelFx::default();
+        fx.note_on(60, 100);
+        let out = run_row(&mut fx, Effect::Arpeggio(4, 7));
+        assert_eq!(out[1].pitch_bend, Some(pitch_bend_for(4.0)));
+        assert_eq!(out[2].pitch_bend, Some(pitch_bend_for(7.0)));
+        assert_eq!(out[3].pitch_bend, Some(PITCH_BEND_CENTER));
+        // The next row without arpeggio drops back to the base pitch
+        fx.tick(1);
+        assert_eq!(fx.start_row(Effect::None).pitch_bend, Some(PITCH_BEND_CENTER));
+    }
+
+    #[test]
+    fn volume_slide_clamps() {
+        let mut fx = ChannelFx::default();
+        fx.start_row(Effect::SetVolume(120));
+        let out = run_row(&mut fx, Effect::VolumeSlide(4, 0));
+        assert_eq!(out[1].volume, Some(124));
+        assert_eq!(out[2].volume, Some(127));
+        assert_eq!(out[3].volume, None);
+
+        run_row(&mut fx, Effect::VolumeSlide(0, 127));
+        assert_eq!(fx.volume, 0);
+    }
+
+    #[test]
+    fn retrigger_needs_held_note() {
+        let mut fx = ChannelFx::default();
+        let out = run_row(&mut fx, Effect::Retrigger(2));
+        assert!(out.iter().all(|o| !o.retrigger));
+
+        fx.note_on(60, 100);
+        let out = run_row(&mut fx, Effect::Retrigger(2));
+        let ticks: Vec<usize> = (0..out.len()).filter(|&t| out[t].retrigger).collect();
+        assert_eq!(ticks, vec![2, 4]);
+    }
+}
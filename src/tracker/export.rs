@@ -0,0 +1,180 @@
+//! Offline song export to WAV
+//!
+//! The song is rendered through an offline copy of the audio engine (same
+//! soundfont, reverb and SPU settings, no audio device) while the tracker's
+//! normal playback code drives it one tick at a time, so effects and reverb
+//! changes sound exactly like live playback. Rendering is spread over frames
+//! so the UI can show a progress bar.
+
+use std::path::PathBuf;
+use super::audio::{AudioEngine, SAMPLE_RATE};
+
+/// Where exported songs are written
+pub const MUSIC_EXPORTS_DIR: &str = "assets/userdata/music-exports";
+
+/// Silence rendered after the last row so note releases and reverb ring out
+const TAIL_SECONDS: f64 = 2.0;
+
+/// A song export in progress
+pub struct SongExport {
+    /// Destination WAV file
+    pub path: PathBuf,
+    /// The live engine, swapped out while the offline one renders
+    pub(super) live_audio: AudioEngine,
+    /// Song tempo before export (SetSpeed effects may change it)
+    pub(super) saved_bpm: u16,
+    pub(super) rows_done: usize,
+    pub(super) rows_total: usize,
+    tail_remaining: usize,
+    left: Vec<f32>,
+    right: Vec<f32>,
+    /// Fractional samples carried between ticks
+    sample_accum: f64,
+}
+
+impl SongExport {
+    pub(super) fn new(path: PathBuf, live_audio: AudioEngine, rows_total: usize, saved_bpm: u16) -> Self {
+        Self {
+            path,
+            live_audio,
+            saved_bpm,
+            rows_done: 0,
+            rows_total,
+            tail_remaining: (TAIL_SECONDS * SAMPLE_RATE as f64) as usize,
+            left: Vec::new(),
+            right: Vec::new(),
+            sample_accum: 0.0,
+        }
+    }
+
+    /// All rows have been played; only the tail is left
+    pub fn song_done(&self) -> bool {
+        self.rows_done >= self.rows_total
+    }
+
+    /// Song and tail fully rendered
+    pub fn is_done(&self) -> bool {
+        self.song_done() && self.tail_remaining == 0
+    }
+
+    /// 0.0..=1.0, by rows played
+    pub fn progress(&self) -> f32 {
+        if self.rows_total == 0 {
+            return 1.0;
+        }
+        (self.rows_done as f32 / self.rows_total as f32).min(1.0)
+    }
+
+    /// Rendered length in seconds
+    pub fn duration(&self) -> f64 {
+        self.left.len() as f64 / SAMPLE_RATE as f64
+    }
+
+    /// Render `seconds` of audio from `audio`
+    pub(super) fn render_seconds(&mut self, audio: &AudioEngine, seconds: f64) {
+        self.sample_accum += seconds * SAMPLE_RATE as f64;
+        let count = self.sample_accum as usize;
+        self.sample_accum -= count as f64;
+        self.render_samples(audio, count);
+    }
+
+    /// Render up to one tick's worth of the tail
+    pub(super) fn render_tail(&mut self, audio: &AudioEngine, max: usize) {
+        let count = self.tail_remaining.min(max);
+        self.tail_remaining -= count;
+        self.render_samples(audio, count);
+    }
+
+    fn render_samples(&mut self, audio: &AudioEngine, count: usize) {
+        let start = self.left.len();
+        self.left.resize(start + count, 0.0);
+        self.right.resize(start + count, 0.0);
+        audio.render_offline(&mut self.left[start..], &mut self.right[start..]);
+    }
+
+    /// Write the rendered audio to `path`
+    pub(super) fn write(&self) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        std::fs::write(&self.path, encode_wav(&self.left, &self.right, SAMPLE_RATE))
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+}
+
+/// Export file path for a song name (unsafe filename characters replaced)
+pub fn export_path(song_name: &str) -> PathBuf {
+    let stem: String = song_name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' { c } else { '_' })
+        .collect();
+    let stem = if stem.is_empty() { "song".to_string() } else { stem };
+    PathBuf::from(MUSIC_EXPORTS_DIR).join(format!("{}.wav", stem))
+}
+
+/// Encode stereo float samples as a 16-bit PCM WAV file
+pub fn encode_wav(left: &[f32], right: &[f32], sample_rate: u32) -> Vec<u8> {
+    let frames = left.len().min(right.len());
+    let data_len = (frames * 4) as u32;
+    let mut out = Vec::with_capacity(44 + data_len as usize);
+
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&2u16.to_le_bytes()); // stereo
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * 4).to_le_bytes()); // byte rate
+    out.extend_from_slice(&4u16.to_le_bytes()); // block align
+    out.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    let to_i16 = |s: f32| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+    for (&l, &r) in left.iter().zip(right).take(frames) {
+        out.extend_from_slice(&to_i16(l).to_le_bytes());
+        out.extend_from_slice(&to_i16(r).to_le_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracker::pattern::{Pattern, Song};
+
+    #[test]
+    fn wav_header_and_clamped_samples() {
+        let wav = encode_wav(&[0.0, 2.0], &[-2.0, 0.5], 44100);
+        assert_eq!(wav.len(), 44 + 8);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes([wav[4], wav[5], wav[6], wav[7]]), 44);
+        assert_eq!(u32::from_le_bytes([wav[24], wav[25], wav[26], wav[27]]), 44100);
+        assert_eq!(u32::from_le_bytes([wav[40], wav[41], wav[42], wav[43]]), 8);
+        // Frame 0: L=0, R=-1 (clamped); frame 1: L=+1 (clamped)
+        assert_eq!(i16::from_le_bytes([wav[44], wav[45]]), 0);
+        assert_eq!(i16::from_le_bytes([wav[46], wav[47]]), -i16::MAX);
+        assert_eq!(i16::from_le_bytes([wav[48], wav[49]]), i16::MAX);
+    }
+
+    #[test]
+    fn export_path_is_filename_safe() {
+        let path = export_path("boss/theme: v2");
+        assert_eq!(path, PathBuf::from(MUSIC_EXPORTS_DIR).join("boss_theme_ v2.wav"));
+        assert_eq!(export_path("  ").file_name().unwrap(), "song.wav");
+    }
+
+    #[test]
+    fn song_rows_follow_arrangement() {
+        let mut song = Song::new();
+        song.patterns.push(Pattern::new(32));
+        song.arrangement = vec![0, 1, 0];
+        let first = song.patterns[0].length;
+        assert_eq!(song.total_rows(), first * 2 + 32);
+    }
+}
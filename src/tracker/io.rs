@@ -4,12 +4,15 @@
 //! Supports both compressed (brotli) and uncompressed RON files.
 //! - Reading: Auto-detects format by checking for valid RON start
 //! - Writing: Always uses brotli compression
+//!
+//! Also imports Standard MIDI Files (.mid) into a new song.
 
+use std::collections::HashMap;
 use std::fs;
 use std::io::Cursor;
 use std::path::Path;
 
-use super::pattern::Song;
+use super::pattern::{Note, Pattern, Song, ChannelSettings, DEFAULT_PATTERN_LEN, MAX_CHANNELS};
 
 /// Save a song to a file in compressed RON format (brotli)
 pub fn save_song(song: &Song, path: &Path) -> Result<(), String> {
@@ -117,6 +120,402 @@ pub fn load_song_with_storage(path: &str, storage: &Storage) -> Result<Song, Str
     load_song_from_str(&contents)
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// MIDI import
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Maximum number of patterns a MIDI import may create
+const MAX_IMPORT_PATTERNS: usize = 128;
+
+/// MIDI channel reserved for GM percussion (channel 10, zero-based 9)
+const MIDI_DRUM_CHANNEL: u8 = 9;
+
+/// Summary of what a MIDI import kept and what it had to drop
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MidiImportReport {
+    /// Notes placed into patterns
+    pub notes_imported: usize,
+    /// Notes dropped because all tracker channels were busy
+    pub dropped_polyphony: usize,
+    /// Notes on the GM drum channel (drum kits aren't supported yet)
+    pub dropped_drums: usize,
+    /// Notes starting after the last importable pattern
+    pub dropped_overflow: usize,
+    /// Channel events with no tracker equivalent (pitch bend, CC, aftertouch)
+    pub ignored_events: usize,
+    /// First tempo found in the file (the song keeps its own tempo)
+    pub midi_bpm: Option<f32>,
+}
+
+impl MidiImportReport {
+    /// Total number of notes that didn't make it into the song
+    pub fn dropped_notes(&self) -> usize {
+        self.dropped_polyphony + self.dropped_drums + self.dropped_overflow
+    }
+
+    /// One-line summary for the status bar
+    pub fn summary(&self) -> String {
+        let mut text = format!("Imported {} notes", self.notes_imported);
+        if self.dropped_notes() > 0 {
+            text.push_str(&format!(
+                ", dropped {} ({} polyphony, {} drums, {} past end)",
+                self.dropped_notes(),
+                self.dropped_polyphony,
+                self.dropped_drums,
+                self.dropped_overflow
+            ));
+        }
+        if self.ignored_events > 0 {
+            text.push_str(&format!(", ignored {} other events", self.ignored_events));
+        }
+        text
+    }
+}
+
+/// A parsed MIDI event (only the kinds the importer cares about)
+#[derive(Debug, Clone, Copy)]
+enum MidiEvent {
+    NoteOn { channel: u8, pitch: u8, velocity: u8 },
+    NoteOff { channel: u8, pitch: u8 },
+    Program { channel: u8, program: u8 },
+    Tempo { micros_per_quarter: u32 },
+    Ignored,
+}
+
+impl MidiEvent {
+    /// Ordering for events on the same tick: programs, then offs, then ons
+    fn priority(&self) -> u8 {
+        match self {
+            MidiEvent::Program { .. } | MidiEvent::Tempo { .. } => 0,
+            MidiEvent::NoteOff { .. } => 1,
+            MidiEvent::NoteOn { .. } => 2,
+            MidiEvent::Ignored => 3,
+        }
+    }
+}
+
+/// A complete note (on + off) in MIDI ticks
+#[derive(Debug, Clone, Copy)]
+struct MidiNote {
+    start: u64,
+    end: u64,
+    channel: u8,
+    pitch: u8,
+    velocity: u8,
+    program: u8,
+}
+
+/// Byte reader for the big-endian SMF format
+struct MidiReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> MidiReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        let b = *self.data.get(self.pos).ok_or("Invalid MIDI: unexpected end of data")?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(n).filter(|&e| e <= self.data.len())
+            .ok_or("Invalid MIDI: unexpected end of data")?;
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// Variable-length quantity (up to 4 bytes)
+    fn vlq(&mut self) -> Result<u32, String> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let b = self.u8()?;
+            value = (value << 7) | (b & 0x7F) as u32;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Invalid MIDI: variable-length value too long".to_string())
+    }
+}
+
+/// Parse one MTrk chunk into (absolute tick, event) pairs
+fn parse_midi_track(data: &[u8], events: &mut Vec<(u64, MidiEvent)>) -> Result<(), String> {
+    let mut r = MidiReader::new(data);
+    let mut tick = 0u64;
+    let mut running_status: Option<u8> = None;
+
+    while !r.is_empty() {
+        tick += r.vlq()? as u64;
+        let mut status = r.u8()?;
+
+        match status {
+            0xFF => {
+                // Meta event
+                let kind = r.u8()?;
+                let len = r.vlq()? as usize;
+                let body = r.bytes(len)?;
+                if kind == 0x51 && len == 3 {
+                    let micros = ((body[0] as u32) << 16) | ((body[1] as u32) << 8) | body[2] as u32;
+                    events.push((tick, MidiEvent::Tempo { micros_per_quarter: micros }));
+                } else if kind == 0x2F {
+                    break; // End of track
+                }
+                continue;
+            }
+            0xF0 | 0xF7 => {
+                // SysEx - skip
+                let len = r.vlq()? as usize;
+                r.bytes(len)?;
+                continue;
+            }
+            _ => {}
+        }
+
+        // Running status: data byte reuses the previous status
+        let first = if status < 0x80 {
+            let data_byte = status;
+            status = running_status.ok_or("Invalid MIDI: data byte without status")?;
+            data_byte
+        } else {
+            running_status = Some(status);
+            r.u8()?
+        };
+
+        let channel = status & 0x0F;
+        let event = match status & 0xF0 {
+            0x80 => {
+                r.u8()?;
+                MidiEvent::NoteOff { channel, pitch: first }
+            }
+            0x90 => {
+                let velocity = r.u8()?;
+                if velocity == 0 {
+                    MidiEvent::NoteOff { channel, pitch: first }
+                } else {
+                    MidiEvent::NoteOn { channel, pitch: first, velocity }
+                }
+            }
+            0xA0 | 0xB0 | 0xE0 => {
+                r.u8()?;
+                MidiEvent::Ignored
+            }
+            0xC0 => MidiEvent::Program { channel, program: first },
+            0xD0 => MidiEvent::Ignored,
+            _ => return Err(format!("Invalid MIDI: unknown status byte {:#04x}", status)),
+        };
+        events.push((tick, event));
+    }
+    Ok(())
+}
+
+/// Parse an SMF file into its time division and merged, time-ordered events
+fn parse_midi(bytes: &[u8]) -> Result<(u16, Vec<(u64, MidiEvent)>), String> {
+    let mut r = MidiReader::new(bytes);
+    if r.bytes(4).ok() != Some(b"MThd".as_slice()) {
+        return Err("Not a MIDI file (missing MThd header)".to_string());
+    }
+    let header_len = r.u32()? as usize;
+    let header = r.bytes(header_len)?;
+    if header.len() < 6 {
+        return Err("Invalid MIDI: header too short".to_string());
+    }
+    let format = u16::from_be_bytes([header[0], header[1]]);
+    let division = u16::from_be_bytes([header[4], header[5]]);
+    if format > 1 {
+        return Err(format!("MIDI format {} is not supported (only 0 and 1)", format));
+    }
+    if division == 0 {
+        return Err("Invalid MIDI: zero time division".to_string());
+    }
+
+    let mut events = Vec::new();
+    while !r.is_empty() {
+        let id = r.bytes(4)?;
+        let len = r.u32()? as usize;
+        let chunk = r.bytes(len)?;
+        // Unknown chunk types must be skipped per the spec
+        if id == b"MTrk" {
+            parse_midi_track(chunk, &mut events)?;
+        }
+    }
+
+    // Stable sort keeps per-track order for events on the same tick
+    events.sort_by_key(|(tick, event)| (*tick, event.priority()));
+    Ok((division, events))
+}
+
+/// Import a Standard MIDI File as a new song
+///
+/// Timing (bpm, rows per beat) and mix settings come from `template`; MIDI
+/// beats are quantized onto rows at that resolution. Each MIDI channel gets
+/// its own tracker channels (more than one for chords), with the program as
+/// the note instrument and velocity as the note volume.
+pub fn import_midi(bytes: &[u8], name: &str, template: &Song) -> Result<(Song, MidiImportReport), String> {
+    let (division, events) = parse_midi(bytes)?;
+    let mut report = MidiImportReport::default();
+
+    // Pair note-ons with note-offs (FIFO per channel + pitch)
+    let mut programs = [0u8; 16];
+    let mut open: HashMap<(u8, u8), Vec<(u64, u8)>> = HashMap::new();
+    let mut notes: Vec<MidiNote> = Vec::new();
+    let mut last_tick = 0u64;
+
+    for &(tick, event) in &events {
+        last_tick = last_tick.max(tick);
+        match event {
+            MidiEvent::NoteOn { channel, pitch, velocity } => {
+                if channel == MIDI_DRUM_CHANNEL {
+                    report.dropped_drums += 1;
+                } else {
+                    open.entry((channel, pitch)).or_default().push((tick, velocity));
+                }
+            }
+            MidiEvent::NoteOff { channel, pitch } => {
+                if let Some(stack) = open.get_mut(&(channel, pitch)) {
+                    if !stack.is_empty() {
+                        let (start, velocity) = stack.remove(0);
+                        notes.push(MidiNote {
+                            start, end: tick, channel, pitch, velocity,
+                            program: programs[channel as usize],
+                        });
+                    }
+                }
+            }
+            MidiEvent::Program { channel, program } => {
+                programs[channel as usize] = program & 0x7F;
+            }
+            MidiEvent::Tempo { micros_per_quarter } => {
+                if report.midi_bpm.is_none() && micros_per_quarter > 0 {
+                    report.midi_bpm = Some(60_000_000.0 / micros_per_quarter as f32);
+                }
+            }
+            MidiEvent::Ignored => report.ignored_events += 1,
+        }
+    }
+    // Notes never released end with the file
+    for ((channel, pitch), stack) in open {
+        for (start, velocity) in stack {
+            notes.push(MidiNote {
+                start, end: last_tick.max(start), channel, pitch, velocity,
+                program: programs[channel as usize],
+            });
+        }
+    }
+
+    // Ticks -> rows. PPQ files map beats to rows directly; SMPTE files go through seconds.
+    let rows_per_tick = if division & 0x8000 == 0 {
+        template.rows_per_beat.max(1) as f64 / division as f64
+    } else {
+        let fps = -(((division >> 8) as u8 as i8) as f64);
+        let ticks_per_frame = (division & 0xFF).max(1) as f64;
+        1.0 / (fps.max(1.0) * ticks_per_frame * template.tick_duration())
+    };
+    let to_row = |tick: u64| (tick as f64 * rows_per_tick).round() as usize;
+
+    notes.sort_by_key(|n| (n.start, n.channel, n.pitch));
+
+    let pattern_len = DEFAULT_PATTERN_LEN;
+    let max_rows = pattern_len * MAX_IMPORT_PATTERNS;
+
+    // Tracker channel allocation: (midi channel, program, row the channel frees up)
+    let mut voices: Vec<(u8, u8, usize)> = Vec::new();
+    let mut placed: Vec<(usize, usize, usize, Note)> = Vec::new(); // (voice, start, end, note)
+
+    for note in &notes {
+        let start = to_row(note.start);
+        let end = to_row(note.end).max(start + 1);
+        if start >= max_rows {
+            report.dropped_overflow += 1;
+            continue;
+        }
+
+        // Prefer a free channel already playing this MIDI channel, then a new
+        // channel, then any free channel
+        let voice = match voices.iter().position(|v| v.0 == note.channel && v.2 <= start) {
+            Some(v) => v,
+            None if voices.len() < MAX_CHANNELS => {
+                voices.push((note.channel, note.program, 0));
+                voices.len() - 1
+            }
+            None => match voices.iter().position(|v| v.2 <= start) {
+                Some(v) => v,
+                None => {
+                    report.dropped_polyphony += 1;
+                    continue;
+                }
+            },
+        };
+        voices[voice].2 = end;
+
+        placed.push((voice, start, end, Note {
+            pitch: Some(note.pitch),
+            instrument: Some(note.program),
+            volume: Some(note.velocity.min(127)),
+            ..Note::EMPTY
+        }));
+        report.notes_imported += 1;
+    }
+
+    let total_rows = placed.iter().map(|p| p.2 + 1).max().unwrap_or(1).min(max_rows);
+    let num_patterns = total_rows.div_ceil(pattern_len).max(1);
+    let num_channels = voices.len().max(1);
+
+    let mut song = Song::new();
+    song.name = name.to_string();
+    song.bpm = template.bpm;
+    song.rows_per_beat = template.rows_per_beat;
+    song.reverb = template.reverb;
+    song.master_volume = template.master_volume;
+    song.patterns = vec![Pattern::with_channels(pattern_len, num_channels); num_patterns];
+    song.arrangement = (0..num_patterns).collect();
+    song.channel_instruments = if voices.is_empty() {
+        vec![0]
+    } else {
+        voices.iter().map(|v| v.1).collect()
+    };
+    song.channel_settings = vec![ChannelSettings::default(); num_channels];
+
+    // Notes are placed in start order, so a note-off is overwritten by a
+    // following note that starts on the same row
+    for (voice, start, end, note) in placed {
+        song.patterns[start / pattern_len].set(voice, start % pattern_len, note);
+        if end < max_rows {
+            song.patterns[end / pattern_len].set(voice, end % pattern_len, Note::off());
+        }
+    }
+
+    Ok((song, report))
+}
+
+/// Import a MIDI file from disk (native only)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn import_midi_file(path: &Path, template: &Song) -> Result<(Song, MidiImportReport), String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("Imported");
+    import_midi(&bytes, name, template)
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,4 +548,86 @@ mod tests {
         let result = load_song(temp_file.path());
         assert!(result.is_err());
     }
+
+    /// Build a format-0 MIDI file (96 PPQ) from raw track event bytes
+    fn midi_file(track: &[u8]) -> Vec<u8> {
+        let mut data = b"MThd".to_vec();
+        data.extend_from_slice(&6u32.to_be_bytes());
+        data.extend_from_slice(&[0, 0, 0, 1, 0, 96]);
+        data.extend_from_slice(b"MTrk");
+        data.extend_from_slice(&(track.len() as u32 + 4).to_be_bytes());
+        data.extend_from_slice(track);
+        data.extend_from_slice(&[0x00, 0xFF, 0x2F, 0x00]);
+        data
+    }
+
+    #[test]
+    fn test_midi_import_notes() {
+        let track = [
+            0x00, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20, // tempo 120 bpm
+            0x00, 0xC0, 0x05,                         // program 5
+            0x00, 0x90, 60, 100,                      // C4 on, velocity 100
+            0x00, 0xE0, 0x00, 0x40,                   // pitch bend (ignored)
+            0x60, 0x80, 60, 0,                        // off after one beat
+            0x00, 0x99, 36, 90,                       // drum hit (dropped)
+            0x30, 60, 0,                              // running status: C4 velocity 0 on ch 10
+        ];
+        let (song, report) = import_midi(&midi_file(&track), "test", &Song::new()).unwrap();
+
+        assert_eq!(report.notes_imported, 1);
+        assert_eq!(report.dropped_drums, 1);
+        assert_eq!(report.ignored_events, 1);
+        assert_eq!(report.midi_bpm, Some(120.0));
+        assert_eq!(song.num_channels(), 1);
+        assert_eq!(song.channel_instruments, vec![5]);
+
+        let note = song.patterns[0].get(0, 0).unwrap();
+        assert_eq!(note.pitch, Some(60));
+        assert_eq!(note.instrument, Some(5));
+        assert_eq!(note.volume, Some(100));
+        // One beat at 4 rows per beat
+        assert_eq!(song.patterns[0].get(0, 4).unwrap().pitch, Some(0xFF));
+    }
+
+    #[test]
+    fn test_midi_import_chord_uses_channels() {
+        let track = [
+            0x00, 0x90, 60, 80,
+            0x00, 64, 80, // running status
+            0x00, 67, 80,
+            0x81, 0x40, 0x80, 60, 0, // 192 ticks later (2 beats)
+            0x00, 0x80, 64, 0,
+            0x00, 0x80, 67, 0,
+            0x0C, 0x90, 72, 80, // 12 ticks = half a row, rounds onto row 9
+            0x18, 0x80, 72, 0,
+        ];
+        let (song, report) = import_midi(&midi_file(&track), "chord", &Song::new()).unwrap();
+
+        assert_eq!(report.notes_imported, 4);
+        assert_eq!(song.num_channels(), 3);
+        for ch in 0..3 {
+            assert!(song.patterns[0].get(ch, 0).unwrap().pitch.is_some());
+        }
+        // The late note reuses the first free channel after quantizing
+        let reused = (0..3).find(|&ch| song.patterns[0].get(ch, 9).unwrap().pitch == Some(72));
+        assert!(reused.is_some());
+    }
+
+    #[test]
+    fn test_midi_import_polyphony_limit() {
+        let mut track = Vec::new();
+        for i in 0..(MAX_CHANNELS as u8 + 2) {
+            track.extend_from_slice(&[0x00, 0x90, 40 + i, 64]);
+        }
+        let (song, report) = import_midi(&midi_file(&track), "big", &Song::new()).unwrap();
+
+        assert_eq!(song.num_channels(), MAX_CHANNELS);
+        assert_eq!(report.notes_imported, MAX_CHANNELS);
+        assert_eq!(report.dropped_polyphony, 2);
+    }
+
+    #[test]
+    fn test_midi_import_rejects_garbage() {
+        assert!(import_midi(b"not a midi file", "x", &Song::new()).is_err());
+    }
 }
@@ -2,19 +2,21 @@
 
 use macroquad::prelude::*;
 use crate::storage::Storage;
+use crate::project::{confirm_delete, Dependency, DependencyGraph, UsagesPanel};
+use crate::texture::TextureLibrary;
 use crate::ui::{
     Rect, UiContext, Toolbar, icon, draw_knob, draw_mini_knob,
     // Theme colors
-    BG_COLOR, HEADER_COLOR, TEXT_COLOR, TEXT_DIM,
-    ROW_EVEN, ROW_ODD, ROW_BEAT, ROW_HIGHLIGHT,
-    CURSOR_COLOR, PLAYBACK_ROW_COLOR,
+    theme,
     NOTE_COLOR, INST_COLOR, VOL_COLOR, FX_COLOR,
 };
 use super::state::{TrackerState, TrackerView};
+use super::pattern::Effect;
 use super::psx_reverb::ReverbType;
 use super::actions::build_context;
 use crate::input::MidiMessage;
 use super::song_browser::{SongBrowserAction, next_available_song_name};
+use super::meters::{LevelMeter, OutputMeters};
 
 // Layout constants
 const ROW_HEIGHT: f32 = 18.0;
@@ -32,7 +34,8 @@ const STATUS_BAR_HEIGHT: f32 = 22.0;
 /// Draw the tracker interface
 pub fn draw_tracker(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState, icon_font: Option<&Font>, storage: &Storage) {
     // Background
-    draw_rectangle(rect.x, rect.y, rect.w, rect.h, BG_COLOR);
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().bg);
+    state.update_meters(get_frame_time());
 
     // Split into header, main area, and status bar
     let header_height = 60.0;
@@ -52,12 +55,51 @@ pub fn draw_tracker(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState, i
     // Draw status bar at bottom
     draw_status_bar(status_rect, state);
 
+    if state.export.is_some() {
+        draw_export_progress(ctx, rect, state);
+        return;
+    }
+
     // Handle input (but not if browser is open)
     if !state.song_browser.open {
         handle_input(ctx, state, storage);
     }
 }
 
+/// Progress overlay for a running WAV export (blocks tracker input)
+fn draw_export_progress(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState) {
+    let Some(export) = &state.export else {
+        return;
+    };
+    let progress = export.progress();
+    let file_name = export.path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0.0, 0.0, 0.0, 0.5));
+    let w = 320.0;
+    let h = 96.0;
+    let panel = Rect::new(
+        (rect.x + (rect.w - w) * 0.5).floor(),
+        (rect.y + (rect.h - h) * 0.5).floor(),
+        w,
+        h,
+    );
+    draw_rectangle(panel.x, panel.y, panel.w, panel.h, theme().header);
+    draw_rectangle_lines(panel.x, panel.y, panel.w, panel.h, 1.0, Color::from_rgba(80, 80, 90, 255));
+    draw_text(&format!("Exporting {}", file_name), panel.x + 12.0, panel.y + 22.0, 14.0, theme().text);
+
+    let bar = Rect::new(panel.x + 12.0, panel.y + 34.0, panel.w - 24.0, 14.0);
+    draw_rectangle(bar.x, bar.y, bar.w, bar.h, theme().bg);
+    draw_rectangle(bar.x, bar.y, bar.w * progress, bar.h, NOTE_COLOR);
+    draw_text(&format!("{:.0}%", progress * 100.0), bar.x, bar.bottom() + 16.0, 12.0, theme().text_dim);
+
+    let cancel_rect = Rect::new(panel.right() - 82.0, panel.bottom() - 30.0, 70.0, 22.0);
+    if crate::ui::text_button(ctx, cancel_rect, "Cancel", "Stop exporting (Esc)") || is_key_pressed(KeyCode::Escape) {
+        state.cancel_export();
+    }
+}
+
 /// Draw song browser dialog and handle actions
 /// Call this separately from draw_tracker so modal input blocking works correctly
 pub fn draw_song_browser(ctx: &mut UiContext, state: &mut TrackerState, icon_font: Option<&Font>, storage: &Storage) -> SongBrowserAction {
@@ -152,9 +194,22 @@ pub fn draw_song_browser(ctx: &mut UiContext, state: &mut TrackerState, icon_fon
         SongBrowserAction::NewSong => {
             state.new_song();
         }
+        SongBrowserAction::FindUsages => {
+            if let Some(name) = state.song_browser.selected_song().map(|info| info.name.clone()) {
+                let graph = song_dependency_graph(storage);
+                state.song_browser.usages = Some(UsagesPanel::find(&graph, Dependency::Song(name)));
+            }
+        }
         SongBrowserAction::DeleteSong => {
-            // Delete selected user song
-            if let Some(song_info) = state.song_browser.selected_song() {
+            // Levels still playing it are listed first; a second delete goes ahead
+            let graph = song_dependency_graph(storage);
+            let in_use = state.song_browser.selected_song()
+                .map(|info| Dependency::Song(info.name.clone()))
+                .is_some_and(|dep| !confirm_delete(&mut state.song_browser.usages, &graph, dep));
+            if in_use {
+                state.set_status("Song is still in use (Delete again to delete anyway)", 3.0);
+            } else if let Some(song_info) = state.song_browser.selected_song() {
+                // Delete selected user song
                 let path = song_info.path.clone();
                 #[cfg(not(target_arch = "wasm32"))]
                 {
@@ -206,9 +261,15 @@ pub fn draw_song_browser(ctx: &mut UiContext, state: &mut TrackerState, icon_fon
     browser_action
 }
 
+/// Saved levels, for finding where songs are used. Songs are only
+/// referenced by level cutscenes, so no texture names are needed.
+fn song_dependency_graph(storage: &Storage) -> DependencyGraph {
+    DependencyGraph::scan(storage, &TextureLibrary::new())
+}
+
 /// Draw the header with transport controls and song info
 fn draw_header(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState, icon_font: Option<&Font>, storage: &Storage) {
-    draw_rectangle(rect.x, rect.y, rect.w, rect.h, HEADER_COLOR);
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().header);
 
     // First row: toolbar with icons (36.0 height to match World Editor)
     let toolbar_rect = Rect::new(rect.x, rect.y, rect.w, 36.0);
@@ -233,6 +294,16 @@ fn draw_header(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState, icon_f
                 }
             }
         }
+        if toolbar.icon_button(ctx, icon::MUSIC, icon_font, "Import MIDI") {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("MIDI", &["mid", "midi"])
+                .pick_file()
+            {
+                if let Err(e) = state.import_midi_file(&path) {
+                    state.set_status(&format!("Import failed: {}", e), 3.0);
+                }
+            }
+        }
         // Save button - save to current file or auto-generate name
         if toolbar.icon_button(ctx, icon::SAVE, icon_font, "Save (Ctrl+S)") {
             if let Some(path) = state.current_file.clone() {
@@ -250,6 +321,12 @@ fn draw_header(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState, icon_f
                 }
             }
         }
+        if toolbar.icon_button(ctx, icon::DOWNLOAD, icon_font, "Export WAV") {
+            match state.start_export() {
+                Ok(path) => state.set_status(&format!("Exporting to {}...", path.display()), 2.0),
+                Err(e) => state.set_status(&format!("Export failed: {}", e), 3.0),
+            }
+        }
         if toolbar.icon_button(ctx, icon::SAVE_AS, icon_font, "Save As") {
             if let Some(path) = rfd::FileDialog::new()
                 .add_filter("Song", &["ron"])
@@ -333,6 +410,22 @@ fn draw_header(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState, icon_f
         state.toggle_playback();
     }
 
+    // Live recording: arm, quantize resolution, metronome
+    let rec_tooltip = if state.recording { "Record: ON (notes played during playback are written)" } else { "Record: OFF" };
+    if toolbar.icon_button_active(ctx, icon::CIRCLE_DOT, icon_font, rec_tooltip, state.recording) {
+        state.recording = !state.recording;
+        let mode = if state.recording { "armed" } else { "off" };
+        state.set_status(&format!("Record {}", mode), 1.0);
+    }
+    if toolbar.text_button(ctx, &format!("Q:{}", state.record_quantize), "Record quantize in rows - click to cycle") {
+        state.cycle_record_quantize();
+        state.set_status(&format!("Record quantize: {} rows", state.record_quantize), 1.0);
+    }
+    let metronome_tooltip = if state.metronome { "Metronome: ON" } else { "Metronome: OFF" };
+    if toolbar.icon_button_active(ctx, icon::TIMER, icon_font, metronome_tooltip, state.metronome) {
+        state.metronome = !state.metronome;
+    }
+
     toolbar.separator();
 
     // BPM controls (Shift+click for ±10, normal click for ±1)
@@ -410,7 +503,7 @@ fn draw_header(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState, icon_f
                  state.current_row,
                  state.current_pattern().map(|p| p.length).unwrap_or(64),
                  state.current_channel + 1),
-        rect.x + 10.0, y2 + 14.0, 14.0, TEXT_COLOR
+        rect.x + 10.0, y2 + 14.0, 14.0, theme().text
     );
 
     // Song name / file name with dirty indicator
@@ -425,13 +518,81 @@ fn draw_header(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState, icon_f
     } else {
         "Untitled".to_string()
     };
-    draw_text(&song_display, rect.x + 380.0, y2 + 14.0, 14.0, TEXT_COLOR);
+    draw_text(&song_display, rect.x + 380.0, y2 + 14.0, 14.0, theme().text);
 
     // Soundfont status
     let sf_status = state.audio.soundfont_name()
         .map(|n| format!("SF: {}", n))
         .unwrap_or_else(|| "No Soundfont".to_string());
-    draw_text(&sf_status, rect.x + 540.0, y2 + 14.0, 14.0, if state.audio.is_loaded() { TEXT_DIM } else { Color::new(0.8, 0.3, 0.3, 1.0) });
+    draw_text(&sf_status, rect.x + 540.0, y2 + 14.0, 14.0, if state.audio.is_loaded() { theme().text_dim } else { Color::new(0.8, 0.3, 0.3, 1.0) });
+
+    // Master oscilloscope and level meters (right side, when there is room)
+    if rect.w >= 1060.0 {
+        draw_master_meters(Rect::new(rect.x + rect.w - 320.0, y2, 310.0, 18.0), &state.meters);
+    }
+}
+
+/// Meter bar color for a level (0..1): green, yellow near the top, red at full scale
+fn meter_color(level: f32) -> Color {
+    if level >= 0.95 {
+        Color::new(0.9, 0.25, 0.2, 1.0)
+    } else if level >= 0.75 {
+        Color::new(0.9, 0.8, 0.25, 1.0)
+    } else {
+        Color::new(0.3, 0.8, 0.4, 1.0)
+    }
+}
+
+/// Horizontal level bar with its peak marker
+fn draw_level_bar(x: f32, y: f32, w: f32, h: f32, meter: &LevelMeter) {
+    draw_rectangle(x, y, w, h, Color::new(0.08, 0.08, 0.1, 1.0));
+    if meter.level > 0.0 {
+        draw_rectangle(x, y, (w * meter.level).round(), h, meter_color(meter.level));
+    }
+    if meter.peak > 0.0 {
+        let peak_x = (x + w * meter.peak).round().min(x + w - 1.0);
+        draw_rectangle(peak_x, y, 1.0, h, theme().text);
+    }
+}
+
+/// Stereo oscilloscope of the master output, L/R meters (dB scale) and clip lights
+fn draw_master_meters(rect: Rect, meters: &OutputMeters) {
+    let scope = Rect::new(rect.x, rect.y, 120.0, rect.h);
+    draw_rectangle(scope.x, scope.y, scope.w, scope.h, Color::new(0.08, 0.08, 0.1, 1.0));
+    let mid = scope.y + scope.h / 2.0;
+    draw_line(scope.x, mid, scope.x + scope.w, mid, 1.0, Color::new(0.2, 0.2, 0.24, 1.0));
+    let trace_colors = [Color::new(0.4, 0.8, 0.9, 0.9), Color::new(0.9, 0.6, 0.3, 0.7)];
+    for (wave, color) in meters.wave.iter().zip(trace_colors) {
+        if wave.len() < 2 {
+            continue;
+        }
+        let step = scope.w / (wave.len() - 1) as f32;
+        let y_of = |sample: f32| mid - sample.clamp(-1.0, 1.0) * (scope.h / 2.0 - 1.0);
+        for (i, pair) in wave.windows(2).enumerate() {
+            let x0 = scope.x + i as f32 * step;
+            draw_line(x0, y_of(pair[0]), x0 + step, y_of(pair[1]), 1.0, color);
+        }
+    }
+
+    // L/R bars stacked, clip lights on the right
+    let bar_x = scope.x + scope.w + 18.0;
+    let clip_w = 22.0;
+    let bar_w = rect.x + rect.w - bar_x - clip_w - 4.0;
+    let bar_h = (rect.h - 2.0) / 2.0;
+    for (side, (meter, label)) in meters.master.iter().zip(["L", "R"]).enumerate() {
+        let y = rect.y + side as f32 * (bar_h + 2.0);
+        draw_text(label, bar_x - 10.0, y + bar_h, 10.0, theme().text_dim);
+        draw_level_bar(bar_x, y, bar_w, bar_h, meter);
+    }
+    let clip_x = bar_x + bar_w + 4.0;
+    let clipping = meters.master.iter().any(|m| m.is_clipping());
+    let (clip_bg, clip_text) = if clipping {
+        (Color::new(0.85, 0.15, 0.15, 1.0), WHITE)
+    } else {
+        (Color::new(0.2, 0.1, 0.1, 1.0), theme().text_dim)
+    };
+    draw_rectangle(clip_x, rect.y, clip_w, rect.h, clip_bg);
+    draw_text("CLIP", clip_x + 1.0, rect.y + rect.h / 2.0 + 3.0, 9.0, clip_text);
 }
 
 /// Height of the channel strip header (simplified: just channel name + instrument)
@@ -509,13 +670,18 @@ fn draw_pattern_grid(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState)
         };
 
         // Display "Ch1: Piano" centered in the channel strip
-        let ch_color = if is_current { NOTE_COLOR } else { TEXT_COLOR };
+        let ch_color = if is_current { NOTE_COLOR } else { theme().text };
         let label = format!("Ch{}: {}", ch + 1, display_name);
         let label_dims = measure_text(&label, None, 12, 1.0);
         let label_x = ch_x + (CHANNEL_WIDTH - label_dims.width) / 2.0;
         let label_y = rect.y + CHANNEL_STRIP_HEIGHT / 2.0 + 4.0;
         draw_text(&label, label_x, label_y, 12.0, ch_color);
 
+        // Channel VU meter along the bottom of the strip
+        if let Some(meter) = state.meters.channels.get(ch) {
+            draw_level_bar(ch_x + 4.0, rect.y + CHANNEL_STRIP_HEIGHT - 5.0, CHANNEL_WIDTH - 9.0, 3.0, meter);
+        }
+
         // Click anywhere in channel strip to select this channel
         let strip_rect = Rect::new(ch_x, rect.y, CHANNEL_WIDTH - 1.0, CHANNEL_STRIP_HEIGHT);
         if ctx.mouse.inside(&strip_rect) && ctx.mouse.left_pressed {
@@ -530,7 +696,7 @@ fn draw_pattern_grid(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState)
 
     // === Column headers (Note, Volume, Fx) ===
     let header_y = rect.y + CHANNEL_STRIP_HEIGHT;
-    draw_rectangle(rect.x, header_y, rect.w, ROW_HEIGHT, HEADER_COLOR);
+    draw_rectangle(rect.x, header_y, rect.w, ROW_HEIGHT, theme().header);
 
     x = rect.x + ROW_NUM_WIDTH;
     for ch in 0..channels_drawn {
@@ -549,7 +715,7 @@ fn draw_pattern_grid(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState)
 
         // Column labels (Note, Volume, Fx - instrument is per-channel in strip)
         let is_current = ch == state.current_channel;
-        let label_color = if is_current { NOTE_COLOR } else { TEXT_DIM };
+        let label_color = if is_current { NOTE_COLOR } else { theme().text_dim };
         draw_text("Not", ch_x + 4.0, header_y + 14.0, 12.0, label_color);
         draw_text("Vl", ch_x + NOTE_WIDTH + 2.0, header_y + 14.0, 12.0, label_color);
         draw_text("Fx", ch_x + NOTE_WIDTH + VOL_WIDTH + 2.0, header_y + 14.0, 12.0, label_color);
@@ -661,20 +827,20 @@ fn draw_pattern_grid(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState)
 
         // Row background
         let row_bg = if state.playing && row_idx == state.playback_row && state.playback_pattern_idx == state.current_pattern_idx {
-            PLAYBACK_ROW_COLOR
+            theme().playback_row
         } else if row_idx == state.current_row {
-            ROW_HIGHLIGHT
+            theme().row_highlight
         } else if row_idx % (rows_per_beat as usize * 4) == 0 {
-            ROW_BEAT
+            theme().row_beat
         } else if row_idx % 2 == 0 {
-            ROW_EVEN
+            theme().row_even
         } else {
-            ROW_ODD
+            theme().row_odd
         };
         draw_rectangle(rect.x, y, rect.w, ROW_HEIGHT, row_bg);
 
         // Row number
-        let row_color = if row_idx % (rows_per_beat as usize) == 0 { TEXT_COLOR } else { TEXT_DIM };
+        let row_color = if row_idx % (rows_per_beat as usize) == 0 { theme().text } else { theme().text_dim };
         draw_text(&format!("{:02X}", row_idx), rect.x + 4.0, y + 14.0, 12.0, row_color);
 
         // Draw each channel
@@ -704,22 +870,22 @@ fn draw_pattern_grid(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState)
                     2 => FX_WIDTH,
                     _ => FXPARAM_WIDTH,
                 };
-                draw_rectangle(col_x, y, col_w, ROW_HEIGHT, CURSOR_COLOR);
+                draw_rectangle(col_x, y, col_w, ROW_HEIGHT, theme().cursor);
             }
 
             // Note
             let note_str = note.pitch_name().unwrap_or_else(|| "---".to_string());
-            let note_color = if note.pitch.is_some() { NOTE_COLOR } else { TEXT_DIM };
+            let note_color = if note.pitch.is_some() { NOTE_COLOR } else { theme().text_dim };
             draw_text(&note_str, x + 2.0, y + 14.0, 12.0, note_color);
 
             // Volume (instrument column removed - instrument is per-channel)
             let vol_str = note.volume.map(|v| format!("{:3}", v)).unwrap_or_else(|| "---".to_string());
-            let vol_color = if note.volume.is_some() { VOL_COLOR } else { TEXT_DIM };
+            let vol_color = if note.volume.is_some() { VOL_COLOR } else { theme().text_dim };
             draw_text(&vol_str, x + NOTE_WIDTH + 2.0, y + 14.0, 12.0, vol_color);
 
             // Effect
             let fx_str = note.effect.map(|e| e.to_string()).unwrap_or_else(|| "-".to_string());
-            let fx_color = if note.effect.is_some() { FX_COLOR } else { TEXT_DIM };
+            let fx_color = if note.effect.is_some() { FX_COLOR } else { theme().text_dim };
             draw_text(&fx_str, x + NOTE_WIDTH + VOL_WIDTH + 2.0, y + 14.0, 12.0, fx_color);
 
             // Effect param
@@ -737,7 +903,7 @@ static mut PATTERN_BANK_SELECTION: usize = 0;
 static mut ARRANGEMENT_FOCUS: bool = true; // true = arrangement, false = pattern bank
 
 fn draw_arrangement_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState) {
-    draw_rectangle(rect.x, rect.y, rect.w, rect.h, BG_COLOR);
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().bg);
 
     // Layout: Pattern Bank (left) | Arrangement (right)
     let bank_width = 200.0;
@@ -747,7 +913,7 @@ fn draw_arrangement_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
     let row_h = 24.0;
 
     // === Pattern Bank (left side) ===
-    draw_text("Pattern Bank", rect.x + 10.0, rect.y + 24.0, 16.0, TEXT_COLOR);
+    draw_text("Pattern Bank", rect.x + 10.0, rect.y + 24.0, 16.0, theme().text);
 
     let bank_rect = Rect::new(rect.x + 10.0, list_top, bank_width - 20.0, list_height);
     draw_rectangle(bank_rect.x, bank_rect.y, bank_rect.w, bank_rect.h, Color::new(0.08, 0.08, 0.1, 1.0));
@@ -769,11 +935,11 @@ fn draw_arrangement_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
         let is_in_arrangement = state.song.arrangement.contains(&i);
 
         let bg = if is_selected {
-            CURSOR_COLOR
+            theme().cursor
         } else if i % 2 == 0 {
-            ROW_EVEN
+            theme().row_even
         } else {
-            ROW_ODD
+            theme().row_odd
         };
         draw_rectangle(bank_rect.x, y, bank_rect.w, row_h - 2.0, bg);
 
@@ -785,7 +951,7 @@ fn draw_arrangement_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
         // Use * for patterns with notes, - for empty
         let indicator = if note_count > 0 { "*" } else { "-" };
 
-        let text_color = if is_selected { Color::new(0.0, 0.0, 0.0, 1.0) } else { TEXT_COLOR };
+        let text_color = if is_selected { Color::new(0.0, 0.0, 0.0, 1.0) } else { theme().text };
         draw_text(
             &format!("{} {:02} [{:3} rows]", indicator, i, pattern.length),
             bank_rect.x + 6.0, y + 16.0, 12.0, text_color
@@ -821,42 +987,78 @@ fn draw_arrangement_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
 
     // === Arrangement (right side) ===
     let arr_x = rect.x + bank_width + 10.0;
-    draw_text("Arrangement", arr_x, rect.y + 24.0, 16.0, TEXT_COLOR);
+    draw_text("Arrangement", arr_x, rect.y + 24.0, 16.0, theme().text);
 
     let arr_rect = Rect::new(arr_x, list_top, arrangement_width - 20.0, list_height);
     draw_rectangle(arr_rect.x, arr_rect.y, arr_rect.w, arr_rect.h, Color::new(0.08, 0.08, 0.1, 1.0));
 
     // Draw arrangement entries
     let visible_arr_rows = (list_height / row_h) as usize;
+    let (loop_start, loop_end) = state.song.loop_bounds();
+    let repeat_x = arr_rect.x + 130.0;
+    let transpose_x = arr_rect.x + 175.0;
+    let mut scroll_edit: Option<(usize, bool, i32)> = None; // (position, transpose?, delta)
     for (i, &pattern_idx) in state.song.arrangement.iter().enumerate() {
         if i >= visible_arr_rows { break; }
 
         let y = arr_rect.y + (i as f32 * row_h);
         let is_current = i == state.current_pattern_idx;
         let is_selected = arr_focus && i == arr_sel;
+        let order = state.song.order(i);
 
         let bg = if is_selected {
-            CURSOR_COLOR
+            theme().cursor
         } else if is_current {
-            ROW_HIGHLIGHT
+            theme().row_highlight
         } else if i % 2 == 0 {
-            ROW_EVEN
+            theme().row_even
         } else {
-            ROW_ODD
+            theme().row_odd
         };
         draw_rectangle(arr_rect.x, y, arr_rect.w, row_h - 2.0, bg);
 
+        // Loop range bar on the left edge, brackets on the marker entries
+        if (loop_start..=loop_end).contains(&i) {
+            draw_rectangle(arr_rect.x, y, 3.0, row_h - 2.0, theme().playback_row);
+        }
+
         // Show position number and pattern reference
         let text_color = if is_selected { Color::new(0.0, 0.0, 0.0, 1.0) }
-            else if is_current { NOTE_COLOR } else { TEXT_COLOR };
+            else if is_current { NOTE_COLOR } else { theme().text };
+        let dim_color = if is_selected { Color::new(0.0, 0.0, 0.0, 0.6) } else { theme().text_dim };
         draw_text(
             &format!("{:02} > Pattern {:02}", i, pattern_idx),
             arr_rect.x + 6.0, y + 16.0, 12.0, text_color
         );
+        draw_text(
+            &format!("x{}", order.repeats),
+            repeat_x, y + 16.0, 12.0, if order.repeats > 1 { text_color } else { dim_color }
+        );
+        let transpose_label = if order.transpose == 0 { "  0".to_string() } else { format!("{:+3}", order.transpose) };
+        draw_text(
+            &transpose_label,
+            transpose_x, y + 16.0, 12.0, if order.transpose != 0 { text_color } else { dim_color }
+        );
+        let mut marker = String::new();
+        if state.song.loop_start == Some(i) {
+            marker.push_str("[ start");
+        }
+        if state.song.loop_end == Some(i) {
+            marker.push_str(if marker.is_empty() { "] end" } else { " ] end" });
+        }
+        if !marker.is_empty() {
+            draw_text(&marker, transpose_x + 40.0, y + 16.0, 12.0, theme().playback_row);
+        }
 
-        // Playback indicator
+        // Playback indicator (with the pass count for repeated entries)
         if is_current && state.playing {
-            draw_text(">", arr_rect.x + arr_rect.w - 20.0, y + 16.0, 12.0, PLAYBACK_ROW_COLOR);
+            let indicator = if order.repeats > 1 {
+                format!("{}/{} >", state.playback_repeat + 1, order.repeats)
+            } else {
+                ">".to_string()
+            };
+            let w = measure_text(&indicator, None, 12, 1.0).width;
+            draw_text(&indicator, arr_rect.x + arr_rect.w - 8.0 - w, y + 16.0, 12.0, theme().playback_row);
         }
 
         // Click to select
@@ -874,14 +1076,32 @@ fn draw_arrangement_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
                 state.current_row = 0;
                 state.view = TrackerView::Pattern;
             }
+            // Scroll over the repeat / transpose columns to change them
+            if ctx.mouse.scroll != 0.0 {
+                let delta = if ctx.mouse.scroll > 0.0 { 1 } else { -1 };
+                if (repeat_x - 4.0..transpose_x - 4.0).contains(&ctx.mouse.x) {
+                    scroll_edit = Some((i, false, delta));
+                } else if (transpose_x - 4.0..transpose_x + 36.0).contains(&ctx.mouse.x) {
+                    scroll_edit = Some((i, true, delta));
+                }
+            }
         }
     }
+    match scroll_edit {
+        Some((i, false, delta)) => state.arrangement_adjust_repeats(i, delta),
+        Some((i, true, delta)) => state.arrangement_adjust_transpose(i, delta),
+        None => {}
+    }
 
     // === Help text ===
     let help_y = rect.y + rect.h - 30.0;
     draw_text(
         "Tab: Switch focus | +: New pattern | Enter: Add to arrangement | Del: Remove | ↑↓: Move",
-        rect.x + 10.0, help_y, 12.0, TEXT_DIM
+        rect.x + 10.0, help_y, 12.0, theme().text_dim
+    );
+    draw_text(
+        "R/Shift+R: Repeats | ,/.: Transpose (Shift: octave) | [ ]: Loop start/end",
+        rect.x + 10.0, help_y + 16.0, 12.0, theme().text_dim
     );
 
     // === Keyboard handling for arrangement view ===
@@ -1012,6 +1232,38 @@ fn handle_arrangement_input(_ctx: &mut UiContext, state: &mut TrackerState) {
             state.arrangement_insert(arr_sel, bank_sel);
             state.set_status(&format!("Inserted pattern {:02}", bank_sel), 1.5);
         }
+
+        // R / Shift+R: Play this entry more / fewer times
+        if is_key_pressed(KeyCode::R) {
+            state.arrangement_adjust_repeats(arr_sel, if shift { -1 } else { 1 });
+        }
+
+        // , / .: Transpose down / up a semitone (Shift: an octave)
+        let step = if shift { 12 } else { 1 };
+        if is_key_pressed(KeyCode::Comma) {
+            state.arrangement_adjust_transpose(arr_sel, -step);
+        }
+        if is_key_pressed(KeyCode::Period) {
+            state.arrangement_adjust_transpose(arr_sel, step);
+        }
+
+        // [ / ]: Loop start / end markers
+        if is_key_pressed(KeyCode::LeftBracket) {
+            state.arrangement_toggle_loop_start(arr_sel);
+            let status = match state.song.loop_start {
+                Some(_) => format!("Loop starts at {:02}", arr_sel),
+                None => "Loop starts at the beginning".to_string(),
+            };
+            state.set_status(&status, 1.5);
+        }
+        if is_key_pressed(KeyCode::RightBracket) {
+            state.arrangement_toggle_loop_end(arr_sel);
+            let status = match state.song.loop_end {
+                Some(_) => format!("Loop ends at {:02}", arr_sel),
+                None => "Loop ends at the last entry".to_string(),
+            };
+            state.set_status(&status, 1.5);
+        }
     }
 }
 
@@ -1068,7 +1320,7 @@ fn is_note_key_down(offset: u8) -> bool {
 
 /// Draw the instruments view with piano keyboard
 fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerState) {
-    draw_rectangle(rect.x, rect.y, rect.w, rect.h, BG_COLOR);
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().bg);
 
     // Split into left (instrument list) and right (piano + info)
     let list_width = 280.0;
@@ -1076,7 +1328,7 @@ fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
 
     // === LEFT: Instrument List ===
     draw_rectangle(list_rect.x, list_rect.y, list_rect.w, list_rect.h, Color::new(0.09, 0.09, 0.11, 1.0));
-    draw_text("Instruments (GM)", list_rect.x + 10.0, list_rect.y + 20.0, 16.0, TEXT_COLOR);
+    draw_text("Instruments (GM)", list_rect.x + 10.0, list_rect.y + 20.0, 16.0, theme().text);
 
     // Scrollable instrument list
     let presets = state.audio.get_preset_names();
@@ -1122,7 +1374,7 @@ fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
         }
 
         // Text
-        let color = if is_current { NOTE_COLOR } else { TEXT_COLOR };
+        let color = if is_current { NOTE_COLOR } else { theme().text };
         draw_text(&format!("{:03}: {}", program, name), item_rect.x + 5.0, y + 13.0, 12.0, color);
     }
 
@@ -1148,14 +1400,14 @@ fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
     let black_key_h = 60.0;
 
     // Piano header with octave
-    draw_text(&format!("Piano - Octave {}", state.octave), piano_x, piano_y - 22.0, 14.0, TEXT_COLOR);
+    draw_text(&format!("Piano - Octave {}", state.octave), piano_x, piano_y - 22.0, 14.0, theme().text);
 
     // MIDI status indicator
     if state.midi.is_connected() {
         let device_name = state.midi.device_name();
         draw_text(&format!("MIDI: {}", device_name), piano_x, piano_y - 8.0, 11.0, Color::new(0.0, 0.8, 0.6, 1.0));
     } else {
-        draw_text("MIDI: No device", piano_x, piano_y - 8.0, 11.0, TEXT_DIM);
+        draw_text("MIDI: No device", piano_x, piano_y - 8.0, 11.0, theme().text_dim);
     }
 
     // Define all white keys we need to display (semitones 0-36, ~3 octaves: C to C)
@@ -1269,7 +1521,7 @@ fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
     let ch = state.current_channel;
 
     // Show which channel we're editing
-    draw_text(&format!("Channel {} Effects", ch + 1), piano_x, effects_y, 16.0, TEXT_COLOR);
+    draw_text(&format!("Channel {} Effects", ch + 1), piano_x, effects_y, 16.0, theme().text);
 
     // Per-channel sample rate buttons
     let sr_y = effects_y + 20.0;
@@ -1298,7 +1550,7 @@ fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
         };
 
         draw_rectangle(btn_x, sr_y, sr_btn_w, sr_btn_h, bg);
-        let text_color = if is_active { WHITE } else { TEXT_COLOR };
+        let text_color = if is_active { WHITE } else { theme().text };
         draw_text(label, btn_x + 6.0, sr_y + 14.0, 11.0, text_color);
 
         if is_hovered && ctx.mouse.left_pressed {
@@ -1337,7 +1589,7 @@ fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
         };
 
         draw_rectangle(btn_x, btn_y, preset_btn_w, preset_btn_h, bg);
-        let text_color = if is_active { WHITE } else { TEXT_COLOR };
+        let text_color = if is_active { WHITE } else { theme().text };
         draw_text(reverb_type.name(), btn_x + 4.0, btn_y + 14.0, 11.0, text_color);
 
         if is_hovered && ctx.mouse.left_pressed {
@@ -1457,7 +1709,7 @@ fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
 
     draw_rectangle(reset_rect.x, reset_rect.y, reset_rect.w, reset_rect.h,
         if reset_hovered { Color::new(0.25, 0.25, 0.3, 1.0) } else { Color::new(0.18, 0.18, 0.22, 1.0) });
-    draw_text("Reset", reset_rect.x + 30.0, reset_rect.y + 14.0, 12.0, TEXT_COLOR);
+    draw_text("Reset", reset_rect.x + 30.0, reset_rect.y + 14.0, 12.0, theme().text);
 
     if reset_hovered && ctx.mouse.left_pressed {
         state.reset_channel_settings(ch);
@@ -1466,10 +1718,10 @@ fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
 
     // === EFFECT BUTTONS (insert at cursor position) ===
     let effects_btn_y = reset_y + 30.0;
-    draw_text("Insert Effect", piano_x, effects_btn_y, 14.0, TEXT_COLOR);
+    draw_text("Insert Effect", piano_x, effects_btn_y, 14.0, theme().text);
 
-    // Effect button definitions: (effect_char, label, tooltip)
-    let effect_btns: [(char, &str); 10] = [
+    // Effect button definitions: (effect_char, label) - tooltip comes from Effect::help
+    let effect_btns: [(char, &str); 11] = [
         ('0', "Arp"),
         ('1', "SlideUp"),
         ('2', "SlideDn"),
@@ -1480,6 +1732,7 @@ fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
         ('E', "Expr"),
         ('M', "Mod"),
         ('P', "Pan"),
+        ('Q', "Retrig"),
     ];
 
     let fx_btn_w = 60.0;
@@ -1504,9 +1757,15 @@ fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
         };
 
         draw_rectangle(btn_x, btn_y, fx_btn_w, fx_btn_h, bg);
-        let text_color = if is_hovered { WHITE } else { TEXT_COLOR };
+        let text_color = if is_hovered { WHITE } else { theme().text };
         draw_text(label, btn_x + 4.0, btn_y + 14.0, 11.0, text_color);
 
+        if is_hovered {
+            if let Some(help) = Effect::help(*effect_char) {
+                ctx.set_tooltip(help, ctx.mouse.x, ctx.mouse.y);
+            }
+        }
+
         if is_hovered && ctx.mouse.left_pressed {
             // Insert effect at cursor position with the current effect amount
             let effect_amount = state.song.get_channel_settings(ch).effect_amount;
@@ -1524,13 +1783,14 @@ fn draw_instruments_view(ctx: &mut UiContext, rect: Rect, state: &mut TrackerSta
     }
 
     // Help text
-    let help_y = fx_btn_start_y + 2.0 * (fx_btn_h + fx_btn_spacing) + 15.0;
+    let fx_btn_rows = effect_btns.len().div_ceil(fx_btns_per_row);
+    let help_y = fx_btn_start_y + fx_btn_rows as f32 * (fx_btn_h + fx_btn_spacing) + 15.0;
     draw_text("Click keys to preview | Keyboard: Z-/ (lower) Q-] (upper)",
-              piano_x, help_y, 12.0, TEXT_DIM);
+              piano_x, help_y, 12.0, theme().text_dim);
     draw_text("Numpad +/- = octave | Drag knobs to adjust effects",
-              piano_x, help_y + 17.0, 12.0, TEXT_DIM);
+              piano_x, help_y + 17.0, 12.0, theme().text_dim);
     draw_text("Click value to type | Use list or channel +/- for instrument",
-              piano_x, help_y + 34.0, 12.0, TEXT_DIM);
+              piano_x, help_y + 34.0, 12.0, theme().text_dim);
 }
 
 /// Draw the status bar at the bottom with context-sensitive help
@@ -1546,24 +1806,34 @@ fn draw_status_bar(rect: Rect, state: &TrackerState) {
         rect.x + 10.0
     };
 
+    // Effect under the cursor: describe it instead of the generic column help
+    let cursor_effect = if state.view == TrackerView::Pattern && state.current_column >= 2 {
+        state.current_pattern()
+            .and_then(|p| p.get(state.current_channel, state.current_row))
+            .and_then(|n| n.effect)
+            .and_then(Effect::help)
+    } else {
+        None
+    };
+
     // Right side: Combined context help + shortcuts
-    let help_text = match state.view {
+    let help_text = cursor_effect.unwrap_or(match state.view {
         TrackerView::Pattern => {
             match state.current_column {
                 0 => "Note: piano keys | ` off | Del clear | Space: Play",
                 1 => "Vol: 0-127 | Del clear | Ctrl+S: Save",
-                2 => "Fx: 0-9 A-P | Del clear | Ctrl+O: Open",
+                2 => "Fx: 0-4 A C D E F M P Q | Del clear | Ctrl+O: Open",
                 _ => "Param: 0-127 | Del clear | Ctrl+N: New",
             }
         }
         TrackerView::Arrangement => {
             "Tab: focus | Enter: edit | +: new | Del: remove | Shift+↑↓: reorder"
         }
-    };
+    });
 
     let help_dims = measure_text(help_text, None, 12, 1.0);
     let help_x = (rect.x + rect.w - help_dims.width - 10.0).max(status_end_x);
-    draw_text(help_text, help_x, rect.y + 15.0, 12.0, TEXT_DIM);
+    draw_text(help_text, help_x, rect.y + 15.0, 12.0, theme().text_dim);
 }
 
 /// Handle keyboard and mouse input
@@ -1790,9 +2060,13 @@ fn handle_input(_ctx: &mut UiContext, state: &mut TrackerState, storage: &Storag
         state.delete_selection(); // This handles both single note and selection
     }
 
+    // Live recording takes the piano keys in any column while the song plays
+    let live_recording = state.view == TrackerView::Pattern && state.is_live_recording();
+
     // Note entry (only in Pattern view, when in edit mode and in note column)
     // Skip if Ctrl/Cmd is held (for copy/paste shortcuts)
-    if state.view == TrackerView::Pattern && state.edit_mode && state.current_column == 0 && !ctrl_held {
+    let note_entry = state.view == TrackerView::Pattern && state.edit_mode && state.current_column == 0;
+    if (note_entry || live_recording) && !ctrl_held {
         // All piano keys: bottom row (Z to /) and top row (Q to ])
         // Note: Period is a piano key now, so we use Apostrophe for note-off
         let note_keys = [
@@ -1812,8 +2086,12 @@ fn handle_input(_ctx: &mut UiContext, state: &mut TrackerState, storage: &Storag
         for key in note_keys {
             if is_key_pressed(key) {
                 if let Some(pitch) = TrackerState::key_to_note(key, state.octave) {
-                    state.enter_note(pitch);
-                    state.clear_selection(); // Clear selection after filling
+                    if live_recording {
+                        state.record_note(pitch, None);
+                    } else {
+                        state.enter_note(pitch);
+                        state.clear_selection(); // Clear selection after filling
+                    }
                 }
             }
             // Stop note preview when key is released
@@ -1825,7 +2103,7 @@ fn handle_input(_ctx: &mut UiContext, state: &mut TrackerState, storage: &Storag
         }
 
         // Note off with backtick (apostrophe key) - period is now a piano key
-        if is_key_pressed(KeyCode::Apostrophe) {
+        if is_key_pressed(KeyCode::Apostrophe) && !live_recording {
             state.enter_note_off();
             state.clear_selection();
         }
@@ -1841,8 +2119,11 @@ fn handle_input(_ctx: &mut UiContext, state: &mut TrackerState, storage: &Storag
                 state.audio.set_program(state.current_channel as i32, instrument as i32);
                 state.audio.note_on(state.current_channel as i32, note as i32, velocity as i32);
 
-                // Enter note into pattern only in edit mode + pattern view + note column
-                if state.view == TrackerView::Pattern && state.edit_mode && state.current_column == 0 {
+                // Record at the playback position, or enter the note at the
+                // cursor in edit mode + pattern view + note column
+                if state.view == TrackerView::Pattern && state.is_live_recording() {
+                    state.record_note(note, Some(velocity));
+                } else if state.view == TrackerView::Pattern && state.edit_mode && state.current_column == 0 {
                     state.enter_note(note);
                     state.clear_selection();
                 }
@@ -1865,7 +2146,7 @@ fn handle_input(_ctx: &mut UiContext, state: &mut TrackerState, storage: &Storag
     // Volume entry (in Pattern view, edit mode, volume column = 1)
     // Type 3 digits for 0-127 (resets on each keypress, last 3 digits kept)
     // Skip if Ctrl/Cmd is held
-    if state.view == TrackerView::Pattern && state.edit_mode && state.current_column == 1 && !ctrl_held {
+    if state.view == TrackerView::Pattern && state.edit_mode && state.current_column == 1 && !ctrl_held && !live_recording {
         let digit_keys = [
             (KeyCode::Key0, 0), (KeyCode::Key1, 1), (KeyCode::Key2, 2),
             (KeyCode::Key3, 3), (KeyCode::Key4, 4), (KeyCode::Key5, 5),
@@ -1889,8 +2170,8 @@ fn handle_input(_ctx: &mut UiContext, state: &mut TrackerState, storage: &Storag
 
     // Effect entry (in Pattern view, edit mode, effect column = 2)
     // Skip if Ctrl/Cmd is held
-    if state.view == TrackerView::Pattern && state.edit_mode && state.current_column == 2 && !ctrl_held {
-        // Effect letters: 0-9, A-F for standard effects, + our new ones (C, E, H, M, P, R)
+    if state.view == TrackerView::Pattern && state.edit_mode && state.current_column == 2 && !ctrl_held && !live_recording {
+        // Effect letters: 0-9, A-F for standard effects, + our new ones (C, E, H, M, P, Q, R)
         let effect_keys = [
             (KeyCode::Key0, '0'), (KeyCode::Key1, '1'), (KeyCode::Key2, '2'),
             (KeyCode::Key3, '3'), (KeyCode::Key4, '4'), (KeyCode::Key5, '5'),
@@ -1898,7 +2179,8 @@ fn handle_input(_ctx: &mut UiContext, state: &mut TrackerState, storage: &Storag
             (KeyCode::Key9, '9'),
             (KeyCode::A, 'A'), (KeyCode::B, 'B'), (KeyCode::C, 'C'),
             (KeyCode::D, 'D'), (KeyCode::E, 'E'), (KeyCode::F, 'F'),
-            (KeyCode::H, 'H'), (KeyCode::M, 'M'), (KeyCode::P, 'P'), (KeyCode::R, 'R'),
+            (KeyCode::H, 'H'), (KeyCode::M, 'M'), (KeyCode::P, 'P'), (KeyCode::Q, 'Q'),
+            (KeyCode::R, 'R'),
         ];
 
         for (key, ch) in effect_keys {
@@ -1912,7 +2194,7 @@ fn handle_input(_ctx: &mut UiContext, state: &mut TrackerState, storage: &Storag
     // Effect parameter entry (in Pattern view, edit mode, fx_param column = 3)
     // Type digits for 0-255 (shift left and add, keep last 3 digits)
     // Skip if Ctrl/Cmd is held
-    if state.view == TrackerView::Pattern && state.edit_mode && state.current_column == 3 && !ctrl_held {
+    if state.view == TrackerView::Pattern && state.edit_mode && state.current_column == 3 && !ctrl_held && !live_recording {
         let digit_keys = [
             (KeyCode::Key0, 0), (KeyCode::Key1, 1), (KeyCode::Key2, 2),
             (KeyCode::Key3, 3), (KeyCode::Key4, 4), (KeyCode::Key5, 5),
@@ -0,0 +1,340 @@
+//! Level Meters and Oscilloscope
+//!
+//! rustysynth mixes its voices internally, so there is no per-voice signal to
+//! tap. The engine records what it can see instead:
+//! - Channels: the velocity, volume and expression of the last note on each
+//!   MIDI channel (`ChannelActivity`), which drive the per-channel VU meters
+//! - Master: the final output after master gain, into a ring buffer per side
+//!   (`ScopeTap`) for the oscilloscope, with peak and clip detection
+//!
+//! The tracker takes a `MeterReadings` every frame and turns it into meter
+//! ballistics (`OutputMeters`).
+
+/// Samples kept for the oscilloscope (about 12ms at 44.1kHz)
+pub const SCOPE_LEN: usize = 512;
+/// MIDI channels with activity meters
+pub const METER_CHANNELS: usize = 16;
+/// Quietest level shown on the master meters (dBFS)
+pub const METER_FLOOR_DB: f32 = -48.0;
+/// How fast a meter falls (full scale per second)
+pub const METER_FALL: f32 = 1.5;
+/// How long the peak marker holds before falling (seconds)
+pub const PEAK_HOLD: f32 = 1.0;
+/// How long the clip light stays on (seconds)
+pub const CLIP_HOLD: f32 = 2.0;
+/// A held note's channel meter settles this far below its strike level
+const SUSTAIN_LEVEL: f32 = 0.6;
+
+/// Ring buffer of recent output samples, with the peak since the last read
+#[derive(Debug, Clone)]
+pub struct ScopeTap {
+    ring: Vec<f32>,
+    write: usize,
+    peak: f32,
+    clipped: bool,
+}
+
+impl Default for ScopeTap {
+    fn default() -> Self {
+        Self { ring: vec![0.0; SCOPE_LEN], write: 0, peak: 0.0, clipped: false }
+    }
+}
+
+impl ScopeTap {
+    /// Record a block of samples
+    pub fn push(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            self.ring[self.write] = sample;
+            self.write = (self.write + 1) % SCOPE_LEN;
+            let level = sample.abs();
+            self.peak = self.peak.max(level);
+            if level >= 1.0 {
+                self.clipped = true;
+            }
+        }
+    }
+
+    /// Recent samples, oldest first
+    pub fn waveform(&self) -> Vec<f32> {
+        self.ring[self.write..].iter().chain(&self.ring[..self.write]).copied().collect()
+    }
+
+    /// Peak level and whether it clipped since the last call (then reset)
+    pub fn take_peak(&mut self) -> (f32, bool) {
+        let reading = (self.peak, self.clipped);
+        self.peak = 0.0;
+        self.clipped = false;
+        reading
+    }
+}
+
+/// What the engine has sent to each MIDI channel
+#[derive(Debug, Clone)]
+pub struct ChannelActivity {
+    velocity: [f32; METER_CHANNELS],
+    volume: [f32; METER_CHANNELS],
+    expression: [f32; METER_CHANNELS],
+    held: [bool; METER_CHANNELS],
+    struck: [bool; METER_CHANNELS],
+}
+
+impl Default for ChannelActivity {
+    fn default() -> Self {
+        Self {
+            velocity: [0.0; METER_CHANNELS],
+            // MIDI defaults: volume 100, expression 127
+            volume: [100.0 / 127.0; METER_CHANNELS],
+            expression: [1.0; METER_CHANNELS],
+            held: [false; METER_CHANNELS],
+            struck: [false; METER_CHANNELS],
+        }
+    }
+}
+
+impl ChannelActivity {
+    fn slot(channel: i32) -> Option<usize> {
+        usize::try_from(channel).ok().filter(|&c| c < METER_CHANNELS)
+    }
+
+    pub fn note_on(&mut self, channel: i32, velocity: i32) {
+        if let Some(c) = Self::slot(channel) {
+            self.velocity[c] = velocity.clamp(0, 127) as f32 / 127.0;
+            self.held[c] = true;
+            self.struck[c] = true;
+        }
+    }
+
+    pub fn note_off(&mut self, channel: i32) {
+        if let Some(c) = Self::slot(channel) {
+            self.held[c] = false;
+        }
+    }
+
+    pub fn all_notes_off(&mut self) {
+        self.held = [false; METER_CHANNELS];
+    }
+
+    pub fn set_volume(&mut self, channel: i32, volume: i32) {
+        if let Some(c) = Self::slot(channel) {
+            self.volume[c] = volume.clamp(0, 127) as f32 / 127.0;
+        }
+    }
+
+    pub fn set_expression(&mut self, channel: i32, expression: i32) {
+        if let Some(c) = Self::slot(channel) {
+            self.expression[c] = expression.clamp(0, 127) as f32 / 127.0;
+        }
+    }
+
+    /// Back to the MIDI default volume and expression
+    pub fn reset_controllers(&mut self, channel: i32) {
+        if let Some(c) = Self::slot(channel) {
+            let defaults = Self::default();
+            self.volume[c] = defaults.volume[c];
+            self.expression[c] = defaults.expression[c];
+        }
+    }
+
+    /// Level of a channel's sounding note (0 once released)
+    fn level(&self, c: usize) -> f32 {
+        if self.held[c] {
+            self.velocity[c] * self.volume[c] * self.expression[c]
+        } else {
+            0.0
+        }
+    }
+}
+
+/// One frame's worth of metering from the engine
+#[derive(Debug, Clone, Default)]
+pub struct MeterReadings {
+    /// Level of each channel's sounding note (0..1)
+    pub channels: [f32; METER_CHANNELS],
+    /// Channels that started a note since the last reading
+    pub struck: [bool; METER_CHANNELS],
+    /// Master output peak per side (left, right) since the last reading
+    pub master_peak: [f32; 2],
+    /// Whether each side hit full scale since the last reading
+    pub master_clipped: [bool; 2],
+    /// Recent master output per side, oldest first
+    pub master_wave: [Vec<f32>; 2],
+}
+
+impl MeterReadings {
+    /// Collect (and reset) the engine's taps
+    pub fn take(activity: &mut ChannelActivity, master: &mut [ScopeTap; 2]) -> Self {
+        let mut readings = Self::default();
+        for c in 0..METER_CHANNELS {
+            readings.channels[c] = activity.level(c);
+        }
+        readings.struck = std::mem::take(&mut activity.struck);
+        for (side, tap) in master.iter_mut().enumerate() {
+            let (peak, clipped) = tap.take_peak();
+            readings.master_peak[side] = peak;
+            readings.master_clipped[side] = clipped;
+            readings.master_wave[side] = tap.waveform();
+        }
+        readings
+    }
+}
+
+/// Master meter position (0..1) for a linear amplitude, on a dB scale
+pub fn db_fraction(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return 0.0;
+    }
+    let db = 20.0 * amplitude.log10();
+    ((db - METER_FLOOR_DB) / -METER_FLOOR_DB).clamp(0.0, 1.0)
+}
+
+/// Displayed level of one meter: jumps up, falls back slowly, with a held
+/// peak marker and a clip light
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LevelMeter {
+    /// Bar length (0..1)
+    pub level: f32,
+    /// Peak marker (0..1)
+    pub peak: f32,
+    peak_age: f32,
+    /// Seconds the clip light stays on
+    pub clip: f32,
+}
+
+impl LevelMeter {
+    /// Follow `target` (0..1) over `delta` seconds
+    pub fn update(&mut self, target: f32, delta: f32) {
+        self.level = if target >= self.level {
+            target
+        } else {
+            (self.level - METER_FALL * delta).max(target)
+        };
+        if self.level >= self.peak {
+            self.peak = self.level;
+            self.peak_age = 0.0;
+        } else {
+            self.peak_age += delta;
+            if self.peak_age > PEAK_HOLD {
+                self.peak = (self.peak - METER_FALL * delta).max(self.level);
+            }
+        }
+        self.clip = (self.clip - delta).max(0.0);
+    }
+
+    /// Light the clip indicator
+    pub fn trigger_clip(&mut self) {
+        self.clip = CLIP_HOLD;
+    }
+
+    pub fn is_clipping(&self) -> bool {
+        self.clip > 0.0
+    }
+}
+
+/// Meters shown in the tracker
+#[derive(Debug, Clone, Default)]
+pub struct OutputMeters {
+    pub channels: [LevelMeter; METER_CHANNELS],
+    /// Master output (left, right)
+    pub master: [LevelMeter; 2],
+    /// Latest master waveform per side, for the oscilloscope
+    pub wave: [Vec<f32>; 2],
+}
+
+impl OutputMeters {
+    /// Apply a frame of readings, `delta` seconds after the last
+    pub fn update(&mut self, readings: MeterReadings, delta: f32) {
+        for (c, meter) in self.channels.iter_mut().enumerate() {
+            let target = readings.channels[c];
+            if readings.struck[c] {
+                // A new note jumps to its full level even if one is already held
+                meter.level = 0.0;
+                meter.update(target, delta);
+            } else {
+                meter.update(target * SUSTAIN_LEVEL, delta);
+            }
+        }
+        for (side, meter) in self.master.iter_mut().enumerate() {
+            meter.update(db_fraction(readings.master_peak[side]), delta);
+            if readings.master_clipped[side] {
+                meter.trigger_clip();
+            }
+        }
+        self.wave = readings.master_wave;
+    }
+
+    /// Drop everything back to silence (playback stopped)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_tap_keeps_latest_samples_and_clips() {
+        let mut tap = ScopeTap::default();
+        let block: Vec<f32> = (0..SCOPE_LEN + 10).map(|i| i as f32 / 10_000.0).collect();
+        tap.push(&block);
+        let wave = tap.waveform();
+        assert_eq!(wave.len(), SCOPE_LEN);
+        assert_eq!(wave[0], 10.0 / 10_000.0);
+        assert_eq!(*wave.last().unwrap(), (SCOPE_LEN + 9) as f32 / 10_000.0);
+        assert_eq!(tap.take_peak(), ((SCOPE_LEN + 9) as f32 / 10_000.0, false));
+
+        tap.push(&[0.5, -1.2]);
+        assert_eq!(tap.take_peak(), (1.2, true));
+        assert_eq!(tap.take_peak(), (0.0, false));
+    }
+
+    #[test]
+    fn test_channel_meter_strikes_then_settles() {
+        let mut activity = ChannelActivity::default();
+        let mut master = [ScopeTap::default(), ScopeTap::default()];
+        let mut meters = OutputMeters::default();
+
+        activity.set_volume(2, 127);
+        activity.note_on(2, 127);
+        meters.update(MeterReadings::take(&mut activity, &mut master), 0.016);
+        assert_eq!(meters.channels[2].level, 1.0);
+        assert_eq!(meters.channels[0].level, 0.0);
+
+        // Held: falls toward the sustain level, then to zero after the note off
+        for _ in 0..60 {
+            meters.update(MeterReadings::take(&mut activity, &mut master), 0.016);
+        }
+        assert!((meters.channels[2].level - SUSTAIN_LEVEL).abs() < 1e-5);
+        activity.note_off(2);
+        for _ in 0..60 {
+            meters.update(MeterReadings::take(&mut activity, &mut master), 0.016);
+        }
+        assert_eq!(meters.channels[2].level, 0.0);
+        // Channels outside the MIDI range are ignored
+        activity.note_on(-1, 100);
+        activity.note_on(METER_CHANNELS as i32, 100);
+    }
+
+    #[test]
+    fn test_master_meter_db_scale_and_clip_light() {
+        assert_eq!(db_fraction(0.0), 0.0);
+        assert_eq!(db_fraction(1.0), 1.0);
+        assert!((db_fraction(10f32.powf(METER_FLOOR_DB / 40.0)) - 0.5).abs() < 1e-5);
+
+        let mut activity = ChannelActivity::default();
+        let mut master = [ScopeTap::default(), ScopeTap::default()];
+        let mut meters = OutputMeters::default();
+        master[0].push(&[0.25, 1.5]);
+        master[1].push(&[0.25]);
+        meters.update(MeterReadings::take(&mut activity, &mut master), 0.016);
+        assert!(meters.master[0].is_clipping() && !meters.master[1].is_clipping());
+        assert_eq!(meters.wave[1][SCOPE_LEN - 1], 0.25);
+
+        // The clip light outlasts the signal, then goes out
+        for _ in 0..((CLIP_HOLD / 0.1) as usize + 1) {
+            meters.update(MeterReadings::take(&mut activity, &mut master), 0.1);
+        }
+        assert!(!meters.master[0].is_clipping());
+        assert_eq!(meters.master[0].level, 0.0);
+    }
+}
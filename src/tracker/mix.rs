@@ -0,0 +1,310 @@
+//! Mix Groups
+//!
+//! Everything the engine plays belongs to one of four groups: music, SFX,
+//! ambient loops and dialogue. Music comes from the main synth; the other
+//! groups each render through their own synth so they can be summed with
+//! independent volumes before reverb, SPU resampling and master gain.
+//!
+//! Dialogue side-chains the music: while dialogue is sounding, an envelope
+//! follower on the dialogue signal pulls the music group down by the duck
+//! amount, and lets it recover once the line ends.
+
+use serde::{Serialize, Deserialize};
+use super::voices::VoicePriority;
+
+/// A group on the master bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MixGroup {
+    #[default]
+    Music,
+    Sfx,
+    Ambient,
+    Dialogue,
+}
+
+impl MixGroup {
+    pub const ALL: [MixGroup; 4] = [MixGroup::Music, MixGroup::Sfx, MixGroup::Ambient, MixGroup::Dialogue];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MixGroup::Music => "Music",
+            MixGroup::Sfx => "SFX",
+            MixGroup::Ambient => "Ambient",
+            MixGroup::Dialogue => "Dialogue",
+        }
+    }
+
+    /// Priority the group's notes get from the SPU voice allocator
+    pub fn voice_priority(&self) -> VoicePriority {
+        match self {
+            MixGroup::Music => VoicePriority::Music,
+            MixGroup::Sfx | MixGroup::Dialogue => VoicePriority::Sfx,
+            MixGroup::Ambient => VoicePriority::Ambient,
+        }
+    }
+
+    /// Group for notes played with only a voice priority
+    pub fn from_priority(priority: VoicePriority) -> Self {
+        match priority {
+            VoicePriority::Ambient => MixGroup::Ambient,
+            VoicePriority::Music => MixGroup::Music,
+            VoicePriority::Sfx => MixGroup::Sfx,
+        }
+    }
+}
+
+/// Group volumes and dialogue ducking (project defaults live on the level,
+/// the game's options menu can override them per session)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MixLevels {
+    #[serde(default = "default_volume")]
+    pub music: f32,
+    #[serde(default = "default_volume")]
+    pub sfx: f32,
+    #[serde(default = "default_volume")]
+    pub ambient: f32,
+    #[serde(default = "default_volume")]
+    pub dialogue: f32,
+    /// How far dialogue pulls the music down (0 = off, 1 = silent)
+    #[serde(default = "default_duck")]
+    pub duck: f32,
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+fn default_duck() -> f32 {
+    0.6
+}
+
+impl Default for MixLevels {
+    fn default() -> Self {
+        Self {
+            music: default_volume(),
+            sfx: default_volume(),
+            ambient: default_volume(),
+            dialogue: default_volume(),
+            duck: default_duck(),
+        }
+    }
+}
+
+impl MixLevels {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Volume of a group (0.0 to 1.0)
+    pub fn volume(&self, group: MixGroup) -> f32 {
+        match group {
+            MixGroup::Music => self.music,
+            MixGroup::Sfx => self.sfx,
+            MixGroup::Ambient => self.ambient,
+            MixGroup::Dialogue => self.dialogue,
+        }
+    }
+
+    pub fn set_volume(&mut self, group: MixGroup, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        match group {
+            MixGroup::Music => self.music = volume,
+            MixGroup::Sfx => self.sfx = volume,
+            MixGroup::Ambient => self.ambient = volume,
+            MixGroup::Dialogue => self.dialogue = volume,
+        }
+    }
+
+    /// Clamp everything into range (after loading)
+    pub fn sanitize(&mut self) {
+        for group in MixGroup::ALL {
+            self.set_volume(group, self.volume(group));
+        }
+        self.duck = self.duck.clamp(0.0, 1.0);
+    }
+}
+
+/// Dialogue level that counts as fully ducking
+const DUCK_THRESHOLD: f32 = 0.05;
+/// Envelope attack/release times in seconds
+const DUCK_ATTACK: f32 = 0.02;
+const DUCK_RELEASE: f32 = 0.4;
+
+/// Envelope follower on the dialogue signal
+#[derive(Debug, Clone)]
+pub struct Ducker {
+    envelope: f32,
+    attack: f32,
+    release: f32,
+}
+
+impl Ducker {
+    pub fn new(sample_rate: u32) -> Self {
+        let coeff = |seconds: f32| 1.0 - (-1.0 / (seconds * sample_rate as f32)).exp();
+        Self { envelope: 0.0, attack: coeff(DUCK_ATTACK), release: coeff(DUCK_RELEASE) }
+    }
+
+    /// Feed one dialogue sample level, returning the music gain (1.0 = no ducking)
+    pub fn step(&mut self, level: f32, duck: f32) -> f32 {
+        let rate = if level > self.envelope { self.attack } else { self.release };
+        self.envelope += (level - self.envelope) * rate;
+        1.0 - duck * (self.envelope / DUCK_THRESHOLD).min(1.0)
+    }
+
+    /// Current music gain without advancing the envelope
+    pub fn gain(&self, duck: f32) -> f32 {
+        1.0 - duck * (self.envelope / DUCK_THRESHOLD).min(1.0)
+    }
+
+    pub fn reset(&mut self) {
+        self.envelope = 0.0;
+    }
+}
+
+/// Stereo buffer for one non-music group
+#[derive(Debug, Clone, Default)]
+pub struct GroupBuffer {
+    pub left: Vec<f32>,
+    pub right: Vec<f32>,
+}
+
+impl GroupBuffer {
+    /// Grow to at least `len` samples and zero the first `len`
+    pub fn prepare(&mut self, len: usize) {
+        if self.left.len() < len {
+            self.left.resize(len, 0.0);
+            self.right.resize(len, 0.0);
+        }
+        self.left[..len].fill(0.0);
+        self.right[..len].fill(0.0);
+    }
+}
+
+/// Sums the groups between the synths and the output stage
+#[derive(Debug, Clone)]
+pub struct MixBus {
+    pub levels: MixLevels,
+    ducker: Ducker,
+    /// SFX, ambient and dialogue, in `MixGroup::ALL` order after music
+    pub buffers: [GroupBuffer; 3],
+}
+
+impl MixBus {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            levels: MixLevels::default(),
+            ducker: Ducker::new(sample_rate),
+            buffers: Default::default(),
+        }
+    }
+
+    /// Index into `buffers` for a non-music group
+    pub fn buffer_index(group: MixGroup) -> Option<usize> {
+        match group {
+            MixGroup::Music => None,
+            MixGroup::Sfx => Some(0),
+            MixGroup::Ambient => Some(1),
+            MixGroup::Dialogue => Some(2),
+        }
+    }
+
+    /// Mix the group buffers into `left`/`right`, which hold the music group
+    pub fn mix(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let len = left.len().min(right.len());
+        for buffer in self.buffers.iter_mut() {
+            if buffer.left.len() < len {
+                buffer.prepare(len);
+            }
+        }
+        let levels = self.levels;
+        let ducker = &mut self.ducker;
+        let [sfx, ambient, dialogue] = &self.buffers;
+        for (i, (l, r)) in left.iter_mut().zip(right.iter_mut()).enumerate().take(len) {
+            let level = dialogue.left[i].abs().max(dialogue.right[i].abs());
+            let music = levels.music * ducker.step(level, levels.duck);
+            *l = *l * music
+                + sfx.left[i] * levels.sfx
+                + ambient.left[i] * levels.ambient
+                + dialogue.left[i] * levels.dialogue;
+            *r = *r * music
+                + sfx.right[i] * levels.sfx
+                + ambient.right[i] * levels.ambient
+                + dialogue.right[i] * levels.dialogue;
+        }
+    }
+
+    /// Music gain after ducking right now (for meters)
+    pub fn music_gain(&self) -> f32 {
+        self.levels.music * self.ducker.gain(self.levels.duck)
+    }
+
+    pub fn reset(&mut self) {
+        self.ducker.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bus_with_dialogue(len: usize, amplitude: f32) -> MixBus {
+        let mut bus = MixBus::new(44100);
+        for buffer in bus.buffers.iter_mut() {
+            buffer.prepare(len);
+        }
+        bus.buffers[2].left.fill(amplitude);
+        bus.buffers[2].right.fill(amplitude);
+        bus
+    }
+
+    #[test]
+    fn test_group_volumes() {
+        let mut bus = MixBus::new(44100);
+        bus.levels.set_volume(MixGroup::Music, 0.5);
+        bus.levels.set_volume(MixGroup::Sfx, 2.0);
+        assert_eq!(bus.levels.sfx, 1.0);
+        for buffer in bus.buffers.iter_mut() {
+            buffer.prepare(4);
+        }
+        bus.buffers[0].left.fill(0.25);
+        let mut left = [1.0; 4];
+        let mut right = [1.0; 4];
+        bus.mix(&mut left, &mut right);
+        assert!((left[0] - 0.75).abs() < 1e-6);
+        assert!((right[0] - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dialogue_ducks_music_and_recovers() {
+        let len = 44100 / 4;
+        let mut bus = bus_with_dialogue(len, 0.5);
+        bus.levels.dialogue = 0.0;
+        let mut left = vec![1.0; len];
+        let mut right = vec![1.0; len];
+        bus.mix(&mut left, &mut right);
+        let ducked = 1.0 - bus.levels.duck;
+        assert!((left[len - 1] - ducked).abs() < 1e-3, "music ducked to {}", left[len - 1]);
+
+        // Dialogue stops: music comes back over the release time
+        let len = len * 16;
+        for buffer in bus.buffers.iter_mut() {
+            buffer.prepare(len);
+        }
+        let mut left = vec![1.0; len];
+        let mut right = vec![1.0; len];
+        bus.mix(&mut left, &mut right);
+        assert!(left[0] < 0.5);
+        assert!(left[len - 1] > 0.99);
+    }
+
+    #[test]
+    fn test_no_duck_when_disabled() {
+        let mut bus = bus_with_dialogue(1000, 0.5);
+        bus.levels.duck = 0.0;
+        bus.levels.dialogue = 0.0;
+        let mut left = vec![1.0; 1000];
+        let mut right = vec![1.0; 1000];
+        bus.mix(&mut left, &mut right);
+        assert!(left.iter().all(|&s| (s - 1.0).abs() < 1e-6));
+    }
+}
@@ -12,22 +12,33 @@
 mod state;
 mod audio;
 mod pattern;
+mod effects;
 mod layout;
 mod psx_reverb;
+mod voices;
+mod mix;
+mod meters;
 mod io;
+mod export;
 pub mod actions;
 mod song_browser;
+mod cue;
 
 // Re-export public API
 // Some of these aren't used externally yet but are part of the intended public API
 pub use state::TrackerState;
+pub use cue::{CueChange, CueSync, MusicCue};
 #[allow(unused_imports)]
-pub use audio::{AudioEngine, OutputSampleRate};
+pub use audio::{AudioEngine, OutputSampleRate, PcmPlacement, SAMPLE_RATE};
 #[allow(unused_imports)]
 pub use pattern::*;
 pub use layout::{draw_tracker, draw_song_browser};
 #[allow(unused_imports)]
 pub use psx_reverb::{PsxReverb, ReverbType};
+#[allow(unused_imports)]
+pub use voices::{VoiceAllocator, VoicePriority, SPU_VOICES};
+#[allow(unused_imports)]
+pub use mix::{MixBus, MixGroup, MixLevels};
 // WASM async loading functions for song browser
 #[allow(unused_imports)]
 pub use song_browser::{load_song_list, load_song_async};
@@ -36,4 +47,9 @@ pub use song_browser::{SongCategory, SongInfo, SAMPLES_SONGS_DIR, USER_SONGS_DIR
 #[cfg(not(target_arch = "wasm32"))]
 pub use song_browser::discover_songs_from_dir;
 // IO functions for cloud loading in main.rs
-pub use io::load_song_from_str;
+pub use io::{load_song_from_str, load_song_with_storage};
+// File IO for crash autosaves
+#[cfg(not(target_arch = "wasm32"))]
+pub use io::{load_song, save_song};
+#[allow(unused_imports)]
+pub use export::{SongExport, MUSIC_EXPORTS_DIR};
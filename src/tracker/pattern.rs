@@ -256,6 +256,37 @@ impl Default for Pattern {
     }
 }
 
+/// Playback settings of one arrangement entry
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OrderSettings {
+    /// Times the pattern plays before moving on (1-16)
+    pub repeats: u8,
+    /// Semitones added to every note (-24 to +24)
+    pub transpose: i8,
+}
+
+impl Default for OrderSettings {
+    fn default() -> Self {
+        Self { repeats: 1, transpose: 0 }
+    }
+}
+
+impl OrderSettings {
+    /// Transpose a note's pitch (note-offs pass through, pitches stay in MIDI range)
+    pub fn transpose_pitch(&self, pitch: u8) -> u8 {
+        if pitch == 0xFF {
+            return pitch;
+        }
+        (pitch as i16 + self.transpose as i16).clamp(0, 127) as u8
+    }
+}
+
+/// Most repeats of a single arrangement entry
+pub const MAX_ORDER_REPEATS: u8 = 16;
+/// Furthest per-entry transpose, in semitones
+pub const MAX_ORDER_TRANSPOSE: i8 = 24;
+
 /// A song is a sequence of pattern indices (arrangement)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -270,6 +301,13 @@ pub struct Song {
     pub patterns: Vec<Pattern>,
     /// The arrangement: sequence of pattern indices
     pub arrangement: Vec<usize>,
+    /// Repeats and transpose per arrangement entry (missing entries play once, untransposed)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub orders: Vec<OrderSettings>,
+    /// Arrangement entry playback loops back to (None = first)
+    pub loop_start: Option<usize>,
+    /// Last arrangement entry before looping (None = last)
+    pub loop_end: Option<usize>,
     /// Instrument names (for display)
     pub instrument_names: Vec<String>,
     /// Per-channel instrument (GM program number 0-127)
@@ -295,6 +333,9 @@ impl Song {
             rows_per_beat: 4,
             patterns: vec![Pattern::default()],
             arrangement: vec![0],
+            orders: Vec::new(),
+            loop_start: None,
+            loop_end: None,
             instrument_names: Vec::new(),
             channel_instruments: vec![0; DEFAULT_CHANNELS], // Piano for all channels
             channel_settings: vec![ChannelSettings::default(); DEFAULT_CHANNELS],
@@ -382,6 +423,86 @@ impl Song {
     pub fn tick_duration(&self) -> f64 {
         60.0 / (self.bpm as f64 * self.rows_per_beat as f64)
     }
+
+    /// Rows played from the start up to the loop end (counting repeats)
+    pub fn total_rows(&self) -> usize {
+        let (_, loop_end) = self.loop_bounds();
+        self.arrangement.iter()
+            .take(loop_end + 1)
+            .enumerate()
+            .filter_map(|(i, &idx)| Some(self.patterns.get(idx)?.length * self.order(i).repeats.max(1) as usize))
+            .sum()
+    }
+
+    /// Settings of an arrangement entry
+    pub fn order(&self, position: usize) -> OrderSettings {
+        self.orders.get(position).copied().unwrap_or_default()
+    }
+
+    /// Settings of an arrangement entry, for editing
+    pub fn order_mut(&mut self, position: usize) -> Option<&mut OrderSettings> {
+        if position >= self.arrangement.len() {
+            return None;
+        }
+        self.sync_orders();
+        self.orders.get_mut(position)
+    }
+
+    /// Give every arrangement entry its settings (older songs have none)
+    pub fn sync_orders(&mut self) {
+        self.orders.resize(self.arrangement.len(), OrderSettings::default());
+    }
+
+    /// Insert an arrangement entry, keeping the loop markers on the same entries
+    pub fn insert_order(&mut self, position: usize, pattern_idx: usize) {
+        let position = position.min(self.arrangement.len());
+        self.sync_orders();
+        self.arrangement.insert(position, pattern_idx);
+        self.orders.insert(position, OrderSettings::default());
+        for marker in [&mut self.loop_start, &mut self.loop_end].into_iter().flatten() {
+            if *marker >= position {
+                *marker += 1;
+            }
+        }
+    }
+
+    /// Remove an arrangement entry; a loop marker on it moves to a neighbor
+    pub fn remove_order(&mut self, position: usize) {
+        if position >= self.arrangement.len() {
+            return;
+        }
+        self.sync_orders();
+        self.arrangement.remove(position);
+        self.orders.remove(position);
+        if let Some(start) = &mut self.loop_start {
+            if *start > position {
+                *start -= 1;
+            }
+        }
+        if let Some(end) = &mut self.loop_end {
+            if *end >= position {
+                *end = end.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Loop range as (start, end) arrangement entries, clamped to the arrangement
+    pub fn loop_bounds(&self) -> (usize, usize) {
+        let last = self.arrangement.len().saturating_sub(1);
+        let end = self.loop_end.unwrap_or(last).min(last);
+        let start = self.loop_start.unwrap_or(0).min(end);
+        (start, end)
+    }
+
+    /// Arrangement entry that plays after `position`, following the loop points
+    pub fn next_order(&self, position: usize) -> usize {
+        let (start, end) = self.loop_bounds();
+        if position >= end || position + 1 >= self.arrangement.len() {
+            start
+        } else {
+            position + 1
+        }
+    }
 }
 
 impl Default for Song {
@@ -420,6 +541,8 @@ pub enum Effect {
     SetExpression(u8),
     /// Modulation (Mxx) - mod wheel 00-7F
     SetModulation(u8),
+    /// Retrigger (Qxx) - re-strike the held note every xx ticks
+    Retrigger(u8),
 }
 
 impl Effect {
@@ -439,6 +562,7 @@ impl Effect {
             'F' => Effect::SetSpeed(param),
             'M' => Effect::SetModulation(param),
             'P' => Effect::SetPan(param),
+            'Q' => Effect::Retrigger(param),
             _ => Effect::None,
         }
     }
@@ -459,6 +583,7 @@ impl Effect {
             Effect::SetSpeed(_) => Some('F'),
             Effect::SetModulation(_) => Some('M'),
             Effect::SetPan(_) => Some('P'),
+            Effect::Retrigger(_) => Some('Q'),
         }
     }
 
@@ -478,6 +603,88 @@ impl Effect {
             Effect::SetSpeed(s) => *s,
             Effect::SetModulation(v) => *v,
             Effect::SetPan(p) => *p,
+            Effect::Retrigger(t) => *t,
         }
     }
+
+    /// One-line description of an effect command and its parameter, for the UI
+    pub fn help(c: char) -> Option<&'static str> {
+        Some(match c.to_ascii_uppercase() {
+            '0' => "0xy Arpeggio: cycle note, +x, +y semitones each tick (param = x*16+y)",
+            '1' => "1xx Slide up: raise pitch xx/16 semitone per tick",
+            '2' => "2xx Slide down: lower pitch xx/16 semitone per tick",
+            '3' => "3xx Portamento: glide to the row's note at xx/16 semitone per tick",
+            '4' => "4xy Vibrato: speed x, depth y (param = x*16+y)",
+            'A' => "Axy Volume slide: +x / -y channel volume per tick (param = x*16+y)",
+            'C' => "Cxx Set channel volume (0-127)",
+            'D' => "Dxx Pattern break",
+            'E' => "Exx Set expression (0-127)",
+            'F' => "Fxx Set speed (BPM)",
+            'M' => "Mxx Set modulation wheel (0-127)",
+            'P' => "Pxx Set pan: 0 left, 64 center, 127 right",
+            'Q' => "Qxx Retrigger: re-strike the note every xx ticks (6 ticks per row)",
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrangement_loops_between_markers() {
+        let mut song = Song::new();
+        song.arrangement = vec![0, 0, 0, 0];
+        assert_eq!(song.loop_bounds(), (0, 3));
+        assert_eq!(song.next_order(2), 3);
+        assert_eq!(song.next_order(3), 0);
+
+        song.loop_start = Some(1);
+        song.loop_end = Some(2);
+        assert_eq!(song.next_order(0), 1);
+        assert_eq!(song.next_order(2), 1);
+
+        // Markers past the end clamp to the arrangement
+        song.loop_start = Some(9);
+        song.loop_end = Some(9);
+        assert_eq!(song.loop_bounds(), (3, 3));
+    }
+
+    #[test]
+    fn order_settings_default_for_old_songs() {
+        let mut song = Song::new();
+        song.arrangement = vec![0, 0];
+        assert_eq!(song.order(1), OrderSettings::default());
+        song.order_mut(1).unwrap().repeats = 3;
+        assert_eq!(song.orders.len(), 2);
+        assert_eq!(song.total_rows(), song.patterns[0].length * 4);
+        assert!(song.order_mut(2).is_none());
+    }
+
+    #[test]
+    fn transpose_keeps_note_offs() {
+        let order = OrderSettings { repeats: 1, transpose: -12 };
+        assert_eq!(order.transpose_pitch(60), 48);
+        assert_eq!(order.transpose_pitch(5), 0);
+        assert_eq!(order.transpose_pitch(0xFF), 0xFF);
+    }
+
+    #[test]
+    fn loop_markers_follow_their_entries() {
+        let mut song = Song::new();
+        song.arrangement = vec![0, 0, 0];
+        song.loop_start = Some(1);
+        song.loop_end = Some(2);
+
+        song.insert_order(0, 0);
+        assert_eq!((song.loop_start, song.loop_end), (Some(2), Some(3)));
+        assert_eq!(song.orders.len(), 4);
+
+        song.remove_order(3);
+        assert_eq!((song.loop_start, song.loop_end), (Some(2), Some(2)));
+        song.remove_order(0);
+        assert_eq!((song.loop_start, song.loop_end), (Some(1), Some(1)));
+        assert_eq!(song.arrangement.len(), song.orders.len());
+    }
 }
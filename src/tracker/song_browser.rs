@@ -3,13 +3,18 @@
 //! Shows two sections:
 //! - SAMPLES: Read-only bundled songs from assets/samples/songs/
 //! - MY SONGS: User-created songs from assets/userdata/songs/
+//!
+//! The filter bar above the list narrows both sections by name, tag or smart
+//! collection.
 
 use std::path::PathBuf;
 use crate::ui::{
-    Rect, UiContext, draw_icon_centered,
-    BG_COLOR, HEADER_COLOR, TEXT_COLOR, TEXT_DIM, ACCENT_COLOR,
+    Rect, UiContext, draw_icon_centered, theme,
 };
 use crate::storage::{PendingLoad, PendingList};
+use crate::tags::{TagFilter, TagKind, draw_tag_filter, draw_item_tags, TAG_FILTER_HEIGHT, TAG_ROW_HEIGHT};
+use crate::browser_list::{draw_browser_list, ListItem, ListSection};
+use crate::project::{draw_usages_panel, Dependency, UsagesPanel};
 use macroquad::prelude::*;
 use super::pattern::Song;
 
@@ -44,6 +49,17 @@ pub struct SongInfo {
     pub category: SongCategory,
 }
 
+impl SongInfo {
+    /// Namespaced key for tag lookups, e.g. "sample:theme" or "user:theme"
+    pub fn library_key(&self) -> String {
+        let prefix = match self.category {
+            SongCategory::Sample => "sample:",
+            SongCategory::User => "user:",
+        };
+        format!("{}{}", prefix, self.name)
+    }
+}
+
 /// Action returned from the browser
 #[derive(Debug, Clone, PartialEq)]
 pub enum SongBrowserAction {
@@ -61,6 +77,8 @@ pub enum SongBrowserAction {
     TogglePreview,
     /// Delete selected user song
     DeleteSong,
+    /// List the levels that play the selected song
+    FindUsages,
     /// Refresh the song list
     Refresh,
 }
@@ -97,6 +115,10 @@ pub struct SongBrowser {
     pub pending_user_list: Option<PendingList>,
     /// Flag to trigger user songs refresh from main loop
     pub pending_refresh: bool,
+    /// Tag filter and tag editing state
+    pub tag_filter: TagFilter,
+    /// Levels using a song (find usages / delete warning), shown while it is selected
+    pub usages: Option<UsagesPanel>,
 }
 
 impl Default for SongBrowser {
@@ -123,6 +145,8 @@ impl SongBrowser {
             pending_preview_load: None,
             pending_user_list: None,
             pending_refresh: false,
+            tag_filter: TagFilter::default(),
+            usages: None,
         }
     }
 
@@ -210,13 +234,13 @@ impl SongBrowser {
         let dialog_y = (screen_rect.h - dialog_h) / 2.0;
         let dialog_rect = Rect::new(dialog_x, dialog_y, dialog_w, dialog_h);
 
-        draw_rectangle(dialog_rect.x, dialog_rect.y, dialog_rect.w, dialog_rect.h, BG_COLOR);
-        draw_rectangle_lines(dialog_rect.x, dialog_rect.y, dialog_rect.w, dialog_rect.h, 2.0, HEADER_COLOR);
+        draw_rectangle(dialog_rect.x, dialog_rect.y, dialog_rect.w, dialog_rect.h, theme().bg);
+        draw_rectangle_lines(dialog_rect.x, dialog_rect.y, dialog_rect.w, dialog_rect.h, 2.0, theme().header);
 
         // Header
         let header_h = 40.0;
-        draw_rectangle(dialog_rect.x, dialog_rect.y, dialog_rect.w, header_h, HEADER_COLOR);
-        draw_text("Song Browser", dialog_rect.x + 12.0, dialog_rect.y + 26.0, 20.0, TEXT_COLOR);
+        draw_rectangle(dialog_rect.x, dialog_rect.y, dialog_rect.w, header_h, theme().header);
+        draw_text("Song Browser", dialog_rect.x + 12.0, dialog_rect.y + 26.0, 20.0, theme().text);
 
         // Close button
         let close_btn = Rect::new(dialog_rect.x + dialog_rect.w - 36.0, dialog_rect.y + 4.0, 32.0, 32.0);
@@ -230,12 +254,52 @@ impl SongBrowser {
 
         // Left side: two-section song list
         let list_w = dialog_w * 0.45;
-        let list_rect = Rect::new(dialog_rect.x + 8.0, content_y, list_w, content_h);
+        let filter_rect = Rect::new(dialog_rect.x + 8.0, content_y, list_w, TAG_FILTER_HEIGHT);
+        draw_tag_filter(ctx, filter_rect, &mut self.tag_filter);
+        let list_top = filter_rect.bottom() + 4.0;
+        let list_rect = Rect::new(dialog_rect.x + 8.0, list_top, list_w, content_y + content_h - list_top);
 
         // Draw two-section list and handle clicks
-        let has_cloud = storage.has_cloud();
-        let list_action = draw_two_section_song_list(ctx, list_rect, self, has_cloud);
-        if let Some((category, idx)) = list_action.clicked {
+        let sections = [
+            ListSection {
+                title: "SAMPLE SONGS",
+                items: self.samples.iter().map(|s| ListItem { name: &s.name, key: s.library_key() }).collect(),
+                collapsed: self.samples_collapsed,
+                loading: false,
+                empty_text: Some("(no sample songs)"),
+                cloud: false,
+            },
+            ListSection {
+                title: "MY SONGS",
+                items: self.user_songs.iter().map(|s| ListItem { name: &s.name, key: s.library_key() }).collect(),
+                collapsed: self.user_collapsed,
+                loading: self.is_loading_user_songs(),
+                empty_text: Some("(no saved songs)"),
+                cloud: storage.has_cloud(),
+            },
+        ];
+        let selected = match (self.selected_category, self.selected_index) {
+            (Some(SongCategory::Sample), Some(i)) => Some((0, i)),
+            (Some(SongCategory::User), Some(i)) => Some((1, i)),
+            _ => None,
+        };
+        let list_action = draw_browser_list(
+            ctx,
+            list_rect,
+            &mut self.scroll_offset,
+            &sections,
+            &self.tag_filter,
+            TagKind::Song,
+            selected,
+            None,
+        );
+        match list_action.toggled {
+            Some(0) => self.samples_collapsed = !self.samples_collapsed,
+            Some(_) => self.user_collapsed = !self.user_collapsed,
+            None => {}
+        }
+        if let Some((section, idx)) = list_action.clicked {
+            let category = if section == 0 { SongCategory::Sample } else { SongCategory::User };
             if self.selected_category != Some(category) || self.selected_index != Some(idx) {
                 self.selected_category = Some(category);
                 self.selected_index = Some(idx);
@@ -249,16 +313,22 @@ impl SongBrowser {
         // Right side: song info/preview
         let info_x = dialog_rect.x + list_w + 24.0;
         let info_w = dialog_w - list_w - 40.0;
-        let info_rect = Rect::new(info_x, content_y, info_w, content_h);
+        let info_rect = Rect::new(info_x, content_y, info_w, content_h - TAG_ROW_HEIGHT - 4.0);
+
+        // Tags of the selected song, under the info panel
+        if let Some(key) = self.selected_song().map(|info| info.library_key()) {
+            let tags_rect = Rect::new(info_x, info_rect.bottom() + 4.0, info_w, TAG_ROW_HEIGHT);
+            draw_item_tags(ctx, tags_rect, &mut self.tag_filter, TagKind::Song, &key);
+        }
 
         draw_rectangle(info_rect.x, info_rect.y, info_rect.w, info_rect.h, Color::new(0.1, 0.1, 0.12, 1.0));
-        draw_rectangle_lines(info_rect.x, info_rect.y, info_rect.w, info_rect.h, 1.0, HEADER_COLOR);
+        draw_rectangle_lines(info_rect.x, info_rect.y, info_rect.w, info_rect.h, 1.0, theme().header);
 
         if let Some(song) = &self.preview_song {
             let mut y = info_rect.y + 20.0;
             let line_h = 22.0;
 
-            draw_text(&format!("Name: {}", song.name), info_rect.x + 12.0, y, 16.0, TEXT_COLOR);
+            draw_text(&format!("Name: {}", song.name), info_rect.x + 12.0, y, 16.0, theme().text);
             y += line_h;
 
             // Show if sample (read-only)
@@ -267,17 +337,17 @@ impl SongBrowser {
                 y += line_h;
             }
 
-            draw_text(&format!("BPM: {}", song.bpm), info_rect.x + 12.0, y, 16.0, TEXT_DIM);
+            draw_text(&format!("BPM: {}", song.bpm), info_rect.x + 12.0, y, 16.0, theme().text_dim);
             y += line_h;
 
-            draw_text(&format!("Patterns: {}", song.patterns.len()), info_rect.x + 12.0, y, 16.0, TEXT_DIM);
+            draw_text(&format!("Patterns: {}", song.patterns.len()), info_rect.x + 12.0, y, 16.0, theme().text_dim);
             y += line_h;
 
-            draw_text(&format!("Arrangement: {} entries", song.arrangement.len()), info_rect.x + 12.0, y, 16.0, TEXT_DIM);
+            draw_text(&format!("Arrangement: {} entries", song.arrangement.len()), info_rect.x + 12.0, y, 16.0, theme().text_dim);
             y += line_h;
 
             let channels = song.patterns.first().map(|p| p.num_channels()).unwrap_or(0);
-            draw_text(&format!("Channels: {}", channels), info_rect.x + 12.0, y, 16.0, TEXT_DIM);
+            draw_text(&format!("Channels: {}", channels), info_rect.x + 12.0, y, 16.0, theme().text_dim);
             y += line_h + 8.0;
 
             // Play/Stop button for preview
@@ -287,17 +357,27 @@ impl SongBrowser {
             let play_color = if self.preview_playing {
                 Color::from_rgba(180, 60, 60, 255)
             } else {
-                ACCENT_COLOR
+                theme().accent
             };
             if draw_text_button(ctx, play_btn, play_text, play_color) {
                 action = SongBrowserAction::TogglePreview;
             }
+            y += 40.0;
+
+            // Usages of the selected song
+            let selected = self.selected_song().map(|info| Dependency::Song(info.name.clone()));
+            if let Some(usages) = self.usages.as_ref().filter(|u| Some(&u.dependency) == selected.as_ref()) {
+                let usages_h = (38.0 + usages.users.len() as f32 * 16.0).min(info_rect.bottom() - y - 8.0);
+                if usages_h > 20.0 {
+                    draw_usages_panel(Rect::new(info_rect.x + 12.0, y, info_rect.w - 24.0, usages_h), usages);
+                }
+            }
         } else if self.samples.is_empty() && self.user_songs.is_empty() {
-            draw_text("No songs found", info_rect.x + 12.0, info_rect.y + 30.0, 16.0, TEXT_DIM);
-            draw_text("Click 'New' to create one", info_rect.x + 12.0, info_rect.y + 52.0, 14.0, TEXT_DIM);
+            draw_text("No songs found", info_rect.x + 12.0, info_rect.y + 30.0, 16.0, theme().text_dim);
+            draw_text("Click 'New' to create one", info_rect.x + 12.0, info_rect.y + 52.0, 14.0, theme().text_dim);
         } else {
-            draw_text("Select a song", info_rect.x + 12.0, info_rect.y + 30.0, 16.0, TEXT_DIM);
-            draw_text("to preview", info_rect.x + 12.0, info_rect.y + 52.0, 14.0, TEXT_DIM);
+            draw_text("Select a song", info_rect.x + 12.0, info_rect.y + 30.0, 16.0, theme().text_dim);
+            draw_text("to preview", info_rect.x + 12.0, info_rect.y + 52.0, 14.0, theme().text_dim);
         }
 
         // Footer buttons
@@ -325,6 +405,12 @@ impl SongBrowser {
             action = SongBrowserAction::Refresh;
         }
 
+        // Usages button
+        let usages_btn = Rect::new(dialog_rect.x + 12.0 + (btn_w + btn_spacing) * 3.0, footer_y, btn_w, btn_h);
+        if draw_text_button_enabled(ctx, usages_btn, "Usages", BTN_BG, self.selected_song().is_some()) {
+            action = SongBrowserAction::FindUsages;
+        }
+
         // Cancel button (right side)
         let cancel_btn = Rect::new(dialog_rect.x + dialog_rect.w - btn_w - 12.0, footer_y, btn_w, btn_h);
         if draw_text_button(ctx, cancel_btn, "Cancel", BTN_BG) {
@@ -334,23 +420,26 @@ impl SongBrowser {
         // Open button
         let open_btn = Rect::new(cancel_btn.x - btn_w - btn_spacing, footer_y, btn_w, btn_h);
         let open_enabled = self.selected_index.is_some();
-        if draw_text_button_enabled(ctx, open_btn, "Open", ACCENT_COLOR, open_enabled) {
+        if draw_text_button_enabled(ctx, open_btn, "Open", theme().accent, open_enabled) {
             action = SongBrowserAction::OpenSong;
         }
 
+        // Escape and Enter belong to a tag field while one is being typed in
+        let typing = self.tag_filter.is_typing();
+
         // Handle escape key
-        if is_key_pressed(KeyCode::Escape) {
+        if is_key_pressed(KeyCode::Escape) && !typing {
             action = SongBrowserAction::Cancel;
         }
 
         // Handle enter key
-        if is_key_pressed(KeyCode::Enter) && self.selected_index.is_some() {
+        if is_key_pressed(KeyCode::Enter) && self.selected_index.is_some() && !typing {
             action = SongBrowserAction::OpenSong;
         }
 
         // Close on certain actions
         match action {
-            SongBrowserAction::OpenSong | SongBrowserAction::NewSong | SongBrowserAction::Cancel | SongBrowserAction::DeleteSong => {
+            SongBrowserAction::OpenSong | SongBrowserAction::NewSong | SongBrowserAction::Cancel => {
                 self.open = false;
             }
             _ => {}
@@ -360,210 +449,6 @@ impl SongBrowser {
     }
 }
 
-/// Result from drawing the two-section list
-struct SongListResult {
-    clicked: Option<(SongCategory, usize)>,
-    double_clicked: bool,
-}
-
-/// Draw the two-section song list (SAMPLES + MY SONGS)
-fn draw_two_section_song_list(
-    ctx: &mut UiContext,
-    rect: Rect,
-    browser: &mut SongBrowser,
-    has_cloud: bool,
-) -> SongListResult {
-    let mut result = SongListResult {
-        clicked: None,
-        double_clicked: false,
-    };
-
-    let item_h = 26.0;
-    let section_h = 28.0;
-
-    let section_bg = Color::from_rgba(40, 40, 50, 255);
-    let item_bg = Color::from_rgba(30, 30, 38, 255);
-    let item_hover = Color::from_rgba(50, 50, 60, 255);
-    let item_selected = Color::from_rgba(60, 80, 120, 255);
-    let text_color = Color::from_rgba(200, 200, 200, 255);
-    let text_dim = Color::from_rgba(140, 140, 140, 255);
-
-    // Draw list background
-    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::from_rgba(25, 25, 30, 255));
-
-    // Calculate total content height for scroll
-    let samples_content_h = if browser.samples_collapsed { 0.0 } else { browser.samples.len() as f32 * item_h };
-    let user_content_h = if browser.user_collapsed { 0.0 } else { browser.user_songs.len() as f32 * item_h };
-    let total_h = section_h * 2.0 + samples_content_h + user_content_h;
-
-    // Handle scroll within list bounds
-    if ctx.mouse.inside(&rect) && ctx.mouse.scroll != 0.0 {
-        browser.scroll_offset = (browser.scroll_offset - ctx.mouse.scroll * 30.0)
-            .clamp(0.0, (total_h - rect.h).max(0.0));
-    }
-
-    let mut y = rect.y - browser.scroll_offset;
-
-    // SAMPLES section header
-    let samples_header_rect = Rect::new(rect.x, y, rect.w, section_h);
-    if y + section_h > rect.y && y < rect.bottom() {
-        let draw_y = y.max(rect.y);
-        let draw_h = section_h.min(rect.bottom() - draw_y);
-        draw_rectangle(rect.x, draw_y, rect.w, draw_h, section_bg);
-
-        if y >= rect.y {
-            let arrow = if browser.samples_collapsed { ">" } else { "v" };
-            draw_text(
-                &format!("{} SAMPLE SONGS ({})", arrow, browser.samples.len()),
-                rect.x + 8.0,
-                y + 18.0,
-                14.0,
-                text_color,
-            );
-        }
-
-        // Toggle collapse on click
-        if ctx.mouse.inside(&samples_header_rect) && ctx.mouse.left_pressed && samples_header_rect.y >= rect.y {
-            browser.samples_collapsed = !browser.samples_collapsed;
-        }
-    }
-    y += section_h;
-
-    // SAMPLES items
-    if !browser.samples_collapsed {
-        if browser.samples.is_empty() {
-            if y + item_h > rect.y && y < rect.bottom() {
-                draw_text("  (no sample songs)", rect.x + 8.0, y + 17.0, 12.0, text_dim);
-            }
-            y += item_h;
-        } else {
-            for (i, song) in browser.samples.iter().enumerate() {
-                let item_rect = Rect::new(rect.x, y, rect.w, item_h);
-
-                if y + item_h > rect.y && y < rect.bottom() {
-                    let is_selected = browser.selected_category == Some(SongCategory::Sample)
-                        && browser.selected_index == Some(i);
-                    let is_hovered = ctx.mouse.inside(&item_rect) && item_rect.y >= rect.y;
-
-                    let bg = if is_selected {
-                        item_selected
-                    } else if is_hovered {
-                        item_hover
-                    } else {
-                        item_bg
-                    };
-
-                    // Clip to list bounds
-                    let draw_y = item_rect.y.max(rect.y);
-                    let draw_h = item_h.min(rect.bottom() - draw_y);
-                    if draw_h > 0.0 {
-                        draw_rectangle(item_rect.x + 2.0, draw_y, item_rect.w - 4.0, draw_h, bg);
-
-                        if y >= rect.y {
-                            draw_text(&song.name, rect.x + 20.0, y + 17.0, 13.0, text_color);
-                        }
-                    }
-
-                    // Handle click
-                    if is_hovered && ctx.mouse.left_pressed && item_rect.y >= rect.y {
-                        result.clicked = Some((SongCategory::Sample, i));
-                    }
-                    if is_hovered && ctx.mouse.double_clicked && item_rect.y >= rect.y {
-                        result.double_clicked = true;
-                    }
-                }
-                y += item_h;
-            }
-        }
-    }
-
-    // MY SONGS section header
-    let user_header_rect = Rect::new(rect.x, y, rect.w, section_h);
-    if y + section_h > rect.y && y < rect.bottom() {
-        let draw_y = y.max(rect.y);
-        let draw_h = section_h.min(rect.bottom() - draw_y);
-        draw_rectangle(rect.x, draw_y, rect.w, draw_h, section_bg);
-
-        if y >= rect.y {
-            let arrow = if browser.user_collapsed { ">" } else { "v" };
-            let cloud_indicator = if has_cloud { " [cloud]" } else { "" };
-            draw_text(
-                &format!("{} MY SONGS ({}){}", arrow, browser.user_songs.len(), cloud_indicator),
-                rect.x + 8.0,
-                y + 18.0,
-                14.0,
-                text_color,
-            );
-        }
-
-        // Toggle collapse on click
-        if ctx.mouse.inside(&user_header_rect) && ctx.mouse.left_pressed && user_header_rect.y >= rect.y {
-            browser.user_collapsed = !browser.user_collapsed;
-        }
-    }
-    y += section_h;
-
-    // MY SONGS items
-    if !browser.user_collapsed {
-        if browser.is_loading_user_songs() {
-            // Show loading indicator
-            if y + item_h > rect.y && y < rect.bottom() {
-                let time = get_time() as f32;
-                let spinner_chars = ['|', '/', '-', '\\'];
-                let spinner_idx = (time * 8.0) as usize % spinner_chars.len();
-                let loading_text = format!("  {} Loading...", spinner_chars[spinner_idx]);
-                draw_text(&loading_text, rect.x + 8.0, y + 17.0, 12.0, text_dim);
-            }
-            // y += item_h; // Not needed since this is the last section
-        } else if browser.user_songs.is_empty() {
-            if y + item_h > rect.y && y < rect.bottom() {
-                draw_text("  (no saved songs)", rect.x + 8.0, y + 17.0, 12.0, text_dim);
-            }
-            // y += item_h; // Not needed since this is the last section
-        } else {
-            for (i, song) in browser.user_songs.iter().enumerate() {
-                let item_rect = Rect::new(rect.x, y, rect.w, item_h);
-
-                if y + item_h > rect.y && y < rect.bottom() {
-                    let is_selected = browser.selected_category == Some(SongCategory::User)
-                        && browser.selected_index == Some(i);
-                    let is_hovered = ctx.mouse.inside(&item_rect) && item_rect.y >= rect.y;
-
-                    let bg = if is_selected {
-                        item_selected
-                    } else if is_hovered {
-                        item_hover
-                    } else {
-                        item_bg
-                    };
-
-                    // Clip to list bounds
-                    let draw_y = item_rect.y.max(rect.y);
-                    let draw_h = item_h.min(rect.bottom() - draw_y);
-                    if draw_h > 0.0 {
-                        draw_rectangle(item_rect.x + 2.0, draw_y, item_rect.w - 4.0, draw_h, bg);
-
-                        if y >= rect.y {
-                            draw_text(&song.name, rect.x + 20.0, y + 17.0, 13.0, text_color);
-                        }
-                    }
-
-                    // Handle click
-                    if is_hovered && ctx.mouse.left_pressed && item_rect.y >= rect.y {
-                        result.clicked = Some((SongCategory::User, i));
-                    }
-                    if is_hovered && ctx.mouse.double_clicked && item_rect.y >= rect.y {
-                        result.double_clicked = true;
-                    }
-                }
-                y += item_h;
-            }
-        }
-    }
-
-    result
-}
-
 /// Discover songs from both samples and user directories
 ///
 /// Returns (samples, user_songs) tuple
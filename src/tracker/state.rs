@@ -1,10 +1,14 @@
 //! Tracker editor state
 
-use super::audio::{AudioEngine, OutputSampleRate};
-use super::pattern::{Song, Note, Effect, MAX_CHANNELS};
+use super::audio::{AudioEngine, OutputSampleRate, SAMPLE_RATE};
+use super::export::SongExport;
+use super::pattern::{Song, Note, Effect, MAX_CHANNELS, MAX_ORDER_REPEATS, MAX_ORDER_TRANSPOSE};
+use super::effects::{ChannelFx, FxOutput, PITCH_BEND_CENTER, PITCH_BEND_RANGE, TICKS_PER_ROW};
 use super::psx_reverb::ReverbType;
 use super::actions::create_tracker_actions;
 use super::song_browser::SongBrowser;
+use super::meters::OutputMeters;
+use super::cue::{due_cues, CueChange, MusicCue};
 use crate::storage::Storage;
 use crate::ui::{ActionRegistry, SplitPanel};
 use crate::input::MidiInput;
@@ -47,6 +51,13 @@ pub struct TrackerState {
     pub default_volume: u8,
     /// Is editing mode active? (vs. navigation only)
     pub edit_mode: bool,
+    /// Record mode: notes played while the pattern plays are written at the
+    /// playback position instead of the cursor
+    pub recording: bool,
+    /// Rows recorded notes snap to (1 = every row)
+    pub record_quantize: usize,
+    /// Click on every beat during playback (accented at each pattern start)
+    pub metronome: bool,
 
     // Playback state
     /// Is playback active?
@@ -55,8 +66,18 @@ pub struct TrackerState {
     pub playback_row: usize,
     /// Current playback pattern in arrangement
     pub playback_pattern_idx: usize,
+    /// Pass through the current arrangement entry (0-based, for entries that repeat)
+    pub playback_repeat: u8,
     /// Time accumulator for playback timing
     pub playback_time: f64,
+    /// Sub-row tick within the current playback row (0..TICKS_PER_ROW)
+    playback_tick: u32,
+    /// Per-channel effect state (slides, vibrato, arpeggio...) during playback
+    channel_fx: [ChannelFx; MAX_CHANNELS],
+    /// Music cues waiting for their sync point (see `cue`)
+    cues: Vec<MusicCue>,
+    /// Channels muted by cues (their notes are skipped until unmuted)
+    muted: [bool; MAX_CHANNELS],
 
     // View state
     /// First visible row in pattern view
@@ -121,6 +142,32 @@ pub struct TrackerState {
 
     /// MIDI keyboard input
     pub midi: MidiInput,
+
+    /// Song export to WAV in progress
+    pub export: Option<SongExport>,
+
+    /// Channel VU meters, master meters and oscilloscope
+    pub meters: OutputMeters,
+}
+
+/// Quantize resolutions (in rows) record mode cycles through
+pub const RECORD_QUANTIZE_STEPS: [usize; 5] = [1, 2, 4, 8, 16];
+
+/// MIDI channel for the metronome, above the song's channels
+const METRONOME_CHANNEL: i32 = 15;
+/// GM Woodblock
+const METRONOME_PROGRAM: i32 = 115;
+const METRONOME_KEY: i32 = 76;
+const METRONOME_ACCENT_KEY: i32 = 84;
+
+/// Row a note played at `position` (in rows, fractional) is recorded on: the
+/// nearest multiple of `resolution`, held back to the last one in the pattern
+/// when rounding would run off its end
+pub fn quantize_row(position: f64, resolution: usize, length: usize) -> usize {
+    let resolution = resolution.max(1);
+    let last = length.saturating_sub(1) / resolution * resolution;
+    let snapped = (position.max(0.0) / resolution as f64).round() as usize * resolution;
+    snapped.min(last)
 }
 
 /// Soundfont filename
@@ -201,11 +248,19 @@ impl TrackerState {
             octave: 4,
             default_volume: 100,
             edit_mode: true,
+            recording: false,
+            record_quantize: 1,
+            metronome: false,
 
             playing: false,
             playback_row: 0,
             playback_pattern_idx: 0,
+            playback_repeat: 0,
             playback_time: 0.0,
+            playback_tick: 0,
+            channel_fx: [ChannelFx::default(); MAX_CHANNELS],
+            cues: Vec::new(),
+            muted: [false; MAX_CHANNELS],
 
             scroll_row: 0,
             visible_rows: 32,
@@ -234,9 +289,17 @@ impl TrackerState {
             tap_times: Vec::new(),
             pattern_split: SplitPanel::horizontal(2000).with_ratio(0.6).with_min_size(200.0),
             midi: MidiInput::new(),
+            export: None,
+            meters: OutputMeters::default(),
         }
     }
 
+    /// Pull the engine's channel activity and output levels into the meters
+    pub fn update_meters(&mut self, delta: f32) {
+        let readings = self.audio.take_meter_readings();
+        self.meters.update(readings, delta);
+    }
+
     /// Record a tap for tap tempo calculation
     /// Returns the calculated BPM if enough taps have been recorded
     pub fn tap_tempo(&mut self) -> Option<u16> {
@@ -279,6 +342,7 @@ impl TrackerState {
     pub fn set_status(&mut self, message: &str, duration: f64) {
         let expiry = macroquad::time::get_time() + duration;
         self.status_message = Some((message.to_string(), expiry));
+        crate::crash::record(message);
     }
 
     /// Get current status message if not expired
@@ -428,7 +492,11 @@ impl TrackerState {
         self.song.patterns.remove(pattern_idx);
 
         // Update arrangement: remove references to deleted pattern, adjust indices
-        self.song.arrangement.retain(|&idx| idx != pattern_idx);
+        for position in (0..self.song.arrangement.len()).rev() {
+            if self.song.arrangement[position] == pattern_idx {
+                self.song.remove_order(position);
+            }
+        }
         for idx in &mut self.song.arrangement {
             if *idx > pattern_idx {
                 *idx -= 1;
@@ -437,7 +505,7 @@ impl TrackerState {
 
         // Make sure arrangement isn't empty
         if self.song.arrangement.is_empty() {
-            self.song.arrangement.push(0);
+            self.song.insert_order(0, 0);
         }
 
         // Adjust current pattern index if needed
@@ -456,8 +524,7 @@ impl TrackerState {
     /// Insert a pattern into the arrangement at the given position
     pub fn arrangement_insert(&mut self, position: usize, pattern_idx: usize) {
         if pattern_idx < self.song.patterns.len() {
-            let pos = position.min(self.song.arrangement.len());
-            self.song.arrangement.insert(pos, pattern_idx);
+            self.song.insert_order(position, pattern_idx);
             self.dirty = true;
         }
     }
@@ -466,7 +533,7 @@ impl TrackerState {
     /// Won't remove if it's the last entry
     pub fn arrangement_remove(&mut self, position: usize) -> bool {
         if self.song.arrangement.len() > 1 && position < self.song.arrangement.len() {
-            self.song.arrangement.remove(position);
+            self.song.remove_order(position);
             // Adjust current position if needed
             if self.current_pattern_idx >= self.song.arrangement.len() {
                 self.current_pattern_idx = self.song.arrangement.len() - 1;
@@ -481,7 +548,9 @@ impl TrackerState {
     /// Move an arrangement entry up (earlier in sequence)
     pub fn arrangement_move_up(&mut self, position: usize) -> bool {
         if position > 0 && position < self.song.arrangement.len() {
+            self.song.sync_orders();
             self.song.arrangement.swap(position, position - 1);
+            self.song.orders.swap(position, position - 1);
             self.dirty = true;
             true
         } else {
@@ -492,7 +561,9 @@ impl TrackerState {
     /// Move an arrangement entry down (later in sequence)
     pub fn arrangement_move_down(&mut self, position: usize) -> bool {
         if position + 1 < self.song.arrangement.len() {
+            self.song.sync_orders();
             self.song.arrangement.swap(position, position + 1);
+            self.song.orders.swap(position, position + 1);
             self.dirty = true;
             true
         } else {
@@ -508,6 +579,55 @@ impl TrackerState {
         }
     }
 
+    /// Change how many times an arrangement entry plays (1 to MAX_ORDER_REPEATS)
+    pub fn arrangement_adjust_repeats(&mut self, position: usize, delta: i32) {
+        if let Some(order) = self.song.order_mut(position) {
+            let repeats = (order.repeats as i32 + delta).clamp(1, MAX_ORDER_REPEATS as i32) as u8;
+            if repeats != order.repeats {
+                order.repeats = repeats;
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Change an arrangement entry's transpose, in semitones
+    pub fn arrangement_adjust_transpose(&mut self, position: usize, delta: i32) {
+        if let Some(order) = self.song.order_mut(position) {
+            let max = MAX_ORDER_TRANSPOSE as i32;
+            let transpose = (order.transpose as i32 + delta).clamp(-max, max) as i8;
+            if transpose != order.transpose {
+                order.transpose = transpose;
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Put the loop start marker on an entry (again on the same entry clears it)
+    pub fn arrangement_toggle_loop_start(&mut self, position: usize) {
+        if position < self.song.arrangement.len() {
+            self.song.loop_start = if self.song.loop_start == Some(position) { None } else { Some(position) };
+            if let Some(end) = self.song.loop_end {
+                if end < position {
+                    self.song.loop_end = None;
+                }
+            }
+            self.dirty = true;
+        }
+    }
+
+    /// Put the loop end marker on an entry (again on the same entry clears it)
+    pub fn arrangement_toggle_loop_end(&mut self, position: usize) {
+        if position < self.song.arrangement.len() {
+            self.song.loop_end = if self.song.loop_end == Some(position) { None } else { Some(position) };
+            if let Some(start) = self.song.loop_start {
+                if start > position {
+                    self.song.loop_start = None;
+                }
+            }
+            self.dirty = true;
+        }
+    }
+
     /// Get arrangement length
     pub fn arrangement_len(&self) -> usize {
         self.song.arrangement.len()
@@ -784,11 +904,16 @@ impl TrackerState {
         if self.playing {
             self.playback_row = self.current_row;
             self.playback_pattern_idx = self.current_pattern_idx;
+            self.playback_repeat = 0;
             self.playback_time = 0.0;
             self.last_played_notes = [None; MAX_CHANNELS];
+        self.reset_channel_fx();
+            self.reset_channel_fx();
         } else {
             self.audio.all_notes_off();
             self.last_played_notes = [None; MAX_CHANNELS];
+        self.reset_channel_fx();
+            self.reset_channel_fx();
         }
     }
 
@@ -797,9 +922,11 @@ impl TrackerState {
         self.audio.all_notes_off();
         self.playback_row = 0;
         self.playback_pattern_idx = 0;
+        self.playback_repeat = 0;
         self.playback_time = 0.0;
         self.playing = true;
         self.last_played_notes = [None; MAX_CHANNELS];
+        self.reset_channel_fx();
     }
 
     /// Stop playback and return cursor to start
@@ -807,23 +934,34 @@ impl TrackerState {
         self.playing = false;
         self.playback_row = 0;
         self.playback_pattern_idx = 0;
+        self.playback_repeat = 0;
         self.current_row = 0;
         self.current_pattern_idx = 0;
         self.scroll_row = 0;
         self.audio.all_notes_off();
         self.last_played_notes = [None; MAX_CHANNELS];
+        self.reset_channel_fx();
         self.preview_song = None;
     }
 
     /// Start preview playback of a song from the browser
     pub fn start_preview_playback(&mut self, song: Song) {
+        self.start_preview_playback_at(song, 0);
+    }
+
+    /// Start preview playback of a song from a position in its arrangement
+    /// (cutscene music)
+    pub fn start_preview_playback_at(&mut self, song: Song, arrangement_idx: usize) {
         self.audio.all_notes_off();
+        self.playback_pattern_idx = arrangement_idx.min(song.arrangement.len().saturating_sub(1));
         self.preview_song = Some(song);
         self.playback_row = 0;
-        self.playback_pattern_idx = 0;
+        self.playback_repeat = 0;
         self.playback_time = 0.0;
         self.playing = true;
         self.last_played_notes = [None; MAX_CHANNELS];
+        self.muted = [false; MAX_CHANNELS];
+        self.reset_channel_fx();
     }
 
     /// Stop preview playback
@@ -831,9 +969,73 @@ impl TrackerState {
         self.playing = false;
         self.playback_row = 0;
         self.playback_pattern_idx = 0;
+        self.playback_repeat = 0;
         self.audio.all_notes_off();
         self.last_played_notes = [None; MAX_CHANNELS];
+        self.reset_channel_fx();
         self.preview_song = None;
+        self.cues.clear();
+        self.muted = [false; MAX_CHANNELS];
+    }
+
+    /// Change playback when the row clock reaches the cue's sync point. With
+    /// nothing playing there's no clock to wait for, so it applies right away.
+    pub fn queue_cue(&mut self, cue: MusicCue) {
+        if self.playing {
+            self.cues.push(cue);
+        } else {
+            self.apply_cue(cue.change);
+        }
+    }
+
+    /// Cues still waiting for their sync point?
+    pub fn has_cues(&self) -> bool {
+        !self.cues.is_empty()
+    }
+
+    /// Apply the cues due on the row about to play
+    fn apply_due_cues(&mut self) {
+        if self.cues.is_empty() {
+            return;
+        }
+        let rows_per_beat = self.playback_song().rows_per_beat as usize;
+        for cue in due_cues(&mut self.cues, self.playback_row, rows_per_beat) {
+            self.apply_cue(cue.change);
+        }
+    }
+
+    fn apply_cue(&mut self, change: CueChange) {
+        match change {
+            CueChange::Song(song, arrangement_idx) => self.start_preview_playback_at(*song, arrangement_idx),
+            CueChange::Jump(arrangement_idx) => {
+                let len = self.playback_song().arrangement.len();
+                self.playback_pattern_idx = arrangement_idx.min(len.saturating_sub(1));
+                self.playback_row = 0;
+                self.playback_repeat = 0;
+            }
+            CueChange::Mute(channel, muted) => {
+                if channel >= MAX_CHANNELS {
+                    return;
+                }
+                self.muted[channel] = muted;
+                // Release the held note; unmuting waits for the channel's next note
+                if let Some(key) = self.last_played_notes[channel].take() {
+                    self.audio.note_off(channel as i32, key as i32);
+                    self.channel_fx[channel].note_off();
+                }
+            }
+            CueChange::Stop => self.stop_preview_playback(),
+        }
+    }
+
+    /// Clear per-channel effect state and put the pitch wheels back to center
+    fn reset_channel_fx(&mut self) {
+        self.playback_tick = 0;
+        self.channel_fx = [ChannelFx::default(); MAX_CHANNELS];
+        for ch in 0..MAX_CHANNELS as i32 {
+            self.audio.set_pitch_bend_range(ch, PITCH_BEND_RANGE);
+            self.audio.set_pitch_bend(ch, PITCH_BEND_CENTER);
+        }
     }
 
     /// Get the current song for playback (preview song if set, else main song)
@@ -849,17 +1051,211 @@ impl TrackerState {
             self.audio.render_audio(delta);
         }
 
+        if self.export.is_some() {
+            self.update_export();
+            return;
+        }
+
         if !self.playing {
             return;
         }
 
         self.playback_time += delta;
-        let tick_duration = self.playback_song().tick_duration();
+        // tick_duration() is one row; effects run on TICKS_PER_ROW sub-ticks
+        let sub_tick = self.playback_song().tick_duration() / TICKS_PER_ROW as f64;
+
+        while self.playing && self.playback_time >= sub_tick {
+            self.playback_time -= sub_tick;
+            if self.playback_tick == 0 {
+                self.click_metronome();
+            }
+            self.step_playback_tick();
+        }
+    }
 
-        while self.playback_time >= tick_duration {
-            self.playback_time -= tick_duration;
+    /// Metronome click for the row about to play, if it starts a beat
+    fn click_metronome(&self) {
+        if !self.metronome || self.preview_song.is_some() {
+            return;
+        }
+        let rows_per_beat = self.song.rows_per_beat.max(1) as usize;
+        if self.playback_row % rows_per_beat != 0 {
+            return;
+        }
+        let key = if self.playback_row == 0 { METRONOME_ACCENT_KEY } else { METRONOME_KEY };
+        self.audio.set_program(METRONOME_CHANNEL, METRONOME_PROGRAM);
+        self.audio.note_off(METRONOME_CHANNEL, key);
+        self.audio.note_on(METRONOME_CHANNEL, key, 110);
+    }
+
+    /// Is record mode capturing notes right now? (armed and playing the song)
+    pub fn is_live_recording(&self) -> bool {
+        self.recording && self.playing && self.preview_song.is_none() && self.export.is_none()
+    }
+
+    /// Step the record quantize resolution to the next size (wrapping)
+    pub fn cycle_record_quantize(&mut self) {
+        let idx = RECORD_QUANTIZE_STEPS.iter().position(|&q| q == self.record_quantize).unwrap_or(0);
+        self.record_quantize = RECORD_QUANTIZE_STEPS[(idx + 1) % RECORD_QUANTIZE_STEPS.len()];
+    }
+
+    /// Fractional row playback has reached in the current pattern
+    fn playback_position(&self) -> f64 {
+        // playback_tick is the next tick to run; the one before it started
+        // playback_time seconds ago
+        let sub_tick = self.song.tick_duration() / TICKS_PER_ROW as f64;
+        let ticks = self.playback_tick as f64 - 1.0 + self.playback_time / sub_tick;
+        self.playback_row as f64 + ticks / TICKS_PER_ROW as f64
+    }
+
+    /// Record a live note into the current channel at the quantized playback
+    /// row and play it. `volume` is stored with the note (MIDI velocity).
+    pub fn record_note(&mut self, pitch: u8, volume: Option<u8>) {
+        let channel = self.current_channel;
+        let instrument = self.current_instrument();
+        let quantize = self.record_quantize;
+        let position = self.playback_position();
+        let Some(&pattern_num) = self.song.arrangement.get(self.playback_pattern_idx) else {
+            return;
+        };
+        let Some(pattern) = self.song.patterns.get_mut(pattern_num) else {
+            return;
+        };
+        let row = quantize_row(position, quantize, pattern.length);
+        let mut note = Note::new(pitch, instrument);
+        note.volume = volume;
+        pattern.set(channel, row, note);
+        self.dirty = true;
+
+        self.audio.set_program(channel as i32, instrument as i32);
+        self.audio.note_on(channel as i32, pitch as i32, volume.unwrap_or(100) as i32);
+        // Already sounding: a note snapped to the next row sustains instead of striking twice
+        self.last_played_notes[channel] = Some(pitch);
+    }
+
+    /// Run one sub-row tick of playback. Returns true when it finished a row.
+    fn step_playback_tick(&mut self) -> bool {
+        if self.playback_tick == 0 {
+            // Cues land on row boundaries, before the row plays
+            self.apply_due_cues();
+            if !self.playing {
+                return false;
+            }
             self.play_current_row();
+        } else {
+            self.process_effect_tick(self.playback_tick);
+        }
+        self.playback_tick += 1;
+        if self.playback_tick >= TICKS_PER_ROW {
+            self.playback_tick = 0;
             self.advance_playback();
+            return true;
+        }
+        false
+    }
+
+    /// Start rendering the song to a WAV in `MUSIC_EXPORTS_DIR`. Live audio
+    /// is swapped for an offline engine until the export finishes.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_export(&mut self) -> Result<PathBuf, String> {
+        if self.export.is_some() {
+            return Err("Export already running".to_string());
+        }
+        let rows_total = self.song.total_rows();
+        if rows_total == 0 {
+            return Err("Song is empty".to_string());
+        }
+        let offline = self.audio.new_offline().ok_or("No soundfont loaded")?;
+
+        self.stop_preview_playback();
+        let live_audio = std::mem::replace(&mut self.audio, offline);
+        self.sync_all_channel_settings();
+        // Preview playback leaves the edit cursor alone
+        self.start_preview_playback(self.song.clone());
+
+        let path = super::export::export_path(&self.song.name);
+        self.export = Some(SongExport::new(path.clone(), live_audio, rows_total, self.song.bpm));
+        Ok(path)
+    }
+
+    /// Render the next slice of a running export (about 12ms of work per
+    /// frame); writes the file and restores live audio once done
+    fn update_export(&mut self) {
+        let Some(mut export) = self.export.take() else {
+            return;
+        };
+        let started = macroquad::time::get_time();
+
+        while !export.is_done() && macroquad::time::get_time() - started < 0.012 {
+            let sub_tick = self.playback_song().tick_duration() / TICKS_PER_ROW as f64;
+            if !export.song_done() {
+                if self.step_playback_tick() {
+                    export.rows_done += 1;
+                    if export.song_done() {
+                        // Release everything so the tail has the note releases and reverb
+                        self.audio.all_notes_off();
+                    }
+                }
+                export.render_seconds(&self.audio, sub_tick);
+            } else {
+                export.render_tail(&self.audio, (sub_tick * SAMPLE_RATE as f64) as usize + 1);
+            }
+        }
+
+        if !export.is_done() {
+            self.export = Some(export);
+            return;
+        }
+
+        let result = export.write();
+        let duration = export.duration();
+        let path = export.path.clone();
+        self.finish_export(export);
+        match result {
+            Ok(()) => self.set_status(&format!("Exported {:.1}s to {}", duration, path.display()), 4.0),
+            Err(e) => self.set_status(&format!("Export failed: {}", e), 4.0),
+        }
+    }
+
+    /// Abort a running export without writing anything
+    pub fn cancel_export(&mut self) {
+        if let Some(export) = self.export.take() {
+            self.finish_export(export);
+            self.set_status("Export cancelled", 2.0);
+        }
+    }
+
+    /// Put the live engine back and restore the song's tempo
+    fn finish_export(&mut self, export: SongExport) {
+        self.audio = export.live_audio;
+        self.song.bpm = export.saved_bpm;
+        self.stop_preview_playback();
+    }
+
+    /// Run one sub-row tick of the per-channel effects
+    fn process_effect_tick(&mut self, tick: u32) {
+        let num_channels = self.playback_song().num_channels().min(MAX_CHANNELS);
+        for channel in 0..num_channels {
+            let out = self.channel_fx[channel].tick(tick);
+            self.apply_fx_output(channel, out);
+        }
+    }
+
+    /// Send a tick's pitch bend / volume / retrigger to the synth
+    fn apply_fx_output(&mut self, channel: usize, out: FxOutput) {
+        let ch = channel as i32;
+        if let Some(bend) = out.pitch_bend {
+            self.audio.set_pitch_bend(ch, bend);
+        }
+        if let Some(volume) = out.volume {
+            self.audio.set_volume(ch, volume);
+        }
+        if out.retrigger {
+            let fx = self.channel_fx[channel];
+            if let Some(key) = fx.note {
+                self.audio.note_off(ch, key as i32);
+                self.audio.note_on(ch, key as i32, fx.velocity as i32);
+            }
         }
     }
 
@@ -875,6 +1271,7 @@ impl TrackerState {
             Some(p) => p,
             None => return,
         };
+        let order = song.order(self.playback_pattern_idx);
 
         // Collect note data first to avoid borrow issues
         let num_channels = song.num_channels();
@@ -895,16 +1292,18 @@ impl TrackerState {
 
         for channel in 0..num_channels {
             if let Some(note) = pattern.get(channel, playback_row) {
+                // Collect effect (effects also run on rows without a note)
+                let effect = match (note.effect, note.effect_param) {
+                    (Some(fx_char), Some(fx_param)) => Effect::from_char(fx_char, fx_param),
+                    _ => Effect::None,
+                };
+                effects_to_apply.push((channel, effect));
+
                 if note.pitch.is_some() {
                     // Has a note - collect note data
                     let inst = note.instrument.unwrap_or(channel_instruments[channel]);
-                    notes_to_play.push((channel, note.pitch, Some(inst), note.volume, None));
-
-                    // Collect effect
-                    if let (Some(fx_char), Some(fx_param)) = (note.effect, note.effect_param) {
-                        let effect = Effect::from_char(fx_char, fx_param);
-                        effects_to_apply.push((channel, effect));
-                    }
+                    let pitch = note.pitch.map(|p| order.transpose_pitch(p));
+                    notes_to_play.push((channel, pitch, Some(inst), note.volume, None));
                 } else {
                     // Empty row (pitch is None) - mark for clearing sustain state
                     empty_channels.push(channel);
@@ -912,6 +1311,7 @@ impl TrackerState {
             } else {
                 // No note data at all - mark for clearing sustain state
                 empty_channels.push(channel);
+                effects_to_apply.push((channel, Effect::None));
             }
         }
 
@@ -922,29 +1322,46 @@ impl TrackerState {
 
         // Now process notes (pattern borrow is released)
         for (channel, pitch, inst, volume, _) in notes_to_play {
+            if self.muted[channel] {
+                continue;
+            }
             if let Some(p) = pitch {
                 if p == 0xFF {
                     // Note off
                     self.audio.note_off(channel as i32, 0);
                     self.last_played_notes[channel] = None;
+                    self.channel_fx[channel].note_off();
                 } else {
+                    // Portamento glides the held note to the new pitch instead of striking it
+                    let porta = effects_to_apply.iter()
+                        .any(|&(ch, fx)| ch == channel && matches!(fx, Effect::Portamento(_)));
+                    if porta && self.channel_fx[channel].portamento_to(p) {
+                        self.last_played_notes[channel] = Some(p);
+                        continue;
+                    }
+
                     // Check if same note is already playing (sustain behavior like Picotron)
                     let last_note = self.last_played_notes[channel];
                     if last_note != Some(p) {
                         // Different note or first note - trigger it
-                        let velocity = volume.unwrap_or(100) as i32;
+                        let velocity = volume.unwrap_or(100).min(127);
                         let instrument = inst.unwrap_or(0);
+                        // Strike at the unbent pitch; start_row keeps ChannelFx in sync
+                        self.audio.set_pitch_bend(channel as i32, PITCH_BEND_CENTER);
                         self.audio.set_program(channel as i32, instrument as i32);
-                        self.audio.note_on(channel as i32, p as i32, velocity);
+                        self.audio.note_on(channel as i32, p as i32, velocity as i32);
                         self.last_played_notes[channel] = Some(p);
+                        self.channel_fx[channel].note_on(p, velocity);
                     }
                     // Same note = sustain, don't re-trigger
                 }
             }
         }
 
-        // Now apply effects
+        // Now apply effects (tick 0 of the row)
         for (channel, effect) in effects_to_apply {
+            let out = self.channel_fx[channel].start_row(effect);
+            self.apply_fx_output(channel, out);
             self.apply_effect(channel, effect);
         }
 
@@ -984,20 +1401,6 @@ impl TrackerState {
             Effect::SetModulation(v) => {
                 self.audio.set_modulation(ch, v as i32);
             }
-            Effect::SlideUp(amount) => {
-                // Pitch bend up: center (8192) + amount * 64
-                let bend = 8192 + (amount as i32 * 64);
-                self.audio.set_pitch_bend(ch, bend.min(16383));
-            }
-            Effect::SlideDown(amount) => {
-                // Pitch bend down: center (8192) - amount * 64
-                let bend = 8192 - (amount as i32 * 64);
-                self.audio.set_pitch_bend(ch, bend.max(0));
-            }
-            Effect::Vibrato(_, depth) => {
-                // Use modulation wheel for vibrato
-                self.audio.set_modulation(ch, (depth as i32 * 8).min(127));
-            }
             Effect::SetSpeed(bpm) => {
                 // Change song tempo
                 if bpm > 0 {
@@ -1011,21 +1414,19 @@ impl TrackerState {
                 // TODO: Implement pattern break properly
                 let _ = row;
             }
-            // Effects that need per-tick processing (not implemented yet)
-            Effect::Arpeggio(_, _) => {
-                // Would need sub-row tick processing
-            }
-            Effect::Portamento(_) => {
-                // Would need note memory and per-tick slide
-            }
-            Effect::VolumeSlide(_, _) => {
-                // Would need per-tick processing
-            }
+            // Per-tick effects are run by ChannelFx on every sub-row tick
+            Effect::Arpeggio(_, _)
+            | Effect::SlideUp(_)
+            | Effect::SlideDown(_)
+            | Effect::Portamento(_)
+            | Effect::Vibrato(_, _)
+            | Effect::VolumeSlide(_, _)
+            | Effect::Retrigger(_) => {}
             // Note: Reverb is now handled via the dedicated reverb column, not the Fx column
         }
     }
 
-    /// Advance playback to next row
+    /// Advance playback to next row, repeating entries and following the loop points
     fn advance_playback(&mut self) {
         let song = self.playback_song();
         let pattern_num = match song.arrangement.get(self.playback_pattern_idx) {
@@ -1044,15 +1445,17 @@ impl TrackerState {
             }
         };
 
-        let arrangement_len = song.arrangement.len();
+        let repeats = song.order(self.playback_pattern_idx).repeats.max(1);
+        let next_order = song.next_order(self.playback_pattern_idx);
 
         self.playback_row += 1;
         if self.playback_row >= pattern_len {
             self.playback_row = 0;
-            self.playback_pattern_idx += 1;
-            if self.playback_pattern_idx >= arrangement_len {
-                // Loop or stop
-                self.playback_pattern_idx = 0; // Loop for now
+            if self.playback_repeat + 1 < repeats {
+                self.playback_repeat += 1;
+            } else {
+                self.playback_repeat = 0;
+                self.playback_pattern_idx = next_order;
             }
         }
 
@@ -1189,7 +1592,9 @@ impl TrackerState {
                 // No selection - copy single cell
                 if let Some(pattern) = self.current_pattern() {
                     if let Some(note) = pattern.get(self.current_channel, self.current_row) {
-                        self.clipboard = Some(vec![vec![*note]]);
+                        let note = *note;
+                        self.clipboard = Some(vec![vec![note]]);
+                        crate::clipboard_history::record(crate::clipboard_history::ClipData::Pattern(vec![vec![note]]));
                         self.set_status("Copied 1 note", 1.0);
                     }
                 }
@@ -1216,6 +1621,7 @@ impl TrackerState {
             clipboard_data.push(channel_notes);
         }
 
+        crate::clipboard_history::record(crate::clipboard_history::ClipData::Pattern(clipboard_data.clone()));
         self.clipboard = Some(clipboard_data);
         self.set_status(&format!("Copied {} notes ({} rows × {} channels)", num_rows * num_channels, num_rows, num_channels), 1.0);
     }
@@ -1340,6 +1746,7 @@ impl TrackerState {
         self.playing = false;
         self.playback_row = 0;
         self.playback_pattern_idx = 0;
+        self.playback_repeat = 0;
         self.current_row = 0;
         self.current_pattern_idx = 0;
         self.current_channel = 0;
@@ -1347,22 +1754,41 @@ impl TrackerState {
         self.clear_selection();
         self.audio.all_notes_off();
 
-        // Make sure channel instruments and settings are synced with audio engine
+        self.sync_song_audio();
+
+        self.set_status(&format!("Loaded: {}", path.file_name().unwrap_or_default().to_string_lossy()), 2.0);
+        Ok(())
+    }
+
+    /// Push the song's instruments, channel settings, reverb and master volume to the audio engine
+    fn sync_song_audio(&mut self) {
         for (ch, &inst) in self.song.channel_instruments.iter().enumerate() {
             self.audio.set_program(ch as i32, inst as i32);
         }
         self.sync_all_channel_settings();
 
-        // Apply reverb settings from loaded song
         let reverb_type = ReverbType::from_index(self.song.reverb.preset);
         self.audio.set_reverb_preset(reverb_type);
         self.audio.set_reverb_wet_level(self.song.reverb.wet as f32 / 127.0);
 
-        // Apply master volume from loaded song
         self.audio.set_master_volume(self.song.master_volume as f32 / 100.0);
+    }
 
-        self.set_status(&format!("Loaded: {}", path.file_name().unwrap_or_default().to_string_lossy()), 2.0);
-        Ok(())
+    /// Swap in a newer copy of the open song (changed on disk), keeping the
+    /// cursor and playback position where they still fit
+    pub fn reload_song(&mut self, song: Song) {
+        self.song = song;
+        self.dirty = false;
+        let last_entry = self.song.arrangement.len().saturating_sub(1);
+        self.current_pattern_idx = self.current_pattern_idx.min(last_entry);
+        if self.playback_pattern_idx > last_entry {
+            self.playback_pattern_idx = 0;
+            self.playback_row = 0;
+            self.playback_repeat = 0;
+        }
+        self.clear_selection();
+        self.audio.all_notes_off();
+        self.sync_song_audio();
     }
 
     /// Apply a song that was loaded asynchronously
@@ -1375,6 +1801,7 @@ impl TrackerState {
         self.playing = false;
         self.playback_row = 0;
         self.playback_pattern_idx = 0;
+        self.playback_repeat = 0;
         self.current_row = 0;
         self.current_pattern_idx = 0;
         self.current_channel = 0;
@@ -1382,19 +1809,7 @@ impl TrackerState {
         self.clear_selection();
         self.audio.all_notes_off();
 
-        // Make sure channel instruments and settings are synced with audio engine
-        for (ch, &inst) in self.song.channel_instruments.iter().enumerate() {
-            self.audio.set_program(ch as i32, inst as i32);
-        }
-        self.sync_all_channel_settings();
-
-        // Apply reverb settings from loaded song
-        let reverb_type = ReverbType::from_index(self.song.reverb.preset);
-        self.audio.set_reverb_preset(reverb_type);
-        self.audio.set_reverb_wet_level(self.song.reverb.wet as f32 / 127.0);
-
-        // Apply master volume from loaded song
-        self.audio.set_master_volume(self.song.master_volume as f32 / 100.0);
+        self.sync_song_audio();
 
         if let Some(p) = path {
             self.set_status(&format!("Loaded: {}", p.file_name().unwrap_or_default().to_string_lossy()), 2.0);
@@ -1403,6 +1818,16 @@ impl TrackerState {
         }
     }
 
+    /// Import a MIDI file as a new, unsaved song using the current tempo
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn import_midi_file(&mut self, path: &std::path::Path) -> Result<(), String> {
+        let (song, report) = super::io::import_midi_file(path, &self.song)?;
+        self.apply_song(song, None);
+        self.dirty = true;
+        self.set_status(&report.summary(), 4.0);
+        Ok(())
+    }
+
     /// Create a new empty song
     pub fn new_song(&mut self) {
         self.song = Song::new();
@@ -1413,6 +1838,7 @@ impl TrackerState {
         self.playing = false;
         self.playback_row = 0;
         self.playback_pattern_idx = 0;
+        self.playback_repeat = 0;
         self.current_row = 0;
         self.current_pattern_idx = 0;
         self.current_channel = 0;
@@ -1557,3 +1983,21 @@ impl TrackerState {
         self.dirty = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantize_row_snaps_to_nearest_step() {
+        assert_eq!(quantize_row(5.2, 1, 64), 5);
+        assert_eq!(quantize_row(5.6, 1, 64), 6);
+        assert_eq!(quantize_row(5.9, 4, 64), 4);
+        assert_eq!(quantize_row(6.1, 4, 64), 8);
+        // Just before the first row still lands on it
+        assert_eq!(quantize_row(-0.2, 4, 64), 0);
+        // Rounding past the end holds on the last step in the pattern
+        assert_eq!(quantize_row(63.8, 1, 64), 63);
+        assert_eq!(quantize_row(62.5, 8, 64), 56);
+    }
+}
@@ -0,0 +1,190 @@
+//! SPU Voice Allocation
+//!
+//! The PS1 SPU has 24 hardware voices shared by everything that makes sound:
+//! music, ambient loops and gameplay SFX. rustysynth mixes its own voices, so
+//! the allocator sits in front of it: every note claims one of the 24 voices
+//! before reaching the synth, and note-offs give it back.
+//!
+//! When all voices are busy a new note steals the least important voice:
+//! lowest priority first, then the least recently started. A note never steals
+//! from a higher priority, so a burst of SFX can take voices from ambient loops
+//! and music but music can't cut off a gameplay sound.
+
+/// Hardware voices on the PS1 SPU
+pub const SPU_VOICES: usize = 24;
+
+/// Who a voice belongs to (higher steals from lower)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum VoicePriority {
+    /// Ambient loops (wind, hum) - first to go
+    Ambient,
+    /// Tracker music
+    #[default]
+    Music,
+    /// Gameplay sound effects
+    Sfx,
+}
+
+impl VoicePriority {
+    pub const ALL: [VoicePriority; 3] = [VoicePriority::Ambient, VoicePriority::Music, VoicePriority::Sfx];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            VoicePriority::Ambient => "Ambient",
+            VoicePriority::Music => "Music",
+            VoicePriority::Sfx => "SFX",
+        }
+    }
+}
+
+/// A sounding note on a voice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Voice {
+    channel: i32,
+    key: i32,
+    priority: VoicePriority,
+    /// Allocation order, for least-recently-used stealing
+    started: u64,
+}
+
+/// Result of a successful allocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoiceGrant {
+    /// Voice index (0..SPU_VOICES)
+    pub voice: usize,
+    /// Note (channel, key) that was playing on the voice and must be stopped
+    pub stolen: Option<(i32, i32)>,
+}
+
+/// Assigns the SPU's voices to notes
+#[derive(Debug, Clone)]
+pub struct VoiceAllocator {
+    voices: [Option<Voice>; SPU_VOICES],
+    clock: u64,
+    /// Notes stolen since the last `clear`
+    steals: usize,
+}
+
+impl Default for VoiceAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VoiceAllocator {
+    pub fn new() -> Self {
+        Self { voices: [None; SPU_VOICES], clock: 0, steals: 0 }
+    }
+
+    /// Claim a voice for a note. Returns None when every voice is playing a
+    /// higher priority note (the note should not play).
+    pub fn allocate(&mut self, channel: i32, key: i32, priority: VoicePriority) -> Option<VoiceGrant> {
+        self.clock += 1;
+        let started = self.clock;
+
+        // Retriggering a sounding note reuses its voice
+        if let Some(voice) = self.find(channel, key) {
+            self.voices[voice] = Some(Voice { channel, key, priority, started });
+            return Some(VoiceGrant { voice, stolen: None });
+        }
+
+        if let Some(voice) = self.voices.iter().position(|v| v.is_none()) {
+            self.voices[voice] = Some(Voice { channel, key, priority, started });
+            return Some(VoiceGrant { voice, stolen: None });
+        }
+
+        let (voice, victim) = self.voices.iter()
+            .enumerate()
+            .filter_map(|(i, v)| v.map(|v| (i, v)))
+            .filter(|(_, v)| v.priority <= priority)
+            .min_by_key(|(_, v)| (v.priority, v.started))?;
+        self.voices[voice] = Some(Voice { channel, key, priority, started });
+        self.steals += 1;
+        Some(VoiceGrant { voice, stolen: Some((victim.channel, victim.key)) })
+    }
+
+    /// Free the voice playing a note (note off). Returns the voice, if any.
+    pub fn release(&mut self, channel: i32, key: i32) -> Option<usize> {
+        let voice = self.find(channel, key)?;
+        self.voices[voice] = None;
+        Some(voice)
+    }
+
+    /// Free every voice
+    pub fn clear(&mut self) {
+        self.voices = [None; SPU_VOICES];
+        self.steals = 0;
+    }
+
+    /// Voices in use
+    pub fn active(&self) -> usize {
+        self.voices.iter().flatten().count()
+    }
+
+    /// Voices in use by one priority
+    pub fn active_with(&self, priority: VoicePriority) -> usize {
+        self.voices.iter().flatten().filter(|v| v.priority == priority).count()
+    }
+
+    /// Notes stolen since the last `clear`
+    pub fn steals(&self) -> usize {
+        self.steals
+    }
+
+    fn find(&self, channel: i32, key: i32) -> Option<usize> {
+        self.voices.iter().position(|v| matches!(v, Some(v) if v.channel == channel && v.key == key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(alloc: &mut VoiceAllocator, channel: i32, priority: VoicePriority) {
+        for key in 0..SPU_VOICES as i32 {
+            assert!(alloc.allocate(channel, key, priority).unwrap().stolen.is_none());
+        }
+    }
+
+    #[test]
+    fn test_steals_least_recent_of_lowest_priority() {
+        let mut alloc = VoiceAllocator::new();
+        fill(&mut alloc, 0, VoicePriority::Music);
+        assert_eq!(alloc.active(), SPU_VOICES);
+
+        // Oldest music note goes first, then the next oldest
+        let grant = alloc.allocate(9, 60, VoicePriority::Sfx).unwrap();
+        assert_eq!(grant.stolen, Some((0, 0)));
+        assert_eq!(alloc.allocate(9, 61, VoicePriority::Music).unwrap().stolen, Some((0, 1)));
+
+        // SFX voices are never stolen by music
+        for key in 0..SPU_VOICES as i32 {
+            alloc.allocate(9, 100 + key, VoicePriority::Sfx);
+        }
+        assert_eq!(alloc.active_with(VoicePriority::Sfx), SPU_VOICES);
+        assert_eq!(alloc.allocate(0, 50, VoicePriority::Music), None);
+    }
+
+    #[test]
+    fn test_ambient_is_stolen_before_music() {
+        let mut alloc = VoiceAllocator::new();
+        fill(&mut alloc, 0, VoicePriority::Music);
+        alloc.release(0, 5);
+        alloc.allocate(3, 40, VoicePriority::Ambient);
+        // The ambient loop is newer than every music note but still goes first
+        assert_eq!(alloc.allocate(9, 60, VoicePriority::Sfx).unwrap().stolen, Some((3, 40)));
+        assert_eq!(alloc.steals(), 1);
+    }
+
+    #[test]
+    fn test_retrigger_and_release() {
+        let mut alloc = VoiceAllocator::new();
+        let first = alloc.allocate(1, 60, VoicePriority::Music).unwrap();
+        let again = alloc.allocate(1, 60, VoicePriority::Music).unwrap();
+        assert_eq!(first.voice, again.voice);
+        assert_eq!(alloc.active(), 1);
+        assert_eq!(alloc.release(1, 60), Some(first.voice));
+        assert_eq!(alloc.release(1, 60), None);
+        assert_eq!(alloc.active(), 0);
+    }
+}
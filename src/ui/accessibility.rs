@@ -0,0 +1,175 @@
+//! Accessibility: widget descriptions and keyboard focus
+//!
+//! Interactive widgets describe themselves to the `UiContext` as they are drawn
+//! (role, accessible name, enabled/checked state). The previous frame's list is
+//! the keyboard traversal order:
+//! - Ctrl+Tab / Ctrl+Shift+Tab: move focus to the next/previous widget
+//!   (plain Tab is taken by tool shortcuts and text fields)
+//! - Enter / Space: activate the focused widget
+//! - Escape: leave keyboard focus
+//!
+//! macroquad has no platform screen-reader bridge, so the same list is exposed
+//! as a `UiSnapshot` (plain text or RON) for assistive tooling to read.
+
+use serde::{Deserialize, Serialize};
+use super::Rect;
+
+/// What kind of control a widget is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WidgetRole {
+    Button,
+    /// Button with an on/off state
+    Toggle,
+    /// Top-level tab
+    Tab,
+    /// Opens a menu or dropdown
+    MenuButton,
+    Link,
+}
+
+impl WidgetRole {
+    pub fn label(&self) -> &'static str {
+        match self {
+            WidgetRole::Button => "Button",
+            WidgetRole::Toggle => "Toggle",
+            WidgetRole::Tab => "Tab",
+            WidgetRole::MenuButton => "Menu",
+            WidgetRole::Link => "Link",
+        }
+    }
+}
+
+/// One interactive widget as described to assistive tooling
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessNode {
+    pub role: WidgetRole,
+    /// Accessible name (usually the tooltip, including the shortcut hint)
+    pub name: String,
+    pub enabled: bool,
+    /// On/off state for toggles and tabs
+    #[serde(default)]
+    pub checked: Option<bool>,
+    /// Screen bounds (x, y, w, h)
+    pub bounds: (f32, f32, f32, f32),
+}
+
+impl AccessNode {
+    pub fn new(role: WidgetRole, name: &str, rect: Rect) -> Self {
+        Self {
+            role,
+            name: name.to_string(),
+            enabled: true,
+            checked: None,
+            bounds: (rect.x, rect.y, rect.w, rect.h),
+        }
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.bounds.0, self.bounds.1, self.bounds.2, self.bounds.3)
+    }
+
+    /// Spoken form, e.g. "Toggle: Show grid, on" or "Button: Delete, disabled"
+    pub fn describe(&self) -> String {
+        let mut text = format!("{}: {}", self.role.label(), self.name);
+        match self.checked {
+            Some(true) => text.push_str(", on"),
+            Some(false) => text.push_str(", off"),
+            None => {}
+        }
+        if !self.enabled {
+            text.push_str(", disabled");
+        }
+        text
+    }
+}
+
+/// All widgets of the last complete frame, plus keyboard focus
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UiSnapshot {
+    /// Index into `widgets` of the keyboard-focused widget
+    pub focused: Option<usize>,
+    pub widgets: Vec<AccessNode>,
+}
+
+impl UiSnapshot {
+    /// The focused widget, if any
+    pub fn focused_widget(&self) -> Option<&AccessNode> {
+        self.focused.and_then(|i| self.widgets.get(i))
+    }
+
+    /// One line per widget, focused widget marked with '>'
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (i, widget) in self.widgets.iter().enumerate() {
+            let marker = if self.focused == Some(i) { '>' } else { ' ' };
+            text.push(marker);
+            text.push(' ');
+            text.push_str(&widget.describe());
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+}
+
+/// Next focus index when stepping through `count` widgets (wraps around).
+/// Starting from no focus goes to the first (or last, backwards) widget.
+pub fn next_focus(current: Option<usize>, count: usize, backwards: bool) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    Some(match (current, backwards) {
+        (None, false) => 0,
+        (None, true) => count - 1,
+        (Some(i), false) => (i.min(count - 1) + 1) % count,
+        (Some(i), true) => (i.min(count - 1) + count - 1) % count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_focus_wraps() {
+        assert_eq!(next_focus(None, 0, false), None);
+        assert_eq!(next_focus(None, 3, false), Some(0));
+        assert_eq!(next_focus(None, 3, true), Some(2));
+        assert_eq!(next_focus(Some(2), 3, false), Some(0));
+        assert_eq!(next_focus(Some(0), 3, true), Some(2));
+        // Focus past the end (widgets disappeared) is clamped first
+        assert_eq!(next_focus(Some(10), 3, false), Some(0));
+        assert_eq!(next_focus(Some(10), 3, true), Some(1));
+    }
+
+    #[test]
+    fn test_snapshot_text() {
+        let rect = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let snapshot = UiSnapshot {
+            focused: Some(1),
+            widgets: vec![
+                AccessNode::new(WidgetRole::Button, "Save (Ctrl+S)", rect),
+                AccessNode::new(WidgetRole::Toggle, "Show grid", rect).checked(true),
+                AccessNode::new(WidgetRole::Button, "Delete", rect).enabled(false),
+            ],
+        };
+        assert_eq!(
+            snapshot.to_text(),
+            "  Button: Save (Ctrl+S)\n> Toggle: Show grid, on\n  Button: Delete, disabled\n"
+        );
+        assert_eq!(snapshot.focused_widget().map(|w| w.name.as_str()), Some("Show grid"));
+    }
+}
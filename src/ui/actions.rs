@@ -432,7 +432,13 @@ impl ActionRegistry {
 
     /// Check if an action is triggered (shortcut pressed and enabled)
     pub fn triggered(&self, id: &str, ctx: &ActionContext) -> bool {
-        self.actions.get(id).map_or(false, |a| a.is_triggered(ctx))
+        let Some(action) = self.actions.get(id) else { return false };
+        let triggered = action.is_triggered(ctx);
+        if let (true, Some(shortcut)) = (triggered, &action.shortcut) {
+            // Shortcut presses go into the crash report's action log
+            crate::crash::record(&format!("{} ({})", action.label, shortcut.display()));
+        }
+        triggered
     }
 
     /// Check if an action is enabled
@@ -0,0 +1,245 @@
+//! Gamepad navigation for the editor UIs
+//!
+//! A virtual cursor for couch setups, driven through the same `MouseState`
+//! the widgets already read, so every editor works with it unchanged:
+//! - Left stick: move the cursor (hold RB to hand the stick to the 3D
+//!   viewport's camera instead)
+//! - D-pad: jump to the nearest widget in that direction
+//! - A: left click (hold to drag)
+//! - B: right click, which cancels drags and transforms
+//! - Right stick: orbit/look in the 3D viewport under the cursor
+//!
+//! The cursor wakes up on the first gamepad input and hands control back as
+//! soon as the real mouse moves or clicks.
+
+use macroquad::prelude::*;
+use super::accessibility::AccessNode;
+use super::{MouseState, Rect};
+
+/// Cursor speed at full stick deflection (pixels per second)
+const CURSOR_SPEED: f32 = 900.0;
+
+/// D-pad direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl NavDirection {
+    /// Unit step on screen (y down)
+    fn step(&self) -> (f32, f32) {
+        match self {
+            NavDirection::Up => (0.0, -1.0),
+            NavDirection::Down => (0.0, 1.0),
+            NavDirection::Left => (-1.0, 0.0),
+            NavDirection::Right => (1.0, 0.0),
+        }
+    }
+}
+
+/// One frame of gamepad input, filled in by the caller (see `input::InputState`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GamepadNavInput {
+    /// Left stick, up positive
+    pub left_stick: Vec2,
+    /// Right stick, up positive
+    pub right_stick: Vec2,
+    /// D-pad direction pressed this frame
+    pub direction: Option<NavDirection>,
+    /// Click button (A) held
+    pub confirm_down: bool,
+    /// Cancel button (B) pressed this frame
+    pub cancel_pressed: bool,
+    /// Camera button (RB) held: the left stick moves the camera
+    pub camera_held: bool,
+}
+
+/// Virtual cursor state
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GamepadCursor {
+    /// The cursor is driving the mouse
+    pub active: bool,
+    pub x: f32,
+    pub y: f32,
+    /// Right stick, for 3D viewports to orbit or look (zero when idle)
+    pub look: Vec2,
+    /// Left stick while the camera button is held, for 3D viewports to move
+    pub fly: Vec2,
+    /// Real mouse position last frame (moving it hands control back)
+    last_mouse: (f32, f32),
+    confirm_down: bool,
+}
+
+impl GamepadCursor {
+    /// Move the cursor and, while it's active, replace `mouse` with it.
+    /// `widgets` are the last frame's widgets (for d-pad jumps), `bounds`
+    /// keeps the cursor on screen.
+    pub fn update(&mut self, pad: &GamepadNavInput, mouse: &mut MouseState, widgets: &[AccessNode], bounds: Rect, delta: f32) {
+        let real = (mouse.x, mouse.y);
+        if real != self.last_mouse || mouse.left_pressed || mouse.right_pressed {
+            self.active = false;
+        }
+        self.last_mouse = real;
+
+        let stick = if pad.camera_held { Vec2::ZERO } else { pad.left_stick };
+        self.look = pad.right_stick;
+        self.fly = if pad.camera_held { pad.left_stick } else { Vec2::ZERO };
+
+        let woken = stick.length() > 0.0 || pad.direction.is_some() || pad.confirm_down || pad.cancel_pressed;
+        if woken && !self.active {
+            self.active = true;
+            self.x = mouse.x;
+            self.y = mouse.y;
+            // Don't turn a held button into a click mid-press
+            self.confirm_down = pad.confirm_down;
+        }
+        if !self.active {
+            self.confirm_down = false;
+            return;
+        }
+
+        // Squared response: small deflections for precise placement
+        let speed = CURSOR_SPEED * stick.length() * delta;
+        self.x += stick.x * speed;
+        self.y -= stick.y * speed;
+        if let Some(direction) = pad.direction {
+            let enabled = widgets.iter().filter(|w| w.enabled).map(|w| w.rect());
+            if let Some(rect) = nearest_in_direction((self.x, self.y), direction, enabled) {
+                self.x = rect.center_x();
+                self.y = rect.center_y();
+            }
+        }
+        self.x = self.x.clamp(bounds.x, bounds.right() - 1.0);
+        self.y = self.y.clamp(bounds.y, bounds.bottom() - 1.0);
+
+        mouse.x = self.x;
+        mouse.y = self.y;
+        mouse.left_pressed = pad.confirm_down && !self.confirm_down;
+        mouse.left_released = !pad.confirm_down && self.confirm_down;
+        mouse.left_down = pad.confirm_down;
+        mouse.right_pressed = pad.cancel_pressed;
+        mouse.right_down = false;
+        mouse.scroll = 0.0;
+        mouse.double_clicked = false;
+        self.confirm_down = pad.confirm_down;
+    }
+
+    /// Draw the cursor (call at end of frame, before the tooltip)
+    pub fn draw(&self) {
+        if !self.active {
+            return;
+        }
+        let (x, y) = (self.x.round(), self.y.round());
+        let color = if self.confirm_down { Color::from_rgba(255, 200, 0, 255) } else { WHITE };
+        // Arrow pointer with a dark outline so it reads on any panel
+        let tip = vec2(x, y);
+        let left = vec2(x, y + 16.0);
+        let right = vec2(x + 11.0, y + 11.0);
+        draw_triangle(tip, left, right, color);
+        draw_triangle_lines(tip, left, right, 1.0, Color::from_rgba(20, 20, 24, 255));
+    }
+}
+
+/// The rect whose center is closest to `from` in `direction`: distance
+/// along the direction plus twice the sideways offset, so widgets in line
+/// win over nearer ones off to the side. Rects containing `from` are skipped.
+pub fn nearest_in_direction(from: (f32, f32), direction: NavDirection, rects: impl Iterator<Item = Rect>) -> Option<Rect> {
+    let (sx, sy) = direction.step();
+    rects
+        .filter(|r| !r.contains(from.0, from.1))
+        .filter_map(|r| {
+            let dx = r.center_x() - from.0;
+            let dy = r.center_y() - from.1;
+            let along = dx * sx + dy * sy;
+            let side = (dx * sy - dy * sx).abs();
+            (along > 0.0).then_some((along + side * 2.0, r))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, r)| r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::accessibility::WidgetRole;
+
+    fn pad() -> GamepadNavInput {
+        GamepadNavInput::default()
+    }
+
+    #[test]
+    fn test_nearest_in_direction_prefers_widgets_in_line() {
+        let rects = [
+            Rect::new(100.0, 0.0, 20.0, 20.0),  // right, in line
+            Rect::new(40.0, 60.0, 20.0, 20.0),  // nearer, but well below
+            Rect::new(-60.0, 0.0, 20.0, 20.0),  // left
+            Rect::new(0.0, 0.0, 20.0, 20.0),    // under the cursor
+        ];
+        let from = (10.0, 10.0);
+        let pick = |d| nearest_in_direction(from, d, rects.iter().copied()).map(|r| r.x);
+        assert_eq!(pick(NavDirection::Right), Some(100.0));
+        assert_eq!(pick(NavDirection::Left), Some(-60.0));
+        assert_eq!(pick(NavDirection::Down), Some(40.0));
+        assert_eq!(pick(NavDirection::Up), None);
+    }
+
+    #[test]
+    fn test_cursor_clicks_and_hands_back_to_mouse() {
+        let bounds = Rect::new(0.0, 0.0, 800.0, 600.0);
+        let mut cursor = GamepadCursor::default();
+        let mut mouse = MouseState { x: 100.0, y: 100.0, ..Default::default() };
+
+        // Idle pad leaves the mouse alone
+        cursor.update(&pad(), &mut mouse, &[], bounds, 0.1);
+        assert!(!cursor.active);
+
+        // Stick right wakes the cursor at the mouse and moves it
+        let input = GamepadNavInput { left_stick: vec2(1.0, 0.0), ..pad() };
+        let mut frame = MouseState { x: 100.0, y: 100.0, ..Default::default() };
+        cursor.update(&input, &mut frame, &[], bounds, 0.1);
+        assert!(cursor.active);
+        assert_eq!((frame.x, frame.y), (100.0 + CURSOR_SPEED * 0.1, 100.0));
+
+        // A press is a click, holding it is a drag, letting go releases
+        let press = GamepadNavInput { confirm_down: true, ..pad() };
+        let mut frame = MouseState { x: 100.0, y: 100.0, ..Default::default() };
+        cursor.update(&press, &mut frame, &[], bounds, 0.1);
+        assert!(frame.left_pressed && frame.left_down);
+        let mut frame = MouseState { x: 100.0, y: 100.0, ..Default::default() };
+        cursor.update(&press, &mut frame, &[], bounds, 0.1);
+        assert!(!frame.left_pressed && frame.left_down);
+        let mut frame = MouseState { x: 100.0, y: 100.0, ..Default::default() };
+        cursor.update(&pad(), &mut frame, &[], bounds, 0.1);
+        assert!(frame.left_released && !frame.left_down);
+
+        // Moving the real mouse takes over again
+        let mut frame = MouseState { x: 120.0, y: 100.0, ..Default::default() };
+        cursor.update(&pad(), &mut frame, &[], bounds, 0.1);
+        assert!(!cursor.active);
+        assert_eq!(frame.x, 120.0);
+    }
+
+    #[test]
+    fn test_dpad_jumps_to_widgets_and_camera_button_frees_stick() {
+        let bounds = Rect::new(0.0, 0.0, 800.0, 600.0);
+        let widgets = [
+            AccessNode::new(WidgetRole::Button, "Save", Rect::new(200.0, 90.0, 20.0, 20.0)),
+            AccessNode::new(WidgetRole::Button, "Open", Rect::new(150.0, 90.0, 20.0, 20.0)).enabled(false),
+        ];
+        let mut cursor = GamepadCursor::default();
+        let mut mouse = MouseState { x: 100.0, y: 100.0, ..Default::default() };
+        let input = GamepadNavInput { direction: Some(NavDirection::Right), ..pad() };
+        cursor.update(&input, &mut mouse, &widgets, bounds, 0.1);
+        // Disabled widgets are skipped
+        assert_eq!((mouse.x, mouse.y), (210.0, 100.0));
+
+        let input = GamepadNavInput { left_stick: vec2(0.0, 1.0), camera_held: true, ..pad() };
+        let mut mouse = MouseState { x: 100.0, y: 100.0, ..Default::default() };
+        cursor.update(&input, &mut mouse, &widgets, bounds, 0.1);
+        assert_eq!((mouse.x, mouse.y), (210.0, 100.0));
+        assert_eq!(cursor.fly, vec2(0.0, 1.0));
+    }
+}
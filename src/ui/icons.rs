@@ -56,6 +56,8 @@ pub mod icon {
     pub const MAXIMIZE_2: char = '\u{e113}';   // UV editor (expand/maximize)
     pub const BRUSH: char = '\u{e1d3}';        // Paint mode
     pub const PAINT_BUCKET: char = '\u{e2e6}'; // Fill tool (paint-bucket)
+    pub const STAMP: char = '\u{e3bb}';        // Clone stamp tool
+    pub const SCAN_LINE: char = '\u{e258}';    // Seam checker (scan-line)
     pub const GIT_BRANCH: char = '\u{e1f4}';   // Hierarchy
     pub const SCAN: char = '\u{e257}';         // Face selection
     pub const CIRCLE_DOT: char = '\u{e345}';   // Vertex selection
@@ -109,6 +111,7 @@ pub mod icon {
     pub const EYE: char = '\u{e0ba}';             // Free camera (eye)
     pub const EYE_OFF: char = '\u{e0bb}';         // Hidden (eye-off)
     pub const ORBIT: char = '\u{e12e}';           // Orbit camera (orbit icon)
+    pub const CCTV: char = '\u{e57d}';            // cctv (fixed/rail camera volume)
 
     // Lock icons
     pub const LOCK: char = '\u{e109}';            // Locked
@@ -140,6 +143,11 @@ pub mod icon {
     pub const DROPLET: char = '\u{e0b4}';         // droplet (fill toggle)
     pub const PIPETTE: char = '\u{e4c6}';         // pipette (eyedropper/color picker)
     pub const WAND: char = '\u{e1a8}';            // wand-2 (magic select by color)
+    pub const SWORD: char = '\u{e2b3}';           // sword (weapon component)
+    pub const SHIELD: char = '\u{e158}';          // shield (armor component)
+    pub const IMAGE: char = '\u{e0f6}';           // image (sprite component)
+    pub const TIMER: char = '\u{e1e0}';           // timer (tracker metronome)
+    pub const TRIANGLE_ALERT: char = '\u{e193}';  // triangle-alert (validation warning)
 }
 
 /// Draw a Lucide icon centered in a rect
@@ -1,6 +1,8 @@
 //! Input state for UI interaction
 
 use super::Rect;
+use super::accessibility::{next_focus, AccessNode, UiSnapshot};
+use super::gamepad_nav::{GamepadCursor, GamepadNavInput};
 use macroquad::prelude::*;
 
 /// Mouse button state
@@ -55,6 +57,16 @@ pub struct UiContext {
     pub tooltip: Option<PendingTooltip>,
     /// Whether a modal dialog is active (blocks input to background)
     modal_active: bool,
+    /// Widgets described so far this frame, in draw order
+    widgets: Vec<AccessNode>,
+    /// Widgets of the previous (complete) frame: the keyboard traversal order
+    last_widgets: Vec<AccessNode>,
+    /// Keyboard-focused widget (index in draw order), None = mouse only
+    pub focus: Option<usize>,
+    /// Enter/Space pressed this frame while a widget had keyboard focus
+    focus_activate: bool,
+    /// Gamepad virtual cursor (see `gamepad_nav`)
+    pub gamepad: GamepadCursor,
 }
 
 impl UiContext {
@@ -66,6 +78,11 @@ impl UiContext {
             id_counter: 0,
             tooltip: None,
             modal_active: false,
+            widgets: Vec::new(),
+            last_widgets: Vec::new(),
+            focus: None,
+            focus_activate: false,
+            gamepad: GamepadCursor::default(),
         }
     }
 
@@ -109,6 +126,11 @@ impl UiContext {
         self.id_counter = 0;
         self.tooltip = None;
         self.modal_active = false;
+        self.last_widgets = std::mem::take(&mut self.widgets);
+        self.focus_activate = false;
+        if self.focus.is_some_and(|i| i >= self.last_widgets.len()) {
+            self.focus = None;
+        }
 
         // Clear dragging if mouse released
         if !self.mouse.left_down {
@@ -116,6 +138,65 @@ impl UiContext {
         }
     }
 
+    /// Handle keyboard focus keys (call after `begin_frame`, before UI code).
+    /// Ctrl+Tab / Ctrl+Shift+Tab move focus, Enter/Space activate, Escape leaves focus.
+    pub fn update_keyboard_focus(&mut self) {
+        let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        if ctrl && is_key_pressed(KeyCode::Tab) {
+            self.focus = next_focus(self.focus, self.last_widgets.len(), shift);
+        } else if self.focus.is_some() {
+            if is_key_pressed(KeyCode::Escape) {
+                self.focus = None;
+            } else if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Space) {
+                self.focus_activate = true;
+            }
+        }
+    }
+
+    /// Let the gamepad drive the mouse (call after `begin_frame`, before UI
+    /// code, in tabs that use the virtual cursor). The mouse state seen by
+    /// widgets afterwards is the cursor's while it's active.
+    pub fn update_gamepad_cursor(&mut self, pad: &GamepadNavInput, delta: f32) {
+        let bounds = Rect::new(0.0, 0.0, screen_width(), screen_height());
+        self.gamepad.update(pad, &mut self.mouse, &self.last_widgets, bounds, delta);
+        if !self.mouse.left_down {
+            self.dragging = None;
+        }
+    }
+
+    /// Describe an interactive widget for keyboard focus and assistive tooling.
+    /// Call once per widget per frame, in draw order. Returns true if the widget
+    /// has keyboard focus and was activated this frame (treat like a click).
+    /// Widgets behind a modal are skipped (not reachable).
+    pub fn describe_widget(&mut self, node: AccessNode) -> bool {
+        if self.modal_active {
+            return false;
+        }
+        let focused = self.focus == Some(self.widgets.len());
+        let enabled = node.enabled;
+        if focused && self.tooltip.is_none() {
+            // Announce the focused widget where sighted keyboard users can see it
+            let rect = node.rect();
+            self.tooltip = Some(PendingTooltip { text: node.describe(), x: rect.x, y: rect.bottom() });
+        }
+        self.widgets.push(node);
+        focused && enabled && self.focus_activate
+    }
+
+    /// The last complete frame's widgets and keyboard focus
+    pub fn accessibility_snapshot(&self) -> UiSnapshot {
+        UiSnapshot { focused: self.focus, widgets: self.last_widgets.clone() }
+    }
+
+    /// Draw the keyboard focus ring (call at end of frame, before the tooltip)
+    pub fn draw_focus_ring(&self) {
+        if let Some(node) = self.focus.and_then(|i| self.widgets.get(i)) {
+            let r = node.rect();
+            draw_rectangle_lines(r.x - 2.0, r.y - 2.0, r.w + 4.0, r.h + 4.0, 2.0, Color::from_rgba(255, 200, 0, 255));
+        }
+    }
+
     /// Set tooltip to show (call from widget when hovered)
     /// Ignored when a modal is active (background widgets shouldn't show tooltips)
     pub fn set_tooltip(&mut self, text: &str, x: f32, y: f32) {
@@ -23,6 +23,8 @@ mod icons;
 mod theme;
 mod actions;
 mod text_input;
+mod accessibility;
+mod gamepad_nav;
 pub mod drag_tracker;
 pub mod tool;
 pub mod tool_box;
@@ -36,7 +38,9 @@ pub use tabbar::*;
 pub use icons::*;
 pub use theme::*;
 pub use actions::*;
-pub use text_input::{TextInputState, draw_text_input};
+pub use accessibility::*;
+pub use gamepad_nav::{GamepadNavInput, NavDirection};
+pub use text_input::{TextInputState, draw_text_input, draw_text_area};
 pub use drag_tracker::{
     DragState, DragStatus, DragConfig, SnapMode, Axis, PickerType, apply_drag_update, snap_angle,
 };
@@ -1,9 +1,15 @@
 //! Resizable panel system
 //!
 //! Panels can be split horizontally or vertically with draggable dividers.
+//!
+//! `DockLayout` arranges named panels on the left, right or bottom edge, or
+//! in floating windows. Dragging a panel header re-docks it (drop it near an
+//! edge, or anywhere else to float it), clicking the header collapses it. The
+//! arrangement is a plain list of `PanelDock` so tools can save it.
 
 use macroquad::prelude::*;
-use super::{Rect, UiContext};
+use serde::{Deserialize, Serialize};
+use super::{Rect, UiContext, icon, icon_button};
 
 /// Direction of a split
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -264,3 +270,406 @@ pub fn draw_collapsible_panel(
         (clicked, Some(content_rect))
     }
 }
+
+/// Where a dockable panel lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DockEdge {
+    #[default]
+    Left,
+    Right,
+    Bottom,
+    /// Free-floating window drawn over the docked panels
+    Floating,
+}
+
+/// Saved placement of one dockable panel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PanelDock {
+    /// Stable panel name the owning tool uses to find it (e.g. "textures")
+    pub key: String,
+    pub edge: DockEdge,
+    #[serde(default)]
+    pub collapsed: bool,
+    /// Edge the pin button docks a floating panel back to
+    #[serde(default)]
+    pub home: DockEdge,
+    /// Floating window bounds (x, y, w, h)
+    #[serde(default = "default_float_rect")]
+    pub float_rect: (f32, f32, f32, f32),
+}
+
+fn default_float_rect() -> (f32, f32, f32, f32) {
+    (120.0, 120.0, 280.0, 320.0)
+}
+
+impl PanelDock {
+    pub fn new(key: &str, edge: DockEdge) -> Self {
+        Self {
+            key: key.to_string(),
+            edge,
+            collapsed: false,
+            home: if edge == DockEdge::Floating { DockEdge::Right } else { edge },
+            float_rect: default_float_rect(),
+        }
+    }
+
+    pub fn collapsed(mut self) -> Self {
+        self.collapsed = true;
+        self
+    }
+}
+
+/// Width of a collapsed panel in the bottom strip (header only)
+const BOTTOM_COLLAPSED_WIDTH: f32 = 120.0;
+/// Smallest floating window
+const MIN_FLOAT_SIZE: (f32, f32) = (160.0, 80.0);
+/// Size of the resize grip in a floating window's corner
+const RESIZE_GRIP: f32 = 10.0;
+/// Mouse travel before a header press becomes a drag
+const DRAG_THRESHOLD: f32 = 4.0;
+/// Drop zones: fraction of the width (left/right) and height (bottom)
+const EDGE_ZONE: f32 = 0.15;
+const BOTTOM_ZONE: f32 = 0.2;
+
+/// Header press being tracked (click to collapse, drag to re-dock)
+#[derive(Debug, Clone, Copy)]
+struct DockDrag {
+    panel: usize,
+    /// Mouse position at the press
+    start: (f32, f32),
+    /// Mouse offset from the panel's top-left at the press
+    grab: (f32, f32),
+    moved: bool,
+    /// Dragging a floating window's resize grip
+    resize: bool,
+}
+
+/// A set of panels that can be re-docked, collapsed and floated.
+///
+/// Per frame: split the tool area with `is_edge_used`, place docked panels
+/// with `stack`, draw each with `draw_header` (content rect is None when
+/// collapsed), draw floating panels last using `float_rect`, then call
+/// `finish` to apply drags and draw the drop preview.
+pub struct DockLayout {
+    pub id: u64,
+    /// Panels in order (the order within each edge is the draw order)
+    pub panels: Vec<PanelDock>,
+    defaults: Vec<PanelDock>,
+    drag: Option<DockDrag>,
+}
+
+impl DockLayout {
+    pub fn new(id: u64, panels: Vec<PanelDock>) -> Self {
+        Self { id, defaults: panels.clone(), panels, drag: None }
+    }
+
+    /// Index of the panel with `key`
+    pub fn find(&self, key: &str) -> Option<usize> {
+        self.panels.iter().position(|p| p.key == key)
+    }
+
+    /// Panel indices on `edge`, in order
+    pub fn on_edge(&self, edge: DockEdge) -> Vec<usize> {
+        (0..self.panels.len()).filter(|&i| self.panels[i].edge == edge).collect()
+    }
+
+    pub fn is_edge_used(&self, edge: DockEdge) -> bool {
+        self.panels.iter().any(|p| p.edge == edge)
+    }
+
+    /// Replace the arrangement, e.g. with a preset or one loaded from a file.
+    /// Unknown keys are dropped and panels missing from `saved` keep their
+    /// default placement, so layouts survive panels being added or renamed.
+    pub fn restore(&mut self, saved: &[PanelDock]) {
+        let mut panels: Vec<PanelDock> = saved.iter()
+            .filter(|p| self.defaults.iter().any(|d| d.key == p.key))
+            .cloned()
+            .collect();
+        for default in &self.defaults {
+            if !panels.iter().any(|p| p.key == default.key) {
+                panels.push(default.clone());
+            }
+        }
+        self.panels = panels;
+        self.drag = None;
+    }
+
+    /// Back to the arrangement the layout was created with
+    pub fn reset(&mut self) {
+        let defaults = self.defaults.clone();
+        self.restore(&defaults);
+    }
+
+    /// Rects of the panels docked on `edge` inside `area`.
+    /// Left/right stack top to bottom, the bottom strip runs left to right.
+    /// Collapsed panels take just their header; the rest share the space.
+    pub fn stack(&self, edge: DockEdge, area: Rect) -> Vec<(usize, Rect)> {
+        let indices = self.on_edge(edge);
+        let horizontal = edge == DockEdge::Bottom;
+        let collapsed_size = if horizontal { BOTTOM_COLLAPSED_WIDTH } else { COLLAPSED_PANEL_HEIGHT };
+        let total = if horizontal { area.w } else { area.h };
+
+        let num_collapsed = indices.iter().filter(|&&i| self.panels[i].collapsed).count();
+        let num_expanded = indices.len() - num_collapsed;
+        let expanded_size = if num_expanded > 0 {
+            (total - num_collapsed as f32 * collapsed_size).max(0.0) / num_expanded as f32
+        } else {
+            0.0
+        };
+
+        let mut offset = 0.0;
+        indices.into_iter()
+            .map(|i| {
+                let collapsed = self.panels[i].collapsed;
+                let size = if collapsed { collapsed_size } else { expanded_size };
+                let rect = if horizontal {
+                    let h = if collapsed { COLLAPSED_PANEL_HEIGHT } else { area.h };
+                    Rect::new(area.x + offset, area.y, size, h)
+                } else {
+                    Rect::new(area.x, area.y + offset, area.w, size)
+                };
+                offset += size;
+                (i, rect)
+            })
+            .collect()
+    }
+
+    /// Bounds of a floating panel, kept inside `bounds`
+    pub fn float_rect(&self, index: usize, bounds: Rect) -> Rect {
+        let (x, y, w, h) = self.panels[index].float_rect;
+        let w = w.max(MIN_FLOAT_SIZE.0).min(bounds.w);
+        let h = if self.panels[index].collapsed {
+            COLLAPSED_PANEL_HEIGHT
+        } else {
+            h.max(MIN_FLOAT_SIZE.1).min(bounds.h)
+        };
+        Rect::new(
+            x.clamp(bounds.x, bounds.right() - w),
+            y.clamp(bounds.y, bounds.bottom() - h),
+            w,
+            h,
+        )
+    }
+
+    /// Is the point over a floating panel? (docked panels below shouldn't get the click)
+    pub fn floating_contains(&self, bounds: Rect, x: f32, y: f32) -> bool {
+        self.on_edge(DockEdge::Floating).into_iter()
+            .any(|i| self.float_rect(i, bounds).contains(x, y))
+    }
+
+    /// Edge a panel dropped at (x, y) docks to
+    pub fn drop_edge(bounds: Rect, x: f32, y: f32) -> DockEdge {
+        if x < bounds.x + bounds.w * EDGE_ZONE {
+            DockEdge::Left
+        } else if x > bounds.right() - bounds.w * EDGE_ZONE {
+            DockEdge::Right
+        } else if y > bounds.bottom() - bounds.h * BOTTOM_ZONE {
+            DockEdge::Bottom
+        } else {
+            DockEdge::Floating
+        }
+    }
+
+    /// Move a panel to the end of `edge`
+    pub fn dock(&mut self, index: usize, edge: DockEdge) {
+        let mut panel = self.panels.remove(index);
+        if edge != DockEdge::Floating {
+            panel.home = edge;
+        }
+        panel.edge = edge;
+        self.panels.push(panel);
+    }
+
+    /// Draw a panel's header (and background) at `rect` and track presses on it.
+    /// Returns the content rect, or None when collapsed.
+    pub fn draw_header(
+        &mut self,
+        ctx: &mut UiContext,
+        index: usize,
+        rect: Rect,
+        title: &str,
+        icon_font: Option<&Font>,
+    ) -> Option<Rect> {
+        let panel = &self.panels[index];
+        let floating = panel.edge == DockEdge::Floating;
+        let bg = Color::from_rgba(35, 35, 40, 255);
+        // Header clicks are handled below (on release), not by the collapsible panel
+        let (_, content) = draw_collapsible_panel(ctx, rect, title, panel.collapsed, bg);
+        if floating {
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, Color::from_rgba(100, 150, 255, 255));
+        }
+
+        // Pop out / pin back button
+        let btn_size = COLLAPSED_PANEL_HEIGHT - 4.0;
+        let btn_rect = Rect::new(rect.right() - btn_size - 2.0, rect.y + 2.0, btn_size, btn_size);
+        let (btn_icon, tooltip) = if floating {
+            (icon::MAP_PIN, "Dock panel")
+        } else {
+            (icon::MAXIMIZE_2, "Float panel (or drag the header to re-dock)")
+        };
+        if icon_button(ctx, btn_rect, btn_icon, icon_font, tooltip) {
+            if floating {
+                let home = self.panels[index].home;
+                self.dock(index, home);
+            } else {
+                let w = rect.w.max(MIN_FLOAT_SIZE.0);
+                let h = if self.panels[index].collapsed { default_float_rect().3 } else { rect.h };
+                self.panels[index].float_rect = (rect.x + 24.0, rect.y + 24.0, w, h);
+                self.dock(index, DockEdge::Floating);
+            }
+            return content;
+        }
+
+        let header = Rect::new(rect.x, rect.y, rect.w - btn_size - 4.0, COLLAPSED_PANEL_HEIGHT);
+        let grip = Rect::new(rect.right() - RESIZE_GRIP, rect.bottom() - RESIZE_GRIP, RESIZE_GRIP, RESIZE_GRIP);
+        let can_resize = floating && content.is_some();
+        if can_resize {
+            draw_triangle(
+                macroquad::math::Vec2::new(grip.right(), grip.y),
+                macroquad::math::Vec2::new(grip.right(), grip.bottom()),
+                macroquad::math::Vec2::new(grip.x, grip.bottom()),
+                Color::from_rgba(90, 90, 100, 255),
+            );
+        }
+        let on_header = ctx.mouse.clicked(&header);
+        let on_grip = can_resize && ctx.mouse.clicked(&grip);
+        if (on_header || on_grip) && ctx.dragging.is_none() && !ctx.is_modal_active() {
+            let (mx, my) = (ctx.mouse.x, ctx.mouse.y);
+            let grab = if on_grip { (rect.w - (mx - rect.x), rect.h - (my - rect.y)) } else { (mx - rect.x, my - rect.y) };
+            self.drag = Some(DockDrag { panel: index, start: (mx, my), grab, moved: on_grip, resize: on_grip });
+            ctx.start_drag(self.id);
+        }
+        content
+    }
+
+    /// Apply the header drag in progress. Call after all panels are drawn;
+    /// `bounds` is the area panels dock into.
+    pub fn finish(&mut self, ctx: &mut UiContext, bounds: Rect) {
+        let Some(mut drag) = self.drag else {
+            return;
+        };
+        if drag.panel >= self.panels.len() {
+            self.drag = None;
+            return;
+        }
+        let (mx, my) = (ctx.mouse.x, ctx.mouse.y);
+        if !drag.moved && ((mx - drag.start.0).abs() > DRAG_THRESHOLD || (my - drag.start.1).abs() > DRAG_THRESHOLD) {
+            drag.moved = true;
+        }
+        let floating = self.panels[drag.panel].edge == DockEdge::Floating;
+
+        if ctx.mouse.left_down {
+            if drag.moved {
+                let panel = &mut self.panels[drag.panel];
+                if drag.resize {
+                    let (x, y, _, _) = panel.float_rect;
+                    panel.float_rect.2 = (mx + drag.grab.0 - x).max(MIN_FLOAT_SIZE.0);
+                    panel.float_rect.3 = (my + drag.grab.1 - y).max(MIN_FLOAT_SIZE.1);
+                } else {
+                    if floating {
+                        panel.float_rect.0 = mx - drag.grab.0;
+                        panel.float_rect.1 = my - drag.grab.1;
+                    }
+                    draw_drop_preview(bounds, Self::drop_edge(bounds, mx, my), &panel.key);
+                }
+            }
+            self.drag = Some(drag);
+            return;
+        }
+
+        // Released
+        self.drag = None;
+        if !drag.moved {
+            let panel = &mut self.panels[drag.panel];
+            panel.collapsed = !panel.collapsed;
+        } else if !drag.resize {
+            let edge = Self::drop_edge(bounds, mx, my);
+            if edge == DockEdge::Floating && !floating {
+                let (_, _, w, h) = self.panels[drag.panel].float_rect;
+                self.panels[drag.panel].float_rect = (mx - drag.grab.0, my - drag.grab.1, w, h);
+            }
+            if edge != self.panels[drag.panel].edge {
+                self.dock(drag.panel, edge);
+            }
+        }
+    }
+}
+
+/// Highlight where a dragged panel will land
+fn draw_drop_preview(bounds: Rect, edge: DockEdge, key: &str) {
+    let zone = match edge {
+        DockEdge::Left => Rect::new(bounds.x, bounds.y, bounds.w * EDGE_ZONE, bounds.h),
+        DockEdge::Right => Rect::new(bounds.right() - bounds.w * EDGE_ZONE, bounds.y, bounds.w * EDGE_ZONE, bounds.h),
+        DockEdge::Bottom => Rect::new(bounds.x, bounds.bottom() - bounds.h * BOTTOM_ZONE, bounds.w, bounds.h * BOTTOM_ZONE),
+        DockEdge::Floating => {
+            let (mx, my) = mouse_position();
+            Rect::new(mx - 60.0, my - 10.0, 120.0, COLLAPSED_PANEL_HEIGHT)
+        }
+    };
+    draw_rectangle(zone.x, zone.y, zone.w, zone.h, Color::from_rgba(100, 150, 255, 50));
+    draw_rectangle_lines(zone.x, zone.y, zone.w, zone.h, 2.0, Color::from_rgba(100, 150, 255, 200));
+    let label = match edge {
+        DockEdge::Floating => format!("Float {}", key),
+        _ => format!("Dock {} {:?}", key, edge).to_lowercase(),
+    };
+    draw_text(&label, zone.x + 6.0, zone.y + 14.0, 14.0, WHITE);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> DockLayout {
+        DockLayout::new(1, vec![
+            PanelDock::new("a", DockEdge::Left),
+            PanelDock::new("b", DockEdge::Left).collapsed(),
+            PanelDock::new("c", DockEdge::Left),
+            PanelDock::new("d", DockEdge::Right),
+        ])
+    }
+
+    #[test]
+    fn stack_gives_collapsed_panels_their_header() {
+        let dock = layout();
+        let rects = dock.stack(DockEdge::Left, Rect::new(0.0, 0.0, 100.0, 420.0));
+        let heights: Vec<f32> = rects.iter().map(|(_, r)| r.h).collect();
+        assert_eq!(heights, vec![200.0, COLLAPSED_PANEL_HEIGHT, 200.0]);
+        assert_eq!(rects[2].1.y, 220.0);
+        assert!(dock.stack(DockEdge::Bottom, Rect::new(0.0, 0.0, 100.0, 100.0)).is_empty());
+    }
+
+    #[test]
+    fn drop_zones_follow_edges() {
+        let bounds = Rect::new(0.0, 0.0, 1000.0, 500.0);
+        assert_eq!(DockLayout::drop_edge(bounds, 10.0, 250.0), DockEdge::Left);
+        assert_eq!(DockLayout::drop_edge(bounds, 990.0, 490.0), DockEdge::Right);
+        assert_eq!(DockLayout::drop_edge(bounds, 500.0, 490.0), DockEdge::Bottom);
+        assert_eq!(DockLayout::drop_edge(bounds, 500.0, 250.0), DockEdge::Floating);
+    }
+
+    #[test]
+    fn restore_keeps_known_panels_and_fills_missing() {
+        let mut dock = layout();
+        dock.dock(0, DockEdge::Bottom);
+        let saved = vec![PanelDock::new("d", DockEdge::Floating), PanelDock::new("gone", DockEdge::Left)];
+        dock.restore(&saved);
+        let keys: Vec<&str> = dock.panels.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(keys, vec!["d", "a", "b", "c"]);
+        assert_eq!(dock.panels[0].edge, DockEdge::Floating);
+        assert_eq!(dock.panels[1].edge, DockEdge::Left);
+
+        dock.reset();
+        assert_eq!(dock.on_edge(DockEdge::Right), vec![3]);
+    }
+
+    #[test]
+    fn floating_rect_stays_on_screen() {
+        let mut dock = layout();
+        dock.panels[3].edge = DockEdge::Floating;
+        dock.panels[3].float_rect = (900.0, -50.0, 300.0, 200.0);
+        let bounds = Rect::new(0.0, 0.0, 1000.0, 500.0);
+        let rect = dock.float_rect(3, bounds);
+        assert_eq!((rect.x, rect.y, rect.w, rect.h), (700.0, 0.0, 300.0, 200.0));
+        assert!(dock.floating_contains(bounds, 800.0, 100.0));
+    }
+}
@@ -4,7 +4,7 @@
 //! Tabs cannot be added or removed - they're always present.
 
 use macroquad::prelude::*;
-use super::{Rect, UiContext};
+use super::{Rect, UiContext, AccessNode, WidgetRole, theme};
 use crate::storage::StorageMode;
 
 /// Actions returned by the tab bar
@@ -20,28 +20,6 @@ pub enum TabBarAction {
     SignOut,
 }
 
-/// Visual style for tab bar
-pub mod style {
-    use macroquad::prelude::Color;
-
-    /// Tab bar background
-    pub const BAR_BG: Color = Color::new(0.12, 0.12, 0.14, 1.0);
-    /// Active tab background
-    pub const TAB_ACTIVE_BG: Color = Color::new(0.18, 0.18, 0.22, 1.0);
-    /// Inactive tab background
-    pub const TAB_INACTIVE_BG: Color = Color::new(0.14, 0.14, 0.16, 1.0);
-    /// Hovered tab background
-    pub const TAB_HOVER_BG: Color = Color::new(0.16, 0.16, 0.20, 1.0);
-    /// Active tab text
-    pub const TAB_ACTIVE_TEXT: Color = Color::new(1.0, 1.0, 1.0, 1.0);
-    /// Inactive tab text
-    pub const TAB_INACTIVE_TEXT: Color = Color::new(0.6, 0.6, 0.65, 1.0);
-    /// Tab border/separator
-    pub const TAB_BORDER: Color = Color::new(0.08, 0.08, 0.10, 1.0);
-    /// Accent color for active tab indicator (cyan like MuseScore)
-    pub const ACCENT: Color = Color::new(0.0, 0.75, 0.9, 1.0);
-}
-
 /// Layout constants
 pub mod layout {
     /// Tab bar height
@@ -96,7 +74,7 @@ pub fn draw_fixed_tabs_with_version(
     version_highlighted: &mut bool,
 ) -> Option<usize> {
     // Draw bar background
-    draw_rectangle(rect.x, rect.y, rect.w, rect.h, style::BAR_BG);
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().panel);
 
     // Bottom border
     draw_rectangle(
@@ -104,7 +82,7 @@ pub fn draw_fixed_tabs_with_version(
         rect.y + rect.h - 1.0,
         rect.w,
         1.0,
-        style::TAB_BORDER,
+        theme().tab_border,
     );
 
     // Draw version at far right if provided
@@ -150,11 +128,11 @@ pub fn draw_fixed_tabs_with_version(
                 // Glow intensity: bright at scanner, fades with distance
                 let glow = (1.0 - distance / 2.0).max(0.0).powf(0.5);
 
-                // Interpolate between gray and cyan based on glow
-                let gray = Color::new(0.4, 0.4, 0.45, 1.0);
-                let r = gray.r + (style::ACCENT.r - gray.r) * glow;
-                let g = gray.g + (style::ACCENT.g - gray.g) * glow;
-                let b = gray.b + (style::ACCENT.b - gray.b) * glow;
+                // Interpolate between dim text and the accent based on glow
+                let (gray, accent) = (theme().text_dim, theme().accent);
+                let r = gray.r + (accent.r - gray.r) * glow;
+                let g = gray.g + (accent.g - gray.g) * glow;
+                let b = gray.b + (accent.b - gray.b) * glow;
                 let char_color = Color::new(r, g, b, 1.0);
 
                 draw_text_ex(
@@ -209,11 +187,11 @@ pub fn draw_fixed_tabs_with_version(
 
         // Determine background color
         let bg_color = if is_active {
-            style::TAB_ACTIVE_BG
+            theme().tab_active_bg
         } else if is_hovered {
-            style::TAB_HOVER_BG
+            theme().tab_hover_bg
         } else {
-            style::TAB_INACTIVE_BG
+            theme().tab_inactive_bg
         };
 
         // Draw tab background
@@ -225,7 +203,7 @@ pub fn draw_fixed_tabs_with_version(
             tab_rect.y + 6.0,
             1.0,
             tab_rect.h - 12.0,
-            style::TAB_BORDER,
+            theme().tab_border,
         );
 
         // Draw active indicator at bottom
@@ -235,15 +213,15 @@ pub fn draw_fixed_tabs_with_version(
                 tab_rect.y + tab_rect.h - layout::INDICATOR_HEIGHT,
                 tab_rect.w,
                 layout::INDICATOR_HEIGHT,
-                style::ACCENT,
+                theme().accent,
             );
         }
 
         // Colors for icon and text
         let content_color = if is_active {
-            style::TAB_ACTIVE_TEXT
+            theme().text_bright
         } else {
-            style::TAB_INACTIVE_TEXT
+            theme().tab_inactive_text
         };
 
         // Calculate vertical center of tab
@@ -284,8 +262,9 @@ pub fn draw_fixed_tabs_with_version(
             },
         );
 
-        // Handle click
-        if ctx.mouse.clicked(&tab_rect) {
+        // Handle click (or keyboard activation)
+        let activated = ctx.describe_widget(AccessNode::new(WidgetRole::Tab, tab.label, tab_rect).checked(is_active));
+        if ctx.mouse.clicked(&tab_rect) || activated {
             clicked_tab = Some(i);
         }
 
@@ -312,7 +291,7 @@ pub fn draw_fixed_tabs_with_auth(
     let mut action = TabBarAction::None;
 
     // Draw bar background
-    draw_rectangle(rect.x, rect.y, rect.w, rect.h, style::BAR_BG);
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().panel);
 
     // Bottom border
     draw_rectangle(
@@ -320,7 +299,7 @@ pub fn draw_fixed_tabs_with_auth(
         rect.y + rect.h - 1.0,
         rect.w,
         1.0,
-        style::TAB_BORDER,
+        theme().tab_border,
     );
 
     // Calculate positions from right edge
@@ -362,10 +341,10 @@ pub fn draw_fixed_tabs_with_auth(
                 let char_dims = measure_text(&char_str, None, ver_font_size as u16, 1.0);
                 let distance = (i as f32 - scanner_pos).abs();
                 let glow = (1.0 - distance / 2.0).max(0.0).powf(0.5);
-                let gray = Color::new(0.4, 0.4, 0.45, 1.0);
-                let r = gray.r + (style::ACCENT.r - gray.r) * glow;
-                let g = gray.g + (style::ACCENT.g - gray.g) * glow;
-                let b = gray.b + (style::ACCENT.b - gray.b) * glow;
+                let (gray, accent) = (theme().text_dim, theme().accent);
+                let r = gray.r + (accent.r - gray.r) * glow;
+                let g = gray.g + (accent.g - gray.g) * glow;
+                let b = gray.b + (accent.b - gray.b) * glow;
                 let char_color = Color::new(r, g, b, 1.0);
 
                 draw_text_ex(
@@ -417,7 +396,7 @@ pub fn draw_fixed_tabs_with_auth(
     let button_border = if is_authenticated {
         Color::new(0.5, 0.5, 0.55, 1.0) // Gray for sign out
     } else {
-        style::ACCENT // Cyan for sign in
+        theme().accent // Accent for sign in
     };
 
     draw_rectangle(button_x, button_y, button_width, button_height, button_bg);
@@ -437,7 +416,8 @@ pub fn draw_fixed_tabs_with_auth(
         },
     );
 
-    if ctx.mouse.clicked(&button_rect) {
+    let activated = ctx.describe_widget(AccessNode::new(WidgetRole::Button, button_text, button_rect));
+    if ctx.mouse.clicked(&button_rect) || activated {
         action = if is_authenticated {
             TabBarAction::SignOut
         } else {
@@ -455,7 +435,7 @@ pub fn draw_fixed_tabs_with_auth(
     };
     let mode_color = match (storage_mode, can_write) {
         (StorageMode::Cloud, _) => Color::new(0.3, 0.8, 0.3, 1.0), // Green for cloud
-        (StorageMode::Local, true) => style::ACCENT,
+        (StorageMode::Local, true) => theme().accent,
         (StorageMode::Local, false) => Color::new(0.6, 0.6, 0.65, 1.0),
     };
 
@@ -491,11 +471,11 @@ pub fn draw_fixed_tabs_with_auth(
             let is_tab_hovered = ctx.mouse.inside(&tab_rect);
 
             let bg_color = if is_active {
-                style::TAB_ACTIVE_BG
+                theme().tab_active_bg
             } else if is_tab_hovered {
-                style::TAB_HOVER_BG
+                theme().tab_hover_bg
             } else {
-                style::TAB_INACTIVE_BG
+                theme().tab_inactive_bg
             };
 
             draw_rectangle(tab_rect.x, tab_rect.y, tab_rect.w, tab_rect.h, bg_color);
@@ -506,7 +486,7 @@ pub fn draw_fixed_tabs_with_auth(
                 tab_rect.y + 6.0,
                 1.0,
                 tab_rect.h - 12.0,
-                style::TAB_BORDER,
+                theme().tab_border,
             );
 
             // Active indicator
@@ -516,14 +496,14 @@ pub fn draw_fixed_tabs_with_auth(
                     tab_rect.y + tab_rect.h - layout::INDICATOR_HEIGHT,
                     tab_rect.w,
                     layout::INDICATOR_HEIGHT,
-                    style::ACCENT,
+                    theme().accent,
                 );
             }
 
             let content_color = if is_active {
-                style::TAB_ACTIVE_TEXT
+                theme().text_bright
             } else {
-                style::TAB_INACTIVE_TEXT
+                theme().tab_inactive_text
             };
 
             let center_y = tab_rect.y + tab_rect.h * 0.5;
@@ -561,8 +541,9 @@ pub fn draw_fixed_tabs_with_auth(
                 },
             );
 
-            // Handle click
-            if ctx.mouse.clicked(&tab_rect) && action == TabBarAction::None {
+            // Handle click (or keyboard activation)
+            let activated = ctx.describe_widget(AccessNode::new(WidgetRole::Tab, tab.label, tab_rect).checked(is_active));
+            if (ctx.mouse.clicked(&tab_rect) || activated) && action == TabBarAction::None {
                 action = TabBarAction::SwitchTab(i);
             }
 
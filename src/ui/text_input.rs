@@ -1,7 +1,7 @@
 //! Text input widget with proper cursor and keyboard handling
 
 use macroquad::prelude::*;
-use super::Rect;
+use super::{Rect, theme};
 
 /// State for a text input field
 #[derive(Debug, Clone)]
@@ -20,6 +20,8 @@ pub struct TextInputState {
     pub last_click_time: f64,
     /// Last click position (byte index) for double-click detection
     pub last_click_pos: usize,
+    /// First visible line (multi-line text areas only)
+    pub scroll_line: usize,
 }
 
 impl TextInputState {
@@ -34,6 +36,7 @@ impl TextInputState {
             focused: true,
             last_click_time: 0.0,
             last_click_pos: 0,
+            scroll_line: 0,
         }
     }
 
@@ -145,6 +148,51 @@ impl TextInputState {
         }
     }
 
+    /// Byte index of the start of the line containing `pos`
+    pub fn line_start(&self, pos: usize) -> usize {
+        self.text[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0)
+    }
+
+    /// Byte index of the end of the line containing `pos` (before the newline)
+    pub fn line_end(&self, pos: usize) -> usize {
+        self.text[pos..].find('\n').map(|i| pos + i).unwrap_or(self.text.len())
+    }
+
+    /// Zero-based line number of the cursor
+    pub fn cursor_line(&self) -> usize {
+        self.text[..self.cursor].matches('\n').count()
+    }
+
+    /// Move cursor up (negative) or down (positive) by lines, keeping the column
+    pub fn move_vertical(&mut self, lines: i32, extend_selection: bool) {
+        let start = self.line_start(self.cursor);
+        let column = self.text[start..self.cursor].chars().count();
+
+        let mut target_start = start;
+        for _ in 0..lines.unsigned_abs() {
+            if lines < 0 {
+                if target_start == 0 {
+                    break;
+                }
+                target_start = self.line_start(target_start - 1);
+            } else {
+                let end = self.line_end(target_start);
+                if end >= self.text.len() {
+                    break;
+                }
+                target_start = end + 1;
+            }
+        }
+
+        let target_end = self.line_end(target_start);
+        let pos = self.text[target_start..target_end]
+            .char_indices()
+            .nth(column)
+            .map(|(i, _)| target_start + i)
+            .unwrap_or(target_end);
+        self.set_cursor(pos, extend_selection);
+    }
+
     /// Select all text
     pub fn select_all(&mut self) {
         self.selection_start = Some(0);
@@ -260,6 +308,11 @@ impl TextInputState {
 
     /// Handle keyboard input, returns true if text changed
     pub fn handle_input(&mut self) -> bool {
+        self.handle_keys(false)
+    }
+
+    /// Handle keyboard input for a single- or multi-line field
+    fn handle_keys(&mut self, multiline: bool) -> bool {
         let old_text = self.text.clone();
         self.blink_timer += get_frame_time();
 
@@ -277,13 +330,45 @@ impl TextInputState {
             self.blink_timer = 0.0;
         }
         if is_key_pressed(KeyCode::Home) {
-            self.move_home(shift);
+            if multiline {
+                let start = self.line_start(self.cursor);
+                self.set_cursor(start, shift);
+            } else {
+                self.move_home(shift);
+            }
             self.blink_timer = 0.0;
         }
         if is_key_pressed(KeyCode::End) {
-            self.move_end(shift);
+            if multiline {
+                let end = self.line_end(self.cursor);
+                self.set_cursor(end, shift);
+            } else {
+                self.move_end(shift);
+            }
             self.blink_timer = 0.0;
         }
+        if multiline {
+            if is_key_pressed(KeyCode::Up) {
+                self.move_vertical(-1, shift);
+            }
+            if is_key_pressed(KeyCode::Down) {
+                self.move_vertical(1, shift);
+            }
+            if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+                // Keep the current line's indentation
+                let start = self.line_start(self.cursor);
+                let indent: String = self.text[start..self.cursor]
+                    .chars()
+                    .take_while(|c| *c == ' ' || *c == '\t')
+                    .collect();
+                self.insert(&format!("\n{}", indent));
+                self.blink_timer = 0.0;
+            }
+            if is_key_pressed(KeyCode::Tab) {
+                self.insert("    ");
+                self.blink_timer = 0.0;
+            }
+        }
 
         // Select all
         if ctrl && is_key_pressed(KeyCode::A) {
@@ -291,6 +376,16 @@ impl TextInputState {
             self.blink_timer = 0.0;
         }
 
+        // Paste (single-line fields take the first line)
+        if ctrl && is_key_pressed(KeyCode::V) {
+            if let Some(pasted) = macroquad::miniquad::window::clipboard_get() {
+                let pasted = pasted.replace('\r', "");
+                let pasted = if multiline { pasted.as_str() } else { pasted.lines().next().unwrap_or_default() };
+                self.insert(pasted);
+                self.blink_timer = 0.0;
+            }
+        }
+
         // Deletion
         if is_key_pressed(KeyCode::Backspace) {
             self.backspace();
@@ -320,12 +415,11 @@ impl TextInputState {
     }
 }
 
-/// Colors for text input
-const INPUT_BG: Color = Color::new(0.12, 0.12, 0.14, 1.0);
-const INPUT_BORDER: Color = Color::new(0.0, 0.75, 0.9, 1.0);
-const INPUT_TEXT: Color = Color::new(0.8, 0.8, 0.85, 1.0);
-const INPUT_SELECTION: Color = Color::new(0.0, 0.5, 0.7, 0.5);
-const INPUT_CURSOR: Color = Color::new(0.9, 0.9, 0.95, 1.0);
+/// Selection highlight: the theme accent, darkened and translucent
+fn selection_color() -> Color {
+    let accent = theme().accent;
+    Color::new(accent.r * 0.7, accent.g * 0.7, accent.b * 0.7, 0.5)
+}
 
 /// Double-click threshold in seconds
 const DOUBLE_CLICK_TIME: f64 = 0.4;
@@ -363,8 +457,8 @@ fn x_to_byte_index(text: &str, text_x: f32, mouse_x: f32, font_size: f32) -> usi
 /// Returns true if the text changed
 pub fn draw_text_input(rect: Rect, state: &mut TextInputState, font_size: f32) -> bool {
     // Draw background
-    draw_rectangle(rect.x, rect.y, rect.w, rect.h, INPUT_BG);
-    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, INPUT_BORDER);
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().panel);
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, theme().accent);
 
     let padding = 8.0;
     let text_x = rect.x + padding;
@@ -411,16 +505,119 @@ pub fn draw_text_input(rect: Rect, state: &mut TextInputState, font_size: f32) -
         let selected_text = &state.text[start..end];
         let start_x = text_x + measure_text(start_text, None, font_size as u16, 1.0).width;
         let sel_width = measure_text(selected_text, None, font_size as u16, 1.0).width;
-        draw_rectangle(start_x, rect.y + 4.0, sel_width, rect.h - 8.0, INPUT_SELECTION);
+        draw_rectangle(start_x, rect.y + 4.0, sel_width, rect.h - 8.0, selection_color());
     }
 
     // Draw text
-    draw_text(&state.text, text_x, text_y, font_size, INPUT_TEXT);
+    draw_text(&state.text, text_x, text_y, font_size, theme().text);
 
     // Draw cursor (blinking)
     if state.focused && (state.blink_timer % 1.0) < 0.5 {
         let cursor_x = text_x + cursor_offset;
-        draw_line(cursor_x, rect.y + 6.0, cursor_x, rect.y + rect.h - 6.0, 1.5, INPUT_CURSOR);
+        draw_line(cursor_x, rect.y + 6.0, cursor_x, rect.y + rect.h - 6.0, 1.5, theme().text_bright);
+    }
+
+    changed
+}
+
+/// Draw a multi-line text area with line numbers and handle input.
+/// Clicking inside focuses it, clicking elsewhere unfocuses it.
+/// `highlight_line` (zero-based) is drawn with a red tint, e.g. for errors.
+/// Returns true if the text changed.
+pub fn draw_text_area(rect: Rect, state: &mut TextInputState, font_size: f32, highlight_line: Option<usize>) -> bool {
+    let line_h = (font_size * 1.25).round();
+    let gutter_w = measure_text("000", None, font_size as u16, 1.0).width + 8.0;
+    let padding = 4.0;
+    let text_x = rect.x + gutter_w + padding;
+    let visible_lines = ((rect.h - padding * 2.0) / line_h).floor().max(1.0) as usize;
+
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, theme().panel);
+    draw_rectangle(rect.x, rect.y, gutter_w, rect.h, Color::new(0.09, 0.09, 0.1, 1.0));
+    let border = if state.focused { theme().accent } else { Color::new(0.3, 0.3, 0.35, 1.0) };
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1.0, border);
+
+    // Focus and cursor placement
+    let (mx, my) = mouse_position();
+    let mouse_in_rect = rect.contains(mx, my);
+    if is_mouse_button_pressed(MouseButton::Left) {
+        state.focused = mouse_in_rect;
+        if mouse_in_rect {
+            let line_count = state.text.matches('\n').count() + 1;
+            let line = (state.scroll_line + ((my - rect.y - padding) / line_h).max(0.0) as usize).min(line_count - 1);
+            let line_start = state.text.split('\n').take(line).map(|l| l.len() + 1).sum::<usize>();
+            let line_end = state.line_end(line_start);
+            let col = x_to_byte_index(&state.text[line_start..line_end], text_x, mx, font_size);
+            let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+            state.set_cursor(line_start + col, shift);
+        }
+    }
+
+    // Mouse wheel scrolling
+    let line_count = state.text.matches('\n').count() + 1;
+    let max_scroll = line_count.saturating_sub(visible_lines);
+    if mouse_in_rect {
+        let wheel = mouse_wheel().1;
+        if wheel > 0.0 {
+            state.scroll_line = state.scroll_line.saturating_sub(3);
+        } else if wheel < 0.0 {
+            state.scroll_line = (state.scroll_line + 3).min(max_scroll);
+        }
+    }
+
+    let changed = if state.focused {
+        let changed = state.handle_keys(true);
+        // Keep the cursor in view while typing/navigating
+        let cursor_line = state.cursor_line();
+        if cursor_line < state.scroll_line {
+            state.scroll_line = cursor_line;
+        } else if cursor_line >= state.scroll_line + visible_lines {
+            state.scroll_line = cursor_line + 1 - visible_lines;
+        }
+        changed
+    } else {
+        false
+    };
+    state.scroll_line = state.scroll_line.min(state.text.matches('\n').count());
+
+    // Draw visible lines
+    let selection = state.selection_range();
+    let cursor_line = state.cursor_line();
+    let mut line_start = 0;
+    for (i, line) in state.text.split('\n').enumerate() {
+        let line_end = line_start + line.len();
+        if i >= state.scroll_line && i < state.scroll_line + visible_lines {
+            let row_y = rect.y + padding + (i - state.scroll_line) as f32 * line_h;
+            let baseline = row_y + line_h * 0.75;
+
+            if highlight_line == Some(i) {
+                draw_rectangle(rect.x + gutter_w, row_y, rect.w - gutter_w, line_h, Color::new(0.5, 0.1, 0.1, 0.35));
+            }
+
+            // Selection overlapping this line
+            if let Some((sel_start, sel_end)) = selection {
+                let a = sel_start.clamp(line_start, line_end);
+                let b = sel_end.clamp(line_start, line_end);
+                if a < b || (sel_start <= line_start && sel_end > line_end) {
+                    let x0 = text_x + measure_text(&line[..a - line_start], None, font_size as u16, 1.0).width;
+                    let mut w = measure_text(&line[a - line_start..b - line_start], None, font_size as u16, 1.0).width;
+                    if sel_end > line_end {
+                        w += font_size * 0.4; // Newline is selected too
+                    }
+                    draw_rectangle(x0, row_y, w, line_h, selection_color());
+                }
+            }
+
+            let number = format!("{}", i + 1);
+            let number_w = measure_text(&number, None, font_size as u16, 1.0).width;
+            draw_text(&number, rect.x + gutter_w - number_w - 4.0, baseline, font_size, Color::new(0.4, 0.4, 0.45, 1.0));
+            draw_text(line, text_x, baseline, font_size, theme().text);
+
+            if state.focused && i == cursor_line && (state.blink_timer % 1.0) < 0.5 {
+                let cursor_x = text_x + measure_text(&state.text[line_start..state.cursor], None, font_size as u16, 1.0).width;
+                draw_line(cursor_x, row_y + 1.0, cursor_x, row_y + line_h - 1.0, 1.5, theme().text_bright);
+            }
+        }
+        line_start = line_end + 1;
     }
 
     changed
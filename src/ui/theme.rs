@@ -1,78 +1,283 @@
 //! UI Theme - Shared colors and styling constants
 //!
-//! Centralized color definitions for consistent look across all editor panels.
+//! Every panel and widget reads its colors from the current [`Theme`]
+//! (`theme()`), so switching presets or the accent color restyles the whole
+//! editor on the next frame. The theme choice is a user setting, saved with
+//! the display settings (see `crate::display`).
+//!
+//! Font sizes and the tracker's column colors stay constant across themes.
 
+use std::cell::Cell;
 use macroquad::prelude::Color;
+use serde::{Deserialize, Serialize};
 
 // =============================================================================
-// Base UI Colors
+// Theme
 // =============================================================================
 
-/// Dark background color
-pub const BG_COLOR: Color = Color::new(0.11, 0.11, 0.13, 1.0);
+/// Colors used by the editor UI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Panel background
+    pub bg: Color,
+    /// Header/toolbar background
+    pub header: Color,
+    /// Tab bar and text field background
+    pub panel: Color,
+    /// Primary text
+    pub text: Color,
+    /// Emphasized text (active tab, text cursor)
+    pub text_bright: Color,
+    /// Dimmed/secondary text
+    pub text_dim: Color,
+    /// Selection, active buttons and highlights
+    pub accent: Color,
 
-/// Header/toolbar background
-pub const HEADER_COLOR: Color = Color::new(0.15, 0.15, 0.18, 1.0);
+    /// Flat button background while hovered
+    pub button_hover: Color,
+    /// Flat button background while pressed
+    pub button_pressed: Color,
+    /// Icon/label color on flat buttons
+    pub icon: Color,
+    /// Icon/label color on hovered flat buttons
+    pub icon_hover: Color,
 
-/// Primary text color
-pub const TEXT_COLOR: Color = Color::new(0.8, 0.8, 0.85, 1.0);
+    /// Dropdown menu background
+    pub dropdown_bg: Color,
+    /// Dropdown menu border
+    pub dropdown_border: Color,
+    /// Dropdown item hover background
+    pub dropdown_hover: Color,
+    /// Dropdown trigger background
+    pub dropdown_trigger_bg: Color,
+    /// Dropdown trigger hover background
+    pub dropdown_trigger_hover: Color,
 
-/// Dimmed/secondary text
-pub const TEXT_DIM: Color = Color::new(0.4, 0.4, 0.45, 1.0);
+    /// Even row background
+    pub row_even: Color,
+    /// Odd row background
+    pub row_odd: Color,
+    /// Beat marker row
+    pub row_beat: Color,
+    /// Highlighted/selected row
+    pub row_highlight: Color,
+    /// Cursor highlight
+    pub cursor: Color,
+    /// Playback position indicator
+    pub playback_row: Color,
 
-// =============================================================================
-// Font Sizes
-// =============================================================================
+    /// Active tab background
+    pub tab_active_bg: Color,
+    /// Inactive tab background
+    pub tab_inactive_bg: Color,
+    /// Hovered tab background
+    pub tab_hover_bg: Color,
+    /// Tab border/separator
+    pub tab_border: Color,
+    /// Inactive tab text
+    pub tab_inactive_text: Color,
+}
 
-/// Header/title text size
-pub const FONT_SIZE_HEADER: f32 = 14.0;
+const fn gray(v: f32) -> Color {
+    Color::new(v, v, v, 1.0)
+}
 
-/// Standard content text size
-pub const FONT_SIZE_CONTENT: f32 = 12.0;
+impl Theme {
+    /// The original editor look
+    pub const DARK: Theme = Theme {
+        bg: Color::new(0.11, 0.11, 0.13, 1.0),
+        header: Color::new(0.15, 0.15, 0.18, 1.0),
+        panel: Color::new(0.12, 0.12, 0.14, 1.0),
+        text: Color::new(0.8, 0.8, 0.85, 1.0),
+        text_bright: gray(1.0),
+        text_dim: Color::new(0.4, 0.4, 0.45, 1.0),
+        accent: Color::new(0.0, 0.75, 0.9, 1.0), // cyan like MuseScore
 
-/// Small/detail text size
-pub const FONT_SIZE_SMALL: f32 = 10.0;
+        button_hover: Color::new(0.196, 0.196, 0.235, 1.0), // ~50, 50, 60
+        button_pressed: Color::new(0.235, 0.235, 0.275, 1.0), // ~60, 60, 70
+        icon: gray(0.706),       // ~180
+        icon_hover: gray(0.863), // ~220
 
-// =============================================================================
-// Dropdown/Menu Colors
-// =============================================================================
+        dropdown_bg: Color::new(0.176, 0.176, 0.196, 1.0), // ~45, 45, 50
+        dropdown_border: gray(0.314),                       // ~80, 80, 80
+        dropdown_hover: Color::new(0.235, 0.314, 0.392, 1.0), // ~60, 80, 100
+        dropdown_trigger_bg: Color::new(0.196, 0.196, 0.216, 1.0), // ~50, 50, 55
+        dropdown_trigger_hover: Color::new(0.235, 0.235, 0.275, 1.0), // ~60, 60, 70
 
-/// Dropdown menu background
-pub const DROPDOWN_BG: Color = Color::new(0.176, 0.176, 0.196, 1.0); // ~45, 45, 50
+        row_even: Color::new(0.13, 0.13, 0.15, 1.0),
+        row_odd: Color::new(0.11, 0.11, 0.13, 1.0),
+        row_beat: Color::new(0.16, 0.14, 0.12, 1.0),
+        row_highlight: Color::new(0.2, 0.25, 0.3, 1.0),
+        cursor: Color::new(0.3, 0.5, 0.8, 0.8),
+        playback_row: Color::new(0.4, 0.2, 0.2, 0.6),
 
-/// Dropdown menu border
-pub const DROPDOWN_BORDER: Color = Color::new(0.314, 0.314, 0.314, 1.0); // ~80, 80, 80
+        tab_active_bg: Color::new(0.18, 0.18, 0.22, 1.0),
+        tab_inactive_bg: Color::new(0.14, 0.14, 0.16, 1.0),
+        tab_hover_bg: Color::new(0.16, 0.16, 0.20, 1.0),
+        tab_border: Color::new(0.08, 0.08, 0.10, 1.0),
+        tab_inactive_text: Color::new(0.6, 0.6, 0.65, 1.0),
+    };
 
-/// Dropdown item hover background
-pub const DROPDOWN_HOVER: Color = Color::new(0.235, 0.314, 0.392, 1.0); // ~60, 80, 100
+    /// Near-black panels for dim rooms and OLED screens
+    pub const DARKER: Theme = Theme {
+        bg: Color::new(0.06, 0.06, 0.07, 1.0),
+        header: Color::new(0.09, 0.09, 0.11, 1.0),
+        panel: Color::new(0.07, 0.07, 0.08, 1.0),
+        text: Color::new(0.78, 0.78, 0.82, 1.0),
+        text_bright: Color::new(0.9, 0.9, 0.95, 1.0),
+        text_dim: Color::new(0.38, 0.38, 0.42, 1.0),
+        accent: Color::new(0.0, 0.75, 0.9, 1.0),
 
-/// Dropdown trigger background
-pub const DROPDOWN_TRIGGER_BG: Color = Color::new(0.196, 0.196, 0.216, 1.0); // ~50, 50, 55
+        button_hover: Color::new(0.13, 0.13, 0.16, 1.0),
+        button_pressed: Color::new(0.17, 0.17, 0.2, 1.0),
+        icon: gray(0.67),
+        icon_hover: gray(0.86),
 
-/// Dropdown trigger hover background
-pub const DROPDOWN_TRIGGER_HOVER: Color = Color::new(0.235, 0.235, 0.275, 1.0); // ~60, 60, 70
+        dropdown_bg: Color::new(0.1, 0.1, 0.11, 1.0),
+        dropdown_border: gray(0.22),
+        dropdown_hover: Color::new(0.15, 0.22, 0.29, 1.0),
+        dropdown_trigger_bg: Color::new(0.12, 0.12, 0.13, 1.0),
+        dropdown_trigger_hover: Color::new(0.17, 0.17, 0.2, 1.0),
 
-// =============================================================================
-// Tracker-specific colors
-// =============================================================================
+        row_even: Color::new(0.08, 0.08, 0.09, 1.0),
+        row_odd: Color::new(0.06, 0.06, 0.07, 1.0),
+        row_beat: Color::new(0.11, 0.09, 0.07, 1.0),
+        row_highlight: Color::new(0.14, 0.18, 0.23, 1.0),
+        cursor: Color::new(0.3, 0.5, 0.8, 0.8),
+        playback_row: Color::new(0.4, 0.18, 0.18, 0.6),
+
+        tab_active_bg: Color::new(0.12, 0.12, 0.15, 1.0),
+        tab_inactive_bg: Color::new(0.08, 0.08, 0.1, 1.0),
+        tab_hover_bg: Color::new(0.1, 0.1, 0.13, 1.0),
+        tab_border: Color::new(0.03, 0.03, 0.04, 1.0),
+        tab_inactive_text: Color::new(0.55, 0.55, 0.6, 1.0),
+    };
+
+    /// Black panels, white text and bright borders for readability
+    pub const HIGH_CONTRAST: Theme = Theme {
+        bg: gray(0.0),
+        header: gray(0.1),
+        panel: gray(0.04),
+        text: gray(1.0),
+        text_bright: gray(1.0),
+        text_dim: gray(0.75),
+        accent: Color::new(1.0, 0.85, 0.0, 1.0),
+
+        button_hover: gray(0.2),
+        button_pressed: gray(0.3),
+        icon: gray(0.9),
+        icon_hover: gray(1.0),
+
+        dropdown_bg: gray(0.06),
+        dropdown_border: gray(0.9),
+        dropdown_hover: Color::new(0.15, 0.3, 0.55, 1.0),
+        dropdown_trigger_bg: gray(0.1),
+        dropdown_trigger_hover: gray(0.2),
+
+        row_even: gray(0.08),
+        row_odd: gray(0.0),
+        row_beat: Color::new(0.2, 0.16, 0.0, 1.0),
+        row_highlight: Color::new(0.15, 0.3, 0.5, 1.0),
+        cursor: Color::new(1.0, 0.85, 0.0, 0.8),
+        playback_row: Color::new(0.7, 0.1, 0.1, 0.7),
+
+        tab_active_bg: gray(0.2),
+        tab_inactive_bg: gray(0.05),
+        tab_hover_bg: gray(0.14),
+        tab_border: gray(0.6),
+        tab_inactive_text: gray(0.8),
+    };
+}
+
+/// Built-in themes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Darker,
+    HighContrast,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 3] = [ThemePreset::Dark, ThemePreset::Darker, ThemePreset::HighContrast];
 
-/// Even row background
-pub const ROW_EVEN: Color = Color::new(0.13, 0.13, 0.15, 1.0);
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThemePreset::Dark => "Dark",
+            ThemePreset::Darker => "Darker",
+            ThemePreset::HighContrast => "High Contrast",
+        }
+    }
 
-/// Odd row background
-pub const ROW_ODD: Color = Color::new(0.11, 0.11, 0.13, 1.0);
+    /// The preset's colors, with its own accent
+    pub fn theme(&self) -> Theme {
+        match self {
+            ThemePreset::Dark => Theme::DARK,
+            ThemePreset::Darker => Theme::DARKER,
+            ThemePreset::HighContrast => Theme::HIGH_CONTRAST,
+        }
+    }
+}
 
-/// Beat marker row
-pub const ROW_BEAT: Color = Color::new(0.16, 0.14, 0.12, 1.0);
+/// Persisted theme choice: a preset plus an optional custom accent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ThemeSettings {
+    #[serde(default)]
+    pub preset: ThemePreset,
+    /// Custom accent color (RGB), None = the preset's accent
+    #[serde(default)]
+    pub accent: Option<[u8; 3]>,
+}
 
-/// Highlighted/selected row
-pub const ROW_HIGHLIGHT: Color = Color::new(0.2, 0.25, 0.3, 1.0);
+impl ThemeSettings {
+    /// Resolve the settings into the colors to draw with
+    pub fn theme(&self) -> Theme {
+        let mut theme = self.preset.theme();
+        if let Some([r, g, b]) = self.accent {
+            theme.accent = Color::from_rgba(r, g, b, 255);
+        }
+        theme
+    }
 
-/// Cursor highlight
-pub const CURSOR_COLOR: Color = Color::new(0.3, 0.5, 0.8, 0.8);
+    /// Accent color as RGB bytes (the custom one, or the preset's)
+    pub fn accent_rgb(&self) -> [u8; 3] {
+        self.accent.unwrap_or_else(|| {
+            let accent = self.preset.theme().accent;
+            [(accent.r * 255.0).round() as u8, (accent.g * 255.0).round() as u8, (accent.b * 255.0).round() as u8]
+        })
+    }
+}
 
-/// Playback position indicator
-pub const PLAYBACK_ROW_COLOR: Color = Color::new(0.4, 0.2, 0.2, 0.6);
+thread_local! {
+    static CURRENT: Cell<Theme> = const { Cell::new(Theme::DARK) };
+}
+
+/// The theme the UI is currently drawn with
+pub fn theme() -> Theme {
+    CURRENT.with(|t| t.get())
+}
+
+/// Switch the UI theme (takes effect on the next draw call)
+pub fn set_theme(theme: Theme) {
+    CURRENT.with(|t| t.set(theme));
+}
+
+// =============================================================================
+// Font Sizes
+// =============================================================================
+
+/// Header/title text size
+pub const FONT_SIZE_HEADER: f32 = 14.0;
+
+/// Standard content text size
+pub const FONT_SIZE_CONTENT: f32 = 12.0;
+
+/// Small/detail text size
+pub const FONT_SIZE_SMALL: f32 = 10.0;
+
+// =============================================================================
+// Tracker column colors
+// =============================================================================
 
 /// Note column color
 pub const NOTE_COLOR: Color = Color::new(0.9, 0.85, 0.5, 1.0);
@@ -85,3 +290,29 @@ pub const VOL_COLOR: Color = Color::new(0.5, 0.7, 0.9, 1.0);
 
 /// Effect column color
 pub const FX_COLOR: Color = Color::new(0.9, 0.5, 0.7, 1.0);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_accent_overrides_preset() {
+        let mut settings = ThemeSettings { preset: ThemePreset::HighContrast, accent: None };
+        assert_eq!(settings.theme(), Theme::HIGH_CONTRAST);
+        assert_eq!(settings.accent_rgb(), [255, 217, 0]);
+
+        settings.accent = Some([255, 0, 128]);
+        let theme = settings.theme();
+        assert_eq!(theme.accent, Color::from_rgba(255, 0, 128, 255));
+        assert_eq!(theme.bg, Theme::HIGH_CONTRAST.bg);
+        assert_eq!(settings.accent_rgb(), [255, 0, 128]);
+    }
+
+    #[test]
+    fn test_set_theme() {
+        assert_eq!(theme(), Theme::DARK);
+        set_theme(ThemePreset::Darker.theme());
+        assert_eq!(theme().bg, Theme::DARKER.bg);
+        set_theme(Theme::DARK);
+    }
+}
@@ -1,7 +1,7 @@
 //! Basic UI widgets
 
 use macroquad::prelude::*;
-use super::{Rect, UiContext, draw_icon_centered};
+use super::{Rect, UiContext, draw_icon_centered, AccessNode, WidgetRole, theme};
 
 // =============================================================================
 // Scrollable List Widget
@@ -19,13 +19,14 @@ pub struct ListColors {
 
 impl Default for ListColors {
     fn default() -> Self {
+        let theme = theme();
         Self {
-            row_even: Color::new(0.13, 0.13, 0.15, 1.0),
-            row_odd: Color::new(0.11, 0.11, 0.13, 1.0),
-            row_selected: ACCENT_COLOR,
-            row_hovered: Color::new(0.20, 0.20, 0.24, 1.0),
-            text_normal: Color::new(0.78, 0.78, 0.78, 1.0),
-            text_selected: WHITE,
+            row_even: theme.row_even,
+            row_odd: theme.row_odd,
+            row_selected: theme.accent,
+            row_hovered: theme.button_hover,
+            text_normal: theme.text,
+            text_selected: theme.text_bright,
         }
     }
 }
@@ -307,7 +308,7 @@ impl Toolbar {
         // Draw background on hover
         let hovered = ctx.mouse.inside(&btn_rect);
         if hovered {
-            draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, Color::from_rgba(60, 60, 70, 255));
+            draw_rectangle(btn_rect.x, btn_rect.y, btn_rect.w, btn_rect.h, theme().button_pressed);
         }
 
         // Draw text
@@ -321,7 +322,8 @@ impl Toolbar {
             ctx.set_tooltip(tooltip, btn_rect.x, btn_rect.bottom());
         }
 
-        (hovered && ctx.mouse.left_pressed, btn_rect)
+        let activated = ctx.describe_widget(AccessNode::new(WidgetRole::MenuButton, accessible_name(tooltip, text), btn_rect));
+        ((hovered && ctx.mouse.left_pressed) || activated, btn_rect)
     }
 
     /// Add a disabled icon button (grayed out, no click, shows tooltip)
@@ -507,9 +509,6 @@ impl Toolbar {
     }
 }
 
-/// Accent color (cyan like MuseScore)
-pub const ACCENT_COLOR: Color = Color::new(0.0, 0.75, 0.9, 1.0);
-
 /// Draw an icon button, returns true if clicked (flat style, no background when inactive)
 pub fn icon_button(ctx: &mut UiContext, rect: Rect, icon: char, icon_font: Option<&Font>, tooltip: &str) -> bool {
     draw_flat_icon_button(ctx, rect, icon, icon_font, tooltip, false)
@@ -532,6 +531,8 @@ pub fn icon_button_disabled(ctx: &mut UiContext, rect: Rect, icon: char, icon_fo
         }
     }
 
+    ctx.describe_widget(AccessNode::new(WidgetRole::Button, tooltip, rect).enabled(false));
+
     // Draw icon dimmed
     let icon_size = (rect.h * 0.55).min(16.0);
     let disabled_color = Color::from_rgba(100, 100, 100, 255);
@@ -543,7 +544,8 @@ pub fn icon_button_danger(ctx: &mut UiContext, rect: Rect, icon: char, icon_font
     let id = ctx.next_id();
     let hovered = ctx.mouse.inside(&rect);
     let pressed = ctx.mouse.clicking(&rect);
-    let clicked = ctx.mouse.clicked(&rect);
+    let activated = ctx.describe_widget(AccessNode::new(WidgetRole::Button, tooltip, rect));
+    let clicked = ctx.mouse.clicked(&rect) || activated;
 
     if hovered {
         ctx.set_hot(id);
@@ -565,7 +567,7 @@ pub fn icon_button_danger(ctx: &mut UiContext, rect: Rect, icon: char, icon_font
     let icon_color = if hovered {
         Color::from_rgba(255, 180, 180, 255)
     } else {
-        Color::from_rgba(180, 180, 180, 255)
+        theme().icon
     };
 
     let icon_size = (rect.h * 0.55).min(16.0);
@@ -579,7 +581,13 @@ fn draw_flat_icon_button(ctx: &mut UiContext, rect: Rect, icon: char, icon_font:
     let id = ctx.next_id();
     let hovered = ctx.mouse.inside(&rect);
     let pressed = ctx.mouse.clicking(&rect);
-    let clicked = ctx.mouse.clicked(&rect);
+    let node = if is_active {
+        AccessNode::new(WidgetRole::Toggle, tooltip, rect).checked(true)
+    } else {
+        AccessNode::new(WidgetRole::Button, tooltip, rect)
+    };
+    let activated = ctx.describe_widget(node);
+    let clicked = ctx.mouse.clicked(&rect) || activated;
 
     if hovered {
         ctx.set_hot(id);
@@ -593,13 +601,13 @@ fn draw_flat_icon_button(ctx: &mut UiContext, rect: Rect, icon: char, icon_font:
     // Draw background only when active or hovered
     if is_active {
         // Cyan rounded rectangle for active state
-        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, ACCENT_COLOR);
+        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, theme().accent);
     } else if pressed {
         // Slight highlight when pressed
-        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, Color::from_rgba(60, 60, 70, 255));
+        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, theme().button_pressed);
     } else if hovered {
         // Subtle hover effect
-        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, Color::from_rgba(50, 50, 60, 255));
+        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, theme().button_hover);
     }
     // No background when inactive and not hovered (flat)
 
@@ -607,9 +615,9 @@ fn draw_flat_icon_button(ctx: &mut UiContext, rect: Rect, icon: char, icon_font:
     let icon_color = if is_active {
         WHITE
     } else if hovered {
-        Color::from_rgba(220, 220, 220, 255)
+        theme().icon_hover
     } else {
-        Color::from_rgba(180, 180, 180, 255)
+        theme().icon
     };
 
     // Draw icon centered
@@ -619,6 +627,11 @@ fn draw_flat_icon_button(ctx: &mut UiContext, rect: Rect, icon: char, icon_font:
     clicked
 }
 
+/// Accessible name for a widget: its tooltip, or its visible text if it has none
+fn accessible_name<'a>(tooltip: &'a str, text: &'a str) -> &'a str {
+    if tooltip.is_empty() { text } else { tooltip }
+}
+
 /// Draw a rounded rectangle (simple approximation using overlapping rects)
 fn draw_rounded_rect(x: f32, y: f32, w: f32, h: f32, r: f32, color: Color) {
     // Main body
@@ -636,7 +649,9 @@ fn draw_arrow_button(ctx: &mut UiContext, rect: Rect, icon_font: Option<&Font>,
     let id = ctx.next_id();
     let hovered = ctx.mouse.inside(&rect);
     let pressed = ctx.mouse.clicking(&rect);
-    let clicked = ctx.mouse.clicked(&rect);
+    let name = if is_left { "Previous" } else { "Next" };
+    let activated = ctx.describe_widget(AccessNode::new(WidgetRole::Button, name, rect));
+    let clicked = ctx.mouse.clicked(&rect) || activated;
 
     if hovered {
         ctx.set_hot(id);
@@ -646,14 +661,14 @@ fn draw_arrow_button(ctx: &mut UiContext, rect: Rect, icon_font: Option<&Font>,
 
     // Draw background on hover/press
     if pressed {
-        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, Color::from_rgba(60, 60, 70, 255));
+        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, theme().button_pressed);
     } else if hovered {
-        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, Color::from_rgba(50, 50, 60, 255));
+        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, theme().button_hover);
     }
 
     // Arrow color
     let arrow_color = if hovered {
-        Color::from_rgba(220, 220, 220, 255)
+        theme().icon_hover
     } else {
         Color::from_rgba(160, 160, 160, 255)
     };
@@ -675,7 +690,9 @@ pub fn letter_button_active(ctx: &mut UiContext, rect: Rect, letter: char, toolt
     let id = ctx.next_id();
     let hovered = ctx.mouse.inside(&rect);
     let pressed = ctx.mouse.clicking(&rect);
-    let clicked = ctx.mouse.clicked(&rect);
+    let name = accessible_name(tooltip, &letter.to_string()).to_string();
+    let activated = ctx.describe_widget(AccessNode::new(WidgetRole::Toggle, &name, rect).checked(is_active));
+    let clicked = ctx.mouse.clicked(&rect) || activated;
 
     if hovered {
         ctx.set_hot(id);
@@ -688,20 +705,20 @@ pub fn letter_button_active(ctx: &mut UiContext, rect: Rect, letter: char, toolt
 
     // Draw background
     if is_active {
-        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, ACCENT_COLOR);
+        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, theme().accent);
     } else if pressed {
-        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, Color::from_rgba(60, 60, 70, 255));
+        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, theme().button_pressed);
     } else if hovered {
-        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, Color::from_rgba(50, 50, 60, 255));
+        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, theme().button_hover);
     }
 
     // Letter color
     let letter_color = if is_active {
         WHITE
     } else if hovered {
-        Color::from_rgba(220, 220, 220, 255)
+        theme().icon_hover
     } else {
-        Color::from_rgba(180, 180, 180, 255)
+        theme().icon
     };
 
     // Draw letter centered
@@ -720,7 +737,8 @@ pub fn text_button(ctx: &mut UiContext, rect: Rect, text: &str, tooltip: &str) -
     let id = ctx.next_id();
     let hovered = ctx.mouse.inside(&rect);
     let pressed = ctx.mouse.clicking(&rect);
-    let clicked = ctx.mouse.clicked(&rect);
+    let activated = ctx.describe_widget(AccessNode::new(WidgetRole::Button, accessible_name(tooltip, text), rect));
+    let clicked = ctx.mouse.clicked(&rect) || activated;
 
     if hovered {
         ctx.set_hot(id);
@@ -733,16 +751,16 @@ pub fn text_button(ctx: &mut UiContext, rect: Rect, text: &str, tooltip: &str) -
 
     // Draw background
     if pressed {
-        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, Color::from_rgba(60, 60, 70, 255));
+        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, theme().button_pressed);
     } else if hovered {
-        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, Color::from_rgba(50, 50, 60, 255));
+        draw_rounded_rect(rect.x, rect.y, rect.w, rect.h, corner_radius, theme().button_hover);
     }
 
     // Text color
     let text_color = if hovered {
-        Color::from_rgba(220, 220, 220, 255)
+        theme().icon_hover
     } else {
-        Color::from_rgba(180, 180, 180, 255)
+        theme().icon
     };
 
     // Draw text centered
@@ -794,7 +812,7 @@ pub fn draw_knob(
     // Colors
     let bg_color = Color::new(0.12, 0.12, 0.15, 1.0);
     let ring_color = Color::new(0.25, 0.25, 0.3, 1.0);
-    let indicator_color = ACCENT_COLOR;
+    let indicator_color = theme().accent;
     let text_color = Color::new(0.8, 0.8, 0.8, 1.0);
     let label_color = Color::new(0.6, 0.6, 0.6, 1.0);
 
@@ -897,7 +915,7 @@ pub fn draw_knob(
 
     // Border when editing
     if is_editing {
-        draw_rectangle_lines(box_x, box_y, box_width, box_height, 1.0, ACCENT_COLOR);
+        draw_rectangle_lines(box_x, box_y, box_width, box_height, 1.0, theme().accent);
     }
 
     // Value text
@@ -985,7 +1003,7 @@ pub fn draw_mini_knob(
     } else {
         Color::new(0.25, 0.25, 0.3, 1.0)
     };
-    let indicator_color = ACCENT_COLOR;
+    let indicator_color = theme().accent;
     let text_color = Color::new(0.7, 0.7, 0.7, 1.0);
 
     // Draw knob body (thinner ring for mini version)
@@ -1130,11 +1148,11 @@ pub fn draw_drag_value_compact_editable(
         Color::from_rgba(40, 40, 45, 255)
     };
     let border_color = if is_editing {
-        ACCENT_COLOR
+        theme().accent
     } else {
         Color::from_rgba(60, 60, 65, 255)
     };
-    let value_color = if is_editing || *is_dragging { ACCENT_COLOR } else { WHITE };
+    let value_color = if is_editing || *is_dragging { theme().accent } else { WHITE };
 
     // Draw background
     draw_rectangle(rect.x, rect.y, rect.w, rect.h, bg_color);
@@ -1157,7 +1175,7 @@ pub fn draw_drag_value_compact_editable(
                 let time = macroquad::time::get_time();
                 if (time * 2.0) as i32 % 2 == 0 {
                     let cursor_x = text_x + text_dims.width + 1.0;
-                    draw_line(cursor_x, rect.y + 3.0, cursor_x, rect.bottom() - 3.0, 1.0, ACCENT_COLOR);
+                    draw_line(cursor_x, rect.y + 3.0, cursor_x, rect.bottom() - 3.0, 1.0, theme().accent);
                 }
 
                 // Handle keyboard input
@@ -1979,6 +1997,17 @@ pub fn draw_three_way_toggle(
     rect: Rect,
     options: [&str; 3],
     selected: usize,
+) -> Option<usize> {
+    draw_pill_toggle(ctx, rect, &options, selected)
+}
+
+/// Draw a pill toggle switch with any number of options (see `draw_three_way_toggle`).
+/// A `selected` out of range highlights nothing.
+pub fn draw_pill_toggle(
+    ctx: &mut UiContext,
+    rect: Rect,
+    options: &[&str],
+    selected: usize,
 ) -> Option<usize> {
     let mut clicked = None;
 
@@ -1990,7 +2019,7 @@ pub fn draw_three_way_toggle(
     draw_rounded_rect_outline(rect.x, rect.y, rect.w, rect.h, corner_radius, 1.0, Color::from_rgba(60, 62, 68, 255));
 
     // Calculate option widths (divide evenly)
-    let option_width = rect.w / 3.0;
+    let option_width = rect.w / options.len().max(1) as f32;
     let padding = 3.0;
 
     for (i, label) in options.iter().enumerate() {
@@ -2069,10 +2098,7 @@ fn draw_rounded_rect_outline(x: f32, y: f32, w: f32, h: f32, r: f32, thickness:
 // Dropdown Menu Widget
 // =============================================================================
 
-use super::theme::{
-    FONT_SIZE_CONTENT, DROPDOWN_BG, DROPDOWN_BORDER, DROPDOWN_HOVER,
-    DROPDOWN_TRIGGER_BG, DROPDOWN_TRIGGER_HOVER, TEXT_COLOR, TEXT_DIM,
-};
+use super::theme::FONT_SIZE_CONTENT;
 use super::icons::icon;
 
 /// State for the dropdown menu system.
@@ -2157,14 +2183,14 @@ pub fn draw_dropdown_trigger(
     icon_font: Option<&Font>,
 ) -> bool {
     let hovered = ctx.mouse.inside(&rect);
-    let bg_color = if hovered { DROPDOWN_TRIGGER_HOVER } else { DROPDOWN_TRIGGER_BG };
+    let bg_color = if hovered { theme().dropdown_trigger_hover } else { theme().dropdown_trigger_bg };
 
     draw_rectangle(rect.x, rect.y, rect.w, rect.h, bg_color);
-    draw_text(current_value, rect.x + 4.0, rect.y + 13.0, FONT_SIZE_CONTENT, TEXT_COLOR);
+    draw_text(current_value, rect.x + 4.0, rect.y + 13.0, FONT_SIZE_CONTENT, theme().text);
 
     // Chevron arrow
     let chevron_rect = Rect::new(rect.right() - 16.0, rect.y, 16.0, rect.h);
-    draw_icon_centered(icon_font, icon::CHEVRON_DOWN, &chevron_rect, 10.0, TEXT_DIM);
+    draw_icon_centered(icon_font, icon::CHEVRON_DOWN, &chevron_rect, 10.0, theme().text_dim);
 
     hovered && ctx.mouse.left_pressed
 }
@@ -2191,8 +2217,8 @@ pub fn begin_dropdown(
     }
 
     // Draw menu background
-    draw_rectangle(menu_rect.x, menu_rect.y, menu_rect.w, menu_rect.h, DROPDOWN_BG);
-    draw_rectangle_lines(menu_rect.x, menu_rect.y, menu_rect.w, menu_rect.h, 1.0, DROPDOWN_BORDER);
+    draw_rectangle(menu_rect.x, menu_rect.y, menu_rect.w, menu_rect.h, theme().dropdown_bg);
+    draw_rectangle_lines(menu_rect.x, menu_rect.y, menu_rect.w, menu_rect.h, 1.0, theme().dropdown_border);
 
     // Check click outside (but not on trigger button)
     let click_outside = ctx.mouse.left_pressed
@@ -2227,7 +2253,7 @@ pub fn dropdown_item(
     let hovered = ctx.mouse.inside(&item_rect);
 
     if hovered {
-        draw_rectangle(item_rect.x, item_rect.y, item_rect.w, item_rect.h, DROPDOWN_HOVER);
+        draw_rectangle(item_rect.x, item_rect.y, item_rect.w, item_rect.h, theme().dropdown_hover);
     }
 
     let mut text_x = item_rect.x + 4.0;
@@ -2235,17 +2261,17 @@ pub fn dropdown_item(
     // Draw icon if provided
     if let Some((icon_char, icon_font)) = icon {
         let icon_rect = Rect::new(item_rect.x + 2.0, item_rect.y + 2.0, 16.0, 16.0);
-        draw_icon_centered(icon_font, icon_char, &icon_rect, 11.0, TEXT_COLOR);
+        draw_icon_centered(icon_font, icon_char, &icon_rect, 11.0, theme().text);
         text_x = item_rect.x + 22.0;
     }
 
     // Draw label
-    let text_color = if is_selected { ACCENT_COLOR } else { TEXT_COLOR };
+    let text_color = if is_selected { theme().accent } else { theme().text };
     draw_text(label, text_x, item_rect.y + 14.0, FONT_SIZE_CONTENT, text_color);
 
     // Draw checkmark for selected item
     if is_selected {
-        draw_text("\u{2713}", item_rect.right() - 18.0, item_rect.y + 14.0, FONT_SIZE_CONTENT, ACCENT_COLOR);
+        draw_text("\u{2713}", item_rect.right() - 18.0, item_rect.y + 14.0, FONT_SIZE_CONTENT, theme().accent);
     }
 
     hovered && ctx.mouse.left_pressed
@@ -0,0 +1,167 @@
+//! Level Atmosphere
+//!
+//! Per-level distance fog and time of day. The fog is classic PS1 depth
+//! cueing: vertex colors blend toward the fog color between `fog_start` and
+//! `fog_end`. Rooms with their own fog enabled keep it; every other room uses
+//! the level's.
+//!
+//! Time of day is a color multiplied into every vertex (bluish at night,
+//! warm at dawn and dusk, neutral at midday), further scaled by a free tint.
+//! Both are applied by the rasterizer through `RasterSettings::tint`.
+
+use serde::{Deserialize, Serialize};
+
+/// Light color over the day: (hour, rgb), wrapping at 24
+const DAY_CYCLE: [(f32, [f32; 3]); 8] = [
+    (0.0, [0.30, 0.34, 0.55]),
+    (5.0, [0.32, 0.35, 0.55]),
+    (6.5, [1.00, 0.72, 0.55]),
+    (9.0, [1.00, 1.00, 1.00]),
+    (16.0, [1.00, 1.00, 1.00]),
+    (18.5, [1.00, 0.62, 0.45]),
+    (20.0, [0.40, 0.38, 0.60]),
+    (24.0, [0.30, 0.34, 0.55]),
+];
+
+/// Hour of day with neutral light
+pub const NOON: f32 = 12.0;
+
+/// Level fog and day/night settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelAtmosphere {
+    /// Level-wide fog (rooms with their own fog override it)
+    #[serde(default)]
+    pub fog_enabled: bool,
+    /// Fog color (RGB 0.0-1.0)
+    #[serde(default = "default_fog_color")]
+    pub fog_color: (f32, f32, f32),
+    /// Camera distance where fog begins
+    #[serde(default = "default_fog_start")]
+    pub fog_start: f32,
+    /// Camera distance where geometry is fully fogged
+    #[serde(default = "default_fog_end")]
+    pub fog_end: f32,
+    /// Hour of day (0-24, 12 = neutral)
+    #[serde(default = "default_time_of_day")]
+    pub time_of_day: f32,
+    /// Extra color multiplied over the time of day (white = none)
+    #[serde(default = "default_tint")]
+    pub tint: (f32, f32, f32),
+}
+
+fn default_fog_color() -> (f32, f32, f32) {
+    (0.5, 0.55, 0.6)
+}
+
+fn default_fog_start() -> f32 {
+    8192.0
+}
+
+fn default_fog_end() -> f32 {
+    24576.0
+}
+
+fn default_time_of_day() -> f32 {
+    NOON
+}
+
+fn default_tint() -> (f32, f32, f32) {
+    (1.0, 1.0, 1.0)
+}
+
+impl Default for LevelAtmosphere {
+    fn default() -> Self {
+        Self {
+            fog_enabled: false,
+            fog_color: default_fog_color(),
+            fog_start: default_fog_start(),
+            fog_end: default_fog_end(),
+            time_of_day: default_time_of_day(),
+            tint: default_tint(),
+        }
+    }
+}
+
+impl LevelAtmosphere {
+    /// True when nothing changes the look (for serde skip_serializing_if)
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Fog as (start, falloff); None when level fog is off
+    pub fn fog_range(&self) -> Option<(f32, f32)> {
+        if !self.fog_enabled {
+            return None;
+        }
+        let start = self.fog_start.max(0.0);
+        Some((start, (self.fog_end - start).max(1.0)))
+    }
+
+    /// Vertex color multiplier: time of day times the tint
+    pub fn light_tint(&self) -> [f32; 3] {
+        let day = day_color(self.time_of_day);
+        let (r, g, b) = self.tint;
+        [day[0] * r, day[1] * g, day[2] * b]
+    }
+
+    /// Time of day as "HH:MM"
+    pub fn clock_label(&self) -> String {
+        let minutes = (self.time_of_day.rem_euclid(24.0) * 60.0).round() as u32 % (24 * 60);
+        format!("{:02}:{:02}", minutes / 60, minutes % 60)
+    }
+}
+
+/// Light color at an hour of day, interpolated between `DAY_CYCLE` keys
+pub fn day_color(hour: f32) -> [f32; 3] {
+    let hour = hour.rem_euclid(24.0);
+    for pair in DAY_CYCLE.windows(2) {
+        let (h0, c0) = pair[0];
+        let (h1, c1) = pair[1];
+        if hour <= h1 {
+            let t = if h1 > h0 { (hour - h0) / (h1 - h0) } else { 0.0 };
+            return [
+                c0[0] + (c1[0] - c0[0]) * t,
+                c0[1] + (c1[1] - c0[1]) * t,
+                c0[2] + (c1[2] - c0[2]) * t,
+            ];
+        }
+    }
+    DAY_CYCLE[0].1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noon_is_neutral_and_night_is_dark() {
+        let atmosphere = LevelAtmosphere::default();
+        assert!(atmosphere.is_default());
+        assert_eq!(atmosphere.light_tint(), [1.0, 1.0, 1.0]);
+
+        let night = day_color(0.0);
+        assert!(night[0] < 0.5 && night[2] > night[0]);
+        assert_eq!(day_color(24.0), day_color(0.0));
+        assert_eq!(day_color(-1.0), day_color(23.0));
+
+        let dusk = day_color(18.5);
+        assert!(dusk[0] > dusk[2]);
+    }
+
+    #[test]
+    fn fog_range_and_clock() {
+        let mut atmosphere = LevelAtmosphere::default();
+        assert_eq!(atmosphere.fog_range(), None);
+        atmosphere.fog_enabled = true;
+        atmosphere.fog_start = 1000.0;
+        atmosphere.fog_end = 5000.0;
+        assert_eq!(atmosphere.fog_range(), Some((1000.0, 4000.0)));
+        atmosphere.fog_end = 500.0;
+        assert_eq!(atmosphere.fog_range(), Some((1000.0, 1.0)));
+
+        atmosphere.time_of_day = 18.5;
+        assert_eq!(atmosphere.clock_label(), "18:30");
+        atmosphere.time_of_day = 24.0;
+        assert_eq!(atmosphere.clock_label(), "00:00");
+    }
+}
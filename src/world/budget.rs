@@ -0,0 +1,305 @@
+//! Content Budgets
+//!
+//! Optional per-level budget presets that emulate real PS1 constraints. With
+//! "Strict PS1" enabled the editor checks the level on save and lists any
+//! violations in the debug panel:
+//!
+//! - Texture memory: unique textures used, sized as the PS1 would store them
+//!   (4/8-bit indexed + CLUT, or 15-bit direct) against 1MB of VRAM
+//! - Triangles per frame: the worst room plus the rooms it sees through
+//!   portals, room geometry and object meshes
+//! - Audio voices: the SPU's 24 hardware voices
+//! - Mirrors: one planar reflection, since each re-renders a room
+//!
+//! Budgets are warnings, not errors: saving always succeeds.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use super::{Direction, Level, TextureRef, MIRROR_BUDGET};
+
+/// Budget preset stored in the level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BudgetPreset {
+    /// No budget checks
+    #[default]
+    Off,
+    /// Real PS1 limits: 1MB VRAM, ~3,500 triangles per frame, 24 voices
+    StrictPs1,
+}
+
+impl BudgetPreset {
+    pub fn is_off(&self) -> bool {
+        *self == BudgetPreset::Off
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BudgetPreset::Off => "Off",
+            BudgetPreset::StrictPs1 => "Strict PS1",
+        }
+    }
+
+    /// Limits for this preset (None when checks are off)
+    pub fn limits(&self) -> Option<ContentBudget> {
+        match self {
+            BudgetPreset::Off => None,
+            BudgetPreset::StrictPs1 => Some(ContentBudget::PS1),
+        }
+    }
+}
+
+/// Limits checked by a budget preset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentBudget {
+    /// Texture memory in bytes (VRAM-equivalent)
+    pub texture_bytes: usize,
+    /// Triangles drawn in one frame
+    pub triangles_per_frame: usize,
+    /// Simultaneous audio voices
+    pub audio_voices: usize,
+    /// Mirror surfaces in the level
+    pub mirrors: usize,
+}
+
+impl ContentBudget {
+    pub const PS1: ContentBudget = ContentBudget {
+        texture_bytes: 1024 * 1024,
+        triangles_per_frame: 3500,
+        audio_voices: 24,
+        mirrors: MIRROR_BUDGET,
+    };
+}
+
+/// Measured usage of a level
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BudgetUsage {
+    /// Bytes of all unique textures the level uses
+    pub texture_bytes: usize,
+    /// Number of unique textures
+    pub texture_count: usize,
+    /// Worst-case triangles per frame
+    pub peak_triangles: usize,
+    /// Room the worst case is seen from
+    pub peak_room: Option<usize>,
+    /// Peak simultaneous audio voices
+    pub audio_voices: usize,
+    /// Distinct mirrors (coplanar mirror faces in a room count once)
+    pub mirrors: usize,
+}
+
+/// Which limit was exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetKind {
+    Textures,
+    Triangles,
+    AudioVoices,
+    Mirrors,
+}
+
+/// One exceeded limit
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetViolation {
+    pub kind: BudgetKind,
+    pub used: usize,
+    pub limit: usize,
+    /// Where it happens (e.g. the worst room), if known
+    pub context: Option<String>,
+}
+
+impl std::fmt::Display for BudgetViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            BudgetKind::Textures => write!(
+                f,
+                "Textures use {} KB of {} KB VRAM",
+                self.used.div_ceil(1024),
+                self.limit / 1024
+            )?,
+            BudgetKind::Triangles => write!(f, "{} triangles per frame (target {})", self.used, self.limit)?,
+            BudgetKind::AudioVoices => write!(f, "{} audio voices (SPU has {})", self.used, self.limit)?,
+            BudgetKind::Mirrors => write!(f, "{} mirrors (limit {})", self.used, self.limit)?,
+        }
+        if let Some(context) = &self.context {
+            write!(f, " - {}", context)?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of checking a level against its budget preset
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BudgetReport {
+    pub usage: BudgetUsage,
+    pub violations: Vec<BudgetViolation>,
+}
+
+impl BudgetReport {
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Compare usage against limits
+pub fn check_budget(usage: &BudgetUsage, budget: &ContentBudget) -> Vec<BudgetViolation> {
+    let mut violations = Vec::new();
+    if usage.texture_bytes > budget.texture_bytes {
+        violations.push(BudgetViolation {
+            kind: BudgetKind::Textures,
+            used: usage.texture_bytes,
+            limit: budget.texture_bytes,
+            context: Some(format!("{} textures", usage.texture_count)),
+        });
+    }
+    if usage.peak_triangles > budget.triangles_per_frame {
+        violations.push(BudgetViolation {
+            kind: BudgetKind::Triangles,
+            used: usage.peak_triangles,
+            limit: budget.triangles_per_frame,
+            context: usage.peak_room.map(|r| format!("seen from room {}", r)),
+        });
+    }
+    if usage.audio_voices > budget.audio_voices {
+        violations.push(BudgetViolation {
+            kind: BudgetKind::AudioVoices,
+            used: usage.audio_voices,
+            limit: budget.audio_voices,
+            context: None,
+        });
+    }
+    if usage.mirrors > budget.mirrors {
+        violations.push(BudgetViolation {
+            kind: BudgetKind::Mirrors,
+            used: usage.mirrors,
+            limit: budget.mirrors,
+            context: None,
+        });
+    }
+    violations
+}
+
+/// VRAM bytes of an indexed texture (pixels at `bits` per pixel plus its CLUT)
+pub fn indexed_texture_bytes(width: usize, height: usize, bits: usize, clut_entries: usize) -> usize {
+    (width * height * bits).div_ceil(8) + clut_entries * 2
+}
+
+/// VRAM bytes of a 15-bit direct color texture
+pub fn direct_texture_bytes(width: usize, height: usize) -> usize {
+    width * height * 2
+}
+
+/// Triangles of one room's geometry (two per floor, ceiling and wall face)
+pub fn room_triangles(room: &super::Room) -> usize {
+    room.iter_sectors()
+        .map(|(_, _, sector)| {
+            let horizontal = sector.floor.is_some() as usize + sector.ceiling.is_some() as usize;
+            let walls: usize = Direction::ALL.iter().map(|&dir| sector.walls(dir).len()).sum();
+            (horizontal + walls) * 2
+        })
+        .sum()
+}
+
+/// Measure a level.
+///
+/// - `texture_bytes`: VRAM size of a referenced texture (None if unknown)
+/// - `object_triangles`: triangles of an object's mesh, by asset id
+/// - `audio_voices`: peak voices of the music/sfx that play with the level
+pub fn measure_level(
+    level: &Level,
+    texture_bytes: impl Fn(&TextureRef) -> Option<usize>,
+    object_triangles: impl Fn(u64) -> usize,
+    audio_voices: usize,
+) -> BudgetUsage {
+    // Unique textures across all faces
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    let mut usage = BudgetUsage { audio_voices, ..Default::default() };
+    let mut count_texture = |tex: &TextureRef, usage: &mut BudgetUsage| {
+        if !tex.is_valid() || !seen.insert((tex.pack.clone(), tex.name.clone())) {
+            return;
+        }
+        if let Some(bytes) = texture_bytes(tex) {
+            usage.texture_bytes += bytes;
+            usage.texture_count += 1;
+        }
+    };
+    for room in &level.rooms {
+        for (_, _, sector) in room.iter_sectors() {
+            for face in sector.floor.iter().chain(sector.ceiling.iter()) {
+                count_texture(&face.texture, &mut usage);
+            }
+            for dir in Direction::ALL {
+                for wall in sector.walls(dir) {
+                    count_texture(&wall.texture, &mut usage);
+                }
+            }
+        }
+    }
+
+    // Per room: its own triangles plus the rooms visible through its portals
+    let own: Vec<usize> = level.rooms.iter()
+        .map(|room| {
+            let objects: usize = room.objects.iter()
+                .filter(|obj| obj.enabled)
+                .map(|obj| object_triangles(obj.asset_id))
+                .sum();
+            room_triangles(room) + objects
+        })
+        .collect();
+    for (idx, room) in level.rooms.iter().enumerate() {
+        let mut visible: Vec<usize> = room.portals.iter()
+            .map(|p| p.target_room)
+            .filter(|&r| r < own.len() && r != idx)
+            .collect();
+        visible.sort_unstable();
+        visible.dedup();
+        let total = own[idx] + visible.iter().map(|&r| own[r]).sum::<usize>();
+        if total > usage.peak_triangles {
+            usage.peak_triangles = total;
+            usage.peak_room = Some(idx);
+        }
+    }
+
+    usage.mirrors = level.mirror_count();
+
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texture_sizes_match_ps1_formats() {
+        // 64x64 4-bit with a 16-color CLUT
+        assert_eq!(indexed_texture_bytes(64, 64, 4, 16), 2048 + 32);
+        // 64x64 8-bit with a 256-color CLUT
+        assert_eq!(indexed_texture_bytes(64, 64, 8, 256), 4096 + 512);
+        assert_eq!(direct_texture_bytes(64, 64), 8192);
+    }
+
+    #[test]
+    fn reports_only_exceeded_limits() {
+        let budget = ContentBudget::PS1;
+        let usage = BudgetUsage {
+            texture_bytes: 2 * 1024 * 1024,
+            texture_count: 40,
+            peak_triangles: 3500,
+            peak_room: Some(2),
+            audio_voices: 30,
+            mirrors: 1,
+        };
+        let violations = check_budget(&usage, &budget);
+        let kinds: Vec<BudgetKind> = violations.iter().map(|v| v.kind).collect();
+        assert_eq!(kinds, vec![BudgetKind::Textures, BudgetKind::AudioVoices]);
+        assert_eq!(violations[0].to_string(), "Textures use 2048 KB of 1024 KB VRAM - 40 textures");
+
+        let two_mirrors = BudgetUsage { mirrors: 2, ..Default::default() };
+        let kinds: Vec<BudgetKind> = check_budget(&two_mirrors, &budget).iter().map(|v| v.kind).collect();
+        assert_eq!(kinds, vec![BudgetKind::Mirrors]);
+    }
+
+    #[test]
+    fn off_preset_has_no_limits() {
+        assert!(BudgetPreset::Off.limits().is_none());
+        assert_eq!(BudgetPreset::StrictPs1.limits(), Some(ContentBudget::PS1));
+    }
+}
@@ -0,0 +1,248 @@
+//! Camera volumes
+//!
+//! Boxes authored in the World Editor (drawn in the 2D grid view, like trigger
+//! volumes) that take the game camera off the player's shoulder while the
+//! player is inside: either a fixed camera or one sliding along a spline rail,
+//! both looking at the player. Classic fixed-angle rooms and corridor dollies.
+//!
+//! Bounds and camera positions are room-relative. The game blends into and
+//! out of a volume's shot over its `blend_time` (see `game::rail_camera`).
+
+use serde::{Serialize, Deserialize};
+use crate::rasterizer::Vec3;
+use super::{snap_trigger_coord, TRIGGER_DEFAULT_HEIGHT, TRIGGER_SNAP};
+
+/// Default seconds to blend into and out of a volume's shot
+pub const CAMERA_DEFAULT_BLEND: f32 = 0.5;
+/// Straight pieces each rail segment is drawn and searched as
+pub const RAIL_SAMPLES_PER_SEGMENT: usize = 8;
+
+fn default_blend_time() -> f32 {
+    CAMERA_DEFAULT_BLEND
+}
+
+/// Where a camera volume puts the camera (room-relative positions)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CameraShot {
+    /// One fixed position
+    Fixed { position: Vec3 },
+    /// A Catmull-Rom spline through `points`; the camera sits on the rail
+    /// point closest to the player
+    Rail { points: Vec<Vec3> },
+}
+
+/// A box that overrides the third-person camera while the player is inside
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraVolume {
+    /// Name shown in the editor
+    pub name: String,
+    /// Minimum corner (room-relative)
+    pub min: Vec3,
+    /// Maximum corner (room-relative)
+    pub max: Vec3,
+    /// Camera placement while inside
+    pub shot: CameraShot,
+    /// Seconds to blend in on enter and back out on exit (0 = cut)
+    #[serde(default = "default_blend_time")]
+    pub blend_time: f32,
+}
+
+impl CameraVolume {
+    /// Volume spanning two room-relative floor-plan corners (any order),
+    /// snapped to the trigger grid, from the room's base up to the default
+    /// ceiling height. The camera starts fixed at the top of the first corner.
+    pub fn from_corners(name: impl Into<String>, a: (f32, f32), b: (f32, f32)) -> Self {
+        let (x0, x1) = (snap_trigger_coord(a.0.min(b.0)), snap_trigger_coord(a.0.max(b.0)));
+        let (z0, z1) = (snap_trigger_coord(a.1.min(b.1)), snap_trigger_coord(a.1.max(b.1)));
+        Self {
+            name: name.into(),
+            min: Vec3::new(x0, 0.0, z0),
+            max: Vec3::new(x1.max(x0 + TRIGGER_SNAP), TRIGGER_DEFAULT_HEIGHT, z1.max(z0 + TRIGGER_SNAP)),
+            shot: CameraShot::Fixed { position: Vec3::new(x0, TRIGGER_DEFAULT_HEIGHT, z0) },
+            blend_time: CAMERA_DEFAULT_BLEND,
+        }
+    }
+
+    /// World-space bounds for a room at `room_position`
+    pub fn world_bounds(&self, room_position: Vec3) -> (Vec3, Vec3) {
+        (room_position + self.min, room_position + self.max)
+    }
+
+    /// World-space center
+    pub fn world_center(&self, room_position: Vec3) -> Vec3 {
+        room_position + (self.min + self.max) * 0.5
+    }
+
+    /// Is a world-space point inside? (room at `room_position`)
+    pub fn contains(&self, room_position: Vec3, point: Vec3) -> bool {
+        let (min, max) = self.world_bounds(room_position);
+        point.x >= min.x && point.x <= max.x
+            && point.y >= min.y && point.y <= max.y
+            && point.z >= min.z && point.z <= max.z
+    }
+
+    /// Size in world units
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// Is the shot a rail?
+    pub fn is_rail(&self) -> bool {
+        matches!(self.shot, CameraShot::Rail { .. })
+    }
+
+    /// Room-relative camera positions: the fixed position, or the rail's points
+    pub fn shot_points(&self) -> &[Vec3] {
+        match &self.shot {
+            CameraShot::Fixed { position } => std::slice::from_ref(position),
+            CameraShot::Rail { points } => points,
+        }
+    }
+
+    /// Room-relative polyline along the rail (the fixed position alone for fixed shots)
+    pub fn rail_samples(&self) -> Vec<Vec3> {
+        let points = self.shot_points();
+        if points.len() < 2 {
+            return points.to_vec();
+        }
+        let segments = points.len() - 1;
+        let count = segments * RAIL_SAMPLES_PER_SEGMENT;
+        (0..=count).map(|i| catmull_rom(points, i as f32 / count as f32)).collect()
+    }
+
+    /// World-space camera position for a player at `target`, or None for a
+    /// rail without points
+    pub fn camera_position(&self, room_position: Vec3, target: Vec3) -> Option<Vec3> {
+        let samples = self.rail_samples();
+        let local = target - room_position;
+        let first = *samples.first()?;
+        if samples.len() == 1 {
+            return Some(room_position + first);
+        }
+        let mut best = (f32::INFINITY, first);
+        for pair in samples.windows(2) {
+            let point = closest_on_segment(pair[0], pair[1], local);
+            let dist = (point - local).len();
+            if dist < best.0 {
+                best = (dist, point);
+            }
+        }
+        Some(room_position + best.1)
+    }
+
+    /// Move by a room-relative offset (bounds and camera positions)
+    pub fn translate(&mut self, offset: Vec3) {
+        self.min = self.min + offset;
+        self.max = self.max + offset;
+        match &mut self.shot {
+            CameraShot::Fixed { position } => *position = *position + offset,
+            CameraShot::Rail { points } => {
+                for point in points {
+                    *point = *point + offset;
+                }
+            }
+        }
+    }
+}
+
+/// Point on a Catmull-Rom spline through `points` at `t` (0 = first point,
+/// 1 = last). End points are repeated so the curve passes through them.
+pub fn catmull_rom(points: &[Vec3], t: f32) -> Vec3 {
+    match points.len() {
+        0 => return Vec3::ZERO,
+        1 => return points[0],
+        _ => {}
+    }
+    let segments = points.len() - 1;
+    let scaled = t.clamp(0.0, 1.0) * segments as f32;
+    let seg = (scaled.floor() as usize).min(segments - 1);
+    let u = scaled - seg as f32;
+
+    let p0 = points[seg.saturating_sub(1)];
+    let p1 = points[seg];
+    let p2 = points[seg + 1];
+    let p3 = points[(seg + 2).min(segments)];
+
+    let u2 = u * u;
+    let u3 = u2 * u;
+    (p1 * 2.0
+        + (p2 - p0) * u
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * u2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * u3)
+        * 0.5
+}
+
+fn closest_on_segment(a: Vec3, b: Vec3, point: Vec3) -> Vec3 {
+    let ab = b - a;
+    let len_sq = ab.dot(ab);
+    if len_sq <= f32::EPSILON {
+        return a;
+    }
+    let t = ((point - a).dot(ab) / len_sq).clamp(0.0, 1.0);
+    a + ab * t
+}
+
+/// Next unused `cameraN` name in a level
+pub fn next_camera_name<'a>(existing: impl Iterator<Item = &'a CameraVolume>) -> String {
+    let highest = existing
+        .filter_map(|c| c.name.strip_prefix("camera")?.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("camera{}", highest + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::SECTOR_SIZE;
+
+    #[test]
+    fn test_from_corners_starts_fixed_above_a_corner() {
+        let c = CameraVolume::from_corners("hall", (SECTOR_SIZE * 2.1, 10.0), (0.0, SECTOR_SIZE * 0.9));
+        assert_eq!((c.min.x, c.max.x, c.max.z), (0.0, SECTOR_SIZE * 2.0, SECTOR_SIZE));
+        assert!(!c.is_rail());
+        let pos = c.camera_position(Vec3::ZERO, Vec3::new(500.0, 0.0, 500.0)).unwrap();
+        assert_eq!((pos.x, pos.y, pos.z), (0.0, TRIGGER_DEFAULT_HEIGHT, 0.0));
+
+        let room = Vec3::new(SECTOR_SIZE * 4.0, 0.0, 0.0);
+        assert!(c.contains(room, Vec3::new(SECTOR_SIZE * 4.5, 10.0, SECTOR_SIZE * 0.5)));
+        assert!(!c.contains(Vec3::ZERO, Vec3::new(SECTOR_SIZE * 4.5, 10.0, SECTOR_SIZE * 0.5)));
+    }
+
+    #[test]
+    fn test_spline_passes_through_points() {
+        let points = [Vec3::new(0.0, 0.0, 0.0), Vec3::new(1000.0, 0.0, 0.0), Vec3::new(1000.0, 0.0, 1000.0)];
+        for (t, point) in [(0.0, points[0]), (0.5, points[1]), (1.0, points[2])] {
+            let p = catmull_rom(&points, t);
+            assert!((p - point).len() < 1e-3);
+        }
+        // Between the first two points the curve stays near the straight line
+        let mid = catmull_rom(&points, 0.25);
+        assert!((mid.x - 500.0).abs() < 100.0 && mid.z.abs() < 100.0);
+    }
+
+    #[test]
+    fn test_rail_camera_tracks_the_player() {
+        let mut c = CameraVolume::from_corners("corridor", (0.0, 0.0), (SECTOR_SIZE * 4.0, SECTOR_SIZE));
+        c.shot = CameraShot::Rail { points: vec![Vec3::new(0.0, 2000.0, -500.0), Vec3::new(4096.0, 2000.0, -500.0)] };
+        let room = Vec3::new(1000.0, 0.0, 0.0);
+        let pos = c.camera_position(room, Vec3::new(1000.0 + 3000.0, 0.0, 512.0)).unwrap();
+        assert!((pos.x - 4000.0).abs() < 1.0);
+        assert!((pos.y - 2000.0).abs() < 1.0 && (pos.z + 500.0).abs() < 1.0);
+
+        // Off the end of the rail the camera waits at the last point
+        let pos = c.camera_position(room, Vec3::new(9000.0, 0.0, 0.0)).unwrap();
+        assert!((pos.x - 5096.0).abs() < 1.0);
+
+        c.shot = CameraShot::Rail { points: Vec::new() };
+        assert!(c.camera_position(room, Vec3::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_next_camera_name() {
+        let a = CameraVolume::from_corners("camera4", (0.0, 0.0), (1.0, 1.0));
+        let b = CameraVolume::from_corners("lobby", (0.0, 0.0), (1.0, 1.0));
+        assert_eq!(next_camera_name([a, b].iter()), "camera5");
+        assert_eq!(next_camera_name(std::iter::empty()), "camera1");
+    }
+}
@@ -0,0 +1,182 @@
+//! Cutscenes
+//!
+//! A cutscene is a camera flight plus a timeline of events, stored in the
+//! level next to its scripts. The camera path is a list of keys captured from
+//! the World Editor's 3D view; playback runs a Catmull-Rom spline through the
+//! key positions and look targets, so the camera glides instead of cutting
+//! between them. Keys are world-space, since a flight can cross rooms.
+//!
+//! The timeline is source text, one timed event per line (see
+//! `game::cutscene` for the commands and playback). Cutscenes start from a
+//! trigger volume's `cutscene` or a script's `cutscene <name>` command.
+
+use serde::{Serialize, Deserialize};
+use crate::rasterizer::Vec3;
+
+/// Gap between a new camera key and the previous one (seconds)
+pub const CAMERA_KEY_SPACING: f32 = 2.0;
+
+/// A camera position and look target at a point in time
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CameraKey {
+    /// Seconds from the start of the cutscene
+    pub time: f32,
+    pub position: Vec3,
+    /// World-space point the camera looks at
+    pub target: Vec3,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A named camera flight and event timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cutscene {
+    pub name: String,
+    /// Camera keys in time order (none: the camera keeps following the player)
+    #[serde(default)]
+    pub camera: Vec<CameraKey>,
+    /// Timed events as source text
+    #[serde(default)]
+    pub timeline: String,
+    /// Draw black bars above and below the picture while playing
+    #[serde(default = "default_true")]
+    pub letterbox: bool,
+    /// Interact ends the cutscene early
+    #[serde(default = "default_true")]
+    pub skippable: bool,
+}
+
+impl Cutscene {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            camera: Vec::new(),
+            timeline: String::new(),
+            letterbox: true,
+            skippable: true,
+        }
+    }
+
+    /// Append a camera key `CAMERA_KEY_SPACING` after the last one (at 0 for the first)
+    pub fn push_camera_key(&mut self, position: Vec3, target: Vec3) {
+        let time = self.camera.last().map_or(0.0, |k| k.time + CAMERA_KEY_SPACING);
+        self.camera.push(CameraKey { time, position, target });
+    }
+
+    /// Put the keys back in time order (after a key's time was edited)
+    pub fn sort_camera_keys(&mut self) {
+        self.camera.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+
+    /// Time of the last camera key
+    pub fn camera_duration(&self) -> f32 {
+        self.camera.last().map_or(0.0, |k| k.time.max(0.0))
+    }
+
+    /// Camera (position, look target) `time` seconds in. Holds the first key
+    /// before it starts and the last key after it ends; None without keys.
+    pub fn camera_at(&self, time: f32) -> Option<(Vec3, Vec3)> {
+        camera_at(&self.camera, time)
+    }
+}
+
+/// Camera (position, look target) along time-ordered keys at `time`
+pub fn camera_at(keys: &[CameraKey], time: f32) -> Option<(Vec3, Vec3)> {
+    let first = keys.first()?;
+    let last = keys[keys.len() - 1];
+    if time <= first.time || keys.len() == 1 {
+        return Some((first.position, first.target));
+    }
+    if time >= last.time {
+        return Some((last.position, last.target));
+    }
+
+    // Segment i runs from key i to key i + 1; the end keys repeat as tangents
+    let i = keys.windows(2).position(|w| time < w[1].time).unwrap_or(keys.len() - 2);
+    let (k1, k2) = (keys[i], keys[i + 1]);
+    let k0 = if i > 0 { keys[i - 1] } else { k1 };
+    let k3 = keys.get(i + 2).copied().unwrap_or(k2);
+    let span = k2.time - k1.time;
+    let t = if span > f32::EPSILON { (time - k1.time) / span } else { 1.0 };
+    Some((
+        catmull_rom(k0.position, k1.position, k2.position, k3.position, t),
+        catmull_rom(k0.target, k1.target, k2.target, k3.target, t),
+    ))
+}
+
+/// Uniform Catmull-Rom between `p1` (t = 0) and `p2` (t = 1)
+fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+        * 0.5
+}
+
+/// Next unused `cutsceneN` name in a level
+pub fn next_cutscene_name<'a>(existing: impl Iterator<Item = &'a Cutscene>) -> String {
+    let highest = existing
+        .filter_map(|c| c.name.strip_prefix("cutscene")?.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("cutscene{}", highest + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flight() -> Cutscene {
+        let mut cutscene = Cutscene::new("intro");
+        for x in [0.0, 1000.0, 2000.0, 3000.0] {
+            cutscene.push_camera_key(Vec3::new(x, 500.0, 0.0), Vec3::new(x, 0.0, 1000.0));
+        }
+        cutscene
+    }
+
+    #[test]
+    fn test_spline_passes_through_keys_and_holds_ends() {
+        let cutscene = flight();
+        assert_eq!(cutscene.camera_duration(), CAMERA_KEY_SPACING * 3.0);
+        for (i, key) in cutscene.camera.iter().enumerate() {
+            let (position, target) = cutscene.camera_at(key.time).unwrap();
+            assert!((position.x - i as f32 * 1000.0).abs() < 1e-3);
+            assert!((target.z - 1000.0).abs() < 1e-3);
+        }
+        // Evenly spaced keys on a line: the spline moves at constant speed
+        let (halfway, _) = cutscene.camera_at(CAMERA_KEY_SPACING * 1.5).unwrap();
+        assert!((halfway.x - 1500.0).abs() < 1e-3 && (halfway.y - 500.0).abs() < 1e-3);
+        let (before, _) = cutscene.camera_at(-1.0).unwrap();
+        let (after, _) = cutscene.camera_at(100.0).unwrap();
+        assert_eq!((before.x, after.x), (0.0, 3000.0));
+    }
+
+    #[test]
+    fn test_spline_curves_through_a_corner() {
+        let mut cutscene = Cutscene::new("corner");
+        cutscene.push_camera_key(Vec3::new(0.0, 0.0, 0.0), Vec3::ZERO);
+        cutscene.push_camera_key(Vec3::new(1000.0, 0.0, 0.0), Vec3::ZERO);
+        cutscene.push_camera_key(Vec3::new(1000.0, 0.0, 1000.0), Vec3::ZERO);
+        // Rounds the corner instead of following the straight legs
+        let (p, _) = cutscene.camera_at(CAMERA_KEY_SPACING * 0.5).unwrap();
+        assert!(p.z < 0.0);
+        let (p, _) = cutscene.camera_at(CAMERA_KEY_SPACING * 1.5).unwrap();
+        assert!(p.x > 1000.0);
+
+        assert!(Cutscene::new("empty").camera_at(1.0).is_none());
+    }
+
+    #[test]
+    fn test_sort_and_next_name() {
+        let mut cutscene = flight();
+        cutscene.camera[0].time = 10.0;
+        cutscene.sort_camera_keys();
+        assert_eq!(cutscene.camera[3].position.x, 0.0);
+        assert_eq!(next_cutscene_name([cutscene, Cutscene::new("cutscene3")].iter()), "cutscene4");
+        assert_eq!(next_cutscene_name(std::iter::empty()), "cutscene1");
+    }
+}
@@ -0,0 +1,442 @@
+//! Level diff
+//!
+//! Compares two levels (e.g. the open level against its last-saved file, or a
+//! community-submitted fix against the original) and reports:
+//! - rooms added, removed or changed (matched by room id)
+//! - per-sector changes, flagged when only textures differ
+//! - objects added, removed or modified
+//! - texture usage changes across the whole level
+//!
+//! Sectors and objects are compared through their serialized form, so any
+//! field that is saved counts as a change.
+
+use std::collections::BTreeMap;
+use super::{Level, Room, Sector, AssetInstance, TextureRef};
+
+/// How something changed between the base and the compared level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl ChangeKind {
+    pub fn symbol(&self) -> char {
+        match self {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Modified => '~',
+        }
+    }
+}
+
+/// A room-level change. `room` is the room index in the compared level
+/// (in the base level for removed rooms, whose contents aren't listed).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomChange {
+    pub room: usize,
+    pub room_id: usize,
+    pub kind: ChangeKind,
+    /// Room properties that changed (position, size, ambient, fog, outdoor, portals, triggers, paths, water)
+    pub properties: Vec<&'static str>,
+}
+
+/// A changed sector, in the compared level's room grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectorChange {
+    pub room: usize,
+    pub x: usize,
+    pub z: usize,
+    pub kind: ChangeKind,
+    /// Geometry is identical, only textures changed
+    pub texture_only: bool,
+}
+
+/// An added, removed or modified object
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectChange {
+    pub room: usize,
+    pub sector_x: usize,
+    pub sector_z: usize,
+    pub asset_id: u64,
+    pub name: String,
+    pub kind: ChangeKind,
+}
+
+/// A texture whose usage count changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureChange {
+    pub texture: TextureRef,
+    pub base_uses: usize,
+    pub uses: usize,
+}
+
+impl TextureChange {
+    pub fn kind(&self) -> ChangeKind {
+        match (self.base_uses, self.uses) {
+            (0, _) => ChangeKind::Added,
+            (_, 0) => ChangeKind::Removed,
+            _ => ChangeKind::Modified,
+        }
+    }
+}
+
+/// Differences between a base level and a compared level
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LevelDiff {
+    pub rooms: Vec<RoomChange>,
+    pub sectors: Vec<SectorChange>,
+    pub objects: Vec<ObjectChange>,
+    pub textures: Vec<TextureChange>,
+    /// Level-wide settings that changed (player settings, skybox, weather, atmosphere, scripts, cutscenes)
+    pub settings: Vec<&'static str>,
+}
+
+impl LevelDiff {
+    /// Compare `level` against `base`
+    pub fn compare(base: &Level, level: &Level) -> Self {
+        let mut diff = LevelDiff::default();
+
+        for (idx, room) in level.rooms.iter().enumerate() {
+            match base.rooms.iter().find(|r| r.id == room.id) {
+                Some(base_room) => diff.compare_rooms(idx, base_room, room),
+                None => {
+                    diff.rooms.push(RoomChange { room: idx, room_id: room.id, kind: ChangeKind::Added, properties: Vec::new() });
+                    for (x, z, _) in sectors(room) {
+                        diff.sectors.push(SectorChange { room: idx, x, z, kind: ChangeKind::Added, texture_only: false });
+                    }
+                    for obj in &room.objects {
+                        diff.objects.push(object_change(idx, obj, ChangeKind::Added));
+                    }
+                }
+            }
+        }
+        for (idx, base_room) in base.rooms.iter().enumerate() {
+            if !level.rooms.iter().any(|r| r.id == base_room.id) {
+                diff.rooms.push(RoomChange { room: idx, room_id: base_room.id, kind: ChangeKind::Removed, properties: Vec::new() });
+            }
+        }
+
+        // Texture usage across the whole level
+        let base_uses = texture_uses(base);
+        let uses = texture_uses(level);
+        let mut names: Vec<&String> = base_uses.keys().chain(uses.keys()).collect();
+        names.sort();
+        names.dedup();
+        for key in names {
+            let (b, n) = (base_uses.get(key), uses.get(key));
+            let base_count = b.map(|(_, c)| *c).unwrap_or(0);
+            let count = n.map(|(_, c)| *c).unwrap_or(0);
+            if base_count != count {
+                let texture = n.or(b).map(|(t, _)| t.clone()).unwrap_or_default();
+                diff.textures.push(TextureChange { texture, base_uses: base_count, uses: count });
+            }
+        }
+
+        if ron_string(&base.player_settings) != ron_string(&level.player_settings) {
+            diff.settings.push("player settings");
+        }
+        if ron_string(&base.skybox) != ron_string(&level.skybox) {
+            diff.settings.push("skybox");
+        }
+        if base.weather != level.weather {
+            diff.settings.push("weather");
+        }
+        if base.atmosphere != level.atmosphere {
+            diff.settings.push("atmosphere");
+        }
+        if base.vertex_snap != level.vertex_snap {
+            diff.settings.push("vertex snap");
+        }
+        if ron_string(&base.scripts) != ron_string(&level.scripts) {
+            diff.settings.push("scripts");
+        }
+        if ron_string(&base.cutscenes) != ron_string(&level.cutscenes) {
+            diff.settings.push("cutscenes");
+        }
+        diff
+    }
+
+    fn compare_rooms(&mut self, idx: usize, base: &Room, room: &Room) {
+        let mut properties = Vec::new();
+        let (a, b) = (base.position, room.position);
+        if (a.x, a.y, a.z) != (b.x, b.y, b.z) {
+            properties.push("position");
+        }
+        if base.width != room.width || base.depth != room.depth {
+            properties.push("size");
+        }
+        if base.ambient != room.ambient || base.ambient_color != room.ambient_color {
+            properties.push("ambient");
+        }
+        if ron_string(&base.fog) != ron_string(&room.fog) {
+            properties.push("fog");
+        }
+        if base.outdoor != room.outdoor {
+            properties.push("outdoor");
+        }
+        if ron_string(&base.portals) != ron_string(&room.portals) {
+            properties.push("portals");
+        }
+        if ron_string(&base.triggers) != ron_string(&room.triggers) {
+            properties.push("triggers");
+        }
+        if ron_string(&base.cameras) != ron_string(&room.cameras) {
+            properties.push("cameras");
+        }
+        if ron_string(&base.paths) != ron_string(&room.paths) {
+            properties.push("paths");
+        }
+        if ron_string(&base.water) != ron_string(&room.water) {
+            properties.push("water");
+        }
+
+        // Sectors, by grid position
+        let sectors_before = self.sectors.len();
+        for (x, z, sector) in sectors(room) {
+            match base.get_sector(x, z) {
+                None => self.sectors.push(SectorChange { room: idx, x, z, kind: ChangeKind::Added, texture_only: false }),
+                Some(base_sector) => {
+                    if ron_string(base_sector) != ron_string(sector) {
+                        let texture_only = ron_string(&without_textures(base_sector)) == ron_string(&without_textures(sector));
+                        self.sectors.push(SectorChange { room: idx, x, z, kind: ChangeKind::Modified, texture_only });
+                    }
+                }
+            }
+        }
+        for (x, z, _) in sectors(base) {
+            if room.get_sector(x, z).is_none() {
+                self.sectors.push(SectorChange { room: idx, x, z, kind: ChangeKind::Removed, texture_only: false });
+            }
+        }
+
+        // Objects: identical ones cancel out, then same asset on the same
+        // sector pairs up as "modified", the rest are added/removed
+        let objects_before = self.objects.len();
+        let mut removed: Vec<&AssetInstance> = Vec::new();
+        let mut added: Vec<&AssetInstance> = room.objects.iter().collect();
+        for obj in &base.objects {
+            let text = ron_string(obj);
+            match added.iter().position(|o| ron_string(*o) == text) {
+                Some(i) => { added.remove(i); }
+                None => removed.push(obj),
+            }
+        }
+        removed.retain(|old| {
+            let pair = added.iter().position(|o| {
+                o.asset_id == old.asset_id && o.sector_x == old.sector_x && o.sector_z == old.sector_z
+            });
+            match pair {
+                Some(i) => {
+                    self.objects.push(object_change(idx, added.remove(i), ChangeKind::Modified));
+                    false
+                }
+                None => true,
+            }
+        });
+        for obj in removed {
+            self.objects.push(object_change(idx, obj, ChangeKind::Removed));
+        }
+        for obj in added {
+            self.objects.push(object_change(idx, obj, ChangeKind::Added));
+        }
+
+        if !properties.is_empty() || self.sectors.len() > sectors_before || self.objects.len() > objects_before {
+            self.rooms.push(RoomChange { room: idx, room_id: room.id, kind: ChangeKind::Modified, properties });
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rooms.is_empty() && self.textures.is_empty() && self.settings.is_empty()
+    }
+
+    /// Changed sectors in one room of the compared level
+    pub fn sectors_in_room(&self, room: usize) -> impl Iterator<Item = &SectorChange> {
+        self.sectors.iter().filter(move |s| s.room == room)
+    }
+
+    /// Object changes in one room of the compared level
+    pub fn objects_in_room(&self, room: usize) -> impl Iterator<Item = &ObjectChange> {
+        self.objects.iter().filter(move |o| o.room == room)
+    }
+
+    /// One-line summary, e.g. "3 rooms, 12 sectors, 2 objects, 1 texture changed"
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return "No differences".to_string();
+        }
+        let plural = |n: usize, word: &str| format!("{} {}{}", n, word, if n == 1 { "" } else { "s" });
+        let mut parts = vec![plural(self.rooms.len(), "room"), plural(self.sectors.len(), "sector")];
+        if !self.objects.is_empty() {
+            parts.push(plural(self.objects.len(), "object"));
+        }
+        if !self.textures.is_empty() {
+            parts.push(plural(self.textures.len(), "texture"));
+        }
+        format!("{} changed", parts.join(", "))
+    }
+
+    /// Human-readable report, one change per line
+    pub fn report(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for change in &self.rooms {
+            let mut line = format!("{} Room {}", change.kind.symbol(), change.room_id);
+            if change.kind == ChangeKind::Modified {
+                let sectors = self.sectors.iter().filter(|s| s.room == change.room).count();
+                let objects = self.objects.iter().filter(|o| o.room == change.room).count();
+                let mut details: Vec<String> = change.properties.iter().map(|p| p.to_string()).collect();
+                if sectors > 0 {
+                    details.push(format!("{} sectors", sectors));
+                }
+                if objects > 0 {
+                    details.push(format!("{} objects", objects));
+                }
+                line.push_str(&format!(": {}", details.join(", ")));
+            }
+            lines.push(line);
+        }
+        for obj in &self.objects {
+            let name = if obj.name.is_empty() { format!("asset {:016X}", obj.asset_id) } else { obj.name.clone() };
+            lines.push(format!("{} Object {} at ({}, {}) in room {}", obj.kind.symbol(), name, obj.sector_x, obj.sector_z, obj.room));
+        }
+        for tex in &self.textures {
+            lines.push(format!(
+                "{} Texture {}/{}: {} -> {} uses",
+                tex.kind().symbol(), tex.texture.pack, tex.texture.name, tex.base_uses, tex.uses
+            ));
+        }
+        for setting in &self.settings {
+            lines.push(format!("~ {}", setting));
+        }
+        lines
+    }
+}
+
+fn object_change(room: usize, obj: &AssetInstance, kind: ChangeKind) -> ObjectChange {
+    ObjectChange {
+        room,
+        sector_x: obj.sector_x,
+        sector_z: obj.sector_z,
+        asset_id: obj.asset_id,
+        name: obj.name.clone(),
+        kind,
+    }
+}
+
+fn ron_string<T: serde::Serialize>(value: &T) -> String {
+    ron::to_string(value).unwrap_or_default()
+}
+
+/// All sectors of a room with their grid positions
+fn sectors(room: &Room) -> impl Iterator<Item = (usize, usize, &Sector)> {
+    room.sectors.iter().enumerate().flat_map(|(x, column)| {
+        column.iter().enumerate().filter_map(move |(z, s)| s.as_ref().map(|s| (x, z, s)))
+    })
+}
+
+/// Every texture reference in a sector (floor, ceiling, all walls)
+fn sector_textures(sector: &Sector) -> Vec<&TextureRef> {
+    let mut textures = Vec::new();
+    for face in sector.floor.iter().chain(sector.ceiling.iter()) {
+        textures.push(&face.texture);
+        textures.extend(face.texture_2.iter());
+    }
+    let walls = [
+        &sector.walls_north, &sector.walls_east, &sector.walls_south,
+        &sector.walls_west, &sector.walls_nwse, &sector.walls_nesw,
+    ];
+    for wall in walls.into_iter().flatten() {
+        textures.push(&wall.texture);
+    }
+    textures
+}
+
+/// Copy of a sector with every texture cleared (for geometry-only comparison)
+fn without_textures(sector: &Sector) -> Sector {
+    let mut sector = sector.clone();
+    for face in sector.floor.iter_mut().chain(sector.ceiling.iter_mut()) {
+        face.texture = TextureRef::none();
+        face.texture_2 = None;
+    }
+    let walls = [
+        &mut sector.walls_north, &mut sector.walls_east, &mut sector.walls_south,
+        &mut sector.walls_west, &mut sector.walls_nwse, &mut sector.walls_nesw,
+    ];
+    for wall in walls.into_iter().flatten() {
+        wall.texture = TextureRef::none();
+    }
+    sector
+}
+
+/// Faces using each texture, keyed by "pack/name"
+fn texture_uses(level: &Level) -> BTreeMap<String, (TextureRef, usize)> {
+    let mut uses: BTreeMap<String, (TextureRef, usize)> = BTreeMap::new();
+    for room in &level.rooms {
+        for (_, _, sector) in sectors(room) {
+            for tex in sector_textures(sector).into_iter().filter(|t| t.is_valid()) {
+                uses.entry(format!("{}/{}", tex.pack, tex.name))
+                    .or_insert_with(|| (tex.clone(), 0))
+                    .1 += 1;
+            }
+        }
+    }
+    uses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{create_empty_level, HorizontalFace};
+
+    #[test]
+    fn test_identical_levels() {
+        let level = create_empty_level();
+        let diff = LevelDiff::compare(&level, &level);
+        assert!(diff.is_empty());
+        assert_eq!(diff.summary(), "No differences");
+    }
+
+    #[test]
+    fn test_sector_and_texture_changes() {
+        let base = create_empty_level();
+        let mut level = base.clone();
+        let (x, z, _) = sectors(&level.rooms[0]).next().map(|(x, z, s)| (x, z, s.clone())).unwrap();
+
+        // Retexture one floor: texture-only change plus usage counts
+        if let Some(floor) = level.rooms[0].get_sector_mut(x, z).and_then(|s| s.floor.as_mut()) {
+            floor.texture = TextureRef::new("TEST", "new_floor");
+        }
+        let diff = LevelDiff::compare(&base, &level);
+        assert_eq!(diff.sectors, vec![SectorChange { room: 0, x, z, kind: ChangeKind::Modified, texture_only: true }]);
+        assert_eq!(diff.rooms.len(), 1);
+        let added = diff.textures.iter().find(|t| t.texture.name == "new_floor").unwrap();
+        assert_eq!((added.kind(), added.uses), (ChangeKind::Added, 1));
+
+        // Raise it: now a geometry change
+        if let Some(sector) = level.rooms[0].get_sector_mut(x, z) {
+            sector.floor = Some(HorizontalFace::flat(512.0, TextureRef::new("TEST", "new_floor")));
+        }
+        let diff = LevelDiff::compare(&base, &level);
+        assert!(!diff.sectors[0].texture_only);
+    }
+
+    #[test]
+    fn test_object_changes() {
+        let base = create_empty_level();
+        let mut level = base.clone();
+        level.rooms[0].objects.push(AssetInstance::new(0, 0, 42));
+        let diff = LevelDiff::compare(&base, &level);
+        assert_eq!(diff.objects.len(), 1);
+        assert_eq!(diff.objects[0].kind, ChangeKind::Added);
+
+        let mut moved = level.clone();
+        moved.rooms[0].objects[0].facing = 1.0;
+        let diff = LevelDiff::compare(&level, &moved);
+        assert_eq!(diff.objects.len(), 1);
+        assert_eq!(diff.objects[0].kind, ChangeKind::Modified);
+
+        let diff = LevelDiff::compare(&level, &base);
+        assert_eq!(diff.objects[0].kind, ChangeKind::Removed);
+    }
+}
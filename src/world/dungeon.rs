@@ -0,0 +1,342 @@
+//! Procedural Dungeon Generator
+//!
+//! Builds a blockout of rooms joined by corridors, meant as a starting point
+//! that gets hand-edited afterwards. Generation works on a flat sector grid:
+//!
+//! 1. Rooms are scattered as non-overlapping rectangles (one sector apart)
+//! 2. A minimum spanning tree over room centers guarantees every room is
+//!    reachable; `loops` extra shortest edges add alternative routes
+//! 3. Each edge is carved as an L-shaped corridor through empty cells
+//!
+//! Every room and corridor becomes its own `Room` with floor, ceiling and
+//! walls on edges that face empty cells. Edges between two regions are left
+//! open, so `Level::recalculate_portals` connects them.
+
+use super::{Direction, Room, TextureRef, SECTOR_SIZE};
+use crate::rasterizer::Vec3;
+
+/// Dungeon generator parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DungeonSettings {
+    /// Same seed, same dungeon
+    pub seed: u64,
+    /// Rooms to place (fewer if they don't fit)
+    pub rooms: usize,
+    /// Room side length range, in sectors
+    pub room_min: usize,
+    pub room_max: usize,
+    /// Corridor width in sectors
+    pub corridor_width: usize,
+    /// Extra connections beyond the spanning tree
+    pub loops: usize,
+    /// Floor to ceiling height
+    pub height: f32,
+}
+
+impl Default for DungeonSettings {
+    fn default() -> Self {
+        Self {
+            seed: 1,
+            rooms: 8,
+            room_min: 3,
+            room_max: 6,
+            corridor_width: 1,
+            loops: 2,
+            height: 2048.0,
+        }
+    }
+}
+
+/// Textures applied to the blockout
+#[derive(Debug, Clone)]
+pub struct DungeonTextures {
+    pub floor: TextureRef,
+    pub ceiling: TextureRef,
+    pub wall: TextureRef,
+}
+
+impl Default for DungeonTextures {
+    fn default() -> Self {
+        Self {
+            floor: TextureRef::new("retro-texture-pack", "FLOOR_1A"),
+            ceiling: TextureRef::new("retro-texture-pack", "FLOOR_1A"),
+            wall: TextureRef::new("retro-texture-pack", "WALL_1A"),
+        }
+    }
+}
+
+/// Cell ownership on the generation grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DungeonCell {
+    Empty,
+    Room(usize),
+    Corridor(usize),
+}
+
+/// Generated layout, before it is turned into rooms
+#[derive(Debug, Clone)]
+pub struct DungeonLayout {
+    pub width: usize,
+    pub depth: usize,
+    /// Cells, row-major by z
+    pub cells: Vec<DungeonCell>,
+    /// Room rectangles (x, z, w, d)
+    pub rooms: Vec<(usize, usize, usize, usize)>,
+    /// Connections between rooms (room indices), one corridor each
+    pub connections: Vec<(usize, usize)>,
+}
+
+/// Small deterministic PRNG (xorshift64*), so seeds behave the same everywhere
+struct DungeonRng(u64);
+
+impl DungeonRng {
+    fn new(seed: u64) -> Self {
+        // Avoid the all-zero state
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform in lo..=hi
+    fn range(&mut self, lo: usize, hi: usize) -> usize {
+        if hi <= lo {
+            return lo;
+        }
+        lo + (self.next_u64() % (hi - lo + 1) as u64) as usize
+    }
+}
+
+impl DungeonLayout {
+    pub fn cell(&self, x: usize, z: usize) -> DungeonCell {
+        self.cells[z * self.width + x]
+    }
+
+    fn set(&mut self, x: usize, z: usize, cell: DungeonCell) {
+        self.cells[z * self.width + x] = cell;
+    }
+
+    /// Number of corridor regions
+    pub fn corridor_count(&self) -> usize {
+        self.connections.len()
+    }
+}
+
+/// Lay out rooms and corridors on a grid
+pub fn generate_dungeon_layout(settings: &DungeonSettings) -> DungeonLayout {
+    let mut rng = DungeonRng::new(settings.seed);
+    let room_min = settings.room_min.max(1);
+    let room_max = settings.room_max.max(room_min);
+    let count = settings.rooms.max(1);
+
+    // Grid big enough that rooms fill roughly a third of it
+    let side = ((count as f32).sqrt().ceil() as usize) * (room_max + 3) + 2;
+    let mut layout = DungeonLayout {
+        width: side,
+        depth: side,
+        cells: vec![DungeonCell::Empty; side * side],
+        rooms: Vec::new(),
+        connections: Vec::new(),
+    };
+
+    // Scatter rooms, keeping a one-sector gap between them
+    let mut attempts = count * 50;
+    while layout.rooms.len() < count && attempts > 0 {
+        attempts -= 1;
+        let w = rng.range(room_min, room_max);
+        let d = rng.range(room_min, room_max);
+        let x = rng.range(1, side - w - 1);
+        let z = rng.range(1, side - d - 1);
+        let overlaps = layout.rooms.iter().any(|&(ox, oz, ow, od)| {
+            x < ox + ow + 1 && ox < x + w + 1 && z < oz + od + 1 && oz < z + d + 1
+        });
+        if overlaps {
+            continue;
+        }
+        let idx = layout.rooms.len();
+        for cz in z..z + d {
+            for cx in x..x + w {
+                layout.set(cx, cz, DungeonCell::Room(idx));
+            }
+        }
+        layout.rooms.push((x, z, w, d));
+    }
+
+    // Candidate edges between all room pairs, shortest first
+    let center = |r: &(usize, usize, usize, usize)| (r.0 + r.2 / 2, r.1 + r.3 / 2);
+    let centers: Vec<(usize, usize)> = layout.rooms.iter().map(center).collect();
+    let mut edges: Vec<(usize, usize, usize)> = Vec::new();
+    for (a, ca) in centers.iter().enumerate() {
+        for (b, cb) in centers.iter().enumerate().skip(a + 1) {
+            edges.push((ca.0.abs_diff(cb.0) + ca.1.abs_diff(cb.1), a, b));
+        }
+    }
+    edges.sort_unstable();
+
+    // Kruskal spanning tree, then the shortest leftovers as loops
+    let mut parent: Vec<usize> = (0..centers.len()).collect();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    let mut leftovers = Vec::new();
+    for &(_, a, b) in &edges {
+        let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+        if ra != rb {
+            parent[ra] = rb;
+            layout.connections.push((a, b));
+        } else {
+            leftovers.push((a, b));
+        }
+    }
+    layout.connections.extend(leftovers.into_iter().take(settings.loops));
+
+    // Carve corridors (L-shaped, random bend order)
+    let width = settings.corridor_width.max(1);
+    let connections = layout.connections.clone();
+    for (corridor, &(a, b)) in connections.iter().enumerate() {
+        let (ax, az) = centers[a];
+        let (bx, bz) = centers[b];
+        let horizontal_first = rng.next_u64() % 2 == 0;
+        let (bend_x, bend_z) = if horizontal_first { (bx, az) } else { (ax, bz) };
+        carve_line(&mut layout, (ax, az), (bend_x, bend_z), width, corridor);
+        carve_line(&mut layout, (bend_x, bend_z), (bx, bz), width, corridor);
+    }
+
+    layout
+}
+
+/// Mark empty cells along a straight run as corridor `id`
+fn carve_line(layout: &mut DungeonLayout, from: (usize, usize), to: (usize, usize), width: usize, id: usize) {
+    let (x0, x1) = (from.0.min(to.0), from.0.max(to.0));
+    let (z0, z1) = (from.1.min(to.1), from.1.max(to.1));
+    let extra = width - 1;
+    for z in z0..=(z1 + extra).min(layout.depth - 1) {
+        for x in x0..=(x1 + extra).min(layout.width - 1) {
+            if layout.cell(x, z) == DungeonCell::Empty {
+                layout.set(x, z, DungeonCell::Corridor(id));
+            }
+        }
+    }
+}
+
+/// Turn a layout into rooms: one per dungeon room, then one per corridor.
+/// Rooms are placed from `origin` and numbered from `first_id`; call
+/// `Level::recalculate_portals` after adding them.
+pub fn build_dungeon_rooms(
+    layout: &DungeonLayout,
+    settings: &DungeonSettings,
+    textures: &DungeonTextures,
+    origin: Vec3,
+    first_id: usize,
+) -> Vec<Room> {
+    let regions: Vec<DungeonCell> = (0..layout.rooms.len())
+        .map(DungeonCell::Room)
+        .chain((0..layout.corridor_count()).map(DungeonCell::Corridor))
+        .collect();
+
+    let mut rooms = Vec::new();
+    for region in regions {
+        // Bounding box of this region's cells
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        for z in 0..layout.depth {
+            for x in 0..layout.width {
+                if layout.cell(x, z) == region {
+                    bounds = Some(match bounds {
+                        None => (x, z, x, z),
+                        Some((x0, z0, x1, z1)) => (x0.min(x), z0.min(z), x1.max(x), z1.max(z)),
+                    });
+                }
+            }
+        }
+        // Corridors fully swallowed by rooms have no cells of their own
+        let Some((x0, z0, x1, z1)) = bounds else { continue };
+
+        let position = Vec3::new(
+            origin.x + x0 as f32 * SECTOR_SIZE,
+            origin.y,
+            origin.z + z0 as f32 * SECTOR_SIZE,
+        );
+        let mut room = Room::new(first_id + rooms.len(), position, x1 - x0 + 1, z1 - z0 + 1);
+        for z in z0..=z1 {
+            for x in x0..=x1 {
+                if layout.cell(x, z) != region {
+                    continue;
+                }
+                let (lx, lz) = (x - x0, z - z0);
+                room.set_floor(lx, lz, 0.0, textures.floor.clone());
+                room.set_ceiling(lx, lz, settings.height, textures.ceiling.clone());
+                for (dir, nx, nz) in [
+                    (Direction::North, x as i64, z as i64 - 1),
+                    (Direction::East, x as i64 + 1, z as i64),
+                    (Direction::South, x as i64, z as i64 + 1),
+                    (Direction::West, x as i64 - 1, z as i64),
+                ] {
+                    let open = nx >= 0
+                        && nz >= 0
+                        && (nx as usize) < layout.width
+                        && (nz as usize) < layout.depth
+                        && layout.cell(nx as usize, nz as usize) != DungeonCell::Empty;
+                    if !open {
+                        room.add_wall(lx, lz, dir, 0.0, settings.height, textures.wall.clone());
+                    }
+                }
+            }
+        }
+        room.recalculate_bounds();
+        rooms.push(room);
+    }
+    rooms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Level;
+
+    #[test]
+    fn same_seed_same_layout() {
+        let settings = DungeonSettings::default();
+        let a = generate_dungeon_layout(&settings);
+        let b = generate_dungeon_layout(&settings);
+        assert_eq!(a.cells, b.cells);
+        let c = generate_dungeon_layout(&DungeonSettings { seed: 2, ..settings });
+        assert_ne!(a.cells, c.cells);
+    }
+
+    #[test]
+    fn spanning_tree_plus_loops() {
+        let settings = DungeonSettings { rooms: 6, loops: 2, ..Default::default() };
+        let layout = generate_dungeon_layout(&settings);
+        let n = layout.rooms.len();
+        assert!(n >= 2);
+        assert_eq!(layout.connections.len(), (n - 1) + 2.min(n * (n - 1) / 2 - (n - 1)));
+    }
+
+    #[test]
+    fn rooms_are_connected_through_portals() {
+        let settings = DungeonSettings { rooms: 5, loops: 0, seed: 7, ..Default::default() };
+        let layout = generate_dungeon_layout(&settings);
+        let mut level = Level::new();
+        level.rooms = build_dungeon_rooms(&layout, &settings, &DungeonTextures::default(), Vec3::ZERO, 0);
+        level.recalculate_portals();
+
+        // Flood fill from room 0 over portals reaches every room
+        let mut seen = vec![false; level.rooms.len()];
+        let mut stack = vec![0];
+        while let Some(r) = stack.pop() {
+            if std::mem::replace(&mut seen[r], true) {
+                continue;
+            }
+            stack.extend(level.rooms[r].portals.iter().map(|p| p.target_room));
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+}
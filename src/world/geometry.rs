@@ -4,7 +4,12 @@
 //! Rooms contain a 2D grid of sectors, each with floor, ceiling, and walls.
 
 use serde::{Serialize, Deserialize};
-use crate::rasterizer::{Vec3, Vec2, Vertex, Face as RasterFace, BlendMode, Color};
+use crate::rasterizer::{Vec3, Vec2, Vertex, Face as RasterFace, BlendMode, Color, Light, VertexSnap};
+use crate::rasterizer::render::shade_multi_light_color;
+use super::{BudgetPreset, CameraVolume, Cutscene, EntityPath, Ladder, LevelAtmosphere, LevelStreaming, LevelWeather, MirrorFace, PathFollow, RoomWater, TriggerVolume, mirror_planes, pick_mirror};
+use crate::game::hud::HudLayout;
+use crate::game::sound::SoundBindings;
+use crate::tracker::MixLevels;
 
 /// TRLE sector size in world units
 pub const SECTOR_SIZE: f32 = 1024.0;
@@ -71,6 +76,27 @@ fn default_true() -> bool { true }
 fn default_neutral_color() -> Color { Color::NEUTRAL }
 fn default_neutral_colors_4() -> [Color; 4] { [Color::NEUTRAL; 4] }
 
+/// Modulate vertex colors by baked light (128 = unchanged, like PS1 vertex colors)
+fn apply_baked_light(colors: &[Color; 4], light: Option<&[Color; 4]>) -> [Color; 4] {
+    let Some(light) = light else {
+        return *colors;
+    };
+    let scale = |c: u8, l: u8| ((c as u32 * l as u32) / 128).min(255) as u8;
+    let mut out = *colors;
+    for (c, l) in out.iter_mut().zip(light) {
+        c.r = scale(c.r, l.r);
+        c.g = scale(c.g, l.g);
+        c.b = scale(c.b, l.b);
+    }
+    out
+}
+
+/// Convert a light level (0.0-1.0 per channel) to baked light (128 = full)
+fn baked_light_color((r, g, b): (f32, f32, f32)) -> Color {
+    let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 128.0).round() as u8;
+    Color::new(to_u8(r), to_u8(g), to_u8(b))
+}
+
 /// Direction for horizontal gradient tint (sun/moon position)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum HorizonDirection {
@@ -1148,6 +1174,12 @@ pub struct HorizontalFace {
     /// If true, pure black pixels (RGB 0,0,0) are treated as transparent (PS1 CLUT-style)
     #[serde(default = "default_true")]
     pub black_transparent: bool,
+    /// Reflects the room about the face's plane while playing, see `world::mirror`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mirror: bool,
+    /// Baked light per corner [NW, NE, SE, SW] (128 = full brightness), see `Room::bake_lighting`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baked_light: Option<[Color; 4]>,
 }
 
 impl HorizontalFace {
@@ -1167,6 +1199,8 @@ impl HorizontalFace {
             blend_mode: BlendMode::Opaque,
             normal_mode: FaceNormalMode::default(),
             black_transparent: true,
+            mirror: false,
+            baked_light: None,
         }
     }
 
@@ -1186,6 +1220,8 @@ impl HorizontalFace {
             blend_mode: BlendMode::Opaque,
             normal_mode: FaceNormalMode::default(),
             black_transparent: true,
+            mirror: false,
+            baked_light: None,
         }
     }
 
@@ -1204,6 +1240,16 @@ impl HorizontalFace {
         self.colors_2.as_ref().unwrap_or(&self.colors)
     }
 
+    /// Triangle 1 vertex colors, with baked light applied if `baked` and present
+    pub fn lit_colors(&self, baked: bool) -> [Color; 4] {
+        apply_baked_light(&self.colors, self.baked_light.as_ref().filter(|_| baked))
+    }
+
+    /// Triangle 2 vertex colors, with baked light applied if `baked` and present
+    pub fn lit_colors_2(&self, baked: bool) -> [Color; 4] {
+        apply_baked_light(self.get_colors_2(), self.baked_light.as_ref().filter(|_| baked))
+    }
+
     /// Get effective heights for triangle 2 (returns heights_2 or falls back to heights)
     pub fn get_heights_2(&self) -> &[f32; 4] {
         self.heights_2.as_ref().unwrap_or(&self.heights)
@@ -1380,6 +1426,12 @@ pub struct VerticalFace {
     /// UV projection mode for sloped walls
     #[serde(default)]
     pub uv_projection: UvProjection,
+    /// Reflects the room about the face's plane while playing, see `world::mirror`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mirror: bool,
+    /// Baked light per corner (128 = full brightness), see `Room::bake_lighting`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baked_light: Option<[Color; 4]>,
 }
 
 impl VerticalFace {
@@ -1416,6 +1468,8 @@ impl VerticalFace {
             normal_mode: FaceNormalMode::default(),
             black_transparent: true,
             uv_projection: UvProjection::default(),
+            mirror: false,
+            baked_light: None,
         }
     }
 
@@ -1434,9 +1488,16 @@ impl VerticalFace {
             normal_mode: FaceNormalMode::default(),
             black_transparent: true,
             uv_projection: UvProjection::Projected,
+            mirror: false,
+            baked_light: None,
         }
     }
 
+    /// Vertex colors with baked light applied if `baked` and present
+    pub fn lit_colors(&self, baked: bool) -> [Color; 4] {
+        apply_baked_light(&self.colors, self.baked_light.as_ref().filter(|_| baked))
+    }
+
     /// Set all vertex colors to the same value (uniform tint)
     pub fn set_uniform_color(&mut self, color: Color) {
         self.colors = [color; 4];
@@ -2075,6 +2136,12 @@ pub enum Direction {
 }
 
 impl Direction {
+    /// All wall directions (4 edges and 2 diagonals)
+    pub const ALL: [Direction; 6] = [
+        Direction::North, Direction::East, Direction::South,
+        Direction::West, Direction::NwSe, Direction::NeSw,
+    ];
+
     /// Get the opposite direction
     pub fn opposite(self) -> Self {
         match self {
@@ -2201,6 +2268,8 @@ pub struct PlayerSettings {
     pub camera_pitch_max: f32,
     /// Camera height offset (legacy, kept for compatibility)
     pub camera_height: f32,
+    /// Maximum distance from the player for lock-on targets
+    pub lock_on_range: f32,
 }
 
 impl Default for PlayerSettings {
@@ -2219,10 +2288,31 @@ impl Default for PlayerSettings {
             camera_pitch_min: -0.8,         // Can look up ~45 degrees
             camera_pitch_max: 0.8,          // Can look down ~45 degrees
             camera_height: 610.0,           // Legacy, kept for compatibility
+            lock_on_range: 12000.0,         // About 12 sectors
         }
     }
 }
 
+impl PlayerSettings {
+    /// Take-off speed of a jump (sprint jumps are higher)
+    pub fn jump_speed(&self, sprint: bool) -> f32 {
+        if sprint { self.jump_velocity * self.sprint_jump_multiplier } else { self.jump_velocity }
+    }
+
+    /// Peak height of a jump above the take-off floor
+    pub fn jump_height(&self, sprint: bool) -> f32 {
+        let v = self.jump_speed(sprint);
+        v * v / (2.0 * self.gravity.max(1.0))
+    }
+
+    /// Horizontal distance of a jump that lands at take-off height
+    /// (walking or sprinting at full speed)
+    pub fn jump_distance(&self, sprint: bool) -> f32 {
+        let speed = if sprint { self.run_speed } else { self.walk_speed };
+        speed * 2.0 * self.jump_speed(sprint) / self.gravity.max(1.0)
+    }
+}
+
 // ============================================================================
 // Asset-Based Object System
 // ============================================================================
@@ -2254,6 +2344,53 @@ impl LightOverride {
     }
 }
 
+/// Per-instance overrides for Enemy component properties (None = asset default)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct EnemyOverride {
+    /// Override starting health
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<i32>,
+    /// Override base damage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub damage: Option<i32>,
+    /// Override patrol radius
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patrol_radius: Option<f32>,
+}
+
+impl EnemyOverride {
+    /// Returns true if any field is overridden
+    pub fn has_any(&self) -> bool {
+        self.health.is_some() || self.damage.is_some() || self.patrol_radius.is_some()
+    }
+}
+
+/// Per-instance overrides for Door component properties (None = asset default)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DoorOverride {
+    /// Override whether the door starts open
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_open: Option<bool>,
+    /// Override the key pickup that unlocks the door (empty = no key needed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_key: Option<String>,
+    /// Override the trigger id that opens the door (empty = Interact only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_trigger: Option<String>,
+}
+
+impl DoorOverride {
+    /// Returns true if any field is overridden
+    pub fn has_any(&self) -> bool {
+        self.start_open.is_some() || self.required_key.is_some() || self.open_trigger.is_some()
+    }
+}
+
+/// An override string where empty means "none"
+fn override_name(value: &str) -> Option<String> {
+    if value.is_empty() { None } else { Some(value.to_string()) }
+}
+
 /// Per-instance component overrides
 ///
 /// Allows overriding specific component properties on a per-instance basis.
@@ -2263,13 +2400,73 @@ pub struct ComponentOverrides {
     /// Light component overrides
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub light: Option<LightOverride>,
-    // Future: add door, enemy, etc. overrides as needed
+    /// Enemy component overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enemy: Option<EnemyOverride>,
+    /// Door component overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub door: Option<DoorOverride>,
 }
 
 impl ComponentOverrides {
     /// Returns true if any overrides are set
     pub fn has_any(&self) -> bool {
         self.light.as_ref().map_or(false, |l| l.has_any())
+            || self.enemy.as_ref().map_or(false, |e| e.has_any())
+            || self.door.as_ref().map_or(false, |d| d.has_any())
+    }
+
+    /// Number of overridden fields (for the properties panel)
+    pub fn count(&self) -> usize {
+        let light = self.light.as_ref().map_or(0, |l| {
+            [l.color.is_some(), l.intensity.is_some(), l.radius.is_some(), l.offset.is_some()]
+                .iter().filter(|&&b| b).count()
+        });
+        let enemy = self.enemy.as_ref().map_or(0, |e| {
+            [e.health.is_some(), e.damage.is_some(), e.patrol_radius.is_some()]
+                .iter().filter(|&&b| b).count()
+        });
+        let door = self.door.as_ref().map_or(0, |d| {
+            [d.start_open.is_some(), d.required_key.is_some(), d.open_trigger.is_some()]
+                .iter().filter(|&&b| b).count()
+        });
+        light + enemy + door
+    }
+
+    /// The asset's component with this instance's overrides applied
+    pub fn apply(&self, component: &crate::asset::AssetComponent) -> crate::asset::AssetComponent {
+        use crate::asset::AssetComponent;
+        let mut result = component.clone();
+        match &mut result {
+            AssetComponent::Light { color, intensity, radius, offset } => {
+                if let Some(o) = &self.light {
+                    *color = o.color.unwrap_or(*color);
+                    *intensity = o.intensity.unwrap_or(*intensity);
+                    *radius = o.radius.unwrap_or(*radius);
+                    *offset = o.offset.unwrap_or(*offset);
+                }
+            }
+            AssetComponent::Enemy { health, damage, patrol_radius, .. } => {
+                if let Some(o) = &self.enemy {
+                    *health = o.health.unwrap_or(*health);
+                    *damage = o.damage.unwrap_or(*damage);
+                    *patrol_radius = o.patrol_radius.unwrap_or(*patrol_radius);
+                }
+            }
+            AssetComponent::Door { start_open, required_key, open_trigger, .. } => {
+                if let Some(o) = &self.door {
+                    *start_open = o.start_open.unwrap_or(*start_open);
+                    if let Some(key) = &o.required_key {
+                        *required_key = override_name(key);
+                    }
+                    if let Some(trigger) = &o.open_trigger {
+                        *open_trigger = override_name(trigger);
+                    }
+                }
+            }
+            _ => {}
+        }
+        result
     }
 
     /// Returns true if no overrides are set (for serde skip_serializing_if)
@@ -2313,6 +2510,9 @@ pub struct AssetInstance {
     /// Per-instance component overrides (light intensity, color, etc.)
     #[serde(default, skip_serializing_if = "ComponentOverrides::is_empty")]
     pub overrides: ComponentOverrides,
+    /// Path this instance follows in play mode (enemies and moving platforms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathFollow>,
 }
 
 impl AssetInstance {
@@ -2327,6 +2527,7 @@ impl AssetInstance {
             name: String::new(),
             enabled: true,
             overrides: ComponentOverrides::default(),
+            path: None,
         }
     }
 
@@ -2444,6 +2645,8 @@ pub struct Room {
     /// Grid depth (number of sectors in Z direction)
     pub depth: usize,
     /// 2D array of sectors [x][z], None = no sector at this position
+    /// (empty in saved v2 files, see `Level::geometry`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub sectors: Vec<Vec<Option<Sector>>>,
     /// Portals to adjacent rooms
     #[serde(default)]
@@ -2454,18 +2657,182 @@ pub struct Room {
     /// Ambient light level (0.0 = dark, 1.0 = bright)
     #[serde(default = "default_ambient")]
     pub ambient: f32,
+    /// Ambient light color (RGB 0.0-1.0), tints `ambient`
+    #[serde(default = "default_ambient_color")]
+    pub ambient_color: (f32, f32, f32),
     /// Asset instances in this room (spawns, lights, props, triggers, etc.)
     #[serde(default)]
     pub objects: Vec<AssetInstance>,
     /// Per-room fog settings (PS1-style depth cueing)
     #[serde(default)]
     pub fog: RoomFog,
+    /// Open to the sky: level weather falls here
+    #[serde(default)]
+    pub outdoor: bool,
+    /// SPU reverb preset for sounds heard in this room (`ReverbType` index, 0 = off)
+    #[serde(default)]
+    pub reverb: u8,
+    /// How much of that reverb is heard (0.0 = dry, 1.0 = full)
+    #[serde(default = "default_reverb_wet")]
+    pub reverb_wet: f32,
+    /// Trigger volumes drawn in the editor (see `trigger`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<TriggerVolume>,
+    /// Ladders the player can climb (see `ladder`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ladders: Vec<Ladder>,
+    /// Fixed and rail camera volumes (see `camera_volume`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cameras: Vec<CameraVolume>,
+    /// Waypoint paths enemies and platforms follow (see `path`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<EntityPath>,
+    /// Flooded height range the player swims in (see `water`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub water: Option<RoomWater>,
 }
 
 fn default_ambient() -> f32 {
     0.5
 }
 
+fn default_reverb_wet() -> f32 {
+    1.0
+}
+
+fn default_ambient_color() -> (f32, f32, f32) {
+    (1.0, 1.0, 1.0)
+}
+
+/// Corners [NW, NE, SE, SW] of a floor/ceiling (heights are room-relative, `y_offset` is the room's Y)
+fn horizontal_corners(y_offset: f32, heights: &[f32; 4], base_x: f32, base_z: f32) -> [Vec3; 4] {
+    [
+        Vec3::new(base_x, y_offset + heights[0], base_z),                             // NW
+        Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[1], base_z),               // NE
+        Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[2], base_z + SECTOR_SIZE), // SE
+        Vec3::new(base_x, y_offset + heights[3], base_z + SECTOR_SIZE),               // SW
+    ]
+}
+
+/// Front normal of a floor (up) or ceiling (down) from its corners
+fn horizontal_normal(corners: &[Vec3; 4], is_floor: bool) -> Vec3 {
+    let edge1 = corners[1] - corners[0];
+    let edge2 = corners[3] - corners[0];
+    if is_floor {
+        edge2.cross(edge1).normalize()
+    } else {
+        edge1.cross(edge2).normalize()
+    }
+}
+
+/// Corners [bottom-left, bottom-right, top-right, top-left] and front normal of a wall
+/// on a sector edge (heights are room-relative, `y_offset` is the room's Y)
+fn wall_corners(y_offset: f32, heights: &[f32; 4], base_x: f32, base_z: f32, direction: Direction) -> ([Vec3; 4], Vec3) {
+    match direction {
+        Direction::North => {
+            // Wall at -Z edge, facing +Z (into room)
+            let corners = [
+                Vec3::new(base_x, y_offset + heights[0], base_z),                    // bottom-left
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[1], base_z),      // bottom-right
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[2], base_z),      // top-right
+                Vec3::new(base_x, y_offset + heights[3], base_z),                    // top-left
+            ];
+            (corners, Vec3::new(0.0, 0.0, 1.0))
+        }
+        Direction::East => {
+            // Wall at +X edge, facing -X (into room)
+            let corners = [
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[0], base_z),
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[1], base_z + SECTOR_SIZE),
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[2], base_z + SECTOR_SIZE),
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[3], base_z),
+            ];
+            (corners, Vec3::new(-1.0, 0.0, 0.0))
+        }
+        Direction::South => {
+            // Wall at +Z edge, facing -Z (into room)
+            let corners = [
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[0], base_z + SECTOR_SIZE),
+                Vec3::new(base_x, y_offset + heights[1], base_z + SECTOR_SIZE),
+                Vec3::new(base_x, y_offset + heights[2], base_z + SECTOR_SIZE),
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[3], base_z + SECTOR_SIZE),
+            ];
+            (corners, Vec3::new(0.0, 0.0, -1.0))
+        }
+        Direction::West => {
+            // Wall at -X edge, facing +X (into room)
+            let corners = [
+                Vec3::new(base_x, y_offset + heights[0], base_z + SECTOR_SIZE),
+                Vec3::new(base_x, y_offset + heights[1], base_z),
+                Vec3::new(base_x, y_offset + heights[2], base_z),
+                Vec3::new(base_x, y_offset + heights[3], base_z + SECTOR_SIZE),
+            ];
+            (corners, Vec3::new(1.0, 0.0, 0.0))
+        }
+        Direction::NwSe => {
+            // Diagonal wall from NW to SE corner
+            // NW = (base_x, base_z), SE = (base_x + SECTOR_SIZE, base_z + SECTOR_SIZE)
+            // Normal faces NE-SW direction (perpendicular to NW-SE)
+            let corners = [
+                Vec3::new(base_x, y_offset + heights[0], base_z),                                 // NW bottom
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[1], base_z + SECTOR_SIZE),     // SE bottom
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[2], base_z + SECTOR_SIZE),     // SE top
+                Vec3::new(base_x, y_offset + heights[3], base_z),                                 // NW top
+            ];
+            // Normal perpendicular to NW-SE line, normalized: (1, 0, -1) / sqrt(2)
+            let n = 1.0 / 2.0_f32.sqrt();
+            (corners, Vec3::new(n, 0.0, -n))
+        }
+        Direction::NeSw => {
+            // Diagonal wall from NE to SW corner
+            // NE = (base_x + SECTOR_SIZE, base_z), SW = (base_x, base_z + SECTOR_SIZE)
+            // Normal faces NW-SE direction (perpendicular to NE-SW)
+            let corners = [
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[0], base_z),                   // NE bottom
+                Vec3::new(base_x, y_offset + heights[1], base_z + SECTOR_SIZE),                   // SW bottom
+                Vec3::new(base_x, y_offset + heights[2], base_z + SECTOR_SIZE),                   // SW top
+                Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[3], base_z),                   // NE top
+            ];
+            // Normal perpendicular to NE-SW line, normalized: (-1, 0, -1) / sqrt(2)
+            let n = 1.0 / 2.0_f32.sqrt();
+            (corners, Vec3::new(-n, 0.0, -n))
+        }
+    }
+}
+
+/// Corners and front normal of a diagonal wall (NW-SE or NE-SW), ordered so the
+/// front face points into the room
+fn diagonal_wall_corners(y_offset: f32, heights: &[f32; 4], base_x: f32, base_z: f32, is_nwse: bool) -> ([Vec3; 4], Vec3) {
+    // Diagonal walls span corner-to-corner
+    // heights = [corner1_bottom, corner2_bottom, corner2_top, corner1_top]
+    // Vertex order is reversed from cardinal walls so front face points INTO the room
+    if is_nwse {
+        // NW-SE diagonal: wall cuts off SW corner, front faces NE (into room)
+        // Corners: SE (bottom), NW (bottom), NW (top), SE (top) - reversed winding
+        let corners = [
+            Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[1], base_z + SECTOR_SIZE),   // SE bottom
+            Vec3::new(base_x, y_offset + heights[0], base_z),                               // NW bottom
+            Vec3::new(base_x, y_offset + heights[3], base_z),                               // NW top
+            Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[2], base_z + SECTOR_SIZE),   // SE top
+        ];
+        // Normal points NE (into room): (n, 0, -n)
+        let n = 1.0 / (2.0_f32).sqrt();
+        (corners, Vec3::new(n, 0.0, -n))
+    } else {
+        // NE-SW diagonal: wall cuts off NW corner, front faces SE (into room)
+        // Corners: SW (bottom), NE (bottom), NE (top), SW (top) - reversed winding
+        let corners = [
+            Vec3::new(base_x, y_offset + heights[1], base_z + SECTOR_SIZE),                 // SW bottom
+            Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[0], base_z),                 // NE bottom
+            Vec3::new(base_x + SECTOR_SIZE, y_offset + heights[3], base_z),                 // NE top
+            Vec3::new(base_x, y_offset + heights[2], base_z + SECTOR_SIZE),                 // SW top
+        ];
+        // Normal points SE (into room): (n, 0, n)
+        let n = 1.0 / (2.0_f32).sqrt();
+        (corners, Vec3::new(n, 0.0, n))
+    }
+}
+
 impl Room {
     /// Create a new empty room with the given grid size
     pub fn new(id: usize, position: Vec3, width: usize, depth: usize) -> Self {
@@ -2483,8 +2850,17 @@ impl Room {
             portals: Vec::new(),
             bounds: Aabb::default(),
             ambient: 0.5,
+            ambient_color: default_ambient_color(),
             objects: Vec::new(),
             fog: RoomFog::default(),
+            outdoor: false,
+            reverb: 0,
+            reverb_wet: default_reverb_wet(),
+            triggers: Vec::new(),
+            ladders: Vec::new(),
+            cameras: Vec::new(),
+            paths: Vec::new(),
+            water: None,
         }
     }
 
@@ -2498,6 +2874,118 @@ impl Room {
         self.sectors.get_mut(x)?.get_mut(z)?.as_mut()
     }
 
+    /// Ambient light per channel (intensity times color)
+    pub fn ambient_rgb(&self) -> [f32; 3] {
+        let (r, g, b) = self.ambient_color;
+        [self.ambient * r, self.ambient * g, self.ambient * b]
+    }
+
+    /// Does any face in this room carry baked light?
+    pub fn has_baked_lighting(&self) -> bool {
+        self.iter_sectors().any(|(_, _, sector)| {
+            sector.floor.as_ref().map_or(false, |f| f.baked_light.is_some())
+                || sector.ceiling.as_ref().map_or(false, |f| f.baked_light.is_some())
+                || Direction::ALL.iter().any(|&d| sector.walls(d).iter().any(|w| w.baked_light.is_some()))
+        })
+    }
+
+    /// Bake ambient plus `lights` into per-corner light on every face (TR1-style
+    /// vertex lighting). Uses the same shading as the dynamic renderer, so a baked
+    /// room looks like its dynamic preview without per-frame light math.
+    pub fn bake_lighting(&mut self, lights: &[Light]) {
+        let ambient = self.ambient_rgb();
+        let origin = self.position;
+        let shade = |corners: &[Vec3; 4], normal: Vec3| -> [Color; 4] {
+            corners.map(|p| baked_light_color(shade_multi_light_color(normal, p, lights, ambient)))
+        };
+
+        for (x, column) in self.sectors.iter_mut().enumerate() {
+            for (z, sector) in column.iter_mut().enumerate() {
+                let Some(sector) = sector else {
+                    continue;
+                };
+                let base_x = origin.x + x as f32 * SECTOR_SIZE;
+                let base_z = origin.z + z as f32 * SECTOR_SIZE;
+
+                for (face, is_floor) in [(&mut sector.floor, true), (&mut sector.ceiling, false)] {
+                    if let Some(face) = face {
+                        let corners = horizontal_corners(origin.y, &face.heights, base_x, base_z);
+                        face.baked_light = Some(shade(&corners, horizontal_normal(&corners, is_floor)));
+                    }
+                }
+                for (walls, direction) in [
+                    (&mut sector.walls_north, Direction::North),
+                    (&mut sector.walls_east, Direction::East),
+                    (&mut sector.walls_south, Direction::South),
+                    (&mut sector.walls_west, Direction::West),
+                ] {
+                    for wall in walls.iter_mut() {
+                        let (corners, normal) = wall_corners(origin.y, &wall.heights, base_x, base_z, direction);
+                        wall.baked_light = Some(shade(&corners, normal));
+                    }
+                }
+                for (walls, is_nwse) in [(&mut sector.walls_nwse, true), (&mut sector.walls_nesw, false)] {
+                    for wall in walls.iter_mut() {
+                        let (corners, normal) = diagonal_wall_corners(origin.y, &wall.heights, base_x, base_z, is_nwse);
+                        wall.baked_light = Some(shade(&corners, normal));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Remove baked light from every face (back to dynamic lighting)
+    pub fn clear_baked_lighting(&mut self) {
+        for sector in self.sectors.iter_mut().flatten().flatten() {
+            if let Some(floor) = &mut sector.floor {
+                floor.baked_light = None;
+            }
+            if let Some(ceiling) = &mut sector.ceiling {
+                ceiling.baked_light = None;
+            }
+            for direction in Direction::ALL {
+                for wall in sector.walls_mut(direction) {
+                    wall.baked_light = None;
+                }
+            }
+        }
+    }
+
+    /// World-space quads of every face flagged `mirror` (`room_idx` is this room's index)
+    pub fn mirror_faces(&self, room_idx: usize) -> Vec<MirrorFace> {
+        let origin = self.position;
+        let mut mirrors = Vec::new();
+        for (x, z, sector) in self.iter_sectors() {
+            let base_x = origin.x + x as f32 * SECTOR_SIZE;
+            let base_z = origin.z + z as f32 * SECTOR_SIZE;
+            for (face, is_floor) in [(&sector.floor, true), (&sector.ceiling, false)] {
+                if let Some(face) = face.as_ref().filter(|f| f.mirror) {
+                    let corners = horizontal_corners(origin.y, &face.heights, base_x, base_z);
+                    let normal = horizontal_normal(&corners, is_floor);
+                    mirrors.push(MirrorFace { room: room_idx, corners, normal });
+                }
+            }
+            for (walls, direction) in [
+                (&sector.walls_north, Direction::North),
+                (&sector.walls_east, Direction::East),
+                (&sector.walls_south, Direction::South),
+                (&sector.walls_west, Direction::West),
+            ] {
+                for wall in walls.iter().filter(|w| w.mirror) {
+                    let (corners, normal) = wall_corners(origin.y, &wall.heights, base_x, base_z, direction);
+                    mirrors.push(MirrorFace { room: room_idx, corners, normal });
+                }
+            }
+            for (walls, is_nwse) in [(&sector.walls_nwse, true), (&sector.walls_nesw, false)] {
+                for wall in walls.iter().filter(|w| w.mirror) {
+                    let (corners, normal) = diagonal_wall_corners(origin.y, &wall.heights, base_x, base_z, is_nwse);
+                    mirrors.push(MirrorFace { room: room_idx, corners, normal });
+                }
+            }
+        }
+        mirrors
+    }
+
     /// Set sector at grid position (creates if doesn't exist)
     pub fn set_sector(&mut self, x: usize, z: usize, sector: Sector) {
         if x < self.width && z < self.depth {
@@ -2837,6 +3325,15 @@ impl Room {
     /// Returns world-space vertices ready for rendering
     /// resolve_texture returns (texture_id, texture_width) - width is used to calculate UV scale
     pub fn to_render_data_with_textures<F>(&self, resolve_texture: F) -> (Vec<Vertex>, Vec<RasterFace>)
+    where
+        F: Fn(&TextureRef) -> Option<(usize, u32)>,
+    {
+        self.to_render_data_lit(resolve_texture, false)
+    }
+
+    /// Render data with baked light applied to the vertex colors when `baked` is set
+    /// (faces without baked light keep their plain vertex colors)
+    pub fn to_render_data_lit<F>(&self, resolve_texture: F, baked: bool) -> (Vec<Vertex>, Vec<RasterFace>)
     where
         F: Fn(&TextureRef) -> Option<(usize, u32)>,
     {
@@ -2858,6 +3355,7 @@ impl Room {
                     grid_x,
                     grid_z,
                     true, // is_floor
+                    baked,
                     &resolve_texture,
                 );
             }
@@ -2873,29 +3371,30 @@ impl Room {
                     grid_x,
                     grid_z,
                     false, // is_ceiling
+                    baked,
                     &resolve_texture,
                 );
             }
 
             // Render walls on each edge
             for wall in &sector.walls_north {
-                self.add_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, Direction::North, &resolve_texture);
+                self.add_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, Direction::North, baked, &resolve_texture);
             }
             for wall in &sector.walls_east {
-                self.add_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, Direction::East, &resolve_texture);
+                self.add_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, Direction::East, baked, &resolve_texture);
             }
             for wall in &sector.walls_south {
-                self.add_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, Direction::South, &resolve_texture);
+                self.add_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, Direction::South, baked, &resolve_texture);
             }
             for wall in &sector.walls_west {
-                self.add_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, Direction::West, &resolve_texture);
+                self.add_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, Direction::West, baked, &resolve_texture);
             }
             // Diagonal walls
             for wall in &sector.walls_nwse {
-                self.add_diagonal_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, true, &resolve_texture);
+                self.add_diagonal_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, true, baked, &resolve_texture);
             }
             for wall in &sector.walls_nesw {
-                self.add_diagonal_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, false, &resolve_texture);
+                self.add_diagonal_wall_to_render_data(&mut vertices, &mut faces, wall, base_x, base_z, grid_x, grid_z, false, baked, &resolve_texture);
             }
         }
 
@@ -2913,6 +3412,7 @@ impl Room {
         grid_x: usize,
         grid_z: usize,
         is_floor: bool,
+        baked: bool,
         resolve_texture: &F,
     )
     where
@@ -2920,21 +3420,11 @@ impl Room {
     {
         // Corner positions for triangle 1: NW, NE, SE, SW
         // Heights are room-relative, so add room.position.y for world-space rendering
-        let corners_1 = [
-            Vec3::new(base_x, self.position.y + face.heights[0], base_z),                         // NW
-            Vec3::new(base_x + SECTOR_SIZE, self.position.y + face.heights[1], base_z),           // NE
-            Vec3::new(base_x + SECTOR_SIZE, self.position.y + face.heights[2], base_z + SECTOR_SIZE), // SE
-            Vec3::new(base_x, self.position.y + face.heights[3], base_z + SECTOR_SIZE),           // SW
-        ];
+        let corners_1 = horizontal_corners(self.position.y, &face.heights, base_x, base_z);
 
         // Corner positions for triangle 2 (may use different heights if unlinked)
         let heights_2 = face.get_heights_2();
-        let corners_2 = [
-            Vec3::new(base_x, self.position.y + heights_2[0], base_z),                         // NW
-            Vec3::new(base_x + SECTOR_SIZE, self.position.y + heights_2[1], base_z),           // NE
-            Vec3::new(base_x + SECTOR_SIZE, self.position.y + heights_2[2], base_z + SECTOR_SIZE), // SE
-            Vec3::new(base_x, self.position.y + heights_2[3], base_z + SECTOR_SIZE),           // SW
-        ];
+        let corners_2 = horizontal_corners(self.position.y, &heights_2, base_x, base_z);
 
         // Resolve textures to get IDs and widths
         let (texture_id_1, tex_width_1) = resolve_texture(&face.texture).unwrap_or((0, 64));
@@ -2975,8 +3465,8 @@ impl Room {
         });
 
         // Colors for each triangle
-        let colors_1 = &face.colors;
-        let colors_2 = face.get_colors_2();
+        let colors_1 = &face.lit_colors(baked);
+        let colors_2 = &face.lit_colors_2(baked);
 
         // Handle normal mode: Front, Back, or Both
         let render_front = face.normal_mode != FaceNormalMode::Back;
@@ -2988,22 +3478,10 @@ impl Room {
         let tri2_corners = split.triangle_2_corners();
 
         // Calculate normals from cross product (each triangle may have different normal if heights differ)
-        let edge1_t1 = corners_1[1] - corners_1[0];
-        let edge2_t1 = corners_1[3] - corners_1[0];
-        let front_normal_1 = if is_floor {
-            edge2_t1.cross(edge1_t1).normalize()
-        } else {
-            edge1_t1.cross(edge2_t1).normalize()
-        };
+        let front_normal_1 = horizontal_normal(&corners_1, is_floor);
         let back_normal_1 = front_normal_1.scale(-1.0);
 
-        let edge1_t2 = corners_2[1] - corners_2[0];
-        let edge2_t2 = corners_2[3] - corners_2[0];
-        let front_normal_2 = if is_floor {
-            edge2_t2.cross(edge1_t2).normalize()
-        } else {
-            edge1_t2.cross(edge2_t2).normalize()
-        };
+        let front_normal_2 = horizontal_normal(&corners_2, is_floor);
         let back_normal_2 = front_normal_2.scale(-1.0);
 
         // Helper to add a single triangle with specific corners
@@ -3058,6 +3536,7 @@ impl Room {
         grid_x: usize,
         grid_z: usize,
         direction: Direction,
+        baked: bool,
         resolve_texture: &F,
     )
     where
@@ -3067,77 +3546,7 @@ impl Room {
         // Each wall has 4 corners: bottom-left, bottom-right, top-right, top-left (from inside room)
         // wall.heights = [bottom-left, bottom-right, top-right, top-left]
         // Heights are room-relative, so add room.position.y for world-space rendering
-        let y_offset = self.position.y;
-        let (corners, front_normal) = match direction {
-            Direction::North => {
-                // Wall at -Z edge, facing +Z (into room)
-                let corners = [
-                    Vec3::new(base_x, y_offset + wall.heights[0], base_z),                    // bottom-left
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[1], base_z),      // bottom-right
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[2], base_z),      // top-right
-                    Vec3::new(base_x, y_offset + wall.heights[3], base_z),                    // top-left
-                ];
-                (corners, Vec3::new(0.0, 0.0, 1.0))
-            }
-            Direction::East => {
-                // Wall at +X edge, facing -X (into room)
-                let corners = [
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[0], base_z),
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[1], base_z + SECTOR_SIZE),
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[2], base_z + SECTOR_SIZE),
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[3], base_z),
-                ];
-                (corners, Vec3::new(-1.0, 0.0, 0.0))
-            }
-            Direction::South => {
-                // Wall at +Z edge, facing -Z (into room)
-                let corners = [
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[0], base_z + SECTOR_SIZE),
-                    Vec3::new(base_x, y_offset + wall.heights[1], base_z + SECTOR_SIZE),
-                    Vec3::new(base_x, y_offset + wall.heights[2], base_z + SECTOR_SIZE),
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[3], base_z + SECTOR_SIZE),
-                ];
-                (corners, Vec3::new(0.0, 0.0, -1.0))
-            }
-            Direction::West => {
-                // Wall at -X edge, facing +X (into room)
-                let corners = [
-                    Vec3::new(base_x, y_offset + wall.heights[0], base_z + SECTOR_SIZE),
-                    Vec3::new(base_x, y_offset + wall.heights[1], base_z),
-                    Vec3::new(base_x, y_offset + wall.heights[2], base_z),
-                    Vec3::new(base_x, y_offset + wall.heights[3], base_z + SECTOR_SIZE),
-                ];
-                (corners, Vec3::new(1.0, 0.0, 0.0))
-            }
-            Direction::NwSe => {
-                // Diagonal wall from NW to SE corner
-                // NW = (base_x, base_z), SE = (base_x + SECTOR_SIZE, base_z + SECTOR_SIZE)
-                // Normal faces NE-SW direction (perpendicular to NW-SE)
-                let corners = [
-                    Vec3::new(base_x, y_offset + wall.heights[0], base_z),                                 // NW bottom
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[1], base_z + SECTOR_SIZE),     // SE bottom
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[2], base_z + SECTOR_SIZE),     // SE top
-                    Vec3::new(base_x, y_offset + wall.heights[3], base_z),                                 // NW top
-                ];
-                // Normal perpendicular to NW-SE line, normalized: (1, 0, -1) / sqrt(2)
-                let n = 1.0 / 2.0_f32.sqrt();
-                (corners, Vec3::new(n, 0.0, -n))
-            }
-            Direction::NeSw => {
-                // Diagonal wall from NE to SW corner
-                // NE = (base_x + SECTOR_SIZE, base_z), SW = (base_x, base_z + SECTOR_SIZE)
-                // Normal faces NW-SE direction (perpendicular to NE-SW)
-                let corners = [
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[0], base_z),                   // NE bottom
-                    Vec3::new(base_x, y_offset + wall.heights[1], base_z + SECTOR_SIZE),                   // SW bottom
-                    Vec3::new(base_x, y_offset + wall.heights[2], base_z + SECTOR_SIZE),                   // SW top
-                    Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[3], base_z),                   // NE top
-                ];
-                // Normal perpendicular to NE-SW line, normalized: (-1, 0, -1) / sqrt(2)
-                let n = 1.0 / 2.0_f32.sqrt();
-                (corners, Vec3::new(-n, 0.0, -n))
-            }
-        };
+        let (corners, front_normal) = wall_corners(self.position.y, &wall.heights, base_x, base_z, direction);
 
         // Resolve texture to get ID and width
         let (texture_id, tex_width) = resolve_texture(&wall.texture).unwrap_or((0, 64));
@@ -3174,14 +3583,9 @@ impl Room {
                 Vec2::new(corner_u[3], 0.0),       // top-left
             ]);
 
-            // Calculate world Y positions (including room y_offset)
+            // Calculate world Y positions (heights are room-relative)
             // heights order: [bottom-left, bottom-right, top-right, top-left]
-            let world_heights = [
-                y_offset + wall.heights[0],
-                y_offset + wall.heights[1],
-                y_offset + wall.heights[2],
-                y_offset + wall.heights[3],
-            ];
+            let world_heights = wall.heights.map(|h| self.position.y + h);
 
             // Calculate V based on absolute world position (scaled by uv_scale)
             // V = -world_y / SECTOR_SIZE * uv_scale (higher Y = lower V value, texture wraps via rasterizer)
@@ -3206,11 +3610,13 @@ impl Room {
         let render_front = wall.normal_mode != FaceNormalMode::Back;
         let render_back = wall.normal_mode != FaceNormalMode::Front;
 
+        let colors = wall.lit_colors(baked);
+
         // Add front-facing face
         if render_front {
             let base_idx = vertices.len();
             for i in 0..4 {
-                vertices.push(Vertex::with_color(corners[i], uvs[i], front_normal, wall.colors[i]));
+                vertices.push(Vertex::with_color(corners[i], uvs[i], front_normal, colors[i]));
             }
             // Two triangles for the quad (CCW winding when viewed from inside room)
             faces.push(RasterFace::with_texture(base_idx, base_idx + 2, base_idx + 1, texture_id).with_black_transparent(wall.black_transparent).with_blend_mode(wall.blend_mode));
@@ -3222,7 +3628,7 @@ impl Room {
             let base_idx = vertices.len();
             let back_normal = front_normal.scale(-1.0);
             for i in 0..4 {
-                vertices.push(Vertex::with_color(corners[i], uvs[i], back_normal, wall.colors[i]));
+                vertices.push(Vertex::with_color(corners[i], uvs[i], back_normal, colors[i]));
             }
             // Reverse winding order for back face
             faces.push(RasterFace::with_texture(base_idx, base_idx + 1, base_idx + 2, texture_id).with_black_transparent(wall.black_transparent).with_blend_mode(wall.blend_mode));
@@ -3242,42 +3648,13 @@ impl Room {
         grid_x: usize,
         _grid_z: usize,
         is_nwse: bool,
+        baked: bool,
         resolve_texture: &F,
     )
     where
         F: Fn(&TextureRef) -> Option<(usize, u32)>,
     {
-        // Heights are room-relative, so add room.position.y for world-space rendering
-        let y_offset = self.position.y;
-
-        // Diagonal walls span corner-to-corner
-        // wall.heights = [corner1_bottom, corner2_bottom, corner2_top, corner1_top]
-        // Vertex order is reversed from cardinal walls so front face points INTO the room
-        let (corners, front_normal) = if is_nwse {
-            // NW-SE diagonal: wall cuts off SW corner, front faces NE (into room)
-            // Corners: SE (bottom), NW (bottom), NW (top), SE (top) - reversed winding
-            let corners = [
-                Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[1], base_z + SECTOR_SIZE),   // SE bottom
-                Vec3::new(base_x, y_offset + wall.heights[0], base_z),                               // NW bottom
-                Vec3::new(base_x, y_offset + wall.heights[3], base_z),                               // NW top
-                Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[2], base_z + SECTOR_SIZE),   // SE top
-            ];
-            // Normal points NE (into room): (n, 0, -n)
-            let n = 1.0 / (2.0_f32).sqrt();
-            (corners, Vec3::new(n, 0.0, -n))
-        } else {
-            // NE-SW diagonal: wall cuts off NW corner, front faces SE (into room)
-            // Corners: SW (bottom), NE (bottom), NE (top), SW (top) - reversed winding
-            let corners = [
-                Vec3::new(base_x, y_offset + wall.heights[1], base_z + SECTOR_SIZE),                 // SW bottom
-                Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[0], base_z),                 // NE bottom
-                Vec3::new(base_x + SECTOR_SIZE, y_offset + wall.heights[3], base_z),                 // NE top
-                Vec3::new(base_x, y_offset + wall.heights[2], base_z + SECTOR_SIZE),                 // SW top
-            ];
-            // Normal points SE (into room): (n, 0, n)
-            let n = 1.0 / (2.0_f32).sqrt();
-            (corners, Vec3::new(n, 0.0, n))
-        };
+        let (corners, front_normal) = diagonal_wall_corners(self.position.y, &wall.heights, base_x, base_z, is_nwse);
 
         // Resolve texture to get ID and width
         let (texture_id, tex_width) = resolve_texture(&wall.texture).unwrap_or((0, 64));
@@ -3300,12 +3677,7 @@ impl Room {
                 Vec2::new(corner_u[3], 0.0),       // top-left
             ]);
 
-            let world_heights = [
-                y_offset + wall.heights[0],
-                y_offset + wall.heights[1],
-                y_offset + wall.heights[2],
-                y_offset + wall.heights[3],
-            ];
+            let world_heights = wall.heights.map(|h| self.position.y + h);
 
             [
                 Vec2::new(base_uvs[0].x, -world_heights[0] / SECTOR_SIZE * uv_scale),
@@ -3327,11 +3699,13 @@ impl Room {
         let render_front = wall.normal_mode != FaceNormalMode::Back;
         let render_back = wall.normal_mode != FaceNormalMode::Front;
 
+        let colors = wall.lit_colors(baked);
+
         // Add front-facing face
         if render_front {
             let base_idx = vertices.len();
             for i in 0..4 {
-                vertices.push(Vertex::with_color(corners[i], uvs[i], front_normal, wall.colors[i]));
+                vertices.push(Vertex::with_color(corners[i], uvs[i], front_normal, colors[i]));
             }
             // Two triangles for the quad
             faces.push(RasterFace::with_texture(base_idx, base_idx + 2, base_idx + 1, texture_id).with_black_transparent(wall.black_transparent).with_blend_mode(wall.blend_mode));
@@ -3343,7 +3717,7 @@ impl Room {
             let base_idx = vertices.len();
             let back_normal = front_normal.scale(-1.0);
             for i in 0..4 {
-                vertices.push(Vertex::with_color(corners[i], uvs[i], back_normal, wall.colors[i]));
+                vertices.push(Vertex::with_color(corners[i], uvs[i], back_normal, colors[i]));
             }
             // Reverse winding order for back face
             faces.push(RasterFace::with_texture(base_idx, base_idx + 1, base_idx + 2, texture_id).with_black_transparent(wall.black_transparent).with_blend_mode(wall.blend_mode));
@@ -3390,6 +3764,12 @@ pub struct EditorLayoutConfig {
     /// 3D orbit camera vertical angle (radians)
     #[serde(default = "default_orbit_elevation")]
     pub orbit_elevation: f32,
+    /// Bottom dock split ratio (3D viewport / bottom panels)
+    #[serde(default = "default_bottom_split")]
+    pub bottom_split: f32,
+    /// Dockable panel arrangement (empty = the editor's default layout)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub panels: Vec<crate::ui::PanelDock>,
 }
 
 fn default_grid_zoom() -> f32 {
@@ -3402,6 +3782,7 @@ fn default_orbit_target_z() -> f32 { 512.0 }
 fn default_orbit_distance() -> f32 { 4000.0 }
 fn default_orbit_azimuth() -> f32 { 0.8 }
 fn default_orbit_elevation() -> f32 { 0.4 }
+fn default_bottom_split() -> f32 { 0.7 }
 
 impl Default for EditorLayoutConfig {
     fn default() -> Self {
@@ -3419,6 +3800,8 @@ impl Default for EditorLayoutConfig {
             orbit_distance: 4000.0,
             orbit_azimuth: 0.8,
             orbit_elevation: 0.4,
+            bottom_split: 0.7,
+            panels: Vec::new(),
         }
     }
 }
@@ -3441,6 +3824,10 @@ pub struct FloorInfo {
 /// The entire level
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Level {
+    /// File format version (see `LEVEL_FORMAT_VERSION`); files from before
+    /// the field existed are version 1
+    #[serde(default = "default_level_version")]
+    pub version: u32,
     pub rooms: Vec<Room>,
     /// Editor layout configuration (optional, uses default if missing)
     #[serde(default)]
@@ -3451,15 +3838,90 @@ pub struct Level {
     /// Skybox configuration (gradient sky)
     #[serde(default)]
     pub skybox: Option<Skybox>,
+    /// Level logic scripts (see `game::script` for the language)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scripts: Vec<LevelScript>,
+    /// Camera flights with timed events (see `cutscene`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cutscenes: Vec<Cutscene>,
+    /// Content budget preset checked on save (see `budget`)
+    #[serde(default, skip_serializing_if = "BudgetPreset::is_off")]
+    pub budget: BudgetPreset,
+    /// Rain, snow, ash or dust particles (see `weather`)
+    #[serde(default, skip_serializing_if = "LevelWeather::is_clear")]
+    pub weather: LevelWeather,
+    /// Default music/SFX/ambient/dialogue volumes and ducking for this project
+    #[serde(default, skip_serializing_if = "MixLevels::is_default")]
+    pub audio_mix: MixLevels,
+    /// Level files streamed in around the player (see `streaming`)
+    #[serde(default, skip_serializing_if = "LevelStreaming::is_off")]
+    pub streaming: LevelStreaming,
+    /// Level fog and time of day (see `atmosphere`)
+    #[serde(default, skip_serializing_if = "LevelAtmosphere::is_default")]
+    pub atmosphere: LevelAtmosphere,
+    /// Snap step and jitter strength of the PS1 vertex wobble for this project
+    #[serde(default, skip_serializing_if = "VertexSnap::is_default")]
+    pub vertex_snap: VertexSnap,
+    /// HUD element placement (see `game::hud`)
+    #[serde(default, skip_serializing_if = "HudLayout::is_default")]
+    pub hud: HudLayout,
+    /// Sound effects played for gameplay events (see `game::sound`)
+    #[serde(default, skip_serializing_if = "SoundBindings::is_default")]
+    pub sounds: SoundBindings,
+    /// Texture packs the game uses, highest priority first; empty for all (see `texture_packs`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub texture_packs: Vec<String>,
+    /// Sector grids packed on save (see `level_format`); always empty once
+    /// loaded, the rooms hold the sectors
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub geometry: String,
+}
+
+fn default_level_version() -> u32 {
+    1
+}
+
+/// A named level script, stored as source text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelScript {
+    pub name: String,
+    #[serde(default)]
+    pub source: String,
+    /// Disabled scripts are kept but not compiled
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl LevelScript {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            source: String::new(),
+            enabled: true,
+        }
+    }
 }
 
 impl Level {
     pub fn new() -> Self {
         Self {
+            version: super::LEVEL_FORMAT_VERSION,
             rooms: Vec::new(),
             editor_layout: EditorLayoutConfig::default(),
             player_settings: PlayerSettings::default(),
             skybox: None,
+            scripts: Vec::new(),
+            cutscenes: Vec::new(),
+            budget: BudgetPreset::Off,
+            weather: LevelWeather::default(),
+            audio_mix: MixLevels::default(),
+            streaming: LevelStreaming::default(),
+            atmosphere: LevelAtmosphere::default(),
+            vertex_snap: VertexSnap::default(),
+            hud: HudLayout::default(),
+            sounds: SoundBindings::default(),
+            texture_packs: Vec::new(),
+            geometry: String::new(),
         }
     }
 
@@ -3562,6 +4024,20 @@ impl Level {
         id
     }
 
+    /// Number of distinct mirrors in the level (coplanar faces in a room count once)
+    pub fn mirror_count(&self) -> usize {
+        let faces: Vec<MirrorFace> = self.rooms.iter().enumerate()
+            .flat_map(|(i, room)| room.mirror_faces(i))
+            .collect();
+        mirror_planes(&faces).len()
+    }
+
+    /// Mirror to render this frame: the nearest one in `room` facing the camera
+    pub fn active_mirror(&self, room: usize, camera_pos: Vec3) -> Option<Vec<MirrorFace>> {
+        let faces = self.rooms.get(room)?.mirror_faces(room);
+        pick_mirror(&faces, camera_pos)
+    }
+
     /// Find which room contains a point
     pub fn find_room_at(&self, point: Vec3) -> Option<usize> {
         for (i, room) in self.rooms.iter().enumerate() {
@@ -4035,3 +4511,102 @@ pub fn create_test_level() -> Level {
 
     level
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_player_jump_reach() {
+        let settings = PlayerSettings::default();
+        // v^2 / 2g: 1200^2 / 4800
+        assert_eq!(settings.jump_height(false), 300.0);
+        assert!(settings.jump_height(true) > settings.jump_height(false));
+        // Airtime 2v/g = 1s at walk speed
+        assert_eq!(settings.jump_distance(false), settings.walk_speed);
+        assert!(settings.jump_distance(true) > settings.jump_distance(false));
+    }
+
+    fn lit_room() -> Room {
+        let mut room = Room::new(0, Vec3::new(0.0, 0.0, 0.0), 2, 1);
+        room.set_floor(0, 0, 0.0, TextureRef::none());
+        room.set_floor(1, 0, 0.0, TextureRef::none());
+        room
+    }
+
+    #[test]
+    fn test_bake_ambient_only() {
+        let mut room = lit_room();
+        room.ambient = 0.5;
+        room.ambient_color = (1.0, 0.5, 0.0);
+        room.bake_lighting(&[]);
+
+        let floor = room.get_sector(0, 0).unwrap().floor.as_ref().unwrap();
+        let light = floor.baked_light.unwrap();
+        assert_eq!((light[0].r, light[0].g, light[0].b), (64, 32, 0));
+        // Neutral vertex colors (128) end up at the baked level
+        let lit = floor.lit_colors(true);
+        assert_eq!((lit[2].r, lit[2].g, lit[2].b), (64, 32, 0));
+        assert_eq!(floor.lit_colors(false), floor.colors);
+    }
+
+    #[test]
+    fn test_bake_point_light_falloff() {
+        let mut room = lit_room();
+        room.ambient = 0.0;
+        let light = Light::point(Vec3::new(SECTOR_SIZE * 0.5, 500.0, SECTOR_SIZE * 0.5), SECTOR_SIZE * 1.5, 1.0);
+        room.bake_lighting(&[light]);
+
+        let near = room.get_sector(0, 0).unwrap().floor.as_ref().unwrap().baked_light.unwrap();
+        let far = room.get_sector(1, 0).unwrap().floor.as_ref().unwrap().baked_light.unwrap();
+        // NW corner of sector 0 is closer to the light than the NE corner of sector 1
+        assert!(near[0].r > far[1].r);
+        assert!(room.has_baked_lighting());
+
+        room.clear_baked_lighting();
+        assert!(!room.has_baked_lighting());
+    }
+
+    #[test]
+    fn test_component_overrides_apply() {
+        use crate::asset::AssetComponent;
+        let door = AssetComponent::Door {
+            required_key: Some("red_key".to_string()),
+            start_open: false,
+            motion: Default::default(),
+            open_time: 1.0,
+            open_trigger: None,
+        };
+        let mut overrides = ComponentOverrides::default();
+        assert!(matches!(overrides.apply(&door), AssetComponent::Door { start_open: false, .. }));
+
+        overrides.door = Some(DoorOverride { start_open: Some(true), ..Default::default() });
+        overrides.light = Some(LightOverride { color: Some([255, 0, 0]), ..Default::default() });
+        assert_eq!(overrides.count(), 2);
+        assert!(matches!(overrides.apply(&door), AssetComponent::Door { start_open: true, .. }));
+
+        let light = AssetComponent::Light { color: [255, 255, 255], intensity: 2.0, radius: 1024.0, offset: [0.0; 3] };
+        match overrides.apply(&light) {
+            AssetComponent::Light { color, intensity, .. } => assert_eq!((color, intensity), ([255, 0, 0], 2.0)),
+            other => panic!("{:?}", other),
+        }
+
+        // An empty key override clears the asset's lock; a trigger override links one
+        overrides.door = Some(DoorOverride {
+            required_key: Some(String::new()),
+            open_trigger: Some("gate".to_string()),
+            ..Default::default()
+        });
+        match overrides.apply(&door) {
+            AssetComponent::Door { required_key, open_trigger, .. } => {
+                assert_eq!(required_key, None);
+                assert_eq!(open_trigger.as_deref(), Some("gate"));
+            }
+            other => panic!("{:?}", other),
+        }
+
+        // Empty overrides serialize to nothing
+        let empty = ComponentOverrides { enemy: Some(EnemyOverride::default()), ..Default::default() };
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+//! Heightmap Terrain Import
+//!
+//! Turns a grayscale image into sloped floor geometry for outdoor areas.
+//! Each sector corner samples the heightmap, so neighbouring sectors (and
+//! neighbouring rooms) share edge heights and the terrain has no cracks.
+//!
+//! The terrain is split into square rooms of `room_size` sectors so portal
+//! culling still works. Wall portals need a floor and a ceiling on both sides,
+//! so every sector also gets a flat "sky lid" ceiling above the highest point.
+//! The lid uses `FaceNormalMode::Back`, which culls it from below: from the
+//! ground you see the skybox, not the lid.
+
+use super::{FaceNormalMode, HorizontalFace, Room, Sector, TextureRef, SECTOR_SIZE};
+use crate::rasterizer::Vec3;
+
+/// Grayscale height samples, 0.0 (black) to 1.0 (white)
+#[derive(Debug, Clone)]
+pub struct Heightmap {
+    pub width: usize,
+    pub height: usize,
+    samples: Vec<f32>,
+}
+
+impl Heightmap {
+    /// Build from raw samples (row-major, `width * height` values in 0..=1)
+    pub fn from_samples(width: usize, height: usize, samples: Vec<f32>) -> Result<Self, String> {
+        if width == 0 || height == 0 || samples.len() != width * height {
+            return Err(format!("Expected {}x{} samples, got {}", width, height, samples.len()));
+        }
+        Ok(Self { width, height, samples })
+    }
+
+    /// Decode an image (PNG, etc.); color images are converted to luminance
+    pub fn from_image_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let img = image::load_from_memory(bytes)
+            .map_err(|e| format!("Failed to decode heightmap: {}", e))?;
+        let luma = img.to_luma16();
+        let (width, height) = luma.dimensions();
+        let samples = luma.pixels().map(|p| p[0] as f32 / u16::MAX as f32).collect();
+        Self::from_samples(width as usize, height as usize, samples)
+    }
+
+    /// Sample at pixel coordinates, clamped to the image
+    pub fn sample(&self, x: usize, y: usize) -> f32 {
+        let x = x.min(self.width - 1);
+        let y = y.min(self.height - 1);
+        self.samples[y * self.width + x]
+    }
+}
+
+/// Terrain import settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeightmapSettings {
+    /// Heightmap pixels per sector (1 = one sector per pixel)
+    pub cell_pixels: usize,
+    /// World height of a white pixel (black is 0)
+    pub height_scale: f32,
+    /// Snap heights to multiples of this (0 = no snapping)
+    pub height_step: f32,
+    /// Sectors per room side; the terrain is split into rooms of this size
+    pub room_size: usize,
+    /// Sky lid height above the highest terrain point
+    pub sky_clearance: f32,
+}
+
+impl Default for HeightmapSettings {
+    fn default() -> Self {
+        Self {
+            cell_pixels: 4,
+            height_scale: 4096.0,
+            height_step: 64.0,
+            room_size: 16,
+            sky_clearance: 4096.0,
+        }
+    }
+}
+
+impl HeightmapSettings {
+    /// Sector grid size for a heightmap
+    pub fn sector_counts(&self, map: &Heightmap) -> (usize, usize) {
+        let cell = self.cell_pixels.max(1);
+        (((map.width - 1) / cell).max(1), ((map.height - 1) / cell).max(1))
+    }
+
+    /// World height of the corner at sector grid position (cx, cz)
+    fn corner_height(&self, map: &Heightmap, cx: usize, cz: usize) -> f32 {
+        let cell = self.cell_pixels.max(1);
+        let h = map.sample(cx * cell, cz * cell) * self.height_scale;
+        if self.height_step > 0.0 {
+            (h / self.height_step).round() * self.height_step
+        } else {
+            h
+        }
+    }
+}
+
+/// Generate terrain rooms from a heightmap.
+///
+/// Rooms are laid out from `origin` (the NW corner) and numbered from
+/// `first_id`. Portals are not created here: add the rooms to the level and
+/// call `Level::recalculate_portals`.
+pub fn generate_terrain(
+    map: &Heightmap,
+    settings: &HeightmapSettings,
+    texture: &TextureRef,
+    origin: Vec3,
+    first_id: usize,
+) -> Vec<Room> {
+    let (sectors_x, sectors_z) = settings.sector_counts(map);
+    let room_size = settings.room_size.max(1);
+
+    // All corner heights up front, shared by neighbouring sectors and rooms
+    let corners: Vec<Vec<f32>> = (0..=sectors_x)
+        .map(|cx| (0..=sectors_z).map(|cz| settings.corner_height(map, cx, cz)).collect())
+        .collect();
+    let top = corners.iter().flatten().fold(0.0f32, |a, &b| a.max(b));
+    let sky = top + settings.sky_clearance.max(SECTOR_SIZE / 4.0);
+
+    let mut rooms = Vec::new();
+    for rz in 0..sectors_z.div_ceil(room_size) {
+        for rx in 0..sectors_x.div_ceil(room_size) {
+            let x0 = rx * room_size;
+            let z0 = rz * room_size;
+            let width = room_size.min(sectors_x - x0);
+            let depth = room_size.min(sectors_z - z0);
+            let position = Vec3::new(
+                origin.x + x0 as f32 * SECTOR_SIZE,
+                origin.y,
+                origin.z + z0 as f32 * SECTOR_SIZE,
+            );
+            let mut room = Room::new(first_id + rooms.len(), position, width, depth);
+            room.outdoor = true;
+
+            for lx in 0..width {
+                for lz in 0..depth {
+                    let (gx, gz) = (x0 + lx, z0 + lz);
+                    // [NW, NE, SE, SW] = (-X,-Z), (+X,-Z), (+X,+Z), (-X,+Z)
+                    let heights = [
+                        corners[gx][gz],
+                        corners[gx + 1][gz],
+                        corners[gx + 1][gz + 1],
+                        corners[gx][gz + 1],
+                    ];
+                    let mut lid = HorizontalFace::flat(sky, texture.clone());
+                    lid.normal_mode = FaceNormalMode::Back;
+                    lid.walkable = false;
+                    room.set_sector(lx, lz, Sector {
+                        floor: Some(HorizontalFace::sloped(heights, texture.clone())),
+                        ceiling: Some(lid),
+                        ..Default::default()
+                    });
+                }
+            }
+
+            room.recalculate_bounds();
+            rooms.push(room);
+        }
+    }
+    rooms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Level;
+
+    fn ramp(width: usize, height: usize) -> Heightmap {
+        let samples = (0..height)
+            .flat_map(|_| (0..width).map(move |x| x as f32 / (width - 1) as f32))
+            .collect();
+        Heightmap::from_samples(width, height, samples).unwrap()
+    }
+
+    #[test]
+    fn splits_into_rooms_with_shared_edges() {
+        let map = ramp(9, 5);
+        let settings = HeightmapSettings {
+            cell_pixels: 1,
+            height_scale: 800.0,
+            height_step: 0.0,
+            room_size: 3,
+            ..Default::default()
+        };
+        let rooms = generate_terrain(&map, &settings, &TextureRef::none(), Vec3::ZERO, 0);
+        // 8x4 sectors in 3x3 rooms: 3 columns by 2 rows
+        assert_eq!(rooms.len(), 6);
+        assert_eq!((rooms[2].width, rooms[2].depth), (2, 3));
+        assert_eq!((rooms[3].width, rooms[3].depth), (3, 1));
+
+        // East edge of room 0 matches the west edge of room 1
+        let a = rooms[0].get_sector(2, 0).unwrap().floor.as_ref().unwrap().heights;
+        let b = rooms[1].get_sector(0, 0).unwrap().floor.as_ref().unwrap().heights;
+        assert_eq!((a[1], a[2]), (b[0], b[3]));
+        assert!((b[0] - 300.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn rooms_get_portals() {
+        let map = ramp(5, 5);
+        let settings = HeightmapSettings { cell_pixels: 1, room_size: 2, ..Default::default() };
+        let mut level = Level::new();
+        level.rooms = generate_terrain(&map, &settings, &TextureRef::none(), Vec3::ZERO, 0);
+        level.recalculate_portals();
+        assert_eq!(level.rooms.len(), 4);
+        assert!(level.rooms.iter().all(|r| !r.portals.is_empty()));
+        assert!(level.rooms[0].portals.iter().any(|p| p.target_room == 1));
+        assert!(level.rooms[0].portals.iter().any(|p| p.target_room == 2));
+    }
+
+    #[test]
+    fn snaps_heights_and_samples_cells() {
+        let map = ramp(9, 2);
+        let settings = HeightmapSettings {
+            cell_pixels: 4,
+            height_scale: 1000.0,
+            height_step: 256.0,
+            ..Default::default()
+        };
+        assert_eq!(settings.sector_counts(&map), (2, 1));
+        let rooms = generate_terrain(&map, &settings, &TextureRef::none(), Vec3::ZERO, 5);
+        assert_eq!(rooms[0].id, 5);
+        let heights = rooms[0].get_sector(1, 0).unwrap().floor.as_ref().unwrap().heights;
+        // Corners at pixels 4 and 8: 500 snaps to 512, 1000 to 1024
+        assert_eq!(heights[0], 512.0);
+        assert_eq!(heights[1], 1024.0);
+    }
+}
@@ -0,0 +1,144 @@
+//! Ladders
+//!
+//! Climbable boxes authored in the World Editor (drawn in the 2D grid view,
+//! like trigger volumes). `facing` is the side the ladder is fixed to: a
+//! climber holds on facing that way. Bounds are room-relative; the bottom
+//! usually sits on the floor and the top at the ledge the ladder leads to.
+//!
+//! The player controller grabs, climbs and dismounts them (see `game::climb`).
+
+use serde::{Serialize, Deserialize};
+use crate::rasterizer::Vec3;
+use super::{snap_trigger_coord, Direction, Level, SECTOR_SIZE, TRIGGER_SNAP};
+
+/// Height of a newly drawn ladder
+pub const LADDER_DEFAULT_HEIGHT: f32 = SECTOR_SIZE * 2.0;
+
+/// A climbable box in a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ladder {
+    /// Minimum corner (room-relative)
+    pub min: Vec3,
+    /// Maximum corner (room-relative)
+    pub max: Vec3,
+    /// Side of the box the ladder is fixed to (cardinal directions only)
+    #[serde(default)]
+    pub facing: Direction,
+}
+
+impl Ladder {
+    /// Ladder spanning two room-relative floor-plan corners (any order),
+    /// snapped to the trigger grid, from the room's base up to the default
+    /// height. Degenerate drags grow to one snap step.
+    pub fn from_corners(a: (f32, f32), b: (f32, f32), facing: Direction) -> Self {
+        let (x0, x1) = (snap_trigger_coord(a.0.min(b.0)), snap_trigger_coord(a.0.max(b.0)));
+        let (z0, z1) = (snap_trigger_coord(a.1.min(b.1)), snap_trigger_coord(a.1.max(b.1)));
+        Self {
+            min: Vec3::new(x0, 0.0, z0),
+            max: Vec3::new(x1.max(x0 + TRIGGER_SNAP), LADDER_DEFAULT_HEIGHT, z1.max(z0 + TRIGGER_SNAP)),
+            facing,
+        }
+    }
+
+    /// World-space bounds for a room at `room_position`
+    pub fn world_bounds(&self, room_position: Vec3) -> (Vec3, Vec3) {
+        (room_position + self.min, room_position + self.max)
+    }
+
+    /// World-space center
+    pub fn world_center(&self, room_position: Vec3) -> Vec3 {
+        room_position + (self.min + self.max) * 0.5
+    }
+
+    /// Size in world units
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// Unit XZ vector pointing at the side the ladder is fixed to
+    pub fn facing_vector(&self) -> Vec3 {
+        let (dx, dz) = self.facing.offset();
+        if dx == 0 && dz == 0 {
+            // Diagonals aren't valid ladder sides; treat them as north
+            return Vec3::new(0.0, 0.0, -1.0);
+        }
+        Vec3::new(dx as f32, 0.0, dz as f32)
+    }
+
+    /// Turn to the next cardinal side (N -> E -> S -> W)
+    pub fn rotate_facing(&mut self) {
+        self.facing = match self.facing {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            _ => Direction::North,
+        };
+    }
+
+    /// Move by a room-relative offset
+    pub fn translate(&mut self, offset: Vec3) {
+        self.min = self.min + offset;
+        self.max = self.max + offset;
+    }
+}
+
+/// The side of a ladder box with the highest floor (or solid rock) right
+/// behind it, which is where a ladder drawn against a ledge belongs.
+/// North when every side is open floor.
+pub fn guess_ladder_facing(level: &Level, room_position: Vec3, ladder: &Ladder) -> Direction {
+    let (min, max) = ladder.world_bounds(room_position);
+    let center = (min + max) * 0.5;
+    let half = (max - min) * 0.5;
+    let mut best = (Direction::North, min.y + 1.0);
+    for dir in [Direction::North, Direction::East, Direction::South, Direction::West] {
+        let (dx, dz) = dir.offset();
+        let probe = Vec3::new(
+            center.x + dx as f32 * (half.x + TRIGGER_SNAP * 0.5),
+            min.y + 1.0,
+            center.z + dz as f32 * (half.z + TRIGGER_SNAP * 0.5),
+        );
+        let height = level.get_floor_info(probe, None).map_or(f32::INFINITY, |info| info.floor);
+        if height > best.1 {
+            best = (dir, height);
+        }
+    }
+    best.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{Room, TextureRef};
+
+    #[test]
+    fn test_from_corners_snaps_and_faces() {
+        let ladder = Ladder::from_corners((SECTOR_SIZE * 1.1, 0.0), (SECTOR_SIZE, 10.0), Direction::East);
+        assert_eq!(ladder.min.x, SECTOR_SIZE);
+        assert_eq!(ladder.size().x, TRIGGER_SNAP);
+        assert_eq!(ladder.size().z, TRIGGER_SNAP);
+        assert_eq!(ladder.max.y, LADDER_DEFAULT_HEIGHT);
+        let facing = ladder.facing_vector();
+        assert_eq!((facing.x, facing.z), (1.0, 0.0));
+
+        let mut ladder = ladder;
+        ladder.rotate_facing();
+        assert_eq!(ladder.facing, Direction::South);
+        ladder.facing = Direction::NwSe;
+        ladder.rotate_facing();
+        assert_eq!(ladder.facing, Direction::North);
+    }
+
+    #[test]
+    fn test_guess_facing_finds_the_ledge() {
+        // Two sectors along X: the east one is a ledge two sectors up
+        let mut level = Level::new();
+        let mut room = Room::new(0, Vec3::ZERO, 2, 1);
+        room.set_floor(0, 0, 0.0, TextureRef::none());
+        room.set_floor(1, 0, SECTOR_SIZE * 2.0, TextureRef::none());
+        room.recalculate_bounds();
+        level.rooms.push(room);
+
+        let ladder = Ladder::from_corners((SECTOR_SIZE * 0.75, 0.25 * SECTOR_SIZE), (SECTOR_SIZE, 0.75 * SECTOR_SIZE), Direction::North);
+        assert_eq!(guess_ladder_facing(&level, Vec3::ZERO, &ladder), Direction::East);
+    }
+}
@@ -4,11 +4,16 @@
 //! Supports both compressed (brotli) and uncompressed RON files.
 //! - Reading: Auto-detects format by checking for valid RON start
 //! - Writing: Always uses brotli compression
+//!
+//! Older file versions are migrated on load, see `level_format`. Loaded
+//! levels are checked with `check_level`; the `repair_*` loaders fix common
+//! problems instead of rejecting the file.
 
 use std::fs;
 use std::io::Cursor;
 use std::path::Path;
-use super::{Level, Room, Sector, HorizontalFace, VerticalFace, TextureRef};
+use crate::load_check::{describe_ron_error, unresolved, LoadIssue};
+use super::{migrate_level, pack_level, Level, Room, Sector, HorizontalFace, VerticalFace, TextureRef};
 
 /// Validation limits to prevent resource exhaustion from malicious files
 pub mod limits {
@@ -28,9 +33,11 @@ pub mod limits {
 #[derive(Debug)]
 pub enum LevelError {
     IoError(std::io::Error),
-    ParseError(ron::error::SpannedError),
+    /// RON that doesn't parse, described by `describe_ron_error`
+    ParseError(String),
     SerializeError(ron::Error),
     ValidationError(String),
+    FormatError(String),
 }
 
 impl From<std::io::Error> for LevelError {
@@ -39,12 +46,6 @@ impl From<std::io::Error> for LevelError {
     }
 }
 
-impl From<ron::error::SpannedError> for LevelError {
-    fn from(e: ron::error::SpannedError) -> Self {
-        LevelError::ParseError(e)
-    }
-}
-
 impl From<ron::Error> for LevelError {
     fn from(e: ron::Error) -> Self {
         LevelError::SerializeError(e)
@@ -58,6 +59,7 @@ impl std::fmt::Display for LevelError {
             LevelError::ParseError(e) => write!(f, "Parse error: {}", e),
             LevelError::SerializeError(e) => write!(f, "Serialize error: {}", e),
             LevelError::ValidationError(e) => write!(f, "Validation error: {}", e),
+            LevelError::FormatError(e) => write!(f, "Format error: {}", e),
         }
     }
 }
@@ -73,186 +75,197 @@ fn is_valid_portal_coord(f: f32) -> bool {
     !f.is_nan() && (f.is_finite() && f.abs() <= limits::MAX_COORD || f.is_infinite())
 }
 
-/// Validate a texture reference
-fn validate_texture_ref(tex: &TextureRef, context: &str) -> Result<(), String> {
+/// Check a texture reference
+fn check_texture_ref(tex: &TextureRef, field: &str, issues: &mut Vec<LoadIssue>) {
     if tex.pack.len() > limits::MAX_STRING_LEN {
-        return Err(format!("{}: texture pack name too long ({} > {})",
-            context, tex.pack.len(), limits::MAX_STRING_LEN));
+        issues.push(LoadIssue::error(format!("{} > pack", field),
+            format!("texture pack name too long ({} > {})", tex.pack.len(), limits::MAX_STRING_LEN)));
     }
     if tex.name.len() > limits::MAX_STRING_LEN {
-        return Err(format!("{}: texture name too long ({} > {})",
-            context, tex.name.len(), limits::MAX_STRING_LEN));
+        issues.push(LoadIssue::error(format!("{} > name", field),
+            format!("texture name too long ({} > {})", tex.name.len(), limits::MAX_STRING_LEN)));
     }
-    Ok(())
 }
 
-/// Validate a horizontal face (floor/ceiling)
-fn validate_horizontal_face(face: &HorizontalFace, context: &str) -> Result<(), String> {
-    for (i, h) in face.heights.iter().enumerate() {
+/// Check a face's corner heights
+fn check_heights(heights: &[f32], field: &str, issues: &mut Vec<LoadIssue>) {
+    for (i, h) in heights.iter().enumerate() {
         if !is_valid_float(*h) {
-            return Err(format!("{}: invalid height[{}] = {}", context, i, h));
+            issues.push(LoadIssue::error(format!("{} > heights[{}]", field, i), format!("invalid height {}", h)));
         }
     }
-    validate_texture_ref(&face.texture, context)?;
-    Ok(())
 }
 
-/// Validate a vertical face (wall)
-fn validate_vertical_face(face: &VerticalFace, context: &str) -> Result<(), String> {
-    for (i, h) in face.heights.iter().enumerate() {
-        if !is_valid_float(*h) {
-            return Err(format!("{}: invalid height[{}] = {}", context, i, h));
-        }
-    }
-    validate_texture_ref(&face.texture, context)?;
-    Ok(())
+/// Check a horizontal face (floor/ceiling)
+fn check_horizontal_face(face: &HorizontalFace, field: &str, issues: &mut Vec<LoadIssue>) {
+    check_heights(&face.heights, field, issues);
+    check_texture_ref(&face.texture, field, issues);
 }
 
-/// Validate a sector
-fn validate_sector(sector: &Sector, context: &str) -> Result<(), String> {
+/// Check a sector; with `fix`, extra walls past the limit are dropped
+fn check_sector(sector: &mut Sector, field: &str, fix: bool, issues: &mut Vec<LoadIssue>) {
     if let Some(floor) = &sector.floor {
-        validate_horizontal_face(floor, &format!("{} floor", context))?;
+        check_horizontal_face(floor, &format!("{} > floor", field), issues);
     }
     if let Some(ceiling) = &sector.ceiling {
-        validate_horizontal_face(ceiling, &format!("{} ceiling", context))?;
-    }
-
-    // Check wall counts
-    if sector.walls_north.len() > limits::MAX_WALLS_PER_EDGE {
-        return Err(format!("{}: too many north walls ({} > {})",
-            context, sector.walls_north.len(), limits::MAX_WALLS_PER_EDGE));
-    }
-    if sector.walls_east.len() > limits::MAX_WALLS_PER_EDGE {
-        return Err(format!("{}: too many east walls ({} > {})",
-            context, sector.walls_east.len(), limits::MAX_WALLS_PER_EDGE));
-    }
-    if sector.walls_south.len() > limits::MAX_WALLS_PER_EDGE {
-        return Err(format!("{}: too many south walls ({} > {})",
-            context, sector.walls_south.len(), limits::MAX_WALLS_PER_EDGE));
-    }
-    if sector.walls_west.len() > limits::MAX_WALLS_PER_EDGE {
-        return Err(format!("{}: too many west walls ({} > {})",
-            context, sector.walls_west.len(), limits::MAX_WALLS_PER_EDGE));
-    }
-
-    // Validate each wall
-    for (i, wall) in sector.walls_north.iter().enumerate() {
-        validate_vertical_face(wall, &format!("{} walls_north[{}]", context, i))?;
-    }
-    for (i, wall) in sector.walls_east.iter().enumerate() {
-        validate_vertical_face(wall, &format!("{} walls_east[{}]", context, i))?;
-    }
-    for (i, wall) in sector.walls_south.iter().enumerate() {
-        validate_vertical_face(wall, &format!("{} walls_south[{}]", context, i))?;
-    }
-    for (i, wall) in sector.walls_west.iter().enumerate() {
-        validate_vertical_face(wall, &format!("{} walls_west[{}]", context, i))?;
+        check_horizontal_face(ceiling, &format!("{} > ceiling", field), issues);
+    }
+
+    let edges: [(&str, &mut Vec<VerticalFace>); 4] = [
+        ("walls_north", &mut sector.walls_north),
+        ("walls_east", &mut sector.walls_east),
+        ("walls_south", &mut sector.walls_south),
+        ("walls_west", &mut sector.walls_west),
+    ];
+    for (name, walls) in edges {
+        if walls.len() > limits::MAX_WALLS_PER_EDGE {
+            issues.push(LoadIssue::repairable(format!("{} > {}", field, name),
+                format!("too many walls ({} > {}), the extra ones are dropped", walls.len(), limits::MAX_WALLS_PER_EDGE), fix));
+            if fix {
+                walls.truncate(limits::MAX_WALLS_PER_EDGE);
+            }
+        }
+        for (i, wall) in walls.iter().enumerate() {
+            let wall_field = format!("{} > {}[{}]", field, name, i);
+            check_heights(&wall.heights, &wall_field, issues);
+            check_texture_ref(&wall.texture, &wall_field, issues);
+        }
     }
-
-    Ok(())
 }
 
-/// Validate a room
-fn validate_room(room: &Room, room_idx: usize, total_rooms: usize) -> Result<(), String> {
-    let context = format!("room[{}]", room_idx);
-
+/// Check a room; with `fix`, repairable problems are repaired
+fn check_room(room: &mut Room, field: &str, total_rooms: usize, fix: bool, issues: &mut Vec<LoadIssue>) {
     // Check room dimensions
+    let mut sized = true;
     if room.width > limits::MAX_ROOM_SIZE {
-        return Err(format!("{}: width too large ({} > {})",
-            context, room.width, limits::MAX_ROOM_SIZE));
+        issues.push(LoadIssue::error(format!("{} > width", field), format!("too large ({} > {})", room.width, limits::MAX_ROOM_SIZE)));
+        sized = false;
     }
     if room.depth > limits::MAX_ROOM_SIZE {
-        return Err(format!("{}: depth too large ({} > {})",
-            context, room.depth, limits::MAX_ROOM_SIZE));
+        issues.push(LoadIssue::error(format!("{} > depth", field), format!("too large ({} > {})", room.depth, limits::MAX_ROOM_SIZE)));
+        sized = false;
     }
 
     // Check position is valid
     if !is_valid_float(room.position.x) || !is_valid_float(room.position.y) || !is_valid_float(room.position.z) {
-        return Err(format!("{}: invalid position ({}, {}, {})",
-            context, room.position.x, room.position.y, room.position.z));
-    }
-
-    // Check sectors array matches dimensions
-    if room.sectors.len() != room.width {
-        return Err(format!("{}: sectors array width mismatch ({} != {})",
-            context, room.sectors.len(), room.width));
-    }
-    for (x, col) in room.sectors.iter().enumerate() {
-        if col.len() != room.depth {
-            return Err(format!("{}: sectors[{}] depth mismatch ({} != {})",
-                context, x, col.len(), room.depth));
+        issues.push(LoadIssue::error(format!("{} > position", field),
+            format!("invalid position ({}, {}, {})", room.position.x, room.position.y, room.position.z)));
+    }
+
+    // Sectors grid must match the dimensions: pad with empty sectors or cut
+    let grid_ok = room.sectors.len() == room.width && room.sectors.iter().all(|col| col.len() == room.depth);
+    if !grid_ok && sized {
+        let found = room.sectors.len();
+        issues.push(LoadIssue::repairable(format!("{} > sectors", field),
+            format!("grid doesn't match the room's {}x{} size ({} columns)", room.width, room.depth, found), fix));
+        if fix {
+            let depth = room.depth;
+            room.sectors.resize_with(room.width, Vec::new);
+            for col in &mut room.sectors {
+                col.resize_with(depth, || None);
+            }
         }
     }
 
-    // Validate portals (no count limit - portals are dynamically calculated)
+    // Portals into missing rooms or with broken coordinates are dropped
+    // (portals are recalculated from the geometry anyway)
+    let mut broken_portals = Vec::new();
     for (i, portal) in room.portals.iter().enumerate() {
+        let portal_field = format!("{} > portals[{}]", field, i);
         if portal.target_room >= total_rooms {
-            return Err(format!("{} portal[{}]: invalid target_room {} (only {} rooms)",
-                context, i, portal.target_room, total_rooms));
+            broken_portals.push(i);
+            issues.push(LoadIssue::repairable(format!("{} > target_room", portal_field),
+                format!("points at room {} but the level has {} rooms", portal.target_room, total_rooms), fix));
+            continue;
         }
-        // Validate portal vertices (Y can be infinite for open-air sectors)
-        for (j, v) in portal.vertices.iter().enumerate() {
-            if !is_valid_portal_coord(v.x) || !is_valid_portal_coord(v.y) || !is_valid_portal_coord(v.z) {
-                return Err(format!("{} portal[{}] vertex[{}]: invalid coordinates ({}, {}, {})",
-                    context, i, j, v.x, v.y, v.z));
-            }
+        // Portal vertices (Y can be infinite for open-air sectors)
+        let bad_vertex = portal.vertices.iter().position(|v| {
+            !is_valid_portal_coord(v.x) || !is_valid_portal_coord(v.y) || !is_valid_portal_coord(v.z)
+        });
+        let bad_normal = !is_valid_float(portal.normal.x) || !is_valid_float(portal.normal.y) || !is_valid_float(portal.normal.z);
+        if let Some(j) = bad_vertex {
+            broken_portals.push(i);
+            issues.push(LoadIssue::repairable(format!("{} > vertices[{}]", portal_field, j), "invalid coordinates", fix));
+        } else if bad_normal {
+            broken_portals.push(i);
+            issues.push(LoadIssue::repairable(format!("{} > normal", portal_field), "invalid normal", fix));
         }
-        // Validate portal normal
-        if !is_valid_float(portal.normal.x) || !is_valid_float(portal.normal.y) || !is_valid_float(portal.normal.z) {
-            return Err(format!("{} portal[{}]: invalid normal", context, i));
+    }
+    if fix {
+        for i in broken_portals.into_iter().rev() {
+            room.portals.remove(i);
         }
     }
 
-    // Validate ambient
+    // Ambient light falls back to the defaults
     if !is_valid_float(room.ambient) {
-        return Err(format!("{}: invalid ambient {}", context, room.ambient));
+        issues.push(LoadIssue::repairable(format!("{} > ambient", field), format!("invalid ambient {}", room.ambient), fix));
+        if fix {
+            room.ambient = 0.5;
+        }
+    }
+    let (r, g, b) = room.ambient_color;
+    if !is_valid_float(r) || !is_valid_float(g) || !is_valid_float(b) {
+        issues.push(LoadIssue::repairable(format!("{} > ambient_color", field), "invalid color", fix));
+        if fix {
+            room.ambient_color = (1.0, 1.0, 1.0);
+        }
     }
 
-    // Validate each sector
-    for (x, col) in room.sectors.iter().enumerate() {
-        for (z, sector_opt) in col.iter().enumerate() {
-            if let Some(sector) = sector_opt {
-                validate_sector(sector, &format!("{} sector[{},{}]", context, x, z))?;
+    // Instances outside the grid move to the nearest sector
+    if sized && room.width > 0 && room.depth > 0 {
+        let (width, depth) = (room.width, room.depth);
+        for (i, obj) in room.objects.iter_mut().enumerate() {
+            if obj.sector_x >= width || obj.sector_z >= depth {
+                issues.push(LoadIssue::repairable(format!("{} > objects[{}]", field, i),
+                    format!("placed at sector ({}, {}) outside the {}x{} room", obj.sector_x, obj.sector_z, width, depth), fix));
+                if fix {
+                    obj.sector_x = obj.sector_x.min(width - 1);
+                    obj.sector_z = obj.sector_z.min(depth - 1);
+                }
             }
         }
     }
 
-    Ok(())
+    // Check each sector
+    for (x, col) in room.sectors.iter_mut().enumerate() {
+        for (z, sector) in col.iter_mut().enumerate() {
+            if let Some(sector) = sector {
+                check_sector(sector, &format!("{} > sectors[{}][{}]", field, x, z), fix, issues);
+            }
+        }
+    }
 }
 
-/// Validate an entire level
-pub fn validate_level(level: &Level) -> Result<(), LevelError> {
-    // Check room count
+/// Check a parsed level against the limits and its own indices (room
+/// counts and sizes, portal targets, coordinates, wall counts). With `fix`,
+/// common problems are repaired in place and come back marked `fixed`;
+/// the rest stop the level from loading.
+pub fn check_level(level: &mut Level, fix: bool) -> Vec<LoadIssue> {
+    let mut issues = Vec::new();
     if level.rooms.len() > limits::MAX_ROOMS {
-        return Err(LevelError::ValidationError(format!(
-            "too many rooms ({} > {})", level.rooms.len(), limits::MAX_ROOMS
-        )));
+        issues.push(LoadIssue::error("rooms", format!("too many rooms ({} > {})", level.rooms.len(), limits::MAX_ROOMS)));
+        return issues;
     }
-
-    // Validate each room
-    for (i, room) in level.rooms.iter().enumerate() {
-        validate_room(room, i, level.rooms.len())
-            .map_err(LevelError::ValidationError)?;
+    let total_rooms = level.rooms.len();
+    for (i, room) in level.rooms.iter_mut().enumerate() {
+        check_room(room, &format!("rooms[{}]", i), total_rooms, fix, &mut issues);
     }
-
-    Ok(())
+    issues
 }
 
-/// Load a level from a RON file (supports both compressed and uncompressed)
-pub fn load_level<P: AsRef<Path>>(path: P) -> Result<Level, LevelError> {
-    let path = path.as_ref();
-    let bytes = fs::read(path)?;
-
+/// Decode level file bytes (brotli-compressed or plain RON) to RON text
+fn level_text(bytes: Vec<u8>) -> Result<String, LevelError> {
     // Detect format: RON files start with '(' or whitespace, brotli is binary
     let is_plain_ron = bytes.first().map(|&b| b == b'(' || b == b' ' || b == b'\n' || b == b'\r' || b == b'\t').unwrap_or(false);
 
-    let contents = if is_plain_ron {
+    if is_plain_ron {
         // Plain RON text
         String::from_utf8(bytes)
             .map_err(|e| LevelError::IoError(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 format!("invalid UTF-8: {}", e)
-            )))?
+            )))
     } else {
         // Brotli compressed - decompress first
         let mut decompressed = Vec::new();
@@ -265,34 +278,22 @@ pub fn load_level<P: AsRef<Path>>(path: P) -> Result<Level, LevelError> {
             .map_err(|e| LevelError::IoError(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 format!("invalid UTF-8 after decompression: {}", e)
-            )))?
-    };
+            )))
+    }
+}
 
-    let mut level: Level = match ron::from_str(&contents) {
-        Ok(l) => l,
-        Err(e) => {
-            // Log detailed error with context
-            eprintln!("RON parse error in {}: {}", path.display(), e);
-            let pos = e.position;
-            // Show context around the error
-            let lines: Vec<&str> = contents.lines().collect();
-            let line_idx = pos.line.saturating_sub(1);
-            if line_idx < lines.len() {
-                let line = lines[line_idx];
-                eprintln!("  Line {}: {}", pos.line, line);
-                if pos.col > 0 && pos.col <= line.len() {
-                    // Show surrounding characters
-                    let start = pos.col.saturating_sub(20);
-                    let end = (pos.col + 30).min(line.len());
-                    eprintln!("  Context: ...{}...", &line[start..end]);
-                }
-            }
-            return Err(e.into());
-        }
-    };
+/// Parse RON level text, migrate it to the current format, check it (repairing
+/// what can be repaired with `fix`) and get it ready to use
+fn level_from_ron(contents: &str, fix: bool) -> Result<(Level, Vec<LoadIssue>), LevelError> {
+    let mut level: Level = ron::from_str(contents)
+        .map_err(|e| LevelError::ParseError(describe_ron_error(contents, &e)))?;
 
-    // Validate level to prevent malicious files
-    validate_level(&level)?;
+    // Unpack v2 geometry, then check the level to reject broken or malicious files
+    migrate_level(&mut level).map_err(LevelError::FormatError)?;
+    let issues = check_level(&mut level, fix);
+    if let Some(problems) = unresolved(&issues) {
+        return Err(LevelError::ValidationError(problems));
+    }
 
     // Strip legacy objects (objects without asset_id) - migration to asset-based system
     for room in &mut level.rooms {
@@ -304,16 +305,35 @@ pub fn load_level<P: AsRef<Path>>(path: P) -> Result<Level, LevelError> {
         room.recalculate_bounds();
     }
 
-    Ok(level)
+    Ok((level, issues))
 }
 
-/// Save a level to a compressed RON file (brotli)
-pub fn save_level<P: AsRef<Path>>(level: &Level, path: P) -> Result<(), LevelError> {
+/// Load a level from a RON file (supports both compressed and uncompressed)
+pub fn load_level<P: AsRef<Path>>(path: P) -> Result<Level, LevelError> {
+    let path = path.as_ref();
+    let contents = level_text(fs::read(path)?)?;
+    match level_from_ron(&contents, false) {
+        Ok((level, _)) => Ok(level),
+        Err(e) => {
+            eprintln!("Failed to load {}: {}", path.display(), e);
+            Err(e)
+        }
+    }
+}
+
+/// Level as RON text in the current format (see `level_format`)
+pub fn level_to_ron(level: &Level) -> Result<String, LevelError> {
+    let packed = pack_level(level).map_err(LevelError::FormatError)?;
     let config = ron::ser::PrettyConfig::new()
         .depth_limit(4)
         .indentor("  ".to_string());
 
-    let ron_string = ron::ser::to_string_pretty(level, config)?;
+    Ok(ron::ser::to_string_pretty(&packed, config)?)
+}
+
+/// Save a level to a compressed RON file (brotli)
+pub fn save_level<P: AsRef<Path>>(level: &Level, path: P) -> Result<(), LevelError> {
+    let ron_string = level_to_ron(level)?;
 
     // Compress with brotli (quality 6, window 22 - good balance of speed/ratio)
     let mut compressed = Vec::new();
@@ -332,21 +352,14 @@ pub fn save_level<P: AsRef<Path>>(level: &Level, path: P) -> Result<(), LevelErr
 
 /// Load a level from a RON string (for embedded levels or testing)
 pub fn load_level_from_str(s: &str) -> Result<Level, LevelError> {
-    let mut level: Level = ron::from_str(s)?;
-
-    // Validate level to prevent malicious files
-    validate_level(&level)?;
-
-    // Strip legacy objects (objects without asset_id) - migration to asset-based system
-    for room in &mut level.rooms {
-        room.objects.retain(|obj| obj.asset_id != 0);
-    }
-
-    for room in &mut level.rooms {
-        room.recalculate_bounds();
-    }
+    level_from_ron(s, false).map(|(level, _)| level)
+}
 
-    Ok(level)
+/// Load a level from a RON string, repairing common problems instead of
+/// failing on them. Returns the level and everything found (repaired issues
+/// are marked `fixed`).
+pub fn repair_level_from_str(s: &str) -> Result<(Level, Vec<LoadIssue>), LevelError> {
+    level_from_ron(s, true)
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -364,92 +377,26 @@ pub fn load_level_with_storage(path: &str, storage: &Storage) -> Result<Level, L
             e.to_string(),
         )))?;
 
-    // Detect format: RON files start with '(' or whitespace, brotli is binary
-    let is_plain_ron = bytes.first().map(|&b| b == b'(' || b == b' ' || b == b'\n' || b == b'\r' || b == b'\t').unwrap_or(false);
-
-    let contents = if is_plain_ron {
-        // Plain RON text
-        String::from_utf8(bytes)
-            .map_err(|e| LevelError::IoError(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("invalid UTF-8: {}", e)
-            )))?
-    } else {
-        // Brotli compressed - decompress first
-        let mut decompressed = Vec::new();
-        brotli::BrotliDecompress(&mut Cursor::new(&bytes), &mut decompressed)
-            .map_err(|e| LevelError::IoError(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("brotli decompression failed: {}", e)
-            )))?;
-        String::from_utf8(decompressed)
-            .map_err(|e| LevelError::IoError(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("invalid UTF-8 after decompression: {}", e)
-            )))?
-    };
-
-    let mut level: Level = ron::from_str(&contents)?;
-
-    // Validate level to prevent malicious files
-    validate_level(&level)?;
-
-    // Strip legacy objects (objects without asset_id) - migration to asset-based system
-    for room in &mut level.rooms {
-        room.objects.retain(|obj| obj.asset_id != 0);
-    }
-
-    // Recalculate bounds for all rooms (not serialized)
-    for room in &mut level.rooms {
-        room.recalculate_bounds();
-    }
+    let contents = level_text(bytes)?;
+    level_from_ron(&contents, false).map(|(level, _)| level)
+}
 
-    Ok(level)
+/// Load a level from storage like `load_level_with_storage`, repairing common
+/// problems instead of failing on them (see `check_level`)
+pub fn repair_level_with_storage(path: &str, storage: &Storage) -> Result<(Level, Vec<LoadIssue>), LevelError> {
+    let bytes = storage
+        .read_sync(path)
+        .map_err(|e| LevelError::IoError(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            e.to_string(),
+        )))?;
+    repair_level_from_str(&level_text(bytes)?)
 }
 
 /// Parse level data from bytes (for async loading)
 pub fn parse_level_data(bytes: &[u8]) -> Result<Level, LevelError> {
-    // Detect format: RON files start with '(' or whitespace, brotli is binary
-    let is_plain_ron = bytes.first().map(|&b| b == b'(' || b == b' ' || b == b'\n' || b == b'\r' || b == b'\t').unwrap_or(false);
-
-    let contents = if is_plain_ron {
-        // Plain RON text
-        String::from_utf8(bytes.to_vec())
-            .map_err(|e| LevelError::IoError(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("invalid UTF-8: {}", e)
-            )))?
-    } else {
-        // Brotli compressed - decompress first
-        let mut decompressed = Vec::new();
-        brotli::BrotliDecompress(&mut Cursor::new(bytes), &mut decompressed)
-            .map_err(|e| LevelError::IoError(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("brotli decompression failed: {}", e)
-            )))?;
-        String::from_utf8(decompressed)
-            .map_err(|e| LevelError::IoError(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("invalid UTF-8 after decompression: {}", e)
-            )))?
-    };
-
-    let mut level: Level = ron::from_str(&contents)?;
-
-    // Validate level to prevent malicious files
-    validate_level(&level)?;
-
-    // Strip legacy objects (objects without asset_id) - migration to asset-based system
-    for room in &mut level.rooms {
-        room.objects.retain(|obj| obj.asset_id != 0);
-    }
-
-    // Recalculate bounds for all rooms (not serialized)
-    for room in &mut level.rooms {
-        room.recalculate_bounds();
-    }
-
-    Ok(level)
+    let contents = level_text(bytes.to_vec())?;
+    level_from_ron(&contents, false).map(|(level, _)| level)
 }
 
 /// Save a level using the storage backend
@@ -465,11 +412,7 @@ pub fn save_level_with_storage(level: &Level, path: &str, storage: &Storage) ->
 
 /// Serialize a level to compressed bytes (for async saving)
 pub fn serialize_level(level: &Level) -> Result<Vec<u8>, LevelError> {
-    let config = ron::ser::PrettyConfig::new()
-        .depth_limit(4)
-        .indentor("  ".to_string());
-
-    let ron_string = ron::ser::to_string_pretty(level, config)?;
+    let ron_string = level_to_ron(level)?;
 
     // Compress with brotli (quality 6, window 22 - good balance of speed/ratio)
     let mut compressed = Vec::new();
@@ -484,3 +427,55 @@ pub fn serialize_level(level: &Level) -> Result<Vec<u8>, LevelError> {
 
     Ok(compressed)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rasterizer::Vec3;
+    use crate::world::{create_test_level, AssetInstance, Portal};
+
+    fn broken_level() -> Level {
+        let mut level = create_test_level();
+        let room = &mut level.rooms[0];
+        room.portals.push(Portal::new(5, [Vec3::ZERO; 4], Vec3::new(1.0, 0.0, 0.0)));
+        room.ambient = f32::NAN;
+        room.objects.push(AssetInstance::new(3, 0, 1));
+        level
+    }
+
+    #[test]
+    fn test_check_level_reports_fields() {
+        let mut level = broken_level();
+        let issues = check_level(&mut level, false);
+        let fields: Vec<&str> = issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(fields, vec!["rooms[0] > portals[0] > target_room", "rooms[0] > ambient", "rooms[0] > objects[0]"]);
+        assert!(issues.iter().all(|i| i.fixable && !i.fixed));
+        // Nothing changes without `fix`
+        assert_eq!(level.rooms[0].portals.len(), 1);
+
+        level.rooms[0].width = 500;
+        let issues = check_level(&mut level, true);
+        assert_eq!(unresolved(&issues).as_deref(), Some("rooms[0] > width: too large (500 > 128)"));
+    }
+
+    #[test]
+    fn test_check_level_repairs() {
+        let mut level = broken_level();
+        let issues = check_level(&mut level, true);
+        assert_eq!(issues.len(), 3);
+        assert!(unresolved(&issues).is_none());
+        let room = &level.rooms[0];
+        assert!(room.portals.is_empty());
+        assert_eq!(room.ambient, 0.5);
+        assert_eq!((room.objects[0].sector_x, room.objects[0].sector_z), (0, 0));
+        assert!(check_level(&mut level, false).is_empty());
+    }
+
+    #[test]
+    fn test_parse_error_names_field() {
+        let Err(LevelError::ParseError(message)) = load_level_from_str("(rooms: [(id: oops") else {
+            panic!("expected a parse error");
+        };
+        assert!(message.contains("in rooms[0] > id"), "{}", message);
+    }
+}
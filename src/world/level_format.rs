@@ -0,0 +1,376 @@
+//! Level file format versions
+//!
+//! Version 1 files spell out every sector of every room in RON, which gets
+//! huge (and slow to write on WASM) once a level has a few thousand faces.
+//! Version 2 keeps the rest of the level as RON but packs the sector grids
+//! into the single `Level::geometry` string:
+//! - Each distinct floor, ceiling and wall face is stored once in a face table
+//! - The grids are varint arrays of face indices, delta-coded so runs of the
+//!   same or neighbouring faces shrink to a byte each
+//! - The blob is brotli-compressed and base64-encoded to stay valid RON
+//!
+//! Loading migrates older files up (`migrate_level`), saving always writes
+//! `LEVEL_FORMAT_VERSION`.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use super::{limits, Level, Sector, HorizontalFace, VerticalFace};
+
+/// Format version written by this build
+pub const LEVEL_FORMAT_VERSION: u32 = 2;
+
+/// Cell flags in the packed grid
+const CELL_PRESENT: u64 = 1;
+const CELL_FLOOR: u64 = 2;
+const CELL_CEILING: u64 = 4;
+const CELL_WALLS: u64 = 8;
+
+/// Face definitions shared by the packed grids
+#[derive(Deserialize)]
+struct FaceTable {
+    horizontal: Vec<HorizontalFace>,
+    vertical: Vec<VerticalFace>,
+}
+
+/// Bring a freshly parsed level up to the current format: unpack v2 geometry
+/// into the rooms (v1 files already have their sectors inline)
+pub fn migrate_level(level: &mut Level) -> Result<(), String> {
+    if level.version > LEVEL_FORMAT_VERSION {
+        return Err(format!(
+            "level format version {} is newer than this build supports ({})",
+            level.version, LEVEL_FORMAT_VERSION
+        ));
+    }
+    if !level.geometry.is_empty() {
+        unpack_geometry(level)?;
+    }
+    level.version = LEVEL_FORMAT_VERSION;
+    Ok(())
+}
+
+/// Copy of `level` ready to save in the current format: sectors packed into
+/// `geometry`, rooms left without their grids
+pub fn pack_level(level: &Level) -> Result<Level, String> {
+    let mut packed = level.clone();
+    for room in &mut packed.rooms {
+        room.sectors = Vec::new();
+    }
+    packed.geometry = pack_geometry(level)?;
+    packed.version = LEVEL_FORMAT_VERSION;
+    Ok(packed)
+}
+
+/// Walls of a sector in packed order
+fn edges(sector: &Sector) -> [&Vec<VerticalFace>; 6] {
+    [&sector.walls_north, &sector.walls_east, &sector.walls_south, &sector.walls_west, &sector.walls_nwse, &sector.walls_nesw]
+}
+
+fn edges_mut(sector: &mut Sector) -> [&mut Vec<VerticalFace>; 6] {
+    [
+        &mut sector.walls_north,
+        &mut sector.walls_east,
+        &mut sector.walls_south,
+        &mut sector.walls_west,
+        &mut sector.walls_nwse,
+        &mut sector.walls_nesw,
+    ]
+}
+
+/// Hands out one index per distinct face, compared by their RON text
+struct FaceInterner {
+    faces: Vec<String>,
+    lookup: HashMap<String, u32>,
+}
+
+impl FaceInterner {
+    fn new() -> Self {
+        Self { faces: Vec::new(), lookup: HashMap::new() }
+    }
+
+    fn index<T: Serialize>(&mut self, face: &T) -> Result<u32, String> {
+        let text = ron::to_string(face).map_err(|e| e.to_string())?;
+        if let Some(&index) = self.lookup.get(&text) {
+            return Ok(index);
+        }
+        let index = self.faces.len() as u32;
+        self.faces.push(text.clone());
+        self.lookup.insert(text, index);
+        Ok(index)
+    }
+}
+
+/// Varint byte stream
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn varint(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.bytes.push(byte);
+                return;
+            }
+            self.bytes.push(byte | 0x80);
+        }
+    }
+
+    /// `value` as a signed step from `last`
+    fn delta(&mut self, value: u32, last: &mut u32) {
+        let step = value as i64 - *last as i64;
+        *last = value;
+        self.varint(((step << 1) ^ (step >> 63)) as u64);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = *self.bytes.get(self.pos).ok_or("geometry data ends early")?;
+            self.pos += 1;
+            if shift >= 64 {
+                return Err("geometry varint too long".to_string());
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    /// A count no larger than `max`
+    fn count(&mut self, max: usize, what: &str) -> Result<usize, String> {
+        let count = self.varint()?;
+        if count > max as u64 {
+            return Err(format!("too many {} in geometry ({} > {})", what, count, max));
+        }
+        Ok(count as usize)
+    }
+
+    /// An index into a table of `len` faces, stepped from `last`
+    fn delta(&mut self, last: &mut u32, len: usize) -> Result<usize, String> {
+        let raw = self.varint()?;
+        let step = (raw >> 1) as i64 ^ -((raw & 1) as i64);
+        let value = *last as i64 + step;
+        if value < 0 || value >= len as i64 {
+            return Err(format!("face index {} out of range ({} faces)", value, len));
+        }
+        *last = value as u32;
+        Ok(value as usize)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len()).ok_or("geometry data ends early")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+}
+
+/// Pack every room's sector grid into a base64 string (see module docs)
+pub fn pack_geometry(level: &Level) -> Result<String, String> {
+    let mut horizontal = FaceInterner::new();
+    let mut vertical = FaceInterner::new();
+    let mut grid = Writer { bytes: Vec::new() };
+    let (mut last_horizontal, mut last_vertical) = (0, 0);
+
+    grid.varint(level.rooms.len() as u64);
+    for room in &level.rooms {
+        grid.varint(room.sectors.len() as u64);
+        for column in &room.sectors {
+            grid.varint(column.len() as u64);
+            for cell in column {
+                let Some(sector) = cell else {
+                    grid.varint(0);
+                    continue;
+                };
+                let has_walls = edges(sector).iter().any(|walls| !walls.is_empty());
+                let mut flags = CELL_PRESENT;
+                if sector.floor.is_some() {
+                    flags |= CELL_FLOOR;
+                }
+                if sector.ceiling.is_some() {
+                    flags |= CELL_CEILING;
+                }
+                if has_walls {
+                    flags |= CELL_WALLS;
+                }
+                grid.varint(flags);
+                for face in [&sector.floor, &sector.ceiling].into_iter().flatten() {
+                    grid.delta(horizontal.index(face)?, &mut last_horizontal);
+                }
+                if has_walls {
+                    for walls in edges(sector) {
+                        grid.varint(walls.len() as u64);
+                        for wall in walls {
+                            grid.delta(vertical.index(wall)?, &mut last_vertical);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Faces are already RON, so the table is spliced together as text
+    let table = format!("(horizontal:[{}],vertical:[{}])", horizontal.faces.join(","), vertical.faces.join(","));
+    let mut bytes = Writer { bytes: Vec::with_capacity(table.len() + grid.bytes.len() + 8) };
+    bytes.varint(table.len() as u64);
+    bytes.bytes.extend_from_slice(table.as_bytes());
+    bytes.bytes.extend_from_slice(&grid.bytes);
+
+    let mut compressed = Vec::new();
+    brotli::BrotliCompress(&mut Cursor::new(&bytes.bytes), &mut compressed, &brotli::enc::BrotliEncoderParams {
+        quality: 6,
+        lgwin: 22,
+        ..Default::default()
+    }).map_err(|e| format!("brotli compression failed: {}", e))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(&compressed))
+}
+
+/// Unpack `level.geometry` back into the rooms' sector grids and clear it
+pub fn unpack_geometry(level: &mut Level) -> Result<(), String> {
+    let compressed = base64::engine::general_purpose::STANDARD
+        .decode(level.geometry.trim())
+        .map_err(|e| format!("invalid geometry base64: {}", e))?;
+    let mut bytes = Vec::new();
+    brotli::BrotliDecompress(&mut Cursor::new(&compressed), &mut bytes)
+        .map_err(|e| format!("geometry decompression failed: {}", e))?;
+
+    let mut reader = Reader { bytes: &bytes, pos: 0 };
+    let table_len = reader.count(bytes.len(), "table bytes")?;
+    let table_text = std::str::from_utf8(reader.take(table_len)?).map_err(|e| format!("invalid face table: {}", e))?;
+    let table: FaceTable = ron::from_str(table_text).map_err(|e| format!("invalid face table: {}", e))?;
+
+    let room_count = reader.count(limits::MAX_ROOMS, "rooms")?;
+    if room_count != level.rooms.len() {
+        return Err(format!("geometry has {} rooms, level has {}", room_count, level.rooms.len()));
+    }
+    let (mut last_horizontal, mut last_vertical) = (0, 0);
+    for room in &mut level.rooms {
+        let columns = reader.count(limits::MAX_ROOM_SIZE, "columns")?;
+        room.sectors = Vec::with_capacity(columns);
+        for _ in 0..columns {
+            let cells = reader.count(limits::MAX_ROOM_SIZE, "cells")?;
+            let mut column = Vec::with_capacity(cells);
+            for _ in 0..cells {
+                let flags = reader.varint()?;
+                if flags & CELL_PRESENT == 0 {
+                    column.push(None);
+                    continue;
+                }
+                let mut sector = Sector::empty();
+                if flags & CELL_FLOOR != 0 {
+                    let index = reader.delta(&mut last_horizontal, table.horizontal.len())?;
+                    sector.floor = Some(table.horizontal[index].clone());
+                }
+                if flags & CELL_CEILING != 0 {
+                    let index = reader.delta(&mut last_horizontal, table.horizontal.len())?;
+                    sector.ceiling = Some(table.horizontal[index].clone());
+                }
+                if flags & CELL_WALLS != 0 {
+                    for walls in edges_mut(&mut sector) {
+                        let count = reader.count(limits::MAX_WALLS_PER_EDGE, "walls")?;
+                        for _ in 0..count {
+                            let index = reader.delta(&mut last_vertical, table.vertical.len())?;
+                            walls.push(table.vertical[index].clone());
+                        }
+                    }
+                }
+                column.push(Some(sector));
+            }
+            room.sectors.push(column);
+        }
+    }
+    if reader.pos != bytes.len() {
+        return Err("trailing data after geometry".to_string());
+    }
+
+    level.geometry.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rasterizer::Vec3;
+    use crate::world::{create_test_level, Direction, Room, TextureRef};
+
+    fn sectors_ron(level: &Level) -> Vec<String> {
+        level.rooms.iter().map(|room| ron::to_string(&room.sectors).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_pack_round_trip_dedups_faces() {
+        let mut level = create_test_level();
+        let mut hall = Room::new(1, Vec3::new(2048.0, 0.0, 0.0), 6, 4);
+        let texture = TextureRef::new("retro-texture-pack", "FLOOR_1A");
+        for x in 0..6 {
+            for z in 0..4 {
+                if (x + z) % 5 != 0 {
+                    hall.set_floor(x, z, 0.0, texture.clone());
+                }
+            }
+            hall.add_wall(x, 0, Direction::North, 0.0, 1024.0, texture.clone());
+        }
+        level.add_room(hall);
+
+        let packed = pack_level(&level).unwrap();
+        assert!(packed.rooms.iter().all(|room| room.sectors.is_empty()));
+        assert_eq!(packed.version, LEVEL_FORMAT_VERSION);
+
+        // Identical floors and walls share one table entry each
+        let compressed = base64::engine::general_purpose::STANDARD.decode(&packed.geometry).unwrap();
+        let mut bytes = Vec::new();
+        brotli::BrotliDecompress(&mut Cursor::new(&compressed), &mut bytes).unwrap();
+        let mut reader = Reader { bytes: &bytes, pos: 0 };
+        let len = reader.varint().unwrap() as usize;
+        let table: FaceTable = ron::from_str(std::str::from_utf8(reader.take(len).unwrap()).unwrap()).unwrap();
+        assert!(table.horizontal.len() <= 2);
+        assert!(table.vertical.len() <= 5);
+
+        let text = ron::to_string(&packed).unwrap();
+        let mut loaded: Level = ron::from_str(&text).unwrap();
+        migrate_level(&mut loaded).unwrap();
+        assert!(loaded.geometry.is_empty());
+        assert_eq!(sectors_ron(&loaded), sectors_ron(&level));
+    }
+
+    #[test]
+    fn test_v1_levels_migrate() {
+        let level = create_test_level();
+        // A v1 file: sectors inline, no version or geometry fields
+        let text = ron::to_string(&level).unwrap().replacen(&format!("version:{},", LEVEL_FORMAT_VERSION), "", 1);
+        let mut loaded: Level = ron::from_str(&text).unwrap();
+        assert_eq!(loaded.version, 1);
+        migrate_level(&mut loaded).unwrap();
+        assert_eq!(loaded.version, LEVEL_FORMAT_VERSION);
+        assert_eq!(sectors_ron(&loaded), sectors_ron(&level));
+    }
+
+    #[test]
+    fn test_bad_geometry_is_rejected() {
+        let mut packed = pack_level(&create_test_level()).unwrap();
+        packed.rooms.pop();
+        assert!(migrate_level(&mut packed.clone()).is_err());
+
+        let mut future = create_test_level();
+        future.version = LEVEL_FORMAT_VERSION + 1;
+        assert!(migrate_level(&mut future).is_err());
+
+        let mut garbage = create_test_level();
+        garbage.geometry = "not base64!".to_string();
+        assert!(migrate_level(&mut garbage).is_err());
+    }
+}
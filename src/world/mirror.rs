@@ -0,0 +1,153 @@
+//! Mirror Surfaces
+//!
+//! Floor, ceiling and wall faces can be flagged `mirror`. While rendering, the
+//! room the camera is in gets re-rendered from a camera reflected about the
+//! nearest mirror's plane and copied into the pixels where that mirror is
+//! visible - the classic PS1 mirrored-room trick, no render targets needed.
+//!
+//! Only one mirror renders per frame (`MIRROR_BUDGET`). Coplanar mirror faces
+//! in a room count as one mirror, so a mirror can span several sectors.
+
+use crate::rasterizer::{Camera, Vec3};
+
+/// Mirrors rendered per frame (and the Strict PS1 budget per level)
+pub const MIRROR_BUDGET: usize = 1;
+
+/// Faces closer than this to a plane (and this parallel) count as coplanar
+const PLANE_EPSILON: f32 = 1.0;
+const NORMAL_EPSILON: f32 = 0.999;
+
+/// A mirror face in world space
+#[derive(Debug, Clone, Copy)]
+pub struct MirrorFace {
+    /// Room containing the face
+    pub room: usize,
+    /// World-space corners (quad)
+    pub corners: [Vec3; 4],
+    /// Front normal (the reflecting side)
+    pub normal: Vec3,
+}
+
+impl MirrorFace {
+    pub fn center(&self) -> Vec3 {
+        (self.corners[0] + self.corners[1] + self.corners[2] + self.corners[3]) * 0.25
+    }
+
+    /// Signed distance from the mirror plane (positive in front)
+    pub fn distance(&self, point: Vec3) -> f32 {
+        (point - self.corners[0]).dot(self.normal)
+    }
+
+    /// Is `point` on the reflecting side?
+    pub fn faces(&self, point: Vec3) -> bool {
+        self.distance(point) > 0.0
+    }
+
+    /// Does `other` lie in the same plane, facing the same way?
+    pub fn coplanar(&self, other: &MirrorFace) -> bool {
+        self.normal.dot(other.normal) > NORMAL_EPSILON
+            && other.corners.iter().all(|&c| self.distance(c).abs() < PLANE_EPSILON)
+    }
+
+    pub fn reflect_point(&self, point: Vec3) -> Vec3 {
+        point - self.normal * (2.0 * self.distance(point))
+    }
+
+    pub fn reflect_direction(&self, dir: Vec3) -> Vec3 {
+        dir - self.normal * (2.0 * dir.dot(self.normal))
+    }
+
+    /// The camera seen in the mirror.
+    ///
+    /// A true reflection flips handedness, which would also flip triangle
+    /// winding and break backface culling. Negating the X axis keeps the basis
+    /// right-handed, so the result renders left-right flipped: read it back
+    /// mirrored horizontally.
+    pub fn reflect_camera(&self, camera: &Camera) -> Camera {
+        let mut reflected = camera.clone();
+        reflected.position = self.reflect_point(camera.position);
+        reflected.basis_x = self.reflect_direction(camera.basis_x) * -1.0;
+        reflected.basis_y = self.reflect_direction(camera.basis_y);
+        reflected.basis_z = self.reflect_direction(camera.basis_z);
+        reflected
+    }
+}
+
+/// Group mirror faces into planes (coplanar faces in the same room are one mirror)
+pub fn mirror_planes(faces: &[MirrorFace]) -> Vec<Vec<MirrorFace>> {
+    let mut planes: Vec<Vec<MirrorFace>> = Vec::new();
+    for face in faces {
+        match planes.iter_mut().find(|p| p[0].room == face.room && p[0].coplanar(face)) {
+            Some(plane) => plane.push(*face),
+            None => planes.push(vec![*face]),
+        }
+    }
+    planes
+}
+
+/// The mirror to render: the plane with the nearest face that faces the camera
+pub fn pick_mirror(faces: &[MirrorFace], camera_pos: Vec3) -> Option<Vec<MirrorFace>> {
+    mirror_planes(faces)
+        .into_iter()
+        .filter(|plane| plane[0].faces(camera_pos))
+        .map(|plane| {
+            let nearest = plane.iter()
+                .map(|f| (f.center() - camera_pos).len())
+                .fold(f32::MAX, f32::min);
+            (nearest, plane)
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, plane)| plane)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wall in the X=0 plane facing +X, spanning z0..z1
+    fn wall(room: usize, z0: f32, z1: f32) -> MirrorFace {
+        MirrorFace {
+            room,
+            corners: [
+                Vec3::new(0.0, 0.0, z0),
+                Vec3::new(0.0, 0.0, z1),
+                Vec3::new(0.0, 1024.0, z1),
+                Vec3::new(0.0, 1024.0, z0),
+            ],
+            normal: Vec3::new(1.0, 0.0, 0.0),
+        }
+    }
+
+    #[test]
+    fn reflects_across_plane() {
+        let mirror = wall(0, 0.0, 1024.0);
+        let p = mirror.reflect_point(Vec3::new(300.0, 50.0, 20.0));
+        assert_eq!((p.x, p.y, p.z), (-300.0, 50.0, 20.0));
+        let d = mirror.reflect_direction(Vec3::new(1.0, 0.0, 1.0));
+        assert_eq!((d.x, d.y, d.z), (-1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn reflected_camera_stays_right_handed() {
+        let mut camera = Camera::new();
+        camera.position = Vec3::new(500.0, 200.0, 300.0);
+        camera.rotation_y = 1.0;
+        camera.update_basis();
+        let reflected = wall(0, 0.0, 1024.0).reflect_camera(&camera);
+        let handedness = |c: &Camera| c.basis_x.cross(c.basis_y).dot(c.basis_z);
+        assert!((handedness(&reflected) - handedness(&camera)).abs() < 0.001);
+        assert!(reflected.position.x < 0.0);
+    }
+
+    #[test]
+    fn coplanar_faces_are_one_mirror() {
+        let faces = [wall(0, 0.0, 1024.0), wall(0, 1024.0, 2048.0), wall(1, 0.0, 1024.0)];
+        let planes = mirror_planes(&faces);
+        assert_eq!(planes.len(), 2);
+        assert_eq!(planes[0].len(), 2);
+
+        // Behind the mirror: nothing to show
+        assert!(pick_mirror(&faces, Vec3::new(-100.0, 0.0, 0.0)).is_none());
+        assert_eq!(pick_mirror(&faces, Vec3::new(100.0, 0.0, 0.0)).map(|p| p.len()), Some(2));
+    }
+}
@@ -12,6 +12,42 @@
 
 mod geometry;
 mod level;
+mod level_format;
+mod diff;
+mod budget;
+mod portal_check;
+mod heightmap;
+mod dungeon;
+mod quake_map;
+mod weather;
+mod mirror;
+mod trigger;
+mod ladder;
+mod camera_volume;
+mod path;
+mod water;
+mod cutscene;
+mod streaming;
+mod atmosphere;
+mod texture_packs;
 
 pub use geometry::*;
 pub use level::*;
+pub use level_format::*;
+pub use diff::*;
+pub use budget::*;
+pub use portal_check::*;
+pub use heightmap::*;
+pub use dungeon::*;
+pub use quake_map::*;
+pub use weather::*;
+pub use mirror::*;
+pub use trigger::*;
+pub use ladder::*;
+pub use camera_volume::*;
+pub use path::*;
+pub use water::*;
+pub use cutscene::*;
+pub use streaming::*;
+pub use atmosphere::*;
+pub use texture_packs::*;
@@ -0,0 +1,237 @@
+//! Entity paths
+//!
+//! Ordered waypoints authored in the World Editor (placed in the 2D grid view)
+//! that enemies and moving platforms follow in play mode. A path either loops
+//! back to its first waypoint or ping-pongs between its ends. Waypoints are
+//! room-relative so paths move with their room.
+//!
+//! Instances attach to a path by id with a `PathFollow`, which also sets how
+//! fast they travel (see `game::level_logic`).
+
+use serde::{Serialize, Deserialize};
+use crate::rasterizer::Vec3;
+use super::SECTOR_SIZE;
+
+/// Waypoints snap to quarter sectors while placing
+pub const WAYPOINT_SNAP: f32 = SECTOR_SIZE / 4.0;
+/// Travel speed of a newly attached follower (world units per second)
+pub const DEFAULT_PATH_SPEED: f32 = SECTOR_SIZE;
+
+/// What a follower does at the last waypoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PathMode {
+    /// Carry on to the first waypoint and go round again
+    #[default]
+    Loop,
+    /// Turn around and walk the path backwards
+    PingPong,
+}
+
+impl PathMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PathMode::Loop => "Loop",
+            PathMode::PingPong => "Ping-Pong",
+        }
+    }
+
+    /// The other mode
+    pub fn toggled(self) -> Self {
+        match self {
+            PathMode::Loop => PathMode::PingPong,
+            PathMode::PingPong => PathMode::Loop,
+        }
+    }
+}
+
+/// An ordered list of waypoints in a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityPath {
+    /// Identifier instances attach to
+    pub path_id: String,
+    #[serde(default)]
+    pub mode: PathMode,
+    /// Waypoints in travel order (room-relative)
+    pub points: Vec<Vec3>,
+}
+
+/// Snap a coordinate to the waypoint grid
+pub fn snap_waypoint_coord(value: f32) -> f32 {
+    (value / WAYPOINT_SNAP).round() * WAYPOINT_SNAP
+}
+
+impl EntityPath {
+    pub fn new(path_id: impl Into<String>) -> Self {
+        Self { path_id: path_id.into(), mode: PathMode::default(), points: Vec::new() }
+    }
+
+    /// World-space waypoints for a room at `room_position`
+    pub fn world_points(&self, room_position: Vec3) -> Vec<Vec3> {
+        self.points.iter().map(|&p| room_position + p).collect()
+    }
+
+    /// World-space average of the waypoints (the room position if there are none)
+    pub fn world_center(&self, room_position: Vec3) -> Vec3 {
+        if self.points.is_empty() {
+            return room_position;
+        }
+        let sum = self.points.iter().fold(Vec3::ZERO, |acc, &p| acc + p);
+        room_position + sum * (1.0 / self.points.len() as f32)
+    }
+
+    /// Move by a room-relative offset
+    pub fn translate(&mut self, offset: Vec3) {
+        for point in &mut self.points {
+            *point = *point + offset;
+        }
+    }
+}
+
+/// Next unused `pathN` id in a level
+pub fn next_path_id<'a>(existing: impl Iterator<Item = &'a EntityPath>) -> String {
+    let highest = existing
+        .filter_map(|p| p.path_id.strip_prefix("path")?.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("path{}", highest + 1)
+}
+
+fn default_path_speed() -> f32 {
+    DEFAULT_PATH_SPEED
+}
+
+/// An instance's link to the path it follows in play mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathFollow {
+    /// `EntityPath::path_id` of the path to follow
+    pub path_id: String,
+    /// World units per second
+    #[serde(default = "default_path_speed")]
+    pub speed: f32,
+}
+
+impl PathFollow {
+    pub fn new(path_id: impl Into<String>) -> Self {
+        Self { path_id: path_id.into(), speed: DEFAULT_PATH_SPEED }
+    }
+}
+
+/// Position and travel direction (normalized, zero when standing still) after
+/// covering `distance` along `points` from the first waypoint.
+/// None for a path without waypoints.
+pub fn point_along_path(points: &[Vec3], mode: PathMode, distance: f32) -> Option<(Vec3, Vec3)> {
+    let first = *points.first()?;
+    let legs = path_legs(points, mode);
+    let total: f32 = legs.iter().map(|(a, b)| (*b - *a).len()).sum();
+    if total <= f32::EPSILON {
+        return Some((first, Vec3::ZERO));
+    }
+    let mut remaining = distance.rem_euclid(total);
+    for (a, b) in legs {
+        let length = (b - a).len();
+        if length <= f32::EPSILON {
+            continue;
+        }
+        let direction = (b - a) * (1.0 / length);
+        if remaining <= length {
+            return Some((a + direction * remaining, direction));
+        }
+        remaining -= length;
+    }
+    Some((first, Vec3::ZERO))
+}
+
+/// Distance from the first waypoint at which each waypoint is reached over
+/// one trip round the path (ping-pong paths count the way back too). The
+/// last entry is the trip length. Empty for a path without waypoints.
+pub fn path_stops(points: &[Vec3], mode: PathMode) -> Vec<f32> {
+    let mut covered = 0.0;
+    path_legs(points, mode)
+        .into_iter()
+        .map(|(a, b)| {
+            covered += (b - a).len();
+            covered
+        })
+        .collect()
+}
+
+/// The legs of one trip round the path, in travel order
+fn path_legs(points: &[Vec3], mode: PathMode) -> Vec<(Vec3, Vec3)> {
+    let Some(&first) = points.first() else {
+        return Vec::new();
+    };
+    let mut legs: Vec<(Vec3, Vec3)> = points.windows(2).map(|w| (w[0], w[1])).collect();
+    match mode {
+        PathMode::Loop => legs.push((points[points.len() - 1], first)),
+        PathMode::PingPong => {
+            let back: Vec<(Vec3, Vec3)> = legs.iter().rev().map(|&(a, b)| (b, a)).collect();
+            legs.extend(back);
+        }
+    }
+    legs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Vec<Vec3> {
+        vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(SECTOR_SIZE, 0.0, 0.0),
+            Vec3::new(SECTOR_SIZE, 0.0, SECTOR_SIZE),
+            Vec3::new(0.0, 0.0, SECTOR_SIZE),
+        ]
+    }
+
+    #[test]
+    fn test_loop_wraps_to_first_waypoint() {
+        let points = square();
+        let (p, dir) = point_along_path(&points, PathMode::Loop, SECTOR_SIZE * 1.5).unwrap();
+        assert_eq!((p.x, p.z), (SECTOR_SIZE, SECTOR_SIZE * 0.5));
+        assert_eq!((dir.x, dir.z), (0.0, 1.0));
+        // The closing leg runs back to the start, then it goes round again
+        let (p, dir) = point_along_path(&points, PathMode::Loop, SECTOR_SIZE * 3.5).unwrap();
+        assert_eq!((p.x, p.z), (0.0, SECTOR_SIZE * 0.5));
+        assert_eq!(dir.z, -1.0);
+        let (p, _) = point_along_path(&points, PathMode::Loop, SECTOR_SIZE * 4.25).unwrap();
+        assert_eq!((p.x, p.z), (SECTOR_SIZE * 0.25, 0.0));
+    }
+
+    #[test]
+    fn test_ping_pong_turns_around() {
+        let points = square();
+        // Three legs out, three back: no closing leg
+        let (p, dir) = point_along_path(&points, PathMode::PingPong, SECTOR_SIZE * 3.5).unwrap();
+        assert_eq!((p.x, p.z), (SECTOR_SIZE * 0.5, SECTOR_SIZE));
+        assert_eq!(dir.x, 1.0);
+        let (p, _) = point_along_path(&points, PathMode::PingPong, SECTOR_SIZE * 6.0).unwrap();
+        assert_eq!((p.x, p.z), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_degenerate_paths() {
+        assert!(point_along_path(&[], PathMode::Loop, 10.0).is_none());
+        let single = [Vec3::new(5.0, 1.0, 5.0)];
+        let (p, dir) = point_along_path(&single, PathMode::PingPong, 100.0).unwrap();
+        assert_eq!((p.x, p.y, p.z), (5.0, 1.0, 5.0));
+        assert_eq!(dir.len(), 0.0);
+    }
+
+    #[test]
+    fn test_path_stops() {
+        let points = square();
+        assert_eq!(path_stops(&points, PathMode::Loop), vec![SECTOR_SIZE, SECTOR_SIZE * 2.0, SECTOR_SIZE * 3.0, SECTOR_SIZE * 4.0]);
+        let lift = [Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, SECTOR_SIZE, 0.0)];
+        assert_eq!(path_stops(&lift, PathMode::PingPong), vec![SECTOR_SIZE, SECTOR_SIZE * 2.0]);
+        assert!(path_stops(&[], PathMode::Loop).is_empty());
+    }
+
+    #[test]
+    fn test_next_path_id() {
+        let a = EntityPath::new("path4");
+        let b = EntityPath::new("guard_route");
+        assert_eq!(next_path_id([a, b].iter()), "path5");
+        assert_eq!(next_path_id(std::iter::empty()), "path1");
+    }
+}
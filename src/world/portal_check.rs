@@ -0,0 +1,305 @@
+//! Portal Checks
+//!
+//! Validates how rooms connect, for the editor's portal debug view:
+//!
+//! - One-way portals: a portal whose target room has no matching portal back
+//!   (or whose target room doesn't exist)
+//! - Open edges: a sector edge that looks straight into another room's sector
+//!   without a portal - grids out of alignment, a wall on one side only, or
+//!   floors and ceilings that don't line up. The renderer can't see through
+//!   these, so the player sees void
+//! - Overlaps: two rooms claiming the same floor space at the same height
+//! - Unreachable rooms: rooms the player start can't reach through portals
+//!
+//! Like budgets, these are warnings for the author; nothing refuses to save.
+
+use std::collections::VecDeque;
+use crate::rasterizer::Vec3;
+use super::{Direction, Level, Room, Sector, SECTOR_SIZE};
+
+/// How close portal corners must be to count as the same opening
+const VERTEX_EPSILON: f32 = 1.0;
+
+/// A problem with how rooms connect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PortalIssue {
+    /// Portal `portal` of `room` leads to `target`, which has no portal back
+    OneWay { room: usize, portal: usize, target: usize },
+    /// Portal `portal` of `room` leads to a room that doesn't exist
+    BadTarget { room: usize, portal: usize, target: usize },
+    /// Edge `dir` of sector (x, z) in `room` opens into `other` with no portal
+    OpenEdge { room: usize, x: usize, z: usize, dir: Direction, other: usize },
+    /// Rooms `a` and `b` both cover sector (x, z) of `a` at overlapping heights
+    Overlap { a: usize, b: usize, x: usize, z: usize },
+    /// No portal path leads from the start room to `room`
+    Unreachable { room: usize },
+}
+
+impl PortalIssue {
+    /// Room the issue is reported against
+    pub fn room(&self) -> usize {
+        match *self {
+            PortalIssue::OneWay { room, .. }
+            | PortalIssue::BadTarget { room, .. }
+            | PortalIssue::OpenEdge { room, .. }
+            | PortalIssue::Unreachable { room } => room,
+            PortalIssue::Overlap { a, .. } => a,
+        }
+    }
+}
+
+impl std::fmt::Display for PortalIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            PortalIssue::OneWay { room, portal, target } => write!(
+                f,
+                "Room {} portal {} has no matching portal back from room {}",
+                room, portal, target
+            ),
+            PortalIssue::BadTarget { room, portal, target } => {
+                write!(f, "Room {} portal {} leads to missing room {}", room, portal, target)
+            }
+            PortalIssue::OpenEdge { room, x, z, dir, other } => write!(
+                f,
+                "Room {} sector ({}, {}) {} edge opens into room {} without a portal",
+                room, x, z, dir.name(), other
+            ),
+            PortalIssue::Overlap { a, b, x, z } => {
+                write!(f, "Rooms {} and {} overlap at sector ({}, {}) of room {}", a, b, x, z, a)
+            }
+            PortalIssue::Unreachable { room } => write!(f, "Room {} can't be reached from the start room", room),
+        }
+    }
+}
+
+/// World-space floor and ceiling of a closed sector (both faces present)
+fn closed_span(room: &Room, sector: &Sector) -> Option<(f32, f32)> {
+    let floor = sector.floor.as_ref()?.avg_height();
+    let ceiling = sector.ceiling.as_ref()?.avg_height();
+    Some((room.position.y + floor, room.position.y + ceiling))
+}
+
+fn spans_overlap(a: (f32, f32), b: (f32, f32)) -> bool {
+    a.0.max(b.0) < a.1.min(b.1)
+}
+
+/// World-space center of sector (x, z)
+fn sector_center(room: &Room, x: usize, z: usize) -> (f32, f32) {
+    (
+        room.position.x + (x as f32 + 0.5) * SECTOR_SIZE,
+        room.position.z + (z as f32 + 0.5) * SECTOR_SIZE,
+    )
+}
+
+fn sector_at(room: &Room, x: f32, z: f32) -> Option<&Sector> {
+    let (gx, gz) = room.world_to_grid(x, z)?;
+    room.get_sector(gx, gz)
+}
+
+fn same_point(a: Vec3, b: Vec3) -> bool {
+    let close = |p: f32, q: f32| p == q || (p - q).abs() <= VERTEX_EPSILON;
+    close(a.x, b.x) && close(a.y, b.y) && close(a.z, b.z)
+}
+
+/// Does `room` have a portal back to `from` covering the same world corners?
+fn has_return_portal(room: &Room, from: usize, corners: &[Vec3; 4]) -> bool {
+    room.portals.iter().filter(|p| p.target_room == from).any(|p| {
+        corners.iter().all(|&c| p.vertices.iter().any(|&v| same_point(room.position + v, c)))
+    })
+}
+
+fn one_way_portals(level: &Level, issues: &mut Vec<PortalIssue>) {
+    for (room_idx, room) in level.rooms.iter().enumerate() {
+        for (portal_idx, portal) in room.portals.iter().enumerate() {
+            let target = portal.target_room;
+            let Some(target_room) = level.rooms.get(target).filter(|_| target != room_idx) else {
+                issues.push(PortalIssue::BadTarget { room: room_idx, portal: portal_idx, target });
+                continue;
+            };
+            let corners = portal.vertices.map(|v| room.position + v);
+            if !has_return_portal(target_room, room_idx, &corners) {
+                issues.push(PortalIssue::OneWay { room: room_idx, portal: portal_idx, target });
+            }
+        }
+    }
+}
+
+fn open_edges(level: &Level, issues: &mut Vec<PortalIssue>) {
+    let cardinal = [Direction::North, Direction::East, Direction::South, Direction::West];
+    for (room_idx, room) in level.rooms.iter().enumerate() {
+        for (x, z, sector) in room.iter_sectors() {
+            let Some(span) = closed_span(room, sector) else { continue };
+            let (cx, cz) = sector_center(room, x, z);
+            for dir in cardinal {
+                if !sector.walls(dir).is_empty() {
+                    continue;
+                }
+                let (dx, dz) = dir.offset();
+                let (bx, bz) = (cx + dx as f32 * SECTOR_SIZE, cz + dz as f32 * SECTOR_SIZE);
+                if room.world_to_grid(bx, bz).is_some() {
+                    continue; // Inside this room: not a room connection
+                }
+                let (ex, ez) = (cx + dx as f32 * SECTOR_SIZE * 0.5, cz + dz as f32 * SECTOR_SIZE * 0.5);
+                for (other_idx, other) in level.rooms.iter().enumerate() {
+                    if other_idx == room_idx {
+                        continue;
+                    }
+                    let Some(neighbor) = sector_at(other, bx, bz) else { continue };
+                    let Some(other_span) = closed_span(other, neighbor) else { continue };
+                    if !spans_overlap(span, other_span) {
+                        continue;
+                    }
+                    let has_portal = room.portals.iter().any(|p| {
+                        let c = room.position + p.center();
+                        p.target_room == other_idx
+                            && (c.x - ex).abs() < SECTOR_SIZE * 0.5
+                            && (c.z - ez).abs() < SECTOR_SIZE * 0.5
+                    });
+                    if !has_portal {
+                        issues.push(PortalIssue::OpenEdge { room: room_idx, x, z, dir, other: other_idx });
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn overlaps(level: &Level, issues: &mut Vec<PortalIssue>) {
+    for (a_idx, a) in level.rooms.iter().enumerate() {
+        for (b_idx, b) in level.rooms.iter().enumerate().skip(a_idx + 1) {
+            let shared = a.iter_sectors().find(|&(x, z, sector)| {
+                let (cx, cz) = sector_center(a, x, z);
+                let Some(span) = closed_span(a, sector) else { return false };
+                sector_at(b, cx, cz)
+                    .and_then(|other| closed_span(b, other))
+                    .is_some_and(|other_span| spans_overlap(span, other_span))
+            });
+            if let Some((x, z, _)) = shared {
+                issues.push(PortalIssue::Overlap { a: a_idx, b: b_idx, x, z });
+            }
+        }
+    }
+}
+
+fn unreachable(level: &Level, start_room: usize, issues: &mut Vec<PortalIssue>) {
+    let mut visited = vec![false; level.rooms.len()];
+    let mut queue = VecDeque::from([start_room]);
+    visited[start_room] = true;
+    while let Some(room_idx) = queue.pop_front() {
+        for portal in &level.rooms[room_idx].portals {
+            if let Some(seen) = visited.get_mut(portal.target_room) {
+                if !*seen {
+                    *seen = true;
+                    queue.push_back(portal.target_room);
+                }
+            }
+        }
+    }
+    for (room_idx, room) in level.rooms.iter().enumerate() {
+        // Empty rooms have nothing to reach
+        if !visited[room_idx] && room.iter_sectors().next().is_some() {
+            issues.push(PortalIssue::Unreachable { room: room_idx });
+        }
+    }
+}
+
+/// Check how the level's rooms connect. `start_room` is where the player
+/// begins (room 0 if unknown); reachability is measured from there.
+pub fn check_portals(level: &Level, start_room: Option<usize>) -> Vec<PortalIssue> {
+    let mut issues = Vec::new();
+    one_way_portals(level, &mut issues);
+    open_edges(level, &mut issues);
+    overlaps(level, &mut issues);
+    let start = start_room.unwrap_or(0);
+    if start < level.rooms.len() {
+        unreachable(level, start, &mut issues);
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::TextureRef;
+
+    /// Closed room of `width` x `depth` sectors, floor at 0 and ceiling at 2048
+    fn closed_room(id: usize, position: Vec3, width: usize, depth: usize) -> Room {
+        let mut room = Room::new(id, position, width, depth);
+        for x in 0..width {
+            for z in 0..depth {
+                room.set_floor(x, z, 0.0, TextureRef::none());
+                room.set_ceiling(x, z, 2048.0, TextureRef::none());
+            }
+        }
+        room
+    }
+
+    fn level_with(rooms: Vec<Room>) -> Level {
+        let mut level = Level::new();
+        level.rooms = rooms;
+        level.recalculate_portals();
+        level
+    }
+
+    #[test]
+    fn aligned_rooms_have_no_issues() {
+        let level = level_with(vec![
+            closed_room(0, Vec3::ZERO, 2, 2),
+            closed_room(1, Vec3::new(SECTOR_SIZE * 2.0, 0.0, 0.0), 2, 2),
+        ]);
+        assert!(!level.rooms[0].portals.is_empty());
+        assert_eq!(check_portals(&level, None), Vec::new());
+    }
+
+    #[test]
+    fn misaligned_rooms_report_open_edges() {
+        let level = level_with(vec![
+            closed_room(0, Vec3::ZERO, 2, 2),
+            closed_room(1, Vec3::new(SECTOR_SIZE * 2.0, 0.0, SECTOR_SIZE * 0.5), 2, 2),
+        ]);
+        let issues = check_portals(&level, None);
+        assert!(issues.contains(&PortalIssue::OpenEdge { room: 0, x: 1, z: 0, dir: Direction::East, other: 1 }));
+        assert!(issues.iter().any(|i| matches!(i, PortalIssue::OpenEdge { room: 1, dir: Direction::West, .. })));
+        assert!(issues.contains(&PortalIssue::Unreachable { room: 1 }));
+    }
+
+    #[test]
+    fn wall_on_one_side_reports_open_edge() {
+        let mut b = closed_room(1, Vec3::new(SECTOR_SIZE * 2.0, 0.0, 0.0), 2, 2);
+        b.add_wall(0, 0, Direction::West, 0.0, 2048.0, TextureRef::none());
+        let level = level_with(vec![closed_room(0, Vec3::ZERO, 2, 2), b]);
+        let issues = check_portals(&level, None);
+        assert!(issues.contains(&PortalIssue::OpenEdge { room: 0, x: 1, z: 0, dir: Direction::East, other: 1 }));
+        // The walled side is closed, and the other row still connects
+        assert!(!issues.iter().any(|i| i.room() == 1));
+    }
+
+    #[test]
+    fn one_way_and_missing_targets() {
+        let mut level = level_with(vec![
+            closed_room(0, Vec3::ZERO, 2, 2),
+            closed_room(1, Vec3::new(SECTOR_SIZE * 2.0, 0.0, 0.0), 2, 2),
+        ]);
+        level.rooms[1].portals.clear();
+        level.rooms[0].portals[0].target_room = 7;
+        let issues = check_portals(&level, None);
+        assert!(issues.contains(&PortalIssue::BadTarget { room: 0, portal: 0, target: 7 }));
+        assert!(issues.contains(&PortalIssue::OneWay { room: 0, portal: 1, target: 1 }));
+        // Room 1 has no way back, but it's still reachable from room 0
+        assert!(!issues.contains(&PortalIssue::Unreachable { room: 1 }));
+    }
+
+    #[test]
+    fn overlapping_and_unreachable_rooms() {
+        let level = level_with(vec![
+            closed_room(0, Vec3::ZERO, 2, 2),
+            closed_room(1, Vec3::new(SECTOR_SIZE, 0.0, 0.0), 2, 2),
+            // Same floor plan, stacked above: no overlap
+            closed_room(2, Vec3::new(0.0, 4096.0, 0.0), 2, 2),
+        ]);
+        let issues = check_portals(&level, Some(0));
+        assert!(issues.contains(&PortalIssue::Overlap { a: 0, b: 1, x: 1, z: 0 }));
+        assert!(!issues.iter().any(|i| matches!(i, PortalIssue::Overlap { b: 2, .. })));
+        assert!(issues.contains(&PortalIssue::Unreachable { room: 2 }));
+    }
+}
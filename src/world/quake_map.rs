@@ -0,0 +1,691 @@
+//! Quake .map Import
+//!
+//! Reads brush layouts made in TrenchBroom (or any Quake .map editor) and
+//! turns them into sector rooms. Brushes are convex solids, so the importer
+//! samples a vertical line through each sector center: the spans inside
+//! brushes are solid and the spans between them are open space. Every open
+//! span with solid both below and above becomes a sector, with its floor and
+//! ceiling taken from the brush faces that bound it (sloped when the face is
+//! a walkable ramp).
+//!
+//! Space is split into rooms of at most `room_size` sectors per side, and
+//! stacked spaces in the same area (a bridge over a corridor) go to separate
+//! rooms. Walls cover the parts of each sector edge that face solid; edges
+//! that open into another room get no walls so `Level::recalculate_portals`
+//! can connect them.
+//!
+//! Quake is Z-up with +Y pointing north; the level is Y-up with north at -Z.
+//! Only world brushes are read (worldspawn, func_group, func_detail). Other
+//! brush entities such as doors and triggers are skipped.
+
+use super::{Direction, HorizontalFace, Room, Sector, TextureRef, SECTOR_SIZE};
+use crate::rasterizer::Vec3;
+use std::collections::HashSet;
+
+/// Tolerance for plane tests, in Quake units
+const PLANE_EPSILON: f32 = 0.01;
+/// Faces steeper than this (normal Z) are not used as sloped floors/ceilings
+const SLOPE_LIMIT: f32 = 0.7;
+
+/// A brush face: the brush is on the back side of the plane
+#[derive(Debug, Clone)]
+pub struct MapPlane {
+    /// Outward unit normal (Quake coordinates)
+    pub normal: Vec3,
+    pub dist: f32,
+    pub texture: String,
+}
+
+impl MapPlane {
+    /// Plane through three points in .map order. None if they are collinear.
+    pub fn from_points(points: [Vec3; 3], texture: impl Into<String>) -> Option<Self> {
+        let normal = (points[0] - points[1]).cross(points[2] - points[1]);
+        let length = normal.len();
+        if length <= f32::EPSILON {
+            return None;
+        }
+        let normal = normal * (1.0 / length);
+        Some(Self { normal, dist: normal.dot(points[1]), texture: texture.into() })
+    }
+
+    /// Signed distance (positive = outside the brush)
+    pub fn side(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) - self.dist
+    }
+
+    /// Z of the plane above (x, y); only meaningful for non-vertical planes
+    fn height_at(&self, x: f32, y: f32) -> f32 {
+        (self.dist - self.normal.x * x - self.normal.y * y) / self.normal.z
+    }
+}
+
+/// A convex solid
+#[derive(Debug, Clone, Default)]
+pub struct MapBrush {
+    pub planes: Vec<MapPlane>,
+}
+
+impl MapBrush {
+    pub fn contains(&self, point: Vec3) -> bool {
+        self.planes.iter().all(|p| p.side(point) <= PLANE_EPSILON)
+    }
+
+    /// Corner points (intersections of three faces that lie on the brush)
+    pub fn vertices(&self) -> Vec<Vec3> {
+        let mut vertices = Vec::new();
+        let n = self.planes.len();
+        for i in 0..n {
+            for j in (i + 1)..n {
+                for k in (j + 1)..n {
+                    let (a, b, c) = (&self.planes[i], &self.planes[j], &self.planes[k]);
+                    let bc = b.normal.cross(c.normal);
+                    let det = a.normal.dot(bc);
+                    if det.abs() <= f32::EPSILON {
+                        continue;
+                    }
+                    let point = (bc * a.dist + c.normal.cross(a.normal) * b.dist
+                        + a.normal.cross(b.normal) * c.dist)
+                        * (1.0 / det);
+                    if self.contains(point) {
+                        vertices.push(point);
+                    }
+                }
+            }
+        }
+        vertices
+    }
+
+    /// Solid span of a vertical line through (x, y), with the faces bounding it
+    fn vertical_span(&self, x: f32, y: f32) -> Option<SolidSpan<'_>> {
+        let mut bottom: Option<(f32, &MapPlane)> = None;
+        let mut top: Option<(f32, &MapPlane)> = None;
+        for plane in &self.planes {
+            let n = plane.normal;
+            if n.z.abs() <= PLANE_EPSILON {
+                // Vertical face: the line is either entirely behind it or outside
+                if n.x * x + n.y * y - plane.dist > PLANE_EPSILON {
+                    return None;
+                }
+                continue;
+            }
+            let z = plane.height_at(x, y);
+            if n.z > 0.0 {
+                if !matches!(top, Some((t, _)) if t <= z) {
+                    top = Some((z, plane));
+                }
+            } else if !matches!(bottom, Some((b, _)) if b >= z) {
+                bottom = Some((z, plane));
+            }
+        }
+        let ((bottom, bottom_plane), (top, top_plane)) = (bottom?, top?);
+        (top - bottom > PLANE_EPSILON).then_some(SolidSpan { bottom, top, bottom_plane, top_plane })
+    }
+}
+
+/// An entity: key/value properties plus any brushes
+#[derive(Debug, Clone, Default)]
+pub struct MapEntity {
+    pub properties: Vec<(String, String)>,
+    pub brushes: Vec<MapBrush>,
+}
+
+impl MapEntity {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.properties.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    pub fn classname(&self) -> &str {
+        self.get("classname").unwrap_or("")
+    }
+
+    /// Brushes of this entity are static level geometry
+    pub fn is_world_geometry(&self) -> bool {
+        matches!(self.classname(), "worldspawn" | "func_group") || self.classname().starts_with("func_detail")
+    }
+}
+
+/// Token cursor over .map text: quoted strings, single-character brackets
+/// and whitespace-separated words, each with its line number. `//` comments
+/// are dropped.
+struct Tokens {
+    tokens: Vec<(usize, String)>,
+    pos: usize,
+}
+
+impl Tokens {
+    fn new(text: &str) -> Self {
+        let mut tokens = Vec::new();
+        for (line_index, line) in text.lines().enumerate() {
+            let line_no = line_index + 1;
+            let mut rest = line.trim_start();
+            while !rest.is_empty() && !rest.starts_with("//") {
+                let end = if let Some(quoted) = rest.strip_prefix('"') {
+                    let close = quoted.find('"').unwrap_or(quoted.len());
+                    tokens.push((line_no, quoted[..close].to_string()));
+                    (close + 2).min(rest.len())
+                } else if rest.starts_with(['{', '}', '(', ')', '[', ']']) {
+                    tokens.push((line_no, rest[..1].to_string()));
+                    1
+                } else {
+                    let end = rest
+                        .find(|c: char| c.is_whitespace() || "{}()[]\"".contains(c))
+                        .unwrap_or(rest.len());
+                    tokens.push((line_no, rest[..end].to_string()));
+                    end
+                };
+                rest = rest[end..].trim_start();
+            }
+        }
+        Self { tokens, pos: 0 }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(|(_, t)| t.as_str())
+    }
+
+    fn next(&mut self) -> Result<(usize, &str), String> {
+        let (line, token) = self.tokens.get(self.pos).ok_or_else(|| "Unexpected end of map file".to_string())?;
+        self.pos += 1;
+        Ok((*line, token.as_str()))
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        let (line, token) = self.next()?;
+        if token != expected {
+            return Err(format!("Line {}: expected '{}', found '{}'", line, expected, token));
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<f32, String> {
+        let (line, token) = self.next()?;
+        token.parse::<f32>().map_err(|_| format!("Line {}: expected a number, found '{}'", line, token))
+    }
+
+    /// `( x y z )`
+    fn point(&mut self) -> Result<Vec3, String> {
+        self.expect("(")?;
+        let point = Vec3::new(self.number()?, self.number()?, self.number()?);
+        self.expect(")")?;
+        Ok(point)
+    }
+}
+
+/// Parse Quake .map text (standard and Valve 220 texture formats)
+pub fn parse_quake_map(text: &str) -> Result<Vec<MapEntity>, String> {
+    let mut tokens = Tokens::new(text);
+    let mut entities = Vec::new();
+    while !tokens.at_end() {
+        tokens.expect("{")?;
+        let mut entity = MapEntity::default();
+        loop {
+            let (line, token) = tokens.next()?;
+            match token {
+                "}" => break,
+                "{" => {
+                    let mut brush = MapBrush::default();
+                    loop {
+                        match tokens.peek() {
+                            Some("}") => {
+                                tokens.pos += 1;
+                                break;
+                            }
+                            Some("(") => {}
+                            _ => {
+                                let (line, token) = tokens.next()?;
+                                return Err(format!("Line {}: unsupported brush syntax '{}'", line, token));
+                            }
+                        }
+                        let points = [tokens.point()?, tokens.point()?, tokens.point()?];
+                        let texture = tokens.next()?.1.to_string();
+                        // Texture offsets/rotation/scale (and Valve axes) are not used
+                        while tokens.peek().is_some_and(|t| t != "(" && t != "}") {
+                            tokens.pos += 1;
+                        }
+                        // Degenerate faces are dropped rather than failing the import
+                        brush.planes.extend(MapPlane::from_points(points, texture));
+                    }
+                    entity.brushes.push(brush);
+                }
+                key => {
+                    let key = key.to_string();
+                    let (_, value) = tokens.next()?;
+                    if value == "{" || value == "}" {
+                        return Err(format!("Line {}: property '{}' has no value", line, key));
+                    }
+                    let value = value.to_string();
+                    entity.properties.push((key, value));
+                }
+            }
+        }
+        entities.push(entity);
+    }
+    Ok(entities)
+}
+
+/// Find a .map texture name among `(pack, texture)` names. Matching ignores
+/// case, directories and Quake's special prefixes (`*` liquids, `+0`
+/// animation frames, `{` masked textures).
+pub fn match_map_texture<'a>(
+    name: &str,
+    textures: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Option<TextureRef> {
+    let mut name = name.rsplit('/').next().unwrap_or(name);
+    name = name.trim_start_matches(['*', '{', '!']);
+    if let Some(frame) = name.strip_prefix('+').and_then(|n| n.get(1..)) {
+        name = frame;
+    }
+    textures
+        .into_iter()
+        .find(|(_, texture)| texture.eq_ignore_ascii_case(name))
+        .map(|(pack, texture)| TextureRef::new(pack, texture))
+}
+
+/// .map import settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuakeMapSettings {
+    /// Quake units per sector (64 is TrenchBroom's default grid)
+    pub units_per_sector: f32,
+    /// Sectors per room side; larger areas are split into rooms of this size
+    pub room_size: usize,
+    /// Openings lower than this (world units) are treated as solid
+    pub min_gap: f32,
+}
+
+impl Default for QuakeMapSettings {
+    fn default() -> Self {
+        Self {
+            units_per_sector: 64.0,
+            room_size: 8,
+            min_gap: SECTOR_SIZE / 4.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SolidSpan<'a> {
+    bottom: f32,
+    top: f32,
+    bottom_plane: &'a MapPlane,
+    top_plane: &'a MapPlane,
+}
+
+/// Open space in a column between two solids (Quake units)
+#[derive(Debug, Clone, Copy)]
+struct Gap<'a> {
+    floor: f32,
+    ceiling: f32,
+    /// Top face of the solid below
+    floor_plane: &'a MapPlane,
+    /// Bottom face of the solid above
+    ceiling_plane: &'a MapPlane,
+}
+
+impl Gap<'_> {
+    /// Shares at least `min_gap` of height with another gap
+    fn overlaps(&self, other: &Gap, min_gap: f32) -> bool {
+        self.ceiling.min(other.ceiling) - self.floor.max(other.floor) >= min_gap
+    }
+}
+
+/// Enclosed openings along a vertical line, bottom to top
+fn column_gaps<'a>(brushes: &[&'a MapBrush], x: f32, y: f32, min_gap: f32) -> Vec<Gap<'a>> {
+    let mut spans: Vec<SolidSpan> = brushes.iter().filter_map(|b| b.vertical_span(x, y)).collect();
+    spans.sort_by(|a, b| a.bottom.total_cmp(&b.bottom));
+
+    let mut merged: Vec<SolidSpan> = Vec::new();
+    for span in spans {
+        match merged.last_mut() {
+            Some(last) if span.bottom <= last.top + PLANE_EPSILON => {
+                if span.top > last.top {
+                    last.top = span.top;
+                    last.top_plane = span.top_plane;
+                }
+            }
+            _ => merged.push(span),
+        }
+    }
+
+    merged
+        .windows(2)
+        .map(|w| Gap {
+            floor: w[0].top,
+            ceiling: w[1].bottom,
+            floor_plane: w[0].top_plane,
+            ceiling_plane: w[1].bottom_plane,
+        })
+        .filter(|gap| gap.ceiling - gap.floor >= min_gap)
+        .collect()
+}
+
+/// Remove `open` spans from `[bottom, top]`, leaving the parts to wall off
+fn subtract_spans(bottom: f32, top: f32, open: &[(f32, f32)]) -> Vec<(f32, f32)> {
+    let mut remaining = vec![(bottom, top)];
+    for &(lo, hi) in open {
+        remaining = remaining
+            .into_iter()
+            .flat_map(|(a, b)| [(a, b.min(lo)), (a.max(hi), b)])
+            .filter(|(a, b)| b - a > 1.0)
+            .collect();
+    }
+    remaining
+}
+
+/// Quake-space direction a sector edge faces
+fn direction_vector(direction: Direction) -> Vec3 {
+    match direction {
+        Direction::North => Vec3::new(0.0, 1.0, 0.0),
+        Direction::East => Vec3::new(1.0, 0.0, 0.0),
+        Direction::South => Vec3::new(0.0, -1.0, 0.0),
+        _ => Vec3::new(-1.0, 0.0, 0.0),
+    }
+}
+
+/// Build rooms from parsed .map entities.
+///
+/// Rooms are laid out from `origin` (the NW corner of the map bounds) and
+/// numbered from `first_id`. `resolve` maps a .map texture name to a texture.
+/// Portals are not created here: add the rooms to the level and call
+/// `Level::recalculate_portals`.
+pub fn build_quake_map_rooms(
+    entities: &[MapEntity],
+    settings: &QuakeMapSettings,
+    resolve: impl Fn(&str) -> TextureRef,
+    origin: Vec3,
+    first_id: usize,
+) -> Result<Vec<Room>, String> {
+    let brushes: Vec<&MapBrush> = entities
+        .iter()
+        .filter(|e| e.is_world_geometry())
+        .flat_map(|e| e.brushes.iter())
+        .collect();
+    let vertices: Vec<Vec3> = brushes.iter().flat_map(|b| b.vertices()).collect();
+    let Some(&first) = vertices.first() else {
+        return Err("Map has no world brushes".to_string());
+    };
+    let (min, max) = vertices.iter().fold((first, first), |(lo, hi), v| {
+        (
+            Vec3::new(lo.x.min(v.x), lo.y.min(v.y), lo.z.min(v.z)),
+            Vec3::new(hi.x.max(v.x), hi.y.max(v.y), hi.z.max(v.z)),
+        )
+    });
+
+    let cell = settings.units_per_sector.max(1.0);
+    let scale = SECTOR_SIZE / cell;
+    let min_gap = settings.min_gap / scale;
+    let room_size = settings.room_size.max(1);
+    // Column (i, j) runs east from `west` and south from `north`
+    let west = (min.x / cell).floor() * cell;
+    let north = (max.y / cell).ceil() * cell;
+    let columns_x = (((max.x - west) / cell).ceil() as usize).max(1);
+    let columns_z = (((north - min.y) / cell).ceil() as usize).max(1);
+    let center = |i: usize, j: usize| (west + (i as f32 + 0.5) * cell, north - (j as f32 + 0.5) * cell);
+    let index = |i: usize, j: usize| j * columns_x + i;
+    let neighbour = |i: usize, j: usize, direction: Direction| -> Option<(usize, usize)> {
+        let (ni, nj) = match direction {
+            Direction::North => (i as isize, j as isize - 1),
+            Direction::East => (i as isize + 1, j as isize),
+            Direction::South => (i as isize, j as isize + 1),
+            _ => (i as isize - 1, j as isize),
+        };
+        (ni >= 0 && nj >= 0 && (ni as usize) < columns_x && (nj as usize) < columns_z)
+            .then_some((ni as usize, nj as usize))
+    };
+    const EDGES: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    let gaps: Vec<Vec<Gap>> = (0..columns_z)
+        .flat_map(|j| (0..columns_x).map(move |i| (i, j)))
+        .map(|(i, j)| {
+            let (x, y) = center(i, j);
+            column_gaps(&brushes, x, y, min_gap)
+        })
+        .collect();
+
+    // Flood-fill connected openings into regions, one per room. A region stays
+    // inside its room_size chunk and holds at most one gap per column.
+    let mut owner: Vec<Vec<Option<usize>>> = gaps.iter().map(|g| vec![None; g.len()]).collect();
+    let mut regions: Vec<Vec<(usize, usize, usize)>> = Vec::new();
+    for j in 0..columns_z {
+        for i in 0..columns_x {
+            for g in 0..gaps[index(i, j)].len() {
+                if owner[index(i, j)][g].is_some() {
+                    continue;
+                }
+                let region = regions.len();
+                let chunk = (i / room_size, j / room_size);
+                let mut cells = Vec::new();
+                let mut taken = HashSet::new();
+                let mut stack = vec![(i, j, g)];
+                while let Some((ci, cj, cg)) = stack.pop() {
+                    if owner[index(ci, cj)][cg].is_some() || !taken.insert((ci, cj)) {
+                        continue;
+                    }
+                    owner[index(ci, cj)][cg] = Some(region);
+                    cells.push((ci, cj, cg));
+                    let gap = gaps[index(ci, cj)][cg];
+                    for direction in EDGES {
+                        let Some((ni, nj)) = neighbour(ci, cj, direction) else { continue };
+                        if (ni / room_size, nj / room_size) != chunk {
+                            continue;
+                        }
+                        for (ng, other) in gaps[index(ni, nj)].iter().enumerate() {
+                            if owner[index(ni, nj)][ng].is_none() && gap.overlaps(other, min_gap) {
+                                stack.push((ni, nj, ng));
+                            }
+                        }
+                    }
+                }
+                regions.push(cells);
+            }
+        }
+    }
+
+    // Heights at the four corners [NW, NE, SE, SW]; flat unless the face is a ramp
+    let face_heights = |plane: &MapPlane, height: f32, i: usize, j: usize| -> [f32; 4] {
+        let (x0, y0) = (west + i as f32 * cell, north - j as f32 * cell);
+        let (x1, y1) = (x0 + cell, y0 - cell);
+        let heights = if plane.normal.z.abs() >= SLOPE_LIMIT {
+            [(x0, y0), (x1, y0), (x1, y1), (x0, y1)].map(|(x, y)| plane.height_at(x, y))
+        } else {
+            [height; 4]
+        };
+        heights.map(|h| (h * scale).round())
+    };
+
+    let mut rooms = Vec::new();
+    for (region, cells) in regions.iter().enumerate() {
+        let i0 = cells.iter().map(|c| c.0).min().unwrap_or(0);
+        let i1 = cells.iter().map(|c| c.0).max().unwrap_or(0);
+        let j0 = cells.iter().map(|c| c.1).min().unwrap_or(0);
+        let j1 = cells.iter().map(|c| c.1).max().unwrap_or(0);
+        let position = Vec3::new(
+            origin.x + i0 as f32 * SECTOR_SIZE,
+            origin.y,
+            origin.z + j0 as f32 * SECTOR_SIZE,
+        );
+        let mut room = Room::new(first_id + region, position, i1 - i0 + 1, j1 - j0 + 1);
+
+        for &(i, j, g) in cells {
+            let gap = gaps[index(i, j)][g];
+            let (lx, lz) = (i - i0, j - j0);
+            let floor = face_heights(gap.floor_plane, gap.floor, i, j);
+            let ceiling = face_heights(gap.ceiling_plane, gap.ceiling, i, j);
+            room.set_sector(lx, lz, Sector {
+                floor: Some(HorizontalFace::sloped(floor, resolve(&gap.floor_plane.texture))),
+                ceiling: Some(HorizontalFace::sloped(ceiling, resolve(&gap.ceiling_plane.texture))),
+                ..Default::default()
+            });
+
+            for direction in EDGES {
+                let neighbour_gaps: &[Gap] = match neighbour(i, j, direction) {
+                    Some((ni, nj)) => &gaps[index(ni, nj)],
+                    None => &[],
+                };
+                // Opening into another room: leave the edge open for a portal
+                let opens_elsewhere = neighbour(i, j, direction).is_some_and(|(ni, nj)| {
+                    neighbour_gaps.iter().enumerate().any(|(ng, other)| {
+                        owner[index(ni, nj)][ng] != Some(region) && gap.overlaps(other, min_gap)
+                    })
+                });
+                if opens_elsewhere {
+                    continue;
+                }
+
+                let (a, b) = match direction {
+                    Direction::North => (0, 1),
+                    Direction::East => (1, 2),
+                    Direction::South => (2, 3),
+                    _ => (3, 0),
+                };
+                let open: Vec<(f32, f32)> =
+                    neighbour_gaps.iter().map(|o| (o.floor * scale, o.ceiling * scale)).collect();
+                let outward = direction_vector(direction);
+                let (x, y) = center(i, j);
+                for (bottom, top) in subtract_spans(floor[a].min(floor[b]), ceiling[a].max(ceiling[b]), &open) {
+                    // Texture of the solid face just past the edge, looking back at this sector
+                    let probe = Vec3::new(x, y, (bottom + top) * 0.5 / scale) + outward * (cell * 0.5 + 1.0);
+                    let texture = brushes
+                        .iter()
+                        .find(|brush| brush.contains(probe))
+                        .and_then(|brush| {
+                            brush.planes.iter().max_by(|p, q| {
+                                (-p.normal.dot(outward)).total_cmp(&-q.normal.dot(outward))
+                            })
+                        })
+                        .map(|plane| resolve(&plane.texture))
+                        .unwrap_or_else(|| resolve(&gap.floor_plane.texture));
+                    room.add_wall(lx, lz, direction, bottom, top, texture);
+                }
+            }
+        }
+
+        room.recalculate_bounds();
+        rooms.push(room);
+    }
+    Ok(rooms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Level;
+
+    /// Axis-aligned brush in the point order TrenchBroom writes
+    fn box_brush(min: [i32; 3], max: [i32; 3], texture: &str) -> String {
+        let ([x0, y0, z0], [x1, y1, z1]) = (min, max);
+        let face = |a: [i32; 3], b: [i32; 3], c: [i32; 3]| {
+            format!(
+                "( {} {} {} ) ( {} {} {} ) ( {} {} {} ) {} 0 0 0 1 1\n",
+                a[0], a[1], a[2], b[0], b[1], b[2], c[0], c[1], c[2], texture
+            )
+        };
+        let mut brush = String::from("{\n");
+        brush += &face([x0, y0, z0], [x0, y0 + 1, z0], [x0, y0, z0 + 1]);
+        brush += &face([x0, y0, z0], [x0, y0, z0 + 1], [x0 + 1, y0, z0]);
+        brush += &face([x0, y0, z0], [x0 + 1, y0, z0], [x0, y0 + 1, z0]);
+        brush += &face([x1, y1, z1], [x1, y1 + 1, z1], [x1 + 1, y1, z1]);
+        brush += &face([x1, y1, z1], [x1 + 1, y1, z1], [x1, y1, z1 + 1]);
+        brush += &face([x1, y1, z1], [x1, y1, z1 + 1], [x1, y1 + 1, z1]);
+        brush + "}\n"
+    }
+
+    /// Hollow box: floor, ceiling and four walls around the given interior
+    fn hollow_box(min: [i32; 3], max: [i32; 3]) -> String {
+        let ([x0, y0, z0], [x1, y1, z1]) = (min, max);
+        let t = 16;
+        [
+            box_brush([x0 - t, y0 - t, z0 - t], [x1 + t, y1 + t, z0], "FLOOR1"),
+            box_brush([x0 - t, y0 - t, z1], [x1 + t, y1 + t, z1 + t], "CEIL1"),
+            box_brush([x0 - t, y0 - t, z0], [x0, y1 + t, z1], "*WALL1"),
+            box_brush([x1, y0 - t, z0], [x1 + t, y1 + t, z1], "*WALL1"),
+            box_brush([x0, y0 - t, z0], [x1, y0, z1], "*WALL1"),
+            box_brush([x0, y1, z0], [x1, y1 + t, z1], "*WALL1"),
+        ]
+        .concat()
+    }
+
+    fn resolve(name: &str) -> TextureRef {
+        match_map_texture(name, [("base", "floor1"), ("base", "ceil1"), ("base", "wall1")])
+            .unwrap_or_else(TextureRef::none)
+    }
+
+    #[test]
+    fn parses_entities_and_faces() {
+        let text = format!(
+            "// Game: Quake\n{{\n\"classname\" \"worldspawn\"\n\"wad\" \"base.wad\"\n{}}}\n\
+             {{\n\"classname\" \"info_player_start\"\n\"origin\" \"0 0 24\"\n}}\n",
+            box_brush([0, 0, 0], [64, 64, 16], "FLOOR1")
+        );
+        let entities = parse_quake_map(&text).unwrap();
+        assert_eq!(entities.len(), 2);
+        assert!(entities[0].is_world_geometry());
+        assert_eq!(entities[0].get("wad"), Some("base.wad"));
+        assert_eq!(entities[1].classname(), "info_player_start");
+
+        let brush = &entities[0].brushes[0];
+        assert_eq!(brush.planes.len(), 6);
+        // Outward normals: the first face is the west side
+        assert_eq!(brush.planes[0].normal.x, -1.0);
+        assert!(brush.contains(Vec3::new(32.0, 32.0, 8.0)));
+        assert!(!brush.contains(Vec3::new(32.0, 32.0, 20.0)));
+        assert_eq!(brush.vertices().len(), 8);
+
+        // Valve 220 texture axes are skipped
+        let valve = "{\n{\n( 0 0 0 ) ( 0 1 0 ) ( 0 0 1 ) stone [ 0 1 0 0 ] [ 0 0 -1 0 ] 0 1 1\n}\n}\n";
+        assert_eq!(parse_quake_map(valve).unwrap()[0].brushes[0].planes[0].texture, "stone");
+        assert!(parse_quake_map("{ \"classname\" ").is_err());
+    }
+
+    #[test]
+    fn builds_enclosed_room() {
+        // 128x128 units (2x2 sectors), 128 units tall
+        let entities = parse_quake_map(&format!(
+            "{{\n\"classname\" \"worldspawn\"\n{}}}\n",
+            hollow_box([0, 0, 0], [128, 128, 128])
+        ))
+        .unwrap();
+        let rooms =
+            build_quake_map_rooms(&entities, &QuakeMapSettings::default(), resolve, Vec3::ZERO, 3).unwrap();
+        assert_eq!(rooms.len(), 1);
+        let room = &rooms[0];
+        assert_eq!(room.id, 3);
+        assert_eq!((room.width, room.depth), (2, 2));
+
+        let sector = room.get_sector(0, 0).unwrap();
+        let floor = sector.floor.as_ref().unwrap();
+        assert_eq!(floor.heights, [0.0; 4]);
+        assert_eq!(floor.texture, TextureRef::new("base", "floor1"));
+        assert_eq!(sector.ceiling.as_ref().unwrap().heights[0], 2048.0);
+        // NW sector: walls to the north and west only, with the liquid prefix ignored
+        assert_eq!(sector.walls_north.len(), 1);
+        assert_eq!(sector.walls_west.len(), 1);
+        assert!(sector.walls_east.is_empty() && sector.walls_south.is_empty());
+        assert_eq!(sector.walls_north[0].texture, TextureRef::new("base", "wall1"));
+    }
+
+    #[test]
+    fn splits_large_spaces_with_portals() {
+        // 3x1 sector corridor. The grid starts a sector west, at the outer
+        // wall, so rooms of 2 hold the first sector and the other two.
+        let entities = parse_quake_map(&format!(
+            "{{\n\"classname\" \"worldspawn\"\n{}}}\n",
+            hollow_box([0, 0, 0], [192, 64, 128])
+        ))
+        .unwrap();
+        let settings = QuakeMapSettings { room_size: 2, ..Default::default() };
+        let mut level = Level::new();
+        level.rooms = build_quake_map_rooms(&entities, &settings, resolve, Vec3::ZERO, 0).unwrap();
+        level.recalculate_portals();
+        assert_eq!(level.rooms.len(), 2);
+        assert_eq!((level.rooms[0].width, level.rooms[1].width), (1, 2));
+        assert!(level.rooms[0].get_sector(0, 0).unwrap().walls_east.is_empty());
+        assert!(level.rooms[0].portals.iter().any(|p| p.target_room == 1));
+        assert!(level.rooms[1].portals.iter().any(|p| p.target_room == 0));
+
+        assert!(build_quake_map_rooms(&[], &settings, resolve, Vec3::ZERO, 0).is_err());
+    }
+}
@@ -0,0 +1,291 @@
+//! Level streaming
+//!
+//! A level can list other level files as chunks of a larger world. The level
+//! itself is the always-loaded part (a hub, or empty); chunks are streamed in
+//! by the game runtime when the player's current room comes within the
+//! streaming distance of a chunk's bounds, and dropped again once the player
+//! moves well away. Only nearby chunks are ever in memory, so a world can be
+//! bigger than any single RON file.
+//!
+//! Chunk rooms use world coordinates, so neighbouring chunks line up exactly
+//! as they were authored; portals between them are rebuilt whenever the set
+//! of loaded chunks changes. Each chunk stores its bounds so the runtime can
+//! decide what to load without opening the file.
+
+use serde::{Serialize, Deserialize};
+use crate::rasterizer::Vec3;
+use super::{Aabb, Level, SECTOR_SIZE};
+
+/// Default streaming distance (world units between room and chunk bounds)
+pub const DEFAULT_STREAM_DISTANCE: f32 = SECTOR_SIZE * 8.0;
+/// Chunks unload at this multiple of the streaming distance, so walking
+/// along the edge doesn't load and unload the same chunk every frame
+pub const STREAM_UNLOAD_FACTOR: f32 = 1.5;
+
+/// A level file streamed in as part of a larger world
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelChunk {
+    /// Level file path
+    pub path: String,
+    /// World-space bounds of the chunk's rooms (taken when it was added)
+    pub bounds: Aabb,
+}
+
+impl LevelChunk {
+    pub fn new(path: impl Into<String>, level: &Level) -> Self {
+        Self {
+            path: path.into(),
+            bounds: level_bounds(level),
+        }
+    }
+
+    /// File name without directory or extension
+    pub fn name(&self) -> &str {
+        let file = self.path.rsplit(['/', '\\']).next().unwrap_or(&self.path);
+        file.strip_suffix(".ron").unwrap_or(file)
+    }
+}
+
+/// Streaming settings for a level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelStreaming {
+    #[serde(default)]
+    pub chunks: Vec<LevelChunk>,
+    /// How close the player's room must get to a chunk before it loads
+    #[serde(default = "default_stream_distance")]
+    pub distance: f32,
+}
+
+fn default_stream_distance() -> f32 {
+    DEFAULT_STREAM_DISTANCE
+}
+
+impl Default for LevelStreaming {
+    fn default() -> Self {
+        Self {
+            chunks: Vec::new(),
+            distance: DEFAULT_STREAM_DISTANCE,
+        }
+    }
+}
+
+impl LevelStreaming {
+    /// True when the level has no chunks (for serde skip_serializing_if)
+    pub fn is_off(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Add a chunk, replacing any existing entry for the same file
+    pub fn add_chunk(&mut self, chunk: LevelChunk) {
+        match self.chunks.iter_mut().find(|c| c.path == chunk.path) {
+            Some(existing) => *existing = chunk,
+            None => self.chunks.push(chunk),
+        }
+    }
+}
+
+/// World-space bounds of all rooms in a level
+pub fn level_bounds(level: &Level) -> Aabb {
+    let mut rooms = level.rooms.iter().map(|r| r.world_bounds());
+    let Some(mut bounds) = rooms.next() else {
+        return Aabb::default();
+    };
+    for room in rooms {
+        bounds.expand(room.min);
+        bounds.expand(room.max);
+    }
+    bounds
+}
+
+/// Gap between two boxes (0 when they touch or overlap)
+fn aabb_gap(a: &Aabb, b: &Aabb) -> f32 {
+    let axis = |a_min: f32, a_max: f32, b_min: f32, b_max: f32| (b_min - a_max).max(a_min - b_max).max(0.0);
+    let dx = axis(a.min.x, a.max.x, b.min.x, b.max.x);
+    let dy = axis(a.min.y, a.max.y, b.min.y, b.max.y);
+    let dz = axis(a.min.z, a.max.z, b.min.z, b.max.z);
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+/// Runtime state: which chunks are loaded, and the level they compose into
+#[derive(Debug, Clone, Default)]
+pub struct ChunkStreamer {
+    /// Loaded chunks (chunk index, level), sorted by index
+    loaded: Vec<(usize, Level)>,
+    /// Chunks that failed to load (not retried until `clear`)
+    failed: Vec<usize>,
+    /// Base level plus loaded chunk rooms (rebuilt when the set changes)
+    composed: Option<Level>,
+    /// Load errors since the last call to `take_errors`
+    errors: Vec<String>,
+}
+
+impl ChunkStreamer {
+    /// Forget all loaded chunks (new play session)
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Indices of the chunks currently loaded
+    pub fn loaded_chunks(&self) -> impl Iterator<Item = usize> + '_ {
+        self.loaded.iter().map(|(i, _)| *i)
+    }
+
+    /// The composed level (base + loaded chunks), once `update` has run
+    pub fn level(&self) -> Option<&Level> {
+        self.composed.as_ref()
+    }
+
+    pub fn take_errors(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Area streaming is centred on: the room containing `player`, or just
+    /// the player's position when between rooms
+    fn focus(&self, player: Vec3) -> Aabb {
+        self.composed.as_ref()
+            .and_then(|level| level.find_room_at(player).map(|i| level.rooms[i].world_bounds()))
+            .unwrap_or(Aabb::new(player, player))
+    }
+
+    /// Load chunks near the player and unload distant ones, rebuilding the
+    /// composed level if anything changed. Before the player exists the
+    /// first chunk is loaded, so a player start placed there is found.
+    /// Returns true if the set of loaded chunks changed.
+    pub fn update(
+        &mut self,
+        base: &Level,
+        player: Option<Vec3>,
+        mut load: impl FnMut(&str) -> Result<Level, String>,
+    ) -> bool {
+        let streaming = &base.streaming;
+        let focus = player.map(|p| self.focus(p));
+        let gap = |i: usize| focus.map(|f| aabb_gap(&f, &streaming.chunks[i].bounds));
+
+        let before = self.loaded.len();
+        self.loaded.retain(|(i, _)| {
+            *i < streaming.chunks.len() && !gap(*i).is_some_and(|g| g > streaming.distance * STREAM_UNLOAD_FACTOR)
+        });
+        let mut changed = self.loaded.len() != before;
+
+        let wanted: Vec<usize> = match focus {
+            Some(_) => (0..streaming.chunks.len())
+                .filter(|&i| gap(i).is_some_and(|g| g <= streaming.distance))
+                .collect(),
+            None if self.loaded.is_empty() && !streaming.chunks.is_empty() => vec![0],
+            None => Vec::new(),
+        };
+        for i in wanted {
+            if self.loaded.iter().any(|(j, _)| *j == i) || self.failed.contains(&i) {
+                continue;
+            }
+            match load(&streaming.chunks[i].path) {
+                Ok(level) => {
+                    let at = self.loaded.partition_point(|(j, _)| *j < i);
+                    self.loaded.insert(at, (i, level));
+                    changed = true;
+                }
+                Err(e) => {
+                    self.errors.push(format!("{}: {}", streaming.chunks[i].name(), e));
+                    self.failed.push(i);
+                }
+            }
+        }
+
+        if changed || self.composed.is_none() {
+            self.compose(base);
+        }
+        changed
+    }
+
+    /// Rebuild the composed level from the base and the loaded chunks
+    fn compose(&mut self, base: &Level) {
+        let mut level = base.clone();
+        for (_, chunk) in &self.loaded {
+            level.rooms.extend(chunk.rooms.iter().cloned());
+        }
+        for (i, room) in level.rooms.iter_mut().enumerate() {
+            room.id = i;
+        }
+        level.recalculate_portals();
+        self.composed = Some(level);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Room;
+
+    /// Level with one 2x2 room at `x`
+    fn chunk_level(x: f32) -> Level {
+        let mut level = Level::new();
+        let mut room = Room::new(0, Vec3::new(x, 0.0, 0.0), 2, 2);
+        room.set_floor(0, 0, 0.0, crate::world::TextureRef::none());
+        room.set_floor(1, 1, 0.0, crate::world::TextureRef::none());
+        room.recalculate_bounds();
+        level.rooms.push(room);
+        level
+    }
+
+    fn streamed_world() -> Level {
+        let mut base = chunk_level(0.0);
+        base.streaming.distance = SECTOR_SIZE * 2.0;
+        base.streaming.add_chunk(LevelChunk::new("near.ron", &chunk_level(SECTOR_SIZE * 3.0)));
+        base.streaming.add_chunk(LevelChunk::new("far.ron", &chunk_level(SECTOR_SIZE * 40.0)));
+        base
+    }
+
+    fn loader(path: &str) -> Result<Level, String> {
+        match path {
+            "near.ron" => Ok(chunk_level(SECTOR_SIZE * 3.0)),
+            "far.ron" => Ok(chunk_level(SECTOR_SIZE * 40.0)),
+            _ => Err("missing".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_chunk_name_and_bounds() {
+        let chunk = LevelChunk::new("assets/levels/cave_02.ron", &chunk_level(SECTOR_SIZE));
+        assert_eq!(chunk.name(), "cave_02");
+        assert_eq!(chunk.bounds.min.x, SECTOR_SIZE);
+        assert_eq!(chunk.bounds.max.x, SECTOR_SIZE * 3.0);
+    }
+
+    #[test]
+    fn test_streams_nearby_chunks_only() {
+        let base = streamed_world();
+        let mut streamer = ChunkStreamer::default();
+
+        // No player yet: the first chunk comes in so its spawn can be found
+        assert!(streamer.update(&base, None, loader));
+        assert_eq!(streamer.loaded_chunks().collect::<Vec<_>>(), vec![0]);
+        assert_eq!(streamer.level().unwrap().rooms.len(), 2);
+
+        // Standing in the base room keeps the near chunk, never loads the far one
+        let player = Vec3::new(SECTOR_SIZE * 0.5, 0.0, SECTOR_SIZE * 0.5);
+        assert!(!streamer.update(&base, Some(player), loader));
+        assert_eq!(streamer.loaded_chunks().collect::<Vec<_>>(), vec![0]);
+
+        // Walking to the far chunk swaps them
+        let far = Vec3::new(SECTOR_SIZE * 39.0, 0.0, SECTOR_SIZE * 0.5);
+        assert!(streamer.update(&base, Some(far), loader));
+        assert_eq!(streamer.loaded_chunks().collect::<Vec<_>>(), vec![1]);
+        let level = streamer.level().unwrap();
+        assert_eq!(level.rooms.len(), 2);
+        assert!(level.rooms.iter().enumerate().all(|(i, r)| r.id == i));
+    }
+
+    #[test]
+    fn test_failed_chunks_are_reported_once() {
+        let mut base = Level::new();
+        base.streaming.add_chunk(LevelChunk::new("gone.ron", &Level::new()));
+        base.streaming.add_chunk(LevelChunk::new("gone.ron", &Level::new()));
+        assert_eq!(base.streaming.chunks.len(), 1);
+
+        let mut streamer = ChunkStreamer::default();
+        assert!(!streamer.update(&base, None, loader));
+        assert!(!streamer.update(&base, None, loader));
+        assert_eq!(streamer.take_errors().len(), 1);
+        assert!(streamer.level().is_some());
+    }
+}
@@ -0,0 +1,171 @@
+//! Level texture pack list
+//!
+//! A level can name the texture packs it uses, highest priority first. The
+//! game resolves textures by name, so when two packs ship a texture with the
+//! same name the earlier pack in the list wins. An empty list means every
+//! loaded pack, in discovery order.
+//!
+//! Also counts which faces reference which pack and remaps faces from one
+//! pack/texture to another in bulk when art is replaced.
+
+use super::{Direction, Level, TextureRef};
+
+/// Faces referencing one texture pack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackUsage {
+    pub pack: String,
+    /// Floors, ceilings and walls (a blended floor texture counts separately)
+    pub faces: usize,
+    /// Indices of the rooms with at least one such face
+    pub rooms: Vec<usize>,
+}
+
+/// Visit every face texture with its room index
+fn for_each_level_texture_mut(level: &mut Level, mut f: impl FnMut(usize, &mut TextureRef)) {
+    for (room_idx, room) in level.rooms.iter_mut().enumerate() {
+        for sector in room.sectors.iter_mut().flatten().flatten() {
+            for face in sector.floor.iter_mut().chain(sector.ceiling.iter_mut()) {
+                f(room_idx, &mut face.texture);
+                if let Some(tex) = &mut face.texture_2 {
+                    f(room_idx, tex);
+                }
+            }
+            for dir in Direction::ALL {
+                for wall in sector.walls_mut(dir) {
+                    f(room_idx, &mut wall.texture);
+                }
+            }
+        }
+    }
+}
+
+/// Face counts per referenced pack, sorted by pack name
+pub fn pack_usage(level: &Level) -> Vec<PackUsage> {
+    let mut usage: Vec<PackUsage> = Vec::new();
+    let mut count = |room_idx: usize, tex: &TextureRef| {
+        if !tex.is_valid() {
+            return;
+        }
+        let entry = match usage.iter().position(|u| u.pack == tex.pack) {
+            Some(i) => &mut usage[i],
+            None => {
+                usage.push(PackUsage { pack: tex.pack.clone(), faces: 0, rooms: Vec::new() });
+                usage.last_mut().unwrap()
+            }
+        };
+        entry.faces += 1;
+        if entry.rooms.last() != Some(&room_idx) {
+            entry.rooms.push(room_idx);
+        }
+    };
+    for (room_idx, room) in level.rooms.iter().enumerate() {
+        for (_, _, sector) in room.iter_sectors() {
+            for face in sector.floor.iter().chain(sector.ceiling.iter()) {
+                count(room_idx, &face.texture);
+                if let Some(tex) = &face.texture_2 {
+                    count(room_idx, tex);
+                }
+            }
+            for dir in Direction::ALL {
+                for wall in sector.walls(dir) {
+                    count(room_idx, &wall.texture);
+                }
+            }
+        }
+    }
+    usage.sort_by(|a, b| a.pack.cmp(&b.pack));
+    usage
+}
+
+/// Which loaded packs (by index into `loaded`) the game uses, in priority order.
+///
+/// With an empty `order` every pack is used as loaded. Otherwise only the
+/// listed packs are used, in list order; names that are not loaded are skipped.
+pub fn ordered_pack_indices<'a>(loaded: impl IntoIterator<Item = &'a str>, order: &[String]) -> Vec<usize> {
+    let loaded: Vec<&str> = loaded.into_iter().collect();
+    if order.is_empty() {
+        return (0..loaded.len()).collect();
+    }
+    order.iter()
+        .filter_map(|name| loaded.iter().position(|l| l == name))
+        .collect()
+}
+
+/// Point faces at a different pack or texture. Returns how many changed.
+///
+/// - `from_name` None matches every texture in `from_pack`
+/// - `to_name` None keeps each face's texture name and only swaps the pack
+pub fn remap_level_textures(
+    level: &mut Level,
+    from_pack: &str,
+    from_name: Option<&str>,
+    to_pack: &str,
+    to_name: Option<&str>,
+) -> usize {
+    let mut changed = 0;
+    for_each_level_texture_mut(level, |_, tex| {
+        if !tex.is_valid() || tex.pack != from_pack || from_name.is_some_and(|n| n != tex.name) {
+            return;
+        }
+        let new = TextureRef::new(to_pack, to_name.unwrap_or(&tex.name));
+        if *tex != new {
+            *tex = new;
+            changed += 1;
+        }
+    });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rasterizer::Vec3;
+    use crate::world::Room;
+
+    fn level_with(textures: &[(&str, &str)]) -> Level {
+        let mut level = Level::new();
+        for (i, (pack, name)) in textures.iter().enumerate() {
+            let mut room = Room::new(i, Vec3::ZERO, 1, 1);
+            room.set_floor(0, 0, 0.0, TextureRef::new(*pack, *name));
+            level.rooms.push(room);
+        }
+        level
+    }
+
+    #[test]
+    fn test_pack_usage_counts_faces_and_rooms() {
+        let level = level_with(&[("STONE", "wall"), ("WOOD", "plank"), ("STONE", "floor")]);
+        let usage = pack_usage(&level);
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0], PackUsage { pack: "STONE".into(), faces: 2, rooms: vec![0, 2] });
+        assert_eq!(usage[1].pack, "WOOD");
+    }
+
+    #[test]
+    fn test_remap_whole_pack_keeps_names() {
+        let mut level = level_with(&[("STONE", "wall"), ("WOOD", "plank"), ("STONE", "floor")]);
+        assert_eq!(remap_level_textures(&mut level, "STONE", None, "STONE_HD", None), 2);
+        let usage = pack_usage(&level);
+        assert_eq!(usage.iter().map(|u| u.pack.as_str()).collect::<Vec<_>>(), ["STONE_HD", "WOOD"]);
+        let floor = level.rooms[2].get_sector(0, 0).unwrap().floor.as_ref().unwrap();
+        assert_eq!(floor.texture, TextureRef::new("STONE_HD", "floor"));
+    }
+
+    #[test]
+    fn test_remap_single_texture() {
+        let mut level = level_with(&[("STONE", "wall"), ("STONE", "floor")]);
+        assert_eq!(remap_level_textures(&mut level, "STONE", Some("wall"), "_USER", Some("bricks")), 1);
+        let floor = level.rooms[0].get_sector(0, 0).unwrap().floor.as_ref().unwrap();
+        assert_eq!(floor.texture, TextureRef::user("bricks"));
+        // Mapping onto itself changes nothing
+        assert_eq!(remap_level_textures(&mut level, "STONE", Some("floor"), "STONE", None), 0);
+    }
+
+    #[test]
+    fn test_ordered_pack_indices() {
+        let loaded = ["A", "B", "C"];
+        assert_eq!(ordered_pack_indices(loaded, &[]), vec![0, 1, 2]);
+        let order = vec!["C".to_string(), "MISSING".to_string(), "A".to_string()];
+        assert_eq!(ordered_pack_indices(loaded, &order), vec![2, 0]);
+    }
+}
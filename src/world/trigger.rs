@@ -0,0 +1,138 @@
+//! Trigger volumes
+//!
+//! Axis-aligned boxes authored in the World Editor (drawn in the 2D grid view)
+//! that fire script events when the player crosses them. Unlike Trigger asset
+//! instances, which cover the one sector they stand on, a volume can be any
+//! size. Bounds are room-relative so volumes move with their room.
+//!
+//! In the game a volume fires `enter`/`exit` for its trigger id, plus its
+//! optional named on_enter/on_exit events (see `game::level_logic`), and can
+//! play a cutscene the first time the player walks in.
+
+use serde::{Serialize, Deserialize};
+use crate::rasterizer::Vec3;
+use super::SECTOR_SIZE;
+
+/// Bounds snap to quarter sectors while drawing
+pub const TRIGGER_SNAP: f32 = SECTOR_SIZE / 4.0;
+/// Height of a newly drawn volume (the editor's default ceiling height)
+pub const TRIGGER_DEFAULT_HEIGHT: f32 = SECTOR_SIZE * 3.0;
+
+/// An axis-aligned trigger box in a room
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerVolume {
+    /// Identifier scripts react to (`on enter <id>`)
+    pub trigger_id: String,
+    /// Event name to fire on enter (if any)
+    #[serde(default)]
+    pub on_enter: Option<String>,
+    /// Event name to fire on exit (if any)
+    #[serde(default)]
+    pub on_exit: Option<String>,
+    /// Cutscene to play on the first enter (if any)
+    #[serde(default)]
+    pub cutscene: Option<String>,
+    /// Minimum corner (room-relative)
+    pub min: Vec3,
+    /// Maximum corner (room-relative)
+    pub max: Vec3,
+}
+
+/// Snap a coordinate to the trigger grid
+pub fn snap_trigger_coord(value: f32) -> f32 {
+    (value / TRIGGER_SNAP).round() * TRIGGER_SNAP
+}
+
+impl TriggerVolume {
+    /// Volume spanning two room-relative floor-plan corners (any order),
+    /// snapped to the trigger grid and running from the room's base up to the
+    /// default ceiling height.
+    /// Degenerate drags grow to one snap step.
+    pub fn from_corners(trigger_id: impl Into<String>, a: (f32, f32), b: (f32, f32)) -> Self {
+        let (x0, x1) = (snap_trigger_coord(a.0.min(b.0)), snap_trigger_coord(a.0.max(b.0)));
+        let (z0, z1) = (snap_trigger_coord(a.1.min(b.1)), snap_trigger_coord(a.1.max(b.1)));
+        Self {
+            trigger_id: trigger_id.into(),
+            on_enter: None,
+            on_exit: None,
+            cutscene: None,
+            min: Vec3::new(x0, 0.0, z0),
+            max: Vec3::new(x1.max(x0 + TRIGGER_SNAP), TRIGGER_DEFAULT_HEIGHT, z1.max(z0 + TRIGGER_SNAP)),
+        }
+    }
+
+    /// World-space bounds for a room at `room_position`
+    pub fn world_bounds(&self, room_position: Vec3) -> (Vec3, Vec3) {
+        (room_position + self.min, room_position + self.max)
+    }
+
+    /// World-space center
+    pub fn world_center(&self, room_position: Vec3) -> Vec3 {
+        room_position + (self.min + self.max) * 0.5
+    }
+
+    /// Is a world-space point inside? (room at `room_position`)
+    pub fn contains(&self, room_position: Vec3, point: Vec3) -> bool {
+        let (min, max) = self.world_bounds(room_position);
+        point.x >= min.x && point.x <= max.x
+            && point.y >= min.y && point.y <= max.y
+            && point.z >= min.z && point.z <= max.z
+    }
+
+    /// Size in world units
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// Move by a room-relative offset
+    pub fn translate(&mut self, offset: Vec3) {
+        self.min = self.min + offset;
+        self.max = self.max + offset;
+    }
+}
+
+/// Next unused `triggerN` id in a level
+pub fn next_trigger_id<'a>(existing: impl Iterator<Item = &'a TriggerVolume>) -> String {
+    let highest = existing
+        .filter_map(|t| t.trigger_id.strip_prefix("trigger")?.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("trigger{}", highest + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_corners_snaps_and_orders() {
+        let t = TriggerVolume::from_corners("door", (SECTOR_SIZE * 2.1, 10.0), (0.0, SECTOR_SIZE * 0.9));
+        assert_eq!(t.min.x, 0.0);
+        assert_eq!(t.max.x, SECTOR_SIZE * 2.0);
+        assert_eq!(t.min.z, 0.0);
+        assert_eq!(t.max.z, SECTOR_SIZE);
+        assert_eq!(t.max.y, TRIGGER_DEFAULT_HEIGHT);
+
+        // A click without dragging still makes a usable box
+        let t = TriggerVolume::from_corners("tiny", (5.0, 5.0), (5.0, 5.0));
+        assert_eq!(t.size().x, TRIGGER_SNAP);
+        assert_eq!(t.size().z, TRIGGER_SNAP);
+    }
+
+    #[test]
+    fn test_contains_is_room_relative() {
+        let t = TriggerVolume::from_corners("a", (0.0, 0.0), (SECTOR_SIZE, SECTOR_SIZE));
+        let room = Vec3::new(SECTOR_SIZE * 4.0, 0.0, 0.0);
+        assert!(t.contains(room, Vec3::new(SECTOR_SIZE * 4.5, 10.0, SECTOR_SIZE * 0.5)));
+        assert!(!t.contains(Vec3::ZERO, Vec3::new(SECTOR_SIZE * 4.5, 10.0, SECTOR_SIZE * 0.5)));
+        assert!(!t.contains(room, Vec3::new(SECTOR_SIZE * 4.5, TRIGGER_DEFAULT_HEIGHT + 1.0, SECTOR_SIZE * 0.5)));
+    }
+
+    #[test]
+    fn test_next_trigger_id() {
+        let a = TriggerVolume::from_corners("trigger2", (0.0, 0.0), (1.0, 1.0));
+        let b = TriggerVolume::from_corners("gate", (0.0, 0.0), (1.0, 1.0));
+        assert_eq!(next_trigger_id([a, b].iter()), "trigger3");
+        assert_eq!(next_trigger_id(std::iter::empty()), "trigger1");
+    }
+}
@@ -0,0 +1,206 @@
+//! Water
+//!
+//! A room can be flooded between two heights (room-relative): anything over
+//! one of its sectors between `bottom` and `surface` is underwater. The
+//! surface is drawn as a semi-transparent sheet over every sector whose floor
+//! dips below it, with its texture scrolling by `flow`. A camera below the
+//! surface sees the scene through the water's color and fog.
+//!
+//! The player controller swims in it (see `game::swim`).
+
+use serde::{Serialize, Deserialize};
+use crate::rasterizer::{BlendMode, Color, Face as RasterFace, Vec2, Vec3, Vertex};
+use super::{Level, Room, TextureRef, SECTOR_SIZE};
+
+/// Depth of newly added water
+pub const WATER_DEFAULT_DEPTH: f32 = SECTOR_SIZE;
+
+/// A room's water volume
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomWater {
+    /// Height of the surface (room-relative)
+    pub surface: f32,
+    /// Height below which the room is dry again (room-relative)
+    pub bottom: f32,
+    /// Water color (RGB 0.0-1.0): the surface without a texture, and the
+    /// underwater tint and fog
+    pub color: (f32, f32, f32),
+    /// Surface texture (none = flat color)
+    #[serde(default = "TextureRef::none")]
+    pub texture: TextureRef,
+    /// Surface texture scroll (texture repeats per second along X and Z)
+    #[serde(default = "default_flow")]
+    pub flow: (f32, f32),
+    /// Distance underwater where everything has faded into the water color
+    #[serde(default = "default_visibility")]
+    pub visibility: f32,
+}
+
+fn default_flow() -> (f32, f32) {
+    (0.05, 0.02)
+}
+
+fn default_visibility() -> f32 {
+    SECTOR_SIZE * 6.0
+}
+
+impl RoomWater {
+    /// Water from `bottom` up to `surface` (room-relative)
+    pub fn new(surface: f32, bottom: f32) -> Self {
+        Self {
+            surface,
+            bottom: bottom.min(surface),
+            color: (0.15, 0.35, 0.45),
+            texture: TextureRef::none(),
+            flow: default_flow(),
+            visibility: default_visibility(),
+        }
+    }
+
+    /// World-space surface height for a room at `room_position`
+    pub fn world_surface(&self, room_position: Vec3) -> f32 {
+        room_position.y + self.surface
+    }
+
+    /// Whether a room-relative height is in the water
+    pub fn contains_height(&self, y: f32) -> bool {
+        y >= self.bottom && y <= self.surface
+    }
+
+    /// Vertex color multiplier for a camera underwater
+    pub fn tint(&self) -> [f32; 3] {
+        // Halfway between white and the water color, so the scene stays readable
+        let (r, g, b) = self.color;
+        [0.5 + r * 0.5, 0.5 + g * 0.5, 0.5 + b * 0.5]
+    }
+
+    /// Fog parameters (start, falloff) for a camera underwater
+    pub fn fog_range(&self) -> (f32, f32) {
+        (self.visibility * 0.1, self.visibility * 0.9)
+    }
+}
+
+impl Room {
+    /// This room's water at a world-space point, if the point is over one of
+    /// its sectors and between the water's bottom and surface
+    pub fn water_at(&self, point: Vec3) -> Option<&RoomWater> {
+        let water = self.water.as_ref()?;
+        let (x, z) = self.world_to_grid(point.x, point.z)?;
+        self.get_sector(x, z)?;
+        water.contains_height(point.y - self.position.y).then_some(water)
+    }
+
+    /// Surface sheet over every sector whose floor dips below the water.
+    /// `time` (seconds) scrolls the texture; `resolve_texture` maps the
+    /// water's texture like room faces (None draws the flat water color).
+    pub fn water_render_data<F>(&self, time: f32, resolve_texture: F) -> (Vec<Vertex>, Vec<RasterFace>)
+    where
+        F: Fn(&TextureRef) -> Option<(usize, u32)>,
+    {
+        let mut vertices = Vec::new();
+        let mut faces = Vec::new();
+        let Some(water) = &self.water else {
+            return (vertices, faces);
+        };
+
+        let texture = if water.texture.is_valid() { resolve_texture(&water.texture) } else { None };
+        let (uv_scale, color) = match texture {
+            // Same texel density as room floors (32 pixels per sector)
+            Some((_, width)) => (32.0 / width as f32, Color::NEUTRAL),
+            None => {
+                let (r, g, b) = water.color;
+                (1.0, Color::new((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8))
+            }
+        };
+        let scroll = Vec2::new(
+            (water.flow.0 * time).rem_euclid(1.0),
+            (water.flow.1 * time).rem_euclid(1.0),
+        );
+        let y = water.world_surface(self.position);
+        let up = Vec3::new(0.0, 1.0, 0.0);
+
+        for (grid_x, grid_z, sector) in self.iter_sectors() {
+            // Pits (no floor) are flooded too
+            let dry = matches!(&sector.floor, Some(floor) if floor.heights.iter().all(|&h| h >= water.surface));
+            if dry {
+                continue;
+            }
+
+            let base_x = self.position.x + grid_x as f32 * SECTOR_SIZE;
+            let base_z = self.position.z + grid_z as f32 * SECTOR_SIZE;
+            let base = vertices.len();
+            // NW, NE, SE, SW
+            for (dx, dz) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+                let uv = Vec2::new(
+                    (grid_x as f32 + dx) * uv_scale + scroll.x,
+                    (grid_z as f32 + dz) * uv_scale + scroll.y,
+                );
+                let pos = Vec3::new(base_x + dx * SECTOR_SIZE, y, base_z + dz * SECTOR_SIZE);
+                vertices.push(Vertex::with_color(pos, uv, up, color));
+            }
+            for (a, b, c) in [(0, 2, 1), (0, 3, 2)] {
+                let mut face = RasterFace::new(base + a, base + b, base + c).with_blend_mode(BlendMode::Average);
+                face.texture_id = texture.map(|(id, _)| id);
+                faces.push(face);
+            }
+        }
+
+        (vertices, faces)
+    }
+}
+
+/// The water containing a world-space point, with its room index
+pub fn water_at(rooms: &[Room], point: Vec3) -> Option<(usize, &RoomWater)> {
+    rooms.iter().enumerate().find_map(|(i, room)| Some((i, room.water_at(point)?)))
+}
+
+impl Level {
+    /// The water containing a world-space point, with its room index
+    pub fn water_at(&self, point: Vec3) -> Option<(usize, &RoomWater)> {
+        water_at(&self.rooms, point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> Room {
+        // Two sectors along X: a pit one sector deep, then a dry ledge
+        let mut room = Room::new(0, Vec3::new(0.0, 100.0, 0.0), 2, 1);
+        room.set_floor(0, 0, -SECTOR_SIZE, TextureRef::none());
+        room.set_floor(1, 0, 0.0, TextureRef::none());
+        room.recalculate_bounds();
+        room.water = Some(RoomWater::new(-SECTOR_SIZE * 0.25, -SECTOR_SIZE));
+        room
+    }
+
+    #[test]
+    fn test_water_at_is_bounded_by_sectors_and_heights() {
+        let room = pool();
+        let inside = Vec3::new(SECTOR_SIZE * 0.5, 100.0 - SECTOR_SIZE * 0.5, SECTOR_SIZE * 0.5);
+        assert!(room.water_at(inside).is_some());
+        // Above the surface, below the bottom, or off the grid: dry
+        assert!(room.water_at(inside + Vec3::new(0.0, SECTOR_SIZE * 0.5, 0.0)).is_none());
+        assert!(room.water_at(inside - Vec3::new(0.0, SECTOR_SIZE, 0.0)).is_none());
+        assert!(room.water_at(inside + Vec3::new(0.0, 0.0, SECTOR_SIZE)).is_none());
+        assert_eq!(water_at(std::slice::from_ref(&room), inside).map(|(i, _)| i), Some(0));
+        assert_eq!(room.water.as_ref().unwrap().world_surface(room.position), 100.0 - SECTOR_SIZE * 0.25);
+    }
+
+    #[test]
+    fn test_surface_covers_only_flooded_sectors_and_scrolls() {
+        let room = pool();
+        let (vertices, faces) = room.water_render_data(0.0, |_| None);
+        // Only the pit is under the surface
+        assert_eq!((vertices.len(), faces.len()), (4, 2));
+        assert!(faces.iter().all(|f| f.blend_mode == BlendMode::Average && f.texture_id.is_none()));
+        assert!(vertices.iter().all(|v| v.pos.y == 100.0 - SECTOR_SIZE * 0.25 && v.pos.x <= SECTOR_SIZE));
+
+        let mut textured = room.clone();
+        textured.water.as_mut().unwrap().texture = TextureRef::new("SAMPLE", "water");
+        let (moved, faces) = textured.water_render_data(2.0, |_| Some((3, 64)));
+        assert_eq!(faces[0].texture_id, Some(3));
+        assert!((moved[0].uv.x - 0.1).abs() < 1e-5 && (moved[0].uv.y - 0.04).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,183 @@
+//! Weather Presets
+//!
+//! Per-level weather: rain, snow, ash or dust particles drawn around the camera
+//! while playing. Rooms marked `outdoor` get the weather; with `outdoor_only`
+//! set (the default) interiors stay dry. Scripts can change the preset at
+//! runtime with the `weather` command, e.g. when a trigger leads outside.
+//!
+//! Only the settings live here; the particle simulation is `game::weather`.
+
+use serde::{Deserialize, Serialize};
+
+/// Weather preset stored in the level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum WeatherPreset {
+    /// No weather
+    #[default]
+    Clear,
+    Rain,
+    Snow,
+    Ash,
+    Dust,
+}
+
+/// Particle look and motion for a preset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeatherParams {
+    /// Particles at full intensity
+    pub count: usize,
+    /// Fall speed (world units per second, negative rises)
+    pub fall_speed: f32,
+    /// Constant sideways drift along X (units per second)
+    pub drift: f32,
+    /// Amplitude of the side-to-side sway (units per second)
+    pub sway: f32,
+    /// Streak length in seconds of fall (0 = single dot)
+    pub streak: f32,
+    /// Particle color (RGB)
+    pub color: (u8, u8, u8),
+    /// Particle opacity (0-255)
+    pub alpha: u8,
+}
+
+impl WeatherPreset {
+    pub const ALL: [WeatherPreset; 5] = [
+        WeatherPreset::Clear,
+        WeatherPreset::Rain,
+        WeatherPreset::Snow,
+        WeatherPreset::Ash,
+        WeatherPreset::Dust,
+    ];
+
+    pub fn is_clear(&self) -> bool {
+        *self == WeatherPreset::Clear
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WeatherPreset::Clear => "Clear",
+            WeatherPreset::Rain => "Rain",
+            WeatherPreset::Snow => "Snow",
+            WeatherPreset::Ash => "Ash",
+            WeatherPreset::Dust => "Dust",
+        }
+    }
+
+    /// Parse a preset name as written in scripts (`off` and `none` mean clear)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "clear" | "off" | "none" => Some(WeatherPreset::Clear),
+            "rain" => Some(WeatherPreset::Rain),
+            "snow" => Some(WeatherPreset::Snow),
+            "ash" => Some(WeatherPreset::Ash),
+            "dust" => Some(WeatherPreset::Dust),
+            _ => None,
+        }
+    }
+
+    /// Looping ambience sent as a sound event when the preset starts
+    pub fn sound_name(&self) -> Option<&'static str> {
+        match self {
+            WeatherPreset::Clear => None,
+            WeatherPreset::Rain => Some("weather_rain"),
+            WeatherPreset::Snow => Some("weather_wind"),
+            WeatherPreset::Ash => Some("weather_embers"),
+            WeatherPreset::Dust => Some("weather_dust"),
+        }
+    }
+
+    pub fn params(&self) -> WeatherParams {
+        match self {
+            WeatherPreset::Clear => WeatherParams {
+                count: 0, fall_speed: 0.0, drift: 0.0, sway: 0.0, streak: 0.0,
+                color: (0, 0, 0), alpha: 0,
+            },
+            WeatherPreset::Rain => WeatherParams {
+                count: 400, fall_speed: 2400.0, drift: 120.0, sway: 0.0, streak: 0.04,
+                color: (150, 170, 200), alpha: 140,
+            },
+            WeatherPreset::Snow => WeatherParams {
+                count: 300, fall_speed: 180.0, drift: 40.0, sway: 80.0, streak: 0.0,
+                color: (235, 235, 245), alpha: 220,
+            },
+            WeatherPreset::Ash => WeatherParams {
+                count: 220, fall_speed: 90.0, drift: 30.0, sway: 50.0, streak: 0.0,
+                color: (95, 90, 85), alpha: 200,
+            },
+            WeatherPreset::Dust => WeatherParams {
+                count: 160, fall_speed: -15.0, drift: 220.0, sway: 30.0, streak: 0.0,
+                color: (190, 160, 110), alpha: 110,
+            },
+        }
+    }
+}
+
+/// Level weather settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelWeather {
+    #[serde(default)]
+    pub preset: WeatherPreset,
+    /// Particle density multiplier (0.0-2.0)
+    #[serde(default = "default_intensity")]
+    pub intensity: f32,
+    /// Only show weather in rooms marked outdoor
+    #[serde(default = "default_outdoor_only")]
+    pub outdoor_only: bool,
+}
+
+fn default_intensity() -> f32 {
+    1.0
+}
+
+fn default_outdoor_only() -> bool {
+    true
+}
+
+impl Default for LevelWeather {
+    fn default() -> Self {
+        Self {
+            preset: WeatherPreset::Clear,
+            intensity: default_intensity(),
+            outdoor_only: default_outdoor_only(),
+        }
+    }
+}
+
+impl LevelWeather {
+    /// True when the level has no weather (for serde skip_serializing_if)
+    pub fn is_clear(&self) -> bool {
+        self.preset.is_clear()
+    }
+
+    /// Particle count for `preset` at this intensity
+    pub fn particle_count(&self, preset: WeatherPreset) -> usize {
+        (preset.params().count as f32 * self.intensity.clamp(0.0, 2.0)).round() as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_names_round_trip() {
+        for preset in WeatherPreset::ALL {
+            assert_eq!(WeatherPreset::from_name(preset.label()), Some(preset));
+        }
+        assert_eq!(WeatherPreset::from_name("off"), Some(WeatherPreset::Clear));
+        assert_eq!(WeatherPreset::from_name("hail"), None);
+        assert!(WeatherPreset::Clear.sound_name().is_none());
+        assert!(WeatherPreset::Rain.sound_name().is_some());
+    }
+
+    #[test]
+    fn particle_count_scales_with_intensity() {
+        let mut weather = LevelWeather { preset: WeatherPreset::Snow, ..LevelWeather::default() };
+        assert_eq!(weather.particle_count(WeatherPreset::Snow), 300);
+        weather.intensity = 0.5;
+        assert_eq!(weather.particle_count(WeatherPreset::Snow), 150);
+        weather.intensity = 10.0;
+        assert_eq!(weather.particle_count(WeatherPreset::Snow), 600);
+        assert_eq!(weather.particle_count(WeatherPreset::Clear), 0);
+    }
+}
@@ -5,6 +5,14 @@
 //!   cargo xtask build-web --dev  # Build with DEV banner
 //!   cargo xtask serve            # Build and serve locally on port 8080
 //!   cargo xtask serve -p 3000    # Build and serve on custom port
+//!   cargo xtask export-game --level assets/userdata/levels/my.ron --name my-game
+//!                                # Standalone player + game.bpk in dist/player/my-game
+//!   cargo xtask export-game --level ... --web  # Same, as a web build
+//!   cargo xtask pack assets/samples/levels assets/samples/assets
+//!                                # Pack folders into assets.bpk (mounted at startup)
+//!   cargo xtask deploy-itch --channel html5
+//!                                # Build, check itch.io limits and push with butler
+//!   cargo xtask deploy-itch --dry-run  # Build and report what would be uploaded
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
@@ -33,6 +41,45 @@ enum Commands {
         #[arg(short, long, default_value = "8080")]
         port: u16,
     },
+    /// Export a standalone game: runtime-only player binary plus packed asset bundle
+    ExportGame {
+        /// Level to boot into (.ron, plain or compressed)
+        #[arg(long)]
+        level: PathBuf,
+        /// Output folder name under dist/player (default: level file name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Window title (default: the output name)
+        #[arg(long)]
+        title: Option<String>,
+        /// Build for the web (WASM) instead of the host platform
+        #[arg(long)]
+        web: bool,
+    },
+    /// Pack folders (relative to the project root) into a .bpk asset bundle
+    Pack {
+        /// Folders to pack, recursively
+        #[arg(required = true)]
+        dirs: Vec<PathBuf>,
+        /// Output bundle (default: assets.bpk in the project root)
+        #[arg(short, long, default_value = "assets.bpk")]
+        output: PathBuf,
+        /// Only pack files with these extensions (e.g. --ext ron --ext png)
+        #[arg(long = "ext")]
+        extensions: Vec<String>,
+    },
+    /// Build for the web and upload the build to itch.io with butler
+    DeployItch {
+        /// itch.io channel to push to
+        #[arg(long, default_value = "html5")]
+        channel: String,
+        /// itch.io project as user/game (default: $ITCH_PROJECT, else bonnie-games/bonnie-32)
+        #[arg(long)]
+        project: Option<String>,
+        /// Only report what would be uploaded (no push)
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 fn main() -> Result<()> {
@@ -41,6 +88,9 @@ fn main() -> Result<()> {
     match cli.command {
         Commands::BuildWeb { dev } => build_web(dev),
         Commands::Serve { port } => serve(port),
+        Commands::ExportGame { level, name, title, web } => export_game(&level, name, title, web),
+        Commands::Pack { dirs, output, extensions } => pack(&dirs, &output, &extensions),
+        Commands::DeployItch { channel, project, dry_run } => deploy_itch(&channel, project, dry_run),
     }
 }
 
@@ -103,6 +153,26 @@ fn copy_dir_recursive_filtered(src: &Path, dst: &Path, exclude: &[&str]) -> Resu
     Ok(())
 }
 
+/// Copy the web shell files (index.html, audio worklet, icons, macroquad JS) into `dist`
+fn copy_web_shell(root: &Path, dist: &Path) -> Result<()> {
+    let docs = root.join("docs");
+    for file in ["index.html", "audio-processor.js", "favicon-16.png", "favicon-32.png", "apple-touch-icon.png"] {
+        let src = docs.join(file);
+        if src.exists() {
+            std::fs::copy(&src, dist.join(file))?;
+        }
+    }
+
+    let mq_js = dist.join("mq_js_bundle.js");
+    if !mq_js.exists() {
+        download_file(
+            "https://raw.githubusercontent.com/not-fl3/macroquad/v0.4.14/js/mq_js_bundle.js",
+            &mq_js,
+        )?;
+    }
+    Ok(())
+}
+
 /// Build WASM for web deployment
 fn build_web(dev: bool) -> Result<()> {
     let root = project_root();
@@ -128,27 +198,18 @@ fn build_web(dev: bool) -> Result<()> {
         dist.join("bonnie-32.wasm"),
     )?;
 
-    // Copy web files from docs/
-    let docs = root.join("docs");
-    for file in ["index.html", "audio-processor.js", "favicon-16.png", "favicon-32.png", "apple-touch-icon.png"] {
-        let src = docs.join(file);
-        if src.exists() {
-            std::fs::copy(&src, dist.join(file))?;
-        }
-    }
-
-    // Download macroquad JS bundle
-    let mq_js = dist.join("mq_js_bundle.js");
-    if !mq_js.exists() {
-        download_file(
-            "https://raw.githubusercontent.com/not-fl3/macroquad/v0.4.14/js/mq_js_bundle.js",
-            &mq_js,
-        )?;
-    }
+    // Copy web files from docs/ and the macroquad JS bundle
+    copy_web_shell(&root, &dist)?;
 
     // Copy assets (excluding large/unused directories to stay under itch.io file limit)
     copy_dir_recursive_filtered(&root.join("assets"), &dist.join("assets"), EXCLUDED_ASSET_DIRS)?;
 
+    // Ship the packed asset bundle (cargo xtask pack) if there is one; the engine mounts it at startup
+    let bundle = root.join("assets.bpk");
+    if bundle.exists() {
+        std::fs::copy(&bundle, dist.join("assets.bpk"))?;
+    }
+
     // Regenerate texture-pack manifest without excluded packs
     regenerate_texture_manifest(&dist.join("assets/samples/texture-packs"))?;
 
@@ -202,6 +263,253 @@ fn serve(port: u16) -> Result<()> {
     Ok(())
 }
 
+// =============================================================================
+// Standalone game export
+// =============================================================================
+
+/// Bundle file the player loads (must match `PLAYER_BUNDLE` in src/bundle.rs)
+const PLAYER_BUNDLE: &str = "game.bpk";
+/// Bundle entry with the player info (must match `PLAYER_INFO_ENTRY`)
+const PLAYER_INFO_ENTRY: &str = "game/player.ron";
+/// Bundle entry the exported level is stored as
+const PLAYER_LEVEL_ENTRY: &str = "game/level.ron";
+
+/// Asset folders packed into the bundle, with the file extensions the player reads
+const BUNDLED_ASSET_DIRS: &[(&str, &[&str])] = &[
+    ("assets/samples/texture-packs", &["png", "jpg", "jpeg"]),
+    ("assets/samples/textures", &["ron"]),
+    ("assets/userdata/textures", &["ron"]),
+    ("assets/samples/assets", &["ron"]),
+    ("assets/userdata/assets", &["ron"]),
+    ("assets/runtime/locale", &["ron", "ttf"]),
+    ("assets/runtime/fonts", &["ttf"]),
+];
+
+/// Build the player binary and pack the level and its asset folders into game.bpk
+fn export_game(level: &Path, name: Option<String>, title: Option<String>, web: bool) -> Result<()> {
+    let root = project_root();
+    let level = if level.is_absolute() { level.to_path_buf() } else { root.join(level) };
+    let level_bytes = std::fs::read(&level)
+        .with_context(|| format!("Failed to read level {}", level.display()))?;
+
+    let name = name.unwrap_or_else(|| {
+        level.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "game".to_string())
+    });
+    let title = title.unwrap_or_else(|| name.clone());
+    let dist = root.join("dist/player").join(&name);
+
+    println!("Building player{}...", if web { " (WASM)" } else { "" });
+    let mut cmd = Command::new("cargo");
+    cmd.current_dir(&root).args(["build", "--release", "--features", "player"]);
+    if web {
+        cmd.args(["--target", "wasm32-unknown-unknown"]);
+    }
+    run_cmd(&mut cmd)?;
+
+    if dist.exists() {
+        std::fs::remove_dir_all(&dist)?;
+    }
+    std::fs::create_dir_all(&dist)?;
+
+    if web {
+        std::fs::copy(
+            root.join("target/wasm32-unknown-unknown/release/bonnie-32.wasm"),
+            dist.join("bonnie-32.wasm"),
+        )?;
+        copy_web_shell(&root, &dist)?;
+    } else {
+        let exe = format!("bonnie-32{}", std::env::consts::EXE_SUFFIX);
+        let out = format!("{}{}", name, std::env::consts::EXE_SUFFIX);
+        std::fs::copy(root.join("target/release").join(exe), dist.join(out))?;
+    }
+
+    // Pack the game
+    let mut entries: Vec<(String, Vec<u8>)> = vec![
+        (PLAYER_INFO_ENTRY.to_string(), format!("(title: {:?}, level: {:?})", title, PLAYER_LEVEL_ENTRY).into_bytes()),
+        (PLAYER_LEVEL_ENTRY.to_string(), level_bytes),
+    ];
+    for (dir, extensions) in BUNDLED_ASSET_DIRS {
+        collect_bundle_files(&root, &root.join(dir), extensions, &mut entries)?;
+    }
+    let bundle = write_bundle(&entries)?;
+    std::fs::write(dist.join(PLAYER_BUNDLE), &bundle)?;
+
+    println!(
+        "Packed {} files into {} ({:.1} MB)",
+        entries.len(),
+        PLAYER_BUNDLE,
+        bundle.len() as f64 / (1024.0 * 1024.0)
+    );
+    println!("Game export complete: dist/player/{}/", name);
+    Ok(())
+}
+
+/// Pack folders into a bundle the engine can mount as a read-only filesystem
+fn pack(dirs: &[PathBuf], output: &Path, extensions: &[String]) -> Result<()> {
+    let root = project_root();
+    let extensions: Vec<&str> = extensions.iter().map(|e| e.trim_start_matches('.')).collect();
+
+    let mut entries = Vec::new();
+    for dir in dirs {
+        let dir = if dir.is_absolute() { dir.clone() } else { root.join(dir) };
+        if !dir.is_dir() {
+            anyhow::bail!("Not a folder: {}", dir.display());
+        }
+        collect_bundle_files(&root, &dir, &extensions, &mut entries)?;
+    }
+
+    let bundle = write_bundle(&entries)?;
+    let output = if output.is_absolute() { output.to_path_buf() } else { root.join(output) };
+    std::fs::write(&output, &bundle)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    let raw: usize = entries.iter().map(|(_, data)| data.len()).sum();
+    println!(
+        "Packed {} files into {} ({:.1} KB -> {:.1} KB)",
+        entries.len(),
+        output.display(),
+        raw as f64 / 1024.0,
+        bundle.len() as f64 / 1024.0
+    );
+    Ok(())
+}
+
+/// Recursively collect files with one of `extensions` (any file if empty) under
+/// `dir`, keyed by their '/'-separated path relative to the project root
+fn collect_bundle_files(root: &Path, dir: &Path, extensions: &[&str], entries: &mut Vec<(String, Vec<u8>)>) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        if path.is_dir() {
+            collect_bundle_files(root, &path, extensions, entries)?;
+            continue;
+        }
+        let matches = extensions.is_empty() || path
+            .extension()
+            .map(|ext| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+            .unwrap_or(false);
+        if matches {
+            let key = path.strip_prefix(root)?.to_string_lossy().replace('\\', "/");
+            entries.push((key, std::fs::read(&path)?));
+        }
+    }
+    Ok(())
+}
+
+/// Write a .bpk bundle (layout documented in src/bundle.rs)
+fn write_bundle(entries: &[(String, Vec<u8>)]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut blobs = Vec::new();
+    out.extend_from_slice(b"BPK1");
+    out.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for (path, data) in entries {
+        let path_len = u16::try_from(path.len()).with_context(|| format!("Path too long: {}", path))?;
+        let mut compressed = Vec::new();
+        brotli::BrotliCompress(&mut std::io::Cursor::new(data), &mut compressed, &brotli::enc::BrotliEncoderParams {
+            quality: 9,
+            lgwin: 22,
+            ..Default::default()
+        })?;
+        out.extend_from_slice(&path_len.to_le_bytes());
+        out.extend_from_slice(path.as_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        blobs.extend_from_slice(&compressed);
+    }
+    out.extend_from_slice(&blobs);
+    Ok(out)
+}
+
+// =============================================================================
+// itch.io deploy
+// =============================================================================
+
+/// itch.io project pushed to when neither --project nor ITCH_PROJECT is set
+const ITCH_PROJECT: &str = "bonnie-games/bonnie-32";
+/// itch.io HTML5 upload limits: file count, total extracted size, single file size
+const ITCH_MAX_FILES: usize = 1000;
+const ITCH_MAX_TOTAL_BYTES: usize = 500 * 1024 * 1024;
+const ITCH_MAX_FILE_BYTES: usize = 200 * 1024 * 1024;
+
+fn megabytes(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+/// Build the web version, check it against itch.io's limits and push it with butler
+fn deploy_itch(channel: &str, project: Option<String>, dry_run: bool) -> Result<()> {
+    let root = project_root();
+    let dist = root.join("dist/web");
+    let project = project
+        .or_else(|| std::env::var("ITCH_PROJECT").ok())
+        .unwrap_or_else(|| ITCH_PROJECT.to_string());
+    let target = format!("{}:{}", project, channel);
+
+    build_web(false)?;
+
+    // Everything in dist/web, keyed by its path inside the build
+    let mut files = Vec::new();
+    collect_bundle_files(&dist, &dist, &[], &mut files)?;
+    let total: usize = files.iter().map(|(_, data)| data.len()).sum();
+    println!(
+        "\n{} files, {:.1} MB (itch.io allows {} files, {:.0} MB)",
+        files.len(),
+        megabytes(total),
+        ITCH_MAX_FILES,
+        megabytes(ITCH_MAX_TOTAL_BYTES)
+    );
+    let mut largest: Vec<&(String, Vec<u8>)> = files.iter().collect();
+    largest.sort_by_key(|(_, data)| std::cmp::Reverse(data.len()));
+    for (path, data) in largest.iter().take(5) {
+        println!("  {:>8.2} MB  {}", megabytes(data.len()), path);
+    }
+
+    let mut problems = Vec::new();
+    if files.len() > ITCH_MAX_FILES {
+        problems.push(format!("{} files (max {})", files.len(), ITCH_MAX_FILES));
+    }
+    if total > ITCH_MAX_TOTAL_BYTES {
+        problems.push(format!("{:.1} MB in total (max {:.0} MB)", megabytes(total), megabytes(ITCH_MAX_TOTAL_BYTES)));
+    }
+    for (path, data) in &files {
+        if data.len() > ITCH_MAX_FILE_BYTES {
+            problems.push(format!("{} is {:.1} MB (max {:.0} MB)", path, megabytes(data.len()), megabytes(ITCH_MAX_FILE_BYTES)));
+        }
+    }
+    if !files.iter().any(|(path, _)| path == "index.html") {
+        problems.push("no index.html at the top of the build".to_string());
+    }
+    if !problems.is_empty() {
+        anyhow::bail!("Web build doesn't fit itch.io's limits:\n  {}", problems.join("\n  "));
+    }
+
+    if dry_run {
+        println!("Dry run: would push dist/web to {}", target);
+        return Ok(());
+    }
+
+    if Command::new("butler").arg("version").output().is_err() {
+        anyhow::bail!("butler not found: install it from https://itch.io/docs/butler/, then run `butler login` or set BUTLER_API_KEY");
+    }
+    println!("Pushing to {}...", target);
+    run_cmd(
+        Command::new("butler")
+            .current_dir(&root)
+            .arg("push")
+            .arg(&dist)
+            .arg(&target),
+    )?;
+
+    println!("Deployed dist/web to {}", target);
+    Ok(())
+}
+
 /// Regenerate texture-pack manifests based on actual directories present.
 /// Creates a top-level manifest listing pack directory names, and a per-pack
 /// manifest inside each directory listing the PNG/JPG files.